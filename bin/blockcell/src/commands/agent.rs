@@ -52,12 +52,24 @@ const BUILTIN_TOOLS: &[(&str, &[(&str, &str)])] = &[
             ("edit_file", "Precise file content editing"),
             ("list_dir", "Browse directory structure"),
             ("file_ops", "Delete/move/copy/compress/decompress/PDF"),
+            (
+                "doc_diff",
+                "Document version diffs, change summaries, watched folders",
+            ),
         ],
     ),
     (
         "⚡ Commands & System",
         &[
             ("exec", "Execute shell commands"),
+            (
+                "python",
+                "Sandboxed Python interpreter with plots as artifacts",
+            ),
+            ("ssh", "Remote hosts: allowlisted commands, SFTP get/put"),
+            ("docker", "Docker/Podman containers, images, logs, stats"),
+            ("git_api", "GitHub PR diffs/reviews, issue labels/assignees"),
+            ("kubectl", "Kubernetes pods, events, logs, rollouts"),
             ("system_info", "Hardware/software/network detection"),
         ],
     ),
@@ -65,24 +77,45 @@ const BUILTIN_TOOLS: &[(&str, &[(&str, &str)])] = &[
         "🌐 Web & Browser",
         &[
             ("web_search", "Search engine queries"),
+            ("weather", "Current weather and forecasts with caching"),
             ("web_fetch", "Fetch web page content"),
+            (
+                "bookmarks",
+                "Read-later bookmarks with summaries and search",
+            ),
             (
                 "browse",
                 "CDP browser automation (35+ actions, tabs/screenshots/PDF/network)",
             ),
             ("http_request", "Generic HTTP/REST API calls"),
+            (
+                "object_storage",
+                "S3/MinIO/R2 upload, download, list, presigned URLs",
+            ),
+            ("rss", "RSS/Atom feed subscriptions and digests"),
         ],
     ),
     (
         "🖥️ GUI Automation",
         &[("app_control", "macOS app control (System Events)")],
     ),
+    (
+        "🏠 IoT",
+        &[(
+            "iot_control",
+            "MQTT device commands, request/reply and state",
+        )],
+    ),
     (
         "🎨 Media",
         &[
             ("camera_capture", "Camera capture"),
             ("audio_transcribe", "Speech-to-text (Whisper/API)"),
             ("tts", "Text-to-speech (say/piper/edge-tts/OpenAI)"),
+            (
+                "media_control",
+                "Music playback and volume (MPRIS/AppleScript/media keys/Spotify)",
+            ),
             ("ocr", "Image text recognition (Tesseract/Vision/API)"),
             (
                 "image_understand",
@@ -99,11 +132,29 @@ const BUILTIN_TOOLS: &[(&str, &[(&str, &str)])] = &[
         "📊 Data Processing",
         &[
             ("data_process", "CSV read/write/stats/query/transform"),
-            ("office_write", "Generate PPTX/DOCX/XLSX documents"),
+            (
+                "calc",
+                "Timezones, date math, recurrences, unit/currency conversion",
+            ),
+            ("nutrition", "Food lookup, recipe macros and meal log"),
+            ("db_query", "Query Postgres/MySQL/SQLite (read-only, paged)"),
+            (
+                "office_write",
+                "Generate PPTX/DOCX/XLSX; xlsx formulas, pivots, conditional formats",
+            ),
+            ("translate", "Translate text/Markdown/DOCX with glossaries"),
             (
                 "knowledge_graph",
                 "Knowledge graph (entities/relations/paths/export DOT/Mermaid)",
             ),
+            (
+                "doc_ingest",
+                "Ingest PDF/Markdown/HTML/Office files and URLs into the document library",
+            ),
+            (
+                "doc_search",
+                "Search ingested documents (keyword + semantic)",
+            ),
         ],
     ),
     (
@@ -179,12 +230,16 @@ const BUILTIN_TOOLS: &[(&str, &[(&str, &str)])] = &[
         &[
             (
                 "stream_subscribe",
-                "Real-time data streams (WebSocket/SSE, CEX feeds)",
+                "Real-time data streams (WebSocket/SSE/MQTT, CEX feeds)",
             ),
             (
                 "alert_rule",
                 "Conditional monitoring alerts (price/indicator/change rate)",
             ),
+            (
+                "backtest",
+                "Rule-based strategy backtests over stored price history",
+            ),
         ],
     ),
     ("⛓️ Blockchain", &[]),
@@ -212,6 +267,28 @@ const BUILTIN_TOOLS: &[(&str, &[(&str, &str)])] = &[
             ("spawn", "Spawn sub-agents for parallel execution"),
             ("list_tasks", "View task status"),
             ("cron", "Scheduled task management"),
+            ("project", "Long-term projects, TODOs and milestones"),
+            ("todo", "Personal TODO list with due dates"),
+            ("calendar", "Free/busy, conflict checks and meeting slots"),
+            (
+                "birthdays",
+                "Birthday/anniversary reminders and greeting drafts",
+            ),
+            ("habits", "Habit tracker with streaks and check-ins"),
+            (
+                "follow_up",
+                "Follow-ups that return to the originating chat",
+            ),
+            ("travel", "Flight/train status and delay/gate alerts"),
+            (
+                "parcel_track",
+                "Parcel tracking with carrier auto-detection",
+            ),
+            ("subscriptions", "Recurring charges and renewal reminders"),
+            (
+                "receipts",
+                "Receipt/invoice extraction and expense CSV export",
+            ),
             ("list_skills", "Skill learning status query"),
             ("capability_evolve", "Self-learn new tools via evolution"),
         ],
@@ -437,6 +514,19 @@ pub async fn run(
                 }
             }
         }
+        match super::provider::create_evolution_audit_provider(&config) {
+            Ok(Some(audit_provider)) => {
+                runtime.set_evolution_audit_provider(audit_provider);
+                info!("Evolution audit model configured");
+            }
+            Ok(None) => {}
+            Err(e) => {
+                warn!(
+                    "Failed to create evolution audit provider: {}, audits use the draft model",
+                    e
+                );
+            }
+        }
 
         if let Some(ref store) = memory_store_handle {
             runtime.set_memory_store(store.clone());
@@ -461,7 +551,7 @@ pub async fn run(
                 if let Ok(event) = serde_json::from_str::<serde_json::Value>(&event_str) {
                     let event_type = event.get("type").and_then(|v| v.as_str()).unwrap_or("");
                     match event_type {
-                        "token" => {
+                        "message_delta" => {
                             if let Some(delta) = event.get("delta").and_then(|v| v.as_str()) {
                                 print!("{}", delta);
                                 let _ = stdout.flush();
@@ -636,6 +726,19 @@ pub async fn run(
                 }
             }
         }
+        match super::provider::create_evolution_audit_provider(&config) {
+            Ok(Some(audit_provider)) => {
+                runtime.set_evolution_audit_provider(audit_provider);
+                info!("Evolution audit model configured");
+            }
+            Ok(None) => {}
+            Err(e) => {
+                warn!(
+                    "Failed to create evolution audit provider: {}, audits use the draft model",
+                    e
+                );
+            }
+        }
 
         // Create event broadcast channel for streaming output
         let (event_tx, mut event_rx) = broadcast::channel::<String>(256);
@@ -666,13 +769,16 @@ pub async fn run(
         // Create and start CronService
         let tick_interval_secs = config.cron_tick_interval_secs;
         let default_timezone = config.default_timezone.as_deref();
-        let cron_service = Arc::new(CronService::new_with_options(
-            paths.clone(),
-            inbound_tx.clone(),
-            Some(agent_id.clone()),
-            Some(tick_interval_secs),
-            default_timezone,
-        ));
+        let cron_service = Arc::new(
+            CronService::new_with_options(
+                paths.clone(),
+                inbound_tx.clone(),
+                Some(agent_id.clone()),
+                Some(tick_interval_secs),
+                default_timezone,
+            )
+            .with_maintenance(config.maintenance.clone()),
+        );
         cron_service.set_event_emitter(event_emitter);
         cron_service.load().await?;
 
@@ -705,7 +811,7 @@ pub async fn run(
                 if let Ok(event) = serde_json::from_str::<serde_json::Value>(&event_str) {
                     let event_type = event.get("type").and_then(|v| v.as_str()).unwrap_or("");
                     match event_type {
-                        "token" => {
+                        "message_delta" => {
                             // Streaming text token - print immediately
                             if let Some(delta) = event.get("delta").and_then(|v| v.as_str()) {
                                 print!("{}", delta);
@@ -1000,6 +1106,7 @@ pub async fn run(
 /// Read a line of input with real-time command picker support.
 /// When user types "/", immediately show command suggestions below the input line.
 /// Supports backspace to delete and escape to cancel picker.
+#[allow(clippy::collapsible_match)]
 fn read_line_with_command_picker(
     paths: &Paths,
     stdout: &mut std::io::Stdout,
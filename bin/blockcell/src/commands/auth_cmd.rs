@@ -0,0 +1,98 @@
+use blockcell_core::login_guard::LoginGuard;
+use blockcell_core::Paths;
+use blockcell_storage::admin_audit::{AdminAction, AdminAuditLog};
+
+fn format_time(ts: i64) -> String {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+/// Show addresses with failed WebUI logins and active lockouts.
+pub async fn status() -> anyhow::Result<()> {
+    let paths = Paths::default();
+    let guard = LoginGuard::open(&paths)?;
+    let now = chrono::Utc::now().timestamp();
+
+    println!();
+    if guard.global_locked_until() > now {
+        println!(
+            "⛔ All password logins paused until {} (too many failures overall)",
+            format_time(guard.global_locked_until())
+        );
+        println!();
+    }
+    if guard.addresses().is_empty() {
+        println!("(No failed logins recorded.)");
+        return Ok(());
+    }
+    println!(
+        "🔐 Failed WebUI logins — {}",
+        paths.login_lockouts_file().display()
+    );
+    println!();
+    println!(
+        "  {:<40} {:<9} {:<9} Locked until",
+        "Address", "Failures", "Lockouts"
+    );
+    println!("  {}", "-".repeat(80));
+    for (addr, record) in guard.addresses() {
+        println!(
+            "  {:<40} {:<9} {:<9} {}",
+            addr,
+            record.failures.len(),
+            record.lockouts,
+            if record.locked_until > now {
+                format_time(record.locked_until)
+            } else {
+                "-".to_string()
+            }
+        );
+    }
+    println!();
+    Ok(())
+}
+
+/// Lift the lockout of one address, or all of them.
+pub async fn unlock(address: Option<&str>, all: bool) -> anyhow::Result<()> {
+    let paths = Paths::default();
+    let mut guard = LoginGuard::open(&paths)?;
+    let (lifted, target) = match (address.map(str::trim), all) {
+        (_, true) => {
+            let count = guard.unlock_all();
+            println!("✓ Cleared {} address(es) and any global pause", count);
+            (true, "*".to_string())
+        }
+        (Some(addr), false) if !addr.is_empty() => {
+            let lifted = guard.unlock(addr);
+            if lifted {
+                println!("✓ Unlocked {}", addr);
+            } else {
+                println!("No failed logins recorded for {}", addr);
+            }
+            (lifted, addr.to_string())
+        }
+        _ => anyhow::bail!("Give an address to unlock, or --all"),
+    };
+    if !lifted {
+        return Ok(());
+    }
+    guard.save()?;
+
+    let audit = AdminAuditLog::new(paths.admin_audit_file());
+    audit.append(AdminAction {
+        actor: "cli".to_string(),
+        client_ip: None,
+        action: "auth.unlock".to_string(),
+        method: "CLI".to_string(),
+        path: "blockcell auth unlock".to_string(),
+        status: 200,
+        details: serde_json::json!({ "address": target }),
+    })?;
+    println!("A running gateway applies this on the next login attempt.");
+    Ok(())
+}
@@ -0,0 +1,554 @@
+//! `blockcell bench`: run a prompt/tool-use suite against one or more models and compare
+//! latency, cost and pass rate with the previous run.
+//!
+//! The suite lives in `workspace/bench/suite.json` (`blockcell bench init` writes the
+//! built-in one there for editing); every run is saved to `workspace/bench/runs/`. Being a
+//! plain CLI command it can be scheduled with cron/systemd after provider price changes.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use blockcell_core::types::{ChatMessage, LLMResponse};
+use blockcell_core::{Config, Paths};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+const CALL_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Expectation {
+    /// Substrings the answer must contain (case-insensitive).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    contains: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    regex: Option<String>,
+    /// Name of the tool the model must call.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tool_call: Option<String>,
+    /// Argument keys the tool call must include.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tool_args: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_latency_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BenchCase {
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    system: Option<String>,
+    prompt: String,
+    /// OpenAI-style function schemas offered to the model.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<Value>,
+    #[serde(default)]
+    expect: Expectation,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BenchSuite {
+    cases: Vec<BenchCase>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CaseResult {
+    case: String,
+    success: bool,
+    latency_ms: u64,
+    input_tokens: u64,
+    output_tokens: u64,
+    cost_usd: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ModelSummary {
+    model: String,
+    provider: Option<String>,
+    passed: usize,
+    total: usize,
+    avg_latency_ms: u64,
+    total_cost_usd: Option<f64>,
+    cases: Vec<CaseResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BenchRun {
+    started_at: String,
+    suite: String,
+    models: Vec<ModelSummary>,
+}
+
+fn bench_dir(paths: &Paths) -> PathBuf {
+    paths.workspace().join("bench")
+}
+
+fn builtin_suite() -> BenchSuite {
+    let case = |name: &str, prompt: &str, expect: Expectation| BenchCase {
+        name: name.to_string(),
+        system: None,
+        prompt: prompt.to_string(),
+        tools: Vec::new(),
+        expect,
+    };
+    BenchSuite {
+        cases: vec![
+            case(
+                "arithmetic",
+                "A train leaves at 14:35 and the trip takes 2 h 50 min. When does it arrive? Answer with HH:MM only.",
+                Expectation {
+                    contains: vec!["17:25".to_string()],
+                    ..Default::default()
+                },
+            ),
+            case(
+                "json_output",
+                "Return a JSON object with the keys \"city\" and \"country\" for the Eiffel Tower. Output JSON only.",
+                Expectation {
+                    regex: Some(r#""city"\s*:\s*"Paris""#.to_string()),
+                    ..Default::default()
+                },
+            ),
+            case(
+                "instruction_following",
+                "Reply with exactly the word OK in uppercase and nothing else.",
+                Expectation {
+                    regex: Some(r"^\s*OK\.?\s*$".to_string()),
+                    ..Default::default()
+                },
+            ),
+            case(
+                "chinese",
+                "用一句话解释什么是光合作用。",
+                Expectation {
+                    contains: vec!["光".to_string()],
+                    ..Default::default()
+                },
+            ),
+            BenchCase {
+                name: "tool_call".to_string(),
+                system: Some("Use the provided tools when they can answer the question.".to_string()),
+                prompt: "What's the weather in Berlin right now?".to_string(),
+                tools: vec![json!({
+                    "type": "function",
+                    "function": {
+                        "name": "get_weather",
+                        "description": "Current weather for a city",
+                        "parameters": {
+                            "type": "object",
+                            "properties": {"city": {"type": "string"}},
+                            "required": ["city"]
+                        }
+                    }
+                })],
+                expect: Expectation {
+                    tool_call: Some("get_weather".to_string()),
+                    tool_args: vec!["city".to_string()],
+                    ..Default::default()
+                },
+            },
+        ],
+    }
+}
+
+/// Check a response against the case's expectations; `Err` explains the first failure.
+fn evaluate(expect: &Expectation, response: &LLMResponse, latency_ms: u64) -> Result<(), String> {
+    let text = response.content.as_deref().unwrap_or_default();
+    if let Some(tool) = &expect.tool_call {
+        let Some(call) = response.tool_calls.iter().find(|c| &c.name == tool) else {
+            return Err(format!("expected a call to `{}`", tool));
+        };
+        if let Some(missing) = expect
+            .tool_args
+            .iter()
+            .find(|k| call.arguments.get(k.as_str()).is_none())
+        {
+            return Err(format!("tool call is missing argument `{}`", missing));
+        }
+    }
+    let lower = text.to_lowercase();
+    if let Some(missing) = expect
+        .contains
+        .iter()
+        .find(|s| !lower.contains(&s.to_lowercase()))
+    {
+        return Err(format!("answer does not contain \"{}\"", missing));
+    }
+    if let Some(pattern) = &expect.regex {
+        let re = regex::Regex::new(pattern).map_err(|e| format!("bad regex: {}", e))?;
+        if !re.is_match(text) {
+            return Err(format!("answer does not match /{}/", pattern));
+        }
+    }
+    if let Some(max) = expect.max_latency_ms {
+        if latency_ms > max {
+            return Err(format!("took {}ms (limit {}ms)", latency_ms, max));
+        }
+    }
+    Ok(())
+}
+
+/// Models to benchmark: `--model` flags, else every `modelPool` entry, else the default model.
+#[allow(clippy::type_complexity)]
+fn targets(
+    config: &Config,
+    models: &[String],
+) -> Vec<(String, Option<String>, Option<(f64, f64)>)> {
+    let defaults = &config.agents.defaults;
+    if !models.is_empty() {
+        return models.iter().map(|m| (m.clone(), None, None)).collect();
+    }
+    if defaults.model_pool.is_empty() {
+        return vec![(defaults.model.clone(), defaults.provider.clone(), None)];
+    }
+    defaults
+        .model_pool
+        .iter()
+        .map(|e| {
+            let price = e.input_price.zip(e.output_price);
+            (e.model.clone(), Some(e.provider.clone()), price)
+        })
+        .collect()
+}
+
+async fn run_model(
+    config: &Config,
+    suite: &BenchSuite,
+    model: &str,
+    provider_name: Option<&str>,
+    price: Option<(f64, f64)>,
+    repeat: usize,
+) -> anyhow::Result<ModelSummary> {
+    let provider = blockcell_providers::create_provider(config, model, provider_name)?;
+    let price = price.or_else(|| {
+        blockcell_core::model_meta::lookup(config, model)
+            .and_then(|m| m.input_price.zip(m.output_price))
+    });
+
+    let mut cases = Vec::new();
+    for case in &suite.cases {
+        for _ in 0..repeat.max(1) {
+            let mut messages = Vec::new();
+            if let Some(system) = &case.system {
+                messages.push(ChatMessage::system(system));
+            }
+            messages.push(ChatMessage::user(&case.prompt));
+
+            let started = Instant::now();
+            let outcome =
+                tokio::time::timeout(CALL_TIMEOUT, provider.chat(&messages, &case.tools)).await;
+            let latency_ms = started.elapsed().as_millis() as u64;
+            let result = match outcome {
+                Ok(Ok(response)) => {
+                    let (input, output) = blockcell_agent::budget::usage_tokens(&response.usage);
+                    let verdict = evaluate(&case.expect, &response, latency_ms);
+                    CaseResult {
+                        case: case.name.clone(),
+                        success: verdict.is_ok(),
+                        latency_ms,
+                        input_tokens: input,
+                        output_tokens: output,
+                        cost_usd: price
+                            .map(|(pi, po)| (input as f64 * pi + output as f64 * po) / 1_000_000.0),
+                        error: verdict.err(),
+                    }
+                }
+                Ok(Err(e)) => failed_case(&case.name, latency_ms, e.to_string()),
+                Err(_) => failed_case(&case.name, latency_ms, "timeout".to_string()),
+            };
+            println!(
+                "  {} {:<24} {:>6}ms{}",
+                if result.success { "✓" } else { "✗" },
+                result.case,
+                result.latency_ms,
+                result
+                    .error
+                    .as_deref()
+                    .map(|e| format!("  {}", e))
+                    .unwrap_or_default()
+            );
+            cases.push(result);
+        }
+    }
+
+    let total = cases.len();
+    let passed = cases.iter().filter(|c| c.success).count();
+    let avg_latency_ms = if total == 0 {
+        0
+    } else {
+        cases.iter().map(|c| c.latency_ms).sum::<u64>() / total as u64
+    };
+    let total_cost_usd = price.map(|_| cases.iter().filter_map(|c| c.cost_usd).sum());
+    Ok(ModelSummary {
+        model: model.to_string(),
+        provider: provider_name.map(str::to_string),
+        passed,
+        total,
+        avg_latency_ms,
+        total_cost_usd,
+        cases,
+    })
+}
+
+fn failed_case(name: &str, latency_ms: u64, error: String) -> CaseResult {
+    CaseResult {
+        case: name.to_string(),
+        success: false,
+        latency_ms,
+        input_tokens: 0,
+        output_tokens: 0,
+        cost_usd: None,
+        error: Some(error.chars().take(160).collect()),
+    }
+}
+
+fn load_runs(dir: &Path) -> Vec<BenchRun> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|rd| {
+            rd.filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+        .iter()
+        .filter_map(|p| std::fs::read_to_string(p).ok())
+        .filter_map(|s| serde_json::from_str(&s).ok())
+        .collect()
+}
+
+/// One line per model comparing with the same model in `previous`.
+fn diff_lines(previous: &BenchRun, current: &BenchRun) -> Vec<String> {
+    current
+        .models
+        .iter()
+        .filter_map(|now| {
+            let before = previous.models.iter().find(|m| m.model == now.model)?;
+            let rate = |m: &ModelSummary| {
+                if m.total == 0 {
+                    0.0
+                } else {
+                    m.passed as f64 * 100.0 / m.total as f64
+                }
+            };
+            let latency_pct = if before.avg_latency_ms == 0 {
+                0.0
+            } else {
+                (now.avg_latency_ms as f64 - before.avg_latency_ms as f64) * 100.0
+                    / before.avg_latency_ms as f64
+            };
+            let cost = match (before.total_cost_usd, now.total_cost_usd) {
+                (Some(b), Some(n)) => format!(", cost {:+.4} USD", n - b),
+                _ => String::new(),
+            };
+            Some(format!(
+                "{}: pass rate {:+.0} pts, latency {:+.0}%{}",
+                now.model,
+                rate(now) - rate(before),
+                latency_pct,
+                cost
+            ))
+        })
+        .collect()
+}
+
+/// Write the built-in suite to `workspace/bench/suite.json` for editing.
+pub async fn init(force: bool) -> anyhow::Result<()> {
+    let paths = Paths::default();
+    let path = bench_dir(&paths).join("suite.json");
+    if path.exists() && !force {
+        anyhow::bail!(
+            "{} already exists (use --force to overwrite)",
+            path.display()
+        );
+    }
+    std::fs::create_dir_all(bench_dir(&paths))?;
+    std::fs::write(&path, serde_json::to_string_pretty(&builtin_suite())?)?;
+    println!("✓ Benchmark suite written to {}", path.display());
+    Ok(())
+}
+
+/// Run the suite against each target model, save the run and print a diff.
+pub async fn run(
+    suite_path: Option<String>,
+    models: Vec<String>,
+    repeat: usize,
+) -> anyhow::Result<()> {
+    let paths = Paths::default();
+    let config = Config::load_or_default(&paths)?;
+    let default_suite = bench_dir(&paths).join("suite.json");
+    let (suite, suite_name) = match suite_path
+        .map(PathBuf::from)
+        .or_else(|| default_suite.exists().then(|| default_suite.clone()))
+    {
+        Some(path) => {
+            let suite: BenchSuite = serde_json::from_str(&std::fs::read_to_string(&path)?)
+                .map_err(|e| anyhow::anyhow!("Invalid suite {}: {}", path.display(), e))?;
+            (suite, path.display().to_string())
+        }
+        None => (builtin_suite(), "built-in".to_string()),
+    };
+
+    let runs_dir = bench_dir(&paths).join("runs");
+    let previous = load_runs(&runs_dir).pop();
+
+    println!();
+    println!(
+        "🏁 Benchmark suite: {} ({} cases)",
+        suite_name,
+        suite.cases.len()
+    );
+    let mut run = BenchRun {
+        started_at: chrono::Utc::now().to_rfc3339(),
+        suite: suite_name,
+        models: Vec::new(),
+    };
+    for (model, provider, price) in targets(&config, &models) {
+        println!();
+        println!("▶ {}", model);
+        match run_model(&config, &suite, &model, provider.as_deref(), price, repeat).await {
+            Ok(summary) => run.models.push(summary),
+            Err(e) => println!("  ⚠ Skipped: {}", e),
+        }
+    }
+
+    println!();
+    println!(
+        "  {:<32} {:>7} {:>10} {:>12}",
+        "Model", "Passed", "Avg ms", "Cost USD"
+    );
+    for m in &run.models {
+        println!(
+            "  {:<32} {:>3}/{:<3} {:>10} {:>12}",
+            m.model,
+            m.passed,
+            m.total,
+            m.avg_latency_ms,
+            m.total_cost_usd
+                .map(|c| format!("{:.4}", c))
+                .unwrap_or_else(|| "-".to_string())
+        );
+    }
+
+    if let Some(previous) = &previous {
+        let lines = diff_lines(previous, &run);
+        if !lines.is_empty() {
+            println!();
+            println!("Compared with {}:", previous.started_at);
+            for line in lines {
+                println!("  {}", line);
+            }
+        }
+    }
+
+    std::fs::create_dir_all(&runs_dir)?;
+    let file = runs_dir.join(format!(
+        "{}.json",
+        chrono::Utc::now().format("%Y%m%d-%H%M%S")
+    ));
+    std::fs::write(&file, serde_json::to_string_pretty(&run)?)?;
+    println!();
+    println!("Saved to {}", file.display());
+    Ok(())
+}
+
+/// List recent runs.
+pub async fn history(limit: usize) -> anyhow::Result<()> {
+    let paths = Paths::default();
+    let runs = load_runs(&bench_dir(&paths).join("runs"));
+    if runs.is_empty() {
+        println!("(No benchmark runs yet. Use `blockcell bench run`.)");
+        return Ok(());
+    }
+    println!();
+    for run in runs.iter().rev().take(limit) {
+        println!("📅 {}  suite: {}", run.started_at, run.suite);
+        for m in &run.models {
+            println!(
+                "  {:<32} {:>3}/{:<3} {:>8}ms  {}",
+                m.model,
+                m.passed,
+                m.total,
+                m.avg_latency_ms,
+                m.total_cost_usd
+                    .map(|c| format!("${:.4}", c))
+                    .unwrap_or_default()
+            );
+        }
+    }
+    println!();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blockcell_core::types::ToolCallRequest;
+
+    #[test]
+    fn test_evaluate_expectations() {
+        let suite = builtin_suite();
+        let tool_case = suite.cases.iter().find(|c| c.name == "tool_call").unwrap();
+        let mut response = LLMResponse {
+            content: Some("It is sunny".to_string()),
+            ..Default::default()
+        };
+        assert!(evaluate(&tool_case.expect, &response, 100).is_err());
+        response.tool_calls.push(ToolCallRequest {
+            id: "1".to_string(),
+            name: "get_weather".to_string(),
+            arguments: json!({"city": "Berlin"}),
+            thought_signature: None,
+        });
+        assert!(evaluate(&tool_case.expect, &response, 100).is_ok());
+
+        let ok_case = suite
+            .cases
+            .iter()
+            .find(|c| c.name == "instruction_following")
+            .unwrap();
+        let reply = |text: &str| LLMResponse {
+            content: Some(text.to_string()),
+            ..Default::default()
+        };
+        assert!(evaluate(&ok_case.expect, &reply("OK"), 10).is_ok());
+        assert!(evaluate(&ok_case.expect, &reply("Sure! OK"), 10).is_err());
+
+        let slow = Expectation {
+            max_latency_ms: Some(50),
+            ..Default::default()
+        };
+        assert!(evaluate(&slow, &reply("x"), 80).is_err());
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        let summary = |passed: usize, latency: u64, cost: f64| ModelSummary {
+            model: "deepseek-chat".to_string(),
+            provider: None,
+            passed,
+            total: 4,
+            avg_latency_ms: latency,
+            total_cost_usd: Some(cost),
+            cases: Vec::new(),
+        };
+        let run = |m: ModelSummary| BenchRun {
+            started_at: String::new(),
+            suite: "built-in".to_string(),
+            models: vec![m],
+        };
+        let lines = diff_lines(&run(summary(2, 1000, 0.01)), &run(summary(3, 1500, 0.02)));
+        assert_eq!(
+            lines,
+            vec!["deepseek-chat: pass rate +25 pts, latency +50%, cost +0.0100 USD"]
+        );
+    }
+}
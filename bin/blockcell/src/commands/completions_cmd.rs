@@ -70,6 +70,25 @@ fn build_cli() -> clap::Command {
                 .subcommand(clap::Command::new("test").about("Test a tool"))
                 .subcommand(clap::Command::new("toggle").about("Enable/disable a tool")),
         )
+        .subcommand(
+            clap::Command::new("secrets")
+                .about("Manage secrets")
+                .subcommand(clap::Command::new("list").about("List secret names"))
+                .subcommand(clap::Command::new("set").about("Store a secret"))
+                .subcommand(clap::Command::new("remove").about("Remove a secret")),
+        )
+        .subcommand(
+            clap::Command::new("prompt")
+                .about("Inspect the system prompt")
+                .subcommand(clap::Command::new("show").about("Show prompt fragments")),
+        )
+        .subcommand(
+            clap::Command::new("telemetry")
+                .about("Preview and opt in to telemetry")
+                .subcommand(clap::Command::new("preview").about("Show the next report"))
+                .subcommand(clap::Command::new("enable").about("Opt in to telemetry"))
+                .subcommand(clap::Command::new("disable").about("Stop sending telemetry")),
+        )
         .subcommand(
             clap::Command::new("run")
                 .about("Execute a tool or message directly")
@@ -145,6 +164,27 @@ fn build_cli() -> clap::Command {
                 .subcommand(clap::Command::new("add").about("Add a rule"))
                 .subcommand(clap::Command::new("remove").about("Remove a rule")),
         )
+        .subcommand(
+            clap::Command::new("auth")
+                .about("WebUI login lockouts")
+                .subcommand(clap::Command::new("status").about("Show failed logins"))
+                .subcommand(clap::Command::new("unlock").about("Lift a login lockout")),
+        )
+        .subcommand(
+            clap::Command::new("hooks")
+                .about("Manage webhook hooks")
+                .subcommand(clap::Command::new("list").about("List hooks"))
+                .subcommand(clap::Command::new("add").about("Add a hook"))
+                .subcommand(clap::Command::new("remove").about("Remove a hook")),
+        )
+        .subcommand(
+            clap::Command::new("docs")
+                .about("Ingest and search documents")
+                .subcommand(clap::Command::new("ingest").about("Ingest a file, directory or URL"))
+                .subcommand(clap::Command::new("list").about("List documents"))
+                .subcommand(clap::Command::new("search").about("Search documents"))
+                .subcommand(clap::Command::new("remove").about("Remove a document")),
+        )
         .subcommand(
             clap::Command::new("streams")
                 .about("Manage data streams")
@@ -1,5 +1,6 @@
 use blockcell_core::{
-    config::{parse_json5_value, stringify_json5_pretty},
+    config::{parse_json5_value, parse_json5_value_unexpanded, stringify_json5_pretty},
+    config_migration::{self, KeyIssueKind, CURRENT_CONFIG_VERSION},
     Config, Paths,
 };
 use serde_json::Value;
@@ -26,9 +27,37 @@ pub async fn schema() -> anyhow::Result<()> {
         "description": "blockcell configuration file (~/.blockcell/config.json5)",
         "type": "object",
         "properties": {
+            "configVersion": {
+                "type": "integer",
+                "default": CURRENT_CONFIG_VERSION,
+                "description": "Schema version; older files are backed up and migrated on start"
+            },
             "providers": {
                 "type": "object",
                 "description": "LLM provider configurations keyed by name (openai, deepseek, kimi, anthropic, gemini, ollama, ...)",
+                "properties": {
+                    "failover": {
+                        "type": "object",
+                        "description": "Retry with backoff on 429/5xx/timeouts, then fall back through these providers in order",
+                        "properties": {
+                            "chain": {
+                                "type": "array",
+                                "items": {
+                                    "type": "object",
+                                    "properties": {
+                                        "provider": { "type": "string" },
+                                        "model": { "type": "string" }
+                                    },
+                                    "required": ["provider", "model"]
+                                }
+                            },
+                            "maxRetries": { "type": "integer", "default": 2 },
+                            "initialBackoffMs": { "type": "integer", "default": 500 },
+                            "maxBackoffMs": { "type": "integer", "default": 8000 },
+                            "attemptTimeoutSecs": { "type": "integer", "default": 120 }
+                        }
+                    }
+                },
                 "additionalProperties": {
                     "type": "object",
                     "properties": {
@@ -136,7 +165,17 @@ pub async fn schema() -> anyhow::Result<()> {
                             "enabled": { "type": "boolean" },
                             "token": { "type": "string" },
                             "allowFrom": { "type": "array", "items": { "type": "string" } },
-                            "proxy": { "type": "string" }
+                            "proxy": { "type": "string" },
+                            "inline": {
+                                "type": "object",
+                                "properties": {
+                                    "enabled": { "type": "boolean", "default": false },
+                                    "allowFrom": { "type": "array", "items": { "type": "string" } },
+                                    "timeoutSecs": { "type": "integer", "default": 8 },
+                                    "maxFetchChars": { "type": "integer", "default": 8000 },
+                                    "cacheTimeSecs": { "type": "integer", "default": 30 }
+                                }
+                            }
                         }
                     },
                     "feishu": {
@@ -203,6 +242,27 @@ pub async fn schema() -> anyhow::Result<()> {
                             "allowFrom": { "type": "array", "items": { "type": "string" } },
                             "pollIntervalSecs": { "type": "integer", "default": 10 }
                         }
+                    },
+                    "outbound": {
+                        "type": "object",
+                        "properties": {
+                            "maxParts": { "type": "integer", "default": 4 },
+                            "linkTtlSecs": { "type": "integer", "default": 604800 },
+                            "downgradeFormatting": { "type": "boolean", "default": true }
+                        }
+                    },
+                    "reactions": {
+                        "type": "object",
+                        "properties": {
+                            "enabled": { "type": "boolean", "default": false },
+                            "actions": {
+                                "type": "object",
+                                "additionalProperties": {
+                                    "type": "string",
+                                    "enum": ["regenerate", "save_memory", "approve", "delete_reminder"]
+                                }
+                            }
+                        }
                     }
                 }
             },
@@ -359,6 +419,106 @@ pub async fn providers() -> anyhow::Result<()> {
         println!("  ⚠ No API key configured");
     }
     println!();
+
+    if let Some(report) = blockcell_providers::health::load_report(&paths.provider_health_file()) {
+        if !report.providers.is_empty() {
+            let age_secs = (chrono::Utc::now().timestamp_millis() as u64)
+                .saturating_sub(report.updated_ms)
+                / 1000;
+            println!(
+                "🩺 Provider health (last 30 min, updated {}s ago)",
+                age_secs
+            );
+            println!();
+            for h in &report.providers {
+                let fmt_ms = |v: Option<u64>| v.map_or("-".to_string(), |ms| format!("{}ms", ms));
+                println!(
+                    "  {:<36} calls: {:<4} errors: {:>5.1}%  avg: {:<8} p95: {:<8}",
+                    h.key,
+                    h.samples,
+                    h.error_rate * 100.0,
+                    fmt_ms(h.avg_latency_ms),
+                    fmt_ms(h.p95_latency_ms)
+                );
+                if let Some(err) = &h.last_error {
+                    println!("      last error: {}", err);
+                }
+            }
+            if config.agents.defaults.latency_routing {
+                println!();
+                println!("  Latency routing: on (within modelPool groups)");
+            }
+            println!();
+        }
+    }
+    Ok(())
+}
+
+/// Check the config file: parse errors, schema version and pending migrations, and
+/// keys that are unknown (ignored) or deprecated. `strict` fails on any key issue.
+pub async fn validate(strict: bool) -> anyhow::Result<()> {
+    let paths = Paths::new();
+    let path = paths.config_file();
+    println!();
+    if !path.exists() {
+        println!(
+            "  No config file at {}; defaults are in use.",
+            path.display()
+        );
+        println!();
+        return Ok(());
+    }
+    println!("🔎 Validating {}", path.display());
+
+    let content = std::fs::read_to_string(&path)?;
+    let raw = parse_json5_value_unexpanded(&content, Some(&path))?;
+    let version = config_migration::file_version(&raw);
+    if version > CURRENT_CONFIG_VERSION {
+        anyhow::bail!(
+            "configVersion {} is newer than this blockcell supports ({})",
+            version,
+            CURRENT_CONFIG_VERSION
+        );
+    }
+    let pending = config_migration::pending_migrations(version);
+    if pending.is_empty() {
+        println!("  ✓ configVersion {} (current)", version);
+    } else {
+        println!(
+            "  ⚠ configVersion {} (current is {}); applied with a backup on next start:",
+            version, CURRENT_CONFIG_VERSION
+        );
+        for migration in &pending {
+            println!("      - {}", migration);
+        }
+    }
+
+    let config = Config::load(&path).map_err(|e| anyhow::anyhow!("  ✗ {}", e))?;
+    println!("  ✓ Parses and passes value checks");
+
+    let issues = config_migration::check_keys(&raw, &serde_json::to_value(&config)?);
+    for issue in &issues {
+        let hint = issue
+            .suggestion
+            .as_deref()
+            .map(|s| match issue.kind {
+                KeyIssueKind::Unknown => format!(" (did you mean `{}`?)", s),
+                KeyIssueKind::Deprecated => format!(" (use `{}`)", s),
+            })
+            .unwrap_or_default();
+        match issue.kind {
+            KeyIssueKind::Unknown => println!("  ✗ unknown key `{}`, ignored{}", issue.path, hint),
+            KeyIssueKind::Deprecated => println!("  ⚠ deprecated key `{}`{}", issue.path, hint),
+        }
+    }
+    if issues.is_empty() {
+        println!("  ✓ No unknown or deprecated keys");
+    }
+    println!();
+
+    if strict && !issues.is_empty() {
+        anyhow::bail!("{} config key issue(s)", issues.len());
+    }
     Ok(())
 }
 
@@ -1,15 +1,25 @@
-use blockcell_core::Paths;
-use blockcell_scheduler::{CronService, ScheduleKind};
+use blockcell_core::{Config, Paths};
+use blockcell_scheduler::{
+    next_cron_run_ms, CronJob, CronService, JobPayload, JobSchedule, JobState, ScheduleKind,
+};
 use chrono::{TimeZone, Utc};
 use tokio::sync::mpsc;
 
-/// List cron jobs for a given agent (read-only, reads from disk).
-/// agent_id: agent to query; empty string or "default" uses the default agent path.
-pub async fn list(show_all: bool, agent_id: &str) -> anyhow::Result<()> {
+/// Cron service for an agent with the configured default timezone, jobs loaded from disk.
+async fn load_service(agent_id: &str) -> anyhow::Result<CronService> {
+    let config = Config::load_or_default(&Paths::new())?;
     let paths = Paths::new().for_agent(agent_id);
     let (tx, _rx) = mpsc::channel(1);
-    let service = CronService::new(paths, tx);
+    let service =
+        CronService::new_with_options(paths, tx, None, None, config.default_timezone.as_deref());
     service.load().await?;
+    Ok(service)
+}
+
+/// List cron jobs for a given agent (read-only, reads from disk).
+/// agent_id: agent to query; empty string or "default" uses the default agent path.
+pub async fn list(show_all: bool, agent_id: &str) -> anyhow::Result<()> {
+    let service = load_service(agent_id).await?;
 
     let jobs = service.list_jobs().await;
 
@@ -26,23 +36,25 @@ pub async fn list(show_all: bool, agent_id: &str) -> anyhow::Result<()> {
         println!("Agent: {}", agent_id);
     }
     println!(
-        "{:<8} {:<22} {:<8} {:<18} Schedule",
+        "{:<8} {:<22} {:<8} {:<21} Schedule",
         "ID", "Name", "Enabled", "Next Run"
     );
-    println!("{}", "-".repeat(80));
+    println!("{}", "-".repeat(90));
 
     for job in &jobs {
         if !show_all && !job.enabled {
             continue;
         }
 
+        // Shown in the job's own timezone, with its abbreviation (e.g. EDT, CST).
+        let tz = service.job_timezone(job);
         let next_run = job
             .state
             .next_run_at_ms
             .map(|ms| {
-                Utc.timestamp_millis_opt(ms)
+                tz.timestamp_millis_opt(ms)
                     .single()
-                    .map(|dt| dt.format("%m-%d %H:%M:%S").to_string())
+                    .map(|dt| dt.format("%m-%d %H:%M:%S %Z").to_string())
                     .unwrap_or_else(|| "invalid".to_string())
             })
             .unwrap_or_else(|| "-".to_string());
@@ -62,12 +74,16 @@ pub async fn list(show_all: bool, agent_id: &str) -> anyhow::Result<()> {
                 format!("every {}s", secs)
             }
             ScheduleKind::Cron => {
-                format!("cron: {}", job.schedule.expr.as_deref().unwrap_or("-"))
+                format!(
+                    "cron: {} ({})",
+                    job.schedule.expr.as_deref().unwrap_or("-"),
+                    tz.name()
+                )
             }
         };
 
         println!(
-            "{:<8} {:<22} {:<8} {:<18} {}",
+            "{:<8} {:<22} {:<8} {:<21} {}",
             &job.id.chars().take(8).collect::<String>(),
             truncate(&job.name, 22),
             if job.enabled { "yes" } else { "no" },
@@ -83,6 +99,188 @@ pub async fn list(show_all: bool, agent_id: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Schedule options of `blockcell cron add`; exactly one of `every_secs`, `cron_expr`
+/// and `at` must be set.
+pub struct AddSchedule {
+    pub every_secs: Option<i64>,
+    pub cron_expr: Option<String>,
+    /// RFC 3339 time for a one-shot job.
+    pub at: Option<String>,
+    /// IANA timezone for `cron_expr`; without it the job follows `defaultTimezone`.
+    pub tz: Option<String>,
+}
+
+impl AddSchedule {
+    fn into_job_schedule(self) -> anyhow::Result<JobSchedule> {
+        if let Some(tz) = &self.tz {
+            if tz.parse::<chrono_tz::Tz>().is_err() {
+                anyhow::bail!(
+                    "Invalid timezone '{}'. Use an IANA timezone like 'Asia/Shanghai' or 'America/New_York'",
+                    tz
+                );
+            }
+        }
+        let mut schedule = JobSchedule {
+            kind: ScheduleKind::Every,
+            at_ms: None,
+            every_ms: None,
+            expr: None,
+            tz: None,
+            run_immediately: false,
+        };
+        match (self.every_secs, self.cron_expr, self.at) {
+            (Some(secs), None, None) => {
+                if secs <= 0 {
+                    anyhow::bail!("--every must be a positive number of seconds");
+                }
+                schedule.every_ms = Some(secs * 1000);
+            }
+            (None, Some(expr), None) => {
+                let now_ms = Utc::now().timestamp_millis();
+                if next_cron_run_ms(&expr, chrono_tz::Tz::UTC, now_ms).is_none() {
+                    anyhow::bail!(
+                        "Invalid cron expression '{}'. Use 6 fields: sec min hour day month weekday",
+                        expr
+                    );
+                }
+                schedule.kind = ScheduleKind::Cron;
+                schedule.expr = Some(expr);
+                schedule.tz = self.tz;
+            }
+            (None, None, Some(at)) => {
+                let at = chrono::DateTime::parse_from_rfc3339(&at).map_err(|e| {
+                    anyhow::anyhow!("Invalid --at time '{}' (expected RFC 3339): {}", at, e)
+                })?;
+                schedule.kind = ScheduleKind::At;
+                schedule.at_ms = Some(at.timestamp_millis());
+            }
+            _ => anyhow::bail!("Specify exactly one of --every, --cron or --at"),
+        }
+        Ok(schedule)
+    }
+}
+
+/// Add a reminder job. A running gateway picks it up from disk on its next tick.
+/// `delivery` is the `(channel, chat_id)` the reminder is sent to.
+pub async fn add(
+    name: &str,
+    message: &str,
+    schedule: AddSchedule,
+    delivery: Option<(String, String)>,
+    agent_id: &str,
+) -> anyhow::Result<()> {
+    let schedule = schedule.into_job_schedule()?;
+    let service = load_service(agent_id).await?;
+    let now_ms = Utc::now().timestamp_millis();
+    let one_shot = schedule.kind == ScheduleKind::At;
+    let (channel, to) = delivery.unzip();
+    let job = CronJob {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        enabled: true,
+        schedule,
+        payload: JobPayload {
+            kind: "reminder".to_string(),
+            message: message.to_string(),
+            deliver: channel.is_some(),
+            channel,
+            to,
+            script_kind: None,
+            skill_name: None,
+            context: None,
+            account_id: None,
+            role: None,
+        },
+        state: JobState::default(),
+        created_at_ms: now_ms,
+        updated_at_ms: now_ms,
+        delete_after_run: one_shot,
+        maintenance_only: false,
+    };
+
+    let tz = service.job_timezone(&job);
+    let next_run = match job.schedule.kind {
+        ScheduleKind::Cron => job
+            .schedule
+            .expr
+            .as_deref()
+            .and_then(|expr| next_cron_run_ms(expr, tz, now_ms)),
+        ScheduleKind::Every => job.schedule.every_ms.map(|every_ms| now_ms + every_ms),
+        ScheduleKind::At => job.schedule.at_ms,
+    };
+    let job_id = job.id.clone();
+    service.add_job(job).await?;
+
+    println!("Added cron job '{}' ({})", name, job_id);
+    if let Some(dt) = next_run.and_then(|ms| tz.timestamp_millis_opt(ms).single()) {
+        println!("Next run: {}", dt.format("%Y-%m-%d %H:%M:%S %Z"));
+    }
+    Ok(())
+}
+
+/// Show the run log of one job. `job_id` may be a unique prefix of a current job's id;
+/// otherwise it is used as-is so runs of deleted jobs stay reachable.
+pub async fn history(job_id: &str, limit: usize, agent_id: &str) -> anyhow::Result<()> {
+    let paths = Paths::new().for_agent(agent_id);
+    let (tx, _rx) = mpsc::channel(1);
+    let service = CronService::new(paths, tx);
+    service.load().await?;
+
+    let jobs = service.list_jobs().await;
+    let matches: Vec<_> = jobs.iter().filter(|j| j.id.starts_with(job_id)).collect();
+    let (job_id, name) = match matches.as_slice() {
+        [job] => (job.id.clone(), Some(job.name.clone())),
+        [] => (job_id.to_string(), None),
+        _ => anyhow::bail!(
+            "Job id prefix '{}' matches {} jobs; use a longer prefix",
+            job_id,
+            matches.len()
+        ),
+    };
+
+    let runs = service.run_history(&job_id, limit)?;
+    let name = name.or_else(|| runs.first().map(|r| r.job_name.clone()));
+    println!("Job: {} ({})", name.as_deref().unwrap_or("-"), job_id);
+    if runs.is_empty() {
+        println!("No recorded runs.");
+        return Ok(());
+    }
+
+    println!(
+        "{:<17} {:<9} {:<8} {:<9} Result",
+        "Started", "Trigger", "Status", "Duration"
+    );
+    println!("{}", "-".repeat(80));
+    for run in &runs {
+        let started = Utc
+            .timestamp_millis_opt(run.started_at_ms)
+            .single()
+            .map(|dt| dt.format("%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| "invalid".to_string());
+        let duration = run
+            .finished_at_ms
+            .map(|end| format!("{:.1}s", (end - run.started_at_ms).max(0) as f64 / 1000.0))
+            .unwrap_or_else(|| "-".to_string());
+        let detail = run
+            .error
+            .as_deref()
+            .or(run.result.as_deref())
+            .unwrap_or("")
+            .replace('\n', " ");
+        println!(
+            "{:<17} {:<9} {:<8} {:<9} {}",
+            started,
+            run.trigger,
+            run.status.as_str(),
+            duration,
+            truncate(&detail, 60)
+        );
+    }
+
+    println!("\nShowing {} run(s)", runs.len());
+    Ok(())
+}
+
 fn truncate(s: &str, max_chars: usize) -> String {
     if s.chars().count() <= max_chars {
         s.to_string()
@@ -91,3 +289,46 @@ fn truncate(s: &str, max_chars: usize) -> String {
         format!("{}...", truncated)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(every: Option<i64>, cron: Option<&str>, at: Option<&str>) -> AddSchedule {
+        AddSchedule {
+            every_secs: every,
+            cron_expr: cron.map(str::to_string),
+            at: at.map(str::to_string),
+            tz: None,
+        }
+    }
+
+    #[test]
+    fn test_add_schedule_keeps_timezone_on_cron_jobs() {
+        let mut cron = schedule(None, Some("0 30 9 * * Mon-Fri"), None);
+        cron.tz = Some("America/New_York".to_string());
+        let job_schedule = cron.into_job_schedule().unwrap();
+        assert_eq!(job_schedule.kind, ScheduleKind::Cron);
+        assert_eq!(job_schedule.tz.as_deref(), Some("America/New_York"));
+
+        let at = schedule(None, None, Some("2026-03-01T09:00:00+08:00"))
+            .into_job_schedule()
+            .unwrap();
+        assert_eq!(at.kind, ScheduleKind::At);
+        assert_eq!(at.at_ms, Some(1_772_326_800_000));
+    }
+
+    #[test]
+    fn test_add_schedule_rejects_invalid_input() {
+        let mut bad_tz = schedule(None, Some("0 0 9 * * *"), None);
+        bad_tz.tz = Some("Mars/Olympus".to_string());
+        assert!(bad_tz.into_job_schedule().is_err());
+        assert!(schedule(None, Some("0 9 * *"), None)
+            .into_job_schedule()
+            .is_err());
+        assert!(schedule(Some(0), None, None).into_job_schedule().is_err());
+        assert!(schedule(Some(60), Some("0 0 9 * * *"), None)
+            .into_job_schedule()
+            .is_err());
+    }
+}
@@ -0,0 +1,375 @@
+//! `blockcell debug bundle`: collect what a bug report needs into one zip.
+//!
+//! Secrets in the config are masked, and every value masked there is also scrubbed from
+//! the logs and transcript. Emails, phone numbers, ID and card numbers are redacted
+//! with the built-in detectors. Before anything is written the user can review each
+//! file, drop files and redact extra strings.
+
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use blockcell_core::config::RedactionConfig;
+use blockcell_core::{session_file_stem, Config, Paths};
+use blockcell_storage::redaction::Redactor;
+use blockcell_storage::SessionStore;
+use serde_json::Value;
+
+const MASK: &str = "[MASKED]";
+const REDACTION_NAMESPACE: &str = "debug_bundle";
+const EVOLUTION_RECORDS_PER_DIR: usize = 10;
+const SECRET_KEY_HINTS: &[&str] = &[
+    "key",
+    "secret",
+    "token",
+    "password",
+    "passwd",
+    "credential",
+    "cookie",
+    "auth",
+];
+
+struct BundleEntry {
+    name: String,
+    content: String,
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let lower = key.to_ascii_lowercase();
+    SECRET_KEY_HINTS.iter().any(|hint| lower.contains(hint))
+}
+
+/// Mask string values under secret-looking keys; returns the masked values so they can
+/// be scrubbed from other files too.
+fn mask_secrets(value: &mut Value, masked: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                match v {
+                    Value::String(s) if is_secret_key(key) && !s.is_empty() => {
+                        if s.len() >= 6 {
+                            masked.push(s.clone());
+                        }
+                        *s = MASK.to_string();
+                    }
+                    _ => mask_secrets(v, masked),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|v| mask_secrets(v, masked)),
+        _ => {}
+    }
+}
+
+fn scrub(text: &str, literals: &[String], redactor: &Redactor) -> String {
+    let mut out = text.to_string();
+    for literal in literals {
+        out = out.replace(literal.as_str(), MASK);
+    }
+    redactor.redact(REDACTION_NAMESPACE, &out)
+}
+
+fn tail_lines(path: &Path, lines: usize) -> Option<String> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let all: Vec<&str> = content.lines().collect();
+    Some(all[all.len().saturating_sub(lines)..].join("\n"))
+}
+
+/// Files in `dir` with one of `exts`, newest first.
+fn newest_files(dir: &Path, exts: &[&str], limit: usize) -> Vec<PathBuf> {
+    let mut files: Vec<(std::time::SystemTime, PathBuf)> = std::fs::read_dir(dir)
+        .map(|rd| {
+            rd.flatten()
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.extension()
+                        .and_then(|e| e.to_str())
+                        .is_some_and(|e| exts.contains(&e))
+                })
+                .filter_map(|p| Some((std::fs::metadata(&p).ok()?.modified().ok()?, p)))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort_by_key(|f| std::cmp::Reverse(f.0));
+    files.into_iter().take(limit).map(|(_, p)| p).collect()
+}
+
+fn system_info(paths: &Paths) -> String {
+    let mut info = vec![
+        format!("blockcell {}", env!("CARGO_PKG_VERSION")),
+        format!(
+            "os: {} ({}), arch: {}",
+            std::env::consts::OS,
+            std::env::consts::FAMILY,
+            std::env::consts::ARCH
+        ),
+        format!(
+            "cpus: {}",
+            std::thread::available_parallelism().map_or(0, |n| n.get())
+        ),
+        format!("generated: {}", chrono::Utc::now().to_rfc3339()),
+        format!("data dir: {}", paths.base.display()),
+    ];
+    if let Ok(meminfo) = std::fs::read_to_string("/proc/meminfo") {
+        info.extend(
+            meminfo
+                .lines()
+                .filter(|l| l.starts_with("MemTotal") || l.starts_with("MemAvailable"))
+                .map(str::to_string),
+        );
+    }
+    info.join("\n") + "\n"
+}
+
+fn collect(
+    paths: &Paths,
+    config: &Config,
+    session: Option<&str>,
+    log_lines: usize,
+) -> anyhow::Result<(Vec<BundleEntry>, Vec<String>)> {
+    let mut entries = vec![BundleEntry {
+        name: "system.txt".to_string(),
+        content: system_info(paths),
+    }];
+
+    let mut masked = Vec::new();
+    let mut config_json = serde_json::to_value(config)?;
+    mask_secrets(&mut config_json, &mut masked);
+    entries.push(BundleEntry {
+        name: "config.json".to_string(),
+        content: serde_json::to_string_pretty(&config_json)?,
+    });
+
+    for log in newest_files(&paths.workspace().join("logs"), &["log", "jsonl"], 2) {
+        if let Some(content) = tail_lines(&log, log_lines) {
+            let name = log.file_name().and_then(|n| n.to_str()).unwrap_or("log");
+            entries.push(BundleEntry {
+                name: format!("logs/{}", name),
+                content,
+            });
+        }
+    }
+
+    if let Some(content) = tail_lines(&paths.admin_audit_file(), log_lines) {
+        entries.push(BundleEntry {
+            name: "audit/admin.jsonl".to_string(),
+            content,
+        });
+    }
+
+    let store = SessionStore::new(paths.clone());
+    let session_key = match session {
+        Some(key) => Some(key.to_string()),
+        None => store.list(0, 1)?.0.pop().map(|s| s.stem),
+    };
+    let exported = match &session_key {
+        Some(key) => store.export_jsonl(key)?,
+        None => None,
+    };
+    match (session, session_key, exported) {
+        (_, Some(key), Some(content)) => entries.push(BundleEntry {
+            name: format!("session/{}.jsonl", session_file_stem(&key)),
+            content,
+        }),
+        (Some(key), _, _) => anyhow::bail!("Session not found: {}", key),
+        (None, _, _) => {}
+    }
+
+    for dir in [
+        paths.workspace().join("evolution_records"),
+        paths.tool_evolution_records_dir(),
+    ] {
+        let label = dir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("records")
+            .to_string();
+        for record in newest_files(&dir, &["json"], EVOLUTION_RECORDS_PER_DIR) {
+            if let Ok(content) = std::fs::read_to_string(&record) {
+                let name = record
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("record.json");
+                entries.push(BundleEntry {
+                    name: format!("{}/{}", label, name),
+                    content,
+                });
+            }
+        }
+    }
+    Ok((entries, masked))
+}
+
+fn print_entries(entries: &[BundleEntry], redactor: &Redactor) {
+    println!();
+    for (idx, entry) in entries.iter().enumerate() {
+        let findings = redactor.scan(&entry.content);
+        let pii = if findings.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "  ⚠ possible PII: {}",
+                findings
+                    .iter()
+                    .map(|(k, v)| format!("{}×{}", k, v))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        };
+        println!(
+            "  [{}] {:<48} {:>8} bytes{}",
+            idx + 1,
+            entry.name,
+            entry.content.len(),
+            pii
+        );
+    }
+    println!();
+}
+
+/// Let the user drop files, redact extra strings and view contents. Returns false when
+/// the user aborts.
+fn review(entries: &mut Vec<BundleEntry>, redactor: &Redactor) -> anyhow::Result<bool> {
+    let stdin = std::io::stdin();
+    loop {
+        print_entries(entries, redactor);
+        println!("Commands: v <n> view · x <n> exclude · r <text> redact text · Enter to write · q to abort");
+        print!("> ");
+        std::io::stdout().flush()?;
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            return Ok(false);
+        }
+        let line = line.trim();
+        let (cmd, arg) = line.split_once(' ').unwrap_or((line, ""));
+        let pick = |arg: &str| {
+            arg.trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|n| (1..=entries.len()).contains(n))
+                .map(|n| n - 1)
+        };
+        match cmd {
+            "" => return Ok(true),
+            "q" => return Ok(false),
+            "v" => match pick(arg) {
+                Some(idx) => {
+                    println!();
+                    for l in entries[idx].content.lines().take(60) {
+                        println!("  {}", l);
+                    }
+                }
+                None => println!("No such file"),
+            },
+            "x" => match pick(arg) {
+                Some(idx) => {
+                    let removed = entries.remove(idx);
+                    println!("Excluded {}", removed.name);
+                }
+                None => println!("No such file"),
+            },
+            "r" if !arg.trim().is_empty() => {
+                let needle = arg.trim();
+                let mut hits = 0;
+                for entry in entries.iter_mut() {
+                    hits += entry.content.matches(needle).count();
+                    entry.content = entry.content.replace(needle, "[REDACTED]");
+                }
+                println!("Redacted {} occurrence(s)", hits);
+            }
+            _ => println!("Unknown command"),
+        }
+    }
+}
+
+fn write_zip(path: &Path, entries: &[BundleEntry]) -> anyhow::Result<()> {
+    let file = std::fs::File::create(path)?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    for entry in entries {
+        zip.start_file(entry.name.as_str(), options)?;
+        zip.write_all(entry.content.as_bytes())?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+/// Build a redacted debug bundle for attaching to an issue.
+pub async fn bundle(
+    session: Option<String>,
+    output: Option<String>,
+    log_lines: usize,
+    yes: bool,
+) -> anyhow::Result<()> {
+    let paths = Paths::default();
+    let config = Config::load_or_default(&paths)?;
+    let (mut entries, masked) = collect(&paths, &config, session.as_deref(), log_lines)?;
+
+    let redactor = Redactor::new(&RedactionConfig {
+        enabled: true,
+        custom_patterns: config.security.redaction.custom_patterns.clone(),
+        ..Default::default()
+    });
+    for entry in entries.iter_mut() {
+        entry.content = scrub(&entry.content, &masked, &redactor);
+    }
+
+    println!();
+    println!("🐞 Debug bundle: {} files", entries.len());
+    if !yes && !review(&mut entries, &redactor)? {
+        println!("Aborted, nothing written.");
+        return Ok(());
+    }
+
+    let output = output.map(PathBuf::from).unwrap_or_else(|| {
+        PathBuf::from(format!(
+            "blockcell-debug-{}.zip",
+            chrono::Utc::now().format("%Y%m%d-%H%M%S")
+        ))
+    });
+    write_zip(&output, &entries)?;
+    println!("✓ Wrote {} ({} files)", output.display(), entries.len());
+    println!("  Please skim it once more before attaching it to an issue.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_mask_secrets() {
+        let mut value = json!({
+            "providers": {"openai": {"apiKey": "sk-abcdef123456", "apiBase": "https://api.openai.com"}},
+            "channels": {"telegram": {"token": "12345:bot-token", "allowFrom": ["alice"]}},
+            "agents": {"defaults": {"maxTokens": 8192, "model": "gpt-4o"}},
+            "gateway": {"apiToken": ""}
+        });
+        let mut masked = Vec::new();
+        mask_secrets(&mut value, &mut masked);
+        masked.sort();
+        assert_eq!(value["providers"]["openai"]["apiKey"], MASK);
+        assert_eq!(
+            value["providers"]["openai"]["apiBase"],
+            "https://api.openai.com"
+        );
+        assert_eq!(value["channels"]["telegram"]["token"], MASK);
+        assert_eq!(value["agents"]["defaults"]["maxTokens"], 8192);
+        assert_eq!(value["gateway"]["apiToken"], "");
+        assert_eq!(masked, vec!["12345:bot-token", "sk-abcdef123456"]);
+    }
+
+    #[test]
+    fn test_scrub_removes_secrets_and_pii() {
+        let redactor = Redactor::new(&RedactionConfig {
+            enabled: true,
+            ..Default::default()
+        });
+        let log = "calling with key sk-abcdef123456 for bob@example.com";
+        let out = scrub(log, &["sk-abcdef123456".to_string()], &redactor);
+        assert!(!out.contains("sk-abcdef123456"));
+        assert!(!out.contains("bob@example.com"));
+        assert!(out.contains(MASK));
+    }
+}
@@ -0,0 +1,157 @@
+use blockcell_core::{Config, Paths};
+use blockcell_storage::documents::{db_path, DocumentStore};
+
+fn agent_workspace(
+    paths: &Paths,
+    config: &Config,
+    agent: Option<&str>,
+) -> anyhow::Result<std::path::PathBuf> {
+    let agent_id = agent
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .unwrap_or("default");
+    if !config.agent_exists(agent_id) {
+        anyhow::bail!("Unknown agent '{}'", agent_id);
+    }
+    Ok(paths.for_agent(agent_id).workspace())
+}
+
+fn one_line(text: &str, max_chars: usize) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() > max_chars {
+        format!("{}…", flat.chars().take(max_chars).collect::<String>())
+    } else {
+        flat
+    }
+}
+
+/// Ingest a file, directory or URL into the agent's document library.
+pub async fn ingest(source: &str, agent: Option<&str>, force: bool) -> anyhow::Result<()> {
+    let paths = Paths::default();
+    let config = Config::load_or_default(&paths)?;
+    let workspace = agent_workspace(&paths, &config, agent)?;
+
+    println!("📥 Ingesting {} ...", source);
+    let summary = blockcell_tools::documents::ingest(&config, &workspace, source, force).await?;
+
+    let results = summary["results"].as_array().cloned().unwrap_or_default();
+    for result in &results {
+        let doc = &result["document"];
+        let status = result["status"].as_str().unwrap_or("");
+        let mark = if status == "unchanged" { "=" } else { "✓" };
+        println!(
+            "  {} {} — {} chunks{}{}",
+            mark,
+            doc["title"].as_str().unwrap_or(""),
+            doc["chunk_count"],
+            if doc["embedding_model"].is_string() {
+                ", embedded"
+            } else {
+                ""
+            },
+            if status == "unchanged" {
+                " (unchanged)"
+            } else {
+                ""
+            }
+        );
+    }
+    let failed = summary["failed"].as_array().cloned().unwrap_or_default();
+    for failure in &failed {
+        println!(
+            "  ✗ {} — {}",
+            failure["source"].as_str().unwrap_or(""),
+            failure["error"].as_str().unwrap_or("")
+        );
+    }
+    println!();
+    println!("{} ingested, {} failed", results.len(), failed.len());
+    if !config.memory.vector.enabled {
+        println!("Tip: enable memory.vector for semantic search; keyword search works without it.");
+    }
+    Ok(())
+}
+
+/// List ingested documents.
+pub async fn list(agent: Option<&str>) -> anyhow::Result<()> {
+    let paths = Paths::default();
+    let config = Config::load_or_default(&paths)?;
+    let workspace = agent_workspace(&paths, &config, agent)?;
+    let documents = DocumentStore::open(&db_path(&workspace))?.list()?;
+
+    if documents.is_empty() {
+        println!("(No documents. Use `blockcell docs ingest <path|url>` to add some.)");
+        return Ok(());
+    }
+
+    println!();
+    println!("📚 Documents ({} total)", documents.len());
+    println!();
+    println!(
+        "  {:<34} {:<10} {:<8} {:<9} Title",
+        "ID", "Kind", "Chunks", "Embedded"
+    );
+    println!("  {}", "-".repeat(90));
+    for doc in &documents {
+        println!(
+            "  {:<34} {:<10} {:<8} {:<9} {}",
+            doc.id,
+            doc.kind,
+            doc.chunk_count,
+            if doc.embedding_model.is_some() {
+                "✓"
+            } else {
+                "-"
+            },
+            one_line(&doc.title, 60)
+        );
+        println!("  {:<34} {}", "", doc.source);
+    }
+    println!();
+    Ok(())
+}
+
+/// Search ingested documents.
+pub async fn search(
+    query: &str,
+    agent: Option<&str>,
+    document: Option<&str>,
+    limit: usize,
+) -> anyhow::Result<()> {
+    let paths = Paths::default();
+    let config = Config::load_or_default(&paths)?;
+    let workspace = agent_workspace(&paths, &config, agent)?;
+    let hits =
+        blockcell_tools::documents::search(&config, &workspace, query, limit, document).await?;
+
+    if hits.is_empty() {
+        println!("No matching passages for '{}'.", query);
+        return Ok(());
+    }
+    println!();
+    for (i, hit) in hits.iter().enumerate() {
+        let heading = if hit.heading.is_empty() {
+            String::new()
+        } else {
+            format!(" › {}", hit.heading)
+        };
+        println!("{}. {}{}  ({:.4})", i + 1, hit.title, heading, hit.score);
+        println!("   {}", hit.source);
+        println!("   {}", one_line(&hit.text, 240));
+        println!();
+    }
+    Ok(())
+}
+
+/// Remove a document by id or source.
+pub async fn remove(document: &str, agent: Option<&str>) -> anyhow::Result<()> {
+    let paths = Paths::default();
+    let config = Config::load_or_default(&paths)?;
+    let workspace = agent_workspace(&paths, &config, agent)?;
+    if DocumentStore::open(&db_path(&workspace))?.remove(document.trim())? {
+        println!("✓ Removed document '{}'", document);
+    } else {
+        println!("Document '{}' not found", document);
+    }
+    Ok(())
+}
@@ -45,8 +45,17 @@ pub async fn run(description: &str, watch: bool) -> anyhow::Result<()> {
     // Derive a skill name from the description
     let skill_name = derive_skill_name(description);
 
-    let evo_config = EvolutionServiceConfig::default();
-    let service = EvolutionService::new(skills_dir, evo_config);
+    let mut evo_config = EvolutionServiceConfig::from_config(&config);
+    // Drafts run on the main provider here (see OpenAILLMAdapter::new).
+    evo_config.draft_model = Some(config.agents.defaults.model.clone());
+    let mut service = EvolutionService::new(skills_dir, evo_config);
+    match super::provider::create_evolution_audit_provider(&config) {
+        Ok(Some(provider)) => {
+            service.set_audit_llm_provider(std::sync::Arc::new(OpenAILLMAdapter { provider }))
+        }
+        Ok(None) => {}
+        Err(e) => println!("  ⚠️  Audit model unavailable, using the main model: {}", e),
+    }
 
     println!();
     println!("🧬 Self-Evolution");
@@ -111,6 +120,20 @@ pub async fn run(description: &str, watch: bool) -> anyhow::Result<()> {
                     }
                 }
 
+                if !record.model_routing.is_empty() {
+                    println!("  🧠 Models:");
+                    for route in &record.model_routing {
+                        println!(
+                            "     #{} [{}] {:?}{} {}",
+                            route.attempt,
+                            route.stage,
+                            route.tier,
+                            if route.escalated { " (escalated)" } else { "" },
+                            route.model.as_deref().unwrap_or("-")
+                        );
+                    }
+                }
+
                 // Show details based on final status
                 if let Some(ref patch) = record.patch {
                     println!("  🔧 Generated patch: {}", patch.patch_id);
@@ -205,7 +228,7 @@ pub async fn list(all: bool, verbose: bool) -> anyhow::Result<()> {
     let records_dir = paths.workspace().join("evolution_records");
 
     let mut records = load_all_records(&records_dir);
-    records.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    records.sort_by_key(|r| std::cmp::Reverse(r.created_at));
 
     if !all {
         // Filter out built-in tool records
@@ -284,7 +307,7 @@ pub async fn show(skill_name: &str) -> anyhow::Result<()> {
     let records_dir = paths.workspace().join("evolution_records");
 
     let mut records = load_all_records(&records_dir);
-    records.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    records.sort_by_key(|r| std::cmp::Reverse(r.created_at));
 
     // Try to match by skill_name or evolution ID prefix
     let matched: Vec<&EvolutionRecord> = records
@@ -348,7 +371,7 @@ pub async fn rollback(skill_name: &str, to: Option<String>) -> anyhow::Result<()
     let records_dir = paths.workspace().join("evolution_records");
 
     let mut records = load_all_records(&records_dir);
-    records.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    records.sort_by_key(|r| std::cmp::Reverse(r.created_at));
 
     let skill_records: Vec<&EvolutionRecord> = records
         .iter()
@@ -813,7 +836,7 @@ fn print_pipeline_stage(name: &str, started: bool, passed: bool) {
 fn print_all_status(paths: &Paths) -> anyhow::Result<()> {
     let records_dir = paths.workspace().join("evolution_records");
     let mut records = load_all_records(&records_dir);
-    records.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+    records.sort_by_key(|r| std::cmp::Reverse(r.updated_at));
 
     let active_count = records.iter().filter(|r| !is_terminal(&r.status)).count();
     let completed_count = records
@@ -22,8 +22,8 @@ use blockcell_channels::whatsapp::WhatsAppChannel;
 use blockcell_channels::ChannelManager;
 use blockcell_core::{Config, InboundMessage, OutboundMessage, Paths};
 use blockcell_scheduler::{
-    CronJob, CronService, DreamService, DreamServiceConfig, GhostService, GhostServiceConfig,
-    HeartbeatService, JobPayload, JobSchedule, JobState, ScheduleKind,
+    CronJob, CronService, DeadMansSwitchService, DreamService, DreamServiceConfig, GhostService,
+    GhostServiceConfig, HeartbeatService, JobPayload, JobSchedule, JobState, ScheduleKind,
 };
 use blockcell_skills::{new_registry_handle, CoreEvolution};
 use blockcell_skills::{EvolutionService, EvolutionServiceConfig};
@@ -53,39 +53,58 @@ use axum::{
 };
 use rust_embed::Embed;
 use serde::{Deserialize, Serialize};
-use tower_http::cors::CorsLayer;
 
 use super::memory_store::open_memory_store;
 
+mod admin_audit;
 mod alerts;
+mod auth;
 mod banner;
 mod capabilities;
 mod channels;
 mod chat;
 mod config_api;
+mod cors;
 mod cron;
 mod files;
+mod hooks;
+mod ingest;
+mod login_limit;
 mod memory;
 mod outbound;
+mod privacy;
+mod proxy;
 mod sessions;
+mod shares;
 mod skills_install;
 mod streams;
 mod toggles;
 mod webhooks;
 mod websocket;
 mod webui;
+mod ws_codec;
+mod ws_filter;
 
+use admin_audit::*;
 use alerts::*;
+use auth::*;
 use banner::*;
 use capabilities::*;
 use channels::*;
 use chat::*;
 use config_api::*;
+use cors::*;
 use cron::*;
 use files::*;
+use hooks::*;
+use ingest::*;
+use login_limit::*;
 use memory::*;
 use outbound::*;
+use privacy::*;
+use proxy::*;
 use sessions::*;
+use shares::*;
 use skills_install::*;
 use streams::*;
 use toggles::*;
@@ -178,6 +197,12 @@ struct GatewayState {
     evolution_service: Arc<Mutex<EvolutionService>>,
     /// Shared ResponseCache for all agents (for /clear command)
     response_caches: Arc<RwLock<HashMap<String, blockcell_agent::ResponseCache>>>,
+    /// Hash-chained log of admin mutations (`audit/admin.jsonl`)
+    admin_audit: Arc<blockcell_storage::admin_audit::AdminAuditLog>,
+    /// WebUI passkeys and cookie sessions
+    web_auth: Arc<WebUiAuth>,
+    /// Failed password logins and lockouts
+    login_limiter: Arc<LoginLimiter>,
 }
 
 #[derive(Deserialize, Default)]
@@ -235,8 +260,8 @@ fn url_decode(input: &str) -> Option<String> {
     Some(out)
 }
 
-fn token_from_query(req: &Request<axum::body::Body>) -> Option<String> {
-    let q = req.uri().query()?;
+fn token_from_query(uri: &axum::http::Uri) -> Option<String> {
+    let q = uri.query()?;
     for pair in q.split('&') {
         let (k, v) = pair.split_once('=')?;
 
@@ -742,9 +767,10 @@ async fn spawn_agent_runtime(
     Option<MemoryStoreHandle>,
     EventEmitterHandle,
 )> {
-    let agent_config = config
+    let mut agent_config = config
         .config_for_agent(agent_id)
         .ok_or_else(|| anyhow::anyhow!("Unknown agent '{}'", agent_id))?;
+    agent_config.apply_lite_limits();
     let agent_paths = paths.for_agent(agent_id);
     agent_paths.ensure_dirs()?;
 
@@ -804,6 +830,16 @@ async fn spawn_agent_runtime(
             }
         }
     }
+    match super::provider::create_evolution_audit_provider(&agent_config) {
+        Ok(Some(audit_provider)) => {
+            runtime.set_evolution_audit_provider(audit_provider);
+            info!(agent_id = %agent_id, "Evolution audit model configured");
+        }
+        Ok(None) => {}
+        Err(e) => {
+            warn!(agent_id = %agent_id, error = %e, "Failed to create evolution audit provider; audits use the draft model");
+        }
+    }
 
     runtime.set_outbound(outbound_tx);
     runtime.set_confirm(confirm_tx);
@@ -832,7 +868,8 @@ async fn spawn_agent_runtime(
 
     let event_emitter = runtime.event_emitter_handle();
 
-    let (agent_inbound_tx, agent_inbound_rx) = mpsc::channel::<InboundMessage>(100);
+    let (agent_inbound_tx, agent_inbound_rx) =
+        mpsc::channel::<InboundMessage>(agent_config.queue_capacity(100));
     let runtime_shutdown_rx = shutdown_tx.subscribe();
     let runtime_handle = tokio::spawn(async move {
         runtime
@@ -857,29 +894,12 @@ async fn auth_middleware(
     req: Request<axum::body::Body>,
     next: Next,
 ) -> Response {
-    let token = match &state.api_token {
-        Some(t) if !t.is_empty() => t,
-        _ => return next.run(req).await,
-    };
-
-    if req.uri().path() == "/v1/health" || req.uri().path() == "/v1/auth/login" {
+    let path = req.uri().path();
+    if path == "/v1/health" || is_public_auth_path(path) {
         return next.run(req).await;
     }
 
-    let auth_header = req
-        .headers()
-        .get(header::AUTHORIZATION)
-        .and_then(|v| v.to_str().ok());
-
-    let authorized = match auth_header {
-        Some(h) if h.starts_with("Bearer ") => secure_eq(&h[7..], token.as_str()),
-        _ => false,
-    };
-
-    let authorized = authorized
-        || token_from_query(&req)
-            .map(|v| secure_eq(&v, token.as_str()))
-            .unwrap_or(false);
+    let authorized = request_authorized(&state, req.headers(), req.uri()).await;
 
     if authorized {
         next.run(req).await
@@ -1084,14 +1104,32 @@ pub async fn run(cli_host: Option<String>, cli_port: Option<u16>) -> anyhow::Res
     // every enabled external channel must be bound to exactly one owner agent.
     validate_channel_owner_bindings(&config)?;
 
+    // Unlock at-rest encryption before any session or memory file is touched, and seal
+    // sessions written while it was off.
+    super::storage_key::unlock(&config)?;
+    if config.storage.encryption.enabled {
+        for agent_id in config.known_agent_ids() {
+            match blockcell_storage::at_rest::encrypt_session_files(&paths.for_agent(&agent_id)) {
+                Ok(0) => {}
+                Ok(n) => info!(agent_id = %agent_id, sessions = n, "Encrypted sessions"),
+                Err(e) => {
+                    warn!(agent_id = %agent_id, error = %e, "Failed to encrypt sessions")
+                }
+            }
+        }
+    }
+
     info!(host = %host, port = port, "Starting blockcell gateway");
+    if config.lite {
+        info!("Lite mode: lazy tools, small buffers, WebUI disabled");
+    }
 
     // ── Create message bus ──
-    let bus = MessageBus::new(100);
+    let bus = MessageBus::new(config.queue_capacity(100));
     let ((inbound_tx, inbound_rx), (outbound_tx, outbound_rx)) = bus.split();
 
     // ── Create WebSocket broadcast channel ──
-    let (ws_broadcast_tx, _) = broadcast::channel::<String>(1000);
+    let (ws_broadcast_tx, _) = broadcast::channel::<String>(config.queue_capacity(1000));
 
     // ── Create shutdown channel ──
     let (shutdown_tx, _) = broadcast::channel::<()>(1);
@@ -1102,7 +1140,7 @@ pub async fn run(cli_host: Option<String>, cli_port: Option<u16>) -> anyhow::Res
 
     // ── Create tool registry (shared for listing tools) ──
     let tool_registry_shared =
-        Arc::new(build_tool_registry_with_all_mcp(Some(&mcp_manager)).await?);
+        Arc::new(build_tool_registry_with_all_mcp(&config, Some(&mcp_manager)).await?);
 
     // ── Set up path confirmation channel (channel-aware) ──
     // pending_ws_confirms: keyed by request_id, for WebUI (ws) confirmations
@@ -1119,10 +1157,12 @@ pub async fn run(cli_host: Option<String>, cli_port: Option<u16>) -> anyhow::Res
 
     // Spawn confirm handler: routes confirmation requests to the correct channel.
     // - ws channel → broadcast confirm_request event to WebUI
-    // - non-ws channels → send text prompt via outbound_tx to originating channel
+    // - non-ws channels → send text prompt via outbound_tx to originating channel,
+    //   answered with /approve or /deny and denied after channels.confirm timeout
     let pending_ws_for_handler = Arc::clone(&pending_ws_confirms);
     let pending_ch_for_handler = Arc::clone(&pending_channel_confirms);
     let ws_broadcast_for_confirm = ws_broadcast_tx.clone();
+    let confirm_prompts = config.channels.confirm.clone();
     let mut confirm_handler_shutdown_rx = shutdown_tx.subscribe();
     let confirm_handler_handle = tokio::spawn(async move {
         loop {
@@ -1149,30 +1189,80 @@ pub async fn run(cli_host: Option<String>, cli_port: Option<u16>) -> anyhow::Res
                     "chat_id": req.chat_id,
                 });
                 let _ = ws_broadcast_for_confirm.send(event.to_string());
+            } else if !confirm_prompts.enabled_for(&req.channel) {
+                let _ = req.response_tx.send(false);
+                let notice = format!(
+                    "⛔ 工具 {} 需要确认，但此渠道未启用确认提示，操作已取消。",
+                    req.tool_name
+                );
+                let _ = outbound_tx_for_confirm
+                    .send(OutboundMessage::new(&req.channel, &req.chat_id, &notice))
+                    .await;
+                info!(channel = %req.channel, tool = %req.tool_name, "Confirm prompts disabled for channel, denied");
             } else {
                 let confirm_key = format!("{}:{}", req.channel, req.chat_id);
+                // The map holds a relay sender; the relay below forwards the answer to the
+                // runtime or denies once the timeout passes.
+                let (answer_tx, answer_rx) = tokio::sync::oneshot::channel::<bool>();
                 {
                     let mut map = pending_ch_for_handler.lock().await;
-                    map.insert(confirm_key.clone(), req.response_tx);
+                    map.insert(confirm_key.clone(), answer_tx);
                 }
-                let prompt = format!(
-                    "⚠️ 工具 {} 需要访问以下路径：
+                let timeout = confirm_prompts.timeout_for(&req.channel);
+                let minutes = timeout.as_secs().div_ceil(60);
+                let prompt = if req.tool_name == "plan" {
+                    format!(
+                        "📋 计划按以下步骤执行：
 {}
 
-回复 yes / y / 允许 / 同意 进行确认，其他任意内容将拒绝。",
-                    req.tool_name,
-                    req.paths.join("\n")
-                );
+回复 /approve（或 yes / 同意）开始执行，/deny 或其他任意内容将取消。{} 分钟内未回复将自动取消。",
+                        req.paths.join("\n"),
+                        minutes
+                    )
+                } else {
+                    format!(
+                        "⚠️ 工具 {} 需要访问以下路径：
+{}
+
+回复 /approve（或 yes / 允许）进行确认，/deny 或其他任意内容将拒绝。{} 分钟内未回复将自动拒绝。",
+                        req.tool_name,
+                        req.paths.join("\n"),
+                        minutes
+                    )
+                };
                 let mut outbound = OutboundMessage::new(&req.channel, &req.chat_id, &prompt);
                 outbound.metadata = serde_json::json!({"confirm_request": true});
                 if outbound_tx_for_confirm.send(outbound).await.is_err() {
-                    let mut map = pending_ch_for_handler.lock().await;
-                    if let Some(tx) = map.remove(&confirm_key) {
-                        let _ = tx.send(false);
-                    }
-                } else {
-                    info!(confirm_key = %confirm_key, tool = %req.tool_name, "Sent confirm_request to channel");
+                    pending_ch_for_handler.lock().await.remove(&confirm_key);
+                    let _ = req.response_tx.send(false);
+                    continue;
                 }
+                info!(confirm_key = %confirm_key, tool = %req.tool_name, "Sent confirm_request to channel");
+
+                let pending = Arc::clone(&pending_ch_for_handler);
+                let outbound_tx = outbound_tx_for_confirm.clone();
+                tokio::spawn(async move {
+                    match tokio::time::timeout(timeout, answer_rx).await {
+                        Ok(answer) => {
+                            let _ = req.response_tx.send(answer.unwrap_or(false));
+                        }
+                        Err(_) => {
+                            let _ = req.response_tx.send(false);
+                            // Only drop our own entry; a newer prompt may have replaced it.
+                            let mut map = pending.lock().await;
+                            if map.get(&confirm_key).is_some_and(|tx| tx.is_closed()) {
+                                map.remove(&confirm_key);
+                            }
+                            drop(map);
+                            warn!(confirm_key = %confirm_key, tool = %req.tool_name, "Channel confirm timed out, denied");
+                            let notice =
+                                format!("⏱️ 确认已超时，工具 {} 的操作已取消。", req.tool_name);
+                            let _ = outbound_tx
+                                .send(OutboundMessage::new(&req.channel, &req.chat_id, &notice))
+                                .await;
+                        }
+                    }
+                });
             }
         }
     });
@@ -1221,17 +1311,20 @@ pub async fn run(cli_host: Option<String>, cli_port: Option<u16>) -> anyhow::Res
     let default_timezone = config.default_timezone.as_deref();
     for agent in &resolved_agents {
         let agent_id = agent.id.clone();
-        let cron_service = Arc::new(CronService::new_with_options(
-            paths.for_agent(&agent_id),
-            inbound_tx.clone(),
-            if agent_id == "default" {
-                None
-            } else {
-                Some(agent_id.clone())
-            },
-            Some(tick_interval_secs),
-            default_timezone,
-        ));
+        let cron_service = Arc::new(
+            CronService::new_with_options(
+                paths.for_agent(&agent_id),
+                inbound_tx.clone(),
+                if agent_id == "default" {
+                    None
+                } else {
+                    Some(agent_id.clone())
+                },
+                Some(tick_interval_secs),
+                default_timezone,
+            )
+            .with_maintenance(config.maintenance.clone()),
+        );
         if let Some(emitter) = agent_event_emitters.get(&agent_id) {
             cron_service.set_event_emitter(emitter.clone());
         }
@@ -1248,6 +1341,19 @@ pub async fn run(cli_host: Option<String>, cli_port: Option<u16>) -> anyhow::Res
     }
 
     let heartbeat_service = Arc::new(HeartbeatService::new(paths.clone(), inbound_tx.clone()));
+    let dead_mans_switch_handle = if config.gateway.dead_mans_switch.enabled {
+        let service = Arc::new(DeadMansSwitchService::new(
+            paths.clone(),
+            config.gateway.dead_mans_switch.clone(),
+            outbound_tx.clone(),
+        ));
+        let shutdown_rx = shutdown_tx.subscribe();
+        Some(tokio::spawn(async move {
+            service.run_loop(shutdown_rx).await;
+        }))
+    } else {
+        None
+    };
 
     // ── Layer 6: Dream Service (跨会话知识整合) ──
     // 使用 default agent 的配置创建 provider_pool
@@ -1321,6 +1427,15 @@ pub async fn run(cli_host: Option<String>, cli_port: Option<u16>) -> anyhow::Res
         });
     }
 
+    // Opt-in usage telemetry (counts only; see `blockcell telemetry preview`).
+    super::telemetry_cmd::spawn_reporter(paths.clone(), config.clone());
+
+    // Shared team memory (scope `team`) synced through the community hub.
+    super::team_memory_sync::spawn_sync(paths.clone(), config.clone());
+
+    // Offline mode: track connectivity so chat falls back to the local model.
+    blockcell_core::connectivity::spawn_monitor(config.offline.clone());
+
     // ── Create Ghost Agent service ──
     let ghost_config = GhostServiceConfig::from_config(&config);
     let ghost_service = GhostService::new(ghost_config, paths.clone(), inbound_tx.clone());
@@ -1328,7 +1443,8 @@ pub async fn run(cli_host: Option<String>, cli_port: Option<u16>) -> anyhow::Res
     // ── Inbound interceptor: check for pending channel confirm replies ──
     // Sits between channel inbound_rx and the runtime, intercepting confirm
     // replies from non-ws channels before they reach the runtime loop.
-    let (filtered_inbound_tx, filtered_inbound_rx) = mpsc::channel::<InboundMessage>(100);
+    let (filtered_inbound_tx, filtered_inbound_rx) =
+        mpsc::channel::<InboundMessage>(config.queue_capacity(100));
     let pending_ch_for_interceptor = Arc::clone(&pending_channel_confirms);
     let mut interceptor_shutdown_rx = shutdown_tx.subscribe();
     // 斜杠命令拦截需要的变量
@@ -1347,6 +1463,54 @@ pub async fn run(cli_host: Option<String>, cli_port: Option<u16>) -> anyhow::Res
                 },
                 _ = interceptor_shutdown_rx.recv() => break,
             };
+            // Inline queries and scheduled follow-ups go straight to the runtime; they
+            // are neither confirm replies nor slash commands.
+            if msg.metadata.get("inline_query_id").is_some()
+                || msg.metadata.get("follow_up").is_some()
+            {
+                if filtered_inbound_tx.send(msg).await.is_err() {
+                    break;
+                }
+                continue;
+            }
+
+            // Reaction quick commands: handled before the confirm check so a reaction
+            // is never taken as a typed confirm reply.
+            if let Some(emoji) = blockcell_channels::reactions::reaction_emoji(&msg) {
+                let Some(action) =
+                    blockcell_channels::reactions::resolve(&slash_config.channels.reactions, emoji)
+                else {
+                    continue;
+                };
+                info!(channel = %msg.channel, chat_id = %msg.chat_id, emoji = %emoji, action = ?action, "Reaction command");
+                match blockcell_channels::reactions::action_prompt(action) {
+                    Some(prompt) => {
+                        msg.content = prompt.to_string();
+                        if filtered_inbound_tx.send(msg).await.is_err() {
+                            break;
+                        }
+                    }
+                    None => {
+                        let confirm_key = format!("{}:{}", msg.channel, msg.chat_id);
+                        let maybe_tx = pending_ch_for_interceptor.lock().await.remove(&confirm_key);
+                        let text = match maybe_tx {
+                            Some(tx) => {
+                                if tx.send(true).is_ok() {
+                                    "✅ Approved."
+                                } else {
+                                    "This approval request has expired."
+                                }
+                            }
+                            None => "Nothing is waiting for approval here.",
+                        };
+                        let mut reply = OutboundMessage::new(&msg.channel, &msg.chat_id, text);
+                        reply.account_id = msg.account_id.clone();
+                        let _ = slash_outbound_tx.send(reply).await;
+                    }
+                }
+                continue;
+            }
+
             // Check if this message is a reply to a pending channel confirm
             if !is_internal_channel(&msg.channel) {
                 let confirm_key = format!("{}:{}", msg.channel, msg.chat_id);
@@ -1357,7 +1521,8 @@ pub async fn run(cli_host: Option<String>, cli_port: Option<u16>) -> anyhow::Res
                 if let Some(tx) = maybe_tx {
                     // Parse the reply as a confirm response
                     let text = msg.content.trim().to_lowercase();
-                    let approved = text == "y"
+                    let approved = text == "/approve"
+                        || text == "y"
                         || text == "yes"
                         || text.contains("允许")
                         || text.contains("确认")
@@ -1369,9 +1534,30 @@ pub async fn run(cli_host: Option<String>, cli_port: Option<u16>) -> anyhow::Res
                         reply = %msg.content.trim(),
                         "Channel confirm reply intercepted"
                     );
-                    let _ = tx.send(approved);
+                    let ack = if tx.send(approved).is_err() {
+                        "This approval request has expired."
+                    } else if approved {
+                        "✅ Approved."
+                    } else {
+                        "❌ Denied."
+                    };
+                    let mut reply = OutboundMessage::new(&msg.channel, &msg.chat_id, ack);
+                    reply.account_id = msg.account_id.clone();
+                    let _ = slash_outbound_tx.send(reply).await;
                     continue; // Don't forward this message to the runtime
                 }
+                // Approval commands (or buttons) with nothing pending, e.g. after a timeout.
+                let command = msg.content.trim();
+                if command == "/approve" || command == "/deny" {
+                    let mut reply = OutboundMessage::new(
+                        &msg.channel,
+                        &msg.chat_id,
+                        "Nothing is waiting for approval here.",
+                    );
+                    reply.account_id = msg.account_id.clone();
+                    let _ = slash_outbound_tx.send(reply).await;
+                    continue;
+                }
             }
 
             // 斜杠命令拦截（在 confirm reply 检查之后，转发给 runtime 之前）
@@ -1502,11 +1688,17 @@ pub async fn run(cli_host: Option<String>, cli_port: Option<u16>) -> anyhow::Res
     let ws_broadcast_for_bridge = ws_broadcast_tx.clone();
     let outbound_shutdown_rx = shutdown_tx.subscribe();
     let channel_manager_for_bridge = Arc::clone(&channel_manager);
+    let max_queued_offline = if config.offline.enabled {
+        config.offline.max_queued_deliveries
+    } else {
+        0
+    };
     let outbound_handle = tokio::spawn(async move {
         outbound_to_ws_bridge(
             outbound_rx,
             ws_broadcast_for_bridge,
             channel_manager_for_bridge,
+            max_queued_offline,
             outbound_shutdown_rx,
         )
         .await;
@@ -1713,6 +1905,15 @@ pub async fn run(cli_host: Option<String>, cli_port: Option<u16>) -> anyhow::Res
         EvolutionServiceConfig::default(),
     )));
 
+    let web_auth = Arc::new(WebUiAuth::new(&config, &paths)?);
+    if web_auth.enabled() {
+        info!(
+            origin = %config.gateway.webauthn_origin(),
+            mode = ?config.gateway.webauthn.mode,
+            "WebUI passkeys enabled"
+        );
+    }
+
     let gateway_state = GatewayState {
         inbound_tx: inbound_tx.clone(),
         task_manager,
@@ -1730,15 +1931,45 @@ pub async fn run(cli_host: Option<String>, cli_port: Option<u16>) -> anyhow::Res
         channel_manager: Arc::clone(&channel_manager),
         evolution_service: shared_evo_service,
         response_caches: response_caches.clone(),
+        admin_audit: Arc::new(blockcell_storage::admin_audit::AdminAuditLog::new(
+            paths.admin_audit_file(),
+        )),
+        web_auth,
+        login_limiter: Arc::new(LoginLimiter::new(&config, &paths)?),
     };
 
+    let base_path = config.gateway.normalized_base_path();
+    let trusted_proxies = Arc::new(TrustedProxies::new(&config.gateway.trusted_proxies));
+
     let app = Router::new()
         // Auth
         .route("/v1/auth/login", post(handle_login))
+        .route("/v1/auth/methods", get(handle_auth_methods))
+        .route("/v1/auth/refresh", post(handle_auth_refresh))
+        .route("/v1/auth/logout", post(handle_auth_logout))
+        .route(
+            "/v1/auth/passkey/login/start",
+            post(handle_passkey_login_start),
+        )
+        .route(
+            "/v1/auth/passkey/login/finish",
+            post(handle_passkey_login_finish),
+        )
+        .route(
+            "/v1/auth/passkey/register/start",
+            post(handle_passkey_register_start),
+        )
+        .route(
+            "/v1/auth/passkey/register/finish",
+            post(handle_passkey_register_finish),
+        )
+        .route("/v1/auth/passkeys", get(handle_passkeys_list))
+        .route("/v1/auth/passkeys/:id", delete(handle_passkey_delete))
         // P0: Core
         .route("/v1/chat", post(handle_chat))
         .route("/v1/health", get(handle_health))
         .route("/v1/tasks", get(handle_tasks))
+        .route("/v1/tasks/:id/artifacts", get(handle_task_artifacts))
         .route("/v1/ws", get(handle_ws_upgrade))
         // P0: Sessions
         .route("/v1/sessions", get(handle_sessions_list))
@@ -1747,6 +1978,10 @@ pub async fn run(cli_host: Option<String>, cli_port: Option<u16>) -> anyhow::Res
             get(handle_session_get).delete(handle_session_delete),
         )
         .route("/v1/sessions/:id/rename", put(handle_session_rename))
+        .route(
+            "/v1/sessions/:id/messages/:idx/context",
+            get(handle_session_message_context),
+        )
         // P1: Config
         .route(
             "/v1/config",
@@ -1778,10 +2013,13 @@ pub async fn run(cli_host: Option<String>, cli_port: Option<u16>) -> anyhow::Res
         )
         .route("/v1/memory/stats", get(handle_memory_stats))
         .route("/v1/memory/:id", delete(handle_memory_delete))
+        // Privacy
+        .route("/v1/privacy/purge", post(handle_privacy_purge))
         // P1: Tools / Skills / Evolution / Stats
         .route("/v1/tools", get(handle_tools))
         .route("/v1/skills", get(handle_skills))
         .route("/v1/skills/search", post(handle_skills_search))
+        .route("/v1/skills/stats", get(handle_skills_stats))
         .route("/v1/evolution", get(handle_evolution))
         .route(
             "/v1/evolution/tool-evolutions",
@@ -1835,6 +2073,7 @@ pub async fn run(cli_host: Option<String>, cli_port: Option<u16>) -> anyhow::Res
         .route("/v1/cron", get(handle_cron_list).post(handle_cron_create))
         .route("/v1/cron/:id", delete(handle_cron_delete))
         .route("/v1/cron/:id/run", post(handle_cron_run))
+        .route("/v1/cron/:id/history", get(handle_cron_history))
         // Toggles
         .route(
             "/v1/toggles",
@@ -1861,17 +2100,32 @@ pub async fn run(cli_host: Option<String>, cli_port: Option<u16>) -> anyhow::Res
         )
         // Pool status
         .route("/v1/pool/status", get(handle_pool_status))
+        .route("/v1/providers/health", get(handle_providers_health))
         // P2: Files
         .route("/v1/files", get(handle_files_list))
         .route("/v1/files/content", get(handle_files_content))
         .route("/v1/files/download", get(handle_files_download))
         .route("/v1/files/serve", get(handle_files_serve))
         .route("/v1/files/upload", post(handle_files_upload))
+        .route("/v1/files/share", post(handle_files_share_create))
+        .route("/v1/files/shares", get(handle_files_shares_list))
+        .route("/v1/files/share/:token", delete(handle_files_share_revoke))
+        // Webhook ingestion — token-authenticated, optional per-source HMAC
+        .route("/v1/ingest/:source", post(handle_ingest))
+        // Named hooks from `blockcell hooks add` — agent message or direct tool call
+        .route("/v1/hooks/:name", post(handle_hook))
+        // Admin audit log
+        .route("/v1/audit/admin", get(handle_admin_audit_list))
+        // Runs after auth, so only authorized admin requests are recorded
+        .layer(middleware::from_fn_with_state(
+            gateway_state.clone(),
+            admin_audit_middleware,
+        ))
         .layer(middleware::from_fn_with_state(
             gateway_state.clone(),
             auth_middleware,
         ))
-        .layer(build_api_cors_layer(&config))
+        .layer(build_api_cors_layer(&config, &host))
         // Webhook endpoints — public (no auth), must be outside auth middleware
         .route("/webhook/lark", post(handle_lark_webhook))
         .route(
@@ -1879,48 +2133,76 @@ pub async fn run(cli_host: Option<String>, cli_port: Option<u16>) -> anyhow::Res
             get(handle_wecom_webhook).post(handle_wecom_webhook),
         )
         .route("/webhook/qq", post(handle_qq_webhook))
+        // File share links — public, the token (plus optional password) is the credential
+        .route("/s/:token", get(handle_share_get).post(handle_share_post));
+    // Single-port mode: the WebUI shares this listener, outside auth like the routes above.
+    // Not served in lite mode.
+    let single_port = !config.lite && config.gateway.is_single_port(port);
+    let app = if single_port {
+        webui_routes(app, &config, true)
+    } else {
+        app
+    };
+    let app = app
+        // Outermost, so every handler and middleware above sees the real client address
+        .layer(middleware::from_fn_with_state(
+            trusted_proxies.clone(),
+            client_ip_middleware,
+        ))
         .with_state(gateway_state);
+    let app = with_base_path(app, &base_path);
 
     let bind_addr = format!("{}:{}", host, port);
     let listener = tokio::net::TcpListener::bind(&bind_addr).await?;
 
     let http_shutdown_rx = shutdown_tx.subscribe();
     let http_handle = tokio::spawn(async move {
-        axum::serve(listener, app)
-            .with_graceful_shutdown(async move {
-                let mut rx = http_shutdown_rx;
-                let _ = rx.recv().await;
-            })
-            .await
-            .ok();
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .with_graceful_shutdown(async move {
+            let mut rx = http_shutdown_rx;
+            let _ = rx.recv().await;
+        })
+        .await
+        .ok();
     });
 
     // ── WebUI static file server (embedded via rust-embed) ──
-    let webui_host = config.gateway.webui_host.clone();
-    let webui_port = config.gateway.webui_port;
-    let webui_bind = format!("{}:{}", webui_host, webui_port);
-    let webui_config = config.clone();
-    let webui_app = Router::new()
-        .route(
-            "/env.js",
-            get(move || {
-                let cfg = webui_config.clone();
-                async move { handle_webui_env_js(cfg).await }
-            }),
-        )
-        .fallback(handle_webui_static)
-        .layer(build_webui_cors_layer(&config));
-    let webui_listener = tokio::net::TcpListener::bind(&webui_bind).await?;
-    let webui_shutdown_rx = shutdown_tx.subscribe();
-    let webui_handle = tokio::spawn(async move {
-        axum::serve(webui_listener, webui_app)
+    // Not served in lite mode; the HTTP API above stays available. In single-port mode
+    // it is already mounted on the API listener.
+    let (webui_host, webui_port) = if single_port {
+        (host.clone(), port)
+    } else {
+        (config.gateway.webui_host.clone(), config.gateway.webui_port)
+    };
+    let webui_handle = if config.lite || single_port {
+        None
+    } else {
+        let webui_bind = format!("{}:{}", webui_host, webui_port);
+        let webui_app = webui_routes(Router::new(), &config, false)
+            .layer(build_webui_cors_layer(&config))
+            .layer(middleware::from_fn_with_state(
+                trusted_proxies.clone(),
+                client_ip_middleware,
+            ));
+        let webui_app = with_base_path(webui_app, &base_path);
+        let webui_listener = tokio::net::TcpListener::bind(&webui_bind).await?;
+        let webui_shutdown_rx = shutdown_tx.subscribe();
+        Some(tokio::spawn(async move {
+            axum::serve(
+                webui_listener,
+                webui_app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+            )
             .with_graceful_shutdown(async move {
                 let mut rx = webui_shutdown_rx;
                 let _ = rx.recv().await;
             })
             .await
             .ok();
-    });
+        }))
+    };
 
     // ── Print beautiful startup banner ──
     print_startup_banner(
@@ -1946,7 +2228,6 @@ pub async fn run(cli_host: Option<String>, cli_port: Option<u16>) -> anyhow::Res
 
     let mut handles: Vec<(String, tokio::task::JoinHandle<()>)> = vec![
         ("http_server".to_string(), http_handle),
-        ("webui_server".to_string(), webui_handle),
         ("confirm_handler".to_string(), confirm_handler_handle),
         ("dispatcher".to_string(), dispatcher_handle),
         ("outbound".to_string(), outbound_handle),
@@ -1954,6 +2235,12 @@ pub async fn run(cli_host: Option<String>, cli_port: Option<u16>) -> anyhow::Res
         ("heartbeat".to_string(), heartbeat_handle),
         ("ghost".to_string(), ghost_handle),
     ];
+    if let Some(handle) = webui_handle {
+        handles.push(("webui_server".to_string(), handle));
+    }
+    if let Some(handle) = dead_mans_switch_handle {
+        handles.push(("dead_mans_switch".to_string(), handle));
+    }
     handles.extend(runtime_handles);
     handles.extend(cron_handles);
     handles.extend(channel_handles);
@@ -2013,16 +2300,6 @@ pub async fn run(cli_host: Option<String>, cli_port: Option<u16>) -> anyhow::Res
     Ok(())
 }
 
-fn build_api_cors_layer(config: &Config) -> CorsLayer {
-    let _ = config;
-    CorsLayer::permissive().allow_credentials(false)
-}
-
-fn build_webui_cors_layer(config: &Config) -> CorsLayer {
-    let _ = config;
-    CorsLayer::permissive().allow_credentials(false)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,217 @@
+use super::*;
+use blockcell_storage::admin_audit::{AdminAction, AdminAuditQuery};
+// ---------------------------------------------------------------------------
+// Admin audit: every mutating admin request is appended to the hash-chained
+// log in `audit/admin.jsonl`
+// ---------------------------------------------------------------------------
+
+/// Request bodies larger than this are not inspected for `details.fields`.
+const MAX_INSPECTED_BODY_BYTES: u64 = 1024 * 1024;
+
+/// The audit action name of an admin mutation, `None` for everything else.
+fn admin_action(method: &str, path: &str) -> Option<&'static str> {
+    let rest = path.strip_prefix("/v1/")?;
+    let segments: Vec<&str> = rest.split('/').filter(|s| !s.is_empty()).collect();
+    let action = match (method, segments.as_slice()) {
+        ("PUT", ["config"]) => "config.update",
+        ("PUT", ["config", "raw"]) => "config.raw_update",
+        ("POST", ["config", "reload"]) => "config.reload",
+        ("PUT", ["ghost", "config"]) => "ghost.config_update",
+        ("PUT", ["toggles"]) => "toggles.update",
+        ("POST", ["cron"]) => "cron.create",
+        ("DELETE", ["cron", _]) => "cron.delete",
+        ("POST", ["cron", _, "run"]) => "cron.run",
+        ("POST", ["alerts"]) => "alerts.create",
+        ("PUT", ["alerts", _]) => "alerts.update",
+        ("DELETE", ["alerts", _]) => "alerts.delete",
+        ("POST", ["evolution", "trigger"]) => "evolution.trigger",
+        ("POST", ["evolution", _, "stop"]) => "evolution.stop",
+        ("POST", ["evolution", _, "resume"]) => "evolution.resume",
+        ("DELETE", ["evolution", _]) => "evolution.delete",
+        ("PUT", ["channels", _]) => "channels.update",
+        ("PUT", ["channel-owners", ..]) => "channel_owners.update",
+        ("DELETE", ["channel-owners", ..]) => "channel_owners.delete",
+        ("DELETE", ["skills", _]) => "skills.delete",
+        ("POST", ["hub", "skills", _, "install"]) | ("POST", ["skills", "install-external"]) => {
+            "skills.install"
+        }
+        ("PUT", ["persona", "file"]) => "persona.update",
+        ("POST", ["privacy", "purge"]) => "privacy.purge",
+        _ => return None,
+    };
+    Some(action)
+}
+
+/// Names of the top-level fields of a JSON body; values are never recorded since
+/// config updates carry API keys.
+fn body_fields(body: &[u8]) -> Option<Vec<String>> {
+    match serde_json::from_slice::<serde_json::Value>(body).ok()? {
+        serde_json::Value::Object(map) => Some(map.keys().cloned().collect()),
+        _ => None,
+    }
+}
+
+/// Query string without credentials.
+fn safe_query(query: Option<&str>) -> Option<String> {
+    let kept: Vec<&str> = query?
+        .split('&')
+        .filter(|pair| {
+            let key = pair.split('=').next().unwrap_or_default();
+            !key.is_empty() && !key.eq_ignore_ascii_case("token")
+        })
+        .collect();
+    (!kept.is_empty()).then(|| kept.join("&"))
+}
+
+pub(super) async fn admin_audit_middleware(
+    State(state): State<GatewayState>,
+    req: Request<axum::body::Body>,
+    next: Next,
+) -> Response {
+    let Some(action) = admin_action(req.method().as_str(), req.uri().path()) else {
+        return next.run(req).await;
+    };
+
+    let method = req.method().to_string();
+    let path = req.uri().path().to_string();
+    let query = safe_query(req.uri().query());
+    let client_ip = req
+        .extensions()
+        .get::<ClientIp>()
+        .map(|ip| ip.0.to_string());
+    let user_agent = req
+        .headers()
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    // Small JSON bodies are buffered so the entry can say which fields changed.
+    let inspect_body = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"))
+        && req
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok())
+            .is_some_and(|len| len <= MAX_INSPECTED_BODY_BYTES);
+    let (req, fields) = if inspect_body {
+        let (parts, body) = req.into_parts();
+        match axum::body::to_bytes(body, MAX_INSPECTED_BODY_BYTES as usize).await {
+            Ok(bytes) => {
+                let fields = body_fields(&bytes);
+                (
+                    Request::from_parts(parts, axum::body::Body::from(bytes)),
+                    fields,
+                )
+            }
+            Err(e) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    format!("Failed to read body: {}", e),
+                )
+                    .into_response()
+            }
+        }
+    } else {
+        (req, None)
+    };
+
+    let response = next.run(req).await;
+
+    let actor = match &state.api_token {
+        Some(token) if !token.is_empty() => "api_token",
+        _ => "anonymous",
+    };
+    let entry = AdminAction {
+        actor: actor.to_string(),
+        client_ip,
+        action: action.to_string(),
+        method,
+        path,
+        status: response.status().as_u16(),
+        details: serde_json::json!({
+            "query": query,
+            "fields": fields,
+            "user_agent": user_agent,
+        }),
+    };
+    if let Err(e) = state.admin_audit.append(entry) {
+        error!(action, error = %e, "Failed to write admin audit entry");
+    }
+    response
+}
+
+#[derive(Deserialize)]
+pub(super) struct AdminAuditListQuery {
+    /// Only entries at or after this unix time in milliseconds.
+    since: Option<i64>,
+    /// Action prefix, e.g. `cron.` or `config.update`.
+    action: Option<String>,
+    actor: Option<String>,
+    limit: Option<usize>,
+}
+
+/// GET /v1/audit/admin — admin actions, newest first, plus a chain integrity check
+pub(super) async fn handle_admin_audit_list(
+    State(state): State<GatewayState>,
+    Query(query): Query<AdminAuditListQuery>,
+) -> impl IntoResponse {
+    let filter = AdminAuditQuery {
+        since_ms: query.since,
+        action_prefix: query.action.filter(|a| !a.is_empty()),
+        actor: query.actor.filter(|a| !a.is_empty()),
+        limit: query.limit.unwrap_or(100).clamp(1, 1000),
+    };
+    let result = state
+        .admin_audit
+        .query(&filter)
+        .and_then(|entries| Ok((entries, state.admin_audit.verify()?)));
+    match result {
+        Ok((entries, chain)) => {
+            let count = entries.len();
+            Json(serde_json::json!({ "entries": entries, "count": count, "chain": chain }))
+        }
+        Err(e) => Json(serde_json::json!({ "error": format!("{}", e) })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_admin_action_covers_mutations_only() {
+        assert_eq!(admin_action("PUT", "/v1/config"), Some("config.update"));
+        assert_eq!(admin_action("GET", "/v1/config"), None);
+        assert_eq!(admin_action("DELETE", "/v1/cron/abc"), Some("cron.delete"));
+        assert_eq!(admin_action("POST", "/v1/cron/abc/run"), Some("cron.run"));
+        assert_eq!(
+            admin_action("PUT", "/v1/channel-owners/telegram/accounts/bot1"),
+            Some("channel_owners.update")
+        );
+        assert_eq!(
+            admin_action("POST", "/v1/evolution/evo-1/resume"),
+            Some("evolution.resume")
+        );
+        assert_eq!(admin_action("POST", "/v1/evolution/test"), None);
+        assert_eq!(admin_action("POST", "/v1/skills/search"), None);
+        assert_eq!(admin_action("POST", "/v1/chat"), None);
+    }
+
+    #[test]
+    fn test_recorded_request_details_exclude_secrets() {
+        assert_eq!(
+            body_fields(br#"{"providers":{"openai":{"apiKey":"sk-1"}},"gateway":{}}"#),
+            Some(vec!["gateway".to_string(), "providers".to_string()])
+        );
+        assert_eq!(body_fields(b"[1,2]"), None);
+        assert_eq!(
+            safe_query(Some("agent=ops&token=secret&TOKEN=x")).as_deref(),
+            Some("agent=ops")
+        );
+        assert_eq!(safe_query(Some("token=secret")), None);
+    }
+}
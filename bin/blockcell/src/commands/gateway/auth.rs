@@ -0,0 +1,771 @@
+use super::*;
+use axum::http::{HeaderMap, HeaderValue};
+use blockcell_core::config::WebAuthnMode;
+use blockcell_core::login_guard::password_matches;
+use blockcell_core::webui_auth::{new_token, WebUiAuthStore};
+use webauthn_rs::prelude::{
+    CredentialID, Passkey, PasskeyAuthentication, PasskeyRegistration, PublicKeyCredential,
+    RegisterPublicKeyCredential, Url,
+};
+use webauthn_rs::{Webauthn, WebauthnBuilder};
+// ---------------------------------------------------------------------------
+// WebUI login: password, passkeys (WebAuthn) and cookie sessions
+// ---------------------------------------------------------------------------
+//
+// Without `gateway.webauthn.enabled` a password login returns the API token, as before.
+// With it, logins set an HttpOnly session cookie (in memory, `sessionTtlSecs`) and a
+// refresh cookie (hashed in webui_auth.json, rotated on every use). API requests are
+// authorized by the API token or a live session.
+
+const SESSION_COOKIE: &str = "blockcell_session";
+const REFRESH_COOKIE: &str = "blockcell_refresh";
+/// How long a passkey challenge or a password-step ticket stays valid.
+const CEREMONY_TTL_SECS: i64 = 300;
+
+/// Auth endpoints reachable without a token or session.
+const PUBLIC_AUTH_PATHS: &[&str] = &[
+    "/v1/auth/login",
+    "/v1/auth/methods",
+    "/v1/auth/refresh",
+    "/v1/auth/logout",
+    "/v1/auth/passkey/login/start",
+    "/v1/auth/passkey/login/finish",
+];
+
+pub(super) fn is_public_auth_path(path: &str) -> bool {
+    PUBLIC_AUTH_PATHS.contains(&path)
+}
+
+enum Ceremony {
+    Register {
+        state: PasskeyRegistration,
+        name: String,
+    },
+    Login {
+        state: PasskeyAuthentication,
+    },
+}
+
+pub(super) struct WebUiAuth {
+    webauthn: Option<Webauthn>,
+    mode: WebAuthnMode,
+    rp_name: String,
+    session_ttl_secs: u64,
+    refresh_ttl_secs: u64,
+    /// Only the refresh and logout endpoints need the refresh cookie.
+    refresh_cookie_path: String,
+    secure_cookies: bool,
+    store: Mutex<WebUiAuthStore>,
+    /// Session token → expiry.
+    sessions: Mutex<HashMap<String, i64>>,
+    /// Challenge id → (expiry, pending ceremony).
+    ceremonies: Mutex<HashMap<String, (i64, Ceremony)>>,
+    /// `password_and_passkey`: tickets for a correct password, awaiting the passkey.
+    tickets: Mutex<HashMap<String, i64>>,
+}
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+fn cred_id_string(id: &CredentialID) -> String {
+    serde_json::to_value(id)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn cookie_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, value)| *key == name && !value.is_empty())
+        .map(|(_, value)| value.to_string())
+}
+
+fn with_cookies(response: impl IntoResponse, cookies: Vec<String>) -> Response {
+    let mut response = response.into_response();
+    for cookie in cookies {
+        if let Ok(value) = HeaderValue::from_str(&cookie) {
+            response.headers_mut().append(header::SET_COOKIE, value);
+        }
+    }
+    response
+}
+
+fn auth_error(status: StatusCode, msg: impl Into<String>) -> Response {
+    (status, Json(serde_json::json!({ "error": msg.into() }))).into_response()
+}
+
+fn not_enabled() -> Response {
+    auth_error(
+        StatusCode::NOT_FOUND,
+        "Passkeys are not enabled (gateway.webauthn.enabled)",
+    )
+}
+
+impl WebUiAuth {
+    pub(super) fn new(config: &Config, paths: &Paths) -> anyhow::Result<Self> {
+        let cfg = &config.gateway.webauthn;
+        let origin = config.gateway.webauthn_origin();
+        let webauthn = if cfg.enabled {
+            let url = Url::parse(&origin)?;
+            Some(
+                WebauthnBuilder::new(&config.gateway.webauthn_rp_id(), &url)?
+                    .rp_name(&cfg.rp_name)
+                    .build()?,
+            )
+        } else {
+            None
+        };
+        // The browser sees the API under publicApiBase (which may carry a proxy prefix),
+        // otherwise under base_path.
+        let api_prefix = match config
+            .gateway
+            .public_api_base
+            .as_deref()
+            .map(str::trim)
+            .filter(|b| !b.is_empty())
+        {
+            Some(base) => Url::parse(base)
+                .map(|u| u.path().trim_end_matches('/').to_string())
+                .unwrap_or_default(),
+            None => config.gateway.normalized_base_path(),
+        };
+        Ok(Self {
+            webauthn,
+            mode: cfg.mode,
+            rp_name: cfg.rp_name.clone(),
+            session_ttl_secs: cfg.session_ttl_secs,
+            refresh_ttl_secs: cfg.refresh_ttl_secs,
+            refresh_cookie_path: format!("{}/v1/auth", api_prefix),
+            secure_cookies: origin.starts_with("https://"),
+            store: Mutex::new(WebUiAuthStore::open(paths)?),
+            sessions: Mutex::new(HashMap::new()),
+            ceremonies: Mutex::new(HashMap::new()),
+            tickets: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub(super) fn enabled(&self) -> bool {
+        self.webauthn.is_some()
+    }
+
+    fn cookie(&self, name: &str, value: &str, path: &str, max_age: u64) -> String {
+        let mut cookie = format!(
+            "{}={}; Path={}; Max-Age={}; HttpOnly; SameSite=Strict",
+            name, value, path, max_age
+        );
+        if self.secure_cookies {
+            cookie.push_str("; Secure");
+        }
+        cookie
+    }
+
+    fn clear_cookies(&self) -> Vec<String> {
+        vec![
+            self.cookie(SESSION_COOKIE, "", "/", 0),
+            self.cookie(REFRESH_COOKIE, "", &self.refresh_cookie_path, 0),
+        ]
+    }
+
+    /// Whether the request carries a live session cookie.
+    pub(super) async fn has_session(&self, headers: &HeaderMap) -> bool {
+        if !self.enabled() {
+            return false;
+        }
+        let Some(token) = cookie_value(headers, SESSION_COOKIE) else {
+            return false;
+        };
+        let sessions = self.sessions.lock().await;
+        sessions
+            .iter()
+            .any(|(t, expires_at)| secure_eq(t, &token) && *expires_at > now())
+    }
+
+    async fn has_passkeys(&self) -> bool {
+        self.store.lock().await.has_passkeys()
+    }
+
+    /// Start a session. Returns the Set-Cookie values.
+    async fn sign_in(&self, passkey: bool) -> anyhow::Result<Vec<String>> {
+        let refresh = {
+            let mut store = self.store.lock().await;
+            let token = store.issue_refresh(self.refresh_ttl_secs, passkey);
+            store.save()?;
+            token
+        };
+        let session = new_token();
+        let now = now();
+        let mut sessions = self.sessions.lock().await;
+        sessions.retain(|_, expires_at| *expires_at > now);
+        sessions.insert(session.clone(), now + self.session_ttl_secs as i64);
+        Ok(vec![
+            self.cookie(SESSION_COOKIE, &session, "/", self.session_ttl_secs),
+            self.cookie(
+                REFRESH_COOKIE,
+                &refresh,
+                &self.refresh_cookie_path,
+                self.refresh_ttl_secs,
+            ),
+        ])
+    }
+
+    async fn sign_in_response(&self, passkey: bool) -> Response {
+        match self.sign_in(passkey).await {
+            Ok(cookies) => with_cookies(
+                Json(serde_json::json!({
+                    "status": "ok",
+                    "expires_in": self.session_ttl_secs,
+                })),
+                cookies,
+            ),
+            Err(e) => auth_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        }
+    }
+
+    async fn begin(&self, ceremony: Ceremony) -> String {
+        let id = new_token();
+        let now = now();
+        let mut ceremonies = self.ceremonies.lock().await;
+        ceremonies.retain(|_, (expires_at, _)| *expires_at > now);
+        ceremonies.insert(id.clone(), (now + CEREMONY_TTL_SECS, ceremony));
+        id
+    }
+
+    async fn take_ceremony(&self, id: &str) -> Option<Ceremony> {
+        self.ceremonies
+            .lock()
+            .await
+            .remove(id)
+            .filter(|(expires_at, _)| *expires_at > now())
+            .map(|(_, ceremony)| ceremony)
+    }
+
+    async fn issue_ticket(&self) -> String {
+        let ticket = new_token();
+        let now = now();
+        let mut tickets = self.tickets.lock().await;
+        tickets.retain(|_, expires_at| *expires_at > now);
+        tickets.insert(ticket.clone(), now + CEREMONY_TTL_SECS);
+        ticket
+    }
+
+    async fn check_ticket(&self, ticket: Option<&str>, consume: bool) -> bool {
+        let Some(ticket) = ticket else {
+            return false;
+        };
+        let mut tickets = self.tickets.lock().await;
+        let valid = tickets.get(ticket).is_some_and(|t| *t > now());
+        if consume {
+            tickets.remove(ticket);
+        }
+        valid
+    }
+
+    /// A password-only login is not enough once passkeys exist in the stricter modes.
+    async fn password_only_allowed(&self) -> bool {
+        self.mode == WebAuthnMode::Optional || !self.has_passkeys().await
+    }
+
+    /// Registered passkeys, keyed by credential id.
+    async fn passkeys(&self) -> Vec<(String, Passkey)> {
+        let store = self.store.lock().await;
+        store
+            .passkeys()
+            .into_iter()
+            .filter_map(|record| {
+                match serde_json::from_value::<Passkey>(record.credential.clone()) {
+                    Ok(passkey) => Some((record.id.clone(), passkey)),
+                    Err(e) => {
+                        warn!(id = %record.id, error = %e, "Skipping unreadable passkey");
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+/// Whether a request may use the API: no token configured, the API token (Bearer or
+/// `?token=`), or a WebUI session cookie.
+pub(super) async fn request_authorized(
+    state: &GatewayState,
+    headers: &HeaderMap,
+    uri: &axum::http::Uri,
+) -> bool {
+    let token = match &state.api_token {
+        Some(t) if !t.is_empty() => t,
+        _ => return true,
+    };
+    let from_header = match headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+    {
+        Some(h) if h.starts_with("Bearer ") => secure_eq(&h[7..], token.as_str()),
+        _ => false,
+    };
+    let from_query = token_from_query(uri)
+        .map(|v| secure_eq(&v, token.as_str()))
+        .unwrap_or(false);
+    from_header || from_query || state.web_auth.has_session(headers).await
+}
+
+// ---------------------------------------------------------------------------
+// Handlers
+// ---------------------------------------------------------------------------
+
+#[derive(Deserialize)]
+pub(super) struct LoginRequest {
+    password: String,
+}
+
+/// GET /v1/auth/methods — which login steps the WebUI should offer.
+pub(super) async fn handle_auth_methods(State(state): State<GatewayState>) -> Response {
+    let auth = &state.web_auth;
+    let passkeys = auth.enabled() && auth.has_passkeys().await;
+    Json(serde_json::json!({
+        "password": !(passkeys && auth.mode == WebAuthnMode::PasskeyOnly),
+        "passkey": passkeys,
+        "mode": auth.mode,
+        "sessions": auth.enabled(),
+    }))
+    .into_response()
+}
+
+/// POST /v1/auth/login — password login, rate limited per client address. Returns the
+/// API token, or with passkeys enabled sets session cookies (or asks for the passkey step).
+pub(super) async fn handle_login(
+    State(state): State<GatewayState>,
+    client_ip: Option<axum::Extension<ClientIp>>,
+    Json(req): Json<LoginRequest>,
+) -> Response {
+    let client_ip = client_ip.map_or_else(|| "unknown".to_string(), |ip| ip.0 .0.to_string());
+    // Locked-out clients are refused before the password is looked at.
+    if let Some(lockout) = state.login_limiter.check(&client_ip).await {
+        return lockout_response(&lockout);
+    }
+    if !password_matches(&req.password, &state.web_password) {
+        let failure = state.login_limiter.failure(&client_ip).await;
+        audit_login_failure(&state, &client_ip, &failure);
+        return match &failure.lockout {
+            Some(lockout) => lockout_response(lockout),
+            None => auth_error(StatusCode::UNAUTHORIZED, "Invalid password"),
+        };
+    }
+    state.login_limiter.success(&client_ip).await;
+    let auth = &state.web_auth;
+    if !auth.enabled() {
+        // Return the api_token as the Bearer token for subsequent API requests
+        return match &state.api_token {
+            Some(token) if !token.is_empty() => {
+                Json(serde_json::json!({ "token": token })).into_response()
+            }
+            // Should never happen after the defensive guarantee in run()
+            _ => auth_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Server token not configured",
+            ),
+        };
+    }
+    if auth.password_only_allowed().await {
+        return auth.sign_in_response(false).await;
+    }
+    match auth.mode {
+        WebAuthnMode::PasswordAndPasskey => Json(serde_json::json!({
+            "status": "passkey_required",
+            "ticket": auth.issue_ticket().await,
+        }))
+        .into_response(),
+        _ => auth_error(
+            StatusCode::FORBIDDEN,
+            "Password login is disabled; sign in with a passkey",
+        ),
+    }
+}
+
+#[derive(Deserialize, Default)]
+pub(super) struct PasskeyLoginStartRequest {
+    /// From the password step (`password_and_passkey`).
+    #[serde(default)]
+    ticket: Option<String>,
+}
+
+/// POST /v1/auth/passkey/login/start — WebAuthn request options for the registered passkeys.
+pub(super) async fn handle_passkey_login_start(
+    State(state): State<GatewayState>,
+    Json(req): Json<PasskeyLoginStartRequest>,
+) -> Response {
+    let auth = &state.web_auth;
+    let Some(webauthn) = auth.webauthn.as_ref() else {
+        return not_enabled();
+    };
+    if auth.mode == WebAuthnMode::PasswordAndPasskey
+        && !auth.check_ticket(req.ticket.as_deref(), false).await
+    {
+        return auth_error(StatusCode::UNAUTHORIZED, "Enter the password first");
+    }
+    let passkeys: Vec<Passkey> = auth.passkeys().await.into_iter().map(|(_, p)| p).collect();
+    if passkeys.is_empty() {
+        return auth_error(StatusCode::BAD_REQUEST, "No passkeys registered");
+    }
+    match webauthn.start_passkey_authentication(&passkeys) {
+        Ok((options, ceremony)) => {
+            let challenge_id = auth.begin(Ceremony::Login { state: ceremony }).await;
+            Json(serde_json::json!({ "challenge_id": challenge_id, "options": options }))
+                .into_response()
+        }
+        Err(e) => auth_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+#[derive(Deserialize)]
+pub(super) struct PasskeyLoginFinishRequest {
+    challenge_id: String,
+    credential: PublicKeyCredential,
+    #[serde(default)]
+    ticket: Option<String>,
+}
+
+/// POST /v1/auth/passkey/login/finish — verify the assertion and start a session.
+pub(super) async fn handle_passkey_login_finish(
+    State(state): State<GatewayState>,
+    Json(req): Json<PasskeyLoginFinishRequest>,
+) -> Response {
+    let auth = &state.web_auth;
+    let Some(webauthn) = auth.webauthn.as_ref() else {
+        return not_enabled();
+    };
+    let Some(Ceremony::Login { state: ceremony }) = auth.take_ceremony(&req.challenge_id).await
+    else {
+        return auth_error(StatusCode::BAD_REQUEST, "Unknown or expired challenge");
+    };
+    if auth.mode == WebAuthnMode::PasswordAndPasskey
+        && !auth.check_ticket(req.ticket.as_deref(), true).await
+    {
+        return auth_error(StatusCode::UNAUTHORIZED, "Enter the password first");
+    }
+    let result = match webauthn.finish_passkey_authentication(&req.credential, &ceremony) {
+        Ok(result) => result,
+        Err(e) => {
+            warn!(error = %e, "Passkey login failed");
+            return auth_error(StatusCode::UNAUTHORIZED, "Passkey verification failed");
+        }
+    };
+
+    // Keep the signature counter and backup state current.
+    let id = cred_id_string(result.cred_id());
+    let updated = auth
+        .passkeys()
+        .await
+        .into_iter()
+        .find(|(pid, _)| *pid == id)
+        .and_then(|(_, mut passkey)| {
+            if passkey.update_credential(&result) == Some(true) {
+                serde_json::to_value(&passkey).ok()
+            } else {
+                None
+            }
+        });
+    {
+        let mut store = auth.store.lock().await;
+        store.touch_passkey(&id, updated);
+        if let Err(e) = store.save() {
+            warn!(error = %e, "Failed to save passkey usage");
+        }
+    }
+    info!(passkey = %id, "WebUI passkey login");
+    auth.sign_in_response(true).await
+}
+
+#[derive(Deserialize, Default)]
+pub(super) struct PasskeyRegisterStartRequest {
+    #[serde(default)]
+    name: Option<String>,
+}
+
+/// POST /v1/auth/passkey/register/start — WebAuthn creation options for a new passkey.
+pub(super) async fn handle_passkey_register_start(
+    State(state): State<GatewayState>,
+    Json(req): Json<PasskeyRegisterStartRequest>,
+) -> Response {
+    let auth = &state.web_auth;
+    let Some(webauthn) = auth.webauthn.as_ref() else {
+        return not_enabled();
+    };
+    let exclude = auth
+        .passkeys()
+        .await
+        .into_iter()
+        .map(|(_, p)| p.cred_id().clone())
+        .collect::<Vec<_>>();
+    let user_id = {
+        let mut store = auth.store.lock().await;
+        let id = store.user_id();
+        if let Err(e) = store.save() {
+            return auth_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+        }
+        id
+    };
+    let display_name = format!("{} WebUI", auth.rp_name);
+    match webauthn.start_passkey_registration(user_id, "webui", &display_name, Some(exclude)) {
+        Ok((options, ceremony)) => {
+            let name = req
+                .name
+                .map(|n| n.trim().to_string())
+                .filter(|n| !n.is_empty())
+                .unwrap_or_else(|| "Passkey".to_string());
+            let challenge_id = auth
+                .begin(Ceremony::Register {
+                    state: ceremony,
+                    name,
+                })
+                .await;
+            Json(serde_json::json!({ "challenge_id": challenge_id, "options": options }))
+                .into_response()
+        }
+        Err(e) => auth_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+#[derive(Deserialize)]
+pub(super) struct PasskeyRegisterFinishRequest {
+    challenge_id: String,
+    credential: RegisterPublicKeyCredential,
+}
+
+/// POST /v1/auth/passkey/register/finish — verify the attestation and save the passkey.
+pub(super) async fn handle_passkey_register_finish(
+    State(state): State<GatewayState>,
+    Json(req): Json<PasskeyRegisterFinishRequest>,
+) -> Response {
+    let auth = &state.web_auth;
+    let Some(webauthn) = auth.webauthn.as_ref() else {
+        return not_enabled();
+    };
+    let Some(Ceremony::Register {
+        state: ceremony,
+        name,
+    }) = auth.take_ceremony(&req.challenge_id).await
+    else {
+        return auth_error(StatusCode::BAD_REQUEST, "Unknown or expired challenge");
+    };
+    let passkey = match webauthn.finish_passkey_registration(&req.credential, &ceremony) {
+        Ok(passkey) => passkey,
+        Err(e) => {
+            return auth_error(
+                StatusCode::BAD_REQUEST,
+                format!("Passkey registration failed: {}", e),
+            )
+        }
+    };
+    let credential = match serde_json::to_value(&passkey) {
+        Ok(v) => v,
+        Err(e) => return auth_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    };
+    let id = cred_id_string(passkey.cred_id());
+    let mut store = auth.store.lock().await;
+    let result = store
+        .add_passkey(&id, &name, credential)
+        .and_then(|record| store.save().map(|_| record));
+    match result {
+        Ok(record) => {
+            info!(passkey = %id, name = %record.name, "WebUI passkey registered");
+            Json(record.to_json()).into_response()
+        }
+        Err(e) => auth_error(StatusCode::BAD_REQUEST, e.to_string()),
+    }
+}
+
+/// GET /v1/auth/passkeys — registered passkeys (without key material).
+pub(super) async fn handle_passkeys_list(State(state): State<GatewayState>) -> Response {
+    let auth = &state.web_auth;
+    let store = auth.store.lock().await;
+    let passkeys: Vec<serde_json::Value> =
+        store.passkeys().into_iter().map(|p| p.to_json()).collect();
+    Json(serde_json::json!({
+        "enabled": auth.enabled(),
+        "mode": auth.mode,
+        "passkeys": passkeys,
+    }))
+    .into_response()
+}
+
+/// DELETE /v1/auth/passkeys/:id
+pub(super) async fn handle_passkey_delete(
+    State(state): State<GatewayState>,
+    AxumPath(id): AxumPath<String>,
+) -> Response {
+    let mut store = state.web_auth.store.lock().await;
+    if !store.remove_passkey(&id) {
+        return auth_error(StatusCode::NOT_FOUND, "Passkey not found");
+    }
+    if let Err(e) = store.save() {
+        return auth_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+    }
+    info!(passkey = %id, "WebUI passkey removed");
+    Json(serde_json::json!({ "status": "deleted", "id": id })).into_response()
+}
+
+/// POST /v1/auth/refresh — trade the refresh cookie for a new session (and refresh cookie).
+pub(super) async fn handle_auth_refresh(
+    State(state): State<GatewayState>,
+    headers: HeaderMap,
+) -> Response {
+    let auth = &state.web_auth;
+    if !auth.enabled() {
+        return not_enabled();
+    }
+    let unauthorized = || {
+        with_cookies(
+            auth_error(StatusCode::UNAUTHORIZED, "Session expired"),
+            auth.clear_cookies(),
+        )
+    };
+    let Some(token) = cookie_value(&headers, REFRESH_COOKIE) else {
+        return unauthorized();
+    };
+    let grant = {
+        let mut store = auth.store.lock().await;
+        let grant = store.redeem_refresh(&token, now());
+        if grant.is_some() {
+            if let Err(e) = store.save() {
+                return auth_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+            }
+        }
+        grant
+    };
+    let Some(grant) = grant else {
+        return unauthorized();
+    };
+    // A password-only session ends once a passkey is required.
+    if !grant.passkey && !auth.password_only_allowed().await {
+        return unauthorized();
+    }
+    auth.sign_in_response(grant.passkey).await
+}
+
+/// POST /v1/auth/logout — end the session and forget the refresh token.
+pub(super) async fn handle_auth_logout(
+    State(state): State<GatewayState>,
+    headers: HeaderMap,
+) -> Response {
+    let auth = &state.web_auth;
+    if let Some(session) = cookie_value(&headers, SESSION_COOKIE) {
+        auth.sessions.lock().await.remove(&session);
+    }
+    if let Some(refresh) = cookie_value(&headers, REFRESH_COOKIE) {
+        let mut store = auth.store.lock().await;
+        if store.revoke_refresh(&refresh) {
+            if let Err(e) = store.save() {
+                warn!(error = %e, "Failed to save revoked refresh token");
+            }
+        }
+    }
+    with_cookies(
+        Json(serde_json::json!({ "status": "ok" })),
+        auth.clear_cookies(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_auth(config: &Config) -> WebUiAuth {
+        let base =
+            std::env::temp_dir().join(format!("blockcell-webui-auth-{}", uuid::Uuid::new_v4()));
+        WebUiAuth::new(config, &Paths::with_base(base)).unwrap()
+    }
+
+    #[test]
+    fn test_cookie_parsing_and_attributes() {
+        let mut headers = HeaderMap::new();
+        headers.append(
+            header::COOKIE,
+            "theme=dark; blockcell_session=abc".parse().unwrap(),
+        );
+        headers.append(header::COOKIE, "blockcell_refresh=def".parse().unwrap());
+        assert_eq!(
+            cookie_value(&headers, SESSION_COOKIE).as_deref(),
+            Some("abc")
+        );
+        assert_eq!(
+            cookie_value(&headers, REFRESH_COOKIE).as_deref(),
+            Some("def")
+        );
+        assert!(cookie_value(&headers, "missing").is_none());
+
+        let mut config = Config::default();
+        config.gateway.base_path = "/blockcell/".to_string();
+        config.gateway.webauthn.enabled = true;
+        let auth = test_auth(&config);
+        assert!(auth.enabled());
+        assert!(!auth.secure_cookies);
+        assert_eq!(auth.refresh_cookie_path, "/blockcell/v1/auth");
+        let cleared = auth.clear_cookies();
+        assert!(cleared[0].starts_with("blockcell_session=; Path=/; Max-Age=0; HttpOnly"));
+        assert!(cleared[1].contains("Path=/blockcell/v1/auth"));
+
+        config.gateway.public_api_base = Some("https://ops.example.com/api/".to_string());
+        let auth = test_auth(&config);
+        assert!(auth.secure_cookies);
+        assert_eq!(auth.refresh_cookie_path, "/api/v1/auth");
+        assert!(auth.clear_cookies()[0].ends_with("; Secure"));
+
+        assert!(is_public_auth_path("/v1/auth/refresh"));
+        assert!(!is_public_auth_path("/v1/auth/passkeys"));
+    }
+
+    #[tokio::test]
+    async fn test_sessions_tickets_and_strict_modes() {
+        let mut config = Config::default();
+        config.gateway.webauthn.enabled = true;
+        let auth = test_auth(&config);
+
+        let cookies = auth.sign_in(false).await.unwrap();
+        let session = cookies[0]
+            .split(';')
+            .next()
+            .unwrap()
+            .split_once('=')
+            .unwrap()
+            .1
+            .to_string();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            format!("{}={}", SESSION_COOKIE, session).parse().unwrap(),
+        );
+        assert!(auth.has_session(&headers).await);
+
+        // Expired sessions no longer authorize.
+        auth.sessions
+            .lock()
+            .await
+            .insert(session.clone(), now() - 1);
+        assert!(!auth.has_session(&headers).await);
+
+        // Password-only refresh grants stop working once a passkey is required.
+        assert!(auth.password_only_allowed().await);
+        let strict = WebUiAuth {
+            mode: WebAuthnMode::PasskeyOnly,
+            ..test_auth(&config)
+        };
+        strict
+            .store
+            .lock()
+            .await
+            .add_passkey("cred", "Laptop", serde_json::json!({}))
+            .unwrap();
+        assert!(!strict.password_only_allowed().await);
+
+        let ticket = strict.issue_ticket().await;
+        assert!(strict.check_ticket(Some(&ticket), true).await);
+        assert!(!strict.check_ticket(Some(&ticket), false).await);
+        assert!(!strict.check_ticket(None, false).await);
+    }
+}
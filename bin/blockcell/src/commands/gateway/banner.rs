@@ -650,19 +650,26 @@ pub(super) fn print_startup_banner(
     // ── Server info ──
     eprintln!("  {}{}Server{}", ansi::BOLD, ansi::WHITE, ansi::RESET);
 
+    let base_path = config.gateway.normalized_base_path();
     eprintln!(
-        "  {}HTTP/WS:{}  http://{}",
+        "  {}HTTP/WS:{}  http://{}{}",
         ansi::CYAN,
         ansi::RESET,
         bind_addr,
+        base_path,
     );
-    eprintln!(
-        "  {}WebUI:{}   http://{}:{}/",
-        ansi::CYAN,
-        ansi::RESET,
-        webui_host,
-        webui_port,
-    );
+    if config.lite {
+        eprintln!("  {}WebUI:{}   off (lite mode)", ansi::CYAN, ansi::RESET);
+    } else {
+        eprintln!(
+            "  {}WebUI:{}   http://{}:{}{}/",
+            ansi::CYAN,
+            ansi::RESET,
+            webui_host,
+            webui_port,
+            base_path,
+        );
+    }
 
     let api_base = config
         .gateway
@@ -670,7 +677,7 @@ pub(super) fn print_startup_banner(
         .as_deref()
         .filter(|s| !s.trim().is_empty())
         .map(|s| s.trim_end_matches('/').to_string())
-        .unwrap_or_else(|| format!("http://{}", bind_addr));
+        .unwrap_or_else(|| format!("http://{}{}", bind_addr, base_path));
     eprintln!(
         "  {}API:{}     POST {}/v1/chat  |  GET {}/v1/health  |  GET {}/v1/ws",
         ansi::CYAN,
@@ -132,6 +132,32 @@ pub(super) async fn handle_skills(State(state): State<GatewayState>) -> impl Int
     }))
 }
 
+/// GET /v1/skills/stats — per-skill invocation counts, success rate, latency and tokens
+pub(super) async fn handle_skills_stats(
+    State(state): State<GatewayState>,
+    Query(agent): Query<AgentScopedQuery>,
+) -> impl IntoResponse {
+    let agent_id = match resolve_requested_agent_id(&state.config, agent.agent.as_deref()) {
+        Ok(agent_id) => agent_id,
+        Err(err) => return Json(serde_json::json!({ "error": err })),
+    };
+    let db = state.paths.for_agent(&agent_id).skill_stats_db();
+    let stats = if db.exists() {
+        match blockcell_storage::skill_stats::SkillStatsStore::open(&db).and_then(|s| s.all()) {
+            Ok(stats) => stats,
+            Err(e) => return Json(serde_json::json!({ "error": e.to_string() })),
+        }
+    } else {
+        Vec::new()
+    };
+    let count = stats.len();
+    Json(serde_json::json!({
+        "agent": agent_id,
+        "skills": stats,
+        "count": count,
+    }))
+}
+
 /// POST /v1/skills/search — search skills by keyword
 #[derive(Deserialize)]
 pub(super) struct SkillSearchRequest {
@@ -400,6 +426,7 @@ pub(super) async fn handle_pool_status(State(state): State<GatewayState>) -> imp
                     "weight": e.weight,
                     "priority": e.priority,
                     "toolCallMode": e.tool_call_mode,
+                    "group": e.group,
                 })
             })
             .collect()
@@ -422,6 +449,27 @@ pub(super) async fn handle_pool_status(State(state): State<GatewayState>) -> imp
     }))
 }
 
+/// GET /v1/providers/health — rolling latency/error rates per provider entry
+pub(super) async fn handle_providers_health(
+    State(state): State<GatewayState>,
+) -> impl IntoResponse {
+    let live = blockcell_providers::health::global().snapshot();
+    let (providers, updated_ms) = if live.is_empty() {
+        // Agent runtimes in another process write their snapshot to disk.
+        blockcell_providers::health::load_report(&state.paths.provider_health_file())
+            .map(|r| (r.providers, Some(r.updated_ms)))
+            .unwrap_or_default()
+    } else {
+        (live, Some(chrono::Utc::now().timestamp_millis() as u64))
+    };
+
+    Json(serde_json::json!({
+        "latencyRouting": state.config.agents.defaults.latency_routing,
+        "updatedMs": updated_ms,
+        "providers": providers,
+    }))
+}
+
 /// Persona files that can be edited via the WebUI
 const PERSONA_FILES: &[&str] = &["AGENTS.md", "SOUL.md", "USER.md"];
 
@@ -1189,6 +1237,27 @@ pub(super) async fn handle_evolution_summary(
 }
 
 /// GET /v1/stats — runtime statistics
+/// Resident/peak/virtual memory of this process in bytes, from `/proc/self/status`.
+/// Null fields on platforms without procfs.
+fn process_memory() -> serde_json::Value {
+    let status = std::fs::read_to_string("/proc/self/status").unwrap_or_default();
+    let field = |name: &str| -> Option<u64> {
+        let line = status.lines().find(|l| l.starts_with(name))?;
+        let kb: u64 = line[name.len()..]
+            .trim()
+            .trim_end_matches("kB")
+            .trim()
+            .parse()
+            .ok()?;
+        Some(kb * 1024)
+    };
+    serde_json::json!({
+        "rss_bytes": field("VmRSS:"),
+        "peak_rss_bytes": field("VmHWM:"),
+        "virtual_bytes": field("VmSize:"),
+    })
+}
+
 pub(super) async fn handle_stats(State(state): State<GatewayState>) -> impl IntoResponse {
     static START: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
     let start = START.get_or_init(std::time::Instant::now);
@@ -1219,5 +1288,8 @@ pub(super) async fn handle_stats(State(state): State<GatewayState>) -> impl Into
             "failed": failed,
         },
         "tools_count": state.tool_registry.tool_names().len(),
+        "tools_loaded": state.tool_registry.loaded_tool_count(),
+        "lite": state.config.lite,
+        "process_memory": process_memory(),
     }))
 }
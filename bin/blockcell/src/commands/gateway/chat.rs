@@ -59,42 +59,6 @@ struct TasksResponse {
     tasks: serde_json::Value,
 }
 
-// ---------------------------------------------------------------------------
-// Auth handler — login with password, returns Bearer token
-// ---------------------------------------------------------------------------
-
-#[derive(Deserialize)]
-pub(super) struct LoginRequest {
-    password: String,
-}
-
-pub(super) async fn handle_login(
-    State(state): State<GatewayState>,
-    Json(req): Json<LoginRequest>,
-) -> Response {
-    if !secure_eq(&req.password, &state.web_password) {
-        return (
-            StatusCode::UNAUTHORIZED,
-            Json(serde_json::json!({ "error": "Invalid password" })),
-        )
-            .into_response();
-    }
-    // Return the api_token as the Bearer token for subsequent API requests
-    match &state.api_token {
-        Some(token) if !token.is_empty() => {
-            Json(serde_json::json!({ "token": token })).into_response()
-        }
-        _ => {
-            // Should never happen after the defensive guarantee above
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({ "error": "Server token not configured" })),
-            )
-                .into_response()
-        }
-    }
-}
-
 // ---------------------------------------------------------------------------
 // P0 HTTP handlers — Core chat + tasks
 // ---------------------------------------------------------------------------
@@ -218,6 +182,39 @@ pub(super) async fn handle_tasks(
     .into_response()
 }
 
+/// GET /v1/tasks/:id/artifacts — files registered by tools while the task ran
+pub(super) async fn handle_task_artifacts(
+    State(state): State<GatewayState>,
+    AxumPath(task_id): AxumPath<String>,
+) -> impl IntoResponse {
+    let Some(task) = state.task_manager.get_task(&task_id).await else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": "Task not found" })),
+        )
+            .into_response();
+    };
+    let agent_id = task.agent_id.as_deref().unwrap_or("default");
+    let artifacts: Vec<serde_json::Value> = task
+        .artifacts
+        .iter()
+        .map(|a| {
+            let mut value = serde_json::to_value(a).unwrap_or_default();
+            value["serve_url"] = serde_json::json!(a.serve_url(agent_id));
+            value["download_url"] = serde_json::json!(a.download_url(agent_id));
+            value
+        })
+        .collect();
+    Json(serde_json::json!({
+        "task_id": task.id,
+        "agent_id": agent_id,
+        "status": task.status.to_string(),
+        "artifacts": artifacts,
+        "count": artifacts.len(),
+    }))
+    .into_response()
+}
+
 #[cfg(test)]
 mod tests {
     use super::assign_session_id;
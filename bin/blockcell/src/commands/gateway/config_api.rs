@@ -237,12 +237,12 @@ pub(super) async fn handle_ghost_config_update(
     }
 }
 
-/// GET /v1/ghost/activity — get ghost agent activity log from session files
+/// GET /v1/ghost/activity — get ghost agent activity log from the session store
 pub(super) async fn handle_ghost_activity(
     State(state): State<GatewayState>,
     Query(params): Query<std::collections::HashMap<String, String>>,
 ) -> impl IntoResponse {
-    let sessions_dir = state.paths.sessions_dir();
+    let session_store = blockcell_storage::SessionStore::new(state.paths.clone());
     let limit: usize = params
         .get("limit")
         .and_then(|v| v.parse().ok())
@@ -250,35 +250,18 @@ pub(super) async fn handle_ghost_activity(
 
     let mut activities: Vec<serde_json::Value> = Vec::new();
 
-    if let Ok(entries) = std::fs::read_dir(&sessions_dir) {
-        let mut ghost_files: Vec<_> = entries
-            .flatten()
-            .filter(|e| {
-                e.path()
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .map(|n| n.starts_with("ghost_") && n.ends_with(".jsonl"))
-                    .unwrap_or(false)
-            })
-            .collect();
-
-        ghost_files.sort_by(|a, b| {
-            let ta = a.metadata().and_then(|m| m.modified()).ok();
-            let tb = b.metadata().and_then(|m| m.modified()).ok();
-            tb.cmp(&ta)
-        });
-
-        for entry in ghost_files.into_iter().take(limit) {
-            let path = entry.path();
-            let session_id = path
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("")
-                .to_string();
-
-            if let Ok(content) = std::fs::read_to_string(&path) {
-                let lines: Vec<&str> = content.lines().collect();
-                let message_count = lines.len();
+    if let Ok((sessions, _)) = session_store.list(0, usize::MAX) {
+        // Already ordered by last update, newest first.
+        let ghost_sessions = sessions
+            .into_iter()
+            .filter(|s| s.stem.starts_with("ghost_"))
+            .take(limit);
+
+        for session in ghost_sessions {
+            let session_id = session.stem;
+
+            if let Ok(messages) = session_store.load(&session_id) {
+                let message_count = session.message_count;
 
                 let raw_ts = session_id
                     .strip_prefix("ghost_")
@@ -292,8 +275,8 @@ pub(super) async fn handle_ghost_activity(
                 let mut summary = String::new();
                 let mut tool_calls: Vec<String> = Vec::new();
 
-                for line in &lines {
-                    if let Ok(msg) = serde_json::from_str::<serde_json::Value>(line) {
+                for message in &messages {
+                    if let Ok(msg) = serde_json::to_value(message) {
                         let role = msg.get("role").and_then(|v| v.as_str()).unwrap_or("");
                         match role {
                             "user" if routine_prompt.is_empty() => {
@@ -0,0 +1,178 @@
+use super::*;
+use axum::http::Method;
+use blockcell_core::config::CorsConfig;
+use tower_http::cors::{AllowHeaders, AllowOrigin, CorsLayer};
+// ---------------------------------------------------------------------------
+// CORS: `gateway.cors.api` / `gateway.cors.webui`
+// ---------------------------------------------------------------------------
+//
+// Without configured origins a loopback bind stays open to any origin (local
+// development, WebUI and API on different ports). Any other bind only lets the
+// WebUI served from the same host call the API, and the WebUI server itself
+// answers no cross-origin requests.
+
+/// Whether a bind address only accepts local connections.
+pub(super) fn is_loopback_host(host: &str) -> bool {
+    let host = host.trim().trim_start_matches('[').trim_end_matches(']');
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Lowercased host of an `Origin` or `Host` value, without scheme or port.
+fn host_part(value: &str) -> Option<String> {
+    let authority = value.split_once("://").map_or(value, |(_, rest)| rest);
+    let authority = authority.split('/').next()?;
+    let host = if let Some(v6) = authority.strip_prefix('[') {
+        v6.split(']').next()?
+    } else {
+        authority.split(':').next()?
+    };
+    (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
+
+/// Origin served from the host the request was sent to (any port), e.g. the WebUI on
+/// :18791 calling the API on :18790 of the same machine or domain.
+fn is_same_host_origin(origin: &str, request_host: Option<&str>) -> bool {
+    match (host_part(origin), request_host.and_then(host_part)) {
+        (Some(origin), Some(host)) => origin == host,
+        _ => false,
+    }
+}
+
+fn build_cors_layer(
+    cors: &CorsConfig,
+    extra_origins: &[String],
+    loopback: bool,
+    default_origin: AllowOrigin,
+) -> CorsLayer {
+    let origins: Vec<&str> = cors
+        .allowed_origins
+        .iter()
+        .chain(extra_origins)
+        .map(|o| o.trim().trim_end_matches('/'))
+        .filter(|o| !o.is_empty())
+        .collect();
+    let any_origin = origins.contains(&"*") || (origins.is_empty() && loopback);
+    // Credentials cannot go with a literal `*`; echoing the request origin is equivalent.
+    let allow_origin = if any_origin && cors.allow_credentials {
+        AllowOrigin::mirror_request()
+    } else if any_origin {
+        AllowOrigin::any()
+    } else if origins.is_empty() {
+        default_origin
+    } else {
+        AllowOrigin::list(origins.iter().filter_map(|o| match o.parse() {
+            Ok(value) => Some(value),
+            Err(_) => {
+                warn!(origin = %o, "Ignoring invalid CORS origin");
+                None
+            }
+        }))
+    };
+
+    let allow_headers = if cors.allowed_headers.iter().any(|h| h.trim() == "*") {
+        if cors.allow_credentials {
+            AllowHeaders::mirror_request()
+        } else {
+            AllowHeaders::any()
+        }
+    } else {
+        let extra = cors.allowed_headers.iter().filter_map(|h| {
+            match h.trim().parse::<header::HeaderName>() {
+                Ok(name) => Some(name),
+                Err(_) => {
+                    warn!(header = %h, "Ignoring invalid CORS header");
+                    None
+                }
+            }
+        });
+        AllowHeaders::list(
+            [header::AUTHORIZATION, header::CONTENT_TYPE]
+                .into_iter()
+                .chain(extra),
+        )
+    };
+
+    CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+            Method::OPTIONS,
+        ])
+        .allow_headers(allow_headers)
+        .allow_credentials(cors.allow_credentials)
+        .max_age(std::time::Duration::from_secs(cors.max_age_secs))
+}
+
+pub(super) fn build_api_cors_layer(config: &Config, host: &str) -> CorsLayer {
+    let mut cors = config.gateway.cors.api.clone();
+    // A WebUI on its own port sends its session cookie with credentialed requests.
+    cors.allow_credentials |= config.gateway.webauthn.enabled;
+    build_cors_layer(
+        &cors,
+        &config.gateway.allowed_origins,
+        is_loopback_host(host),
+        AllowOrigin::predicate(|origin, parts| {
+            let request_host = parts
+                .headers
+                .get(header::HOST)
+                .and_then(|v| v.to_str().ok())
+                .or_else(|| parts.uri.authority().map(|a| a.as_str()));
+            origin
+                .to_str()
+                .is_ok_and(|origin| is_same_host_origin(origin, request_host))
+        }),
+    )
+}
+
+pub(super) fn build_webui_cors_layer(config: &Config) -> CorsLayer {
+    build_cors_layer(
+        &config.gateway.cors.webui,
+        &[],
+        is_loopback_host(&config.gateway.webui_host),
+        AllowOrigin::list(Vec::<header::HeaderValue>::new()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loopback_hosts() {
+        for host in ["localhost", "127.0.0.1", "::1", "[::1]", "LOCALHOST"] {
+            assert!(is_loopback_host(host), "{}", host);
+        }
+        for host in ["0.0.0.0", "::", "192.168.1.5", "gateway.example.com"] {
+            assert!(!is_loopback_host(host), "{}", host);
+        }
+    }
+
+    #[test]
+    fn test_same_host_origin_ignores_port_and_scheme() {
+        assert!(is_same_host_origin(
+            "http://192.168.1.5:18791",
+            Some("192.168.1.5:18790")
+        ));
+        assert!(is_same_host_origin(
+            "https://Ops.Example.com",
+            Some("ops.example.com")
+        ));
+        assert!(is_same_host_origin(
+            "http://[::1]:18791",
+            Some("[::1]:18790")
+        ));
+        assert!(!is_same_host_origin(
+            "https://evil.example.net",
+            Some("ops.example.com")
+        ));
+        assert!(!is_same_host_origin("null", Some("ops.example.com")));
+        assert!(!is_same_host_origin("http://ops.example.com", None));
+    }
+}
@@ -1,4 +1,5 @@
 use super::*;
+use chrono::TimeZone;
 // ---------------------------------------------------------------------------
 // P1: Cron management endpoints
 // ---------------------------------------------------------------------------
@@ -16,7 +17,24 @@ pub(super) async fn handle_cron_list(
     let jobs = cron_service.list_jobs().await;
     let jobs_json: Vec<serde_json::Value> = jobs
         .iter()
-        .map(|j| serde_json::to_value(j).unwrap_or_default())
+        .map(|j| {
+            let mut value = serde_json::to_value(j).unwrap_or_default();
+            // Next run rendered in the timezone the schedule is evaluated in.
+            let tz = cron_service.job_timezone(j);
+            let next_run_local = j
+                .state
+                .next_run_at_ms
+                .and_then(|ms| tz.timestamp_millis_opt(ms).single())
+                .map(|dt| dt.to_rfc3339());
+            if let Some(obj) = value.as_object_mut() {
+                obj.insert("timezone".to_string(), serde_json::json!(tz.name()));
+                obj.insert(
+                    "next_run_local".to_string(),
+                    serde_json::json!(next_run_local),
+                );
+            }
+            value
+        })
         .collect();
 
     let count = jobs_json.len();
@@ -36,10 +54,16 @@ pub(super) struct CronCreateRequest {
     every_seconds: Option<i64>,
     #[serde(default)]
     cron_expr: Option<String>,
+    /// IANA timezone for cron_expr (e.g. "America/New_York"); defaults to `defaultTimezone`.
+    #[serde(default)]
+    tz: Option<String>,
     #[serde(default)]
     skill_name: Option<String>,
     #[serde(default)]
     delete_after_run: bool,
+    /// Heavy job: only runs inside the maintenance calendar.
+    #[serde(default)]
+    maintenance_only: bool,
     #[serde(default)]
     deliver: bool,
     #[serde(default)]
@@ -75,6 +99,13 @@ fn resolve_cron_skill_payload_kind(paths: &Paths, skill_name: Option<&str>) -> &
 }
 
 fn build_manual_cron_inbound(job: &CronJob, agent_id: &str) -> InboundMessage {
+    if job.payload.kind == "follow_up" {
+        if let Some(mut msg) = blockcell_scheduler::cron_service::follow_up_inbound(job) {
+            msg.metadata["manual_trigger"] = serde_json::json!(true);
+            blockcell_scheduler::apply_scheduled_role(&mut msg.metadata, job);
+            return with_route_agent_id(msg, agent_id);
+        }
+    }
     let (content, mut metadata) = match job.payload.kind.as_str() {
         "reminder" => (
             job.payload.message.clone(),
@@ -132,6 +163,7 @@ fn build_manual_cron_inbound(job: &CronJob, agent_id: &str) -> InboundMessage {
         obj.entry("route_agent_id".to_string())
             .or_insert_with(|| serde_json::json!(agent_id));
     }
+    blockcell_scheduler::apply_scheduled_role(&mut metadata, job);
 
     with_route_agent_id(
         InboundMessage {
@@ -156,6 +188,21 @@ pub(super) async fn handle_cron_create(
 ) -> impl IntoResponse {
     let now_ms = chrono::Utc::now().timestamp_millis();
 
+    let tz = req
+        .tz
+        .map(|tz| tz.trim().to_string())
+        .filter(|tz| !tz.is_empty());
+    if let Some(tz) = &tz {
+        if tz.parse::<chrono_tz::Tz>().is_err() {
+            return Json(serde_json::json!({
+                "error": format!(
+                    "Invalid timezone '{}'. Use an IANA timezone like 'Asia/Shanghai' or 'America/New_York'",
+                    tz
+                )
+            }));
+        }
+    }
+
     let schedule = if let Some(at_ms) = req.at_ms {
         JobSchedule {
             kind: ScheduleKind::At,
@@ -180,7 +227,7 @@ pub(super) async fn handle_cron_create(
             at_ms: None,
             every_ms: None,
             expr: Some(expr),
-            tz: None,
+            tz,
             run_immediately: false, // Not applicable for Cron jobs
         }
     } else {
@@ -219,11 +266,15 @@ pub(super) async fn handle_cron_create(
             to: req.deliver_to,
             script_kind: script_kind.map(|value| value.to_string()),
             skill_name: req.skill_name,
+            context: None,
+            account_id: None,
+            role: None,
         },
         state: JobState::default(),
         created_at_ms: now_ms,
         updated_at_ms: now_ms,
         delete_after_run: req.delete_after_run,
+        maintenance_only: req.maintenance_only,
     };
 
     let job_id = job.id.clone();
@@ -269,14 +320,44 @@ pub(super) async fn handle_cron_run(
 
     match job {
         Some(job) => {
-            let inbound = build_manual_cron_inbound(job, &agent_id);
+            let mut inbound = build_manual_cron_inbound(job, &agent_id);
+            let run_id = cron_service.start_manual_run(job);
+            if let Some(run_id) = run_id.as_deref() {
+                blockcell_scheduler::tag_cron_run(&mut inbound, run_id);
+            }
             let _ = state.inbound_tx.send(inbound).await;
-            Json(serde_json::json!({ "status": "triggered", "job_id": job.id }))
+            Json(serde_json::json!({ "status": "triggered", "job_id": job.id, "run_id": run_id }))
         }
         None => Json(serde_json::json!({ "status": "not_found", "job_id": job_id })),
     }
 }
 
+#[derive(Deserialize)]
+pub(super) struct CronHistoryQuery {
+    agent: Option<String>,
+    limit: Option<usize>,
+}
+
+/// GET /v1/cron/:id/history — past runs of a cron job, newest first
+pub(super) async fn handle_cron_history(
+    State(state): State<GatewayState>,
+    AxumPath(job_id): AxumPath<String>,
+    Query(query): Query<CronHistoryQuery>,
+) -> impl IntoResponse {
+    let (_, cron_service) = match cron_service_for_agent(&state, query.agent.as_deref()) {
+        Ok(value) => value,
+        Err(err) => return Json(serde_json::json!({ "error": err })),
+    };
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+    match cron_service.run_history(&job_id, limit) {
+        Ok(runs) => {
+            let count = runs.len();
+            Json(serde_json::json!({ "job_id": job_id, "runs": runs, "count": count }))
+        }
+        Err(e) => Json(serde_json::json!({ "error": format!("{}", e) })),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -303,11 +384,15 @@ mod tests {
                 to: Some("manual:test".to_string()),
                 script_kind: Some("markdown".to_string()),
                 skill_name: Some("weather".to_string()),
+                context: None,
+                account_id: None,
+                role: None,
             },
             state: JobState::default(),
             created_at_ms: now_ms,
             updated_at_ms: now_ms,
             delete_after_run: false,
+            maintenance_only: false,
         }
     }
 
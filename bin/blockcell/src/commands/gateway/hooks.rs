@@ -0,0 +1,257 @@
+use super::*;
+use blockcell_core::config::IngestSourceConfig;
+use blockcell_core::hooks::{HookDef, HookStore, HookTarget};
+use blockcell_core::ingest;
+// ---------------------------------------------------------------------------
+// Named webhook triggers: POST /v1/hooks/:name
+// ---------------------------------------------------------------------------
+
+/// Channel of messages triggered by webhooks. Not internal, so permissions treat the
+/// sender as an outside user rather than as the WebUI admin.
+pub(super) const HOOK_CHANNEL: &str = "webhook";
+
+fn hook_metadata(hook: &HookDef, event: Option<&str>, skill: Option<&str>) -> serde_json::Value {
+    let mut metadata = serde_json::json!({ "hook": hook.name });
+    if let Some(event) = event {
+        metadata["hook_event"] = serde_json::json!(event);
+    }
+    if let Some(skill) = skill.map(str::trim).filter(|s| !s.is_empty()) {
+        metadata["forced_skill_name"] = serde_json::json!(skill);
+    }
+    metadata
+}
+
+/// POST /v1/hooks/:name — run a hook saved with `blockcell hooks add`. Requires the API
+/// token like every `/v1` route; hooks with a `secret` also need a valid HMAC signature.
+/// Agent hooks answer 202 once the message is queued, tool hooks 200 with the tool result.
+pub(super) async fn handle_hook(
+    State(state): State<GatewayState>,
+    AxumPath(name): AxumPath<String>,
+    headers: axum::http::HeaderMap,
+    body: axum::body::Bytes,
+) -> Response {
+    let error = |status: StatusCode, message: String| {
+        (status, Json(serde_json::json!({ "error": message }))).into_response()
+    };
+
+    let hook = match HookStore::open(&state.paths) {
+        Ok(store) => store.get(name.trim()).cloned(),
+        Err(e) => {
+            return error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to load hooks: {}", e),
+            )
+        }
+    };
+    let Some(hook) = hook.filter(|h| h.enabled) else {
+        return error(
+            StatusCode::NOT_FOUND,
+            format!("Hook '{}' not found or disabled", name),
+        );
+    };
+
+    if let Some(secret) = hook.secret.as_deref().filter(|s| !s.trim().is_empty()) {
+        let secret = match blockcell_core::secrets::resolve_secret_ref(&state.paths, secret) {
+            Ok(secret) => secret,
+            Err(e) => {
+                warn!(hook = %hook.name, error = %e, "Hook secret could not be resolved");
+                return error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Hook secret is not available".to_string(),
+                );
+            }
+        };
+        let header_name = hook.signature_header(|h| headers.contains_key(h));
+        let signature = headers.get(&header_name).and_then(|v| v.to_str().ok());
+        if let Err(e) =
+            ingest::verify_signature(&secret, signature, &body, chrono::Utc::now().timestamp())
+        {
+            warn!(hook = %hook.name, error = %e, "Rejected hook call with bad signature");
+            return error(StatusCode::UNAUTHORIZED, e.to_string());
+        }
+    }
+
+    let payload = webhook_payload(&body);
+    let header_map = webhook_headers(&headers);
+    let event = ingest::event_name(&header_map, &payload);
+    if !ingest::event_allowed(&hook.events, event.as_deref()) {
+        debug!(hook = %hook.name, event = ?event, "Hook event filtered out");
+        return (
+            StatusCode::ACCEPTED,
+            Json(serde_json::json!({ "status": "ignored", "event": event })),
+        )
+            .into_response();
+    }
+
+    let agent_id = match resolve_requested_agent_id(&state.config, hook.agent.as_deref()) {
+        Ok(agent_id) => agent_id,
+        Err(err) => return error(StatusCode::BAD_REQUEST, err),
+    };
+    let max_payload_chars = IngestSourceConfig::default().max_payload_chars;
+    let chat_id = hook
+        .chat_id
+        .clone()
+        .filter(|c| !c.trim().is_empty())
+        .unwrap_or_else(|| format!("hook_{}", hook.name));
+
+    match &hook.target {
+        HookTarget::Agent { skill } => {
+            let template = hook.template.as_deref().unwrap_or(ingest::DEFAULT_TEMPLATE);
+            let content = ingest::render(
+                template,
+                &hook.name,
+                &payload,
+                &header_map,
+                max_payload_chars,
+            );
+            if content.is_empty() {
+                return error(
+                    StatusCode::BAD_REQUEST,
+                    "Template rendered an empty message".to_string(),
+                );
+            }
+            let channel = hook
+                .channel
+                .clone()
+                .filter(|c| !c.trim().is_empty())
+                .unwrap_or_else(|| HOOK_CHANNEL.to_string());
+            let inbound = InboundMessage {
+                channel,
+                account_id: None,
+                sender_id: format!("hook:{}", hook.name),
+                chat_id: chat_id.clone(),
+                content,
+                media: vec![],
+                metadata: hook_metadata(&hook, event.as_deref(), skill.as_deref()),
+                timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            };
+            let inbound = with_route_agent_id(inbound, &agent_id);
+            match state.inbound_tx.send(inbound).await {
+                Ok(_) => {
+                    info!(hook = %hook.name, chat_id = %chat_id, agent_id = %agent_id, "Hook triggered agent");
+                    (
+                        StatusCode::ACCEPTED,
+                        Json(serde_json::json!({
+                            "status": "accepted",
+                            "hook": hook.name,
+                            "session_id": chat_id,
+                        })),
+                    )
+                        .into_response()
+                }
+                Err(e) => error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Failed to queue message: {}", e),
+                ),
+            }
+        }
+        HookTarget::Tool { name: tool, .. } => {
+            let params = hook.render_params(&payload, &header_map, max_payload_chars);
+            let params = if params.is_null() {
+                serde_json::json!({})
+            } else {
+                params
+            };
+            let agent_paths = state.paths.for_agent(&agent_id);
+            let memory_store = memory_store_for_agent(&state, Some(&agent_id))
+                .ok()
+                .map(|(_, store)| store);
+            let ctx = blockcell_tools::ToolContext {
+                workspace: agent_paths.workspace(),
+                builtin_skills_dir: Some(state.paths.builtin_skills_dir()),
+                active_skill_dir: None,
+                session_key: blockcell_core::build_session_key(HOOK_CHANNEL, &chat_id),
+                channel: HOOK_CHANNEL.to_string(),
+                account_id: None,
+                sender_id: Some(format!("hook:{}", hook.name)),
+                chat_id,
+                config: state.config.clone(),
+                permissions: blockcell_core::types::PermissionSet::new(),
+                task_manager: Some(Arc::new(state.task_manager.clone())),
+                memory_store,
+                outbound_tx: None,
+                spawn_handle: None,
+                capability_registry: None,
+                core_evolution: None,
+                event_emitter: None,
+                channel_contacts_file: Some(agent_paths.channel_contacts_file()),
+                response_cache: None,
+                artifacts: None,
+            };
+            match state.tool_registry.execute(tool, ctx, params).await {
+                Ok(result) => {
+                    info!(hook = %hook.name, tool = %tool, "Hook ran tool");
+                    Json(serde_json::json!({
+                        "status": "ok",
+                        "hook": hook.name,
+                        "tool": tool,
+                        "result": result,
+                    }))
+                    .into_response()
+                }
+                Err(e) => {
+                    warn!(hook = %hook.name, tool = %tool, error = %e, "Hook tool call failed");
+                    let status = match e {
+                        blockcell_core::Error::Validation(_) => StatusCode::BAD_REQUEST,
+                        _ => StatusCode::BAD_GATEWAY,
+                    };
+                    error(status, e.to_string())
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_channel_is_not_internal() {
+        use blockcell_core::permissions::{Role, ToolPermissions};
+        let mut config = Config::default();
+        config.permissions.enabled = true;
+        let permissions = ToolPermissions::new(&config.permissions);
+        assert_ne!(
+            permissions.role_for(HOOK_CHANNEL, "hook:deploys"),
+            Role::Admin
+        );
+    }
+
+    #[test]
+    fn test_hook_metadata_forces_skill() {
+        let hook = HookDef {
+            name: "deploys".to_string(),
+            enabled: true,
+            description: None,
+            target: HookTarget::Agent { skill: None },
+            template: None,
+            secret: None,
+            signature_header: None,
+            events: vec![],
+            agent: None,
+            channel: None,
+            chat_id: None,
+            created_at: 0,
+        };
+        let metadata = hook_metadata(&hook, Some("push"), Some("release_notes"));
+        assert_eq!(metadata["hook"], "deploys");
+        assert_eq!(metadata["hook_event"], "push");
+        assert_eq!(metadata["forced_skill_name"], "release_notes");
+        assert!(hook_metadata(&hook, None, Some(" "))
+            .get("forced_skill_name")
+            .is_none());
+    }
+
+    #[test]
+    fn test_webhook_payload_wraps_non_json() {
+        assert_eq!(
+            webhook_payload(br#"{"ref":"main"}"#),
+            serde_json::json!({"ref": "main"})
+        );
+        assert_eq!(
+            webhook_payload(b"state=ok"),
+            serde_json::json!({"body": "state=ok"})
+        );
+    }
+}
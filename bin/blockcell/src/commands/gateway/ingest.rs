@@ -0,0 +1,213 @@
+use super::*;
+use blockcell_core::config::IngestSourceConfig;
+use blockcell_core::ingest;
+// ---------------------------------------------------------------------------
+// Generic webhook ingestion: POST /v1/ingest/:source
+// ---------------------------------------------------------------------------
+
+fn valid_source_name(source: &str) -> bool {
+    !source.is_empty()
+        && source.len() <= 64
+        && source
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Non-JSON bodies (form posts, plain text) are wrapped so templates still work.
+pub(super) fn webhook_payload(body: &[u8]) -> serde_json::Value {
+    match serde_json::from_slice(body) {
+        Ok(value) => value,
+        Err(_) => serde_json::json!({ "body": String::from_utf8_lossy(body) }),
+    }
+}
+
+/// Request headers available to templates, minus credentials.
+pub(super) fn webhook_headers(
+    headers: &axum::http::HeaderMap,
+) -> std::collections::BTreeMap<String, String> {
+    headers
+        .iter()
+        .filter(|(name, _)| {
+            let name = name.as_str();
+            name != "authorization" && name != "cookie"
+        })
+        .filter_map(|(name, value)| {
+            value
+                .to_str()
+                .ok()
+                .map(|v| (name.as_str().to_string(), v.to_string()))
+        })
+        .collect()
+}
+
+fn ingest_metadata(source: &str, event: Option<&str>, skill: Option<&str>) -> serde_json::Value {
+    let mut metadata = serde_json::json!({ "ingest_source": source });
+    if let Some(event) = event {
+        metadata["ingest_event"] = serde_json::json!(event);
+    }
+    if let Some(skill) = skill.map(str::trim).filter(|s| !s.is_empty()) {
+        metadata["forced_skill_name"] = serde_json::json!(skill);
+    }
+    metadata
+}
+
+/// POST /v1/ingest/:source — turn an arbitrary JSON webhook (GitHub, Grafana, Stripe, ...)
+/// into an inbound message for the agent. Requires the API token (Bearer or `?token=`);
+/// sources with a `secret` in `gateway.ingest` must also carry a valid HMAC signature.
+pub(super) async fn handle_ingest(
+    State(state): State<GatewayState>,
+    AxumPath(source): AxumPath<String>,
+    headers: axum::http::HeaderMap,
+    body: axum::body::Bytes,
+) -> Response {
+    let error = |status: StatusCode, message: String| {
+        (status, Json(serde_json::json!({ "error": message }))).into_response()
+    };
+
+    let source = source.trim().to_ascii_lowercase();
+    if !valid_source_name(&source) {
+        return error(StatusCode::BAD_REQUEST, "Invalid source name".to_string());
+    }
+    let cfg = state
+        .config
+        .gateway
+        .ingest
+        .get(&source)
+        .cloned()
+        .unwrap_or_else(IngestSourceConfig::default);
+    if !cfg.enabled {
+        return error(
+            StatusCode::NOT_FOUND,
+            format!("Ingest source '{}' is disabled", source),
+        );
+    }
+
+    if let Some(secret) = cfg.secret.as_deref().filter(|s| !s.is_empty()) {
+        let header_name = ingest::signature_header(&source, &cfg);
+        let signature = headers.get(&header_name).and_then(|v| v.to_str().ok());
+        if let Err(e) =
+            ingest::verify_signature(secret, signature, &body, chrono::Utc::now().timestamp())
+        {
+            warn!(source = %source, error = %e, "Rejected webhook with bad signature");
+            return error(StatusCode::UNAUTHORIZED, e.to_string());
+        }
+    }
+
+    // Dead-man's-switch check-in. Sources that are only monitored (no `gateway.ingest`
+    // entry) are acknowledged without waking the agent.
+    let dead_mans_switch = &state.config.gateway.dead_mans_switch;
+    if dead_mans_switch.enabled && dead_mans_switch.checks.contains_key(&source) {
+        if let Err(e) = blockcell_scheduler::dead_mans_switch::check_in(&state.paths, &source) {
+            warn!(source = %source, error = %e, "Failed to record check-in");
+        }
+        if !state.config.gateway.ingest.contains_key(&source) {
+            return (
+                StatusCode::ACCEPTED,
+                Json(serde_json::json!({ "status": "checked_in", "source": source })),
+            )
+                .into_response();
+        }
+    }
+
+    let payload = webhook_payload(&body);
+    let header_map = webhook_headers(&headers);
+
+    let event = ingest::event_name(&header_map, &payload);
+    if !ingest::event_allowed(&cfg.events, event.as_deref()) {
+        debug!(source = %source, event = ?event, "Webhook event filtered out");
+        return (
+            StatusCode::ACCEPTED,
+            Json(serde_json::json!({ "status": "ignored", "event": event })),
+        )
+            .into_response();
+    }
+
+    let template = cfg.template.as_deref().unwrap_or(ingest::DEFAULT_TEMPLATE);
+    let content = ingest::render(
+        template,
+        &source,
+        &payload,
+        &header_map,
+        cfg.max_payload_chars,
+    );
+    if content.is_empty() {
+        return error(
+            StatusCode::BAD_REQUEST,
+            "Template rendered an empty message".to_string(),
+        );
+    }
+
+    let agent_id = match resolve_requested_agent_id(&state.config, cfg.agent.as_deref()) {
+        Ok(agent_id) => agent_id,
+        Err(err) => return error(StatusCode::BAD_REQUEST, err),
+    };
+    let channel = cfg
+        .channel
+        .clone()
+        .filter(|c| !c.trim().is_empty())
+        .unwrap_or_else(|| HOOK_CHANNEL.to_string());
+    let chat_id = cfg
+        .chat_id
+        .clone()
+        .filter(|c| !c.trim().is_empty())
+        .unwrap_or_else(|| format!("webhook_{}", source));
+
+    let inbound = InboundMessage {
+        channel,
+        account_id: None,
+        sender_id: format!("webhook:{}", source),
+        chat_id: chat_id.clone(),
+        content,
+        media: vec![],
+        metadata: ingest_metadata(&source, event.as_deref(), cfg.skill.as_deref()),
+        timestamp_ms: chrono::Utc::now().timestamp_millis(),
+    };
+    let inbound = with_route_agent_id(inbound, &agent_id);
+
+    match state.inbound_tx.send(inbound).await {
+        Ok(_) => {
+            info!(source = %source, chat_id = %chat_id, agent_id = %agent_id, "Webhook event ingested");
+            (
+                StatusCode::ACCEPTED,
+                Json(serde_json::json!({
+                    "status": "accepted",
+                    "source": source,
+                    "session_id": chat_id,
+                })),
+            )
+                .into_response()
+        }
+        Err(e) => error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to queue message: {}", e),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_source_name() {
+        assert!(valid_source_name("github"));
+        assert!(valid_source_name("grafana-prod_1"));
+        assert!(!valid_source_name(""));
+        assert!(!valid_source_name("../etc"));
+        assert!(!valid_source_name(&"x".repeat(65)));
+    }
+
+    #[test]
+    fn test_ingest_metadata_forces_skill() {
+        let metadata = ingest_metadata(
+            "github",
+            Some("pull_request.opened"),
+            Some("github_pr_review"),
+        );
+        assert_eq!(metadata["forced_skill_name"], "github_pr_review");
+        assert_eq!(metadata["ingest_event"], "pull_request.opened");
+        assert!(ingest_metadata("grafana", None, Some(" "))
+            .get("forced_skill_name")
+            .is_none());
+    }
+}
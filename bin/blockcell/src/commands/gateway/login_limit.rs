@@ -0,0 +1,222 @@
+use super::*;
+use blockcell_core::config::LoginLimitConfig;
+use blockcell_core::login_guard::{Lockout, LoginGuard};
+use blockcell_storage::admin_audit::AdminAction;
+// ---------------------------------------------------------------------------
+// Password login rate limiting: per-address and global lockouts
+// ---------------------------------------------------------------------------
+//
+// State lives in `login_lockouts.json` so lockouts survive restarts and
+// `blockcell auth unlock` can lift them while the gateway runs.
+
+pub(super) struct LoginLimiter {
+    limits: LoginLimitConfig,
+    guard: Mutex<LoginGuard>,
+}
+
+/// What a failed attempt led to.
+pub(super) struct LoginFailure {
+    /// Failures from the address within the window, this one included.
+    pub(super) failures: usize,
+    pub(super) lockout: Option<Lockout>,
+}
+
+impl LoginLimiter {
+    pub(super) fn new(config: &Config, paths: &Paths) -> anyhow::Result<Self> {
+        Ok(Self {
+            limits: config.gateway.login_limit.clone(),
+            guard: Mutex::new(LoginGuard::open(paths)?),
+        })
+    }
+
+    /// Lock the guard after picking up any change `blockcell auth unlock` made to the
+    /// file, so a write never puts back a lockout the CLI lifted.
+    async fn guard(&self) -> tokio::sync::MutexGuard<'_, LoginGuard> {
+        let mut guard = self.guard.lock().await;
+        if let Err(e) = guard.reload_if_changed() {
+            warn!(error = %e, "Failed to reload login lockouts");
+        }
+        guard
+    }
+
+    /// The lockout that refuses a login from `addr` right now, if any.
+    pub(super) async fn check(&self, addr: &str) -> Option<Lockout> {
+        if !self.limits.enabled {
+            return None;
+        }
+        self.guard()
+            .await
+            .check(addr, chrono::Utc::now().timestamp())
+    }
+
+    pub(super) async fn failure(&self, addr: &str) -> LoginFailure {
+        if !self.limits.enabled {
+            return LoginFailure {
+                failures: 0,
+                lockout: None,
+            };
+        }
+        let now = chrono::Utc::now().timestamp();
+        let window = self.limits.window_secs as i64;
+        let mut guard = self.guard().await;
+        let failures = guard.addresses().get(addr).map_or(0, |record| {
+            record.failures.iter().filter(|t| now - *t < window).count()
+        }) + 1;
+        let lockout = guard.record_failure(&self.limits, addr, now);
+        if let Err(e) = guard.save() {
+            warn!(error = %e, "Failed to save login lockouts");
+        }
+        LoginFailure { failures, lockout }
+    }
+
+    pub(super) async fn success(&self, addr: &str) {
+        if !self.limits.enabled {
+            return;
+        }
+        let mut guard = self.guard().await;
+        if guard.record_success(addr) {
+            if let Err(e) = guard.save() {
+                warn!(error = %e, "Failed to save login lockouts");
+            }
+        }
+    }
+}
+
+pub(super) fn lockout_response(lockout: &Lockout) -> Response {
+    let mut response = (
+        StatusCode::TOO_MANY_REQUESTS,
+        Json(serde_json::json!({
+            "error": format!(
+                "Too many failed logins; try again in {} seconds",
+                lockout.retry_after_secs
+            ),
+            "scope": lockout.scope,
+            "retry_after_secs": lockout.retry_after_secs,
+        })),
+    )
+        .into_response();
+    if let Ok(value) = lockout.retry_after_secs.to_string().parse() {
+        response.headers_mut().insert(header::RETRY_AFTER, value);
+    }
+    response
+}
+
+/// Audit a repeated failure or a lockout. A single mistyped password is not recorded.
+pub(super) fn audit_login_failure(state: &GatewayState, client_ip: &str, failure: &LoginFailure) {
+    let (action, status, details) = match &failure.lockout {
+        Some(lockout) => (
+            "auth.lockout",
+            StatusCode::TOO_MANY_REQUESTS,
+            serde_json::json!({
+                "scope": lockout.scope,
+                "lockout_secs": lockout.retry_after_secs,
+                "failures": failure.failures,
+            }),
+        ),
+        None if failure.failures >= 2 => (
+            "auth.login_failed",
+            StatusCode::UNAUTHORIZED,
+            serde_json::json!({ "failures": failure.failures }),
+        ),
+        None => return,
+    };
+    if let Some(lockout) = &failure.lockout {
+        warn!(
+            client_ip,
+            scope = ?lockout.scope,
+            secs = lockout.retry_after_secs,
+            "WebUI login locked out after repeated failures"
+        );
+    }
+    let entry = AdminAction {
+        actor: "anonymous".to_string(),
+        client_ip: Some(client_ip.to_string()),
+        action: action.to_string(),
+        method: "POST".to_string(),
+        path: "/v1/auth/login".to_string(),
+        status: status.as_u16(),
+        details,
+    };
+    if let Err(e) = state.admin_audit.append(entry) {
+        warn!(error = %e, "Failed to append admin audit entry");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blockcell_core::login_guard::LockoutScope;
+
+    #[tokio::test]
+    async fn test_limiter_locks_out_and_disables() {
+        let base =
+            std::env::temp_dir().join(format!("blockcell-login-limit-{}", uuid::Uuid::new_v4()));
+        let paths = Paths::with_base(base.clone());
+        let mut config = Config::default();
+        config.gateway.login_limit.max_failures = 2;
+        let limiter = LoginLimiter::new(&config, &paths).unwrap();
+
+        assert!(limiter.check("1.2.3.4").await.is_none());
+        let first = limiter.failure("1.2.3.4").await;
+        assert_eq!(first.failures, 1);
+        assert!(first.lockout.is_none());
+        let second = limiter.failure("1.2.3.4").await;
+        assert_eq!(second.failures, 2);
+        assert_eq!(second.lockout.unwrap().scope, LockoutScope::Address);
+        assert!(limiter.check("1.2.3.4").await.is_some());
+        assert!(limiter.check("5.6.7.8").await.is_none());
+
+        // Unlocking through the file (as the CLI does) takes effect immediately.
+        let mut cli = LoginGuard::open(&paths).unwrap();
+        assert_eq!(cli.unlock_all(), 1);
+        cli.save().unwrap();
+        assert!(limiter.check("1.2.3.4").await.is_none());
+
+        config.gateway.login_limit.enabled = false;
+        let limiter = LoginLimiter::new(&config, &paths).unwrap();
+        for _ in 0..5 {
+            assert!(limiter.failure("1.2.3.4").await.lockout.is_none());
+        }
+        assert!(limiter.check("1.2.3.4").await.is_none());
+        let _ = std::fs::remove_dir_all(base);
+    }
+
+    #[tokio::test]
+    async fn test_failure_after_cli_unlock_does_not_restore_lockout() {
+        let base =
+            std::env::temp_dir().join(format!("blockcell-login-limit-{}", uuid::Uuid::new_v4()));
+        let paths = Paths::with_base(base.clone());
+        let mut config = Config::default();
+        config.gateway.login_limit.max_failures = 2;
+        let limiter = LoginLimiter::new(&config, &paths).unwrap();
+
+        limiter.failure("1.2.3.4").await;
+        assert!(limiter.failure("1.2.3.4").await.lockout.is_some());
+
+        let mut cli = LoginGuard::open(&paths).unwrap();
+        assert_eq!(cli.unlock_all(), 1);
+        cli.save().unwrap();
+
+        // No check in between: the failure itself must see the unlock.
+        let next = limiter.failure("1.2.3.4").await;
+        assert_eq!(next.failures, 1);
+        assert!(next.lockout.is_none());
+        assert!(limiter.check("1.2.3.4").await.is_none());
+        let on_disk = LoginGuard::open(&paths).unwrap();
+        assert!(on_disk
+            .check("1.2.3.4", chrono::Utc::now().timestamp())
+            .is_none());
+        let _ = std::fs::remove_dir_all(base);
+    }
+
+    #[test]
+    fn test_lockout_response_sets_retry_after() {
+        let response = lockout_response(&Lockout {
+            scope: LockoutScope::Global,
+            until: 0,
+            retry_after_secs: 42,
+        });
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "42");
+    }
+}
@@ -40,6 +40,7 @@ async fn execute_memory_create_via_tool(
         event_emitter: None,
         channel_contacts_file: Some(agent_paths.channel_contacts_file()),
         response_cache: None,
+        artifacts: None,
     };
 
     state.tool_registry.execute("memory_upsert", ctx, req).await
@@ -260,6 +261,8 @@ mod tests {
             paths.skills_dir(),
             EvolutionServiceConfig::default(),
         )));
+        let web_auth = Arc::new(WebUiAuth::new(&config, &paths).expect("web auth"));
+        let login_limiter = Arc::new(LoginLimiter::new(&config, &paths).expect("login limiter"));
 
         GatewayState {
             inbound_tx,
@@ -278,6 +281,11 @@ mod tests {
             channel_manager,
             evolution_service,
             response_caches: Arc::new(RwLock::new(HashMap::new())),
+            admin_audit: Arc::new(blockcell_storage::admin_audit::AdminAuditLog::new(
+                PathBuf::from("/tmp/blockcell-gateway-memory-tests/audit/admin.jsonl"),
+            )),
+            web_auth,
+            login_limiter,
         }
     }
 
@@ -3,13 +3,19 @@ use super::*;
 // Outbound → WebSocket broadcast bridge
 // ---------------------------------------------------------------------------
 
-/// Forwards outbound messages from the runtime to all connected WebSocket clients
+/// Forwards outbound messages from the runtime to all connected WebSocket clients.
+/// While offline, deliveries to external channels are held (up to `max_queued_offline`,
+/// oldest dropped first) and sent in order once the network is back.
 pub(super) async fn outbound_to_ws_bridge(
     mut outbound_rx: mpsc::Receiver<blockcell_core::OutboundMessage>,
     ws_broadcast: broadcast::Sender<String>,
     channel_manager: Arc<ChannelManager>,
+    max_queued_offline: usize,
     mut shutdown_rx: broadcast::Receiver<()>,
 ) {
+    let mut offline_rx = blockcell_core::connectivity::subscribe();
+    let mut queued: std::collections::VecDeque<blockcell_core::OutboundMessage> =
+        std::collections::VecDeque::new();
     loop {
         tokio::select! {
             msg = outbound_rx.recv() => {
@@ -33,12 +39,37 @@ pub(super) async fn outbound_to_ws_bridge(
 
                 // Also dispatch to external channels (telegram, slack, etc.)
                 if msg.channel != "ws" && msg.channel != "cli" && msg.channel != "http" {
-                    if let Err(e) = channel_manager.dispatch_outbound_msg(&msg).await {
+                    if max_queued_offline > 0 && blockcell_core::connectivity::is_offline() {
+                        if queued.len() >= max_queued_offline {
+                            queued.pop_front();
+                            warn!("Offline delivery queue full; dropped the oldest reply");
+                        }
+                        debug!(channel = %msg.channel, queued = queued.len() + 1, "Offline; queued outbound message");
+                        queued.push_back(msg);
+                    } else if let Err(e) = channel_manager.dispatch_outbound_msg(&msg).await {
                         error!(error = %e, channel = %msg.channel, "Failed to dispatch outbound message");
                     }
                 }
             }
+            changed = offline_rx.changed() => {
+                if changed.is_err() {
+                    continue;
+                }
+                let offline = *offline_rx.borrow_and_update();
+                if offline || queued.is_empty() {
+                    continue;
+                }
+                info!(count = queued.len(), "Back online; sending queued outbound messages");
+                while let Some(msg) = queued.pop_front() {
+                    if let Err(e) = channel_manager.dispatch_outbound_msg(&msg).await {
+                        error!(error = %e, channel = %msg.channel, "Failed to dispatch queued outbound message");
+                    }
+                }
+            }
             _ = shutdown_rx.recv() => {
+                if !queued.is_empty() {
+                    warn!(count = queued.len(), "Shutting down with undelivered offline replies");
+                }
                 debug!("outbound_to_ws_bridge received shutdown signal");
                 break;
             }
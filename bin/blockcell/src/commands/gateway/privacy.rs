@@ -0,0 +1,81 @@
+use super::*;
+use blockcell_storage::privacy::{purge_sender, save_report, signing_key, AuditMode, PurgeRequest};
+// ---------------------------------------------------------------------------
+// Privacy: per-sender data purge
+// ---------------------------------------------------------------------------
+
+#[derive(Deserialize)]
+pub(super) struct PrivacyPurgeRequest {
+    sender: String,
+    #[serde(default)]
+    channel: Option<String>,
+    #[serde(default)]
+    agent: Option<String>,
+    #[serde(default)]
+    audit_mode: Option<String>,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+fn purge_error(status: StatusCode, err: impl std::fmt::Display) -> Response {
+    (
+        status,
+        Json(serde_json::json!({ "error": err.to_string() })),
+    )
+        .into_response()
+}
+
+/// POST /v1/privacy/purge — delete or anonymize a sender's sessions, memory items,
+/// audit entries and artifacts; returns the signed purge report
+pub(super) async fn handle_privacy_purge(
+    State(state): State<GatewayState>,
+    Json(req): Json<PrivacyPurgeRequest>,
+) -> Response {
+    let agent_id = match resolve_requested_agent_id(&state.config, req.agent.as_deref()) {
+        Ok(agent_id) => agent_id,
+        Err(err) => return purge_error(StatusCode::BAD_REQUEST, err),
+    };
+    let mut purge = match PurgeRequest::new(&req.sender, &state.config.security.privacy) {
+        Ok(purge) => purge,
+        Err(e) => return purge_error(StatusCode::BAD_REQUEST, e),
+    };
+    purge.channel = req.channel.filter(|c| !c.trim().is_empty());
+    purge.dry_run = req.dry_run;
+    if let Some(mode) = req.audit_mode.as_deref() {
+        match AuditMode::parse(mode) {
+            Ok(mode) => purge.audit_mode = mode,
+            Err(e) => return purge_error(StatusCode::BAD_REQUEST, e),
+        }
+    }
+
+    let agent_paths = state.paths.for_agent(&agent_id);
+    let store = state
+        .memory_stores
+        .get(&agent_id)
+        .and_then(|handle| handle.store());
+    let result = purge_sender(&agent_paths, store.as_ref(), &purge).and_then(|mut report| {
+        report.sign(&signing_key(&state.paths)?)?;
+        let path = if report.dry_run {
+            None
+        } else {
+            Some(save_report(&agent_paths, &report)?)
+        };
+        Ok((report, path))
+    });
+    match result {
+        Ok((report, path)) => {
+            info!(
+                agent = %agent_id,
+                sender = %report.sender,
+                dry_run = report.dry_run,
+                "Privacy purge requested via API"
+            );
+            Json(serde_json::json!({
+                "report": report,
+                "report_path": path.map(|p| p.display().to_string()),
+            }))
+            .into_response()
+        }
+        Err(e) => purge_error(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
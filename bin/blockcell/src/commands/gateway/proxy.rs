@@ -0,0 +1,195 @@
+use super::*;
+use axum::extract::ConnectInfo;
+use std::net::{IpAddr, SocketAddr};
+use tracing::Instrument;
+// ---------------------------------------------------------------------------
+// Reverse proxies: `gateway.basePath` and `gateway.trustedProxies`
+// ---------------------------------------------------------------------------
+//
+// Forwarded-for headers are only believed when the TCP peer is a trusted proxy;
+// otherwise any client could claim any address. Everything that needs the client
+// address (logs, audit entries, rate limits) reads the `ClientIp` extension.
+
+/// Real client address of a request, set by `client_ip_middleware`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct ClientIp(pub IpAddr);
+
+/// Parsed `gateway.trustedProxies`.
+#[derive(Debug, Clone, Default)]
+pub(super) struct TrustedProxies {
+    nets: Vec<(IpAddr, u8)>,
+}
+
+impl TrustedProxies {
+    pub(super) fn new(entries: &[String]) -> Self {
+        let nets = entries
+            .iter()
+            .filter_map(|entry| {
+                let parsed = parse_net(entry.trim());
+                if parsed.is_none() {
+                    warn!(entry = %entry, "Ignoring invalid gateway.trustedProxies entry");
+                }
+                parsed
+            })
+            .collect();
+        Self { nets }
+    }
+
+    pub(super) fn contains(&self, ip: IpAddr) -> bool {
+        let ip = canonical(ip);
+        self.nets
+            .iter()
+            .any(|(net, prefix)| in_net(ip, *net, *prefix))
+    }
+}
+
+/// `10.0.0.1`, `10.0.0.0/8` or `fd00::/8`.
+fn parse_net(value: &str) -> Option<(IpAddr, u8)> {
+    let (addr, prefix) = match value.split_once('/') {
+        Some((addr, prefix)) => (addr, Some(prefix.parse::<u8>().ok()?)),
+        None => (value, None),
+    };
+    let ip = canonical(addr.parse::<IpAddr>().ok()?);
+    let max = if ip.is_ipv4() { 32 } else { 128 };
+    let prefix = prefix.unwrap_or(max);
+    (prefix <= max).then_some((ip, prefix))
+}
+
+/// IPv4-mapped IPv6 addresses (dual-stack sockets) compare as IPv4.
+fn canonical(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+        v4 => v4,
+    }
+}
+
+fn in_net(ip: IpAddr, net: IpAddr, prefix: u8) -> bool {
+    match (ip, net) {
+        (IpAddr::V4(ip), IpAddr::V4(net)) => {
+            let mask = u32::MAX.checked_shl(32 - u32::from(prefix)).unwrap_or(0);
+            u32::from(ip) & mask == u32::from(net) & mask
+        }
+        (IpAddr::V6(ip), IpAddr::V6(net)) => {
+            let mask = u128::MAX.checked_shl(128 - u32::from(prefix)).unwrap_or(0);
+            u128::from(ip) & mask == u128::from(net) & mask
+        }
+        _ => false,
+    }
+}
+
+/// Client address of a request that arrived from `peer`. `X-Forwarded-For` is walked
+/// from the right, skipping trusted proxies, so entries a client put there itself are
+/// never used.
+pub(super) fn resolve_client_ip(
+    peer: IpAddr,
+    headers: &axum::http::HeaderMap,
+    trusted: &TrustedProxies,
+) -> IpAddr {
+    let peer = canonical(peer);
+    if !trusted.contains(peer) {
+        return peer;
+    }
+
+    let forwarded: Vec<IpAddr> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .filter_map(|hop| hop.trim().parse::<IpAddr>().ok().map(canonical))
+        .collect();
+    if let Some(client) = forwarded.iter().rev().find(|ip| !trusted.contains(**ip)) {
+        return *client;
+    }
+    if let Some(first) = forwarded.first() {
+        // Every hop is a trusted proxy; the left-most one is closest to the client.
+        return *first;
+    }
+
+    headers
+        .get("x-real-ip")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<IpAddr>().ok())
+        .map(canonical)
+        .unwrap_or(peer)
+}
+
+pub(super) async fn client_ip_middleware(
+    State(trusted): State<Arc<TrustedProxies>>,
+    mut req: Request<axum::body::Body>,
+    next: Next,
+) -> Response {
+    let Some(peer) = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|info| info.0.ip())
+    else {
+        return next.run(req).await;
+    };
+    let client_ip = resolve_client_ip(peer, req.headers(), &trusted);
+    req.extensions_mut().insert(ClientIp(client_ip));
+    let span = tracing::info_span!("http", client_ip = %client_ip);
+    next.run(req).instrument(span).await
+}
+
+/// Serve `app` under `gateway.basePath`, or unchanged when it is empty.
+pub(super) fn with_base_path(app: Router, base_path: &str) -> Router {
+    if base_path.is_empty() {
+        app
+    } else {
+        Router::new().nest(base_path, app)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&str, &str)]) -> axum::http::HeaderMap {
+        let mut headers = axum::http::HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(
+                axum::http::HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                value.parse().unwrap(),
+            );
+        }
+        headers
+    }
+
+    #[test]
+    fn test_trusted_proxy_networks() {
+        let trusted = TrustedProxies::new(&[
+            "10.0.0.0/8".to_string(),
+            "::1".to_string(),
+            "not-an-ip".to_string(),
+            "192.168.1.1/33".to_string(),
+        ]);
+        assert_eq!(trusted.nets.len(), 2);
+        assert!(trusted.contains("10.20.30.40".parse().unwrap()));
+        assert!(trusted.contains("::ffff:10.1.2.3".parse().unwrap()));
+        assert!(trusted.contains("::1".parse().unwrap()));
+        assert!(!trusted.contains("11.0.0.1".parse().unwrap()));
+        assert!(!trusted.contains("127.0.0.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_forwarded_headers_only_trusted_from_proxies() {
+        let trusted = TrustedProxies::new(&["10.0.0.0/8".to_string()]);
+        let spoofed = headers(&[("x-forwarded-for", "1.2.3.4, 203.0.113.7, 10.0.0.2")]);
+
+        // A direct client cannot pick its own address.
+        let direct: IpAddr = "198.51.100.9".parse().unwrap();
+        assert_eq!(resolve_client_ip(direct, &spoofed, &trusted), direct);
+
+        // Behind two proxies, the right-most untrusted hop is the client.
+        let proxy: IpAddr = "10.0.0.1".parse().unwrap();
+        assert_eq!(
+            resolve_client_ip(proxy, &spoofed, &trusted),
+            "203.0.113.7".parse::<IpAddr>().unwrap()
+        );
+        assert_eq!(
+            resolve_client_ip(proxy, &headers(&[("x-real-ip", "203.0.113.8")]), &trusted),
+            "203.0.113.8".parse::<IpAddr>().unwrap()
+        );
+        assert_eq!(resolve_client_ip(proxy, &headers(&[]), &trusted), proxy);
+    }
+}
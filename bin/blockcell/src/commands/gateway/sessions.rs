@@ -1,8 +1,6 @@
 use super::*;
-use blockcell_core::{
-    resolve_session_key_from_id, session_file_stem, session_id_from_file_stem,
-    session_title_from_id,
-};
+use blockcell_core::{resolve_session_key_from_id, session_file_stem, session_title_from_id};
+use blockcell_storage::context_snapshots::ContextSnapshotStore;
 use blockcell_storage::SessionStore;
 // ---------------------------------------------------------------------------
 // P0: Session management endpoints
@@ -16,22 +14,10 @@ struct SessionInfo {
     message_count: usize,
 }
 
-fn session_file_stems(sessions_dir: &std::path::Path) -> Vec<String> {
-    std::fs::read_dir(sessions_dir)
-        .ok()
-        .into_iter()
-        .flatten()
-        .flatten()
-        .filter_map(|entry| {
-            let path = entry.path();
-            if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
-                return None;
-            }
-            path.file_stem()
-                .and_then(|s| s.to_str())
-                .map(|s| s.to_string())
-        })
-        .collect()
+fn session_stems(agent_paths: &Paths) -> Vec<String> {
+    SessionStore::new(agent_paths.clone())
+        .stems()
+        .unwrap_or_default()
 }
 
 #[derive(Deserialize)]
@@ -41,7 +27,7 @@ pub(super) struct SessionsListQuery {
     agent: Option<String>,
 }
 
-/// GET /v1/sessions — list sessions (supports pagination)
+/// GET /v1/sessions — list sessions from the session store (supports pagination)
 pub(super) async fn handle_sessions_list(
     State(state): State<GatewayState>,
     Query(params): Query<SessionsListQuery>,
@@ -51,13 +37,14 @@ pub(super) async fn handle_sessions_list(
         Err(err) => return Json(serde_json::json!({ "error": err })),
     };
     let agent_paths = state.paths.for_agent(&agent_id);
-    let sessions_dir = agent_paths.sessions_dir();
     let limit = params.limit;
-    let cursor = params.cursor;
+    let cursor = params.cursor.unwrap_or(0);
 
-    let result = tokio::task::spawn_blocking(move || {
-        let mut sessions = Vec::new();
-        let meta_path = sessions_dir.join("_meta.json");
+    let result = tokio::task::spawn_blocking(move || -> blockcell_core::Result<_> {
+        let (summaries, total) =
+            SessionStore::new(agent_paths.clone()).list(cursor, limit.unwrap_or(usize::MAX))?;
+
+        let meta_path = agent_paths.sessions_dir().join("_meta.json");
         let meta: serde_json::Map<String, serde_json::Value> = if meta_path.exists() {
             std::fs::read_to_string(&meta_path)
                 .ok()
@@ -67,93 +54,62 @@ pub(super) async fn handle_sessions_list(
             serde_json::Map::new()
         };
 
-        if let Ok(entries) = std::fs::read_dir(&sessions_dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
-                    continue;
-                }
-                let file_stem = path
-                    .file_stem()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("")
-                    .to_string();
-
-                let session_id = session_id_from_file_stem(&file_stem);
-
-                let updated_at = std::fs::metadata(&path)
-                    .and_then(|m| m.modified())
-                    .map(|t| {
-                        let dt: chrono::DateTime<chrono::Utc> = t.into();
-                        dt.to_rfc3339()
-                    })
-                    .unwrap_or_default();
-
-                let message_count = std::fs::read_to_string(&path)
-                    .map(|c| {
-                        c.lines()
-                            .filter(|l| !l.trim().is_empty())
-                            .count()
-                            .saturating_sub(1)
-                    })
-                    .unwrap_or(0);
-
+        let page: Vec<SessionInfo> = summaries
+            .into_iter()
+            .map(|summary| {
                 let name = meta
-                    .get(&file_stem)
-                    .or_else(|| meta.get(&session_id))
+                    .get(&summary.stem)
+                    .or_else(|| meta.get(&summary.session_id))
                     .and_then(|v| v.get("name"))
                     .and_then(|v| v.as_str())
                     .map(|s| s.to_string())
-                    .unwrap_or_else(|| session_title_from_id(&session_id));
-
-                sessions.push(SessionInfo {
-                    id: session_id,
+                    .unwrap_or_else(|| session_title_from_id(&summary.session_id));
+                let updated_at = chrono::DateTime::from_timestamp_millis(summary.updated_at_ms)
+                    .map(|dt| dt.to_rfc3339())
+                    .unwrap_or_default();
+                SessionInfo {
+                    id: summary.session_id,
                     name,
                     updated_at,
-                    message_count,
-                });
-            }
-        }
-
-        sessions.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
-
-        let total = sessions.len();
-        let limit = limit.unwrap_or(total);
-        let cursor = cursor.unwrap_or(0);
-
-        if cursor >= total {
-            return serde_json::json!({
-                "sessions": [],
-                "next_cursor": null,
-                "total": total,
-            });
-        }
+                    message_count: summary.message_count,
+                }
+            })
+            .collect();
 
-        let end = std::cmp::min(cursor.saturating_add(limit), total);
-        let page = sessions[cursor..end].to_vec();
+        let end = cursor.saturating_add(page.len());
         let next_cursor = if end < total { Some(end) } else { None };
 
-        serde_json::json!({
+        Ok(serde_json::json!({
             "sessions": page,
             "next_cursor": next_cursor,
             "total": total,
-        })
+        }))
     })
     .await;
 
     match result {
-        Ok(v) => Json(v),
+        Ok(Ok(v)) => Json(v),
+        Ok(Err(e)) => {
+            Json(serde_json::json!({ "error": format!("Failed to list sessions: {}", e) }))
+        }
         Err(e) => Json(serde_json::json!({ "error": format!("Failed to list sessions: {}", e) })),
     }
 }
 
-/// GET /v1/sessions/:id — get session history
+#[derive(Deserialize)]
+pub(super) struct SessionGetQuery {
+    agent: Option<String>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+}
+
+/// GET /v1/sessions/:id — get session history (`offset`/`limit` page through messages)
 pub(super) async fn handle_session_get(
     State(state): State<GatewayState>,
     AxumPath(session_id): AxumPath<String>,
-    Query(agent): Query<AgentScopedQuery>,
+    Query(params): Query<SessionGetQuery>,
 ) -> impl IntoResponse {
-    let agent_id = match resolve_requested_agent_id(&state.config, agent.agent.as_deref()) {
+    let agent_id = match resolve_requested_agent_id(&state.config, params.agent.as_deref()) {
         Ok(agent_id) => agent_id,
         Err(err) => {
             return (
@@ -164,14 +120,15 @@ pub(super) async fn handle_session_get(
         }
     };
     let agent_paths = state.paths.for_agent(&agent_id);
-    let session_stems = session_file_stems(&agent_paths.sessions_dir());
+    let session_stems = session_stems(&agent_paths);
     let session_key =
         resolve_session_key_from_id(&session_id, session_stems.iter().map(|s| s.as_str()));
     let session_store = SessionStore::new(agent_paths);
-    let loaded_messages: blockcell_core::Result<Vec<blockcell_core::types::ChatMessage>> =
-        session_store.load(&session_key);
+    let offset = params.offset.unwrap_or(0);
+    let limit = params.limit.unwrap_or(usize::MAX);
+    let loaded_messages = session_store.load_page(&session_key, offset, limit);
     match loaded_messages {
-        Ok(messages) if !messages.is_empty() => {
+        Ok((messages, total)) if total > 0 => {
             let msgs: Vec<serde_json::Value> = messages
                 .iter()
                 .map(|m| {
@@ -184,11 +141,15 @@ pub(super) async fn handle_session_get(
                     })
                 })
                 .collect();
+            let end = offset.saturating_add(limit).min(total);
             (
                 StatusCode::OK,
                 Json(serde_json::json!({
                     "session_id": session_id,
                     "messages": msgs,
+                    "offset": offset,
+                    "total": total,
+                    "next_offset": (end < total).then_some(end),
                 })),
             )
                 .into_response()
@@ -210,6 +171,71 @@ pub(super) async fn handle_session_get(
     }
 }
 
+/// GET /v1/sessions/:id/messages/:idx/context — the context sent to the model for the
+/// turn that produced message `idx`
+pub(super) async fn handle_session_message_context(
+    State(state): State<GatewayState>,
+    AxumPath((session_id, index)): AxumPath<(String, usize)>,
+    Query(agent): Query<AgentScopedQuery>,
+) -> impl IntoResponse {
+    let agent_id = match resolve_requested_agent_id(&state.config, agent.agent.as_deref()) {
+        Ok(agent_id) => agent_id,
+        Err(err) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": err })),
+            )
+                .into_response()
+        }
+    };
+    let agent_paths = state.paths.for_agent(&agent_id);
+    let session_stems = session_stems(&agent_paths);
+    let session_key =
+        resolve_session_key_from_id(&session_id, session_stems.iter().map(|s| s.as_str()));
+    let messages = match SessionStore::new(agent_paths.clone()).load(&session_key) {
+        Ok(messages) => messages,
+        Err(e) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({ "error": format!("Session not found: {}", e) })),
+            )
+                .into_response()
+        }
+    };
+    if index >= messages.len() {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": format!("Message {} not found ({} messages)", index, messages.len())
+            })),
+        )
+            .into_response();
+    }
+    match ContextSnapshotStore::new(&agent_paths).reconstruct(&session_key, index, &messages) {
+        Ok(Some(context)) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "session_id": session_id,
+                "message_index": index,
+                "context": context,
+            })),
+        )
+            .into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": "No context snapshot recorded for this message"
+            })),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": e.to_string() })),
+        )
+            .into_response(),
+    }
+}
+
 /// DELETE /v1/sessions/:id — delete a session
 pub(super) async fn handle_session_delete(
     State(state): State<GatewayState>,
@@ -221,17 +247,22 @@ pub(super) async fn handle_session_delete(
         Err(err) => return Json(serde_json::json!({ "status": "error", "message": err })),
     };
     let agent_paths = state.paths.for_agent(&agent_id);
-    let session_stems = session_file_stems(&agent_paths.sessions_dir());
+    let session_stems = session_stems(&agent_paths);
     let session_key =
         resolve_session_key_from_id(&session_id, session_stems.iter().map(|s| s.as_str()));
-    let path = agent_paths.session_file(&session_key);
+    let snapshots = ContextSnapshotStore::new(&agent_paths);
+    let session_store = SessionStore::new(agent_paths);
     let session_id_clone = session_id.clone();
     let result = tokio::task::spawn_blocking(move || {
-        if path.exists() {
-            let _ = std::fs::remove_file(&path);
-            serde_json::json!({ "status": "deleted", "session_id": session_id_clone })
-        } else {
-            serde_json::json!({ "status": "not_found", "session_id": session_id_clone })
+        let _ = snapshots.remove(&session_key);
+        match session_store.clear(&session_key) {
+            Ok(true) => {
+                serde_json::json!({ "status": "deleted", "session_id": session_id_clone })
+            }
+            Ok(false) => {
+                serde_json::json!({ "status": "not_found", "session_id": session_id_clone })
+            }
+            Err(e) => serde_json::json!({ "status": "error", "message": e.to_string() }),
         }
     })
     .await;
@@ -259,7 +290,7 @@ pub(super) async fn handle_session_rename(
         Err(err) => return Json(serde_json::json!({ "status": "error", "message": err })),
     };
     let agent_paths = state.paths.for_agent(&agent_id);
-    let session_stems = session_file_stems(&agent_paths.sessions_dir());
+    let session_stems = session_stems(&agent_paths);
     let session_key =
         resolve_session_key_from_id(&session_id, session_stems.iter().map(|s| s.as_str()));
     let file_stem = session_file_stem(&session_key);
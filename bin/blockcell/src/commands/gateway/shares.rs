@@ -0,0 +1,333 @@
+use super::*;
+use blockcell_core::file_share::{validate_share_path, NewShare, ShareLink, ShareStore};
+// ---------------------------------------------------------------------------
+// File share links: authenticated management + public /s/:token download
+// ---------------------------------------------------------------------------
+
+/// Serializes read-modify-write of the per-agent share files.
+static SHARES_LOCK: once_cell::sync::Lazy<Mutex<()>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(()));
+
+#[derive(Deserialize)]
+pub(super) struct ShareCreateRequest {
+    path: String,
+    #[serde(default)]
+    agent: Option<String>,
+    #[serde(default)]
+    expires_in_secs: Option<u64>,
+    #[serde(default)]
+    password: Option<String>,
+    #[serde(default)]
+    max_downloads: Option<u32>,
+    #[serde(default)]
+    note: Option<String>,
+}
+
+/// POST /v1/files/share — create an expiring (optionally password-protected) share link
+pub(super) async fn handle_files_share_create(
+    State(state): State<GatewayState>,
+    Json(req): Json<ShareCreateRequest>,
+) -> Response {
+    let agent_id = match resolve_requested_agent_id(&state.config, req.agent.as_deref()) {
+        Ok(agent_id) => agent_id,
+        Err(err) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": err })),
+            )
+                .into_response()
+        }
+    };
+    let rel = match validate_share_path(&req.path) {
+        Ok(p) => p,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": e.to_string() })),
+            )
+                .into_response()
+        }
+    };
+    let agent_paths = state.paths.for_agent(&agent_id);
+    if resolve_shared_file(&agent_paths.workspace(), &rel).is_none() {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": "File not found in workspace" })),
+        )
+            .into_response();
+    }
+
+    let _guard = SHARES_LOCK.lock().await;
+    let result = ShareStore::open(&agent_paths).and_then(|mut store| {
+        let link = store.create(
+            &rel,
+            NewShare {
+                ttl_secs: req.expires_in_secs,
+                password: req.password.as_deref(),
+                max_downloads: req.max_downloads,
+                note: req.note.as_deref(),
+            },
+            state.config.gateway.share_max_ttl_secs,
+        )?;
+        store.save()?;
+        Ok(link)
+    });
+    match result {
+        Ok(link) => {
+            info!(agent = %agent_id, path = %rel, "File share link created");
+            Json(link.to_json(&state.config.gateway)).into_response()
+        }
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": e.to_string() })),
+        )
+            .into_response(),
+    }
+}
+
+/// GET /v1/files/shares — list active share links
+pub(super) async fn handle_files_shares_list(
+    State(state): State<GatewayState>,
+    Query(agent): Query<AgentScopedQuery>,
+) -> impl IntoResponse {
+    let agent_id = match resolve_requested_agent_id(&state.config, agent.agent.as_deref()) {
+        Ok(agent_id) => agent_id,
+        Err(err) => return Json(serde_json::json!({ "error": err })),
+    };
+    let store = match ShareStore::open(&state.paths.for_agent(&agent_id)) {
+        Ok(store) => store,
+        Err(e) => return Json(serde_json::json!({ "error": e.to_string() })),
+    };
+    let now = chrono::Utc::now().timestamp();
+    let shares: Vec<serde_json::Value> = store
+        .list(now)
+        .into_iter()
+        .map(|l| l.to_json(&state.config.gateway))
+        .collect();
+    Json(serde_json::json!({ "shares": shares, "count": shares.len() }))
+}
+
+/// DELETE /v1/files/share/:token — revoke a share link
+pub(super) async fn handle_files_share_revoke(
+    State(state): State<GatewayState>,
+    AxumPath(token): AxumPath<String>,
+    Query(agent): Query<AgentScopedQuery>,
+) -> impl IntoResponse {
+    let agent_id = match resolve_requested_agent_id(&state.config, agent.agent.as_deref()) {
+        Ok(agent_id) => agent_id,
+        Err(err) => return Json(serde_json::json!({ "error": err })),
+    };
+    let _guard = SHARES_LOCK.lock().await;
+    let result = ShareStore::open(&state.paths.for_agent(&agent_id)).and_then(|mut store| {
+        let removed = store.revoke(&token);
+        if removed {
+            store.save()?;
+        }
+        Ok(removed)
+    });
+    match result {
+        Ok(true) => Json(serde_json::json!({ "status": "revoked", "token": token })),
+        Ok(false) => Json(serde_json::json!({ "error": "Share link not found" })),
+        Err(e) => Json(serde_json::json!({ "error": e.to_string() })),
+    }
+}
+
+#[derive(Deserialize, Default)]
+pub(super) struct SharePasswordForm {
+    #[serde(default)]
+    password: String,
+}
+
+/// GET /s/:token — public download (shows a password form for protected links)
+pub(super) async fn handle_share_get(
+    State(state): State<GatewayState>,
+    AxumPath(token): AxumPath<String>,
+) -> Response {
+    serve_share(&state, &token, None).await
+}
+
+/// POST /s/:token — public download of a password-protected link
+pub(super) async fn handle_share_post(
+    State(state): State<GatewayState>,
+    AxumPath(token): AxumPath<String>,
+    axum::Form(form): axum::Form<SharePasswordForm>,
+) -> Response {
+    serve_share(&state, &token, Some(form.password)).await
+}
+
+/// Resolve a share's file, refusing anything that escapes the workspace (e.g. via symlink).
+fn resolve_shared_file(workspace: &Path, rel: &str) -> Option<std::path::PathBuf> {
+    let canonical = workspace.join(rel).canonicalize().ok()?;
+    let ws_canonical = workspace.canonicalize().ok()?;
+    (canonical.starts_with(&ws_canonical) && canonical.is_file()).then_some(canonical)
+}
+
+fn share_not_found() -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        "This link is invalid, expired or has been revoked.",
+    )
+        .into_response()
+}
+
+fn share_password_page(link: &ShareLink, error: Option<&str>) -> Response {
+    let name = Path::new(&link.path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let html = format!(
+        "<!doctype html><html><head><meta charset=\"utf-8\">\
+         <meta name=\"viewport\" content=\"width=device-width,initial-scale=1\">\
+         <title>{name}</title></head>\
+         <body style=\"font-family:system-ui,sans-serif;max-width:24rem;margin:4rem auto\">\
+         <h3>{name}</h3><p>This file is password-protected.</p>{error}\
+         <form method=\"post\"><input type=\"password\" name=\"password\" autofocus required> \
+         <button type=\"submit\">Download</button></form></body></html>",
+        name = html_escape(&name),
+        error = error
+            .map(|e| format!("<p style=\"color:#b00\">{}</p>", html_escape(e)))
+            .unwrap_or_default(),
+    );
+    let status = if error.is_some() {
+        StatusCode::UNAUTHORIZED
+    } else {
+        StatusCode::OK
+    };
+    (
+        status,
+        [
+            (header::CONTENT_TYPE, "text/html; charset=utf-8"),
+            (header::CACHE_CONTROL, "no-store"),
+        ],
+        html,
+    )
+        .into_response()
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+async fn serve_share(state: &GatewayState, token: &str, password: Option<String>) -> Response {
+    if token.len() != 64 || !token.chars().all(|c| c.is_ascii_hexdigit()) {
+        return share_not_found();
+    }
+    let now = chrono::Utc::now().timestamp();
+    let _guard = SHARES_LOCK.lock().await;
+
+    // Tokens are random, so looking through every agent's store is unambiguous.
+    for agent_id in state.config.known_agent_ids() {
+        let agent_paths = state.paths.for_agent(&agent_id);
+        let Ok(mut store) = ShareStore::open(&agent_paths) else {
+            continue;
+        };
+        let Some(link) = store.get(token).cloned() else {
+            continue;
+        };
+        if !link.is_usable(now) {
+            return share_not_found();
+        }
+
+        if link.password_protected() {
+            let Some(password) = password else {
+                return share_password_page(&link, None);
+            };
+            if !link.check_password(&password) {
+                if let Some(l) = store.get_mut(token) {
+                    l.failed_attempts += 1;
+                }
+                let _ = store.save();
+                warn!(agent = %agent_id, "Wrong password for file share link");
+                return share_password_page(&link, Some("Wrong password."));
+            }
+        }
+
+        let Some(file) = resolve_shared_file(&agent_paths.workspace(), &link.path) else {
+            return share_not_found();
+        };
+        let bytes = match tokio::fs::read(&file).await {
+            Ok(b) => b,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Read error: {}", e),
+                )
+                    .into_response()
+            }
+        };
+        if let Some(l) = store.get_mut(token) {
+            l.downloads += 1;
+            l.failed_attempts = 0;
+        }
+        let _ = store.save();
+
+        let filename = file
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("download")
+            .to_string();
+        let ascii_name: String = filename
+            .chars()
+            .map(|c| {
+                if (c.is_ascii_graphic() && c != '"') || c == ' ' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let content_type = mime_guess::from_path(&file)
+            .first_or_octet_stream()
+            .to_string();
+        let headers = [
+            (header::CONTENT_TYPE, content_type),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+                    ascii_name,
+                    urlencoding::encode(&filename)
+                ),
+            ),
+            (header::CACHE_CONTROL, "no-store".to_string()),
+        ];
+        return (headers, bytes).into_response();
+    }
+    share_not_found()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_shared_file_stays_in_workspace() {
+        let base = std::env::temp_dir().join(format!("blockcell-share-{}", uuid::Uuid::new_v4()));
+        let ws = base.join("workspace");
+        std::fs::create_dir_all(ws.join("out")).unwrap();
+        std::fs::write(ws.join("out/report.md"), "# hi").unwrap();
+        std::fs::write(base.join("secret.txt"), "nope").unwrap();
+
+        assert!(resolve_shared_file(&ws, "out/report.md").is_some());
+        assert!(resolve_shared_file(&ws, "out").is_none());
+        assert!(resolve_shared_file(&ws, "missing.md").is_none());
+        assert!(resolve_shared_file(&ws, "../secret.txt").is_none());
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(base.join("secret.txt"), ws.join("link.txt")).unwrap();
+            assert!(resolve_shared_file(&ws, "link.txt").is_none());
+        }
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_html_escape() {
+        assert_eq!(
+            html_escape("<b>\"a&b\"</b>"),
+            "&lt;b&gt;&quot;a&amp;b&quot;&lt;/b&gt;"
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use super::*;
 use crate::commands::gateway::chat::assign_session_id;
+use crate::commands::gateway::ws_codec::{WsCodec, WsCodecQuery};
+use crate::commands::gateway::ws_filter::WsSubscription;
 use crate::commands::slash_commands::{CommandContext, CommandResult, SLASH_COMMAND_HANDLER};
 // ---------------------------------------------------------------------------
 // P0: WebSocket with structured protocol
@@ -8,27 +10,13 @@ use crate::commands::slash_commands::{CommandContext, CommandResult, SLASH_COMMA
 pub(super) async fn handle_ws_upgrade(
     ws: WebSocketUpgrade,
     State(state): State<GatewayState>,
+    Query(codec): Query<WsCodecQuery>,
     req: axum::extract::Request,
 ) -> impl IntoResponse {
     // Validate token inside the WS handler so we can close with code 4401
     // instead of rejecting the HTTP upgrade with 401 (which gives client code 1006).
-    let token_valid = match &state.api_token {
-        Some(t) if !t.is_empty() => {
-            let auth_header = req
-                .headers()
-                .get(header::AUTHORIZATION)
-                .and_then(|v| v.to_str().ok());
-            let from_header = match auth_header {
-                Some(h) if h.starts_with("Bearer ") => secure_eq(&h[7..], t.as_str()),
-                _ => false,
-            };
-            let from_query = token_from_query(&req)
-                .map(|v| secure_eq(&v, t.as_str()))
-                .unwrap_or(false);
-            from_header || from_query
-        }
-        _ => true, // no token configured → open access
-    };
+    let token_valid = request_authorized(&state, req.headers(), req.uri()).await;
+    let codec = WsCodec::negotiate(&codec, &state.config);
 
     ws.on_upgrade(move |socket| async move {
         if !token_valid {
@@ -41,15 +29,16 @@ pub(super) async fn handle_ws_upgrade(
                 .await;
             return;
         }
-        handle_ws_connection(socket, state).await;
+        handle_ws_connection(socket, state, codec).await;
     })
 }
 
-pub(super) async fn handle_ws_connection(socket: WebSocket, state: GatewayState) {
-    info!("WebSocket client connected");
+pub(super) async fn handle_ws_connection(socket: WebSocket, state: GatewayState, codec: WsCodec) {
+    info!(binary = codec.is_binary(), "WebSocket client connected");
 
     let (mut ws_sender, mut ws_receiver) = socket.split();
     let mut broadcast_rx = state.ws_broadcast.subscribe();
+    let (subscription_tx, subscription_rx) = tokio::sync::watch::channel(WsSubscription::default());
 
     use futures::SinkExt;
     use futures::StreamExt;
@@ -57,7 +46,10 @@ pub(super) async fn handle_ws_connection(socket: WebSocket, state: GatewayState)
     // Task: forward broadcast events to this WS client
     let send_task = tokio::spawn(async move {
         while let Ok(msg) = broadcast_rx.recv().await {
-            if ws_sender.send(WsMessage::Text(msg)).await.is_err() {
+            if !subscription_rx.borrow().allows(&msg) {
+                continue;
+            }
+            if ws_sender.send(codec.encode(msg)).await.is_err() {
                 break;
             }
         }
@@ -127,6 +119,7 @@ pub(super) async fn handle_ws_connection(socket: WebSocket, state: GatewayState)
                             };
 
                             let chat_id = assign_session_id(&client_chat_id, &resolved_agent_id);
+                            subscription_tx.send_if_modified(|sub| sub.track_chat(&chat_id));
 
                             let _ = ws_broadcast.send(
                                 WsEvent::SessionBound {
@@ -240,6 +233,11 @@ pub(super) async fn handle_ws_connection(socket: WebSocket, state: GatewayState)
                                 break;
                             }
                         }
+                        "subscribe" => {
+                            let subscription = WsSubscription::from_message(&parsed);
+                            debug!(?subscription, "WS subscription updated");
+                            subscription_tx.send_replace(subscription);
+                        }
                         "confirm_response" => {
                             let request_id = parsed
                                 .get("request_id")
@@ -7,7 +7,43 @@ use super::*;
 #[folder = "../../webui/dist"]
 struct WebUiAssets;
 
-pub(super) async fn handle_webui_static(uri: axum::http::Uri) -> impl IntoResponse {
+/// Add `/env.js` and the static files to `router`. `same_origin` is set when the router
+/// is the API listener (single-port mode), so the WebUI calls the API on its own origin.
+pub(super) fn webui_routes<S>(router: Router<S>, config: &Config, same_origin: bool) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let env_config = config.clone();
+    let base_path = config.gateway.normalized_base_path();
+    router
+        .route(
+            "/env.js",
+            get(move || {
+                let cfg = env_config.clone();
+                async move { handle_webui_env_js(cfg, same_origin).await }
+            }),
+        )
+        .fallback(move |uri: axum::http::Uri| {
+            let base_path = base_path.clone();
+            async move { handle_webui_static(uri, base_path).await }
+        })
+}
+
+/// Unknown API paths get a real 404 instead of the SPA page.
+fn is_api_path(path: &str) -> bool {
+    ["/v1/", "/webhook/", "/s/"]
+        .iter()
+        .any(|prefix| path.starts_with(prefix))
+}
+
+/// `base_path` is `gateway.basePath`; the router has already stripped it from `uri`.
+pub(super) async fn handle_webui_static(
+    uri: axum::http::Uri,
+    base_path: String,
+) -> impl IntoResponse {
+    if is_api_path(uri.path()) {
+        return (StatusCode::NOT_FOUND, "Not Found").into_response();
+    }
     let path = uri.path().trim_start_matches('/');
     // Try the exact path first, then fall back to index.html for SPA routing
     let file_path = if path.is_empty() { "index.html" } else { path };
@@ -22,9 +58,11 @@ pub(super) async fn handle_webui_static(uri: axum::http::Uri) -> impl IntoRespon
             // Runtime injection: make WebUI load /env.js before the main bundle.
             // This allows changing backend address via config.json5 without rebuilding dist.
             if file_path == "index.html" {
-                let html = String::from_utf8_lossy(&body);
-                let injected = inject_env_js_into_index_html(&html);
-                body = injected.into_bytes();
+                let html = rebase_root_urls(file_path, &String::from_utf8_lossy(&body), &base_path);
+                body = inject_env_js_into_index_html(&html, &base_path).into_bytes();
+            } else if matches!(file_path, "manifest.json" | "sw.js") && !base_path.is_empty() {
+                body = rebase_root_urls(file_path, &String::from_utf8_lossy(&body), &base_path)
+                    .into_bytes();
             }
             // index.html must never be cached: a stale index.html that references
             // old hashed JS/CSS bundle filenames causes a blank page after rebuild.
@@ -50,10 +88,10 @@ pub(super) async fn handle_webui_static(uri: axum::http::Uri) -> impl IntoRespon
             // SPA fallback: serve index.html for any unknown route
             match WebUiAssets::get("index.html") {
                 Some(content) => {
-                    let mut body: Vec<u8> = content.data.into();
-                    let html = String::from_utf8_lossy(&body);
-                    let injected = inject_env_js_into_index_html(&html);
-                    body = injected.into_bytes();
+                    let body: Vec<u8> = content.data.into();
+                    let html =
+                        rebase_root_urls("index.html", &String::from_utf8_lossy(&body), &base_path);
+                    let body = inject_env_js_into_index_html(&html, &base_path).into_bytes();
                     (
                         StatusCode::OK,
                         [
@@ -73,24 +111,57 @@ pub(super) async fn handle_webui_static(uri: axum::http::Uri) -> impl IntoRespon
     }
 }
 
-fn inject_env_js_into_index_html(html: &str) -> String {
-    let tag = "<script src=\"/env.js\"></script>";
-    if html.contains(tag) {
+fn inject_env_js_into_index_html(html: &str, base_path: &str) -> String {
+    let tag = format!("<script src=\"{}/env.js\"></script>", base_path);
+    if html.contains(&tag) {
         return html.to_string();
     }
     if let Some(idx) = html.find("</head>") {
         let mut out = String::with_capacity(html.len() + tag.len() + 1);
         out.push_str(&html[..idx]);
-        out.push_str(tag);
+        out.push_str(&tag);
         out.push_str(&html[idx..]);
         return out;
     }
     format!("{}{}", tag, html)
 }
 
-pub(super) async fn handle_webui_env_js(config: Config) -> impl IntoResponse {
+/// Prefix the root-relative URLs the WebUI build emits (`/assets/...`, `/sw.js`,
+/// manifest icons) with `gateway.basePath`, so the page works under a sub-path.
+fn rebase_root_urls(file_path: &str, text: &str, base_path: &str) -> String {
+    if base_path.is_empty() {
+        return text.to_string();
+    }
+    let prefixes: &[&str] = match file_path {
+        "index.html" => &["src=\"", "href=\"", "register('"],
+        "manifest.json" => &[": \""],
+        "sw.js" => &["'"],
+        _ => return text.to_string(),
+    };
+    let mut out = text.to_string();
+    for prefix in prefixes {
+        out = out
+            .replace(
+                &format!("{}/", prefix),
+                &format!("{}{}/", prefix, base_path),
+            )
+            // Protocol-relative URLs point at another host.
+            .replace(
+                &format!("{}{}//", prefix, base_path),
+                &format!("{}//", prefix),
+            );
+    }
+    if file_path == "sw.js" {
+        // `pathname.includes('/ws')` matches the WebSocket path with or without a prefix.
+        out = out.replace(&format!("'{}/ws'", base_path), "'/ws'");
+    }
+    out
+}
+
+pub(super) async fn handle_webui_env_js(config: Config, same_origin: bool) -> impl IntoResponse {
     let api_port = config.gateway.port;
     let public_base = config.gateway.public_api_base.clone().unwrap_or_default();
+    let base_path = config.gateway.normalized_base_path();
 
     // JS runs in browser, can compute hostname dynamically.
     // If publicApiBase is provided, use it as-is.
@@ -99,6 +170,13 @@ pub(super) async fn handle_webui_env_js(config: Config) -> impl IntoResponse {
             "window.BLOCKCELL_API_BASE = {};\nwindow.BLOCKCELL_WS_URL = (window.BLOCKCELL_API_BASE.startsWith('https://') ? 'wss://' : 'ws://') + window.BLOCKCELL_API_BASE.replace(/^https?:\\/\\//, '') + '/v1/ws';\n",
             serde_json::to_string(&public_base).unwrap_or_else(|_| "\"\"".to_string())
         )
+    } else if same_origin || !base_path.is_empty() {
+        // Single-port mode, or behind a reverse proxy: the API shares the page's origin
+        // and prefix.
+        format!(
+            "(function(){{\n  var base = window.location.origin + {};\n  window.BLOCKCELL_API_BASE = base;\n  window.BLOCKCELL_WS_URL = base.replace(/^http/, 'ws') + '/v1/ws';\n}})();\n",
+            serde_json::to_string(&base_path).unwrap_or_else(|_| "\"\"".to_string())
+        )
     } else {
         format!(
             "(function(){{\n  var proto = window.location.protocol;\n  var host = window.location.hostname;\n  var apiPort = {};\n  window.BLOCKCELL_API_BASE = proto + '//' + host + ':' + apiPort;\n  var wsProto = (proto === 'https:') ? 'wss://' : 'ws://';\n  window.BLOCKCELL_WS_URL = wsProto + host + ':' + apiPort + '/v1/ws';\n}})();\n",
@@ -121,3 +199,58 @@ pub(super) async fn handle_webui_env_js(config: Config) -> impl IntoResponse {
         js,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INDEX: &str = r#"<head><link rel="manifest" href="/manifest.json" /><link href="https://fonts.example.com/css" rel="stylesheet" /><script type="module" crossorigin src="/assets/index-abc.js"></script></head><body><script>navigator.serviceWorker.register('/sw.js')</script></body>"#;
+
+    #[test]
+    fn test_index_html_without_base_path_is_unchanged() {
+        let html = inject_env_js_into_index_html(&rebase_root_urls("index.html", INDEX, ""), "");
+        assert!(html.contains(r#"src="/assets/index-abc.js""#));
+        assert!(html.contains(r#"<script src="/env.js"></script></head>"#));
+    }
+
+    #[test]
+    fn test_api_paths_are_not_spa_routes() {
+        assert!(is_api_path("/v1/unknown"));
+        assert!(is_api_path("/webhook/slack"));
+        assert!(!is_api_path("/"));
+        assert!(!is_api_path("/assets/index-abc.js"));
+        assert!(!is_api_path("/v1"));
+    }
+
+    #[test]
+    fn test_root_urls_get_base_path() {
+        let html = inject_env_js_into_index_html(
+            &rebase_root_urls("index.html", INDEX, "/blockcell"),
+            "/blockcell",
+        );
+        assert!(html.contains(r#"src="/blockcell/assets/index-abc.js""#));
+        assert!(html.contains(r#"href="/blockcell/manifest.json""#));
+        assert!(html.contains("register('/blockcell/sw.js')"));
+        assert!(html.contains(r#"href="https://fonts.example.com/css""#));
+        assert!(html.contains(r#"<script src="/blockcell/env.js"></script>"#));
+
+        let manifest = rebase_root_urls(
+            "manifest.json",
+            r#"{"start_url": "/", "icons": [{"src": "/icon.svg"}]}"#,
+            "/blockcell",
+        );
+        assert_eq!(
+            manifest,
+            r#"{"start_url": "/blockcell/", "icons": [{"src": "/blockcell/icon.svg"}]}"#
+        );
+        let sw = rebase_root_urls(
+            "sw.js",
+            "['/icon.svg']; url.pathname.includes('/ws'); url.pathname.startsWith('/v1/')",
+            "/blockcell",
+        );
+        assert_eq!(
+            sw,
+            "['/blockcell/icon.svg']; url.pathname.includes('/ws'); url.pathname.startsWith('/blockcell/v1/')"
+        );
+    }
+}
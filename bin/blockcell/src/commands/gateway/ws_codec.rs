@@ -0,0 +1,237 @@
+use super::*;
+use std::io::Write;
+// ---------------------------------------------------------------------------
+// WebSocket frame encoding, negotiated per client
+// ---------------------------------------------------------------------------
+//
+// Clients opt in with query params on the upgrade request:
+// `/v1/ws?encoding=msgpack&compress=deflate`. Events smaller than
+// `gateway.wsBinaryMinBytes` always go out as text JSON; larger ones go out as a
+// binary frame whose first byte holds flags and whose rest is the payload:
+// bit 0 = MessagePack body (else JSON), bit 1 = raw-deflate compressed (RFC 1951,
+// `DecompressionStream("deflate-raw")` in browsers).
+
+const FLAG_MSGPACK: u8 = 0x01;
+const FLAG_DEFLATE: u8 = 0x02;
+
+#[derive(Deserialize, Default)]
+pub(super) struct WsCodecQuery {
+    encoding: Option<String>,
+    compress: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct WsCodec {
+    msgpack: bool,
+    deflate: bool,
+    min_bytes: usize,
+}
+
+impl WsCodec {
+    pub(super) fn negotiate(query: &WsCodecQuery, config: &Config) -> Self {
+        let is = |value: &Option<String>, expected: &str| {
+            value
+                .as_deref()
+                .is_some_and(|v| v.trim().eq_ignore_ascii_case(expected))
+        };
+        Self {
+            msgpack: is(&query.encoding, "msgpack"),
+            deflate: is(&query.compress, "deflate"),
+            min_bytes: config.gateway.ws_binary_min_bytes,
+        }
+    }
+
+    /// Whether this client ever gets binary frames.
+    pub(super) fn is_binary(&self) -> bool {
+        self.msgpack || self.deflate
+    }
+
+    /// Frame for one broadcast event (a JSON string).
+    pub(super) fn encode(&self, event: String) -> WsMessage {
+        if !self.is_binary() || event.len() < self.min_bytes {
+            return WsMessage::Text(event);
+        }
+        let mut flags = 0;
+        let parsed = self
+            .msgpack
+            .then(|| serde_json::from_str::<serde_json::Value>(&event).ok())
+            .flatten();
+        let mut body = match parsed {
+            Some(value) => {
+                flags |= FLAG_MSGPACK;
+                let mut out = Vec::with_capacity(event.len());
+                write_msgpack(&mut out, &value);
+                out
+            }
+            None => event.into_bytes(),
+        };
+        if self.deflate {
+            let mut encoder = flate2::write::DeflateEncoder::new(
+                Vec::with_capacity(body.len() / 2),
+                flate2::Compression::fast(),
+            );
+            if let Ok(compressed) = encoder.write_all(&body).and_then(|_| encoder.finish()) {
+                flags |= FLAG_DEFLATE;
+                body = compressed;
+            }
+        }
+        let mut frame = Vec::with_capacity(body.len() + 1);
+        frame.push(flags);
+        frame.extend_from_slice(&body);
+        WsMessage::Binary(frame)
+    }
+}
+
+/// MessagePack encoding of a JSON value (the same shape, smallest representations).
+fn write_msgpack(out: &mut Vec<u8>, value: &serde_json::Value) {
+    use serde_json::Value;
+    match value {
+        Value::Null => out.push(0xc0),
+        Value::Bool(b) => out.push(if *b { 0xc3 } else { 0xc2 }),
+        Value::Number(n) => {
+            if let Some(u) = n.as_u64() {
+                match u {
+                    0..=0x7f => out.push(u as u8),
+                    0x80..=0xff => out.extend_from_slice(&[0xcc, u as u8]),
+                    0x100..=0xffff => {
+                        out.push(0xcd);
+                        out.extend_from_slice(&(u as u16).to_be_bytes());
+                    }
+                    0x1_0000..=0xffff_ffff => {
+                        out.push(0xce);
+                        out.extend_from_slice(&(u as u32).to_be_bytes());
+                    }
+                    _ => {
+                        out.push(0xcf);
+                        out.extend_from_slice(&u.to_be_bytes());
+                    }
+                }
+            } else if let Some(i) = n.as_i64() {
+                // Only negative values reach here.
+                match i {
+                    -32..=-1 => out.push(i as i8 as u8),
+                    -0x80..=-33 => out.extend_from_slice(&[0xd0, i as i8 as u8]),
+                    -0x8000..=-0x81 => {
+                        out.push(0xd1);
+                        out.extend_from_slice(&(i as i16).to_be_bytes());
+                    }
+                    -0x8000_0000..=-0x8001 => {
+                        out.push(0xd2);
+                        out.extend_from_slice(&(i as i32).to_be_bytes());
+                    }
+                    _ => {
+                        out.push(0xd3);
+                        out.extend_from_slice(&i.to_be_bytes());
+                    }
+                }
+            } else {
+                out.push(0xcb);
+                out.extend_from_slice(&n.as_f64().unwrap_or(0.0).to_be_bytes());
+            }
+        }
+        Value::String(s) => {
+            write_msgpack_len(out, s.len(), 0xa0, 31, [0xd9, 0xda, 0xdb]);
+            out.extend_from_slice(s.as_bytes());
+        }
+        Value::Array(items) => {
+            write_msgpack_len(out, items.len(), 0x90, 15, [0, 0xdc, 0xdd]);
+            for item in items {
+                write_msgpack(out, item);
+            }
+        }
+        Value::Object(map) => {
+            write_msgpack_len(out, map.len(), 0x80, 15, [0, 0xde, 0xdf]);
+            for (key, item) in map {
+                write_msgpack_len(out, key.len(), 0xa0, 31, [0xd9, 0xda, 0xdb]);
+                out.extend_from_slice(key.as_bytes());
+                write_msgpack(out, item);
+            }
+        }
+    }
+}
+
+/// Length header: fix form up to `fix_max`, then the 8/16/32-bit markers (8-bit marker
+/// 0 = the type has no 8-bit form).
+fn write_msgpack_len(out: &mut Vec<u8>, len: usize, fix: u8, fix_max: usize, markers: [u8; 3]) {
+    if len <= fix_max {
+        out.push(fix | len as u8);
+    } else if len <= 0xff && markers[0] != 0 {
+        out.extend_from_slice(&[markers[0], len as u8]);
+    } else if len <= 0xffff {
+        out.push(markers[1]);
+        out.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        out.push(markers[2]);
+        out.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn codec(encoding: &str, compress: &str) -> WsCodec {
+        let query = WsCodecQuery {
+            encoding: Some(encoding.to_string()),
+            compress: Some(compress.to_string()),
+        };
+        let mut config = Config::default();
+        config.gateway.ws_binary_min_bytes = 64;
+        WsCodec::negotiate(&query, &config)
+    }
+
+    #[test]
+    fn test_msgpack_encoding_of_json_values() {
+        let mut out = Vec::new();
+        write_msgpack(
+            &mut out,
+            &serde_json::json!({ "a": [1, -1, -200, 300, 1.5, null, true], "b": "hi" }),
+        );
+        let expected: [&[u8]; 4] = [
+            &[
+                0x82, 0xa1, b'a', 0x97, 0x01, 0xff, 0xd1, 0xff, 0x38, 0xcd, 0x01, 0x2c,
+            ],
+            &[0xcb],
+            &1.5f64.to_be_bytes(),
+            &[0xc0, 0xc3, 0xa1, b'b', 0xa2, b'h', b'i'],
+        ];
+        assert_eq!(out, expected.concat());
+
+        let mut out = Vec::new();
+        write_msgpack(&mut out, &serde_json::Value::String("x".repeat(40)));
+        assert_eq!(&out[..2], &[0xd9, 40]);
+        let mut out = Vec::new();
+        write_msgpack(&mut out, &serde_json::json!(vec![0; 20]));
+        assert_eq!(&out[..3], &[0xdc, 0x00, 20]);
+    }
+
+    #[test]
+    fn test_small_events_stay_text_and_large_ones_switch_to_binary() {
+        let small = r#"{"type":"message_delta","delta":"hi"}"#.to_string();
+        let large =
+            serde_json::json!({ "type": "message_done", "content": "x".repeat(500) }).to_string();
+
+        let plain = WsCodec::negotiate(&WsCodecQuery::default(), &Config::default());
+        assert!(matches!(plain.encode(large.clone()), WsMessage::Text(_)));
+
+        let deflate = codec("json", "deflate");
+        assert!(matches!(deflate.encode(small), WsMessage::Text(_)));
+        let WsMessage::Binary(frame) = deflate.encode(large.clone()) else {
+            panic!("expected a binary frame");
+        };
+        assert_eq!(frame[0], FLAG_DEFLATE);
+        assert!(frame.len() < large.len() / 4);
+        let mut inflated = String::new();
+        flate2::read::DeflateDecoder::new(&frame[1..])
+            .read_to_string(&mut inflated)
+            .unwrap();
+        assert_eq!(inflated, large);
+
+        let WsMessage::Binary(frame) = codec("MsgPack", "").encode(large) else {
+            panic!("expected a binary frame");
+        };
+        assert_eq!(frame[0], FLAG_MSGPACK);
+        assert_eq!(frame[1], 0x82);
+    }
+}
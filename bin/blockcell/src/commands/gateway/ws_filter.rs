@@ -0,0 +1,134 @@
+use super::*;
+use std::collections::HashSet;
+// ---------------------------------------------------------------------------
+// Per-connection broadcast filtering
+// ---------------------------------------------------------------------------
+//
+// Every WS connection receives the shared broadcast stream. A client narrows it
+// with `{"type":"subscribe","chat_ids":[...],"event_types":[...]}`; an empty or
+// missing list means "everything" for that dimension, so a bare `subscribe`
+// resets the filter. Events without a `chat_id` (skills_updated, alerts, ...)
+// are only filtered by type. Chats the connection itself starts are added to its
+// `chat_ids` automatically so a filtered client still sees its own replies.
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(super) struct WsSubscription {
+    chat_ids: HashSet<String>,
+    event_types: HashSet<String>,
+}
+
+/// The fields of a broadcast event that filtering looks at.
+#[derive(Deserialize)]
+struct EventHeader {
+    #[serde(default, rename = "type")]
+    event_type: Option<String>,
+    #[serde(default)]
+    chat_id: Option<String>,
+    #[serde(default)]
+    client_chat_id: Option<String>,
+}
+
+impl WsSubscription {
+    /// Build from a `subscribe` message.
+    pub(super) fn from_message(parsed: &serde_json::Value) -> Self {
+        let list = |key: &str| -> HashSet<String> {
+            parsed
+                .get(key)
+                .and_then(|v| v.as_array())
+                .map(|items| {
+                    items
+                        .iter()
+                        .filter_map(|v| v.as_str())
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        Self {
+            chat_ids: list("chat_ids"),
+            event_types: list("event_types"),
+        }
+    }
+
+    pub(super) fn is_open(&self) -> bool {
+        self.chat_ids.is_empty() && self.event_types.is_empty()
+    }
+
+    /// Add a chat started on this connection; a no-op while chats are unfiltered.
+    pub(super) fn track_chat(&mut self, chat_id: &str) -> bool {
+        !self.chat_ids.is_empty() && !chat_id.is_empty() && self.chat_ids.insert(chat_id.into())
+    }
+
+    /// Whether a broadcast event (a JSON string) should reach this connection.
+    pub(super) fn allows(&self, event: &str) -> bool {
+        if self.is_open() {
+            return true;
+        }
+        let Ok(header) = serde_json::from_str::<EventHeader>(event) else {
+            return true;
+        };
+        if !self.event_types.is_empty()
+            && !header
+                .event_type
+                .as_deref()
+                .is_some_and(|t| self.event_types.contains(t))
+        {
+            return false;
+        }
+        if self.chat_ids.is_empty() {
+            return true;
+        }
+        let ids: Vec<&str> = [header.chat_id.as_deref(), header.client_chat_id.as_deref()]
+            .into_iter()
+            .flatten()
+            .filter(|id| !id.is_empty())
+            .collect();
+        ids.is_empty() || ids.iter().any(|id| self.chat_ids.contains(*id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscription_filters_by_chat_and_type() {
+        let open = WsSubscription::default();
+        assert!(open.allows(r#"{"type":"message_delta","chat_id":"a","delta":"x"}"#));
+
+        let sub = WsSubscription::from_message(&serde_json::json!({
+            "type": "subscribe",
+            "chat_ids": ["chat-a", " "],
+            "event_types": ["message_delta", "message_done", "skills_updated"],
+        }));
+        assert!(sub.allows(r#"{"type":"message_delta","chat_id":"chat-a","delta":"x"}"#));
+        assert!(!sub.allows(r#"{"type":"message_delta","chat_id":"chat-b","delta":"x"}"#));
+        assert!(!sub.allows(r#"{"type":"tool_call_start","chat_id":"chat-a"}"#));
+        // Global events carry no chat id and pass the chat filter.
+        assert!(sub.allows(r#"{"type":"skills_updated","new_skills":[]}"#));
+        assert!(!sub.allows(r#"{"type":"alert_triggered"}"#));
+
+        let reset = WsSubscription::from_message(&serde_json::json!({ "type": "subscribe" }));
+        assert!(reset.is_open());
+    }
+
+    #[test]
+    fn test_tracked_chats_match_bound_and_client_ids() {
+        let mut sub = WsSubscription::from_message(&serde_json::json!({
+            "chat_ids": ["mine"],
+        }));
+        assert!(!sub.allows(r#"{"type":"message_done","chat_id":"ws_123"}"#));
+        assert!(
+            sub.allows(r#"{"type":"session_bound","client_chat_id":"mine","chat_id":"ws_123"}"#)
+        );
+        assert!(sub.track_chat("ws_123"));
+        assert!(!sub.track_chat("ws_123"));
+        assert!(sub.allows(r#"{"type":"message_done","chat_id":"ws_123"}"#));
+
+        let mut open = WsSubscription::default();
+        assert!(!open.track_chat("ws_123"));
+        assert!(open.is_open());
+    }
+}
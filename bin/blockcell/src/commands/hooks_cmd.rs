@@ -0,0 +1,151 @@
+use blockcell_core::hooks::{HookDef, HookStore, HookTarget};
+use blockcell_core::{Config, Paths};
+
+/// Options for `blockcell hooks add`.
+pub struct HookAddArgs {
+    pub name: String,
+    pub template: Option<String>,
+    pub skill: Option<String>,
+    pub tool: Option<String>,
+    pub params: Option<String>,
+    pub secret: Option<String>,
+    pub signature_header: Option<String>,
+    pub events: Vec<String>,
+    pub agent: Option<String>,
+    pub channel: Option<String>,
+    pub chat_id: Option<String>,
+    pub description: Option<String>,
+    pub disabled: bool,
+}
+
+/// List webhook hooks.
+pub async fn list() -> anyhow::Result<()> {
+    let paths = Paths::default();
+    let store = HookStore::open(&paths)?;
+    let hooks = store.list();
+
+    if hooks.is_empty() {
+        println!("(No hooks. Use `blockcell hooks add <name>` to create one.)");
+        return Ok(());
+    }
+
+    println!();
+    println!(
+        "🪝 Hooks ({} total) — {}",
+        hooks.len(),
+        paths.hooks_file().display()
+    );
+    println!();
+    println!(
+        "  {:<24} {:<8} {:<8} {:<28} Events",
+        "Name", "Enabled", "Signed", "Target"
+    );
+    println!("  {}", "-".repeat(80));
+    for hook in hooks {
+        let target = match &hook.target {
+            HookTarget::Agent { skill: Some(skill) } => format!("agent (skill: {})", skill),
+            HookTarget::Agent { skill: None } => "agent".to_string(),
+            HookTarget::Tool { name, .. } => format!("tool: {}", name),
+        };
+        println!(
+            "  {:<24} {:<8} {:<8} {:<28} {}",
+            hook.name,
+            if hook.enabled { "✓" } else { "✗" },
+            if hook.secret.is_some() { "✓" } else { "-" },
+            target,
+            if hook.events.is_empty() {
+                "*".to_string()
+            } else {
+                hook.events.join(",")
+            }
+        );
+    }
+    println!();
+    println!("  Trigger: POST {}/v1/hooks/<name>", api_base(&paths));
+    println!();
+    Ok(())
+}
+
+/// Add or replace a hook.
+pub async fn add(args: HookAddArgs) -> anyhow::Result<()> {
+    let target = match (&args.tool, &args.skill) {
+        (Some(_), Some(_)) => anyhow::bail!("Use either --tool or --skill, not both"),
+        (Some(tool), None) => {
+            if args.template.is_some() {
+                anyhow::bail!("--template applies to agent hooks; tool hooks use --params");
+            }
+            let params = match args.params.as_deref() {
+                Some(raw) => serde_json::from_str(raw)
+                    .map_err(|e| anyhow::anyhow!("--params is not valid JSON: {}", e))?,
+                None => serde_json::json!({}),
+            };
+            HookTarget::Tool {
+                name: tool.trim().to_string(),
+                params,
+            }
+        }
+        (None, skill) => {
+            if args.params.is_some() {
+                anyhow::bail!("--params needs --tool");
+            }
+            HookTarget::Agent {
+                skill: skill.clone().filter(|s| !s.trim().is_empty()),
+            }
+        }
+    };
+
+    let paths = Paths::default();
+    let mut store = HookStore::open(&paths)?;
+    let name = args.name.trim().to_string();
+    let hook = HookDef {
+        name: name.clone(),
+        enabled: !args.disabled,
+        description: args.description,
+        target,
+        template: args.template,
+        secret: args.secret.filter(|s| !s.trim().is_empty()),
+        signature_header: args.signature_header,
+        events: args.events,
+        agent: args.agent,
+        channel: args.channel,
+        chat_id: args.chat_id,
+        created_at: chrono::Utc::now().timestamp(),
+    };
+    let replaced = store.upsert(hook)?;
+    store.save()?;
+
+    println!(
+        "✓ {} hook '{}'. Trigger it with POST {}/v1/hooks/{}",
+        if replaced { "Updated" } else { "Added" },
+        name,
+        api_base(&paths),
+        name
+    );
+    Ok(())
+}
+
+/// Remove a hook.
+pub async fn remove(name: &str) -> anyhow::Result<()> {
+    let paths = Paths::default();
+    let mut store = HookStore::open(&paths)?;
+    if store.remove(name) {
+        store.save()?;
+        println!("✓ Removed hook '{}'", name);
+    } else {
+        println!("Hook '{}' not found", name);
+    }
+    Ok(())
+}
+
+fn api_base(paths: &Paths) -> String {
+    let config = Config::load_or_default(paths).unwrap_or_default();
+    match config.gateway.public_api_base.as_deref().map(str::trim) {
+        Some(base) if !base.is_empty() => base.trim_end_matches('/').to_string(),
+        _ => format!(
+            "http://{}:{}{}",
+            config.gateway.host,
+            config.gateway.port,
+            config.gateway.normalized_base_path()
+        ),
+    }
+}
@@ -1,5 +1,6 @@
 use blockcell_core::{Config, Paths};
 use blockcell_storage::memory::QueryParams;
+use blockcell_storage::redaction::{Findings, Redactor};
 use blockcell_storage::MemoryStore;
 
 use super::memory_store::open_memory_store;
@@ -191,6 +192,115 @@ pub async fn stats() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Scan stored memory items (and optionally audit logs) for PII that slipped through.
+pub async fn audit_pii(include_audit_logs: bool, limit: usize) -> anyhow::Result<()> {
+    let paths = Paths::default();
+    let config = Config::load_or_default(&paths)?;
+    let redactor = Redactor::new(&config.security.redaction);
+    let db_path = paths.workspace().join("memory").join("memory.db");
+
+    println!();
+    println!("🔍 PII audit");
+    if db_path.exists() {
+        let store = open_memory_store(&paths, &config)?;
+        let ids = store
+            .list_ids(false)
+            .map_err(|e| anyhow::anyhow!("Failed to list memory items: {}", e))?;
+        let mut totals: Findings = Findings::new();
+        let mut flagged = 0usize;
+        for id in &ids {
+            let Some(item) = store
+                .get_by_id(id)
+                .map_err(|e| anyhow::anyhow!("Failed to load {}: {}", id, e))?
+            else {
+                continue;
+            };
+            let text = format!(
+                "{}\n{}\n{}",
+                item.title.as_deref().unwrap_or(""),
+                item.content,
+                item.summary.as_deref().unwrap_or("")
+            );
+            let findings = redactor.scan(&text);
+            if findings.is_empty() {
+                continue;
+            }
+            flagged += 1;
+            if flagged <= limit {
+                println!(
+                    "  #{} [{}] {}",
+                    item.id.chars().take(8).collect::<String>(),
+                    item.item_type,
+                    format_findings(&findings)
+                );
+            }
+            for (name, n) in findings {
+                *totals.entry(name).or_default() += n;
+            }
+        }
+        if flagged > limit {
+            println!("  ... {} more", flagged - limit);
+        }
+        println!(
+            "  Memory: {} of {} items contain possible PII {}",
+            flagged,
+            ids.len(),
+            if totals.is_empty() {
+                String::new()
+            } else {
+                format!("({})", format_findings(&totals))
+            }
+        );
+    } else {
+        println!("  (Memory database not created yet)");
+    }
+
+    if include_audit_logs {
+        let mut totals: Findings = Findings::new();
+        let mut files = 0usize;
+        if let Ok(entries) = std::fs::read_dir(paths.audit_dir()) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+                    continue;
+                }
+                let Ok(content) = std::fs::read_to_string(&path) else {
+                    continue;
+                };
+                let findings = redactor.scan(&content);
+                if findings.is_empty() {
+                    continue;
+                }
+                files += 1;
+                println!(
+                    "  {} {}",
+                    path.file_name().unwrap_or_default().to_string_lossy(),
+                    format_findings(&findings)
+                );
+                for (name, n) in findings {
+                    *totals.entry(name).or_default() += n;
+                }
+            }
+        }
+        println!("  Audit logs: {} files contain possible PII", files);
+    }
+
+    if !config.security.redaction.enabled {
+        println!();
+        println!("  Redaction is off. Enable security.redaction to mask new writes.");
+    }
+    println!();
+    Ok(())
+}
+
+fn format_findings(findings: &Findings) -> String {
+    findings
+        .iter()
+        .map(|(name, n)| format!("{}×{}", name, n))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// Search memory items.
 pub async fn search(
     query: &str,
@@ -363,3 +473,142 @@ pub async fn clear(scope: Option<String>) -> anyhow::Result<()> {
     println!("   Memories moved to recycle bin. Use `maintenance` to permanently purge.");
     Ok(())
 }
+
+/// Read the archive text: the file itself, or `result.json` / the first `.txt` in a zip.
+fn read_archive(path: &std::path::Path) -> anyhow::Result<(String, String)> {
+    use std::io::Read;
+
+    let is_zip = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+    if !is_zip {
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default()
+            .to_string();
+        return Ok((name, std::fs::read_to_string(path)?));
+    }
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(path)?)?;
+    let names: Vec<String> = archive.file_names().map(str::to_string).collect();
+    let name = names
+        .iter()
+        .find(|n| n.ends_with("result.json"))
+        .or_else(|| names.iter().find(|n| n.ends_with(".txt")))
+        .ok_or_else(|| anyhow::anyhow!("No result.json or .txt chat export in the archive"))?
+        .clone();
+    let mut content = String::new();
+    archive.by_name(&name)?.read_to_string(&mut content)?;
+    let base = name.rsplit('/').next().unwrap_or(&name).to_string();
+    Ok((base, content))
+}
+
+/// Ingest an exported chat archive: extract stable facts/preferences via the LLM in
+/// batches and store them as long-term memory with the chat and date range as provenance.
+pub async fn ingest(
+    path: &str,
+    format: &str,
+    chat_name: Option<String>,
+    batch_chars: usize,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    use blockcell_core::types::ChatMessage;
+    use blockcell_storage::chat_archive::{self, ArchiveFormat};
+
+    let input = std::path::Path::new(path);
+    if !input.is_file() {
+        anyhow::bail!("File not found: {}", path);
+    }
+    let (file_name, content) = read_archive(input)?;
+    let format = match format.trim().to_lowercase().as_str() {
+        "auto" if file_name.ends_with(".json") => ArchiveFormat::Telegram,
+        "auto" => ArchiveFormat::WhatsApp,
+        other => ArchiveFormat::parse(other)
+            .ok_or_else(|| anyhow::anyhow!("Unknown format '{}' (telegram, whatsapp)", other))?,
+    };
+    let archives = match format {
+        ArchiveFormat::Telegram => chat_archive::parse_telegram(&serde_json::from_str(&content)?)?,
+        ArchiveFormat::WhatsApp => {
+            let name = chat_name.unwrap_or_else(|| {
+                file_name
+                    .trim_end_matches(".txt")
+                    .trim_start_matches("WhatsApp Chat with ")
+                    .trim_start_matches("WhatsApp Chat - ")
+                    .to_string()
+            });
+            vec![chat_archive::parse_whatsapp(&content, &name)]
+        }
+    };
+
+    let batch_chars = batch_chars.max(1000);
+    let plan: Vec<_> = archives
+        .iter()
+        .map(|a| (a, chat_archive::batches(a, batch_chars)))
+        .collect();
+    let total_batches: usize = plan.iter().map(|(_, b)| b.len()).sum();
+
+    println!();
+    for (archive, batches) in &plan {
+        println!(
+            "💬 {} \"{}\": {} messages, {} batches",
+            format.label(),
+            archive.chat_name,
+            archive.messages.len(),
+            batches.len()
+        );
+    }
+    if dry_run || total_batches == 0 {
+        println!();
+        if dry_run {
+            println!("Dry run: no LLM calls made, nothing written.");
+        }
+        return Ok(());
+    }
+
+    let paths = Paths::default();
+    let config = Config::load_or_default(&paths)?;
+    let provider = super::provider::create_provider(&config)?;
+    let store = open_memory_store(&paths, &config)?;
+
+    let (mut done, mut saved, mut failed) = (0usize, 0usize, 0usize);
+    for (archive, batches) in &plan {
+        for batch in batches {
+            done += 1;
+            let messages = [
+                ChatMessage::system(chat_archive::EXTRACTION_SYSTEM_PROMPT),
+                ChatMessage::user(&chat_archive::extraction_prompt(archive, batch)),
+            ];
+            let reply = match provider.chat(&messages, &[]).await {
+                Ok(response) => response.content.unwrap_or_default(),
+                Err(e) => {
+                    failed += 1;
+                    eprintln!("  ⚠ Batch {}/{} failed: {}", done, total_batches, e);
+                    continue;
+                }
+            };
+            let items = chat_archive::parse_extracted(&reply);
+            for item in &items {
+                match store.upsert(chat_archive::memory_params(archive, batch, item)) {
+                    Ok(_) => saved += 1,
+                    Err(e) => eprintln!("  ⚠ Failed to save memory: {}", e),
+                }
+            }
+            println!(
+                "  [{}/{}] {} – {}: {} items",
+                done,
+                total_batches,
+                batch.first_timestamp,
+                batch.last_timestamp,
+                items.len()
+            );
+        }
+    }
+
+    println!();
+    println!(
+        "✅ Saved {} facts/preferences ({} batches failed)",
+        saved, failed
+    );
+    println!("   Review with `blockcell memory list --type fact` or `--type preference`.");
+    Ok(())
+}
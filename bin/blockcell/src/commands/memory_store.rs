@@ -5,11 +5,13 @@ use anyhow::Context;
 use blockcell_core::{Config, Paths};
 use blockcell_providers::create_embedder;
 use blockcell_storage::rabitq_index::RabitqIndex;
+use blockcell_storage::redaction::Redactor;
 use blockcell_storage::vector::VectorRuntime;
 use blockcell_storage::{MemoryStore, MemoryStoreOptions};
 use tracing::warn;
 
 pub fn open_memory_store(paths: &Paths, config: &Config) -> anyhow::Result<MemoryStore> {
+    super::storage_key::unlock(config)?;
     let memory_db_path = paths.memory_dir().join("memory.db");
     let vector = match build_vector_runtime(paths, config) {
         Ok(vector) => vector,
@@ -23,7 +25,8 @@ pub fn open_memory_store(paths: &Paths, config: &Config) -> anyhow::Result<Memor
         }
     };
 
-    MemoryStore::open_with_options(&memory_db_path, MemoryStoreOptions { vector })
+    let redactor = Some(Arc::new(Redactor::new(&config.security.redaction)));
+    MemoryStore::open_with_options(&memory_db_path, MemoryStoreOptions { vector, redactor })
         .map_err(|error| anyhow::anyhow!("Failed to open memory db: {}", error))
 }
 
@@ -1,25 +1,39 @@
 pub mod agent;
 pub mod alerts_cmd;
+pub mod auth_cmd;
+pub mod bench_cmd;
 pub mod channels;
 pub mod completions_cmd;
 pub mod config_cmd;
 pub mod cron;
+pub mod debug_cmd;
+pub mod docs_cmd;
 pub mod doctor;
 pub mod embedded_skills;
 pub mod evolve;
 pub mod gateway;
+pub mod hooks_cmd;
 pub mod knowledge_cmd;
 pub mod logs_cmd;
 pub mod mcp;
 pub mod memory;
 pub mod memory_store;
 pub mod onboard;
+pub mod privacy_cmd;
+pub mod prompt_cmd;
 pub mod provider;
+pub mod replay_cmd;
 pub mod run_cmd;
+pub mod secrets_cmd;
+pub mod selftest_cmd;
+pub mod sessions_cmd;
 pub mod setup;
 pub mod skills;
 pub mod slash_commands;
 pub mod status;
+pub mod storage_key;
 pub mod streams_cmd;
+pub mod team_memory_sync;
+pub mod telemetry_cmd;
 pub mod tools_cmd;
 pub mod upgrade;
@@ -0,0 +1,112 @@
+use blockcell_core::{Config, Paths};
+use blockcell_storage::privacy::{
+    enforce_retention, purge_sender, save_report, signing_key, AuditMode, PurgeReport, PurgeRequest,
+};
+use blockcell_storage::MemoryStore;
+
+use super::memory_store::open_memory_store;
+
+fn agent_paths(paths: &Paths, config: &Config, agent: Option<&str>) -> anyhow::Result<Paths> {
+    let agent_id = agent
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .unwrap_or("default");
+    if !config.agent_exists(agent_id) {
+        anyhow::bail!("Unknown agent '{}'", agent_id);
+    }
+    Ok(paths.for_agent(agent_id))
+}
+
+fn open_store_if_present(paths: &Paths, config: &Config) -> anyhow::Result<Option<MemoryStore>> {
+    if !paths.memory_dir().join("memory.db").exists() {
+        return Ok(None);
+    }
+    open_memory_store(paths, config).map(Some)
+}
+
+/// Purge everything tied to a sender and write a signed report.
+pub async fn purge(
+    sender: &str,
+    channel: Option<String>,
+    agent: Option<&str>,
+    audit_mode: Option<&str>,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    let paths = Paths::default();
+    let config = Config::load_or_default(&paths)?;
+    let agent_paths = agent_paths(&paths, &config, agent)?;
+    let store = open_store_if_present(&agent_paths, &config)?;
+
+    let mut req = PurgeRequest::new(sender, &config.security.privacy)?;
+    req.channel = channel.filter(|c| !c.trim().is_empty());
+    req.dry_run = dry_run;
+    if let Some(mode) = audit_mode {
+        req.audit_mode = AuditMode::parse(mode)?;
+    }
+
+    let mut report = purge_sender(&agent_paths, store.as_ref(), &req)?;
+    report.sign(&signing_key(&paths)?)?;
+
+    println!();
+    if dry_run {
+        println!("🔍 Dry run — nothing was changed");
+    } else {
+        println!("🧹 Purge complete");
+    }
+    println!("  Sender:         {}", report.sender);
+    println!("  Sessions:       {}", report.sessions.len());
+    println!("  Messages:       {}", report.messages);
+    println!("  Memory items:   {}", report.memory_items);
+    println!(
+        "  Audit entries:  {} ({})",
+        report.audit_entries,
+        match report.audit_mode {
+            AuditMode::Anonymize => "anonymized",
+            AuditMode::Delete => "deleted",
+        }
+    );
+    println!("  Artifacts:      {}", report.artifacts);
+    for err in &report.errors {
+        println!("  ⚠ {}", err);
+    }
+    if !dry_run {
+        let path = save_report(&agent_paths, &report)?;
+        println!("  Report:         {}", path.display());
+    }
+    println!();
+    Ok(())
+}
+
+/// Check a purge report's signature against this install's signing key.
+pub async fn verify(report_path: &str) -> anyhow::Result<()> {
+    let paths = Paths::default();
+    let content = std::fs::read_to_string(report_path)?;
+    let report: PurgeReport = serde_json::from_str(&content)?;
+    if report.verify(&signing_key(&paths)?) {
+        println!("✅ Signature valid (report {})", report.report_id);
+        Ok(())
+    } else {
+        anyhow::bail!(
+            "Signature invalid or missing for report {}",
+            report.report_id
+        )
+    }
+}
+
+/// Apply the configured per-channel retention windows now.
+pub async fn retention(agent: Option<&str>) -> anyhow::Result<()> {
+    let paths = Paths::default();
+    let config = Config::load_or_default(&paths)?;
+    if config.security.privacy.retention_days.is_empty() {
+        println!("(No retention windows configured in security.privacy.retentionDays)");
+        return Ok(());
+    }
+    let agent_paths = agent_paths(&paths, &config, agent)?;
+    let store = open_store_if_present(&agent_paths, &config)?;
+    let report = enforce_retention(&agent_paths, store.as_ref(), &config.security.privacy)?;
+    println!(
+        "🗑 Removed {} session(s), {} memory item(s), {} audit entr(ies), {} artifact(s)",
+        report.sessions, report.memory_items, report.audit_entries, report.artifacts
+    );
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use blockcell_agent::prompt_fragments::load_fragments;
+use blockcell_agent::ContextBuilder;
+use blockcell_core::{Config, Paths};
+
+/// List the `prompt.d/` fragments, or print the assembled system prompt with `effective`.
+pub async fn show(effective: bool, agent: Option<&str>) -> anyhow::Result<()> {
+    let paths = Paths::default();
+    let config = Config::load_or_default(&paths)?;
+    let agent_id = agent
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .unwrap_or("default");
+    if !config.agent_exists(agent_id) {
+        anyhow::bail!("Unknown agent '{}'", agent_id);
+    }
+    let agent_paths = paths.for_agent(agent_id);
+
+    if effective {
+        // Tool list, memory brief and active skill are filled in per message.
+        let builder = ContextBuilder::new(agent_paths, config);
+        println!("{}", builder.build_system_prompt());
+        return Ok(());
+    }
+
+    let dir = agent_paths.prompt_d_dir();
+    let fragments = load_fragments(&dir);
+    println!();
+    println!("📝 Prompt fragments in {}", dir.display());
+    println!();
+    if fragments.is_empty() {
+        println!("  (none — add ordered *.md files such as 10-identity.md, 20-safety.md)");
+    }
+    for fragment in &fragments {
+        let file = fragment
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let note = if fragment.is_identity() {
+            "  (replaces the built-in identity)"
+        } else {
+            ""
+        };
+        println!(
+            "  {:<28} {:>6} chars{}",
+            file,
+            fragment.content.chars().count(),
+            note
+        );
+    }
+    println!();
+    println!("Run `blockcell prompt show --effective` to print the assembled prompt.");
+    println!();
+    Ok(())
+}
@@ -8,3 +8,9 @@ pub fn create_provider(config: &Config) -> anyhow::Result<Box<dyn Provider>> {
 pub fn create_evolution_provider(config: &Config) -> anyhow::Result<Box<dyn Provider>> {
     blockcell_providers::create_evolution_provider(config)
 }
+
+pub fn create_evolution_audit_provider(
+    config: &Config,
+) -> anyhow::Result<Option<Box<dyn Provider>>> {
+    blockcell_providers::create_evolution_audit_provider(config)
+}
@@ -0,0 +1,117 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use blockcell_agent::replay::{DivergenceLog, ReplayProvider, ReplayTools, TurnRecording};
+use blockcell_agent::{AgentRuntime, TaskManager};
+use blockcell_core::{Config, Paths};
+use blockcell_providers::ProviderPool;
+use blockcell_storage::SessionStore;
+use blockcell_tools::build_tool_registry_for_agent_config;
+use blockcell_tools::mcp::manager::McpManager;
+
+fn resolve_bundle(paths: &Paths, bundle: &str) -> PathBuf {
+    let direct = PathBuf::from(bundle);
+    if direct.exists() {
+        return direct;
+    }
+    let in_dir = paths.recordings_dir().join(bundle);
+    if in_dir.exists() {
+        in_dir
+    } else {
+        paths.recordings_dir().join(format!("{}.json", bundle))
+    }
+}
+
+/// List saved turn recordings, newest first.
+pub async fn list(limit: usize) -> anyhow::Result<()> {
+    let paths = Paths::default();
+    let mut files: Vec<PathBuf> = std::fs::read_dir(paths.recordings_dir())
+        .map(|rd| {
+            rd.filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+                .collect()
+        })
+        .unwrap_or_default();
+    if files.is_empty() {
+        println!("(No recordings. Set agents.defaults.recordTurns = true to record turns.)");
+        return Ok(());
+    }
+    files.sort();
+    println!();
+    for path in files.iter().rev().take(limit) {
+        let name = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+        match TurnRecording::load(path) {
+            Ok(rec) => println!(
+                "  {:<48} {:>2} LLM {:>2} tools  {}",
+                name,
+                rec.llm_calls.len(),
+                rec.tool_calls.len(),
+                rec.inbound.content.chars().take(50).collect::<String>()
+            ),
+            Err(e) => println!("  {:<48} ⚠ {}", name, e),
+        }
+    }
+    println!();
+    Ok(())
+}
+
+/// Re-run a recorded turn against recorded model responses and tool results.
+pub async fn run(bundle: &str, check: bool) -> anyhow::Result<()> {
+    let paths = Paths::default();
+    let path = resolve_bundle(&paths, bundle);
+    let recording = TurnRecording::load(&path)
+        .map_err(|e| anyhow::anyhow!("Cannot load {}: {}", path.display(), e))?;
+    let mut config = Config::load_or_default(&paths)?;
+    config.agents.defaults.record_turns = false;
+
+    let log = DivergenceLog::default();
+    let provider = ReplayProvider::new(recording.llm_calls.clone(), log.clone());
+    let pool = ProviderPool::from_single_provider(&recording.model, "replay", Arc::new(provider));
+    let mcp_manager = Arc::new(McpManager::load(&paths).await?);
+    let tool_registry = build_tool_registry_for_agent_config(&config, Some(&mcp_manager)).await?;
+    let mut runtime = AgentRuntime::new(config, paths.clone(), pool, tool_registry)?;
+    runtime.set_task_manager(TaskManager::new());
+    runtime.set_replay_tools(ReplayTools::new(recording.tool_calls.clone(), log.clone()));
+
+    // Replay into a throwaway session seeded with the recorded history.
+    let mut inbound = recording.inbound.clone();
+    inbound.chat_id = format!("replay-{}", &uuid::Uuid::new_v4().to_string()[..8]);
+    let session_key = inbound.session_key();
+    let sessions = SessionStore::new(paths.clone());
+    sessions.save(&session_key, &recording.history)?;
+    let result = runtime.process_message(inbound).await;
+    let _ = sessions.clear(&session_key);
+    let response = result?;
+
+    println!();
+    println!(
+        "🔁 Replayed {} ({} LLM calls, {} tool calls, recorded {})",
+        path.display(),
+        recording.llm_calls.len(),
+        recording.tool_calls.len(),
+        recording.recorded_at
+    );
+    let divergences = log.lock().map(|d| d.clone()).unwrap_or_default();
+    let response_changed = response.trim() != recording.final_response.trim();
+    if divergences.is_empty() && !response_changed {
+        println!("  ✓ Replay matched the recording");
+    }
+    for divergence in &divergences {
+        println!("  ✗ {}", divergence);
+    }
+    if response_changed {
+        println!("  ✗ Final response differs");
+        println!("    recorded: {}", recording.final_response.trim());
+        println!("    replayed: {}", response.trim());
+    }
+    println!();
+
+    if check && (response_changed || !divergences.is_empty()) {
+        anyhow::bail!(
+            "Replay diverged from the recording ({} differences)",
+            divergences.len() + usize::from(response_changed)
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use blockcell_core::secrets::SecretStore;
+use blockcell_core::Paths;
+use std::io::{BufRead, IsTerminal};
+
+/// List stored secret names.
+pub async fn list() -> anyhow::Result<()> {
+    let paths = Paths::default();
+    let store = SecretStore::open(&paths)?;
+    let names = store.names();
+
+    if names.is_empty() {
+        println!("(No secrets. Use `blockcell secrets set <name>` to add one.)");
+        return Ok(());
+    }
+
+    println!();
+    println!(
+        "🔑 Secrets ({} total) — {}",
+        names.len(),
+        paths.secrets_file().display()
+    );
+    println!();
+    for name in names {
+        println!("  secret:{}", name);
+    }
+    println!();
+    Ok(())
+}
+
+/// Store a secret. Reads the value from stdin when not given on the command line,
+/// so it does not end up in shell history.
+pub async fn set(name: &str, value: Option<String>) -> anyhow::Result<()> {
+    let value = match value {
+        Some(v) => v,
+        None => {
+            if std::io::stdin().is_terminal() {
+                eprint!("Value for '{}': ", name);
+            }
+            let mut line = String::new();
+            std::io::stdin().lock().read_line(&mut line)?;
+            line.trim_end_matches(['\r', '\n']).to_string()
+        }
+    };
+    if value.is_empty() {
+        anyhow::bail!("Secret value is empty");
+    }
+
+    let paths = Paths::default();
+    let mut store = SecretStore::open(&paths)?;
+    store.set(name, &value)?;
+    println!(
+        "✓ Saved secret '{}'. Reference it in config as \"secret:{}\".",
+        name, name
+    );
+    Ok(())
+}
+
+/// Remove a secret.
+pub async fn remove(name: &str) -> anyhow::Result<()> {
+    let paths = Paths::default();
+    let mut store = SecretStore::open(&paths)?;
+    if store.remove(name)? {
+        println!("✓ Removed secret '{}'", name);
+    } else {
+        println!("Secret '{}' not found", name);
+    }
+    Ok(())
+}
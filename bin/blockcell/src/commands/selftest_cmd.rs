@@ -0,0 +1,471 @@
+//! `blockcell selftest`: an offline end-to-end check for CI.
+//!
+//! The agent runtime is started in-process against a throwaway data directory, with the
+//! real config but a scripted provider in place of the LLM. Each scenario drives real
+//! tools (fs, memory, cron, and `web_fetch` against a loopback mock page) through the
+//! normal tool loop, then checks what the tools returned and wrote. The user's workspace
+//! skills are loaded and their Rhai scripts compiled. Nothing touches `~/.blockcell`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use blockcell_agent::{AgentRuntime, MemoryStoreAdapter, TaskManager};
+use blockcell_core::types::{ChatMessage, LLMResponse, ToolCallRequest};
+use blockcell_core::{Config, InboundMessage, Paths};
+use blockcell_providers::{Provider, ProviderPool};
+use blockcell_skills::SkillManager;
+use blockcell_tools::build_tool_registry_for_agent_config;
+use serde_json::{json, Value};
+
+use super::memory_store::open_memory_store;
+
+const SCENARIO_TIMEOUT: Duration = Duration::from_secs(60);
+const WEB_MARKER: &str = "blockcell-selftest-page";
+const MEMORY_MARKER: &str = "selftest-favourite-colour-teal";
+
+/// One scripted model turn.
+#[derive(Clone)]
+enum Step {
+    Call(&'static str, Value),
+    Reply(&'static str),
+}
+
+struct Scenario {
+    name: &'static str,
+    prompt: String,
+    steps: Vec<Step>,
+}
+
+fn marker(name: &str) -> String {
+    format!("[selftest:{}]", name)
+}
+
+fn scenarios(web_url: &str) -> Vec<Scenario> {
+    let scenario = |name: &'static str, prompt: &str, steps: Vec<Step>| Scenario {
+        name,
+        prompt: format!("{} {}", marker(name), prompt),
+        steps,
+    };
+    vec![
+        scenario(
+            "fs",
+            "Write a note to selftest/hello.txt and read it back.",
+            vec![
+                Step::Call(
+                    "write_file",
+                    json!({"path": "selftest/hello.txt", "content": "hello from selftest"}),
+                ),
+                Step::Call("read_file", json!({"path": "selftest/hello.txt"})),
+                Step::Reply("The note was written and read back."),
+            ],
+        ),
+        scenario(
+            "memory",
+            "Remember that my favourite colour is teal, then recall it.",
+            vec![
+                Step::Call(
+                    "memory_upsert",
+                    json!({
+                        "content": format!("Favourite colour is teal ({})", MEMORY_MARKER),
+                        "scope": "long_term",
+                        "type": "preference",
+                        "title": "Favourite colour"
+                    }),
+                ),
+                Step::Call("memory_query", json!({"query": MEMORY_MARKER})),
+                Step::Reply("Your favourite colour is teal."),
+            ],
+        ),
+        scenario(
+            "cron",
+            "Remind me to stretch in an hour.",
+            vec![
+                Step::Call(
+                    "cron",
+                    json!({
+                        "action": "add",
+                        "name": "selftest stretch",
+                        "message": "Time to stretch",
+                        "delay_seconds": 3600
+                    }),
+                ),
+                Step::Reply("Reminder scheduled."),
+            ],
+        ),
+        scenario(
+            "web",
+            "Fetch the test page.",
+            vec![
+                Step::Call("web_fetch", json!({"url": web_url})),
+                Step::Reply("The page was fetched."),
+            ],
+        ),
+    ]
+}
+
+fn message_text(msg: &ChatMessage) -> String {
+    match &msg.content {
+        Value::String(s) => s.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|p| p.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// Replays each scenario's steps; anything without a scenario marker (summaries, memory
+/// extraction, ...) gets a plain "OK".
+struct ScriptedProvider {
+    scripts: Vec<(String, Vec<Step>)>,
+    /// Tool results seen so far, per scenario.
+    tool_results: Arc<Mutex<HashMap<String, Vec<String>>>>,
+}
+
+impl ScriptedProvider {
+    fn next_response(&self, messages: &[ChatMessage]) -> LLMResponse {
+        let usage = json!({"prompt_tokens": 0, "completion_tokens": 0});
+        let reply = |text: &str| LLMResponse {
+            content: Some(text.to_string()),
+            finish_reason: "stop".to_string(),
+            usage: usage.clone(),
+            ..Default::default()
+        };
+
+        let found = messages.iter().enumerate().rev().find_map(|(idx, m)| {
+            if m.role != "user" {
+                return None;
+            }
+            let text = message_text(m);
+            self.scripts
+                .iter()
+                .find(|(marker, _)| text.contains(marker.as_str()))
+                .map(|script| (idx, script))
+        });
+        let Some((start, (marker, steps))) = found else {
+            return reply("OK");
+        };
+
+        let after = &messages[start + 1..];
+        let results: Vec<String> = after
+            .iter()
+            .filter(|m| m.role == "tool")
+            .map(message_text)
+            .collect();
+        if let Ok(mut seen) = self.tool_results.lock() {
+            seen.insert(marker.clone(), results);
+        }
+
+        let turn = after
+            .iter()
+            .filter(|m| {
+                m.role == "assistant" && m.tool_calls.as_ref().is_some_and(|c| !c.is_empty())
+            })
+            .count();
+        match steps.get(turn) {
+            Some(Step::Call(name, arguments)) => LLMResponse {
+                tool_calls: vec![ToolCallRequest {
+                    id: format!("selftest-{}", turn),
+                    name: name.to_string(),
+                    arguments: arguments.clone(),
+                    thought_signature: None,
+                }],
+                finish_reason: "tool_calls".to_string(),
+                usage: usage.clone(),
+                ..Default::default()
+            },
+            Some(Step::Reply(text)) => reply(text),
+            None => reply("Done."),
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for ScriptedProvider {
+    async fn chat(
+        &self,
+        messages: &[ChatMessage],
+        _tools: &[Value],
+    ) -> blockcell_core::Result<LLMResponse> {
+        Ok(self.next_response(messages))
+    }
+}
+
+struct Check {
+    name: String,
+    result: Result<(), String>,
+}
+
+impl Check {
+    fn new(name: impl Into<String>, result: Result<(), String>) -> Self {
+        Self {
+            name: name.into(),
+            result,
+        }
+    }
+}
+
+/// Serve a fixed HTML page on a loopback port for the web scenario.
+async fn start_mock_web() -> anyhow::Result<String> {
+    let page = format!(
+        "<html><head><title>Selftest</title></head><body><p>{}</p></body></html>",
+        WEB_MARKER
+    );
+    let app = axum::Router::new().route(
+        "/page",
+        axum::routing::get(move || {
+            let page = page.clone();
+            async move { axum::response::Html(page) }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, app).await;
+    });
+    Ok(format!("http://{}/page", addr))
+}
+
+fn expect_result(results: &[String], idx: usize, needle: &str) -> Result<(), String> {
+    let result = results
+        .get(idx)
+        .ok_or_else(|| format!("tool call #{} produced no result", idx + 1))?;
+    if result.contains(needle) {
+        Ok(())
+    } else {
+        Err(format!(
+            "tool result does not contain \"{}\": {}",
+            needle,
+            result.chars().take(200).collect::<String>()
+        ))
+    }
+}
+
+/// Check the side effects a scenario should have left behind.
+fn verify(name: &str, paths: &Paths, results: &[String]) -> Result<(), String> {
+    match name {
+        "fs" => {
+            let written = std::fs::read_to_string(paths.workspace().join("selftest/hello.txt"))
+                .map_err(|e| format!("selftest/hello.txt not written: {}", e))?;
+            if written != "hello from selftest" {
+                return Err(format!("unexpected file content: {:?}", written));
+            }
+            expect_result(results, 1, "hello from selftest")
+        }
+        "memory" => expect_result(results, 1, MEMORY_MARKER),
+        "cron" => {
+            let jobs = std::fs::read_to_string(paths.cron_jobs_file())
+                .map_err(|e| format!("no cron jobs file: {}", e))?;
+            if jobs.contains("selftest stretch") {
+                Ok(())
+            } else {
+                Err("job was not saved".to_string())
+            }
+        }
+        "web" => expect_result(results, 0, WEB_MARKER),
+        _ => Ok(()),
+    }
+}
+
+/// Load the user's skills and compile their Rhai scripts.
+fn check_skills(paths: &Paths) -> Vec<Check> {
+    let mut manager = SkillManager::new();
+    if let Err(e) = manager.load_from_paths(paths) {
+        return vec![Check::new("skills", Err(e.to_string()))];
+    }
+    let Ok(entries) = std::fs::read_dir(paths.skills_dir()) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| is_skill_dir(p))
+        .collect();
+    dirs.sort();
+
+    let engine = rhai::Engine::new();
+    dirs.iter()
+        .filter_map(|dir| dir.file_name().and_then(|n| n.to_str()).map(str::to_string))
+        .map(|name| {
+            let result = match manager.get(&name) {
+                None => Err("failed to load".to_string()),
+                Some(skill) if !skill.available => Err(skill
+                    .unavailable_reason
+                    .clone()
+                    .unwrap_or_else(|| "unavailable".to_string())),
+                Some(skill) => match skill.load_rhai() {
+                    Some(script) => engine
+                        .compile(&script)
+                        .map(|_| ())
+                        .map_err(|e| format!("SKILL.rhai: {}", e)),
+                    None => Ok(()),
+                },
+            };
+            Check::new(format!("skill {}", name), result)
+        })
+        .collect()
+}
+
+fn is_skill_dir(path: &Path) -> bool {
+    path.is_dir()
+        && ["SKILL.md", "SKILL.rhai", "meta.yaml", "meta.json"]
+            .iter()
+            .any(|f| path.join(f).exists())
+}
+
+async fn run_scenarios(config: &Config, paths: &Paths) -> anyhow::Result<Vec<Check>> {
+    let web_url = start_mock_web().await?;
+    let scenarios = scenarios(&web_url);
+    let tool_results = Arc::new(Mutex::new(HashMap::new()));
+    let provider = Arc::new(ScriptedProvider {
+        scripts: scenarios
+            .iter()
+            .map(|s| (marker(s.name), s.steps.clone()))
+            .collect(),
+        tool_results: tool_results.clone(),
+    });
+    let pool = ProviderPool::from_single_provider("selftest-mock", "selftest", provider);
+
+    let tool_registry = build_tool_registry_for_agent_config(config, None).await?;
+    let mut runtime = AgentRuntime::new(config.clone(), paths.clone(), pool, tool_registry)?;
+    runtime.set_task_manager(TaskManager::new());
+    let store = open_memory_store(paths, config)?;
+    runtime.set_memory_store(Arc::new(MemoryStoreAdapter::new(store)));
+
+    let mut checks = vec![Check::new(
+        "intent router",
+        runtime.validate_intent_router().map_err(|e| e.to_string()),
+    )];
+    for scenario in &scenarios {
+        let inbound = InboundMessage {
+            channel: "cli".to_string(),
+            account_id: None,
+            sender_id: "selftest".to_string(),
+            chat_id: format!("selftest-{}", scenario.name),
+            content: scenario.prompt.clone(),
+            media: vec![],
+            metadata: Value::Null,
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+        };
+        let outcome =
+            tokio::time::timeout(SCENARIO_TIMEOUT, runtime.process_message(inbound)).await;
+        let result = match outcome {
+            Err(_) => Err("timed out".to_string()),
+            Ok(Err(e)) => Err(e.to_string()),
+            Ok(Ok(_)) => {
+                let results = tool_results
+                    .lock()
+                    .ok()
+                    .and_then(|seen| seen.get(&marker(scenario.name)).cloned())
+                    .unwrap_or_default();
+                verify(scenario.name, paths, &results)
+            }
+        };
+        checks.push(Check::new(format!("scenario {}", scenario.name), result));
+    }
+    Ok(checks)
+}
+
+/// Run the self-test; fails (non-zero exit) when any check fails.
+pub async fn run(keep: bool) -> anyhow::Result<()> {
+    let real_paths = Paths::default();
+    let mut checks = Vec::new();
+
+    let config = match Config::load_or_default(&real_paths) {
+        Ok(config) => {
+            checks.push(Check::new("config", Ok(())));
+            config
+        }
+        Err(e) => {
+            checks.push(Check::new("config", Err(e.to_string())));
+            Config::default()
+        }
+    };
+    checks.extend(check_skills(&real_paths));
+
+    let base = std::env::temp_dir().join(format!("blockcell-selftest-{}", uuid::Uuid::new_v4()));
+    let paths = Paths::with_base(base.clone());
+    paths.ensure_dirs()?;
+    let mut test_config = config.clone();
+    // The web scenario fetches from a loopback mock server.
+    test_config.tools.egress.enabled = false;
+    match run_scenarios(&test_config, &paths).await {
+        Ok(results) => checks.extend(results),
+        Err(e) => checks.push(Check::new("runtime", Err(e.to_string()))),
+    }
+
+    println!();
+    println!("🧪 blockcell selftest");
+    for check in &checks {
+        match &check.result {
+            Ok(()) => println!("  ✓ {}", check.name),
+            Err(e) => println!("  ✗ {}: {}", check.name, e),
+        }
+    }
+    let failed = checks.iter().filter(|c| c.result.is_err()).count();
+    println!();
+    if keep {
+        println!("Data directory kept at {}", base.display());
+    } else {
+        let _ = std::fs::remove_dir_all(&base);
+    }
+    if failed > 0 {
+        anyhow::bail!("{} of {} self-test checks failed", failed, checks.len());
+    }
+    println!("All {} checks passed.", checks.len());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(web_url: &str) -> ScriptedProvider {
+        ScriptedProvider {
+            scripts: scenarios(web_url)
+                .into_iter()
+                .map(|s| (marker(s.name), s.steps))
+                .collect(),
+            tool_results: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    #[test]
+    fn test_scripted_provider_follows_steps() {
+        let provider = provider("http://127.0.0.1:1/page");
+        let mut messages = vec![
+            ChatMessage::system("system prompt"),
+            ChatMessage::user("[selftest:fs] Write a note"),
+        ];
+
+        let first = provider.next_response(&messages);
+        assert_eq!(first.tool_calls[0].name, "write_file");
+
+        let mut assistant = ChatMessage::assistant("");
+        assistant.tool_calls = Some(first.tool_calls.clone());
+        messages.push(assistant);
+        messages.push(ChatMessage::tool_result("selftest-0", "Wrote 19 bytes"));
+        let second = provider.next_response(&messages);
+        assert_eq!(second.tool_calls[0].name, "read_file");
+        assert_eq!(
+            provider.tool_results.lock().unwrap()["[selftest:fs]"],
+            vec!["Wrote 19 bytes".to_string()]
+        );
+
+        let unrelated = provider.next_response(&[ChatMessage::user("summarize this")]);
+        assert_eq!(unrelated.content.as_deref(), Some("OK"));
+        assert!(unrelated.tool_calls.is_empty());
+    }
+
+    #[test]
+    fn test_expect_result() {
+        let results = vec!["ok".to_string(), format!("found {}", MEMORY_MARKER)];
+        assert!(expect_result(&results, 1, MEMORY_MARKER).is_ok());
+        assert!(expect_result(&results, 0, MEMORY_MARKER).is_err());
+        assert!(expect_result(&results, 2, "anything").is_err());
+    }
+}
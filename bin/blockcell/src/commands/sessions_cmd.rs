@@ -0,0 +1,138 @@
+use std::io::Read;
+use std::path::Path;
+
+use blockcell_core::{Config, Paths};
+use blockcell_storage::chat_import::{self, ImportSource, ImportedConversation};
+use blockcell_storage::SessionStore;
+
+use super::memory_store::open_memory_store;
+
+/// Read `conversations.json` (or the first `.jsonl`) out of an export archive.
+fn read_from_zip(path: &Path) -> anyhow::Result<(String, String)> {
+    let file = std::fs::File::open(path)?;
+    let mut archive = zip::ZipArchive::new(file)?;
+    let names: Vec<String> = archive.file_names().map(str::to_string).collect();
+    let name = names
+        .iter()
+        .find(|n| n.rsplit('/').next() == Some("conversations.json"))
+        .or_else(|| names.iter().find(|n| n.ends_with(".jsonl")))
+        .ok_or_else(|| {
+            anyhow::anyhow!("No conversations.json or .jsonl file found in the archive")
+        })?
+        .clone();
+    let mut content = String::new();
+    archive.by_name(&name)?.read_to_string(&mut content)?;
+    Ok((name, content))
+}
+
+fn parse(
+    file_name: &str,
+    content: &str,
+    format: &str,
+) -> anyhow::Result<(ImportSource, Vec<ImportedConversation>)> {
+    let source = match format {
+        "auto" if file_name.ends_with(".jsonl") => ImportSource::Jsonl,
+        "auto" => {
+            let json: serde_json::Value = serde_json::from_str(content)?;
+            chat_import::detect(&json).ok_or_else(|| {
+                anyhow::anyhow!("Unrecognized export format; pass --format chatgpt|claude|jsonl")
+            })?
+        }
+        other => ImportSource::parse(other)
+            .ok_or_else(|| anyhow::anyhow!("Unknown format '{}'", other))?,
+    };
+    let conversations = match source {
+        ImportSource::ChatGpt => chat_import::parse_chatgpt(&serde_json::from_str(content)?)?,
+        ImportSource::Claude => chat_import::parse_claude(&serde_json::from_str(content)?)?,
+        ImportSource::Jsonl => {
+            let stem = Path::new(file_name)
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("transcript");
+            chat_import::parse_jsonl(content, stem)
+        }
+    };
+    Ok((source, conversations))
+}
+
+/// Import chat history from a ChatGPT/Claude export or a JSONL transcript.
+pub async fn import(path: &str, format: &str, memory: bool, dry_run: bool) -> anyhow::Result<()> {
+    let input = Path::new(path);
+    if !input.exists() {
+        anyhow::bail!("File not found: {}", path);
+    }
+    let format = format.trim().to_lowercase();
+
+    let (file_name, content) = if input.is_dir() {
+        let file = input.join("conversations.json");
+        (
+            "conversations.json".to_string(),
+            std::fs::read_to_string(&file)
+                .map_err(|e| anyhow::anyhow!("Cannot read {}: {}", file.display(), e))?,
+        )
+    } else if input
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+    {
+        read_from_zip(input)?
+    } else {
+        (path.to_string(), std::fs::read_to_string(input)?)
+    };
+
+    let (source, conversations) = parse(&file_name, &content, &format)?;
+    let message_count: usize = conversations.iter().map(|c| c.messages.len()).sum();
+
+    println!();
+    println!(
+        "📥 {}: {} conversations, {} messages",
+        source.label(),
+        conversations.len(),
+        message_count
+    );
+    if dry_run {
+        for conv in conversations.iter().take(20) {
+            println!(
+                "  {:<40} {:>4} msgs  → {}",
+                conv.title.as_deref().unwrap_or("(untitled)"),
+                conv.messages.len(),
+                chat_import::session_key(source, conv)
+            );
+        }
+        if conversations.len() > 20 {
+            println!("  ... and {} more", conversations.len() - 20);
+        }
+        println!();
+        println!("Dry run: nothing written.");
+        return Ok(());
+    }
+
+    let paths = Paths::default();
+    let sessions = SessionStore::new(paths.clone());
+    let mut written = 0;
+    for conv in &conversations {
+        match chat_import::write_session(&sessions, source, conv) {
+            Ok(_) => written += 1,
+            Err(e) => eprintln!("  ⚠ Failed to import '{}': {}", conv.id, e),
+        }
+    }
+    println!(
+        "  ✓ {} sessions written to {}",
+        written,
+        paths.sessions_dir().display()
+    );
+
+    if memory {
+        let config = Config::load_or_default(&paths)?;
+        let store = open_memory_store(&paths, &config)?;
+        let mut notes = 0;
+        for conv in &conversations {
+            match store.upsert(chat_import::memory_params(source, conv)) {
+                Ok(_) => notes += 1,
+                Err(e) => eprintln!("  ⚠ Failed to save memory for '{}': {}", conv.id, e),
+            }
+        }
+        println!("  ✓ {} memory notes saved", notes);
+    }
+    println!();
+    Ok(())
+}
@@ -184,6 +184,7 @@ fn configure_provider(
         output_price: None,
         temperature: None,
         tool_call_mode: ToolCallMode::Native,
+        group: None,
     }];
     config.agents.defaults.provider = Some(provider.to_string());
     config.agents.defaults.model = final_model;
@@ -4,6 +4,7 @@ use blockcell_core::{build_session_key, Config, InboundMessage, Paths};
 use blockcell_skills::evolution::EvolutionRecord;
 use blockcell_skills::is_builtin_tool;
 use blockcell_skills::SkillTestFixture;
+use blockcell_storage::skill_stats::SkillStatsStore;
 use blockcell_storage::SessionStore;
 use blockcell_tools::build_tool_registry_for_agent_config;
 use blockcell_tools::mcp::manager::McpManager;
@@ -290,7 +291,7 @@ async fn run_skill_fixtures(
         };
 
         println!(" {}", outcome);
-        let _ = std::fs::remove_file(runtime_paths.session_file(&session_key));
+        let _ = session_store.clear(&session_key);
     }
 
     Ok(summary)
@@ -318,7 +319,7 @@ pub async fn list(all: bool, enabled_only: bool) -> anyhow::Result<()> {
             }
         }
     }
-    records.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    records.sort_by_key(|r| std::cmp::Reverse(r.created_at));
 
     // Categorize: deduplicate by skill_name (keep latest record per skill)
     let mut seen = std::collections::HashSet::new();
@@ -516,7 +517,7 @@ pub async fn show(name: &str) -> anyhow::Result<()> {
             }
         }
     }
-    records.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    records.sort_by_key(|r| std::cmp::Reverse(r.created_at));
     if !records.is_empty() {
         println!();
         println!("  Evolution records ({}):", records.len());
@@ -605,6 +606,57 @@ pub async fn reload() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Show per-skill performance analytics.
+pub async fn stats(agent: Option<&str>, json: bool) -> anyhow::Result<()> {
+    let paths = Paths::default();
+    let config = Config::load_or_default(&paths)?;
+    let agent_id = agent
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .unwrap_or("default");
+    if !config.agent_exists(agent_id) {
+        anyhow::bail!("Unknown agent '{}'", agent_id);
+    }
+    let db = paths.for_agent(agent_id).skill_stats_db();
+    let stats = if db.exists() {
+        SkillStatsStore::open(&db)?.all()?
+    } else {
+        Vec::new()
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+    if stats.is_empty() {
+        println!("(No skill runs recorded yet)");
+        return Ok(());
+    }
+
+    println!();
+    println!(
+        "  {:<28} {:>7} {:>9} {:>12} {:>10} {:>10}  Flagged",
+        "Skill", "Calls", "Success", "Mean ms", "Tokens", "Tok/call"
+    );
+    for s in &stats {
+        println!(
+            "  {:<28} {:>7} {:>8.1}% {:>12.0} {:>10} {:>10.0}  {}",
+            s.skill,
+            s.calls,
+            s.success_rate * 100.0,
+            s.mean_latency_ms,
+            s.total_tokens,
+            s.mean_tokens,
+            s.flagged_at
+                .as_deref()
+                .map(|t| t.get(..10).unwrap_or(t))
+                .unwrap_or("-")
+        );
+    }
+    println!();
+    Ok(())
+}
+
 /// Clear all evolution records.
 pub async fn clear() -> anyhow::Result<()> {
     let paths = Paths::default();
@@ -9,7 +9,7 @@
 //! - WebSocket: `ws:{session_id}`
 //! - Telegram: `telegram:{chat_id}`
 //!
-//! 这个格式与 `SessionStore::session_file()` 的路径计算保持一致。
+//! `SessionStore` 以这个 key 的文件名形式（`:` 替换为 `_`）作为会话主键。
 
 use crate::commands::slash_commands::*;
 use blockcell_storage::SessionStore;
@@ -10,6 +10,7 @@ mod quit;
 mod session_metrics;
 mod skill_mgmt;
 mod skills;
+mod style;
 mod tasks;
 mod tools;
 
@@ -21,5 +22,6 @@ pub use quit::{ExitCommand, QuitCommand};
 pub use session_metrics::SessionMetricsCommand;
 pub use skill_mgmt::{ClearSkillsCommand, ForgetSkillCommand};
 pub use skills::SkillsCommand;
+pub use style::StyleCommand;
 pub use tasks::TasksCommand;
 pub use tools::ToolsCommand;
@@ -8,8 +8,31 @@
 //! - `/session-metrics --json` - JSON 格式输出
 //! - `/session-metrics --reset` - 重置计数器
 //! - `/session-metrics --layer N` - 只显示第 N 层 (N 为 1-7)
+//! - `/session-metrics --tools` - 显示工具 schema 裁剪指标
 
 use crate::commands::slash_commands::*;
+use blockcell_agent::tool_selection;
+
+/// 工具选择（`agents.defaults.toolSelection`）指标表
+fn format_tool_selection() -> String {
+    let summary = tool_selection::metrics().summary();
+    format!(
+        "## Tool Selection Metrics\n\n\
+         | Metric | Value |\n|---|---|\n\
+         | Pruned turns | {} |\n\
+         | Schemas sent | {} |\n\
+         | Schemas pruned | {} |\n\
+         | Calls to sent tools | {} |\n\
+         | Calls to pruned tools | {} |\n\
+         | Selection accuracy | {:.1}% |",
+        summary.pruned_turns,
+        summary.schemas_sent,
+        summary.schemas_pruned,
+        summary.calls_selected,
+        summary.calls_pruned,
+        summary.accuracy * 100.0
+    )
+}
 
 /// /session-metrics 命令 - 显示记忆系统监控指标
 pub struct SessionMetricsCommand;
@@ -44,7 +67,7 @@ impl SlashCommand for SessionMetricsCommand {
                 CommandResult::Handled(CommandResponse::markdown(content))
             }
             1 => {
-                // 1 个参数：只能是 --json 或 --reset
+                // 1 个参数：--json、--reset 或 --tools
                 match parts[0] {
                     "--json" | "-j" => {
                         let summary = blockcell_agent::session_metrics::get_metrics_summary();
@@ -56,13 +79,17 @@ impl SlashCommand for SessionMetricsCommand {
                     }
                     "--reset" | "-r" => {
                         blockcell_agent::session_metrics::reset_metrics();
+                        tool_selection::metrics().reset();
                         CommandResult::Handled(CommandResponse::markdown(
                             "✅ Metrics counters have been reset.".to_string()
                         ))
                     }
+                    "--tools" | "-t" => {
+                        CommandResult::Handled(CommandResponse::markdown(format_tool_selection()))
+                    }
                     _ => {
                         CommandResult::Handled(CommandResponse::markdown(
-                            format!("❌ 无效参数: `{}`\n\n用法:\n- `/session-metrics` - 显示所有层\n- `/session-metrics --json` - JSON 格式\n- `/session-metrics --reset` - 重置计数器\n- `/session-metrics --layer N` - 显示第 N 层 (1-7)", parts[0])
+                            format!("❌ 无效参数: `{}`\n\n用法:\n- `/session-metrics` - 显示所有层\n- `/session-metrics --json` - JSON 格式\n- `/session-metrics --reset` - 重置计数器\n- `/session-metrics --layer N` - 显示第 N 层 (1-7)\n- `/session-metrics --tools` - 工具 schema 裁剪指标", parts[0])
                         ))
                     }
                 }
@@ -136,6 +163,19 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_tools_arg() {
+        let ctx = CommandContext::test_context();
+        let cmd = SessionMetricsCommand;
+        let result = cmd.execute("--tools", &ctx).await;
+
+        if let CommandResult::Handled(response) = result {
+            assert!(response.content.contains("Selection accuracy"));
+        } else {
+            panic!("Expected Handled result");
+        }
+    }
+
     #[tokio::test]
     async fn test_reset_arg() {
         let ctx = CommandContext::test_context();
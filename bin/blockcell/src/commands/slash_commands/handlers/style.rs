@@ -0,0 +1,83 @@
+//! # /style 命令
+//!
+//! 设置当前会话的回复风格（阅读难度/语气/长度），由运行时在发送前改写回复。
+//!
+//! - `/style` 查看当前设置与可用预设
+//! - `/style kid` 使用预设
+//! - `/style 像体育解说员一样` 使用自定义描述
+//! - `/style off` 清除本会话设置，回到配置中的默认值
+
+use crate::commands::slash_commands::*;
+use blockcell_agent::reply_style::{load_overrides, set_chat_style, PRESETS};
+
+/// /style 命令 - 设置回复风格
+pub struct StyleCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for StyleCommand {
+    fn name(&self) -> &str {
+        "style"
+    }
+
+    fn description(&self) -> &str {
+        "Set the reply style for this chat (kid, executive, brief, ... or off)"
+    }
+
+    fn accepts_args(&self) -> bool {
+        true
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> CommandResult {
+        let session_key = format!("{}:{}", ctx.source.channel, ctx.source.chat_id);
+        let style = args.trim();
+
+        if style.is_empty() {
+            let current = load_overrides(&ctx.paths)
+                .get(&session_key)
+                .cloned()
+                .unwrap_or_else(|| "(config default)".to_string());
+            let presets: Vec<String> = PRESETS
+                .iter()
+                .map(|(name, instruction)| format!("- `{}`: {}", name, instruction))
+                .collect();
+            return CommandResult::Handled(CommandResponse::markdown(format!(
+                "🎨 **Reply style:** {}\n\nPresets:\n{}\n\nUsage: `/style <preset | description | off>`\n",
+                current,
+                presets.join("\n")
+            )));
+        }
+
+        let value = if matches!(style.to_lowercase().as_str(), "off" | "none" | "default") {
+            None
+        } else {
+            Some(style)
+        };
+        let content = match set_chat_style(&ctx.paths, &session_key, value) {
+            Ok(()) => match value {
+                Some(style) => format!("✅ Replies in this chat will use style: {}\n", style),
+                None => "✅ Reply style cleared for this chat\n".to_string(),
+            },
+            Err(e) => format!("⚠️ Failed to save reply style: {}\n", e),
+        };
+        CommandResult::Handled(CommandResponse::markdown(content))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_style_command_lists_presets() {
+        let cmd = StyleCommand;
+        let ctx = CommandContext::test_context();
+
+        let result = cmd.execute("", &ctx).await;
+        if let CommandResult::Handled(response) = result {
+            assert!(response.content.contains("executive"));
+            assert!(response.content.contains("Usage"));
+        } else {
+            panic!("expected Handled");
+        }
+    }
+}
@@ -21,6 +21,9 @@ const BUILTIN_TOOLS: &[(&str, &[(&str, &str)])] = &[
         "⚡ Commands & System",
         &[
             ("exec", "Execute shell commands"),
+            ("ssh", "Remote hosts: allowlisted commands, SFTP get/put"),
+            ("docker", "Docker/Podman containers, images, logs, stats"),
+            ("kubectl", "Kubernetes pods, events, logs, rollouts"),
             ("system_info", "Hardware/software/network detection"),
         ],
     ),
@@ -31,6 +34,11 @@ const BUILTIN_TOOLS: &[(&str, &[(&str, &str)])] = &[
             ("web_fetch", "Fetch web page content"),
             ("browse", "CDP browser automation (35+ actions)"),
             ("http_request", "Generic HTTP/REST API calls"),
+            (
+                "object_storage",
+                "S3/MinIO/R2 upload, download, list, presigned URLs",
+            ),
+            ("rss", "RSS/Atom feed subscriptions and digests"),
         ],
     ),
     (
@@ -53,7 +61,12 @@ const BUILTIN_TOOLS: &[(&str, &[(&str, &str)])] = &[
         "📊 Data Processing",
         &[
             ("data_process", "CSV read/write/stats/query/transform"),
-            ("office_write", "Generate PPTX/DOCX/XLSX documents"),
+            ("db_query", "Query Postgres/MySQL/SQLite (read-only, paged)"),
+            (
+                "office_write",
+                "Generate PPTX/DOCX/XLSX; xlsx formulas, pivots, conditional formats",
+            ),
+            ("translate", "Translate text/Markdown/DOCX with glossaries"),
             ("knowledge_graph", "Knowledge graph operations"),
         ],
     ),
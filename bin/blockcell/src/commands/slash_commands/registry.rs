@@ -7,7 +7,8 @@ use std::sync::Arc;
 
 use crate::commands::slash_commands::handlers::{
     ClearCommand, ClearSkillsCommand, CompactCommand, ExitCommand, ForgetSkillCommand, HelpCommand,
-    LearnCommand, QuitCommand, SessionMetricsCommand, SkillsCommand, TasksCommand, ToolsCommand,
+    LearnCommand, QuitCommand, SessionMetricsCommand, SkillsCommand, StyleCommand, TasksCommand,
+    ToolsCommand,
 };
 
 /// 创建默认命令处理器
@@ -28,6 +29,7 @@ pub fn create_default_handler() -> SlashCommandHandler {
     handler.register(CompactCommand);
     handler.register(ClearSkillsCommand);
     handler.register(ForgetSkillCommand);
+    handler.register(StyleCommand);
 
     // 监控命令
     handler.register(SessionMetricsCommand);
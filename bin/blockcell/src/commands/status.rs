@@ -1,3 +1,4 @@
+use blockcell_agent::budget::SpendLedger;
 use blockcell_agent::intent::IntentToolResolver;
 use blockcell_channels::account::{channel_configured, listener_labels};
 use blockcell_core::{Config, Paths};
@@ -169,6 +170,22 @@ pub async fn run() -> anyhow::Result<()> {
         println!("⚠ No provider configured with API key");
     }
 
+    let budget = &config.agents.defaults.budget;
+    if budget.is_active() {
+        let ledger = SpendLedger::load(&paths);
+        println!(
+            "Budget:    ${:.2} / ${:.2} this month ({}){}",
+            ledger.spent_usd,
+            budget.monthly_usd,
+            ledger.month,
+            if ledger.over_cap(budget) {
+                " — cap reached"
+            } else {
+                ""
+            }
+        );
+    }
+
     println!();
     println!("Intent Router:");
     match config.intent_router.as_ref() {
@@ -190,7 +207,7 @@ pub async fn run() -> anyhow::Result<()> {
             }
 
             let mcp_manager = Arc::new(McpManager::load(&paths).await?);
-            let registry = build_tool_registry_with_all_mcp(Some(&mcp_manager)).await?;
+            let registry = build_tool_registry_with_all_mcp(&config, Some(&mcp_manager)).await?;
             let mcp = blockcell_core::mcp_config::McpResolvedConfig::load_merged(&paths)?;
             match IntentToolResolver::new(&config).validate_with_mcp(&registry, Some(&mcp)) {
                 Ok(_) => println!("  validate:  ✓ tools and MCP config ok"),
@@ -506,6 +523,7 @@ mod tests {
             output_price: None,
             temperature: None,
             tool_call_mode: blockcell_core::config::ToolCallMode::Native,
+            group: None,
         }];
         config.agents.defaults.provider = Some("deepseek".to_string());
         config.agents.defaults.model = "deepseek-chat".to_string();
@@ -533,6 +551,7 @@ mod tests {
             output_price: None,
             temperature: None,
             tool_call_mode: blockcell_core::config::ToolCallMode::Native,
+            group: None,
         }];
         config.agents.defaults.provider = Some("ollama".to_string());
         config.agents.defaults.model = "llama3".to_string();
@@ -0,0 +1,133 @@
+//! Unlocks the at-rest storage key (`storage.encryption`) before sessions or the memory
+//! database are opened.
+//!
+//! `storage_key.json` in the root data directory keeps the passphrase salt and a key
+//! check, never the key itself. The key lives in the OS keyring or is derived from a
+//! passphrase on every start.
+
+use anyhow::{bail, Context};
+use base64::engine::general_purpose::STANDARD as B64;
+use base64::Engine;
+use blockcell_core::config::StorageKeySource;
+use blockcell_core::{Config, Paths};
+use blockcell_storage::at_rest::{self, KEY_LEN};
+use serde::{Deserialize, Serialize};
+use std::io::IsTerminal;
+use tracing::info;
+
+const KEYRING_SERVICE: &str = "blockcell";
+const KEYRING_USER: &str = "storage-key";
+const PASSPHRASE_ENV: &str = "BLOCKCELL_STORAGE_PASSPHRASE";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct KeyFile {
+    #[serde(default)]
+    salt: String,
+    #[serde(default)]
+    check: String,
+}
+
+fn decode_key(encoded: &str) -> Option<[u8; KEY_LEN]> {
+    B64.decode(encoded.trim()).ok()?.try_into().ok()
+}
+
+fn keyring_key() -> anyhow::Result<[u8; KEY_LEN]> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)
+        .context("Cannot access the OS keyring")?;
+    match entry.get_password() {
+        Ok(stored) => decode_key(&stored).context("Storage key in the OS keyring is malformed"),
+        Err(keyring::Error::NoEntry) => {
+            let key = at_rest::generate_key();
+            entry
+                .set_password(&B64.encode(key))
+                .context("Cannot store the storage key in the OS keyring")?;
+            info!("Created storage encryption key in the OS keyring");
+            Ok(key)
+        }
+        Err(e) => Err(e).context("Cannot read the storage key from the OS keyring"),
+    }
+}
+
+fn read_passphrase(first_setup: bool) -> anyhow::Result<String> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+        if !passphrase.is_empty() {
+            return Ok(passphrase);
+        }
+    }
+    if !std::io::stdin().is_terminal() {
+        bail!(
+            "storage.encryption needs a passphrase; set {} for unattended starts",
+            PASSPHRASE_ENV
+        );
+    }
+    let passphrase = rpassword::prompt_password("Storage passphrase: ")?;
+    if passphrase.is_empty() {
+        bail!("Empty storage passphrase");
+    }
+    if first_setup && rpassword::prompt_password("Repeat passphrase: ")? != passphrase {
+        bail!("Passphrases do not match");
+    }
+    Ok(passphrase)
+}
+
+/// Install the storage key for this process when encryption is enabled. Idempotent.
+pub fn unlock(config: &Config) -> anyhow::Result<()> {
+    let encryption = &config.storage.encryption;
+    if !encryption.enabled || at_rest::is_unlocked() {
+        return Ok(());
+    }
+
+    let key_file = Paths::new().storage_key_file();
+    let mut stored: KeyFile = match std::fs::read_to_string(&key_file) {
+        Ok(content) => serde_json::from_str(&content)
+            .with_context(|| format!("Malformed {}", key_file.display()))?,
+        Err(_) => KeyFile::default(),
+    };
+    let first_setup = stored.check.is_empty();
+
+    let key = match encryption.key_source {
+        StorageKeySource::Keyring => keyring_key()?,
+        StorageKeySource::Passphrase => {
+            if stored.salt.is_empty() {
+                stored.salt = B64.encode(at_rest::generate_salt());
+            }
+            let salt = B64
+                .decode(&stored.salt)
+                .context("Malformed storage key salt")?;
+            at_rest::derive_key(&read_passphrase(first_setup)?, &salt)?
+        }
+    };
+
+    if first_setup {
+        stored.check = at_rest::key_check(&key);
+        if let Some(parent) = key_file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&key_file, serde_json::to_string_pretty(&stored)?)?;
+    } else if !at_rest::verify_key_check(&key, &stored.check) {
+        bail!("Wrong storage key; cannot unlock encrypted storage");
+    }
+
+    at_rest::install_key(key);
+    info!(source = ?encryption.key_source, "Storage encryption unlocked");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlock_is_noop_when_disabled() {
+        assert!(unlock(&Config::default()).is_ok());
+        assert!(!at_rest::is_unlocked());
+    }
+
+    #[test]
+    fn test_decode_key_checks_length() {
+        let key = at_rest::generate_key();
+        assert_eq!(decode_key(&B64.encode(key)), Some(key));
+        assert_eq!(decode_key(&B64.encode([1u8; 16])), None);
+        assert_eq!(decode_key("not base64!"), None);
+    }
+}
@@ -0,0 +1,157 @@
+//! Background sync of shared team memory (`memory.team`) with the Community Hub.
+//!
+//! Each round pushes this node's unsent team item versions and pulls what other members
+//! changed since the last cursor, for every agent's memory store.
+
+use blockcell_core::{Config, Paths};
+use blockcell_storage::team_memory::{TeamEntry, TeamMemory};
+use blockcell_storage::MemoryStore;
+use serde::Deserialize;
+use tracing::{debug, info, warn};
+
+use super::memory_store::open_memory_store;
+
+#[derive(Debug, Default, Deserialize)]
+struct PullResponse {
+    #[serde(default)]
+    entries: Vec<TeamEntry>,
+    #[serde(default)]
+    cursor: Option<String>,
+}
+
+struct Hub {
+    client: reqwest::Client,
+    endpoint: String,
+    api_key: Option<String>,
+}
+
+impl Hub {
+    fn authorize(&self, req: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.api_key {
+            Some(key) => req.header("Authorization", format!("Bearer {}", key)),
+            None => req,
+        }
+    }
+
+    async fn push(&self, node: &str, entries: &[TeamEntry]) -> anyhow::Result<()> {
+        let body = serde_json::json!({ "node": node, "entries": entries });
+        self.authorize(self.client.post(&self.endpoint).json(&body))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    async fn pull(&self, cursor: Option<&str>) -> anyhow::Result<PullResponse> {
+        let mut req = self.client.get(&self.endpoint);
+        if let Some(cursor) = cursor {
+            req = req.query(&[("since", cursor)]);
+        }
+        Ok(self
+            .authorize(req)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+}
+
+/// One push/pull round for one agent's store. Returns how many entries were applied.
+async fn sync_store(hub: &Hub, store: &MemoryStore, node: &str) -> anyhow::Result<usize> {
+    let pending = TeamMemory::new(store, node)?.local_changes()?;
+    if !pending.is_empty() {
+        hub.push(node, &pending).await?;
+        TeamMemory::new(store, node)?.mark_pushed(&pending)?;
+        debug!(node = %node, count = pending.len(), "Pushed team memory changes");
+    }
+
+    let cursor = TeamMemory::new(store, node)?.cursor();
+    let pulled = hub.pull(cursor.as_deref()).await?;
+    let team = TeamMemory::new(store, node)?;
+    let applied = team.apply_remote(&pulled.entries)?;
+    if let Some(cursor) = pulled.cursor {
+        team.set_cursor(&cursor)?;
+    }
+    Ok(applied)
+}
+
+/// Start the sync loop for the gateway. Does nothing unless `memory.team` is enabled.
+pub fn spawn_sync(paths: Paths, config: Config) {
+    let Some(endpoint) = config.team_memory_endpoint() else {
+        if config.memory.team.enabled {
+            warn!(
+                "Team memory is enabled but memory.team.teamId or communityHub.hubUrl is missing"
+            );
+        }
+        return;
+    };
+    let Some(alias) = config.community_hub.node_alias.clone() else {
+        warn!("Team memory needs communityHub.nodeAlias to identify this node");
+        return;
+    };
+
+    let mut stores = Vec::new();
+    for agent_id in config.known_agent_ids() {
+        match open_memory_store(&paths.for_agent(&agent_id), &config) {
+            // Agents on one node edit independently, so each gets its own clock entry.
+            Ok(store) => stores.push((format!("{}/{}", alias, agent_id), store)),
+            Err(e) => {
+                warn!(agent_id = %agent_id, error = %e, "Team memory: cannot open memory store")
+            }
+        }
+    }
+    let hub = Hub {
+        client: reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(30))
+            .build()
+            .unwrap_or_default(),
+        endpoint,
+        api_key: config.community_hub_api_key(),
+    };
+    let every = std::time::Duration::from_secs(config.memory.team.sync_interval_secs.max(30));
+    info!(agents = stores.len(), "Team memory sync started");
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        loop {
+            interval.tick().await;
+            for (node, store) in &stores {
+                match sync_store(&hub, store, node).await {
+                    Ok(0) => {}
+                    Ok(applied) => info!(node = %node, applied, "Merged team memory from hub"),
+                    Err(e) => warn!(node = %node, error = %e, "Team memory sync failed"),
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pull_response_tolerates_missing_fields() {
+        let empty: PullResponse = serde_json::from_str("{}").unwrap();
+        assert!(empty.entries.is_empty());
+        assert!(empty.cursor.is_none());
+
+        let pulled: PullResponse = serde_json::from_value(serde_json::json!({
+            "entries": [{
+                "key": "staging.db",
+                "type": "fact",
+                "content": "staging DB host is db-stg-1",
+                "importance": 0.7,
+                "changedAt": "2026-10-01T08:00:00Z",
+                "origin": "54c6be7b/default",
+                "clock": { "54c6be7b/default": 1 }
+            }],
+            "cursor": "42"
+        }))
+        .unwrap();
+        assert_eq!(pulled.entries[0].key, "staging.db");
+        assert!(!pulled.entries[0].deleted);
+        assert_eq!(pulled.cursor.as_deref(), Some("42"));
+    }
+}
@@ -0,0 +1,114 @@
+use blockcell_core::{Config, Paths};
+use blockcell_skills::service::is_builtin_tool;
+use blockcell_storage::telemetry::{mark_sent, pending_report, TelemetryReport};
+use tracing::{debug, info, warn};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+fn agent_paths(paths: &Paths, config: &Config) -> Vec<Paths> {
+    config
+        .known_agent_ids()
+        .iter()
+        .map(|id| paths.for_agent(id))
+        .collect()
+}
+
+fn report(paths: &Paths, config: &Config) -> anyhow::Result<Option<TelemetryReport>> {
+    Ok(pending_report(
+        paths,
+        &agent_paths(paths, config),
+        VERSION,
+        is_builtin_tool,
+    )?)
+}
+
+/// Print exactly what the next report would send.
+pub async fn preview() -> anyhow::Result<()> {
+    let paths = Paths::default();
+    let config = Config::load_or_default(&paths)?;
+
+    println!();
+    println!(
+        "📊 Telemetry is {}",
+        if config.telemetry.enabled {
+            "enabled"
+        } else {
+            "disabled (nothing is sent)"
+        }
+    );
+    match config.telemetry_endpoint() {
+        Some(endpoint) => println!("  Endpoint: {}", endpoint),
+        None => println!("  Endpoint: (none — set telemetry.endpoint or communityHub.hubUrl)"),
+    }
+    println!();
+    match report(&paths, &config)? {
+        Some(report) => println!("{}", serde_json::to_string_pretty(&report)?),
+        None => println!("(Nothing pending — today's usage is reported after the day ends.)"),
+    }
+    println!();
+    Ok(())
+}
+
+/// Opt in or out of telemetry.
+pub async fn set_enabled(enabled: bool) -> anyhow::Result<()> {
+    let paths = Paths::default();
+    let mut config = Config::load_or_default(&paths)?;
+    config.telemetry.enabled = enabled;
+    config.save(&paths.config_file())?;
+    if enabled {
+        println!("✓ Telemetry enabled. Run `blockcell telemetry preview` to see what is sent.");
+    } else {
+        println!("✓ Telemetry disabled.");
+    }
+    Ok(())
+}
+
+/// Post the pending report. Returns false when there was nothing to send.
+async fn send_pending(
+    client: &reqwest::Client,
+    paths: &Paths,
+    config: &Config,
+) -> anyhow::Result<bool> {
+    let Some(endpoint) = config.telemetry_endpoint() else {
+        return Ok(false);
+    };
+    let Some(report) = report(paths, config)? else {
+        return Ok(false);
+    };
+    client
+        .post(&endpoint)
+        .json(&report)
+        .send()
+        .await?
+        .error_for_status()?;
+    mark_sent(paths, &report)?;
+    info!(period_end = %report.period_end, "Sent telemetry report");
+    Ok(true)
+}
+
+/// Background reporter for the gateway. Does nothing unless `telemetry.enabled`.
+pub fn spawn_reporter(paths: Paths, config: Config) {
+    if !config.telemetry.enabled {
+        return;
+    }
+    if config.telemetry_endpoint().is_none() {
+        warn!("Telemetry is enabled but no endpoint is configured");
+        return;
+    }
+    let every = std::time::Duration::from_secs(config.telemetry.interval_hours.max(1) * 3600);
+    tokio::spawn(async move {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(15))
+            .build()
+            .unwrap_or_default();
+        let mut interval = tokio::time::interval(every);
+        loop {
+            interval.tick().await;
+            match send_pending(&client, &paths, &config).await {
+                Ok(true) => {}
+                Ok(false) => debug!("No telemetry report pending"),
+                Err(e) => debug!(error = %e, "Telemetry report not sent"),
+            }
+        }
+    });
+}
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use blockcell_core::Paths;
+use blockcell_core::{Config, Paths};
 use blockcell_tools::build_tool_registry_with_all_mcp;
 use blockcell_tools::mcp::manager::McpManager;
 use serde_json::Value;
@@ -13,8 +13,9 @@ fn schema_function(schema: &Value) -> &Value {
 /// List all registered tools.
 pub async fn list(category: Option<String>) -> anyhow::Result<()> {
     let paths = Paths::new();
+    let config = Config::load_or_default(&paths)?;
     let mcp_manager = Arc::new(McpManager::load(&paths).await?);
-    let registry = build_tool_registry_with_all_mcp(Some(&mcp_manager)).await?;
+    let registry = build_tool_registry_with_all_mcp(&config, Some(&mcp_manager)).await?;
     let schemas = registry.get_tool_schemas();
 
     println!();
@@ -58,8 +59,9 @@ pub async fn list(category: Option<String>) -> anyhow::Result<()> {
 /// Show detailed info for a specific tool.
 pub async fn info(tool_name: &str) -> anyhow::Result<()> {
     let paths = Paths::new();
+    let config = Config::load_or_default(&paths)?;
     let mcp_manager = Arc::new(McpManager::load(&paths).await?);
-    let registry = build_tool_registry_with_all_mcp(Some(&mcp_manager)).await?;
+    let registry = build_tool_registry_with_all_mcp(&config, Some(&mcp_manager)).await?;
     let schemas = registry.get_tool_schemas();
 
     let schema = schemas
@@ -144,8 +146,9 @@ pub async fn info(tool_name: &str) -> anyhow::Result<()> {
 /// Test a tool by calling it directly with JSON params.
 pub async fn test(tool_name: &str, params_json: &str) -> anyhow::Result<()> {
     let paths = Paths::new();
+    let config = Config::load_or_default(&paths)?;
     let mcp_manager = Arc::new(McpManager::load(&paths).await?);
-    let registry = build_tool_registry_with_all_mcp(Some(&mcp_manager)).await?;
+    let registry = build_tool_registry_with_all_mcp(&config, Some(&mcp_manager)).await?;
     let paths = Paths::new();
 
     let tool = registry
@@ -165,7 +168,7 @@ pub async fn test(tool_name: &str, params_json: &str) -> anyhow::Result<()> {
         workspace: paths.workspace(),
         builtin_skills_dir: Some(paths.builtin_skills_dir()),
         active_skill_dir: None,
-        config: blockcell_core::Config::load_or_default(&paths)?,
+        config,
         session_key: "cli:test".to_string(),
         channel: String::new(),
         account_id: None,
@@ -181,6 +184,7 @@ pub async fn test(tool_name: &str, params_json: &str) -> anyhow::Result<()> {
         event_emitter: None,
         channel_contacts_file: Some(paths.channel_contacts_file()),
         response_cache: None,
+        artifacts: None,
     };
 
     println!("⏳ Executing {} ...", tool_name);
@@ -214,8 +218,9 @@ pub async fn toggle(tool_name: &str, enable: bool) -> anyhow::Result<()> {
 
     // Verify tool exists
     let paths = Paths::new();
+    let config = Config::load_or_default(&paths)?;
     let mcp_manager = Arc::new(McpManager::load(&paths).await?);
-    let registry = build_tool_registry_with_all_mcp(Some(&mcp_manager)).await?;
+    let registry = build_tool_registry_with_all_mcp(&config, Some(&mcp_manager)).await?;
     if registry.get(tool_name).is_none() {
         eprintln!(
             "⚠ Tool '{}' not found in registry, but toggle state will be recorded.",
@@ -246,20 +251,29 @@ pub async fn toggle(tool_name: &str, enable: bool) -> anyhow::Result<()> {
 
 fn categorize_tool(name: &str) -> &'static str {
     match name {
-        "read_file" | "write_file" | "edit_file" | "list_dir" | "file_ops" => "Filesystem",
-        "exec" => "Execution",
-        "web_search" | "web_fetch" | "browse" | "http_request" => "Web/Browser",
+        "read_file" | "write_file" | "edit_file" | "list_dir" | "file_ops" | "doc_diff" => {
+            "Filesystem"
+        }
+        "exec" | "python" | "ssh" | "docker" | "kubectl" | "git_api" => "Execution",
+        "web_search" | "web_fetch" | "browse" | "http_request" | "rss" | "object_storage"
+        | "weather" | "bookmarks" => "Web/Browser",
         "app_control" => "GUI Automation",
         "message" | "spawn" | "list_tasks" | "email" => "Communication",
-        "cron" => "Scheduling",
+        "cron" | "calendar" | "travel" | "parcel_track" | "birthdays" | "habits" | "follow_up" => {
+            "Scheduling"
+        }
+        "project" | "todo" | "subscriptions" | "receipts" => "Projects",
         "memory_query" | "memory_upsert" | "memory_forget" => "Memory",
         "list_skills" | "toggle_manage" => "Skill Management",
         "system_info" | "capability_evolve" => "System/Evolution",
-        "camera_capture" | "ocr" | "image_understand" | "tts" | "audio_transcribe" => "Media",
-        "chart_generate" | "office_write" | "data_process" => "Data/Documents",
+        "camera_capture" | "ocr" | "image_understand" | "tts" | "audio_transcribe"
+        | "media_control" => "Media",
+        "chart_generate" | "office_write" | "data_process" | "translate" | "db_query" | "calc"
+        | "nutrition" | "doc_ingest" | "doc_search" => "Data/Documents",
         "video_process" => "Video",
-        "alert_rule" | "stream_subscribe" => "Finance/Trading",
+        "alert_rule" | "stream_subscribe" | "backtest" => "Finance/Trading",
         "encrypt" | "network_monitor" => "Security/Network",
+        "iot_control" => "IoT",
         "knowledge_graph" => "Knowledge Graph",
         _ => "Other",
     }
@@ -114,6 +114,30 @@ enum Commands {
         command: ToolsCommands,
     },
 
+    /// Manage named secrets referenced from config as `secret:<name>`
+    Secrets {
+        #[command(subcommand)]
+        command: SecretsCommands,
+    },
+
+    /// Purge a sender's data and apply retention windows
+    Privacy {
+        #[command(subcommand)]
+        command: PrivacyCommands,
+    },
+
+    /// Inspect the system prompt and its prompt.d fragments
+    Prompt {
+        #[command(subcommand)]
+        command: PromptCommands,
+    },
+
+    /// Preview and opt in to anonymous usage telemetry
+    Telemetry {
+        #[command(subcommand)]
+        command: TelemetryCommands,
+    },
+
     /// Manage MCP servers
     Mcp {
         #[command(subcommand)]
@@ -160,6 +184,37 @@ enum Commands {
         command: MemoryCommands,
     },
 
+    /// Manage chat sessions
+    Sessions {
+        #[command(subcommand)]
+        command: SessionsCommands,
+    },
+
+    /// Run an offline end-to-end check of config, tools and skills
+    Selftest {
+        /// Keep the temporary data directory for inspection
+        #[arg(long)]
+        keep: bool,
+    },
+
+    /// Inspect and replay recorded agent turns
+    Replay {
+        #[command(subcommand)]
+        command: ReplayCommands,
+    },
+
+    /// Diagnostics for bug reports
+    Debug {
+        #[command(subcommand)]
+        command: DebugCommands,
+    },
+
+    /// Benchmark models on a prompt/tool-use suite
+    Bench {
+        #[command(subcommand)]
+        command: BenchCommands,
+    },
+
     /// Trigger and observe skill evolution
     Evolve {
         #[command(subcommand)]
@@ -172,6 +227,24 @@ enum Commands {
         command: AlertsCommands,
     },
 
+    /// WebUI login lockouts: show failed logins, unlock addresses
+    Auth {
+        #[command(subcommand)]
+        command: AuthCommands,
+    },
+
+    /// Manage named webhook triggers served at POST /v1/hooks/:name
+    Hooks {
+        #[command(subcommand)]
+        command: HooksCommands,
+    },
+
+    /// Ingest and search documents (PDF, Markdown, HTML, Office, URLs) for the agent
+    Docs {
+        #[command(subcommand)]
+        command: DocsCommands,
+    },
+
     /// Manage real-time data stream subscriptions
     Streams {
         #[command(subcommand)]
@@ -205,6 +278,12 @@ enum ConfigCommands {
     Show,
     /// Print the JSON Schema for the config file
     Schema,
+    /// Check the config file for parse errors, unknown/deprecated keys and pending migrations
+    Validate {
+        /// Exit with an error when unknown or deprecated keys are found
+        #[arg(long)]
+        strict: bool,
+    },
     /// Get a config value by dot-separated key (e.g. agents.defaults.model)
     Get {
         /// Config key path (e.g. "agents.defaults.model", "providers.openai.api_key")
@@ -229,6 +308,88 @@ enum ConfigCommands {
     },
 }
 
+// ── P0: Secrets ─────────────────────────────────────────────────────────────
+
+#[derive(Subcommand)]
+enum SecretsCommands {
+    /// List secret names (values are never printed)
+    List,
+    /// Store a secret (reads the value from stdin when omitted)
+    Set {
+        /// Secret name, referenced in config as "secret:<name>"
+        name: String,
+        /// Secret value
+        value: Option<String>,
+    },
+    /// Remove a secret
+    Remove {
+        /// Secret name
+        name: String,
+    },
+}
+
+// ── Privacy ─────────────────────────────────────────────────────────────────
+
+#[derive(Subcommand)]
+enum PrivacyCommands {
+    /// Delete or anonymize everything tied to a sender and write a signed report
+    Purge {
+        /// Sender id as seen by the channel (e.g. Telegram user id)
+        #[arg(long)]
+        sender: String,
+        /// Only purge sessions on this channel
+        #[arg(long)]
+        channel: Option<String>,
+        /// Agent whose data to purge (default: default)
+        #[arg(long)]
+        agent: Option<String>,
+        /// Audit entries: anonymize (default from config) or delete
+        #[arg(long)]
+        audit_mode: Option<String>,
+        /// Report what would be removed without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Verify the signature of a purge report
+    Verify {
+        /// Path to a purge-<id>.json report
+        report: String,
+    },
+    /// Apply security.privacy.retentionDays now
+    Retention {
+        /// Agent to clean up (default: default)
+        #[arg(long)]
+        agent: Option<String>,
+    },
+}
+
+// ── Prompt ──────────────────────────────────────────────────────────────────
+
+#[derive(Subcommand)]
+enum PromptCommands {
+    /// List prompt.d fragments, or print the assembled system prompt
+    Show {
+        /// Print the final system prompt with all fragments merged
+        #[arg(long)]
+        effective: bool,
+        /// Agent whose workspace to read (default: default)
+        #[arg(long)]
+        agent: Option<String>,
+    },
+}
+
+// ── Telemetry ───────────────────────────────────────────────────────────────
+
+#[derive(Subcommand)]
+enum TelemetryCommands {
+    /// Show exactly what the next report would send
+    Preview,
+    /// Opt in to sending anonymous usage counts
+    Enable,
+    /// Stop sending telemetry
+    Disable,
+}
+
 // ── P0: Tools ───────────────────────────────────────────────────────────────
 
 #[derive(Subcommand)]
@@ -415,6 +576,122 @@ enum AlertsCommands {
     },
 }
 
+// ── Auth ────────────────────────────────────────────────────────────────────
+
+#[derive(Subcommand)]
+enum AuthCommands {
+    /// Show failed WebUI logins and active lockouts
+    Status,
+    /// Lift a login lockout
+    Unlock {
+        /// Client address to unlock, e.g. 203.0.113.7
+        address: Option<String>,
+        /// Unlock every address and end a global pause
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+// ── Hooks ───────────────────────────────────────────────────────────────────
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Subcommand)]
+enum HooksCommands {
+    /// List hooks
+    List,
+    /// Add or replace a hook (agent message by default, or --tool for a direct call)
+    Add {
+        /// Hook name, used in the URL /v1/hooks/<name>
+        name: String,
+        /// Agent message template, e.g. "Deploy of {{repository.name}} finished: {{status}}"
+        #[arg(long)]
+        template: Option<String>,
+        /// Skill the agent must use for every event
+        #[arg(long)]
+        skill: Option<String>,
+        /// Call this tool directly instead of messaging the agent
+        #[arg(long)]
+        tool: Option<String>,
+        /// Tool parameters as JSON; string values are templates
+        #[arg(long)]
+        params: Option<String>,
+        /// HMAC-SHA256 secret (literal, secret:<name> or env:<VAR>)
+        #[arg(long)]
+        secret: Option<String>,
+        /// Header carrying the signature (default: GitHub/Stripe/X-Signature-256)
+        #[arg(long)]
+        signature_header: Option<String>,
+        /// Only trigger on these events (repeatable), e.g. pull_request.opened
+        #[arg(long = "event")]
+        events: Vec<String>,
+        /// Agent that handles the event
+        #[arg(short = 'a', long)]
+        agent: Option<String>,
+        /// Channel the agent replies on (default: webhook)
+        #[arg(long)]
+        channel: Option<String>,
+        /// Chat the agent replies to (default: hook_<name>)
+        #[arg(long)]
+        chat_id: Option<String>,
+        /// Free-form description
+        #[arg(long)]
+        description: Option<String>,
+        /// Save the hook without enabling it
+        #[arg(long)]
+        disabled: bool,
+    },
+    /// Remove a hook
+    Remove {
+        /// Hook name
+        name: String,
+    },
+}
+
+// ── Docs ────────────────────────────────────────────────────────────────────
+
+#[derive(Subcommand)]
+enum DocsCommands {
+    /// Ingest a file, a directory of documents or a URL
+    Ingest {
+        /// File or directory path, or http(s) URL
+        source: String,
+        /// Re-ingest even if the source is unchanged
+        #[arg(long)]
+        force: bool,
+        /// Agent whose document library to use (default: "default")
+        #[arg(short = 'a', long)]
+        agent: Option<String>,
+    },
+    /// List ingested documents
+    List {
+        /// Agent whose document library to use
+        #[arg(short = 'a', long)]
+        agent: Option<String>,
+    },
+    /// Search ingested documents
+    Search {
+        /// Search query
+        query: String,
+        /// Only search this document (id or source)
+        #[arg(long)]
+        document: Option<String>,
+        /// Max results
+        #[arg(long, default_value = "5")]
+        limit: usize,
+        /// Agent whose document library to use
+        #[arg(short = 'a', long)]
+        agent: Option<String>,
+    },
+    /// Remove a document by id or source
+    Remove {
+        /// Document id or source
+        document: String,
+        /// Agent whose document library to use
+        #[arg(short = 'a', long)]
+        agent: Option<String>,
+    },
+}
+
 // ── P1: Streams ─────────────────────────────────────────────────────────────
 
 #[derive(Subcommand)]
@@ -558,7 +835,7 @@ enum ChannelOwnerCommands {
 
 #[derive(Subcommand)]
 enum CronCommands {
-    /// List cron jobs (read-only; manage jobs via the WebUI or chat channels)
+    /// List cron jobs with their next run in each job's timezone
     List {
         /// Show all jobs including disabled
         #[arg(long)]
@@ -567,6 +844,50 @@ enum CronCommands {
         #[arg(long, default_value = "default")]
         agent: String,
     },
+    /// Add a reminder job (cron expression, fixed interval or one-shot time)
+    Add {
+        /// Job name
+        #[arg(long)]
+        name: String,
+        /// Reminder text sent when the job fires
+        #[arg(long)]
+        message: String,
+        /// Run every N seconds
+        #[arg(long, conflicts_with_all = ["cron", "at"])]
+        every: Option<i64>,
+        /// Cron expression (6 fields: sec min hour day month weekday), e.g. "0 30 9 * * Mon-Fri"
+        #[arg(long, conflicts_with = "at")]
+        cron: Option<String>,
+        /// Run once at an RFC 3339 time, e.g. 2026-03-01T09:00:00+08:00
+        #[arg(long)]
+        at: Option<String>,
+        /// IANA timezone for --cron, e.g. "America/New_York" (default: defaultTimezone)
+        #[arg(long, requires = "cron")]
+        tz: Option<String>,
+        /// Deliver the reminder to --channel / --to
+        #[arg(long, requires_all = ["channel", "to"])]
+        deliver: bool,
+        /// Target channel (e.g. telegram)
+        #[arg(long)]
+        channel: Option<String>,
+        /// Target chat ID
+        #[arg(long)]
+        to: Option<String>,
+        /// Agent ID to add the job to (default: "default")
+        #[arg(long, default_value = "default")]
+        agent: String,
+    },
+    /// Show past runs of a cron job (status, timing, result or error)
+    History {
+        /// Job ID (or a unique prefix, as shown by `cron list`)
+        job_id: String,
+        /// Number of runs to show
+        #[arg(long, default_value = "20")]
+        limit: usize,
+        /// Agent ID to query (default: "default")
+        #[arg(long, default_value = "default")]
+        agent: String,
+    },
 }
 
 #[derive(Subcommand, Default)]
@@ -640,6 +961,15 @@ enum SkillsCommands {
         #[arg(long)]
         version: Option<String>,
     },
+    /// Show per-skill invocation counts, success rate, latency and token cost
+    Stats {
+        /// Agent whose stats to show (default: default)
+        #[arg(long)]
+        agent: Option<String>,
+        /// Print raw JSON
+        #[arg(long)]
+        json: bool,
+    },
     /// Clear all skill evolution records
     Clear,
     /// Forget (delete) records for a specific skill
@@ -769,6 +1099,115 @@ enum MemoryCommands {
         #[arg(long)]
         scope: Option<String>,
     },
+    /// Scan stored memory items for PII (emails, phone and ID numbers, cards)
+    AuditPii {
+        /// Also scan audit logs
+        #[arg(long)]
+        audit_logs: bool,
+        /// Max flagged items to list
+        #[arg(long, default_value = "50")]
+        limit: usize,
+    },
+    /// Learn facts and preferences from an exported chat archive (Telegram JSON, WhatsApp txt)
+    Ingest {
+        /// result.json, WhatsApp .txt, or a .zip containing one
+        path: String,
+        /// Archive format (auto, telegram, whatsapp)
+        #[arg(long, default_value = "auto")]
+        format: String,
+        /// Chat name recorded as provenance (WhatsApp; default: from the file name)
+        #[arg(long)]
+        chat_name: Option<String>,
+        /// Max transcript characters per LLM request
+        #[arg(long, default_value = "12000")]
+        batch_chars: usize,
+        /// Parse and report batches without calling the LLM
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum SessionsCommands {
+    /// Import chat history exported from another assistant
+    Import {
+        /// Export archive (.zip), conversations.json, or a .jsonl transcript
+        path: String,
+        /// Export format (auto, chatgpt, claude, jsonl)
+        #[arg(long, default_value = "auto")]
+        format: String,
+        /// Also create a long-term memory note per conversation
+        #[arg(long)]
+        memory: bool,
+        /// Parse and report without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ReplayCommands {
+    /// List saved recordings (enable with agents.defaults.recordTurns)
+    List {
+        /// Maximum number of recordings to show
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+    },
+    /// Re-run a recorded turn against the recorded model responses and tool results
+    Run {
+        /// Recording file, or its name under workspace/recordings/
+        bundle: String,
+        /// Exit with an error if the replay diverges from the recording
+        #[arg(long)]
+        check: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum DebugCommands {
+    /// Collect version, masked config, logs, a session and evolution records into a zip
+    Bundle {
+        /// Session to include (default: the most recently active one)
+        #[arg(long)]
+        session: Option<String>,
+        /// Output file (default: ./blockcell-debug-<timestamp>.zip)
+        #[arg(short, long)]
+        output: Option<String>,
+        /// Lines to keep from the end of each log file
+        #[arg(long, default_value = "500")]
+        log_lines: usize,
+        /// Skip the interactive review step
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum BenchCommands {
+    /// Run the suite and compare with the previous run
+    Run {
+        /// Suite file (default: workspace/bench/suite.json, else the built-in suite)
+        #[arg(long)]
+        suite: Option<String>,
+        /// Model to benchmark (repeatable; default: every modelPool entry)
+        #[arg(short, long)]
+        model: Vec<String>,
+        /// Run each case this many times
+        #[arg(long, default_value = "1")]
+        repeat: usize,
+    },
+    /// Write the built-in suite to workspace/bench/suite.json for editing
+    Init {
+        /// Overwrite an existing suite file
+        #[arg(long)]
+        force: bool,
+    },
+    /// Show recent benchmark runs
+    History {
+        /// Maximum number of runs to show
+        #[arg(short, long, default_value = "10")]
+        limit: usize,
+    },
 }
 
 #[tokio::main]
@@ -841,6 +1280,9 @@ async fn main() -> anyhow::Result<()> {
             ConfigCommands::Schema => {
                 commands::config_cmd::schema().await?;
             }
+            ConfigCommands::Validate { strict } => {
+                commands::config_cmd::validate(strict).await?;
+            }
             ConfigCommands::Get { key } => {
                 commands::config_cmd::get(&key).await?;
             }
@@ -858,6 +1300,59 @@ async fn main() -> anyhow::Result<()> {
             }
         },
 
+        Commands::Privacy { command } => match command {
+            PrivacyCommands::Purge {
+                sender,
+                channel,
+                agent,
+                audit_mode,
+                dry_run,
+            } => {
+                commands::privacy_cmd::purge(
+                    &sender,
+                    channel,
+                    agent.as_deref(),
+                    audit_mode.as_deref(),
+                    dry_run,
+                )
+                .await?;
+            }
+            PrivacyCommands::Verify { report } => {
+                commands::privacy_cmd::verify(&report).await?;
+            }
+            PrivacyCommands::Retention { agent } => {
+                commands::privacy_cmd::retention(agent.as_deref()).await?;
+            }
+        },
+        Commands::Prompt { command } => match command {
+            PromptCommands::Show { effective, agent } => {
+                commands::prompt_cmd::show(effective, agent.as_deref()).await?;
+            }
+        },
+        Commands::Telemetry { command } => match command {
+            TelemetryCommands::Preview => {
+                commands::telemetry_cmd::preview().await?;
+            }
+            TelemetryCommands::Enable => {
+                commands::telemetry_cmd::set_enabled(true).await?;
+            }
+            TelemetryCommands::Disable => {
+                commands::telemetry_cmd::set_enabled(false).await?;
+            }
+        },
+        // ── P0: Secrets ─────────────────────────────────────────────────
+        Commands::Secrets { command } => match command {
+            SecretsCommands::List => {
+                commands::secrets_cmd::list().await?;
+            }
+            SecretsCommands::Set { name, value } => {
+                commands::secrets_cmd::set(&name, value).await?;
+            }
+            SecretsCommands::Remove { name } => {
+                commands::secrets_cmd::remove(&name).await?;
+            }
+        },
+
         // ── P0: Tools ───────────────────────────────────────────────────
         Commands::Tools { command } => match command {
             ToolsCommands::List { category } => {
@@ -983,6 +1478,34 @@ async fn main() -> anyhow::Result<()> {
             CronCommands::List { all, agent } => {
                 commands::cron::list(all, &agent).await?;
             }
+            CronCommands::Add {
+                name,
+                message,
+                every,
+                cron,
+                at,
+                tz,
+                deliver,
+                channel,
+                to,
+                agent,
+            } => {
+                let schedule = commands::cron::AddSchedule {
+                    every_secs: every,
+                    cron_expr: cron,
+                    at,
+                    tz,
+                };
+                let delivery = deliver.then(|| channel.zip(to)).flatten();
+                commands::cron::add(&name, &message, schedule, delivery, &agent).await?;
+            }
+            CronCommands::History {
+                job_id,
+                limit,
+                agent,
+            } => {
+                commands::cron::history(&job_id, limit, &agent).await?;
+            }
         },
         Commands::Upgrade { check, command } => {
             if check {
@@ -1029,6 +1552,9 @@ async fn main() -> anyhow::Result<()> {
             SkillsCommands::Install { name, version } => {
                 commands::skills::install(&name, version).await?;
             }
+            SkillsCommands::Stats { agent, json } => {
+                commands::skills::stats(agent.as_deref(), json).await?;
+            }
             SkillsCommands::Clear => {
                 commands::skills::clear().await?;
             }
@@ -1079,6 +1605,52 @@ async fn main() -> anyhow::Result<()> {
                 commands::evolve::list(all, verbose).await?;
             }
         },
+        Commands::Sessions { command } => match command {
+            SessionsCommands::Import {
+                path,
+                format,
+                memory,
+                dry_run,
+            } => {
+                commands::sessions_cmd::import(&path, &format, memory, dry_run).await?;
+            }
+        },
+        Commands::Selftest { keep } => {
+            commands::selftest_cmd::run(keep).await?;
+        }
+        Commands::Replay { command } => match command {
+            ReplayCommands::List { limit } => {
+                commands::replay_cmd::list(limit).await?;
+            }
+            ReplayCommands::Run { bundle, check } => {
+                commands::replay_cmd::run(&bundle, check).await?;
+            }
+        },
+        Commands::Debug { command } => match command {
+            DebugCommands::Bundle {
+                session,
+                output,
+                log_lines,
+                yes,
+            } => {
+                commands::debug_cmd::bundle(session, output, log_lines, yes).await?;
+            }
+        },
+        Commands::Bench { command } => match command {
+            BenchCommands::Run {
+                suite,
+                model,
+                repeat,
+            } => {
+                commands::bench_cmd::run(suite, model, repeat).await?;
+            }
+            BenchCommands::Init { force } => {
+                commands::bench_cmd::init(force).await?;
+            }
+            BenchCommands::History { limit } => {
+                commands::bench_cmd::history(limit).await?;
+            }
+        },
         Commands::Memory { command } => match command {
             MemoryCommands::List { item_type, limit } => {
                 commands::memory::list(item_type, limit).await?;
@@ -1112,6 +1684,18 @@ async fn main() -> anyhow::Result<()> {
             MemoryCommands::Clear { scope } => {
                 commands::memory::clear(scope).await?;
             }
+            MemoryCommands::AuditPii { audit_logs, limit } => {
+                commands::memory::audit_pii(audit_logs, limit).await?;
+            }
+            MemoryCommands::Ingest {
+                path,
+                format,
+                chat_name,
+                batch_chars,
+                dry_run,
+            } => {
+                commands::memory::ingest(&path, &format, chat_name, batch_chars, dry_run).await?;
+            }
         },
 
         // ── P1: Alerts ──────────────────────────────────────────────────
@@ -1139,6 +1723,84 @@ async fn main() -> anyhow::Result<()> {
             }
         },
 
+        // ── Auth ────────────────────────────────────────────────────────
+        Commands::Auth { command } => match command {
+            AuthCommands::Status => {
+                commands::auth_cmd::status().await?;
+            }
+            AuthCommands::Unlock { address, all } => {
+                commands::auth_cmd::unlock(address.as_deref(), all).await?;
+            }
+        },
+
+        // ── Hooks ───────────────────────────────────────────────────────
+        Commands::Hooks { command } => match command {
+            HooksCommands::List => {
+                commands::hooks_cmd::list().await?;
+            }
+            HooksCommands::Add {
+                name,
+                template,
+                skill,
+                tool,
+                params,
+                secret,
+                signature_header,
+                events,
+                agent,
+                channel,
+                chat_id,
+                description,
+                disabled,
+            } => {
+                commands::hooks_cmd::add(commands::hooks_cmd::HookAddArgs {
+                    name,
+                    template,
+                    skill,
+                    tool,
+                    params,
+                    secret,
+                    signature_header,
+                    events,
+                    agent,
+                    channel,
+                    chat_id,
+                    description,
+                    disabled,
+                })
+                .await?;
+            }
+            HooksCommands::Remove { name } => {
+                commands::hooks_cmd::remove(&name).await?;
+            }
+        },
+
+        // ── Docs ────────────────────────────────────────────────────────
+        Commands::Docs { command } => match command {
+            DocsCommands::Ingest {
+                source,
+                force,
+                agent,
+            } => {
+                commands::docs_cmd::ingest(&source, agent.as_deref(), force).await?;
+            }
+            DocsCommands::List { agent } => {
+                commands::docs_cmd::list(agent.as_deref()).await?;
+            }
+            DocsCommands::Search {
+                query,
+                document,
+                limit,
+                agent,
+            } => {
+                commands::docs_cmd::search(&query, agent.as_deref(), document.as_deref(), limit)
+                    .await?;
+            }
+            DocsCommands::Remove { document, agent } => {
+                commands::docs_cmd::remove(&document, agent.as_deref()).await?;
+            }
+        },
+
         // ── P1: Streams ─────────────────────────────────────────────────
         Commands::Streams { command } => match command {
             StreamsCommands::List => {
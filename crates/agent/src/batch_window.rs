@@ -0,0 +1,253 @@
+//! Coalescing of rapid-fire chat messages (`channels.batch`).
+//!
+//! Sits between the inbound queue and the runtime loop. A message from a channel with a
+//! window is held; further messages from the same sender in the same chat extend the wait
+//! until they go quiet for the window, or `maxWaitSecs` after the first one. The batch
+//! is then forwarded as one message with every text in arrival order, so the agent runs
+//! a single turn instead of several racing ones.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use blockcell_core::config::BatchWindowConfig;
+use blockcell_core::InboundMessage;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tracing::{debug, info};
+
+struct Pending {
+    messages: Vec<InboundMessage>,
+    first_at: Instant,
+    deadline: Instant,
+}
+
+pub struct Batcher {
+    config: BatchWindowConfig,
+    pending: HashMap<String, Pending>,
+}
+
+fn batch_key(msg: &InboundMessage) -> String {
+    // Keyed by sender too, so two people in a group chat are never merged.
+    format!("{}|{}", msg.session_key(), msg.sender_id)
+}
+
+fn is_cancel(msg: &InboundMessage) -> bool {
+    msg.metadata.get("cancel").and_then(|v| v.as_bool()) == Some(true)
+}
+
+/// One message with the batch's texts and media in order; metadata of the last message.
+fn merge(mut messages: Vec<InboundMessage>) -> InboundMessage {
+    if messages.len() == 1 {
+        return messages.remove(0);
+    }
+    let count = messages.len();
+    let content = messages
+        .iter()
+        .map(|m| m.content.trim())
+        .filter(|c| !c.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let media = messages.iter().flat_map(|m| m.media.clone()).collect();
+    let mut merged = messages.pop().expect("batch is not empty");
+    merged.content = content;
+    merged.media = media;
+    if let Some(meta) = merged.metadata.as_object_mut() {
+        meta.insert("batched_messages".to_string(), count.into());
+    }
+    merged
+}
+
+impl Batcher {
+    pub fn new(config: BatchWindowConfig) -> Self {
+        Self {
+            config,
+            pending: HashMap::new(),
+        }
+    }
+
+    fn window(&self, msg: &InboundMessage) -> Option<Duration> {
+        if !crate::idle::is_interactive(msg)
+            || is_cancel(msg)
+            || crate::inline_query::inline_query_id(msg).is_some()
+            || msg.content.trim_start().starts_with('/')
+        {
+            return None;
+        }
+        self.config.window_for(&msg.channel)
+    }
+
+    /// Take `msg` in; returns what must be forwarded right away, in order.
+    pub fn push(&mut self, msg: InboundMessage, now: Instant) -> Vec<InboundMessage> {
+        let key = batch_key(&msg);
+        let Some(window) = self.window(&msg) else {
+            let held = self.pending.remove(&key);
+            if is_cancel(&msg) {
+                // The user stopped the conversation, including what they were still typing.
+                return vec![msg];
+            }
+            // Commands and system messages go after what the sender already wrote.
+            let mut out: Vec<InboundMessage> =
+                held.map(|p| merge(p.messages)).into_iter().collect();
+            out.push(msg);
+            return out;
+        };
+
+        let max_wait = Duration::from_secs(self.config.max_wait_secs);
+        let pending = self.pending.entry(key).or_insert_with(|| Pending {
+            messages: Vec::new(),
+            first_at: now,
+            deadline: now,
+        });
+        pending.messages.push(msg);
+        pending.deadline = (now + window).min(pending.first_at + max_wait);
+        Vec::new()
+    }
+
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.pending.values().map(|p| p.deadline).min()
+    }
+
+    /// Batches whose wait is over, oldest first.
+    pub fn take_due(&mut self, now: Instant) -> Vec<InboundMessage> {
+        let mut due: Vec<(Instant, String)> = self
+            .pending
+            .iter()
+            .filter(|(_, p)| p.deadline <= now)
+            .map(|(key, p)| (p.first_at, key.clone()))
+            .collect();
+        due.sort();
+        due.into_iter()
+            .filter_map(|(_, key)| self.pending.remove(&key))
+            .map(|p| {
+                if p.messages.len() > 1 {
+                    debug!(messages = p.messages.len(), "Coalesced message batch");
+                }
+                merge(p.messages)
+            })
+            .collect()
+    }
+
+    /// Everything still held, for shutdown.
+    pub fn drain(&mut self) -> Vec<InboundMessage> {
+        let mut all: Vec<Pending> = self.pending.drain().map(|(_, p)| p).collect();
+        all.sort_by_key(|p| p.first_at);
+        all.into_iter().map(|p| merge(p.messages)).collect()
+    }
+}
+
+/// Put a batcher in front of `inbound_rx` and return the receiver the runtime reads.
+pub fn spawn(
+    mut inbound_rx: mpsc::Receiver<InboundMessage>,
+    config: BatchWindowConfig,
+) -> mpsc::Receiver<InboundMessage> {
+    let (tx, rx) = mpsc::channel(100);
+    info!(
+        window_secs = config.window_secs,
+        channels = config.channels.len(),
+        "Message batching enabled"
+    );
+    tokio::spawn(async move {
+        let mut batcher = Batcher::new(config);
+        loop {
+            let deadline = batcher.next_deadline();
+            let ready = tokio::select! {
+                msg = inbound_rx.recv() => match msg {
+                    Some(msg) => batcher.push(msg, Instant::now()),
+                    None => {
+                        for msg in batcher.drain() {
+                            let _ = tx.send(msg).await;
+                        }
+                        return;
+                    }
+                },
+                _ = async {
+                    match deadline {
+                        Some(at) => tokio::time::sleep_until(at).await,
+                        None => std::future::pending::<()>().await,
+                    }
+                } => batcher.take_due(Instant::now()),
+            };
+            for msg in ready {
+                if tx.send(msg).await.is_err() {
+                    return;
+                }
+            }
+        }
+    });
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn telegram(content: &str) -> InboundMessage {
+        InboundMessage {
+            channel: "telegram".to_string(),
+            account_id: None,
+            sender_id: "42".to_string(),
+            chat_id: "42".to_string(),
+            content: content.to_string(),
+            media: vec![],
+            metadata: serde_json::json!({}),
+            timestamp_ms: 0,
+        }
+    }
+
+    fn batcher() -> Batcher {
+        let mut config = BatchWindowConfig::default();
+        config.channels.insert("telegram".to_string(), 3);
+        config.max_wait_secs = 10;
+        Batcher::new(config)
+    }
+
+    #[test]
+    fn test_messages_within_window_become_one_turn() {
+        let mut batcher = batcher();
+        let start = Instant::now();
+        assert!(batcher.push(telegram("hi"), start).is_empty());
+        assert!(batcher
+            .push(telegram("can you check"), start + Duration::from_secs(2))
+            .is_empty());
+        assert!(batcher
+            .push(telegram("my calendar"), start + Duration::from_secs(4))
+            .is_empty());
+
+        // Each message restarts the window.
+        assert!(batcher.take_due(start + Duration::from_secs(6)).is_empty());
+        let out = batcher.take_due(start + Duration::from_secs(7));
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].content, "hi\ncan you check\nmy calendar");
+        assert_eq!(out[0].metadata["batched_messages"], 3);
+        assert!(batcher.next_deadline().is_none());
+
+        // A steady stream is still cut off at maxWaitSecs.
+        for i in 0..6 {
+            batcher.push(telegram("more"), start + Duration::from_secs(20 + i * 2));
+        }
+        assert_eq!(
+            batcher.next_deadline(),
+            Some(start + Duration::from_secs(30))
+        );
+    }
+
+    #[test]
+    fn test_commands_flush_and_other_channels_pass_through() {
+        let mut batcher = batcher();
+        let now = Instant::now();
+        batcher.push(telegram("first"), now);
+        let out = batcher.push(telegram("/status"), now);
+        let texts: Vec<&str> = out.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(texts, vec!["first", "/status"]);
+
+        let mut ws = telegram("hello");
+        ws.channel = "ws".to_string();
+        assert_eq!(batcher.push(ws, now).len(), 1);
+
+        batcher.push(telegram("typing..."), now);
+        let mut cancel = telegram("");
+        cancel.metadata = serde_json::json!({ "cancel": true });
+        assert_eq!(batcher.push(cancel, now).len(), 1);
+        assert!(batcher.next_deadline().is_none());
+    }
+}
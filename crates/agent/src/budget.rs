@@ -0,0 +1,251 @@
+//! Monthly provider spend guardrail (`agents.defaults.budget`).
+//!
+//! Each LLM call's token usage is priced with the pool entry's `inputPrice`/`outputPrice`
+//! (USD per 1M tokens) and accumulated in a small JSON ledger that resets every calendar
+//! month (UTC). The runtime turns newly crossed thresholds into user notifications; once
+//! the hard cap is reached chat moves to the fallback model and background work pauses.
+
+use std::collections::BTreeMap;
+
+use blockcell_core::config::BudgetConfig;
+use blockcell_core::{Paths, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Spend accumulated during one calendar month.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpendLedger {
+    /// Month the totals belong to, as `YYYY-MM`.
+    pub month: String,
+    pub spent_usd: f64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub calls: u64,
+    #[serde(default)]
+    pub by_model: BTreeMap<String, f64>,
+    /// Soft thresholds already notified this month.
+    #[serde(default)]
+    pub notified: Vec<f64>,
+    #[serde(default)]
+    pub cap_notified: bool,
+}
+
+/// A threshold crossing that should be reported to the user.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BudgetAlert {
+    Soft {
+        threshold: f64,
+        spent_usd: f64,
+        limit_usd: f64,
+    },
+    HardCap {
+        spent_usd: f64,
+        limit_usd: f64,
+    },
+}
+
+fn current_month() -> String {
+    Utc::now().format("%Y-%m").to_string()
+}
+
+/// `(input, output)` token counts from a provider usage object. Providers normalize to
+/// `prompt_tokens`/`completion_tokens`; raw Anthropic-style names are accepted too.
+pub fn usage_tokens(usage: &Value) -> (u64, u64) {
+    let pick = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|k| usage.get(*k).and_then(|v| v.as_u64()))
+            .unwrap_or(0)
+    };
+    (
+        pick(&["prompt_tokens", "input_tokens"]),
+        pick(&["completion_tokens", "output_tokens"]),
+    )
+}
+
+impl SpendLedger {
+    /// Load this month's ledger; a missing, unreadable or stale file starts a new month.
+    pub fn load(paths: &Paths) -> Self {
+        let month = current_month();
+        let ledger = std::fs::read_to_string(paths.spend_ledger_file())
+            .ok()
+            .and_then(|s| serde_json::from_str::<SpendLedger>(&s).ok())
+            .filter(|l| l.month == month);
+        ledger.unwrap_or(SpendLedger {
+            month,
+            ..Default::default()
+        })
+    }
+
+    pub fn save(&self, paths: &Paths) -> Result<()> {
+        let path = paths.spend_ledger_file();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Add one call's usage. Returns its cost in USD (0 when the model has no price).
+    pub fn record(
+        &mut self,
+        model: &str,
+        usage: &Value,
+        input_price: Option<f64>,
+        output_price: Option<f64>,
+    ) -> f64 {
+        let (input, output) = usage_tokens(usage);
+        let cost = (input as f64 * input_price.unwrap_or(0.0)
+            + output as f64 * output_price.unwrap_or(0.0))
+            / 1_000_000.0;
+        self.spent_usd += cost;
+        self.input_tokens += input;
+        self.output_tokens += output;
+        self.calls += 1;
+        *self.by_model.entry(model.to_string()).or_insert(0.0) += cost;
+        cost
+    }
+
+    pub fn over_cap(&self, cfg: &BudgetConfig) -> bool {
+        cfg.is_active() && self.spent_usd >= cfg.monthly_usd
+    }
+
+    /// Thresholds crossed since the last call, marked as notified so each fires once
+    /// per month. A hard-cap crossing supersedes the soft alerts it skipped over.
+    pub fn take_alerts(&mut self, cfg: &BudgetConfig) -> Vec<BudgetAlert> {
+        if !cfg.is_active() {
+            return Vec::new();
+        }
+        let limit_usd = cfg.monthly_usd;
+        let ratio = self.spent_usd / limit_usd;
+        if ratio >= 1.0 {
+            if self.cap_notified {
+                return Vec::new();
+            }
+            self.cap_notified = true;
+            self.notified = cfg.soft_thresholds.clone();
+            return vec![BudgetAlert::HardCap {
+                spent_usd: self.spent_usd,
+                limit_usd,
+            }];
+        }
+        let mut crossed: Vec<f64> = cfg
+            .soft_thresholds
+            .iter()
+            .copied()
+            .filter(|t| *t > 0.0 && *t < 1.0 && ratio >= *t && !self.notified.contains(t))
+            .collect();
+        crossed.sort_by(|a, b| a.total_cmp(b));
+        self.notified.extend(crossed.iter().copied());
+        // Only the highest newly crossed threshold is worth a message.
+        crossed
+            .last()
+            .map(|threshold| BudgetAlert::Soft {
+                threshold: *threshold,
+                spent_usd: self.spent_usd,
+                limit_usd,
+            })
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Whether background work (ghost routines, evolution) should be refused right now.
+pub fn background_paused(paths: &Paths, cfg: &BudgetConfig) -> bool {
+    cfg.is_active() && cfg.pause_background && SpendLedger::load(paths).over_cap(cfg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn budget(limit: f64) -> BudgetConfig {
+        BudgetConfig {
+            enabled: true,
+            monthly_usd: limit,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_record_prices_usage() {
+        let mut ledger = SpendLedger::default();
+        let cost = ledger.record(
+            "gpt-4o",
+            &json!({"prompt_tokens": 200_000, "completion_tokens": 50_000}),
+            Some(2.5),
+            Some(10.0),
+        );
+        assert!((cost - 1.0).abs() < 1e-9);
+        ledger.record(
+            "claude",
+            &json!({"input_tokens": 1000, "output_tokens": 1000}),
+            None,
+            None,
+        );
+        assert_eq!(ledger.calls, 2);
+        assert_eq!(ledger.input_tokens, 201_000);
+        assert!((ledger.spent_usd - 1.0).abs() < 1e-9);
+        assert_eq!(ledger.by_model.get("claude"), Some(&0.0));
+    }
+
+    #[test]
+    fn test_alerts_fire_once_per_threshold() {
+        let cfg = budget(10.0);
+        let mut ledger = SpendLedger {
+            spent_usd: 4.0,
+            ..Default::default()
+        };
+        assert!(ledger.take_alerts(&cfg).is_empty());
+
+        ledger.spent_usd = 8.5;
+        assert_eq!(
+            ledger.take_alerts(&cfg),
+            vec![BudgetAlert::Soft {
+                threshold: 0.8,
+                spent_usd: 8.5,
+                limit_usd: 10.0
+            }]
+        );
+        assert!(ledger.take_alerts(&cfg).is_empty());
+        assert!(!ledger.over_cap(&cfg));
+
+        ledger.spent_usd = 10.2;
+        assert!(matches!(
+            ledger.take_alerts(&cfg).as_slice(),
+            [BudgetAlert::HardCap { .. }]
+        ));
+        assert!(ledger.take_alerts(&cfg).is_empty());
+        assert!(ledger.over_cap(&cfg));
+
+        assert!(!ledger.over_cap(&budget(0.0)));
+        assert!(SpendLedger::default().take_alerts(&budget(0.0)).is_empty());
+    }
+
+    #[test]
+    fn test_ledger_rolls_over_each_month() {
+        let dir = std::env::temp_dir().join(format!("blockcell_budget_{}", uuid::Uuid::new_v4()));
+        let paths = Paths::with_base(dir.clone());
+        let stale = SpendLedger {
+            month: "1999-01".to_string(),
+            spent_usd: 99.0,
+            cap_notified: true,
+            ..Default::default()
+        };
+        stale.save(&paths).unwrap();
+        let ledger = SpendLedger::load(&paths);
+        assert_eq!(ledger.month, current_month());
+        assert_eq!(ledger.spent_usd, 0.0);
+        assert!(!background_paused(&paths, &budget(1.0)));
+
+        let mut current = ledger;
+        current.spent_usd = 2.0;
+        current.save(&paths).unwrap();
+        assert!(background_paused(&paths, &budget(1.0)));
+        let mut keep_going = budget(1.0);
+        keep_going.pause_background = false;
+        assert!(!background_paused(&paths, &keep_going));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -83,6 +83,7 @@ impl CapabilityRegistryOps for CapabilityRegistryAdapter {
                 "status": format!("{:?}", c.status),
                 "version": c.version,
                 "privilege": format!("{:?}", c.privilege),
+                "network": c.network,
             })
         })
     }
@@ -212,10 +213,15 @@ impl CoreEvolutionOps for CoreEvolutionAdapter {
     async fn unblock_capability(&self, capability_id: &str) -> Result<Value> {
         let core_evo = self.inner.lock().await;
         let unblocked = core_evo.unblock_capability(capability_id)?;
+        // Also lift a block set by the network sandbox.
+        let network_unblocked = core_evo.registry().lock().await.unblock(capability_id);
         Ok(json!({
             "capability_id": capability_id,
             "unblocked": unblocked,
-            "message": if unblocked > 0 {
+            "network_unblocked": network_unblocked,
+            "message": if network_unblocked {
+                format!("Capability '{}' has been unblocked after a network policy violation. Re-evolve it with the right network scope if it needs more hosts.", capability_id)
+            } else if unblocked > 0 {
                 format!("Capability '{}' has been unblocked ({} records). It can now be auto-triggered again.", capability_id, unblocked)
             } else {
                 format!("Capability '{}' was not blocked.", capability_id)
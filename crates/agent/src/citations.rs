@@ -0,0 +1,249 @@
+//! Citation mode (`agents.defaults.citations`).
+//!
+//! Results of web, file and memory tools are numbered as sources while the turn runs;
+//! each tool result tells the model which numbers it may cite as `[n]`. When the answer
+//! is delivered, the sources it cites (or all of them, if it cites none) are appended as
+//! a numbered list and sent as `citations` metadata for the WebUI to render as links.
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Sources taken from a single search or memory query.
+const MAX_PER_RESULT: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Source {
+    pub id: usize,
+    /// `web`, `file` or `memory`.
+    pub kind: &'static str,
+    pub title: String,
+    /// URL, workspace path or memory id.
+    pub target: String,
+}
+
+/// Sources collected during the current turn.
+#[derive(Debug, Default)]
+pub struct CitationTracker {
+    sources: Vec<Source>,
+}
+
+/// A finished answer's source list.
+pub struct Citations {
+    pub footer: String,
+    pub sources: Value,
+}
+
+impl Citations {
+    pub fn append_to(&self, text: &str) -> String {
+        format!("{}\n\n{}", text.trim_end(), self.footer)
+    }
+}
+
+fn extract(tool: &str, params: &Value, result: &Value) -> Vec<(&'static str, String, String)> {
+    let str_of = |v: &Value, key: &str| {
+        v.get(key)
+            .and_then(|s| s.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+    match tool {
+        "web_search" => result
+            .get("results")
+            .and_then(|r| r.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|r| {
+                let url = str_of(r, "url")?;
+                let title = str_of(r, "title").unwrap_or_else(|| url.clone());
+                Some(("web", title, url))
+            })
+            .take(MAX_PER_RESULT)
+            .collect(),
+        "web_fetch" => str_of(result, "finalUrl")
+            .or_else(|| str_of(params, "url"))
+            .map(|url| {
+                (
+                    "web",
+                    str_of(result, "title").unwrap_or_else(|| url.clone()),
+                    url,
+                )
+            })
+            .into_iter()
+            .collect(),
+        "read_file" => str_of(params, "path")
+            .map(|path| ("file", path.clone(), path))
+            .into_iter()
+            .collect(),
+        "memory_query" => result
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|r| {
+                let item = r.get("item")?;
+                let id = str_of(item, "id")?;
+                let title = str_of(item, "title")
+                    .or_else(|| str_of(item, "summary"))
+                    .map(|t| t.chars().take(80).collect())
+                    .unwrap_or_else(|| id.clone());
+                Some(("memory", title, id))
+            })
+            .take(MAX_PER_RESULT)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// `[n]` markers in `text`.
+fn cited_ids(text: &str) -> Vec<usize> {
+    let mut ids = Vec::new();
+    let mut rest = text;
+    while let Some(open) = rest.find('[') {
+        rest = &rest[open + 1..];
+        if let Some(close) = rest.find(']') {
+            if let Ok(id) = rest[..close].parse::<usize>() {
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+    }
+    ids
+}
+
+impl CitationTracker {
+    pub fn clear(&mut self) {
+        self.sources.clear();
+    }
+
+    /// Number the sources in a tool result. Returns a note for the model listing the
+    /// numbers to cite, or `None` when the result contributed no source.
+    pub fn record(
+        &mut self,
+        tool: &str,
+        params: &Value,
+        result: &Value,
+        max_sources: usize,
+    ) -> Option<String> {
+        let mut ids = Vec::new();
+        for (kind, title, target) in extract(tool, params, result) {
+            let id = match self.sources.iter().find(|s| s.target == target) {
+                Some(existing) => existing.id,
+                None if self.sources.len() < max_sources => {
+                    let id = self.sources.len() + 1;
+                    self.sources.push(Source {
+                        id,
+                        kind,
+                        title,
+                        target,
+                    });
+                    id
+                }
+                None => continue,
+            };
+            ids.push(id);
+        }
+        if ids.is_empty() {
+            return None;
+        }
+        let listed: Vec<String> = ids
+            .iter()
+            .filter_map(|id| self.sources.get(id - 1))
+            .map(|s| format!("[{}] {}", s.id, s.title))
+            .collect();
+        Some(format!(
+            "[Sources — cite as [n] where used] {}",
+            listed.join("; ")
+        ))
+    }
+
+    /// Sources for the final answer: those it cites, or every source when it cites none.
+    pub fn finish(&self, reply: &str) -> Option<Citations> {
+        if self.sources.is_empty() {
+            return None;
+        }
+        let cited: Vec<&Source> = cited_ids(reply)
+            .into_iter()
+            .filter_map(|id| self.sources.get(id.wrapping_sub(1)))
+            .collect();
+        let mut used = if cited.is_empty() {
+            self.sources.iter().collect()
+        } else {
+            cited
+        };
+        used.sort_by_key(|s| s.id);
+
+        let mut footer = String::from("Sources:");
+        for source in &used {
+            if source.title == source.target {
+                footer.push_str(&format!("\n[{}] {}", source.id, source.target));
+            } else {
+                footer.push_str(&format!(
+                    "\n[{}] {} — {}",
+                    source.id, source.title, source.target
+                ));
+            }
+        }
+        Some(Citations {
+            footer,
+            sources: serde_json::to_value(&used).unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_record_numbers_and_dedupes_sources() {
+        let mut tracker = CitationTracker::default();
+        let search = json!({"results": [
+            {"title": "Rust 2.0", "url": "https://a.io"},
+            {"title": "Blog", "url": "https://b.io"}
+        ]});
+        let note = tracker
+            .record("web_search", &json!({"query": "rust"}), &search, 10)
+            .unwrap();
+        assert!(note.contains("[1] Rust 2.0; [2] Blog"));
+
+        let fetch = json!({"url": "https://a.io", "finalUrl": "https://a.io", "text": "..."});
+        let note = tracker
+            .record("web_fetch", &json!({"url": "https://a.io"}), &fetch, 10)
+            .unwrap();
+        assert!(note.contains("[1] Rust 2.0"));
+
+        let memory = json!([{"item": {"id": "m-7", "summary": "User likes Rust"}, "score": 0.9}]);
+        assert!(tracker
+            .record("memory_query", &json!({}), &memory, 3)
+            .unwrap()
+            .contains("[3] User likes Rust"));
+        assert!(tracker
+            .record("read_file", &json!({"path": "notes.md"}), &json!("x"), 3)
+            .is_none());
+        assert!(tracker.record("exec", &json!({}), &json!({}), 10).is_none());
+    }
+
+    #[test]
+    fn test_finish_lists_cited_sources() {
+        let mut tracker = CitationTracker::default();
+        assert!(tracker.finish("no tools used").is_none());
+        tracker.record("read_file", &json!({"path": "docs/a.md"}), &json!("a"), 10);
+        tracker.record("read_file", &json!({"path": "docs/b.md"}), &json!("b"), 10);
+
+        let citations = tracker.finish("B says so [2].").unwrap();
+        assert_eq!(citations.footer, "Sources:\n[2] docs/b.md");
+        assert_eq!(citations.sources[0]["kind"], "file");
+        assert_eq!(
+            citations.append_to("B says so [2].\n"),
+            "B says so [2].\n\nSources:\n[2] docs/b.md"
+        );
+
+        let all = tracker.finish("Uncited answer, see [note].").unwrap();
+        assert_eq!(all.footer, "Sources:\n[1] docs/a.md\n[2] docs/b.md");
+
+        tracker.clear();
+        assert!(tracker.finish("[1]").is_none());
+    }
+}
@@ -0,0 +1,290 @@
+//! Response validation: side-effect claims in a final answer must be backed by evidence.
+//!
+//! Automation answers often end with "saved the report to X" or "job created with id
+//! Y". When no tool in the turn actually produced X or Y the claim is a hallucinated
+//! side effect. This module extracts such claims and checks them against the turn's tool
+//! calls, tool results and artifacts (`agents.defaults.responseValidation`).
+
+use std::path::{Path, PathBuf};
+
+use blockcell_core::types::ChatMessage;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Claim {
+    /// A file the answer says was written.
+    File(String),
+    /// An identifier the answer says a tool returned (`kind` is the noun it followed).
+    Id { kind: String, id: String },
+}
+
+impl Claim {
+    fn describe(&self) -> String {
+        match self {
+            Claim::File(path) => format!("文件 `{}`", path),
+            Claim::Id { kind, id } => format!("{} ID `{}`", kind, id),
+        }
+    }
+}
+
+const PATH: &str = r"(?:~|\.{1,2})?/?[\w.-]+(?:/[\w.-]+)*\.[A-Za-z0-9]{1,8}";
+
+static FILE_CLAIMS: Lazy<Vec<Regex>> = Lazy::new(|| {
+    [
+        format!(
+            r"(?i)\b(?:saved|wrote|written|exported|generated|stored|downloaded|created)\b[^.\n]{{0,60}}?[`'\x22]?(?P<path>{})",
+            PATH
+        ),
+        format!(
+            r"(?:保存|写入|生成|导出|创建|下载|存储)[^。\n]{{0,20}}?[`'\x22「]?(?P<path>{})",
+            PATH
+        ),
+    ]
+    .iter()
+    .map(|p| Regex::new(p).expect("valid file claim pattern"))
+    .collect()
+});
+
+static ID_CLAIMS: Lazy<Vec<Regex>> = Lazy::new(|| {
+    [
+        r"(?i)\b(?P<kind>cron job|job|task|reminder|schedule|issue|ticket|order|event|record|entry|memory|share)\b[^.\n]{0,30}?\bid\b\s*(?:is|:|#|=)?\s*[`'\x22]?(?P<id>[A-Za-z0-9][A-Za-z0-9_:-]{3,})",
+        r"(?P<kind>定时任务|任务|作业|提醒|工单|订单|事件|记录|记忆|分享)[^。\n]{0,15}?(?:ID|id|编号)\s*(?:[:：为是]\s*)?[`'\x22]?(?P<id>[A-Za-z0-9][A-Za-z0-9_:-]{3,})",
+    ]
+    .iter()
+    .map(|p| Regex::new(p).expect("valid id claim pattern"))
+    .collect()
+});
+
+/// Side-effect claims made in `text`, deduplicated, in order of appearance.
+pub fn extract_claims(text: &str) -> Vec<Claim> {
+    let mut claims: Vec<(usize, Claim)> = Vec::new();
+    for re in FILE_CLAIMS.iter() {
+        for caps in re.captures_iter(text) {
+            let m = caps.name("path").expect("path group");
+            let path = m.as_str().trim_end_matches('.');
+            // "e.g"/"v1.2" style tokens are not paths.
+            if path.contains('/')
+                || path
+                    .rsplit('.')
+                    .next()
+                    .is_some_and(|ext| ext.chars().any(|c| c.is_ascii_alphabetic()))
+            {
+                claims.push((m.start(), Claim::File(path.to_string())));
+            }
+        }
+    }
+    for re in ID_CLAIMS.iter() {
+        for caps in re.captures_iter(text) {
+            let m = caps.name("id").expect("id group");
+            let id = m.as_str().trim_end_matches([':', '-']);
+            // Require a digit so words like "task id is missing" are not claims.
+            if id.chars().any(|c| c.is_ascii_digit()) {
+                claims.push((
+                    m.start(),
+                    Claim::Id {
+                        kind: caps["kind"].to_lowercase(),
+                        id: id.to_string(),
+                    },
+                ));
+            }
+        }
+    }
+    claims.sort_by_key(|(pos, _)| *pos);
+    let mut out: Vec<Claim> = Vec::new();
+    for (_, claim) in claims {
+        if !out.contains(&claim) {
+            out.push(claim);
+        }
+    }
+    out
+}
+
+/// Everything the turn's tools did, as searchable text.
+pub struct Evidence {
+    corpus: String,
+    workspace: Option<PathBuf>,
+}
+
+impl Evidence {
+    /// Collect tool calls and results from `messages` plus artifact paths.
+    pub fn from_turn(messages: &[ChatMessage], artifacts: &[String]) -> Self {
+        let mut corpus = String::new();
+        for msg in messages {
+            if let Some(calls) = &msg.tool_calls {
+                for call in calls {
+                    corpus.push_str(&call.name);
+                    corpus.push('\n');
+                    corpus.push_str(&call.arguments.to_string());
+                    corpus.push('\n');
+                }
+            }
+            if msg.role == "tool" {
+                match msg.content.as_str() {
+                    Some(s) => corpus.push_str(s),
+                    None => corpus.push_str(&msg.content.to_string()),
+                }
+                corpus.push('\n');
+            }
+        }
+        for artifact in artifacts {
+            corpus.push_str(artifact);
+            corpus.push('\n');
+        }
+        // Tool arguments are JSON-escaped; unescape slashes so paths match.
+        let corpus = corpus.replace("\\/", "/");
+        Self {
+            corpus,
+            workspace: None,
+        }
+    }
+
+    /// Also accept file claims whose path exists relative to `workspace`.
+    pub fn with_workspace(mut self, workspace: &Path) -> Self {
+        self.workspace = Some(workspace.to_path_buf());
+        self
+    }
+
+    fn file_exists(&self, path: &str) -> bool {
+        let Some(workspace) = &self.workspace else {
+            return false;
+        };
+        let candidate = if let Some(rest) = path.strip_prefix("~/") {
+            match dirs::home_dir() {
+                Some(home) => home.join(rest),
+                None => return false,
+            }
+        } else if Path::new(path).is_absolute() {
+            PathBuf::from(path)
+        } else {
+            workspace.join(path)
+        };
+        candidate.is_file()
+    }
+
+    fn supports(&self, claim: &Claim) -> bool {
+        match claim {
+            Claim::File(path) => {
+                let name = Path::new(path)
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or(path);
+                self.corpus.contains(path.trim_start_matches("./"))
+                    || self.corpus.contains(name)
+                    || self.file_exists(path)
+            }
+            Claim::Id { id, .. } => self.corpus.contains(id.as_str()),
+        }
+    }
+}
+
+/// Claims in `response` that nothing in `evidence` backs up.
+pub fn unverified_claims(response: &str, evidence: &Evidence) -> Vec<Claim> {
+    extract_claims(response)
+        .into_iter()
+        .filter(|c| !evidence.supports(c))
+        .collect()
+}
+
+/// Correction turn asking the model to fix or actually perform the unbacked claims.
+pub fn correction_prompt(claims: &[Claim]) -> String {
+    let list = claims
+        .iter()
+        .map(|c| format!("- {}", c.describe()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "[系统] 你的回复声称完成了以下操作，但本轮的工具调用和结果中没有任何证据：\n{}\n\
+         如果这些操作确实需要执行，请现在调用相应工具完成；否则请如实改写回复，不要声称已保存文件或已创建 ID。",
+        list
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blockcell_core::types::ToolCallRequest;
+    use serde_json::json;
+
+    fn turn(args: serde_json::Value, result: &str) -> Vec<ChatMessage> {
+        let mut call = ChatMessage::assistant("");
+        call.tool_calls = Some(vec![ToolCallRequest {
+            id: "c1".into(),
+            name: "write_file".into(),
+            arguments: args,
+            thought_signature: None,
+        }]);
+        vec![call, ChatMessage::tool_result("c1", result)]
+    }
+
+    #[test]
+    fn test_extract_claims() {
+        let claims = extract_claims(
+            "I saved the report to `reports/q3.md` and the cron job was created with ID job-8f2a91. \
+             已将图表导出到 charts/sales.png，任务ID：task_1042。Version 1.2 is e.g. fine.",
+        );
+        assert_eq!(
+            claims,
+            vec![
+                Claim::File("reports/q3.md".into()),
+                Claim::Id {
+                    kind: "cron job".into(),
+                    id: "job-8f2a91".into()
+                },
+                Claim::File("charts/sales.png".into()),
+                Claim::Id {
+                    kind: "任务".into(),
+                    id: "task_1042".into()
+                },
+            ]
+        );
+        assert!(extract_claims("The task id is missing, please retry.").is_empty());
+    }
+
+    #[test]
+    fn test_claims_backed_by_tool_results_pass() {
+        let messages = turn(
+            json!({"path": "reports/q3.md", "content": "..."}),
+            r#"{"status":"ok","job_id":"job-8f2a91"}"#,
+        );
+        let evidence = Evidence::from_turn(&messages, &[]);
+        let response = "Saved to reports/q3.md; cron job id job-8f2a91.";
+        assert!(unverified_claims(response, &evidence).is_empty());
+    }
+
+    #[test]
+    fn test_hallucinated_side_effects_are_flagged() {
+        let messages = turn(json!({"path": "notes.txt"}), r#"{"status":"ok"}"#);
+        let evidence = Evidence::from_turn(&messages, &["/ws/out/chart.png".to_string()]);
+        let response = "I saved the summary to summary.pdf, exported out/chart.png, \
+                        and the reminder was created with id rem-5521.";
+        let missing = unverified_claims(response, &evidence);
+        assert_eq!(
+            missing,
+            vec![
+                Claim::File("summary.pdf".into()),
+                Claim::Id {
+                    kind: "reminder".into(),
+                    id: "rem-5521".into()
+                }
+            ]
+        );
+        let prompt = correction_prompt(&missing);
+        assert!(prompt.starts_with("[系统]"));
+        assert!(prompt.contains("summary.pdf") && prompt.contains("rem-5521"));
+    }
+
+    #[test]
+    fn test_existing_workspace_file_counts_as_evidence() {
+        let dir = std::env::temp_dir().join(format!("blockcell_claims_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("done.csv"), "a,b").unwrap();
+        let evidence = Evidence::from_turn(&[], &[]).with_workspace(&dir);
+        assert!(unverified_claims("Exported the data to done.csv.", &evidence).is_empty());
+        assert_eq!(
+            unverified_claims("Exported the data to missing.csv.", &evidence).len(),
+            1
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
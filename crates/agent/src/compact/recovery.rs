@@ -84,7 +84,7 @@ impl CompactRecoveryContext {
     pub fn truncate_files_to_budget(&mut self, max_tokens: usize) {
         // 按重要性排序：修改过的文件优先
         self.files
-            .sort_by(|a, b| b.was_modified.cmp(&a.was_modified));
+            .sort_by_key(|b| std::cmp::Reverse(b.was_modified));
 
         let mut used_tokens = 0;
         self.files.retain(|f| {
@@ -150,7 +150,7 @@ pub async fn create_recovery_context(
         .collect();
 
     // 按重要性排序，保留最多 MAX_FILES_TO_RECOVER 个
-    file_states.sort_by(|a, b| b.estimated_tokens.cmp(&a.estimated_tokens));
+    file_states.sort_by_key(|b| std::cmp::Reverse(b.estimated_tokens));
     file_states.truncate(MAX_FILES_TO_RECOVER);
 
     for file in file_states {
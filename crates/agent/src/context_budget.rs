@@ -0,0 +1,195 @@
+//! Token budgets for prompt assembly.
+//!
+//! The budget is derived from the smallest context window among the models a request may
+//! be routed to (the whole `modelPool`, or the single default model), minus `maxTokens`
+//! reserved for the answer. Sections are truncated on char boundaries by estimated token
+//! count, never by byte offset.
+
+use crate::token::{estimate_message_tokens, estimate_tokens};
+use blockcell_core::model_meta;
+use blockcell_core::types::ChatMessage;
+use blockcell_core::Config;
+use tracing::debug;
+
+/// Effective per-section token budgets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextBudget {
+    /// Model the window was taken from.
+    pub model: String,
+    pub context_window: usize,
+    /// Tokens kept free for the completion.
+    pub reserved_output: usize,
+    pub memory: usize,
+    pub skills: usize,
+    pub history: usize,
+    /// The user's message, including attachment notes.
+    pub attachments: usize,
+}
+
+impl ContextBudget {
+    /// Budget for one model.
+    pub fn for_model(config: &Config, model: &str) -> Self {
+        let defaults = &config.agents.defaults;
+        let context_window = model_meta::lookup(config, model)
+            .map(|meta| meta.context_window)
+            .unwrap_or(defaults.max_context_tokens) as usize;
+        let reserved_output = (defaults.max_tokens as usize).min(context_window / 2);
+        let usable = context_window.saturating_sub(reserved_output);
+        let shares = &defaults.context_budget;
+        let share = |s: f64| (usable as f64 * s.clamp(0.0, 1.0)) as usize;
+        Self {
+            model: model.to_string(),
+            context_window,
+            reserved_output,
+            memory: share(shares.memory_share),
+            skills: share(shares.skills_share),
+            history: share(shares.history_share),
+            attachments: share(shares.attachments_share),
+        }
+    }
+
+    /// Budget for the configured chat model(s). With a pool, the tightest entry wins since
+    /// any entry may serve the request.
+    pub fn from_config(config: &Config) -> Self {
+        let defaults = &config.agents.defaults;
+        let budget = defaults
+            .model_pool
+            .iter()
+            .map(|entry| Self::for_model(config, &entry.model))
+            .min_by_key(|b| b.context_window)
+            .unwrap_or_else(|| Self::for_model(config, &defaults.model));
+        debug!(
+            model = %budget.model,
+            context_window = budget.context_window,
+            reserved_output = budget.reserved_output,
+            memory = budget.memory,
+            skills = budget.skills,
+            history = budget.history,
+            attachments = budget.attachments,
+            "Effective context budget"
+        );
+        budget
+    }
+}
+
+/// Longest prefix of `chars` whose estimate fits `max_tokens`.
+fn fit_prefix(chars: &[char], max_tokens: usize) -> usize {
+    let (mut lo, mut hi) = (0, chars.len());
+    while lo < hi {
+        let mid = (lo + hi).div_ceil(2);
+        let text: String = chars[..mid].iter().collect();
+        if estimate_tokens(&text) <= max_tokens {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    lo
+}
+
+/// Keep the beginning of `text` within `max_tokens`.
+pub fn truncate_to_tokens(text: &str, max_tokens: usize) -> String {
+    let total = estimate_tokens(text);
+    if total <= max_tokens {
+        return text.to_string();
+    }
+    let chars: Vec<char> = text.chars().collect();
+    let keep = fit_prefix(&chars, max_tokens);
+    let mut out: String = chars[..keep].iter().collect();
+    out.push_str(&format!(
+        "\n...<trimmed ~{} tokens>...",
+        total.saturating_sub(max_tokens)
+    ));
+    out
+}
+
+/// Keep the head (2/3) and tail (1/3) of `text` within `max_tokens`.
+pub fn trim_head_tail_tokens(text: &str, max_tokens: usize) -> String {
+    let total = estimate_tokens(text);
+    if total <= max_tokens {
+        return text.to_string();
+    }
+    let chars: Vec<char> = text.chars().collect();
+    let head = fit_prefix(&chars, max_tokens * 2 / 3);
+    let reversed: Vec<char> = chars[head..].iter().rev().copied().collect();
+    let tail = fit_prefix(&reversed, max_tokens.saturating_sub(max_tokens * 2 / 3));
+    format!(
+        "{}\n...<trimmed ~{} tokens>...\n{}",
+        chars[..head].iter().collect::<String>(),
+        total.saturating_sub(max_tokens),
+        chars[chars.len() - tail..].iter().collect::<String>()
+    )
+}
+
+/// Index of the oldest history message that still fits `max_tokens` counting back from
+/// the newest. The caller must still skip orphaned tool messages at that point.
+pub fn history_start_within(history: &[ChatMessage], max_tokens: usize) -> usize {
+    let mut used = 0;
+    for (idx, msg) in history.iter().enumerate().rev() {
+        used += estimate_message_tokens(msg);
+        if used > max_tokens {
+            return idx + 1;
+        }
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_uses_tightest_pool_entry() {
+        let mut config = Config::default();
+        config.agents.defaults.max_tokens = 4096;
+        let entry = |model: &str| blockcell_core::config::ModelEntry {
+            model: model.to_string(),
+            provider: "openai".to_string(),
+            weight: 1,
+            priority: 1,
+            input_price: None,
+            output_price: None,
+            temperature: None,
+            tool_call_mode: blockcell_core::config::ToolCallMode::Native,
+            group: None,
+        };
+        config.agents.defaults.model_pool = vec![entry("gpt-4o"), entry("deepseek-chat")];
+
+        let budget = ContextBudget::from_config(&config);
+        assert_eq!(budget.model, "deepseek-chat");
+        assert_eq!(budget.context_window, 65_536);
+        assert_eq!(budget.reserved_output, 4096);
+        assert_eq!(budget.history, ((65_536 - 4096) as f64 * 0.55) as usize);
+
+        let unknown = ContextBudget::for_model(&config, "mystery-model");
+        assert_eq!(
+            unknown.context_window,
+            config.agents.defaults.max_context_tokens as usize
+        );
+    }
+
+    #[test]
+    fn test_truncation_is_char_safe() {
+        let text = "记忆".repeat(2000) + "tail";
+        let trimmed = truncate_to_tokens(&text, 100);
+        assert!(trimmed.starts_with("记忆"));
+        assert!(trimmed.contains("<trimmed"));
+        assert!(estimate_tokens(&trimmed) < 130);
+
+        let both = trim_head_tail_tokens(&text, 90);
+        assert!(both.starts_with("记忆"));
+        assert!(both.ends_with("tail"));
+        assert_eq!(trim_head_tail_tokens("short", 90), "short");
+    }
+
+    #[test]
+    fn test_history_start_keeps_newest() {
+        let history: Vec<ChatMessage> = (0..20)
+            .map(|i| ChatMessage::user(&format!("message {} {}", i, "word ".repeat(50))))
+            .collect();
+        let per_msg = estimate_message_tokens(&history[0]);
+        let start = history_start_within(&history, per_msg * 5 + 1);
+        assert!((14..=16).contains(&start), "start = {}", start);
+        assert_eq!(history_start_within(&history, usize::MAX), 0);
+    }
+}
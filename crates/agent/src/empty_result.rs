@@ -0,0 +1,212 @@
+//! Honest empty-result contract for recall tools (memory, knowledge graph, session cache).
+//!
+//! A recall that finds nothing usually comes back as `[]`, a zero count or a terse
+//! `not_found`. Models have been seen to paper over that with invented memories or a
+//! made-up `ref:XXXXXXXX` cache ID (the `session_recall` regression), so the runtime
+//! rewrites such results into one explicit structured shape before the model sees them.
+
+use serde_json::{json, Value};
+
+/// Tools whose empty results are normalized.
+pub const RECALL_TOOLS: &[&str] = &["memory_query", "knowledge_graph", "session_recall"];
+
+/// `knowledge_graph` actions that read; write actions keep their own output.
+const KNOWLEDGE_READ_ACTIONS: &[&str] = &[
+    "search_entities",
+    "get_relations",
+    "find_path",
+    "subgraph",
+    "query",
+];
+
+const NO_RESULTS_INSTRUCTION: &str = "0 results. Tell the user nothing was found. Do not fabricate \
+     memories, entities, references or cache IDs (ref:XXXXXXXX); only cite IDs that appear verbatim \
+     in this conversation.";
+
+fn is_empty_array(value: Option<&Value>) -> bool {
+    value
+        .and_then(|v| v.as_array())
+        .is_some_and(|a| a.is_empty())
+}
+
+/// Whether `result` is an empty answer from a recall tool.
+fn is_empty_recall(tool_name: &str, args: &Value, result: &Value) -> bool {
+    match tool_name {
+        "session_recall" => result.get("status").and_then(|s| s.as_str()) == Some("not_found"),
+        "memory_query" => {
+            if args.get("stats").and_then(|v| v.as_bool()).unwrap_or(false) {
+                return false;
+            }
+            match result {
+                Value::Array(items) => items.is_empty(),
+                Value::Object(map) if !map.contains_key("error") => {
+                    is_empty_array(map.get("results")) || is_empty_array(map.get("items"))
+                }
+                _ => false,
+            }
+        }
+        "knowledge_graph" => {
+            let action = args.get("action").and_then(|a| a.as_str()).unwrap_or("");
+            if !KNOWLEDGE_READ_ACTIONS.contains(&action) || result.get("error").is_some() {
+                return false;
+            }
+            if result.get("found").and_then(|f| f.as_bool()) == Some(false) {
+                return true;
+            }
+            if result.get("count").and_then(|c| c.as_u64()) == Some(0) {
+                return true;
+            }
+            let lists: Vec<&Value> = ["entities", "relations"]
+                .iter()
+                .filter_map(|k| result.get(*k))
+                .collect();
+            !lists.is_empty() && lists.iter().all(|v| is_empty_array(Some(v)))
+        }
+        _ => false,
+    }
+}
+
+/// Build the structured "no results" tool result, or `None` if `result` is not an
+/// empty recall.
+pub fn no_results_payload(tool_name: &str, args: &Value, result: &str) -> Option<Value> {
+    if !RECALL_TOOLS.contains(&tool_name) {
+        return None;
+    }
+    let parsed: Value = serde_json::from_str(result.trim()).ok()?;
+    if !is_empty_recall(tool_name, args, &parsed) {
+        return None;
+    }
+    let mut payload = json!({
+        "status": "no_results",
+        "tool": tool_name,
+        "result_count": 0,
+        "instruction": NO_RESULTS_INSTRUCTION,
+    });
+    for key in ["query", "id", "action", "entity_id", "scope", "type"] {
+        if let Some(value) = args.get(key).filter(|v| !v.is_null()) {
+            payload[key] = value.clone();
+        }
+    }
+    if let Some(detail) = parsed.get("error").and_then(|e| e.as_str()) {
+        payload["detail"] = json!(detail);
+    }
+    Some(payload)
+}
+
+/// Post-tool-result normalization: replace empty recall output with the explicit
+/// no-results contract, leave everything else untouched.
+pub fn normalize_tool_result(tool_name: &str, args: &Value, result: String) -> String {
+    match no_results_payload(tool_name, args, &result) {
+        Some(payload) => {
+            tracing::debug!(tool = tool_name, "Normalized empty recall result");
+            payload.to_string()
+        }
+        None => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalized(tool: &str, args: Value, result: Value) -> Value {
+        serde_json::from_str(&normalize_tool_result(tool, &args, result.to_string())).unwrap()
+    }
+
+    #[test]
+    fn test_session_recall_miss_is_explicit() {
+        // Regression: a not_found recall was followed by an invented ref ID.
+        let out = normalized(
+            "session_recall",
+            json!({"id": "ref:deadbeef"}),
+            json!({"ref_id": "ref:deadbeef", "error": "未找到对应的缓存内容", "status": "not_found"}),
+        );
+        assert_eq!(out["status"], "no_results");
+        assert_eq!(out["result_count"], 0);
+        assert_eq!(out["id"], "ref:deadbeef");
+        assert!(out["instruction"]
+            .as_str()
+            .unwrap()
+            .contains("Do not fabricate"));
+        assert!(out["detail"].as_str().is_some());
+
+        let found = json!({"ref_id": "ref:1", "content": "x", "status": "found"});
+        assert_eq!(
+            normalized("session_recall", json!({"id": "ref:1"}), found.clone()),
+            found
+        );
+    }
+
+    #[test]
+    fn test_memory_query_empty_and_non_empty() {
+        let out = normalized(
+            "memory_query",
+            json!({"query": "wife's birthday"}),
+            json!([]),
+        );
+        assert_eq!(out["status"], "no_results");
+        assert_eq!(out["query"], "wife's birthday");
+
+        let hits = json!([{"item": {"id": "m1"}, "score": 0.9}]);
+        assert_eq!(
+            normalized("memory_query", json!({"query": "x"}), hits.clone()),
+            hits
+        );
+        // Stats mode is not a recall.
+        let stats = json!({"total_active": 0, "by_scope": []});
+        assert_eq!(
+            normalized("memory_query", json!({"stats": true}), stats.clone()),
+            stats
+        );
+    }
+
+    #[test]
+    fn test_knowledge_graph_read_actions_only() {
+        let out = normalized(
+            "knowledge_graph",
+            json!({"action": "search_entities", "query": "Alice"}),
+            json!({"entities": [], "count": 0, "query": "Alice"}),
+        );
+        assert_eq!(out["status"], "no_results");
+        let out = normalized(
+            "knowledge_graph",
+            json!({"action": "find_path"}),
+            json!({"found": false, "source_id": "a", "target_id": "b"}),
+        );
+        assert_eq!(out["status"], "no_results");
+        let out = normalized(
+            "knowledge_graph",
+            json!({"action": "subgraph", "entity_id": "a"}),
+            json!({"entities": [], "relations": [], "entity_count": 0}),
+        );
+        assert_eq!(out["entity_id"], "a");
+
+        let deleted = json!({"status": "deleted", "count": 0});
+        assert_eq!(
+            normalized(
+                "knowledge_graph",
+                json!({"action": "delete_entity"}),
+                deleted.clone()
+            ),
+            deleted
+        );
+    }
+
+    #[test]
+    fn test_other_tools_and_non_json_untouched() {
+        assert_eq!(
+            normalize_tool_result("web_search", &json!({}), "[]".to_string()),
+            "[]"
+        );
+        assert_eq!(
+            normalize_tool_result(
+                "memory_query",
+                &json!({}),
+                "Error: store offline".to_string()
+            ),
+            "Error: store offline"
+        );
+        let err = json!({"error": "Memory store not available"});
+        assert_eq!(normalized("memory_query", json!({}), err.clone()), err);
+    }
+}
@@ -67,6 +67,41 @@ pub(crate) fn dangerous_file_ops_denied() -> String {
     )
 }
 
+/// Build a permission-denied error for tool calls that declared confirmation items.
+pub(crate) fn confirmation_denied(tool_name: &str, has_confirm_channel: bool) -> String {
+    let hint = if has_confirm_channel {
+        "The user did not approve this operation. Do not retry it unless the user asks again."
+    } else {
+        "This channel cannot show an interactive confirm prompt. Ask the user to reply with '确认执行' to proceed."
+    };
+    tool_denied_json(
+        tool_name,
+        "Permission denied: this operation requires explicit user confirmation.",
+        hint,
+    )
+}
+
+/// Build the error for a network tool called while offline mode is active.
+pub(crate) fn offline_tool_result(tool_name: &str) -> String {
+    tool_denied_json(
+        tool_name,
+        &format!(
+            "Tool '{}' needs the network, and this device is offline right now.",
+            tool_name
+        ),
+        "Tell the user the network is unavailable and answer from local tools and memory. Network tools work again automatically once the connection is back.",
+    )
+}
+
+/// Build the error for a tool the sender's role may not use (`permissions`).
+pub(crate) fn role_denied_result(tool_name: &str, reason: &str) -> String {
+    tool_denied_json(
+        tool_name,
+        reason,
+        "Tell the user this action needs a higher role and do not retry it. An admin can change roles under `permissions` in config.json5.",
+    )
+}
+
 /// Build a path-access denied error.
 pub(crate) fn path_access_denied(tool_name: &str, path: &str) -> String {
     tool_denied_json(
@@ -0,0 +1,376 @@
+//! Idle-time self-maintenance (`agents.defaults.idleMaintenance`).
+//!
+//! The runtime notes every interactive message. Once none has arrived for `idleMinutes`
+//! the tick loop starts at most one maintenance job per tick, picking whichever due job
+//! ran longest ago. Jobs run as background tasks so the next user message can abort the
+//! one in flight; an aborted job is simply retried on the next idle stretch.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime};
+
+use blockcell_core::config::IdleMaintenanceConfig;
+use blockcell_core::system_event::{EventPriority, SystemEvent};
+use blockcell_core::types::ChatMessage;
+use blockcell_core::{Config, InboundMessage, Paths};
+use blockcell_storage::SessionStore;
+use blockcell_tools::{EventEmitterHandle, MemoryStoreHandle};
+use tracing::{debug, info, warn};
+
+/// Channels fed by the agent itself; their messages do not reset the idle timer.
+const BACKGROUND_CHANNELS: &[&str] = &["cron", "ghost", "system", "heartbeat"];
+
+/// Sessions summarized per backfill run, so one run stays short.
+const SUMMARY_BATCH: usize = 50;
+
+/// Whether `msg` comes from a person rather than a scheduler.
+pub fn is_interactive(msg: &InboundMessage) -> bool {
+    !BACKGROUND_CHANNELS.contains(&msg.channel.as_str())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MaintenanceJob {
+    Memory,
+    WorkspaceGc,
+    SessionSummaries,
+    UpdateCheck,
+}
+
+impl MaintenanceJob {
+    pub const ALL: [MaintenanceJob; 4] = [
+        MaintenanceJob::Memory,
+        MaintenanceJob::WorkspaceGc,
+        MaintenanceJob::SessionSummaries,
+        MaintenanceJob::UpdateCheck,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MaintenanceJob::Memory => "memory_maintenance",
+            MaintenanceJob::WorkspaceGc => "workspace_gc",
+            MaintenanceJob::SessionSummaries => "session_summaries",
+            MaintenanceJob::UpdateCheck => "update_check",
+        }
+    }
+
+    fn min_interval(self, cfg: &IdleMaintenanceConfig) -> Duration {
+        let interval = Duration::from_secs(cfg.job_interval_minutes.max(1) * 60);
+        match self {
+            // The manifest rarely changes; once a day is plenty.
+            MaintenanceJob::UpdateCheck => interval.max(Duration::from_secs(86400)),
+            _ => interval,
+        }
+    }
+}
+
+/// Tracks user activity and decides which maintenance job may start.
+pub struct IdleScheduler {
+    cfg: IdleMaintenanceConfig,
+    check_updates: bool,
+    last_activity: Instant,
+    last_run: HashMap<MaintenanceJob, Instant>,
+    running: Option<MaintenanceJob>,
+}
+
+impl IdleScheduler {
+    /// `check_updates` is off for non-default agents so only one runtime polls the manifest.
+    pub fn new(cfg: IdleMaintenanceConfig, check_updates: bool, now: Instant) -> Self {
+        Self {
+            check_updates: check_updates && cfg.check_updates,
+            cfg,
+            last_activity: now,
+            last_run: HashMap::new(),
+            running: None,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.cfg.enabled
+    }
+
+    pub fn is_idle(&self, now: Instant) -> bool {
+        self.cfg.enabled
+            && now.saturating_duration_since(self.last_activity)
+                >= Duration::from_secs(self.cfg.idle_minutes * 60)
+    }
+
+    /// Record an interactive message. Returns the job that was running, which the caller
+    /// must abort; it becomes due again immediately.
+    pub fn note_activity(&mut self, now: Instant) -> Option<MaintenanceJob> {
+        self.last_activity = now;
+        let paused = self.running.take();
+        if let Some(job) = paused {
+            self.last_run.remove(&job);
+        }
+        paused
+    }
+
+    /// The job to start now, if the agent is idle and nothing is running.
+    pub fn next_due(&self, now: Instant) -> Option<MaintenanceJob> {
+        if self.running.is_some() || !self.is_idle(now) {
+            return None;
+        }
+        MaintenanceJob::ALL
+            .into_iter()
+            .filter(|job| *job != MaintenanceJob::UpdateCheck || self.check_updates)
+            .filter(|job| {
+                self.last_run.get(job).is_none_or(|t| {
+                    now.saturating_duration_since(*t) >= job.min_interval(&self.cfg)
+                })
+            })
+            .min_by_key(|job| self.last_run.get(job).copied())
+    }
+
+    pub fn start(&mut self, job: MaintenanceJob, now: Instant) {
+        self.running = Some(job);
+        self.last_run.insert(job, now);
+    }
+
+    pub fn finish(&mut self) {
+        self.running = None;
+    }
+
+    pub fn running(&self) -> Option<MaintenanceJob> {
+        self.running
+    }
+}
+
+/// What a spawned job needs from the runtime.
+#[derive(Clone)]
+pub struct JobContext {
+    pub config: Config,
+    pub paths: Paths,
+    pub memory_store: Option<MemoryStoreHandle>,
+    pub event_emitter: EventEmitterHandle,
+}
+
+pub async fn run_job(job: MaintenanceJob, ctx: JobContext) {
+    let started = Instant::now();
+    let outcome = match job {
+        MaintenanceJob::Memory => match &ctx.memory_store {
+            Some(store) => store
+                .maintenance(30)
+                .map(|(expired, purged)| format!("{} expired, {} purged", expired, purged))
+                .map_err(|e| e.to_string()),
+            None => Ok("no memory store".to_string()),
+        },
+        MaintenanceJob::WorkspaceGc => {
+            let max_age = Duration::from_secs(
+                ctx.config.agents.defaults.idle_maintenance.gc_max_age_days * 86400,
+            );
+            let cutoff = SystemTime::now()
+                .checked_sub(max_age)
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            Ok(format!(
+                "{} file(s) removed",
+                gc_workspace(&ctx.paths, cutoff)
+            ))
+        }
+        MaintenanceJob::SessionSummaries => match &ctx.memory_store {
+            Some(store) => Ok(format!(
+                "{} summar(ies) written",
+                backfill_session_summaries(&ctx.paths, store).await
+            )),
+            None => Ok("no memory store".to_string()),
+        },
+        MaintenanceJob::UpdateCheck => check_for_update(&ctx).await,
+    };
+    match outcome {
+        Ok(detail) => info!(
+            job = job.name(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            %detail,
+            "Idle maintenance job finished"
+        ),
+        Err(e) => warn!(job = job.name(), error = %e, "Idle maintenance job failed"),
+    }
+}
+
+fn remove_files_older_than(dir: &Path, cutoff: SystemTime) -> usize {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            removed += remove_files_older_than(&path, cutoff);
+            // Only succeeds once the directory is empty.
+            let _ = std::fs::remove_dir(&path);
+            continue;
+        }
+        let stale = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .is_ok_and(|modified| modified < cutoff);
+        if stale && std::fs::remove_file(&path).is_ok() {
+            removed += 1;
+        }
+    }
+    removed
+}
+
+/// Delete downloaded media and tool artifacts last modified before `cutoff`.
+pub fn gc_workspace(paths: &Paths, cutoff: SystemTime) -> usize {
+    [paths.media_dir(), paths.tool_artifacts_dir()]
+        .iter()
+        .map(|dir| remove_files_older_than(dir, cutoff))
+        .sum()
+}
+
+/// Write extractive summaries for sessions that have none yet (e.g. imported sessions
+/// or ones that grew past the threshold outside a normal turn).
+pub async fn backfill_session_summaries(paths: &Paths, store: &MemoryStoreHandle) -> usize {
+    let session_store = SessionStore::new(paths.clone());
+    let Ok((sessions, _)) = session_store.list(0, usize::MAX) else {
+        return 0;
+    };
+    let mut written = 0;
+    for session in sessions.iter().filter(|s| s.message_count >= 6) {
+        if written >= SUMMARY_BATCH {
+            break;
+        }
+        // Stems are `channel_chatid`; the summary is keyed by `channel:chatid`.
+        let session_key = session.stem.replacen('_', ":", 1);
+        if store
+            .get_session_summary(&session_key)
+            .ok()
+            .flatten()
+            .is_some()
+        {
+            continue;
+        }
+        let history: Vec<ChatMessage> = session_store.load(&session_key).unwrap_or_default();
+        if history.len() >= 6 {
+            let summary = crate::runtime::AgentRuntime::build_extractive_summary(&history);
+            if !summary.is_empty() {
+                match store.upsert_session_summary(&session_key, &summary) {
+                    Ok(()) => written += 1,
+                    Err(e) => debug!(session_key, error = %e, "Failed to store session summary"),
+                }
+            }
+        }
+        // Give a pending abort the chance to land between sessions.
+        tokio::task::yield_now().await;
+    }
+    written
+}
+
+async fn check_for_update(ctx: &JobContext) -> Result<String, String> {
+    let manager = blockcell_updater::UpdateManager::new(ctx.config.clone(), ctx.paths.clone());
+    match manager.check().await.map_err(|e| e.to_string())? {
+        Some(manifest) => {
+            let mut event = SystemEvent::new_main_session(
+                "update.available",
+                "updater",
+                EventPriority::Normal,
+                "发现新版本",
+                format!(
+                    "blockcell {} 可用（当前 {}），运行 `blockcell upgrade` 查看详情",
+                    manifest.version,
+                    env!("CARGO_PKG_VERSION")
+                ),
+            );
+            event.dedup_key = Some(format!("update:{}", manifest.version));
+            ctx.event_emitter.emit(event);
+            Ok(format!("version {} available", manifest.version))
+        }
+        None => Ok("up to date".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cfg() -> IdleMaintenanceConfig {
+        IdleMaintenanceConfig {
+            enabled: true,
+            idle_minutes: 10,
+            job_interval_minutes: 60,
+            ..Default::default()
+        }
+    }
+
+    fn mins(n: u64) -> Duration {
+        Duration::from_secs(n * 60)
+    }
+
+    #[test]
+    fn test_jobs_only_start_when_idle_and_rotate() {
+        let t0 = Instant::now();
+        let mut sched = IdleScheduler::new(cfg(), false, t0);
+        assert!(sched.next_due(t0 + mins(5)).is_none());
+
+        let t = t0 + mins(11);
+        let mut seen = Vec::new();
+        while let Some(job) = sched.next_due(t) {
+            sched.start(job, t);
+            assert!(sched.next_due(t).is_none(), "one job at a time");
+            sched.finish();
+            seen.push(job);
+        }
+        assert_eq!(
+            seen,
+            vec![
+                MaintenanceJob::Memory,
+                MaintenanceJob::WorkspaceGc,
+                MaintenanceJob::SessionSummaries
+            ]
+        );
+        assert!(sched.next_due(t + mins(30)).is_none());
+        assert_eq!(sched.next_due(t + mins(61)), Some(MaintenanceJob::Memory));
+
+        let disabled = IdleScheduler::new(IdleMaintenanceConfig::default(), true, t0);
+        assert!(disabled.next_due(t0 + mins(600)).is_none());
+    }
+
+    #[test]
+    fn test_user_message_pauses_running_job() {
+        let t0 = Instant::now();
+        let mut sched = IdleScheduler::new(cfg(), true, t0);
+        let t = t0 + mins(15);
+        let job = sched.next_due(t).unwrap();
+        sched.start(job, t);
+        assert_eq!(sched.note_activity(t + mins(1)), Some(job));
+        assert!(sched.running().is_none());
+        assert!(sched.next_due(t + mins(2)).is_none());
+        // The paused job is first in line once idle again.
+        assert_eq!(sched.next_due(t + mins(12)), Some(job));
+        assert_eq!(sched.note_activity(t + mins(12)), None);
+    }
+
+    #[test]
+    fn test_background_channels_do_not_count_as_activity() {
+        let mut msg = InboundMessage {
+            channel: "cron".to_string(),
+            account_id: None,
+            sender_id: "cron".to_string(),
+            chat_id: "job".to_string(),
+            content: "tick".to_string(),
+            media: vec![],
+            metadata: serde_json::Value::Null,
+            timestamp_ms: 0,
+        };
+        assert!(!is_interactive(&msg));
+        msg.channel = "telegram".to_string();
+        assert!(is_interactive(&msg));
+    }
+
+    #[test]
+    fn test_gc_removes_only_stale_files() {
+        let dir = std::env::temp_dir().join(format!("blockcell_idle_{}", uuid::Uuid::new_v4()));
+        let paths = Paths::with_base(dir.clone());
+        std::fs::create_dir_all(paths.media_dir().join("nested")).unwrap();
+        std::fs::create_dir_all(paths.tool_artifacts_dir()).unwrap();
+        std::fs::write(paths.media_dir().join("nested/a.jpg"), "x").unwrap();
+        std::fs::write(paths.tool_artifacts_dir().join("b.json"), "{}").unwrap();
+
+        let past = SystemTime::now() - Duration::from_secs(3600);
+        assert_eq!(gc_workspace(&paths, past), 0);
+        let future = SystemTime::now() + Duration::from_secs(3600);
+        assert_eq!(gc_workspace(&paths, future), 2);
+        assert!(!paths.media_dir().join("nested").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,187 @@
+//! Fast path for Telegram inline queries (`@yourbot summarize <url>` typed in any chat).
+//!
+//! Inline answers have to arrive within seconds and nobody is there to confirm a tool
+//! call, so the agent loop is skipped: URLs in the query are fetched read-only with
+//! `web_fetch`, then a single tool-less model call writes the answer card. Nothing is
+//! written to a session.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use blockcell_core::types::{ChatMessage, PermissionSet};
+use blockcell_core::{Config, InboundMessage, OutboundMessage, Paths};
+use blockcell_providers::{CallResult, ProviderPool};
+use blockcell_tools::{ToolContext, ToolRegistry};
+use serde_json::json;
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+const SYSTEM_PROMPT: &str = "You answer inline queries from a chat app. The answer is shown as a card, so keep it to a few short paragraphs and use the language of the query. When page content is provided, base the answer on it.";
+/// URLs fetched per query.
+const MAX_URLS: usize = 2;
+/// Telegram caps the text of an inline result at 4096 characters.
+const MAX_ANSWER_CHARS: usize = 4000;
+const TIMEOUT_ANSWER: &str =
+    "⏱️ This needs more time than inline mode allows. Ask me in a chat instead.";
+
+/// The inline query id, `None` for ordinary messages.
+pub fn inline_query_id(msg: &InboundMessage) -> Option<&str> {
+    msg.metadata.get("inline_query_id").and_then(|v| v.as_str())
+}
+
+fn extract_urls(query: &str) -> Vec<&str> {
+    query
+        .split_whitespace()
+        .filter(|w| w.starts_with("http://") || w.starts_with("https://"))
+        .map(|w| w.trim_end_matches([')', ']', '>', ',', '.']))
+        .take(MAX_URLS)
+        .collect()
+}
+
+fn build_messages(query: &str, pages: &[(&str, String)]) -> Vec<ChatMessage> {
+    let mut prompt = String::new();
+    for (url, text) in pages {
+        prompt.push_str(&format!("Content of {}:\n{}\n\n", url, text));
+    }
+    prompt.push_str(&format!("Query: {}", query));
+    vec![
+        ChatMessage::system(SYSTEM_PROMPT),
+        ChatMessage::user(&prompt),
+    ]
+}
+
+fn tool_context(config: &Config, paths: &Paths, msg: &InboundMessage) -> ToolContext {
+    ToolContext {
+        workspace: paths.workspace(),
+        builtin_skills_dir: None,
+        active_skill_dir: None,
+        session_key: msg.session_key(),
+        channel: msg.channel.clone(),
+        account_id: msg.account_id.clone(),
+        sender_id: Some(msg.sender_id.clone()),
+        chat_id: msg.chat_id.clone(),
+        config: config.clone(),
+        permissions: PermissionSet::new(),
+        task_manager: None,
+        memory_store: None,
+        outbound_tx: None,
+        spawn_handle: None,
+        capability_registry: None,
+        core_evolution: None,
+        event_emitter: None,
+        channel_contacts_file: None,
+        response_cache: None,
+        artifacts: None,
+    }
+}
+
+async fn compose(
+    config: &Config,
+    paths: &Paths,
+    pool: &ProviderPool,
+    tool_registry: &ToolRegistry,
+    msg: &InboundMessage,
+) -> Option<String> {
+    let max_chars = config.channels.telegram.inline.max_fetch_chars;
+    let mut pages = Vec::new();
+    for url in extract_urls(&msg.content) {
+        let params = json!({ "url": url, "extractMode": "text", "maxChars": max_chars });
+        match tool_registry
+            .execute("web_fetch", tool_context(config, paths, msg), params)
+            .await
+        {
+            Ok(result) => {
+                if let Some(text) = result.get("text").and_then(|v| v.as_str()) {
+                    pages.push((url, text.to_string()));
+                }
+            }
+            Err(e) => debug!(error = %e, url = %url, "Inline query: fetch failed"),
+        }
+    }
+
+    let (idx, provider) = pool.acquire()?;
+    match provider
+        .chat(&build_messages(&msg.content, &pages), &[])
+        .await
+    {
+        Ok(response) => {
+            pool.report(idx, CallResult::Success);
+            response
+                .content
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty())
+        }
+        Err(e) => {
+            pool.report(idx, ProviderPool::classify_error(&e.to_string()));
+            warn!(error = %e, "Inline query: model call failed");
+            None
+        }
+    }
+}
+
+/// Answer an inline query; the reply goes out with the query id in its metadata.
+pub async fn answer(
+    config: Config,
+    paths: Paths,
+    pool: Arc<ProviderPool>,
+    tool_registry: ToolRegistry,
+    outbound_tx: mpsc::Sender<OutboundMessage>,
+    msg: InboundMessage,
+) {
+    let Some(query_id) = inline_query_id(&msg).map(str::to_string) else {
+        return;
+    };
+    let timeout = Duration::from_secs(config.channels.telegram.inline.timeout_secs.max(1));
+    let composed = tokio::time::timeout(
+        timeout,
+        compose(&config, &paths, &pool, &tool_registry, &msg),
+    )
+    .await;
+    let text = match composed {
+        Ok(Some(text)) => text.chars().take(MAX_ANSWER_CHARS).collect(),
+        Ok(None) => "Sorry, I couldn't answer that right now.".to_string(),
+        Err(_) => {
+            info!(query_id = %query_id, "Inline query timed out");
+            TIMEOUT_ANSWER.to_string()
+        }
+    };
+
+    let mut reply = OutboundMessage::new(&msg.channel, &msg.chat_id, &text);
+    reply.account_id = msg.account_id.clone();
+    reply.metadata = json!({
+        "inline_query_id": query_id,
+        "inline_title": msg.content.chars().take(64).collect::<String>(),
+    });
+    if outbound_tx.send(reply).await.is_err() {
+        warn!("Inline query: outbound channel closed");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_urls() {
+        assert_eq!(
+            extract_urls("summarize https://a.io/x, and (https://b.io/y) https://c.io"),
+            vec!["https://a.io/x", "https://c.io"]
+        );
+        assert!(extract_urls("what is rust?").is_empty());
+    }
+
+    #[test]
+    fn test_build_messages_includes_pages() {
+        let messages = build_messages(
+            "summarize https://a.io",
+            &[("https://a.io", "Rust 2.0 released".to_string())],
+        );
+        assert_eq!(messages.len(), 2);
+        let prompt = messages[1].content.as_str().unwrap();
+        assert!(prompt.starts_with("Content of https://a.io:\nRust 2.0 released"));
+        assert!(prompt.ends_with("Query: summarize https://a.io"));
+
+        let msg = InboundMessage::cli("hi");
+        assert!(inline_query_id(&msg).is_none());
+    }
+}
@@ -136,7 +136,7 @@ impl IntentClassifier {
         }
 
         // Sort by priority descending
-        matches.sort_by(|a, b| b.1.cmp(&a.1));
+        matches.sort_by_key(|b| std::cmp::Reverse(b.1));
         matches.dedup_by(|a, b| a.0 == b.0);
 
         // If Chat is the only match, return it alone
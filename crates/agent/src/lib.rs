@@ -1,16 +1,29 @@
 pub mod auto_memory;
+pub mod batch_window;
+pub mod budget;
 pub mod bus;
 pub mod capability_adapter;
+pub mod citations;
+pub mod claim_check;
 pub mod compact;
 pub mod context;
+pub mod context_budget;
+pub mod empty_result;
 pub(crate) mod error;
 pub mod forked;
 pub mod health;
 pub mod history_projector;
+pub mod idle;
+pub mod inline_query;
 pub mod intent;
+pub mod loop_guard;
 pub mod memory_adapter;
 pub mod memory_system;
+pub mod planning;
+pub mod prompt_fragments;
 pub mod prompt_skill_executor;
+pub mod replay;
+pub mod reply_style;
 pub mod response_cache;
 pub mod runtime;
 pub mod session_memory;
@@ -24,6 +37,8 @@ pub mod system_event_orchestrator;
 pub mod system_event_store;
 pub mod task_manager;
 pub(crate) mod token;
+pub mod tool_selection;
+pub mod untrusted;
 
 pub use auto_memory::{
     extract_auto_memory, get_memory_dir, get_memory_file_path, should_extract_auto_memory,
@@ -0,0 +1,208 @@
+//! Per-turn tool-call budget and repeated-call (loop) detection.
+//!
+//! The guard fingerprints every tool call (name + canonicalised arguments). When the same
+//! fingerprint shows up `maxRepeats` times within the last `window` calls the turn is
+//! considered stuck; the runtime then re-plans (planning mode) or asks the user a
+//! clarifying question instead of burning the remaining iterations.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+
+use blockcell_core::config::LoopDetectionConfig;
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoopVerdict {
+    Ok,
+    /// Same call seen before in the window, but below the stuck threshold.
+    Repeat {
+        repeats: u32,
+    },
+    /// Same call repeated `repeats` times in the window; do not execute it again.
+    Stuck {
+        repeats: u32,
+    },
+    /// The per-turn tool budget is used up.
+    BudgetExhausted,
+}
+
+pub struct LoopGuard {
+    enabled: bool,
+    window: usize,
+    max_repeats: u32,
+    budget: u32,
+    warn_at: u32,
+    recent: VecDeque<u64>,
+    calls: u32,
+    warned: bool,
+}
+
+impl LoopGuard {
+    pub fn new(cfg: &LoopDetectionConfig) -> Self {
+        let budget = cfg.turn_tool_budget;
+        let ratio = cfg.budget_warning_ratio.clamp(0.0, 1.0);
+        Self {
+            enabled: cfg.enabled,
+            window: cfg.window.max(2),
+            max_repeats: cfg.max_repeats.max(2),
+            budget,
+            warn_at: ((budget as f32) * ratio).ceil() as u32,
+            recent: VecDeque::new(),
+            calls: 0,
+            warned: false,
+        }
+    }
+
+    /// Record a call about to run and decide whether it may proceed.
+    pub fn record(&mut self, tool: &str, args: &Value) -> LoopVerdict {
+        if self.budget > 0 && self.calls >= self.budget {
+            return LoopVerdict::BudgetExhausted;
+        }
+        self.calls += 1;
+        if !self.enabled {
+            return LoopVerdict::Ok;
+        }
+
+        let fp = fingerprint(tool, args);
+        let repeats = 1 + self.recent.iter().filter(|f| **f == fp).count() as u32;
+        self.recent.push_back(fp);
+        while self.recent.len() > self.window {
+            self.recent.pop_front();
+        }
+        if repeats >= self.max_repeats {
+            LoopVerdict::Stuck { repeats }
+        } else if repeats > 1 {
+            LoopVerdict::Repeat { repeats }
+        } else {
+            LoopVerdict::Ok
+        }
+    }
+
+    /// Remaining calls once the warning threshold is crossed; reported once per turn.
+    pub fn take_budget_warning(&mut self) -> Option<u32> {
+        if self.budget == 0 || self.warned || self.calls < self.warn_at {
+            return None;
+        }
+        self.warned = true;
+        Some(self.budget.saturating_sub(self.calls))
+    }
+
+    /// Forget the recent window, e.g. after re-planning gives the model a fresh start.
+    pub fn reset_window(&mut self) {
+        self.recent.clear();
+    }
+
+    pub fn calls(&self) -> u32 {
+        self.calls
+    }
+}
+
+fn fingerprint(tool: &str, args: &Value) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    tool.hash(&mut hasher);
+    hash_value(args, &mut hasher);
+    hasher.finish()
+}
+
+/// Hash JSON with object keys in sorted order so key order does not matter.
+fn hash_value(value: &Value, hasher: &mut DefaultHasher) {
+    match value {
+        Value::Null => 0u8.hash(hasher),
+        Value::Bool(b) => b.hash(hasher),
+        Value::Number(n) => n.to_string().hash(hasher),
+        Value::String(s) => s.trim().hash(hasher),
+        Value::Array(items) => {
+            items.len().hash(hasher);
+            for item in items {
+                hash_value(item, hasher);
+            }
+        }
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            for key in keys {
+                key.hash(hasher);
+                hash_value(&map[key], hasher);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn cfg() -> LoopDetectionConfig {
+        LoopDetectionConfig {
+            window: 4,
+            max_repeats: 3,
+            turn_tool_budget: 10,
+            budget_warning_ratio: 0.8,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_detects_identical_calls_regardless_of_key_order() {
+        let mut guard = LoopGuard::new(&cfg());
+        let a = json!({"url": "https://x", "limit": 5});
+        let b = json!({"limit": 5, "url": "https://x "});
+        assert_eq!(guard.record("web_fetch", &a), LoopVerdict::Ok);
+        assert_eq!(
+            guard.record("web_fetch", &b),
+            LoopVerdict::Repeat { repeats: 2 }
+        );
+        assert_eq!(guard.record("web_search", &a), LoopVerdict::Ok);
+        assert_eq!(
+            guard.record("web_fetch", &a),
+            LoopVerdict::Stuck { repeats: 3 }
+        );
+    }
+
+    #[test]
+    fn test_window_forgets_old_calls() {
+        let mut guard = LoopGuard::new(&cfg());
+        let a = json!({"q": "a"});
+        guard.record("t", &a);
+        for i in 0..4 {
+            guard.record("t", &json!({ "q": i }));
+        }
+        assert_eq!(guard.record("t", &a), LoopVerdict::Ok);
+        guard.reset_window();
+        assert_eq!(guard.record("t", &a), LoopVerdict::Ok);
+    }
+
+    #[test]
+    fn test_budget_warning_and_exhaustion() {
+        let mut guard = LoopGuard::new(&cfg());
+        for i in 0..7 {
+            guard.record("t", &json!({ "i": i }));
+            assert!(guard.take_budget_warning().is_none());
+        }
+        guard.record("t", &json!({ "i": 7 }));
+        assert_eq!(guard.take_budget_warning(), Some(2));
+        assert!(guard.take_budget_warning().is_none());
+        guard.record("t", &json!({ "i": 8 }));
+        guard.record("t", &json!({ "i": 9 }));
+        assert_eq!(
+            guard.record("t", &json!({ "i": 10 })),
+            LoopVerdict::BudgetExhausted
+        );
+        assert_eq!(guard.calls(), 10);
+    }
+
+    #[test]
+    fn test_disabled_still_enforces_budget() {
+        let mut guard = LoopGuard::new(&LoopDetectionConfig {
+            enabled: false,
+            turn_tool_budget: 2,
+            ..Default::default()
+        });
+        let a = json!({});
+        assert_eq!(guard.record("t", &a), LoopVerdict::Ok);
+        assert_eq!(guard.record("t", &a), LoopVerdict::Ok);
+        assert_eq!(guard.record("t", &a), LoopVerdict::BudgetExhausted);
+    }
+}
@@ -35,6 +35,28 @@ impl MemoryStoreAdapter {
     fn get_string_or(value: &Value, key: &str, default: &str) -> String {
         Self::get_string(value, key).unwrap_or_else(|| default.to_string())
     }
+
+    /// Build query params shared by keyword/hybrid and semantic queries
+    fn query_params(params_json: &Value) -> QueryParams {
+        let tags = Self::parse_tags(params_json, "tags");
+        let tags = if tags.is_empty() { None } else { Some(tags) };
+
+        QueryParams {
+            query: Self::get_string(params_json, "query"),
+            scope: Self::get_string(params_json, "scope"),
+            item_type: Self::get_string(params_json, "type"),
+            tags,
+            time_range_days: params_json.get("time_range_days").and_then(|v| v.as_i64()),
+            top_k: params_json
+                .get("top_k")
+                .and_then(|v| v.as_i64())
+                .unwrap_or(20) as usize,
+            include_deleted: params_json
+                .get("include_deleted")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+        }
+    }
 }
 
 impl MemoryStoreOps for MemoryStoreAdapter {
@@ -64,26 +86,16 @@ impl MemoryStoreOps for MemoryStoreAdapter {
     }
 
     fn query_json(&self, params_json: Value) -> Result<Value> {
-        let tags = Self::parse_tags(&params_json, "tags");
-        let tags = if tags.is_empty() { None } else { Some(tags) };
-
-        let params = QueryParams {
-            query: Self::get_string(&params_json, "query"),
-            scope: Self::get_string(&params_json, "scope"),
-            item_type: Self::get_string(&params_json, "type"),
-            tags,
-            time_range_days: params_json.get("time_range_days").and_then(|v| v.as_i64()),
-            top_k: params_json
-                .get("top_k")
-                .and_then(|v| v.as_i64())
-                .unwrap_or(20) as usize,
-            include_deleted: params_json
-                .get("include_deleted")
-                .and_then(|v| v.as_bool())
-                .unwrap_or(false),
-        };
+        let results = self.store.query(&Self::query_params(&params_json))?;
+        serde_json::to_value(results).map_err(|e| {
+            blockcell_core::Error::Storage(format!("Failed to serialize query results: {}", e))
+        })
+    }
 
-        let results = self.store.query(&params)?;
+    fn semantic_query_json(&self, params_json: Value) -> Result<Value> {
+        let results = self
+            .store
+            .semantic_query(&Self::query_params(&params_json))?;
         serde_json::to_value(results).map_err(|e| {
             blockcell_core::Error::Storage(format!("Failed to serialize query results: {}", e))
         })
@@ -140,6 +152,10 @@ impl MemoryStoreOps for MemoryStoreAdapter {
     fn maintenance(&self, recycle_days: i64) -> Result<(usize, usize)> {
         self.store.maintenance(recycle_days)
     }
+
+    fn store(&self) -> Option<MemoryStore> {
+        Some(self.store.clone())
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,417 @@
+//! Optional planning phase for complex requests.
+//!
+//! When `agents.defaults.planning.enabled` is set and a request looks multi-step, the
+//! runtime asks the model for a short step plan before running tools, publishes it as a
+//! `plan_proposed` event and waits for approval. During execution the model reports
+//! progress through the runtime-handled `plan_update` tool; a failed step can trigger a
+//! revised plan, bounded by `maxReplans`.
+
+use blockcell_core::config::PlanningConfig;
+use blockcell_core::types::ChatMessage;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Name of the virtual tool the model uses to report step progress.
+pub const PLAN_UPDATE_TOOL: &str = "plan_update";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Pending,
+    Running,
+    Done,
+    Failed,
+    Skipped,
+}
+
+impl StepStatus {
+    fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "pending" => Some(Self::Pending),
+            "running" | "in_progress" | "started" => Some(Self::Running),
+            "done" | "completed" | "success" => Some(Self::Done),
+            "failed" | "error" => Some(Self::Failed),
+            "skipped" => Some(Self::Skipped),
+            _ => None,
+        }
+    }
+
+    fn is_terminal(self) -> bool {
+        matches!(self, Self::Done | Self::Failed | Self::Skipped)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PlanStep {
+    /// 1-based position in the current revision.
+    pub index: usize,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub detail: String,
+    pub status: StepStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Plan {
+    pub id: String,
+    pub revision: u32,
+    pub steps: Vec<PlanStep>,
+    /// Number of revisions triggered by failed steps.
+    pub replans: u32,
+}
+
+impl Plan {
+    pub fn new(steps: Vec<PlanStep>) -> Self {
+        Self {
+            id: format!("plan_{}", uuid::Uuid::new_v4().simple()),
+            revision: 1,
+            steps,
+            replans: 0,
+        }
+    }
+
+    /// Replace everything after the last finished step with `remaining`.
+    pub fn revise(&mut self, remaining: Vec<PlanStep>) {
+        self.steps.retain(|s| s.status.is_terminal());
+        let offset = self.steps.len();
+        self.steps
+            .extend(remaining.into_iter().enumerate().map(|(i, mut s)| {
+                s.index = offset + i + 1;
+                s.status = StepStatus::Pending;
+                s
+            }));
+        self.revision += 1;
+        self.replans += 1;
+    }
+
+    /// Apply a `plan_update` call. Returns the updated step.
+    pub fn update_step(&mut self, params: &Value) -> std::result::Result<PlanStep, String> {
+        let index = params
+            .get("step")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| "'step' (1-based number) is required".to_string())?
+            as usize;
+        let status = params
+            .get("status")
+            .and_then(|v| v.as_str())
+            .and_then(StepStatus::parse)
+            .ok_or_else(|| "'status' must be running, done, failed or skipped".to_string())?;
+        let note = params
+            .get("note")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .map(String::from);
+        let step = self
+            .steps
+            .iter_mut()
+            .find(|s| s.index == index)
+            .ok_or_else(|| format!("Plan has no step {}", index))?;
+        step.status = status;
+        if note.is_some() {
+            step.note = note;
+        }
+        Ok(step.clone())
+    }
+
+    /// Mark the step in progress (or the next pending one) as failed.
+    pub fn fail_current(&mut self, note: &str) -> Option<PlanStep> {
+        let pos = self
+            .steps
+            .iter()
+            .position(|s| s.status == StepStatus::Running)
+            .or_else(|| {
+                self.steps
+                    .iter()
+                    .position(|s| s.status == StepStatus::Pending)
+            })?;
+        let step = &mut self.steps[pos];
+        step.status = StepStatus::Failed;
+        step.note = Some(note.to_string());
+        Some(step.clone())
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.steps.iter().all(|s| s.status.is_terminal())
+    }
+
+    /// One line per step, used as the approval prompt items.
+    pub fn confirmation_items(&self) -> Vec<String> {
+        self.steps
+            .iter()
+            .filter(|s| !s.status.is_terminal())
+            .map(|s| {
+                if s.detail.is_empty() {
+                    format!("{}. {}", s.index, s.title)
+                } else {
+                    format!("{}. {} — {}", s.index, s.title, s.detail)
+                }
+            })
+            .collect()
+    }
+
+    /// Instruction injected into the conversation once the plan is approved.
+    pub fn execution_prompt(&self) -> String {
+        format!(
+            "[系统] 用户已批准以下执行计划（第 {} 版）：\n{}\n\n\
+             请严格按顺序执行。开始每一步前调用 `{}`（status=running），完成后调用（status=done）；\
+             某一步无法完成时调用（status=failed，并在 note 中说明原因），系统会给出修订后的计划。\
+             全部完成后直接给出最终答复。",
+            self.revision,
+            self.confirmation_items().join("\n"),
+            PLAN_UPDATE_TOOL
+        )
+    }
+}
+
+/// Decide whether a request deserves a planning phase.
+pub fn should_plan(cfg: &PlanningConfig, text: &str, has_tools: bool) -> bool {
+    if !cfg.enabled || !has_tools {
+        return false;
+    }
+    let text = text.trim();
+    if text.starts_with('/') {
+        return false;
+    }
+    text.chars().count() >= cfg.min_chars || looks_multi_step(text)
+}
+
+/// Enumerated lists or explicit sequencing ("first … then …", "先…然后…") read as multi-step.
+fn looks_multi_step(text: &str) -> bool {
+    let numbered = text
+        .lines()
+        .filter(|l| {
+            let l = l.trim_start();
+            let digits = l.chars().take_while(|c| c.is_ascii_digit()).count();
+            (digits > 0 && matches!(l[digits..].chars().next(), Some('.' | ')' | '、')))
+                || l.starts_with("- ")
+        })
+        .count();
+    if numbered >= 3 {
+        return true;
+    }
+    let lower = text.to_lowercase();
+    let markers = [
+        "然后",
+        "接着",
+        "之后再",
+        "最后",
+        "第一步",
+        "步骤",
+        " then ",
+        "after that",
+        "finally",
+        "step 1",
+        "first,",
+    ];
+    markers.iter().filter(|m| lower.contains(*m)).count() >= 2
+}
+
+/// Prompt asking the model for a JSON step plan. With `failure`, asks for a revision of
+/// the remaining work instead.
+pub fn planning_messages(
+    request: &str,
+    tool_names: &[String],
+    max_steps: usize,
+    failure: Option<(&Plan, &PlanStep)>,
+) -> Vec<ChatMessage> {
+    let system = format!(
+        "你是任务规划器。把用户请求拆解为不超过 {} 个可执行步骤，每步尽量对应一次或少量工具调用。\n\
+         可用工具：{}\n\
+         只输出 JSON，不要输出其他内容，格式：{{\"steps\":[{{\"title\":\"简短标题\",\"detail\":\"要做什么/用什么工具\"}}]}}",
+        max_steps.max(1),
+        tool_names.join(", ")
+    );
+    let user = match failure {
+        None => format!("用户请求：\n{}", request),
+        Some((plan, failed)) => {
+            let progress: Vec<String> = plan
+                .steps
+                .iter()
+                .map(|s| {
+                    format!(
+                        "{}. [{}] {}{}",
+                        s.index,
+                        serde_json::to_value(s.status)
+                            .ok()
+                            .and_then(|v| v.as_str().map(String::from))
+                            .unwrap_or_default(),
+                        s.title,
+                        s.note
+                            .as_deref()
+                            .map(|n| format!("（{}）", n))
+                            .unwrap_or_default()
+                    )
+                })
+                .collect();
+            format!(
+                "用户请求：\n{}\n\n当前计划进度：\n{}\n\n第 {} 步「{}」失败。\
+                 请只为剩余工作给出修订后的步骤（不要重复已完成的步骤），换一种可行的方法。",
+                request,
+                progress.join("\n"),
+                failed.index,
+                failed.title
+            )
+        }
+    };
+    vec![ChatMessage::system(&system), ChatMessage::user(&user)]
+}
+
+/// Parse the planner reply. Accepts `{"steps":[…]}` or a bare array, optionally inside a
+/// code fence, where each step is an object with `title`/`detail` or a plain string.
+pub fn parse_plan_steps(text: &str, max_steps: usize) -> Option<Vec<PlanStep>> {
+    let start = text.find(['{', '['])?;
+    let end = text.rfind(['}', ']'])?;
+    if end < start {
+        return None;
+    }
+    let value: Value = serde_json::from_str(&text[start..=end]).ok()?;
+    let items = match &value {
+        Value::Array(items) => items,
+        Value::Object(obj) => obj.get("steps")?.as_array()?,
+        _ => return None,
+    };
+    let steps: Vec<PlanStep> = items
+        .iter()
+        .filter_map(|item| {
+            let (title, detail) = match item {
+                Value::String(s) => (s.trim().to_string(), String::new()),
+                Value::Object(o) => (
+                    o.get("title")
+                        .or_else(|| o.get("step"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("")
+                        .trim()
+                        .to_string(),
+                    o.get("detail")
+                        .or_else(|| o.get("description"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("")
+                        .trim()
+                        .to_string(),
+                ),
+                _ => return None,
+            };
+            (!title.is_empty()).then_some((title, detail))
+        })
+        .take(max_steps.max(1))
+        .enumerate()
+        .map(|(i, (title, detail))| PlanStep {
+            index: i + 1,
+            title,
+            detail,
+            status: StepStatus::Pending,
+            note: None,
+        })
+        .collect();
+    (!steps.is_empty()).then_some(steps)
+}
+
+/// Schema for the runtime-handled `plan_update` tool.
+pub fn plan_update_schema() -> Value {
+    json!({
+        "type": "function",
+        "function": {
+            "name": PLAN_UPDATE_TOOL,
+            "description": "Report progress on the approved plan. Call with status=running before a step, done after it, failed (with a note) when it cannot be completed, or skipped.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "step": { "type": "integer", "description": "1-based step number" },
+                    "status": { "type": "string", "enum": ["running", "done", "failed", "skipped"] },
+                    "note": { "type": "string", "description": "Short result or failure reason" }
+                },
+                "required": ["step", "status"]
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cfg() -> PlanningConfig {
+        PlanningConfig {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_should_plan() {
+        assert!(!should_plan(
+            &PlanningConfig::default(),
+            &"x".repeat(500),
+            true
+        ));
+        assert!(!should_plan(&cfg(), &"x".repeat(500), false));
+        assert!(should_plan(&cfg(), &"x".repeat(500), true));
+        assert!(!should_plan(&cfg(), "今天天气怎么样", true));
+        assert!(!should_plan(&cfg(), "/tasks", true));
+        assert!(should_plan(
+            &cfg(),
+            "先抓取这三个网页，然后汇总要点，最后生成一份 docx 报告",
+            true
+        ));
+        assert!(should_plan(
+            &cfg(),
+            "1. fetch\n2. summarize\n3. email it",
+            true
+        ));
+    }
+
+    #[test]
+    fn test_parse_plan_steps() {
+        let reply = "好的：\n```json\n{\"steps\":[{\"title\":\"抓取\",\"detail\":\"web_fetch\"},{\"title\":\"\"},\"汇总\"]}\n```";
+        let steps = parse_plan_steps(reply, 8).unwrap();
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].title, "抓取");
+        assert_eq!(steps[0].detail, "web_fetch");
+        assert_eq!(steps[1].index, 2);
+        assert_eq!(steps[1].status, StepStatus::Pending);
+
+        let capped = parse_plan_steps(r#"["a","b","c"]"#, 2).unwrap();
+        assert_eq!(capped.len(), 2);
+        assert!(parse_plan_steps("no plan here", 8).is_none());
+        assert!(parse_plan_steps(r#"{"steps":[]}"#, 8).is_none());
+    }
+
+    #[test]
+    fn test_update_and_revise() {
+        let mut plan = Plan::new(parse_plan_steps(r#"["a","b","c"]"#, 8).unwrap());
+        plan.update_step(&json!({"step": 1, "status": "done"}))
+            .unwrap();
+        let failed = plan
+            .update_step(&json!({"step": 2, "status": "failed", "note": "403"}))
+            .unwrap();
+        assert_eq!(failed.note.as_deref(), Some("403"));
+        assert!(plan
+            .update_step(&json!({"step": 9, "status": "done"}))
+            .is_err());
+        assert!(plan
+            .update_step(&json!({"step": 1, "status": "maybe"}))
+            .is_err());
+        assert!(!plan.is_finished());
+
+        plan.revise(parse_plan_steps(r#"["b2","c"]"#, 8).unwrap());
+        assert_eq!(plan.revision, 2);
+        assert_eq!(plan.replans, 1);
+        let titles: Vec<_> = plan.steps.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, vec!["a", "b", "b2", "c"]);
+        assert_eq!(plan.steps[3].index, 4);
+        assert_eq!(plan.confirmation_items(), vec!["3. b2", "4. c"]);
+    }
+
+    #[test]
+    fn test_fail_current_prefers_running_step() {
+        let mut plan = Plan::new(parse_plan_steps(r#"["a","b"]"#, 8).unwrap());
+        plan.update_step(&json!({"step": 2, "status": "running"}))
+            .unwrap();
+        assert_eq!(plan.fail_current("stuck").unwrap().index, 2);
+        assert_eq!(plan.fail_current("stuck").unwrap().index, 1);
+        assert!(plan.fail_current("stuck").is_none());
+    }
+}
@@ -0,0 +1,105 @@
+//! Ordered system prompt fragments from the workspace `prompt.d/` directory.
+//!
+//! Every `*.md` file is a fragment, ordered by file name, so a numeric prefix sets the
+//! position: `10-identity.md`, `20-safety.md`, `30-formatting.md`, `90-team-rules.md`.
+//! The name after the prefix is the fragment's role. An `identity` fragment replaces the
+//! built-in opening line; all others are added after the user preferences, in order.
+//! The directory is read on every prompt build, so edits apply to the next message.
+
+use std::path::{Path, PathBuf};
+
+/// Role of the fragment that replaces the built-in identity line.
+pub const IDENTITY: &str = "identity";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptFragment {
+    /// Role without the ordering prefix, e.g. `safety` for `20-safety.md`.
+    pub name: String,
+    pub path: PathBuf,
+    pub content: String,
+}
+
+impl PromptFragment {
+    pub fn is_identity(&self) -> bool {
+        self.name == IDENTITY
+    }
+}
+
+/// `20-safety` → `safety`; names without a separated numeric prefix are kept as-is.
+fn fragment_name(stem: &str) -> String {
+    let rest = stem.trim_start_matches(|c: char| c.is_ascii_digit());
+    match rest.strip_prefix(['-', '_', '.', ' ']) {
+        Some(name) if rest.len() < stem.len() && !name.is_empty() => name.to_string(),
+        _ => stem.to_string(),
+    }
+}
+
+/// Non-empty fragments in `dir`, ordered by file name. A missing directory yields none.
+pub fn load_fragments(dir: &Path) -> Vec<PromptFragment> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path.extension().and_then(|e| e.to_str()) == Some("md")
+                && !path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with('.'))
+        })
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let content = std::fs::read_to_string(&path).ok()?;
+            let content = content.trim();
+            if content.is_empty() {
+                return None;
+            }
+            let stem = path.file_stem()?.to_str()?;
+            Some(PromptFragment {
+                name: fragment_name(stem),
+                content: content.to_string(),
+                path,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fragment_name_strips_order_prefix() {
+        assert_eq!(fragment_name("10-identity"), IDENTITY);
+        assert_eq!(fragment_name("020_safety"), "safety");
+        assert_eq!(fragment_name("team-rules"), "team-rules");
+        assert_eq!(fragment_name("2fa-policy"), "2fa-policy");
+    }
+
+    #[test]
+    fn test_load_fragments_in_file_name_order() {
+        let dir =
+            std::env::temp_dir().join(format!("blockcell-prompt-d-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("30-formatting.md"), "Use short lists.").unwrap();
+        std::fs::write(dir.join("10-identity.md"), "You are Ops Bot.\n").unwrap();
+        std::fs::write(dir.join("20-empty.md"), "  \n").unwrap();
+        std::fs::write(dir.join("40-notes.txt"), "ignored").unwrap();
+        std::fs::write(dir.join(".50-draft.md"), "ignored").unwrap();
+
+        let fragments = load_fragments(&dir);
+        let names: Vec<&str> = fragments.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["identity", "formatting"]);
+        assert!(fragments[0].is_identity());
+        assert_eq!(fragments[0].content, "You are Ops Bot.");
+        assert!(load_fragments(&dir.join("missing")).is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,521 @@
+//! Record-and-replay of agent turns.
+//!
+//! With `agents.defaults.recordTurns` on, the runtime captures the session history a turn
+//! started from, every main-loop LLM request/response and every tool result into a
+//! [`TurnRecording`] saved under `workspace/recordings/`. [`ReplayProvider`] and
+//! [`ReplayTools`] feed that data back so the turn can be re-run offline without calling
+//! a model or executing a tool; wherever the current code asks for something different
+//! (a changed prompt, another tool call) a [`Divergence`] is logged.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use blockcell_core::types::{ChatMessage, LLMResponse, ToolCallRequest};
+use blockcell_core::{Error, InboundMessage, Result};
+use blockcell_providers::Provider;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const RECORDING_VERSION: u32 = 1;
+
+/// One main-loop LLM call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedLlmCall {
+    pub messages: Vec<ChatMessage>,
+    /// Names of the tools offered with the request.
+    pub tools: Vec<String>,
+    pub response: LLMResponse,
+}
+
+/// One executed tool call and the result the model saw.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedToolCall {
+    pub name: String,
+    pub arguments: Value,
+    pub result: String,
+}
+
+/// Everything needed to re-run one turn.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TurnRecording {
+    pub version: u32,
+    pub recorded_at: String,
+    pub session_key: String,
+    pub model: String,
+    pub inbound: InboundMessage,
+    /// Session history before the turn.
+    pub history: Vec<ChatMessage>,
+    pub llm_calls: Vec<RecordedLlmCall>,
+    pub tool_calls: Vec<RecordedToolCall>,
+    pub final_response: String,
+}
+
+impl TurnRecording {
+    pub fn new(
+        inbound: &InboundMessage,
+        session_key: &str,
+        model: &str,
+        history: Vec<ChatMessage>,
+    ) -> Self {
+        Self {
+            version: RECORDING_VERSION,
+            recorded_at: chrono::Utc::now().to_rfc3339(),
+            session_key: session_key.to_string(),
+            model: model.to_string(),
+            inbound: inbound.clone(),
+            history,
+            llm_calls: Vec::new(),
+            tool_calls: Vec::new(),
+            final_response: String::new(),
+        }
+    }
+
+    pub fn record_llm_call(
+        &mut self,
+        messages: &[ChatMessage],
+        tools: &[Value],
+        response: &LLMResponse,
+    ) {
+        self.llm_calls.push(RecordedLlmCall {
+            messages: messages.to_vec(),
+            tools: tool_names(tools),
+            response: response.clone(),
+        });
+    }
+
+    pub fn record_tool_call(&mut self, call: &ToolCallRequest, result: &str) {
+        self.tool_calls.push(RecordedToolCall {
+            name: call.name.clone(),
+            arguments: call.arguments.clone(),
+            result: result.to_string(),
+        });
+    }
+
+    /// Write the bundle to `dir` as `<timestamp>-<session>.json`.
+    pub fn save(&self, dir: &Path) -> Result<PathBuf> {
+        std::fs::create_dir_all(dir)?;
+        let session: String = self
+            .session_key
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .take(60)
+            .collect();
+        let path = dir.join(format!(
+            "{}-{}.json",
+            chrono::Utc::now().format("%Y%m%d-%H%M%S%3f"),
+            session
+        ));
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let recording: Self = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        if recording.version > RECORDING_VERSION {
+            return Err(Error::Validation(format!(
+                "Recording version {} is newer than supported ({})",
+                recording.version, RECORDING_VERSION
+            )));
+        }
+        Ok(recording)
+    }
+}
+
+fn tool_names(tools: &[Value]) -> Vec<String> {
+    tools
+        .iter()
+        .filter_map(|t| {
+            t.pointer("/function/name")
+                .or_else(|| t.get("name"))
+                .and_then(|n| n.as_str())
+                .map(str::to_string)
+        })
+        .collect()
+}
+
+fn message_text(msg: &ChatMessage) -> String {
+    match &msg.content {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Where a replay departed from the recording.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Divergence {
+    /// A request message differs; `removed`/`added` are the differing lines.
+    Prompt {
+        call: usize,
+        message: usize,
+        removed: Vec<String>,
+        added: Vec<String>,
+    },
+    MessageCount {
+        call: usize,
+        recorded: usize,
+        actual: usize,
+    },
+    Tools {
+        call: usize,
+        removed: Vec<String>,
+        added: Vec<String>,
+    },
+    ExtraLlmCall {
+        call: usize,
+    },
+    ToolCall {
+        index: usize,
+        recorded: String,
+        actual: String,
+    },
+    ExtraToolCall {
+        index: usize,
+        name: String,
+    },
+}
+
+fn preview(lines: &[String]) -> String {
+    let shown: Vec<String> = lines
+        .iter()
+        .take(3)
+        .map(|l| l.chars().take(100).collect())
+        .collect();
+    let more = lines.len().saturating_sub(shown.len());
+    if more > 0 {
+        format!("{:?} (+{} more)", shown, more)
+    } else {
+        format!("{:?}", shown)
+    }
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Divergence::Prompt {
+                call,
+                message,
+                removed,
+                added,
+            } => write!(
+                f,
+                "LLM call #{}: message #{} changed, removed {}, added {}",
+                call + 1,
+                message,
+                preview(removed),
+                preview(added)
+            ),
+            Divergence::MessageCount {
+                call,
+                recorded,
+                actual,
+            } => write!(
+                f,
+                "LLM call #{}: {} messages (recorded {})",
+                call + 1,
+                actual,
+                recorded
+            ),
+            Divergence::Tools {
+                call,
+                removed,
+                added,
+            } => write!(
+                f,
+                "LLM call #{}: tools removed {:?}, added {:?}",
+                call + 1,
+                removed,
+                added
+            ),
+            Divergence::ExtraLlmCall { call } => {
+                write!(f, "LLM call #{} was not in the recording", call + 1)
+            }
+            Divergence::ToolCall {
+                index,
+                recorded,
+                actual,
+            } => write!(
+                f,
+                "tool call #{}: {} (recorded {})",
+                index + 1,
+                actual,
+                recorded
+            ),
+            Divergence::ExtraToolCall { index, name } => write!(
+                f,
+                "tool call #{} ({}) was not in the recording",
+                index + 1,
+                name
+            ),
+        }
+    }
+}
+
+/// Shared divergence log for one replay.
+pub type DivergenceLog = Arc<Mutex<Vec<Divergence>>>;
+
+fn log_divergence(log: &DivergenceLog, divergence: Divergence) {
+    if let Ok(mut entries) = log.lock() {
+        entries.push(divergence);
+    }
+}
+
+/// Lines of `a` missing from `b`, in order.
+fn missing_lines(a: &str, b: &str) -> Vec<String> {
+    let other: std::collections::HashSet<&str> = b.lines().collect();
+    a.lines()
+        .filter(|l| !other.contains(l))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Compare a live request with the recorded one.
+fn compare_request(
+    call: usize,
+    recorded: &RecordedLlmCall,
+    messages: &[ChatMessage],
+    tools: &[Value],
+) -> Vec<Divergence> {
+    let mut out = Vec::new();
+    if recorded.messages.len() != messages.len() {
+        out.push(Divergence::MessageCount {
+            call,
+            recorded: recorded.messages.len(),
+            actual: messages.len(),
+        });
+    }
+    for (idx, (before, now)) in recorded.messages.iter().zip(messages).enumerate() {
+        let (before, now) = (message_text(before), message_text(now));
+        if before != now {
+            out.push(Divergence::Prompt {
+                call,
+                message: idx,
+                removed: missing_lines(&before, &now),
+                added: missing_lines(&now, &before),
+            });
+        }
+    }
+    let names = tool_names(tools);
+    let removed: Vec<String> = recorded
+        .tools
+        .iter()
+        .filter(|t| !names.contains(t))
+        .cloned()
+        .collect();
+    let added: Vec<String> = names
+        .iter()
+        .filter(|t| !recorded.tools.contains(t))
+        .cloned()
+        .collect();
+    if !removed.is_empty() || !added.is_empty() {
+        out.push(Divergence::Tools {
+            call,
+            removed,
+            added,
+        });
+    }
+    out
+}
+
+/// Answers LLM calls from a recording, in order.
+pub struct ReplayProvider {
+    calls: Vec<RecordedLlmCall>,
+    next: AtomicUsize,
+    log: DivergenceLog,
+}
+
+impl ReplayProvider {
+    pub fn new(calls: Vec<RecordedLlmCall>, log: DivergenceLog) -> Self {
+        Self {
+            calls,
+            next: AtomicUsize::new(0),
+            log,
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for ReplayProvider {
+    async fn chat(&self, messages: &[ChatMessage], tools: &[Value]) -> Result<LLMResponse> {
+        let call = self.next.fetch_add(1, Ordering::SeqCst);
+        let Some(recorded) = self.calls.get(call) else {
+            log_divergence(&self.log, Divergence::ExtraLlmCall { call });
+            return Ok(LLMResponse {
+                content: Some("[replay] no recorded response for this call".to_string()),
+                finish_reason: "stop".to_string(),
+                ..Default::default()
+            });
+        };
+        for divergence in compare_request(call, recorded, messages, tools) {
+            log_divergence(&self.log, divergence);
+        }
+        Ok(recorded.response.clone())
+    }
+}
+
+/// Answers tool calls from a recording instead of executing them.
+pub struct ReplayTools {
+    calls: Vec<RecordedToolCall>,
+    next: usize,
+    log: DivergenceLog,
+}
+
+impl ReplayTools {
+    pub fn new(calls: Vec<RecordedToolCall>, log: DivergenceLog) -> Self {
+        Self {
+            calls,
+            next: 0,
+            log,
+        }
+    }
+
+    pub fn next_result(&mut self, call: &ToolCallRequest) -> String {
+        let index = self.next;
+        self.next += 1;
+        let Some(recorded) = self.calls.get(index) else {
+            log_divergence(
+                &self.log,
+                Divergence::ExtraToolCall {
+                    index,
+                    name: call.name.clone(),
+                },
+            );
+            return serde_json::json!({
+                "error": format!("Replay: tool call '{}' is not in the recording", call.name)
+            })
+            .to_string();
+        };
+        if recorded.name != call.name || recorded.arguments != call.arguments {
+            log_divergence(
+                &self.log,
+                Divergence::ToolCall {
+                    index,
+                    recorded: format!("{}({})", recorded.name, recorded.arguments),
+                    actual: format!("{}({})", call.name, call.arguments),
+                },
+            );
+        }
+        if recorded.name != call.name {
+            return serde_json::json!({
+                "error": format!("Replay: recording has '{}' at this point", recorded.name)
+            })
+            .to_string();
+        }
+        recorded.result.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tool_call(name: &str, arguments: Value) -> ToolCallRequest {
+        ToolCallRequest {
+            id: "call-1".to_string(),
+            name: name.to_string(),
+            arguments,
+            thought_signature: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replay_provider_reports_prompt_changes() {
+        let inbound = InboundMessage {
+            channel: "cli".to_string(),
+            account_id: None,
+            sender_id: "user".to_string(),
+            chat_id: "default".to_string(),
+            content: "hi".to_string(),
+            media: vec![],
+            metadata: Value::Null,
+            timestamp_ms: 0,
+        };
+        let tools = vec![json!({"type": "function", "function": {"name": "read_file"}})];
+        let mut recording = TurnRecording::new(&inbound, "cli:default", "gpt-4o", Vec::new());
+        let messages = vec![
+            ChatMessage::system("You are helpful.\nBe brief."),
+            ChatMessage::user("hi"),
+        ];
+        let response = LLMResponse {
+            content: Some("hello".to_string()),
+            ..Default::default()
+        };
+        recording.record_llm_call(&messages, &tools, &response);
+
+        let dir = std::env::temp_dir().join(format!("replay-test-{}", uuid::Uuid::new_v4()));
+        let path = recording.save(&dir).unwrap();
+        let loaded = TurnRecording::load(&path).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let log = DivergenceLog::default();
+        let provider = ReplayProvider::new(loaded.llm_calls, log.clone());
+        let same = provider.chat(&messages, &tools).await.unwrap();
+        assert_eq!(same.content.as_deref(), Some("hello"));
+        assert!(log.lock().unwrap().is_empty());
+
+        let provider = ReplayProvider::new(recording.llm_calls.clone(), log.clone());
+        let changed = vec![
+            ChatMessage::system("You are helpful.\nBe thorough."),
+            ChatMessage::user("hi"),
+        ];
+        provider.chat(&changed, &[]).await.unwrap();
+        provider.chat(&changed, &[]).await.unwrap();
+        let entries = log.lock().unwrap().clone();
+        assert_eq!(
+            entries,
+            vec![
+                Divergence::Prompt {
+                    call: 0,
+                    message: 0,
+                    removed: vec!["Be brief.".to_string()],
+                    added: vec!["Be thorough.".to_string()],
+                },
+                Divergence::Tools {
+                    call: 0,
+                    removed: vec!["read_file".to_string()],
+                    added: vec![],
+                },
+                Divergence::ExtraLlmCall { call: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_replay_tools_return_recorded_results() {
+        let log = DivergenceLog::default();
+        let mut tools = ReplayTools::new(
+            vec![RecordedToolCall {
+                name: "read_file".to_string(),
+                arguments: json!({"path": "a.txt"}),
+                result: "contents".to_string(),
+            }],
+            log.clone(),
+        );
+        assert_eq!(
+            tools.next_result(&tool_call("read_file", json!({"path": "b.txt"}))),
+            "contents"
+        );
+        assert!(tools
+            .next_result(&tool_call("exec", json!({})))
+            .contains("not in the recording"));
+        let entries = log.lock().unwrap();
+        assert!(matches!(entries[0], Divergence::ToolCall { index: 0, .. }));
+        assert!(matches!(
+            entries[1],
+            Divergence::ExtraToolCall { index: 1, .. }
+        ));
+    }
+}
@@ -0,0 +1,287 @@
+//! Outbound reply rewriting to a per-chat reading level, tone or length.
+//!
+//! The style for a chat is looked up in the `/style` overrides
+//! (`workspace/reply_styles.json`), then `agents.defaults.replyStyle.chats`, then
+//! `defaultStyle`. The rewrite is a single extra LLM call on the final answer; results are
+//! cached so repeated answers (cron digests, canned replies) cost nothing the second time.
+
+use blockcell_core::config::ReplyStyleConfig;
+use blockcell_core::types::ChatMessage;
+use blockcell_core::{Config, Paths, Result};
+use blockcell_providers::{CallResult, ProviderPool};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, warn};
+
+/// Built-in presets: name → rewrite instruction.
+pub const PRESETS: &[(&str, &str)] = &[
+    (
+        "kid",
+        "Rewrite for an 8-10 year old: short sentences, everyday words, a friendly tone, and a simple example where it helps.",
+    ),
+    (
+        "simple",
+        "Rewrite in plain language at about a 6th-grade reading level. Explain or replace jargon.",
+    ),
+    (
+        "executive",
+        "Rewrite as an executive summary: the conclusion first, then at most 3 short bullet points with the key facts, numbers and decisions needed. No background.",
+    ),
+    (
+        "formal",
+        "Rewrite in a formal, professional tone suitable for business correspondence.",
+    ),
+    (
+        "casual",
+        "Rewrite in a relaxed, conversational tone, like a friend texting.",
+    ),
+    (
+        "brief",
+        "Rewrite to at most a third of the length, keeping only what the reader needs.",
+    ),
+];
+
+const SYSTEM_PROMPT: &str = "You rewrite an assistant's reply before it is delivered. Follow the style instruction exactly. Keep the reply's language, facts, numbers, names, links, file paths and code blocks unchanged. Do not add new information, greetings or commentary about the rewrite. Output only the rewritten reply.";
+
+fn store_path(paths: &Paths) -> PathBuf {
+    paths.workspace().join("reply_styles.json")
+}
+
+/// Styles set with `/style`, keyed by `channel:chat_id`.
+pub fn load_overrides(paths: &Paths) -> HashMap<String, String> {
+    std::fs::read_to_string(store_path(paths))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+/// Set (or with `None`, clear) the style override for one chat.
+pub fn set_chat_style(paths: &Paths, session_key: &str, style: Option<&str>) -> Result<()> {
+    let mut overrides = load_overrides(paths);
+    match style {
+        Some(style) => {
+            overrides.insert(session_key.to_string(), style.trim().to_string());
+        }
+        None => {
+            overrides.remove(session_key);
+        }
+    }
+    let path = store_path(paths);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(&overrides)?)?;
+    Ok(())
+}
+
+/// Rewrite instruction for a style name or free-form text; `None` for "off".
+pub fn instruction_for(cfg: &ReplyStyleConfig, style: &str) -> Option<String> {
+    let style = style.trim();
+    if style.is_empty() || matches!(style.to_lowercase().as_str(), "off" | "none" | "default") {
+        return None;
+    }
+    let key = style.to_lowercase();
+    if let Some(custom) = cfg.presets.get(&key) {
+        return Some(custom.clone());
+    }
+    if let Some((_, builtin)) = PRESETS.iter().find(|(name, _)| *name == key) {
+        return Some(builtin.to_string());
+    }
+    Some(format!("Rewrite the reply in this style: {}", style))
+}
+
+/// The style name/text in effect for a chat, if any.
+pub fn style_for_chat(
+    cfg: &ReplyStyleConfig,
+    overrides: &HashMap<String, String>,
+    channel: &str,
+    chat_id: &str,
+) -> Option<String> {
+    let key = format!("{}:{}", channel, chat_id);
+    overrides
+        .get(&key)
+        .or_else(|| cfg.chats.get(&key))
+        .or_else(|| cfg.chats.get(channel))
+        .cloned()
+        .or_else(|| cfg.default_style.clone())
+}
+
+/// Pool for `replyStyle.model`, if one is configured.
+pub fn build_pool(config: &Config) -> Option<Arc<ProviderPool>> {
+    let cfg = &config.agents.defaults.reply_style;
+    let model = cfg
+        .model
+        .as_deref()
+        .map(str::trim)
+        .filter(|m| !m.is_empty())?;
+    match blockcell_providers::create_provider(config, model, cfg.provider.as_deref()) {
+        Ok(provider) => Some(ProviderPool::from_single_provider(
+            model,
+            cfg.provider.clone().unwrap_or_default(),
+            Arc::from(provider),
+        )),
+        Err(e) => {
+            warn!(model = %model, error = %e, "Reply style model unavailable");
+            None
+        }
+    }
+}
+
+/// Bounded cache of rewrites, keyed by instruction and reply text.
+pub struct ReplyRewriter {
+    cache: Mutex<(HashMap<u64, String>, VecDeque<u64>)>,
+}
+
+impl Default for ReplyRewriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReplyRewriter {
+    pub fn new() -> Self {
+        Self {
+            cache: Mutex::new((HashMap::new(), VecDeque::new())),
+        }
+    }
+
+    fn cache_key(instruction: &str, text: &str) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        instruction.hash(&mut hasher);
+        text.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn cached(&self, key: u64) -> Option<String> {
+        let cache = self.cache.lock().unwrap_or_else(|p| p.into_inner());
+        cache.0.get(&key).cloned()
+    }
+
+    fn store(&self, key: u64, value: String, capacity: usize) {
+        if capacity == 0 {
+            return;
+        }
+        let mut cache = self.cache.lock().unwrap_or_else(|p| p.into_inner());
+        if cache.0.insert(key, value).is_none() {
+            cache.1.push_back(key);
+        }
+        while cache.1.len() > capacity {
+            if let Some(old) = cache.1.pop_front() {
+                cache.0.remove(&old);
+            }
+        }
+    }
+
+    /// Rewrite `text` per `instruction`. Falls back to the original on any failure so
+    /// delivery is never blocked by the post-processor.
+    pub async fn rewrite(
+        &self,
+        cfg: &ReplyStyleConfig,
+        pool: &ProviderPool,
+        instruction: &str,
+        text: &str,
+    ) -> String {
+        if text.chars().count() < cfg.min_chars {
+            return text.to_string();
+        }
+        let key = Self::cache_key(instruction, text);
+        if let Some(hit) = self.cached(key) {
+            debug!("Reply style cache hit");
+            return hit;
+        }
+        let Some((idx, provider)) = pool.acquire() else {
+            return text.to_string();
+        };
+        let messages = [
+            ChatMessage::system(SYSTEM_PROMPT),
+            ChatMessage::user(&format!(
+                "Style instruction: {}\n\nReply to rewrite:\n{}",
+                instruction, text
+            )),
+        ];
+        match provider.chat(&messages, &[]).await {
+            Ok(response) => {
+                pool.report(idx, CallResult::Success);
+                let rewritten = response.content.unwrap_or_default().trim().to_string();
+                if rewritten.is_empty() {
+                    return text.to_string();
+                }
+                self.store(key, rewritten.clone(), cfg.cache_entries);
+                rewritten
+            }
+            Err(e) => {
+                pool.report(idx, ProviderPool::classify_error(&e.to_string()));
+                warn!(error = %e, "Reply style rewrite failed, sending original");
+                text.to_string()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instruction_for() {
+        let mut cfg = ReplyStyleConfig::default();
+        cfg.presets
+            .insert("pirate".to_string(), "Talk like a pirate.".to_string());
+        assert!(instruction_for(&cfg, "Kid")
+            .unwrap()
+            .contains("8-10 year old"));
+        assert_eq!(
+            instruction_for(&cfg, "pirate").unwrap(),
+            "Talk like a pirate."
+        );
+        assert!(instruction_for(&cfg, "off").is_none());
+        assert!(instruction_for(&cfg, "like a sports commentator")
+            .unwrap()
+            .ends_with("like a sports commentator"));
+    }
+
+    #[test]
+    fn test_style_for_chat_precedence() {
+        let mut cfg = ReplyStyleConfig {
+            default_style: Some("brief".to_string()),
+            ..Default::default()
+        };
+        cfg.chats
+            .insert("telegram".to_string(), "casual".to_string());
+        cfg.chats
+            .insert("telegram:42".to_string(), "kid".to_string());
+        let mut overrides = HashMap::new();
+
+        assert_eq!(
+            style_for_chat(&cfg, &overrides, "telegram", "42").as_deref(),
+            Some("kid")
+        );
+        assert_eq!(
+            style_for_chat(&cfg, &overrides, "telegram", "7").as_deref(),
+            Some("casual")
+        );
+        assert_eq!(
+            style_for_chat(&cfg, &overrides, "slack", "C1").as_deref(),
+            Some("brief")
+        );
+        overrides.insert("telegram:42".to_string(), "executive".to_string());
+        assert_eq!(
+            style_for_chat(&cfg, &overrides, "telegram", "42").as_deref(),
+            Some("executive")
+        );
+    }
+
+    #[test]
+    fn test_cache_is_bounded() {
+        let rewriter = ReplyRewriter::new();
+        for i in 0..5u64 {
+            rewriter.store(i, format!("v{}", i), 3);
+        }
+        assert!(rewriter.cached(0).is_none());
+        assert!(rewriter.cached(1).is_none());
+        assert_eq!(rewriter.cached(4).as_deref(), Some("v4"));
+    }
+}
@@ -786,6 +786,7 @@ pub async fn persist_tool_result(
 }
 
 #[cfg(test)]
+#[allow(clippy::items_after_test_module)]
 mod layer1_tests {
     use super::*;
 
@@ -1144,7 +1145,7 @@ pub fn apply_budget(
     // 需要持久化哪些结果？
     // 策略：按大小降序排列，持久化最大的，直到总大小低于预算
     let mut sorted_candidates: Vec<_> = candidates.iter().collect();
-    sorted_candidates.sort_by(|a, b| b.size.cmp(&a.size));
+    sorted_candidates.sort_by_key(|b| std::cmp::Reverse(b.size));
 
     // 标记需要持久化的候选
     let mut to_persist = std::collections::HashSet::new();
@@ -1236,7 +1237,7 @@ pub async fn apply_budget_async(
 
     // 需要持久化哪些结果？
     let mut sorted_candidates: Vec<_> = candidates.iter().collect();
-    sorted_candidates.sort_by(|a, b| b.size.cmp(&a.size));
+    sorted_candidates.sort_by_key(|b| std::cmp::Reverse(b.size));
 
     let mut to_persist = std::collections::HashSet::new();
     let mut current_size = total_size;
@@ -1,4 +1,5 @@
 use blockcell_core::path_policy::{PathOp, PathPolicy, PolicyAction};
+use blockcell_core::permissions::ToolPermissions;
 use blockcell_core::system_event::{EventPriority, EventScope, SessionSummary, SystemEvent};
 use blockcell_core::types::{
     ChatMessage, LLMResponse, StreamChunk, ToolCallAccumulator, ToolCallRequest,
@@ -18,13 +19,17 @@ use std::sync::Arc;
 use tokio::sync::{broadcast, mpsc};
 use tracing::{debug, error, info, warn};
 
+use crate::budget::{BudgetAlert, SpendLedger};
 use crate::context::{ActiveSkillContext, ContextBuilder, InteractionMode};
 use crate::error::{
-    classify_tool_failure, dangerous_exec_denied, dangerous_file_ops_denied, disabled_skill_result,
-    disabled_tool_result, llm_exhausted_error, scoped_tool_denied_result, ToolFailureKind,
+    classify_tool_failure, confirmation_denied, dangerous_exec_denied, dangerous_file_ops_denied,
+    disabled_skill_result, disabled_tool_result, llm_exhausted_error, offline_tool_result,
+    role_denied_result, scoped_tool_denied_result, ToolFailureKind,
 };
 use crate::history_projector::{HistoryProjector, TimeBasedMCConfig};
 use crate::intent::{IntentCategory, IntentToolResolver};
+use crate::loop_guard::{LoopGuard, LoopVerdict};
+use crate::planning::{self, Plan, PlanStep, StepStatus};
 use crate::session_metrics::{ProcessingMetrics, ScopedTimer};
 use crate::skill_executor::{determine_manual_load_mode, SkillExecutionResult};
 use crate::skill_kernel::SkillRunMode;
@@ -35,10 +40,24 @@ use crate::system_event_orchestrator::{
 use crate::system_event_store::{InMemorySystemEventStore, SystemEventStoreOps};
 use crate::task_manager::TaskManager;
 use crate::token::estimate_messages_tokens;
+use crate::tool_selection::{self, SelectionInput, ToolSelection};
+use blockcell_storage::cron_history::{CronRunStatus, CronRunStore, CRON_RUN_ID_KEY};
+use blockcell_storage::skill_stats::{SkillStatsStore, UnderperformerCriteria};
 
 const TOOL_ROUND_THROTTLE_MS: u64 = 600;
 const TOOL_ROUND_THROTTLE_AFTER_RATE_LIMIT_MS: u64 = 2_500;
 const ACTIVATE_SKILL_TOOL_NAME: &str = "activate_skill";
+/// Tools a ghost routine always gets, whatever the intent classifier decided.
+const GHOST_REQUIRED_TOOLS: [&str; 8] = [
+    "community_hub",
+    "memory_maintenance",
+    "memory_query",
+    "memory_upsert",
+    "list_dir",
+    "read_file",
+    "file_ops",
+    "notification",
+];
 
 /// Compact execution context - contains info needed for notifications.
 ///
@@ -1245,6 +1264,15 @@ fn extract_urls_from_search_result(raw: &str) -> Vec<String> {
         .collect()
 }
 
+/// Calls to otherwise networked tools that only read local data, allowed while offline.
+fn is_local_only_call(tool_call: &ToolCallRequest) -> bool {
+    let action = tool_call.arguments.get("action").and_then(|v| v.as_str());
+    matches!(
+        (tool_call.name.as_str(), action),
+        ("stream_subscribe", Some("local_history"))
+    )
+}
+
 fn is_dangerous_exec_command(command: &str) -> bool {
     let c = command.to_lowercase();
     let c = c.trim();
@@ -1397,11 +1425,90 @@ fn load_disabled_toggles(paths: &Paths, category: &str) -> HashSet<String> {
     disabled
 }
 
+/// Pool used for chat once the monthly budget cap is reached (`budget.fallbackModel`).
+fn build_budget_fallback_pool(config: &Config) -> Option<Arc<ProviderPool>> {
+    let budget = &config.agents.defaults.budget;
+    let model = budget
+        .fallback_model
+        .as_deref()
+        .map(str::trim)
+        .filter(|m| !m.is_empty())?;
+    match blockcell_providers::create_provider(config, model, budget.fallback_provider.as_deref()) {
+        Ok(provider) => Some(ProviderPool::from_single_provider(
+            model,
+            budget.fallback_provider.clone().unwrap_or_default(),
+            Arc::from(provider),
+        )),
+        Err(e) => {
+            warn!(model = %model, error = %e, "Budget fallback model unavailable");
+            None
+        }
+    }
+}
+
+/// Pools with a lower output token limit for channels that set
+/// `channels.verbosity.<channel>.maxTokens` below `agents.defaults.maxTokens`.
+fn build_verbosity_pools(config: &Config) -> HashMap<String, Arc<ProviderPool>> {
+    let mut pools = HashMap::new();
+    for (channel, verbosity) in &config.channels.verbosity {
+        let Some(max_tokens) = verbosity
+            .max_tokens
+            .filter(|max| *max > 0 && *max < config.agents.defaults.max_tokens)
+        else {
+            continue;
+        };
+        let mut limited = config.clone();
+        limited.agents.defaults.max_tokens = max_tokens;
+        match ProviderPool::from_config(&limited) {
+            Ok(pool) => {
+                pools.insert(channel.clone(), pool);
+            }
+            Err(e) => {
+                warn!(channel = %channel, error = %e, "Channel token limit unavailable")
+            }
+        }
+    }
+    pools
+}
+
+/// Pool used while offline mode is active (`offline.localModel`, served by Ollama).
+fn build_offline_pool(config: &Config) -> Option<Arc<ProviderPool>> {
+    let offline = &config.offline;
+    let model = offline
+        .local_model
+        .as_deref()
+        .map(str::trim)
+        .filter(|m| !m.is_empty() && offline.enabled)?;
+    match blockcell_providers::create_provider(config, model, Some("ollama")) {
+        Ok(provider) => Some(ProviderPool::from_single_provider(
+            model,
+            "ollama".to_string(),
+            Arc::from(provider),
+        )),
+        Err(e) => {
+            warn!(model = %model, error = %e, "Offline model unavailable");
+            None
+        }
+    }
+}
+
 pub struct AgentRuntime {
     config: Config,
+    /// Compiled `config.permissions`, checked before every tool call.
+    tool_permissions: ToolPermissions,
     paths: Paths,
     context_builder: ContextBuilder,
     provider_pool: Arc<ProviderPool>,
+    /// Single-entry pool for `budget.fallbackModel`, used once the monthly cap is hit.
+    budget_fallback_pool: Option<Arc<ProviderPool>>,
+    /// Local model used while the network is down (`offline.localModel`).
+    offline_pool: Option<Arc<ProviderPool>>,
+    /// Per-channel pools with a lower output token limit (`channels.verbosity`).
+    verbosity_pools: HashMap<String, Arc<ProviderPool>>,
+    /// Single-entry pool for `replyStyle.model`; the chat pool is used when unset.
+    reply_style_pool: Option<Arc<ProviderPool>>,
+    /// Cached outbound style rewrites (see `reply_style`).
+    reply_rewriter: crate::reply_style::ReplyRewriter,
     tool_registry: ToolRegistry,
     session_store: SessionStore,
     audit_logger: AuditLogger,
@@ -1445,6 +1552,22 @@ pub struct AgentRuntime {
     /// Flag to signal that memory injector cache needs refresh after Layer 5 extraction.
     /// Uses Arc<AtomicBool> because background tasks need to set this flag.
     memory_injector_needs_reload: Arc<std::sync::atomic::AtomicBool>,
+    /// Files registered by tools during the current turn (see `ToolContext::register_artifact`).
+    turn_artifacts: Vec<blockcell_tools::ToolArtifact>,
+    /// Sources numbered during the current turn (`agents.defaults.citations`).
+    turn_citations: crate::citations::CitationTracker,
+    /// TaskManager entry this runtime is working for, if any; artifacts are stored there.
+    current_task_id: Option<String>,
+    /// Approved plan for the current turn when planning mode kicked in.
+    active_plan: Option<Plan>,
+    /// Per-skill invocation / success / latency / token aggregates.
+    skill_stats: Option<SkillStatsStore>,
+    /// Tokens reported by every LLM call so far; diffed to attribute usage to a skill run.
+    llm_tokens_used: std::sync::atomic::AtomicU64,
+    /// Bundle for the current turn when `recordTurns` is on.
+    recording: Option<crate::replay::TurnRecording>,
+    /// Recorded tool results served instead of executing tools (`blockcell replay`).
+    replay_tools: Option<crate::replay::ReplayTools>,
 }
 
 impl AgentRuntime {
@@ -1466,8 +1589,21 @@ impl AgentRuntime {
             warn!("🧠 [自进化] Failed to acquire provider from pool for evolution — evolution pipeline will not auto-drive");
         }
 
+        let budget_fallback_pool = build_budget_fallback_pool(&config);
+        let offline_pool = build_offline_pool(&config);
+        let verbosity_pools = build_verbosity_pools(&config);
+        let reply_style_pool = crate::reply_style::build_pool(&config);
+        let skill_stats = match SkillStatsStore::open(&paths.skill_stats_db()) {
+            Ok(store) => Some(store),
+            Err(e) => {
+                warn!(error = %e, "Skill stats store unavailable");
+                None
+            }
+        };
         let session_store = SessionStore::new(paths.clone());
-        let audit_logger = AuditLogger::new(paths.clone());
+        let audit_logger = AuditLogger::new(paths.clone()).with_redactor(Arc::new(
+            blockcell_storage::redaction::Redactor::new(&config.security.redaction),
+        ));
         let channel_contacts = blockcell_storage::ChannelContacts::new(paths.clone());
         let path_policy = load_path_policy(&config, &paths);
         let system_event_store = InMemorySystemEventStore::default();
@@ -1480,12 +1616,19 @@ impl AgentRuntime {
         let system_event_emitter: EventEmitterHandle = Arc::new(RuntimeSystemEventEmitter {
             store: system_event_store.clone(),
         });
+        let tool_permissions = ToolPermissions::new(&config.permissions);
 
         Ok(Self {
+            tool_permissions,
             config,
             paths,
             context_builder,
             provider_pool,
+            budget_fallback_pool,
+            offline_pool,
+            verbosity_pools,
+            reply_style_pool,
+            reply_rewriter: crate::reply_style::ReplyRewriter::new(),
             tool_registry,
             session_store,
             audit_logger,
@@ -1509,6 +1652,14 @@ impl AgentRuntime {
             response_cache: crate::response_cache::ResponseCache::new(),
             memory_system: None,
             memory_injector_needs_reload: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            turn_artifacts: Vec::new(),
+            turn_citations: Default::default(),
+            current_task_id: None,
+            active_plan: None,
+            skill_stats,
+            llm_tokens_used: std::sync::atomic::AtomicU64::new(0),
+            recording: None,
+            replay_tools: None,
         })
     }
 
@@ -1518,14 +1669,14 @@ impl AgentRuntime {
     /// - Channel type (napcat, telegram, discord, etc.)
     /// - User whitelist membership
     /// - Admin status
-    fn build_tool_permissions(
-        &self,
-        channel: &str,
-        sender_id: Option<&str>,
-        chat_id: &str,
-    ) -> blockcell_core::types::PermissionSet {
+    /// - The message's role from `permissions` (`role:admin` / `role:user` / `role:readonly`)
+    fn build_tool_permissions(&self, msg: &InboundMessage) -> blockcell_core::types::PermissionSet {
         use blockcell_core::types::PermissionSet;
 
+        let channel = msg.channel.as_str();
+        let sender_id = Some(msg.sender_id.as_str());
+        let chat_id = msg.chat_id.as_str();
+
         let mut perms = PermissionSet::new();
 
         // Grant channel-specific permissions
@@ -1586,7 +1737,9 @@ impl AgentRuntime {
             }
         }
 
-        perms
+        // Role from `permissions`, so tools can require e.g. `role:admin`.
+        let role = self.tool_permissions.role_for_message(msg);
+        perms.with_permission(&format!("role:{}", role.as_str()))
     }
 
     pub fn context_builder(&self) -> &ContextBuilder {
@@ -1621,6 +1774,11 @@ impl AgentRuntime {
         self.sync_task_manager_event_emitter();
     }
 
+    /// Attribute tool artifacts produced by this runtime to a TaskManager entry.
+    pub fn set_current_task_id(&mut self, task_id: Option<String>) {
+        self.current_task_id = task_id;
+    }
+
     /// Set the broadcast sender for streaming events to WebSocket clients.
     pub fn set_event_tx(&mut self, tx: broadcast::Sender<String>) {
         self.event_tx = Some(tx);
@@ -1635,6 +1793,11 @@ impl AgentRuntime {
         self.system_event_emitter.clone()
     }
 
+    /// Answer tool calls from a recording instead of executing them.
+    pub fn set_replay_tools(&mut self, tools: crate::replay::ReplayTools) {
+        self.replay_tools = Some(tools);
+    }
+
     /// Set a shared ResponseCache instance.
     ///
     /// This allows external code (like the CLI stdin loop) to share the same
@@ -1880,6 +2043,15 @@ impl AgentRuntime {
         self.context_builder.set_evolution_llm_provider(llm_adapter);
     }
 
+    /// 设置自进化审计/复核用的强模型 provider（evolution_audit_model）
+    pub fn set_evolution_audit_provider(&mut self, provider: Box<dyn Provider>) {
+        let llm_adapter = Arc::new(ProviderLLMAdapter {
+            provider: Arc::from(provider),
+        });
+        self.context_builder
+            .set_evolution_audit_llm_provider(llm_adapter);
+    }
+
     /// Set the memory store handle for tools and context builder.
     pub fn set_memory_store(&mut self, store: MemoryStoreHandle) {
         self.memory_store = Some(store.clone());
@@ -2067,7 +2239,7 @@ impl AgentRuntime {
 
     /// Build an extractive summary from session history (no LLM call).
     /// Extracts user questions and final assistant answers, truncated to fit.
-    fn build_extractive_summary(history: &[ChatMessage]) -> String {
+    pub(crate) fn build_extractive_summary(history: &[ChatMessage]) -> String {
         let mut summary_parts: Vec<String> = Vec::new();
         let mut i = 0;
         while i < history.len() {
@@ -2325,13 +2497,16 @@ impl AgentRuntime {
         messages: &[ChatMessage],
         tools: &[serde_json::Value],
     ) -> Result<LLMResponse> {
-        if let Some((pidx, provider)) = self.provider_pool.acquire() {
+        let pool = self.llm_pool();
+        if let Some((pidx, provider)) = pool.acquire() {
+            let started = std::time::Instant::now();
             let result = provider.chat(messages, tools).await;
             match &result {
-                Ok(_) => self.provider_pool.report(pidx, CallResult::Success),
-                Err(e) => self
-                    .provider_pool
-                    .report(pidx, ProviderPool::classify_error(&format!("{}", e))),
+                Ok(response) => {
+                    pool.report_with_latency(pidx, CallResult::Success, started.elapsed());
+                    self.record_llm_spend(&pool, pidx, &response.usage);
+                }
+                Err(e) => pool.report(pidx, ProviderPool::classify_error(&format!("{}", e))),
             }
             result
         } else {
@@ -2341,6 +2516,133 @@ impl AgentRuntime {
         }
     }
 
+    /// Pool for the next LLM call: the local model while offline, the budget fallback
+    /// once the monthly cap is hit.
+    fn llm_pool(&self) -> Arc<ProviderPool> {
+        if let Some(local) = &self.offline_pool {
+            if blockcell_core::connectivity::is_offline() {
+                return Arc::clone(local);
+            }
+        }
+        let budget = &self.config.agents.defaults.budget;
+        if let Some(fallback) = &self.budget_fallback_pool {
+            if budget.is_active() && SpendLedger::load(&self.paths).over_cap(budget) {
+                return Arc::clone(fallback);
+            }
+        }
+        Arc::clone(&self.provider_pool)
+    }
+
+    /// Pool for a reply on `channel`: the channel's token limit applies unless the budget
+    /// fallback or the offline model has taken over.
+    fn llm_pool_for_channel(&self, channel: &str) -> Arc<ProviderPool> {
+        let pool = self.llm_pool();
+        if !Arc::ptr_eq(&pool, &self.provider_pool) {
+            return pool;
+        }
+        self.verbosity_pools.get(channel).cloned().unwrap_or(pool)
+    }
+
+    /// Price one LLM call into the monthly ledger and notify on newly crossed thresholds.
+    fn record_llm_spend(&self, pool: &ProviderPool, pool_idx: usize, usage: &serde_json::Value) {
+        let (input, output) = crate::budget::usage_tokens(usage);
+        self.llm_tokens_used
+            .fetch_add(input + output, std::sync::atomic::Ordering::Relaxed);
+        let budget = &self.config.agents.defaults.budget;
+        if !budget.enabled {
+            return;
+        }
+        let Some(pricing) = pool.entry_pricing(pool_idx) else {
+            return;
+        };
+        let list_price = blockcell_core::model_meta::lookup(&self.config, &pricing.model);
+        let mut ledger = SpendLedger::load(&self.paths);
+        ledger.record(
+            &pricing.model,
+            usage,
+            pricing
+                .input_price
+                .or(budget.input_price)
+                .or(list_price.as_ref().and_then(|m| m.input_price)),
+            pricing
+                .output_price
+                .or(budget.output_price)
+                .or(list_price.as_ref().and_then(|m| m.output_price)),
+        );
+        let alerts = ledger.take_alerts(budget);
+        if let Err(e) = ledger.save(&self.paths) {
+            warn!(error = %e, "Failed to save spend ledger");
+        }
+        for alert in alerts {
+            self.emit_budget_alert(&ledger.month, &alert);
+        }
+    }
+
+    fn emit_budget_alert(&self, month: &str, alert: &BudgetAlert) {
+        let budget = &self.config.agents.defaults.budget;
+        let (kind, priority, title, summary, delivery, dedup) = match alert {
+            BudgetAlert::Soft {
+                threshold,
+                spent_usd,
+                limit_usd,
+            } => (
+                "budget.threshold",
+                EventPriority::High,
+                "模型费用提醒".to_string(),
+                format!(
+                    "本月模型费用已达 ${:.2}，超过预算 ${:.2} 的 {:.0}%",
+                    spent_usd,
+                    limit_usd,
+                    threshold * 100.0
+                ),
+                blockcell_core::system_event::DeliveryPolicy::default(),
+                format!("budget:{}:{}", month, threshold),
+            ),
+            BudgetAlert::HardCap {
+                spent_usd,
+                limit_usd,
+            } => {
+                let mut actions = Vec::new();
+                if let Some(model) = &budget.fallback_model {
+                    actions.push(format!("对话已切换到备用模型 {}", model));
+                }
+                if budget.pause_background {
+                    actions.push("后台任务（Ghost、自进化）已暂停".to_string());
+                }
+                let mut summary = format!(
+                    "本月模型费用 ${:.2} 已达到预算上限 ${:.2}",
+                    spent_usd, limit_usd
+                );
+                if !actions.is_empty() {
+                    summary.push('；');
+                    summary.push_str(&actions.join("，"));
+                }
+                (
+                    "budget.cap_reached",
+                    EventPriority::Critical,
+                    "模型费用已达上限".to_string(),
+                    summary,
+                    blockcell_core::system_event::DeliveryPolicy::critical(),
+                    format!("budget:{}:cap", month),
+                )
+            }
+        };
+        warn!(kind, summary = %summary, "Budget threshold crossed");
+        if let Some(ref event_tx) = self.event_tx {
+            let event = serde_json::json!({
+                "type": "budget_alert",
+                "agent_id": self.agent_id.clone().unwrap_or_else(|| "default".to_string()),
+                "kind": kind,
+                "summary": summary.clone(),
+            });
+            let _ = event_tx.send(event.to_string());
+        }
+        let mut event = SystemEvent::new_main_session(kind, "budget", priority, title, summary);
+        event.delivery = delivery;
+        event.dedup_key = Some(dedup);
+        self.system_event_emitter.emit(event);
+    }
+
     async fn run_prompt_skill_loop(
         &mut self,
         msg: &InboundMessage,
@@ -2420,6 +2722,14 @@ impl AgentRuntime {
                         })
                         .to_string()
                     };
+                let tool_result = crate::empty_result::normalize_tool_result(
+                    &tool_call.name,
+                    &tool_call.arguments,
+                    tool_result,
+                );
+                let tool_result = self
+                    .guard_untrusted_output(&tool_call.name, tool_result)
+                    .await;
                 let mut tool_message = ChatMessage::tool_result(&tool_call.id, &tool_result);
                 tool_message.name = Some(tool_call.name.clone());
                 current_messages.push(tool_message.clone());
@@ -2586,6 +2896,124 @@ impl AgentRuntime {
         Ok(result)
     }
 
+    fn record_skill_run(&self, skill: &str, success: bool, latency_ms: u64, tokens: u64) {
+        if let Some(stats) = &self.skill_stats {
+            if let Err(e) = stats.record(skill, success, latency_ms, tokens) {
+                debug!(skill, error = %e, "Failed to record skill stats");
+            }
+        }
+    }
+
+    /// Queue skills that keep failing as evolution improvement candidates.
+    async fn queue_underperforming_skills(&self) {
+        let (Some(stats), Some(evo_service)) =
+            (&self.skill_stats, self.context_builder.evolution_service())
+        else {
+            return;
+        };
+        let criteria = UnderperformerCriteria::default();
+        let candidates = match stats.underperformers(&criteria) {
+            Ok(candidates) => candidates,
+            Err(e) => {
+                warn!(error = %e, "Skill stats review failed");
+                return;
+            }
+        };
+        for skill in candidates {
+            let metric = format!(
+                "success_rate={:.2} over {} calls, mean_latency_ms={:.0}",
+                skill.success_rate, skill.calls, skill.mean_latency_ms
+            );
+            match evo_service
+                .queue_improvement_candidate(&skill.skill, &metric, criteria.min_success_rate)
+                .await
+            {
+                Ok(evolution_id) => {
+                    info!(skill = %skill.skill, evolution_id = %evolution_id, %metric, "Queued underperforming skill for improvement");
+                }
+                Err(e) => {
+                    debug!(skill = %skill.skill, error = %e, "Skipped improvement candidate");
+                }
+            }
+            // Flag either way so a skill that cannot evolve is not retried every day.
+            let _ = stats.mark_flagged(&skill.skill);
+        }
+    }
+
+    /// Emit the morning TODO digest once per local day, at or after the configured hour.
+    /// Subscriptions renewing this week are appended to it.
+    /// `last_date` caches the day already handled so later ticks skip the database.
+    fn send_todo_digest(&self, last_date: &mut Option<String>) {
+        let cfg = &self.config.tools.todo;
+        if !cfg.morning_digest {
+            return;
+        }
+        let tz = self.config.default_timezone.as_deref();
+        let (today, hour) = blockcell_tools::todo::local_date_hour(tz);
+        if hour < cfg.digest_hour || last_date.as_deref() == Some(today.as_str()) {
+            return;
+        }
+        let workspace = self.paths.workspace();
+        let store = match blockcell_storage::todos::TodoStore::open(
+            &blockcell_storage::todos::db_path(&workspace),
+        ) {
+            Ok(store) => store,
+            Err(e) => {
+                warn!(error = %e, "Failed to open TODO store for digest");
+                return;
+            }
+        };
+        if store.meta("last_digest").ok().flatten().as_deref() == Some(today.as_str()) {
+            *last_date = Some(today);
+            return;
+        }
+        let cutoff = blockcell_tools::todo::end_of_today(tz);
+        let todos = match blockcell_tools::todo::digest(&store, cutoff, tz) {
+            Ok(todos) => todos,
+            Err(e) => {
+                warn!(error = %e, "TODO digest failed");
+                return;
+            }
+        };
+        let renewals = blockcell_tools::subscriptions::renewals_digest(&workspace, tz);
+        let habits = blockcell_tools::habits::digest_section(&self.config, &workspace);
+        let sections: Vec<String> = todos.into_iter().chain(renewals).chain(habits).collect();
+        let with_weather = !self.config.tools.weather.default_location.trim().is_empty();
+        if !sections.is_empty() || with_weather {
+            // The weather line needs a (cached) network lookup, so the event is assembled
+            // off the tick loop.
+            let config = self.config.clone();
+            let emitter = self.system_event_emitter.clone();
+            let date = today.clone();
+            tokio::spawn(async move {
+                let mut sections = sections;
+                if with_weather {
+                    if let Some(line) =
+                        blockcell_tools::weather::digest_line(&config, &workspace).await
+                    {
+                        sections.insert(0, line);
+                    }
+                }
+                if sections.is_empty() {
+                    return;
+                }
+                let mut event = SystemEvent::new_main_session(
+                    "todo.digest",
+                    "todo",
+                    EventPriority::Normal,
+                    "今日待办",
+                    sections.join("\n\n"),
+                );
+                event.delivery.immediate = true;
+                event.dedup_key = Some(format!("todo-digest:{}", date));
+                emitter.emit(event);
+                info!(date = %date, "Morning TODO digest queued");
+            });
+        }
+        let _ = store.set_meta("last_digest", &today);
+        *last_date = Some(today);
+    }
+
     fn resolved_skill_tool_names(&self, active_skill: &ActiveSkillContext) -> Vec<String> {
         let available_tools = self
             .tool_registry
@@ -2628,9 +3056,27 @@ impl AgentRuntime {
             prompt_skill.inject_prompt_md && manual_mode.should_load_manual();
 
         let allowed_tools = self.resolved_skill_tool_names(&prompt_skill);
-        let (final_response, trace_messages, session_metadata) = self
+        let started = std::time::Instant::now();
+        let tokens_before = self
+            .llm_tokens_used
+            .load(std::sync::atomic::Ordering::Relaxed);
+        let run = self
             .run_prompt_skill_for_session(&prompt_skill, msg, history, session_key, &allowed_tools)
-            .await?;
+            .await;
+        let success = run
+            .as_ref()
+            .is_ok_and(|(response, _, _)| !response.trim().is_empty());
+        let tokens = self
+            .llm_tokens_used
+            .load(std::sync::atomic::Ordering::Relaxed)
+            .saturating_sub(tokens_before);
+        self.record_skill_run(
+            &active_skill.name,
+            success,
+            started.elapsed().as_millis() as u64,
+            tokens,
+        );
+        let (final_response, trace_messages, session_metadata) = run?;
 
         Ok((
             SkillExecutionResult {
@@ -2655,7 +3101,18 @@ impl AgentRuntime {
             collected_media,
             cron_deliver_target,
         } = ctx;
-        let final_response = strip_fake_tool_calls(final_response.trim());
+        let answer = strip_fake_tool_calls(final_response.trim());
+        let artifacts = compact_artifacts(&self.turn_artifacts);
+        let citations = self.turn_citations.finish(&answer);
+        let final_response = match &citations {
+            Some(c) => {
+                let cited = c.append_to(&answer);
+                overwrite_last_assistant_message(history, &cited);
+                cited
+            }
+            None => answer.clone(),
+        };
+        let citation_sources = citations.as_ref().map(|c| c.sources.clone());
 
         if let Some(stub) = self
             .response_cache
@@ -2664,8 +3121,16 @@ impl AgentRuntime {
             overwrite_last_assistant_message(history, &stub);
         }
 
+        let mut session_metadata = session_metadata.clone();
+        blockcell_storage::privacy::record_artifacts(
+            &mut session_metadata,
+            self.turn_artifacts
+                .iter()
+                .map(|a| a.path.as_str())
+                .chain(collected_media.iter().map(String::as_str)),
+        );
         self.session_store
-            .save_with_metadata(persist_session_key, history, session_metadata)?;
+            .save_with_metadata(persist_session_key, history, &session_metadata)?;
 
         if history.len() >= 6 {
             if let Some(ref store) = self.memory_store {
@@ -2690,7 +3155,10 @@ impl AgentRuntime {
                     OutboundMessage::new(&msg.channel, &msg.chat_id, &final_response);
                 outbound.account_id = msg.account_id.clone();
                 outbound.media = collected_media.clone();
-                outbound.metadata = extract_reply_metadata(msg);
+                outbound.metadata = with_citations(
+                    with_artifacts(extract_reply_metadata(msg), &artifacts),
+                    citation_sources.as_ref(),
+                );
                 let _ = tx.send(outbound).await;
             }
 
@@ -2729,6 +3197,13 @@ impl AgentRuntime {
             return Ok(final_response.to_string());
         }
 
+        // History keeps the original answer; only the delivered copy is restyled. The
+        // source list is left out of the rewrite and re-attached afterwards.
+        let final_response = match &citations {
+            Some(c) => c.append_to(&self.apply_reply_style(msg, answer).await),
+            None => self.apply_reply_style(msg, final_response).await,
+        };
+
         if msg.channel == "ws" {
             if let Some(ref event_tx) = self.event_tx {
                 let event = serde_json::json!({
@@ -2740,6 +3215,8 @@ impl AgentRuntime {
                     "tool_calls": 0,
                     "duration_ms": 0,
                     "media": collected_media,
+                    "artifacts": artifacts,
+                    "citations": citation_sources,
                 });
                 let _ = event_tx.send(event.to_string());
             }
@@ -2751,7 +3228,10 @@ impl AgentRuntime {
                     OutboundMessage::new(&msg.channel, &msg.chat_id, &final_response);
                 outbound.account_id = msg.account_id.clone();
                 outbound.media = collected_media.clone();
-                outbound.metadata = extract_reply_metadata(msg);
+                outbound.metadata = with_citations(
+                    with_artifacts(extract_reply_metadata(msg), &artifacts),
+                    citation_sources.as_ref(),
+                );
                 let _ = tx.send(outbound).await;
             }
         }
@@ -2775,6 +3255,31 @@ impl AgentRuntime {
         Ok(final_response.to_string())
     }
 
+    /// Rewrite a reply to the chat's style (`/style`, `agents.defaults.replyStyle`), if any.
+    async fn apply_reply_style(&self, msg: &InboundMessage, text: String) -> String {
+        if matches!(
+            msg.channel.as_str(),
+            "system" | "cron" | "subagent" | "ghost"
+        ) {
+            return text;
+        }
+        let cfg = &self.config.agents.defaults.reply_style;
+        let overrides = crate::reply_style::load_overrides(&self.paths);
+        let Some(instruction) =
+            crate::reply_style::style_for_chat(cfg, &overrides, &msg.channel, &msg.chat_id)
+                .and_then(|style| crate::reply_style::instruction_for(cfg, &style))
+        else {
+            return text;
+        };
+        let pool = self
+            .reply_style_pool
+            .as_ref()
+            .unwrap_or(&self.provider_pool);
+        self.reply_rewriter
+            .rewrite(cfg, pool, &instruction, &text)
+            .await
+    }
+
     /// Extracted sub-function (#15): Call LLM with streaming and retry on transient errors.
     /// Returns the LLM response on success, or the last error on exhaustion.
     async fn call_llm_with_retry(
@@ -2788,6 +3293,7 @@ impl AgentRuntime {
         let max_retries = self.config.agents.defaults.llm_max_retries;
         let base_delay_ms = self.config.agents.defaults.llm_retry_delay_ms;
         let mut last_error = None;
+        let pool = self.llm_pool_for_channel(&msg.channel);
 
         for attempt in 0..=max_retries {
             if attempt > 0 {
@@ -2801,7 +3307,7 @@ impl AgentRuntime {
                 );
                 tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
             }
-            let (pool_idx, provider) = match self.provider_pool.acquire() {
+            let (pool_idx, provider) = match pool.acquire() {
                 Some(p) => p,
                 None => {
                     last_error = Some(blockcell_core::Error::Config(
@@ -2811,6 +3317,7 @@ impl AgentRuntime {
                 }
             };
 
+            let call_started = std::time::Instant::now();
             match provider.chat_stream(current_messages, tools).await {
                 Ok(mut stream_rx) => {
                     if attempt > 0 {
@@ -2827,6 +3334,8 @@ impl AgentRuntime {
                         HashMap::new();
                     let mut emitted_text_delta = false;
                     let mut stream_error: Option<blockcell_core::Error> = None;
+                    // Time to first chunk, reported to the provider health tracker.
+                    let mut first_chunk_latency: Option<std::time::Duration> = None;
 
                     const STREAM_TIMEOUT_SECS: u64 = 300;
 
@@ -2837,6 +3346,10 @@ impl AgentRuntime {
                         )
                         .await;
 
+                        if first_chunk_latency.is_none() && matches!(recv_result, Ok(Some(_))) {
+                            first_chunk_latency = Some(call_started.elapsed());
+                        }
+
                         match recv_result {
                             Ok(Some(chunk)) => match chunk {
                                 StreamChunk::TextDelta { delta } => {
@@ -2844,7 +3357,7 @@ impl AgentRuntime {
                                     emitted_text_delta = true;
                                     if let Some(ref event_tx) = self.event_tx {
                                         let event = serde_json::json!({
-                                            "type": "token",
+                                            "type": "message_delta",
                                             "agent_id": self.agent_id.clone().unwrap_or_else(|| "default".to_string()),
                                             "chat_id": msg.chat_id.clone(),
                                             "delta": delta,
@@ -2900,6 +3413,13 @@ impl AgentRuntime {
                                         response.reasoning_content.clone()
                                     };
 
+                                    pool.report_with_latency(
+                                        pool_idx,
+                                        CallResult::Success,
+                                        first_chunk_latency
+                                            .unwrap_or_else(|| call_started.elapsed()),
+                                    );
+                                    self.record_llm_spend(&pool, pool_idx, &response.usage);
                                     return Ok(LLMResponse {
                                         content: final_content,
                                         reasoning_content: final_reasoning,
@@ -2937,7 +3457,11 @@ impl AgentRuntime {
                     if stream_error.is_none()
                         && (!tool_call_accumulators.is_empty() || !accumulated_content.is_empty())
                     {
-                        self.provider_pool.report(pool_idx, CallResult::Success);
+                        pool.report_with_latency(
+                            pool_idx,
+                            CallResult::Success,
+                            first_chunk_latency.unwrap_or_else(|| call_started.elapsed()),
+                        );
                         let final_tool_calls: Vec<ToolCallRequest> = tool_call_accumulators
                             .into_values()
                             .map(|acc| acc.to_tool_call_request())
@@ -2981,7 +3505,7 @@ impl AgentRuntime {
                     if matches!(&call_result, CallResult::RateLimit) {
                         *saw_rate_limit_this_turn = true;
                     }
-                    self.provider_pool.report(pool_idx, call_result);
+                    pool.report(pool_idx, call_result);
                     last_error = Some(err);
                 }
                 Err(e) => {
@@ -2991,7 +3515,7 @@ impl AgentRuntime {
                     if matches!(&call_result, CallResult::RateLimit) {
                         *saw_rate_limit_this_turn = true;
                     }
-                    self.provider_pool.report(pool_idx, call_result);
+                    pool.report(pool_idx, call_result);
                     last_error = Some(e);
                 }
             }
@@ -3003,6 +3527,31 @@ impl AgentRuntime {
     }
 
     pub async fn process_message(&mut self, msg: InboundMessage) -> Result<String> {
+        if !self.config.agents.defaults.record_turns || self.replay_tools.is_some() {
+            return self.process_message_inner(msg).await;
+        }
+        let session_key = msg.session_key();
+        let history = self.session_store.load(&session_key).unwrap_or_default();
+        self.recording = Some(crate::replay::TurnRecording::new(
+            &msg,
+            &session_key,
+            &self.config.agents.defaults.model,
+            history,
+        ));
+        let result = self.process_message_inner(msg).await;
+        if let Some(mut recording) = self.recording.take() {
+            if let Ok(response) = &result {
+                recording.final_response = response.clone();
+            }
+            match recording.save(&self.paths.recordings_dir()) {
+                Ok(path) => debug!(path = %path.display(), "Turn recording saved"),
+                Err(e) => warn!(error = %e, "Failed to save turn recording"),
+            }
+        }
+        result
+    }
+
+    async fn process_message_inner(&mut self, msg: InboundMessage) -> Result<String> {
         let mut metrics = ProcessingMetrics::new();
         let session_key = msg.session_key();
         let cron_deliver_target = resolve_cron_deliver_target(&msg);
@@ -3240,6 +3789,7 @@ impl AgentRuntime {
         // Load session history
         let mut history = self.session_store.load(&session_key)?;
         let mut session_metadata = self.session_store.load_metadata(&persist_session_key)?;
+        blockcell_storage::privacy::record_sender(&mut session_metadata, &msg.sender_id);
 
         // Layer 2: 时间触发的轻量压缩
         // 检查会话最后更新时间，如果超过阈值则清理旧工具结果
@@ -3337,18 +3887,9 @@ impl AgentRuntime {
         // Ghost routine: ensure required tools are always available.
         // Rationale: intent classification may treat the routine prompt as Chat, producing zero tools,
         // which would cause the LLM to think tools are unavailable.
-        if msg.metadata.get("ghost").and_then(|v| v.as_bool()) == Some(true) {
-            let required = [
-                "community_hub",
-                "memory_maintenance",
-                "memory_query",
-                "memory_upsert",
-                "list_dir",
-                "read_file",
-                "file_ops",
-                "notification",
-            ];
-            for name in required {
+        let is_ghost = msg.metadata.get("ghost").and_then(|v| v.as_bool()) == Some(true);
+        if is_ghost {
+            for name in GHOST_REQUIRED_TOOLS {
                 if !tool_names.iter().any(|tool_name| tool_name == name) {
                     tool_names.push(name.to_string());
                 }
@@ -3398,6 +3939,11 @@ impl AgentRuntime {
             .get("media_pending_intent")
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
+        self.context_builder
+            .set_project_brief(blockcell_tools::projects::active_project_brief(
+                &self.paths.workspace(),
+                &msg.session_key(),
+            ));
         let mut messages = self.context_builder.build_messages_for_mode_with_channel(
             &history,
             &msg.content,
@@ -3411,6 +3957,10 @@ impl AgentRuntime {
             &tool_names,
             &tool_prompt_rules,
         );
+        let base_prompt_len = messages
+            .first()
+            .and_then(|m| m.content.as_str())
+            .map_or(0, str::len);
         if decision.active_skill.is_none() {
             inject_skill_cards_into_system_prompt(
                 &mut messages,
@@ -3418,8 +3968,15 @@ impl AgentRuntime {
                 recent_skill_name.as_deref(),
             );
         }
+        let skill_cards_text = messages
+            .first()
+            .and_then(|m| m.content.as_str())
+            .and_then(|p| p.get(base_prompt_len..))
+            .unwrap_or_default()
+            .to_string();
 
         // Now add user message to history for session persistence
+        let user_message_index = history.len();
         history.push(ChatMessage::user(&msg.content));
 
         // Layer 4: Initialize memory system if needed
@@ -3436,12 +3993,67 @@ impl AgentRuntime {
             }
         }
 
+        // Tool selection: cap the schemas sent per call to the most relevant tools.
+        // Pruned tools stay in `tool_names`, so the model can still call them by name.
+        let selection_config = &self.config.agents.defaults.tool_selection;
+        let tool_selection: Option<ToolSelection> =
+            (selection_config.max_tools_per_call > 0 && !tool_names.is_empty()).then(|| {
+                let mut pinned: HashSet<String> =
+                    selection_config.always_include.iter().cloned().collect();
+                pinned.insert(ACTIVATE_SKILL_TOOL_NAME.to_string());
+                if let Some(skill) = decision.active_skill.as_ref() {
+                    pinned.extend(skill.tools.iter().cloned());
+                }
+                if is_ghost {
+                    pinned.extend(GHOST_REQUIRED_TOOLS.iter().map(|name| name.to_string()));
+                }
+                let intent_tools: HashSet<String> = resolve_profile_tool_names(
+                    &self.config,
+                    self.agent_id.as_deref(),
+                    &decision.chat_intents,
+                    &available_tools,
+                )
+                .into_iter()
+                .collect();
+                let core_tools: HashSet<String> = global_core_tool_names().into_iter().collect();
+                let recent =
+                    tool_selection::recent_tool_calls(&history, selection_config.recent_messages);
+                let selection = tool_selection::select_tools(
+                    &tool_names,
+                    selection_config.max_tools_per_call,
+                    &SelectionInput {
+                        query: &msg.content,
+                        pinned: &pinned,
+                        intent_tools: &intent_tools,
+                        core_tools: &core_tools,
+                        recent: &recent,
+                    },
+                    |name| {
+                        self.tool_registry
+                            .get(name)
+                            .map(|tool| tool.schema().description.to_string())
+                    },
+                );
+                if !selection.pruned.is_empty() {
+                    debug!(
+                        selected = selection.selected.len(),
+                        pruned = ?selection.pruned,
+                        "Pruned tool schemas for this turn"
+                    );
+                }
+                tool_selection::metrics().record_turn(&selection);
+                selection
+            });
+
         // Get tool schemas from resolved tool names
         let mut tools = if tool_names.is_empty() {
             // Chat mode: no tools
             vec![]
         } else {
-            let tool_name_refs: Vec<&str> = tool_names.iter().map(String::as_str).collect();
+            let schema_names = tool_selection
+                .as_ref()
+                .map_or(&tool_names, |selection| &selection.selected);
+            let tool_name_refs: Vec<&str> = schema_names.iter().map(String::as_str).collect();
             let mut schemas = self.tool_registry.get_tiered_schemas(
                 &tool_name_refs,
                 blockcell_tools::registry::global_core_tool_names(),
@@ -3482,6 +4094,8 @@ impl AgentRuntime {
         let mut tool_call_counts: HashMap<String, u32> = HashMap::new();
         let mut over_iteration: bool = false;
         let mut current_messages = messages;
+        let mut loop_guard = LoopGuard::new(&self.config.agents.defaults.loop_detection);
+        let mut context_snapshot_pending = self.config.agents.defaults.context_snapshots;
 
         // Layer 1: 消息级别预算检查
         // 如果工具结果总和超过预算，持久化最大的结果
@@ -3590,10 +4204,41 @@ impl AgentRuntime {
         let mut saw_rate_limit_this_turn = false;
         // Collect media paths produced by tools (screenshots, generated images, etc.)
         let mut collected_media: Vec<String> = Vec::new();
+        self.turn_artifacts.clear();
+        self.turn_citations.clear();
+
+        // Planning mode: propose a step plan for complex requests and wait for approval.
+        self.active_plan = None;
+        if planning::should_plan(
+            &self.config.agents.defaults.planning,
+            &msg.content,
+            !tools.is_empty(),
+        ) && !matches!(msg.channel.as_str(), "cron" | "ghost")
+            && !session_key.starts_with("subagent:")
+        {
+            if let Some(steps) = self.draft_plan(&msg.content, &tool_names, None).await {
+                let plan = Plan::new(steps);
+                info!(plan_id = %plan.id, steps = plan.steps.len(), "📋 Plan proposed");
+                if self.request_plan_approval(&plan, &msg).await {
+                    current_messages.push(ChatMessage::user(&plan.execution_prompt()));
+                    tools.push(planning::plan_update_schema());
+                    self.active_plan = Some(plan);
+                } else {
+                    info!(plan_id = %plan.id, "Plan rejected by user");
+                    tools.clear();
+                    current_messages.push(ChatMessage::user(
+                        "[系统] 用户没有批准刚才提出的执行计划。不要调用任何工具，简要询问用户希望如何调整。",
+                    ));
+                }
+            }
+        }
 
         // Schema cache flag: tools are loaded once before the loop.
         // Only dynamic supplement (below) mutates the `tools` vec — no redundant reload.
         let mut _schema_cache_dirty = false;
+        // Start of this turn's messages, for response validation evidence.
+        let turn_start = current_messages.len();
+        let mut response_corrections = 0u32;
 
         loop {
             debug!(iteration = ?tool_call_counts, "LLM call iteration");
@@ -3616,6 +4261,18 @@ impl AgentRuntime {
                 should_throttle_next_tool_round = false;
             }
 
+            if context_snapshot_pending {
+                context_snapshot_pending = false;
+                self.record_context_snapshot(
+                    &persist_session_key,
+                    user_message_index,
+                    &current_messages,
+                    &history[..user_message_index.min(history.len())],
+                    &tools,
+                    &skill_cards_text,
+                );
+            }
+
             // Call LLM with extracted sub-function (#15)
             let llm_timer = ScopedTimer::new();
             let llm_result = self
@@ -3628,6 +4285,9 @@ impl AgentRuntime {
                 )
                 .await;
             metrics.record_llm_call(llm_timer.elapsed_ms());
+            if let (Some(recording), Ok(r)) = (self.recording.as_mut(), &llm_result) {
+                recording.record_llm_call(&current_messages, &tools, r);
+            }
 
             let response = match llm_result {
                 Ok(r) => r,
@@ -3761,6 +4421,7 @@ impl AgentRuntime {
                 let mut tool_results: Vec<ChatMessage> = Vec::new();
                 let mut wants_forced_answer = false;
                 let mut web_search_thin_results: Vec<String> = Vec::new(); // URLs from thin search results
+                let mut stuck_call: Option<(String, u32)> = None;
                 for tool_call in &response.tool_calls {
                     if tool_call.name == "web_search" || tool_call.name == "web_fetch" {
                         wants_forced_answer = true;
@@ -3778,7 +4439,44 @@ impl AgentRuntime {
                         }
                     }
                     let tool_timer = ScopedTimer::new();
-                    let result = if tool_names.iter().any(|allowed| allowed == &tool_call.name) {
+                    let verdict = if tool_call.name == planning::PLAN_UPDATE_TOOL {
+                        LoopVerdict::Ok
+                    } else {
+                        loop_guard.record(&tool_call.name, &tool_call.arguments)
+                    };
+                    let result = if let LoopVerdict::Stuck { repeats } = verdict {
+                        warn!(tool = %tool_call.name, repeats, "Identical tool call repeated; not executing");
+                        stuck_call = Some((tool_call.name.clone(), repeats));
+                        serde_json::json!({
+                            "error": format!(
+                                "Tool '{}' was called {} times with identical parameters without progress.",
+                                tool_call.name, repeats
+                            ),
+                            "tool": tool_call.name,
+                            "stuck": true,
+                            "repeats": repeats,
+                        })
+                        .to_string()
+                    } else if verdict == LoopVerdict::BudgetExhausted {
+                        over_iteration = true;
+                        serde_json::json!({
+                            "error": format!(
+                                "Per-turn tool budget ({}) exhausted.",
+                                self.config.agents.defaults.loop_detection.turn_tool_budget
+                            ),
+                            "tool": tool_call.name,
+                            "hint": "Answer with what you have gathered so far or adjust loopDetection.turnToolBudget."
+                        })
+                        .to_string()
+                    } else if tool_call.name == planning::PLAN_UPDATE_TOOL
+                        && self.active_plan.is_some()
+                    {
+                        self.handle_plan_update(tool_call, &msg, &tool_names).await
+                    } else if tool_names.iter().any(|allowed| allowed == &tool_call.name) {
+                        if let Some(selection) = tool_selection.as_ref() {
+                            tool_selection::metrics()
+                                .record_call(selection.selected.contains(&tool_call.name));
+                        }
                         let max_iterations = tools_max_iterations
                             .get(&tool_call.name)
                             .copied()
@@ -3806,6 +4504,13 @@ impl AgentRuntime {
 
                     metrics.record_tool_execution(&tool_call.name, tool_timer.elapsed_ms());
 
+                    // Registered artifacts are delivered like media so channels attach them.
+                    for artifact in &self.turn_artifacts {
+                        if !collected_media.contains(&artifact.path) {
+                            collected_media.push(artifact.path.clone());
+                        }
+                    }
+
                     // Collect media paths from tool results for WebUI display.
                     // Skip the "message" tool — it already dispatches its own OutboundMessage
                     // with media; collecting here would cause a duplicate send.
@@ -3936,6 +4641,12 @@ impl AgentRuntime {
                         resource_missing_hints_sent.remove(&tool_call.name);
                     }
 
+                    let result = crate::empty_result::normalize_tool_result(
+                        &tool_call.name,
+                        &tool_call.arguments,
+                        result,
+                    );
+                    let result = self.guard_untrusted_output(&tool_call.name, result).await;
                     let mut tool_msg = ChatMessage::tool_result(&tool_call.id, &result);
                     tool_msg.name = Some(tool_call.name.clone());
                     tool_results.push(tool_msg);
@@ -3980,6 +4691,25 @@ impl AgentRuntime {
                     history.push(tool_msg);
                 }
 
+                if let Some((stuck_tool, repeats)) = stuck_call {
+                    self.handle_stuck_turn(
+                        &stuck_tool,
+                        repeats,
+                        &msg,
+                        &tool_names,
+                        &mut current_messages,
+                        &mut tools,
+                    )
+                    .await;
+                    loop_guard.reset_window();
+                }
+                if let Some(remaining) = loop_guard.take_budget_warning() {
+                    current_messages.push(ChatMessage::user(&format!(
+                        "[系统] 本轮工具调用预算即将用完（剩余 {} 次）。请优先完成最关键的步骤，并尽快给出答复。",
+                        remaining
+                    )));
+                }
+
                 if wants_forced_answer && !over_iteration {
                     if !web_search_thin_results.is_empty() {
                         // Thin results: guide LLM to fetch actual page content instead of giving up
@@ -4103,20 +4833,7 @@ impl AgentRuntime {
                         "请基于以上工具调用的结果，直接给出最终答案。不要再调用任何工具，也不要输出类似[Called: ...]的过程信息。",
                     ));
 
-                    let chat_result = if let Some((pidx, p)) = self.provider_pool.acquire() {
-                        let r = p.chat(&final_messages, &[]).await;
-                        match &r {
-                            Ok(_) => self.provider_pool.report(pidx, CallResult::Success),
-                            Err(e) => self
-                                .provider_pool
-                                .report(pidx, ProviderPool::classify_error(&format!("{}", e))),
-                        }
-                        r
-                    } else {
-                        Err(blockcell_core::Error::Config(
-                            "ProviderPool: no healthy providers".to_string(),
-                        ))
-                    };
+                    let chat_result = self.chat_with_provider(&final_messages, &[]).await;
                     match chat_result {
                         Ok(r) => {
                             final_response = r.content.unwrap_or_default();
@@ -4135,6 +4852,25 @@ impl AgentRuntime {
                 // No tool calls, we have the final response
                 final_response = response.content.unwrap_or_default();
 
+                let validation = &self.config.agents.defaults.response_validation;
+                if validation.enabled && response_corrections < validation.max_corrections {
+                    let turn_messages = current_messages
+                        .get(turn_start..)
+                        .unwrap_or(&current_messages[..]);
+                    if let Some(correction) =
+                        self.unverified_claims_correction(&final_response, turn_messages)
+                    {
+                        response_corrections += 1;
+                        warn!(
+                            attempt = response_corrections,
+                            "Final answer claims side effects without tool evidence; requesting correction"
+                        );
+                        current_messages.push(ChatMessage::assistant(&final_response));
+                        current_messages.push(ChatMessage::user(&correction));
+                        continue;
+                    }
+                }
+
                 // Add to history
                 history.push(ChatMessage::assistant(&final_response));
                 break;
@@ -4403,6 +5139,39 @@ impl AgentRuntime {
         .await
     }
 
+    /// Store what the first LLM call of a turn was sent, for
+    /// `GET /v1/sessions/:id/messages/:idx/context`.
+    fn record_context_snapshot(
+        &self,
+        session_key: &str,
+        message_index: usize,
+        messages: &[ChatMessage],
+        session: &[ChatMessage],
+        tools: &[serde_json::Value],
+        skill_cards_text: &str,
+    ) {
+        let sections = self.context_builder.last_prompt_sections();
+        let skills = format!("{}{}", sections.skills, skill_cards_text);
+        let tool_names = tools
+            .iter()
+            .filter_map(|t| t.pointer("/function/name").and_then(|n| n.as_str()))
+            .map(str::to_string)
+            .collect();
+        let store = blockcell_storage::context_snapshots::ContextSnapshotStore::new(&self.paths);
+        let input = blockcell_storage::context_snapshots::SnapshotInput {
+            message_index,
+            model: &self.config.agents.defaults.model,
+            messages,
+            session,
+            memory: &sections.memory,
+            skills: &skills,
+            tools: tool_names,
+        };
+        if let Err(e) = store.record(session_key, input) {
+            warn!(error = %e, session_key, "Failed to record context snapshot");
+        }
+    }
+
     /// Extract filesystem paths from tool call parameters.
     fn extract_paths(&self, tool_name: &str, args: &serde_json::Value) -> Vec<String> {
         let mut paths = Vec::new();
@@ -4413,7 +5182,8 @@ impl AgentRuntime {
                 }
             }
             "file_ops" | "data_process" | "audio_transcribe" | "chart_generate"
-            | "office_write" | "video_process" | "health_api" | "encrypt" => {
+            | "office_write" | "video_process" | "health_api" | "encrypt" | "translate"
+            | "object_storage" => {
                 if let Some(p) = args.get("path").and_then(|v| v.as_str()) {
                     paths.push(p.to_string());
                 }
@@ -4431,6 +5201,11 @@ impl AgentRuntime {
                     }
                 }
             }
+            "ssh" => {
+                if let Some(p) = args.get("local_path").and_then(|v| v.as_str()) {
+                    paths.push(p.to_string());
+                }
+            }
             "message" => {
                 if let Some(arr) = args.get("media").and_then(|v| v.as_array()) {
                     for p in arr {
@@ -4676,11 +5451,312 @@ impl AgentRuntime {
         }
     }
 
+    /// Ask the model for a step plan (or, with `failed`, a revision of the remaining work).
+    async fn draft_plan(
+        &self,
+        request: &str,
+        tool_names: &[String],
+        failed: Option<&PlanStep>,
+    ) -> Option<Vec<PlanStep>> {
+        let max_steps = self.config.agents.defaults.planning.max_steps;
+        let failure = failed.and_then(|step| self.active_plan.as_ref().map(|plan| (plan, step)));
+        let messages = planning::planning_messages(request, tool_names, max_steps, failure);
+        match self.chat_with_provider(&messages, &[]).await {
+            Ok(response) => {
+                let steps = planning::parse_plan_steps(
+                    response.content.as_deref().unwrap_or(""),
+                    max_steps,
+                );
+                if steps.is_none() {
+                    warn!("Planner reply had no usable steps, continuing without a plan");
+                }
+                steps
+            }
+            Err(e) => {
+                warn!(error = %e, "Planning call failed, continuing without a plan");
+                None
+            }
+        }
+    }
+
+    /// Publish a plan and wait for the user's decision. Auto-approves when configured or
+    /// when there is no confirmation channel to ask through.
+    async fn request_plan_approval(&mut self, plan: &Plan, msg: &InboundMessage) -> bool {
+        self.emit_plan_event("plan_proposed", plan, &msg.chat_id, None);
+        if self.config.agents.defaults.planning.auto_approve || self.confirm_tx.is_none() {
+            return true;
+        }
+        self.confirm_dangerous_operation("plan", plan.confirmation_items(), msg)
+            .await
+    }
+
+    /// Handle a `plan_update` call from the model: record progress and re-plan on failure.
+    async fn handle_plan_update(
+        &mut self,
+        tool_call: &ToolCallRequest,
+        msg: &InboundMessage,
+        tool_names: &[String],
+    ) -> String {
+        let Some(plan) = self.active_plan.as_mut() else {
+            return serde_json::json!({ "error": "No active plan" }).to_string();
+        };
+        let step = match plan.update_step(&tool_call.arguments) {
+            Ok(step) => step,
+            Err(e) => return serde_json::json!({ "error": e }).to_string(),
+        };
+        let snapshot = plan.clone();
+        self.emit_plan_event("plan_step", &snapshot, &msg.chat_id, Some(&step));
+        if step.status != StepStatus::Failed {
+            return serde_json::json!({
+                "status": "ok",
+                "step": step.index,
+                "finished": snapshot.is_finished(),
+            })
+            .to_string();
+        }
+
+        self.replan_after_failure(&step, msg, tool_names)
+            .await
+            .to_string()
+    }
+
+    /// Ask for a revised plan after `step` failed and get it approved. The returned JSON is
+    /// handed back to the model as guidance.
+    async fn replan_after_failure(
+        &mut self,
+        step: &PlanStep,
+        msg: &InboundMessage,
+        tool_names: &[String],
+    ) -> serde_json::Value {
+        let replans = self.active_plan.as_ref().map(|p| p.replans).unwrap_or(0);
+        if replans >= self.config.agents.defaults.planning.max_replans {
+            return serde_json::json!({
+                "status": "failed",
+                "hint": "已达到最大重新规划次数。请停止执行剩余步骤，向用户说明已完成的部分和失败原因。",
+            });
+        }
+        let Some(remaining) = self.draft_plan(&msg.content, tool_names, Some(step)).await else {
+            return serde_json::json!({
+                "status": "failed",
+                "hint": "无法生成修订计划。请判断能否用其他方式继续，否则向用户说明情况。",
+            });
+        };
+        let revised = match self.active_plan.as_mut() {
+            Some(plan) => {
+                plan.revise(remaining);
+                plan.clone()
+            }
+            None => return serde_json::json!({ "error": "No active plan" }),
+        };
+        info!(plan_id = %revised.id, revision = revised.revision, "📋 Plan revised after failed step");
+        if !self.request_plan_approval(&revised, msg).await {
+            self.active_plan = None;
+            return serde_json::json!({
+                "status": "replan_rejected",
+                "hint": "用户拒绝了修订后的计划。请停止调用工具，说明当前进度并询问用户希望如何继续。",
+            });
+        }
+        serde_json::json!({
+            "status": "replanned",
+            "revision": revised.revision,
+            "instructions": revised.execution_prompt(),
+        })
+    }
+
+    /// Correction prompt when the final answer claims files or IDs that no tool call,
+    /// tool result or artifact in this turn backs up (`agents.defaults.responseValidation`).
+    fn unverified_claims_correction(
+        &self,
+        response: &str,
+        turn_messages: &[ChatMessage],
+    ) -> Option<String> {
+        let artifacts: Vec<String> = self.turn_artifacts.iter().map(|a| a.path.clone()).collect();
+        let mut evidence = crate::claim_check::Evidence::from_turn(turn_messages, &artifacts);
+        if self
+            .config
+            .agents
+            .defaults
+            .response_validation
+            .check_filesystem
+        {
+            evidence = evidence.with_workspace(&self.paths.workspace());
+        }
+        let missing = crate::claim_check::unverified_claims(response, &evidence);
+        if missing.is_empty() {
+            return None;
+        }
+        debug!(claims = ?missing, "Unverified claims in final answer");
+        Some(crate::claim_check::correction_prompt(&missing))
+    }
+
+    /// Sanitize and fence output of tools listed in `security.untrustedContent.tools`
+    /// before it enters the conversation.
+    async fn guard_untrusted_output(&self, tool_name: &str, result: String) -> String {
+        let policy = &self.config.security.untrusted_content;
+        if !policy.applies_to(tool_name) || result.trim().is_empty() {
+            return result;
+        }
+        let mut content = result;
+        let mut removed = 0;
+        if policy.strip_instructions {
+            let (clean, n) = crate::untrusted::strip_instruction_patterns(&content);
+            content = clean;
+            removed += n;
+        }
+        if policy.classifier && content.chars().count() <= policy.classifier_max_chars {
+            match self
+                .chat_with_provider(&crate::untrusted::classifier_messages(&content), &[])
+                .await
+            {
+                Ok(reply) => {
+                    let spans = crate::untrusted::parse_classifier_spans(
+                        &reply.content.unwrap_or_default(),
+                    );
+                    let (clean, n) = crate::untrusted::remove_spans(&content, &spans);
+                    content = clean;
+                    removed += n;
+                }
+                Err(e) => warn!(tool = tool_name, error = %e, "Injection classifier failed"),
+            }
+        }
+        if removed > 0 {
+            warn!(
+                tool = tool_name,
+                removed, "Stripped instruction-like text from untrusted tool output"
+            );
+        }
+        crate::untrusted::wrap_untrusted(tool_name, &content)
+    }
+
+    /// Structured outcome for a turn stuck on repeated identical calls: re-plan when a
+    /// plan is active, otherwise drop the tools and make the model ask a clarifying question.
+    async fn handle_stuck_turn(
+        &mut self,
+        tool: &str,
+        repeats: u32,
+        msg: &InboundMessage,
+        tool_names: &[String],
+        current_messages: &mut Vec<ChatMessage>,
+        tools: &mut Vec<serde_json::Value>,
+    ) {
+        let note = format!("重复调用 {} {} 次仍无进展", tool, repeats);
+        let failed = self
+            .active_plan
+            .as_mut()
+            .and_then(|plan| plan.fail_current(&note));
+        let mut outcome = "clarify";
+        if let Some(step) = failed {
+            if let Some(plan) = self.active_plan.clone() {
+                self.emit_plan_event("plan_step", &plan, &msg.chat_id, Some(&step));
+            }
+            let guidance = self.replan_after_failure(&step, msg, tool_names).await;
+            if guidance["status"] == "replanned" {
+                if let Some(instructions) = guidance["instructions"].as_str() {
+                    current_messages.push(ChatMessage::user(instructions));
+                }
+                outcome = "replan";
+            }
+        }
+        if outcome == "clarify" {
+            tools.clear();
+            current_messages.push(ChatMessage::user(&format!(
+                "[系统] 你已经用相同参数调用 `{}` {} 次仍没有进展。不要再调用工具；向用户简要说明卡在哪里，并提出一个具体的澄清问题。",
+                tool, repeats
+            )));
+        }
+        warn!(tool, repeats, outcome, "Agent stuck in a tool loop");
+
+        if let Some(ref event_tx) = self.event_tx {
+            let event = serde_json::json!({
+                "type": "agent_stuck",
+                "agent_id": self.agent_id.clone().unwrap_or_else(|| "default".to_string()),
+                "chat_id": msg.chat_id,
+                "task_id": self.current_task_id.as_deref().unwrap_or(""),
+                "tool": tool,
+                "repeats": repeats,
+                "outcome": outcome,
+            });
+            let _ = event_tx.send(event.to_string());
+        }
+    }
+
+    fn emit_plan_event(&self, kind: &str, plan: &Plan, chat_id: &str, step: Option<&PlanStep>) {
+        if let Some(ref event_tx) = self.event_tx {
+            let mut event = serde_json::json!({
+                "type": kind,
+                "agent_id": self.agent_id.clone().unwrap_or_else(|| "default".to_string()),
+                "chat_id": chat_id,
+                "task_id": self.current_task_id.as_deref().unwrap_or(""),
+                "plan_id": plan.id,
+                "revision": plan.revision,
+                "steps": plan.steps,
+                "finished": plan.is_finished(),
+                "auto_approve": self.config.agents.defaults.planning.auto_approve,
+            });
+            if let Some(step) = step {
+                event["step"] = serde_json::json!(step);
+            }
+            let _ = event_tx.send(event.to_string());
+        }
+    }
+
+    /// Broadcast an `artifact` event so clients can render a download card for the file.
+    fn announce_artifact(
+        &self,
+        artifact: &blockcell_tools::ToolArtifact,
+        tool: &str,
+        call_id: &str,
+        chat_id: &str,
+    ) {
+        info!(tool, path = %artifact.path, size = artifact.size, "📎 Tool registered artifact");
+        if let Some(ref event_tx) = self.event_tx {
+            let agent_id = self
+                .agent_id
+                .clone()
+                .unwrap_or_else(|| "default".to_string());
+            let event = serde_json::json!({
+                "type": "artifact",
+                "agent_id": agent_id,
+                "chat_id": chat_id,
+                "task_id": self.current_task_id.as_deref().unwrap_or(""),
+                "tool": tool,
+                "call_id": call_id,
+                "name": artifact.name,
+                "path": artifact.path,
+                "kind": artifact.kind,
+                "mime": artifact.mime,
+                "size": artifact.size,
+                "description": artifact.description,
+                "serve_url": artifact.serve_url(&agent_id),
+                "download_url": artifact.download_url(&agent_id),
+            });
+            let _ = event_tx.send(event.to_string());
+        }
+    }
+
     async fn execute_tool_call(
         &mut self,
         tool_call: &ToolCallRequest,
         msg: &InboundMessage,
         active_skill_dir: Option<PathBuf>,
+    ) -> String {
+        if let Some(replay) = self.replay_tools.as_mut() {
+            return replay.next_result(tool_call);
+        }
+        let result = self
+            .execute_tool_call_inner(tool_call, msg, active_skill_dir)
+            .await;
+        if let Some(recording) = self.recording.as_mut() {
+            recording.record_tool_call(tool_call, &result);
+        }
+        result
+    }
+
+    async fn execute_tool_call_inner(
+        &mut self,
+        tool_call: &ToolCallRequest,
+        msg: &InboundMessage,
+        active_skill_dir: Option<PathBuf>,
     ) -> String {
         // Hard block: reject disabled tools at execution level (not just prompt filtering)
         let disabled_tools = load_disabled_toggles(&self.paths, "tools");
@@ -4692,6 +5768,22 @@ impl AgentRuntime {
         if disabled_skills.contains(&tool_call.name) {
             return disabled_skill_result(&tool_call.name);
         }
+        // Role-based access: channel senders only get the tools their role allows.
+        if let Err(e) = self.tool_permissions.check_message(msg, &tool_call.name) {
+            warn!(
+                tool = %tool_call.name,
+                channel = %msg.channel,
+                sender_id = %msg.sender_id,
+                "Tool call denied by role"
+            );
+            return role_denied_result(&tool_call.name, &e.to_string());
+        }
+        if blockcell_core::connectivity::is_offline()
+            && self.config.offline.is_network_tool(&tool_call.name)
+            && !is_local_only_call(tool_call)
+        {
+            return offline_tool_result(&tool_call.name);
+        }
 
         // Dangerous-operation gate: require explicit user confirmation before executing
         // self-destructive commands or destructive file operations.
@@ -4759,6 +5851,25 @@ impl AgentRuntime {
             }
         }
 
+        // Tools that declare their own confirmation needs (remote exec, uploads, …).
+        let items = self
+            .tool_registry
+            .get(&tool_call.name)
+            .map(|t| t.confirmation_items(&self.config, &tool_call.arguments))
+            .unwrap_or_default();
+        if !items.is_empty() {
+            if self.confirm_tx.is_none() {
+                if !user_explicitly_confirms_dangerous_op(&msg.content) {
+                    return confirmation_denied(&tool_call.name, false);
+                }
+            } else if !self
+                .confirm_dangerous_operation(&tool_call.name, items, msg)
+                .await
+            {
+                return confirmation_denied(&tool_call.name, true);
+            }
+        }
+
         // Check path safety before executing filesystem/exec tools
         if !self
             .check_path_permission(&tool_call.name, &tool_call.arguments, msg)
@@ -4784,6 +5895,7 @@ impl AgentRuntime {
             event_emitter: self.system_event_emitter.clone(),
         });
 
+        let artifacts: blockcell_tools::ArtifactCollector = Arc::default();
         let ctx = blockcell_tools::ToolContext {
             workspace: self.paths.workspace(),
             builtin_skills_dir: Some(self.paths.builtin_skills_dir()),
@@ -4794,11 +5906,7 @@ impl AgentRuntime {
             sender_id: Some(msg.sender_id.clone()),
             chat_id: msg.chat_id.clone(),
             config: self.config.clone(),
-            permissions: self.build_tool_permissions(
-                &msg.channel,
-                Some(&msg.sender_id),
-                &msg.chat_id,
-            ),
+            permissions: self.build_tool_permissions(msg),
             task_manager: Some(tm_handle),
             memory_store: self.memory_store.clone(),
             outbound_tx: self.outbound_tx.clone(),
@@ -4810,6 +5918,7 @@ impl AgentRuntime {
             response_cache: Some(
                 Arc::new(self.response_cache.clone()) as blockcell_tools::ResponseCacheHandle
             ),
+            artifacts: Some(artifacts.clone()),
         };
 
         // Emit tool_call_start event to WebSocket clients
@@ -4833,6 +5942,22 @@ impl AgentRuntime {
             .await;
         let duration_ms = start.elapsed().as_millis() as u64;
 
+        let registered = artifacts
+            .lock()
+            .map(|mut list| std::mem::take(&mut *list))
+            .unwrap_or_default();
+        for artifact in registered {
+            self.announce_artifact(&artifact, &tool_call.name, &tool_call.id, &msg.chat_id);
+            if let Some(ref task_id) = self.current_task_id {
+                self.task_manager
+                    .add_artifact(task_id, artifact.clone())
+                    .await;
+            }
+            if !self.turn_artifacts.iter().any(|a| a.path == artifact.path) {
+                self.turn_artifacts.push(artifact);
+            }
+        }
+
         let is_error = result.is_err();
         let (result_str, result_json) = match &result {
             Ok(val) => (val.to_string(), val.clone()),
@@ -4841,6 +5966,17 @@ impl AgentRuntime {
                 (err_str.clone(), serde_json::json!({"error": err_str}))
             }
         };
+        let citations = &self.config.agents.defaults.citations;
+        let citation_note = if citations.enabled && !is_error {
+            self.turn_citations.record(
+                &tool_call.name,
+                &tool_call.arguments,
+                &result_json,
+                citations.max_sources,
+            )
+        } else {
+            None
+        };
 
         // Detect writes to the skills directory and trigger hot-reload + Dashboard refresh
         if !is_error && (tool_call.name == "write_file" || tool_call.name == "edit_file") {
@@ -4915,6 +6051,15 @@ impl AgentRuntime {
                 }
             }
         }
+        // Skills registered as tools count towards skill analytics too.
+        if self
+            .context_builder
+            .skill_manager()
+            .is_some_and(|manager| manager.get(&tool_call.name).is_some())
+        {
+            self.record_skill_run(&tool_call.name, !is_error, duration_ms, 0);
+        }
+
         // 报告调用结果给灰度统计
         if let Some(evo_service) = self.context_builder.evolution_service() {
             let reported_name = tool_call.name.clone();
@@ -4992,6 +6137,11 @@ impl AgentRuntime {
             }
         }
 
+        let result_str = match citation_note {
+            Some(note) => format!("{}\n\n{}", result_str, note),
+            None => result_str,
+        };
+
         // 在工具结果中追加学习提示，让 LLM 自然地回复用户
         match learning_hint {
             Some(hint) => format!("{}\n\n{}", result_str, hint),
@@ -5029,6 +6179,11 @@ impl AgentRuntime {
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
         let session_metadata = self.session_store.load_metadata(session_key)?;
+        self.context_builder
+            .set_project_brief(blockcell_tools::projects::active_project_brief(
+                &self.paths.workspace(),
+                &msg.session_key(),
+            ));
         let messages = self.context_builder.build_messages_for_mode_with_channel(
             history,
             &msg.content,
@@ -5293,6 +6448,7 @@ impl AgentRuntime {
                     event_emitter: Some(event_emitter.clone()),
                     channel_contacts_file: Some(paths.channel_contacts_file()),
                     response_cache: None,
+                    artifacts: None,
                 };
 
                 // Execute tool synchronously via a new tokio runtime handle
@@ -5376,11 +6532,18 @@ impl AgentRuntime {
 
     pub async fn run_loop(
         &mut self,
-        mut inbound_rx: mpsc::Receiver<InboundMessage>,
+        inbound_rx: mpsc::Receiver<InboundMessage>,
         mut shutdown_rx: Option<broadcast::Receiver<()>>,
     ) {
         info!("AgentRuntime started");
 
+        // Coalesce rapid-fire messages per chat before they reach the loop.
+        let mut inbound_rx = if self.config.channels.batch.is_enabled() {
+            crate::batch_window::spawn(inbound_rx, self.config.channels.batch.clone())
+        } else {
+            inbound_rx
+        };
+
         // 启动灰度发布调度器（每 60 秒 tick 一次）
         let has_evolution = self.context_builder.evolution_service().is_some();
         if has_evolution {
@@ -5393,6 +6556,18 @@ impl AgentRuntime {
         tick_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
         let mut active_chat_tasks: HashMap<String, String> = HashMap::new();
         let mut active_message_tasks: HashMap<String, tokio::task::JoinHandle<()>> = HashMap::new();
+        let mut last_retention_run: Option<std::time::Instant> = None;
+        let mut last_skill_review: Option<std::time::Instant> = None;
+        let mut last_todo_digest: Option<String> = None;
+        let mut idle = crate::idle::IdleScheduler::new(
+            self.config.agents.defaults.idle_maintenance.clone(),
+            self.agent_id.as_deref().is_none_or(|id| id == "default"),
+            std::time::Instant::now(),
+        );
+        if idle.enabled() {
+            info!("Idle-time maintenance enabled");
+        }
+        let mut idle_task: Option<tokio::task::JoinHandle<()>> = None;
         let (task_done_tx, mut task_done_rx) = mpsc::unbounded_channel::<(String, String)>();
 
         async fn abort_active_message_tasks(
@@ -5437,6 +6612,16 @@ impl AgentRuntime {
                 msg = inbound_rx.recv() => {
                     match msg {
                         Some(msg) => {
+                            // A user message pauses idle maintenance right away.
+                            if crate::idle::is_interactive(&msg) {
+                                if let Some(job) = idle.note_activity(std::time::Instant::now()) {
+                                    if let Some(handle) = idle_task.take() {
+                                        handle.abort();
+                                    }
+                                    info!(job = job.name(), "Paused idle maintenance for incoming message");
+                                }
+                            }
+
                             if msg.metadata.get("cancel").and_then(|v| v.as_bool()).unwrap_or(false) {
                                 let chat_id = msg.chat_id.clone();
                                 let mut cancelled = false;
@@ -5466,6 +6651,20 @@ impl AgentRuntime {
                                 continue;
                             }
 
+                            if crate::inline_query::inline_query_id(&msg).is_some() {
+                                if let Some(outbound_tx) = self.outbound_tx.clone() {
+                                    tokio::spawn(crate::inline_query::answer(
+                                        self.config.clone(),
+                                        self.paths.clone(),
+                                        Arc::clone(&self.provider_pool),
+                                        self.tool_registry.clone(),
+                                        outbound_tx,
+                                        msg,
+                                    ));
+                                }
+                                continue;
+                            }
+
                             self.update_main_session_target(&msg);
 
                             // Spawn each message as a background task so the loop
@@ -5551,19 +6750,122 @@ impl AgentRuntime {
                         std::time::Duration::from_secs(300)
                     ).await;
 
-                    // Memory maintenance (TTL cleanup, recycle bin purge)
-                    if let Some(ref store) = self.memory_store {
-                        if let Err(e) = store.maintenance(30) {
-                            warn!(error = %e, "Memory maintenance error");
+                    // Memory maintenance (TTL cleanup, recycle bin purge); deferred to idle time when enabled
+                    if !idle.enabled() {
+                        if let Some(ref store) = self.memory_store {
+                            if let Err(e) = store.maintenance(30) {
+                                warn!(error = %e, "Memory maintenance error");
+                            }
+                        }
+                    }
+
+                    // Idle-time maintenance: one background job per tick while nobody is chatting
+                    let now = std::time::Instant::now();
+                    if idle_task.as_ref().is_some_and(|h| h.is_finished()) {
+                        idle_task = None;
+                        idle.finish();
+                    }
+                    if let Some(job) = idle.next_due(now) {
+                        idle.start(job, now);
+                        let ctx = crate::idle::JobContext {
+                            config: self.config.clone(),
+                            paths: self.paths.clone(),
+                            memory_store: self.memory_store.clone(),
+                            event_emitter: self.system_event_emitter.clone(),
+                        };
+                        debug!(job = job.name(), "Starting idle maintenance job");
+                        idle_task = Some(tokio::spawn(crate::idle::run_job(job, ctx)));
+                    }
+
+                    // Per-channel retention windows (at most hourly)
+                    let privacy = &self.config.security.privacy;
+                    if !privacy.retention_days.is_empty()
+                        && last_retention_run
+                            .is_none_or(|t| t.elapsed() >= std::time::Duration::from_secs(3600))
+                    {
+                        last_retention_run = Some(std::time::Instant::now());
+                        let store = self.memory_store.as_ref().and_then(|s| s.store());
+                        if let Err(e) = blockcell_storage::privacy::enforce_retention(
+                            &self.paths,
+                            store.as_ref(),
+                            privacy,
+                        ) {
+                            warn!(error = %e, "Retention cleanup error");
                         }
                     }
 
+                    // RSS feed refresh (only feeds whose interval elapsed, runs in background)
+                    blockcell_tools::rss::spawn_due_refresh(&self.config, self.paths.workspace());
+
+                    // Watched document folders (change summaries, runs in background)
+                    blockcell_tools::doc_diff::spawn_due_scans(
+                        self.config.clone(),
+                        self.paths.workspace(),
+                        self.system_event_emitter.clone(),
+                    );
+
+                    // Trip watchers (delays, gate/platform changes, runs in background)
+                    blockcell_tools::travel::spawn_due_checks(
+                        self.config.clone(),
+                        self.paths.workspace(),
+                        self.system_event_emitter.clone(),
+                    );
+
+                    // Parcel tracking polls (status changes, runs in background)
+                    blockcell_tools::parcel_track::spawn_due_checks(
+                        self.config.clone(),
+                        self.paths.workspace(),
+                        self.system_event_emitter.clone(),
+                    );
+
+                    // Weekly "saved but not read" bookmark digest
+                    blockcell_tools::bookmarks::send_weekly_digest(
+                        &self.config,
+                        &self.paths.workspace(),
+                        &self.system_event_emitter,
+                    );
+
+                    // Habit check-in prompts (once per habit per day)
+                    blockcell_tools::habits::send_due_checkins(
+                        &self.config,
+                        &self.paths.workspace(),
+                        &self.system_event_emitter,
+                    );
+
+                    // Provider health snapshot for `blockcell config providers`
+                    if let Err(e) = blockcell_providers::health::global()
+                        .persist(&self.paths.provider_health_file())
+                    {
+                        debug!(error = %e, "Failed to persist provider health");
+                    }
+
+                    // Morning TODO digest (once per local day)
+                    self.send_todo_digest(&mut last_todo_digest);
+
                     let _ = self
                         .process_system_event_tick(chrono::Utc::now().timestamp_millis())
                         .await;
 
+                    // Evolution work is non-critical: it pauses at the budget cap and, with
+                    // idle maintenance enabled, waits until nobody is chatting.
+                    let budget_paused = crate::budget::background_paused(
+                        &self.paths,
+                        &self.config.agents.defaults.budget,
+                    );
+                    let evolution_paused = budget_paused || (idle.enabled() && !idle.is_idle(now));
+
+                    // Chronically underperforming skills become improvement candidates (daily)
+                    if has_evolution
+                        && !evolution_paused
+                        && last_skill_review
+                            .is_none_or(|t| t.elapsed() >= std::time::Duration::from_secs(86400))
+                    {
+                        last_skill_review = Some(std::time::Instant::now());
+                        self.queue_underperforming_skills().await;
+                    }
+
                     // Evolution rollout tick
-                    if has_evolution {
+                    if has_evolution && !evolution_paused {
                         if let Some(evo_service) = self.context_builder.evolution_service() {
                             if let Err(e) = evo_service.tick().await {
                                 warn!(error = %e, "Evolution rollout tick error");
@@ -5572,7 +6874,7 @@ impl AgentRuntime {
                     }
 
                     // Process pending core evolutions
-                    if let Some(ref core_evo_handle) = self.core_evolution {
+                    if let Some(core_evo_handle) = self.core_evolution.as_ref().filter(|_| !evolution_paused) {
                         let core_evo = core_evo_handle.lock().await;
                         match core_evo.run_pending_evolutions().await {
                             Ok(n) if n > 0 => {
@@ -5610,7 +6912,7 @@ impl AgentRuntime {
 
                     // Auto-trigger Capability evolution for missing skill dependencies
                     // With 24h cooldown per capability to prevent repeated requests
-                    if let Some(ref core_evo_handle) = self.core_evolution {
+                    if let Some(core_evo_handle) = self.core_evolution.as_ref().filter(|_| !evolution_paused) {
                         let missing = self.context_builder.get_missing_capabilities();
                         let now = chrono::Utc::now().timestamp();
                         const COOLDOWN_SECS: i64 = 86400; // 24 hours
@@ -5659,6 +6961,9 @@ impl AgentRuntime {
             &mut active_message_tasks,
         )
         .await;
+        if let Some(handle) = idle_task.take() {
+            handle.abort();
+        }
         info!("AgentRuntime stopped");
     }
 }
@@ -5759,10 +7064,18 @@ async fn run_message_task(
     task_id: String,
 ) {
     task_manager.set_running(&task_id).await;
+    let cron_run = msg
+        .metadata
+        .get(CRON_RUN_ID_KEY)
+        .and_then(|v| v.as_str())
+        .map(|run_id| (paths.cron_history_db(), run_id.to_string()));
 
     let mut runtime = match AgentRuntime::new(config, paths, provider_pool, tool_registry) {
         Ok(r) => r,
         Err(e) => {
+            if let Some((db, run_id)) = &cron_run {
+                finish_cron_run(db, run_id, Err(&e.to_string()));
+            }
             task_manager.set_failed(&task_id, &format!("{}", e)).await;
             if let Some(tx) = &outbound_tx {
                 let mut outbound =
@@ -5784,6 +7097,7 @@ async fn run_message_task(
     runtime.set_task_manager(task_manager.clone());
     runtime.set_agent_id(agent_id.clone());
     runtime.set_event_emitter(event_emitter);
+    runtime.set_current_task_id(Some(task_id.clone()));
     if let Some(store) = memory_store {
         runtime.set_memory_store(store);
     }
@@ -5799,12 +7113,29 @@ async fn run_message_task(
 
     let error_chat_id = msg.chat_id.clone();
 
-    match runtime.process_message(msg).await {
+    let result = runtime.process_message(msg).await;
+    if let Some((db, run_id)) = &cron_run {
+        match &result {
+            Ok(reply) => finish_cron_run(db, run_id, Ok(reply)),
+            Err(e) => finish_cron_run(db, run_id, Err(&e.to_string())),
+        }
+    }
+    match result {
         Ok(response) => {
             debug!(task_id = %task_id, response_len = response.len(), "Message task completed");
             // Remove completed message tasks immediately — the response was already
-            // sent via outbound_tx. Only subagent tasks persist in the task list.
-            task_manager.remove_task(&task_id).await;
+            // sent via outbound_tx. Only subagent tasks persist in the task list, plus
+            // message tasks that produced files so /v1/tasks/:id/artifacts can list them
+            // until tick cleanup drops them.
+            let has_artifacts = task_manager
+                .artifacts(&task_id)
+                .await
+                .is_some_and(|a| !a.is_empty());
+            if has_artifacts {
+                task_manager.set_completed(&task_id, &response).await;
+            } else {
+                task_manager.remove_task(&task_id).await;
+            }
         }
         Err(e) => {
             let err_msg = format!("{}", e);
@@ -5827,6 +7158,18 @@ async fn run_message_task(
     }
 }
 
+/// Close the cron run a dispatched cron message belongs to.
+fn finish_cron_run(db: &std::path::Path, run_id: &str, outcome: std::result::Result<&str, &str>) {
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let recorded = CronRunStore::open(db).and_then(|store| match outcome {
+        Ok(reply) => store.finish(run_id, CronRunStatus::Success, Some(reply), None, now_ms),
+        Err(error) => store.finish(run_id, CronRunStatus::Failed, None, Some(error), now_ms),
+    });
+    if let Err(e) = recorded {
+        warn!(run_id = %run_id, error = %e, "Failed to record cron run result");
+    }
+}
+
 /// Free async function that runs a subagent task in the background.
 /// This is separate from `AgentRuntime` methods to break the recursive async type
 /// chain that would otherwise prevent the future from being `Send`.
@@ -5875,6 +7218,7 @@ async fn run_subagent_task(
     sub_runtime.set_task_manager(task_manager.clone());
     sub_runtime.set_agent_id(agent_id.clone());
     sub_runtime.set_event_emitter(event_emitter);
+    sub_runtime.set_current_task_id(Some(task_id.clone()));
 
     // Create a unique session key for this subagent
     let session_key = format!("subagent:{}", task_id);
@@ -5981,6 +7325,52 @@ async fn deliver_subagent_result_to_origin(
 
 /// Build outbound metadata containing reply-to information from an inbound message.
 /// Only applies to group chats — single/DM chats return Null so no quoting is added.
+/// Compact artifact list attached to the final assistant message.
+fn compact_artifacts(artifacts: &[blockcell_tools::ToolArtifact]) -> serde_json::Value {
+    serde_json::Value::Array(
+        artifacts
+            .iter()
+            .map(|a| {
+                serde_json::json!({
+                    "name": a.name,
+                    "path": a.path,
+                    "kind": a.kind,
+                    "mime": a.mime,
+                    "size": a.size,
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Merge a non-empty artifact list into outbound metadata under `artifacts`.
+fn with_artifacts(metadata: serde_json::Value, artifacts: &serde_json::Value) -> serde_json::Value {
+    if artifacts.as_array().is_none_or(|a| a.is_empty()) {
+        return metadata;
+    }
+    let mut metadata = match metadata {
+        serde_json::Value::Object(_) => metadata,
+        _ => serde_json::json!({}),
+    };
+    metadata["artifacts"] = artifacts.clone();
+    metadata
+}
+
+fn with_citations(
+    metadata: serde_json::Value,
+    citations: Option<&serde_json::Value>,
+) -> serde_json::Value {
+    let Some(citations) = citations else {
+        return metadata;
+    };
+    let mut metadata = match metadata {
+        serde_json::Value::Object(_) => metadata,
+        _ => serde_json::json!({}),
+    };
+    metadata["citations"] = citations.clone();
+    metadata
+}
+
 fn extract_reply_metadata(msg: &InboundMessage) -> serde_json::Value {
     match msg.channel.as_str() {
         "telegram" => {
@@ -6763,6 +8153,7 @@ mod tests {
             event_emitter: Some(Arc::new(NoopEmitter)),
             channel_contacts_file: None,
             response_cache: None,
+            artifacts: None,
         };
 
         assert!(ctx.event_emitter.is_some());
@@ -7724,11 +9115,24 @@ description: local demo
         assert!(
             contains_event_subsequence(
                 &event_types,
-                &["token", "stream_reset", "token", "message_done"]
+                &[
+                    "message_delta",
+                    "stream_reset",
+                    "message_delta",
+                    "message_done"
+                ]
             ),
             "unexpected event order: {:?}",
             event_types
         );
+        // One event per streamed delta: "partial", then "final answer" after the retry.
+        let deltas: Vec<_> = events
+            .iter()
+            .filter(|e| e["type"] == "message_delta")
+            .map(|e| e["delta"].as_str().unwrap_or_default())
+            .collect();
+        assert_eq!(deltas, vec!["partial", "final answer"]);
+        assert!(!event_types.iter().any(|t| t == "token"));
         let final_event = events
             .iter()
             .rev()
@@ -7955,10 +9359,13 @@ description: local demo
             _ => false,
         }));
 
-        let cron_path = runtime.paths.session_file(&cron_session_key);
+        let cron_history = runtime
+            .session_store
+            .load(&cron_session_key)
+            .expect("load cron session history");
         assert!(
-            !cron_path.exists(),
-            "cron job session file should not be created"
+            cron_history.is_empty(),
+            "cron job session should not be created"
         );
     }
 
@@ -8200,4 +9607,26 @@ description: local demo
         assert_eq!(json["content"], "第15条内容已经整理完成");
         assert_eq!(json["background_delivery"], true);
     }
+
+    #[test]
+    fn test_with_artifacts_merges_into_reply_metadata() {
+        let artifacts = compact_artifacts(&[blockcell_tools::ToolArtifact {
+            name: "q3.xlsx".to_string(),
+            path: "/ws/q3.xlsx".to_string(),
+            kind: "document".to_string(),
+            mime: "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet".to_string(),
+            size: 42,
+            description: Some("Q3 numbers".to_string()),
+        }]);
+        assert!(artifacts[0].get("description").is_none());
+
+        let merged = with_artifacts(serde_json::Value::Null, &artifacts);
+        assert_eq!(merged["artifacts"][0]["name"], "q3.xlsx");
+        let merged = with_artifacts(serde_json::json!({ "reply_to_message_id": 7 }), &artifacts);
+        assert_eq!(merged["reply_to_message_id"], 7);
+        assert_eq!(merged["artifacts"][0]["size"], 42);
+
+        let untouched = with_artifacts(serde_json::Value::Null, &compact_artifacts(&[]));
+        assert!(untouched.is_null());
+    }
 }
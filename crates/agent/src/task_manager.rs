@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use blockcell_core::system_event::{DeliveryPolicy, EventPriority, SystemEvent};
-use blockcell_tools::{EventEmitterHandle, TaskManagerOps};
+use blockcell_tools::{EventEmitterHandle, TaskManagerOps, ToolArtifact};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -58,6 +58,9 @@ pub struct TaskInfo {
     pub origin_chat_id: String,
     /// Agent that owns this task. Missing values are treated as the default agent.
     pub agent_id: Option<String>,
+    /// Files registered by tools while this task ran.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<ToolArtifact>,
     #[serde(default)]
     emit_system_events: bool,
 }
@@ -193,6 +196,7 @@ impl TaskManager {
             origin_channel: origin_channel.to_string(),
             origin_chat_id: origin_chat_id.to_string(),
             agent_id: agent_id.map(str::to_string),
+            artifacts: Vec::new(),
             emit_system_events,
         };
         {
@@ -279,6 +283,23 @@ impl TaskManager {
         }
     }
 
+    /// Record a file produced during the task. Re-registering the same path replaces the entry.
+    pub async fn add_artifact(&self, task_id: &str, artifact: ToolArtifact) -> bool {
+        let mut tasks = self.tasks.lock().await;
+        let Some(task) = tasks.get_mut(task_id) else {
+            return false;
+        };
+        task.artifacts.retain(|a| a.path != artifact.path);
+        task.artifacts.push(artifact);
+        true
+    }
+
+    /// Artifacts registered for a task, or `None` if the task is unknown.
+    pub async fn artifacts(&self, task_id: &str) -> Option<Vec<ToolArtifact>> {
+        let tasks = self.tasks.lock().await;
+        tasks.get(task_id).map(|t| t.artifacts.clone())
+    }
+
     /// Get info for a specific task.
     pub async fn get_task(&self, task_id: &str) -> Option<TaskInfo> {
         let tasks = self.tasks.lock().await;
@@ -299,7 +320,7 @@ impl TaskManager {
             })
             .cloned()
             .collect();
-        result.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        result.sort_by_key(|b| std::cmp::Reverse(b.created_at));
         result
     }
 
@@ -404,6 +425,7 @@ impl TaskManagerOps for TaskManager {
                 "origin_channel": t.origin_channel,
                 "origin_chat_id": t.origin_chat_id,
                 "agent_id": t.agent_id,
+                "artifacts": t.artifacts,
             })
         })
     }
@@ -545,4 +567,42 @@ mod tests {
         let tasks = manager.list_tasks(None).await;
         assert!(tasks.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_task_manager_tracks_artifacts() {
+        let manager = TaskManager::new();
+        manager
+            .create_task(
+                "task-1",
+                "demo",
+                "make a report",
+                "ws",
+                "chat-1",
+                None,
+                false,
+            )
+            .await;
+        let artifact = ToolArtifact {
+            name: "q3.docx".to_string(),
+            path: "/tmp/ws/q3.docx".to_string(),
+            kind: "document".to_string(),
+            mime: "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+                .to_string(),
+            size: 10,
+            description: None,
+        };
+
+        assert!(manager.add_artifact("task-1", artifact.clone()).await);
+        let updated = ToolArtifact {
+            size: 20,
+            ..artifact.clone()
+        };
+        assert!(manager.add_artifact("task-1", updated).await);
+        assert!(!manager.add_artifact("missing", artifact).await);
+
+        let artifacts = manager.artifacts("task-1").await.unwrap();
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0].size, 20);
+        assert!(manager.artifacts("missing").await.is_none());
+    }
 }
@@ -0,0 +1,275 @@
+//! Per-turn tool schema pruning (`agents.defaults.toolSelection`).
+//!
+//! Tools in scope are ranked by how likely they are to be needed this turn: pinned
+//! tools always stay, then tools used recently in the session, tools the intent
+//! classifier routed to, and tools whose name or description shares words with the
+//! message. Only the top `maxToolsPerCall` schemas are sent. Pruned tools remain
+//! callable by name; such a call counts as a selection miss in the metrics.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+use blockcell_core::types::ChatMessage;
+use serde::Serialize;
+
+const RECENT_WEIGHT: f64 = 3.0;
+const INTENT_WEIGHT: f64 = 2.0;
+const CORE_WEIGHT: f64 = 1.0;
+const KEYWORD_WEIGHT: f64 = 0.75;
+const MAX_KEYWORD_HITS: usize = 4;
+
+/// What the ranking knows about one turn.
+pub struct SelectionInput<'a> {
+    pub query: &'a str,
+    /// Always sent: configured list, active skill tools, runtime-required tools.
+    pub pinned: &'a HashSet<String>,
+    /// Tools the intent classifier routed this turn to.
+    pub intent_tools: &'a HashSet<String>,
+    pub core_tools: &'a HashSet<String>,
+    /// Recently called tools, most recent first.
+    pub recent: &'a [String],
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolSelection {
+    pub selected: Vec<String>,
+    pub pruned: Vec<String>,
+}
+
+/// Tool names called in the last `window` history messages, most recent first.
+pub fn recent_tool_calls(history: &[ChatMessage], window: usize) -> Vec<String> {
+    let mut recent = Vec::new();
+    for message in history.iter().rev().take(window) {
+        for call in message.tool_calls.iter().flatten() {
+            if !recent.contains(&call.name) {
+                recent.push(call.name.clone());
+            }
+        }
+    }
+    recent
+}
+
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 3)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn score(
+    name: &str,
+    description: &str,
+    input: &SelectionInput<'_>,
+    query: &HashSet<String>,
+) -> f64 {
+    let mut score = 0.0;
+    if let Some(pos) = input.recent.iter().position(|r| r == name) {
+        // Fade with age so the last few tools outrank older ones.
+        score += RECENT_WEIGHT / (1.0 + pos as f64 * 0.25);
+    }
+    if input.intent_tools.contains(name) {
+        score += INTENT_WEIGHT;
+    }
+    if input.core_tools.contains(name) {
+        score += CORE_WEIGHT;
+    }
+    let mut tool_words = words(&name.replace('_', " "));
+    tool_words.extend(words(description));
+    let hits = query
+        .intersection(&tool_words)
+        .count()
+        .min(MAX_KEYWORD_HITS);
+    score + hits as f64 * KEYWORD_WEIGHT
+}
+
+/// Keep at most `max` of `candidates` (0 = keep all). Pinned tools are always kept,
+/// even beyond `max`. `describe` returns a tool's description.
+pub fn select_tools(
+    candidates: &[String],
+    max: usize,
+    input: &SelectionInput<'_>,
+    describe: impl Fn(&str) -> Option<String>,
+) -> ToolSelection {
+    if max == 0 || candidates.len() <= max {
+        return ToolSelection {
+            selected: candidates.to_vec(),
+            pruned: Vec::new(),
+        };
+    }
+
+    let query = words(input.query);
+    let (pinned, rest): (Vec<&String>, Vec<&String>) = candidates
+        .iter()
+        .partition(|name| input.pinned.contains(*name));
+    let mut ranked: Vec<(f64, &String)> = rest
+        .into_iter()
+        .map(|name| {
+            let description = describe(name).unwrap_or_default();
+            (score(name, &description, input, &query), name)
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+
+    let room = max.saturating_sub(pinned.len());
+    let mut selected: Vec<String> = pinned.into_iter().cloned().collect();
+    let mut pruned = Vec::new();
+    for (i, (_, name)) in ranked.into_iter().enumerate() {
+        if i < room {
+            selected.push(name.clone());
+        } else {
+            pruned.push(name.clone());
+        }
+    }
+    selected.sort();
+    pruned.sort();
+    ToolSelection { selected, pruned }
+}
+
+/// Process-wide selection counters, shown by `/session-metrics --tools`.
+#[derive(Debug, Default)]
+pub struct ToolSelectionMetrics {
+    pruned_turns: AtomicU64,
+    schemas_sent: AtomicU64,
+    schemas_pruned: AtomicU64,
+    calls_selected: AtomicU64,
+    calls_pruned: AtomicU64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolSelectionSummary {
+    pub pruned_turns: u64,
+    pub schemas_sent: u64,
+    pub schemas_pruned: u64,
+    /// Tool calls to a tool whose schema was sent.
+    pub calls_selected: u64,
+    /// Tool calls to a tool that had been pruned.
+    pub calls_pruned: u64,
+    /// Share of tool calls that hit the selection; 1.0 when there were none.
+    pub accuracy: f64,
+}
+
+static METRICS: OnceLock<ToolSelectionMetrics> = OnceLock::new();
+
+pub fn metrics() -> &'static ToolSelectionMetrics {
+    METRICS.get_or_init(ToolSelectionMetrics::default)
+}
+
+impl ToolSelectionMetrics {
+    pub fn record_turn(&self, selection: &ToolSelection) {
+        self.pruned_turns.fetch_add(1, Ordering::Relaxed);
+        self.schemas_sent
+            .fetch_add(selection.selected.len() as u64, Ordering::Relaxed);
+        self.schemas_pruned
+            .fetch_add(selection.pruned.len() as u64, Ordering::Relaxed);
+    }
+
+    pub fn record_call(&self, was_selected: bool) {
+        let counter = if was_selected {
+            &self.calls_selected
+        } else {
+            &self.calls_pruned
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn summary(&self) -> ToolSelectionSummary {
+        let calls_selected = self.calls_selected.load(Ordering::Relaxed);
+        let calls_pruned = self.calls_pruned.load(Ordering::Relaxed);
+        let calls = calls_selected + calls_pruned;
+        ToolSelectionSummary {
+            pruned_turns: self.pruned_turns.load(Ordering::Relaxed),
+            schemas_sent: self.schemas_sent.load(Ordering::Relaxed),
+            schemas_pruned: self.schemas_pruned.load(Ordering::Relaxed),
+            calls_selected,
+            calls_pruned,
+            accuracy: if calls == 0 {
+                1.0
+            } else {
+                calls_selected as f64 / calls as f64
+            },
+        }
+    }
+
+    pub fn reset(&self) {
+        for counter in [
+            &self.pruned_turns,
+            &self.schemas_sent,
+            &self.schemas_pruned,
+            &self.calls_selected,
+            &self.calls_pruned,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(names: &[&str]) -> HashSet<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn test_select_tools_ranks_pinned_recent_intent_and_keywords() {
+        let candidates: Vec<String> = [
+            "calendar",
+            "email",
+            "exec",
+            "read_file",
+            "weather",
+            "web_search",
+        ]
+        .iter()
+        .map(|n| n.to_string())
+        .collect();
+        let pinned = set(&["exec"]);
+        let intent_tools = set(&["web_search"]);
+        let core_tools = set(&[]);
+        let recent = vec!["read_file".to_string()];
+        let input = SelectionInput {
+            query: "What's the weather forecast in Berlin?",
+            pinned: &pinned,
+            intent_tools: &intent_tools,
+            core_tools: &core_tools,
+            recent: &recent,
+        };
+        let selection = select_tools(&candidates, 4, &input, |name| {
+            (name == "weather").then(|| "Current weather and forecast".to_string())
+        });
+        assert_eq!(
+            selection.selected,
+            vec!["exec", "read_file", "weather", "web_search"]
+        );
+        assert_eq!(selection.pruned, vec!["calendar", "email"]);
+
+        // No limit, or fewer candidates than the limit: nothing is pruned.
+        assert!(select_tools(&candidates, 0, &input, |_| None)
+            .pruned
+            .is_empty());
+        assert!(select_tools(&candidates, 10, &input, |_| None)
+            .pruned
+            .is_empty());
+    }
+
+    #[test]
+    fn test_recent_tool_calls_newest_first() {
+        let call = |name: &str| blockcell_core::types::ToolCallRequest {
+            id: name.to_string(),
+            name: name.to_string(),
+            arguments: serde_json::json!({}),
+            thought_signature: None,
+        };
+        let mut first = ChatMessage::assistant("");
+        first.tool_calls = Some(vec![call("email")]);
+        let mut second = ChatMessage::assistant("");
+        second.tool_calls = Some(vec![call("calendar"), call("email")]);
+        let history = vec![first, ChatMessage::user("next"), second];
+
+        assert_eq!(recent_tool_calls(&history, 10), vec!["calendar", "email"]);
+        assert_eq!(recent_tool_calls(&history, 1), vec!["calendar", "email"]);
+        assert!(recent_tool_calls(&history, 0).is_empty());
+    }
+}
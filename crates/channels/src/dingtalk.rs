@@ -1,4 +1,5 @@
 use crate::account::dingtalk_account_id;
+use crate::split::split_message_chars;
 use blockcell_core::{Config, Error, InboundMessage, Result};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -960,7 +961,7 @@ pub async fn send_message(config: &Config, chat_id: &str, text: &str) -> Result<
         let app_key = &config.channels.dingtalk.app_key;
         let app_secret = &config.channels.dingtalk.app_secret;
         let token = fetch_access_token(&client, app_key, app_secret).await?;
-        let chunks = split_message(text, DINGTALK_MSG_LIMIT);
+        let chunks = split_message_chars(text, DINGTALK_MSG_LIMIT);
         for (i, chunk) in chunks.iter().enumerate() {
             do_send_message(&client, &token, chat_id, chunk).await?;
             if i + 1 < chunks.len() {
@@ -1067,7 +1068,7 @@ pub async fn send_message_to_user(config: &Config, user_id: &str, text: &str) ->
 
     let token = fetch_access_token(&client, app_key, app_secret).await?;
 
-    let chunks = split_message(text, DINGTALK_MSG_LIMIT);
+    let chunks = split_message_chars(text, DINGTALK_MSG_LIMIT);
     for (i, chunk) in chunks.iter().enumerate() {
         #[derive(Serialize)]
         struct OrgMsgRequest<'a> {
@@ -1113,40 +1114,13 @@ pub async fn send_message_to_user(config: &Config, user_id: &str, text: &str) ->
     Ok(())
 }
 
-fn split_message(text: &str, max_len: usize) -> Vec<String> {
-    if text.chars().count() <= max_len {
-        return vec![text.to_string()];
-    }
-    let mut chunks = Vec::new();
-    let mut remaining = text;
-    while !remaining.is_empty() {
-        if remaining.chars().count() <= max_len {
-            chunks.push(remaining.to_string());
-            break;
-        }
-        // Find a safe byte boundary at max_len chars
-        let byte_limit = remaining
-            .char_indices()
-            .nth(max_len)
-            .map(|(i, _)| i)
-            .unwrap_or(remaining.len());
-        let split_at = remaining[..byte_limit]
-            .rfind('\n')
-            .map(|i| i + 1)
-            .unwrap_or(byte_limit);
-        chunks.push(remaining[..split_at].to_string());
-        remaining = &remaining[split_at..];
-    }
-    chunks
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_split_message_short() {
-        let chunks = split_message("hello world", 4096);
+        let chunks = split_message_chars("hello world", 4096);
         assert_eq!(chunks.len(), 1);
         assert_eq!(chunks[0], "hello world");
     }
@@ -1155,7 +1129,7 @@ mod tests {
     fn test_split_message_long() {
         let line = "a".repeat(100);
         let text = (0..50).map(|_| line.clone()).collect::<Vec<_>>().join("\n");
-        let chunks = split_message(&text, 4096);
+        let chunks = split_message_chars(&text, 4096);
         assert!(chunks.len() > 1);
         for chunk in &chunks {
             assert!(chunk.chars().count() <= 4096);
@@ -1166,7 +1140,7 @@ mod tests {
     fn test_split_message_chinese() {
         // Each Chinese char is 3 bytes; 5000 chars = 15000 bytes
         let text = "钉钉".repeat(2500);
-        let chunks = split_message(&text, 4096);
+        let chunks = split_message_chars(&text, 4096);
         assert!(chunks.len() > 1);
         for chunk in &chunks {
             assert!(
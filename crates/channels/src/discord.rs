@@ -1,4 +1,5 @@
 use crate::account::discord_account_id;
+use crate::split::split_message;
 use blockcell_core::{Config, Error, InboundMessage, Result};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -242,7 +243,11 @@ impl DiscordChannel {
 
         // Send Identify
         // Intents: GUILDS (1<<0) | GUILD_MESSAGES (1<<9) | MESSAGE_CONTENT (1<<15) | DIRECT_MESSAGES (1<<12)
-        let intents: u64 = (1 << 0) | (1 << 9) | (1 << 12) | (1 << 15);
+        let mut intents: u64 = (1 << 0) | (1 << 9) | (1 << 12) | (1 << 15);
+        if self.config.channels.reactions.enabled {
+            // GUILD_MESSAGE_REACTIONS (1<<10) | DIRECT_MESSAGE_REACTIONS (1<<13)
+            intents |= (1 << 10) | (1 << 13);
+        }
         let identify = GatewayIdentify {
             op: GATEWAY_IDENTIFY,
             d: IdentifyData {
@@ -315,6 +320,12 @@ impl DiscordChannel {
                                                         error!(error = %e, "Failed to handle Discord message");
                                                     }
                                                 }
+                                            } else if event_type == "MESSAGE_REACTION_ADD" {
+                                                if let Some(d) = payload.d {
+                                                    if let Err(e) = self.handle_reaction_add(d).await {
+                                                        error!(error = %e, "Failed to handle Discord reaction");
+                                                    }
+                                                }
                                             }
                                         }
                                     }
@@ -405,6 +416,41 @@ impl DiscordChannel {
         Ok(path.to_string_lossy().to_string())
     }
 
+    async fn handle_reaction_add(&self, data: serde_json::Value) -> Result<()> {
+        let field = |key: &str| data.get(key).and_then(|v| v.as_str()).unwrap_or("");
+        let (user_id, channel_id, message_id) =
+            (field("user_id"), field("channel_id"), field("message_id"));
+        let Some(emoji) = data
+            .get("emoji")
+            .and_then(|e| e.get("name"))
+            .and_then(|v| v.as_str())
+        else {
+            return Ok(());
+        };
+        let is_bot = data
+            .pointer("/member/user/bot")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if is_bot || user_id.is_empty() || !self.is_allowed(user_id) {
+            return Ok(());
+        }
+        if !self.is_monitored_channel(channel_id) {
+            return Ok(());
+        }
+        let inbound = crate::reactions::reaction_message(
+            "discord",
+            discord_account_id(&self.config),
+            user_id,
+            channel_id,
+            emoji,
+            message_id,
+        );
+        self.inbound_tx
+            .send(inbound)
+            .await
+            .map_err(|e| Error::Channel(e.to_string()))
+    }
+
     async fn handle_message_create(&self, data: serde_json::Value) -> Result<()> {
         let msg: DiscordMessage = serde_json::from_value(data)
             .map_err(|e| Error::Channel(format!("Failed to parse Discord message: {}", e)))?;
@@ -576,34 +622,6 @@ pub async fn send_message_reply(
     Ok(())
 }
 
-/// Split a message into chunks at newline boundaries, respecting a max length.
-fn split_message(text: &str, max_len: usize) -> Vec<String> {
-    if text.len() <= max_len {
-        return vec![text.to_string()];
-    }
-
-    let mut chunks = Vec::new();
-    let mut remaining = text;
-
-    while !remaining.is_empty() {
-        if remaining.len() <= max_len {
-            chunks.push(remaining.to_string());
-            break;
-        }
-
-        // Try to split at a newline within the limit
-        let split_at = remaining[..max_len]
-            .rfind('\n')
-            .map(|i| i + 1)
-            .unwrap_or(max_len);
-
-        chunks.push(remaining[..split_at].to_string());
-        remaining = &remaining[split_at..];
-    }
-
-    chunks
-}
-
 /// Send a media file as an attachment to a Discord channel.
 /// Discord supports any file type as an attachment via multipart form upload.
 pub async fn send_media_message(config: &Config, chat_id: &str, file_path: &str) -> Result<()> {
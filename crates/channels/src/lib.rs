@@ -1,6 +1,8 @@
 pub mod account;
 pub mod manager;
 pub mod rate_limit;
+pub mod reactions;
+pub mod split;
 
 #[cfg(feature = "telegram")]
 pub mod telegram;
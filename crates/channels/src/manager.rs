@@ -1,12 +1,14 @@
+use crate::split;
+use blockcell_core::file_share::{share_url, NewShare, ShareStore};
 use blockcell_core::{Config, Error, InboundMessage, OutboundMessage, Paths, Result};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::mpsc;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 pub struct ChannelManager {
     config: Config,
-    #[allow(dead_code)]
     paths: Paths,
     #[allow(dead_code)]
     inbound_tx: mpsc::Sender<InboundMessage>,
@@ -317,12 +319,132 @@ impl ChannelManager {
         info!("Outbound dispatcher stopped");
     }
 
+    /// Save an oversized reply under `workspace/outbox/`; returns the workspace-relative
+    /// and absolute paths.
+    fn write_overflow(&self, content: &str) -> Result<(String, PathBuf)> {
+        let dir = self.paths.workspace().join("outbox");
+        std::fs::create_dir_all(&dir)?;
+        let name = format!("reply-{}.md", chrono::Utc::now().format("%Y%m%d-%H%M%S%3f"));
+        let path = dir.join(&name);
+        std::fs::write(&path, content)?;
+        Ok((format!("outbox/{}", name), path))
+    }
+
+    /// Share link for an overflow file. Only created when `gateway.publicApiBase` is
+    /// set, since a localhost link is useless to a chat recipient.
+    fn share_overflow(&self, rel_path: &str) -> Option<String> {
+        let gateway = &self.config.gateway;
+        gateway
+            .public_api_base
+            .as_deref()
+            .filter(|base| !base.trim().is_empty())?;
+        let ttl = self
+            .config
+            .channels
+            .outbound
+            .link_ttl_secs
+            .min(gateway.share_max_ttl_secs);
+        let result = ShareStore::open(&self.paths).and_then(|mut store| {
+            let link = store.create(
+                rel_path,
+                NewShare {
+                    ttl_secs: Some(ttl),
+                    note: Some("oversized reply"),
+                    ..Default::default()
+                },
+                gateway.share_max_ttl_secs,
+            )?;
+            store.save()?;
+            Ok(link)
+        });
+        match result {
+            Ok(link) => Some(share_url(gateway, &link.token)),
+            Err(e) => {
+                warn!(error = %e, "Failed to create share link for oversized reply");
+                None
+            }
+        }
+    }
+
+    /// Fit a reply to its channel: downgrade Markdown the channel cannot render, and
+    /// turn replies that would need more than `channels.outbound.maxParts` messages into
+    /// an excerpt plus a link to (or attachment of) the full text.
+    fn adapt_outbound(&self, msg: &OutboundMessage) -> OutboundMessage {
+        let cfg = &self.config.channels.outbound;
+        let mut out = msg.clone();
+        if msg.content.is_empty() {
+            return out;
+        }
+        if cfg.downgrade_formatting {
+            out.content = split::downgrade_formatting(&msg.channel, &msg.content);
+        }
+        let (Some(limit), Some(parts)) = (
+            split::channel_limit(&msg.channel),
+            split::split_for_channel(&msg.channel, &out.content),
+        ) else {
+            return out;
+        };
+        if cfg.max_parts == 0 || parts.len() <= cfg.max_parts {
+            return out;
+        }
+
+        let (rel_path, path) = match self.write_overflow(&msg.content) {
+            Ok(saved) => saved,
+            Err(e) => {
+                warn!(error = %e, channel = %msg.channel, "Failed to save oversized reply; sending it split");
+                return out;
+            }
+        };
+        let excerpt = split::split_message(&out.content, limit / 2)
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        let total = msg.content.chars().count();
+        out.content = match self.share_overflow(&rel_path) {
+            Some(url) => format!(
+                "{}\n\n… ({} characters in total)\n📄 Full reply: {}",
+                excerpt.trim_end(),
+                total,
+                url
+            ),
+            None => {
+                out.media.push(path.to_string_lossy().to_string());
+                format!(
+                    "{}\n\n… ({} characters in total)\n📄 Full reply attached as {}",
+                    excerpt.trim_end(),
+                    total,
+                    rel_path
+                )
+            }
+        };
+        info!(channel = %msg.channel, parts = parts.len(), file = %rel_path, "Oversized reply moved to a file");
+        out
+    }
+
     pub async fn dispatch_outbound_msg(&self, msg: &OutboundMessage) -> Result<()> {
+        let adapted = self.adapt_outbound(msg);
+        let msg = &adapted;
         let send_config = self.config_for_outbound(msg)?;
         match msg.channel.as_str() {
             "telegram" => {
                 #[cfg(feature = "telegram")]
                 {
+                    if let Some(query_id) =
+                        msg.metadata.get("inline_query_id").and_then(|v| v.as_str())
+                    {
+                        let title = msg
+                            .metadata
+                            .get("inline_title")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("Answer");
+                        return crate::telegram::answer_inline_query(
+                            &send_config,
+                            query_id,
+                            Some(title),
+                            &msg.content,
+                        )
+                        .await;
+                    }
                     if !msg.media.is_empty() {
                         for file_path in &msg.media {
                             if let Err(e) = crate::telegram::send_media_message(
@@ -336,6 +458,14 @@ impl ChannelManager {
                             }
                         }
                     }
+                    if msg.metadata.get("confirm_request").is_some() {
+                        return crate::telegram::send_confirm_prompt(
+                            &send_config,
+                            &msg.chat_id,
+                            &msg.content,
+                        )
+                        .await;
+                    }
                     if !msg.content.is_empty() {
                         let reply_to = msg
                             .metadata
@@ -611,11 +741,11 @@ impl ChannelManager {
                     }
                 }
             }
-            "cli" | "cron" | "ws" => {
+            "cli" | "cron" | "ws" | "webhook" => {
                 // Internal channels — handled directly, not through external channel dispatch
             }
             _ => {
-                tracing::warn!(channel = %msg.channel, "Unknown channel for outbound message");
+                warn!(channel = %msg.channel, "Unknown channel for outbound message");
             }
         }
         Ok(())
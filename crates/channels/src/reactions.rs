@@ -0,0 +1,126 @@
+//! Emoji reactions as quick commands.
+//!
+//! Channels that receive reaction events turn them into an [`InboundMessage`] whose
+//! content is the emoji and whose metadata carries `"reaction"`. The gateway resolves
+//! the emoji against `channels.reactions.actions`.
+
+use blockcell_core::config::{ReactionAction, ReactionCommandsConfig};
+use blockcell_core::InboundMessage;
+
+/// Slack reports reactions by name; names of the emojis mapped by default.
+const SLACK_NAMES: &[(&str, &str)] = &[
+    ("repeat", "🔁"),
+    ("pushpin", "📌"),
+    ("white_check_mark", "✅"),
+    ("wastebasket", "🗑"),
+];
+
+/// Unicode emoji for a Slack reaction name, or `:name:` for the rest.
+pub fn slack_emoji(name: &str) -> String {
+    SLACK_NAMES
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, emoji)| emoji.to_string())
+        .unwrap_or_else(|| format!(":{}:", name))
+}
+
+/// Drop variation selectors so "🗑️" and "🗑" match.
+fn normalize(emoji: &str) -> String {
+    emoji.trim().chars().filter(|c| *c != '\u{fe0f}').collect()
+}
+
+pub fn reaction_message(
+    channel: &str,
+    account_id: Option<String>,
+    sender_id: &str,
+    chat_id: &str,
+    emoji: &str,
+    message_id: &str,
+) -> InboundMessage {
+    InboundMessage {
+        channel: channel.to_string(),
+        account_id,
+        sender_id: sender_id.to_string(),
+        chat_id: chat_id.to_string(),
+        content: emoji.to_string(),
+        media: vec![],
+        metadata: serde_json::json!({
+            "reaction": { "emoji": emoji, "message_id": message_id },
+        }),
+        timestamp_ms: chrono::Utc::now().timestamp_millis(),
+    }
+}
+
+/// The emoji of a reaction event, `None` for ordinary messages.
+pub fn reaction_emoji(msg: &InboundMessage) -> Option<&str> {
+    msg.metadata.get("reaction")?.get("emoji")?.as_str()
+}
+
+/// The action mapped to `emoji`, if reactions are enabled.
+pub fn resolve(config: &ReactionCommandsConfig, emoji: &str) -> Option<ReactionAction> {
+    if !config.enabled {
+        return None;
+    }
+    let wanted = normalize(emoji);
+    config
+        .actions
+        .iter()
+        .find(|(key, _)| normalize(key) == wanted)
+        .map(|(_, action)| *action)
+}
+
+/// The instruction an action is turned into for the agent. `Approve` is handled by the
+/// gateway directly.
+pub fn action_prompt(action: ReactionAction) -> Option<&'static str> {
+    match action {
+        ReactionAction::Regenerate => Some(
+            "[Reaction 🔁] Please answer my previous message again with a fresh response.",
+        ),
+        ReactionAction::SaveMemory => {
+            Some("[Reaction 📌] Save the key information from your last reply to memory.")
+        }
+        ReactionAction::DeleteReminder => Some(
+            "[Reaction 🗑] Delete the reminder or scheduled job set up in your last reply, then confirm briefly.",
+        ),
+        ReactionAction::Approve => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_normalizes_variation_selectors() {
+        let mut config = ReactionCommandsConfig::default();
+        assert_eq!(resolve(&config, "🔁"), None);
+
+        config.enabled = true;
+        assert_eq!(resolve(&config, "🗑️"), Some(ReactionAction::DeleteReminder));
+        assert_eq!(resolve(&config, "✅"), Some(ReactionAction::Approve));
+        assert_eq!(resolve(&config, "👍"), None);
+
+        config
+            .actions
+            .insert(":thumbsup:".to_string(), ReactionAction::Approve);
+        assert_eq!(
+            resolve(&config, &slack_emoji("thumbsup")),
+            Some(ReactionAction::Approve)
+        );
+        assert_eq!(
+            resolve(&config, &slack_emoji("pushpin")),
+            Some(ReactionAction::SaveMemory)
+        );
+    }
+
+    #[test]
+    fn test_reaction_message_round_trip() {
+        let msg = reaction_message("telegram", None, "42", "1001", "📌", "77");
+        assert_eq!(reaction_emoji(&msg), Some("📌"));
+        assert_eq!(msg.metadata["reaction"]["message_id"], "77");
+
+        let plain = InboundMessage::cli("hello");
+        assert_eq!(reaction_emoji(&plain), None);
+        assert!(action_prompt(ReactionAction::Approve).is_none());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::account::slack_account_id;
+use crate::split::split_message_chars;
 use blockcell_core::{Config, Error, InboundMessage, Result};
 use futures::{SinkExt, StreamExt};
 use reqwest::Client;
@@ -190,8 +191,10 @@ impl SlackChannel {
             Some(e) => e,
             None => return Ok(()),
         };
-        if event.get("type").and_then(|v| v.as_str()).unwrap_or("") != "message" {
-            return Ok(());
+        match event.get("type").and_then(|v| v.as_str()).unwrap_or("") {
+            "message" => {}
+            "reaction_added" => return self.handle_reaction_added(event).await,
+            _ => return Ok(()),
         }
         if event.get("bot_id").is_some() {
             return Ok(());
@@ -275,6 +278,46 @@ impl SlackChannel {
             .map_err(|e| Error::Channel(e.to_string()))
     }
 
+    async fn handle_reaction_added(&self, event: &serde_json::Value) -> Result<()> {
+        if !self.config.channels.reactions.enabled {
+            return Ok(());
+        }
+        let user = event.get("user").and_then(|v| v.as_str()).unwrap_or("");
+        let name = event.get("reaction").and_then(|v| v.as_str()).unwrap_or("");
+        let item = event.get("item");
+        let channel_id = item
+            .and_then(|i| i.get("channel"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+        let ts = item
+            .and_then(|i| i.get("ts"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+        if user.is_empty() || name.is_empty() || channel_id.is_empty() {
+            return Ok(());
+        }
+        if !self.is_allowed(user) {
+            debug!(user = %user, "Slack: reaction from user not in allowlist");
+            return Ok(());
+        }
+        let monitored = &self.config.channels.slack.channels;
+        if !monitored.is_empty() && !monitored.iter().any(|c| c == channel_id) {
+            return Ok(());
+        }
+        let inbound = crate::reactions::reaction_message(
+            "slack",
+            slack_account_id(&self.config),
+            user,
+            channel_id,
+            &crate::reactions::slack_emoji(name),
+            ts,
+        );
+        self.inbound_tx
+            .send(inbound)
+            .await
+            .map_err(|e| Error::Channel(e.to_string()))
+    }
+
     /// Download a Slack file using the private URL (requires bot token auth).
     async fn download_slack_file(&self, url: &str, file_name: &str) -> Result<String> {
         let token = &self.config.channels.slack.bot_token;
@@ -489,7 +532,7 @@ pub async fn send_message_threaded(
     let client = shared_client();
     let token = &config.channels.slack.bot_token;
 
-    let chunks = split_message(text, SLACK_MSG_LIMIT);
+    let chunks = split_message_chars(text, SLACK_MSG_LIMIT);
     for (i, chunk) in chunks.iter().enumerate() {
         let mut body = serde_json::json!({
             "channel": chat_id,
@@ -525,34 +568,6 @@ pub async fn send_message_threaded(
     Ok(())
 }
 
-/// Split text into chunks at newline boundaries, each at most `max_len` chars.
-fn split_message(text: &str, max_len: usize) -> Vec<String> {
-    if text.chars().count() <= max_len {
-        return vec![text.to_string()];
-    }
-    let mut chunks = Vec::new();
-    let mut remaining = text;
-    while !remaining.is_empty() {
-        if remaining.chars().count() <= max_len {
-            chunks.push(remaining.to_string());
-            break;
-        }
-        // Find a safe byte boundary at max_len chars
-        let byte_limit = remaining
-            .char_indices()
-            .nth(max_len)
-            .map(|(i, _)| i)
-            .unwrap_or(remaining.len());
-        let split_at = remaining[..byte_limit]
-            .rfind('\n')
-            .map(|i| i + 1)
-            .unwrap_or(byte_limit);
-        chunks.push(remaining[..split_at].to_string());
-        remaining = &remaining[split_at..];
-    }
-    chunks
-}
-
 /// Upload a file to Slack using the v2 upload API and share it to a channel.
 /// Flow: getUploadURLExternal → PUT bytes → completeUploadExternal
 pub async fn send_media_message(config: &Config, chat_id: &str, file_path: &str) -> Result<()> {
@@ -704,7 +719,7 @@ mod tests {
 
     #[test]
     fn test_split_message_short() {
-        let chunks = split_message("hello world", 4000);
+        let chunks = split_message_chars("hello world", 4000);
         assert_eq!(chunks.len(), 1);
         assert_eq!(chunks[0], "hello world");
     }
@@ -713,7 +728,7 @@ mod tests {
     fn test_split_message_long() {
         let line = "a".repeat(100);
         let text = (0..50).map(|_| line.clone()).collect::<Vec<_>>().join("\n");
-        let chunks = split_message(&text, 4000);
+        let chunks = split_message_chars(&text, 4000);
         assert!(chunks.len() > 1);
         for chunk in &chunks {
             assert!(chunk.chars().count() <= 4000);
@@ -724,7 +739,7 @@ mod tests {
     fn test_split_message_chinese() {
         // Each Chinese char is 3 bytes; 5000 chars = 15000 bytes
         let text = "Slack消息".repeat(1000);
-        let chunks = split_message(&text, 4000);
+        let chunks = split_message_chars(&text, 4000);
         assert!(chunks.len() > 1);
         for chunk in &chunks {
             assert!(
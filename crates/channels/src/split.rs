@@ -0,0 +1,342 @@
+//! Splitting and formatting of outbound text for channels with size limits.
+//!
+//! Long replies are split at paragraph breaks first, then at line breaks, and only
+//! mid-line as a last resort. A fenced code block that has to be split is closed at the
+//! end of one part and reopened (with its language tag) at the start of the next, so
+//! every part renders on its own.
+
+const FENCE: &str = "```";
+/// Room kept for the "\n```" that closes a code block cut in the middle.
+const FENCE_CLOSE_RESERVE: usize = 4;
+
+#[derive(Clone, Copy)]
+enum Unit {
+    Bytes,
+    Chars,
+}
+
+impl Unit {
+    fn len(self, s: &str) -> usize {
+        match self {
+            Unit::Bytes => s.len(),
+            Unit::Chars => s.chars().count(),
+        }
+    }
+
+    /// Byte index of the end of the longest prefix of `s` within `limit` units.
+    fn prefix_end(self, s: &str, limit: usize) -> usize {
+        match self {
+            Unit::Bytes => {
+                let mut idx = limit.min(s.len());
+                while idx > 0 && !s.is_char_boundary(idx) {
+                    idx -= 1;
+                }
+                idx
+            }
+            Unit::Chars => s.char_indices().nth(limit).map_or(s.len(), |(i, _)| i),
+        }
+    }
+}
+
+/// Built-in message length limit of a channel, in the unit its `send` path counts.
+pub fn channel_limit(channel: &str) -> Option<usize> {
+    match channel {
+        "telegram" | "dingtalk" => Some(4096),
+        "slack" => Some(4000),
+        "wecom" => Some(2048),
+        "discord" | "weixin" => Some(2000),
+        _ => None,
+    }
+}
+
+/// Split `text` into parts of at most `max_len` bytes.
+pub fn split_message(text: &str, max_len: usize) -> Vec<String> {
+    split_with(text, max_len, Unit::Bytes)
+}
+
+/// Split `text` into parts of at most `max_chars` characters.
+pub fn split_message_chars(text: &str, max_chars: usize) -> Vec<String> {
+    split_with(text, max_chars, Unit::Chars)
+}
+
+/// Split `text` the way `channel`'s send path does; `None` for channels without a limit.
+pub fn split_for_channel(channel: &str, text: &str) -> Option<Vec<String>> {
+    let limit = channel_limit(channel)?;
+    Some(match channel {
+        "slack" | "wecom" | "dingtalk" => split_message_chars(text, limit),
+        _ => split_message(text, limit),
+    })
+}
+
+struct Cut {
+    at: usize,
+    /// Header line of the code block still open at the cut.
+    open_fence: Option<String>,
+}
+
+fn fence_header(line: &str) -> Option<&str> {
+    let trimmed = line.trim();
+    trimmed.starts_with(FENCE).then_some(trimmed)
+}
+
+/// Pick where to end the next part, looking only at the first `window` units.
+fn find_cut(text: &str, window: usize, open_at_start: Option<&str>, unit: Unit) -> Cut {
+    let limit = unit.prefix_end(text, window);
+    let mut open = open_at_start.map(str::to_string);
+    let mut paragraph = None;
+    let mut line_outside = None;
+    let mut line_inside = None;
+    let mut pos = 0;
+
+    for line in text[..limit].split_inclusive('\n') {
+        if !line.ends_with('\n') {
+            break;
+        }
+        pos += line.len();
+        if let Some(header) = fence_header(line) {
+            open = match open {
+                Some(_) => None,
+                None => Some(header.to_string()),
+            };
+        }
+        match &open {
+            None => {
+                line_outside = Some(pos);
+                if line.trim().is_empty() {
+                    paragraph = Some(pos);
+                }
+            }
+            Some(header) => line_inside = Some((pos, header.clone())),
+        }
+    }
+
+    // Avoid tiny parts: a boundary in the first quarter is only used as a fallback.
+    let min = limit / 4;
+    if let Some(at) = paragraph.filter(|at| *at >= min) {
+        return Cut {
+            at,
+            open_fence: None,
+        };
+    }
+    if let Some(at) = line_outside.filter(|at| *at >= min) {
+        return Cut {
+            at,
+            open_fence: None,
+        };
+    }
+    if let Some((at, header)) = line_inside.filter(|(at, _)| *at >= min) {
+        return Cut {
+            at,
+            open_fence: Some(header),
+        };
+    }
+    if let Some(at) = line_outside.filter(|at| *at > 0) {
+        return Cut {
+            at,
+            open_fence: None,
+        };
+    }
+    // Hard cut mid-line; take at least one character.
+    let at = if limit == 0 {
+        text.chars().next().map_or(text.len(), char::len_utf8)
+    } else {
+        limit
+    };
+    Cut {
+        at,
+        open_fence: open,
+    }
+}
+
+fn split_with(text: &str, max_len: usize, unit: Unit) -> Vec<String> {
+    if unit.len(text) <= max_len {
+        return vec![text.to_string()];
+    }
+    let reserve = if text.contains(FENCE) {
+        FENCE_CLOSE_RESERVE
+    } else {
+        0
+    };
+
+    let mut chunks = Vec::new();
+    let mut remaining = text;
+    let mut reopen: Option<String> = None;
+    while !remaining.is_empty() {
+        let prefix = reopen
+            .as_ref()
+            .map(|header| format!("{}\n", header))
+            .unwrap_or_default();
+        if unit.len(&prefix) + unit.len(remaining) <= max_len {
+            chunks.push(prefix + remaining);
+            break;
+        }
+        let window = max_len.saturating_sub(unit.len(&prefix) + reserve).max(1);
+        let cut = find_cut(remaining, window, reopen.as_deref(), unit);
+        let mut chunk = prefix + &remaining[..cut.at];
+        if cut.open_fence.is_some() {
+            if !chunk.ends_with('\n') {
+                chunk.push('\n');
+            }
+            chunk.push_str(FENCE);
+        }
+        chunks.push(chunk);
+        remaining = &remaining[cut.at..];
+        reopen = cut.open_fence;
+    }
+    chunks
+}
+
+/// Replace `[text](url)` links using `render(text, url)`.
+fn rewrite_links(line: &str, render: impl Fn(&str, &str) -> String) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(open) = rest.find('[') {
+        let after = &rest[open + 1..];
+        let link = after.find("](").and_then(|mid| {
+            let url_start = mid + 2;
+            after[url_start..]
+                .find(')')
+                .map(|end| (mid, url_start, url_start + end))
+        });
+        match link {
+            Some((mid, url_start, url_end)) if !after[..mid].contains('[') => {
+                out.push_str(&rest[..open]);
+                out.push_str(&render(&after[..mid], &after[url_start..url_end]));
+                rest = &after[url_end + 1..];
+            }
+            _ => {
+                out.push_str(&rest[..open + 1]);
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn heading_text(line: &str) -> Option<&str> {
+    let hashes = line.len() - line.trim_start_matches('#').len();
+    if (1..=6).contains(&hashes) && line[hashes..].starts_with(' ') {
+        Some(line[hashes..].trim())
+    } else {
+        None
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Flavor {
+    /// Slack mrkdwn: `*bold*`, `~strike~`, `<url|text>`.
+    Slack,
+    /// WhatsApp: `*bold*`, `~strike~`, bare URLs.
+    WhatsApp,
+    /// No markup at all.
+    Plain,
+}
+
+fn downgrade_line(line: &str, flavor: Flavor) -> String {
+    let (line, heading) = match heading_text(line) {
+        Some(text) => (text, true),
+        None => (line, false),
+    };
+    let line = match flavor {
+        Flavor::Slack => rewrite_links(line, |text, url| format!("<{}|{}>", url, text)),
+        Flavor::WhatsApp | Flavor::Plain => rewrite_links(line, |text, url| {
+            if text == url {
+                url.to_string()
+            } else {
+                format!("{} ({})", text, url)
+            }
+        }),
+    };
+    let line = match flavor {
+        Flavor::Slack | Flavor::WhatsApp => line.replace("**", "*").replace("~~", "~"),
+        Flavor::Plain => line.replace("**", "").replace("~~", "").replace('`', ""),
+    };
+    match (heading, flavor) {
+        (true, Flavor::Slack | Flavor::WhatsApp) => format!("*{}*", line.trim_matches('*')),
+        _ => line,
+    }
+}
+
+/// Rewrite Markdown into what `channel` can render. Channels that render Markdown (or
+/// escape it themselves, like Telegram) get the text unchanged. Code blocks are left
+/// alone, except that plain-text channels lose the fence lines.
+pub fn downgrade_formatting(channel: &str, text: &str) -> String {
+    let flavor = match channel {
+        "slack" => Flavor::Slack,
+        "whatsapp" => Flavor::WhatsApp,
+        "weixin" | "qq" | "napcat" => Flavor::Plain,
+        _ => return text.to_string(),
+    };
+    let mut out = String::with_capacity(text.len());
+    let mut in_code = false;
+    for line in text.split_inclusive('\n') {
+        let (body, newline) = match line.strip_suffix('\n') {
+            Some(body) => (body, "\n"),
+            None => (line, ""),
+        };
+        if fence_header(body).is_some() {
+            in_code = !in_code;
+            if !matches!(flavor, Flavor::Plain) {
+                out.push_str(line);
+            }
+            continue;
+        }
+        if in_code {
+            out.push_str(line);
+        } else {
+            out.push_str(&downgrade_line(body, flavor));
+            out.push_str(newline);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_prefers_paragraphs_and_reopens_code_blocks() {
+        let para = "word ".repeat(30);
+        let text = format!("{}\n\n{}\n\n{}", para, para, para);
+        let chunks = split_message(&text, 320);
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].ends_with("\n\n"));
+        assert_eq!(chunks.concat(), text);
+
+        let code = (0..40)
+            .map(|i| format!("let x{} = {};\n", i, i))
+            .collect::<String>();
+        let text = format!("Intro\n\n```rust\n{}```\nDone", code);
+        let chunks = split_message(&text, 200);
+        assert!(chunks.len() > 2);
+        for chunk in &chunks {
+            assert!(chunk.len() <= 200, "chunk too long: {}", chunk.len());
+            assert_eq!(chunk.matches(FENCE).count() % 2, 0, "unbalanced: {}", chunk);
+        }
+        assert!(chunks[1].starts_with("```rust\n"));
+
+        let chunks = split_message_chars(&"中".repeat(3000), 2048);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].chars().count(), 2048);
+    }
+
+    #[test]
+    fn test_downgrade_formatting() {
+        let text = "## Result\nSee **this** [doc](https://x.io) ~~old~~\n```\n**raw**\n```\n";
+        assert_eq!(
+            downgrade_formatting("slack", text),
+            "*Result*\nSee *this* <https://x.io|doc> ~old~\n```\n**raw**\n```\n"
+        );
+        assert_eq!(
+            downgrade_formatting("whatsapp", text),
+            "*Result*\nSee *this* doc (https://x.io) ~old~\n```\n**raw**\n```\n"
+        );
+        assert_eq!(
+            downgrade_formatting("weixin", text),
+            "Result\nSee this doc (https://x.io) old\n**raw**\n"
+        );
+        assert_eq!(downgrade_formatting("discord", text), text);
+    }
+}
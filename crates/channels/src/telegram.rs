@@ -1,10 +1,12 @@
 use crate::account::telegram_account_id;
+use crate::split::split_message;
 use blockcell_core::{Config, Error, InboundMessage, Result};
 use reqwest::Client;
 use reqwest::Proxy;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::io::AsyncWriteExt;
 use tokio::sync::mpsc;
@@ -23,6 +25,54 @@ struct TelegramResponse<T> {
 struct Update {
     update_id: i64,
     message: Option<Message>,
+    message_reaction: Option<MessageReactionUpdated>,
+    inline_query: Option<InlineQuery>,
+    callback_query: Option<CallbackQuery>,
+}
+
+/// A tap on an inline keyboard button.
+#[derive(Debug, Deserialize)]
+struct CallbackQuery {
+    id: String,
+    from: User,
+    message: Option<Message>,
+    data: Option<String>,
+}
+
+/// Callback data of the approval buttons and the command each one stands for.
+const CONFIRM_BUTTONS: &[(&str, &str, &str)] = &[
+    ("✅ Approve", "confirm:approve", "/approve"),
+    ("❌ Deny", "confirm:deny", "/deny"),
+];
+
+fn confirm_command(data: &str) -> Option<&'static str> {
+    CONFIRM_BUTTONS
+        .iter()
+        .find(|(_, callback, _)| *callback == data)
+        .map(|(_, _, command)| *command)
+}
+
+#[derive(Debug, Deserialize)]
+struct InlineQuery {
+    id: String,
+    from: User,
+    query: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageReactionUpdated {
+    chat: Chat,
+    message_id: i64,
+    user: Option<User>,
+    #[serde(default)]
+    old_reaction: Vec<ReactionType>,
+    #[serde(default)]
+    new_reaction: Vec<ReactionType>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReactionType {
+    emoji: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -85,11 +135,17 @@ struct Chat {
     id: i64,
 }
 
+/// Telegram sends an inline query per keystroke; only a query that stays unchanged this
+/// long is answered.
+const INLINE_DEBOUNCE: Duration = Duration::from_millis(800);
+
 pub struct TelegramChannel {
     config: Config,
     client: Client,
     inbound_tx: mpsc::Sender<InboundMessage>,
     media_dir: PathBuf,
+    /// Latest inline query id per user, for debouncing.
+    inline_latest: Arc<Mutex<HashMap<i64, String>>>,
 }
 
 impl TelegramChannel {
@@ -122,6 +178,7 @@ impl TelegramChannel {
             client,
             inbound_tx,
             media_dir,
+            inline_latest: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -139,6 +196,10 @@ impl TelegramChannel {
             return true;
         }
 
+        Self::in_allowlist(allow_from, user)
+    }
+
+    fn in_allowlist(allow_from: &[String], user: &User) -> bool {
         let user_id = user.id.to_string();
         let username = user.username.as_deref().unwrap_or("");
 
@@ -157,6 +218,22 @@ impl TelegramChannel {
         if let Some(off) = offset {
             params.push(("offset", off.to_string()));
         }
+        let mut update_types = vec!["message"];
+        if self.config.channels.reactions.enabled {
+            update_types.push("message_reaction");
+        }
+        if self.config.channels.telegram.inline.enabled {
+            update_types.push("inline_query");
+        }
+        if self.config.channels.confirm.enabled_for("telegram") {
+            update_types.push("callback_query");
+        }
+        if update_types.len() > 1 {
+            params.push((
+                "allowed_updates",
+                serde_json::to_string(&update_types).unwrap_or_default(),
+            ));
+        }
 
         let response = self
             .client
@@ -209,6 +286,19 @@ impl TelegramChannel {
                                         error!(error = %e, "Failed to handle Telegram message");
                                     }
                                 }
+                                if let Some(reaction) = update.message_reaction {
+                                    if let Err(e) = self.handle_reaction(reaction).await {
+                                        error!(error = %e, "Failed to handle Telegram reaction");
+                                    }
+                                }
+                                if let Some(query) = update.inline_query {
+                                    self.handle_inline_query(query);
+                                }
+                                if let Some(query) = update.callback_query {
+                                    if let Err(e) = self.handle_callback_query(query).await {
+                                        error!(error = %e, "Failed to handle Telegram button press");
+                                    }
+                                }
                             }
                         }
                         Err(e) => {
@@ -417,6 +507,136 @@ impl TelegramChannel {
         Ok(())
     }
 
+    async fn handle_reaction(&self, reaction: MessageReactionUpdated) -> Result<()> {
+        let Some(user) = &reaction.user else {
+            return Ok(());
+        };
+        if !self.is_allowed(user) {
+            debug!(
+                user_id = user.id,
+                "User not in allowlist, ignoring reaction"
+            );
+            return Ok(());
+        }
+        let previous: Vec<&str> = reaction
+            .old_reaction
+            .iter()
+            .filter_map(|r| r.emoji.as_deref())
+            .collect();
+        for emoji in reaction
+            .new_reaction
+            .iter()
+            .filter_map(|r| r.emoji.as_deref())
+            .filter(|e| !previous.contains(e))
+        {
+            let inbound = crate::reactions::reaction_message(
+                "telegram",
+                telegram_account_id(&self.config),
+                &user.id.to_string(),
+                &reaction.chat.id.to_string(),
+                emoji,
+                &reaction.message_id.to_string(),
+            );
+            self.inbound_tx
+                .send(inbound)
+                .await
+                .map_err(|e| Error::Channel(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Queue an inline query for the runtime's fast path once the user stops typing.
+    fn handle_inline_query(&self, query: InlineQuery) {
+        let inline = &self.config.channels.telegram.inline;
+        if !inline.enabled || query.query.trim().is_empty() {
+            return;
+        }
+        if !Self::in_allowlist(&inline.allow_from, &query.from) {
+            debug!(
+                user_id = query.from.id,
+                "User not in inline allowlist, ignoring"
+            );
+            let config = self.config.clone();
+            tokio::spawn(async move {
+                if let Err(e) = answer_inline_query(&config, &query.id, None, "").await {
+                    debug!(error = %e, "Failed to answer inline query");
+                }
+            });
+            return;
+        }
+
+        let user_id = query.from.id;
+        if let Ok(mut latest) = self.inline_latest.lock() {
+            latest.insert(user_id, query.id.clone());
+        }
+        let latest = Arc::clone(&self.inline_latest);
+        let inbound_tx = self.inbound_tx.clone();
+        let account_id = telegram_account_id(&self.config);
+        tokio::spawn(async move {
+            tokio::time::sleep(INLINE_DEBOUNCE).await;
+            let still_latest = latest
+                .lock()
+                .map(|l| l.get(&user_id) == Some(&query.id))
+                .unwrap_or(false);
+            if !still_latest {
+                return;
+            }
+            let inbound = InboundMessage {
+                channel: "telegram".to_string(),
+                account_id,
+                sender_id: user_id.to_string(),
+                chat_id: user_id.to_string(),
+                content: query.query.trim().to_string(),
+                media: vec![],
+                metadata: serde_json::json!({
+                    "inline_query_id": query.id,
+                    "username": query.from.username,
+                }),
+                timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            };
+            if let Err(e) = inbound_tx.send(inbound).await {
+                error!(error = %e, "Failed to forward Telegram inline query");
+            }
+        });
+    }
+
+    /// Turn an approval button press into the matching `/approve` or `/deny` message.
+    async fn handle_callback_query(&self, query: CallbackQuery) -> Result<()> {
+        let command = query.data.as_deref().and_then(confirm_command);
+        let allowed = self.is_allowed(&query.from);
+        // Always answer so the client stops showing a progress indicator.
+        let config = self.config.clone();
+        let query_id = query.id.clone();
+        tokio::spawn(async move {
+            if let Err(e) = answer_callback_query(&config, &query_id).await {
+                debug!(error = %e, "Failed to answer callback query");
+            }
+        });
+        let (Some(command), Some(message), true) = (command, query.message, allowed) else {
+            debug!(user_id = query.from.id, "Ignoring Telegram button press");
+            return Ok(());
+        };
+
+        let inbound = InboundMessage {
+            channel: "telegram".to_string(),
+            account_id: telegram_account_id(&self.config),
+            sender_id: query.from.id.to_string(),
+            chat_id: message.chat.id.to_string(),
+            content: command.to_string(),
+            media: vec![],
+            metadata: serde_json::json!({
+                "message_id": message.message_id,
+                "username": query.from.username,
+            }),
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+        };
+        self.inbound_tx
+            .send(inbound)
+            .await
+            .map_err(|e| Error::Channel(e.to_string()))?;
+        Ok(())
+    }
+
     /// Attempt to transcribe a voice file.
     /// Priority: local `whisper` CLI → OpenAI Whisper API → None (caller shows raw path).
     async fn transcribe_voice(&self, path: &str) -> Option<String> {
@@ -570,6 +790,71 @@ pub async fn send_message(config: &Config, chat_id: &str, text: &str) -> Result<
     Ok(())
 }
 
+/// Send an approval prompt with Approve / Deny buttons under its last chunk.
+pub async fn send_confirm_prompt(config: &Config, chat_id: &str, text: &str) -> Result<()> {
+    let chunks = split_message(text, 4096);
+    let Some((last, head)) = chunks.split_last() else {
+        return Ok(());
+    };
+    for chunk in head {
+        send_message(config, chat_id, chunk).await?;
+    }
+
+    crate::rate_limit::telegram_limiter().acquire().await;
+    let mut builder = Client::builder().timeout(Duration::from_secs(30));
+    if let Some(proxy) = config.channels.telegram.proxy.as_deref() {
+        if let Ok(p) = Proxy::all(proxy) {
+            builder = builder.proxy(p);
+        }
+    }
+    let client = builder.build().unwrap_or_else(|_| Client::new());
+    let url = format!(
+        "{}/bot{}/sendMessage",
+        TELEGRAM_API_BASE, config.channels.telegram.token
+    );
+    let buttons: Vec<serde_json::Value> = CONFIRM_BUTTONS
+        .iter()
+        .map(|(label, data, _)| serde_json::json!({ "text": label, "callback_data": data }))
+        .collect();
+    let body = serde_json::json!({
+        "chat_id": chat_id,
+        "text": last,
+        "reply_markup": { "inline_keyboard": [buttons] },
+    });
+    let response = client
+        .post(&url)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| Error::Channel(format!("Failed to send Telegram message: {}", e)))?;
+    if !response.status().is_success() {
+        let err = response.text().await.unwrap_or_default();
+        return Err(Error::Channel(format!("Telegram API error: {}", err)));
+    }
+    Ok(())
+}
+
+async fn answer_callback_query(config: &Config, query_id: &str) -> Result<()> {
+    let mut builder = Client::builder().timeout(Duration::from_secs(10));
+    if let Some(proxy) = config.channels.telegram.proxy.as_deref() {
+        if let Ok(p) = Proxy::all(proxy) {
+            builder = builder.proxy(p);
+        }
+    }
+    let client = builder.build().unwrap_or_else(|_| Client::new());
+    let url = format!(
+        "{}/bot{}/answerCallbackQuery",
+        TELEGRAM_API_BASE, config.channels.telegram.token
+    );
+    client
+        .post(&url)
+        .json(&serde_json::json!({ "callback_query_id": query_id }))
+        .send()
+        .await
+        .map_err(|e| Error::Channel(format!("Telegram answerCallbackQuery failed: {}", e)))?;
+    Ok(())
+}
+
 /// Send a message to a Telegram chat, quoting a specific message when `reply_to_message_id` is set.
 /// Only the first chunk of a long message carries the reply reference.
 pub async fn send_message_reply(
@@ -679,52 +964,63 @@ async fn do_send_message(
     Err(Error::Channel(format!("Telegram API error: {}", body)))
 }
 
-/// Split a message into chunks at newline boundaries, respecting a max length.
-fn split_message(text: &str, max_len: usize) -> Vec<String> {
-    if text.len() <= max_len {
-        return vec![text.to_string()];
-    }
-
-    let mut chunks = Vec::new();
-    let mut remaining = text;
-
-    while !remaining.is_empty() {
-        if remaining.len() <= max_len {
-            chunks.push(remaining.to_string());
-            break;
-        }
-
-        // Find the best split point within the limit.
-        let mut split_at = max_len;
-
-        // Ensure split_at is a char boundary before we slice
-        while split_at > 0 && !remaining.is_char_boundary(split_at) {
-            split_at -= 1;
-        }
-
-        // If we somehow couldn't find a boundary (e.g., max_len too small), fallback to first char
-        if split_at == 0 {
-            split_at = remaining
-                .chars()
-                .next()
-                .map(|c| c.len_utf8())
-                .unwrap_or(max_len);
-        }
-
-        // Try to split on a newline within the safe boundary
-        if let Some(last_newline) = remaining[..split_at].rfind('\n') {
-            split_at = last_newline + 1; // Include the newline in the chunk
+/// Send a media file (photo/audio/video/document) to a Telegram chat.
+/// Automatically selects the correct Telegram API method based on file extension.
+/// Answer an inline query with a single article, or with no results when `title` is
+/// `None`.
+pub async fn answer_inline_query(
+    config: &Config,
+    query_id: &str,
+    title: Option<&str>,
+    text: &str,
+) -> Result<()> {
+    let mut builder = Client::builder().timeout(Duration::from_secs(10));
+    if let Some(proxy) = config.channels.telegram.proxy.as_deref() {
+        if let Ok(p) = Proxy::all(proxy) {
+            builder = builder.proxy(p);
         }
-
-        chunks.push(remaining[..split_at].to_string());
-        remaining = &remaining[split_at..];
     }
+    let client = builder.build().unwrap_or_else(|_| Client::new());
+    let url = format!(
+        "{}/bot{}/answerInlineQuery",
+        TELEGRAM_API_BASE, config.channels.telegram.token
+    );
 
-    chunks
+    let results = match title {
+        Some(title) => serde_json::json!([{
+            "type": "article",
+            "id": "1",
+            "title": title,
+            "description": text.chars().take(120).collect::<String>(),
+            "input_message_content": { "message_text": text },
+        }]),
+        None => serde_json::json!([]),
+    };
+    let body = serde_json::json!({
+        "inline_query_id": query_id,
+        "results": results,
+        "cache_time": config.channels.telegram.inline.cache_time_secs,
+        "is_personal": true,
+    });
+    let response: TelegramResponse<bool> = client
+        .post(&url)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| Error::Channel(format!("Telegram answerInlineQuery failed: {}", e)))?
+        .json()
+        .await
+        .map_err(|e| Error::Channel(format!("Failed to parse Telegram response: {}", e)))?;
+    if !response.ok {
+        return Err(Error::Channel(
+            response
+                .description
+                .unwrap_or_else(|| "answerInlineQuery rejected".to_string()),
+        ));
+    }
+    Ok(())
 }
 
-/// Send a media file (photo/audio/video/document) to a Telegram chat.
-/// Automatically selects the correct Telegram API method based on file extension.
 pub async fn send_media_message(config: &Config, chat_id: &str, file_path: &str) -> Result<()> {
     crate::rate_limit::telegram_limiter().acquire().await;
 
@@ -811,6 +1107,30 @@ fn telegram_mime_for_ext(ext: &str) -> &'static str {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_confirm_button_commands() {
+        assert_eq!(confirm_command("confirm:approve"), Some("/approve"));
+        assert_eq!(confirm_command("confirm:deny"), Some("/deny"));
+        assert_eq!(confirm_command("something_else"), None);
+
+        let update: Update = serde_json::from_value(serde_json::json!({
+            "update_id": 7,
+            "callback_query": {
+                "id": "cb1",
+                "from": { "id": 42, "username": "alice" },
+                "message": { "message_id": 9, "chat": { "id": 42 } },
+                "data": "confirm:deny"
+            }
+        }))
+        .unwrap();
+        let query = update.callback_query.unwrap();
+        assert_eq!(query.message.unwrap().chat.id, 42);
+        assert_eq!(
+            confirm_command(query.data.as_deref().unwrap()),
+            Some("/deny")
+        );
+    }
+
     #[test]
     fn test_escape_plain_text() {
         assert_eq!(escape_markdown_v2("hello world"), "hello world");
@@ -1,4 +1,5 @@
 use crate::account::{wecom_account_id, wecom_listener_configs};
+use crate::split::split_message_chars;
 use aes::cipher::{BlockDecryptMut, KeyIvInit};
 use base64::{
     alphabet,
@@ -2551,7 +2552,7 @@ pub async fn send_message(config: &Config, chat_id: &str, text: &str) -> Result<
         let bot_id = config.channels.wecom.bot_id.trim().to_string();
         let registry = LONGCONN_REGISTRY.lock().unwrap();
         if let Some(tx) = registry.get(&bot_id) {
-            let chunks = split_message(text, WECOM_MSG_LIMIT);
+            let chunks = split_message_chars(text, WECOM_MSG_LIMIT);
             for chunk in chunks {
                 let msg = LongConnOutbound::Text {
                     chat_id: chat_id.to_string(),
@@ -2570,7 +2571,7 @@ pub async fn send_message(config: &Config, chat_id: &str, text: &str) -> Result<
     crate::rate_limit::wecom_limiter().acquire().await;
 
     let client = shared_client();
-    let chunks = split_message(text, WECOM_MSG_LIMIT);
+    let chunks = split_message_chars(text, WECOM_MSG_LIMIT);
     for (i, chunk) in chunks.iter().enumerate() {
         do_send_message(&client, config, chat_id, chunk).await?;
         if i + 1 < chunks.len() {
@@ -2700,40 +2701,13 @@ async fn do_send_message(
     Ok(())
 }
 
-fn split_message(text: &str, max_len: usize) -> Vec<String> {
-    if text.chars().count() <= max_len {
-        return vec![text.to_string()];
-    }
-    let mut chunks = Vec::new();
-    let mut remaining = text;
-    while !remaining.is_empty() {
-        if remaining.chars().count() <= max_len {
-            chunks.push(remaining.to_string());
-            break;
-        }
-        // Find a safe byte boundary at max_len chars
-        let byte_limit = remaining
-            .char_indices()
-            .nth(max_len)
-            .map(|(i, _)| i)
-            .unwrap_or(remaining.len());
-        let split_at = remaining[..byte_limit]
-            .rfind('\n')
-            .map(|i| i + 1)
-            .unwrap_or(byte_limit);
-        chunks.push(remaining[..split_at].to_string());
-        remaining = &remaining[split_at..];
-    }
-    chunks
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_split_message_short() {
-        let chunks = split_message("hello world", 2048);
+        let chunks = split_message_chars("hello world", 2048);
         assert_eq!(chunks.len(), 1);
         assert_eq!(chunks[0], "hello world");
     }
@@ -2742,7 +2716,7 @@ mod tests {
     fn test_split_message_long() {
         let line = "a".repeat(100);
         let text = (0..25).map(|_| line.clone()).collect::<Vec<_>>().join("\n");
-        let chunks = split_message(&text, 2048);
+        let chunks = split_message_chars(&text, 2048);
         assert!(chunks.len() > 1);
         for chunk in &chunks {
             assert!(chunk.chars().count() <= 2048);
@@ -2753,7 +2727,7 @@ mod tests {
     fn test_split_message_chinese() {
         // Each Chinese char is 3 bytes; 1000 chars = 3000 bytes
         let text = "中".repeat(3000);
-        let chunks = split_message(&text, 2048);
+        let chunks = split_message_chars(&text, 2048);
         assert!(chunks.len() > 1);
         for chunk in &chunks {
             assert!(
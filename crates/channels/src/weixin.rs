@@ -1,4 +1,5 @@
 use crate::account::weixin_account_id;
+use crate::split::split_message;
 use base64::Engine;
 use blockcell_core::{Config, Error, InboundMessage, Result};
 use reqwest::Client;
@@ -584,47 +585,6 @@ async fn send_text_chunk(
     Ok(())
 }
 
-fn split_message(text: &str, max_len: usize) -> Vec<String> {
-    if text.len() <= max_len {
-        return vec![text.to_string()];
-    }
-
-    let mut chunks = Vec::new();
-    let mut remaining = text;
-
-    while !remaining.is_empty() {
-        if remaining.len() <= max_len {
-            chunks.push(remaining.to_string());
-            break;
-        }
-
-        // Find a valid UTF-8 boundary at or before the max length.
-        let mut split_at = max_len;
-        while split_at > 0 && !remaining.is_char_boundary(split_at) {
-            split_at -= 1;
-        }
-
-        // Try to split at a newline boundary within the safe range.
-        if let Some(last_newline) = remaining[..split_at].rfind('\n') {
-            split_at = last_newline + 1;
-        }
-
-        // If the max length is smaller than a single char, fall back to one char.
-        if split_at == 0 {
-            split_at = remaining
-                .chars()
-                .next()
-                .map(|c| c.len_utf8())
-                .unwrap_or(max_len);
-        }
-
-        chunks.push(remaining[..split_at].to_string());
-        remaining = &remaining[split_at..];
-    }
-
-    chunks
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
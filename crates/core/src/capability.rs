@@ -76,6 +76,51 @@ pub enum ProviderKind {
     ExternalApi,
 }
 
+/// Network access an evolved capability declares. Enforced when it runs: requests go
+/// through a policy proxy and a request outside the scope blocks the capability.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum NetworkScope {
+    /// No network access.
+    #[default]
+    None,
+    /// Only these hosts (`example.com` includes subdomains, `*.example.com` only
+    /// subdomains).
+    Domains { domains: Vec<String> },
+}
+
+impl NetworkScope {
+    /// Scope from a declaration like `"none"`, `["api.example.com"]` or
+    /// `{"domains": [...]}`. Empty domain lists mean no access.
+    pub fn from_declaration(value: &serde_json::Value) -> Self {
+        let domains = value
+            .get("domains")
+            .unwrap_or(value)
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|d| d.as_str())
+                    .map(|d| d.trim().to_ascii_lowercase())
+                    .filter(|d| !d.is_empty() && d != "*")
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        if domains.is_empty() {
+            NetworkScope::None
+        } else {
+            NetworkScope::Domains { domains }
+        }
+    }
+
+    pub fn domains(&self) -> &[String] {
+        match self {
+            NetworkScope::None => &[],
+            NetworkScope::Domains { domains } => domains,
+        }
+    }
+}
+
 /// 能力描述符 — 对应文档中的 capability YAML 定义
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CapabilityDescriptor {
@@ -105,6 +150,9 @@ pub struct CapabilityDescriptor {
     pub provider_path: Option<String>,
     /// 依赖的其他能力 ID
     pub dependencies: Vec<String>,
+    /// Declared network access (ignored for built-in providers)
+    #[serde(default)]
+    pub network: NetworkScope,
     /// 元数据
     pub metadata: HashMap<String, serde_json::Value>,
     /// 创建时间
@@ -136,6 +184,7 @@ impl CapabilityDescriptor {
             version: env!("CARGO_PKG_VERSION").to_string(),
             provider_path: None,
             dependencies: Vec::new(),
+            network: NetworkScope::None,
             metadata: HashMap::new(),
             created_at: now,
             updated_at: now,
@@ -157,6 +206,11 @@ impl CapabilityDescriptor {
         self
     }
 
+    pub fn with_network(mut self, network: NetworkScope) -> Self {
+        self.network = network;
+        self
+    }
+
     pub fn is_available(&self) -> bool {
         matches!(
             self.status,
@@ -244,6 +298,38 @@ mod tests {
         assert_eq!(cap.privilege, PrivilegeLevel::Full);
     }
 
+    #[test]
+    fn test_network_scope_declaration() {
+        use serde_json::json;
+        assert_eq!(
+            NetworkScope::from_declaration(&json!("none")),
+            NetworkScope::None
+        );
+        assert_eq!(
+            NetworkScope::from_declaration(&json!({"domains": ["API.example.com", "*"]})),
+            NetworkScope::Domains {
+                domains: vec!["api.example.com".to_string()]
+            }
+        );
+        assert_eq!(
+            NetworkScope::from_declaration(&json!(["wttr.in"])).domains(),
+            ["wttr.in".to_string()]
+        );
+
+        // Descriptors saved before network scopes existed get no access.
+        let mut value = serde_json::to_value(CapabilityDescriptor::new(
+            "net.fetch",
+            "Fetch",
+            "",
+            CapabilityType::External,
+            ProviderKind::ExternalApi,
+        ))
+        .unwrap();
+        value.as_object_mut().unwrap().remove("network");
+        let cap: CapabilityDescriptor = serde_json::from_value(value).unwrap();
+        assert_eq!(cap.network, NetworkScope::None);
+    }
+
     #[test]
     fn test_survival_invariants() {
         let mut inv = SurvivalInvariants::default();
@@ -32,6 +32,96 @@ fn default_api_type() -> String {
     "openai".to_string()
 }
 
+/// The `providers` section: credentials per provider name, plus the optional
+/// `failover` chain. Derefs to the name → config map.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProvidersConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failover: Option<FailoverConfig>,
+    #[serde(flatten)]
+    pub entries: HashMap<String, ProviderConfig>,
+}
+
+impl std::ops::Deref for ProvidersConfig {
+    type Target = HashMap<String, ProviderConfig>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.entries
+    }
+}
+
+impl std::ops::DerefMut for ProvidersConfig {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.entries
+    }
+}
+
+impl From<HashMap<String, ProviderConfig>> for ProvidersConfig {
+    fn from(entries: HashMap<String, ProviderConfig>) -> Self {
+        Self {
+            failover: None,
+            entries,
+        }
+    }
+}
+
+/// `providers.failover`: when a call keeps failing with 429/5xx/timeouts, retry with
+/// backoff and then move on to the next provider in `chain`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailoverConfig {
+    /// Tried in order after the model's own provider, e.g.
+    /// `[{"provider": "openai", "model": "gpt-4o-mini"}, {"provider": "ollama", "model": "qwen2.5:7b"}]`.
+    #[serde(default)]
+    pub chain: Vec<FailoverTarget>,
+    /// Retries on the same provider before moving on. Default: 2
+    #[serde(default = "default_failover_retries")]
+    pub max_retries: u32,
+    /// Wait before the first retry, doubled on each further one. Default: 500
+    #[serde(default = "default_failover_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    /// Upper bound for the wait between retries. Default: 8000
+    #[serde(default = "default_failover_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+    /// Give up on a single attempt after this many seconds. Default: 120
+    #[serde(default = "default_failover_attempt_timeout")]
+    pub attempt_timeout_secs: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailoverTarget {
+    pub provider: String,
+    pub model: String,
+}
+
+fn default_failover_retries() -> u32 {
+    2
+}
+
+fn default_failover_backoff_ms() -> u64 {
+    500
+}
+
+fn default_failover_max_backoff_ms() -> u64 {
+    8000
+}
+
+fn default_failover_attempt_timeout() -> u64 {
+    120
+}
+
+impl Default for FailoverConfig {
+    fn default() -> Self {
+        Self {
+            chain: Vec::new(),
+            max_retries: default_failover_retries(),
+            initial_backoff_ms: default_failover_backoff_ms(),
+            max_backoff_ms: default_failover_max_backoff_ms(),
+            attempt_timeout_secs: default_failover_attempt_timeout(),
+        }
+    }
+}
+
 fn is_default_api_type(t: &str) -> bool {
     t == "openai"
 }
@@ -105,6 +195,10 @@ pub struct ModelEntry {
     /// - auto: 先尝试 native，失败或被中继剥离后自动退化为 text
     #[serde(default, skip_serializing_if = "is_default_tool_call_mode")]
     pub tool_call_mode: ToolCallMode,
+    /// 等价组名称（可选）。同组条目视为可互相替代；
+    /// 开启 `agents.defaults.latencyRouting` 后，在组内优先选用近期延迟低、错误少的条目。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
 }
 
 fn default_entry_weight() -> u32 {
@@ -152,17 +246,477 @@ pub struct AgentDefaults {
     /// 如果不指定，将从 evolution_model 推断，或使用主 provider
     #[serde(default)]
     pub evolution_provider: Option<String>,
+    /// 自进化审计/复核用的强模型（可选）
+    /// 配置后草稿仍由 evolution_model 生成，审计和多次测试失败后的重新生成改用此模型
+    #[serde(default)]
+    pub evolution_audit_model: Option<String>,
+    /// 审计模型的 provider（可选），不指定时从 evolution_audit_model 推断
+    #[serde(default)]
+    pub evolution_audit_provider: Option<String>,
+    /// 编译/契约检查累计失败多少次后，重新生成升级到审计模型（0 = 不升级）
+    #[serde(default = "default_evolution_escalate_after_failures")]
+    pub evolution_escalate_after_failures: u32,
     /// 多模型高可用池（可选）。
     /// 配置后，系统将从池中按优先级+权重选取 provider，失败自动降级。
     /// 若留空，则沿用旧的单 model + provider 配置（向后兼容）。
     #[serde(default)]
     pub model_pool: Vec<ModelEntry>,
+    /// Within a `modelPool` equivalence `group`, prefer the entry with the best recent
+    /// latency and error rate instead of the weighted pick. Default: false
+    #[serde(default)]
+    pub latency_routing: bool,
     /// Allowed MCP server names visible to this agent.
     #[serde(default)]
     pub allowed_mcp_servers: Vec<String>,
     /// Allowed MCP tool names visible to this agent.
     #[serde(default)]
     pub allowed_mcp_tools: Vec<String>,
+    /// Optional plan-then-execute phase for complex requests.
+    #[serde(default)]
+    pub planning: PlanningConfig,
+    /// Repeated-call detection and the per-turn tool budget.
+    #[serde(default)]
+    pub loop_detection: LoopDetectionConfig,
+    /// Check side-effect claims in the final answer against this turn's tool results.
+    #[serde(default)]
+    pub response_validation: ResponseValidationConfig,
+    /// Outbound rewrite of replies to a per-chat reading level / tone / length.
+    #[serde(default)]
+    pub reply_style: ReplyStyleConfig,
+    /// Numbered sources for answers built from web, file or memory tool results.
+    #[serde(default)]
+    pub citations: CitationsConfig,
+    /// Monthly provider spend cap with soft alerts and a hard stop.
+    #[serde(default)]
+    pub budget: BudgetConfig,
+    /// Maintenance that only runs while nobody is chatting.
+    #[serde(default)]
+    pub idle_maintenance: IdleMaintenanceConfig,
+    /// Token budgets for prompt sections, derived from the model's context window.
+    #[serde(default)]
+    pub context_budget: ContextBudgetConfig,
+    /// Save every turn's provider responses and tool results as a replay bundle under
+    /// `workspace/recordings/` (see `blockcell replay`). Default: false
+    #[serde(default)]
+    pub record_turns: bool,
+    /// Keep a compact per-turn snapshot of the context sent to the model, served by
+    /// `GET /v1/sessions/:id/messages/:idx/context`. Default: true
+    #[serde(default = "default_true")]
+    pub context_snapshots: bool,
+    /// Per-turn pruning of the tool schemas sent to the model.
+    #[serde(default)]
+    pub tool_selection: ToolSelectionConfig,
+}
+
+/// Send only the most relevant tool schemas on each call. Tools are ranked by the
+/// classified intent, recent use in the session and overlap with the message; the
+/// rest stay callable by name and are added back if the model asks for one.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolSelectionConfig {
+    /// Upper bound on tool schemas per call. 0 = send every tool in scope. Default: 0
+    #[serde(default)]
+    pub max_tools_per_call: usize,
+    /// Tools that are always sent, on top of the active skill's tools.
+    #[serde(default)]
+    pub always_include: Vec<String>,
+    /// History messages scanned for recently used tools. Default: 20
+    #[serde(default = "default_tool_selection_recent_messages")]
+    pub recent_messages: usize,
+}
+
+fn default_tool_selection_recent_messages() -> usize {
+    20
+}
+
+impl Default for ToolSelectionConfig {
+    fn default() -> Self {
+        Self {
+            max_tools_per_call: 0,
+            always_include: Vec::new(),
+            recent_messages: default_tool_selection_recent_messages(),
+        }
+    }
+}
+
+/// Idle-time self-maintenance. Once no interactive message has arrived for
+/// `idleMinutes`, the runtime works through memory maintenance, workspace GC, session
+/// summary backfill, pending evolutions and an update check, one job per tick. A user
+/// message aborts the running job and defers the rest until the agent is idle again.
+///
+/// While enabled, memory maintenance and evolution work no longer run on every tick.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IdleMaintenanceConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Minutes without interactive messages before the agent counts as idle.
+    #[serde(default = "default_idle_minutes")]
+    pub idle_minutes: u64,
+    /// Minimum minutes between two runs of the same job.
+    #[serde(default = "default_idle_job_interval_minutes")]
+    pub job_interval_minutes: u64,
+    /// Media and tool artifacts older than this are removed by workspace GC.
+    #[serde(default = "default_idle_gc_max_age_days")]
+    pub gc_max_age_days: u64,
+    /// Check the upgrade manifest (at most daily) and notify when a newer version exists.
+    #[serde(default = "default_true")]
+    pub check_updates: bool,
+}
+
+impl Default for IdleMaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            idle_minutes: default_idle_minutes(),
+            job_interval_minutes: default_idle_job_interval_minutes(),
+            gc_max_age_days: default_idle_gc_max_age_days(),
+            check_updates: true,
+        }
+    }
+}
+
+fn default_idle_minutes() -> u64 {
+    10
+}
+
+fn default_idle_job_interval_minutes() -> u64 {
+    60
+}
+
+fn default_idle_gc_max_age_days() -> u64 {
+    14
+}
+
+/// Monthly spend guardrail. Every LLM call is priced from the pool entry's
+/// `inputPrice`/`outputPrice` (USD per 1M tokens), falling back to the budget's own
+/// prices and then the model metadata table, and added to a per-month ledger.
+/// Crossing a soft threshold notifies the user once; at the hard cap interactive
+/// chat switches to `fallbackModel` (if set) and background work (ghost, evolution)
+/// is paused until the month rolls over.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Hard cap in USD per calendar month (UTC). `0` disables the cap.
+    #[serde(default)]
+    pub monthly_usd: f64,
+    /// Fractions of `monthlyUsd` that trigger a one-time notification.
+    #[serde(default = "default_budget_soft_thresholds")]
+    pub soft_thresholds: Vec<f64>,
+    /// Cheap or local model used for interactive chat once the cap is reached.
+    /// Without it chat keeps using the normal pool.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_provider: Option<String>,
+    /// Refuse ghost routines and evolution work while over the cap.
+    #[serde(default = "default_true")]
+    pub pause_background: bool,
+    /// Prices for calls whose pool entry has none (e.g. the legacy single-model setup).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_price: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_price: Option<f64>,
+}
+
+impl Default for BudgetConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            monthly_usd: 0.0,
+            soft_thresholds: default_budget_soft_thresholds(),
+            fallback_model: None,
+            fallback_provider: None,
+            pause_background: true,
+            input_price: None,
+            output_price: None,
+        }
+    }
+}
+
+impl BudgetConfig {
+    /// Whether a cap is actually being enforced.
+    pub fn is_active(&self) -> bool {
+        self.enabled && self.monthly_usd > 0.0
+    }
+}
+
+fn default_budget_soft_thresholds() -> Vec<f64> {
+    vec![0.5, 0.8]
+}
+
+/// Verification pass for final answers. Claims such as "saved the file to X" or
+/// "job created with id Y" must be backed by a tool call, tool result or artifact from
+/// the same turn; otherwise the model gets a correction turn before anything is sent.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseValidationConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Correction turns allowed per message before the answer is sent as-is.
+    #[serde(default = "default_response_validation_max_corrections")]
+    pub max_corrections: u32,
+    /// Also accept file claims when the path exists in the workspace.
+    #[serde(default = "default_true")]
+    pub check_filesystem: bool,
+}
+
+impl Default for ResponseValidationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_corrections: default_response_validation_max_corrections(),
+            check_filesystem: true,
+        }
+    }
+}
+
+/// Reply post-processing: before delivery, a secondary LLM pass rewrites the final answer
+/// to the chat's style (e.g. kid-friendly, executive summary). Styles come from the
+/// `/style` command, then `chats`, then `defaultStyle`; a style is a preset name
+/// (`kid`, `simple`, `executive`, `formal`, `casual`, `brief`, or one from `presets`) or a
+/// free-form instruction. Session history keeps the original answer.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplyStyleConfig {
+    /// Style for chats without their own. Default: none (replies are sent unchanged)
+    #[serde(default)]
+    pub default_style: Option<String>,
+    /// Styles keyed by `channel:chat_id`, or by `channel` for every chat on it.
+    #[serde(default)]
+    pub chats: HashMap<String, String>,
+    /// Extra presets: name → rewrite instruction.
+    #[serde(default)]
+    pub presets: HashMap<String, String>,
+    /// Model for the rewrite pass; a small, cheap model is enough. Default: the chat model
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Replies shorter than this are sent unchanged. Default: 80
+    #[serde(default = "default_reply_style_min_chars")]
+    pub min_chars: usize,
+    /// Rewrites kept in memory, keyed by style and reply text. Default: 256
+    #[serde(default = "default_reply_style_cache_entries")]
+    pub cache_entries: usize,
+}
+
+impl Default for ReplyStyleConfig {
+    fn default() -> Self {
+        Self {
+            default_style: None,
+            chats: HashMap::new(),
+            presets: HashMap::new(),
+            model: None,
+            provider: None,
+            min_chars: default_reply_style_min_chars(),
+            cache_entries: default_reply_style_cache_entries(),
+        }
+    }
+}
+
+/// Citation mode. Results of `web_search`, `web_fetch`, `read_file` and `memory_query`
+/// are numbered as sources the model can cite as `[n]`; the final reply gets a source
+/// list and the mapping is sent in the message metadata (`citations`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CitationsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Sources tracked per turn; later results are not numbered. Default: 10
+    #[serde(default = "default_citations_max_sources")]
+    pub max_sources: usize,
+}
+
+impl Default for CitationsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_sources: default_citations_max_sources(),
+        }
+    }
+}
+
+fn default_citations_max_sources() -> usize {
+    10
+}
+
+/// Context window and list price of one model, used for prompt budgets and spend tracking.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelMetadata {
+    /// Total context window in tokens (prompt + completion).
+    pub context_window: u32,
+    /// Input price (USD/1M tokens).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_price: Option<f64>,
+    /// Output price (USD/1M tokens).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_price: Option<f64>,
+}
+
+/// Token-based budgets for context assembly. The usable window (context window minus
+/// `maxTokens` reserved for the answer) is split into shares for the memory brief, skill
+/// instructions, conversation history and the user's message with attachment notes; the
+/// remainder is left for the fixed system prompt and tool schemas. Models missing from the
+/// built-in table and from `models` fall back to `maxContextTokens`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextBudgetConfig {
+    /// Metadata keyed by model name or name prefix (e.g. `"my-finetune"`, `"qwen2.5"`);
+    /// overrides the built-in table.
+    #[serde(default)]
+    pub models: HashMap<String, ModelMetadata>,
+    /// Default: 0.10
+    #[serde(default = "default_context_memory_share")]
+    pub memory_share: f64,
+    /// Default: 0.15
+    #[serde(default = "default_context_skills_share")]
+    pub skills_share: f64,
+    /// Default: 0.55
+    #[serde(default = "default_context_history_share")]
+    pub history_share: f64,
+    /// Default: 0.10
+    #[serde(default = "default_context_attachments_share")]
+    pub attachments_share: f64,
+}
+
+impl Default for ContextBudgetConfig {
+    fn default() -> Self {
+        Self {
+            models: HashMap::new(),
+            memory_share: default_context_memory_share(),
+            skills_share: default_context_skills_share(),
+            history_share: default_context_history_share(),
+            attachments_share: default_context_attachments_share(),
+        }
+    }
+}
+
+fn default_context_memory_share() -> f64 {
+    0.10
+}
+
+fn default_context_skills_share() -> f64 {
+    0.15
+}
+
+fn default_context_history_share() -> f64 {
+    0.55
+}
+
+fn default_context_attachments_share() -> f64 {
+    0.10
+}
+
+fn default_reply_style_min_chars() -> usize {
+    80
+}
+
+fn default_reply_style_cache_entries() -> usize {
+    256
+}
+
+fn default_evolution_escalate_after_failures() -> u32 {
+    2
+}
+
+fn default_response_validation_max_corrections() -> u32 {
+    1
+}
+
+/// Settings for the planning phase: the model proposes a step plan, the user approves
+/// it (unless `autoApprove`), then steps run with progress events and re-planning on failure.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanningConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Skip the approval prompt and start executing the proposed plan immediately.
+    #[serde(default)]
+    pub auto_approve: bool,
+    /// Requests shorter than this (in characters) only plan when they read as multi-step.
+    #[serde(default = "default_planning_min_chars")]
+    pub min_chars: usize,
+    #[serde(default = "default_planning_max_steps")]
+    pub max_steps: usize,
+    /// How many times a failed step may trigger a revised plan within one turn.
+    #[serde(default = "default_planning_max_replans")]
+    pub max_replans: u32,
+}
+
+impl Default for PlanningConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            auto_approve: false,
+            min_chars: default_planning_min_chars(),
+            max_steps: default_planning_max_steps(),
+            max_replans: default_planning_max_replans(),
+        }
+    }
+}
+
+/// Guards against the agent calling the same tool with the same params over and over.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LoopDetectionConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Number of most recent tool calls compared against each new call.
+    #[serde(default = "default_loop_window")]
+    pub window: usize,
+    /// Identical calls within the window that mark the turn as stuck.
+    #[serde(default = "default_loop_max_repeats")]
+    pub max_repeats: u32,
+    /// Total tool calls allowed in one turn (0 = unlimited).
+    #[serde(default = "default_turn_tool_budget")]
+    pub turn_tool_budget: u32,
+    /// Fraction of the budget after which the model is told how many calls remain.
+    #[serde(default = "default_budget_warning_ratio")]
+    pub budget_warning_ratio: f32,
+}
+
+impl Default for LoopDetectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window: default_loop_window(),
+            max_repeats: default_loop_max_repeats(),
+            turn_tool_budget: default_turn_tool_budget(),
+            budget_warning_ratio: default_budget_warning_ratio(),
+        }
+    }
+}
+
+fn default_loop_window() -> usize {
+    8
+}
+
+fn default_loop_max_repeats() -> u32 {
+    3
+}
+
+fn default_turn_tool_budget() -> u32 {
+    60
+}
+
+fn default_budget_warning_ratio() -> f32 {
+    0.8
+}
+
+fn default_planning_min_chars() -> usize {
+    200
+}
+
+fn default_planning_max_steps() -> usize {
+    8
+}
+
+fn default_planning_max_replans() -> u32 {
+    2
 }
 
 fn default_workspace() -> String {
@@ -216,9 +770,24 @@ impl Default for AgentDefaults {
             provider: None,
             evolution_model: None,
             evolution_provider: None,
+            evolution_audit_model: None,
+            evolution_audit_provider: None,
+            evolution_escalate_after_failures: default_evolution_escalate_after_failures(),
             model_pool: Vec::new(),
+            latency_routing: false,
             allowed_mcp_servers: Vec::new(),
             allowed_mcp_tools: Vec::new(),
+            planning: PlanningConfig::default(),
+            loop_detection: LoopDetectionConfig::default(),
+            response_validation: ResponseValidationConfig::default(),
+            reply_style: ReplyStyleConfig::default(),
+            citations: CitationsConfig::default(),
+            budget: BudgetConfig::default(),
+            idle_maintenance: IdleMaintenanceConfig::default(),
+            context_budget: ContextBudgetConfig::default(),
+            record_turns: false,
+            context_snapshots: true,
+            tool_selection: ToolSelectionConfig::default(),
         }
     }
 }
@@ -485,6 +1054,11 @@ fn default_intent_router_profiles() -> HashMap<String, IntentToolProfileConfig>
                         "file_ops".to_string(),
                         "data_process".to_string(),
                         "office_write".to_string(),
+                        "object_storage".to_string(),
+                        "translate".to_string(),
+                        "doc_diff".to_string(),
+                        "doc_ingest".to_string(),
+                        "doc_search".to_string(),
                     ]),
                 ),
                 (
@@ -492,6 +1066,9 @@ fn default_intent_router_profiles() -> HashMap<String, IntentToolProfileConfig>
                     IntentToolEntryConfig::Tools(vec![
                         "browse".to_string(),
                         "http_request".to_string(),
+                        "rss".to_string(),
+                        "weather".to_string(),
+                        "bookmarks".to_string(),
                     ]),
                 ),
                 (
@@ -502,10 +1079,13 @@ fn default_intent_router_profiles() -> HashMap<String, IntentToolProfileConfig>
                         "chart_generate".to_string(),
                         "alert_rule".to_string(),
                         "stream_subscribe".to_string(),
+                        "backtest".to_string(),
                         "knowledge_graph".to_string(),
                         "cron".to_string(),
                         "office_write".to_string(),
                         "browse".to_string(),
+                        "receipts".to_string(),
+                        "calc".to_string(),
                     ]),
                 ),
                 (
@@ -522,8 +1102,11 @@ fn default_intent_router_profiles() -> HashMap<String, IntentToolProfileConfig>
                         "edit_file".to_string(),
                         "file_ops".to_string(),
                         "data_process".to_string(),
+                        "db_query".to_string(),
                         "chart_generate".to_string(),
+                        "python".to_string(),
                         "office_write".to_string(),
+                        "object_storage".to_string(),
                         "http_request".to_string(),
                     ]),
                 ),
@@ -534,6 +1117,7 @@ fn default_intent_router_profiles() -> HashMap<String, IntentToolProfileConfig>
                         "message".to_string(),
                         "http_request".to_string(),
                         "community_hub".to_string(),
+                        "translate".to_string(),
                         // NapCatQQ - User tools
                         "napcat_get_login_info".to_string(),
                         "napcat_get_status".to_string(),
@@ -581,8 +1165,10 @@ fn default_intent_router_profiles() -> HashMap<String, IntentToolProfileConfig>
                     "SystemControl".to_string(),
                     IntentToolEntryConfig::Tools(vec![
                         "system_info".to_string(),
+                        "docker".to_string(),
                         "capability_evolve".to_string(),
                         "app_control".to_string(),
+                        "media_control".to_string(),
                         "camera_capture".to_string(),
                         "browse".to_string(),
                         "image_understand".to_string(),
@@ -600,13 +1186,29 @@ fn default_intent_router_profiles() -> HashMap<String, IntentToolProfileConfig>
                         "list_skills".to_string(),
                         "memory_maintenance".to_string(),
                         "community_hub".to_string(),
+                        "rss".to_string(),
+                        "project".to_string(),
+                        "todo".to_string(),
+                        "calendar".to_string(),
+                        "subscriptions".to_string(),
+                        "calc".to_string(),
+                        "weather".to_string(),
+                        "travel".to_string(),
+                        "parcel_track".to_string(),
+                        "nutrition".to_string(),
+                        "birthdays".to_string(),
+                        "habits".to_string(),
+                        "follow_up".to_string(),
                     ]),
                 ),
                 (
                     "IoT".to_string(),
                     IntentToolEntryConfig::Tools(vec![
+                        "iot_control".to_string(),
+                        "stream_subscribe".to_string(),
                         "http_request".to_string(),
                         "cron".to_string(),
+                        "network_monitor".to_string(),
                     ]),
                 ),
                 (
@@ -623,7 +1225,13 @@ fn default_intent_router_profiles() -> HashMap<String, IntentToolProfileConfig>
                 (
                     "DevOps".to_string(),
                     IntentToolEntryConfig::Tools(vec![
+                        "git_api".to_string(),
                         "network_monitor".to_string(),
+                        "ssh".to_string(),
+                        "docker".to_string(),
+                        "kubectl".to_string(),
+                        "db_query".to_string(),
+                        "object_storage".to_string(),
                         "encrypt".to_string(),
                         "http_request".to_string(),
                         "edit_file".to_string(),
@@ -640,6 +1248,7 @@ fn default_intent_router_profiles() -> HashMap<String, IntentToolProfileConfig>
                         "edit_file".to_string(),
                         "file_ops".to_string(),
                         "office_write".to_string(),
+                        "translate".to_string(),
                         "http_request".to_string(),
                         "browse".to_string(),
                         "spawn".to_string(),
@@ -851,31 +1460,81 @@ pub struct TelegramConfig {
     pub accounts: HashMap<String, TelegramAccountConfig>,
     #[serde(default)]
     pub default_account_id: Option<String>,
+    /// Inline mode (`@yourbot <question>` in any chat). Needs inline mode switched on
+    /// with @BotFather.
+    #[serde(default)]
+    pub inline: TelegramInlineConfig,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// Inline queries are answered by a fast path: no tools with side effects, a single
+/// model call and a short deadline. Nothing is written to a session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct FeishuConfig {
+pub struct TelegramInlineConfig {
     #[serde(default)]
     pub enabled: bool,
-    #[serde(default)]
-    pub app_id: String,
-    #[serde(default)]
-    pub app_secret: String,
-    #[serde(default)]
-    pub encrypt_key: String,
-    #[serde(default)]
-    pub verification_token: String,
+    /// Telegram user ids or usernames allowed to use inline mode. Separate from
+    /// `allowFrom`, since inline queries can come from any chat. Empty = nobody.
     #[serde(default)]
     pub allow_from: Vec<String>,
-    #[serde(default)]
-    pub accounts: HashMap<String, FeishuAccountConfig>,
-    #[serde(default)]
-    pub default_account_id: Option<String>,
+    /// Give up and answer with a timeout card after this long. Default: 8
+    #[serde(default = "default_inline_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Characters of each fetched URL passed to the model. Default: 8000
+    #[serde(default = "default_inline_max_fetch_chars")]
+    pub max_fetch_chars: usize,
+    /// How long Telegram may cache an answer for the same query. Default: 30
+    #[serde(default = "default_inline_cache_time_secs")]
+    pub cache_time_secs: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-#[serde(rename_all = "camelCase")]
+fn default_inline_timeout_secs() -> u64 {
+    8
+}
+
+fn default_inline_max_fetch_chars() -> usize {
+    8000
+}
+
+fn default_inline_cache_time_secs() -> u32 {
+    30
+}
+
+impl Default for TelegramInlineConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            allow_from: Vec::new(),
+            timeout_secs: default_inline_timeout_secs(),
+            max_fetch_chars: default_inline_max_fetch_chars(),
+            cache_time_secs: default_inline_cache_time_secs(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FeishuConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub app_id: String,
+    #[serde(default)]
+    pub app_secret: String,
+    #[serde(default)]
+    pub encrypt_key: String,
+    #[serde(default)]
+    pub verification_token: String,
+    #[serde(default)]
+    pub allow_from: Vec<String>,
+    #[serde(default)]
+    pub accounts: HashMap<String, FeishuAccountConfig>,
+    #[serde(default)]
+    pub default_account_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
 pub struct SlackConfig {
     #[serde(default)]
     pub enabled: bool,
@@ -1457,6 +2116,234 @@ pub struct ChannelsConfig {
     pub napcat: NapCatConfig,
     #[serde(default)]
     pub weixin: WeixinConfig,
+    /// Splitting, overflow and formatting of replies sent to chat channels.
+    #[serde(default)]
+    pub outbound: OutboundFormatConfig,
+    /// Emoji reactions that trigger quick commands (Telegram, Slack, Discord).
+    #[serde(default)]
+    pub reactions: ReactionCommandsConfig,
+    /// Approval prompts for tool confirmations raised from chat channels.
+    #[serde(default)]
+    pub confirm: ConfirmPromptConfig,
+    /// Reply length and style per channel, keyed by channel name (`whatsapp`, `ws`, ...).
+    #[serde(default)]
+    pub verbosity: HashMap<String, ChannelVerbosity>,
+    /// Coalescing of rapid-fire messages from one chat into a single turn.
+    #[serde(default)]
+    pub batch: BatchWindowConfig,
+}
+
+/// Messages from one chat that arrive within `windowSecs` of each other are answered
+/// as one turn, with their texts in arrival order. Off unless a window is set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchWindowConfig {
+    /// Quiet time after the last message before the batch is processed. 0 = off.
+    /// Default: 0
+    #[serde(default)]
+    pub window_secs: u64,
+    /// Longest a batch waits after its first message, however fast messages keep
+    /// arriving. Default: 20
+    #[serde(default = "default_batch_max_wait_secs")]
+    pub max_wait_secs: u64,
+    /// Per-channel window overrides keyed by channel name, e.g. `telegram: 3`.
+    #[serde(default)]
+    pub channels: HashMap<String, u64>,
+}
+
+fn default_batch_max_wait_secs() -> u64 {
+    20
+}
+
+impl Default for BatchWindowConfig {
+    fn default() -> Self {
+        Self {
+            window_secs: 0,
+            max_wait_secs: default_batch_max_wait_secs(),
+            channels: HashMap::new(),
+        }
+    }
+}
+
+impl BatchWindowConfig {
+    /// Debounce window for `channel`, or `None` when batching is off there.
+    pub fn window_for(&self, channel: &str) -> Option<std::time::Duration> {
+        let secs = self
+            .channels
+            .get(channel)
+            .copied()
+            .unwrap_or(self.window_secs);
+        (secs > 0).then(|| std::time::Duration::from_secs(secs))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.window_secs > 0 || self.channels.values().any(|secs| *secs > 0)
+    }
+}
+
+/// How long and how structured replies on one channel should be. Applied through the
+/// provider's output token limit and a short section in the system prompt.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelVerbosity {
+    /// Output token limit for replies on this channel. Never raises
+    /// `agents.defaults.maxTokens`.
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    /// Prefer a few bullet points over paragraphs.
+    #[serde(default)]
+    pub bullet_summaries: bool,
+    /// Give only the result, without describing tool calls or intermediate steps.
+    #[serde(default)]
+    pub suppress_tool_narration: bool,
+}
+
+impl ChannelsConfig {
+    pub fn verbosity_for(&self, channel: &str) -> Option<&ChannelVerbosity> {
+        self.verbosity.get(channel)
+    }
+}
+
+/// Tool confirmations raised from a chat channel are asked in that chat, answered with
+/// `/approve` or `/deny` (buttons on Telegram). Unanswered prompts are denied after the
+/// timeout. The WebUI always uses its own dialog.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmPromptConfig {
+    /// When false, confirmations from chat channels are denied without asking.
+    /// Default: true
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Seconds to wait for an answer before denying. Default: 300
+    #[serde(default = "default_confirm_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Per-channel overrides keyed by channel name, e.g. `telegram`.
+    #[serde(default)]
+    pub channels: HashMap<String, ConfirmChannelRule>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmChannelRule {
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+fn default_confirm_timeout_secs() -> u64 {
+    300
+}
+
+impl Default for ConfirmPromptConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            timeout_secs: default_confirm_timeout_secs(),
+            channels: HashMap::new(),
+        }
+    }
+}
+
+impl ConfirmPromptConfig {
+    pub fn enabled_for(&self, channel: &str) -> bool {
+        self.channels
+            .get(channel)
+            .and_then(|rule| rule.enabled)
+            .unwrap_or(self.enabled)
+    }
+
+    pub fn timeout_for(&self, channel: &str) -> std::time::Duration {
+        let secs = self
+            .channels
+            .get(channel)
+            .and_then(|rule| rule.timeout_secs)
+            .unwrap_or(self.timeout_secs);
+        std::time::Duration::from_secs(secs.max(1))
+    }
+}
+
+/// What a reaction emoji does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReactionAction {
+    /// Answer the last message again.
+    Regenerate,
+    /// Save the reacted-to reply to memory.
+    SaveMemory,
+    /// Approve the confirmation waiting in this chat.
+    Approve,
+    /// Delete the reminder the reacted-to reply set up.
+    DeleteReminder,
+}
+
+/// Reacting to a message with a mapped emoji runs its action. Telegram only delivers
+/// reactions in groups where the bot is an admin, and in private chats; Slack needs the
+/// `reactions:read` scope and the `reaction_added` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReactionCommandsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Emoji → action. Slack reactions can also be keyed by name, e.g. `:repeat:`.
+    /// Default: 🔁 regenerate, 📌 save_memory, ✅ approve, 🗑 delete_reminder
+    #[serde(default = "default_reaction_actions")]
+    pub actions: HashMap<String, ReactionAction>,
+}
+
+fn default_reaction_actions() -> HashMap<String, ReactionAction> {
+    HashMap::from([
+        ("🔁".to_string(), ReactionAction::Regenerate),
+        ("📌".to_string(), ReactionAction::SaveMemory),
+        ("✅".to_string(), ReactionAction::Approve),
+        ("🗑".to_string(), ReactionAction::DeleteReminder),
+    ])
+}
+
+impl Default for ReactionCommandsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            actions: default_reaction_actions(),
+        }
+    }
+}
+
+/// How replies are fitted to a channel's message size limit and markup support.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboundFormatConfig {
+    /// Replies that would need more parts than this are saved to `workspace/outbox/`
+    /// and sent as an excerpt plus a share link (or the file itself when
+    /// `gateway.publicApiBase` is not set). 0 = always split. Default: 4
+    #[serde(default = "default_outbound_max_parts")]
+    pub max_parts: usize,
+    /// Lifetime of overflow share links, capped by `gateway.shareMaxTtlSecs`.
+    /// Default: 7 days
+    #[serde(default = "default_outbound_link_ttl_secs")]
+    pub link_ttl_secs: u64,
+    /// Rewrite Markdown for channels that render something else (Slack mrkdwn,
+    /// WhatsApp) or nothing at all (WeChat, QQ). Default: true
+    #[serde(default = "default_true")]
+    pub downgrade_formatting: bool,
+}
+
+fn default_outbound_max_parts() -> usize {
+    4
+}
+
+fn default_outbound_link_ttl_secs() -> u64 {
+    7 * 24 * 3600
+}
+
+impl Default for OutboundFormatConfig {
+    fn default() -> Self {
+        Self {
+            max_parts: default_outbound_max_parts(),
+            link_ttl_secs: default_outbound_link_ttl_secs(),
+            downgrade_formatting: true,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1470,143 +2357,1714 @@ pub struct GatewayConfig {
     pub webui_host: String,
     #[serde(default = "default_webui_port")]
     pub webui_port: u16,
+    /// Serve the WebUI from the API listener (`/v1/...` keeps its paths) instead of its
+    /// own `webuiHost:webuiPort` listener. Unset = on when `webuiPort` equals `port`.
+    #[serde(default)]
+    pub single_port: Option<bool>,
     /// Optional public API base URL injected into WebUI at runtime.
     /// Example: "https://your-domain.example.com" or "https://your-domain.example.com/api".
     /// If not set, WebUI will default to current hostname + gateway.port.
     #[serde(default)]
     pub public_api_base: Option<String>,
+    /// Path prefix the API and WebUI are served under, for a reverse proxy that forwards
+    /// e.g. `https://example.com/blockcell/...` with the prefix intact. Empty = `/`.
+    #[serde(default)]
+    pub base_path: String,
+    /// Reverse proxies (IPs or CIDRs such as `10.0.0.0/8`) whose `X-Forwarded-For` /
+    /// `X-Real-IP` headers are believed. Requests from any other address are attributed
+    /// to the TCP peer and those headers are ignored.
+    #[serde(default)]
+    pub trusted_proxies: Vec<String>,
     #[serde(default)]
     pub api_token: Option<String>,
+    /// Extra origins allowed to call the API; merged into `cors.api.allowedOrigins`.
     #[serde(default)]
     pub allowed_origins: Vec<String>,
+    /// CORS policies of the API and WebUI servers.
+    #[serde(default)]
+    pub cors: GatewayCorsConfig,
     /// WebUI login password. If empty/None, a temporary password is printed at startup.
     #[serde(default)]
     pub webui_pass: Option<String>,
+    /// Passkey (WebAuthn) login for the WebUI, with cookie sessions.
+    #[serde(default)]
+    pub webauthn: WebAuthnConfig,
+    /// Rate limiting and lockout of password logins (`/v1/auth/login`).
+    #[serde(default)]
+    pub login_limit: LoginLimitConfig,
+    /// Longest lifetime a file share link may be given. Default: 7 days
+    #[serde(default = "default_share_max_ttl_secs")]
+    pub share_max_ttl_secs: u64,
+    /// Per-source settings for `POST /v1/ingest/:source`, keyed by source name
+    /// (e.g. `github`, `grafana`, `stripe`). Unlisted sources use the defaults.
+    #[serde(default)]
+    pub ingest: HashMap<String, IngestSourceConfig>,
+    /// Escalate through a secondary channel when the gateway or a monitored job stops
+    /// checking in.
+    #[serde(default)]
+    pub dead_mans_switch: DeadMansSwitchConfig,
+    /// WebSocket events of at least this many bytes are sent as binary frames to
+    /// clients that connected with `encoding=msgpack` or `compress=deflate`; smaller
+    /// events stay text JSON. Default: 8192
+    #[serde(default = "default_ws_binary_min_bytes")]
+    pub ws_binary_min_bytes: usize,
+}
+
+/// How passkeys combine with `webuiPass` at WebUI login.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebAuthnMode {
+    /// Password or passkey.
+    #[default]
+    Optional,
+    /// Password, then a passkey.
+    PasswordAndPasskey,
+    /// Passkey only. The password still signs in while no passkey is registered, so
+    /// the first one can be added.
+    PasskeyOnly,
 }
 
-fn default_gateway_host() -> String {
-    "localhost".to_string()
+/// WebUI passkeys. When enabled, WebUI logins get HttpOnly session cookies (renewed
+/// through a refresh cookie) instead of the API token, so the browser never holds a
+/// credential for the whole API. Programmatic clients keep using `apiToken`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebAuthnConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub mode: WebAuthnMode,
+    /// Origin the WebUI is opened from, e.g. `https://ops.example.com`. Default: the
+    /// origin of `publicApiBase`, else `http://localhost:<webuiPort>`.
+    #[serde(default)]
+    pub origin: Option<String>,
+    /// Relying party id passkeys are bound to: the origin's host or a parent domain.
+    /// Default: the origin's host.
+    #[serde(default)]
+    pub rp_id: Option<String>,
+    #[serde(default = "default_webauthn_rp_name")]
+    pub rp_name: String,
+    /// Lifetime of the session cookie. Default: 3600
+    #[serde(default = "default_webui_session_ttl_secs")]
+    pub session_ttl_secs: u64,
+    /// How long a browser stays signed in without a new login. Default: 30 days
+    #[serde(default = "default_webui_refresh_ttl_secs")]
+    pub refresh_ttl_secs: u64,
 }
 
-fn default_gateway_port() -> u16 {
-    18790
+fn default_webauthn_rp_name() -> String {
+    "blockcell".to_string()
 }
 
-fn default_webui_host() -> String {
-    "localhost".to_string()
+fn default_webui_session_ttl_secs() -> u64 {
+    3600
 }
 
-fn default_webui_port() -> u16 {
-    18791
+fn default_webui_refresh_ttl_secs() -> u64 {
+    30 * 24 * 3600
 }
 
-impl Default for GatewayConfig {
+impl Default for WebAuthnConfig {
     fn default() -> Self {
         Self {
-            host: default_gateway_host(),
-            port: default_gateway_port(),
-            webui_host: default_webui_host(),
-            webui_port: default_webui_port(),
-            public_api_base: None,
-            api_token: None,
-            allowed_origins: vec![],
-            webui_pass: None,
+            enabled: false,
+            mode: WebAuthnMode::default(),
+            origin: None,
+            rp_id: None,
+            rp_name: default_webauthn_rp_name(),
+            session_ttl_secs: default_webui_session_ttl_secs(),
+            refresh_ttl_secs: default_webui_refresh_ttl_secs(),
         }
     }
 }
 
+/// Failed password logins lock out the client address, with a lockout that doubles
+/// on every repeat; too many failures from everywhere pause password logins for all.
+/// `blockcell auth unlock` lifts lockouts.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct WebSearchConfig {
-    /// Brave Search API key (optional, for brave_search)
-    #[serde(default)]
-    pub api_key: String,
-    /// Baidu AI Search API key (optional, for baidu_search)
-    /// Get from https://qianfan.baidubce.com — set env BAIDU_API_KEY or this field
-    #[serde(default)]
-    pub baidu_api_key: String,
-    #[serde(default = "default_max_results")]
-    pub max_results: u32,
+pub struct LoginLimitConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Failures from one address, within `windowSecs`, that trigger a lockout. Default: 5
+    #[serde(default = "default_login_max_failures")]
+    pub max_failures: u32,
+    /// Failures older than this are forgotten. Default: 900
+    #[serde(default = "default_login_window_secs")]
+    pub window_secs: u64,
+    /// First lockout of an address; each further one doubles it. Default: 60
+    #[serde(default = "default_login_lockout_secs")]
+    pub lockout_secs: u64,
+    /// Cap on the doubled lockout. Default: 3600
+    #[serde(default = "default_login_max_lockout_secs")]
+    pub max_lockout_secs: u64,
+    /// Failures from all addresses, within `windowSecs`, that pause every password
+    /// login. 0 disables the global limit. Default: 50
+    #[serde(default = "default_login_global_max_failures")]
+    pub global_max_failures: u32,
+    /// Length of the global pause. Default: 300
+    #[serde(default = "default_login_global_lockout_secs")]
+    pub global_lockout_secs: u64,
+}
+
+fn default_login_max_failures() -> u32 {
+    5
 }
 
-impl Default for WebSearchConfig {
+fn default_login_window_secs() -> u64 {
+    900
+}
+
+fn default_login_lockout_secs() -> u64 {
+    60
+}
+
+fn default_login_max_lockout_secs() -> u64 {
+    3600
+}
+
+fn default_login_global_max_failures() -> u32 {
+    50
+}
+
+fn default_login_global_lockout_secs() -> u64 {
+    300
+}
+
+impl Default for LoginLimitConfig {
     fn default() -> Self {
         Self {
-            api_key: String::new(),
-            baidu_api_key: String::new(),
-            max_results: default_max_results(),
+            enabled: true,
+            max_failures: default_login_max_failures(),
+            window_secs: default_login_window_secs(),
+            lockout_secs: default_login_lockout_secs(),
+            max_lockout_secs: default_login_max_lockout_secs(),
+            global_max_failures: default_login_global_max_failures(),
+            global_lockout_secs: default_login_global_lockout_secs(),
         }
     }
 }
 
-fn default_max_results() -> u32 {
-    5
-}
-
+/// Dead-man's switch: jobs check in via `POST /v1/ingest/<name>` (e.g. a backup script
+/// calling `/v1/ingest/backup`); when a check-in, or the gateway's own heartbeat, is
+/// overdue an escalation is sent to `escalation`.
+///
+/// A gateway that is down cannot report itself, so a lapsed gateway heartbeat is
+/// reported once it runs again (after a crash, hang or host sleep). Clean shutdowns are
+/// not reported.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct ExecConfig {
-    #[serde(default = "default_exec_timeout")]
-    pub timeout: u32,
+pub struct DeadMansSwitchConfig {
     #[serde(default)]
-    pub restrict_to_workspace: bool,
+    pub enabled: bool,
+    /// Report gaps in the gateway's own heartbeat longer than this. 0 = off. Default: 15
+    #[serde(default = "default_dead_mans_self_window_minutes")]
+    pub self_window_minutes: u64,
+    /// Monitored check-ins keyed by name (the `:source` of `/v1/ingest/:source`).
+    #[serde(default)]
+    pub checks: HashMap<String, CheckInConfig>,
+    /// Where escalations are sent; should be a different channel from the one the
+    /// monitored jobs normally report to.
+    #[serde(default)]
+    pub escalation: EscalationTarget,
+    /// Repeat the escalation every N minutes while a check stays overdue. 0 = once per
+    /// outage. Default: 0
+    #[serde(default)]
+    pub repeat_minutes: u64,
 }
 
-impl Default for ExecConfig {
+fn default_dead_mans_self_window_minutes() -> u64 {
+    15
+}
+
+impl Default for DeadMansSwitchConfig {
     fn default() -> Self {
         Self {
-            timeout: default_exec_timeout(),
-            restrict_to_workspace: false,
+            enabled: false,
+            self_window_minutes: default_dead_mans_self_window_minutes(),
+            checks: HashMap::new(),
+            escalation: EscalationTarget::default(),
+            repeat_minutes: 0,
         }
     }
 }
 
-fn default_exec_timeout() -> u32 {
-    60
+/// One monitored check-in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckInConfig {
+    /// Escalate when no check-in arrived for this long. The clock starts when the check
+    /// is first configured, so a job that never checks in is reported too.
+    pub window_minutes: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// Channel and chat an escalation is delivered to.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct WebToolsConfig {
+pub struct EscalationTarget {
     #[serde(default)]
-    pub search: WebSearchConfig,
+    pub channel: String,
+    #[serde(default)]
+    pub account_id: Option<String>,
+    #[serde(default)]
+    pub chat_id: String,
 }
 
+/// One webhook source accepted by `POST /v1/ingest/:source`.
+///
+/// The request must carry the API token like every other `/v1` route; when `secret` is
+/// set the body must additionally be signed with HMAC-SHA256 (GitHub `X-Hub-Signature-256`,
+/// Stripe `Stripe-Signature`, or a hex digest in `signatureHeader`).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct ToolsConfig {
+pub struct IngestSourceConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// HMAC-SHA256 signing secret. Unset = no signature check.
     #[serde(default)]
-    pub web: WebToolsConfig,
+    pub secret: Option<String>,
+    /// Header carrying the signature. Defaults by source name: `X-Hub-Signature-256`
+    /// for github, `Stripe-Signature` for stripe, `X-Signature-256` otherwise.
     #[serde(default)]
-    pub exec: ExecConfig,
-    /// Tick interval in seconds for the agent runtime loop (alert checks, cron, evolution).
-    /// Lower values enable faster alert response. Default: 30. Min: 10. Max: 300.
-    #[serde(default = "default_tick_interval")]
-    pub tick_interval_secs: u32,
+    pub signature_header: Option<String>,
+    /// Message template. `{{a.b.0}}` reads a JSON path from the payload,
+    /// `{{headers.x-github-event}}` a request header, `{{source}}` the source name
+    /// and `{{payload}}` the (truncated) pretty-printed body.
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Agent that handles the event (default agent if unset).
+    #[serde(default)]
+    pub agent: Option<String>,
+    /// Channel the agent replies on. Default: `webhook` (replies show up in the WebUI).
+    #[serde(default)]
+    pub channel: Option<String>,
+    /// Chat the agent replies to. Default: `webhook_<source>`.
+    #[serde(default)]
+    pub chat_id: Option<String>,
+    /// Skill that handles every event from this source (e.g. `github_pr_review`).
+    #[serde(default)]
+    pub skill: Option<String>,
+    /// Only forward these events; others are acknowledged and dropped. The event name
+    /// comes from `X-GitHub-Event` (plus `.<action>`, e.g. `pull_request.opened`) or the
+    /// payload `type` field. Empty = forward everything.
+    #[serde(default)]
+    pub events: Vec<String>,
+    /// Longest `{{payload}}` rendering, in characters.
+    #[serde(default = "default_ingest_max_payload_chars")]
+    pub max_payload_chars: usize,
 }
 
-impl Default for ToolsConfig {
+fn default_ingest_max_payload_chars() -> usize {
+    4000
+}
+
+impl Default for IngestSourceConfig {
     fn default() -> Self {
         Self {
-            web: WebToolsConfig::default(),
-            exec: ExecConfig::default(),
-            tick_interval_secs: default_tick_interval(),
+            enabled: true,
+            secret: None,
+            signature_header: None,
+            template: None,
+            agent: None,
+            channel: None,
+            chat_id: None,
+            skill: None,
+            events: Vec::new(),
+            max_payload_chars: default_ingest_max_payload_chars(),
         }
     }
 }
 
-fn default_tick_interval() -> u32 {
-    30
+fn default_share_max_ttl_secs() -> u64 {
+    7 * 24 * 3600
 }
 
-/// Configuration for the path-access policy system.
-/// Points to the separate `path_access.json5` rules file.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct PathAccessConfig {
-    /// Whether the path-access policy system is active.
-    #[serde(default = "default_true")]
-    pub enabled: bool,
+fn default_ws_binary_min_bytes() -> usize {
+    8192
+}
+
+fn default_gateway_host() -> String {
+    "localhost".to_string()
+}
+
+fn default_gateway_port() -> u16 {
+    18790
+}
+
+fn default_webui_host() -> String {
+    "localhost".to_string()
+}
+
+fn default_webui_port() -> u16 {
+    18791
+}
+
+impl Default for GatewayConfig {
+    fn default() -> Self {
+        Self {
+            host: default_gateway_host(),
+            port: default_gateway_port(),
+            webui_host: default_webui_host(),
+            webui_port: default_webui_port(),
+            single_port: None,
+            public_api_base: None,
+            base_path: String::new(),
+            trusted_proxies: vec![],
+            api_token: None,
+            allowed_origins: vec![],
+            cors: GatewayCorsConfig::default(),
+            webui_pass: None,
+            webauthn: WebAuthnConfig::default(),
+            login_limit: LoginLimitConfig::default(),
+            share_max_ttl_secs: default_share_max_ttl_secs(),
+            ingest: HashMap::new(),
+            dead_mans_switch: DeadMansSwitchConfig::default(),
+            ws_binary_min_bytes: default_ws_binary_min_bytes(),
+        }
+    }
+}
+
+/// Lowercased host of an origin such as `https://ops.example.com:8443`.
+fn origin_host(origin: &str) -> String {
+    let authority = origin.split_once("://").map_or(origin, |(_, a)| a);
+    let authority = authority.split('/').next().unwrap_or_default();
+    let host = if let Some(v6) = authority.strip_prefix('[') {
+        v6.split(']').next().unwrap_or_default()
+    } else {
+        authority.split(':').next().unwrap_or_default()
+    };
+    host.to_ascii_lowercase()
+}
+
+impl GatewayConfig {
+    /// Whether the WebUI is mounted on the API listener bound to `api_port` (which may
+    /// come from `--port` rather than `port`).
+    pub fn is_single_port(&self, api_port: u16) -> bool {
+        self.single_port.unwrap_or(self.webui_port == api_port)
+    }
+
+    /// Origin passkeys are registered for (`webauthn.origin`, else derived).
+    pub fn webauthn_origin(&self) -> String {
+        let configured = self
+            .webauthn
+            .origin
+            .as_deref()
+            .or(self.public_api_base.as_deref())
+            .map(str::trim)
+            .filter(|o| !o.is_empty());
+        match configured {
+            Some(url) => match url.split_once("://") {
+                Some((scheme, rest)) => {
+                    let authority = rest.split('/').next().unwrap_or_default();
+                    format!("{}://{}", scheme.to_ascii_lowercase(), authority)
+                }
+                None => url.trim_end_matches('/').to_string(),
+            },
+            None => format!("http://localhost:{}", self.webui_port),
+        }
+    }
+
+    /// Relying party id (`webauthn.rpId`, else the origin's host).
+    pub fn webauthn_rp_id(&self) -> String {
+        if let Some(rp_id) = self.webauthn.rp_id.as_deref().map(str::trim) {
+            if !rp_id.is_empty() {
+                return rp_id.to_ascii_lowercase();
+            }
+        }
+        origin_host(&self.webauthn_origin())
+    }
+
+    /// `base_path` as `/a/b` (no trailing slash), or empty when served at the root.
+    pub fn normalized_base_path(&self) -> String {
+        let trimmed = self.base_path.trim().trim_matches('/');
+        if trimmed.is_empty() {
+            String::new()
+        } else {
+            format!("/{}", trimmed)
+        }
+    }
+}
+
+/// CORS for the two HTTP servers. Each is configured on its own because the WebUI only
+/// serves static files while the API is what browsers on other origins call.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayCorsConfig {
+    #[serde(default)]
+    pub api: CorsConfig,
+    #[serde(default)]
+    pub webui: CorsConfig,
+}
+
+/// CORS policy of one HTTP server.
+///
+/// With no `allowedOrigins` the policy depends on the bind address: a loopback bind
+/// (`localhost`, `127.0.0.1`, `::1`) accepts any origin, any other bind only accepts
+/// the WebUI on the same host (API) or no cross-origin requests at all (WebUI).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CorsConfig {
+    /// Exact origins such as `https://ops.example.com`; `"*"` allows any origin.
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+    /// Request headers allowed in addition to `Authorization` and `Content-Type`;
+    /// `"*"` allows any header.
+    #[serde(default)]
+    pub allowed_headers: Vec<String>,
+    /// Send `Access-Control-Allow-Credentials: true`. Cannot be combined with `"*"`.
+    #[serde(default)]
+    pub allow_credentials: bool,
+    /// How long browsers may cache a preflight response. Default: 600
+    #[serde(default = "default_cors_max_age_secs")]
+    pub max_age_secs: u64,
+}
+
+fn default_cors_max_age_secs() -> u64 {
+    600
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            allowed_headers: Vec::new(),
+            allow_credentials: false,
+            max_age_secs: default_cors_max_age_secs(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebSearchConfig {
+    /// Brave Search API key (optional, for brave_search)
+    #[serde(default)]
+    pub api_key: String,
+    /// Baidu AI Search API key (optional, for baidu_search)
+    /// Get from https://qianfan.baidubce.com — set env BAIDU_API_KEY or this field
+    #[serde(default)]
+    pub baidu_api_key: String,
+    /// SearXNG instance base URL (optional, self-hosted), e.g. `http://127.0.0.1:8888`.
+    /// The instance must have `json` enabled in `search.formats`. Env fallback: SEARXNG_URL
+    #[serde(default)]
+    pub searxng_url: String,
+    /// Bing Web Search API key (optional). Env fallback: BING_API_KEY
+    #[serde(default)]
+    pub bing_api_key: String,
+    /// Engine fallback order, e.g. `["searxng", "brave", "bing", "baidu"]`.
+    /// Empty = automatic (Chinese queries prefer Baidu, others prefer Brave).
+    #[serde(default)]
+    pub engines: Vec<String>,
+    #[serde(default = "default_max_results")]
+    pub max_results: u32,
+}
+
+impl Default for WebSearchConfig {
+    fn default() -> Self {
+        Self {
+            api_key: String::new(),
+            baidu_api_key: String::new(),
+            searxng_url: String::new(),
+            bing_api_key: String::new(),
+            engines: Vec::new(),
+            max_results: default_max_results(),
+        }
+    }
+}
+
+fn default_max_results() -> u32 {
+    5
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecConfig {
+    #[serde(default = "default_exec_timeout")]
+    pub timeout: u32,
+    #[serde(default)]
+    pub restrict_to_workspace: bool,
+    #[serde(default)]
+    pub sandbox: ExecSandboxConfig,
+}
+
+impl Default for ExecConfig {
+    fn default() -> Self {
+        Self {
+            timeout: default_exec_timeout(),
+            restrict_to_workspace: false,
+            sandbox: ExecSandboxConfig::default(),
+        }
+    }
+}
+
+fn default_exec_timeout() -> u32 {
+    60
+}
+
+/// Where an `exec` command runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SandboxBackend {
+    /// Directly on the host.
+    #[default]
+    None,
+    /// A throwaway Docker container.
+    Docker,
+    /// Linux user/mount/pid/network namespaces via bubblewrap (`bwrap`).
+    Namespaces,
+    /// firejail.
+    Firejail,
+}
+
+impl SandboxBackend {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Docker => "docker",
+            Self::Namespaces => "namespaces",
+            Self::Firejail => "firejail",
+        }
+    }
+}
+
+/// Sandboxing of the `exec` tool (`tools.exec.sandbox`).
+///
+/// The backend is picked by the privilege of the caller: admins get `full`, `user`
+/// members `limited`, `readonly` members `readOnly`. Unattended runs (cron, heartbeat,
+/// ghost, subagents) never get more than `limited`. Sandboxed commands only see the
+/// workspace, mounted read-only for `readOnly`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecSandboxConfig {
+    #[serde(default)]
+    pub read_only: SandboxBackend,
+    #[serde(default)]
+    pub limited: SandboxBackend,
+    #[serde(default)]
+    pub full: SandboxBackend,
+    /// Image for the `docker` backend.
+    #[serde(default = "default_sandbox_image")]
+    pub image: String,
+    /// Allow network access inside the sandbox.
+    #[serde(default)]
+    pub network: bool,
+    /// CPU cores (docker) available to a command.
+    #[serde(default = "default_sandbox_cpus")]
+    pub cpus: f64,
+    /// Memory limit in MiB (address space for `namespaces` and `firejail`).
+    #[serde(default = "default_sandbox_memory_mb")]
+    pub memory_mb: u64,
+    /// Maximum number of processes.
+    #[serde(default = "default_sandbox_max_processes")]
+    pub max_processes: u32,
+}
+
+impl Default for ExecSandboxConfig {
+    fn default() -> Self {
+        Self {
+            read_only: SandboxBackend::None,
+            limited: SandboxBackend::None,
+            full: SandboxBackend::None,
+            image: default_sandbox_image(),
+            network: false,
+            cpus: default_sandbox_cpus(),
+            memory_mb: default_sandbox_memory_mb(),
+            max_processes: default_sandbox_max_processes(),
+        }
+    }
+}
+
+impl ExecSandboxConfig {
+    pub fn backend_for(&self, level: &crate::capability::PrivilegeLevel) -> SandboxBackend {
+        use crate::capability::PrivilegeLevel;
+        match level {
+            PrivilegeLevel::ReadOnly => self.read_only,
+            PrivilegeLevel::Limited => self.limited,
+            PrivilegeLevel::Full => self.full,
+        }
+    }
+}
+
+fn default_sandbox_image() -> String {
+    "debian:bookworm-slim".to_string()
+}
+
+fn default_sandbox_cpus() -> f64 {
+    1.0
+}
+
+fn default_sandbox_memory_mb() -> u64 {
+    512
+}
+
+fn default_sandbox_max_processes() -> u32 {
+    256
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WebToolsConfig {
+    #[serde(default)]
+    pub search: WebSearchConfig,
+}
+
+/// A named database connection for the `db_query` tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DbConnectionConfig {
+    /// `"postgres"` | `"mysql"` | `"sqlite"`. Inferred from the DSN scheme when empty.
+    #[serde(default)]
+    pub driver: String,
+    /// Connection string, e.g. `postgres://user@host:5432/db`, `mysql://user@host/db`,
+    /// `sqlite:///path/to.db`. Use `secret:<name>` to keep credentials in the secrets store.
+    #[serde(default)]
+    pub dsn: String,
+    /// Reject anything but read statements. Default: true
+    #[serde(default = "default_true")]
+    pub read_only: bool,
+    /// Max rows returned per page. Default: 200
+    #[serde(default = "default_db_max_rows")]
+    pub max_rows: u32,
+    /// Query timeout in seconds. Default: 30
+    #[serde(default = "default_db_timeout")]
+    pub timeout_secs: u32,
+    /// Human description shown to the agent (what lives in this database).
+    #[serde(default)]
+    pub description: String,
+}
+
+impl Default for DbConnectionConfig {
+    fn default() -> Self {
+        Self {
+            driver: String::new(),
+            dsn: String::new(),
+            read_only: true,
+            max_rows: default_db_max_rows(),
+            timeout_secs: default_db_timeout(),
+            description: String::new(),
+        }
+    }
+}
+
+fn default_db_max_rows() -> u32 {
+    200
+}
+
+fn default_db_timeout() -> u32 {
+    30
+}
+
+/// Settings for the `db_query` tool.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DbQueryConfig {
+    /// Named connection profiles.
+    #[serde(default)]
+    pub connections: HashMap<String, DbConnectionConfig>,
+    /// Profile used when the call does not name one.
+    #[serde(default)]
+    pub default_connection: Option<String>,
+}
+
+/// An S3-compatible storage target (AWS S3, MinIO, Cloudflare R2, …) for `object_storage`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectStorageProfile {
+    /// Endpoint URL, e.g. `https://<account>.r2.cloudflarestorage.com` or `http://minio:9000`.
+    /// Empty means AWS S3 for `region`.
+    #[serde(default)]
+    pub endpoint: String,
+    /// Signing region. Default: `us-east-1` (R2 uses `auto`).
+    #[serde(default)]
+    pub region: String,
+    /// Bucket used when the call does not name one.
+    #[serde(default)]
+    pub bucket: String,
+    /// Access key id. Accepts `secret:<name>` / `env:<VAR>`.
+    #[serde(default)]
+    pub access_key_id: String,
+    /// Secret access key. Accepts `secret:<name>` / `env:<VAR>`.
+    #[serde(default)]
+    pub secret_access_key: String,
+    /// Address buckets as `endpoint/bucket/key` instead of `bucket.endpoint/key`.
+    /// Defaults to true for custom endpoints, false for AWS.
+    #[serde(default)]
+    pub path_style: Option<bool>,
+    /// Key prefix prepended to every uploaded key, e.g. `reports/`.
+    #[serde(default)]
+    pub prefix: String,
+    /// Public base URL for objects (CDN / public bucket). When set, uploads return `public_url`.
+    #[serde(default)]
+    pub public_base_url: Option<String>,
+}
+
+/// Settings for the `object_storage` tool.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectStorageConfig {
+    /// Named storage profiles.
+    #[serde(default)]
+    pub profiles: HashMap<String, ObjectStorageProfile>,
+    /// Profile used when the call does not name one.
+    #[serde(default)]
+    pub default_profile: Option<String>,
+}
+
+/// A named remote host for the `ssh` tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SshHostConfig {
+    pub host: String,
+    #[serde(default = "default_ssh_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub user: String,
+    /// Private key: a file path, or `secret:<name>` holding either a path or the PEM itself.
+    /// Empty uses the ssh agent / default keys.
+    #[serde(default)]
+    pub identity: String,
+    /// known_hosts file. Empty uses the system default.
+    #[serde(default)]
+    pub known_hosts: String,
+    /// Refuse unknown host keys. When false, new keys are accepted on first use.
+    #[serde(default = "default_true")]
+    pub strict_host_key_checking: bool,
+    /// Command patterns allowed on this host (`*` wildcard, e.g. `"systemctl status *"`).
+    /// Commands that match nothing are rejected. `["*"]` allows anything.
+    #[serde(default)]
+    pub allow_commands: Vec<String>,
+    /// Allowed commands that still need user confirmation before running.
+    #[serde(default)]
+    pub confirm_commands: Vec<String>,
+    /// Require confirmation for every command on this host.
+    #[serde(default)]
+    pub confirm_all: bool,
+    /// Allow `put` uploads (always confirmed).
+    #[serde(default)]
+    pub allow_upload: bool,
+    /// Remote directory prefixes reachable by `get`/`put`. Empty allows any path.
+    #[serde(default)]
+    pub remote_dirs: Vec<String>,
+    /// Default: 60
+    #[serde(default = "default_ssh_timeout")]
+    pub timeout_secs: u32,
+    #[serde(default)]
+    pub description: String,
+}
+
+fn default_ssh_port() -> u16 {
+    22
+}
+
+fn default_ssh_timeout() -> u32 {
+    60
+}
+
+/// Settings for the `ssh` tool.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SshConfig {
+    #[serde(default)]
+    pub hosts: HashMap<String, SshHostConfig>,
+    #[serde(default)]
+    pub default_host: Option<String>,
+}
+
+/// Settings for the `docker` tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DockerConfig {
+    /// Engine socket path or `unix://` URL. Empty tries `DOCKER_HOST`, `/var/run/docker.sock`,
+    /// then the rootless Podman socket.
+    #[serde(default)]
+    pub socket: String,
+    /// Ask for confirmation before start/stop/restart. Default: true
+    #[serde(default = "default_true")]
+    pub confirm_lifecycle: bool,
+    /// Upper bound for `logs` tail. Default: 2000
+    #[serde(default = "default_docker_max_log_lines")]
+    pub max_log_lines: u32,
+}
+
+impl Default for DockerConfig {
+    fn default() -> Self {
+        Self {
+            socket: String::new(),
+            confirm_lifecycle: true,
+            max_log_lines: default_docker_max_log_lines(),
+        }
+    }
+}
+
+fn default_docker_max_log_lines() -> u32 {
+    2000
+}
+
+/// Settings for the `kubectl` tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KubernetesConfig {
+    /// kubeconfig path. Empty uses `KUBECONFIG` / `~/.kube/config`, or in-cluster credentials.
+    #[serde(default)]
+    pub kubeconfig: String,
+    /// Context used when the call does not name one. Empty uses the kubeconfig's current context.
+    #[serde(default)]
+    pub context: Option<String>,
+    /// Default: "default"
+    #[serde(default = "default_k8s_namespace")]
+    pub default_namespace: String,
+    /// Only these namespaces may be queried. Empty allows all.
+    #[serde(default)]
+    pub allowed_namespaces: Vec<String>,
+    /// Enable `scale` and `rollout_restart` (always confirmed). Default: false
+    #[serde(default)]
+    pub allow_mutations: bool,
+    /// Upper bound for `logs` tail. Default: 1000
+    #[serde(default = "default_k8s_max_log_lines")]
+    pub max_log_lines: u32,
+}
+
+impl Default for KubernetesConfig {
+    fn default() -> Self {
+        Self {
+            kubeconfig: String::new(),
+            context: None,
+            default_namespace: default_k8s_namespace(),
+            allowed_namespaces: Vec::new(),
+            allow_mutations: false,
+            max_log_lines: default_k8s_max_log_lines(),
+        }
+    }
+}
+
+fn default_k8s_namespace() -> String {
+    "default".to_string()
+}
+
+fn default_k8s_max_log_lines() -> u32 {
+    1000
+}
+
+/// LAN discovery settings for `network_monitor` (`lan_scan`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanScanConfig {
+    /// IPv4 CIDRs to sweep, e.g. `["192.168.1.0/24"]`. Empty uses the primary interface's /24.
+    #[serde(default)]
+    pub subnets: Vec<String>,
+    /// Refuse subnets larger than this many hosts. Default: 1024
+    #[serde(default = "default_lan_max_hosts")]
+    pub max_hosts: u32,
+    /// Per-host probe timeout. Default: 600
+    #[serde(default = "default_lan_probe_timeout_ms")]
+    pub probe_timeout_ms: u64,
+    /// How long to listen for mDNS/SSDP replies. Default: 3
+    #[serde(default = "default_lan_discovery_secs")]
+    pub discovery_secs: u64,
+    /// Raise a system event when an untrusted device first appears. Default: true
+    #[serde(default = "default_true")]
+    pub alert_on_new_device: bool,
+}
+
+impl Default for LanScanConfig {
+    fn default() -> Self {
+        Self {
+            subnets: Vec::new(),
+            max_hosts: default_lan_max_hosts(),
+            probe_timeout_ms: default_lan_probe_timeout_ms(),
+            discovery_secs: default_lan_discovery_secs(),
+            alert_on_new_device: true,
+        }
+    }
+}
+
+fn default_lan_max_hosts() -> u32 {
+    1024
+}
+
+fn default_lan_probe_timeout_ms() -> u64 {
+    600
+}
+
+fn default_lan_discovery_secs() -> u64 {
+    3
+}
+
+/// Settings for the `translate` tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslateConfig {
+    /// Default backend: `"llm"` | `"deepl"` | `"google"`. Default: llm
+    #[serde(default = "default_translate_backend")]
+    pub backend: String,
+    /// Target language used when the caller does not specify one. Default: zh
+    #[serde(default = "default_translate_target")]
+    pub default_target: String,
+    /// Model for the LLM backend. Empty = use the agent's model.
+    #[serde(default)]
+    pub model: String,
+    /// Provider for `model` (optional, inferred from the model name when omitted).
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// DeepL API key. Env fallback: DEEPL_API_KEY
+    #[serde(default)]
+    pub deepl_api_key: String,
+    /// Google Cloud Translation API key. Env fallback: GOOGLE_TRANSLATE_API_KEY
+    #[serde(default)]
+    pub google_api_key: String,
+}
+
+impl Default for TranslateConfig {
+    fn default() -> Self {
+        Self {
+            backend: default_translate_backend(),
+            default_target: default_translate_target(),
+            model: String::new(),
+            provider: None,
+            deepl_api_key: String::new(),
+            google_api_key: String::new(),
+        }
+    }
+}
+
+fn default_translate_backend() -> String {
+    "llm".to_string()
+}
+
+fn default_translate_target() -> String {
+    "zh".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolsConfig {
+    #[serde(default)]
+    pub web: WebToolsConfig,
+    #[serde(default)]
+    pub exec: ExecConfig,
+    #[serde(default)]
+    pub translate: TranslateConfig,
+    #[serde(default)]
+    pub db: DbQueryConfig,
+    #[serde(default)]
+    pub object_storage: ObjectStorageConfig,
+    #[serde(default)]
+    pub ssh: SshConfig,
+    #[serde(default)]
+    pub docker: DockerConfig,
+    #[serde(default)]
+    pub kubernetes: KubernetesConfig,
+    #[serde(default)]
+    pub lan: LanScanConfig,
+    #[serde(default)]
+    pub egress: EgressConfig,
+    #[serde(default)]
+    pub todo: TodoConfig,
+    #[serde(default)]
+    pub github: GitHubConfig,
+    #[serde(default)]
+    pub calendar: CalendarConfig,
+    #[serde(default)]
+    pub python: PythonConfig,
+    #[serde(default)]
+    pub weather: WeatherConfig,
+    #[serde(default)]
+    pub travel: TravelConfig,
+    #[serde(default)]
+    pub parcel: ParcelConfig,
+    #[serde(default)]
+    pub nutrition: NutritionConfig,
+    #[serde(default)]
+    pub bookmarks: BookmarksConfig,
+    #[serde(default)]
+    pub birthdays: BirthdaysConfig,
+    #[serde(default)]
+    pub habits: HabitsConfig,
+    #[serde(default)]
+    pub media_control: MediaControlConfig,
+    #[serde(default)]
+    pub browser: BrowserConfig,
+    /// Tick interval in seconds for the agent runtime loop (alert checks, cron, evolution).
+    /// Lower values enable faster alert response. Default: 30. Min: 10. Max: 300.
+    #[serde(default = "default_tick_interval")]
+    pub tick_interval_secs: u32,
+}
+
+impl Default for ToolsConfig {
+    fn default() -> Self {
+        Self {
+            web: WebToolsConfig::default(),
+            exec: ExecConfig::default(),
+            translate: TranslateConfig::default(),
+            db: DbQueryConfig::default(),
+            object_storage: ObjectStorageConfig::default(),
+            ssh: SshConfig::default(),
+            docker: DockerConfig::default(),
+            kubernetes: KubernetesConfig::default(),
+            lan: LanScanConfig::default(),
+            egress: EgressConfig::default(),
+            todo: TodoConfig::default(),
+            github: GitHubConfig::default(),
+            calendar: CalendarConfig::default(),
+            python: PythonConfig::default(),
+            weather: WeatherConfig::default(),
+            travel: TravelConfig::default(),
+            parcel: ParcelConfig::default(),
+            nutrition: NutritionConfig::default(),
+            bookmarks: BookmarksConfig::default(),
+            birthdays: BirthdaysConfig::default(),
+            habits: HabitsConfig::default(),
+            media_control: MediaControlConfig::default(),
+            browser: BrowserConfig::default(),
+            tick_interval_secs: default_tick_interval(),
+        }
+    }
+}
+
+/// Settings for the `git_api` tool (GitHub REST API).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitHubConfig {
+    /// Personal access token; may be a `secret:`/`env:` reference. Env fallback: GITHUB_TOKEN
+    #[serde(default)]
+    pub token: String,
+    /// API base URL (GitHub Enterprise: `https://host/api/v3`). Default: https://api.github.com
+    #[serde(default = "default_github_api_base")]
+    pub api_base: String,
+    /// `owner/repo` (or `owner/*`) the tool may touch. Empty = any repository.
+    #[serde(default)]
+    pub repos: Vec<String>,
+    /// Ask for confirmation before reviews, comments, labels and assignments. Default: true
+    #[serde(default = "default_true")]
+    pub confirm_writes: bool,
+    /// Longest PR diff returned to the model, in characters. Default: 60000
+    #[serde(default = "default_github_max_diff_chars")]
+    pub max_diff_chars: usize,
+}
+
+fn default_github_api_base() -> String {
+    "https://api.github.com".to_string()
+}
+
+fn default_github_max_diff_chars() -> usize {
+    60_000
+}
+
+impl Default for GitHubConfig {
+    fn default() -> Self {
+        Self {
+            token: String::new(),
+            api_base: default_github_api_base(),
+            repos: Vec::new(),
+            confirm_writes: true,
+            max_diff_chars: default_github_max_diff_chars(),
+        }
+    }
+}
+
+/// Settings for the `python` code interpreter tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PythonConfig {
+    /// `"venv"` (local interpreter in a dedicated virtualenv) or `"docker"` (throwaway
+    /// container without network). Default: venv
+    #[serde(default = "default_python_backend")]
+    pub backend: String,
+    /// Interpreter used to create the venv. Default: python3
+    #[serde(default = "default_python_interpreter")]
+    pub interpreter: String,
+    /// Image for the docker backend. Default: python:3.12-slim
+    #[serde(default = "default_python_image")]
+    pub image: String,
+    /// Wall-clock limit per call. Default: 60
+    #[serde(default = "default_python_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Address-space (venv) or container (docker) memory limit. Default: 1024
+    #[serde(default = "default_python_memory_mb")]
+    pub memory_mb: u64,
+    /// Packages `install` may add (PyPI names, case-insensitive).
+    #[serde(default = "default_python_packages")]
+    pub allowed_packages: Vec<String>,
+    /// Longest stdout/stderr returned to the model, in characters. Default: 20000
+    #[serde(default = "default_python_max_output_chars")]
+    pub max_output_chars: usize,
+    /// Persistent sessions kept alive at once; the least recently used one is stopped
+    /// when a new session would exceed this. Default: 4
+    #[serde(default = "default_python_max_sessions")]
+    pub max_sessions: usize,
+    /// Sessions idle for longer than this are stopped. Default: 1800
+    #[serde(default = "default_python_session_idle_secs")]
+    pub session_idle_secs: u64,
+}
+
+fn default_python_backend() -> String {
+    "venv".to_string()
+}
+
+fn default_python_interpreter() -> String {
+    "python3".to_string()
+}
+
+fn default_python_image() -> String {
+    "python:3.12-slim".to_string()
+}
+
+fn default_python_timeout_secs() -> u64 {
+    60
+}
+
+fn default_python_memory_mb() -> u64 {
+    1024
+}
+
+fn default_python_packages() -> Vec<String> {
+    [
+        "numpy",
+        "pandas",
+        "matplotlib",
+        "scipy",
+        "seaborn",
+        "openpyxl",
+        "statsmodels",
+        "scikit-learn",
+        "sympy",
+        "tabulate",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+fn default_python_max_output_chars() -> usize {
+    20_000
+}
+
+fn default_python_max_sessions() -> usize {
+    4
+}
+
+fn default_python_session_idle_secs() -> u64 {
+    1800
+}
+
+impl Default for PythonConfig {
+    fn default() -> Self {
+        Self {
+            backend: default_python_backend(),
+            interpreter: default_python_interpreter(),
+            image: default_python_image(),
+            timeout_secs: default_python_timeout_secs(),
+            memory_mb: default_python_memory_mb(),
+            allowed_packages: default_python_packages(),
+            max_output_chars: default_python_max_output_chars(),
+            max_sessions: default_python_max_sessions(),
+            session_idle_secs: default_python_session_idle_secs(),
+        }
+    }
+}
+
+/// Settings for the `weather` tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeatherConfig {
+    /// `"open-meteo"` (keyless) or `"openweather"`. The other provider is tried when the
+    /// preferred one fails. Default: open-meteo
+    #[serde(default = "default_weather_provider")]
+    pub provider: String,
+    /// OpenWeather API key; may be a `secret:`/`env:` reference. Env fallback: OPENWEATHER_API_KEY
+    #[serde(default)]
+    pub openweather_api_key: String,
+    /// `"metric"` or `"imperial"`. Default: metric
+    #[serde(default = "default_weather_units")]
+    pub units: String,
+    /// How long a forecast is reused before the provider is asked again. Default: 30
+    #[serde(default = "default_weather_cache_minutes")]
+    pub cache_minutes: u32,
+    /// Place used when no location is given; also adds a weather line to the morning
+    /// TODO digest. Empty = none.
+    #[serde(default)]
+    pub default_location: String,
+}
+
+fn default_weather_provider() -> String {
+    "open-meteo".to_string()
+}
+
+fn default_weather_units() -> String {
+    "metric".to_string()
+}
+
+fn default_weather_cache_minutes() -> u32 {
+    30
+}
+
+impl Default for WeatherConfig {
+    fn default() -> Self {
+        Self {
+            provider: default_weather_provider(),
+            openweather_api_key: String::new(),
+            units: default_weather_units(),
+            cache_minutes: default_weather_cache_minutes(),
+            default_location: String::new(),
+        }
+    }
+}
+
+/// Settings for the `travel` tool (flight and train status, trip watchers).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TravelConfig {
+    /// aviationstack access key; may be a `secret:`/`env:` reference. Env fallback:
+    /// AVIATIONSTACK_API_KEY. Flight lookups are unavailable without it.
+    #[serde(default)]
+    pub aviationstack_api_key: String,
+    /// aviationstack API base. Free plans only serve plain HTTP. Default: https://api.aviationstack.com/v1
+    #[serde(default = "default_aviationstack_base")]
+    pub aviationstack_base: String,
+    /// HAFAS REST endpoint (db-rest compatible) for train status. Empty disables trains.
+    /// Default: https://v6.db.transport.rest
+    #[serde(default = "default_train_api_base")]
+    pub train_api_base: String,
+    /// Minutes between checks of an active trip watcher. Default: 15
+    #[serde(default = "default_travel_poll_minutes")]
+    pub poll_minutes: u32,
+    /// Delay changes smaller than this do not trigger an alert. Default: 15
+    #[serde(default = "default_travel_delay_threshold")]
+    pub delay_threshold_minutes: i64,
+}
+
+fn default_aviationstack_base() -> String {
+    "https://api.aviationstack.com/v1".to_string()
+}
+
+fn default_train_api_base() -> String {
+    "https://v6.db.transport.rest".to_string()
+}
+
+fn default_travel_poll_minutes() -> u32 {
+    15
+}
+
+fn default_travel_delay_threshold() -> i64 {
+    15
+}
+
+impl Default for TravelConfig {
+    fn default() -> Self {
+        Self {
+            aviationstack_api_key: String::new(),
+            aviationstack_base: default_aviationstack_base(),
+            train_api_base: default_train_api_base(),
+            poll_minutes: default_travel_poll_minutes(),
+            delay_threshold_minutes: default_travel_delay_threshold(),
+        }
+    }
+}
+
+/// Settings for the `parcel_track` tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParcelConfig {
+    /// Tracking API: `"17track"` or `"aftership"`. Default: 17track
+    #[serde(default = "default_parcel_provider")]
+    pub provider: String,
+    /// Provider API key; may be a `secret:`/`env:` reference. Env fallback:
+    /// SEVENTEENTRACK_API_KEY or AFTERSHIP_API_KEY, depending on the provider.
+    #[serde(default)]
+    pub api_key: String,
+    /// API base override (self-hosted proxies, sandboxes). Empty = the provider's default.
+    #[serde(default)]
+    pub api_base: String,
+    /// Minutes between status polls of an undelivered parcel. Default: 60
+    #[serde(default = "default_parcel_poll_minutes")]
+    pub poll_minutes: u32,
+}
+
+fn default_parcel_provider() -> String {
+    "17track".to_string()
+}
+
+fn default_parcel_poll_minutes() -> u32 {
+    60
+}
+
+impl Default for ParcelConfig {
+    fn default() -> Self {
+        Self {
+            provider: default_parcel_provider(),
+            api_key: String::new(),
+            api_base: String::new(),
+            poll_minutes: default_parcel_poll_minutes(),
+        }
+    }
+}
+
+/// Settings for the `nutrition` tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NutritionConfig {
+    /// USDA FoodData Central key; may be a `secret:`/`env:` reference. Env fallback:
+    /// USDA_FDC_API_KEY. Without it, name lookups use Open Food Facts only.
+    #[serde(default)]
+    pub usda_api_key: String,
+    /// Default: https://world.openfoodfacts.org
+    #[serde(default = "default_open_food_facts_base")]
+    pub open_food_facts_base: String,
+    /// Default: https://api.nal.usda.gov/fdc/v1
+    #[serde(default = "default_usda_base")]
+    pub usda_base: String,
+    /// Daily energy goal shown next to summaries, in kcal. 0 = none. Default: 0
+    #[serde(default)]
+    pub daily_kcal_target: u32,
+}
+
+fn default_open_food_facts_base() -> String {
+    "https://world.openfoodfacts.org".to_string()
+}
+
+fn default_usda_base() -> String {
+    "https://api.nal.usda.gov/fdc/v1".to_string()
+}
+
+impl Default for NutritionConfig {
+    fn default() -> Self {
+        Self {
+            usda_api_key: String::new(),
+            open_food_facts_base: default_open_food_facts_base(),
+            usda_base: default_usda_base(),
+            daily_kcal_target: 0,
+        }
+    }
+}
+
+/// Settings for the `bookmarks` tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookmarksConfig {
+    /// Summarize saved pages with the default model. Default: true
+    #[serde(default = "default_true")]
+    pub summarize: bool,
+    /// Also store each bookmark (title, tags, summary) as a memory item. Default: true
+    #[serde(default = "default_true")]
+    pub save_to_memory: bool,
+    /// Longest page text kept for search, in characters. Default: 40000
+    #[serde(default = "default_bookmarks_max_chars")]
+    pub max_content_chars: usize,
+    /// Send a weekly "saved but not read" digest. Default: true
+    #[serde(default = "default_true")]
+    pub weekly_digest: bool,
+    /// Day of the digest: `mon`..`sun`. Default: sun
+    #[serde(default = "default_bookmarks_digest_day")]
+    pub digest_day: String,
+    /// Local hour (0-23) at or after which the digest is sent. Default: 18
+    #[serde(default = "default_bookmarks_digest_hour")]
+    pub digest_hour: u32,
+}
+
+fn default_bookmarks_max_chars() -> usize {
+    40_000
+}
+
+fn default_bookmarks_digest_day() -> String {
+    "sun".to_string()
+}
+
+fn default_bookmarks_digest_hour() -> u32 {
+    18
+}
+
+impl Default for BookmarksConfig {
+    fn default() -> Self {
+        Self {
+            summarize: true,
+            save_to_memory: true,
+            max_content_chars: default_bookmarks_max_chars(),
+            weekly_digest: true,
+            digest_day: default_bookmarks_digest_day(),
+            digest_hour: default_bookmarks_digest_hour(),
+        }
+    }
+}
+
+/// Settings for the `birthdays` tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BirthdaysConfig {
+    /// Days before each occasion to remind; 0 = on the day. Default: [0, 3]
+    #[serde(default = "default_birthday_lead_days")]
+    pub lead_days: Vec<u32>,
+    /// Local hour (0-23) at which reminders fire. Default: 9
+    #[serde(default = "default_birthday_remind_hour")]
+    pub remind_hour: u32,
+    /// On the day itself, have the agent draft a greeting and ask before sending it
+    /// (instead of a plain reminder). Default: true
+    #[serde(default = "default_true")]
+    pub draft_messages: bool,
+}
+
+fn default_birthday_lead_days() -> Vec<u32> {
+    vec![0, 3]
+}
+
+fn default_birthday_remind_hour() -> u32 {
+    9
+}
+
+impl Default for BirthdaysConfig {
+    fn default() -> Self {
+        Self {
+            lead_days: default_birthday_lead_days(),
+            remind_hour: default_birthday_remind_hour(),
+            draft_messages: true,
+        }
+    }
+}
+
+/// Settings for the `habits` tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HabitsConfig {
+    /// Send a check-in prompt for habits still open at their check-in time. Default: true
+    #[serde(default = "default_true")]
+    pub checkin_prompts: bool,
+    /// Check-in time (`HH:MM`, local) for habits that do not set their own. Default: 20:00
+    #[serde(default = "default_habit_checkin_time")]
+    pub default_checkin_time: String,
+    /// Add a habits section to the morning TODO digest. Default: true
+    #[serde(default = "default_true")]
+    pub in_digest: bool,
+}
+
+fn default_habit_checkin_time() -> String {
+    "20:00".to_string()
+}
+
+impl Default for HabitsConfig {
+    fn default() -> Self {
+        Self {
+            checkin_prompts: true,
+            default_checkin_time: default_habit_checkin_time(),
+            in_digest: true,
+        }
+    }
+}
+
+/// Settings for the `media_control` tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaControlConfig {
+    /// `"auto"` (local player, falling back to Spotify Connect when configured), `"local"`
+    /// or `"spotify"`. Default: auto
+    #[serde(default = "default_media_backend")]
+    pub backend: String,
+    /// Preferred local player: an MPRIS name on Linux (`spotify`, `vlc`), an app name on
+    /// macOS (`Music`, `Spotify`). Empty = whichever player is active.
+    #[serde(default)]
+    pub player: String,
+    /// Spotify app client id; may be a `secret:`/`env:` reference. Env fallback: SPOTIFY_CLIENT_ID
+    #[serde(default)]
+    pub spotify_client_id: String,
+    /// Spotify app client secret; may be a `secret:`/`env:` reference. Env fallback:
+    /// SPOTIFY_CLIENT_SECRET
+    #[serde(default)]
+    pub spotify_client_secret: String,
+    /// OAuth refresh token with the `user-modify-playback-state` and
+    /// `user-read-playback-state` scopes. Env fallback: SPOTIFY_REFRESH_TOKEN
+    #[serde(default)]
+    pub spotify_refresh_token: String,
+    /// Spotify Connect device name or id to control. Empty = the active device.
+    #[serde(default)]
+    pub spotify_device: String,
+}
+
+fn default_media_backend() -> String {
+    "auto".to_string()
+}
+
+impl Default for MediaControlConfig {
+    fn default() -> Self {
+        Self {
+            backend: default_media_backend(),
+            player: String::new(),
+            spotify_client_id: String::new(),
+            spotify_client_secret: String::new(),
+            spotify_refresh_token: String::new(),
+            spotify_device: String::new(),
+        }
+    }
+}
+
+/// Settings for the `browse` tool.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrowserConfig {
+    /// Return screen-reader style page summaries (landmarks, headings outline, forms,
+    /// grouped links) instead of the raw accessibility tree by default, for users who
+    /// browse by listening. Per call: `mode`. Default: false
+    #[serde(default)]
+    pub accessible_mode: bool,
+}
+
+/// One iCalendar feed read by the `calendar` tool.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarSource {
+    pub name: String,
+    /// `https://` ICS URL (e.g. a Google/Outlook "secret address"), a local `.ics` path,
+    /// or a `secret:`/`env:` reference to either.
+    pub url: String,
+}
+
+/// Settings for the `calendar` tool (free/busy, conflict checks, slot suggestions).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarConfig {
+    #[serde(default)]
+    pub sources: Vec<CalendarSource>,
+    /// Local working hours used when proposing times, `HH:MM`. Default: 09:00-18:00
+    #[serde(default = "default_calendar_work_start")]
+    pub work_start: String,
+    #[serde(default = "default_calendar_work_end")]
+    pub work_end: String,
+    /// Propose weekend slots too. Default: false
+    #[serde(default)]
+    pub include_weekends: bool,
+    /// Minutes a blockcell reminder or due TODO occupies. Default: 15
+    #[serde(default = "default_calendar_reminder_minutes")]
+    pub reminder_minutes: u32,
+    /// How long fetched feeds are reused. Default: 10 minutes
+    #[serde(default = "default_calendar_cache_minutes")]
+    pub cache_minutes: u32,
+}
+
+fn default_calendar_work_start() -> String {
+    "09:00".to_string()
+}
+
+fn default_calendar_work_end() -> String {
+    "18:00".to_string()
+}
+
+fn default_calendar_reminder_minutes() -> u32 {
+    15
+}
+
+fn default_calendar_cache_minutes() -> u32 {
+    10
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        Self {
+            sources: Vec::new(),
+            work_start: default_calendar_work_start(),
+            work_end: default_calendar_work_end(),
+            include_weekends: false,
+            reminder_minutes: default_calendar_reminder_minutes(),
+            cache_minutes: default_calendar_cache_minutes(),
+        }
+    }
+}
+
+/// Personal TODO list (`todo` tool). The morning digest lists overdue and due-today
+/// items once a day, at or after `digestHour` in `defaultTimezone`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TodoConfig {
+    #[serde(default = "default_true")]
+    pub morning_digest: bool,
+    /// Local hour (0-23) from which the digest may be sent.
+    #[serde(default = "default_todo_digest_hour")]
+    pub digest_hour: u32,
+}
+
+fn default_todo_digest_hour() -> u32 {
+    8
+}
+
+impl Default for TodoConfig {
+    fn default() -> Self {
+        Self {
+            morning_digest: true,
+            digest_hour: default_todo_digest_hour(),
+        }
+    }
+}
+
+/// Outbound network policy for tools. Entries are domains (`example.com` also matches
+/// subdomains, `*.example.com` only subdomains, `*` everything), IPs or CIDR ranges.
+/// Deny entries win over allow entries; unmatched hosts fall back to `defaultAction`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EgressConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// `"allow"` or `"deny"` for hosts no rule matches.
+    #[serde(default = "default_egress_action")]
+    pub default_action: String,
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub deny: Vec<String>,
+    /// Per-tool rules keyed by tool name.
+    #[serde(default)]
+    pub tools: HashMap<String, ToolEgressRule>,
+}
+
+fn default_egress_action() -> String {
+    "allow".to_string()
+}
+
+impl Default for EgressConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            default_action: default_egress_action(),
+            allow: Vec::new(),
+            deny: Vec::new(),
+            tools: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolEgressRule {
+    /// Overrides the global `defaultAction` for this tool.
+    #[serde(default)]
+    pub default_action: Option<String>,
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub deny: Vec<String>,
+    /// Also apply the global allow/deny lists (default: true).
+    #[serde(default = "default_true")]
+    pub inherit: bool,
+}
+
+impl Default for ToolEgressRule {
+    fn default() -> Self {
+        Self {
+            default_action: None,
+            allow: Vec::new(),
+            deny: Vec::new(),
+            inherit: true,
+        }
+    }
+}
+
+fn default_tick_interval() -> u32 {
+    30
+}
+
+/// Configuration for the path-access policy system.
+/// Points to the separate `path_access.json5` rules file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathAccessConfig {
+    /// Whether the path-access policy system is active.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
 
     /// Path to the rules file. Supports `~/` expansion.
     #[serde(default = "default_path_access_policy_file")]
@@ -1641,13 +4099,374 @@ impl Default for PathAccessConfig {
     }
 }
 
+/// How output from tools that return external (untrusted) content is fed to the model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UntrustedContentConfig {
+    /// Wrap untrusted tool output in delimited blocks the model is told never to obey.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Tools whose output counts as untrusted. A trailing `*` matches a prefix
+    /// (e.g. `"github__*"` for every tool of one MCP server).
+    #[serde(default = "default_untrusted_tools")]
+    pub tools: Vec<String>,
+
+    /// Replace instruction-like phrases ("ignore previous instructions", fake role
+    /// markers, ...) before the content reaches the model.
+    #[serde(default = "default_true")]
+    pub strip_instructions: bool,
+
+    /// Additionally ask the LLM to flag injected instructions. Costs one extra call
+    /// per untrusted tool result, so it is off by default.
+    #[serde(default)]
+    pub classifier: bool,
+
+    /// Results longer than this skip the classifier (pattern stripping still applies).
+    #[serde(default = "default_classifier_max_chars")]
+    pub classifier_max_chars: usize,
+}
+
+fn default_untrusted_tools() -> Vec<String> {
+    [
+        "web_fetch",
+        "web_search",
+        "browse",
+        "http_request",
+        "rss",
+        "email",
+        "bookmarks",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+fn default_classifier_max_chars() -> usize {
+    12_000
+}
+
+impl Default for UntrustedContentConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            tools: default_untrusted_tools(),
+            strip_instructions: true,
+            classifier: false,
+            classifier_max_chars: default_classifier_max_chars(),
+        }
+    }
+}
+
+impl UntrustedContentConfig {
+    pub fn applies_to(&self, tool_name: &str) -> bool {
+        self.enabled
+            && self
+                .tools
+                .iter()
+                .any(|pattern| match pattern.strip_suffix('*') {
+                    Some(prefix) => tool_name.starts_with(prefix),
+                    None => pattern == tool_name,
+                })
+    }
+}
+
 /// Top-level security settings for the agent runtime.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct SecurityConfig {
-    /// Path-access policy rules.
+pub struct SecurityConfig {
+    /// Path-access policy rules.
+    #[serde(default)]
+    pub path_access: PathAccessConfig,
+    /// Prompt-injection defenses for web/browser and other external content.
+    #[serde(default)]
+    pub untrusted_content: UntrustedContentConfig,
+    /// PII masking applied before text is persisted.
+    #[serde(default)]
+    pub redaction: RedactionConfig,
+    /// Per-sender data purge and per-channel retention windows.
+    #[serde(default)]
+    pub privacy: PrivacyConfig,
+}
+
+/// Role-based tool permissions (`permissions`).
+///
+/// Senders are identified as `channel:sender_id` (e.g. `telegram:123456789`);
+/// `channel:*` sets the role for everyone else on that channel. Roles are `admin`
+/// (every tool), `user` (everything but `restrictedTools`) and `readonly` (only
+/// `readonlyTools`). Gateway-internal channels (WebUI, CLI, cron, ...) always act as admin.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Role of senders without an entry in `members`. Default: `user`
+    #[serde(default = "default_permissions_role")]
+    pub default_role: String,
+    /// Role by `channel:sender_id` or `channel:*`.
+    #[serde(default)]
+    pub members: HashMap<String, String>,
+    /// Tools only admins may call.
+    #[serde(default = "default_restricted_tools")]
+    pub restricted_tools: Vec<String>,
+    /// The only tools readonly senders may call.
+    #[serde(default = "default_readonly_tools")]
+    pub readonly_tools: Vec<String>,
+}
+
+fn default_permissions_role() -> String {
+    "user".to_string()
+}
+
+/// Tools that run code on the host or change files, servers or remote infrastructure,
+/// plus the ones that schedule work to run later.
+fn default_restricted_tools() -> Vec<String> {
+    [
+        "exec",
+        "exec_local",
+        "exec_skill_script",
+        "python",
+        "capability_evolve",
+        "file_ops",
+        "write_file",
+        "edit_file",
+        "ssh",
+        "docker",
+        "kubectl",
+        "db_query",
+        "http_request",
+        "object_storage",
+        "cron",
+        "alert_rule",
+        "follow_up",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+fn default_readonly_tools() -> Vec<String> {
+    [
+        "read_file",
+        "list_dir",
+        "web_search",
+        "web_fetch",
+        "memory_query",
+        "session_recall",
+        "doc_search",
+        "list_skills",
+        "list_tasks",
+        "calc",
+        "weather",
+        "translate",
+        "system_info",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+impl Default for PermissionsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            default_role: default_permissions_role(),
+            members: HashMap::new(),
+            restricted_tools: default_restricted_tools(),
+            readonly_tools: default_readonly_tools(),
+        }
+    }
+}
+
+/// IoT device integrations (`iot`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IotConfig {
+    #[serde(default)]
+    pub mqtt: MqttConfig,
+}
+
+/// MQTT broker shared by `iot_control` and `stream_subscribe` (`iot.mqtt`).
+///
+/// One connection is opened on first use; topic subscriptions are re-sent after
+/// every reconnect.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MqttConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub host: String,
+    /// Default: 1883, or 8883 with TLS
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// Default: `blockcell-<random>`
+    #[serde(default)]
+    pub client_id: Option<String>,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub tls: MqttTlsConfig,
+    #[serde(default = "default_mqtt_keep_alive_secs")]
+    pub keep_alive_secs: u64,
+    /// QoS used when a tool call doesn't give one (0, 1 or 2). Default: 1
+    #[serde(default = "default_mqtt_qos")]
+    pub default_qos: u8,
+    /// Prefix put in front of device names by `iot_control` (e.g. `home/devices/`).
+    #[serde(default)]
+    pub topic_prefix: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MqttTlsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// PEM file with the CA that signed the broker certificate; system roots otherwise.
+    #[serde(default)]
+    pub ca_file: Option<String>,
+    /// PEM client certificate and key for brokers that require mutual TLS.
+    #[serde(default)]
+    pub client_cert_file: Option<String>,
+    #[serde(default)]
+    pub client_key_file: Option<String>,
+}
+
+fn default_mqtt_keep_alive_secs() -> u64 {
+    30
+}
+
+fn default_mqtt_qos() -> u8 {
+    1
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: String::new(),
+            port: None,
+            client_id: None,
+            username: None,
+            password: None,
+            tls: MqttTlsConfig::default(),
+            keep_alive_secs: default_mqtt_keep_alive_secs(),
+            default_qos: default_mqtt_qos(),
+            topic_prefix: String::new(),
+        }
+    }
+}
+
+impl MqttConfig {
+    pub fn effective_port(&self) -> u16 {
+        self.port
+            .unwrap_or(if self.tls.enabled { 8883 } else { 1883 })
+    }
+}
+
+/// Data purge and retention (`blockcell privacy purge`, `POST /v1/privacy/purge`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivacyConfig {
+    /// Retention window in days keyed by channel name (`telegram`, `ws`, ...); `*`
+    /// applies to channels without their own entry. 0 or missing keeps data forever.
+    #[serde(default)]
+    pub retention_days: HashMap<String, u32>,
+    /// What purge does with matching audit entries: `anonymize` (default) keeps the
+    /// entry with params/results removed and the session key hashed; `delete` drops it.
+    #[serde(default = "default_privacy_audit_mode")]
+    pub audit_mode: String,
+    /// Delete files in the workspace that the purged sessions produced.
+    #[serde(default = "default_true")]
+    pub delete_artifacts: bool,
+}
+
+fn default_privacy_audit_mode() -> String {
+    "anonymize".to_string()
+}
+
+impl Default for PrivacyConfig {
+    fn default() -> Self {
+        Self {
+            retention_days: HashMap::new(),
+            audit_mode: default_privacy_audit_mode(),
+            delete_artifacts: true,
+        }
+    }
+}
+
+impl PrivacyConfig {
+    /// Retention window for `channel`, if one is configured.
+    pub fn retention_for(&self, channel: &str) -> Option<u32> {
+        self.retention_days
+            .get(channel)
+            .or_else(|| self.retention_days.get("*"))
+            .copied()
+            .filter(|days| *days > 0)
+    }
+}
+
+/// PII redaction applied before text is written to memory items, session summaries
+/// and audit logs. Built-in detectors: `email`, `phone`, `id_number`, `credit_card`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_redaction_detectors")]
+    pub detectors: Vec<String>,
+    /// Extra regex detectors, e.g. internal employee numbers.
+    #[serde(default)]
+    pub custom_patterns: Vec<RedactionPattern>,
+    /// Optional NER command. It receives the text on stdin and prints a JSON array of
+    /// strings (or `{"text": ...}` objects) to mask, e.g. names found by a spaCy script.
+    #[serde(default)]
+    pub ner_command: Option<String>,
+    /// Per-namespace overrides keyed by `memory`, `session_summary` or `audit`.
+    #[serde(default)]
+    pub namespaces: HashMap<String, RedactionNamespacePolicy>,
+}
+
+fn default_redaction_detectors() -> Vec<String> {
+    ["email", "phone", "id_number", "credit_card"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            detectors: default_redaction_detectors(),
+            custom_patterns: Vec::new(),
+            ner_command: None,
+            namespaces: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionPattern {
+    pub name: String,
+    pub pattern: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionNamespacePolicy {
+    /// Overrides `enabled` for this namespace.
     #[serde(default)]
-    pub path_access: PathAccessConfig,
+    pub enabled: Option<bool>,
+    /// Replaces the global detector list for this namespace.
+    #[serde(default)]
+    pub detectors: Option<Vec<String>>,
+    /// Whether to run `nerCommand` (default: true when configured).
+    #[serde(default)]
+    pub ner: Option<bool>,
 }
 
 fn default_memory_vector_table() -> String {
@@ -1686,6 +4505,39 @@ impl Default for MemoryVectorConfig {
 pub struct MemoryConfig {
     #[serde(default)]
     pub vector: MemoryVectorConfig,
+    /// Shared team memory synchronized through the Community Hub.
+    #[serde(default)]
+    pub team: TeamMemoryConfig,
+}
+
+/// Items saved with scope `team` are shared with every node in the same team through
+/// the Community Hub (`communityHub.apiKey` authenticates this node). Conflicting edits
+/// are resolved per item by vector clock, falling back to last-write-wins.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamMemoryConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Team namespace on the hub, shared by all members.
+    #[serde(default)]
+    pub team_id: Option<String>,
+    /// Seconds between sync rounds. Default: 300
+    #[serde(default = "default_team_memory_sync_interval_secs")]
+    pub sync_interval_secs: u64,
+}
+
+fn default_team_memory_sync_interval_secs() -> u64 {
+    300
+}
+
+impl Default for TeamMemoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            team_id: None,
+            sync_interval_secs: default_team_memory_sync_interval_secs(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -1699,10 +4551,196 @@ pub struct AutoUpgradeConfig {
     pub manifest_url: String,
     #[serde(default = "default_require_signature")]
     pub require_signature: bool,
+    /// `HH:MM-HH:MM` window for applying updates. Ignored when the global
+    /// `maintenance` calendar has windows.
     #[serde(default)]
     pub maintenance_window: String,
 }
 
+/// Global maintenance calendar. Inside a window updates are applied, heavy background
+/// work (Ghost routines, skill evolution, cron jobs marked `maintenanceOnly`) runs and
+/// routine notifications are held back. With no windows nothing is restricted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceConfig {
+    /// Maintenance is active while any window matches (local time).
+    #[serde(default)]
+    pub windows: Vec<MaintenanceWindowConfig>,
+    /// Hold Ghost routines, skill evolution and `maintenanceOnly` cron jobs until a
+    /// window opens. Default: true
+    #[serde(default = "default_true")]
+    pub defer_heavy_jobs: bool,
+    /// Silence routine notifications (e.g. cron started/completed) inside a window;
+    /// failures still come through. Default: true
+    #[serde(default = "default_true")]
+    pub quiet_notifications: bool,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            windows: Vec::new(),
+            defer_heavy_jobs: true,
+            quiet_notifications: true,
+        }
+    }
+}
+
+/// One recurring maintenance window.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceWindowConfig {
+    /// `HH:MM-HH:MM`; may cross midnight (`23:00-02:00`).
+    pub time: String,
+    /// Days the window starts on (`mon` … `sun`). Empty = every day.
+    #[serde(default)]
+    pub days: Vec<String>,
+}
+
+/// Local storage settings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageConfig {
+    #[serde(default)]
+    pub encryption: StorageEncryptionConfig,
+}
+
+/// At-rest encryption of session files and the memory database, for laptops and phones.
+/// Session lines are sealed with XChaCha20-Poly1305; the memory database uses SQLCipher
+/// and needs a build with the `sqlcipher` feature. Existing plaintext data is encrypted
+/// at the next start. Turning encryption off again does not decrypt existing data.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageEncryptionConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Where the key comes from. Default: keyring
+    #[serde(default)]
+    pub key_source: StorageKeySource,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageKeySource {
+    /// Random key kept in the OS keyring (Keychain, Credential Manager, Secret Service),
+    /// created on first start.
+    #[default]
+    Keyring,
+    /// Key derived from a passphrase asked for at start. Unattended starts can pass it
+    /// in `BLOCKCELL_STORAGE_PASSPHRASE`.
+    Passphrase,
+}
+
+/// Anonymous usage telemetry. Off unless enabled; `blockcell telemetry preview` prints
+/// the exact report. Reports carry counts only (calls per built-in tool, tool calls
+/// per channel, error classes) plus version and platform, never message content, ids or
+/// paths.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Where reports are posted. Default: `<communityHub.hubUrl>/v1/telemetry`
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// Hours between reports. Default: 24
+    #[serde(default = "default_telemetry_interval_hours")]
+    pub interval_hours: u64,
+}
+
+fn default_telemetry_interval_hours() -> u64 {
+    24
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: None,
+            interval_hours: default_telemetry_interval_hours(),
+        }
+    }
+}
+
+/// Offline mode. The gateway probes `probeHosts`; while none is reachable, chat runs on
+/// `localModel` (an Ollama model) if set, the tools in `networkTools` fail with a clear
+/// error, and replies to chat channels are queued until the network is back.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfflineConfig {
+    /// Default: true
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// `host:port` addresses; the network counts as up while any accepts a TCP connection.
+    /// Default: ["1.1.1.1:443", "8.8.8.8:53"]
+    #[serde(default = "default_offline_probe_hosts")]
+    pub probe_hosts: Vec<String>,
+    /// Seconds between probes. Default: 30
+    #[serde(default = "default_offline_check_interval_secs")]
+    pub check_interval_secs: u64,
+    /// Ollama model used while offline, e.g. `llama3.2`. Unset = keep the normal models.
+    #[serde(default)]
+    pub local_model: Option<String>,
+    /// Tools that need the network and are refused while offline.
+    #[serde(default = "default_offline_network_tools")]
+    pub network_tools: Vec<String>,
+    /// Most chat channel replies held while offline; older ones are dropped. Default: 500
+    #[serde(default = "default_offline_max_queued")]
+    pub max_queued_deliveries: usize,
+}
+
+fn default_offline_probe_hosts() -> Vec<String> {
+    vec!["1.1.1.1:443".to_string(), "8.8.8.8:53".to_string()]
+}
+
+fn default_offline_check_interval_secs() -> u64 {
+    30
+}
+
+fn default_offline_network_tools() -> Vec<String> {
+    [
+        "web_search",
+        "web_fetch",
+        "http_request",
+        "weather",
+        "rss",
+        "git_api",
+        "email",
+        "community_hub",
+        "translate",
+        "parcel_track",
+        "travel",
+        "object_storage",
+        "stream_subscribe",
+    ]
+    .iter()
+    .map(|name| name.to_string())
+    .collect()
+}
+
+fn default_offline_max_queued() -> usize {
+    500
+}
+
+impl Default for OfflineConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            probe_hosts: default_offline_probe_hosts(),
+            check_interval_secs: default_offline_check_interval_secs(),
+            local_model: None,
+            network_tools: default_offline_network_tools(),
+            max_queued_deliveries: default_offline_max_queued(),
+        }
+    }
+}
+
+impl OfflineConfig {
+    pub fn is_network_tool(&self, tool: &str) -> bool {
+        self.network_tools.iter().any(|name| name == tool)
+    }
+}
+
 fn default_upgrade_channel() -> String {
     "stable".to_string()
 }
@@ -1718,8 +4756,12 @@ fn default_manifest_url() -> String {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Config {
+    /// Schema version the file was written for; older files are backed up and
+    /// migrated on load (see `config_migration`). Missing = 1.
+    #[serde(default = "legacy_config_version")]
+    pub config_version: u32,
     #[serde(default)]
-    pub providers: HashMap<String, ProviderConfig>,
+    pub providers: ProvidersConfig,
     #[serde(default)]
     pub memory: MemoryConfig,
     #[serde(default)]
@@ -1748,7 +4790,25 @@ pub struct Config {
     #[serde(default)]
     pub auto_upgrade: AutoUpgradeConfig,
     #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+    #[serde(default)]
     pub security: SecurityConfig,
+    /// Role-based access to dangerous tools for channel users.
+    #[serde(default)]
+    pub permissions: PermissionsConfig,
+    #[serde(default)]
+    pub iot: IotConfig,
+    #[serde(default)]
+    pub storage: StorageConfig,
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+    #[serde(default)]
+    pub offline: OfflineConfig,
+    /// Low-memory mode for small devices such as a Raspberry Pi: built-in tools are
+    /// created on first use, message queues and caches shrink, and the embedded WebUI is
+    /// not served (the HTTP API, including `/v1/stats`, stays up). Default: false
+    #[serde(default)]
+    pub lite: bool,
     /// Default timezone for cron jobs and time-related operations.
     /// IANA timezone name, e.g., "Asia/Shanghai", "America/New_York", "Europe/London".
     /// If not set, system timezone is detected, falling back to UTC.
@@ -1764,6 +4824,10 @@ fn default_cron_tick_interval() -> u64 {
     1
 }
 
+fn legacy_config_version() -> u32 {
+    1
+}
+
 /// Minimum allowed cron tick interval in seconds.
 const MIN_CRON_TICK_INTERVAL_SECS: u64 = 1;
 /// Maximum allowed cron tick interval in seconds.
@@ -1888,7 +4952,8 @@ impl Default for Config {
         );
 
         Self {
-            providers,
+            config_version: crate::config_migration::CURRENT_CONFIG_VERSION,
+            providers: providers.into(),
             memory: MemoryConfig::default(),
             network: NetworkConfig::default(),
             community_hub: CommunityHubConfig::default(),
@@ -1900,7 +4965,14 @@ impl Default for Config {
             tools: ToolsConfig::default(),
             intent_router: Some(IntentRouterConfig::default()),
             auto_upgrade: AutoUpgradeConfig::default(),
+            maintenance: MaintenanceConfig::default(),
             security: SecurityConfig::default(),
+            permissions: PermissionsConfig::default(),
+            iot: IotConfig::default(),
+            storage: StorageConfig::default(),
+            telemetry: TelemetryConfig::default(),
+            offline: OfflineConfig::default(),
+            lite: false,
             default_timezone: None,
             cron_tick_interval_secs: default_cron_tick_interval(),
         }
@@ -1988,6 +5060,11 @@ pub fn parse_json5_value(content: &str) -> Result<Value> {
     parse_json5_str(content)
 }
 
+/// Parse JSON5 without expanding `${VAR}` references, for rewriting a file in place.
+pub fn parse_json5_value_unexpanded(content: &str, path: Option<&Path>) -> Result<Value> {
+    json5::from_str(content).map_err(|e| format_json5_parse_error(path, "Config JSON5", &e))
+}
+
 pub fn stringify_json5_pretty<T>(value: &T) -> Result<String>
 where
     T: Serialize,
@@ -2050,6 +5127,24 @@ fn detect_system_timezone() -> Option<String> {
 }
 
 impl Config {
+    /// Queue/buffer capacity for `normal`, shrunk in `lite` mode.
+    pub fn queue_capacity(&self, normal: usize) -> usize {
+        if self.lite {
+            (normal / 8).max(4)
+        } else {
+            normal
+        }
+    }
+
+    /// Apply `lite` limits to in-memory caches. The config file is not changed.
+    pub fn apply_lite_limits(&mut self) {
+        if !self.lite {
+            return;
+        }
+        let reply_style = &mut self.agents.defaults.reply_style;
+        reply_style.cache_entries = reply_style.cache_entries.min(16);
+    }
+
     pub fn load(path: &Path) -> Result<Self> {
         let content = std::fs::read_to_string(path)?;
         let config: Config = validate_config_json5_file(path, &content)?;
@@ -2063,6 +5158,7 @@ impl Config {
         let config_path = paths.config_file();
 
         let config = if config_path.exists() {
+            crate::config_migration::migrate_file(&config_path)?;
             Self::load(&config_path)?
         } else {
             // New config: detect system timezone once
@@ -2132,6 +5228,126 @@ impl Config {
             )));
         }
 
+        for (server, cors) in [
+            ("api", &self.gateway.cors.api),
+            ("webui", &self.gateway.cors.webui),
+        ] {
+            let wildcard = cors.allowed_origins.iter().any(|o| o.trim() == "*")
+                || cors.allowed_headers.iter().any(|h| h.trim() == "*");
+            if cors.allow_credentials && wildcard {
+                return Err(crate::Error::Config(format!(
+                    "gateway.cors.{}: allowCredentials cannot be combined with \"*\" origins or headers",
+                    server
+                )));
+            }
+        }
+
+        let base_path = self.gateway.normalized_base_path();
+        let valid_path = base_path.split('/').skip(1).all(|segment| {
+            !segment.is_empty()
+                && segment != ".."
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-_.~".contains(c))
+        });
+        if !valid_path {
+            return Err(crate::Error::Config(format!(
+                "Invalid gateway.basePath '{}': use plain path segments like '/blockcell'",
+                self.gateway.base_path
+            )));
+        }
+
+        if self.gateway.single_port == Some(false) && self.gateway.webui_port == self.gateway.port {
+            return Err(crate::Error::Config(format!(
+                "gateway.webuiPort ({}) must differ from gateway.port when singlePort is false",
+                self.gateway.webui_port
+            )));
+        }
+
+        let login_limit = &self.gateway.login_limit;
+        if login_limit.enabled
+            && (login_limit.max_failures == 0
+                || login_limit.window_secs == 0
+                || login_limit.lockout_secs == 0
+                || login_limit.max_lockout_secs < login_limit.lockout_secs)
+        {
+            return Err(crate::Error::Config(
+                "gateway.loginLimit: maxFailures, windowSecs and lockoutSecs must be positive, and maxLockoutSecs at least lockoutSecs"
+                    .to_string(),
+            ));
+        }
+
+        let sandbox = &self.tools.exec.sandbox;
+        if sandbox.cpus <= 0.0 || sandbox.memory_mb < 16 || sandbox.max_processes == 0 {
+            return Err(crate::Error::Config(
+                "tools.exec.sandbox: cpus and maxProcesses must be positive, and memoryMb at least 16"
+                    .to_string(),
+            ));
+        }
+        let uses_docker =
+            [sandbox.read_only, sandbox.limited, sandbox.full].contains(&SandboxBackend::Docker);
+        if uses_docker && sandbox.image.trim().is_empty() {
+            return Err(crate::Error::Config(
+                "tools.exec.sandbox.image is required for the docker backend".to_string(),
+            ));
+        }
+
+        let webauthn = &self.gateway.webauthn;
+        if webauthn.enabled {
+            let origin = self.gateway.webauthn_origin();
+            let rp_id = self.gateway.webauthn_rp_id();
+            let host = origin_host(&origin);
+            if !(origin.starts_with("https://") || host == "localhost") {
+                return Err(crate::Error::Config(format!(
+                    "gateway.webauthn.origin '{}' must use https (browsers only allow passkeys on http://localhost)",
+                    origin
+                )));
+            }
+            if rp_id.is_empty() || !(host == rp_id || host.ends_with(&format!(".{}", rp_id))) {
+                return Err(crate::Error::Config(format!(
+                    "gateway.webauthn.rpId '{}' must be the host of {} or a parent domain of it",
+                    rp_id, origin
+                )));
+            }
+            if webauthn.session_ttl_secs == 0
+                || webauthn.refresh_ttl_secs < webauthn.session_ttl_secs
+            {
+                return Err(crate::Error::Config(
+                    "gateway.webauthn: sessionTtlSecs must be positive and no longer than refreshTtlSecs"
+                        .to_string(),
+                ));
+            }
+        }
+
+        for role in
+            std::iter::once(&self.permissions.default_role).chain(self.permissions.members.values())
+        {
+            if crate::permissions::Role::parse(role).is_none() {
+                return Err(crate::Error::Config(format!(
+                    "Invalid permissions role '{}'. Use admin, user or readonly",
+                    role
+                )));
+            }
+        }
+
+        let mqtt = &self.iot.mqtt;
+        if mqtt.enabled && mqtt.host.trim().is_empty() {
+            return Err(crate::Error::Config(
+                "iot.mqtt.host is required when iot.mqtt.enabled is true".to_string(),
+            ));
+        }
+        if mqtt.default_qos > 2 {
+            return Err(crate::Error::Config(format!(
+                "iot.mqtt.defaultQos must be 0, 1 or 2, got {}",
+                mqtt.default_qos
+            )));
+        }
+        if mqtt.tls.client_cert_file.is_some() != mqtt.tls.client_key_file.is_some() {
+            return Err(crate::Error::Config(
+                "iot.mqtt.tls.clientCertFile and clientKeyFile must be set together".to_string(),
+            ));
+        }
+
         // Validate default_timezone if set
         if let Some(ref tz) = self.default_timezone {
             if tz.parse::<chrono_tz::Tz>().is_err() {
@@ -2187,6 +5403,35 @@ impl Config {
         None
     }
 
+    /// Where telemetry reports go: `telemetry.endpoint`, else the community hub.
+    pub fn telemetry_endpoint(&self) -> Option<String> {
+        self.telemetry
+            .endpoint
+            .as_deref()
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+            .map(str::to_string)
+            .or_else(|| {
+                self.community_hub_url()
+                    .map(|hub| format!("{}/v1/telemetry", hub))
+            })
+    }
+
+    /// Hub URL of the team memory namespace, when team memory is enabled and configured.
+    pub fn team_memory_endpoint(&self) -> Option<String> {
+        let team = &self.memory.team;
+        if !team.enabled {
+            return None;
+        }
+        let team_id = team
+            .team_id
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())?;
+        self.community_hub_url()
+            .map(|hub| format!("{}/v1/teams/{}/memory", hub, team_id))
+    }
+
     pub fn community_hub_api_key(&self) -> Option<String> {
         if let Some(key) = self.community_hub.api_key.as_ref() {
             let key = key.trim();
@@ -2854,4 +6099,244 @@ mod tests {
             .collect();
         assert_eq!(ids, vec!["default".to_string(), "ops".to_string()]);
     }
+
+    #[test]
+    fn test_confirm_prompt_channel_overrides() {
+        let raw = r#"{
+  "channels": {
+    "confirm": {
+      "timeoutSecs": 120,
+      "channels": {
+        "telegram": { "timeoutSecs": 600 },
+        "wecom": { "enabled": false }
+      }
+    }
+  }
+}"#;
+
+        let cfg: Config = serde_json::from_str(raw).unwrap();
+        let confirm = &cfg.channels.confirm;
+        assert!(confirm.enabled_for("telegram"));
+        assert!(!confirm.enabled_for("wecom"));
+        assert_eq!(confirm.timeout_for("telegram").as_secs(), 600);
+        assert_eq!(confirm.timeout_for("slack").as_secs(), 120);
+        assert!(Config::default().channels.confirm.enabled_for("slack"));
+    }
+
+    #[test]
+    fn test_channel_verbosity_parses() {
+        let raw = r#"{
+  "channels": {
+    "verbosity": {
+      "whatsapp": { "maxTokens": 400, "bulletSummaries": true, "suppressToolNarration": true }
+    }
+  }
+}"#;
+
+        let cfg: Config = serde_json::from_str(raw).unwrap();
+        let whatsapp = cfg.channels.verbosity_for("whatsapp").unwrap();
+        assert_eq!(whatsapp.max_tokens, Some(400));
+        assert!(whatsapp.bullet_summaries && whatsapp.suppress_tool_narration);
+        assert!(cfg.channels.verbosity_for("ws").is_none());
+    }
+
+    #[test]
+    fn test_providers_failover_parses_beside_provider_entries() {
+        let raw = r#"{
+  "providers": {
+    "deepseek": { "apiKey": "sk-ds" },
+    "openai": { "apiKey": "sk-oa" },
+    "failover": {
+      "chain": [
+        { "provider": "openai", "model": "gpt-4o-mini" },
+        { "provider": "ollama", "model": "qwen2.5:7b" }
+      ],
+      "maxRetries": 1
+    }
+  }
+}"#;
+
+        let cfg: Config = serde_json::from_str(raw).unwrap();
+        assert_eq!(cfg.providers.len(), 2);
+        assert!(!cfg.providers.contains_key("failover"));
+        assert_eq!(cfg.providers.get("deepseek").unwrap().api_key, "sk-ds");
+        let failover = cfg.providers.failover.as_ref().unwrap();
+        assert_eq!(failover.chain[1].provider, "ollama");
+        assert_eq!(failover.max_retries, 1);
+        assert_eq!(failover.initial_backoff_ms, 500);
+
+        let round_trip: Config =
+            serde_json::from_value(serde_json::to_value(&cfg).unwrap()).unwrap();
+        assert_eq!(round_trip.providers.failover.unwrap().chain.len(), 2);
+        assert!(
+            serde_json::to_value(Config::default()).unwrap()["providers"]
+                .get("failover")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_lite_mode_shrinks_buffers() {
+        let mut cfg: Config = serde_json::from_str(r#"{ "lite": true }"#).unwrap();
+        assert_eq!(cfg.queue_capacity(1000), 125);
+        assert_eq!(cfg.queue_capacity(16), 4);
+        cfg.apply_lite_limits();
+        assert!(cfg.agents.defaults.reply_style.cache_entries <= 16);
+        assert_eq!(Config::default().queue_capacity(100), 100);
+    }
+
+    #[test]
+    fn test_batch_window_per_channel() {
+        let raw = r#"{ "channels": { "batch": { "channels": { "telegram": 3 } } } }"#;
+        let cfg: Config = serde_json::from_str(raw).unwrap();
+        let batch = &cfg.channels.batch;
+        assert!(batch.is_enabled());
+        assert_eq!(batch.window_for("telegram").unwrap().as_secs(), 3);
+        assert!(batch.window_for("ws").is_none());
+        assert_eq!(batch.max_wait_secs, 20);
+        assert!(!Config::default().channels.batch.is_enabled());
+    }
+
+    #[test]
+    fn test_gateway_cors_rejects_credentials_with_wildcard() {
+        let raw = r#"{ "gateway": { "cors": { "api": { "allowedOrigins": ["https://ops.example.com"], "allowCredentials": true } } } }"#;
+        let cfg: Config = serde_json::from_str(raw).unwrap();
+        assert_eq!(cfg.gateway.cors.api.max_age_secs, 600);
+        assert!(cfg.gateway.cors.webui.allowed_origins.is_empty());
+        assert!(cfg.validate().is_ok());
+
+        let raw = r#"{ "gateway": { "cors": { "webui": { "allowedHeaders": ["*"], "allowCredentials": true } } } }"#;
+        let cfg: Config = serde_json::from_str(raw).unwrap();
+        let err = cfg.validate().unwrap_err().to_string();
+        assert!(err.contains("gateway.cors.webui"), "{}", err);
+    }
+
+    #[test]
+    fn test_gateway_base_path_normalization() {
+        let mut cfg = Config::default();
+        assert_eq!(cfg.gateway.normalized_base_path(), "");
+        cfg.gateway.base_path = "blockcell/".to_string();
+        assert_eq!(cfg.gateway.normalized_base_path(), "/blockcell");
+        assert!(cfg.validate().is_ok());
+
+        let mut cfg = Config::default();
+        cfg.gateway.base_path = "/api/:id".to_string();
+        assert!(cfg.validate().is_err());
+    }
+
+    #[test]
+    fn test_gateway_single_port_mode() {
+        let cfg = Config::default();
+        assert!(!cfg.gateway.is_single_port(cfg.gateway.port));
+        // `--port 18791` lands on the WebUI port, so both share one listener.
+        assert!(cfg.gateway.is_single_port(cfg.gateway.webui_port));
+
+        let raw = r#"{ "gateway": { "port": 8080, "webuiPort": 8080 } }"#;
+        let cfg: Config = serde_json::from_str(raw).unwrap();
+        assert!(cfg.gateway.is_single_port(8080));
+        assert!(cfg.validate().is_ok());
+
+        let raw = r#"{ "gateway": { "singlePort": true } }"#;
+        let cfg: Config = serde_json::from_str(raw).unwrap();
+        assert!(cfg.gateway.is_single_port(cfg.gateway.port));
+
+        let raw = r#"{ "gateway": { "port": 8080, "webuiPort": 8080, "singlePort": false } }"#;
+        let cfg: Config = serde_json::from_str(raw).unwrap();
+        assert!(cfg.validate().is_err());
+    }
+
+    #[test]
+    fn test_gateway_login_limit_defaults_and_validation() {
+        let cfg = Config::default();
+        assert!(cfg.gateway.login_limit.enabled);
+        assert_eq!(cfg.gateway.login_limit.max_failures, 5);
+        assert_eq!(cfg.gateway.login_limit.global_max_failures, 50);
+
+        let raw =
+            r#"{ "gateway": { "loginLimit": { "lockoutSecs": 600, "maxLockoutSecs": 60 } } }"#;
+        let cfg: Config = serde_json::from_str(raw).unwrap();
+        assert!(cfg.validate().is_err());
+
+        let raw = r#"{ "gateway": { "loginLimit": { "enabled": false, "maxFailures": 0 } } }"#;
+        let cfg: Config = serde_json::from_str(raw).unwrap();
+        assert!(cfg.validate().is_ok());
+    }
+
+    #[test]
+    fn test_exec_sandbox_backend_per_privilege() {
+        use crate::capability::PrivilegeLevel;
+        let cfg = Config::default();
+        let sandbox = &cfg.tools.exec.sandbox;
+        assert_eq!(
+            sandbox.backend_for(&PrivilegeLevel::ReadOnly),
+            SandboxBackend::None
+        );
+        assert_eq!(sandbox.memory_mb, 512);
+
+        let raw = r#"{ "tools": { "exec": { "sandbox": {
+            "readOnly": "firejail", "limited": "docker", "full": "namespaces" } } } }"#;
+        let cfg: Config = serde_json::from_str(raw).unwrap();
+        let sandbox = &cfg.tools.exec.sandbox;
+        assert_eq!(
+            sandbox.backend_for(&PrivilegeLevel::ReadOnly),
+            SandboxBackend::Firejail
+        );
+        assert_eq!(
+            sandbox.backend_for(&PrivilegeLevel::Limited),
+            SandboxBackend::Docker
+        );
+        assert_eq!(
+            sandbox.backend_for(&PrivilegeLevel::Full),
+            SandboxBackend::Namespaces
+        );
+        assert!(cfg.validate().is_ok());
+
+        let raw = r#"{ "tools": { "exec": { "sandbox": { "limited": "docker", "image": "" } } } }"#;
+        let cfg: Config = serde_json::from_str(raw).unwrap();
+        assert!(cfg.validate().is_err());
+    }
+
+    #[test]
+    fn test_gateway_webauthn_origin_and_validation() {
+        let cfg = Config::default();
+        assert_eq!(cfg.gateway.webauthn_origin(), "http://localhost:18791");
+        assert_eq!(cfg.gateway.webauthn_rp_id(), "localhost");
+        assert_eq!(cfg.gateway.webauthn.mode, WebAuthnMode::Optional);
+
+        let raw = r#"{ "gateway": { "publicApiBase": "https://Ops.Example.com/blockcell/api",
+            "webauthn": { "enabled": true, "mode": "passkey_only" } } }"#;
+        let cfg: Config = serde_json::from_str(raw).unwrap();
+        assert_eq!(cfg.gateway.webauthn_origin(), "https://Ops.Example.com");
+        assert_eq!(cfg.gateway.webauthn_rp_id(), "ops.example.com");
+        assert_eq!(cfg.gateway.webauthn.mode, WebAuthnMode::PasskeyOnly);
+        assert!(cfg.validate().is_ok());
+
+        let raw = r#"{ "gateway": { "webauthn": { "enabled": true,
+            "origin": "https://ops.example.com:8443", "rpId": "example.com" } } }"#;
+        let cfg: Config = serde_json::from_str(raw).unwrap();
+        assert!(cfg.validate().is_ok());
+
+        for raw in [
+            r#"{ "gateway": { "webauthn": { "enabled": true, "origin": "http://192.168.1.5:18791" } } }"#,
+            r#"{ "gateway": { "webauthn": { "enabled": true, "origin": "https://ops.example.com", "rpId": "other.com" } } }"#,
+            r#"{ "gateway": { "webauthn": { "enabled": true, "sessionTtlSecs": 0 } } }"#,
+        ] {
+            let cfg: Config = serde_json::from_str(raw).unwrap();
+            assert!(cfg.validate().is_err(), "{}", raw);
+        }
+    }
+
+    #[test]
+    fn test_mqtt_config_defaults_and_validation() {
+        let raw = r#"{ "iot": { "mqtt": { "enabled": true, "host": "broker.local", "tls": { "enabled": true } } } }"#;
+        let cfg: Config = serde_json::from_str(raw).unwrap();
+        assert_eq!(cfg.iot.mqtt.effective_port(), 8883);
+        assert_eq!(cfg.iot.mqtt.default_qos, 1);
+        assert!(cfg.validate().is_ok());
+        assert_eq!(Config::default().iot.mqtt.effective_port(), 1883);
+
+        let raw = r#"{ "iot": { "mqtt": { "enabled": true, "host": " " } } }"#;
+        let cfg: Config = serde_json::from_str(raw).unwrap();
+        assert!(cfg.validate().is_err());
+    }
 }
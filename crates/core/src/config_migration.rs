@@ -0,0 +1,300 @@
+//! Config schema versions, migrations and key-level validation.
+//!
+//! `configVersion` records the schema a config file was written for (missing = 1). On
+//! load, an older file is copied to `config.json5.v<N>.bak` and migrated step by step.
+//! `blockcell config validate` uses [`check_keys`] to report keys serde silently ignores.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+
+use crate::config::{parse_json5_value_unexpanded, write_json5_pretty};
+use crate::{Error, Result};
+
+pub const CURRENT_CONFIG_VERSION: u32 = 2;
+
+struct Migration {
+    /// Version this migration upgrades from (to `from + 1`).
+    from: u32,
+    description: &'static str,
+    apply: fn(&mut Value),
+}
+
+const MIGRATIONS: &[Migration] = &[Migration {
+    from: 1,
+    description: "autoUpgrade.maintenanceWindow → maintenance.windows",
+    apply: migrate_v1_maintenance_window,
+}];
+
+/// Keys that still parse but have a replacement: (path, replacement).
+const DEPRECATED_KEYS: &[(&str, &str)] =
+    &[("autoUpgrade.maintenanceWindow", "maintenance.windows")];
+
+/// v1 → v2: the updater's single window becomes a calendar entry. Deferring jobs and
+/// quieting notifications are turned off so behaviour stays as before.
+fn migrate_v1_maintenance_window(config: &mut Value) {
+    let window = config
+        .get_mut("autoUpgrade")
+        .and_then(|v| v.as_object_mut())
+        .and_then(|o| o.remove("maintenanceWindow"));
+    let Some(window) = window.and_then(|w| w.as_str().map(str::to_string)) else {
+        return;
+    };
+    if window.trim().is_empty() || config.get("maintenance").is_some() {
+        return;
+    }
+    if let Some(obj) = config.as_object_mut() {
+        obj.insert(
+            "maintenance".to_string(),
+            json!({
+                "windows": [{ "time": window }],
+                "deferHeavyJobs": false,
+                "quietNotifications": false,
+            }),
+        );
+    }
+}
+
+pub fn file_version(config: &Value) -> u32 {
+    config
+        .get("configVersion")
+        .and_then(|v| v.as_u64())
+        .map_or(1, |v| v as u32)
+}
+
+/// Descriptions of the migrations a file at `version` still needs.
+pub fn pending_migrations(version: u32) -> Vec<&'static str> {
+    MIGRATIONS
+        .iter()
+        .filter(|m| m.from >= version)
+        .map(|m| m.description)
+        .collect()
+}
+
+/// Migrate a raw config to the current version; returns what was applied.
+pub fn migrate(config: &mut Value) -> Result<Vec<&'static str>> {
+    let version = file_version(config);
+    if version > CURRENT_CONFIG_VERSION {
+        return Err(Error::Config(format!(
+            "config version {} is newer than this blockcell supports ({}); upgrade blockcell",
+            version, CURRENT_CONFIG_VERSION
+        )));
+    }
+    let mut applied = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|m| m.from >= version) {
+        (migration.apply)(config);
+        applied.push(migration.description);
+    }
+    if let Some(obj) = config.as_object_mut() {
+        obj.insert("configVersion".to_string(), json!(CURRENT_CONFIG_VERSION));
+    }
+    Ok(applied)
+}
+
+fn backup_path(path: &Path, version: u32) -> PathBuf {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("config.json5");
+    let candidate = path.with_file_name(format!("{}.v{}.bak", name, version));
+    if candidate.exists() {
+        path.with_file_name(format!(
+            "{}.v{}.{}.bak",
+            name,
+            version,
+            chrono::Utc::now().format("%Y%m%d%H%M%S")
+        ))
+    } else {
+        candidate
+    }
+}
+
+/// Back up and migrate the config file if it predates the current version.
+pub fn migrate_file(path: &Path) -> Result<()> {
+    let content = std::fs::read_to_string(path)?;
+    let mut config = parse_json5_value_unexpanded(&content, Some(path))?;
+    let version = file_version(&config);
+    if version == CURRENT_CONFIG_VERSION {
+        return Ok(());
+    }
+    let applied = migrate(&mut config)?;
+    let backup = backup_path(path, version);
+    std::fs::copy(path, &backup)?;
+    write_json5_pretty(path, &config)?;
+    tracing::info!(
+        from = version,
+        to = CURRENT_CONFIG_VERSION,
+        backup = %backup.display(),
+        migrations = ?applied,
+        "Migrated config file"
+    );
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyIssueKind {
+    /// Not part of the schema; ignored when loading.
+    Unknown,
+    /// Still read, but superseded.
+    Deprecated,
+}
+
+#[derive(Debug, Clone)]
+pub struct KeyIssue {
+    pub path: String,
+    pub kind: KeyIssueKind,
+    pub suggestion: Option<String>,
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur.push((prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1));
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+fn snake_to_camel(key: &str) -> String {
+    let mut out = String::new();
+    let mut upper = false;
+    for c in key.chars() {
+        if c == '_' || c == '-' {
+            upper = true;
+        } else if upper {
+            out.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn suggest<'a>(key: &str, known: impl Iterator<Item = &'a String>) -> Option<String> {
+    let camel = snake_to_camel(key).to_lowercase();
+    known
+        .map(|k| {
+            let lower = k.to_lowercase();
+            let distance = if lower == camel {
+                0
+            } else {
+                edit_distance(&key.to_lowercase(), &lower)
+            };
+            (distance, k)
+        })
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, k)| k.clone())
+}
+
+fn collect_unknown(raw: &Value, parsed: &Value, prefix: &str, issues: &mut Vec<KeyIssue>) {
+    match (raw, parsed) {
+        (Value::Object(raw_map), Value::Object(parsed_map)) => {
+            for (key, raw_value) in raw_map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                match parsed_map.get(key) {
+                    Some(parsed_value) => collect_unknown(raw_value, parsed_value, &path, issues),
+                    // `null` for an optional field is simply not re-serialized.
+                    None if raw_value.is_null() => {}
+                    None => issues.push(KeyIssue {
+                        suggestion: suggest(key, parsed_map.keys()),
+                        path,
+                        kind: KeyIssueKind::Unknown,
+                    }),
+                }
+            }
+        }
+        (Value::Array(raw_items), Value::Array(parsed_items)) => {
+            for (idx, (r, p)) in raw_items.iter().zip(parsed_items).enumerate() {
+                collect_unknown(r, p, &format!("{}[{}]", prefix, idx), issues);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Compare the raw file with the parsed config re-serialized: keys only in the raw file
+/// were ignored by serde. `parsed` must come from `serde_json::to_value(&Config)`.
+pub fn check_keys(raw: &Value, parsed: &Value) -> Vec<KeyIssue> {
+    let mut issues: Vec<KeyIssue> = DEPRECATED_KEYS
+        .iter()
+        .filter(|(path, _)| path.split('.').try_fold(raw, |v, key| v.get(key)).is_some())
+        .map(|(path, replacement)| KeyIssue {
+            path: path.to_string(),
+            kind: KeyIssueKind::Deprecated,
+            suggestion: Some(replacement.to_string()),
+        })
+        .collect();
+    collect_unknown(raw, parsed, "", &mut issues);
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_migrate_v1_maintenance_window() {
+        let mut raw = json!({
+            "autoUpgrade": {"enabled": true, "maintenanceWindow": "03:00-05:00"}
+        });
+        assert_eq!(pending_migrations(file_version(&raw)).len(), 1);
+        let applied = migrate(&mut raw).unwrap();
+        assert_eq!(applied.len(), 1);
+        assert_eq!(raw["configVersion"], CURRENT_CONFIG_VERSION);
+        assert!(raw["autoUpgrade"].get("maintenanceWindow").is_none());
+        assert_eq!(raw["maintenance"]["windows"][0]["time"], "03:00-05:00");
+        assert_eq!(raw["maintenance"]["deferHeavyJobs"], false);
+
+        let config: Config = serde_json::from_value(raw.clone()).unwrap();
+        assert_eq!(config.config_version, CURRENT_CONFIG_VERSION);
+        assert!(config.maintenance.has_windows());
+        assert!(migrate(&mut raw).unwrap().is_empty());
+
+        let mut future = json!({ "configVersion": CURRENT_CONFIG_VERSION + 1 });
+        assert!(migrate(&mut future).is_err());
+    }
+
+    #[test]
+    fn test_check_keys_reports_unknown_and_deprecated() {
+        let raw = json!({
+            "configVersion": 2,
+            "agents": {"defaults": {"model": "gpt-4o", "max_tokens": 100, "modle": "x"}},
+            "gatway": {},
+            "autoUpgrade": {"maintenanceWindow": "03:00-05:00"},
+            "gateway": {"apiToken": null}
+        });
+        let config: Config = serde_json::from_value(raw.clone()).unwrap();
+        let parsed = serde_json::to_value(&config).unwrap();
+        let issues = check_keys(&raw, &parsed);
+
+        let find = |path: &str| issues.iter().find(|i| i.path == path).cloned();
+        let deprecated = find("autoUpgrade.maintenanceWindow").unwrap();
+        assert_eq!(deprecated.kind, KeyIssueKind::Deprecated);
+        assert_eq!(
+            find("agents.defaults.max_tokens").unwrap().suggestion,
+            Some("maxTokens".to_string())
+        );
+        assert_eq!(
+            find("agents.defaults.modle").unwrap().suggestion,
+            Some("model".to_string())
+        );
+        assert_eq!(
+            find("gatway").unwrap().suggestion,
+            Some("gateway".to_string())
+        );
+        assert!(find("gateway.apiToken").is_none());
+        assert_eq!(issues.len(), 4);
+    }
+}
@@ -0,0 +1,113 @@
+//! Process-wide online/offline state for offline mode (`offline`).
+//!
+//! The gateway runs [`spawn_monitor`]; everything else reads [`is_offline`] or waits on
+//! [`subscribe`] to react when the state flips. Without a monitor the process is online.
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+use crate::config::OfflineConfig;
+
+/// A probe host that does not answer within this time counts as unreachable.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+fn state() -> &'static watch::Sender<bool> {
+    static STATE: OnceLock<watch::Sender<bool>> = OnceLock::new();
+    STATE.get_or_init(|| watch::channel(false).0)
+}
+
+pub fn is_offline() -> bool {
+    *state().borrow()
+}
+
+/// Record the current state; returns true when it changed.
+pub fn set_offline(offline: bool) -> bool {
+    state().send_if_modified(|current| {
+        let changed = *current != offline;
+        *current = offline;
+        changed
+    })
+}
+
+/// Receiver that wakes on every state change; the value is `true` while offline.
+pub fn subscribe() -> watch::Receiver<bool> {
+    state().subscribe()
+}
+
+/// Whether any of `hosts` (`host:port`) accepts a TCP connection.
+pub async fn probe(hosts: &[String]) -> bool {
+    let mut attempts = tokio::task::JoinSet::new();
+    for host in hosts.iter().cloned() {
+        attempts.spawn(async move {
+            matches!(
+                tokio::time::timeout(PROBE_TIMEOUT, tokio::net::TcpStream::connect(host)).await,
+                Ok(Ok(_))
+            )
+        });
+    }
+    while let Some(result) = attempts.join_next().await {
+        if result.unwrap_or(false) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Probe the network every `checkIntervalSecs` and keep the shared state current.
+pub fn spawn_monitor(config: OfflineConfig) {
+    if !config.enabled || config.probe_hosts.is_empty() {
+        return;
+    }
+    let every = Duration::from_secs(config.check_interval_secs.max(5));
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            interval.tick().await;
+            let online = probe(&config.probe_hosts).await;
+            if set_offline(!online) {
+                if online {
+                    info!("Network is back; leaving offline mode");
+                } else {
+                    warn!(
+                        local_model = config.local_model.as_deref().unwrap_or("none"),
+                        "Network unreachable; entering offline mode"
+                    );
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_probe_reaches_local_listener() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let open = listener.local_addr().unwrap().to_string();
+        drop(listener);
+        assert!(!probe(std::slice::from_ref(&open)).await);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let reachable = listener.local_addr().unwrap().to_string();
+        assert!(probe(&[open, reachable]).await);
+        assert!(!probe(&[]).await);
+    }
+
+    #[test]
+    fn test_set_offline_reports_changes() {
+        let mut rx = subscribe();
+        assert!(set_offline(true));
+        assert!(!set_offline(true));
+        assert!(is_offline());
+        assert!(rx.has_changed().unwrap());
+        assert!(*rx.borrow_and_update());
+        assert!(set_offline(false));
+        assert!(!is_offline());
+    }
+}
@@ -0,0 +1,298 @@
+//! Egress (outbound network) policy evaluation for tools (`tools.egress`).
+//!
+//! The policy only decides; enforcement lives in the tools crate's HTTP client
+//! factory, which checks the initial URL (including resolved addresses when CIDR
+//! rules are configured) and every redirect hop.
+
+use std::net::IpAddr;
+
+use crate::config::EgressConfig;
+use crate::{Error, NetworkScope, Result};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Rule {
+    Any,
+    /// `example.com` — the domain itself and all subdomains.
+    Domain(String),
+    /// `*.example.com` — subdomains only.
+    Subdomains(String),
+    Cidr(IpAddr, u8),
+}
+
+impl Rule {
+    fn parse(raw: &str) -> Option<Self> {
+        let s = raw.trim().trim_end_matches('.').to_ascii_lowercase();
+        if s.is_empty() {
+            return None;
+        }
+        if s == "*" {
+            return Some(Rule::Any);
+        }
+        if let Some((addr, bits)) = s.split_once('/') {
+            let ip: IpAddr = addr.parse().ok()?;
+            let max = if ip.is_ipv4() { 32 } else { 128 };
+            let bits: u8 = bits.parse().ok().filter(|b| *b <= max)?;
+            return Some(Rule::Cidr(ip, bits));
+        }
+        if let Ok(ip) = s.trim_matches(['[', ']']).parse::<IpAddr>() {
+            let bits = if ip.is_ipv4() { 32 } else { 128 };
+            return Some(Rule::Cidr(ip, bits));
+        }
+        match s.strip_prefix("*.") {
+            Some(base) => Some(Rule::Subdomains(base.to_string())),
+            None => Some(Rule::Domain(s)),
+        }
+    }
+
+    fn is_network(&self) -> bool {
+        matches!(self, Rule::Cidr(..))
+    }
+
+    fn matches(&self, host: &str, ips: &[IpAddr]) -> bool {
+        match self {
+            Rule::Any => true,
+            Rule::Domain(d) => host == d || host.ends_with(&format!(".{}", d)),
+            Rule::Subdomains(d) => host.ends_with(&format!(".{}", d)),
+            Rule::Cidr(net, bits) => ips.iter().any(|ip| cidr_contains(*net, *bits, *ip)),
+        }
+    }
+}
+
+fn cidr_contains(net: IpAddr, bits: u8, ip: IpAddr) -> bool {
+    let ip = match (net, ip) {
+        // Treat ::ffff:a.b.c.d like a.b.c.d so IPv4 rules cannot be bypassed.
+        (IpAddr::V4(_), IpAddr::V6(v6)) => match v6.to_ipv4_mapped() {
+            Some(v4) => IpAddr::V4(v4),
+            None => return false,
+        },
+        _ => ip,
+    };
+    match (net, ip) {
+        (IpAddr::V4(n), IpAddr::V4(a)) => {
+            let mask = if bits == 0 {
+                0
+            } else {
+                u32::MAX << (32 - bits)
+            };
+            u32::from(n) & mask == u32::from(a) & mask
+        }
+        (IpAddr::V6(n), IpAddr::V6(a)) => {
+            let mask = if bits == 0 {
+                0
+            } else {
+                u128::MAX << (128 - bits)
+            };
+            u128::from(n) & mask == u128::from(a) & mask
+        }
+        _ => false,
+    }
+}
+
+fn parse_rules(entries: &[String]) -> Vec<Rule> {
+    entries.iter().filter_map(|e| Rule::parse(e)).collect()
+}
+
+#[derive(Debug, Clone)]
+struct RuleSet {
+    allow: Vec<Rule>,
+    deny: Vec<Rule>,
+    default_allow: bool,
+}
+
+/// Compiled `tools.egress` policy.
+#[derive(Debug, Clone)]
+pub struct EgressPolicy {
+    enabled: bool,
+    global: RuleSet,
+    tools: std::collections::HashMap<String, RuleSet>,
+}
+
+fn default_allows(action: &str) -> bool {
+    !action.trim().eq_ignore_ascii_case("deny")
+}
+
+impl EgressPolicy {
+    pub fn new(cfg: &EgressConfig) -> Self {
+        let global = RuleSet {
+            allow: parse_rules(&cfg.allow),
+            deny: parse_rules(&cfg.deny),
+            default_allow: default_allows(&cfg.default_action),
+        };
+        let tools = cfg
+            .tools
+            .iter()
+            .map(|(name, rule)| {
+                let mut allow = parse_rules(&rule.allow);
+                let mut deny = parse_rules(&rule.deny);
+                if rule.inherit {
+                    allow.extend(global.allow.iter().cloned());
+                    deny.extend(global.deny.iter().cloned());
+                }
+                let set = RuleSet {
+                    allow,
+                    deny,
+                    default_allow: rule
+                        .default_action
+                        .as_deref()
+                        .map(default_allows)
+                        .unwrap_or(global.default_allow),
+                };
+                (name.clone(), set)
+            })
+            .collect();
+        Self {
+            enabled: cfg.enabled,
+            global,
+            tools,
+        }
+    }
+
+    /// Policy admitting only the hosts of an evolved capability's declared scope.
+    pub fn for_scope(scope: &NetworkScope) -> Self {
+        Self::new(&EgressConfig {
+            enabled: true,
+            default_action: "deny".to_string(),
+            allow: scope.domains().to_vec(),
+            ..Default::default()
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn rules_for(&self, tool: &str) -> &RuleSet {
+        self.tools.get(tool).unwrap_or(&self.global)
+    }
+
+    /// Whether deciding for `tool` needs the host's resolved addresses (CIDR rules).
+    pub fn needs_resolution(&self, tool: &str) -> bool {
+        let rules = self.rules_for(tool);
+        self.enabled && rules.allow.iter().chain(&rules.deny).any(Rule::is_network)
+    }
+
+    /// Decide whether `tool` may connect to `host`. `resolved` holds the host's
+    /// addresses; IP-literal hosts are matched against CIDR rules directly.
+    pub fn check_host(&self, tool: &str, host: &str, resolved: &[IpAddr]) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let host = host
+            .trim()
+            .trim_matches(['[', ']'])
+            .trim_end_matches('.')
+            .to_ascii_lowercase();
+        let mut ips: Vec<IpAddr> = resolved.to_vec();
+        if let Ok(ip) = host.parse::<IpAddr>() {
+            ips.push(ip);
+        }
+        let rules = self.rules_for(tool);
+        let denied = |why: &str| {
+            Err(Error::PermissionDenied(format!(
+                "Egress to '{}' blocked for tool '{}' ({}). Adjust tools.egress to allow it.",
+                host, tool, why
+            )))
+        };
+        if rules.deny.iter().any(|r| r.matches(&host, &ips)) {
+            return denied("deny rule");
+        }
+        if rules.allow.iter().any(|r| r.matches(&host, &ips)) {
+            return Ok(());
+        }
+        if rules.default_allow {
+            Ok(())
+        } else {
+            denied("not in allow list")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ToolEgressRule;
+
+    fn policy() -> EgressPolicy {
+        let mut cfg = EgressConfig {
+            enabled: true,
+            deny: vec!["169.254.0.0/16".into(), "*.internal.corp".into()],
+            ..Default::default()
+        };
+        cfg.tools.insert(
+            "http_request".into(),
+            ToolEgressRule {
+                default_action: Some("deny".into()),
+                allow: vec!["api.github.com".into(), "10.0.0.0/8".into()],
+                ..Default::default()
+            },
+        );
+        EgressPolicy::new(&cfg)
+    }
+
+    #[test]
+    fn test_rule_parsing() {
+        assert_eq!(Rule::parse("*"), Some(Rule::Any));
+        assert_eq!(
+            Rule::parse("Example.COM."),
+            Some(Rule::Domain("example.com".into()))
+        );
+        assert_eq!(
+            Rule::parse("*.example.com"),
+            Some(Rule::Subdomains("example.com".into()))
+        );
+        assert!(matches!(Rule::parse("10.1.2.3"), Some(Rule::Cidr(_, 32))));
+        assert!(matches!(Rule::parse("fd00::/8"), Some(Rule::Cidr(_, 8))));
+        assert_eq!(Rule::parse("10.0.0.0/33"), None);
+        assert_eq!(Rule::parse("  "), None);
+    }
+
+    #[test]
+    fn test_global_deny_and_default_allow() {
+        let p = policy();
+        assert!(p.check_host("web_fetch", "example.com", &[]).is_ok());
+        assert!(p.check_host("web_fetch", "169.254.169.254", &[]).is_err());
+        let metadata: IpAddr = "169.254.169.254".parse().unwrap();
+        assert!(p.check_host("web_fetch", "evil.test", &[metadata]).is_err());
+        assert!(p.check_host("web_fetch", "db.internal.corp", &[]).is_err());
+        assert!(p.check_host("web_fetch", "internal.corp", &[]).is_ok());
+        let mapped: IpAddr = "::ffff:169.254.1.1".parse().unwrap();
+        assert!(p.check_host("web_fetch", "x.test", &[mapped]).is_err());
+    }
+
+    #[test]
+    fn test_per_tool_allow_list() {
+        let p = policy();
+        assert!(p.check_host("http_request", "api.github.com", &[]).is_ok());
+        assert!(p
+            .check_host("http_request", "uploads.api.github.com", &[])
+            .is_ok());
+        assert!(p.check_host("http_request", "example.com", &[]).is_err());
+        assert!(p.check_host("http_request", "10.2.3.4", &[]).is_ok());
+        // Inherited global deny still wins.
+        assert!(p
+            .check_host("http_request", "a.internal.corp", &[])
+            .is_err());
+        assert!(p.needs_resolution("http_request"));
+    }
+
+    #[test]
+    fn test_capability_scope() {
+        let none = EgressPolicy::for_scope(&NetworkScope::None);
+        assert!(none.check_host("cap", "example.com", &[]).is_err());
+        let scoped = EgressPolicy::for_scope(&NetworkScope::Domains {
+            domains: vec!["wttr.in".into()],
+        });
+        assert!(scoped.check_host("cap", "v2.wttr.in", &[]).is_ok());
+        assert!(scoped.check_host("cap", "attacker.test", &[]).is_err());
+    }
+
+    #[test]
+    fn test_disabled_allows_everything() {
+        let p = EgressPolicy::new(&EgressConfig {
+            deny: vec!["*".into()],
+            ..Default::default()
+        });
+        assert!(p.check_host("web_fetch", "example.com", &[]).is_ok());
+        assert!(!p.needs_resolution("web_fetch"));
+    }
+}
@@ -0,0 +1,373 @@
+//! Expiring share links for workspace files (`<agent base>/file_shares.json`).
+//!
+//! A share hands one workspace file to someone without exposing the API token
+//! or WebUI password. Links expire, can be password-protected and can be capped
+//! to a number of downloads. The gateway serves them at `/s/<token>`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::config::GatewayConfig;
+use crate::{Error, Paths, Result};
+
+/// Default lifetime when the caller does not pass one (24 hours).
+pub const DEFAULT_SHARE_TTL_SECS: u64 = 24 * 3600;
+/// Failed password attempts after which a link stops accepting guesses.
+pub const MAX_PASSWORD_ATTEMPTS: u32 = 10;
+const HASH_ROUNDS: u32 = 50_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PasswordHash {
+    salt: String,
+    hash: String,
+}
+
+impl PasswordHash {
+    fn new(password: &str) -> Self {
+        let salt = uuid::Uuid::new_v4().simple().to_string();
+        let hash = hash_password(&salt, password);
+        Self { salt, hash }
+    }
+
+    fn matches(&self, password: &str) -> bool {
+        let candidate = hash_password(&self.salt, password);
+        candidate.len() == self.hash.len()
+            && candidate
+                .bytes()
+                .zip(self.hash.bytes())
+                .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+                == 0
+    }
+}
+
+/// Iterated salted SHA-256; enough to make a leaked shares file useless for
+/// guessing short passwords offline.
+fn hash_password(salt: &str, password: &str) -> String {
+    let mut digest = Sha256::new()
+        .chain_update(salt.as_bytes())
+        .chain_update(password.as_bytes())
+        .finalize();
+    for _ in 1..HASH_ROUNDS {
+        digest = Sha256::new()
+            .chain_update(digest)
+            .chain_update(salt.as_bytes())
+            .finalize();
+    }
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareLink {
+    pub token: String,
+    /// Workspace-relative path of the shared file.
+    pub path: String,
+    pub created_at: i64,
+    pub expires_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    password: Option<PasswordHash>,
+    #[serde(default)]
+    pub max_downloads: Option<u32>,
+    #[serde(default)]
+    pub downloads: u32,
+    #[serde(default)]
+    pub failed_attempts: u32,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+impl ShareLink {
+    pub fn password_protected(&self) -> bool {
+        self.password.is_some()
+    }
+
+    pub fn check_password(&self, password: &str) -> bool {
+        match &self.password {
+            Some(hash) => hash.matches(password),
+            None => true,
+        }
+    }
+
+    /// Expired, exhausted or locked out after too many wrong passwords.
+    pub fn is_usable(&self, now: i64) -> bool {
+        now < self.expires_at
+            && self.max_downloads.is_none_or(|max| self.downloads < max)
+            && self.failed_attempts < MAX_PASSWORD_ATTEMPTS
+    }
+
+    /// API-facing view (never includes the password hash).
+    pub fn to_json(&self, gateway: &GatewayConfig) -> serde_json::Value {
+        serde_json::json!({
+            "token": self.token,
+            "url": share_url(gateway, &self.token),
+            "path": self.path,
+            "created_at": chrono::DateTime::from_timestamp(self.created_at, 0).map(|t| t.to_rfc3339()),
+            "expires_at": chrono::DateTime::from_timestamp(self.expires_at, 0).map(|t| t.to_rfc3339()),
+            "password_protected": self.password_protected(),
+            "max_downloads": self.max_downloads,
+            "downloads": self.downloads,
+            "note": self.note,
+        })
+    }
+}
+
+/// Options for [`ShareStore::create`].
+#[derive(Debug, Default, Clone)]
+pub struct NewShare<'a> {
+    pub ttl_secs: Option<u64>,
+    pub password: Option<&'a str>,
+    pub max_downloads: Option<u32>,
+    pub note: Option<&'a str>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SharesFile {
+    #[serde(default)]
+    version: u32,
+    #[serde(default)]
+    shares: BTreeMap<String, ShareLink>,
+}
+
+#[derive(Debug)]
+pub struct ShareStore {
+    path: PathBuf,
+    data: SharesFile,
+}
+
+impl ShareStore {
+    pub fn open(paths: &Paths) -> Result<Self> {
+        Self::open_at(paths.file_shares_file())
+    }
+
+    pub fn open_at(path: PathBuf) -> Result<Self> {
+        let data = if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            serde_json::from_str(&content)?
+        } else {
+            SharesFile {
+                version: 1,
+                ..Default::default()
+            }
+        };
+        Ok(Self { path, data })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.data)?)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = std::fs::set_permissions(&self.path, std::fs::Permissions::from_mode(0o600));
+        }
+        Ok(())
+    }
+
+    /// Create a link for `rel_path` (already validated as workspace-relative).
+    /// `max_ttl_secs` caps the requested lifetime.
+    pub fn create(
+        &mut self,
+        rel_path: &str,
+        opts: NewShare<'_>,
+        max_ttl_secs: u64,
+    ) -> Result<ShareLink> {
+        let ttl = opts.ttl_secs.unwrap_or(DEFAULT_SHARE_TTL_SECS);
+        if ttl == 0 {
+            return Err(Error::Validation("expires_in_secs must be > 0".to_string()));
+        }
+        if ttl > max_ttl_secs {
+            return Err(Error::Validation(format!(
+                "expires_in_secs {} exceeds gateway.shareMaxTtlSecs ({})",
+                ttl, max_ttl_secs
+            )));
+        }
+        let password = opts
+            .password
+            .filter(|p| !p.is_empty())
+            .map(PasswordHash::new);
+        let now = Utc::now().timestamp();
+        self.prune(now);
+        let link = ShareLink {
+            token: format!(
+                "{}{}",
+                uuid::Uuid::new_v4().simple(),
+                uuid::Uuid::new_v4().simple()
+            ),
+            path: rel_path.to_string(),
+            created_at: now,
+            expires_at: now + ttl as i64,
+            password,
+            max_downloads: opts.max_downloads.filter(|n| *n > 0),
+            downloads: 0,
+            failed_attempts: 0,
+            note: opts.note.map(String::from).filter(|n| !n.trim().is_empty()),
+        };
+        self.data.shares.insert(link.token.clone(), link.clone());
+        Ok(link)
+    }
+
+    pub fn get(&self, token: &str) -> Option<&ShareLink> {
+        self.data.shares.get(token)
+    }
+
+    pub fn get_mut(&mut self, token: &str) -> Option<&mut ShareLink> {
+        self.data.shares.get_mut(token)
+    }
+
+    /// Active links, newest first.
+    pub fn list(&self, now: i64) -> Vec<&ShareLink> {
+        let mut links: Vec<&ShareLink> = self
+            .data
+            .shares
+            .values()
+            .filter(|l| l.is_usable(now))
+            .collect();
+        links.sort_by_key(|l| std::cmp::Reverse(l.created_at));
+        links
+    }
+
+    pub fn revoke(&mut self, token: &str) -> bool {
+        self.data.shares.remove(token).is_some()
+    }
+
+    /// Drop links that can no longer be used. Returns how many were removed.
+    pub fn prune(&mut self, now: i64) -> usize {
+        let before = self.data.shares.len();
+        self.data.shares.retain(|_, l| l.is_usable(now));
+        before - self.data.shares.len()
+    }
+}
+
+/// Absolute URL for a share token, using `gateway.publicApiBase` when set.
+pub fn share_url(gateway: &GatewayConfig, token: &str) -> String {
+    let base = match gateway.public_api_base.as_deref().map(str::trim) {
+        Some(base) if !base.is_empty() => base.trim_end_matches('/').to_string(),
+        _ => format!("http://{}:{}", gateway.host, gateway.port),
+    };
+    format!("{}/s/{}", base, token)
+}
+
+/// Reject absolute paths and `..` so a share can only name a file inside the workspace.
+pub fn validate_share_path(path: &str) -> Result<String> {
+    let trimmed = path.trim().trim_start_matches("./");
+    let p = Path::new(trimmed);
+    if trimmed.is_empty()
+        || p.is_absolute()
+        || p.components()
+            .any(|c| !matches!(c, std::path::Component::Normal(_)))
+    {
+        return Err(Error::Validation(format!(
+            "Share path must be workspace-relative: '{}'",
+            path
+        )));
+    }
+    Ok(trimmed.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_store() -> (PathBuf, ShareStore) {
+        let dir = std::env::temp_dir().join(format!("blockcell-shares-{}", uuid::Uuid::new_v4()));
+        let store = ShareStore::open_at(dir.join("file_shares.json")).unwrap();
+        (dir, store)
+    }
+
+    #[test]
+    fn test_create_password_and_roundtrip() {
+        let (dir, mut store) = temp_store();
+        let link = store
+            .create(
+                "reports/q3.pdf",
+                NewShare {
+                    password: Some("hunter2"),
+                    max_downloads: Some(2),
+                    ..Default::default()
+                },
+                7 * 86400,
+            )
+            .unwrap();
+        assert_eq!(link.token.len(), 64);
+        assert!(link.password_protected());
+        assert!(link.check_password("hunter2"));
+        assert!(!link.check_password("hunter3"));
+        assert_eq!(
+            link.expires_at - link.created_at,
+            DEFAULT_SHARE_TTL_SECS as i64
+        );
+        store.save().unwrap();
+
+        let raw = std::fs::read_to_string(dir.join("file_shares.json")).unwrap();
+        assert!(!raw.contains("hunter2"));
+        let reopened = ShareStore::open_at(dir.join("file_shares.json")).unwrap();
+        assert!(reopened.get(&link.token).unwrap().check_password("hunter2"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_usability_and_prune() {
+        let (_dir, mut store) = temp_store();
+        let opts = NewShare {
+            ttl_secs: Some(60),
+            max_downloads: Some(1),
+            ..Default::default()
+        };
+        let token = store.create("a.txt", opts, 3600).unwrap().token;
+        let now = Utc::now().timestamp();
+        assert_eq!(store.list(now).len(), 1);
+        assert!(!store.get(&token).unwrap().is_usable(now + 61));
+
+        store.get_mut(&token).unwrap().downloads = 1;
+        assert!(!store.get(&token).unwrap().is_usable(now));
+        assert_eq!(store.prune(now), 1);
+        assert!(store.get(&token).is_none());
+
+        let locked = store
+            .create("b.txt", NewShare::default(), 86400)
+            .unwrap()
+            .token;
+        store.get_mut(&locked).unwrap().failed_attempts = MAX_PASSWORD_ATTEMPTS;
+        assert!(store.list(now).is_empty());
+        assert!(store.revoke(&locked));
+        assert!(!store.revoke(&locked));
+    }
+
+    #[test]
+    fn test_ttl_limits_and_paths() {
+        let (_dir, mut store) = temp_store();
+        let too_long = NewShare {
+            ttl_secs: Some(10_000),
+            ..Default::default()
+        };
+        assert!(store.create("a.txt", too_long, 3600).is_err());
+        let zero = NewShare {
+            ttl_secs: Some(0),
+            ..Default::default()
+        };
+        assert!(store.create("a.txt", zero, 3600).is_err());
+
+        assert_eq!(
+            validate_share_path("./out/report.md").unwrap(),
+            "out/report.md"
+        );
+        assert!(validate_share_path("../secrets.json").is_err());
+        assert!(validate_share_path("/etc/passwd").is_err());
+        assert!(validate_share_path("out/../../x").is_err());
+        assert!(validate_share_path("").is_err());
+    }
+
+    #[test]
+    fn test_share_url() {
+        let mut gw = GatewayConfig::default();
+        assert_eq!(share_url(&gw, "abc"), "http://localhost:18790/s/abc");
+        gw.public_api_base = Some("https://bot.example.com/api/".to_string());
+        assert_eq!(share_url(&gw, "abc"), "https://bot.example.com/api/s/abc");
+    }
+}
@@ -0,0 +1,300 @@
+//! Named webhook triggers for `POST /v1/hooks/:name` (`<workspace>/hooks.json`).
+//!
+//! Where `gateway.ingest` sources only ever wake the agent, a hook is a saved recipe:
+//! render the payload into an agent message (optionally forcing a skill), or call one
+//! tool directly with parameters rendered from the payload. Hooks are managed with
+//! `blockcell hooks list/add/remove` and picked up by the gateway on the next request.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{ingest, Error, Paths, Result};
+
+/// Headers checked, in order, when a hook has a secret but no `signatureHeader`.
+const DEFAULT_SIGNATURE_HEADERS: &[&str] =
+    &["x-hub-signature-256", "stripe-signature", "x-signature-256"];
+
+/// What a hook does with an event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HookTarget {
+    /// Send the rendered template to the agent, optionally forcing a skill.
+    Agent {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        skill: Option<String>,
+    },
+    /// Call a tool without involving the model. String values in `params` are
+    /// templates rendered against the payload.
+    Tool {
+        name: String,
+        #[serde(default)]
+        params: Value,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookDef {
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub target: HookTarget,
+    /// Message template for agent hooks; same placeholders as `gateway.ingest`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// HMAC-SHA256 signing secret, literal or a `secret:<name>` / `env:<VAR>` reference.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature_header: Option<String>,
+    /// Only these events trigger the hook (see `ingest::event_allowed`). Empty = all.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+    /// Channel and chat the agent replies on. Default: `webhook` / `hook_<name>`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chat_id: Option<String>,
+    #[serde(default)]
+    pub created_at: i64,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl HookDef {
+    /// Header the signature is read from: `signatureHeader`, else the first of the
+    /// GitHub / Stripe / generic headers present in the request.
+    pub fn signature_header(&self, present: impl Fn(&str) -> bool) -> String {
+        if let Some(header) = self.signature_header.as_deref().filter(|h| !h.is_empty()) {
+            return header.to_ascii_lowercase();
+        }
+        DEFAULT_SIGNATURE_HEADERS
+            .iter()
+            .find(|h| present(h))
+            .unwrap_or(&DEFAULT_SIGNATURE_HEADERS[2])
+            .to_string()
+    }
+
+    /// Tool parameters for one event: every string in `params` rendered as a template.
+    pub fn render_params(
+        &self,
+        payload: &Value,
+        headers: &BTreeMap<String, String>,
+        max_payload_chars: usize,
+    ) -> Value {
+        fn walk(
+            value: &Value,
+            name: &str,
+            payload: &Value,
+            headers: &BTreeMap<String, String>,
+            max: usize,
+        ) -> Value {
+            match value {
+                Value::String(s) if s.contains("{{") => {
+                    Value::String(ingest::render(s, name, payload, headers, max))
+                }
+                Value::Array(items) => Value::Array(
+                    items
+                        .iter()
+                        .map(|v| walk(v, name, payload, headers, max))
+                        .collect(),
+                ),
+                Value::Object(map) => Value::Object(
+                    map.iter()
+                        .map(|(k, v)| (k.clone(), walk(v, name, payload, headers, max)))
+                        .collect(),
+                ),
+                other => other.clone(),
+            }
+        }
+        match &self.target {
+            HookTarget::Tool { params, .. } => {
+                walk(params, &self.name, payload, headers, max_payload_chars)
+            }
+            HookTarget::Agent { .. } => Value::Null,
+        }
+    }
+}
+
+/// Hook names appear in URLs: letters, digits, `-` and `_`, at most 64 characters.
+pub fn validate_hook_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::Validation(format!(
+            "Invalid hook name '{}': use letters, digits, '-' or '_' (max 64)",
+            name
+        )))
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HooksFile {
+    #[serde(default)]
+    version: u32,
+    #[serde(default)]
+    hooks: BTreeMap<String, HookDef>,
+}
+
+#[derive(Debug)]
+pub struct HookStore {
+    path: PathBuf,
+    data: HooksFile,
+}
+
+impl HookStore {
+    pub fn open(paths: &Paths) -> Result<Self> {
+        Self::open_at(paths.hooks_file())
+    }
+
+    pub fn open_at(path: PathBuf) -> Result<Self> {
+        let data = if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            serde_json::from_str(&content)?
+        } else {
+            HooksFile {
+                version: 1,
+                ..Default::default()
+            }
+        };
+        Ok(Self { path, data })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.data)?)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = std::fs::set_permissions(&self.path, std::fs::Permissions::from_mode(0o600));
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&HookDef> {
+        self.data.hooks.get(name)
+    }
+
+    /// Hooks sorted by name.
+    pub fn list(&self) -> Vec<&HookDef> {
+        self.data.hooks.values().collect()
+    }
+
+    /// Add or replace a hook. Returns whether one with the same name was replaced.
+    pub fn upsert(&mut self, hook: HookDef) -> Result<bool> {
+        validate_hook_name(&hook.name)?;
+        if let HookTarget::Tool { name, params } = &hook.target {
+            if name.trim().is_empty() {
+                return Err(Error::Validation("Tool hooks need a tool name".to_string()));
+            }
+            if !(params.is_object() || params.is_null()) {
+                return Err(Error::Validation(
+                    "Tool hook params must be a JSON object".to_string(),
+                ));
+            }
+        }
+        Ok(self.data.hooks.insert(hook.name.clone(), hook).is_some())
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.data.hooks.remove(name).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tool_hook() -> HookDef {
+        HookDef {
+            name: "grafana-alert".to_string(),
+            enabled: true,
+            description: None,
+            target: HookTarget::Tool {
+                name: "notification".to_string(),
+                params: json!({
+                    "message": "{{title}} is {{state}}",
+                    "tags": ["{{headers.x-source}}", 3],
+                    "priority": 2,
+                }),
+            },
+            template: None,
+            secret: None,
+            signature_header: None,
+            events: vec![],
+            agent: None,
+            channel: None,
+            chat_id: None,
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_store_round_trip_and_validation() {
+        let dir = std::env::temp_dir().join(format!("blockcell-hooks-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("hooks.json");
+        let mut store = HookStore::open_at(path.clone()).unwrap();
+        assert!(!store.upsert(tool_hook()).unwrap());
+        assert!(store.upsert(tool_hook()).unwrap());
+
+        let mut bad = tool_hook();
+        bad.name = "../x".to_string();
+        assert!(store.upsert(bad).is_err());
+        let mut bad = tool_hook();
+        bad.target = HookTarget::Tool {
+            name: "notification".to_string(),
+            params: json!("text"),
+        };
+        assert!(store.upsert(bad).is_err());
+        store.save().unwrap();
+
+        let mut store = HookStore::open_at(path).unwrap();
+        assert_eq!(store.list().len(), 1);
+        assert!(store.get("grafana-alert").unwrap().enabled);
+        assert!(store.remove("grafana-alert"));
+        assert!(!store.remove("grafana-alert"));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_render_params_and_signature_header() {
+        let hook = tool_hook();
+        let mut headers = BTreeMap::new();
+        headers.insert("x-source".to_string(), "grafana".to_string());
+        let params = hook.render_params(
+            &json!({"title": "CPU high", "state": "alerting"}),
+            &headers,
+            1000,
+        );
+        assert_eq!(
+            params,
+            json!({"message": "CPU high is alerting", "tags": ["grafana", 3], "priority": 2})
+        );
+
+        assert_eq!(
+            hook.signature_header(|h| h == "stripe-signature"),
+            "stripe-signature"
+        );
+        assert_eq!(hook.signature_header(|_| false), "x-signature-256");
+        let mut hook = hook;
+        hook.signature_header = Some("X-Grafana-Signature".to_string());
+        assert_eq!(hook.signature_header(|_| true), "x-grafana-signature");
+    }
+}
@@ -0,0 +1,293 @@
+//! Webhook ingestion for `POST /v1/ingest/:source`: signature checks and message templates.
+//!
+//! External systems (GitHub, Grafana, Stripe, ...) post arbitrary JSON; the gateway
+//! verifies the optional HMAC signature and renders the payload into the text of an
+//! `InboundMessage` using the per-source template from `gateway.ingest`.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::config::IngestSourceConfig;
+use crate::{Error, Result};
+
+/// Template used when a source has none configured.
+pub const DEFAULT_TEMPLATE: &str = "[Webhook: {{source}}] External event received:\n{{payload}}";
+/// Stripe rejects signatures older than this; we do the same.
+const STRIPE_TOLERANCE_SECS: i64 = 300;
+
+/// Header the signature is read from for `source`.
+pub fn signature_header(source: &str, cfg: &IngestSourceConfig) -> String {
+    if let Some(header) = cfg.signature_header.as_deref().filter(|h| !h.is_empty()) {
+        return header.to_ascii_lowercase();
+    }
+    match source {
+        "github" => "x-hub-signature-256",
+        "stripe" => "stripe-signature",
+        _ => "x-signature-256",
+    }
+    .to_string()
+}
+
+/// Check `header` against the HMAC-SHA256 of `body`.
+///
+/// Stripe-style values (`t=<unix>,v1=<hex>`) sign `"<t>.<body>"` and must be recent;
+/// anything else is a hex digest with an optional `sha256=` prefix.
+pub fn verify_signature(
+    secret: &str,
+    header: Option<&str>,
+    body: &[u8],
+    now_secs: i64,
+) -> Result<()> {
+    let header = header
+        .map(str::trim)
+        .filter(|h| !h.is_empty())
+        .ok_or_else(|| Error::PermissionDenied("Missing webhook signature".to_string()))?;
+
+    if header.contains("v1=") && header.contains("t=") {
+        let mut timestamp = None;
+        let mut candidates = Vec::new();
+        for part in header.split(',') {
+            match part.trim().split_once('=') {
+                Some(("t", t)) => timestamp = t.parse::<i64>().ok(),
+                Some(("v1", sig)) => candidates.push(sig.to_ascii_lowercase()),
+                _ => {}
+            }
+        }
+        let timestamp = timestamp
+            .ok_or_else(|| Error::PermissionDenied("Malformed webhook signature".to_string()))?;
+        if (now_secs - timestamp).abs() > STRIPE_TOLERANCE_SECS {
+            return Err(Error::PermissionDenied(
+                "Webhook signature timestamp outside tolerance".to_string(),
+            ));
+        }
+        let mut signed = format!("{}.", timestamp).into_bytes();
+        signed.extend_from_slice(body);
+        let expected = to_hex(&hmac_sha256(secret.as_bytes(), &signed));
+        if candidates.iter().any(|c| constant_time_eq(c, &expected)) {
+            return Ok(());
+        }
+        return Err(Error::PermissionDenied(
+            "Webhook signature mismatch".to_string(),
+        ));
+    }
+
+    let given = header
+        .strip_prefix("sha256=")
+        .unwrap_or(header)
+        .to_ascii_lowercase();
+    let expected = to_hex(&hmac_sha256(secret.as_bytes(), body));
+    if constant_time_eq(&given, &expected) {
+        Ok(())
+    } else {
+        Err(Error::PermissionDenied(
+            "Webhook signature mismatch".to_string(),
+        ))
+    }
+}
+
+/// Event name used by `IngestSourceConfig::events`: `X-GitHub-Event` (with `.<action>`
+/// when the payload has one), else the payload `type` field (Stripe), else `None`.
+pub fn event_name(headers: &BTreeMap<String, String>, payload: &Value) -> Option<String> {
+    if let Some(event) = headers.get("x-github-event") {
+        return Some(match payload.get("action").and_then(|a| a.as_str()) {
+            Some(action) => format!("{}.{}", event, action),
+            None => event.clone(),
+        });
+    }
+    payload
+        .get("type")
+        .and_then(|t| t.as_str())
+        .map(str::to_string)
+}
+
+/// Whether an event passes the `events` filter. `pull_request` matches every
+/// `pull_request.<action>`; an empty filter matches everything.
+pub fn event_allowed(filter: &[String], event: Option<&str>) -> bool {
+    if filter.is_empty() {
+        return true;
+    }
+    let Some(event) = event else {
+        return false;
+    };
+    filter.iter().any(|f| {
+        let f = f.trim();
+        f == event
+            || event
+                .strip_prefix(f)
+                .is_some_and(|rest| rest.starts_with('.'))
+    })
+}
+
+/// Render `template` for one event. Unknown placeholders render as empty strings.
+pub fn render(
+    template: &str,
+    source: &str,
+    payload: &Value,
+    headers: &BTreeMap<String, String>,
+    max_payload_chars: usize,
+) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            out.push_str(&rest[start..]);
+            return out.trim().to_string();
+        };
+        let key = after[..end].trim();
+        out.push_str(&placeholder(
+            key,
+            source,
+            payload,
+            headers,
+            max_payload_chars,
+        ));
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    out.trim().to_string()
+}
+
+fn placeholder(
+    key: &str,
+    source: &str,
+    payload: &Value,
+    headers: &BTreeMap<String, String>,
+    max_payload_chars: usize,
+) -> String {
+    match key {
+        "source" => return source.to_string(),
+        "payload" => {
+            let text = serde_json::to_string_pretty(payload).unwrap_or_default();
+            if text.chars().count() <= max_payload_chars {
+                return text;
+            }
+            let mut cut: String = text.chars().take(max_payload_chars).collect();
+            cut.push_str("\n…(truncated)");
+            return cut;
+        }
+        _ => {}
+    }
+    if let Some(name) = key.strip_prefix("headers.") {
+        return headers
+            .get(&name.to_ascii_lowercase())
+            .cloned()
+            .unwrap_or_default();
+    }
+    let mut current = payload;
+    for segment in key.split('.') {
+        let next = match current {
+            Value::Object(map) => map.get(segment),
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        };
+        match next {
+            Some(value) => current = value,
+            None => return String::new(),
+        }
+    }
+    match current {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// HMAC-SHA256 (RFC 2104). Shared by webhook signatures, S3 request signing and
+/// privacy report signing.
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK: usize = 64;
+    let mut block = [0u8; BLOCK];
+    if key.len() > BLOCK {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let mut inner = Sha256::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    inner.update(message);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_hex_and_stripe_signatures() {
+        let body = br#"{"action":"opened"}"#;
+        let sig = to_hex(&hmac_sha256(b"s3cret", body));
+        assert!(verify_signature("s3cret", Some(&format!("sha256={}", sig)), body, 0).is_ok());
+        assert!(verify_signature("s3cret", Some(&sig), body, 0).is_ok());
+        assert!(verify_signature("other", Some(&sig), body, 0).is_err());
+        assert!(verify_signature("s3cret", None, body, 0).is_err());
+
+        let mut signed = b"1700000000.".to_vec();
+        signed.extend_from_slice(body);
+        let stripe = format!(
+            "t=1700000000,v1={}",
+            to_hex(&hmac_sha256(b"whsec", &signed))
+        );
+        assert!(verify_signature("whsec", Some(&stripe), body, 1_700_000_100).is_ok());
+        assert!(verify_signature("whsec", Some(&stripe), body, 1_700_001_000).is_err());
+    }
+
+    #[test]
+    fn test_render_template() {
+        let payload = json!({
+            "action": "opened",
+            "pull_request": {"title": "Fix bug", "number": 42},
+            "labels": [{"name": "urgent"}]
+        });
+        let mut headers = BTreeMap::new();
+        headers.insert("x-github-event".to_string(), "pull_request".to_string());
+        let text = render(
+            "{{source}} {{headers.X-GitHub-Event}}: #{{pull_request.number}} {{pull_request.title}} ({{action}}, {{labels.0.name}}){{missing.key}}",
+            "github",
+            &payload,
+            &headers,
+            100,
+        );
+        assert_eq!(text, "github pull_request: #42 Fix bug (opened, urgent)");
+
+        let truncated = render("{{payload}}", "x", &payload, &BTreeMap::new(), 10);
+        assert!(truncated.ends_with("(truncated)"));
+        assert_eq!(
+            signature_header("stripe", &IngestSourceConfig::default()),
+            "stripe-signature"
+        );
+
+        let event = event_name(&headers, &payload);
+        assert_eq!(event.as_deref(), Some("pull_request.opened"));
+        let filter = vec!["pull_request".to_string()];
+        assert!(event_allowed(&filter, event.as_deref()));
+        assert!(!event_allowed(
+            &filter,
+            Some("pull_request_review.submitted")
+        ));
+        assert!(!event_allowed(
+            &["issues.opened".to_string()],
+            event.as_deref()
+        ));
+        assert!(event_allowed(&[], None));
+    }
+}
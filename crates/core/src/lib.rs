@@ -1,17 +1,29 @@
 pub mod capability;
 pub mod config;
+pub mod config_migration;
+pub mod connectivity;
+pub mod egress;
 pub mod error;
+pub mod file_share;
+pub mod hooks;
+pub mod ingest;
+pub mod login_guard;
+pub mod maintenance;
 pub mod mcp_config;
 pub mod message;
+pub mod model_meta;
 pub mod path_policy;
 pub mod paths;
+pub mod permissions;
+pub mod secrets;
 pub mod session_key;
 pub mod system_event;
 pub mod types;
+pub mod webui_auth;
 
 pub use capability::{
     CapabilityCost, CapabilityDescriptor, CapabilityLifecycle, CapabilityStatus, CapabilityType,
-    PrivilegeLevel, ProviderKind, SurvivalInvariants,
+    NetworkScope, PrivilegeLevel, ProviderKind, SurvivalInvariants,
 };
 pub use config::Config;
 pub use error::{Error, Result};
@@ -0,0 +1,336 @@
+//! Password login lockouts (`<base>/login_lockouts.json`).
+//!
+//! The gateway records failed WebUI logins per client address and locks an address
+//! out after `gateway.loginLimit.maxFailures`, doubling the lockout on every repeat.
+//! `blockcell auth unlock` edits the same file; the gateway notices the change on the
+//! next login attempt.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::config::LoginLimitConfig;
+use crate::{Paths, Result};
+
+/// Compare a submitted password with the expected one in constant time. Both sides are
+/// hashed first, so neither the length nor a common prefix shows in the timing.
+pub fn password_matches(given: &str, expected: &str) -> bool {
+    let a = Sha256::digest(given.as_bytes());
+    let b = Sha256::digest(expected.as_bytes());
+    a.iter()
+        .zip(b.iter())
+        .fold(0u8, |diff, (x, y)| diff | (x ^ y))
+        == 0
+}
+
+/// What a lockout applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LockoutScope {
+    /// One client address.
+    Address,
+    /// Every password login, after too many failures overall.
+    Global,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lockout {
+    pub scope: LockoutScope,
+    pub until: i64,
+    pub retry_after_secs: u64,
+}
+
+impl Lockout {
+    fn new(scope: LockoutScope, until: i64, now: i64) -> Self {
+        Self {
+            scope,
+            until,
+            retry_after_secs: (until - now).max(1) as u64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressRecord {
+    /// Unix times of recent failures.
+    #[serde(default)]
+    pub failures: Vec<i64>,
+    /// Lockouts so far; the next one lasts `lockoutSecs * 2^lockouts`.
+    #[serde(default)]
+    pub lockouts: u32,
+    #[serde(default)]
+    pub locked_until: i64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LoginGuardFile {
+    #[serde(default)]
+    addresses: BTreeMap<String, AddressRecord>,
+    #[serde(default)]
+    global_failures: Vec<i64>,
+    #[serde(default)]
+    global_locked_until: i64,
+}
+
+#[derive(Debug)]
+pub struct LoginGuard {
+    path: PathBuf,
+    data: LoginGuardFile,
+    /// (mtime, size) of the file as last read or written.
+    stamp: Option<(SystemTime, u64)>,
+}
+
+impl LoginGuard {
+    pub fn open(paths: &Paths) -> Result<Self> {
+        Self::open_at(paths.login_lockouts_file())
+    }
+
+    pub fn open_at(path: PathBuf) -> Result<Self> {
+        let mut guard = Self {
+            path,
+            data: LoginGuardFile::default(),
+            stamp: None,
+        };
+        guard.reload_if_changed()?;
+        Ok(guard)
+    }
+
+    fn file_stamp(&self) -> Option<(SystemTime, u64)> {
+        let meta = std::fs::metadata(&self.path).ok()?;
+        Some((meta.modified().ok()?, meta.len()))
+    }
+
+    /// Re-read the file if something else (the CLI) wrote it. Returns whether it did.
+    pub fn reload_if_changed(&mut self) -> Result<bool> {
+        let stamp = self.file_stamp();
+        if stamp == self.stamp {
+            return Ok(false);
+        }
+        self.data = match stamp {
+            Some(_) => serde_json::from_str(&std::fs::read_to_string(&self.path)?)?,
+            None => LoginGuardFile::default(),
+        };
+        self.stamp = stamp;
+        Ok(true)
+    }
+
+    pub fn save(&mut self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.data)?)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = std::fs::set_permissions(&self.path, std::fs::Permissions::from_mode(0o600));
+        }
+        self.stamp = self.file_stamp();
+        Ok(())
+    }
+
+    /// The lockout blocking a login from `addr` right now, if any.
+    pub fn check(&self, addr: &str, now: i64) -> Option<Lockout> {
+        if self.data.global_locked_until > now {
+            return Some(Lockout::new(
+                LockoutScope::Global,
+                self.data.global_locked_until,
+                now,
+            ));
+        }
+        self.data
+            .addresses
+            .get(addr)
+            .filter(|record| record.locked_until > now)
+            .map(|record| Lockout::new(LockoutScope::Address, record.locked_until, now))
+    }
+
+    /// Record a failed login. Returns the lockout it started, if any.
+    pub fn record_failure(
+        &mut self,
+        limits: &LoginLimitConfig,
+        addr: &str,
+        now: i64,
+    ) -> Option<Lockout> {
+        self.prune(limits, now);
+        let window = limits.window_secs as i64;
+        let mut started = None;
+
+        let record = self.data.addresses.entry(addr.to_string()).or_default();
+        record.failures.retain(|t| now - t < window);
+        record.failures.push(now);
+        if record.failures.len() >= limits.max_failures as usize {
+            let secs = limits
+                .lockout_secs
+                .saturating_mul(1u64 << record.lockouts.min(32))
+                .min(limits.max_lockout_secs);
+            record.lockouts += 1;
+            record.locked_until = now + secs as i64;
+            record.failures.clear();
+            started = Some(Lockout::new(
+                LockoutScope::Address,
+                record.locked_until,
+                now,
+            ));
+        }
+
+        if limits.global_max_failures > 0 {
+            let global = &mut self.data.global_failures;
+            global.retain(|t| now - t < window);
+            global.push(now);
+            if global.len() >= limits.global_max_failures as usize {
+                global.clear();
+                self.data.global_locked_until = now + limits.global_lockout_secs as i64;
+                started = Some(Lockout::new(
+                    LockoutScope::Global,
+                    self.data.global_locked_until,
+                    now,
+                ));
+            }
+        }
+        started
+    }
+
+    /// Forget the failures of an address after a successful login. Returns whether
+    /// there was anything to forget.
+    pub fn record_success(&mut self, addr: &str) -> bool {
+        self.data.addresses.remove(addr).is_some()
+    }
+
+    /// Addresses with recent failures or lockouts.
+    pub fn addresses(&self) -> &BTreeMap<String, AddressRecord> {
+        &self.data.addresses
+    }
+
+    /// End of the global pause (0 or in the past when there is none).
+    pub fn global_locked_until(&self) -> i64 {
+        self.data.global_locked_until
+    }
+
+    /// Lift the lockout of one address and forget its failures.
+    pub fn unlock(&mut self, addr: &str) -> bool {
+        self.data.addresses.remove(addr).is_some()
+    }
+
+    /// Lift every lockout, including the global one. Returns how many addresses were
+    /// cleared.
+    pub fn unlock_all(&mut self) -> usize {
+        let count = self.data.addresses.len();
+        self.data = LoginGuardFile::default();
+        count
+    }
+
+    /// Drop addresses with no recent failures whose last lockout ended more than
+    /// `maxLockoutSecs` ago; their next lockout starts from `lockoutSecs` again.
+    pub fn prune(&mut self, limits: &LoginLimitConfig, now: i64) {
+        let window = limits.window_secs as i64;
+        let memory = limits.max_lockout_secs as i64;
+        self.data.addresses.retain(|_, record| {
+            record.failures.iter().any(|t| now - t < window)
+                || now < record.locked_until.saturating_add(memory)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> LoginLimitConfig {
+        LoginLimitConfig {
+            max_failures: 3,
+            window_secs: 600,
+            lockout_secs: 60,
+            max_lockout_secs: 200,
+            global_max_failures: 10,
+            global_lockout_secs: 300,
+            ..Default::default()
+        }
+    }
+
+    fn temp_path() -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("blockcell-login-guard-{}", uuid::Uuid::new_v4()))
+            .join("login_lockouts.json")
+    }
+
+    #[test]
+    fn test_lockouts_double_and_go_global() {
+        let limits = limits();
+        let mut guard = LoginGuard::open_at(temp_path()).unwrap();
+        let now = 1_000_000;
+
+        assert!(guard.record_failure(&limits, "1.2.3.4", now).is_none());
+        assert!(guard.record_failure(&limits, "1.2.3.4", now + 1).is_none());
+        let first = guard.record_failure(&limits, "1.2.3.4", now + 2).unwrap();
+        assert_eq!(first.scope, LockoutScope::Address);
+        assert_eq!(first.retry_after_secs, 60);
+        assert!(guard.check("1.2.3.4", now + 30).is_some());
+        assert!(guard.check("5.6.7.8", now + 30).is_none());
+        assert!(guard.check("1.2.3.4", now + 62).is_none());
+
+        // The next lockout doubles, up to the cap.
+        let t = now + 100;
+        for i in 0..3 {
+            guard.record_failure(&limits, "1.2.3.4", t + i);
+        }
+        assert_eq!(guard.check("1.2.3.4", t + 2).unwrap().retry_after_secs, 120);
+        let t = t + 200;
+        for i in 0..3 {
+            guard.record_failure(&limits, "1.2.3.4", t + i);
+        }
+        assert_eq!(guard.check("1.2.3.4", t + 2).unwrap().retry_after_secs, 200);
+
+        // A success forgets the address, so its next lockout is short again.
+        assert!(guard.record_success("1.2.3.4"));
+        assert!(guard.check("1.2.3.4", t + 3).is_none());
+
+        // Failures spread over many addresses pause every login.
+        let t = t + 1000;
+        let mut global = None;
+        for i in 0..10 {
+            global = guard.record_failure(&limits, &format!("10.0.0.{}", i), t);
+        }
+        assert_eq!(global.unwrap().scope, LockoutScope::Global);
+        assert_eq!(
+            guard.check("192.168.1.1", t + 1).unwrap().scope,
+            LockoutScope::Global
+        );
+
+        // Old records are pruned once their lockout is long over.
+        guard.prune(&limits, t + 10_000);
+        assert!(guard.addresses().is_empty());
+    }
+
+    #[test]
+    fn test_unlock_from_another_handle_is_picked_up() {
+        assert!(password_matches("hunter2", "hunter2"));
+        assert!(!password_matches("hunter", "hunter2"));
+        assert!(!password_matches("", "hunter2"));
+
+        let limits = limits();
+        let path = temp_path();
+        let now = chrono::Utc::now().timestamp();
+        let mut gateway = LoginGuard::open_at(path.clone()).unwrap();
+        for _ in 0..3 {
+            gateway.record_failure(&limits, "1.2.3.4", now);
+        }
+        gateway.save().unwrap();
+        assert!(!gateway.reload_if_changed().unwrap());
+
+        let mut cli = LoginGuard::open_at(path.clone()).unwrap();
+        assert!(cli.check("1.2.3.4", now).is_some());
+        assert!(cli.unlock("1.2.3.4"));
+        assert!(!cli.unlock("1.2.3.4"));
+        cli.save().unwrap();
+
+        assert!(gateway.reload_if_changed().unwrap());
+        assert!(gateway.check("1.2.3.4", now).is_none());
+        assert_eq!(gateway.unlock_all(), 0);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
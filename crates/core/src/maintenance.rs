@@ -0,0 +1,205 @@
+//! Maintenance windows shared by the updater and the background services.
+
+use chrono::{Datelike, Duration, NaiveDateTime, Timelike, Weekday};
+use tracing::warn;
+
+use crate::config::{MaintenanceConfig, MaintenanceWindowConfig};
+
+/// 维护窗口检查器
+pub struct MaintenanceWindow {
+    window: String, // 格式: "HH:MM-HH:MM"
+}
+
+impl MaintenanceWindow {
+    pub fn new(window: String) -> Self {
+        Self { window }
+    }
+
+    /// 检查当前时间是否在维护窗口内
+    pub fn is_in_window(&self) -> bool {
+        if self.window.is_empty() {
+            return true; // 没有配置维护窗口，任何时间都可以
+        }
+        let now = chrono::Local::now();
+        self.contains(now.hour(), now.minute())
+    }
+
+    /// Whether `hour:minute` falls inside the window. An unparseable window never matches.
+    pub fn contains(&self, hour: u32, minute: u32) -> bool {
+        let Some((start, end)) = self.bounds() else {
+            return false;
+        };
+        let current = (hour, minute);
+
+        // 处理跨午夜的情况
+        if start <= end {
+            current >= start && current < end
+        } else {
+            current >= start || current < end
+        }
+    }
+
+    /// Whether `hour:minute` falls in the after-midnight part of a window that crosses midnight.
+    fn after_midnight(&self, hour: u32, minute: u32) -> bool {
+        self.bounds()
+            .is_some_and(|(start, end)| start > end && (hour, minute) < end)
+    }
+
+    fn bounds(&self) -> Option<((u32, u32), (u32, u32))> {
+        let parts: Vec<&str> = self.window.split('-').collect();
+        if parts.len() != 2 {
+            warn!(window = %self.window, "Invalid maintenance window format");
+            return None;
+        }
+        Some((self.parse_time(parts[0])?, self.parse_time(parts[1])?))
+    }
+
+    fn parse_time(&self, time_str: &str) -> Option<(u32, u32)> {
+        let parts: Vec<&str> = time_str.split(':').collect();
+        if parts.len() != 2 {
+            return None;
+        }
+
+        let hour = parts[0].trim().parse::<u32>().ok()?;
+        let minute = parts[1].trim().parse::<u32>().ok()?;
+
+        if hour >= 24 || minute >= 60 {
+            return None;
+        }
+
+        Some((hour, minute))
+    }
+}
+
+fn parse_weekday(day: &str) -> Option<Weekday> {
+    day.trim().parse::<Weekday>().ok()
+}
+
+fn window_matches(window: &MaintenanceWindowConfig, at: NaiveDateTime) -> bool {
+    let time = MaintenanceWindow::new(window.time.clone());
+    if !time.contains(at.hour(), at.minute()) {
+        return false;
+    }
+    if window.days.is_empty() {
+        return true;
+    }
+    // `days` names the day a window starts on, so the after-midnight part of a
+    // wrapping window belongs to the previous day.
+    let start_day = if time.after_midnight(at.hour(), at.minute()) {
+        (at - Duration::days(1)).weekday()
+    } else {
+        at.weekday()
+    };
+    window
+        .days
+        .iter()
+        .filter_map(|d| parse_weekday(d))
+        .any(|d| d == start_day)
+}
+
+impl MaintenanceConfig {
+    /// Whether any window is configured.
+    pub fn has_windows(&self) -> bool {
+        !self.windows.is_empty()
+    }
+
+    /// Whether maintenance is active at the given local time.
+    pub fn is_active_at(&self, at: NaiveDateTime) -> bool {
+        self.windows.iter().any(|w| window_matches(w, at))
+    }
+
+    /// Whether maintenance is active now (local time).
+    pub fn is_active(&self) -> bool {
+        self.is_active_at(chrono::Local::now().naive_local())
+    }
+
+    /// Whether heavy background work should wait for the next window.
+    pub fn defers_heavy_work(&self) -> bool {
+        self.defer_heavy_jobs && self.has_windows() && !self.is_active()
+    }
+
+    /// Whether routine notifications should be silenced right now.
+    pub fn is_quiet(&self) -> bool {
+        self.quiet_notifications && self.is_active()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_parse_time() {
+        let window = MaintenanceWindow::new("03:00-05:00".to_string());
+        assert_eq!(window.parse_time("03:00"), Some((3, 0)));
+        assert_eq!(window.parse_time("23:59"), Some((23, 59)));
+        assert_eq!(window.parse_time("24:00"), None);
+        assert_eq!(window.parse_time("invalid"), None);
+        assert!(window.contains(4, 30));
+        assert!(!window.contains(5, 0));
+        assert!(MaintenanceWindow::new("23:00-02:00".to_string()).contains(1, 0));
+        assert!(!MaintenanceWindow::new("bad".to_string()).contains(1, 0));
+    }
+
+    #[test]
+    fn test_calendar_days_and_midnight_wrap() {
+        // 2026-10-17 is a Saturday.
+        let config = MaintenanceConfig {
+            windows: vec![
+                MaintenanceWindowConfig {
+                    time: "23:00-02:00".to_string(),
+                    days: vec!["sat".to_string()],
+                },
+                MaintenanceWindowConfig {
+                    time: "03:00-04:00".to_string(),
+                    days: Vec::new(),
+                },
+            ],
+            ..Default::default()
+        };
+        assert!(config.is_active_at(at("2026-10-17 23:30")));
+        assert!(config.is_active_at(at("2026-10-18 01:30")));
+        assert!(!config.is_active_at(at("2026-10-16 23:30")));
+        assert!(!config.is_active_at(at("2026-10-17 01:30")));
+        assert!(config.is_active_at(at("2026-10-15 03:15")));
+        assert!(!config.is_active_at(at("2026-10-15 12:00")));
+        assert!(!MaintenanceConfig::default().defers_heavy_work());
+    }
+
+    #[test]
+    fn test_wrap_window_ending_after_noon() {
+        let config = MaintenanceConfig {
+            windows: vec![MaintenanceWindowConfig {
+                time: "22:00-14:00".to_string(),
+                days: vec!["sat".to_string()],
+            }],
+            ..Default::default()
+        };
+        assert!(config.is_active_at(at("2026-10-17 23:00")));
+        assert!(config.is_active_at(at("2026-10-18 13:00")));
+        assert!(!config.is_active_at(at("2026-10-18 14:00")));
+        assert!(!config.is_active_at(at("2026-10-17 13:00")));
+    }
+
+    #[test]
+    fn test_day_restricted_wrap_window() {
+        // 2026-10-19 is a Monday and 2026-10-23 a Friday.
+        let config = MaintenanceConfig {
+            windows: vec![MaintenanceWindowConfig {
+                time: "20:00-06:00".to_string(),
+                days: vec!["mon".to_string(), "fri".to_string()],
+            }],
+            ..Default::default()
+        };
+        assert!(config.is_active_at(at("2026-10-19 21:00")));
+        assert!(config.is_active_at(at("2026-10-20 05:00")));
+        assert!(!config.is_active_at(at("2026-10-19 05:00")));
+        assert!(!config.is_active_at(at("2026-10-20 21:00")));
+        assert!(config.is_active_at(at("2026-10-24 05:59")));
+        assert!(!config.is_active_at(at("2026-10-24 06:00")));
+    }
+}
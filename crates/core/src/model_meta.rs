@@ -0,0 +1,135 @@
+//! Model metadata table: context window and list price per model family.
+//!
+//! Entries match by longest name prefix after stripping a `provider/` prefix, so
+//! `openrouter/anthropic/claude-3-5-sonnet-20241022` resolves to `claude-3-5-sonnet`.
+//! `agents.defaults.contextBudget.models` overrides or extends the built-in rows.
+
+use crate::config::ModelMetadata;
+use crate::Config;
+
+/// (prefix, context window, input USD/1M, output USD/1M)
+const BUILTIN: &[(&str, u32, Option<f64>, Option<f64>)] = &[
+    ("gpt-4o-mini", 128_000, Some(0.15), Some(0.6)),
+    ("gpt-4o", 128_000, Some(2.5), Some(10.0)),
+    ("gpt-4.1-nano", 1_047_576, Some(0.1), Some(0.4)),
+    ("gpt-4.1-mini", 1_047_576, Some(0.4), Some(1.6)),
+    ("gpt-4.1", 1_047_576, Some(2.0), Some(8.0)),
+    ("gpt-4-turbo", 128_000, Some(10.0), Some(30.0)),
+    ("gpt-4", 8_192, Some(30.0), Some(60.0)),
+    ("gpt-3.5-turbo", 16_385, Some(0.5), Some(1.5)),
+    ("o1", 200_000, Some(15.0), Some(60.0)),
+    ("o3-mini", 200_000, Some(1.1), Some(4.4)),
+    ("o3", 200_000, Some(2.0), Some(8.0)),
+    ("o4-mini", 200_000, Some(1.1), Some(4.4)),
+    ("claude-3-haiku", 200_000, Some(0.25), Some(1.25)),
+    ("claude-3-5-haiku", 200_000, Some(0.8), Some(4.0)),
+    ("claude-3-5-sonnet", 200_000, Some(3.0), Some(15.0)),
+    ("claude-3-7-sonnet", 200_000, Some(3.0), Some(15.0)),
+    ("claude-sonnet-4", 200_000, Some(3.0), Some(15.0)),
+    ("claude-3-opus", 200_000, Some(15.0), Some(75.0)),
+    ("claude-opus-4", 200_000, Some(15.0), Some(75.0)),
+    ("deepseek-chat", 65_536, Some(0.27), Some(1.1)),
+    ("deepseek-reasoner", 65_536, Some(0.55), Some(2.19)),
+    ("gemini-1.5-flash", 1_048_576, Some(0.075), Some(0.3)),
+    ("gemini-1.5-pro", 2_097_152, Some(1.25), Some(5.0)),
+    ("gemini-2.0-flash", 1_048_576, Some(0.1), Some(0.4)),
+    ("gemini-2.5-flash", 1_048_576, Some(0.3), Some(2.5)),
+    ("gemini-2.5-pro", 1_048_576, Some(1.25), Some(10.0)),
+    ("qwen-max", 32_768, None, None),
+    ("qwen-plus", 131_072, None, None),
+    ("qwen-turbo", 1_000_000, None, None),
+    ("qwen2.5", 32_768, None, None),
+    ("glm-4", 128_000, None, None),
+    ("moonshot-v1-8k", 8_192, None, None),
+    ("moonshot-v1-32k", 32_768, None, None),
+    ("moonshot-v1-128k", 131_072, None, None),
+    ("kimi", 131_072, None, None),
+    ("llama3.1", 131_072, None, None),
+    ("llama3", 8_192, None, None),
+    ("mistral", 32_768, None, None),
+];
+
+/// Lowercased model name without `provider/` prefixes.
+fn normalize(model: &str) -> String {
+    let model = model.trim().to_lowercase();
+    match model.rsplit_once('/') {
+        Some((_, name)) => name.to_string(),
+        None => model,
+    }
+}
+
+/// Metadata for `model`: config overrides first (exact name, then longest prefix), then
+/// the built-in table.
+pub fn lookup(config: &Config, model: &str) -> Option<ModelMetadata> {
+    let overrides = &config.agents.defaults.context_budget.models;
+    if let Some(meta) = overrides.get(model) {
+        return Some(meta.clone());
+    }
+    let name = normalize(model);
+    let custom = overrides
+        .iter()
+        .filter(|(key, _)| name.starts_with(&normalize(key)))
+        .max_by_key(|(key, _)| normalize(key).len())
+        .map(|(_, meta)| meta.clone());
+    custom.or_else(|| builtin(&name))
+}
+
+fn builtin(name: &str) -> Option<ModelMetadata> {
+    BUILTIN
+        .iter()
+        .filter(|(prefix, ..)| name.starts_with(prefix))
+        .max_by_key(|(prefix, ..)| prefix.len())
+        .map(|(_, window, input, output)| ModelMetadata {
+            context_window: *window,
+            input_price: *input,
+            output_price: *output,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_longest_prefix() {
+        let config = Config::default();
+        let mini = lookup(&config, "gpt-4o-mini-2024-07-18").unwrap();
+        assert_eq!(mini.context_window, 128_000);
+        assert_eq!(mini.input_price, Some(0.15));
+
+        let sonnet = lookup(&config, "openrouter/anthropic/Claude-3-5-Sonnet-20241022").unwrap();
+        assert_eq!(sonnet.context_window, 200_000);
+        assert!(lookup(&config, "totally-unknown-model").is_none());
+    }
+
+    #[test]
+    fn test_config_overrides_builtin() {
+        let mut config = Config::default();
+        config.agents.defaults.context_budget.models.insert(
+            "deepseek-chat".to_string(),
+            ModelMetadata {
+                context_window: 131_072,
+                input_price: None,
+                output_price: None,
+            },
+        );
+        config.agents.defaults.context_budget.models.insert(
+            "my-finetune".to_string(),
+            ModelMetadata {
+                context_window: 16_000,
+                input_price: Some(1.0),
+                output_price: Some(2.0),
+            },
+        );
+        assert_eq!(
+            lookup(&config, "deepseek/deepseek-chat")
+                .unwrap()
+                .context_window,
+            131_072
+        );
+        assert_eq!(
+            lookup(&config, "my-finetune-v2").unwrap().output_price,
+            Some(2.0)
+        );
+    }
+}
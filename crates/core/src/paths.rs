@@ -99,6 +99,36 @@ impl Paths {
         self.base.join(".env")
     }
 
+    /// Named secrets (DSNs, keys) referenced from config as `secret:<name>`.
+    pub fn secrets_file(&self) -> PathBuf {
+        self.base.join("secrets.json")
+    }
+
+    /// WebUI passkeys and refresh tokens.
+    pub fn webui_auth_file(&self) -> PathBuf {
+        self.base.join("webui_auth.json")
+    }
+
+    /// Failed WebUI logins and active lockouts.
+    pub fn login_lockouts_file(&self) -> PathBuf {
+        self.base.join("login_lockouts.json")
+    }
+
+    /// Share links for this agent's workspace files (`/s/<token>`).
+    pub fn file_shares_file(&self) -> PathBuf {
+        self.base.join("file_shares.json")
+    }
+
+    /// Salt and key check for at-rest encryption (`storage.encryption`).
+    pub fn storage_key_file(&self) -> PathBuf {
+        self.base.join("storage_key.json")
+    }
+
+    /// Install id and last report time for opt-in telemetry.
+    pub fn telemetry_state_file(&self) -> PathBuf {
+        self.base.join("telemetry.json")
+    }
+
     pub fn mcp_config_file(&self) -> PathBuf {
         self.base.join("mcp.json")
     }
@@ -134,10 +164,51 @@ impl Paths {
         self.sessions_dir().join(format!("{}.jsonl", safe_key))
     }
 
+    /// Turn replay bundles written when `agents.defaults.recordTurns` is on.
+    pub fn recordings_dir(&self) -> PathBuf {
+        self.workspace().join("recordings")
+    }
+
+    /// Per-turn snapshots of the context sent to the model (see `GET
+    /// /v1/sessions/:id/messages/:idx/context`).
+    pub fn context_snapshots_dir(&self) -> PathBuf {
+        self.base.join("context_snapshots")
+    }
+
     pub fn audit_dir(&self) -> PathBuf {
         self.base.join("audit")
     }
 
+    /// Hash-chained log of admin actions taken through the gateway.
+    pub fn admin_audit_file(&self) -> PathBuf {
+        self.audit_dir().join("admin.jsonl")
+    }
+
+    /// Signed reports written by `blockcell privacy purge`.
+    pub fn privacy_reports_dir(&self) -> PathBuf {
+        self.base.join("privacy")
+    }
+
+    /// SQLite aggregates behind `blockcell skills stats`.
+    pub fn skill_stats_db(&self) -> PathBuf {
+        self.base.join("skill_stats.db")
+    }
+
+    /// Monthly provider spend ledger used by `agents.defaults.budget`.
+    pub fn spend_ledger_file(&self) -> PathBuf {
+        self.base.join("spend_ledger.json")
+    }
+
+    /// Rolling per-provider latency/error snapshot, written by the running agent.
+    pub fn provider_health_file(&self) -> PathBuf {
+        self.base.join("provider_health.json")
+    }
+
+    /// Last check-in per dead-man's-switch check (see `gateway.deadMansSwitch`).
+    pub fn dead_mans_switch_file(&self) -> PathBuf {
+        self.base.join("dead_mans_switch.json")
+    }
+
     pub fn cron_dir(&self) -> PathBuf {
         self.base.join("cron")
     }
@@ -146,6 +217,11 @@ impl Paths {
         self.cron_dir().join("jobs.json")
     }
 
+    /// SQLite run log behind `blockcell cron history`.
+    pub fn cron_history_db(&self) -> PathBuf {
+        self.cron_dir().join("history.db")
+    }
+
     pub fn media_dir(&self) -> PathBuf {
         self.workspace().join("media")
     }
@@ -175,6 +251,11 @@ impl Paths {
         self.workspace().join("USER.md")
     }
 
+    /// Ordered system prompt fragments (`*.md`).
+    pub fn prompt_d_dir(&self) -> PathBuf {
+        self.workspace().join("prompt.d")
+    }
+
     pub fn heartbeat_md(&self) -> PathBuf {
         self.workspace().join("HEARTBEAT.md")
     }
@@ -217,6 +298,11 @@ impl Paths {
         self.base.join("path_access.json5")
     }
 
+    /// Named webhook triggers served at `/v1/hooks/:name`.
+    pub fn hooks_file(&self) -> PathBuf {
+        self.workspace().join("hooks.json")
+    }
+
     pub fn toggles_file(&self) -> PathBuf {
         self.workspace().join("toggles.json")
     }
@@ -0,0 +1,258 @@
+//! Role-based tool permissions for channel senders (`permissions`).
+//!
+//! The policy only decides; `AgentRuntime` checks it before every tool call and turns
+//! a denial into a tool error the model can explain to the user.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::capability::PrivilegeLevel;
+use crate::config::PermissionsConfig;
+use crate::{Error, InboundMessage, Result};
+
+/// Metadata key for the role of whoever scheduled a message (cron jobs, follow-ups). These
+/// messages arrive on internal channels, but they never run with more than that role.
+pub const SCHEDULED_ROLE_KEY: &str = "scheduled_role";
+
+/// Channels fed by the gateway itself or an authenticated operator.
+const INTERNAL_CHANNELS: &[&str] = &[
+    "ws",
+    "cli",
+    "cron",
+    "system",
+    "subagent",
+    "heartbeat",
+    "ghost",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Admin,
+    User,
+    Readonly,
+}
+
+impl Role {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "admin" => Some(Self::Admin),
+            "user" => Some(Self::User),
+            "readonly" | "read_only" => Some(Self::Readonly),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Admin => "admin",
+            Self::User => "user",
+            Self::Readonly => "readonly",
+        }
+    }
+
+    fn rank(self) -> u8 {
+        match self {
+            Self::Readonly => 0,
+            Self::User => 1,
+            Self::Admin => 2,
+        }
+    }
+
+    /// This role, lowered to `cap` if that grants less.
+    pub fn capped(self, cap: Role) -> Role {
+        if cap.rank() < self.rank() {
+            cap
+        } else {
+            self
+        }
+    }
+
+    pub fn privilege_level(self) -> PrivilegeLevel {
+        match self {
+            Self::Admin => PrivilegeLevel::Full,
+            Self::User => PrivilegeLevel::Limited,
+            Self::Readonly => PrivilegeLevel::ReadOnly,
+        }
+    }
+}
+
+/// Compiled `permissions` config.
+#[derive(Debug, Clone)]
+pub struct ToolPermissions {
+    enabled: bool,
+    default_role: Role,
+    members: HashMap<String, Role>,
+    restricted: HashSet<String>,
+    readonly: HashSet<String>,
+}
+
+impl ToolPermissions {
+    /// Unknown role names fall back to `readonly`, so a typo never grants more access.
+    pub fn new(cfg: &PermissionsConfig) -> Self {
+        let role = |value: &str| Role::parse(value).unwrap_or(Role::Readonly);
+        Self {
+            enabled: cfg.enabled,
+            default_role: role(&cfg.default_role),
+            members: cfg
+                .members
+                .iter()
+                .map(|(key, value)| (key.trim().to_string(), role(value)))
+                .collect(),
+            restricted: cfg.restricted_tools.iter().cloned().collect(),
+            readonly: cfg.readonly_tools.iter().cloned().collect(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Role of a sender: exact `channel:sender_id`, then `channel:*`, then the default.
+    pub fn role_for(&self, channel: &str, sender_id: &str) -> Role {
+        if !self.enabled || INTERNAL_CHANNELS.contains(&channel) {
+            return Role::Admin;
+        }
+        self.members
+            .get(&format!("{}:{}", channel, sender_id))
+            .or_else(|| self.members.get(&format!("{}:*", channel)))
+            .copied()
+            .unwrap_or(self.default_role)
+    }
+
+    /// Role a message runs with: its sender's role, capped by the role recorded when it
+    /// was scheduled. An unreadable recorded role counts as `readonly`.
+    pub fn role_for_message(&self, msg: &InboundMessage) -> Role {
+        let role = self.role_for(&msg.channel, &msg.sender_id);
+        if !self.enabled {
+            return role;
+        }
+        match msg.metadata.get(SCHEDULED_ROLE_KEY) {
+            Some(recorded) => role.capped(
+                recorded
+                    .as_str()
+                    .and_then(Role::parse)
+                    .unwrap_or(Role::Readonly),
+            ),
+            None => role,
+        }
+    }
+
+    pub fn allows(&self, role: Role, tool: &str) -> bool {
+        match role {
+            Role::Admin => true,
+            Role::User => !self.restricted.contains(tool),
+            Role::Readonly => self.readonly.contains(tool),
+        }
+    }
+
+    /// Decide whether `sender_id` on `channel` may call `tool`.
+    pub fn check(&self, channel: &str, sender_id: &str, tool: &str) -> Result<()> {
+        self.check_role(self.role_for(channel, sender_id), channel, sender_id, tool)
+    }
+
+    /// Decide whether `msg` may call `tool`, see [`role_for_message`](Self::role_for_message).
+    pub fn check_message(&self, msg: &InboundMessage, tool: &str) -> Result<()> {
+        let role = self.role_for_message(msg);
+        self.check_role(role, &msg.channel, &msg.sender_id, tool)
+    }
+
+    fn check_role(&self, role: Role, channel: &str, sender_id: &str, tool: &str) -> Result<()> {
+        if self.allows(role, tool) {
+            return Ok(());
+        }
+        Err(Error::PermissionDenied(format!(
+            "Tool '{}' is not available to the {} role ({}:{})",
+            tool,
+            role.as_str(),
+            channel,
+            sender_id
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> ToolPermissions {
+        let mut cfg = PermissionsConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        cfg.members
+            .insert("telegram:1001".to_string(), "admin".to_string());
+        cfg.members
+            .insert("discord:*".to_string(), "readonly".to_string());
+        cfg.members
+            .insert("slack:42".to_string(), "superuser".to_string());
+        ToolPermissions::new(&cfg)
+    }
+
+    #[test]
+    fn test_roles_resolve_by_sender_then_channel_then_default() {
+        let p = policy();
+        assert_eq!(p.role_for("telegram", "1001"), Role::Admin);
+        assert_eq!(p.role_for("telegram", "2002"), Role::User);
+        assert_eq!(p.role_for("discord", "anyone"), Role::Readonly);
+        assert_eq!(p.role_for("ws", "anyone"), Role::Admin);
+        // Unknown role names never grant more than readonly.
+        assert_eq!(p.role_for("slack", "42"), Role::Readonly);
+
+        let off = ToolPermissions::new(&PermissionsConfig::default());
+        assert!(!off.is_enabled());
+        assert!(off.check("discord", "anyone", "exec").is_ok());
+    }
+
+    #[test]
+    fn test_dangerous_tools_require_admin() {
+        let p = policy();
+        assert!(p.check("telegram", "1001", "exec").is_ok());
+        assert!(p.check("telegram", "2002", "web_search").is_ok());
+        let err = p.check("telegram", "2002", "exec").unwrap_err();
+        assert!(err.to_string().contains("user role"), "{}", err);
+        for tool in [
+            "cron",
+            "alert_rule",
+            "follow_up",
+            "python",
+            "docker",
+            "kubectl",
+            "db_query",
+            "write_file",
+            "http_request",
+        ] {
+            assert!(p.check("telegram", "2002", tool).is_err(), "{}", tool);
+        }
+        assert!(p.check("discord", "x", "read_file").is_ok());
+        assert!(p.check("discord", "x", "write_file").is_err());
+    }
+
+    #[test]
+    fn test_scheduled_messages_keep_the_scheduling_role() {
+        let p = policy();
+        let mut msg = InboundMessage {
+            channel: "cron".to_string(),
+            account_id: None,
+            sender_id: "cron".to_string(),
+            chat_id: "job-1".to_string(),
+            content: "run".to_string(),
+            media: vec![],
+            metadata: serde_json::json!({}),
+            timestamp_ms: 0,
+        };
+        assert_eq!(p.role_for_message(&msg), Role::Admin);
+        assert!(p.check_message(&msg, "exec").is_ok());
+
+        msg.metadata = serde_json::json!({ SCHEDULED_ROLE_KEY: "user" });
+        assert_eq!(p.role_for_message(&msg), Role::User);
+        assert!(p.check_message(&msg, "exec").is_err());
+        assert!(p.check_message(&msg, "web_search").is_ok());
+
+        // A recorded role only ever lowers the sender's role.
+        msg.channel = "discord".to_string();
+        msg.metadata = serde_json::json!({ SCHEDULED_ROLE_KEY: "admin" });
+        assert_eq!(p.role_for_message(&msg), Role::Readonly);
+        msg.channel = "cron".to_string();
+        msg.metadata = serde_json::json!({ SCHEDULED_ROLE_KEY: "root" });
+        assert_eq!(p.role_for_message(&msg), Role::Readonly);
+    }
+}
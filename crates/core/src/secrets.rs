@@ -0,0 +1,179 @@
+//! Local secrets store (`~/.blockcell/secrets.json`).
+//!
+//! Config values that hold credentials (database DSNs, storage keys, SSH key
+//! passphrases) can reference a secret instead of embedding it:
+//!
+//! - `secret:<name>` — looked up in the secrets store
+//! - `env:<VAR>` — read from the environment
+//! - anything else — used literally
+//!
+//! Secrets are managed with `blockcell secrets set/list/remove`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Paths, Result};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SecretsFile {
+    #[serde(default)]
+    version: u32,
+    #[serde(default)]
+    secrets: BTreeMap<String, String>,
+}
+
+#[derive(Debug)]
+pub struct SecretStore {
+    path: PathBuf,
+    data: SecretsFile,
+}
+
+impl SecretStore {
+    pub fn open(paths: &Paths) -> Result<Self> {
+        Self::open_at(paths.secrets_file())
+    }
+
+    pub fn open_at(path: PathBuf) -> Result<Self> {
+        let data = if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            serde_json::from_str(&content)?
+        } else {
+            SecretsFile::default()
+        };
+        Ok(Self { path, data })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.data.secrets.get(name).map(|s| s.as_str())
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.data.secrets.keys().map(|k| k.as_str()).collect()
+    }
+
+    pub fn set(&mut self, name: &str, value: &str) -> Result<()> {
+        validate_name(name)?;
+        self.data
+            .secrets
+            .insert(name.to_string(), value.to_string());
+        self.save()
+    }
+
+    pub fn remove(&mut self, name: &str) -> Result<bool> {
+        let removed = self.data.secrets.remove(name).is_some();
+        if removed {
+            self.save()?;
+        }
+        Ok(removed)
+    }
+
+    fn save(&mut self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        self.data.version = 1;
+        let content = serde_json::to_string_pretty(&self.data)?;
+        write_private(&self.path, &content)
+    }
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        return Err(Error::Validation(format!(
+            "Invalid secret name '{}': use letters, digits, '_', '-' or '.'",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// Write a file readable only by the current user (0600 on Unix).
+fn write_private(path: &Path, content: &str) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::io::Write;
+        use std::os::unix::fs::OpenOptionsExt;
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)?;
+        file.write_all(content.as_bytes())?;
+        Ok(())
+    }
+    #[cfg(not(unix))]
+    {
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+/// Resolve a config value that may be a `secret:` or `env:` reference.
+pub fn resolve_secret_ref(paths: &Paths, value: &str) -> Result<String> {
+    let value = value.trim();
+    if let Some(name) = value.strip_prefix("secret:") {
+        let store = SecretStore::open(paths)?;
+        return store.get(name.trim()).map(str::to_string).ok_or_else(|| {
+            Error::Config(format!(
+                "Secret '{}' not found. Add it with `blockcell secrets set {}`",
+                name.trim(),
+                name.trim()
+            ))
+        });
+    }
+    if let Some(var) = value.strip_prefix("env:") {
+        return std::env::var(var.trim()).map_err(|_| {
+            Error::Config(format!("Environment variable '{}' is not set", var.trim()))
+        });
+    }
+    Ok(value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_store_roundtrip_and_resolve() {
+        let base = std::env::temp_dir().join(format!("blockcell-secrets-{}", uuid::Uuid::new_v4()));
+        let paths = Paths::with_base(base.clone());
+
+        let mut store = SecretStore::open(&paths).unwrap();
+        store.set("pg_main", "postgres://u:p@localhost/db").unwrap();
+        assert!(store.set("bad name", "x").is_err());
+
+        let reopened = SecretStore::open(&paths).unwrap();
+        assert_eq!(reopened.names(), vec!["pg_main"]);
+        assert_eq!(
+            resolve_secret_ref(&paths, "secret:pg_main").unwrap(),
+            "postgres://u:p@localhost/db"
+        );
+        assert!(resolve_secret_ref(&paths, "secret:missing").is_err());
+        assert_eq!(
+            resolve_secret_ref(&paths, "sqlite:///tmp/a.db").unwrap(),
+            "sqlite:///tmp/a.db"
+        );
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(paths.secrets_file())
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let mut store = SecretStore::open(&paths).unwrap();
+        assert!(store.remove("pg_main").unwrap());
+        assert!(!store.remove("pg_main").unwrap());
+        let _ = std::fs::remove_dir_all(base);
+    }
+}
@@ -0,0 +1,304 @@
+//! WebUI passkeys and refresh tokens (`<base>/webui_auth.json`).
+//!
+//! Passkeys are kept as the opaque JSON produced by the gateway's WebAuthn library, so
+//! this crate does not depend on it. Refresh tokens are stored as SHA-256 hashes: the
+//! file never contains a credential that could be replayed as a cookie.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::{Error, Paths, Result};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyRecord {
+    /// Base64url credential id.
+    pub id: String,
+    pub name: String,
+    /// Serialized passkey (public key, signature counter, ...).
+    pub credential: Value,
+    pub created_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<i64>,
+}
+
+impl PasskeyRecord {
+    /// API-facing view (never includes the key material).
+    pub fn to_json(&self) -> Value {
+        serde_json::json!({
+            "id": self.id,
+            "name": self.name,
+            "created_at": chrono::DateTime::from_timestamp(self.created_at, 0).map(|t| t.to_rfc3339()),
+            "last_used_at": self
+                .last_used_at
+                .and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+                .map(|t| t.to_rfc3339()),
+        })
+    }
+}
+
+/// A signed-in browser, as remembered by its refresh token.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshGrant {
+    pub created_at: i64,
+    pub expires_at: i64,
+    /// The login that started this session included a passkey.
+    #[serde(default)]
+    pub passkey: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WebUiAuthFile {
+    #[serde(default)]
+    version: u32,
+    /// WebAuthn user handle of the (single) WebUI user.
+    #[serde(default)]
+    user_id: Option<String>,
+    #[serde(default)]
+    passkeys: BTreeMap<String, PasskeyRecord>,
+    /// SHA-256 of the refresh token → grant.
+    #[serde(default)]
+    refresh_tokens: BTreeMap<String, RefreshGrant>,
+}
+
+/// Random 64-hex-character token for session and refresh cookies.
+pub fn new_token() -> String {
+    format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+fn token_hash(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[derive(Debug)]
+pub struct WebUiAuthStore {
+    path: PathBuf,
+    data: WebUiAuthFile,
+}
+
+impl WebUiAuthStore {
+    pub fn open(paths: &Paths) -> Result<Self> {
+        Self::open_at(paths.webui_auth_file())
+    }
+
+    pub fn open_at(path: PathBuf) -> Result<Self> {
+        let data = if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            serde_json::from_str(&content)?
+        } else {
+            WebUiAuthFile {
+                version: 1,
+                ..Default::default()
+            }
+        };
+        Ok(Self { path, data })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.data)?)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = std::fs::set_permissions(&self.path, std::fs::Permissions::from_mode(0o600));
+        }
+        Ok(())
+    }
+
+    /// Stable WebAuthn user handle, created on first use.
+    pub fn user_id(&mut self) -> uuid::Uuid {
+        if let Some(id) = self
+            .data
+            .user_id
+            .as_deref()
+            .and_then(|id| uuid::Uuid::parse_str(id).ok())
+        {
+            return id;
+        }
+        let id = uuid::Uuid::new_v4();
+        self.data.user_id = Some(id.to_string());
+        id
+    }
+
+    /// Registered passkeys, oldest first.
+    pub fn passkeys(&self) -> Vec<&PasskeyRecord> {
+        let mut keys: Vec<&PasskeyRecord> = self.data.passkeys.values().collect();
+        keys.sort_by_key(|k| k.created_at);
+        keys
+    }
+
+    pub fn has_passkeys(&self) -> bool {
+        !self.data.passkeys.is_empty()
+    }
+
+    pub fn add_passkey(
+        &mut self,
+        id: &str,
+        name: &str,
+        credential: Value,
+    ) -> Result<PasskeyRecord> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > 64 {
+            return Err(Error::Validation(
+                "Passkey name must be 1-64 characters".to_string(),
+            ));
+        }
+        if self.data.passkeys.contains_key(id) {
+            return Err(Error::Validation(
+                "This passkey is already registered".to_string(),
+            ));
+        }
+        let record = PasskeyRecord {
+            id: id.to_string(),
+            name: name.to_string(),
+            credential,
+            created_at: Utc::now().timestamp(),
+            last_used_at: None,
+        };
+        self.data.passkeys.insert(id.to_string(), record.clone());
+        Ok(record)
+    }
+
+    /// Record a successful login; `credential` replaces the stored one when the signature
+    /// counter or backup state changed.
+    pub fn touch_passkey(&mut self, id: &str, credential: Option<Value>) -> bool {
+        match self.data.passkeys.get_mut(id) {
+            Some(record) => {
+                record.last_used_at = Some(Utc::now().timestamp());
+                if let Some(credential) = credential {
+                    record.credential = credential;
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn remove_passkey(&mut self, id: &str) -> bool {
+        self.data.passkeys.remove(id).is_some()
+    }
+
+    /// Remember a new signed-in browser and return its refresh token.
+    pub fn issue_refresh(&mut self, ttl_secs: u64, passkey: bool) -> String {
+        let now = Utc::now().timestamp();
+        self.prune(now);
+        let token = new_token();
+        self.data.refresh_tokens.insert(
+            token_hash(&token),
+            RefreshGrant {
+                created_at: now,
+                expires_at: now + ttl_secs as i64,
+                passkey,
+            },
+        );
+        token
+    }
+
+    /// Consume a refresh token. Tokens are single-use: the caller issues a new one, so a
+    /// stolen token stops working as soon as either party refreshes.
+    pub fn redeem_refresh(&mut self, token: &str, now: i64) -> Option<RefreshGrant> {
+        self.data
+            .refresh_tokens
+            .remove(&token_hash(token))
+            .filter(|grant| grant.expires_at > now)
+    }
+
+    pub fn revoke_refresh(&mut self, token: &str) -> bool {
+        self.data
+            .refresh_tokens
+            .remove(&token_hash(token))
+            .is_some()
+    }
+
+    /// Sign out every browser.
+    pub fn revoke_all_refresh(&mut self) -> usize {
+        let count = self.data.refresh_tokens.len();
+        self.data.refresh_tokens.clear();
+        count
+    }
+
+    /// Drop expired refresh tokens. Returns how many were removed.
+    pub fn prune(&mut self, now: i64) -> usize {
+        let before = self.data.refresh_tokens.len();
+        self.data.refresh_tokens.retain(|_, g| g.expires_at > now);
+        before - self.data.refresh_tokens.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_store() -> (PathBuf, WebUiAuthStore) {
+        let dir =
+            std::env::temp_dir().join(format!("blockcell-webui-auth-{}", uuid::Uuid::new_v4()));
+        let store = WebUiAuthStore::open_at(dir.join("webui_auth.json")).unwrap();
+        (dir, store)
+    }
+
+    #[test]
+    fn test_passkeys_round_trip() {
+        let (dir, mut store) = temp_store();
+        let user = store.user_id();
+        assert_eq!(store.user_id(), user);
+        assert!(!store.has_passkeys());
+
+        store
+            .add_passkey("cred-1", "Laptop", json!({"cred": {"counter": 1}}))
+            .unwrap();
+        assert!(store.add_passkey("cred-1", "Again", json!({})).is_err());
+        assert!(store.add_passkey("cred-2", " ", json!({})).is_err());
+        assert!(store.touch_passkey("cred-1", Some(json!({"cred": {"counter": 2}}))));
+        assert!(!store.touch_passkey("missing", None));
+        store.save().unwrap();
+
+        let mut store = WebUiAuthStore::open_at(dir.join("webui_auth.json")).unwrap();
+        assert_eq!(store.user_id(), user);
+        let keys = store.passkeys();
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].credential["cred"]["counter"], 2);
+        assert!(keys[0].last_used_at.is_some());
+        assert!(keys[0].to_json().get("credential").is_none());
+        assert!(store.remove_passkey("cred-1"));
+        assert!(!store.has_passkeys());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_refresh_tokens_are_single_use_and_expire() {
+        let (_dir, mut store) = temp_store();
+        let now = Utc::now().timestamp();
+        let token = store.issue_refresh(3600, true);
+        assert_eq!(token.len(), 64);
+        // Only the hash is kept.
+        assert!(!store.data.refresh_tokens.contains_key(&token));
+
+        let grant = store.redeem_refresh(&token, now).unwrap();
+        assert!(grant.passkey);
+        assert!(store.redeem_refresh(&token, now).is_none());
+
+        let token = store.issue_refresh(60, false);
+        assert!(store.redeem_refresh(&token, now + 61).is_none());
+
+        let token = store.issue_refresh(60, false);
+        assert_eq!(store.revoke_all_refresh(), 1);
+        assert!(!store.revoke_refresh(&token));
+    }
+}
@@ -104,6 +104,93 @@ impl Embedder for OpenAICompatibleEmbedder {
     }
 }
 
+/// Local embeddings from an Ollama server (`POST /api/embed`); no API key needed.
+pub struct OllamaEmbedder {
+    client: Client,
+    api_base: String,
+    model: String,
+    dimensions: AtomicUsize,
+}
+
+impl OllamaEmbedder {
+    pub fn new_with_proxy(
+        api_base: Option<&str>,
+        model: &str,
+        provider_proxy: Option<&str>,
+        global_proxy: Option<&str>,
+        no_proxy: &[String],
+    ) -> Self {
+        let resolved_base = api_base
+            .unwrap_or("http://localhost:11434")
+            .trim_end_matches('/')
+            .to_string();
+        let client = build_blocking_http_client(
+            provider_proxy,
+            global_proxy,
+            no_proxy,
+            &resolved_base,
+            Duration::from_secs(120),
+        );
+
+        Self {
+            client,
+            api_base: resolved_base,
+            model: model.to_string(),
+            dimensions: AtomicUsize::new(0),
+        }
+    }
+
+    fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let response = self
+            .client
+            .post(format!("{}/api/embed", self.api_base))
+            .json(&json!({
+                "model": self.model,
+                "input": text,
+            }))
+            .send()
+            .map_err(|error| {
+                Error::Provider(format!("Ollama embedding request failed: {}", error))
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().unwrap_or_default();
+            return Err(Error::Provider(format!(
+                "Ollama embedding request failed with status {}: {}",
+                status, body
+            )));
+        }
+
+        let payload: OllamaEmbedResponse = response.json().map_err(|error| {
+            Error::Provider(format!("Ollama embedding decode failed: {}", error))
+        })?;
+        let vector = payload.embeddings.into_iter().next().ok_or_else(|| {
+            Error::Provider("Ollama embedding response returned no vectors".to_string())
+        })?;
+        self.dimensions.store(vector.len(), Ordering::SeqCst);
+        Ok(vector)
+    }
+}
+
+impl Embedder for OllamaEmbedder {
+    fn model_id(&self) -> &str {
+        &self.model
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions.load(Ordering::SeqCst)
+    }
+
+    fn embed_query(&self, text: &str) -> Result<Vec<f32>> {
+        self.embed(text)
+    }
+
+    fn embed_document(&self, text: &str) -> Result<Vec<f32>> {
+        self.embed(text)
+    }
+}
+
 pub fn create_embedder(config: &Config) -> anyhow::Result<Option<Arc<dyn Embedder>>> {
     let vector_cfg = &config.memory.vector;
     if !vector_cfg.enabled {
@@ -123,10 +210,18 @@ pub fn create_embedder(config: &Config) -> anyhow::Result<Option<Arc<dyn Embedde
         .get(provider_name)
         .with_context(|| format!("Provider '{}' not found for memory.vector", provider_name))?;
 
-    if matches!(
-        provider_cfg.api_type.as_str(),
-        "anthropic" | "gemini" | "ollama"
-    ) {
+    if provider_name == "ollama" || provider_cfg.api_type == "ollama" {
+        let embedder = OllamaEmbedder::new_with_proxy(
+            provider_cfg.api_base.as_deref(),
+            model,
+            provider_cfg.proxy.as_deref(),
+            config.network.proxy.as_deref(),
+            &config.network.no_proxy,
+        );
+        return Ok(Some(Arc::new(embedder)));
+    }
+
+    if matches!(provider_cfg.api_type.as_str(), "anthropic" | "gemini") {
         return Err(anyhow!(
             "memory.vector provider '{}' is not OpenAI-compatible",
             provider_name
@@ -166,6 +261,11 @@ struct EmbeddingData {
     embedding: Vec<f32>,
 }
 
+#[derive(Debug, Deserialize)]
+struct OllamaEmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -188,4 +288,21 @@ mod tests {
         assert!(embedder.is_some());
         assert_eq!(embedder.unwrap().model_id(), "text-embedding-3-small");
     }
+
+    #[test]
+    fn test_create_embedder_uses_local_ollama_without_api_key() {
+        let mut config = Config::default();
+        config.memory.vector.enabled = true;
+        config.memory.vector.provider = "ollama".to_string();
+        config.memory.vector.model = "nomic-embed-text".to_string();
+        config.providers.get_mut("ollama").unwrap().api_key = String::new();
+
+        let embedder = create_embedder(&config).unwrap().unwrap();
+        assert_eq!(embedder.model_id(), "nomic-embed-text");
+        assert_eq!(embedder.dimensions(), 0);
+
+        config.providers.get_mut("minimax").unwrap().api_key = "sk-test".to_string();
+        config.memory.vector.provider = "minimax".to_string();
+        assert!(create_embedder(&config).is_err());
+    }
 }
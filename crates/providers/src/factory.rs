@@ -293,6 +293,29 @@ pub fn create_evolution_provider(config: &Config) -> anyhow::Result<Box<dyn Prov
     create_provider_with_tool_mode(config, model, explicit_provider, None, None)
 }
 
+/// 为自进化审计/复核创建强模型 provider；未配置 evolution_audit_model 时返回 None
+pub fn create_evolution_audit_provider(
+    config: &Config,
+) -> anyhow::Result<Option<Box<dyn Provider>>> {
+    let defaults = &config.agents.defaults;
+    let Some(model) = defaults
+        .evolution_audit_model
+        .as_deref()
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+    else {
+        return Ok(None);
+    };
+    create_provider_with_tool_mode(
+        config,
+        model,
+        defaults.evolution_audit_provider.as_deref(),
+        None,
+        None,
+    )
+    .map(Some)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
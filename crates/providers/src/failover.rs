@@ -0,0 +1,359 @@
+//! Ordered provider failover (`providers.failover`).
+//!
+//! [`FailoverProvider`] wraps a pool entry's provider together with the configured
+//! chain. A call that fails with a rate limit, server error, timeout or network error is
+//! retried on the same provider with exponential backoff; once retries run out (or right
+//! away on an auth error) the next provider in the chain gets the request. Other client
+//! errors (400 invalid request, context length exceeded, ...) would fail the same way
+//! everywhere, so they reach the caller at once.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use blockcell_core::config::{FailoverConfig, ToolCallMode};
+use blockcell_core::types::{ChatMessage, LLMResponse, StreamChunk};
+use blockcell_core::{Config, Error, Result};
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::factory::create_provider_with_tool_mode;
+use crate::pool::{CallResult, ProviderPool};
+use crate::Provider;
+
+/// What follows a failed attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Next {
+    Retry,
+    Failover,
+    Stop,
+}
+
+/// HTTP status in a provider error such as `API error 400 Bad Request: ...`.
+fn http_status(error: &str) -> Option<u16> {
+    let lower = error.to_ascii_lowercase();
+    ["error ", "http "].iter().find_map(|prefix| {
+        lower.match_indices(prefix).find_map(|(i, _)| {
+            let start = i + prefix.len();
+            lower
+                .get(start..start + 3)?
+                .parse::<u16>()
+                .ok()
+                .filter(|code| (400..600).contains(code))
+        })
+    })
+}
+
+fn next_step(error: &str) -> Next {
+    match http_status(error) {
+        Some(408 | 429) => Next::Retry,
+        Some(401 | 403) => Next::Failover,
+        Some(code) if code >= 500 => Next::Retry,
+        Some(_) => Next::Stop,
+        None => match ProviderPool::classify_error(error) {
+            CallResult::RateLimit | CallResult::ServerError => Next::Retry,
+            CallResult::AuthError => Next::Failover,
+            _ => {
+                let lower = error.to_lowercase();
+                let network = ["request failed", "timed out", "timeout", "connection"]
+                    .iter()
+                    .any(|needle| lower.contains(needle));
+                if network {
+                    Next::Retry
+                } else {
+                    Next::Failover
+                }
+            }
+        },
+    }
+}
+
+struct Target {
+    /// `provider/model`, for logs and the final error.
+    label: String,
+    provider: Arc<dyn Provider>,
+}
+
+pub struct FailoverProvider {
+    targets: Vec<Target>,
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    attempt_timeout: Duration,
+}
+
+impl FailoverProvider {
+    /// `targets` in the order they are tried; the first one is the primary.
+    pub fn new(targets: Vec<(String, Arc<dyn Provider>)>, policy: &FailoverConfig) -> Self {
+        Self {
+            targets: targets
+                .into_iter()
+                .map(|(label, provider)| Target { label, provider })
+                .collect(),
+            max_retries: policy.max_retries,
+            initial_backoff: Duration::from_millis(policy.initial_backoff_ms),
+            max_backoff: Duration::from_millis(
+                policy.max_backoff_ms.max(policy.initial_backoff_ms),
+            ),
+            attempt_timeout: Duration::from_secs(policy.attempt_timeout_secs.max(1)),
+        }
+    }
+
+    /// Put `primary` in front of the configured chain. Returns `primary` unchanged when
+    /// no failover is configured or no chain provider could be built.
+    pub fn wrap(
+        config: &Config,
+        primary_label: &str,
+        primary: Arc<dyn Provider>,
+        tool_call_mode: ToolCallMode,
+        temperature: f32,
+    ) -> Arc<dyn Provider> {
+        let Some(policy) = config.providers.failover.as_ref() else {
+            return primary;
+        };
+        let mut targets = vec![(primary_label.to_string(), primary.clone())];
+        for target in &policy.chain {
+            let label = format!("{}/{}", target.provider, target.model);
+            if label == primary_label {
+                continue;
+            }
+            match create_provider_with_tool_mode(
+                config,
+                &target.model,
+                Some(&target.provider),
+                Some(tool_call_mode),
+                Some(temperature),
+            ) {
+                Ok(provider) => targets.push((label, Arc::from(provider))),
+                Err(e) => warn!(target = %label, error = %e, "Failover: skipping chain entry"),
+            }
+        }
+        if targets.len() == 1 {
+            return primary;
+        }
+        info!(
+            primary = %primary_label,
+            fallbacks = targets.len() - 1,
+            "Provider failover enabled"
+        );
+        Arc::new(Self::new(targets, policy))
+    }
+
+    fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(1u32 << retry.min(16))
+            .min(self.max_backoff)
+    }
+
+    async fn run<T, F, Fut>(&self, call: F) -> Result<T>
+    where
+        F: Fn(Arc<dyn Provider>) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut failures = Vec::new();
+        for (i, target) in self.targets.iter().enumerate() {
+            let mut retry = 0;
+            loop {
+                let (error, next) =
+                    match tokio::time::timeout(self.attempt_timeout, call(target.provider.clone()))
+                        .await
+                    {
+                        Ok(Ok(value)) => {
+                            if i > 0 || retry > 0 {
+                                info!(provider = %target.label, retry, "Failover: call succeeded");
+                            }
+                            return Ok(value);
+                        }
+                        Ok(Err(e)) => {
+                            let error = e.to_string();
+                            match next_step(&error) {
+                                Next::Stop => return Err(e),
+                                next => (error, next),
+                            }
+                        }
+                        Err(_) => (
+                            format!("timed out after {}s", self.attempt_timeout.as_secs()),
+                            Next::Retry,
+                        ),
+                    };
+                if next == Next::Retry && retry < self.max_retries {
+                    let wait = self.backoff(retry);
+                    warn!(
+                        provider = %target.label,
+                        retry = retry + 1,
+                        wait_ms = wait.as_millis() as u64,
+                        error = %error,
+                        "Failover: retrying provider"
+                    );
+                    tokio::time::sleep(wait).await;
+                    retry += 1;
+                    continue;
+                }
+                if let Some(next) = self.targets.get(i + 1) {
+                    warn!(
+                        provider = %target.label,
+                        next = %next.label,
+                        error = %error,
+                        "Failover: moving to next provider"
+                    );
+                }
+                failures.push(format!("{}: {}", target.label, error));
+                break;
+            }
+        }
+        Err(Error::Provider(format!(
+            "All providers failed: {}",
+            failures.join("; ")
+        )))
+    }
+}
+
+#[async_trait]
+impl Provider for FailoverProvider {
+    async fn chat(&self, messages: &[ChatMessage], tools: &[Value]) -> Result<LLMResponse> {
+        self.run(|provider| async move { provider.chat(messages, tools).await })
+            .await
+    }
+
+    /// Fails over while opening the stream; once chunks flow, errors reach the caller.
+    async fn chat_stream(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[Value],
+    ) -> Result<mpsc::Receiver<StreamChunk>> {
+        self.run(|provider| async move { provider.chat_stream(messages, tools).await })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Scripted {
+        errors: Vec<&'static str>,
+        calls: AtomicUsize,
+    }
+
+    impl Scripted {
+        fn new(errors: Vec<&'static str>) -> Arc<Self> {
+            Arc::new(Self {
+                errors,
+                calls: AtomicUsize::new(0),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl Provider for Scripted {
+        async fn chat(&self, _messages: &[ChatMessage], _tools: &[Value]) -> Result<LLMResponse> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            match self.errors.get(call) {
+                Some(error) => Err(Error::Provider(error.to_string())),
+                None => Ok(LLMResponse {
+                    content: Some("ok".to_string()),
+                    reasoning_content: None,
+                    tool_calls: vec![],
+                    finish_reason: "stop".to_string(),
+                    usage: Value::Null,
+                }),
+            }
+        }
+    }
+
+    fn policy(max_retries: u32) -> FailoverConfig {
+        FailoverConfig {
+            max_retries,
+            initial_backoff_ms: 1,
+            max_backoff_ms: 2,
+            ..FailoverConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_then_fails_over_to_next_provider() {
+        let primary = Scripted::new(vec!["HTTP 429 Too Many Requests", "HTTP 503"]);
+        let backup = Scripted::new(vec![]);
+        let failover = FailoverProvider::new(
+            vec![
+                (
+                    "deepseek/deepseek-chat".to_string(),
+                    primary.clone() as Arc<dyn Provider>,
+                ),
+                (
+                    "openai/gpt-4o-mini".to_string(),
+                    backup.clone() as Arc<dyn Provider>,
+                ),
+            ],
+            &policy(1),
+        );
+
+        let response = failover.chat(&[], &[]).await.unwrap();
+        assert_eq!(response.content.as_deref(), Some("ok"));
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 2);
+        assert_eq!(backup.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_auth_errors_skip_retries_and_all_failures_are_reported() {
+        let primary = Scripted::new(vec!["HTTP 401 Unauthorized"]);
+        let backup = Scripted::new(vec!["HTTP 500", "HTTP 500", "HTTP 500"]);
+        let failover = FailoverProvider::new(
+            vec![
+                (
+                    "openai/gpt-4o".to_string(),
+                    primary.clone() as Arc<dyn Provider>,
+                ),
+                (
+                    "ollama/qwen2.5".to_string(),
+                    backup.clone() as Arc<dyn Provider>,
+                ),
+            ],
+            &policy(2),
+        );
+
+        let err = failover.chat(&[], &[]).await.unwrap_err().to_string();
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(backup.calls.load(Ordering::SeqCst), 3);
+        assert!(err.contains("openai/gpt-4o: "));
+        assert!(err.contains("ollama/qwen2.5: "));
+        assert_eq!(failover.backoff(10), Duration::from_millis(2));
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_fail_fast() {
+        let primary = Scripted::new(vec![
+            "API error 400 Bad Request: maximum context length is 8192 tokens, max_tokens 500",
+        ]);
+        let backup = Scripted::new(vec![]);
+        let failover = FailoverProvider::new(
+            vec![
+                (
+                    "openai/gpt-4o".to_string(),
+                    primary.clone() as Arc<dyn Provider>,
+                ),
+                (
+                    "ollama/qwen2.5".to_string(),
+                    backup.clone() as Arc<dyn Provider>,
+                ),
+            ],
+            &policy(3),
+        );
+
+        let err = failover.chat(&[], &[]).await.unwrap_err().to_string();
+        assert!(err.contains("400 Bad Request"), "{}", err);
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(backup.calls.load(Ordering::SeqCst), 0);
+
+        assert_eq!(
+            next_step("Request failed: error sending request"),
+            Next::Retry
+        );
+        assert_eq!(next_step("Anthropic API error 529 Overloaded"), Next::Retry);
+        assert_eq!(next_step("API error 422 Unprocessable Entity"), Next::Stop);
+        assert_eq!(next_step("Failed to parse response: EOF"), Next::Failover);
+    }
+}
@@ -0,0 +1,251 @@
+//! Rolling latency / error-rate tracking per provider entry.
+//!
+//! Every [`ProviderPool`](crate::ProviderPool) in the process reports into one global
+//! tracker keyed by `provider/model`, so pools rebuilt on config reload keep their
+//! history. The runtime persists [`HealthTracker::snapshot`] to `provider_health.json` for
+//! `blockcell config providers`; the gateway serves it live at `/v1/providers/health`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Calls kept per entry.
+const WINDOW_CALLS: usize = 50;
+/// Calls older than this drop out of the window.
+const WINDOW_AGE: Duration = Duration::from_secs(30 * 60);
+/// Below this many samples an entry is scored optimistically so it gets tried.
+const MIN_SAMPLES: usize = 3;
+
+struct Sample {
+    at: Instant,
+    ok: bool,
+    latency_ms: Option<u64>,
+}
+
+#[derive(Default)]
+struct EntryWindow {
+    samples: VecDeque<Sample>,
+    last_error: Option<String>,
+    last_success_ms: Option<u64>,
+}
+
+impl EntryWindow {
+    fn prune(&mut self, now: Instant) {
+        while self.samples.len() > WINDOW_CALLS
+            || self
+                .samples
+                .front()
+                .is_some_and(|s| now.duration_since(s.at) > WINDOW_AGE)
+        {
+            self.samples.pop_front();
+        }
+    }
+}
+
+/// Health of one `provider/model` over the rolling window.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderHealth {
+    pub key: String,
+    pub provider: String,
+    pub model: String,
+    pub samples: usize,
+    /// Failed calls / all calls in the window (0.0-1.0).
+    pub error_rate: f64,
+    pub avg_latency_ms: Option<u64>,
+    pub p95_latency_ms: Option<u64>,
+    pub last_error: Option<String>,
+    /// Unix millis of the last successful call.
+    pub last_success_ms: Option<u64>,
+}
+
+/// Snapshot written to disk for other processes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    pub updated_ms: u64,
+    pub providers: Vec<ProviderHealth>,
+}
+
+#[derive(Default)]
+pub struct HealthTracker {
+    entries: Mutex<HashMap<String, EntryWindow>>,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+pub fn entry_key(provider: &str, model: &str) -> String {
+    if provider.is_empty() {
+        model.to_string()
+    } else {
+        format!("{}/{}", provider, model)
+    }
+}
+
+/// The process-wide tracker.
+pub fn global() -> &'static HealthTracker {
+    static TRACKER: OnceLock<HealthTracker> = OnceLock::new();
+    TRACKER.get_or_init(HealthTracker::default)
+}
+
+impl HealthTracker {
+    pub fn record(&self, key: &str, ok: bool, latency: Option<Duration>, error: Option<&str>) {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap_or_else(|p| p.into_inner());
+        let window = entries.entry(key.to_string()).or_default();
+        window.samples.push_back(Sample {
+            at: now,
+            ok,
+            latency_ms: latency.map(|l| l.as_millis() as u64),
+        });
+        if ok {
+            window.last_success_ms = Some(now_ms());
+        } else if let Some(error) = error {
+            window.last_error = Some(error.chars().take(200).collect());
+        }
+        window.prune(now);
+    }
+
+    /// Routing score (lower is better): average success latency, inflated by the error
+    /// rate. `None` until the entry has enough samples.
+    pub fn score(&self, key: &str) -> Option<f64> {
+        let health = self.health(key)?;
+        if health.samples < MIN_SAMPLES {
+            return None;
+        }
+        // An entry that only fails has no latency; rank it behind every working one.
+        let latency = health.avg_latency_ms.unwrap_or(60_000) as f64;
+        Some(latency * (1.0 + 4.0 * health.error_rate))
+    }
+
+    pub fn health(&self, key: &str) -> Option<ProviderHealth> {
+        let mut entries = self.entries.lock().unwrap_or_else(|p| p.into_inner());
+        let window = entries.get_mut(key)?;
+        window.prune(Instant::now());
+        Some(summarize(key, window))
+    }
+
+    pub fn snapshot(&self) -> Vec<ProviderHealth> {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap_or_else(|p| p.into_inner());
+        let mut out: Vec<ProviderHealth> = entries
+            .iter_mut()
+            .map(|(key, window)| {
+                window.prune(now);
+                summarize(key, window)
+            })
+            .collect();
+        out.sort_by(|a, b| a.key.cmp(&b.key));
+        out
+    }
+
+    /// Write the current snapshot for `blockcell config providers`.
+    pub fn persist(&self, path: &Path) -> std::io::Result<()> {
+        let report = HealthReport {
+            updated_ms: now_ms(),
+            providers: self.snapshot(),
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(&report)?)
+    }
+}
+
+/// Read a snapshot written by [`HealthTracker::persist`].
+pub fn load_report(path: &Path) -> Option<HealthReport> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn summarize(key: &str, window: &EntryWindow) -> ProviderHealth {
+    let samples = window.samples.len();
+    let failures = window.samples.iter().filter(|s| !s.ok).count();
+    let mut latencies: Vec<u64> = window
+        .samples
+        .iter()
+        .filter(|s| s.ok)
+        .filter_map(|s| s.latency_ms)
+        .collect();
+    latencies.sort_unstable();
+    let avg =
+        (!latencies.is_empty()).then(|| latencies.iter().sum::<u64>() / latencies.len() as u64);
+    let p95 = (!latencies.is_empty()).then(|| {
+        let rank = ((latencies.len() as f64) * 0.95).ceil() as usize;
+        latencies[rank.clamp(1, latencies.len()) - 1]
+    });
+    let (provider, model) = key.split_once('/').unwrap_or(("", key));
+    ProviderHealth {
+        key: key.to_string(),
+        provider: provider.to_string(),
+        model: model.to_string(),
+        samples,
+        error_rate: if samples == 0 {
+            0.0
+        } else {
+            failures as f64 / samples as f64
+        },
+        avg_latency_ms: avg,
+        p95_latency_ms: p95,
+        last_error: window.last_error.clone(),
+        last_success_ms: window.last_success_ms,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_summary() {
+        let tracker = HealthTracker::default();
+        for ms in [100, 200, 300] {
+            tracker.record("openai/gpt-4o", true, Some(Duration::from_millis(ms)), None);
+        }
+        tracker.record("openai/gpt-4o", false, None, Some("HTTP 503"));
+
+        let health = tracker.health("openai/gpt-4o").unwrap();
+        assert_eq!(health.samples, 4);
+        assert_eq!(health.provider, "openai");
+        assert_eq!(health.model, "gpt-4o");
+        assert!((health.error_rate - 0.25).abs() < 1e-9);
+        assert_eq!(health.avg_latency_ms, Some(200));
+        assert_eq!(health.p95_latency_ms, Some(300));
+        assert_eq!(health.last_error.as_deref(), Some("HTTP 503"));
+    }
+
+    #[test]
+    fn test_score_prefers_fast_reliable_entries() {
+        let tracker = HealthTracker::default();
+        for _ in 0..5 {
+            tracker.record("a/m", true, Some(Duration::from_millis(800)), None);
+            tracker.record("b/m", true, Some(Duration::from_millis(300)), None);
+        }
+        assert!(tracker.score("a/m").unwrap() > tracker.score("b/m").unwrap());
+
+        // Failures outweigh a small latency advantage.
+        for _ in 0..5 {
+            tracker.record("b/m", false, None, Some("timeout"));
+        }
+        assert!(tracker.score("b/m").unwrap() > tracker.score("a/m").unwrap());
+
+        tracker.record("c/m", true, Some(Duration::from_millis(10)), None);
+        assert!(tracker.score("c/m").is_none());
+    }
+
+    #[test]
+    fn test_window_is_bounded() {
+        let tracker = HealthTracker::default();
+        for _ in 0..(WINDOW_CALLS + 20) {
+            tracker.record("x/m", true, Some(Duration::from_millis(5)), None);
+        }
+        assert_eq!(tracker.health("x/m").unwrap().samples, WINDOW_CALLS);
+    }
+}
@@ -2,7 +2,9 @@ pub mod anthropic;
 pub mod client;
 pub mod embeddings;
 pub mod factory;
+pub mod failover;
 pub mod gemini;
+pub mod health;
 pub mod ollama;
 pub mod openai;
 pub mod openai_responses;
@@ -59,10 +61,12 @@ pub trait Provider: Send + Sync {
 pub use anthropic::AnthropicProvider;
 pub use embeddings::{create_embedder, OpenAICompatibleEmbedder};
 pub use factory::{
-    create_evolution_provider, create_main_provider, create_provider, infer_provider_from_model,
+    create_evolution_audit_provider, create_evolution_provider, create_main_provider,
+    create_provider, infer_provider_from_model,
 };
+pub use failover::FailoverProvider;
 pub use gemini::GeminiProvider;
 pub use ollama::OllamaProvider;
 pub use openai::OpenAIProvider;
 pub use openai_responses::OpenAIResponsesProvider;
-pub use pool::{CallResult, PoolEntryStatus, ProviderPool};
+pub use pool::{CallResult, EntryPricing, PoolEntryStatus, ProviderPool};
@@ -642,7 +642,7 @@ mod tests {
         };
 
         let value = serde_json::to_value(request).unwrap();
-        assert_eq!(value["temperature"], 0.7);
+        assert_eq!(value["temperature"], f64::from(0.7_f32));
         assert_eq!(value["max_output_tokens"], 128);
     }
 }
@@ -2,11 +2,13 @@ use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
-use blockcell_core::config::ToolCallMode;
+use blockcell_core::config::{ModelEntry, ToolCallMode};
 use blockcell_core::Config;
 use tracing::{info, warn};
 
-use crate::factory::create_provider_with_tool_mode;
+use crate::factory::{create_provider_with_tool_mode, infer_provider_from_model};
+use crate::failover::FailoverProvider;
+use crate::health;
 use crate::Provider;
 
 /// 单个池条目的运行时健康状态
@@ -57,6 +59,11 @@ struct BuiltEntry {
     weight: u32,
     /// 优先级（小数字 = 高优先级）
     priority: u32,
+    /// 输入/输出价格（USD/1M tokens），用于预算计费
+    input_price: Option<f64>,
+    output_price: Option<f64>,
+    /// 等价组：同组条目可互相替代，启用延迟路由时在组内挑选最健康的条目
+    group: Option<String>,
     /// 预先构建好的 provider 实例（Arc 以支持多处引用）
     provider: Arc<dyn Provider>,
 }
@@ -75,6 +82,8 @@ pub struct ProviderPool {
     fail_threshold: u32,
     /// 冷却时长，默认 60 秒
     cooldown: Duration,
+    /// `agents.defaults.latencyRouting`：组内按近期延迟/错误率选取
+    latency_routing: bool,
 }
 
 impl ProviderPool {
@@ -91,6 +100,9 @@ impl ProviderPool {
                 provider_name: provider_name.into(),
                 weight: 1,
                 priority: 1,
+                input_price: None,
+                output_price: None,
+                group: None,
                 provider,
             }],
             state: Mutex::new(PoolState {
@@ -99,6 +111,7 @@ impl ProviderPool {
             }),
             fail_threshold: 3,
             cooldown: Duration::from_secs(60),
+            latency_routing: false,
         })
     }
 
@@ -110,28 +123,22 @@ impl ProviderPool {
         let defaults = &config.agents.defaults;
 
         // 收集 ModelEntry 列表（兼容旧配置）
-        let entries_cfg: Vec<(String, String, u32, u32, ToolCallMode, Option<f32>)> =
-            if !defaults.model_pool.is_empty() {
-                defaults
-                    .model_pool
-                    .iter()
-                    .map(|e| {
-                        (
-                            e.model.clone(),
-                            e.provider.clone(),
-                            e.weight,
-                            e.priority,
-                            e.tool_call_mode,
-                            e.temperature,
-                        )
-                    })
-                    .collect()
-            } else {
-                // 旧配置：单条目
-                let model = defaults.model.clone();
-                let provider_name = defaults.provider.clone().unwrap_or_default();
-                vec![(model, provider_name, 1, 1, ToolCallMode::Native, None)]
-            };
+        let entries_cfg: Vec<ModelEntry> = if !defaults.model_pool.is_empty() {
+            defaults.model_pool.clone()
+        } else {
+            // 旧配置：单条目
+            vec![ModelEntry {
+                model: defaults.model.clone(),
+                provider: defaults.provider.clone().unwrap_or_default(),
+                weight: 1,
+                priority: 1,
+                input_price: None,
+                output_price: None,
+                temperature: None,
+                tool_call_mode: ToolCallMode::Native,
+                group: None,
+            }]
+        };
 
         if entries_cfg.is_empty() {
             return Err(anyhow::anyhow!(
@@ -143,9 +150,18 @@ impl ProviderPool {
         let mut health_map = HashMap::new();
         let stats_map = HashMap::new();
 
-        for (idx, (model, provider_name, weight, priority, tool_call_mode, temperature)) in
-            entries_cfg.into_iter().enumerate()
-        {
+        for (idx, entry) in entries_cfg.into_iter().enumerate() {
+            let ModelEntry {
+                model,
+                provider: provider_name,
+                weight,
+                priority,
+                input_price,
+                output_price,
+                temperature,
+                tool_call_mode,
+                group,
+            } = entry;
             let explicit = if provider_name.is_empty() {
                 None
             } else {
@@ -164,12 +180,29 @@ impl ProviderPool {
                         idx, model = %model, provider = %provider_name,
                         weight, priority, "ProviderPool: entry loaded"
                     );
+                    let label = format!(
+                        "{}/{}",
+                        explicit
+                            .or_else(|| infer_provider_from_model(&model))
+                            .unwrap_or("default"),
+                        model
+                    );
+                    let provider = FailoverProvider::wrap(
+                        config,
+                        &label,
+                        Arc::from(p),
+                        tool_call_mode,
+                        temperature,
+                    );
                     entries.push(BuiltEntry {
                         model,
                         provider_name,
                         weight,
                         priority,
-                        provider: Arc::from(p),
+                        input_price,
+                        output_price,
+                        group: group.filter(|g| !g.trim().is_empty()),
+                        provider,
                     });
                     health_map.insert(idx, EntryHealth::Healthy);
                 }
@@ -194,6 +227,7 @@ impl ProviderPool {
             }),
             fail_threshold: 3,
             cooldown: Duration::from_secs(60),
+            latency_routing: defaults.latency_routing,
         }))
     }
 
@@ -204,6 +238,7 @@ impl ProviderPool {
     /// 2. 按 priority 升序分组，取最高优先级组
     /// 3. 在组内按 weight 加权随机选取
     /// 4. 若 healthy 池为空，临时解除所有冷却（降级保底）
+    /// 5. 启用延迟路由时，若选中条目属于等价组，改为组内评分最好的健康条目
     ///
     /// 返回 `(entry_index, Arc<dyn Provider>)`
     pub fn acquire(&self) -> Option<(usize, Arc<dyn Provider>)> {
@@ -256,7 +291,8 @@ impl ProviderPool {
             .unwrap_or(1);
 
         let top_group: Vec<usize> = candidates
-            .into_iter()
+            .iter()
+            .copied()
             .filter(|idx| self.entries[*idx].priority == min_priority)
             .collect();
 
@@ -286,11 +322,65 @@ impl ProviderPool {
             }
         }
 
+        if self.latency_routing {
+            selected = self.route_within_group(selected, &candidates);
+        }
+
         Some((selected, Arc::clone(&self.entries[selected].provider)))
     }
 
+    fn entry_key(&self, idx: usize) -> String {
+        let entry = &self.entries[idx];
+        health::entry_key(&entry.provider_name, &entry.model)
+    }
+
+    /// 在选中条目的等价组内挑选评分最好（近期延迟低、错误少）的候选条目。
+    /// 样本不足的条目按 0 分处理，保证新条目会被试用。
+    fn route_within_group(&self, selected: usize, candidates: &[usize]) -> usize {
+        let Some(group) = self.entries[selected].group.as_deref() else {
+            return selected;
+        };
+        let tracker = health::global();
+        let score = |idx: usize| tracker.score(&self.entry_key(idx)).unwrap_or(0.0);
+        let mut best = (selected, score(selected));
+        for &idx in candidates {
+            if idx == selected || self.entries[idx].group.as_deref() != Some(group) {
+                continue;
+            }
+            let s = score(idx);
+            if s < best.1 {
+                best = (idx, s);
+            }
+        }
+        if best.0 != selected {
+            info!(
+                group,
+                from = %self.entries[selected].model,
+                to = %self.entries[best.0].model,
+                "ProviderPool: latency routing picked a healthier entry"
+            );
+        }
+        best.0
+    }
+
     /// 上报调用结果，驱动健康状态变更。
     pub fn report(&self, idx: usize, result: CallResult) {
+        self.report_inner(idx, result, None);
+    }
+
+    /// 同 `report`，并记录本次调用延迟（流式调用取首包时间），供健康面板与延迟路由使用。
+    pub fn report_with_latency(&self, idx: usize, result: CallResult, latency: Duration) {
+        self.report_inner(idx, result, Some(latency));
+    }
+
+    fn report_inner(&self, idx: usize, result: CallResult, latency: Option<Duration>) {
+        if idx >= self.entries.len() {
+            return;
+        }
+        let ok = matches!(result, CallResult::Success);
+        let error = (!ok).then(|| format!("{:?}", result));
+        health::global().record(&self.entry_key(idx), ok, latency, error.as_deref());
+
         let mut state = self.state.lock().unwrap();
 
         match result {
@@ -359,6 +449,15 @@ impl ProviderPool {
         }
     }
 
+    /// 条目的模型名与价格（USD/1M tokens），供预算计费使用
+    pub fn entry_pricing(&self, idx: usize) -> Option<EntryPricing> {
+        self.entries.get(idx).map(|entry| EntryPricing {
+            model: entry.model.clone(),
+            input_price: entry.input_price,
+            output_price: entry.output_price,
+        })
+    }
+
     /// 返回池状态摘要（用于日志/status 命令）
     pub fn status_summary(&self) -> Vec<PoolEntryStatus> {
         let state = self.state.lock().unwrap();
@@ -391,6 +490,14 @@ impl ProviderPool {
     }
 }
 
+/// 池条目的计费信息
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntryPricing {
+    pub model: String,
+    pub input_price: Option<f64>,
+    pub output_price: Option<f64>,
+}
+
 /// 池中单条目的状态摘要（用于外部展示）
 #[derive(Debug, Clone)]
 pub struct PoolEntryStatus {
@@ -469,6 +576,7 @@ mod tests {
             output_price: None,
             temperature: None,
             tool_call_mode: blockcell_core::config::ToolCallMode::Native,
+            group: None,
         }];
         let result = ProviderPool::from_config(&config);
         assert!(
@@ -494,6 +602,7 @@ mod tests {
             output_price: None,
             temperature: None,
             tool_call_mode: blockcell_core::config::ToolCallMode::Native,
+            group: None,
         }];
         let pool = ProviderPool::from_config(&config).unwrap();
         // 连续上报 3 次 Transient 应触发冷却
@@ -519,10 +628,42 @@ mod tests {
             output_price: None,
             temperature: None,
             tool_call_mode: blockcell_core::config::ToolCallMode::Native,
+            group: None,
         }];
         let pool = ProviderPool::from_config(&config).unwrap();
         pool.report(0, CallResult::AuthError);
         let status = pool.status_summary();
         assert_eq!(status[0].health, "dead");
     }
+
+    #[test]
+    fn test_latency_routing_within_group() {
+        let entry = |model: &str, priority: u32| blockcell_core::config::ModelEntry {
+            model: model.to_string(),
+            provider: "ollama".to_string(),
+            weight: 1,
+            priority,
+            input_price: None,
+            output_price: None,
+            temperature: None,
+            tool_call_mode: blockcell_core::config::ToolCallMode::Native,
+            group: Some("local".to_string()),
+        };
+        let mut config = Config::default();
+        config.agents.defaults.model_pool = vec![
+            entry("ollama/routing-slow", 1),
+            entry("ollama/routing-fast", 2),
+        ];
+        let pool = ProviderPool::from_config(&config).unwrap();
+        for _ in 0..3 {
+            pool.report_with_latency(0, CallResult::Success, Duration::from_millis(900));
+            pool.report_with_latency(1, CallResult::Success, Duration::from_millis(100));
+        }
+        // Without latency routing, priority wins.
+        assert_eq!(pool.acquire().unwrap().0, 0);
+
+        config.agents.defaults.latency_routing = true;
+        let routed = ProviderPool::from_config(&config).unwrap();
+        assert_eq!(routed.acquire().unwrap().0, 1);
+    }
 }
@@ -1,6 +1,9 @@
 use crate::job::{CronJob, ScheduleKind};
+use blockcell_core::config::MaintenanceConfig;
+use blockcell_core::permissions::SCHEDULED_ROLE_KEY;
 use blockcell_core::system_event::{DeliveryPolicy, EventPriority, SystemEvent};
 use blockcell_core::{InboundMessage, Paths, Result};
+use blockcell_storage::cron_history::{CronRun, CronRunStatus, CronRunStore, CRON_RUN_ID_KEY};
 use blockcell_tools::EventEmitterHandle;
 use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
@@ -39,6 +42,56 @@ pub struct CronService {
     tick_interval_secs: u64,
     /// Default timezone for jobs without a specified timezone or with invalid timezone.
     default_timezone: Option<Tz>,
+    /// Global maintenance calendar: defers `maintenance_only` jobs and quiets routine
+    /// job events inside a window.
+    maintenance: MaintenanceConfig,
+    /// Per-job run log; `None` if `cron/history.db` could not be opened.
+    run_history: Option<CronRunStore>,
+}
+
+/// Tag a dispatched cron message with its run id so the runtime can close the run.
+pub fn tag_cron_run(msg: &mut InboundMessage, run_id: &str) {
+    if !msg.metadata.is_object() {
+        msg.metadata = serde_json::json!({});
+    }
+    if let Some(obj) = msg.metadata.as_object_mut() {
+        obj.insert(CRON_RUN_ID_KEY.to_string(), serde_json::json!(run_id));
+    }
+}
+
+/// Open a run for `job` in the log and return its id.
+fn start_run(history: Option<&CronRunStore>, job: &CronJob, trigger: &str) -> Option<String> {
+    let history = history?;
+    let run_id = uuid::Uuid::new_v4().to_string();
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    match history.start(&run_id, &job.id, &job.name, trigger, now_ms) {
+        Ok(()) => Some(run_id),
+        Err(e) => {
+            tracing::warn!(job_id = %job.id, error = %e, "Failed to record cron run");
+            None
+        }
+    }
+}
+
+fn fail_run(history: Option<&CronRunStore>, run_id: Option<&str>, error: &str) {
+    if let (Some(history), Some(run_id)) = (history, run_id) {
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        if let Err(e) = history.finish(run_id, CronRunStatus::Failed, None, Some(error), now_ms) {
+            tracing::warn!(run_id = %run_id, error = %e, "Failed to record cron run result");
+        }
+    }
+}
+
+/// Carry the role the job was created with, so the runtime runs it with no more.
+pub fn apply_scheduled_role(metadata: &mut serde_json::Value, job: &CronJob) {
+    if let Some(role) = job.payload.role.as_deref() {
+        if !metadata.is_object() {
+            *metadata = serde_json::json!({});
+        }
+        if let Some(obj) = metadata.as_object_mut() {
+            obj.insert(SCHEDULED_ROLE_KEY.to_string(), serde_json::json!(role));
+        }
+    }
 }
 
 fn apply_route_agent_id(metadata: &mut serde_json::Value, agent_id: Option<&str>) {
@@ -52,6 +105,73 @@ fn apply_route_agent_id(metadata: &mut serde_json::Value, agent_id: Option<&str>
     }
 }
 
+/// The message a `follow_up` job sends into the chat it was scheduled from, so the
+/// agent answers with that session's history and memory. `None` when the job has no
+/// origin chat.
+pub fn follow_up_inbound(job: &CronJob) -> Option<InboundMessage> {
+    let channel = job.payload.channel.clone().filter(|c| !c.is_empty())?;
+    let chat_id = job.payload.to.clone().filter(|c| !c.is_empty())?;
+    let mut content = String::from(
+        "[Scheduled follow-up] You scheduled this follow-up earlier in this conversation.",
+    );
+    if let Some(context) = job.payload.context.as_deref().filter(|c| !c.is_empty()) {
+        content.push_str(&format!("\nContext: {}", context));
+    }
+    content.push_str(&format!(
+        "\nNow: {}\nWrite the follow-up message to the user.",
+        job.payload.message
+    ));
+    Some(InboundMessage {
+        channel,
+        account_id: job.payload.account_id.clone(),
+        sender_id: "cron".to_string(),
+        chat_id,
+        content,
+        media: vec![],
+        metadata: serde_json::json!({
+            "job_id": job.id,
+            "job_name": job.name,
+            "follow_up": true,
+        }),
+        timestamp_ms: chrono::Utc::now().timestamp_millis(),
+    })
+}
+
+/// Next fire time of a cron expression strictly after `after_ms`, evaluated on the wall
+/// clock of `tz`. Across DST changes a local time skipped by the jump fires as if the
+/// clocks had not moved yet (02:30 becomes 03:30), and a repeated local time fires at its
+/// first occurrence that is still ahead.
+pub fn next_cron_run_ms(expr: &str, tz: Tz, after_ms: i64) -> Option<i64> {
+    use chrono::{LocalResult, Offset, TimeZone, Utc};
+
+    let schedule = expr.parse::<cron::Schedule>().ok()?;
+    let after_local = tz.timestamp_millis_opt(after_ms).single()?.naive_local();
+    // Walk the schedule on a naive wall clock, then map each candidate into `tz`.
+    schedule
+        .after(&Utc.from_utc_datetime(&after_local))
+        .find_map(|candidate| {
+            let local = candidate.naive_utc();
+            let fire_ms = match tz.from_local_datetime(&local) {
+                LocalResult::Single(dt) => dt.timestamp_millis(),
+                LocalResult::Ambiguous(a, b) => {
+                    let (a, b) = (a.timestamp_millis(), b.timestamp_millis());
+                    if a.min(b) > after_ms {
+                        a.min(b)
+                    } else {
+                        a.max(b)
+                    }
+                }
+                LocalResult::None => {
+                    // In a DST gap: use the offset in effect a day earlier.
+                    let before = tz.from_utc_datetime(&(local - chrono::Duration::days(1)));
+                    let offset_secs = before.offset().fix().local_minus_utc() as i64;
+                    (Utc.from_utc_datetime(&local).timestamp() - offset_secs) * 1000
+                }
+            };
+            (fire_ms > after_ms).then_some(fire_ms)
+        })
+}
+
 /// Parse a timezone string (e.g., "Asia/Shanghai") into a Tz.
 /// Returns None and logs a warning if the timezone string is invalid.
 fn parse_timezone(tz_str: &str) -> Option<Tz> {
@@ -103,6 +223,14 @@ impl CronService {
             }
         });
 
+        let run_history = match CronRunStore::open(&paths.cron_history_db()) {
+            Ok(store) => Some(store),
+            Err(e) => {
+                tracing::warn!(error = %e, "Cron run history unavailable");
+                None
+            }
+        };
+
         Self {
             paths,
             jobs: Arc::new(RwLock::new(Vec::new())),
@@ -115,9 +243,16 @@ impl CronService {
             has_unsaved_changes: Arc::new(RwLock::new(false)),
             tick_interval_secs: tick_interval_secs.unwrap_or(1),
             default_timezone: default_tz,
+            maintenance: MaintenanceConfig::default(),
+            run_history,
         }
     }
 
+    pub fn with_maintenance(mut self, maintenance: MaintenanceConfig) -> Self {
+        self.maintenance = maintenance;
+        self
+    }
+
     pub fn set_event_emitter(&self, emitter: EventEmitterHandle) {
         let mut slot = self
             .event_emitter
@@ -208,6 +343,31 @@ impl CronService {
         Ok(removed)
     }
 
+    /// Open a run for a job triggered outside the schedule (e.g. `POST /v1/cron/:id/run`).
+    /// Tag the dispatched message with the returned id via [`tag_cron_run`].
+    pub fn start_manual_run(&self, job: &CronJob) -> Option<String> {
+        start_run(self.run_history.as_ref(), job, "manual")
+    }
+
+    /// The newest `limit` runs of a job, newest first.
+    pub fn run_history(&self, job_id: &str, limit: usize) -> Result<Vec<CronRun>> {
+        match &self.run_history {
+            Some(history) => history.history(job_id, limit),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// The timezone a job's schedule is evaluated in: its own `tz` if valid, otherwise
+    /// the service default, otherwise UTC.
+    pub fn job_timezone(&self, job: &CronJob) -> Tz {
+        job.schedule
+            .tz
+            .as_deref()
+            .and_then(|tz| tz.parse::<Tz>().ok())
+            .or(self.default_timezone)
+            .unwrap_or(Tz::UTC)
+    }
+
     pub async fn list_jobs(&self) -> Vec<CronJob> {
         self.jobs.read().await.clone()
     }
@@ -414,6 +574,7 @@ impl CronService {
             jobs.iter().map(|job| job.id.clone()).collect();
         let mut jobs_to_run = Vec::new();
         let mut state_changed = false;
+        let defer_heavy = self.maintenance.defers_heavy_work();
 
         for job in jobs.iter_mut() {
             if !job.enabled {
@@ -451,6 +612,11 @@ impl CronService {
                 None => self.calculate_next_run(job, now_ms, tz.as_ref()),
             };
 
+            // Heavy jobs stay due until the maintenance window opens.
+            if should_run && job.maintenance_only && defer_heavy {
+                continue;
+            }
+
             if should_run {
                 jobs_to_run.push(job.clone());
 
@@ -474,7 +640,7 @@ impl CronService {
                         // Calculate next cron time with timezone support
                         if let Some(expr) = &job.schedule.expr {
                             if let Some(next_ms) =
-                                self.calculate_next_cron_run_ms(expr, tz.as_ref())
+                                self.calculate_next_cron_run_ms(expr, tz.as_ref(), now_ms)
                             {
                                 job.state.next_run_at_ms = Some(next_ms);
                             }
@@ -528,14 +694,24 @@ impl CronService {
         let inbound_tx = self.inbound_tx.clone();
         let event_emitter = self.event_emitter.clone();
         let agent_id = self.agent_id.clone();
+        let quiet = self.maintenance.is_quiet();
 
         for job in jobs_to_run {
             let inbound_tx = inbound_tx.clone();
             let event_emitter = event_emitter.clone();
             let agent_id = agent_id.clone();
+            let run_history = self.run_history.clone();
 
             tokio::spawn(async move {
-                Self::execute_job_internal(&job, inbound_tx, event_emitter, agent_id).await;
+                Self::execute_job_internal(
+                    &job,
+                    inbound_tx,
+                    event_emitter,
+                    agent_id,
+                    quiet,
+                    run_history,
+                )
+                .await;
             });
         }
         Ok(())
@@ -547,8 +723,18 @@ impl CronService {
         inbound_tx: mpsc::Sender<InboundMessage>,
         event_emitter: Arc<StdMutex<Option<EventEmitterHandle>>>,
         agent_id: Option<String>,
+        quiet: bool,
+        run_history: Option<CronRunStore>,
     ) {
         debug!(job_id = %job.id, job_name = %job.name, kind = %job.payload.kind, "Executing cron job");
+        let run_id = start_run(run_history.as_ref(), job, "schedule");
+        // Inside a maintenance window routine started/completed events are kept but
+        // not pushed to the user; failures stay critical.
+        let routine_delivery = if quiet {
+            DeliveryPolicy::silent()
+        } else {
+            DeliveryPolicy::default()
+        };
 
         // Emit start event
         if let Some(emitter) = event_emitter.lock().ok().and_then(|e| e.clone()) {
@@ -559,7 +745,7 @@ impl CronService {
                 "定时任务开始执行",
                 format!("定时任务 {} 已开始执行", job.name),
             );
-            event.delivery = DeliveryPolicy::default();
+            event.delivery = routine_delivery.clone();
             event.details = serde_json::json!({
                 "job_id": job.id.clone(),
                 "job_name": job.name.clone(),
@@ -571,71 +757,94 @@ impl CronService {
             emitter.emit(event);
         }
 
-        let (content, metadata) = match job.payload.kind.as_str() {
-            "reminder" => {
-                let content = job.payload.message.clone();
-                let metadata = serde_json::json!({
-                    "job_id": job.id,
-                    "job_name": job.name,
-                    "reminder": true,
-                    "reminder_message": job.payload.message,
-                    "deliver": job.payload.deliver,
-                    "deliver_channel": job.payload.channel,
-                    "deliver_to": job.payload.to,
-                });
-                (content, metadata)
-            }
-            "script" => {
-                let skill_name = job.payload.skill_name.as_deref().unwrap_or("unknown");
-                let content = job.payload.message.clone();
-                let metadata = serde_json::json!({
-                    "job_id": job.id,
-                    "job_name": job.name,
-                    "skill_name": skill_name,
-                    "forced_skill_name": skill_name,
-                    "skill_run_mode": "cron",
-                    "deliver": job.payload.deliver,
-                    "deliver_channel": job.payload.channel,
-                    "deliver_to": job.payload.to,
-                });
-                (content, metadata)
-            }
-            "agent" => {
-                let content = job.payload.message.clone();
-                let metadata = serde_json::json!({
-                    "job_id": job.id,
-                    "job_name": job.name,
-                    "cron_agent": true,
-                    "deliver": job.payload.deliver,
-                    "deliver_channel": job.payload.channel,
-                    "deliver_to": job.payload.to,
-                });
-                (content, metadata)
-            }
-            _ => {
-                error!(job_id = %job.id, kind = %job.payload.kind, "Unknown cron payload kind");
-                return;
+        let mut msg = if job.payload.kind == "follow_up" {
+            match follow_up_inbound(job) {
+                Some(msg) => msg,
+                None => {
+                    error!(job_id = %job.id, "Follow-up job has no origin chat");
+                    fail_run(
+                        run_history.as_ref(),
+                        run_id.as_deref(),
+                        "Follow-up job has no origin chat",
+                    );
+                    return;
+                }
             }
-        };
-
-        let (msg_channel, msg_chat_id) = ("cron".to_string(), job.id.clone());
-
-        let mut metadata = metadata;
-        apply_route_agent_id(&mut metadata, agent_id.as_deref());
+        } else {
+            let (content, metadata) = match job.payload.kind.as_str() {
+                "reminder" => {
+                    let content = job.payload.message.clone();
+                    let metadata = serde_json::json!({
+                        "job_id": job.id,
+                        "job_name": job.name,
+                        "reminder": true,
+                        "reminder_message": job.payload.message,
+                        "deliver": job.payload.deliver,
+                        "deliver_channel": job.payload.channel,
+                        "deliver_to": job.payload.to,
+                    });
+                    (content, metadata)
+                }
+                "script" => {
+                    let skill_name = job.payload.skill_name.as_deref().unwrap_or("unknown");
+                    let content = job.payload.message.clone();
+                    let metadata = serde_json::json!({
+                        "job_id": job.id,
+                        "job_name": job.name,
+                        "skill_name": skill_name,
+                        "forced_skill_name": skill_name,
+                        "skill_run_mode": "cron",
+                        "deliver": job.payload.deliver,
+                        "deliver_channel": job.payload.channel,
+                        "deliver_to": job.payload.to,
+                    });
+                    (content, metadata)
+                }
+                "agent" => {
+                    let content = job.payload.message.clone();
+                    let metadata = serde_json::json!({
+                        "job_id": job.id,
+                        "job_name": job.name,
+                        "cron_agent": true,
+                        "deliver": job.payload.deliver,
+                        "deliver_channel": job.payload.channel,
+                        "deliver_to": job.payload.to,
+                    });
+                    (content, metadata)
+                }
+                _ => {
+                    error!(job_id = %job.id, kind = %job.payload.kind, "Unknown cron payload kind");
+                    fail_run(
+                        run_history.as_ref(),
+                        run_id.as_deref(),
+                        &format!("Unknown cron payload kind: {}", job.payload.kind),
+                    );
+                    return;
+                }
+            };
 
-        let msg = InboundMessage {
-            channel: msg_channel,
-            account_id: None,
-            sender_id: "cron".to_string(),
-            chat_id: msg_chat_id,
-            content,
-            media: vec![],
-            metadata,
-            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            let (msg_channel, msg_chat_id) = ("cron".to_string(), job.id.clone());
+
+            InboundMessage {
+                channel: msg_channel,
+                account_id: None,
+                sender_id: "cron".to_string(),
+                chat_id: msg_chat_id,
+                content,
+                media: vec![],
+                metadata,
+                timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            }
         };
+        apply_route_agent_id(&mut msg.metadata, agent_id.as_deref());
+        apply_scheduled_role(&mut msg.metadata, job);
+        if let Some(run_id) = run_id.as_deref() {
+            tag_cron_run(&mut msg, run_id);
+        }
 
         if let Err(e) = inbound_tx.send(msg).await {
             error!(error = %e, "Failed to send cron job message");
+            fail_run(run_history.as_ref(), run_id.as_deref(), &e.to_string());
 
             // Emit failure event
             if let Some(emitter) = event_emitter.lock().ok().and_then(|e| e.clone()) {
@@ -664,7 +873,7 @@ impl CronService {
                     "定时任务已派发",
                     format!("定时任务 {} 已成功派发", job.name),
                 );
-                event.delivery = DeliveryPolicy::default();
+                event.delivery = routine_delivery;
                 event.details = serde_json::json!({
                     "job_id": job.id.clone(),
                     "job_name": job.name.clone(),
@@ -682,29 +891,20 @@ impl CronService {
             self.inbound_tx.clone(),
             self.event_emitter.clone(),
             self.agent_id.clone(),
+            self.maintenance.is_quiet(),
+            self.run_history.clone(),
         )
         .await;
     }
 
-    /// Calculate the next cron run time with timezone support.
-    /// Returns the next run time as milliseconds since epoch.
-    fn calculate_next_cron_run_ms(&self, expr: &str, tz: Option<&Tz>) -> Option<i64> {
-        match expr.parse::<cron::Schedule>() {
-            Ok(schedule) => match tz {
-                Some(tz_ref) => schedule
-                    .upcoming(*tz_ref)
-                    .next()
-                    .map(|dt| dt.timestamp_millis()),
-                None => schedule
-                    .upcoming(chrono::Utc)
-                    .next()
-                    .map(|dt| dt.timestamp_millis()),
-            },
-            Err(e) => {
-                tracing::error!(expr = %expr, error = %e, "Invalid cron expression");
-                None
-            }
+    /// Calculate the next cron run time after `now_ms` with timezone support (UTC when
+    /// `tz` is `None`). Returns the next run time as milliseconds since epoch.
+    fn calculate_next_cron_run_ms(&self, expr: &str, tz: Option<&Tz>, now_ms: i64) -> Option<i64> {
+        if let Err(e) = expr.parse::<cron::Schedule>() {
+            tracing::error!(expr = %expr, error = %e, "Invalid cron expression");
+            return None;
         }
+        next_cron_run_ms(expr, tz.copied().unwrap_or(Tz::UTC), now_ms)
     }
 
     fn calculate_next_run(&self, job: &mut CronJob, now_ms: i64, tz: Option<&Tz>) -> bool {
@@ -732,7 +932,7 @@ impl CronService {
             }
             ScheduleKind::Cron => {
                 if let Some(expr) = &job.schedule.expr {
-                    if let Some(next_ms) = self.calculate_next_cron_run_ms(expr, tz) {
+                    if let Some(next_ms) = self.calculate_next_cron_run_ms(expr, tz, now_ms) {
                         job.state.next_run_at_ms = Some(next_ms);
                         debug!(
                             job_id = %job.id,
@@ -851,11 +1051,15 @@ mod tests {
                 to: None,
                 script_kind: None,
                 skill_name: None,
+                context: None,
+                account_id: None,
+                role: None,
             },
             state: crate::job::JobState::default(),
             created_at_ms: now_ms,
             updated_at_ms: now_ms,
             delete_after_run: false,
+            maintenance_only: false,
         }
     }
 
@@ -881,11 +1085,15 @@ mod tests {
                 to: Some("12345".to_string()),
                 script_kind: None,
                 skill_name: None,
+                context: None,
+                account_id: None,
+                role: None,
             },
             state: crate::job::JobState::default(),
             created_at_ms: now_ms,
             updated_at_ms: now_ms,
             delete_after_run: false,
+            maintenance_only: false,
         }
     }
 
@@ -911,11 +1119,15 @@ mod tests {
                 to: Some("ws:test-reminder".to_string()),
                 script_kind: None,
                 skill_name: None,
+                context: None,
+                account_id: None,
+                role: None,
             },
             state: crate::job::JobState::default(),
             created_at_ms: now_ms,
             updated_at_ms: now_ms,
             delete_after_run: true,
+            maintenance_only: false,
         }
     }
 
@@ -936,6 +1148,48 @@ mod tests {
         assert!(metadata.get("route_agent_id").is_none());
     }
 
+    fn utc_ms(rfc3339: &str) -> i64 {
+        chrono::DateTime::parse_from_rfc3339(rfc3339)
+            .unwrap()
+            .timestamp_millis()
+    }
+
+    #[test]
+    fn test_next_cron_run_uses_job_timezone_and_dst_gap() {
+        let shanghai: Tz = "Asia/Shanghai".parse().unwrap();
+        assert_eq!(
+            next_cron_run_ms("0 0 9 * * *", shanghai, utc_ms("2026-01-01T00:00:00Z")),
+            Some(utc_ms("2026-01-01T01:00:00Z"))
+        );
+
+        // 2026-03-08 02:30 does not exist in New York; it fires at 03:30 EDT instead.
+        let new_york: Tz = "America/New_York".parse().unwrap();
+        let gap = next_cron_run_ms("0 30 2 * * *", new_york, utc_ms("2026-03-07T17:00:00Z"));
+        assert_eq!(gap, Some(utc_ms("2026-03-08T07:30:00Z")));
+        assert_eq!(
+            next_cron_run_ms("0 30 2 * * *", new_york, gap.unwrap()),
+            Some(utc_ms("2026-03-09T06:30:00Z"))
+        );
+        assert_eq!(next_cron_run_ms("not a cron", new_york, 0), None);
+    }
+
+    #[test]
+    fn test_next_cron_run_fires_repeated_local_time_once() {
+        // 2026-11-01 01:30 happens twice in New York (EDT, then EST).
+        let new_york: Tz = "America/New_York".parse().unwrap();
+        let first = next_cron_run_ms("0 30 1 * * *", new_york, utc_ms("2026-10-31T16:00:00Z"));
+        assert_eq!(first, Some(utc_ms("2026-11-01T05:30:00Z")));
+        assert_eq!(
+            next_cron_run_ms("0 30 1 * * *", new_york, first.unwrap()),
+            Some(utc_ms("2026-11-02T06:30:00Z"))
+        );
+        // Starting inside the repeated hour, only the second 01:30 is still ahead.
+        assert_eq!(
+            next_cron_run_ms("0 30 1 * * *", new_york, utc_ms("2026-11-01T06:10:00Z")),
+            Some(utc_ms("2026-11-01T06:30:00Z"))
+        );
+    }
+
     #[tokio::test]
     async fn test_cron_event_execute_job_emits_started_and_completed() {
         let paths = Paths::with_base(
@@ -959,6 +1213,69 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_job_created_by_user_cannot_exec_when_it_fires() {
+        use blockcell_core::permissions::ToolPermissions;
+        use blockcell_tools::Tool;
+
+        let paths = Paths::with_base(
+            std::env::temp_dir().join(format!("blockcell-cron-service-{}", uuid::Uuid::new_v4())),
+        );
+        let mut config = blockcell_core::Config::default();
+        config.permissions.enabled = true;
+        // The operator lets users schedule jobs; exec stays admin-only.
+        config.permissions.restricted_tools.retain(|t| t != "cron");
+        let ctx = blockcell_tools::ToolContext {
+            workspace: paths.workspace(),
+            builtin_skills_dir: None,
+            active_skill_dir: None,
+            session_key: "telegram:42".to_string(),
+            channel: "telegram".to_string(),
+            account_id: None,
+            sender_id: Some("42".to_string()),
+            chat_id: "42".to_string(),
+            config: config.clone(),
+            permissions: blockcell_core::types::PermissionSet::new(),
+            task_manager: None,
+            memory_store: None,
+            outbound_tx: None,
+            spawn_handle: None,
+            capability_registry: None,
+            core_evolution: None,
+            event_emitter: None,
+            channel_contacts_file: None,
+            response_cache: None,
+            artifacts: None,
+        };
+        blockcell_tools::cron::CronTool
+            .execute(
+                ctx,
+                serde_json::json!({
+                    "action": "add",
+                    "name": "cleanup",
+                    "message": "run exec rm -rf /tmp/cache",
+                    "every_seconds": 60,
+                }),
+            )
+            .await
+            .expect("user creates cron job");
+
+        let (tx, mut rx) = mpsc::channel(1);
+        let service = CronService::new(paths, tx);
+        service.load().await.expect("load jobs");
+        let jobs = service.list_jobs().await;
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].payload.role.as_deref(), Some("user"));
+
+        service.execute_job(&jobs[0]).await;
+
+        let message = rx.recv().await.expect("receive cron inbound message");
+        assert_eq!(message.channel, "cron");
+        let policy = ToolPermissions::new(&config.permissions);
+        assert!(policy.check_message(&message, "exec").is_err());
+        assert!(policy.check_message(&message, "web_search").is_ok());
+    }
+
     #[tokio::test]
     async fn test_cron_event_execute_job_emits_failed_on_send_error() {
         let paths = Paths::with_base(
@@ -985,6 +1302,38 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_execute_job_records_run_history() {
+        let paths = Paths::with_base(
+            std::env::temp_dir().join(format!("blockcell-cron-service-{}", uuid::Uuid::new_v4())),
+        );
+        let (tx, mut rx) = mpsc::channel(1);
+        let service = CronService::new(paths.clone(), tx);
+        let job = test_agent_job();
+
+        service.execute_job(&job).await;
+
+        let message = rx.recv().await.expect("receive cron inbound message");
+        let run_id = message
+            .metadata
+            .get(CRON_RUN_ID_KEY)
+            .and_then(|v| v.as_str())
+            .expect("run id tagged on message")
+            .to_string();
+        let runs = service.run_history(&job.id, 10).unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].run_id, run_id);
+        assert_eq!(runs[0].status, CronRunStatus::Running);
+        assert_eq!(runs[0].trigger, "schedule");
+
+        drop(rx);
+        service.execute_job(&job).await;
+        let runs = service.run_history(&job.id, 10).unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].status, CronRunStatus::Failed);
+        assert!(runs[0].error.is_some());
+    }
+
     #[tokio::test]
     async fn test_execute_agent_job_sends_plain_cron_message_without_fast_path_flags() {
         let paths = Paths::with_base(
@@ -1006,6 +1355,33 @@ mod tests {
         assert!(message.metadata.get("skill_script").is_none());
     }
 
+    #[tokio::test]
+    async fn test_execute_follow_up_job_lands_in_origin_chat() {
+        let paths = Paths::with_base(
+            std::env::temp_dir().join(format!("blockcell-cron-service-{}", uuid::Uuid::new_v4())),
+        );
+        let (tx, mut rx) = mpsc::channel(1);
+        let service = CronService::new(paths, tx);
+        let mut job = test_agent_job();
+        job.payload.kind = "follow_up".to_string();
+        job.payload.message = "Ask how the deployment went".to_string();
+        job.payload.context = Some("User deploys v2 to production tonight".to_string());
+        job.payload.account_id = Some("work".to_string());
+
+        service.execute_job(&job).await;
+
+        let message = rx.recv().await.expect("receive follow-up message");
+        assert_eq!(message.channel, "telegram");
+        assert_eq!(message.chat_id, "12345");
+        assert_eq!(message.account_id.as_deref(), Some("work"));
+        assert!(message.content.contains("Context: User deploys v2"));
+        assert!(message.content.contains("Now: Ask how the deployment went"));
+        assert_eq!(message.metadata["follow_up"], true);
+
+        job.payload.to = None;
+        assert!(follow_up_inbound(&job).is_none());
+    }
+
     #[tokio::test]
     async fn test_run_tick_executes_due_at_job_loaded_from_disk() {
         let paths = Paths::with_base(
@@ -1039,6 +1415,46 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_run_tick_defers_maintenance_only_job_outside_window() {
+        let paths = Paths::with_base(
+            std::env::temp_dir().join(format!("blockcell-cron-service-{}", uuid::Uuid::new_v4())),
+        );
+        tokio::fs::create_dir_all(paths.cron_dir())
+            .await
+            .expect("create cron dir");
+        let mut job = test_due_at_job();
+        job.maintenance_only = true;
+        let store = JobStore {
+            version: 1,
+            jobs: vec![job],
+        };
+        let content = serde_json::to_string_pretty(&store).expect("serialize cron store");
+        tokio::fs::write(paths.cron_jobs_file(), content)
+            .await
+            .expect("write cron store");
+
+        // A one-hour window starting six hours from now.
+        let start = chrono::Local::now() + chrono::Duration::hours(6);
+        let end = start + chrono::Duration::hours(1);
+        let maintenance = MaintenanceConfig {
+            windows: vec![blockcell_core::config::MaintenanceWindowConfig {
+                time: format!("{}-{}", start.format("%H:%M"), end.format("%H:%M")),
+                days: Vec::new(),
+            }],
+            ..Default::default()
+        };
+        let (tx, mut rx) = mpsc::channel(1);
+        let service = CronService::new(paths, tx).with_maintenance(maintenance);
+
+        service.run_tick().await.expect("run tick");
+
+        assert!(rx.try_recv().is_err());
+        let jobs = service.list_jobs().await;
+        assert!(jobs[0].enabled);
+        assert!(jobs[0].state.last_run_at_ms.is_none());
+    }
+
     #[tokio::test]
     async fn test_run_tick_does_not_readd_delete_after_run_job_from_disk() {
         let paths = Paths::with_base(
@@ -0,0 +1,275 @@
+use blockcell_core::config::DeadMansSwitchConfig;
+use blockcell_core::{OutboundMessage, Paths, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
+
+/// Serializes read-modify-write of the state file between check-ins (HTTP handlers)
+/// and the service loop.
+static STATE_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CheckState {
+    /// Unix seconds of the last check-in.
+    #[serde(default)]
+    last_seen: Option<i64>,
+    /// When the check was first seen in the config; stands in for `last_seen` until the
+    /// first check-in.
+    #[serde(default)]
+    watching_since: Option<i64>,
+    /// Set while an escalation for the current outage has been sent.
+    #[serde(default)]
+    alerted_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct GatewayBeat {
+    #[serde(default)]
+    last_seen: Option<i64>,
+    /// The previous run shut down cleanly, so the gap up to now is expected.
+    #[serde(default)]
+    clean_stop: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SwitchState {
+    #[serde(default)]
+    gateway: GatewayBeat,
+    #[serde(default)]
+    checks: BTreeMap<String, CheckState>,
+}
+
+fn load_state(paths: &Paths) -> SwitchState {
+    std::fs::read_to_string(paths.dead_mans_switch_file())
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(paths: &Paths, state: &SwitchState) -> Result<()> {
+    std::fs::create_dir_all(&paths.base)?;
+    std::fs::write(
+        paths.dead_mans_switch_file(),
+        serde_json::to_string_pretty(state)?,
+    )?;
+    Ok(())
+}
+
+fn update_state<T>(paths: &Paths, f: impl FnOnce(&mut SwitchState) -> T) -> Result<T> {
+    let _guard = STATE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut state = load_state(paths);
+    let out = f(&mut state);
+    save_state(paths, &state)?;
+    Ok(out)
+}
+
+/// Record a check-in for `name` (called by `POST /v1/ingest/:source`).
+pub fn check_in(paths: &Paths, name: &str) -> Result<()> {
+    let now = Utc::now().timestamp();
+    update_state(paths, |state| {
+        state.checks.entry(name.to_string()).or_default().last_seen = Some(now);
+    })
+}
+
+fn format_ts(secs: i64) -> String {
+    chrono::DateTime::from_timestamp(secs, 0)
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_else(|| secs.to_string())
+}
+
+/// Advance the state to `now` and return the notifications to send.
+fn evaluate(state: &mut SwitchState, config: &DeadMansSwitchConfig, now: i64) -> Vec<String> {
+    let mut notices = Vec::new();
+
+    if config.self_window_minutes > 0 {
+        if let Some(last) = state.gateway.last_seen {
+            let gap = now - last;
+            if !state.gateway.clean_stop && gap > config.self_window_minutes as i64 * 60 {
+                notices.push(format!(
+                    "🚨 Gateway heartbeat missed for {} min (last beat {}). It is running again now; check the host and logs.",
+                    gap / 60,
+                    format_ts(last)
+                ));
+            }
+        }
+    }
+    state.gateway = GatewayBeat {
+        last_seen: Some(now),
+        clean_stop: false,
+    };
+
+    state
+        .checks
+        .retain(|name, _| config.checks.contains_key(name));
+    for (name, check) in &config.checks {
+        let entry = state.checks.entry(name.clone()).or_default();
+        let since = *entry.watching_since.get_or_insert(now);
+        let reference = entry.last_seen.unwrap_or(since);
+        let overdue = now - reference > check.window_minutes as i64 * 60;
+        if overdue {
+            let due = match entry.alerted_at {
+                None => true,
+                Some(at) => {
+                    config.repeat_minutes > 0 && now - at >= config.repeat_minutes as i64 * 60
+                }
+            };
+            if due {
+                let last = entry
+                    .last_seen
+                    .map(format_ts)
+                    .unwrap_or_else(|| "never".to_string());
+                notices.push(format!(
+                    "🚨 '{}' has not checked in for {} min (window {} min, last check-in: {}).",
+                    name,
+                    (now - reference) / 60,
+                    check.window_minutes,
+                    last
+                ));
+                entry.alerted_at = Some(now);
+            }
+        } else if entry.alerted_at.take().is_some() {
+            notices.push(format!("✅ '{}' checked in again.", name));
+        }
+    }
+    notices
+}
+
+pub struct DeadMansSwitchService {
+    paths: Paths,
+    config: DeadMansSwitchConfig,
+    interval: Duration,
+    outbound_tx: mpsc::Sender<OutboundMessage>,
+}
+
+impl DeadMansSwitchService {
+    pub fn new(
+        paths: Paths,
+        config: DeadMansSwitchConfig,
+        outbound_tx: mpsc::Sender<OutboundMessage>,
+    ) -> Self {
+        Self {
+            paths,
+            config,
+            interval: Duration::from_secs(60),
+            outbound_tx,
+        }
+    }
+
+    async fn tick(&self) -> Result<()> {
+        let now = Utc::now().timestamp();
+        let notices = update_state(&self.paths, |state| evaluate(state, &self.config, now))?;
+        let target = &self.config.escalation;
+        for notice in notices {
+            warn!(notice = %notice, "Dead man's switch escalation");
+            let mut msg = OutboundMessage::new(&target.channel, &target.chat_id, &notice);
+            msg.account_id = target.account_id.clone();
+            self.outbound_tx
+                .send(msg)
+                .await
+                .map_err(|e| blockcell_core::Error::Channel(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    pub async fn run_loop(self: Arc<Self>, mut shutdown: tokio::sync::broadcast::Receiver<()>) {
+        if self.config.escalation.channel.trim().is_empty()
+            || self.config.escalation.chat_id.trim().is_empty()
+        {
+            warn!("Dead man's switch enabled without an escalation channel/chatId; not starting");
+            return;
+        }
+        info!(
+            checks = self.config.checks.len(),
+            channel = %self.config.escalation.channel,
+            "DeadMansSwitchService started"
+        );
+
+        // The first tick fires immediately, so a gap since the last run is reported at
+        // startup.
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if let Err(e) = self.tick().await {
+                        error!(error = %e, "Dead man's switch check failed");
+                    }
+                }
+                _ = shutdown.recv() => {
+                    let marked = update_state(&self.paths, |state| {
+                        state.gateway.clean_stop = true;
+                    });
+                    if let Err(e) = marked {
+                        warn!(error = %e, "Failed to record clean gateway stop");
+                    }
+                    info!("DeadMansSwitchService shutting down");
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blockcell_core::config::CheckInConfig;
+
+    fn config() -> DeadMansSwitchConfig {
+        let mut config = DeadMansSwitchConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        config
+            .checks
+            .insert("backup".to_string(), CheckInConfig { window_minutes: 60 });
+        config
+    }
+
+    #[test]
+    fn test_check_escalates_once_and_recovers() {
+        let config = DeadMansSwitchConfig {
+            self_window_minutes: 0,
+            ..config()
+        };
+        let mut state = SwitchState::default();
+        let t0 = 1_700_000_000;
+        assert!(evaluate(&mut state, &config, t0).is_empty());
+        assert!(evaluate(&mut state, &config, t0 + 30 * 60).is_empty());
+
+        let notices = evaluate(&mut state, &config, t0 + 61 * 60);
+        assert_eq!(notices.len(), 1);
+        assert!(notices[0].contains("'backup'"));
+        assert!(notices[0].contains("never"));
+        assert!(evaluate(&mut state, &config, t0 + 90 * 60).is_empty());
+
+        state.checks.get_mut("backup").unwrap().last_seen = Some(t0 + 95 * 60);
+        let notices = evaluate(&mut state, &config, t0 + 96 * 60);
+        assert_eq!(notices, vec!["✅ 'backup' checked in again.".to_string()]);
+        assert!(state.checks["backup"].alerted_at.is_none());
+    }
+
+    #[test]
+    fn test_gateway_gap_ignores_clean_stop() {
+        let config = config();
+        let mut state = SwitchState::default();
+        let t0 = 1_700_000_000;
+        evaluate(&mut state, &config, t0);
+        state.checks.get_mut("backup").unwrap().last_seen = Some(t0 + 2 * 3600);
+
+        let notices = evaluate(&mut state, &config, t0 + 2 * 3600);
+        assert_eq!(notices.len(), 1);
+        assert!(notices[0].contains("Gateway heartbeat missed for 120 min"));
+
+        state.gateway.clean_stop = true;
+        assert!(evaluate(&mut state, &config, t0 + 2 * 3600 + 1800).is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+use blockcell_core::config::{BudgetConfig, MaintenanceConfig};
 use blockcell_core::{Config, InboundMessage, Paths, Result};
 use chrono::Utc;
 use tokio::sync::mpsc;
@@ -21,6 +22,10 @@ pub struct GhostServiceConfig {
     pub schedule: String,
     pub max_syncs_per_day: u32,
     pub auto_social: bool,
+    /// Ghost routines are skipped while the monthly spend cap is exceeded.
+    pub budget: BudgetConfig,
+    /// Due routines wait for the next maintenance window when one is configured.
+    pub maintenance: MaintenanceConfig,
 }
 
 impl GhostServiceConfig {
@@ -32,6 +37,8 @@ impl GhostServiceConfig {
             schedule: ghost.schedule.clone(),
             max_syncs_per_day: ghost.max_syncs_per_day,
             auto_social: ghost.auto_social,
+            budget: config.agents.defaults.budget.clone(),
+            maintenance: config.maintenance.clone(),
         }
     }
 }
@@ -142,6 +149,11 @@ impl GhostService {
             return Ok(());
         }
 
+        if blockcell_agent::budget::background_paused(&self.paths, &self.config.budget) {
+            info!("👻 Ghost: monthly budget cap reached, skipping routine");
+            return Ok(());
+        }
+
         info!("👻 Ghost Agent: starting routine cycle");
         self.sync_tracker.record_sync();
 
@@ -226,7 +238,9 @@ impl GhostService {
                                      schedule_changed ||
                                      new_ghost.model != self.config.model ||
                                      new_ghost.max_syncs_per_day != self.config.max_syncs_per_day ||
-                                     new_ghost.auto_social != self.config.auto_social;
+                                     new_ghost.auto_social != self.config.auto_social ||
+                                     new_ghost.budget != self.config.budget ||
+                                     new_ghost.maintenance != self.config.maintenance;
 
                         if changed {
                             info!("👻 Ghost config updated via hot-reload");
@@ -272,6 +286,12 @@ impl GhostService {
                         None => false,
                     };
 
+                    // A due routine stays due until the maintenance window opens.
+                    if should_run && self.config.maintenance.defers_heavy_work() {
+                        debug!("👻 Ghost routine due, waiting for the maintenance window");
+                        continue;
+                    }
+
                     if should_run {
                         // 推进到下一个计划时间
                         next_scheduled = schedule.upcoming(Utc).next();
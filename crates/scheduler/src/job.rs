@@ -15,6 +15,9 @@ pub struct CronJob {
     pub updated_at_ms: i64,
     #[serde(default)]
     pub delete_after_run: bool,
+    /// Heavy job: when due outside the maintenance calendar it waits for the next window.
+    #[serde(default)]
+    pub maintenance_only: bool,
 }
 
 fn default_true() -> bool {
@@ -68,6 +71,16 @@ pub struct JobPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "skillName")]
     pub skill_name: Option<String>,
+    /// For kind="follow_up": why the follow-up was scheduled, shown to the agent when it fires.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    /// For kind="follow_up": account of the originating chat on multi-account channels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account_id: Option<String>,
+    /// Role of whoever created the job through the agent; the job never runs with more.
+    /// `None` for jobs created by an operator (CLI, WebUI).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
 }
 
 fn default_payload_kind() -> String {
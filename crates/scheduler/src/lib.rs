@@ -1,5 +1,6 @@
 pub mod consolidator;
 pub mod cron_service;
+pub mod dead_mans_switch;
 pub mod dream_service;
 pub mod ghost;
 pub mod heartbeat;
@@ -9,7 +10,8 @@ pub use consolidator::{
     check_gates, DreamConsolidator, DreamError, DreamState, GateCheckResult,
     SESSION_GATE_THRESHOLD, TIME_GATE_THRESHOLD_HOURS,
 };
-pub use cron_service::CronService;
+pub use cron_service::{apply_scheduled_role, next_cron_run_ms, tag_cron_run, CronService};
+pub use dead_mans_switch::DeadMansSwitchService;
 pub use dream_service::{DreamService, DreamServiceConfig};
 pub use ghost::{GhostService, GhostServiceConfig};
 pub use heartbeat::HeartbeatService;
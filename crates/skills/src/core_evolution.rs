@@ -4,8 +4,8 @@ use crate::capability_provider::{
 use crate::capability_versioning::{CapabilityVersionManager, CapabilityVersionSource};
 use crate::evolution::LLMProvider;
 use blockcell_core::{
-    CapabilityDescriptor, CapabilityStatus, CapabilityType, Error, PrivilegeLevel, ProviderKind,
-    Result,
+    CapabilityDescriptor, CapabilityStatus, CapabilityType, Error, NetworkScope, PrivilegeLevel,
+    ProviderKind, Result,
 };
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
@@ -38,6 +38,9 @@ pub struct CoreEvolutionRecord {
     /// Output schema (JSON Schema) extracted from LLM response
     #[serde(default)]
     pub output_schema: Option<serde_json::Value>,
+    /// Network access declared alongside the schema; enforced when the capability runs
+    #[serde(default)]
+    pub network: NetworkScope,
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -348,6 +351,7 @@ impl CoreEvolution {
                 feedback_history: Vec::new(),
                 input_schema: None,
                 output_schema: None,
+                network: NetworkScope::None,
                 created_at: chrono::Utc::now().timestamp(),
                 updated_at: chrono::Utc::now().timestamp(),
             };
@@ -384,6 +388,7 @@ impl CoreEvolution {
             feedback_history: Vec::new(),
             input_schema: None,
             output_schema: None,
+            network: NetworkScope::None,
             created_at: chrono::Utc::now().timestamp(),
             updated_at: chrono::Utc::now().timestamp(),
         };
@@ -441,6 +446,7 @@ impl CoreEvolution {
                 record.input_schema = Some(input_schema);
                 record.output_schema = Some(output_schema);
             }
+            record.network = Self::extract_network_scope(&raw_response);
             record.status = CoreEvolutionStatus::Generated;
             self.save_record(&record)?;
 
@@ -664,8 +670,13 @@ impl CoreEvolution {
         prompt.push_str(
             "After the code block, output a JSON block describing the input and output schema.\n",
         );
-        prompt.push_str("Format:\n```json\n{\n  \"input_schema\": {\n    \"type\": \"object\",\n    \"properties\": { ... },\n    \"required\": [ ... ]\n  },\n  \"output_schema\": {\n    \"type\": \"object\",\n    \"properties\": { ... }\n  }\n}\n```\n");
-        prompt.push_str("This schema helps the agent understand how to call this capability.\n\n");
+        prompt.push_str("Format:\n```json\n{\n  \"input_schema\": {\n    \"type\": \"object\",\n    \"properties\": { ... },\n    \"required\": [ ... ]\n  },\n  \"output_schema\": {\n    \"type\": \"object\",\n    \"properties\": { ... }\n  },\n  \"network\": \"none\"\n}\n```\n");
+        prompt.push_str("This schema helps the agent understand how to call this capability.\n");
+        prompt.push_str(
+            "`network` declares the hosts the code contacts: \"none\", or {\"domains\": [\"api.example.com\"]}. \
+             Network access goes through an HTTP proxy set in HTTP_PROXY/HTTPS_PROXY that refuses undeclared hosts, \
+             and a refused request disables the capability.\n\n",
+        );
 
         // Add feedback history if retrying
         if !record.feedback_history.is_empty() {
@@ -1000,7 +1011,8 @@ impl CoreEvolution {
         )
         .with_privilege(PrivilegeLevel::Limited)
         .with_status(CapabilityStatus::Available) // Starts as Available; promoted to Active after canary passes
-        .with_provider_path(artifact_path);
+        .with_provider_path(artifact_path)
+        .with_network(record.network.clone());
 
         // Apply input/output schema if extracted from LLM response
         if let Some(ref schema) = record.input_schema {
@@ -1100,7 +1112,7 @@ impl CoreEvolution {
                 }
             }
         }
-        records.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        records.sort_by_key(|r| std::cmp::Reverse(r.created_at));
         Ok(records)
     }
 
@@ -1130,6 +1142,18 @@ impl CoreEvolution {
         None
     }
 
+    /// Network scope from the ```json block after the code. No declaration means no access.
+    fn extract_network_scope(response: &str) -> NetworkScope {
+        let json_marker = "```json";
+        response
+            .rfind(json_marker)
+            .map(|start| &response[start + json_marker.len()..])
+            .and_then(|after| after.find("```").map(|end| after[..end].trim()))
+            .and_then(|block| serde_json::from_str::<serde_json::Value>(block).ok())
+            .and_then(|schema| schema.get("network").map(NetworkScope::from_declaration))
+            .unwrap_or_default()
+    }
+
     /// Rollback a capability to its previous version AND rebuild the executor in the registry.
     /// This ensures the rollback is effective at runtime, not just on disk.
     pub async fn rollback_capability(&self, capability_id: &str) -> Result<bool> {
@@ -1232,6 +1256,21 @@ mod tests {
         assert!(code.contains("import json"));
     }
 
+    #[test]
+    fn test_extract_network_scope() {
+        let response = "```python\nimport urllib\n```\n```json\n{\"input_schema\": {}, \"network\": {\"domains\": [\"wttr.in\"]}}\n```";
+        assert_eq!(
+            CoreEvolution::extract_network_scope(response),
+            NetworkScope::Domains {
+                domains: vec!["wttr.in".to_string()]
+            }
+        );
+        assert_eq!(
+            CoreEvolution::extract_network_scope("```bash\necho hi\n```"),
+            NetworkScope::None
+        );
+    }
+
     #[tokio::test]
     async fn test_request_idempotent() {
         let dir = std::env::temp_dir().join("test_core_evo_idempotent");
@@ -1284,6 +1323,7 @@ mod tests {
                 feedback_history: Vec::new(),
                 input_schema: None,
                 output_schema: None,
+                network: NetworkScope::None,
                 created_at: chrono::Utc::now().timestamp() - (MAX_AUTO_FAILURES - i) as i64,
                 updated_at: chrono::Utc::now().timestamp(),
             };
@@ -257,6 +257,28 @@ pub struct FeedbackEntry {
     pub timestamp: i64,
 }
 
+/// 进化流水线的模型档位：草稿用便宜模型，审计与升级后的重新生成用强模型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelTier {
+    Draft,
+    Strong,
+}
+
+/// 某个阶段实际使用的模型（按记录汇报）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelRouteEntry {
+    pub stage: String, // "generate", "regenerate", "audit"
+    pub tier: ModelTier,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub attempt: u32,
+    /// 因测试连续失败而从草稿模型升级到强模型
+    #[serde(default)]
+    pub escalated: bool,
+    pub timestamp: i64,
+}
+
 /// 进化记录
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EvolutionRecord {
@@ -278,6 +300,9 @@ pub struct EvolutionRecord {
     /// 历次重试的反馈记录
     #[serde(default)]
     pub feedback_history: Vec<FeedbackEntry>,
+    /// 各阶段的模型路由记录
+    #[serde(default)]
+    pub model_routing: Vec<ModelRouteEntry>,
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -440,6 +465,7 @@ impl SkillEvolution {
             status: EvolutionStatus::Triggered,
             attempt: 1,
             feedback_history: Vec::new(),
+            model_routing: Vec::new(),
             created_at: chrono::Utc::now().timestamp(),
             updated_at: chrono::Utc::now().timestamp(),
         };
@@ -1389,7 +1415,7 @@ impl SkillEvolution {
             }
         }
 
-        summaries.sort_by(|a, b| b.0.cmp(&a.0));
+        summaries.sort_by_key(|s| std::cmp::Reverse(s.0));
         summaries.into_iter().take(max).map(|(_, s)| s).collect()
     }
 
@@ -2812,6 +2838,13 @@ or\n\
         self.save_record(record)
     }
 
+    /// 追加一条模型路由记录
+    pub fn record_model_route(&self, evolution_id: &str, entry: ModelRouteEntry) -> Result<()> {
+        let mut record = self.load_record(evolution_id)?;
+        record.model_routing.push(entry);
+        self.save_record(&record)
+    }
+
     /// P2-7: 原子写入 — write-tmp-then-rename，避免崩溃时文件损坏
     fn save_record(&self, record: &EvolutionRecord) -> Result<()> {
         let records_dir = self
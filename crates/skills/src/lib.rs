@@ -6,6 +6,7 @@ pub mod dispatcher;
 pub mod engine;
 pub mod evolution;
 pub mod manager;
+pub mod network_sandbox;
 pub mod service;
 pub mod versioning;
 
@@ -20,7 +21,8 @@ pub use core_evolution::CoreEvolution;
 pub use dispatcher::{SkillDispatchResult, SkillDispatcher, ToolCallRecord};
 pub use engine::{EngineConfig, ExecutionResult, RhaiEngine, SkillExecutor};
 pub use evolution::{
-    EvolutionContext, LLMProvider, SkillEvolution, SkillLayout, SkillType, TriggerReason,
+    EvolutionContext, LLMProvider, ModelRouteEntry, ModelTier, SkillEvolution, SkillLayout,
+    SkillType, TriggerReason,
 };
 pub use manager::{Skill, SkillCard, SkillManager, SkillMeta, SkillTestFixture};
 pub use service::{
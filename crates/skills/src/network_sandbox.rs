@@ -0,0 +1,271 @@
+//! Network isolation for evolved capabilities.
+//!
+//! Every run of a non-built-in capability gets its own loopback HTTP proxy that admits
+//! only the hosts in the capability's declared [`NetworkScope`], decided by the same
+//! `EgressPolicy` the tools' HTTP clients use. The child process is pointed at the
+//! proxy through the standard proxy variables, which curl, wget and Python's
+//! urllib/requests honour. Refused hosts are recorded so the registry can block the
+//! capability after the run.
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use blockcell_core::egress::EgressPolicy;
+use blockcell_core::{Error, NetworkScope, Result};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// Request heads larger than this are refused.
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+
+const FORBIDDEN: &[u8] =
+    b"HTTP/1.1 403 Forbidden\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const BAD_REQUEST: &[u8] =
+    b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const BAD_GATEWAY: &[u8] =
+    b"HTTP/1.1 502 Bad Gateway\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+#[derive(Debug, PartialEq, Eq)]
+struct Target {
+    host: String,
+    port: u16,
+    /// Origin-form path for plain HTTP; `None` for `CONNECT` tunnels.
+    path: Option<String>,
+}
+
+fn split_authority(authority: &str, default_port: u16) -> Option<(String, u16)> {
+    let (host, port) = match authority.strip_prefix('[') {
+        // [v6]:port
+        Some(rest) => {
+            let (host, after) = rest.split_once(']')?;
+            let port = match after.strip_prefix(':') {
+                Some(p) => p.parse().ok()?,
+                None => default_port,
+            };
+            (host, port)
+        }
+        None => match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().ok()?),
+            None => (authority, default_port),
+        },
+    };
+    // Userinfo would let `allowed.com@evil.test` pass a naive host check.
+    if host.is_empty() || host.contains('@') {
+        return None;
+    }
+    Some((host.to_ascii_lowercase(), port))
+}
+
+/// Target of a proxy request line: `CONNECT host:port ...` or `GET http://host/path ...`.
+fn parse_target(request_line: &str) -> Option<(Target, &str, &str)> {
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next()?;
+    let uri = parts.next()?;
+    let version = parts.next()?;
+    if method.eq_ignore_ascii_case("CONNECT") {
+        let (host, port) = split_authority(uri, 443)?;
+        return Some((
+            Target {
+                host,
+                port,
+                path: None,
+            },
+            method,
+            version,
+        ));
+    }
+    let rest = uri.strip_prefix("http://")?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let (host, port) = split_authority(authority, 80)?;
+    Some((
+        Target {
+            host,
+            port,
+            path: Some(path.to_string()),
+        },
+        method,
+        version,
+    ))
+}
+
+async fn handle(
+    mut client: TcpStream,
+    capability_id: Arc<str>,
+    policy: Arc<EgressPolicy>,
+    violations: Arc<Mutex<Vec<String>>>,
+) -> std::io::Result<()> {
+    let mut buf = Vec::with_capacity(1024);
+    let head_end = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        if buf.len() > MAX_HEAD_BYTES {
+            return client.write_all(BAD_REQUEST).await;
+        }
+        let mut chunk = [0u8; 2048];
+        let n = client.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+    let line_end = buf
+        .windows(2)
+        .position(|w| w == b"\r\n")
+        .unwrap_or(head_end);
+    let request_line = String::from_utf8_lossy(&buf[..line_end]).to_string();
+    let Some((target, method, version)) = parse_target(&request_line) else {
+        return client.write_all(BAD_REQUEST).await;
+    };
+
+    if policy
+        .check_host(&capability_id, &target.host, &[])
+        .is_err()
+    {
+        warn!(
+            capability_id = %capability_id,
+            host = %target.host,
+            "Capability network access outside its declared scope"
+        );
+        if let Ok(mut v) = violations.lock() {
+            if !v.contains(&target.host) {
+                v.push(target.host.clone());
+            }
+        }
+        return client.write_all(FORBIDDEN).await;
+    }
+
+    let mut upstream = match TcpStream::connect((target.host.as_str(), target.port)).await {
+        Ok(stream) => stream,
+        Err(e) => {
+            debug!(host = %target.host, error = %e, "Sandbox proxy: upstream connect failed");
+            return client.write_all(BAD_GATEWAY).await;
+        }
+    };
+    match &target.path {
+        None => {
+            client
+                .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+                .await?;
+            upstream.write_all(&buf[head_end..]).await?;
+        }
+        Some(path) => {
+            upstream
+                .write_all(format!("{} {} {}", method, path, version).as_bytes())
+                .await?;
+            upstream.write_all(&buf[line_end..]).await?;
+        }
+    }
+    // Keep-alive requests on this connection can only reach the host checked above.
+    tokio::io::copy_bidirectional(&mut client, &mut upstream).await?;
+    Ok(())
+}
+
+/// A running proxy for one capability execution.
+pub struct NetworkSandbox {
+    addr: SocketAddr,
+    violations: Arc<Mutex<Vec<String>>>,
+    task: JoinHandle<()>,
+}
+
+impl NetworkSandbox {
+    pub async fn start(capability_id: &str, scope: &NetworkScope) -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| Error::Tool(format!("Failed to start network sandbox: {}", e)))?;
+        let addr = listener.local_addr()?;
+        let violations = Arc::new(Mutex::new(Vec::new()));
+        let policy = Arc::new(EgressPolicy::for_scope(scope));
+        let capability_id: Arc<str> = Arc::from(capability_id);
+
+        let seen = violations.clone();
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let (id, policy, seen) = (capability_id.clone(), policy.clone(), seen.clone());
+                tokio::spawn(async move {
+                    if let Err(e) = handle(stream, id, policy, seen).await {
+                        debug!(error = %e, "Sandbox proxy connection ended");
+                    }
+                });
+            }
+        });
+        Ok(Self {
+            addr,
+            violations,
+            task,
+        })
+    }
+
+    /// Environment that routes the child's HTTP(S) traffic through the proxy.
+    pub fn env(&self) -> Vec<(String, String)> {
+        let proxy = format!("http://{}", self.addr);
+        let mut env = Vec::new();
+        for name in ["HTTP_PROXY", "HTTPS_PROXY", "ALL_PROXY"] {
+            env.push((name.to_string(), proxy.clone()));
+            env.push((name.to_ascii_lowercase(), proxy.clone()));
+        }
+        env.push(("NO_PROXY".to_string(), String::new()));
+        env.push(("no_proxy".to_string(), String::new()));
+        env
+    }
+
+    /// Stop the proxy and return the hosts it refused.
+    pub fn finish(self) -> Vec<String> {
+        self.task.abort();
+        self.violations
+            .lock()
+            .map(|v| v.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_target() {
+        let (target, _, _) = parse_target("CONNECT api.example.com:443 HTTP/1.1").unwrap();
+        assert_eq!(target.host, "api.example.com");
+        assert_eq!(target.port, 443);
+        assert!(target.path.is_none());
+
+        let (target, method, _) =
+            parse_target("GET http://Wttr.in/Berlin?format=3 HTTP/1.1").unwrap();
+        assert_eq!(method, "GET");
+        assert_eq!(target.host, "wttr.in");
+        assert_eq!(target.port, 80);
+        assert_eq!(target.path.as_deref(), Some("/Berlin?format=3"));
+
+        assert_eq!(
+            parse_target("CONNECT [::1]:8443 HTTP/1.1").unwrap().0.host,
+            "::1"
+        );
+        assert!(parse_target("GET http://ok.com@evil.test/ HTTP/1.1").is_none());
+        assert!(parse_target("GET /relative HTTP/1.1").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_refused_host_is_recorded() {
+        let sandbox = NetworkSandbox::start("net.probe", &NetworkScope::None)
+            .await
+            .unwrap();
+        let proxy = sandbox.env()[0].1.trim_start_matches("http://").to_string();
+
+        let mut stream = TcpStream::connect(&proxy).await.unwrap();
+        stream
+            .write_all(b"CONNECT attacker.test:443 HTTP/1.1\r\nHost: attacker.test\r\n\r\n")
+            .await
+            .unwrap();
+        let mut reply = String::new();
+        stream.read_to_string(&mut reply).await.unwrap();
+        assert!(reply.starts_with("HTTP/1.1 403"));
+
+        assert_eq!(sandbox.finish(), vec!["attacker.test".to_string()]);
+    }
+}
@@ -1,7 +1,8 @@
 use crate::evolution::{
-    EvolutionContext, EvolutionRecord, EvolutionStatus, FeedbackEntry, LLMProvider, SkillEvolution,
-    SkillLayout, SkillType, TriggerReason,
+    EvolutionContext, EvolutionRecord, EvolutionStatus, FeedbackEntry, LLMProvider,
+    ModelRouteEntry, ModelTier, SkillEvolution, SkillLayout, SkillType, TriggerReason,
 };
+use blockcell_core::config::MaintenanceConfig;
 use blockcell_core::{Error, Result};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
@@ -19,8 +20,11 @@ const BUILTIN_TOOLS: &[&str] = &[
     "edit_file",
     "list_dir",
     "exec",
+    "python",
     "web_search",
     "web_fetch",
+    "bookmarks",
+    "weather",
     "browse",
     "message",
     "spawn",
@@ -35,8 +39,14 @@ const BUILTIN_TOOLS: &[&str] = &[
     "capability_evolve",
     "camera_capture",
     "app_control",
+    "media_control",
     "file_ops",
+    "doc_diff",
+    "doc_ingest",
+    "doc_search",
     "data_process",
+    "calc",
+    "nutrition",
     "http_request",
     "email",
     "audio_transcribe",
@@ -50,7 +60,27 @@ const BUILTIN_TOOLS: &[&str] = &[
     "network_monitor",
     "knowledge_graph",
     "stream_subscribe",
+    "iot_control",
     "alert_rule",
+    "backtest",
+    "rss",
+    "project",
+    "todo",
+    "calendar",
+    "birthdays",
+    "habits",
+    "follow_up",
+    "travel",
+    "parcel_track",
+    "subscriptions",
+    "receipts",
+    "translate",
+    "db_query",
+    "object_storage",
+    "ssh",
+    "docker",
+    "git_api",
+    "kubectl",
     "community_hub",
     "memory_maintenance",
     "toggle_manage",
@@ -276,6 +306,14 @@ pub struct EvolutionServiceConfig {
     pub max_retries: u32,
     /// LLM 调用超时时间（秒）
     pub llm_timeout_secs: u64,
+    /// 草稿生成使用的模型名（仅用于记录；provider 通过 `set_llm_provider` 注入）
+    pub draft_model: Option<String>,
+    /// 审计/复核使用的强模型名（provider 通过 `set_audit_llm_provider` 注入）
+    pub audit_model: Option<String>,
+    /// 编译/契约检查累计失败多少次后，重新生成升级到强模型（0 = 不升级）
+    pub escalate_after_failures: u32,
+    /// Global maintenance calendar: background pipelines in `tick()` wait for a window.
+    pub maintenance: MaintenanceConfig,
 }
 
 impl Default for EvolutionServiceConfig {
@@ -286,6 +324,29 @@ impl Default for EvolutionServiceConfig {
             enabled: true,
             max_retries: 3,
             llm_timeout_secs: 300, // 5分钟
+            draft_model: None,
+            audit_model: None,
+            escalate_after_failures: 2,
+            maintenance: MaintenanceConfig::default(),
+        }
+    }
+}
+
+impl EvolutionServiceConfig {
+    /// 从 `agents.defaults` 读取进化模型路由配置
+    pub fn from_config(config: &blockcell_core::Config) -> Self {
+        let defaults = &config.agents.defaults;
+        Self {
+            draft_model: Some(
+                defaults
+                    .evolution_model
+                    .clone()
+                    .unwrap_or_else(|| defaults.model.clone()),
+            ),
+            audit_model: defaults.evolution_audit_model.clone(),
+            escalate_after_failures: defaults.evolution_escalate_after_failures,
+            maintenance: config.maintenance.clone(),
+            ..Self::default()
         }
     }
 }
@@ -308,6 +369,8 @@ pub struct EvolutionService {
     config: EvolutionServiceConfig,
     /// 可选的 LLM provider，设置后 tick() 会自动驱动完整进化 pipeline
     llm_provider: Option<Arc<dyn LLMProvider>>,
+    /// 可选的强模型 provider：负责审计，以及测试多次失败后的重新生成
+    audit_llm_provider: Option<Arc<dyn LLMProvider>>,
 }
 
 impl EvolutionService {
@@ -584,6 +647,7 @@ impl EvolutionService {
             pipeline_locks: Arc::new(Mutex::new(HashSet::new())),
             config,
             llm_provider: None,
+            audit_llm_provider: None,
         }
     }
 
@@ -593,6 +657,82 @@ impl EvolutionService {
         self.llm_provider = Some(provider);
     }
 
+    /// 设置审计/复核用的强模型 provider。未设置时所有阶段都使用草稿 provider。
+    pub fn set_audit_llm_provider(&mut self, provider: Arc<dyn LLMProvider>) {
+        self.audit_llm_provider = Some(provider);
+    }
+
+    fn is_test_stage(stage: &str) -> bool {
+        matches!(stage, "compile" | "contract")
+    }
+
+    /// 为一个阶段选择模型并写入记录。`escalated` 仅对重新生成有意义。
+    fn route_model<'a>(
+        &'a self,
+        evolution_id: &str,
+        stage: &str,
+        attempt: u32,
+        wants_strong: bool,
+        escalated: bool,
+        draft: &'a dyn LLMProvider,
+    ) -> &'a dyn LLMProvider {
+        let (tier, provider, model) = match self.audit_llm_provider.as_deref() {
+            Some(strong) if wants_strong => {
+                (ModelTier::Strong, strong, self.config.audit_model.clone())
+            }
+            _ => (ModelTier::Draft, draft, self.config.draft_model.clone()),
+        };
+        let entry = ModelRouteEntry {
+            stage: stage.to_string(),
+            tier,
+            model,
+            attempt,
+            escalated: escalated && tier == ModelTier::Strong,
+            timestamp: chrono::Utc::now().timestamp(),
+        };
+        if entry.escalated {
+            info!(
+                evolution_id = %evolution_id,
+                model = ?entry.model,
+                "🧠 [pipeline] Tests failed repeatedly, escalating regeneration to the strong model"
+            );
+        }
+        if let Err(e) = self.evolution.record_model_route(evolution_id, entry) {
+            warn!(evolution_id = %evolution_id, error = %e, "Failed to record model route");
+        }
+        provider
+    }
+
+    /// 带模型路由的重新生成：测试（编译/契约检查）累计失败达到阈值后改用强模型
+    async fn regenerate_routed(
+        &self,
+        evolution_id: &str,
+        record: &EvolutionRecord,
+        draft: &dyn LLMProvider,
+        feedback: &FeedbackEntry,
+    ) -> Result<()> {
+        let test_failures = record
+            .feedback_history
+            .iter()
+            .filter(|f| Self::is_test_stage(&f.stage))
+            .count() as u32
+            + u32::from(Self::is_test_stage(&feedback.stage));
+        let threshold = self.config.escalate_after_failures;
+        let escalate = threshold > 0 && test_failures >= threshold;
+        let provider = self.route_model(
+            evolution_id,
+            "regenerate",
+            record.attempt + 1,
+            escalate,
+            escalate,
+            draft,
+        );
+        self.evolution
+            .regenerate_with_feedback(evolution_id, provider, feedback)
+            .await?;
+        Ok(())
+    }
+
     /// 报告技能执行错误
     ///
     /// 每次调用都会返回 ErrorReport，包含：
@@ -827,9 +967,17 @@ impl EvolutionService {
         // ═══════════════════════════════════════════════════════════
         if record.status == EvolutionStatus::Triggered {
             info!(evolution_id = %evolution_id, "🧠 [pipeline] ═══ Step 1: Generating initial patch ═══");
+            let provider = self.route_model(
+                evolution_id,
+                "generate",
+                record.attempt,
+                false,
+                false,
+                llm_provider,
+            );
             let patch = self
                 .evolution
-                .generate_patch(evolution_id, llm_provider)
+                .generate_patch(evolution_id, provider)
                 .await?;
             info!(
                 evolution_id = %evolution_id,
@@ -890,8 +1038,7 @@ impl EvolutionService {
                             timestamp: chrono::Utc::now().timestamp(),
                         };
 
-                        self.evolution
-                            .regenerate_with_feedback(evolution_id, llm_provider, &feedback)
+                        self.regenerate_routed(evolution_id, &record, llm_provider, &feedback)
                             .await?;
                         continue;
                     }
@@ -915,10 +1062,15 @@ impl EvolutionService {
             let record = self.evolution.load_record(evolution_id)?;
             if record.status == EvolutionStatus::Generated {
                 info!(evolution_id = %evolution_id, "🧠 [pipeline] ═══ Auditing patch (attempt {}) ═══", attempt);
-                let audit = self
-                    .evolution
-                    .audit_patch(evolution_id, llm_provider)
-                    .await?;
+                let provider = self.route_model(
+                    evolution_id,
+                    "audit",
+                    record.attempt,
+                    true,
+                    false,
+                    llm_provider,
+                );
+                let audit = self.evolution.audit_patch(evolution_id, provider).await?;
 
                 if !audit.passed {
                     let issues_text = audit
@@ -953,8 +1105,7 @@ impl EvolutionService {
                         timestamp: chrono::Utc::now().timestamp(),
                     };
 
-                    self.evolution
-                        .regenerate_with_feedback(evolution_id, llm_provider, &feedback)
+                    self.regenerate_routed(evolution_id, &record, llm_provider, &feedback)
                         .await?;
                     continue;
                 }
@@ -990,8 +1141,7 @@ impl EvolutionService {
                         timestamp: chrono::Utc::now().timestamp(),
                     };
 
-                    self.evolution
-                        .regenerate_with_feedback(evolution_id, llm_provider, &feedback)
+                    self.regenerate_routed(evolution_id, &record, llm_provider, &feedback)
                         .await?;
                     continue;
                 }
@@ -1028,8 +1178,7 @@ impl EvolutionService {
                         timestamp: chrono::Utc::now().timestamp(),
                     };
 
-                    self.evolution
-                        .regenerate_with_feedback(evolution_id, llm_provider, &feedback)
+                    self.regenerate_routed(evolution_id, &record, llm_provider, &feedback)
                         .await?;
                     continue;
                 }
@@ -1081,7 +1230,12 @@ impl EvolutionService {
             "🧠 [自进化] tick() 开始 (LLM provider: {})",
             if has_llm { "已配置" } else { "未配置" }
         );
-        let pending = self.list_pending_ids().await;
+        let pending = if self.config.maintenance.defers_heavy_work() {
+            debug!("🧠 [自进化] 不在维护窗口内，待处理的进化推迟执行");
+            Vec::new()
+        } else {
+            self.list_pending_ids().await
+        };
         if !pending.is_empty() {
             info!(
                 count = pending.len(),
@@ -1697,6 +1851,67 @@ impl EvolutionService {
         &self,
         skill_name: &str,
         description: &str,
+    ) -> Result<String> {
+        let evolution_id = self
+            .trigger_with_reason(
+                skill_name,
+                TriggerReason::ManualRequest {
+                    description: description.to_string(),
+                },
+            )
+            .await?;
+
+        info!(
+            skill = %skill_name,
+            evolution_id = %evolution_id,
+            "🧠 [自进化] 用户手动触发技能 `{}` 的进化: {}",
+            skill_name, description
+        );
+
+        Ok(evolution_id)
+    }
+
+    /// 将表现长期不佳的技能作为改进候选加入进化队列（来自技能性能统计）。
+    ///
+    /// `metric` 描述不达标的指标（如 "success_rate=0.42"），`threshold` 为对应阈值。
+    pub async fn queue_improvement_candidate(
+        &self,
+        skill_name: &str,
+        metric: &str,
+        threshold: f64,
+    ) -> Result<String> {
+        if is_builtin_tool(skill_name) {
+            return Err(Error::Evolution(format!(
+                "`{}` 是内置工具，不参与技能进化",
+                skill_name
+            )));
+        }
+        let evolution_id = self
+            .trigger_with_reason(
+                skill_name,
+                TriggerReason::PerformanceDegradation {
+                    metric: metric.to_string(),
+                    threshold,
+                },
+            )
+            .await?;
+
+        info!(
+            skill = %skill_name,
+            evolution_id = %evolution_id,
+            metric = %metric,
+            "🧠 [自进化] 技能 `{}` 表现不佳 ({})，已加入改进队列",
+            skill_name, metric
+        );
+
+        Ok(evolution_id)
+    }
+
+    /// 不经过 ErrorTracker，直接以给定触发原因创建进化记录。
+    async fn trigger_with_reason(
+        &self,
+        skill_name: &str,
+        trigger: TriggerReason,
     ) -> Result<String> {
         // 以磁盘记录为准检查进行中的进化，避免多实例内存状态不一致。
         if let Some(existing_id) = self.resolve_in_progress_evolution_id(skill_name).await {
@@ -1719,9 +1934,7 @@ impl EvolutionService {
         let context = EvolutionContext {
             skill_name: skill_name.to_string(),
             current_version,
-            trigger: TriggerReason::ManualRequest {
-                description: description.to_string(),
-            },
+            trigger,
             error_stack: None,
             source_snippet,
             source_path,
@@ -1740,13 +1953,6 @@ impl EvolutionService {
             active.insert(skill_name.to_string(), evolution_id.clone());
         }
 
-        info!(
-            skill = %skill_name,
-            evolution_id = %evolution_id,
-            "🧠 [自进化] 用户手动触发技能 `{}` 的进化: {}",
-            skill_name, description
-        );
-
         Ok(evolution_id)
     }
 
@@ -1782,7 +1988,7 @@ impl EvolutionService {
         }
 
         // Sort by created_at descending
-        records.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        records.sort_by_key(|r| std::cmp::Reverse(r.created_at));
         Ok(records)
     }
 
@@ -2315,4 +2521,81 @@ mod tests {
 
         let _ = std::fs::remove_dir_all(root);
     }
+
+    struct RecordingLLM {
+        name: &'static str,
+        calls: Arc<std::sync::Mutex<Vec<&'static str>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl LLMProvider for RecordingLLM {
+        async fn generate(&self, _prompt: &str) -> Result<String> {
+            self.calls.lock().unwrap().push(self.name);
+            Ok(String::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_model_routing_escalates_after_repeated_test_failures() {
+        let (root, skills_dir) = setup_test_dirs("model_routing");
+        let mut service = EvolutionService::new(
+            skills_dir,
+            EvolutionServiceConfig {
+                draft_model: Some("cheap".to_string()),
+                audit_model: Some("strong".to_string()),
+                ..Default::default()
+            },
+        );
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let draft = RecordingLLM {
+            name: "draft",
+            calls: calls.clone(),
+        };
+        service.set_audit_llm_provider(Arc::new(RecordingLLM {
+            name: "strong",
+            calls: calls.clone(),
+        }));
+        let id = service
+            .evolution
+            .trigger_evolution(test_context("skill_route"))
+            .await
+            .expect("seed evolution record");
+
+        // Audits always go to the strong model.
+        let auditor = service.route_model(&id, "audit", 1, true, false, &draft);
+        let _ = auditor.generate("audit").await;
+
+        // First compile failure regenerates on the draft model, the second escalates.
+        for attempt in 1..=2 {
+            let record = service.evolution.load_record(&id).unwrap();
+            let feedback = FeedbackEntry {
+                attempt,
+                stage: "compile".to_string(),
+                feedback: "syntax error".to_string(),
+                previous_code: String::new(),
+                timestamp: 0,
+            };
+            let _ = service
+                .regenerate_routed(&id, &record, &draft, &feedback)
+                .await;
+        }
+        assert_eq!(*calls.lock().unwrap(), vec!["strong", "draft", "strong"]);
+
+        let record = service.evolution.load_record(&id).unwrap();
+        let routes: Vec<_> = record
+            .model_routing
+            .iter()
+            .map(|r| (r.stage.as_str(), r.tier, r.escalated, r.model.as_deref()))
+            .collect();
+        assert_eq!(
+            routes,
+            vec![
+                ("audit", ModelTier::Strong, false, Some("strong")),
+                ("regenerate", ModelTier::Draft, false, Some("cheap")),
+                ("regenerate", ModelTier::Strong, true, Some("strong")),
+            ]
+        );
+
+        let _ = std::fs::remove_dir_all(root);
+    }
 }
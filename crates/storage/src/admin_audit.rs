@@ -0,0 +1,300 @@
+//! Append-only, hash-chained log of admin actions taken through the gateway
+//! (`audit/admin.jsonl`).
+//!
+//! Every entry carries the hash of the previous one, so editing or deleting a line
+//! breaks the chain from that point on; [`AdminAuditLog::verify`] reports where.
+//! The gateway appends one entry per mutating admin request, `GET /v1/audit/admin`
+//! reads them back and `blockcell debug bundle` includes the tail of the file.
+
+use std::fs::OpenOptions;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use blockcell_core::{Error, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// `prev_hash` of the first entry.
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// What happened, as reported by the caller.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct AdminAction {
+    /// Who made the request (e.g. `api_token`, `anonymous`).
+    pub actor: String,
+    pub client_ip: Option<String>,
+    /// Dotted action name, e.g. `config.update`, `cron.delete`.
+    pub action: String,
+    pub method: String,
+    pub path: String,
+    /// HTTP status of the response.
+    pub status: u16,
+    /// Free-form context; must not contain secrets.
+    #[serde(default)]
+    pub details: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AdminAuditEntry {
+    pub seq: u64,
+    pub timestamp_ms: i64,
+    #[serde(flatten)]
+    pub action: AdminAction,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+impl AdminAuditEntry {
+    fn compute_hash(&self) -> String {
+        let body = serde_json::json!({
+            "seq": self.seq,
+            "timestamp_ms": self.timestamp_ms,
+            "action": self.action,
+            "prev_hash": self.prev_hash,
+        });
+        let mut hasher = Sha256::new();
+        hasher.update(body.to_string().as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+/// Result of [`AdminAuditLog::verify`].
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct AdminAuditVerification {
+    pub entries: u64,
+    pub valid: bool,
+    /// Line number (1-based) of the first entry that breaks the chain.
+    pub broken_at_line: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AdminAuditQuery {
+    pub since_ms: Option<i64>,
+    /// Keep entries whose action starts with this prefix (e.g. `cron.`).
+    pub action_prefix: Option<String>,
+    pub actor: Option<String>,
+    pub limit: usize,
+}
+
+pub struct AdminAuditLog {
+    path: PathBuf,
+    /// `(seq, hash)` of the last entry, loaded from the file on first append.
+    tail: Mutex<Option<(u64, String)>>,
+}
+
+impl AdminAuditLog {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            tail: Mutex::new(None),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&self, action: AdminAction) -> Result<AdminAuditEntry> {
+        let mut tail = self
+            .tail
+            .lock()
+            .map_err(|e| Error::Storage(format!("Lock error: {}", e)))?;
+        let (last_seq, prev_hash) = match tail.take() {
+            Some(tail) => tail,
+            None => self.read_tail()?,
+        };
+
+        let mut entry = AdminAuditEntry {
+            seq: last_seq + 1,
+            timestamp_ms: Utc::now().timestamp_millis(),
+            action,
+            prev_hash,
+            hash: String::new(),
+        };
+        entry.hash = entry.compute_hash();
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+
+        *tail = Some((entry.seq, entry.hash.clone()));
+        Ok(entry)
+    }
+
+    /// Matching entries, newest first.
+    pub fn query(&self, query: &AdminAuditQuery) -> Result<Vec<AdminAuditEntry>> {
+        let mut entries: Vec<AdminAuditEntry> = self
+            .read_all()?
+            .into_iter()
+            .filter_map(|(_, entry)| entry)
+            .filter(|e| query.since_ms.is_none_or(|since| e.timestamp_ms >= since))
+            .filter(|e| {
+                query
+                    .action_prefix
+                    .as_deref()
+                    .is_none_or(|prefix| e.action.action.starts_with(prefix))
+            })
+            .filter(|e| {
+                query
+                    .actor
+                    .as_deref()
+                    .is_none_or(|actor| e.action.actor == actor)
+            })
+            .collect();
+        entries.reverse();
+        entries.truncate(query.limit);
+        Ok(entries)
+    }
+
+    /// Re-hash every entry and check that each links to the one before it.
+    pub fn verify(&self) -> Result<AdminAuditVerification> {
+        let lines = self.read_all()?;
+        let mut prev_hash = GENESIS_HASH.to_string();
+        let mut prev_seq = 0;
+        for (line_no, entry) in &lines {
+            let intact = entry.as_ref().is_some_and(|e| {
+                e.seq == prev_seq + 1 && e.prev_hash == prev_hash && e.hash == e.compute_hash()
+            });
+            match entry {
+                Some(e) if intact => {
+                    prev_hash = e.hash.clone();
+                    prev_seq = e.seq;
+                }
+                _ => {
+                    return Ok(AdminAuditVerification {
+                        entries: lines.len() as u64,
+                        valid: false,
+                        broken_at_line: Some(*line_no),
+                    })
+                }
+            }
+        }
+        Ok(AdminAuditVerification {
+            entries: lines.len() as u64,
+            valid: true,
+            broken_at_line: None,
+        })
+    }
+
+    /// Every non-empty line with its 1-based number; `None` for lines that don't parse.
+    fn read_all(&self) -> Result<Vec<(u64, Option<AdminAuditEntry>)>> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| (i as u64 + 1, serde_json::from_str(line).ok()))
+            .collect())
+    }
+
+    /// `(seq, hash)` of the last line, reading only the end of the file.
+    fn read_tail(&self) -> Result<(u64, String)> {
+        let mut file = match std::fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok((0, GENESIS_HASH.to_string()))
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let len = file.metadata()?.len();
+        let mut window = 4096u64;
+        loop {
+            let start = len.saturating_sub(window);
+            file.seek(SeekFrom::Start(start))?;
+            let mut buf = Vec::new();
+            file.read_to_end(&mut buf)?;
+            let mut lines = buf
+                .split(|b| *b == b'\n')
+                .filter(|l| !l.iter().all(u8::is_ascii_whitespace))
+                .rev();
+            if let Some(last) = lines.next() {
+                // A line cut by the window start is only trusted if it is whole.
+                if start == 0 || lines.next().is_some() {
+                    let entry: AdminAuditEntry = serde_json::from_slice(last).map_err(|e| {
+                        Error::Storage(format!("admin audit log has a corrupt last entry: {}", e))
+                    })?;
+                    return Ok((entry.seq, entry.hash));
+                }
+            } else if start == 0 {
+                return Ok((0, GENESIS_HASH.to_string()));
+            }
+            window *= 4;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn action(name: &str) -> AdminAction {
+        AdminAction {
+            actor: "api_token".to_string(),
+            client_ip: Some("10.0.0.5".to_string()),
+            action: name.to_string(),
+            method: "PUT".to_string(),
+            path: "/v1/config".to_string(),
+            status: 200,
+            details: serde_json::json!({ "keys": ["gateway"] }),
+        }
+    }
+
+    #[test]
+    fn test_entries_chain_and_survive_reopen() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("audit").join("admin.jsonl");
+        let log = AdminAuditLog::new(path.clone());
+        let first = log.append(action("config.update")).unwrap();
+        assert_eq!(first.seq, 1);
+        assert_eq!(first.prev_hash, GENESIS_HASH);
+
+        let reopened = AdminAuditLog::new(path);
+        let second = reopened.append(action("cron.delete")).unwrap();
+        assert_eq!(second.seq, 2);
+        assert_eq!(second.prev_hash, first.hash);
+        reopened.append(action("cron.create")).unwrap();
+
+        let cron = reopened
+            .query(&AdminAuditQuery {
+                action_prefix: Some("cron.".to_string()),
+                limit: 10,
+                ..Default::default()
+            })
+            .unwrap();
+        let names: Vec<&str> = cron.iter().map(|e| e.action.action.as_str()).collect();
+        assert_eq!(names, vec!["cron.create", "cron.delete"]);
+        assert!(reopened.verify().unwrap().valid);
+    }
+
+    #[test]
+    fn test_verify_detects_edited_and_removed_lines() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("admin.jsonl");
+        let log = AdminAuditLog::new(path.clone());
+        for name in ["toggles.update", "alerts.create", "evolution.trigger"] {
+            log.append(action(name)).unwrap();
+        }
+
+        let original = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, original.replace("alerts.create", "alerts.delete")).unwrap();
+        let report = log.verify().unwrap();
+        assert!(!report.valid);
+        assert_eq!(report.broken_at_line, Some(2));
+
+        let without_first: Vec<&str> = original.lines().skip(1).collect();
+        std::fs::write(&path, without_first.join("\n")).unwrap();
+        assert_eq!(log.verify().unwrap().broken_at_line, Some(1));
+    }
+}
@@ -0,0 +1,236 @@
+//! At-rest encryption for sessions and the memory database (`storage.encryption`).
+//!
+//! The binary unlocks a 32-byte key at start (OS keyring or passphrase) and installs it
+//! here once per process. Sessions stay line-oriented: each stored message and metadata
+//! value is sealed on its own (`enc1:<base64 nonce+ciphertext>`), so appends keep working
+//! and plaintext values written before encryption was enabled are still readable. The memory database
+//! is opened through SQLCipher with the same key.
+
+use std::borrow::Cow;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use base64::engine::general_purpose::STANDARD as B64;
+use base64::Engine;
+use blockcell_core::{Error, Paths, Result};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use rusqlite::Connection;
+use tracing::info;
+
+pub const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 24;
+pub(crate) const LINE_PREFIX: &str = "enc1:";
+/// Plaintext sealed into the key check so a wrong passphrase is caught at start.
+const KEY_CHECK_PLAINTEXT: &str = "blockcell-storage-key";
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+
+static KEY: OnceLock<[u8; KEY_LEN]> = OnceLock::new();
+
+/// Install the process-wide key. Returns false if a different key is already installed.
+pub fn install_key(key: [u8; KEY_LEN]) -> bool {
+    *KEY.get_or_init(|| key) == key
+}
+
+pub fn is_unlocked() -> bool {
+    KEY.get().is_some()
+}
+
+pub fn generate_key() -> [u8; KEY_LEN] {
+    let mut key = [0u8; KEY_LEN];
+    OsRng.fill_bytes(&mut key);
+    key
+}
+
+pub fn generate_salt() -> [u8; 16] {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    salt
+}
+
+/// Derive the key from a passphrase with Argon2id.
+pub fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; KEY_LEN]> {
+    let mut key = [0u8; KEY_LEN];
+    argon2::Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| Error::Storage(format!("Key derivation failed: {}", e)))?;
+    Ok(key)
+}
+
+fn seal_with(key: &[u8; KEY_LEN], plaintext: &str) -> String {
+    let cipher = XChaCha20Poly1305::new(key.into());
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    // Encryption only fails for inputs beyond the AEAD size limit.
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_bytes())
+        .unwrap_or_default();
+    let mut blob = nonce.to_vec();
+    blob.extend_from_slice(&ciphertext);
+    format!("{}{}", LINE_PREFIX, B64.encode(blob))
+}
+
+fn open_with(key: &[u8; KEY_LEN], sealed: &str) -> Option<String> {
+    let blob = B64.decode(sealed.strip_prefix(LINE_PREFIX)?.trim()).ok()?;
+    if blob.len() < NONCE_LEN {
+        return None;
+    }
+    let (nonce, ciphertext) = blob.split_at(NONCE_LEN);
+    let plaintext = XChaCha20Poly1305::new(key.into())
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .ok()?;
+    String::from_utf8(plaintext).ok()
+}
+
+/// A value stored next to the key material to verify the key on the next start.
+pub fn key_check(key: &[u8; KEY_LEN]) -> String {
+    seal_with(key, KEY_CHECK_PLAINTEXT)
+}
+
+pub fn verify_key_check(key: &[u8; KEY_LEN], check: &str) -> bool {
+    open_with(key, check).as_deref() == Some(KEY_CHECK_PLAINTEXT)
+}
+
+pub fn is_sealed(line: &str) -> bool {
+    line.starts_with(LINE_PREFIX)
+}
+
+/// Seal one session line when a key is installed; otherwise return it unchanged.
+pub fn seal_line(line: &str) -> Cow<'_, str> {
+    match KEY.get() {
+        Some(key) => Cow::Owned(seal_with(key, line)),
+        None => Cow::Borrowed(line),
+    }
+}
+
+/// The plaintext of a session line. `None` for a sealed line that cannot be opened
+/// (storage locked or wrong key).
+pub fn open_line(line: &str) -> Option<Cow<'_, str>> {
+    if !is_sealed(line) {
+        return Some(Cow::Borrowed(line));
+    }
+    open_with(KEY.get()?, line).map(Cow::Owned)
+}
+
+/// Seal the plaintext messages and metadata of every session. Returns the number of
+/// sessions changed.
+pub fn encrypt_session_files(paths: &Paths) -> Result<usize> {
+    crate::SessionStore::new(paths.clone()).seal_plaintext()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn key_pragma(key: &[u8; KEY_LEN]) -> String {
+    format!("\"x'{}'\"", hex(key))
+}
+
+fn is_plaintext_db(path: &Path) -> bool {
+    use std::io::Read;
+    let mut header = [0u8; 16];
+    std::fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .is_ok_and(|_| header == SQLITE_HEADER)
+}
+
+fn sqlite_err(e: rusqlite::Error) -> Error {
+    Error::Storage(format!("SQLite error: {}", e))
+}
+
+/// Rewrite a plaintext database as an SQLCipher database keyed with `key`.
+fn encrypt_plaintext_db(path: &Path, key: &[u8; KEY_LEN]) -> Result<()> {
+    let tmp = path.with_extension("db.encrypting");
+    let _ = std::fs::remove_file(&tmp);
+    {
+        let conn = Connection::open(path).map_err(sqlite_err)?;
+        conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);")
+            .map_err(sqlite_err)?;
+        conn.execute(
+            &format!("ATTACH DATABASE ?1 AS encrypted KEY {}", key_pragma(key)),
+            [tmp.to_string_lossy()],
+        )
+        .map_err(sqlite_err)?;
+        conn.query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(()))
+            .map_err(sqlite_err)?;
+        conn.execute_batch("DETACH DATABASE encrypted;")
+            .map_err(sqlite_err)?;
+    }
+    std::fs::rename(&tmp, path)?;
+    for suffix in ["-wal", "-shm"] {
+        let mut side = path.as_os_str().to_owned();
+        side.push(suffix);
+        let _ = std::fs::remove_file(side);
+    }
+    info!(path = %path.display(), "Encrypted existing database");
+    Ok(())
+}
+
+/// Open a SQLite database, keyed with the storage key when one is installed. A plaintext
+/// database is converted first.
+pub fn open_database(path: &Path) -> Result<Connection> {
+    let Some(key) = KEY.get() else {
+        if path.exists() && !is_plaintext_db(path) && std::fs::metadata(path)?.len() > 0 {
+            return Err(Error::Storage(format!(
+                "{} is encrypted; enable storage.encryption to open it",
+                path.display()
+            )));
+        }
+        return Connection::open(path).map_err(sqlite_err);
+    };
+
+    let probe = Connection::open_in_memory().map_err(sqlite_err)?;
+    let has_cipher = probe
+        .query_row("PRAGMA cipher_version", [], |r| r.get::<_, String>(0))
+        .is_ok();
+    if !has_cipher {
+        return Err(Error::Storage(
+            "storage.encryption needs SQLCipher; rebuild blockcell with `--features sqlcipher`"
+                .to_string(),
+        ));
+    }
+
+    if is_plaintext_db(path) {
+        encrypt_plaintext_db(path, key)?;
+    }
+    let conn = Connection::open(path).map_err(sqlite_err)?;
+    conn.execute_batch(&format!("PRAGMA key = {};", key_pragma(key)))
+        .map_err(sqlite_err)?;
+    // Fails here, not on first use, when the key does not match.
+    conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))
+        .map_err(|e| Error::Storage(format!("Cannot open {}: {}", path.display(), e)))?;
+    Ok(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_and_open_round_trip() {
+        let key = generate_key();
+        let sealed = seal_with(&key, r#"{"role":"user","content":"hi"}"#);
+        assert!(is_sealed(&sealed));
+        assert!(!sealed.contains("content"));
+        assert_eq!(
+            open_with(&key, &sealed).as_deref(),
+            Some(r#"{"role":"user","content":"hi"}"#)
+        );
+        assert!(open_with(&generate_key(), &sealed).is_none());
+
+        let check = key_check(&key);
+        assert!(verify_key_check(&key, &check));
+        assert!(!verify_key_check(&generate_key(), &check));
+    }
+
+    #[test]
+    fn test_derive_key_and_plaintext_lines() {
+        let salt = generate_salt();
+        let a = derive_key("correct horse", &salt).unwrap();
+        assert_eq!(a, derive_key("correct horse", &salt).unwrap());
+        assert_ne!(a, derive_key("battery staple", &salt).unwrap());
+
+        // Lines written before encryption was enabled pass through unchanged.
+        assert_eq!(open_line("{\"a\":1}").as_deref(), Some("{\"a\":1}"));
+    }
+}
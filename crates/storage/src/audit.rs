@@ -4,8 +4,11 @@ use serde::{Deserialize, Serialize};
 use std::fs::OpenOptions;
 use std::io::Write;
 use std::path::PathBuf;
+use std::sync::Arc;
 use tracing::error;
 
+use crate::redaction::{Redactor, NS_AUDIT};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum AuditEvent {
@@ -39,6 +42,7 @@ pub enum AuditEvent {
 pub struct AuditLogger {
     paths: Paths,
     current_date: String,
+    redactor: Option<Arc<Redactor>>,
 }
 
 impl AuditLogger {
@@ -46,9 +50,16 @@ impl AuditLogger {
         Self {
             paths,
             current_date: Utc::now().format("%Y-%m-%d").to_string(),
+            redactor: None,
         }
     }
 
+    /// Mask PII in tool params/results before they are written.
+    pub fn with_redactor(mut self, redactor: Arc<Redactor>) -> Self {
+        self.redactor = Some(redactor);
+        self
+    }
+
     pub fn log_tool_call(
         &mut self,
         tool_name: &str,
@@ -58,6 +69,11 @@ impl AuditLogger {
         trace_id: Option<String>,
         duration_ms: Option<u64>,
     ) -> Result<()> {
+        let (mut params, mut result) = (params, result);
+        if let Some(redactor) = self.redactor.as_deref() {
+            redactor.redact_json(NS_AUDIT, &mut params);
+            redactor.redact_json(NS_AUDIT, &mut result);
+        }
         let event = AuditEvent::ToolCall {
             tool_name: tool_name.to_string(),
             params,
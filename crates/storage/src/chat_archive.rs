@@ -0,0 +1,370 @@
+//! Parsing of exported group/personal chat archives (Telegram JSON, WhatsApp txt) for
+//! memory ingestion.
+//!
+//! The archive is cut into transcript batches; the caller sends each batch through the
+//! LLM with [`extraction_prompt`], and [`parse_extracted`] turns the reply into memory
+//! items that record which chat and date range they came from.
+
+use crate::memory::UpsertParams;
+use blockcell_core::{Error, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// `result.json` from Telegram Desktop's "Export chat history" (one chat or all chats).
+    Telegram,
+    /// "Export chat" text file from WhatsApp (Android or iOS layout).
+    WhatsApp,
+}
+
+impl ArchiveFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ArchiveFormat::Telegram => "telegram",
+            ArchiveFormat::WhatsApp => "whatsapp",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ArchiveFormat::Telegram => "Telegram",
+            ArchiveFormat::WhatsApp => "WhatsApp",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "telegram" | "tg" => Some(ArchiveFormat::Telegram),
+            "whatsapp" | "wa" => Some(ArchiveFormat::WhatsApp),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveMessage {
+    /// Timestamp as written in the export (WhatsApp date order depends on locale).
+    pub timestamp: String,
+    pub sender: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatArchive {
+    pub format: ArchiveFormat,
+    pub chat_name: String,
+    pub messages: Vec<ArchiveMessage>,
+}
+
+fn telegram_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .map(|p| match p {
+                Value::String(s) => s.clone(),
+                other => other["text"].as_str().unwrap_or_default().to_string(),
+            })
+            .collect(),
+        _ => String::new(),
+    }
+}
+
+fn telegram_chat(chat: &Value) -> Option<ChatArchive> {
+    let list = chat.get("messages")?.as_array()?;
+    let messages: Vec<ArchiveMessage> = list
+        .iter()
+        .filter(|m| m["type"].as_str() == Some("message"))
+        .filter_map(|m| {
+            let text = telegram_text(&m["text"]);
+            let sender = m["from"].as_str()?;
+            (!text.trim().is_empty()).then(|| ArchiveMessage {
+                timestamp: m["date"].as_str().unwrap_or_default().replace('T', " "),
+                sender: sender.to_string(),
+                text: text.trim().to_string(),
+            })
+        })
+        .collect();
+    (!messages.is_empty()).then(|| ChatArchive {
+        format: ArchiveFormat::Telegram,
+        chat_name: chat["name"].as_str().unwrap_or("Telegram chat").to_string(),
+        messages,
+    })
+}
+
+/// Parse a Telegram export: a single chat, or a full export with `chats.list`.
+pub fn parse_telegram(json: &Value) -> Result<Vec<ChatArchive>> {
+    if let Some(list) = json.pointer("/chats/list").and_then(|l| l.as_array()) {
+        return Ok(list.iter().filter_map(telegram_chat).collect());
+    }
+    if json.get("messages").is_some() {
+        return Ok(telegram_chat(json).into_iter().collect());
+    }
+    Err(Error::Validation(
+        "Not a Telegram export: expected `messages` or `chats.list`".to_string(),
+    ))
+}
+
+/// `12/31/23, 10:15 PM - Alice: hi` (Android) or `[31/12/2023, 22:15:03] Alice: hi` (iOS).
+static WHATSAPP_LINE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^\u{200e}?\[?(\d{1,4}[./-]\d{1,2}[./-]\d{1,4}),? (\d{1,2}:\d{2}(?::\d{2})?(?:\s?[AaPp]\.?\s?[Mm]\.?)?)\]?(?:\s-)?\s(.*)$",
+    )
+    .expect("valid regex")
+});
+
+/// Parse a WhatsApp text export. Lines that don't start with a timestamp continue the
+/// previous message; system notices (no `sender:`) and media placeholders are dropped.
+pub fn parse_whatsapp(text: &str, chat_name: &str) -> ChatArchive {
+    let mut messages: Vec<ArchiveMessage> = Vec::new();
+    let mut in_system_line = false;
+    for line in text.lines() {
+        let line = line.trim_end_matches('\r');
+        let Some(caps) = WHATSAPP_LINE.captures(line) else {
+            if !in_system_line {
+                if let Some(last) = messages.last_mut() {
+                    last.text.push('\n');
+                    last.text.push_str(line);
+                }
+            }
+            continue;
+        };
+        let rest = caps[3].trim_start_matches('\u{200e}');
+        let Some((sender, body)) = rest.split_once(": ") else {
+            in_system_line = true;
+            continue;
+        };
+        in_system_line = false;
+        let body = body.trim_start_matches('\u{200e}').trim();
+        let placeholder = (body.starts_with('<') && body.ends_with('>'))
+            || (body.ends_with(" omitted") && body.len() < 32);
+        if body.is_empty() || placeholder {
+            // `<Media omitted>`, `<attached: ...>`, `image omitted` etc.
+            in_system_line = true;
+            continue;
+        }
+        messages.push(ArchiveMessage {
+            timestamp: format!("{} {}", &caps[1], &caps[2]),
+            sender: sender.to_string(),
+            text: body.to_string(),
+        });
+    }
+    ChatArchive {
+        format: ArchiveFormat::WhatsApp,
+        chat_name: chat_name.to_string(),
+        messages,
+    }
+}
+
+/// Transcript batch sent to the LLM in one request.
+#[derive(Debug, Clone)]
+pub struct TranscriptBatch {
+    pub first_timestamp: String,
+    pub last_timestamp: String,
+    pub transcript: String,
+}
+
+/// Split the archive into transcripts of at most `max_chars` (a single longer message
+/// still gets its own batch).
+pub fn batches(archive: &ChatArchive, max_chars: usize) -> Vec<TranscriptBatch> {
+    let mut out: Vec<TranscriptBatch> = Vec::new();
+    let mut current: Option<TranscriptBatch> = None;
+    for msg in &archive.messages {
+        let line = format!("[{}] {}: {}\n", msg.timestamp, msg.sender, msg.text);
+        if let Some(batch) = current.as_mut() {
+            if batch.transcript.len() + line.len() <= max_chars {
+                batch.transcript.push_str(&line);
+                batch.last_timestamp = msg.timestamp.clone();
+                continue;
+            }
+        }
+        if let Some(done) = current.take() {
+            out.push(done);
+        }
+        current = Some(TranscriptBatch {
+            first_timestamp: msg.timestamp.clone(),
+            last_timestamp: msg.timestamp.clone(),
+            transcript: line,
+        });
+    }
+    out.extend(current);
+    out
+}
+
+pub const EXTRACTION_SYSTEM_PROMPT: &str = "You extract durable knowledge from chat transcripts for a personal assistant's long-term memory. Only keep stable facts and preferences: who people are, relationships, roles, places, recurring plans and routines, likes and dislikes, decisions that still hold. Skip small talk, one-off logistics, jokes, and anything that is a secret, password or financial account number. Reply with a JSON array only: [{\"type\": \"fact\" | \"preference\", \"about\": \"<person or group>\", \"content\": \"<one self-contained sentence>\", \"importance\": <0.0-1.0>}]. Reply with [] when there is nothing worth keeping.";
+
+/// User message for one batch.
+pub fn extraction_prompt(archive: &ChatArchive, batch: &TranscriptBatch) -> String {
+    format!(
+        "{} chat \"{}\", messages {} to {}:\n\n{}",
+        archive.format.label(),
+        archive.chat_name,
+        batch.first_timestamp,
+        batch.last_timestamp,
+        batch.transcript
+    )
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct ExtractedItem {
+    #[serde(rename = "type", default)]
+    pub kind: String,
+    #[serde(default)]
+    pub about: Option<String>,
+    pub content: String,
+    #[serde(default)]
+    pub importance: Option<f64>,
+}
+
+/// Items from an LLM reply; tolerates code fences and text around the JSON array.
+pub fn parse_extracted(reply: &str) -> Vec<ExtractedItem> {
+    let (Some(start), Some(end)) = (reply.find('['), reply.rfind(']')) else {
+        return Vec::new();
+    };
+    if end < start {
+        return Vec::new();
+    }
+    let items: Vec<Value> = serde_json::from_str(&reply[start..=end]).unwrap_or_default();
+    items
+        .into_iter()
+        .filter_map(|v| serde_json::from_value::<ExtractedItem>(v).ok())
+        .filter(|i| !i.content.trim().is_empty())
+        .collect()
+}
+
+/// Memory item for an extracted fact, with the chat and date range as provenance.
+pub fn memory_params(
+    archive: &ChatArchive,
+    batch: &TranscriptBatch,
+    item: &ExtractedItem,
+) -> UpsertParams {
+    let item_type = if item.kind.eq_ignore_ascii_case("preference") {
+        "preference"
+    } else {
+        "fact"
+    };
+    let content = item.content.trim().to_string();
+    let digest = Sha256::digest(content.to_lowercase().as_bytes());
+    let dedup: String = digest
+        .iter()
+        .take(8)
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    let chat_tag = archive
+        .chat_name
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-");
+    UpsertParams {
+        scope: "long_term".to_string(),
+        item_type: item_type.to_string(),
+        title: item.about.clone().filter(|a| !a.trim().is_empty()),
+        content,
+        summary: Some(format!(
+            "From {} chat \"{}\", {} – {}",
+            archive.format.label(),
+            archive.chat_name,
+            batch.first_timestamp,
+            batch.last_timestamp
+        )),
+        tags: vec![
+            "chat_archive".to_string(),
+            archive.format.name().to_string(),
+            chat_tag,
+        ],
+        source: "chat_archive".to_string(),
+        channel: Some(archive.format.name().to_string()),
+        session_key: None,
+        importance: item.importance.unwrap_or(0.5).clamp(0.1, 0.9),
+        dedup_key: Some(format!("chat_archive:{}", dedup)),
+        expires_at: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_telegram_export() {
+        let export = json!({
+            "name": "Family",
+            "type": "private_group",
+            "messages": [
+                {"id": 1, "type": "service", "date": "2024-01-01T09:00:00", "actor": "Mom", "action": "create_group"},
+                {"id": 2, "type": "message", "date": "2024-01-01T09:01:00", "from": "Mom", "text": "Dinner on Sundays at 6 as usual"},
+                {"id": 3, "type": "message", "date": "2024-01-01T09:02:00", "from": "Ben", "text": ["I'm ", {"type": "bold", "text": "vegetarian"}, " now"]},
+                {"id": 4, "type": "message", "date": "2024-01-01T09:03:00", "from": "Ben", "text": ""}
+            ]
+        });
+        let chats = parse_telegram(&export).unwrap();
+        assert_eq!(chats.len(), 1);
+        assert_eq!(chats[0].chat_name, "Family");
+        assert_eq!(chats[0].messages.len(), 2);
+        assert_eq!(chats[0].messages[1].text, "I'm vegetarian now");
+        assert_eq!(chats[0].messages[0].timestamp, "2024-01-01 09:01:00");
+
+        let full = json!({"chats": {"list": [export]}});
+        assert_eq!(parse_telegram(&full).unwrap().len(), 1);
+        assert!(parse_telegram(&json!({"foo": 1})).is_err());
+    }
+
+    #[test]
+    fn test_parse_whatsapp_layouts() {
+        let android = "12/31/23, 10:15 PM - Messages and calls are end-to-end encrypted.\n\
+12/31/23, 10:16 PM - Alice: Our book club meets\n\
+on the first Monday\n\
+12/31/23, 10:17 PM - Bob: <Media omitted>\n\
+1/1/24, 9:00 AM - Bob: Happy new year";
+        let chat = parse_whatsapp(android, "Book club");
+        assert_eq!(chat.messages.len(), 2);
+        assert_eq!(
+            chat.messages[0].text,
+            "Our book club meets\non the first Monday"
+        );
+        assert_eq!(chat.messages[1].sender, "Bob");
+
+        let ios = "[31/12/2023, 22:15:03] Alice: See you at the climbing gym\n\
+[31/12/2023, 22:16:10] Bob: \u{200e}image omitted\n\
+[31/12/2023, 22:17:00] Bob: Tuesdays work best for me";
+        let chat = parse_whatsapp(ios, "Climbing");
+        assert_eq!(chat.messages.len(), 2);
+        assert_eq!(chat.messages[1].text, "Tuesdays work best for me");
+        assert_eq!(chat.messages[0].timestamp, "31/12/2023 22:15:03");
+    }
+
+    #[test]
+    fn test_batches_and_extraction() {
+        let archive = ChatArchive {
+            format: ArchiveFormat::WhatsApp,
+            chat_name: "Book Club".to_string(),
+            messages: (0..10)
+                .map(|i| ArchiveMessage {
+                    timestamp: format!("t{}", i),
+                    sender: "Alice".to_string(),
+                    text: "x".repeat(30),
+                })
+                .collect(),
+        };
+        let batches = batches(&archive, 100);
+        assert_eq!(batches.len(), 5);
+        assert_eq!(batches[0].first_timestamp, "t0");
+        assert_eq!(batches[0].last_timestamp, "t1");
+
+        let reply = "```json\n[{\"type\":\"preference\",\"about\":\"Alice\",\"content\":\"Alice prefers sci-fi\",\"importance\":0.7},{\"type\":\"fact\",\"content\":\"\"}]\n```";
+        let items = parse_extracted(reply);
+        assert_eq!(items.len(), 1);
+        let params = memory_params(&archive, &batches[0], &items[0]);
+        assert_eq!(params.item_type, "preference");
+        assert_eq!(params.title.as_deref(), Some("Alice"));
+        assert!(params.summary.unwrap().contains("Book Club"));
+        assert!(params.tags.contains(&"book-club".to_string()));
+        assert!(parse_extracted("nothing here").is_empty());
+    }
+}
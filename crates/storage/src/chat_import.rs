@@ -0,0 +1,499 @@
+//! Importers for chat history exported from other assistants.
+//!
+//! Each format is parsed into [`ImportedConversation`]s, which are written as ordinary
+//! sessions under the `import` channel (`import:<source>-<id>`) so they show up in the
+//! session list and history search. Re-importing the same export overwrites the same
+//! sessions instead of duplicating them.
+
+use crate::memory::UpsertParams;
+use crate::SessionStore;
+use blockcell_core::types::ChatMessage;
+use blockcell_core::{build_session_key, Error, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Export format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportSource {
+    /// `conversations.json` from a ChatGPT data export.
+    ChatGpt,
+    /// `conversations.json` from a Claude data export.
+    Claude,
+    /// One JSON object per line: either `{role, content}` messages (grouped by an optional
+    /// `conversation_id`) or `{messages: [...]}` transcripts.
+    Jsonl,
+}
+
+impl ImportSource {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ImportSource::ChatGpt => "chatgpt",
+            ImportSource::Claude => "claude",
+            ImportSource::Jsonl => "jsonl",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ImportSource::ChatGpt => "ChatGPT",
+            ImportSource::Claude => "Claude",
+            ImportSource::Jsonl => "JSONL transcript",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "chatgpt" | "openai" => Some(ImportSource::ChatGpt),
+            "claude" | "anthropic" => Some(ImportSource::Claude),
+            "jsonl" => Some(ImportSource::Jsonl),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedMessage {
+    /// `user` or `assistant`.
+    pub role: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedConversation {
+    pub id: String,
+    pub title: Option<String>,
+    /// RFC 3339 creation time from the export, if present.
+    pub created_at: Option<String>,
+    pub messages: Vec<ImportedMessage>,
+}
+
+/// Tell ChatGPT and Claude `conversations.json` apart.
+pub fn detect(json: &Value) -> Option<ImportSource> {
+    let first = json.as_array()?.first()?;
+    if first.get("mapping").is_some() {
+        Some(ImportSource::ChatGpt)
+    } else if first.get("chat_messages").is_some() {
+        Some(ImportSource::Claude)
+    } else {
+        None
+    }
+}
+
+fn normalize_role(role: &str) -> Option<&'static str> {
+    match role.to_lowercase().as_str() {
+        "user" | "human" => Some("user"),
+        "assistant" | "ai" | "bot" | "model" => Some("assistant"),
+        _ => None,
+    }
+}
+
+/// Text of a `content` value: a string, a list of strings, or a list of `{text}` parts.
+fn content_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|p| match p {
+                Value::String(s) => Some(s.clone()),
+                other => other
+                    .get("text")
+                    .and_then(|t| t.as_str())
+                    .map(str::to_string),
+            })
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Value::Object(_) => value
+            .get("parts")
+            .or_else(|| value.get("text"))
+            .map(content_text)
+            .unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+fn push_message(messages: &mut Vec<ImportedMessage>, role: &str, text: String) {
+    let Some(role) = normalize_role(role) else {
+        return;
+    };
+    let text = text.trim();
+    if text.is_empty() {
+        return;
+    }
+    messages.push(ImportedMessage {
+        role: role.to_string(),
+        content: text.to_string(),
+    });
+}
+
+fn unix_to_rfc3339(secs: f64) -> Option<String> {
+    chrono::DateTime::from_timestamp(secs as i64, 0).map(|t| t.to_rfc3339())
+}
+
+/// Parse a ChatGPT `conversations.json`. Each conversation is a tree of edits and
+/// regenerations; the branch ending at `current_node` is the one the user saw last.
+pub fn parse_chatgpt(json: &Value) -> Result<Vec<ImportedConversation>> {
+    let items = json
+        .as_array()
+        .ok_or_else(|| Error::Validation("ChatGPT export must be a JSON array".to_string()))?;
+    let mut out = Vec::new();
+    for conv in items {
+        let Some(mapping) = conv.get("mapping").and_then(|m| m.as_object()) else {
+            continue;
+        };
+        let id = conv
+            .get("conversation_id")
+            .or_else(|| conv.get("id"))
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+
+        // Walk from the current leaf to the root, then reverse.
+        let mut branch = Vec::new();
+        let mut cursor = conv
+            .get("current_node")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        while let Some(node_id) = cursor {
+            if branch.len() > mapping.len() {
+                break;
+            }
+            let Some(node) = mapping.get(&node_id) else {
+                break;
+            };
+            branch.push(node);
+            cursor = node
+                .get("parent")
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
+        }
+        branch.reverse();
+        if branch.is_empty() {
+            // No current_node: fall back to creation order.
+            let mut nodes: Vec<&Value> = mapping.values().collect();
+            nodes.sort_by(|a, b| {
+                let t = |n: &Value| n["message"]["create_time"].as_f64().unwrap_or(0.0);
+                t(a).total_cmp(&t(b))
+            });
+            branch = nodes;
+        }
+
+        let mut messages = Vec::new();
+        for node in branch {
+            let message = &node["message"];
+            if message.is_null() {
+                continue;
+            }
+            let role = message["author"]["role"].as_str().unwrap_or_default();
+            push_message(&mut messages, role, content_text(&message["content"]));
+        }
+        if messages.is_empty() {
+            continue;
+        }
+        out.push(ImportedConversation {
+            id,
+            title: conv
+                .get("title")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+            created_at: conv
+                .get("create_time")
+                .and_then(|v| v.as_f64())
+                .and_then(unix_to_rfc3339),
+            messages,
+        });
+    }
+    Ok(out)
+}
+
+/// Parse a Claude `conversations.json`.
+pub fn parse_claude(json: &Value) -> Result<Vec<ImportedConversation>> {
+    let items = json
+        .as_array()
+        .ok_or_else(|| Error::Validation("Claude export must be a JSON array".to_string()))?;
+    let mut out = Vec::new();
+    for conv in items {
+        let Some(chat) = conv.get("chat_messages").and_then(|m| m.as_array()) else {
+            continue;
+        };
+        let mut messages = Vec::new();
+        for msg in chat {
+            let text = msg
+                .get("text")
+                .and_then(|t| t.as_str())
+                .filter(|t| !t.trim().is_empty())
+                .map(str::to_string)
+                .unwrap_or_else(|| content_text(&msg["content"]));
+            push_message(
+                &mut messages,
+                msg["sender"].as_str().unwrap_or_default(),
+                text,
+            );
+        }
+        if messages.is_empty() {
+            continue;
+        }
+        out.push(ImportedConversation {
+            id: conv["uuid"].as_str().unwrap_or_default().to_string(),
+            title: conv
+                .get("name")
+                .and_then(|v| v.as_str())
+                .filter(|n| !n.is_empty())
+                .map(str::to_string),
+            created_at: conv
+                .get("created_at")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+            messages,
+        });
+    }
+    Ok(out)
+}
+
+/// Parse a generic JSONL transcript. Lines without a conversation id go to `default_id`.
+pub fn parse_jsonl(text: &str, default_id: &str) -> Vec<ImportedConversation> {
+    let mut order: Vec<String> = Vec::new();
+    let mut grouped: HashMap<String, ImportedConversation> = HashMap::new();
+    for (line_no, line) in text.lines().enumerate() {
+        let Ok(value) = serde_json::from_str::<Value>(line.trim()) else {
+            continue;
+        };
+        if let Some(list) = value.get("messages").and_then(|m| m.as_array()) {
+            let mut messages = Vec::new();
+            for msg in list {
+                push_message(
+                    &mut messages,
+                    msg["role"].as_str().unwrap_or_default(),
+                    content_text(&msg["content"]),
+                );
+            }
+            if messages.is_empty() {
+                continue;
+            }
+            let id = value
+                .get("id")
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("{}-{}", default_id, line_no + 1));
+            order.push(id.clone());
+            grouped.insert(
+                id.clone(),
+                ImportedConversation {
+                    id,
+                    title: value
+                        .get("title")
+                        .and_then(|v| v.as_str())
+                        .map(str::to_string),
+                    created_at: None,
+                    messages,
+                },
+            );
+            continue;
+        }
+
+        let id = ["conversation_id", "conversation", "session_id", "session"]
+            .iter()
+            .find_map(|k| value.get(*k).and_then(|v| v.as_str()))
+            .unwrap_or(default_id)
+            .to_string();
+        let conv = grouped.entry(id.clone()).or_insert_with(|| {
+            order.push(id.clone());
+            ImportedConversation {
+                id,
+                title: None,
+                created_at: None,
+                messages: Vec::new(),
+            }
+        });
+        push_message(
+            &mut conv.messages,
+            value["role"].as_str().unwrap_or_default(),
+            content_text(&value["content"]),
+        );
+    }
+    order
+        .into_iter()
+        .filter_map(|id| grouped.remove(&id))
+        .filter(|c| !c.messages.is_empty())
+        .collect()
+}
+
+/// `import:<source>-<id>`
+pub fn session_key(source: ImportSource, conv: &ImportedConversation) -> String {
+    let id: String = conv
+        .id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .take(64)
+        .collect();
+    build_session_key("import", &format!("{}-{}", source.name(), id))
+}
+
+/// Write one conversation as a session. Returns the session key.
+pub fn write_session(
+    store: &SessionStore,
+    source: ImportSource,
+    conv: &ImportedConversation,
+) -> Result<String> {
+    let key = session_key(source, conv);
+    let messages: Vec<ChatMessage> = conv
+        .messages
+        .iter()
+        .map(|m| match m.role.as_str() {
+            "user" => ChatMessage::user(&m.content),
+            _ => ChatMessage::assistant(&m.content),
+        })
+        .collect();
+    let metadata = serde_json::json!({
+        "imported_from": source.name(),
+        "original_id": conv.id,
+        "title": conv.title,
+        "original_created_at": conv.created_at,
+    });
+    store.save_with_metadata(&key, &messages, &metadata)?;
+    if let Some(title) = conv.title.as_deref().filter(|t| !t.trim().is_empty()) {
+        store.set_session_name_if_new(&key, title);
+    } else if let Some(first) = conv.messages.iter().find(|m| m.role == "user") {
+        store.set_session_name_if_new(&key, &first.content);
+    }
+    Ok(key)
+}
+
+/// A long-term memory note describing what the user discussed in the conversation.
+pub fn memory_params(source: ImportSource, conv: &ImportedConversation) -> UpsertParams {
+    let key = session_key(source, conv);
+    let title = conv.title.clone().unwrap_or_else(|| "Untitled".to_string());
+    let mut content = format!(
+        "Conversation \"{}\" imported from {}{}.\nThe user asked:\n",
+        title,
+        source.label(),
+        conv.created_at
+            .as_deref()
+            .map(|d| format!(" (started {})", d.get(..10).unwrap_or(d)))
+            .unwrap_or_default()
+    );
+    for msg in conv.messages.iter().filter(|m| m.role == "user").take(8) {
+        let line = msg.content.lines().next().unwrap_or_default();
+        let excerpt: String = line.chars().take(200).collect();
+        content.push_str(&format!("- {}\n", excerpt));
+    }
+    UpsertParams {
+        scope: "long_term".to_string(),
+        item_type: "note".to_string(),
+        title: Some(format!("Imported chat: {}", title)),
+        content,
+        summary: None,
+        tags: vec!["imported".to_string(), source.name().to_string()],
+        source: "import".to_string(),
+        channel: Some("import".to_string()),
+        session_key: Some(key.clone()),
+        importance: 0.4,
+        dedup_key: Some(key),
+        expires_at: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_chatgpt_follows_current_branch() {
+        let export = json!([{
+            "id": "c1",
+            "title": "Trip planning",
+            "create_time": 1_700_000_000.0,
+            "current_node": "a2",
+            "mapping": {
+                "root": {"id": "root", "message": null, "parent": null},
+                "sys": {"message": {"author": {"role": "system"}, "content": {"parts": ["hidden"]}}, "parent": "root"},
+                "u1": {"message": {"author": {"role": "user"}, "content": {"content_type": "text", "parts": ["Plan a weekend in Lisbon"]}}, "parent": "sys"},
+                "a1": {"message": {"author": {"role": "assistant"}, "content": {"parts": ["old draft"]}}, "parent": "u1"},
+                "a2": {"message": {"author": {"role": "assistant"}, "content": {"parts": ["Day 1: Alfama"]}}, "parent": "u1"}
+            }
+        }]);
+        assert_eq!(detect(&export), Some(ImportSource::ChatGpt));
+        let convs = parse_chatgpt(&export).unwrap();
+        assert_eq!(convs.len(), 1);
+        assert_eq!(convs[0].title.as_deref(), Some("Trip planning"));
+        let texts: Vec<&str> = convs[0]
+            .messages
+            .iter()
+            .map(|m| m.content.as_str())
+            .collect();
+        assert_eq!(texts, vec!["Plan a weekend in Lisbon", "Day 1: Alfama"]);
+        assert!(convs[0]
+            .created_at
+            .as_deref()
+            .unwrap()
+            .starts_with("2023-11-14"));
+    }
+
+    #[test]
+    fn test_parse_claude_and_jsonl() {
+        let export = json!([{
+            "uuid": "abc",
+            "name": "Rust lifetimes",
+            "created_at": "2024-03-01T10:00:00Z",
+            "chat_messages": [
+                {"sender": "human", "text": "Explain 'a"},
+                {"sender": "assistant", "text": "", "content": [{"type": "text", "text": "A lifetime is..."}]}
+            ]
+        }]);
+        assert_eq!(detect(&export), Some(ImportSource::Claude));
+        let convs = parse_claude(&export).unwrap();
+        assert_eq!(convs[0].messages[1].content, "A lifetime is...");
+        assert_eq!(convs[0].messages[0].role, "user");
+
+        let jsonl = r#"{"conversation_id":"x","role":"user","content":"hi"}
+{"conversation_id":"x","role":"assistant","content":[{"type":"text","text":"hello"}]}
+not json
+{"id":"t2","title":"Second","messages":[{"role":"human","content":"q"},{"role":"model","content":"a"}]}"#;
+        let convs = parse_jsonl(jsonl, "file");
+        assert_eq!(convs.len(), 2);
+        assert_eq!(convs[0].id, "x");
+        assert_eq!(convs[0].messages.len(), 2);
+        assert_eq!(convs[1].title.as_deref(), Some("Second"));
+        assert_eq!(convs[1].messages[1].role, "assistant");
+    }
+
+    #[test]
+    fn test_write_session_is_idempotent() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let store = SessionStore::new(blockcell_core::Paths::with_base(dir.path().to_path_buf()));
+        let conv = ImportedConversation {
+            id: "abc/1".to_string(),
+            title: Some("Title".to_string()),
+            created_at: None,
+            messages: vec![
+                ImportedMessage {
+                    role: "user".to_string(),
+                    content: "q".to_string(),
+                },
+                ImportedMessage {
+                    role: "assistant".to_string(),
+                    content: "a".to_string(),
+                },
+            ],
+        };
+        let key = write_session(&store, ImportSource::Claude, &conv).unwrap();
+        assert_eq!(key, "import:claude-abc-1");
+        write_session(&store, ImportSource::Claude, &conv).unwrap();
+        assert_eq!(store.load(&key).unwrap().len(), 2);
+        assert_eq!(
+            store.load_metadata(&key).unwrap()["imported_from"],
+            "claude"
+        );
+
+        let params = memory_params(ImportSource::Claude, &conv);
+        assert!(params.content.contains("- q"));
+        assert_eq!(params.dedup_key.as_deref(), Some("import:claude-abc-1"));
+    }
+}
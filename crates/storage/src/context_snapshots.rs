@@ -0,0 +1,383 @@
+//! Per-turn snapshots of the context sent to the model.
+//!
+//! At the first LLM call of a turn the runtime records what the model saw: the system
+//! prompt, the memory and skills text injected into it, the history window and the user
+//! message. To stay small, prompt text is split into `## ` sections stored once as
+//! content-addressed blobs, and history messages that are still in the session file are
+//! stored as indices into it. Only messages that were altered before sending (projected
+//! tool results, trimmed content) are stored inline.
+
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+use blockcell_core::types::ChatMessage;
+use blockcell_core::{session_file_stem, Paths, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// A history message in a snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum HistoryEntry {
+    /// Unchanged message at this index of the session.
+    Index {
+        index: usize,
+    },
+    Inline(ChatMessage),
+}
+
+/// One stored snapshot (a line of `<session>.jsonl`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextSnapshot {
+    /// Index of the turn's user message in the session.
+    pub message_index: usize,
+    pub timestamp: String,
+    pub model: String,
+    /// Blob ids of the system prompt sections, in order.
+    pub system_sections: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skills: Option<String>,
+    pub history: Vec<HistoryEntry>,
+    pub user_message: ChatMessage,
+    pub tools: Vec<String>,
+}
+
+/// What the runtime hands over at turn time.
+pub struct SnapshotInput<'a> {
+    pub message_index: usize,
+    pub model: &'a str,
+    /// The request as sent: system prompt, history window, user message.
+    pub messages: &'a [ChatMessage],
+    /// Session history before the turn.
+    pub session: &'a [ChatMessage],
+    pub memory: &'a str,
+    pub skills: &'a str,
+    pub tools: Vec<String>,
+}
+
+/// A snapshot with blobs and history indices resolved.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReconstructedContext {
+    pub message_index: usize,
+    pub timestamp: String,
+    pub model: String,
+    pub system_prompt: String,
+    pub memory_brief: String,
+    pub skills_brief: String,
+    pub history: Vec<ChatMessage>,
+    /// History entries whose session message no longer exists (e.g. after compaction).
+    pub missing_history: usize,
+    pub user_message: ChatMessage,
+    pub tools: Vec<String>,
+}
+
+pub struct ContextSnapshotStore {
+    dir: PathBuf,
+}
+
+/// Split at `## ` headings so unchanged sections share a blob across turns.
+fn split_sections(prompt: &str) -> Vec<&str> {
+    let mut sections = Vec::new();
+    let mut start = 0;
+    for (idx, _) in prompt.match_indices("\n## ") {
+        if idx + 1 > start {
+            sections.push(&prompt[start..idx + 1]);
+            start = idx + 1;
+        }
+    }
+    sections.push(&prompt[start..]);
+    sections.retain(|s| !s.is_empty());
+    sections
+}
+
+fn same_message(a: &ChatMessage, b: &ChatMessage) -> bool {
+    a.role == b.role && a.content == b.content && a.tool_call_id == b.tool_call_id
+}
+
+impl ContextSnapshotStore {
+    pub fn new(paths: &Paths) -> Self {
+        Self {
+            dir: paths.context_snapshots_dir(),
+        }
+    }
+
+    fn index_file(&self, session_key: &str) -> PathBuf {
+        self.dir
+            .join(format!("{}.jsonl", session_file_stem(session_key)))
+    }
+
+    fn blob_path(&self, id: &str) -> PathBuf {
+        self.dir.join("blobs").join(format!("{}.txt", id))
+    }
+
+    fn put_blob(&self, text: &str) -> Result<String> {
+        let digest = Sha256::digest(text.as_bytes());
+        let id: String = digest
+            .iter()
+            .take(12)
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        let path = self.blob_path(&id);
+        if !path.exists() {
+            std::fs::create_dir_all(self.dir.join("blobs"))?;
+            std::fs::write(&path, text)?;
+        }
+        Ok(id)
+    }
+
+    fn get_blob(&self, id: &str) -> String {
+        std::fs::read_to_string(self.blob_path(id)).unwrap_or_default()
+    }
+
+    pub fn record(&self, session_key: &str, input: SnapshotInput<'_>) -> Result<()> {
+        let (system, rest) = match input.messages.split_first() {
+            Some((first, rest)) if first.role == "system" => {
+                (first.content.as_str().unwrap_or_default(), rest)
+            }
+            _ => ("", input.messages),
+        };
+        let Some((user_message, window)) = rest.split_last() else {
+            return Ok(());
+        };
+
+        let system_sections = split_sections(system)
+            .into_iter()
+            .map(|section| self.put_blob(section))
+            .collect::<Result<Vec<_>>>()?;
+        let optional_blob = |text: &str| -> Result<Option<String>> {
+            if text.is_empty() {
+                Ok(None)
+            } else {
+                self.put_blob(text).map(Some)
+            }
+        };
+
+        // Window messages are matched in order against the session, so a search resumes
+        // after the previous hit.
+        let mut cursor = 0;
+        let history = window
+            .iter()
+            .map(|msg| {
+                let hit = input.session[cursor.min(input.session.len())..]
+                    .iter()
+                    .position(|s| (msg.id.is_none() || s.id == msg.id) && same_message(s, msg))
+                    .map(|offset| cursor + offset);
+                match hit {
+                    Some(index) => {
+                        cursor = index + 1;
+                        HistoryEntry::Index { index }
+                    }
+                    None => HistoryEntry::Inline(msg.clone()),
+                }
+            })
+            .collect();
+
+        let snapshot = ContextSnapshot {
+            message_index: input.message_index,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            model: input.model.to_string(),
+            system_sections,
+            memory: optional_blob(input.memory)?,
+            skills: optional_blob(input.skills)?,
+            history,
+            user_message: user_message.clone(),
+            tools: input.tools,
+        };
+        std::fs::create_dir_all(&self.dir)?;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.index_file(session_key))?;
+        let line = serde_json::to_string(&snapshot)?;
+        writeln!(file, "{}", crate::at_rest::seal_line(&line))?;
+        Ok(())
+    }
+
+    pub fn list(&self, session_key: &str) -> Result<Vec<ContextSnapshot>> {
+        let path = self.index_file(session_key);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let reader = BufReader::new(std::fs::File::open(path)?);
+        Ok(reader
+            .lines()
+            .map_while(|l| l.ok())
+            .filter_map(|l| serde_json::from_str(&crate::at_rest::open_line(&l)?).ok())
+            .collect())
+    }
+
+    /// Context of the turn that produced message `message_index`: the latest snapshot
+    /// whose user message is at or before that index.
+    pub fn reconstruct(
+        &self,
+        session_key: &str,
+        message_index: usize,
+        session: &[ChatMessage],
+    ) -> Result<Option<ReconstructedContext>> {
+        let Some(snapshot) = self
+            .list(session_key)?
+            .into_iter()
+            .filter(|s| s.message_index <= message_index)
+            .max_by_key(|s| s.message_index)
+        else {
+            return Ok(None);
+        };
+
+        let mut missing_history = 0;
+        let history = snapshot
+            .history
+            .into_iter()
+            .filter_map(|entry| match entry {
+                HistoryEntry::Inline(msg) => Some(msg),
+                HistoryEntry::Index { index } => {
+                    let found = session.get(index).cloned();
+                    if found.is_none() {
+                        missing_history += 1;
+                    }
+                    found
+                }
+            })
+            .collect();
+
+        Ok(Some(ReconstructedContext {
+            message_index: snapshot.message_index,
+            timestamp: snapshot.timestamp,
+            model: snapshot.model,
+            system_prompt: snapshot
+                .system_sections
+                .iter()
+                .map(|id| self.get_blob(id))
+                .collect(),
+            memory_brief: snapshot
+                .memory
+                .map(|id| self.get_blob(&id))
+                .unwrap_or_default(),
+            skills_brief: snapshot
+                .skills
+                .map(|id| self.get_blob(&id))
+                .unwrap_or_default(),
+            history,
+            missing_history,
+            user_message: snapshot.user_message,
+            tools: snapshot.tools,
+        }))
+    }
+
+    /// Drop a session's snapshots (blobs are shared and left in place).
+    pub fn remove(&self, session_key: &str) -> Result<()> {
+        let path = self.index_file(session_key);
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> (ContextSnapshotStore, PathBuf) {
+        let base = std::env::temp_dir().join(format!("ctx-snap-{}", uuid::Uuid::new_v4()));
+        let paths = Paths::with_base(base.clone());
+        (ContextSnapshotStore::new(&paths), base)
+    }
+
+    #[test]
+    fn test_record_and_reconstruct() {
+        let (store, base) = store();
+        let session = vec![
+            ChatMessage::user("first question"),
+            ChatMessage::assistant("first answer"),
+            ChatMessage::tool_result("call-1", "very long tool output"),
+        ];
+        let projected = ChatMessage::tool_result("call-1", "[output trimmed]");
+        let system = "You are blockcell.\n\n## Memory Brief\nlikes tea\n\n## Tools\n- be brief\n";
+        let messages = vec![
+            ChatMessage::system(system),
+            session[0].clone(),
+            session[1].clone(),
+            projected.clone(),
+            ChatMessage::user("second question"),
+        ];
+        store
+            .record(
+                "cli:default",
+                SnapshotInput {
+                    message_index: 3,
+                    model: "gpt-4o",
+                    messages: &messages,
+                    session: &session,
+                    memory: "## Memory Brief\nlikes tea\n\n",
+                    skills: "",
+                    tools: vec!["read_file".to_string()],
+                },
+            )
+            .unwrap();
+
+        let stored = store.list("cli:default").unwrap();
+        assert_eq!(stored[0].system_sections.len(), 3);
+        assert!(matches!(
+            stored[0].history[0],
+            HistoryEntry::Index { index: 0 }
+        ));
+        assert!(matches!(stored[0].history[2], HistoryEntry::Inline(_)));
+
+        let ctx = store
+            .reconstruct("cli:default", 4, &session)
+            .unwrap()
+            .unwrap();
+        assert_eq!(ctx.system_prompt, system);
+        assert_eq!(ctx.memory_brief, "## Memory Brief\nlikes tea\n\n");
+        assert_eq!(ctx.history.len(), 3);
+        assert_eq!(ctx.history[2].content, projected.content);
+        assert_eq!(ctx.user_message.content, "second question");
+        assert!(store
+            .reconstruct("cli:default", 2, &session)
+            .unwrap()
+            .is_none());
+
+        let ctx = store
+            .reconstruct("cli:default", 3, &session[..1])
+            .unwrap()
+            .unwrap();
+        assert_eq!(ctx.missing_history, 1);
+        let _ = std::fs::remove_dir_all(base);
+    }
+
+    #[test]
+    fn test_sections_are_shared_between_turns() {
+        let (store, base) = store();
+        let session: Vec<ChatMessage> = Vec::new();
+        for (idx, time) in ["09:00", "09:05"].iter().enumerate() {
+            let system = format!(
+                "You are blockcell.\nCurrent time: {}\n\n## Agent Guidelines\nbe kind\n",
+                time
+            );
+            let messages = vec![ChatMessage::system(&system), ChatMessage::user("hi")];
+            store
+                .record(
+                    "cli:default",
+                    SnapshotInput {
+                        message_index: idx * 2,
+                        model: "m",
+                        messages: &messages,
+                        session: &session,
+                        memory: "",
+                        skills: "",
+                        tools: Vec::new(),
+                    },
+                )
+                .unwrap();
+        }
+        let blobs = std::fs::read_dir(base.join("context_snapshots/blobs"))
+            .unwrap()
+            .count();
+        assert_eq!(blobs, 3);
+        assert_eq!(split_sections("no headings"), vec!["no headings"]);
+        let _ = std::fs::remove_dir_all(base);
+    }
+}
@@ -0,0 +1,282 @@
+//! Per-job cron run log (`cron/history.db`).
+//!
+//! `CronService` opens a run when it dispatches a job and tags the inbound message
+//! with its `cron_run_id`; the agent runtime closes the run once the message has been
+//! processed. `blockcell cron history` and `GET /v1/cron/:id/history` read it back.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use blockcell_core::{Error, Result};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+/// Metadata key carrying the run id on dispatched cron messages.
+pub const CRON_RUN_ID_KEY: &str = "cron_run_id";
+
+/// Runs kept per job; older ones are dropped when a new run starts.
+const MAX_RUNS_PER_JOB: usize = 200;
+
+/// Longest stored result snippet, in characters.
+const RESULT_SNIPPET_CHARS: usize = 500;
+
+fn db_err(e: rusqlite::Error) -> Error {
+    Error::Storage(format!("cron history: {}", e))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CronRunStatus {
+    Running,
+    Success,
+    Failed,
+}
+
+impl CronRunStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Success => "success",
+            Self::Failed => "failed",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "success" => Self::Success,
+            "failed" => Self::Failed,
+            _ => Self::Running,
+        }
+    }
+}
+
+/// One execution of a cron job.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CronRun {
+    pub run_id: String,
+    pub job_id: String,
+    pub job_name: String,
+    /// `schedule` or `manual`.
+    pub trigger: String,
+    pub started_at_ms: i64,
+    pub finished_at_ms: Option<i64>,
+    pub status: CronRunStatus,
+    /// First characters of the agent's reply.
+    pub result: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct CronRunStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl CronRunStore {
+    pub fn open(db_path: &Path) -> Result<Self> {
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(db_path).map_err(db_err)?;
+        conn.execute_batch("PRAGMA journal_mode=WAL;").ok();
+        Self::init(conn)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory().map_err(db_err)?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            "
+            CREATE TABLE IF NOT EXISTS cron_runs (
+                run_id TEXT PRIMARY KEY,
+                job_id TEXT NOT NULL,
+                job_name TEXT NOT NULL,
+                trigger TEXT NOT NULL,
+                started_at INTEGER NOT NULL,
+                finished_at INTEGER,
+                status TEXT NOT NULL,
+                result TEXT,
+                error TEXT
+            );
+            CREATE INDEX IF NOT EXISTS idx_cron_runs_job ON cron_runs(job_id, started_at);
+            ",
+        )
+        .map_err(db_err)?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>> {
+        self.conn
+            .lock()
+            .map_err(|e| Error::Storage(format!("Lock error: {}", e)))
+    }
+
+    /// Record a dispatched run and trim the job's log to the newest runs.
+    pub fn start(
+        &self,
+        run_id: &str,
+        job_id: &str,
+        job_name: &str,
+        trigger: &str,
+        started_at_ms: i64,
+    ) -> Result<()> {
+        let conn = self.lock()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO cron_runs (run_id, job_id, job_name, trigger, started_at, status)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                run_id,
+                job_id,
+                job_name,
+                trigger,
+                started_at_ms,
+                CronRunStatus::Running.as_str()
+            ],
+        )
+        .map_err(db_err)?;
+        conn.execute(
+            "DELETE FROM cron_runs WHERE job_id = ?1 AND run_id NOT IN (
+                 SELECT run_id FROM cron_runs WHERE job_id = ?1
+                 ORDER BY started_at DESC, rowid DESC LIMIT ?2
+             )",
+            params![job_id, MAX_RUNS_PER_JOB as i64],
+        )
+        .map_err(db_err)?;
+        Ok(())
+    }
+
+    /// Close a run. Returns false if the run id is unknown.
+    pub fn finish(
+        &self,
+        run_id: &str,
+        status: CronRunStatus,
+        result: Option<&str>,
+        error: Option<&str>,
+        finished_at_ms: i64,
+    ) -> Result<bool> {
+        let snippet = result.map(|text| {
+            let trimmed = text.trim();
+            match trimmed.char_indices().nth(RESULT_SNIPPET_CHARS) {
+                Some((end, _)) => format!("{}...", &trimmed[..end]),
+                None => trimmed.to_string(),
+            }
+        });
+        let updated = self
+            .lock()?
+            .execute(
+                "UPDATE cron_runs SET finished_at = ?2, status = ?3, result = ?4, error = ?5
+                 WHERE run_id = ?1",
+                params![run_id, finished_at_ms, status.as_str(), snippet, error],
+            )
+            .map_err(db_err)?;
+        Ok(updated > 0)
+    }
+
+    /// The newest `limit` runs of a job, newest first.
+    pub fn history(&self, job_id: &str, limit: usize) -> Result<Vec<CronRun>> {
+        let conn = self.lock()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT run_id, job_id, job_name, trigger, started_at, finished_at, status, result, error
+                 FROM cron_runs WHERE job_id = ?1
+                 ORDER BY started_at DESC, rowid DESC LIMIT ?2",
+            )
+            .map_err(db_err)?;
+        let runs = stmt
+            .query_map(params![job_id, limit as i64], |row| {
+                Ok(CronRun {
+                    run_id: row.get(0)?,
+                    job_id: row.get(1)?,
+                    job_name: row.get(2)?,
+                    trigger: row.get(3)?,
+                    started_at_ms: row.get(4)?,
+                    finished_at_ms: row.get(5)?,
+                    status: CronRunStatus::parse(&row.get::<_, String>(6)?),
+                    result: row.get(7)?,
+                    error: row.get(8)?,
+                })
+            })
+            .map_err(db_err)?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(db_err)?;
+        Ok(runs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_runs_are_opened_closed_and_listed_newest_first() {
+        let store = CronRunStore::open_in_memory().unwrap();
+        store
+            .start("r1", "job-a", "Daily report", "schedule", 1_000)
+            .unwrap();
+        store
+            .start("r2", "job-a", "Daily report", "manual", 2_000)
+            .unwrap();
+        store
+            .start("r3", "job-b", "Other", "schedule", 1_500)
+            .unwrap();
+
+        assert!(store
+            .finish("r1", CronRunStatus::Success, Some("  done  "), None, 1_200)
+            .unwrap());
+        assert!(store
+            .finish(
+                "r2",
+                CronRunStatus::Failed,
+                None,
+                Some("provider down"),
+                2_100
+            )
+            .unwrap());
+        assert!(!store
+            .finish("missing", CronRunStatus::Success, None, None, 3_000)
+            .unwrap());
+
+        let runs = store.history("job-a", 10).unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].run_id, "r2");
+        assert_eq!(runs[0].status, CronRunStatus::Failed);
+        assert_eq!(runs[0].trigger, "manual");
+        assert_eq!(runs[0].error.as_deref(), Some("provider down"));
+        assert_eq!(runs[1].result.as_deref(), Some("done"));
+        assert_eq!(runs[1].finished_at_ms, Some(1_200));
+        assert_eq!(
+            store.history("job-b", 10).unwrap()[0].status,
+            CronRunStatus::Running
+        );
+    }
+
+    #[test]
+    fn test_results_are_truncated_and_old_runs_trimmed() {
+        let store = CronRunStore::open_in_memory().unwrap();
+        for i in 0..(MAX_RUNS_PER_JOB + 5) {
+            store
+                .start(&format!("r{}", i), "job", "Job", "schedule", i as i64)
+                .unwrap();
+        }
+        let long = "é".repeat(RESULT_SNIPPET_CHARS + 50);
+        store
+            .finish(
+                &format!("r{}", MAX_RUNS_PER_JOB + 4),
+                CronRunStatus::Success,
+                Some(&long),
+                None,
+                10_000,
+            )
+            .unwrap();
+
+        let runs = store.history("job", 1_000).unwrap();
+        assert_eq!(runs.len(), MAX_RUNS_PER_JOB);
+        assert_eq!(runs.last().unwrap().run_id, "r5");
+        let result = runs[0].result.as_deref().unwrap();
+        assert_eq!(result.chars().count(), RESULT_SNIPPET_CHARS + 3);
+        assert!(result.ends_with("..."));
+    }
+}
@@ -0,0 +1,518 @@
+//! Document chunks behind `doc_ingest` / `doc_search` and `blockcell docs`.
+//!
+//! Each ingested file or URL is one `documents` row and a list of text chunks. Chunks
+//! carry an embedding when `memory.vector` is enabled; retrieval fuses FTS5 keyword
+//! ranks with cosine ranks over the stored embeddings (reciprocal rank fusion), so the
+//! store still answers keyword queries without an embedding provider.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use blockcell_core::{Error, Result};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+
+/// Candidates taken from each ranking before fusion.
+const CANDIDATES_PER_RANKING: usize = 50;
+/// Reciprocal rank fusion constant (the usual 60).
+const RRF_K: f64 = 60.0;
+
+fn db_err(e: rusqlite::Error) -> Error {
+    Error::Storage(format!("documents: {}", e))
+}
+
+/// Location of the document store inside an agent workspace.
+pub fn db_path(workspace: &Path) -> PathBuf {
+    workspace.join("documents.db")
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NewChunk {
+    pub heading: String,
+    pub text: String,
+    pub embedding: Option<Vec<f32>>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NewDocument {
+    /// Absolute path or URL; re-ingesting the same source replaces the document.
+    pub source: String,
+    pub title: String,
+    /// `pdf`, `markdown`, `html`, `docx`, ...
+    pub kind: String,
+    /// SHA-256 of the extracted text, used to skip unchanged sources.
+    pub content_hash: String,
+    pub chars: usize,
+    pub embedding_model: Option<String>,
+    pub chunks: Vec<NewChunk>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DocumentInfo {
+    pub id: String,
+    pub source: String,
+    pub title: String,
+    pub kind: String,
+    pub content_hash: String,
+    pub chars: usize,
+    pub chunk_count: usize,
+    pub embedding_model: Option<String>,
+    pub ingested_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChunkHit {
+    pub doc_id: String,
+    pub source: String,
+    pub title: String,
+    pub seq: usize,
+    pub heading: String,
+    pub text: String,
+    pub score: f64,
+}
+
+const DOC_COLUMNS: &str =
+    "id, source, title, kind, content_hash, chars, chunk_count, embedding_model, ingested_at";
+
+fn row_to_document(row: &Row<'_>) -> rusqlite::Result<DocumentInfo> {
+    Ok(DocumentInfo {
+        id: row.get(0)?,
+        source: row.get(1)?,
+        title: row.get(2)?,
+        kind: row.get(3)?,
+        content_hash: row.get(4)?,
+        chars: row.get::<_, i64>(5)? as usize,
+        chunk_count: row.get::<_, i64>(6)? as usize,
+        embedding_model: row.get(7)?,
+        ingested_at: row.get(8)?,
+    })
+}
+
+fn encode_embedding(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn decode_embedding(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f64 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let (mut dot, mut na, mut nb) = (0.0f64, 0.0f64, 0.0f64);
+    for (x, y) in a.iter().zip(b) {
+        let (x, y) = (*x as f64, *y as f64);
+        dot += x * y;
+        na += x * x;
+        nb += y * y;
+    }
+    if na == 0.0 || nb == 0.0 {
+        0.0
+    } else {
+        dot / (na.sqrt() * nb.sqrt())
+    }
+}
+
+/// Any query term may match; bm25 ranks chunks matching more of them first.
+fn fts_any_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| format!("\"{}\"", t))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" OR "))
+}
+
+#[derive(Clone)]
+pub struct DocumentStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl DocumentStore {
+    pub fn open(db_path: &Path) -> Result<Self> {
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(db_path).map_err(db_err)?;
+        conn.execute_batch("PRAGMA journal_mode=WAL;").ok();
+        Self::init(conn)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory().map_err(db_err)?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            "
+            CREATE TABLE IF NOT EXISTS documents (
+                id TEXT PRIMARY KEY,
+                source TEXT NOT NULL UNIQUE,
+                title TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                chars INTEGER NOT NULL,
+                chunk_count INTEGER NOT NULL,
+                embedding_model TEXT,
+                ingested_at INTEGER NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS document_chunks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                doc_id TEXT NOT NULL,
+                seq INTEGER NOT NULL,
+                heading TEXT NOT NULL DEFAULT '',
+                text TEXT NOT NULL,
+                embedding BLOB
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_document_chunks_doc ON document_chunks(doc_id);
+
+            CREATE VIRTUAL TABLE IF NOT EXISTS document_chunks_fts USING fts5(
+                heading,
+                text,
+                content='document_chunks',
+                content_rowid='id'
+            );
+
+            CREATE TRIGGER IF NOT EXISTS document_chunks_ai AFTER INSERT ON document_chunks BEGIN
+                INSERT INTO document_chunks_fts(rowid, heading, text)
+                VALUES (new.id, new.heading, new.text);
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS document_chunks_ad AFTER DELETE ON document_chunks BEGIN
+                INSERT INTO document_chunks_fts(document_chunks_fts, rowid, heading, text)
+                VALUES ('delete', old.id, old.heading, old.text);
+            END;
+            ",
+        )
+        .map_err(db_err)?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>> {
+        self.conn
+            .lock()
+            .map_err(|e| Error::Storage(format!("documents lock error: {}", e)))
+    }
+
+    pub fn find_by_source(&self, source: &str) -> Result<Option<DocumentInfo>> {
+        let conn = self.lock()?;
+        conn.query_row(
+            &format!("SELECT {} FROM documents WHERE source = ?1", DOC_COLUMNS),
+            params![source],
+            row_to_document,
+        )
+        .optional()
+        .map_err(db_err)
+    }
+
+    /// Store a document, replacing any earlier version from the same source.
+    pub fn upsert(&self, doc: NewDocument) -> Result<DocumentInfo> {
+        let mut conn = self.lock()?;
+        let tx = conn.transaction().map_err(db_err)?;
+        let existing: Option<String> = tx
+            .query_row(
+                "SELECT id FROM documents WHERE source = ?1",
+                params![doc.source],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(db_err)?;
+        if let Some(id) = &existing {
+            tx.execute("DELETE FROM document_chunks WHERE doc_id = ?1", params![id])
+                .map_err(db_err)?;
+            tx.execute("DELETE FROM documents WHERE id = ?1", params![id])
+                .map_err(db_err)?;
+        }
+
+        let info = DocumentInfo {
+            id: existing.unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string()),
+            source: doc.source,
+            title: doc.title,
+            kind: doc.kind,
+            content_hash: doc.content_hash,
+            chars: doc.chars,
+            chunk_count: doc.chunks.len(),
+            embedding_model: doc.embedding_model,
+            ingested_at: Utc::now().timestamp(),
+        };
+        tx.execute(
+            &format!(
+                "INSERT INTO documents ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                DOC_COLUMNS
+            ),
+            params![
+                info.id,
+                info.source,
+                info.title,
+                info.kind,
+                info.content_hash,
+                info.chars as i64,
+                info.chunk_count as i64,
+                info.embedding_model,
+                info.ingested_at,
+            ],
+        )
+        .map_err(db_err)?;
+        {
+            let mut insert = tx
+                .prepare(
+                    "INSERT INTO document_chunks (doc_id, seq, heading, text, embedding)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                )
+                .map_err(db_err)?;
+            for (seq, chunk) in doc.chunks.iter().enumerate() {
+                insert
+                    .execute(params![
+                        info.id,
+                        seq as i64,
+                        chunk.heading,
+                        chunk.text,
+                        chunk.embedding.as_deref().map(encode_embedding),
+                    ])
+                    .map_err(db_err)?;
+            }
+        }
+        tx.commit().map_err(db_err)?;
+        Ok(info)
+    }
+
+    /// Documents, most recently ingested first.
+    pub fn list(&self) -> Result<Vec<DocumentInfo>> {
+        let conn = self.lock()?;
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM documents ORDER BY ingested_at DESC, title",
+                DOC_COLUMNS
+            ))
+            .map_err(db_err)?;
+        let rows = stmt.query_map([], row_to_document).map_err(db_err)?;
+        rows.collect::<rusqlite::Result<Vec<_>>>().map_err(db_err)
+    }
+
+    /// Remove a document by id or source. Returns whether one was removed.
+    pub fn remove(&self, id_or_source: &str) -> Result<bool> {
+        let mut conn = self.lock()?;
+        let tx = conn.transaction().map_err(db_err)?;
+        let id: Option<String> = tx
+            .query_row(
+                "SELECT id FROM documents WHERE id = ?1 OR source = ?1",
+                params![id_or_source],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(db_err)?;
+        let Some(id) = id else {
+            return Ok(false);
+        };
+        tx.execute("DELETE FROM document_chunks WHERE doc_id = ?1", params![id])
+            .map_err(db_err)?;
+        tx.execute("DELETE FROM documents WHERE id = ?1", params![id])
+            .map_err(db_err)?;
+        tx.commit().map_err(db_err)?;
+        Ok(true)
+    }
+
+    /// Best `top_k` chunks for `query`. `query_embedding` adds a semantic ranking;
+    /// `document` (id or source) restricts the search to one document.
+    pub fn search(
+        &self,
+        query: &str,
+        query_embedding: Option<&[f32]>,
+        top_k: usize,
+        document: Option<&str>,
+    ) -> Result<Vec<ChunkHit>> {
+        let conn = self.lock()?;
+        let doc_filter: Option<String> = match document {
+            Some(doc) => {
+                let id: Option<String> = conn
+                    .query_row(
+                        "SELECT id FROM documents WHERE id = ?1 OR source = ?1",
+                        params![doc],
+                        |row| row.get(0),
+                    )
+                    .optional()
+                    .map_err(db_err)?;
+                match id {
+                    Some(id) => Some(id),
+                    None => return Ok(Vec::new()),
+                }
+            }
+            None => None,
+        };
+
+        let mut fused: HashMap<i64, f64> = HashMap::new();
+        if let Some(fts_query) = fts_any_query(query) {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT c.id FROM document_chunks_fts
+                     JOIN document_chunks c ON c.id = document_chunks_fts.rowid
+                     WHERE document_chunks_fts MATCH ?1 AND (?2 IS NULL OR c.doc_id = ?2)
+                     ORDER BY bm25(document_chunks_fts)
+                     LIMIT ?3",
+                )
+                .map_err(db_err)?;
+            let ids = stmt
+                .query_map(
+                    params![fts_query, doc_filter, CANDIDATES_PER_RANKING as i64],
+                    |row| row.get::<_, i64>(0),
+                )
+                .map_err(db_err)?
+                .collect::<rusqlite::Result<Vec<_>>>()
+                .map_err(db_err)?;
+            for (rank, id) in ids.into_iter().enumerate() {
+                *fused.entry(id).or_default() += 1.0 / (RRF_K + rank as f64 + 1.0);
+            }
+        }
+
+        if let Some(query_vector) = query_embedding {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT id, embedding FROM document_chunks
+                     WHERE embedding IS NOT NULL AND (?1 IS NULL OR doc_id = ?1)",
+                )
+                .map_err(db_err)?;
+            let mut scored = stmt
+                .query_map(params![doc_filter], |row| {
+                    let bytes: Vec<u8> = row.get(1)?;
+                    Ok((row.get::<_, i64>(0)?, bytes))
+                })
+                .map_err(db_err)?
+                .filter_map(|row| row.ok())
+                .map(|(id, bytes)| (id, cosine(query_vector, &decode_embedding(&bytes))))
+                .filter(|(_, score)| *score > 0.0)
+                .collect::<Vec<_>>();
+            scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+            for (rank, (id, _)) in scored.into_iter().take(CANDIDATES_PER_RANKING).enumerate() {
+                *fused.entry(id).or_default() += 1.0 / (RRF_K + rank as f64 + 1.0);
+            }
+        }
+
+        let mut ranked: Vec<(i64, f64)> = fused.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked.truncate(top_k);
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT c.doc_id, d.source, d.title, c.seq, c.heading, c.text
+                 FROM document_chunks c JOIN documents d ON d.id = c.doc_id
+                 WHERE c.id = ?1",
+            )
+            .map_err(db_err)?;
+        let mut hits = Vec::with_capacity(ranked.len());
+        for (id, score) in ranked {
+            let hit = stmt
+                .query_row(params![id], |row| {
+                    Ok(ChunkHit {
+                        doc_id: row.get(0)?,
+                        source: row.get(1)?,
+                        title: row.get(2)?,
+                        seq: row.get::<_, i64>(3)? as usize,
+                        heading: row.get(4)?,
+                        text: row.get(5)?,
+                        score,
+                    })
+                })
+                .optional()
+                .map_err(db_err)?;
+            hits.extend(hit);
+        }
+        Ok(hits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(source: &str, chunks: &[(&str, Option<Vec<f32>>)]) -> NewDocument {
+        NewDocument {
+            source: source.to_string(),
+            title: source.to_string(),
+            kind: "markdown".to_string(),
+            content_hash: "h".to_string(),
+            chars: 10,
+            embedding_model: None,
+            chunks: chunks
+                .iter()
+                .map(|(text, embedding)| NewChunk {
+                    heading: String::new(),
+                    text: text.to_string(),
+                    embedding: embedding.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_upsert_replaces_source_and_remove() {
+        let store = DocumentStore::open_in_memory().unwrap();
+        let first = store
+            .upsert(doc("/notes/a.md", &[("alpha beta", None), ("gamma", None)]))
+            .unwrap();
+        let second = store
+            .upsert(doc("/notes/a.md", &[("delta", None)]))
+            .unwrap();
+        assert_eq!(first.id, second.id);
+        assert_eq!(store.list().unwrap().len(), 1);
+        assert_eq!(
+            store
+                .find_by_source("/notes/a.md")
+                .unwrap()
+                .unwrap()
+                .chunk_count,
+            1
+        );
+        // Old chunks are gone from the keyword index too.
+        assert!(store.search("alpha", None, 5, None).unwrap().is_empty());
+        assert_eq!(store.search("delta", None, 5, None).unwrap().len(), 1);
+
+        assert!(store.remove(&second.id).unwrap());
+        assert!(!store.remove("/notes/a.md").unwrap());
+        assert!(store.search("delta", None, 5, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_search_fuses_keyword_and_vector_ranks() {
+        let store = DocumentStore::open_in_memory().unwrap();
+        store
+            .upsert(doc(
+                "/docs/lease.pdf",
+                &[
+                    ("The deposit is two months of rent", Some(vec![1.0, 0.0])),
+                    ("Pets are allowed with approval", Some(vec![0.0, 1.0])),
+                ],
+            ))
+            .unwrap();
+        store
+            .upsert(doc("/docs/other.md", &[("rent payment schedule", None)]))
+            .unwrap();
+
+        // Keyword only.
+        let hits = store.search("rent", None, 5, None).unwrap();
+        assert_eq!(hits.len(), 2);
+
+        // The vector ranking lifts the semantically closest chunk even without shared words.
+        let hits = store.search("animals", Some(&[0.1, 0.9]), 1, None).unwrap();
+        assert_eq!(hits[0].text, "Pets are allowed with approval");
+
+        let hits = store
+            .search("rent", None, 5, Some("/docs/other.md"))
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].title, "/docs/other.md");
+        assert!(store
+            .search("rent", None, 5, Some("missing"))
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -1,14 +1,29 @@
+pub mod admin_audit;
+pub mod at_rest;
 pub mod audit;
+pub mod chat_archive;
+pub mod chat_import;
 pub mod contacts;
+pub mod context_snapshots;
+pub mod cron_history;
+pub mod documents;
 pub mod memory;
 pub mod memory_contract;
 pub mod memory_service;
+pub mod privacy;
 pub mod rabitq_index;
+pub mod receipts;
+pub mod redaction;
 pub mod retriever;
 pub mod session;
+pub mod skill_stats;
+pub mod team_memory;
+pub mod telemetry;
+pub mod timeseries;
+pub mod todos;
 pub mod vector;
 
 pub use audit::{AuditEvent, AuditLogger};
 pub use contacts::{ChannelContact, ChannelContacts};
 pub use memory::{MemoryStore, MemoryStoreOptions};
-pub use session::SessionStore;
+pub use session::{SessionStore, SessionSummary};
@@ -8,6 +8,7 @@ use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use tracing::{debug, info, warn};
 
+use crate::redaction::{Redactor, NS_MEMORY, NS_SESSION_SUMMARY};
 use crate::retriever::HybridMemoryRetriever;
 use crate::vector::{VectorMeta, VectorRuntime};
 
@@ -143,6 +144,8 @@ struct PendingVectorSync {
 #[derive(Clone, Default)]
 pub struct MemoryStoreOptions {
     pub vector: Option<Arc<VectorRuntime>>,
+    /// PII redaction applied to title/content/summary before writes.
+    pub redactor: Option<Arc<Redactor>>,
 }
 
 /// SQLite-backed memory store with FTS5 full-text search.
@@ -152,6 +155,7 @@ pub struct MemoryStore {
     #[allow(dead_code)]
     db_path: PathBuf,
     pub(crate) vector: Option<Arc<VectorRuntime>>,
+    redactor: Option<Arc<Redactor>>,
 }
 
 impl MemoryStore {
@@ -167,7 +171,7 @@ impl MemoryStore {
             })?;
         }
 
-        let conn = Connection::open(db_path).map_err(|e| {
+        let conn = crate::at_rest::open_database(db_path).map_err(|e| {
             blockcell_core::Error::Storage(format!("Failed to open memory db: {}", e))
         })?;
 
@@ -178,6 +182,7 @@ impl MemoryStore {
             inner: Arc::new(Mutex::new(conn)),
             db_path: db_path.to_path_buf(),
             vector: options.vector,
+            redactor: options.redactor,
         };
         store.init_schema()?;
         Ok(store)
@@ -275,6 +280,7 @@ impl MemoryStore {
     /// Upsert a memory item. If dedup_key is set and a matching non-deleted item exists,
     /// update it instead of inserting a new one.
     pub fn upsert(&self, params: UpsertParams) -> Result<MemoryItem> {
+        let params = self.redact_params(params);
         let item = {
             let conn = self
                 .inner
@@ -418,6 +424,99 @@ impl MemoryStore {
         Ok(item)
     }
 
+    fn redact_params(&self, mut params: UpsertParams) -> UpsertParams {
+        let Some(redactor) = self.redactor.as_deref() else {
+            return params;
+        };
+        let namespace = if params.item_type == "session_summary" {
+            NS_SESSION_SUMMARY
+        } else {
+            NS_MEMORY
+        };
+        if !redactor.is_active(namespace) {
+            return params;
+        }
+        params.content = redactor.redact(namespace, &params.content);
+        params.title = params.title.map(|t| redactor.redact(namespace, &t));
+        params.summary = params.summary.map(|s| redactor.redact(namespace, &s));
+        params
+    }
+
+    /// IDs of every stored item (optionally including soft-deleted ones), oldest first.
+    pub fn list_ids(&self, include_deleted: bool) -> Result<Vec<String>> {
+        let conn = self
+            .inner
+            .lock()
+            .map_err(|e| blockcell_core::Error::Storage(format!("Lock error: {}", e)))?;
+        let sql = if include_deleted {
+            "SELECT id FROM memory_items ORDER BY created_at"
+        } else {
+            "SELECT id FROM memory_items WHERE deleted_at IS NULL ORDER BY created_at"
+        };
+        let mut stmt = conn
+            .prepare(sql)
+            .map_err(|e| blockcell_core::Error::Storage(format!("Query error: {}", e)))?;
+        let ids = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| blockcell_core::Error::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(ids)
+    }
+
+    /// `(id, session_key, updated_at)` for every item recorded from a session,
+    /// including soft-deleted ones (privacy purge and retention).
+    pub fn session_bound_items(&self) -> Result<Vec<(String, String, String)>> {
+        let conn = self
+            .inner
+            .lock()
+            .map_err(|e| blockcell_core::Error::Storage(format!("Lock error: {}", e)))?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, session_key, updated_at FROM memory_items
+                 WHERE session_key IS NOT NULL AND session_key != ''",
+            )
+            .map_err(|e| blockcell_core::Error::Storage(format!("Query error: {}", e)))?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })
+            .map_err(|e| blockcell_core::Error::Storage(format!("Query error: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(rows)
+    }
+
+    /// Permanently delete items, bypassing the recycle bin. Returns rows removed.
+    pub fn hard_delete(&self, ids: &[String]) -> Result<usize> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        let removed = {
+            let conn = self
+                .inner
+                .lock()
+                .map_err(|e| blockcell_core::Error::Storage(format!("Lock error: {}", e)))?;
+            let placeholders = (0..ids.len())
+                .map(|offset| format!("?{}", offset + 1))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let sql = format!("DELETE FROM memory_items WHERE id IN ({})", placeholders);
+            let values: Vec<&dyn rusqlite::types::ToSql> = ids
+                .iter()
+                .map(|id| id as &dyn rusqlite::types::ToSql)
+                .collect();
+            conn.execute(&sql, values.as_slice())
+                .map_err(|e| blockcell_core::Error::Storage(format!("Hard delete error: {}", e)))?
+        };
+        self.sync_vector_delete_ids(ids);
+        Ok(removed)
+    }
+
     /// Query memory items using FTS5 + structured filters + scoring.
     pub fn query(&self, params: &QueryParams) -> Result<Vec<MemoryResult>> {
         let results = HybridMemoryRetriever::new(self).search(params)?;
@@ -425,6 +524,13 @@ impl MemoryStore {
         Ok(results)
     }
 
+    /// Query memory items by vector similarity alone (`memory.vector` must be enabled).
+    pub fn semantic_query(&self, params: &QueryParams) -> Result<Vec<MemoryResult>> {
+        let results = HybridMemoryRetriever::new(self).semantic_search(params)?;
+        self.record_accesses(&results)?;
+        Ok(results)
+    }
+
     /// Get a single item by ID.
     pub fn get_by_id(&self, id: &str) -> Result<Option<MemoryItem>> {
         let conn = self
@@ -1369,11 +1475,11 @@ impl MemoryStore {
 
         let mut brief = String::new();
 
-        // Long-term items: highest importance, use summary if available
+        // Long-term and team items: highest importance, use summary if available
         let mut stmt = conn
             .prepare(
                 "SELECT id, title, summary, content, type, importance FROM memory_items
-             WHERE scope = 'long_term' AND deleted_at IS NULL
+             WHERE scope IN ('long_term', 'team') AND deleted_at IS NULL
                AND (expires_at IS NULL OR expires_at > ?1)
              ORDER BY importance DESC, access_count DESC, updated_at DESC
              LIMIT ?2",
@@ -1827,10 +1933,10 @@ fn format_relevant_brief_item(item: &MemoryItem) -> String {
                 })
         })
         .unwrap_or_else(|| truncate_chars(item.content.trim(), 120));
-    let scope_tag = if item.scope == "long_term" {
-        "LT"
-    } else {
-        "ST"
+    let scope_tag = match item.scope.as_str() {
+        "long_term" => "LT",
+        "team" => "TEAM",
+        _ => "ST",
     };
     format!("- [{}|{}] {}", item.item_type, scope_tag, display)
 }
@@ -2110,8 +2216,14 @@ mod tests {
     fn test_store_with_vector(vector: Option<Arc<VectorRuntime>>) -> (MemoryStore, TempDir) {
         let dir = TempDir::new().unwrap();
         let db_path = dir.path().join("memory.db");
-        let store =
-            MemoryStore::open_with_options(&db_path, MemoryStoreOptions { vector }).unwrap();
+        let store = MemoryStore::open_with_options(
+            &db_path,
+            MemoryStoreOptions {
+                vector,
+                ..Default::default()
+            },
+        )
+        .unwrap();
         (store, dir)
     }
 
@@ -2122,6 +2234,51 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_upsert_redacts_pii_when_configured() {
+        let dir = TempDir::new().unwrap();
+        let redactor = Redactor::new(&blockcell_core::config::RedactionConfig {
+            enabled: true,
+            ..Default::default()
+        });
+        let store = MemoryStore::open_with_options(
+            &dir.path().join("memory.db"),
+            MemoryStoreOptions {
+                redactor: Some(Arc::new(redactor)),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let item = store
+            .upsert(UpsertParams {
+                scope: "long_term".to_string(),
+                item_type: "contact".to_string(),
+                title: Some("Bob bob@example.com".to_string()),
+                content: "Bob's phone is 13812345678".to_string(),
+                summary: None,
+                tags: vec![],
+                source: "user".to_string(),
+                channel: None,
+                session_key: None,
+                importance: 0.5,
+                dedup_key: None,
+                expires_at: None,
+            })
+            .unwrap();
+        assert_eq!(item.title.as_deref(), Some("Bob [REDACTED_EMAIL]"));
+        assert_eq!(item.content, "Bob's phone is [REDACTED_PHONE]");
+
+        store
+            .upsert_session_summary("cli:1", "Reach me at carol@example.org")
+            .unwrap();
+        assert_eq!(
+            store.get_session_summary("cli:1").unwrap().as_deref(),
+            Some("Reach me at [REDACTED_EMAIL]")
+        );
+        assert_eq!(store.list_ids(false).unwrap().len(), 2);
+    }
+
     #[test]
     fn test_upsert_and_query() {
         let (store, _dir) = test_store();
@@ -2443,7 +2600,7 @@ Language: Chinese
         assert_eq!(
             *embedder.document_inputs.lock().unwrap(),
             vec![
-                "Title: favorite database\nSummary: Prefers LanceDB\nTags: vector, database"
+                "Title: favorite database\nSummary: Prefers RabitQ\nTags: vector, database"
                     .to_string()
             ]
         );
@@ -2667,6 +2824,7 @@ Language: Chinese
             &db_path,
             MemoryStoreOptions {
                 vector: Some(failing_runtime),
+                ..Default::default()
             },
         )
         .unwrap();
@@ -2700,6 +2858,7 @@ Language: Chinese
             &db_path,
             MemoryStoreOptions {
                 vector: Some(retry_runtime),
+                ..Default::default()
             },
         )
         .unwrap();
@@ -2733,6 +2892,7 @@ Language: Chinese
             &db_path,
             MemoryStoreOptions {
                 vector: Some(failing_runtime),
+                ..Default::default()
             },
         )
         .unwrap();
@@ -2767,6 +2927,7 @@ Language: Chinese
             &db_path,
             MemoryStoreOptions {
                 vector: Some(retry_runtime),
+                ..Default::default()
             },
         )
         .unwrap();
@@ -2983,4 +3144,66 @@ Language: Chinese
             vec!["semantic match".to_string()]
         );
     }
+
+    #[test]
+    fn test_semantic_query_ranks_by_vector_hits_only() {
+        let embedder = FakeEmbedder::new(3);
+        let index = FakeVectorIndex::with_hits(vec![]);
+        let runtime = fake_vector_runtime(embedder.clone(), index.clone());
+        let (store, _dir) = test_store_with_vector(Some(runtime));
+
+        let upsert = |title: &str, content: &str| {
+            store
+                .upsert(UpsertParams {
+                    scope: "long_term".to_string(),
+                    item_type: "fact".to_string(),
+                    title: Some(title.to_string()),
+                    content: content.to_string(),
+                    summary: None,
+                    tags: vec![],
+                    source: "user".to_string(),
+                    channel: None,
+                    session_key: None,
+                    importance: 0.5,
+                    dedup_key: None,
+                    expires_at: None,
+                })
+                .unwrap()
+        };
+        let coffee = upsert("coffee", "Prefers flat white in the morning");
+        let keyword = upsert("morning drink", "morning drink keyword match");
+        let tea = upsert("tea", "Green tea after lunch");
+
+        index.state.lock().unwrap().search_hits = vec![
+            VectorHit {
+                id: tea.id.clone(),
+                score: 0.71,
+            },
+            VectorHit {
+                id: coffee.id.clone(),
+                score: 0.93,
+            },
+        ];
+
+        let results = store
+            .semantic_query(&QueryParams {
+                query: Some("morning drink".to_string()),
+                top_k: 5,
+                ..Default::default()
+            })
+            .unwrap();
+        let ids: Vec<&str> = results.iter().map(|r| r.item.id.as_str()).collect();
+        assert_eq!(ids, vec![coffee.id.as_str(), tea.id.as_str()]);
+        assert!(!ids.contains(&keyword.id.as_str()));
+        assert!((results[0].score - 0.93).abs() < 1e-9);
+
+        let (plain, _plain_dir) = test_store_with_vector(None);
+        assert!(plain
+            .semantic_query(&QueryParams {
+                query: Some("morning drink".to_string()),
+                top_k: 5,
+                ..Default::default()
+            })
+            .is_err());
+    }
 }
@@ -0,0 +1,748 @@
+//! Per-sender data purge and per-channel retention (`security.privacy`).
+//!
+//! Sessions are keyed by `channel:chat_id`, so a sender's data is found through
+//! their direct-message sessions (chat id == sender id) plus any session whose
+//! metadata lists them in `senders`, which the runtime records on every turn.
+//! A purge deletes those sessions, hard-deletes memory items recorded from them,
+//! anonymizes (or drops) their audit entries, removes artifacts they produced and
+//! writes an HMAC-signed report that never contains the raw sender id.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use blockcell_core::config::PrivacyConfig;
+use blockcell_core::ingest::hmac_sha256;
+use blockcell_core::secrets::SecretStore;
+use blockcell_core::{session_file_stem, session_id_from_file_stem, Error, Paths, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+
+use crate::{MemoryStore, SessionStore};
+
+/// Secret holding the HMAC key used to sign purge reports.
+pub const SIGNING_KEY_SECRET: &str = "privacy.signing_key";
+/// Cap on artifact paths remembered per session.
+const MAX_TRACKED_ARTIFACTS: usize = 500;
+
+/// Remember that `sender_id` spoke in this session (session metadata).
+pub fn record_sender(metadata: &mut Value, sender_id: &str) {
+    let sender_id = sender_id.trim();
+    if sender_id.is_empty() {
+        return;
+    }
+    push_unique(metadata, "senders", sender_id, usize::MAX);
+}
+
+/// Remember files a session produced so a purge can delete them.
+pub fn record_artifacts<'a>(metadata: &mut Value, paths: impl IntoIterator<Item = &'a str>) {
+    for path in paths {
+        if !path.trim().is_empty() {
+            push_unique(metadata, "artifacts", path.trim(), MAX_TRACKED_ARTIFACTS);
+        }
+    }
+}
+
+fn push_unique(metadata: &mut Value, field: &str, value: &str, cap: usize) {
+    if !metadata.is_object() {
+        *metadata = Value::Object(serde_json::Map::new());
+    }
+    let Some(map) = metadata.as_object_mut() else {
+        return;
+    };
+    let entry = map
+        .entry(field.to_string())
+        .or_insert_with(|| Value::Array(Vec::new()));
+    if !entry.is_array() {
+        *entry = Value::Array(Vec::new());
+    }
+    if let Some(items) = entry.as_array_mut() {
+        if items.len() < cap && !items.iter().any(|v| v.as_str() == Some(value)) {
+            items.push(Value::String(value.to_string()));
+        }
+    }
+}
+
+/// Stable pseudonym for an identifier (first 16 hex chars of SHA-256).
+pub fn pseudonym(value: &str) -> String {
+    let digest = Sha256::digest(value.as_bytes());
+    digest
+        .iter()
+        .take(8)
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditMode {
+    Anonymize,
+    Delete,
+}
+
+impl AuditMode {
+    pub fn parse(raw: &str) -> Result<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "" | "anonymize" => Ok(AuditMode::Anonymize),
+            "delete" => Ok(AuditMode::Delete),
+            other => Err(Error::Validation(format!(
+                "Unknown audit mode '{}': use 'anonymize' or 'delete'",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PurgeRequest {
+    pub sender_id: String,
+    /// Restrict the purge to one channel.
+    pub channel: Option<String>,
+    pub dry_run: bool,
+    pub audit_mode: AuditMode,
+    pub delete_artifacts: bool,
+}
+
+impl PurgeRequest {
+    pub fn new(sender_id: &str, cfg: &PrivacyConfig) -> Result<Self> {
+        let sender_id = sender_id.trim();
+        if sender_id.is_empty() {
+            return Err(Error::Validation("sender id is required".to_string()));
+        }
+        Ok(Self {
+            sender_id: sender_id.to_string(),
+            channel: None,
+            dry_run: false,
+            audit_mode: AuditMode::parse(&cfg.audit_mode)?,
+            delete_artifacts: cfg.delete_artifacts,
+        })
+    }
+}
+
+/// Result of a purge. Identifiers are pseudonymized so the report itself can be
+/// kept as evidence without retaining the data it describes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PurgeReport {
+    pub report_id: String,
+    pub sender: String,
+    pub channel: Option<String>,
+    pub dry_run: bool,
+    pub started_at: String,
+    pub finished_at: String,
+    pub sessions: Vec<String>,
+    pub messages: usize,
+    pub memory_items: usize,
+    pub audit_mode: AuditMode,
+    pub audit_entries: usize,
+    pub artifacts: usize,
+    pub errors: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+impl PurgeReport {
+    fn signing_payload(&self) -> Result<Vec<u8>> {
+        let mut unsigned = self.clone();
+        unsigned.signature = None;
+        Ok(serde_json::to_vec(&unsigned)?)
+    }
+
+    /// Sign with HMAC-SHA256 (`hmac-sha256:<hex>`).
+    pub fn sign(&mut self, key: &[u8]) -> Result<()> {
+        let mac = hmac_sha256(key, &self.signing_payload()?);
+        self.signature = Some(format!("hmac-sha256:{}", to_hex(&mac)));
+        Ok(())
+    }
+
+    pub fn verify(&self, key: &[u8]) -> bool {
+        let Some(sig) = self.signature.as_deref() else {
+            return false;
+        };
+        let Ok(payload) = self.signing_payload() else {
+            return false;
+        };
+        let expected = format!("hmac-sha256:{}", to_hex(&hmac_sha256(key, &payload)));
+        expected.len() == sig.len()
+            && expected
+                .bytes()
+                .zip(sig.bytes())
+                .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+                == 0
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Load the report signing key from the secrets store, creating it on first use.
+pub fn signing_key(paths: &Paths) -> Result<Vec<u8>> {
+    let mut store = SecretStore::open(paths)?;
+    if let Some(key) = store.get(SIGNING_KEY_SECRET) {
+        return Ok(key.as_bytes().to_vec());
+    }
+    let key = format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    store.set(SIGNING_KEY_SECRET, &key)?;
+    Ok(key.into_bytes())
+}
+
+/// Write a signed report to `<base>/privacy/purge-<id>.json`.
+pub fn save_report(paths: &Paths, report: &PurgeReport) -> Result<PathBuf> {
+    let dir = paths.privacy_reports_dir();
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("purge-{}.json", report.report_id));
+    std::fs::write(&path, serde_json::to_string_pretty(report)?)?;
+    Ok(path)
+}
+
+struct StoredSession {
+    stem: String,
+    updated_at: Option<DateTime<Utc>>,
+    metadata: Value,
+    messages: usize,
+}
+
+impl StoredSession {
+    fn channel(&self) -> &str {
+        self.stem.split('_').next().unwrap_or_default()
+    }
+}
+
+fn list_sessions(store: &SessionStore) -> Result<Vec<StoredSession>> {
+    let (summaries, _) = store.list(0, usize::MAX)?;
+    let mut sessions = summaries
+        .into_iter()
+        .map(|s| {
+            Ok(StoredSession {
+                updated_at: DateTime::from_timestamp_millis(s.updated_at_ms),
+                metadata: store.load_metadata(&s.stem)?,
+                messages: s.message_count,
+                stem: s.stem,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    sessions.sort_by(|a, b| a.stem.cmp(&b.stem));
+    Ok(sessions)
+}
+
+fn session_belongs_to(session: &StoredSession, sender_id: &str) -> bool {
+    if session_id_from_file_stem(&session.stem) == session_file_stem(sender_id) {
+        return true;
+    }
+    session
+        .metadata
+        .get("senders")
+        .and_then(|v| v.as_array())
+        .is_some_and(|senders| senders.iter().any(|s| s.as_str() == Some(sender_id)))
+}
+
+/// What to remove for one batch of sessions.
+struct Removal<'a> {
+    sessions: Vec<&'a StoredSession>,
+    audit_mode: AuditMode,
+    delete_artifacts: bool,
+    dry_run: bool,
+}
+
+#[derive(Default)]
+struct RemovalCounts {
+    messages: usize,
+    memory_items: usize,
+    audit_entries: usize,
+    artifacts: usize,
+    errors: Vec<String>,
+}
+
+fn remove_sessions(
+    paths: &Paths,
+    store: &SessionStore,
+    memory: Option<&MemoryStore>,
+    removal: &Removal<'_>,
+) -> RemovalCounts {
+    let mut counts = RemovalCounts::default();
+    let stems: HashSet<&str> = removal.sessions.iter().map(|s| s.stem.as_str()).collect();
+    if stems.is_empty() {
+        return counts;
+    }
+
+    if let Some(store) = memory {
+        match store.session_bound_items() {
+            Ok(items) => {
+                let ids: Vec<String> = items
+                    .into_iter()
+                    .filter(|(_, key, _)| stems.contains(session_file_stem(key).as_str()))
+                    .map(|(id, _, _)| id)
+                    .collect();
+                counts.memory_items = ids.len();
+                if !removal.dry_run {
+                    if let Err(e) = store.hard_delete(&ids) {
+                        counts.errors.push(format!("memory: {}", e));
+                    }
+                }
+            }
+            Err(e) => counts.errors.push(format!("memory: {}", e)),
+        }
+    }
+
+    match rewrite_audit_logs(paths, &stems, removal.audit_mode, removal.dry_run) {
+        Ok(n) => counts.audit_entries = n,
+        Err(e) => counts.errors.push(format!("audit: {}", e)),
+    }
+
+    for session in &removal.sessions {
+        counts.messages += session.messages;
+        if removal.delete_artifacts {
+            for artifact in session_artifacts(paths, session) {
+                counts.artifacts += 1;
+                if !removal.dry_run {
+                    if let Err(e) = std::fs::remove_file(&artifact) {
+                        counts.errors.push(format!("artifact: {}", e));
+                    }
+                }
+            }
+        }
+        if !removal.dry_run {
+            if let Err(e) = store.clear(&session.stem) {
+                counts.errors.push(format!("session: {}", e));
+            }
+        }
+    }
+
+    if !removal.dry_run {
+        if let Err(e) = remove_session_names(paths, &stems) {
+            counts.errors.push(format!("session names: {}", e));
+        }
+    }
+    counts
+}
+
+/// Tracked artifact files that still exist inside the workspace or media dir.
+fn session_artifacts(paths: &Paths, session: &StoredSession) -> Vec<PathBuf> {
+    let roots: Vec<PathBuf> = [paths.workspace(), paths.media_dir()]
+        .iter()
+        .filter_map(|r| r.canonicalize().ok())
+        .collect();
+    session
+        .metadata
+        .get("artifacts")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str())
+        .filter_map(|p| {
+            let path = PathBuf::from(p);
+            let path = if path.is_absolute() {
+                path
+            } else {
+                paths.workspace().join(path)
+            };
+            path.canonicalize().ok()
+        })
+        .filter(|p| p.is_file() && roots.iter().any(|r| p.starts_with(r)))
+        .collect()
+}
+
+/// Anonymize or drop audit entries whose session is in `stems`. Returns entries hit.
+fn rewrite_audit_logs(
+    paths: &Paths,
+    stems: &HashSet<&str>,
+    mode: AuditMode,
+    dry_run: bool,
+) -> Result<usize> {
+    let Ok(entries) = std::fs::read_dir(paths.audit_dir()) else {
+        return Ok(0);
+    };
+    let mut total = 0;
+    for path in entries.flatten().map(|e| e.path()) {
+        if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+            continue;
+        }
+        let content = std::fs::read_to_string(&path)?;
+        let mut hits = 0;
+        let mut out = String::with_capacity(content.len());
+        for line in content.lines() {
+            let Ok(mut event) = serde_json::from_str::<Value>(line) else {
+                out.push_str(line);
+                out.push('\n');
+                continue;
+            };
+            let matched = event
+                .get("session_key")
+                .and_then(|k| k.as_str())
+                .is_some_and(|k| stems.contains(session_file_stem(k).as_str()));
+            if !matched {
+                out.push_str(line);
+                out.push('\n');
+                continue;
+            }
+            hits += 1;
+            if mode == AuditMode::Delete {
+                continue;
+            }
+            anonymize_audit_event(&mut event);
+            out.push_str(&serde_json::to_string(&event)?);
+            out.push('\n');
+        }
+        if hits > 0 && !dry_run {
+            std::fs::write(&path, out)?;
+        }
+        total += hits;
+    }
+    Ok(total)
+}
+
+fn anonymize_audit_event(event: &mut Value) {
+    let Some(map) = event.as_object_mut() else {
+        return;
+    };
+    if let Some(key) = map.get("session_key").and_then(|k| k.as_str()) {
+        let hashed = format!("purged:{}", pseudonym(key));
+        map.insert("session_key".to_string(), Value::String(hashed));
+    }
+    for field in ["params", "result", "reason", "error"] {
+        if let Some(value) = map.get_mut(field) {
+            // Keep the JSON type so the entry still parses as an `AuditEvent`.
+            *value = if value.is_string() {
+                Value::String("[purged]".to_string())
+            } else {
+                serde_json::json!({ "purged": true })
+            };
+        }
+    }
+}
+
+/// Drop display names (the first user message) of purged sessions from `_meta.json`.
+fn remove_session_names(paths: &Paths, stems: &HashSet<&str>) -> Result<()> {
+    let meta_path = paths.sessions_dir().join("_meta.json");
+    if !meta_path.exists() {
+        return Ok(());
+    }
+    let mut meta: serde_json::Map<String, Value> =
+        serde_json::from_str(&std::fs::read_to_string(&meta_path)?).unwrap_or_default();
+    let before = meta.len();
+    meta.retain(|key, _| {
+        !stems
+            .iter()
+            .any(|stem| key == stem || session_id_from_file_stem(stem) == *key)
+    });
+    if meta.len() != before {
+        std::fs::write(&meta_path, serde_json::to_string_pretty(&meta)?)?;
+    }
+    Ok(())
+}
+
+/// Delete or anonymize everything tied to `req.sender_id` and return the report
+/// (unsigned; see [`PurgeReport::sign`]).
+pub fn purge_sender(
+    paths: &Paths,
+    memory: Option<&MemoryStore>,
+    req: &PurgeRequest,
+) -> Result<PurgeReport> {
+    let started_at = Utc::now().to_rfc3339();
+    let store = SessionStore::new(paths.clone());
+    let sessions = list_sessions(&store)?;
+    let matched: Vec<&StoredSession> = sessions
+        .iter()
+        .filter(|s| req.channel.as_deref().is_none_or(|c| s.channel() == c))
+        .filter(|s| session_belongs_to(s, &req.sender_id))
+        .collect();
+
+    let counts = remove_sessions(
+        paths,
+        &store,
+        memory,
+        &Removal {
+            sessions: matched.clone(),
+            audit_mode: req.audit_mode,
+            delete_artifacts: req.delete_artifacts,
+            dry_run: req.dry_run,
+        },
+    );
+
+    let report = PurgeReport {
+        report_id: uuid::Uuid::new_v4().to_string(),
+        sender: pseudonym(&req.sender_id),
+        channel: req.channel.clone(),
+        dry_run: req.dry_run,
+        started_at,
+        finished_at: Utc::now().to_rfc3339(),
+        sessions: matched.iter().map(|s| pseudonym(&s.stem)).collect(),
+        messages: counts.messages,
+        memory_items: counts.memory_items,
+        audit_mode: req.audit_mode,
+        audit_entries: counts.audit_entries,
+        artifacts: counts.artifacts,
+        errors: counts.errors,
+        signature: None,
+    };
+    info!(
+        sender = %report.sender,
+        sessions = report.sessions.len(),
+        memory_items = report.memory_items,
+        audit_entries = report.audit_entries,
+        dry_run = report.dry_run,
+        "Privacy purge completed"
+    );
+    Ok(report)
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RetentionReport {
+    pub sessions: usize,
+    pub messages: usize,
+    pub memory_items: usize,
+    pub audit_entries: usize,
+    pub artifacts: usize,
+}
+
+/// Remove sessions (and the data tied to them) that have been idle longer than
+/// their channel's retention window. Channels without a window are untouched.
+pub fn enforce_retention(
+    paths: &Paths,
+    memory: Option<&MemoryStore>,
+    cfg: &PrivacyConfig,
+) -> Result<RetentionReport> {
+    if cfg.retention_days.is_empty() {
+        return Ok(RetentionReport::default());
+    }
+    let now = Utc::now();
+    let store = SessionStore::new(paths.clone());
+    let sessions = list_sessions(&store)?;
+    let expired: Vec<&StoredSession> = sessions
+        .iter()
+        .filter(|s| {
+            let (Some(days), Some(updated_at)) = (cfg.retention_for(s.channel()), s.updated_at)
+            else {
+                return false;
+            };
+            now - updated_at > chrono::Duration::days(days as i64)
+        })
+        .collect();
+    if expired.is_empty() {
+        return Ok(RetentionReport::default());
+    }
+
+    let counts = remove_sessions(
+        paths,
+        &store,
+        memory,
+        &Removal {
+            sessions: expired.clone(),
+            audit_mode: AuditMode::parse(&cfg.audit_mode)?,
+            delete_artifacts: cfg.delete_artifacts,
+            dry_run: false,
+        },
+    );
+    for err in &counts.errors {
+        warn!(error = %err, "Retention cleanup error");
+    }
+    let report = RetentionReport {
+        sessions: expired.len(),
+        messages: counts.messages,
+        memory_items: counts.memory_items,
+        audit_entries: counts.audit_entries,
+        artifacts: counts.artifacts,
+    };
+    info!(sessions = report.sessions, "Retention cleanup completed");
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::UpsertParams;
+    use crate::session::SESSIONS_DB;
+    use crate::AuditLogger;
+    use blockcell_core::types::ChatMessage;
+
+    fn has_session(paths: &Paths, session_key: &str) -> bool {
+        !SessionStore::new(paths.clone())
+            .load(session_key)
+            .unwrap()
+            .is_empty()
+    }
+
+    fn memory_params(session_key: &str, content: &str) -> UpsertParams {
+        UpsertParams {
+            scope: "short_term".into(),
+            item_type: "note".into(),
+            title: None,
+            content: content.into(),
+            summary: None,
+            tags: vec![],
+            source: "user".into(),
+            channel: Some("telegram".into()),
+            session_key: Some(session_key.into()),
+            importance: 0.5,
+            dedup_key: None,
+            expires_at: None,
+        }
+    }
+
+    fn seed(paths: &Paths) -> MemoryStore {
+        let sessions = SessionStore::new(paths.clone());
+        let msgs = vec![
+            ChatMessage::user("hi, I'm Bob"),
+            ChatMessage::assistant("hello"),
+        ];
+        sessions.save("telegram:42", &msgs).unwrap();
+        sessions.save("telegram:77", &msgs).unwrap();
+        // Group chat where 42 spoke.
+        let mut meta = serde_json::json!({});
+        record_sender(&mut meta, "42");
+        record_sender(&mut meta, "42");
+        assert_eq!(meta["senders"], serde_json::json!(["42"]));
+        sessions
+            .save_with_metadata("slack:team-room", &msgs, &meta)
+            .unwrap();
+
+        let mut audit = AuditLogger::new(paths.clone());
+        audit
+            .log_tool_call(
+                "web_search",
+                serde_json::json!({"q": "bob"}),
+                serde_json::json!("ok"),
+                "telegram:42",
+                None,
+                None,
+            )
+            .unwrap();
+        audit
+            .log_tool_call(
+                "web_search",
+                serde_json::json!({"q": "x"}),
+                serde_json::json!("ok"),
+                "telegram:77",
+                None,
+                None,
+            )
+            .unwrap();
+
+        let store = MemoryStore::open(&paths.memory_dir().join("memory.db")).unwrap();
+        store
+            .upsert(memory_params("telegram:42", "Bob likes tea"))
+            .unwrap();
+        store
+            .upsert(memory_params("telegram:77", "Someone else"))
+            .unwrap();
+        store
+    }
+
+    #[test]
+    fn test_purge_sender_removes_sessions_memory_and_audit() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = Paths::with_base(dir.path().to_path_buf());
+        let store = seed(&paths);
+
+        let mut req = PurgeRequest::new("42", &PrivacyConfig::default()).unwrap();
+        req.dry_run = true;
+        let preview = purge_sender(&paths, Some(&store), &req).unwrap();
+        assert_eq!(preview.sessions.len(), 2);
+        assert!(has_session(&paths, "telegram:42"));
+
+        req.dry_run = false;
+        let mut report = purge_sender(&paths, Some(&store), &req).unwrap();
+        assert_eq!(report.sessions.len(), 2);
+        assert_eq!(report.messages, 4);
+        assert_eq!(report.memory_items, 1);
+        assert_eq!(report.audit_entries, 1);
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert!(!has_session(&paths, "telegram:42"));
+        assert!(!has_session(&paths, "slack:team-room"));
+        assert!(has_session(&paths, "telegram:77"));
+        assert_eq!(store.session_bound_items().unwrap().len(), 1);
+
+        let audit = AuditLogger::new(paths.clone()).read_today().unwrap();
+        let serialized = serde_json::to_string(&audit).unwrap();
+        assert!(!serialized.contains("telegram:42"));
+        assert!(!serialized.contains("bob"));
+        assert!(serialized.contains("telegram:77"));
+
+        // The report never carries the raw id, and the signature covers its content.
+        assert_ne!(report.sender, "42");
+        report.sign(b"k").unwrap();
+        assert!(report.verify(b"k"));
+        assert!(!report.verify(b"other"));
+        report.memory_items = 0;
+        assert!(!report.verify(b"k"));
+    }
+
+    #[test]
+    fn test_purge_scoped_to_channel_and_audit_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = Paths::with_base(dir.path().to_path_buf());
+        let store = seed(&paths);
+
+        let mut req = PurgeRequest::new("42", &PrivacyConfig::default()).unwrap();
+        req.channel = Some("slack".into());
+        req.audit_mode = AuditMode::Delete;
+        let report = purge_sender(&paths, Some(&store), &req).unwrap();
+        assert_eq!(report.sessions.len(), 1);
+        assert!(has_session(&paths, "telegram:42"));
+        assert!(PurgeRequest::new("  ", &PrivacyConfig::default()).is_err());
+        assert!(AuditMode::parse("shred").is_err());
+    }
+
+    #[test]
+    fn test_purge_deletes_tracked_artifacts_inside_workspace_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = Paths::with_base(dir.path().join("base"));
+        std::fs::create_dir_all(paths.workspace()).unwrap();
+        let inside = paths.workspace().join("report.pdf");
+        let outside = dir.path().join("keep.txt");
+        std::fs::write(&inside, "x").unwrap();
+        std::fs::write(&outside, "x").unwrap();
+
+        let mut meta = serde_json::json!({});
+        record_artifacts(
+            &mut meta,
+            [inside.to_str().unwrap(), outside.to_str().unwrap()],
+        );
+        SessionStore::new(paths.clone())
+            .save_with_metadata("ws:42", &[ChatMessage::user("hi")], &meta)
+            .unwrap();
+
+        let req = PurgeRequest::new("42", &PrivacyConfig::default()).unwrap();
+        let report = purge_sender(&paths, None, &req).unwrap();
+        assert_eq!(report.artifacts, 1);
+        assert!(!inside.exists());
+        assert!(outside.exists());
+    }
+
+    #[test]
+    fn test_retention_per_channel() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = Paths::with_base(dir.path().to_path_buf());
+        let store = seed(&paths);
+        // Age the telegram sessions.
+        let old = DateTime::parse_from_rfc3339("2020-01-01T00:00:00Z").unwrap();
+        rusqlite::Connection::open(paths.sessions_dir().join(SESSIONS_DB))
+            .unwrap()
+            .execute(
+                "UPDATE sessions SET updated_at = ?1 WHERE stem LIKE 'telegram_%'",
+                [old.timestamp_millis()],
+            )
+            .unwrap();
+
+        let mut cfg = PrivacyConfig::default();
+        assert_eq!(
+            enforce_retention(&paths, Some(&store), &cfg)
+                .unwrap()
+                .sessions,
+            0
+        );
+        cfg.retention_days.insert("telegram".into(), 30);
+        cfg.retention_days.insert("slack".into(), 0);
+        let report = enforce_retention(&paths, Some(&store), &cfg).unwrap();
+        assert_eq!(report.sessions, 2);
+        assert_eq!(report.memory_items, 2);
+        assert!(has_session(&paths, "slack:team-room"));
+        assert_eq!(cfg.retention_for("slack"), None);
+    }
+}
@@ -0,0 +1,271 @@
+//! Receipt/invoice ledger behind the `receipts` tool.
+//!
+//! Each row is one normalized document (vendor, date, totals, line items) extracted from a
+//! photo or PDF. Amounts are stored as REAL in the document's own currency; dates are
+//! `YYYY-MM-DD` so a month is a simple prefix match.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use blockcell_core::{Error, Result};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+fn db_err(e: rusqlite::Error) -> Error {
+    Error::Storage(format!("receipts: {}", e))
+}
+
+/// Location of the receipt ledger inside an agent workspace.
+pub fn db_path(workspace: &Path) -> PathBuf {
+    workspace.join("receipts.db")
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LineItem {
+    pub description: String,
+    #[serde(default)]
+    pub quantity: Option<f64>,
+    #[serde(default)]
+    pub unit_price: Option<f64>,
+    #[serde(default)]
+    pub amount: Option<f64>,
+}
+
+/// A normalized receipt or invoice as produced by extraction.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReceiptRecord {
+    pub vendor: String,
+    /// `YYYY-MM-DD`.
+    pub date: String,
+    #[serde(default)]
+    pub currency: String,
+    #[serde(default)]
+    pub subtotal: Option<f64>,
+    #[serde(default)]
+    pub tax: Option<f64>,
+    pub total: f64,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub invoice_number: Option<String>,
+    #[serde(default)]
+    pub line_items: Vec<LineItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Receipt {
+    pub id: String,
+    #[serde(flatten)]
+    pub record: ReceiptRecord,
+    /// File the record was extracted from.
+    pub source_path: Option<String>,
+    pub created_at: i64,
+}
+
+#[derive(Clone)]
+pub struct ReceiptStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+const COLUMNS: &str = "id, vendor, date, currency, subtotal, tax, total, category, \
+     invoice_number, line_items, source_path, created_at";
+
+fn row_to_receipt(row: &Row<'_>) -> rusqlite::Result<Receipt> {
+    let items: String = row.get(9)?;
+    Ok(Receipt {
+        id: row.get(0)?,
+        record: ReceiptRecord {
+            vendor: row.get(1)?,
+            date: row.get(2)?,
+            currency: row.get(3)?,
+            subtotal: row.get(4)?,
+            tax: row.get(5)?,
+            total: row.get(6)?,
+            category: row.get(7)?,
+            invoice_number: row.get(8)?,
+            line_items: serde_json::from_str(&items).unwrap_or_default(),
+        },
+        source_path: row.get(10)?,
+        created_at: row.get(11)?,
+    })
+}
+
+impl ReceiptStore {
+    pub fn open(db_path: &Path) -> Result<Self> {
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(db_path).map_err(db_err)?;
+        conn.execute_batch("PRAGMA journal_mode=WAL;").ok();
+        Self::init(conn)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory().map_err(db_err)?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            "
+            CREATE TABLE IF NOT EXISTS receipts (
+                id TEXT PRIMARY KEY,
+                vendor TEXT NOT NULL,
+                date TEXT NOT NULL,
+                currency TEXT NOT NULL DEFAULT '',
+                subtotal REAL,
+                tax REAL,
+                total REAL NOT NULL,
+                category TEXT,
+                invoice_number TEXT,
+                line_items TEXT NOT NULL DEFAULT '[]',
+                source_path TEXT,
+                created_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_receipts_date ON receipts(date);
+            ",
+        )
+        .map_err(db_err)?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>> {
+        self.conn
+            .lock()
+            .map_err(|e| Error::Storage(format!("Lock error: {}", e)))
+    }
+
+    pub fn insert(&self, record: ReceiptRecord, source_path: Option<String>) -> Result<Receipt> {
+        if record.vendor.trim().is_empty() {
+            return Err(Error::Validation(
+                "Receipt vendor must not be empty".to_string(),
+            ));
+        }
+        if chrono::NaiveDate::parse_from_str(&record.date, "%Y-%m-%d").is_err() {
+            return Err(Error::Validation(format!(
+                "Invalid receipt date '{}'. Use YYYY-MM-DD",
+                record.date
+            )));
+        }
+        let receipt = Receipt {
+            id: format!(
+                "rcpt_{}",
+                uuid::Uuid::new_v4()
+                    .to_string()
+                    .split('-')
+                    .next()
+                    .unwrap_or("x")
+            ),
+            record,
+            source_path,
+            created_at: Utc::now().timestamp_millis(),
+        };
+        let r = &receipt.record;
+        self.lock()?
+            .execute(
+                &format!(
+                    "INSERT INTO receipts ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                    COLUMNS
+                ),
+                params![
+                    receipt.id,
+                    r.vendor.trim(),
+                    r.date,
+                    r.currency,
+                    r.subtotal,
+                    r.tax,
+                    r.total,
+                    r.category,
+                    r.invoice_number,
+                    serde_json::to_string(&r.line_items)?,
+                    receipt.source_path,
+                    receipt.created_at
+                ],
+            )
+            .map_err(db_err)?;
+        Ok(receipt)
+    }
+
+    pub fn get(&self, id: &str) -> Result<Option<Receipt>> {
+        self.lock()?
+            .query_row(
+                &format!("SELECT {} FROM receipts WHERE id = ?1", COLUMNS),
+                params![id],
+                row_to_receipt,
+            )
+            .optional()
+            .map_err(db_err)
+    }
+
+    pub fn remove(&self, id: &str) -> Result<bool> {
+        let n = self
+            .lock()?
+            .execute("DELETE FROM receipts WHERE id = ?1", params![id])
+            .map_err(db_err)?;
+        Ok(n > 0)
+    }
+
+    /// Receipts dated in `month` (`YYYY-MM`), or all of them, oldest first.
+    pub fn list(&self, month: Option<&str>, limit: usize) -> Result<Vec<Receipt>> {
+        let conn = self.lock()?;
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM receipts WHERE (?1 IS NULL OR date LIKE ?1 || '-%') \
+                 ORDER BY date, created_at LIMIT {}",
+                COLUMNS, limit
+            ))
+            .map_err(db_err)?;
+        let rows = stmt
+            .query_map(params![month], row_to_receipt)
+            .map_err(db_err)?;
+        rows.collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(db_err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(vendor: &str, date: &str, total: f64) -> ReceiptRecord {
+        ReceiptRecord {
+            vendor: vendor.to_string(),
+            date: date.to_string(),
+            currency: "EUR".to_string(),
+            total,
+            line_items: vec![LineItem {
+                description: "Coffee".to_string(),
+                quantity: Some(2.0),
+                unit_price: Some(1.5),
+                amount: Some(3.0),
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_insert_list_by_month_and_remove() {
+        let store = ReceiptStore::open_in_memory().unwrap();
+        let march = store
+            .insert(record("Cafe", "2026-03-14", 3.0), Some("a.jpg".into()))
+            .unwrap();
+        store
+            .insert(record("Hotel", "2026-04-01", 120.0), None)
+            .unwrap();
+        assert!(store.insert(record("", "2026-03-01", 1.0), None).is_err());
+        assert!(store
+            .insert(record("Shop", "14/03/2026", 1.0), None)
+            .is_err());
+
+        let listed = store.list(Some("2026-03"), 50).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0], march);
+        assert_eq!(listed[0].record.line_items.len(), 1);
+        assert_eq!(store.list(None, 50).unwrap().len(), 2);
+
+        assert!(store.remove(&march.id).unwrap());
+        assert!(store.get(&march.id).unwrap().is_none());
+    }
+}
@@ -0,0 +1,356 @@
+//! PII redaction applied before text is persisted (`security.redaction`).
+//!
+//! Namespaces group what is being written — memory items, session summaries and
+//! audit logs — so each can have its own detectors. Built-in detectors are regex
+//! based; an optional external NER command can flag names and other entities the
+//! regexes cannot see.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use blockcell_core::config::RedactionConfig;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde_json::Value;
+use tracing::warn;
+
+pub const NS_MEMORY: &str = "memory";
+pub const NS_SESSION_SUMMARY: &str = "session_summary";
+pub const NS_AUDIT: &str = "audit";
+
+pub const BUILTIN_DETECTORS: &[&str] = &["credit_card", "id_number", "email", "phone"];
+
+static EMAIL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}")
+        .expect("email regex")
+});
+static PHONE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?:\+\d{1,3}[\s-]?)?(?:\(\d{2,4}\)[\s-]?)?\b\d{3,4}[\s-]\d{3,4}[\s-]\d{3,4}\b|(?:\+?86[\s-]?)?\b1[3-9]\d{9}\b",
+    )
+    .expect("phone regex")
+});
+static ID_NUMBER: Lazy<Regex> = Lazy::new(|| {
+    // Mainland China resident ID (18 chars) and US SSN.
+    Regex::new(
+        r"\b[1-9]\d{5}(?:19|20)\d{2}(?:0[1-9]|1[0-2])(?:0[1-9]|[12]\d|3[01])\d{3}[\dXx]\b|\b\d{3}-\d{2}-\d{4}\b",
+    )
+    .expect("id number regex")
+});
+static CARD_CANDIDATE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\b(?:\d[ -]?){12,18}\d\b").expect("card regex"));
+
+fn luhn_valid(candidate: &str) -> bool {
+    let digits: Vec<u32> = candidate.chars().filter_map(|c| c.to_digit(10)).collect();
+    if !(13..=19).contains(&digits.len()) {
+        return false;
+    }
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, d)| {
+            if i % 2 == 1 {
+                let doubled = d * 2;
+                if doubled > 9 {
+                    doubled - 9
+                } else {
+                    doubled
+                }
+            } else {
+                *d
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+fn placeholder(detector: &str) -> String {
+    format!("[REDACTED_{}]", detector.to_ascii_uppercase())
+}
+
+/// Detector hits found by [`Redactor::scan`], keyed by detector name.
+pub type Findings = BTreeMap<String, usize>;
+
+pub struct Redactor {
+    cfg: RedactionConfig,
+    custom: Vec<(String, Regex)>,
+}
+
+impl Redactor {
+    pub fn new(cfg: &RedactionConfig) -> Self {
+        let custom = cfg
+            .custom_patterns
+            .iter()
+            .filter_map(|p| match Regex::new(&p.pattern) {
+                Ok(re) => Some((p.name.clone(), re)),
+                Err(e) => {
+                    warn!(name = %p.name, error = %e, "Invalid redaction pattern, skipped");
+                    None
+                }
+            })
+            .collect();
+        Self {
+            cfg: cfg.clone(),
+            custom,
+        }
+    }
+
+    /// Whether anything is redacted in `namespace`.
+    pub fn is_active(&self, namespace: &str) -> bool {
+        self.cfg
+            .namespaces
+            .get(namespace)
+            .and_then(|ns| ns.enabled)
+            .unwrap_or(self.cfg.enabled)
+    }
+
+    fn detectors_for(&self, namespace: &str) -> &[String] {
+        self.cfg
+            .namespaces
+            .get(namespace)
+            .and_then(|ns| ns.detectors.as_deref())
+            .unwrap_or(&self.cfg.detectors)
+    }
+
+    fn ner_enabled(&self, namespace: &str) -> bool {
+        self.cfg.ner_command.is_some()
+            && self
+                .cfg
+                .namespaces
+                .get(namespace)
+                .and_then(|ns| ns.ner)
+                .unwrap_or(true)
+    }
+
+    /// Mask PII in `text` according to the namespace policy.
+    pub fn redact(&self, namespace: &str, text: &str) -> String {
+        if !self.is_active(namespace) || text.is_empty() {
+            return text.to_string();
+        }
+        let mut findings = Findings::new();
+        let mut out = self.apply(self.detectors_for(namespace), text, &mut findings, true);
+        if self.ner_enabled(namespace) {
+            out = self.apply_ner(&out, &mut findings);
+        }
+        out
+    }
+
+    /// Redact every string in a JSON value in place (audit params/results).
+    pub fn redact_json(&self, namespace: &str, value: &mut Value) {
+        if !self.is_active(namespace) {
+            return;
+        }
+        match value {
+            Value::String(s) => *s = self.redact(namespace, s),
+            Value::Array(items) => items
+                .iter_mut()
+                .for_each(|v| self.redact_json(namespace, v)),
+            Value::Object(map) => map
+                .values_mut()
+                .for_each(|v| self.redact_json(namespace, v)),
+            _ => {}
+        }
+    }
+
+    /// Count PII hits without modifying anything, using every built-in and custom
+    /// detector regardless of whether redaction is enabled (for `memory audit-pii`).
+    pub fn scan(&self, text: &str) -> Findings {
+        let all: Vec<String> = BUILTIN_DETECTORS.iter().map(|d| d.to_string()).collect();
+        let mut findings = Findings::new();
+        self.apply(&all, text, &mut findings, false);
+        findings
+    }
+
+    fn apply(
+        &self,
+        detectors: &[String],
+        text: &str,
+        findings: &mut Findings,
+        mask_custom: bool,
+    ) -> String {
+        let mut out = text.to_string();
+        // Fixed order: card/ID numbers before phones so long numbers are not half-masked.
+        for name in BUILTIN_DETECTORS {
+            if !detectors.iter().any(|d| d == name) {
+                continue;
+            }
+            let re: &Regex = match *name {
+                "credit_card" => &CARD_CANDIDATE,
+                "id_number" => &ID_NUMBER,
+                "email" => &EMAIL,
+                _ => &PHONE,
+            };
+            let mut hits = 0;
+            let replaced = re.replace_all(&out, |caps: &Captures| {
+                let m = &caps[0];
+                if *name == "credit_card" && !luhn_valid(m) {
+                    return m.to_string();
+                }
+                hits += 1;
+                placeholder(name)
+            });
+            if hits > 0 {
+                out = replaced.into_owned();
+                *findings.entry(name.to_string()).or_default() += hits;
+            }
+        }
+        for (name, re) in &self.custom {
+            let hits = re.find_iter(&out).count();
+            if hits > 0 {
+                if mask_custom {
+                    out = re
+                        .replace_all(&out, placeholder(name).as_str())
+                        .into_owned();
+                }
+                *findings.entry(name.clone()).or_default() += hits;
+            }
+        }
+        out
+    }
+
+    fn apply_ner(&self, text: &str, findings: &mut Findings) -> String {
+        let Some(command) = self.cfg.ner_command.as_deref() else {
+            return text.to_string();
+        };
+        let entities = match run_ner(command, text) {
+            Ok(entities) => entities,
+            Err(e) => {
+                warn!(error = %e, "NER redaction command failed; regex redaction only");
+                return text.to_string();
+            }
+        };
+        let mut out = text.to_string();
+        for entity in entities.iter().filter(|e| e.chars().count() >= 2) {
+            let hits = out.matches(entity.as_str()).count();
+            if hits > 0 {
+                out = out.replace(entity.as_str(), &placeholder("entity"));
+                *findings.entry("ner".to_string()).or_default() += hits;
+            }
+        }
+        out
+    }
+}
+
+fn run_ner(command: &str, text: &str) -> std::io::Result<Vec<String>> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(std::io::Error::other(format!(
+            "exit status {}",
+            output.status
+        )));
+    }
+    Ok(parse_ner_output(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn parse_ner_output(stdout: &str) -> Vec<String> {
+    let Ok(Value::Array(items)) = serde_json::from_str::<Value>(stdout.trim()) else {
+        return Vec::new();
+    };
+    items
+        .into_iter()
+        .filter_map(|item| match item {
+            Value::String(s) => Some(s),
+            Value::Object(o) => o.get("text").and_then(|t| t.as_str()).map(String::from),
+            _ => None,
+        })
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blockcell_core::config::{RedactionNamespacePolicy, RedactionPattern};
+
+    fn enabled() -> RedactionConfig {
+        RedactionConfig {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_redacts_builtin_detectors() {
+        let r = Redactor::new(&enabled());
+        let text = "Mail alice.w@example.co.uk or call 13812345678 / +1 415-555-2671. \
+                    ID 110105199003078512, SSN 123-45-6789, card 4111 1111 1111 1111, \
+                    order 1234567890123 on 2024-01-01.";
+        let out = r.redact(NS_MEMORY, text);
+        assert!(out.contains("[REDACTED_EMAIL]"));
+        assert!(!out.contains("13812345678"));
+        assert!(!out.contains("555-2671"));
+        assert!(!out.contains("110105199003078512"));
+        assert!(!out.contains("123-45-6789"));
+        assert!(out.contains("[REDACTED_CREDIT_CARD]"));
+        // Not a valid Luhn number, and dates are left alone.
+        assert!(out.contains("1234567890123"));
+        assert!(out.contains("2024-01-01"));
+        // Idempotent.
+        assert_eq!(r.redact(NS_MEMORY, &out), out);
+    }
+
+    #[test]
+    fn test_namespace_policy_and_custom_patterns() {
+        let mut cfg = enabled();
+        cfg.custom_patterns.push(RedactionPattern {
+            name: "employee_id".into(),
+            pattern: r"\bEMP-\d{5}\b".into(),
+        });
+        cfg.namespaces.insert(
+            NS_AUDIT.into(),
+            RedactionNamespacePolicy {
+                detectors: Some(vec!["email".into()]),
+                ..Default::default()
+            },
+        );
+        cfg.namespaces.insert(
+            NS_SESSION_SUMMARY.into(),
+            RedactionNamespacePolicy {
+                enabled: Some(false),
+                ..Default::default()
+            },
+        );
+        let r = Redactor::new(&cfg);
+        let text = "bob@example.com 13812345678 EMP-12345";
+        assert_eq!(
+            r.redact(NS_AUDIT, text),
+            "[REDACTED_EMAIL] 13812345678 [REDACTED_EMPLOYEE_ID]"
+        );
+        assert_eq!(r.redact(NS_SESSION_SUMMARY, text), text);
+
+        let mut value = serde_json::json!({"to": ["bob@example.com"], "n": 1});
+        r.redact_json(NS_AUDIT, &mut value);
+        assert_eq!(value["to"][0], "[REDACTED_EMAIL]");
+    }
+
+    #[test]
+    fn test_scan_ignores_enabled_flag() {
+        let r = Redactor::new(&RedactionConfig::default());
+        let text = "a@b.io, c@d.io, 13812345678";
+        assert_eq!(r.redact(NS_MEMORY, text), text);
+        let findings = r.scan(text);
+        assert_eq!(findings.get("email"), Some(&2));
+        assert_eq!(findings.get("phone"), Some(&1));
+    }
+
+    #[test]
+    fn test_parse_ner_output() {
+        let out =
+            parse_ner_output(r#"["Zhang San", {"text": "Acme Corp", "label": "ORG"}, 3, ""]"#);
+        assert_eq!(out, vec!["Zhang San", "Acme Corp"]);
+        assert!(parse_ner_output("not json").is_empty());
+    }
+}
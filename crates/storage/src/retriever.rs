@@ -1,6 +1,6 @@
 use crate::memory::{sanitize_fts_query, MemoryResult, MemoryStore, QueryParams};
 use crate::vector::VectorHit;
-use blockcell_core::Result;
+use blockcell_core::{Error, Result};
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 use tracing::warn;
@@ -80,6 +80,47 @@ impl<'a> HybridMemoryRetriever<'a> {
         Ok(results)
     }
 
+    /// Vector similarity only. Unlike [`search`](Self::search), embedding or index
+    /// failures are returned instead of falling back to keyword search.
+    pub fn semantic_search(&self, params: &QueryParams) -> Result<Vec<MemoryResult>> {
+        let runtime = self.store.vector.as_ref().ok_or_else(|| {
+            Error::Storage("Semantic search needs memory.vector to be enabled".to_string())
+        })?;
+        let query = params.query.as_deref().map(str::trim).unwrap_or_default();
+        if query.is_empty() {
+            return Err(Error::Storage("Semantic search needs a query".to_string()));
+        }
+        if params.top_k == 0 {
+            return Ok(Vec::new());
+        }
+
+        let vector = runtime.embedder.embed_query(query)?;
+        let hits = runtime
+            .index
+            .search(&vector, candidate_window(params.top_k))?;
+        let scores: HashMap<String, f64> =
+            hits.into_iter().map(|hit| (hit.id, hit.score)).collect();
+        if scores.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids: Vec<String> = scores.keys().cloned().collect();
+        let mut results: Vec<MemoryResult> = self
+            .store
+            .load_items_by_ids(&ids)?
+            .into_iter()
+            .filter(|item| self.store.item_matches_query(item, params))
+            .map(|item| {
+                let score = scores.get(&item.id).copied().unwrap_or_default();
+                MemoryResult { item, score }
+            })
+            .collect();
+
+        results.sort_by(compare_results);
+        results.truncate(params.top_k);
+        Ok(results)
+    }
+
     fn search_vector_candidates(&self, query: &str, top_k: usize) -> Vec<VectorHit> {
         let Some(runtime) = self.store.vector.as_ref() else {
             return Vec::new();
@@ -1,10 +1,20 @@
 use blockcell_core::types::ChatMessage;
-use blockcell_core::{session_file_stem, session_id_from_file_stem, Paths, Result};
+use blockcell_core::{session_file_stem, session_id_from_file_stem, Error, Paths, Result};
+use rusqlite::{params, Connection, OptionalExtension, Transaction, TransactionBehavior};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::fs::{File, OpenOptions};
-use std::io::{BufRead, BufReader, Write};
-use tracing::debug;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use crate::at_rest;
+
+/// Database file inside the sessions directory.
+pub(crate) const SESSIONS_DB: &str = "_sessions.db";
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(tag = "_type")]
@@ -20,200 +30,380 @@ enum SessionLine {
     Message(ChatMessage),
 }
 
+/// Chat sessions in SQLite (`sessions/_sessions.db`).
+///
+/// Sessions are keyed by the file stem of their session key (`ws:chat-1` → `ws_chat-1`),
+/// the name the `.jsonl` session files of earlier versions used. Those files are imported
+/// when the store is first used and removed once their session is committed. Message and
+/// metadata values are sealed with the storage key (see [`at_rest`]) the same way the
+/// session file lines were.
 pub struct SessionStore {
     paths: Paths,
+    conn: Mutex<Option<Connection>>,
 }
 
 impl SessionStore {
     pub fn new(paths: Paths) -> Self {
-        Self { paths }
-    }
-
-    pub fn load(&self, session_key: &str) -> Result<Vec<ChatMessage>> {
-        let path = self.paths.session_file(session_key);
-
-        if !path.exists() {
-            return Ok(Vec::new());
+        Self {
+            paths,
+            conn: Mutex::new(None),
         }
+    }
 
-        let file = File::open(&path)?;
-        let reader = BufReader::new(file);
-        let mut messages = Vec::new();
-
-        for line in reader.lines() {
-            let line = line?;
-            if line.trim().is_empty() {
-                continue;
-            }
+    /// Run `f` on the connection, opening the database on first use.
+    fn with_conn<T>(&self, f: impl FnOnce(&mut Connection) -> Result<T>) -> Result<T> {
+        let mut guard = self
+            .conn
+            .lock()
+            .map_err(|e| Error::Storage(format!("Lock error: {}", e)))?;
+        let conn = match &mut *guard {
+            Some(conn) => conn,
+            slot => slot.insert(open_sessions_db(&self.paths.sessions_dir())?),
+        };
+        f(conn)
+    }
 
-            match serde_json::from_str::<SessionLine>(&line) {
-                Ok(SessionLine::Message(msg)) => {
-                    messages.push(msg);
-                }
-                Ok(SessionLine::Metadata { .. }) => {
-                    // Skip metadata line
-                }
-                Err(e) => {
-                    debug!(error = %e, "Failed to parse session line, skipping");
-                }
-            }
-        }
+    pub fn load(&self, session_key: &str) -> Result<Vec<ChatMessage>> {
+        let stem = session_file_stem(session_key);
+        self.with_conn(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT message FROM messages WHERE stem = ?1 ORDER BY seq")
+                .map_err(db_err)?;
+            let lines = stmt
+                .query_map(params![stem], |row| row.get::<_, String>(0))
+                .map_err(db_err)?
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(db_err)?;
+            Ok(lines
+                .iter()
+                .filter_map(|line| parse_message(line))
+                .collect())
+        })
+    }
 
-        Ok(messages)
+    /// Up to `limit` messages starting at message `offset`, plus the total message
+    /// count. Only the requested rows are read and decrypted.
+    pub fn load_page(
+        &self,
+        session_key: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<ChatMessage>, usize)> {
+        let stem = session_file_stem(session_key);
+        self.with_conn(|conn| {
+            let total: i64 = conn
+                .query_row(
+                    "SELECT message_count FROM sessions WHERE stem = ?1",
+                    params![stem],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(db_err)?
+                .unwrap_or(0);
+            let mut stmt = conn
+                .prepare(
+                    "SELECT message FROM messages WHERE stem = ?1
+                     ORDER BY seq LIMIT ?2 OFFSET ?3",
+                )
+                .map_err(db_err)?;
+            let lines = stmt
+                .query_map(
+                    params![
+                        stem,
+                        i64::try_from(limit).unwrap_or(-1),
+                        i64::try_from(offset).unwrap_or(i64::MAX)
+                    ],
+                    |row| row.get::<_, String>(0),
+                )
+                .map_err(db_err)?
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(db_err)?;
+            let messages = lines
+                .iter()
+                .filter_map(|line| parse_message(line))
+                .collect();
+            Ok((messages, total as usize))
+        })
     }
 
     pub fn load_metadata(&self, session_key: &str) -> Result<Value> {
-        let path = self.paths.session_file(session_key);
-
-        if !path.exists() {
-            return Ok(Value::Object(serde_json::Map::new()));
-        }
-
-        Ok(self
-            .read_metadata_line(&path)
-            .map(|(_, metadata)| metadata)
+        let stem = session_file_stem(session_key);
+        let sealed: Option<String> = self.with_conn(|conn| {
+            conn.query_row(
+                "SELECT metadata FROM sessions WHERE stem = ?1",
+                params![stem],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(db_err)
+        })?;
+        Ok(sealed
+            .as_deref()
+            .and_then(at_rest::open_line)
+            .and_then(|m| serde_json::from_str::<Value>(&m).ok())
+            .filter(Value::is_object)
             .unwrap_or_else(|| Value::Object(serde_json::Map::new())))
     }
 
+    /// Replace the messages of a session, keeping its `created_at` and metadata.
     pub fn save(&self, session_key: &str, messages: &[ChatMessage]) -> Result<()> {
-        let path = self.paths.session_file(session_key);
-
-        // Ensure directory exists
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
-
-        let now = chrono::Utc::now().to_rfc3339();
-
-        // 保留原始 created_at：若文件已存在则从第一行读取，否则使用当前时间
-        let (created_at, metadata) = if path.exists() {
-            self.read_metadata_line(&path)
-                .unwrap_or_else(|| (now.clone(), Value::Object(serde_json::Map::new())))
-        } else {
-            (now.clone(), Value::Object(serde_json::Map::new()))
-        };
-
-        self.write_session_file(&path, &created_at, &now, messages, &metadata)
+        self.write_session(session_key, messages, None)
     }
 
+    /// Replace the messages and the metadata of a session, keeping its `created_at`.
     pub fn save_with_metadata(
         &self,
         session_key: &str,
         messages: &[ChatMessage],
         metadata: &Value,
     ) -> Result<()> {
-        let path = self.paths.session_file(session_key);
-
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
-
-        let now = chrono::Utc::now().to_rfc3339();
-        let created_at = if path.exists() {
-            self.read_created_at(&path).unwrap_or_else(|| now.clone())
-        } else {
-            now.clone()
-        };
-
-        self.write_session_file(&path, &created_at, &now, messages, metadata)
-    }
-
-    /// 从 session 文件第一行读取 created_at 字段。
-    fn read_created_at(&self, path: &std::path::Path) -> Option<String> {
-        self.read_metadata_line(path)
-            .map(|(created_at, _)| created_at)
+        self.write_session(session_key, messages, Some(metadata))
     }
 
-    fn read_metadata_line(&self, path: &std::path::Path) -> Option<(String, Value)> {
-        let file = File::open(path).ok()?;
-        let mut reader = BufReader::new(file);
-        let mut first_line = String::new();
-        reader.read_line(&mut first_line).ok()?;
-        let line: SessionLine = serde_json::from_str(first_line.trim()).ok()?;
-        match line {
-            SessionLine::Metadata {
-                created_at,
-                metadata,
-                ..
-            } => Some((created_at, metadata)),
-            _ => None,
-        }
+    fn write_session(
+        &self,
+        session_key: &str,
+        messages: &[ChatMessage],
+        metadata: Option<&Value>,
+    ) -> Result<()> {
+        let stem = session_file_stem(session_key);
+        let metadata = metadata
+            .map(|m| serde_json::to_string(m).map(|m| at_rest::seal_line(&m).into_owned()))
+            .transpose()?;
+        let lines = messages
+            .iter()
+            .map(|m| serde_json::to_string(m).map(|m| at_rest::seal_line(&m).into_owned()))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let now = chrono::Utc::now();
+        self.with_conn(|conn| {
+            let tx = conn
+                .transaction_with_behavior(TransactionBehavior::Immediate)
+                .map_err(db_err)?;
+            tx.execute(
+                "INSERT INTO sessions
+                 (stem, session_id, created_at, updated_at, metadata, message_count)
+                 VALUES (?1, ?2, ?3, ?4, COALESCE(?5, '{}'), ?6)
+                 ON CONFLICT(stem) DO UPDATE SET
+                     updated_at = excluded.updated_at,
+                     message_count = excluded.message_count,
+                     metadata = COALESCE(?5, metadata)",
+                params![
+                    stem,
+                    session_id_from_file_stem(&stem),
+                    now.to_rfc3339(),
+                    now.timestamp_millis(),
+                    metadata,
+                    lines.len() as i64
+                ],
+            )
+            .map_err(db_err)?;
+            tx.execute("DELETE FROM messages WHERE stem = ?1", params![stem])
+                .map_err(db_err)?;
+            insert_messages(&tx, &stem, 0, &lines)?;
+            tx.commit().map_err(db_err)
+        })
     }
 
     pub fn append(&self, session_key: &str, message: &ChatMessage) -> Result<()> {
-        let path = self.paths.session_file(session_key);
+        let stem = session_file_stem(session_key);
+        let line = at_rest::seal_line(&serde_json::to_string(message)?).into_owned();
+        let now = chrono::Utc::now();
+        self.with_conn(|conn| {
+            // IMMEDIATE so a concurrent append cannot take the same sequence number.
+            let tx = conn
+                .transaction_with_behavior(TransactionBehavior::Immediate)
+                .map_err(db_err)?;
+            tx.execute(
+                "INSERT OR IGNORE INTO sessions
+                 (stem, session_id, created_at, updated_at, metadata, message_count)
+                 VALUES (?1, ?2, ?3, ?4, '{}', 0)",
+                params![
+                    stem,
+                    session_id_from_file_stem(&stem),
+                    now.to_rfc3339(),
+                    now.timestamp_millis()
+                ],
+            )
+            .map_err(db_err)?;
+            let next: i64 = tx
+                .query_row(
+                    "SELECT message_count FROM sessions WHERE stem = ?1",
+                    params![stem],
+                    |row| row.get(0),
+                )
+                .map_err(db_err)?;
+            insert_messages(&tx, &stem, next, std::slice::from_ref(&line))?;
+            tx.execute(
+                "UPDATE sessions SET message_count = ?2, updated_at = ?3 WHERE stem = ?1",
+                params![stem, next + 1, now.timestamp_millis()],
+            )
+            .map_err(db_err)?;
+            tx.commit().map_err(db_err)
+        })
+    }
 
-        // Ensure directory exists
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)?;
+    /// Clear session history by deleting the session.
+    /// Returns true if the session existed and was deleted, false if it didn't exist.
+    pub fn clear(&self, session_key: &str) -> Result<bool> {
+        let stem = session_file_stem(session_key);
+        let deleted = self.with_conn(|conn| {
+            let tx = conn.transaction().map_err(db_err)?;
+            tx.execute("DELETE FROM messages WHERE stem = ?1", params![stem])
+                .map_err(db_err)?;
+            let deleted = tx
+                .execute("DELETE FROM sessions WHERE stem = ?1", params![stem])
+                .map_err(db_err)?;
+            tx.commit().map_err(db_err)?;
+            Ok(deleted > 0)
+        })?;
+        if deleted {
+            debug!(session_key = %session_key, "Session deleted");
         }
+        Ok(deleted)
+    }
 
-        // 使用 create_new 原子性地判断文件是否为首次创建，消除 TOCTOU 竞态
-        let is_new = OpenOptions::new()
-            .write(true)
-            .create_new(true)
-            .open(&path)
-            .map(|mut f| {
-                let now = chrono::Utc::now().to_rfc3339();
-                let metadata = SessionLine::Metadata {
-                    created_at: now.clone(),
-                    updated_at: now,
-                    metadata: serde_json::Value::Object(serde_json::Map::new()),
-                };
-                // 写入 metadata 行；若失败忽略（后续 append 仍可工作）
-                let _ = writeln!(
-                    f,
-                    "{}",
-                    serde_json::to_string(&metadata).unwrap_or_default()
-                );
-                true
-            })
-            .unwrap_or(false);
-        let _ = is_new; // 仅用于首次写入 metadata，无需后续使用
-
-        // Append message
-        let mut file = OpenOptions::new().append(true).open(&path)?;
-        writeln!(file, "{}", serde_json::to_string(message)?)?;
-
-        Ok(())
+    /// Up to `limit` sessions from `offset`, most recently updated first, plus the total.
+    pub fn list(&self, offset: usize, limit: usize) -> Result<(Vec<SessionSummary>, usize)> {
+        self.with_conn(|conn| {
+            let total: i64 = conn
+                .query_row("SELECT COUNT(*) FROM sessions", [], |row| row.get(0))
+                .map_err(db_err)?;
+            let mut stmt = conn
+                .prepare(
+                    "SELECT stem, session_id, created_at, updated_at, message_count FROM sessions
+                     ORDER BY updated_at DESC, stem LIMIT ?1 OFFSET ?2",
+                )
+                .map_err(db_err)?;
+            let sessions = stmt
+                .query_map(
+                    params![
+                        i64::try_from(limit).unwrap_or(-1),
+                        i64::try_from(offset).unwrap_or(i64::MAX)
+                    ],
+                    |row| {
+                        Ok(SessionSummary {
+                            stem: row.get(0)?,
+                            session_id: row.get(1)?,
+                            created_at: row.get(2)?,
+                            updated_at_ms: row.get(3)?,
+                            message_count: row.get::<_, i64>(4)? as usize,
+                        })
+                    },
+                )
+                .map_err(db_err)?
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(db_err)?;
+            Ok((sessions, total as usize))
+        })
     }
 
-    fn write_session_file(
-        &self,
-        path: &std::path::Path,
-        created_at: &str,
-        updated_at: &str,
-        messages: &[ChatMessage],
-        metadata: &Value,
-    ) -> Result<()> {
-        let mut file = File::create(path)?;
+    /// Stems of all stored sessions, for resolving a session id to its key.
+    pub fn stems(&self) -> Result<Vec<String>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT stem FROM sessions ORDER BY stem")
+                .map_err(db_err)?;
+            let stems = stmt
+                .query_map([], |row| row.get(0))
+                .map_err(db_err)?
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(db_err)?;
+            Ok(stems)
+        })
+    }
 
-        let metadata_line = SessionLine::Metadata {
-            created_at: created_at.to_string(),
-            updated_at: updated_at.to_string(),
-            metadata: metadata.clone(),
+    /// The session in the `.jsonl` layout earlier versions stored it in (a metadata line,
+    /// then one line per message), decrypted. `None` if there is no such session.
+    pub fn export_jsonl(&self, session_key: &str) -> Result<Option<String>> {
+        let stem = session_file_stem(session_key);
+        let times: Option<(String, i64)> = self.with_conn(|conn| {
+            conn.query_row(
+                "SELECT created_at, updated_at FROM sessions WHERE stem = ?1",
+                params![stem],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .map_err(db_err)
+        })?;
+        let Some((created_at, updated_at_ms)) = times else {
+            return Ok(None);
         };
-        writeln!(file, "{}", serde_json::to_string(&metadata_line)?)?;
-
-        for msg in messages {
-            writeln!(file, "{}", serde_json::to_string(msg)?)?;
+        let metadata = SessionLine::Metadata {
+            created_at,
+            updated_at: chrono::DateTime::from_timestamp_millis(updated_at_ms)
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_default(),
+            metadata: self.load_metadata(session_key)?,
+        };
+        let mut out = serde_json::to_string(&metadata)?;
+        out.push('\n');
+        for message in self.load(session_key)? {
+            out.push_str(&serde_json::to_string(&message)?);
+            out.push('\n');
         }
-
-        Ok(())
+        Ok(Some(out))
     }
 
-    /// Clear session history by deleting the session file.
-    /// Returns true if the file existed and was deleted, false if it didn't exist.
-    pub fn clear(&self, session_key: &str) -> Result<bool> {
-        let path = self.paths.session_file(session_key);
-
-        if path.exists() {
-            std::fs::remove_file(&path)?;
-            debug!(session_key = %session_key, path = %path.display(), "Session file deleted");
-            Ok(true)
-        } else {
-            Ok(false)
+    /// Seal message and metadata values stored while encryption was off. Returns the
+    /// number of sessions changed.
+    pub fn seal_plaintext(&self) -> Result<usize> {
+        if !at_rest::is_unlocked() {
+            return Ok(0);
         }
+        self.with_conn(|conn| {
+            let tx = conn.transaction().map_err(db_err)?;
+            let mut changed = HashSet::new();
+            let messages: Vec<(String, i64, String)> = {
+                let mut stmt = tx
+                    .prepare(
+                        "SELECT stem, seq, message FROM messages
+                         WHERE substr(message, 1, length(?1)) != ?1",
+                    )
+                    .map_err(db_err)?;
+                let rows = stmt
+                    .query_map(params![at_rest::LINE_PREFIX], |row| {
+                        Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+                    })
+                    .map_err(db_err)?
+                    .collect::<std::result::Result<_, _>>()
+                    .map_err(db_err)?;
+                rows
+            };
+            for (stem, seq, message) in messages {
+                tx.execute(
+                    "UPDATE messages SET message = ?3 WHERE stem = ?1 AND seq = ?2",
+                    params![stem, seq, at_rest::seal_line(&message)],
+                )
+                .map_err(db_err)?;
+                changed.insert(stem);
+            }
+            let metadata: Vec<(String, String)> = {
+                let mut stmt = tx
+                    .prepare(
+                        "SELECT stem, metadata FROM sessions
+                         WHERE substr(metadata, 1, length(?1)) != ?1",
+                    )
+                    .map_err(db_err)?;
+                let rows = stmt
+                    .query_map(params![at_rest::LINE_PREFIX], |row| {
+                        Ok((row.get(0)?, row.get(1)?))
+                    })
+                    .map_err(db_err)?
+                    .collect::<std::result::Result<_, _>>()
+                    .map_err(db_err)?;
+                rows
+            };
+            for (stem, value) in metadata {
+                tx.execute(
+                    "UPDATE sessions SET metadata = ?2 WHERE stem = ?1",
+                    params![stem, at_rest::seal_line(&value)],
+                )
+                .map_err(db_err)?;
+                changed.insert(stem);
+            }
+            tx.commit().map_err(db_err)?;
+            Ok(changed.len())
+        })
     }
 
     /// Set session display name in _meta.json, only if not already set.
@@ -267,6 +457,203 @@ impl SessionStore {
     }
 }
 
+fn db_err(e: rusqlite::Error) -> Error {
+    Error::Storage(format!("session store: {}", e))
+}
+
+/// One stored session.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionSummary {
+    /// File stem of the session key, e.g. `ws_chat-1`.
+    pub stem: String,
+    pub session_id: String,
+    pub created_at: String,
+    /// Time of the last write, in milliseconds.
+    pub updated_at_ms: i64,
+    pub message_count: usize,
+}
+
+fn open_sessions_db(sessions_dir: &Path) -> Result<Connection> {
+    std::fs::create_dir_all(sessions_dir)?;
+    let mut conn = Connection::open(sessions_dir.join(SESSIONS_DB)).map_err(db_err)?;
+    conn.execute_batch("PRAGMA journal_mode=WAL;").ok();
+    conn.busy_timeout(Duration::from_secs(5)).ok();
+    conn.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS sessions (
+            stem TEXT PRIMARY KEY,
+            session_id TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at INTEGER NOT NULL,
+            metadata TEXT NOT NULL,
+            message_count INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_sessions_session_id ON sessions(session_id);
+        CREATE INDEX IF NOT EXISTS idx_sessions_updated_at ON sessions(updated_at);
+        CREATE TABLE IF NOT EXISTS messages (
+            stem TEXT NOT NULL,
+            seq INTEGER NOT NULL,
+            message TEXT NOT NULL,
+            PRIMARY KEY (stem, seq)
+        );
+        ",
+    )
+    .map_err(db_err)?;
+    let imported = import_session_files(&mut conn, sessions_dir)?;
+    if imported > 0 {
+        info!(imported, "Imported session files into the session database");
+    }
+    Ok(conn)
+}
+
+fn insert_messages(
+    tx: &Transaction<'_>,
+    stem: &str,
+    first_seq: i64,
+    lines: &[String],
+) -> Result<()> {
+    let mut stmt = tx
+        .prepare("INSERT INTO messages (stem, seq, message) VALUES (?1, ?2, ?3)")
+        .map_err(db_err)?;
+    for (seq, line) in (first_seq..).zip(lines) {
+        stmt.execute(params![stem, seq, line]).map_err(db_err)?;
+    }
+    Ok(())
+}
+
+fn parse_message(line: &str) -> Option<ChatMessage> {
+    let Some(line) = at_rest::open_line(line) else {
+        debug!("Cannot decrypt session message (storage locked?), skipping");
+        return None;
+    };
+    match serde_json::from_str::<ChatMessage>(&line) {
+        Ok(msg) => Some(msg),
+        Err(e) => {
+            debug!(error = %e, "Failed to parse session message, skipping");
+            None
+        }
+    }
+}
+
+/// A `.jsonl` session file written by an earlier version.
+struct SessionFile {
+    created_at: Option<String>,
+    metadata: Value,
+    /// Message lines as stored, sealed or not.
+    lines: Vec<String>,
+    /// From the metadata line, else the file's modification time.
+    updated_at_ms: i64,
+}
+
+/// Read a session file. `None` if its first line cannot be opened (storage locked).
+fn read_session_file(path: &Path) -> Result<Option<SessionFile>> {
+    let updated_at_ms = std::fs::metadata(path)?
+        .modified()
+        .map(|t| chrono::DateTime::<chrono::Utc>::from(t).timestamp_millis())
+        .unwrap_or(0);
+    let mut lines: Vec<String> = BufReader::new(File::open(path)?)
+        .lines()
+        .collect::<std::io::Result<Vec<_>>>()?;
+    lines.retain(|line| !line.trim().is_empty());
+    let mut file = SessionFile {
+        created_at: None,
+        metadata: Value::Object(serde_json::Map::new()),
+        lines: Vec::new(),
+        updated_at_ms,
+    };
+    // Only the first line can be the metadata line.
+    if let Some(first) = lines.first() {
+        let Some(first) = at_rest::open_line(first) else {
+            return Ok(None);
+        };
+        if let Ok(SessionLine::Metadata {
+            created_at,
+            updated_at,
+            metadata,
+        }) = serde_json::from_str::<SessionLine>(&first)
+        {
+            if let Ok(updated_at) = chrono::DateTime::parse_from_rfc3339(&updated_at) {
+                file.updated_at_ms = updated_at.timestamp_millis();
+            }
+            file.created_at = Some(created_at);
+            if metadata.is_object() {
+                file.metadata = metadata;
+            }
+            lines.remove(0);
+        }
+    }
+    file.lines = lines;
+    Ok(Some(file))
+}
+
+/// Move the `.jsonl` session files of earlier versions into the database. Message lines
+/// are copied as stored, so sealed lines stay sealed. A file is removed once its session
+/// is committed; a file whose session is already in the database is left alone.
+fn import_session_files(conn: &mut Connection, sessions_dir: &Path) -> Result<usize> {
+    let Ok(entries) = std::fs::read_dir(sessions_dir) else {
+        return Ok(0);
+    };
+    let mut imported = 0;
+    for path in entries.flatten().map(|e| e.path()) {
+        if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+            continue;
+        }
+        let Some(stem) = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .map(str::to_string)
+        else {
+            continue;
+        };
+        let exists = conn
+            .query_row(
+                "SELECT 1 FROM sessions WHERE stem = ?1",
+                params![stem],
+                |_| Ok(()),
+            )
+            .optional()
+            .map_err(db_err)?
+            .is_some();
+        if exists {
+            debug!(path = %path.display(), "Session already in the database, keeping file");
+            continue;
+        }
+        let Some(file) = read_session_file(&path)? else {
+            warn!(
+                path = %path.display(),
+                "Cannot open session file (storage locked?), importing it later"
+            );
+            continue;
+        };
+        let created_at = file.created_at.unwrap_or_else(|| {
+            chrono::DateTime::from_timestamp_millis(file.updated_at_ms)
+                .unwrap_or_default()
+                .to_rfc3339()
+        });
+        let metadata = at_rest::seal_line(&serde_json::to_string(&file.metadata)?).into_owned();
+        let tx = conn.transaction().map_err(db_err)?;
+        tx.execute(
+            "INSERT INTO sessions
+             (stem, session_id, created_at, updated_at, metadata, message_count)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                stem,
+                session_id_from_file_stem(&stem),
+                created_at,
+                file.updated_at_ms,
+                metadata,
+                file.lines.len() as i64
+            ],
+        )
+        .map_err(db_err)?;
+        insert_messages(&tx, &stem, 0, &file.lines)?;
+        tx.commit().map_err(db_err)?;
+        std::fs::remove_file(&path)?;
+        imported += 1;
+    }
+    Ok(imported)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -306,5 +693,119 @@ mod tests {
             .load_metadata(session_key)
             .expect("load metadata after save");
         assert_eq!(loaded["skill_state"]["last_skill"], "deep_analysis");
+        assert_eq!(store.load(session_key).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_load_page_returns_window_and_total() {
+        let (store, _dir) = test_store();
+        let session_key = "ws:chat-2";
+        for i in 0..5 {
+            store
+                .append(session_key, &ChatMessage::user(&format!("message {}", i)))
+                .expect("append");
+        }
+
+        let (page, total) = store.load_page(session_key, 1, 2).expect("load page");
+        assert_eq!(total, 5);
+        let texts: Vec<String> = page
+            .iter()
+            .map(|m| m.content.as_str().unwrap_or_default().to_string())
+            .collect();
+        assert_eq!(texts, vec!["message 1", "message 2"]);
+
+        let (past_end, total) = store.load_page(session_key, 10, 2).expect("load page");
+        assert!(past_end.is_empty());
+        assert_eq!(total, 5);
+        assert_eq!(store.load_page("ws:missing", 0, 2).unwrap().1, 0);
+    }
+
+    #[test]
+    fn test_list_orders_by_update_and_clear_removes() {
+        let (store, _dir) = test_store();
+        store
+            .save("ws:old", &[ChatMessage::user("a"), ChatMessage::user("b")])
+            .expect("save");
+        std::thread::sleep(Duration::from_millis(5));
+        store
+            .append("telegram:42", &ChatMessage::user("hi"))
+            .expect("append");
+
+        let (page, total) = store.list(0, 1).expect("list");
+        assert_eq!(total, 2);
+        assert_eq!(page[0].stem, "telegram_42");
+        assert_eq!(page[0].session_id, "42");
+        assert_eq!(page[0].message_count, 1);
+        let (page, _) = store.list(1, 10).expect("list");
+        assert_eq!(page[0].session_id, "old");
+        assert_eq!(page[0].message_count, 2);
+        assert_eq!(store.stems().unwrap(), vec!["telegram_42", "ws_old"]);
+
+        assert!(store.clear("telegram:42").expect("clear"));
+        assert!(!store.clear("telegram:42").expect("clear"));
+        let (page, total) = store.list(0, usize::MAX).expect("list");
+        assert_eq!(total, 1);
+        assert_eq!(page[0].stem, "ws_old");
+        assert!(store.load("telegram:42").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_imports_jsonl_session_files() {
+        let dir = TempDir::new().expect("temp dir");
+        let paths = Paths::with_base(dir.path().to_path_buf());
+        std::fs::create_dir_all(paths.sessions_dir()).unwrap();
+        let metadata = json!({
+            "_type": "metadata",
+            "created_at": "2026-01-02T03:04:05+00:00",
+            "updated_at": "2026-01-02T03:04:05+00:00",
+            "metadata": {"senders": ["42"]}
+        });
+        let lines = [
+            metadata.to_string(),
+            serde_json::to_string(&ChatMessage::user("hello")).unwrap(),
+            serde_json::to_string(&ChatMessage::assistant("hi there")).unwrap(),
+        ];
+        std::fs::write(paths.session_file("telegram:42"), lines.join("\n") + "\n").unwrap();
+        // A file without a metadata line is imported too.
+        std::fs::write(
+            paths.session_file("ws:bare"),
+            serde_json::to_string(&ChatMessage::user("bare")).unwrap() + "\n",
+        )
+        .unwrap();
+
+        let store = SessionStore::new(paths.clone());
+        let history = store.load("telegram:42").expect("load");
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].content, "hi there");
+        assert_eq!(
+            store.load_metadata("telegram:42").unwrap()["senders"][0],
+            "42"
+        );
+        assert_eq!(store.load("ws:bare").unwrap().len(), 1);
+        assert!(!paths.session_file("telegram:42").exists());
+        assert!(!paths.session_file("ws:bare").exists());
+
+        let (sessions, total) = store.list(0, 10).unwrap();
+        assert_eq!(total, 2);
+        let imported = sessions.iter().find(|s| s.stem == "telegram_42").unwrap();
+        assert_eq!(imported.created_at, "2026-01-02T03:04:05+00:00");
+        assert_eq!(imported.message_count, 2);
+
+        // New messages continue after the imported ones.
+        store
+            .append("telegram:42", &ChatMessage::user("again"))
+            .expect("append");
+        let (page, total) = store.load_page("telegram:42", 2, 10).unwrap();
+        assert_eq!(total, 3);
+        assert_eq!(page[0].content, "again");
+
+        let exported = store.export_jsonl("telegram:42").unwrap().unwrap();
+        assert_eq!(exported.lines().count(), 4);
+        assert!(exported
+            .lines()
+            .next()
+            .unwrap()
+            .contains("\"_type\":\"metadata\""));
+        assert!(store.export_jsonl("ws:missing").unwrap().is_none());
     }
 }
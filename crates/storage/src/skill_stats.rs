@@ -0,0 +1,274 @@
+//! Per-skill performance aggregates: invocation count, success rate, latency and tokens.
+//!
+//! The runtime records one row update per skill run; `blockcell skills stats` and
+//! `GET /v1/skills/stats` read the aggregates back, and the evolution tick uses
+//! [`SkillStatsStore::underperformers`] to queue chronically failing or slow skills.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use blockcell_core::{Error, Result};
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+fn db_err(e: rusqlite::Error) -> Error {
+    Error::Storage(format!("skill stats: {}", e))
+}
+
+/// Aggregated numbers for one skill.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SkillStats {
+    pub skill: String,
+    pub calls: u64,
+    pub successes: u64,
+    pub failures: u64,
+    pub success_rate: f64,
+    pub mean_latency_ms: f64,
+    pub total_tokens: u64,
+    pub mean_tokens: f64,
+    pub last_called_at: String,
+    /// When the skill was last queued for improvement, if ever.
+    pub flagged_at: Option<String>,
+}
+
+/// Thresholds for [`SkillStatsStore::underperformers`].
+#[derive(Debug, Clone, Copy)]
+pub struct UnderperformerCriteria {
+    /// Ignore skills with fewer runs than this.
+    pub min_calls: u64,
+    /// Flag skills whose success rate is below this.
+    pub min_success_rate: f64,
+    /// Flag skills whose mean latency exceeds this (0 = ignore latency).
+    pub max_mean_latency_ms: f64,
+    /// Skip skills flagged within this many days.
+    pub cooldown_days: i64,
+}
+
+impl Default for UnderperformerCriteria {
+    fn default() -> Self {
+        Self {
+            min_calls: 10,
+            min_success_rate: 0.6,
+            max_mean_latency_ms: 0.0,
+            cooldown_days: 7,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SkillStatsStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SkillStatsStore {
+    pub fn open(db_path: &Path) -> Result<Self> {
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(db_path).map_err(db_err)?;
+        conn.execute_batch("PRAGMA journal_mode=WAL;").ok();
+        Self::init(conn)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory().map_err(db_err)?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            "
+            CREATE TABLE IF NOT EXISTS skill_stats (
+                skill TEXT PRIMARY KEY,
+                calls INTEGER NOT NULL DEFAULT 0,
+                successes INTEGER NOT NULL DEFAULT 0,
+                total_latency_ms INTEGER NOT NULL DEFAULT 0,
+                total_tokens INTEGER NOT NULL DEFAULT 0,
+                last_called_at TEXT NOT NULL,
+                flagged_at TEXT
+            );
+            ",
+        )
+        .map_err(db_err)?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>> {
+        self.conn
+            .lock()
+            .map_err(|e| Error::Storage(format!("Lock error: {}", e)))
+    }
+
+    /// Fold one skill run into the aggregates.
+    pub fn record(&self, skill: &str, success: bool, latency_ms: u64, tokens: u64) -> Result<()> {
+        let conn = self.lock()?;
+        conn.execute(
+            "INSERT INTO skill_stats (skill, calls, successes, total_latency_ms, total_tokens, last_called_at)
+             VALUES (?1, 1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(skill) DO UPDATE SET
+                calls = calls + 1,
+                successes = successes + excluded.successes,
+                total_latency_ms = total_latency_ms + excluded.total_latency_ms,
+                total_tokens = total_tokens + excluded.total_tokens,
+                last_called_at = excluded.last_called_at",
+            params![
+                skill,
+                i64::from(success),
+                latency_ms as i64,
+                tokens as i64,
+                Utc::now().to_rfc3339()
+            ],
+        )
+        .map_err(db_err)?;
+        Ok(())
+    }
+
+    /// All skills, most-used first.
+    pub fn all(&self) -> Result<Vec<SkillStats>> {
+        let conn = self.lock()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT skill, calls, successes, total_latency_ms, total_tokens, last_called_at, flagged_at
+                 FROM skill_stats ORDER BY calls DESC, skill ASC",
+            )
+            .map_err(db_err)?;
+        let rows = stmt
+            .query_map([], |row| {
+                let calls = row.get::<_, i64>(1)?.max(0) as u64;
+                let successes = row.get::<_, i64>(2)?.max(0) as u64;
+                let total_latency = row.get::<_, i64>(3)?.max(0) as f64;
+                let total_tokens = row.get::<_, i64>(4)?.max(0) as u64;
+                let per_call = |total: f64| {
+                    if calls == 0 {
+                        0.0
+                    } else {
+                        total / calls as f64
+                    }
+                };
+                Ok(SkillStats {
+                    skill: row.get(0)?,
+                    calls,
+                    successes,
+                    failures: calls.saturating_sub(successes),
+                    success_rate: if calls == 0 {
+                        1.0
+                    } else {
+                        successes as f64 / calls as f64
+                    },
+                    mean_latency_ms: per_call(total_latency),
+                    total_tokens,
+                    mean_tokens: per_call(total_tokens as f64),
+                    last_called_at: row.get(5)?,
+                    flagged_at: row.get(6)?,
+                })
+            })
+            .map_err(db_err)?;
+        rows.collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(db_err)
+    }
+
+    pub fn get(&self, skill: &str) -> Result<Option<SkillStats>> {
+        Ok(self.all()?.into_iter().find(|s| s.skill == skill))
+    }
+
+    /// Skills that keep failing (or are too slow) and were not flagged recently.
+    pub fn underperformers(&self, criteria: &UnderperformerCriteria) -> Result<Vec<SkillStats>> {
+        let cutoff = Utc::now() - chrono::Duration::days(criteria.cooldown_days);
+        Ok(self
+            .all()?
+            .into_iter()
+            .filter(|s| s.calls >= criteria.min_calls)
+            .filter(|s| {
+                s.success_rate < criteria.min_success_rate
+                    || (criteria.max_mean_latency_ms > 0.0
+                        && s.mean_latency_ms > criteria.max_mean_latency_ms)
+            })
+            .filter(|s| {
+                s.flagged_at
+                    .as_deref()
+                    .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+                    .is_none_or(|t| t < cutoff)
+            })
+            .collect())
+    }
+
+    /// Remember that `skill` was queued for improvement.
+    pub fn mark_flagged(&self, skill: &str) -> Result<()> {
+        let conn = self.lock()?;
+        conn.execute(
+            "UPDATE skill_stats SET flagged_at = ?2 WHERE skill = ?1",
+            params![skill, Utc::now().to_rfc3339()],
+        )
+        .map_err(db_err)?;
+        Ok(())
+    }
+
+    /// Drop the aggregates for one skill (e.g. after it was rewritten).
+    pub fn reset(&self, skill: &str) -> Result<bool> {
+        let conn = self.lock()?;
+        let n = conn
+            .execute("DELETE FROM skill_stats WHERE skill = ?1", params![skill])
+            .map_err(db_err)?;
+        Ok(n > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_aggregates() {
+        let store = SkillStatsStore::open_in_memory().unwrap();
+        store.record("weather", true, 100, 500).unwrap();
+        store.record("weather", false, 300, 1500).unwrap();
+        store.record("stocks", true, 50, 0).unwrap();
+
+        let all = store.all().unwrap();
+        assert_eq!(all[0].skill, "weather");
+        let weather = &all[0];
+        assert_eq!(
+            (weather.calls, weather.successes, weather.failures),
+            (2, 1, 1)
+        );
+        assert!((weather.success_rate - 0.5).abs() < 1e-9);
+        assert!((weather.mean_latency_ms - 200.0).abs() < 1e-9);
+        assert_eq!(weather.total_tokens, 2000);
+        assert!((weather.mean_tokens - 1000.0).abs() < 1e-9);
+        assert_eq!(store.get("stocks").unwrap().unwrap().calls, 1);
+        assert!(store.get("missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_underperformers_respect_min_calls_and_cooldown() {
+        let store = SkillStatsStore::open_in_memory().unwrap();
+        for i in 0..10 {
+            store.record("flaky", i < 3, 10, 10).unwrap();
+            store.record("solid", true, 10, 10).unwrap();
+        }
+        for _ in 0..3 {
+            store.record("rare", false, 10, 10).unwrap();
+        }
+        let criteria = UnderperformerCriteria::default();
+        let flagged: Vec<String> = store
+            .underperformers(&criteria)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.skill)
+            .collect();
+        assert_eq!(flagged, vec!["flaky".to_string()]);
+
+        store.mark_flagged("flaky").unwrap();
+        assert!(store.underperformers(&criteria).unwrap().is_empty());
+
+        // Latency can flag a skill that always succeeds.
+        let slow = UnderperformerCriteria {
+            max_mean_latency_ms: 5.0,
+            ..criteria
+        };
+        assert_eq!(store.underperformers(&slow).unwrap()[0].skill, "solid");
+        assert!(store.reset("solid").unwrap());
+    }
+}
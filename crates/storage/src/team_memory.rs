@@ -0,0 +1,502 @@
+//! Shared team memory (`memory.team`).
+//!
+//! Items saved with scope `team` are exchanged with the other members of a team through
+//! the Community Hub. Each item is keyed by its `dedup_key` (or its id when it has none)
+//! and carries a vector clock with one counter per node. A newer clock always wins;
+//! concurrent edits fall back to last-write-wins on the edit time, with the node id as
+//! tie-break, so every node converges on the same version.
+//!
+//! Local edits are found by comparing each team item with the version last seen in
+//! `team_memory_sync`, so the normal memory write paths need no hooks.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::sync::MutexGuard;
+
+use blockcell_core::{Error, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::memory::{MemoryStore, UpsertParams};
+
+pub const TEAM_SCOPE: &str = "team";
+
+/// One counter per node that has edited an item.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct VectorClock(BTreeMap<String, u64>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockOrder {
+    Before,
+    After,
+    Equal,
+    Concurrent,
+}
+
+impl VectorClock {
+    pub fn increment(&mut self, node: &str) {
+        *self.0.entry(node.to_string()).or_default() += 1;
+    }
+
+    pub fn merge(&mut self, other: &VectorClock) {
+        for (node, count) in &other.0 {
+            let entry = self.0.entry(node.clone()).or_default();
+            *entry = (*entry).max(*count);
+        }
+    }
+
+    /// How `self` relates to `other`.
+    pub fn compare(&self, other: &VectorClock) -> ClockOrder {
+        let (mut ahead, mut behind) = (false, false);
+        for node in self.0.keys().chain(other.0.keys()) {
+            let mine = self.0.get(node).copied().unwrap_or(0);
+            let theirs = other.0.get(node).copied().unwrap_or(0);
+            match mine.cmp(&theirs) {
+                Ordering::Greater => ahead = true,
+                Ordering::Less => behind = true,
+                Ordering::Equal => {}
+            }
+        }
+        match (ahead, behind) {
+            (false, false) => ClockOrder::Equal,
+            (true, false) => ClockOrder::After,
+            (false, true) => ClockOrder::Before,
+            (true, true) => ClockOrder::Concurrent,
+        }
+    }
+}
+
+/// An item version as exchanged with the hub.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamEntry {
+    pub key: String,
+    #[serde(rename = "type")]
+    pub item_type: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    pub content: String,
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub importance: f64,
+    #[serde(default)]
+    pub deleted: bool,
+    /// When the winning edit was made (RFC 3339).
+    pub changed_at: String,
+    /// Node that made the edit.
+    pub origin: String,
+    pub clock: VectorClock,
+}
+
+#[derive(Debug, Default)]
+struct SyncRecord {
+    clock: VectorClock,
+    origin: String,
+    changed_at: String,
+    /// Fingerprint of the local item when last synced.
+    seen: String,
+    dirty: bool,
+}
+
+struct LocalItem {
+    id: String,
+    key: String,
+    item_type: String,
+    title: Option<String>,
+    content: String,
+    summary: Option<String>,
+    tags: Vec<String>,
+    importance: f64,
+    updated_at: String,
+    deleted_at: Option<String>,
+}
+
+impl LocalItem {
+    fn fingerprint(&self) -> String {
+        format!(
+            "{}|{}",
+            self.updated_at,
+            self.deleted_at.as_deref().unwrap_or("")
+        )
+    }
+
+    fn to_entry(&self, record: &SyncRecord) -> TeamEntry {
+        TeamEntry {
+            key: self.key.clone(),
+            item_type: self.item_type.clone(),
+            title: self.title.clone(),
+            content: self.content.clone(),
+            summary: self.summary.clone(),
+            tags: self.tags.clone(),
+            importance: self.importance,
+            deleted: self.deleted_at.is_some(),
+            changed_at: record.changed_at.clone(),
+            origin: record.origin.clone(),
+            clock: record.clock.clone(),
+        }
+    }
+}
+
+fn storage_err(context: &str) -> impl Fn(rusqlite::Error) -> Error + '_ {
+    move |e| Error::Storage(format!("{}: {}", context, e))
+}
+
+/// Team memory view over one agent's memory store. `node` identifies this store in
+/// vector clocks and must be unique within the team.
+pub struct TeamMemory<'a> {
+    store: &'a MemoryStore,
+    node: String,
+}
+
+impl<'a> TeamMemory<'a> {
+    pub fn new(store: &'a MemoryStore, node: impl Into<String>) -> Result<Self> {
+        let team = Self {
+            store,
+            node: node.into(),
+        };
+        team.conn()?
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS team_memory_sync (
+                    key TEXT PRIMARY KEY,
+                    clock TEXT NOT NULL DEFAULT '{}',
+                    origin TEXT NOT NULL DEFAULT '',
+                    changed_at TEXT NOT NULL DEFAULT '',
+                    seen TEXT NOT NULL DEFAULT '',
+                    dirty INTEGER NOT NULL DEFAULT 0
+                );",
+            )
+            .map_err(storage_err("Failed to init team memory schema"))?;
+        Ok(team)
+    }
+
+    fn conn(&self) -> Result<MutexGuard<'a, Connection>> {
+        self.store
+            .inner
+            .lock()
+            .map_err(|e| Error::Storage(format!("Lock error: {}", e)))
+    }
+
+    /// Team items by key. Where a key has several items the live one wins over
+    /// deleted ones.
+    fn local_items(&self) -> Result<BTreeMap<String, LocalItem>> {
+        let conn = self.conn()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, dedup_key, type, title, content, summary, tags, importance,
+                        updated_at, deleted_at
+                 FROM memory_items WHERE scope = ?1
+                 ORDER BY (deleted_at IS NULL), updated_at",
+            )
+            .map_err(storage_err("Team memory query error"))?;
+        let rows = stmt
+            .query_map(params![TEAM_SCOPE], |row| {
+                let id: String = row.get(0)?;
+                let dedup_key: Option<String> = row.get(1)?;
+                let tags: String = row.get(6)?;
+                Ok(LocalItem {
+                    key: dedup_key
+                        .filter(|k| !k.is_empty())
+                        .unwrap_or_else(|| id.clone()),
+                    id,
+                    item_type: row.get(2)?,
+                    title: row.get(3)?,
+                    content: row.get(4)?,
+                    summary: row.get(5)?,
+                    tags: tags
+                        .split(',')
+                        .filter(|t| !t.is_empty())
+                        .map(str::to_string)
+                        .collect(),
+                    importance: row.get(7)?,
+                    updated_at: row.get(8)?,
+                    deleted_at: row.get(9)?,
+                })
+            })
+            .map_err(storage_err("Team memory query error"))?;
+        let mut items = BTreeMap::new();
+        for item in rows.flatten() {
+            items.insert(item.key.clone(), item);
+        }
+        Ok(items)
+    }
+
+    fn load_record(&self, key: &str) -> Result<SyncRecord> {
+        let row = self
+            .conn()?
+            .query_row(
+                "SELECT clock, origin, changed_at, seen, dirty FROM team_memory_sync WHERE key = ?1",
+                params![key],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, String>(3)?,
+                        row.get::<_, i64>(4)?,
+                    ))
+                },
+            )
+            .optional()
+            .map_err(storage_err("Team memory sync query error"))?;
+        Ok(match row {
+            Some((clock, origin, changed_at, seen, dirty)) => SyncRecord {
+                clock: serde_json::from_str(&clock).unwrap_or_default(),
+                origin,
+                changed_at,
+                seen,
+                dirty: dirty != 0,
+            },
+            None => SyncRecord::default(),
+        })
+    }
+
+    fn save_record(&self, key: &str, record: &SyncRecord) -> Result<()> {
+        self.conn()?
+            .execute(
+                "INSERT OR REPLACE INTO team_memory_sync (key, clock, origin, changed_at, seen, dirty)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    key,
+                    serde_json::to_string(&record.clock)?,
+                    record.origin,
+                    record.changed_at,
+                    record.seen,
+                    record.dirty as i64
+                ],
+            )
+            .map_err(storage_err("Team memory sync write error"))?;
+        Ok(())
+    }
+
+    /// Record local edits since the last sync and return every version not yet pushed.
+    pub fn local_changes(&self) -> Result<Vec<TeamEntry>> {
+        let mut changes = Vec::new();
+        for (key, item) in self.local_items()? {
+            let mut record = self.load_record(&key)?;
+            let fingerprint = item.fingerprint();
+            if record.seen != fingerprint {
+                record.clock.increment(&self.node);
+                record.origin = self.node.clone();
+                record.changed_at = item
+                    .deleted_at
+                    .clone()
+                    .unwrap_or_else(|| item.updated_at.clone());
+                record.seen = fingerprint;
+                record.dirty = true;
+                self.save_record(&key, &record)?;
+            }
+            if record.dirty {
+                changes.push(item.to_entry(&record));
+            }
+        }
+        Ok(changes)
+    }
+
+    /// Clear the pending flag of pushed versions that have not changed since.
+    pub fn mark_pushed(&self, entries: &[TeamEntry]) -> Result<()> {
+        let conn = self.conn()?;
+        for entry in entries {
+            conn.execute(
+                "UPDATE team_memory_sync SET dirty = 0 WHERE key = ?1 AND clock = ?2",
+                params![entry.key, serde_json::to_string(&entry.clock)?],
+            )
+            .map_err(storage_err("Team memory sync write error"))?;
+        }
+        Ok(())
+    }
+
+    fn write_local(&self, entry: &TeamEntry) -> Result<String> {
+        let live = self
+            .local_items()?
+            .remove(&entry.key)
+            .filter(|item| item.deleted_at.is_none());
+        if entry.deleted {
+            if let Some(item) = live {
+                self.store.soft_delete(&item.id)?;
+            }
+        } else {
+            self.store.upsert(UpsertParams {
+                scope: TEAM_SCOPE.to_string(),
+                item_type: entry.item_type.clone(),
+                title: entry.title.clone(),
+                content: entry.content.clone(),
+                summary: entry.summary.clone(),
+                tags: entry.tags.clone(),
+                source: "team".to_string(),
+                channel: None,
+                session_key: None,
+                importance: entry.importance,
+                dedup_key: Some(entry.key.clone()),
+                expires_at: None,
+            })?;
+        }
+        Ok(self
+            .local_items()?
+            .get(&entry.key)
+            .map(LocalItem::fingerprint)
+            .unwrap_or_default())
+    }
+
+    /// Merge versions pulled from the hub. Returns how many changed the local store.
+    pub fn apply_remote(&self, entries: &[TeamEntry]) -> Result<usize> {
+        // Pick up local edits first so they take part in conflict resolution.
+        self.local_changes()?;
+        let mut applied = 0;
+        for entry in entries {
+            let mut record = self.load_record(&entry.key)?;
+            match entry.clock.compare(&record.clock) {
+                ClockOrder::Before | ClockOrder::Equal => continue,
+                ClockOrder::After => {
+                    record.seen = self.write_local(entry)?;
+                    record.clock = entry.clock.clone();
+                    record.origin = entry.origin.clone();
+                    record.changed_at = entry.changed_at.clone();
+                    record.dirty = false;
+                    applied += 1;
+                }
+                ClockOrder::Concurrent => {
+                    let remote_wins =
+                        (&entry.changed_at, &entry.origin) > (&record.changed_at, &record.origin);
+                    if remote_wins {
+                        record.seen = self.write_local(entry)?;
+                        record.origin = entry.origin.clone();
+                        record.changed_at = entry.changed_at.clone();
+                        applied += 1;
+                    }
+                    // The merged clock supersedes both versions; push it so the other
+                    // side settles on the same winner.
+                    record.clock.merge(&entry.clock);
+                    record.dirty = true;
+                }
+            }
+            debug!(key = %entry.key, origin = %entry.origin, "Team memory entry merged");
+            self.save_record(&entry.key, &record)?;
+        }
+        Ok(applied)
+    }
+
+    /// Hub cursor of the last pull.
+    pub fn cursor(&self) -> Option<String> {
+        self.conn()
+            .ok()?
+            .query_row(
+                "SELECT value FROM memory_meta WHERE key = 'team_sync_cursor'",
+                [],
+                |row| row.get(0),
+            )
+            .ok()
+    }
+
+    pub fn set_cursor(&self, cursor: &str) -> Result<()> {
+        self.conn()?
+            .execute(
+                "INSERT OR REPLACE INTO memory_meta (key, value) VALUES ('team_sync_cursor', ?1)",
+                params![cursor],
+            )
+            .map_err(storage_err("Team memory cursor write error"))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn save(store: &MemoryStore, key: &str, content: &str) {
+        store
+            .upsert(UpsertParams {
+                scope: TEAM_SCOPE.to_string(),
+                item_type: "fact".to_string(),
+                title: None,
+                content: content.to_string(),
+                summary: None,
+                tags: vec![],
+                source: "tool".to_string(),
+                channel: None,
+                session_key: None,
+                importance: 0.7,
+                dedup_key: Some(key.to_string()),
+                expires_at: None,
+            })
+            .unwrap();
+    }
+
+    fn content(store: &MemoryStore, node: &str, key: &str) -> String {
+        TeamMemory::new(store, node)
+            .unwrap()
+            .local_items()
+            .unwrap()
+            .remove(key)
+            .unwrap()
+            .content
+    }
+
+    #[test]
+    fn test_vector_clock_order() {
+        let mut a = VectorClock::default();
+        a.increment("alice");
+        let mut b = a.clone();
+        assert_eq!(a.compare(&b), ClockOrder::Equal);
+        b.increment("bob");
+        assert_eq!(b.compare(&a), ClockOrder::After);
+        assert_eq!(a.compare(&b), ClockOrder::Before);
+        a.increment("alice");
+        assert_eq!(a.compare(&b), ClockOrder::Concurrent);
+        a.merge(&b);
+        assert_eq!(a.compare(&b), ClockOrder::After);
+    }
+
+    #[test]
+    fn test_sync_between_two_nodes_converges() {
+        let dir = TempDir::new().unwrap();
+        let alice = MemoryStore::open(&dir.path().join("alice.db")).unwrap();
+        let bob = MemoryStore::open(&dir.path().join("bob.db")).unwrap();
+
+        save(&alice, "staging.db", "staging DB host is db-stg-1");
+        let pushed = TeamMemory::new(&alice, "alice")
+            .unwrap()
+            .local_changes()
+            .unwrap();
+        assert_eq!(pushed.len(), 1);
+        TeamMemory::new(&alice, "alice")
+            .unwrap()
+            .mark_pushed(&pushed)
+            .unwrap();
+        let bob_team = TeamMemory::new(&bob, "bob").unwrap();
+        assert_eq!(bob_team.apply_remote(&pushed).unwrap(), 1);
+        assert_eq!(
+            content(&bob, "bob", "staging.db"),
+            "staging DB host is db-stg-1"
+        );
+        // Applying a remote version is not a local edit.
+        assert!(bob_team.local_changes().unwrap().is_empty());
+
+        // Both edit concurrently; the later edit wins on both sides.
+        save(&alice, "staging.db", "staging DB host is db-stg-2");
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        save(&bob, "staging.db", "staging DB host is db-stg-3");
+        let from_alice = TeamMemory::new(&alice, "alice")
+            .unwrap()
+            .local_changes()
+            .unwrap();
+        let from_bob = bob_team.local_changes().unwrap();
+        TeamMemory::new(&alice, "alice")
+            .unwrap()
+            .apply_remote(&from_bob)
+            .unwrap();
+        bob_team.apply_remote(&from_alice).unwrap();
+        assert_eq!(
+            content(&alice, "alice", "staging.db"),
+            "staging DB host is db-stg-3"
+        );
+        assert_eq!(
+            content(&bob, "bob", "staging.db"),
+            "staging DB host is db-stg-3"
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! Opt-in anonymous usage telemetry (`telemetry`).
+//!
+//! Reports are aggregated from the local audit logs over whole days since the last
+//! report. Only counts leave the machine: calls per built-in tool (custom, MCP and skill
+//! tools are folded into `other`), tool calls per channel, and error classes taken from
+//! the error kind, never the message. The install id is a random UUID kept in
+//! `telemetry.json` and is not derived from anything on the machine.
+
+use std::collections::BTreeMap;
+
+use blockcell_core::{Paths, Result};
+use chrono::{Days, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::audit::{AuditEvent, AuditLogger};
+
+pub const SCHEMA_VERSION: u32 = 1;
+/// Days covered by the first report.
+const FIRST_REPORT_DAYS: u64 = 7;
+
+/// Channel names reported as-is; anything else counts as `other`.
+const KNOWN_CHANNELS: &[&str] = &[
+    "cli", "ws", "webui", "telegram", "slack", "discord", "feishu", "lark", "dingtalk", "wecom",
+    "whatsapp", "qq", "napcat", "weixin", "cron", "ghost", "subagent",
+];
+
+/// `Display` prefixes of `blockcell_core::Error` and the class they are reported as.
+const ERROR_CLASSES: &[(&str, &str)] = &[
+    ("Config error:", "config"),
+    ("IO error:", "io"),
+    ("JSON error:", "json"),
+    ("YAML error:", "yaml"),
+    ("Provider error:", "provider"),
+    ("Tool error:", "tool"),
+    ("Session error:", "session"),
+    ("Channel error:", "channel"),
+    ("Skill error:", "skill"),
+    ("Validation error:", "validation"),
+    ("Permission denied:", "permission_denied"),
+    ("Not found:", "not_found"),
+    ("Timeout:", "timeout"),
+    ("Evolution error:", "evolution"),
+    ("Storage error:", "storage"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryState {
+    pub install_id: String,
+    /// Last day included in a sent report (`YYYY-MM-DD`).
+    #[serde(default)]
+    pub last_report_date: Option<String>,
+}
+
+impl TelemetryState {
+    /// Load the state, creating an install id on first use.
+    pub fn load_or_create(paths: &Paths) -> Result<Self> {
+        let path = paths.telemetry_state_file();
+        if let Ok(content) = std::fs::read_to_string(&path) {
+            if let Ok(state) = serde_json::from_str(&content) {
+                return Ok(state);
+            }
+        }
+        let state = Self {
+            install_id: uuid::Uuid::new_v4().to_string(),
+            last_report_date: None,
+        };
+        state.save(paths)?;
+        Ok(state)
+    }
+
+    pub fn save(&self, paths: &Paths) -> Result<()> {
+        std::fs::create_dir_all(&paths.base)?;
+        std::fs::write(
+            paths.telemetry_state_file(),
+            serde_json::to_string_pretty(self)?,
+        )?;
+        Ok(())
+    }
+
+    /// Whole days not yet reported, up to yesterday. `None` when there are none.
+    pub fn pending_period(&self, today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+        let end = today.checked_sub_days(Days::new(1))?;
+        let start = self
+            .last_report_date
+            .as_deref()
+            .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+            .and_then(|d| d.checked_add_days(Days::new(1)))
+            .unwrap_or_else(|| today - Days::new(FIRST_REPORT_DAYS));
+        (start <= end).then_some((start, end))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryReport {
+    pub schema: u32,
+    pub install_id: String,
+    pub version: String,
+    pub os: &'static str,
+    pub arch: &'static str,
+    pub period_start: String,
+    pub period_end: String,
+    pub agents: usize,
+    pub tool_calls: BTreeMap<String, u64>,
+    pub channels: BTreeMap<String, u64>,
+    pub error_classes: BTreeMap<String, u64>,
+}
+
+fn channel_of(session_key: &str) -> &str {
+    let channel = session_key.split(':').next().unwrap_or_default();
+    if KNOWN_CHANNELS.contains(&channel) {
+        channel
+    } else {
+        "other"
+    }
+}
+
+fn error_class(result: &serde_json::Value) -> Option<&'static str> {
+    let message = result.get("error")?.as_str()?;
+    let message = message.strip_prefix("Error: ").unwrap_or(message);
+    Some(
+        ERROR_CLASSES
+            .iter()
+            .find(|(prefix, _)| message.starts_with(prefix))
+            .map(|(_, class)| *class)
+            .unwrap_or("other"),
+    )
+}
+
+/// Aggregate the audit logs of `agents` over `period`. `is_builtin_tool` decides which
+/// tool names are reported by name.
+pub fn build_report(
+    state: &TelemetryState,
+    agents: &[Paths],
+    period: (NaiveDate, NaiveDate),
+    version: &str,
+    is_builtin_tool: impl Fn(&str) -> bool,
+) -> TelemetryReport {
+    let mut report = TelemetryReport {
+        schema: SCHEMA_VERSION,
+        install_id: state.install_id.clone(),
+        version: version.to_string(),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        period_start: period.0.format("%Y-%m-%d").to_string(),
+        period_end: period.1.format("%Y-%m-%d").to_string(),
+        agents: agents.len(),
+        tool_calls: BTreeMap::new(),
+        channels: BTreeMap::new(),
+        error_classes: BTreeMap::new(),
+    };
+
+    for paths in agents {
+        let logger = AuditLogger::new(paths.clone());
+        for day in period.0.iter_days().take_while(|d| *d <= period.1) {
+            let events = logger
+                .read_events(&day.format("%Y-%m-%d").to_string())
+                .unwrap_or_default();
+            for event in events {
+                let AuditEvent::ToolCall {
+                    tool_name,
+                    result,
+                    session_key,
+                    ..
+                } = event
+                else {
+                    continue;
+                };
+                let tool = if is_builtin_tool(&tool_name) {
+                    tool_name
+                } else {
+                    "other".to_string()
+                };
+                *report.tool_calls.entry(tool).or_default() += 1;
+                *report
+                    .channels
+                    .entry(channel_of(&session_key).to_string())
+                    .or_default() += 1;
+                if let Some(class) = error_class(&result) {
+                    *report.error_classes.entry(class.to_string()).or_default() += 1;
+                }
+            }
+        }
+    }
+    report
+}
+
+/// The report for the days not yet sent, `None` when there are none.
+pub fn pending_report(
+    paths: &Paths,
+    agents: &[Paths],
+    version: &str,
+    is_builtin_tool: impl Fn(&str) -> bool,
+) -> Result<Option<TelemetryReport>> {
+    let state = TelemetryState::load_or_create(paths)?;
+    Ok(state
+        .pending_period(Utc::now().date_naive())
+        .map(|period| build_report(&state, agents, period, version, is_builtin_tool)))
+}
+
+/// Remember that everything up to the report's last day has been sent.
+pub fn mark_sent(paths: &Paths, report: &TelemetryReport) -> Result<()> {
+    let mut state = TelemetryState::load_or_create(paths)?;
+    state.last_report_date = Some(report.period_end.clone());
+    state.save(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_pending_period() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        let mut state = TelemetryState {
+            install_id: "x".to_string(),
+            last_report_date: None,
+        };
+        let (start, end) = state.pending_period(today).unwrap();
+        assert_eq!(start, NaiveDate::from_ymd_opt(2026, 3, 3).unwrap());
+        assert_eq!(end, NaiveDate::from_ymd_opt(2026, 3, 9).unwrap());
+
+        state.last_report_date = Some("2026-03-09".to_string());
+        assert!(state.pending_period(today).is_none());
+        state.last_report_date = Some("2026-03-07".to_string());
+        assert_eq!(
+            state.pending_period(today).unwrap().0,
+            NaiveDate::from_ymd_opt(2026, 3, 8).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_build_report_counts_without_content() {
+        let dir = TempDir::new().unwrap();
+        let paths = Paths::with_base(dir.path().to_path_buf());
+        let mut logger = AuditLogger::new(paths.clone());
+        logger
+            .log_tool_call(
+                "web_search",
+                json!({"query": "secret plans"}),
+                json!({"results": []}),
+                "telegram:12345",
+                None,
+                None,
+            )
+            .unwrap();
+        logger
+            .log_tool_call(
+                "mcp_private_crm",
+                json!({}),
+                json!({"error": "Error: Timeout: crm.example.com did not answer"}),
+                "mychannel:1",
+                None,
+                None,
+            )
+            .unwrap();
+
+        let state = TelemetryState {
+            install_id: "install".to_string(),
+            last_report_date: None,
+        };
+        let today = Utc::now().date_naive();
+        let report = build_report(&state, &[paths], (today, today), "1.2.3", |name| {
+            name == "web_search"
+        });
+        assert_eq!(report.tool_calls["web_search"], 1);
+        assert_eq!(report.tool_calls["other"], 1);
+        assert_eq!(report.channels["telegram"], 1);
+        assert_eq!(report.channels["other"], 1);
+        assert_eq!(report.error_classes["timeout"], 1);
+
+        let sent = serde_json::to_string(&report).unwrap();
+        for private in ["secret plans", "12345", "crm", "mychannel"] {
+            assert!(!sent.contains(private), "{} leaked", private);
+        }
+    }
+}
@@ -0,0 +1,280 @@
+//! Local time-series history for streamed market data (`streams/history.db`).
+//!
+//! `stream_subscribe` appends every tick of a subscription created with `persist: true`
+//! under its series name; `stream_subscribe action=local_history` reads windows back,
+//! so backtests and alert rules can work from stored data instead of re-fetching
+//! remote APIs, including while offline.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use blockcell_core::{Error, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+fn db_err(e: rusqlite::Error) -> Error {
+    Error::Storage(format!("time series: {}", e))
+}
+
+/// One stored tick. `value` is the numeric field picked by the subscription, if any.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimeSeriesPoint {
+    pub timestamp: i64,
+    pub value: Option<f64>,
+    pub data: String,
+}
+
+/// Aggregates over the numeric values of a window.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WindowStats {
+    pub count: u64,
+    pub first: Option<f64>,
+    pub last: Option<f64>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub avg: Option<f64>,
+    /// Percent change from `first` to `last`.
+    pub change_pct: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SeriesInfo {
+    pub series: String,
+    pub points: u64,
+    pub first_timestamp: i64,
+    pub last_timestamp: i64,
+}
+
+#[derive(Clone)]
+pub struct TimeSeriesStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl TimeSeriesStore {
+    pub fn open(db_path: &Path) -> Result<Self> {
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(db_path).map_err(db_err)?;
+        conn.execute_batch("PRAGMA journal_mode=WAL;").ok();
+        Self::init(conn)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory().map_err(db_err)?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            "
+            CREATE TABLE IF NOT EXISTS points (
+                series TEXT NOT NULL,
+                ts INTEGER NOT NULL,
+                value REAL,
+                data TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_points_series_ts ON points(series, ts);
+            ",
+        )
+        .map_err(db_err)?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>> {
+        self.conn
+            .lock()
+            .map_err(|e| Error::Storage(format!("Lock error: {}", e)))
+    }
+
+    pub fn append(
+        &self,
+        series: &str,
+        timestamp: i64,
+        value: Option<f64>,
+        data: &str,
+    ) -> Result<()> {
+        self.lock()?
+            .execute(
+                "INSERT INTO points (series, ts, value, data) VALUES (?1, ?2, ?3, ?4)",
+                params![series, timestamp, value, data],
+            )
+            .map_err(db_err)?;
+        Ok(())
+    }
+
+    /// The newest `limit` points in `[since, until]`, oldest first.
+    pub fn range(
+        &self,
+        series: &str,
+        since: Option<i64>,
+        until: Option<i64>,
+        limit: usize,
+    ) -> Result<Vec<TimeSeriesPoint>> {
+        let conn = self.lock()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT ts, value, data FROM points
+                 WHERE series = ?1 AND ts >= ?2 AND ts <= ?3
+                 ORDER BY ts DESC, rowid DESC LIMIT ?4",
+            )
+            .map_err(db_err)?;
+        let mut points = stmt
+            .query_map(
+                params![
+                    series,
+                    since.unwrap_or(i64::MIN),
+                    until.unwrap_or(i64::MAX),
+                    limit as i64
+                ],
+                |row| {
+                    Ok(TimeSeriesPoint {
+                        timestamp: row.get(0)?,
+                        value: row.get(1)?,
+                        data: row.get(2)?,
+                    })
+                },
+            )
+            .map_err(db_err)?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(db_err)?;
+        points.reverse();
+        Ok(points)
+    }
+
+    /// Aggregates over every numeric point in `[since, until]`.
+    pub fn stats(
+        &self,
+        series: &str,
+        since: Option<i64>,
+        until: Option<i64>,
+    ) -> Result<WindowStats> {
+        let conn = self.lock()?;
+        let (since, until) = (since.unwrap_or(i64::MIN), until.unwrap_or(i64::MAX));
+        let bounds: [&dyn rusqlite::ToSql; 3] = [&series, &since, &until];
+        let (count, min, max, avg): (i64, Option<f64>, Option<f64>, Option<f64>) = conn
+            .query_row(
+                "SELECT COUNT(value), MIN(value), MAX(value), AVG(value) FROM points
+                 WHERE series = ?1 AND ts >= ?2 AND ts <= ?3 AND value IS NOT NULL",
+                &bounds[..],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .map_err(db_err)?;
+        let edge = |order: &str| -> Result<Option<f64>> {
+            conn.query_row(
+                &format!(
+                    "SELECT value FROM points
+                     WHERE series = ?1 AND ts >= ?2 AND ts <= ?3 AND value IS NOT NULL
+                     ORDER BY ts {order}, rowid {order} LIMIT 1"
+                ),
+                &bounds[..],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(db_err)
+        };
+        let first = edge("ASC")?;
+        let last = edge("DESC")?;
+        let change_pct = match (first, last) {
+            (Some(first), Some(last)) if first != 0.0 => Some((last - first) / first * 100.0),
+            _ => None,
+        };
+        Ok(WindowStats {
+            count: count as u64,
+            first,
+            last,
+            min,
+            max,
+            avg,
+            change_pct,
+        })
+    }
+
+    pub fn list_series(&self) -> Result<Vec<SeriesInfo>> {
+        let conn = self.lock()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT series, COUNT(*), MIN(ts), MAX(ts) FROM points
+                 GROUP BY series ORDER BY series",
+            )
+            .map_err(db_err)?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(SeriesInfo {
+                    series: row.get(0)?,
+                    points: row.get::<_, i64>(1)? as u64,
+                    first_timestamp: row.get(2)?,
+                    last_timestamp: row.get(3)?,
+                })
+            })
+            .map_err(db_err)?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(db_err)?;
+        Ok(rows)
+    }
+
+    /// Delete points of `series` older than `before`; returns how many were removed.
+    pub fn prune_before(&self, series: &str, before: i64) -> Result<usize> {
+        self.lock()?
+            .execute(
+                "DELETE FROM points WHERE series = ?1 AND ts < ?2",
+                params![series, before],
+            )
+            .map_err(db_err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_returns_newest_points_oldest_first() {
+        let store = TimeSeriesStore::open_in_memory().unwrap();
+        for (ts, value) in [(1_000, 10.0), (2_000, 11.0), (3_000, 12.0), (4_000, 13.0)] {
+            store
+                .append("binance:trade:btcusdt", ts, Some(value), "{}")
+                .unwrap();
+        }
+        store.append("other", 2_500, Some(1.0), "{}").unwrap();
+
+        let points = store
+            .range("binance:trade:btcusdt", Some(1_500), None, 2)
+            .unwrap();
+        let stamps: Vec<i64> = points.iter().map(|p| p.timestamp).collect();
+        assert_eq!(stamps, vec![3_000, 4_000]);
+
+        assert_eq!(
+            store.prune_before("binance:trade:btcusdt", 2_000).unwrap(),
+            1
+        );
+        let series = store.list_series().unwrap();
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[0].series, "binance:trade:btcusdt");
+        assert_eq!(series[0].points, 3);
+        assert_eq!(series[0].first_timestamp, 2_000);
+    }
+
+    #[test]
+    fn test_stats_skip_non_numeric_points() {
+        let store = TimeSeriesStore::open_in_memory().unwrap();
+        store.append("eth", 1, Some(100.0), "{}").unwrap();
+        store.append("eth", 2, None, "ping").unwrap();
+        store.append("eth", 3, Some(90.0), "{}").unwrap();
+        store.append("eth", 4, Some(110.0), "{}").unwrap();
+
+        let stats = store.stats("eth", None, None).unwrap();
+        assert_eq!(stats.count, 3);
+        assert_eq!(stats.first, Some(100.0));
+        assert_eq!(stats.last, Some(110.0));
+        assert_eq!(stats.min, Some(90.0));
+        assert_eq!(stats.max, Some(110.0));
+        assert_eq!(stats.avg, Some(100.0));
+        assert!((stats.change_pct.unwrap() - 10.0).abs() < 1e-9);
+
+        let empty = store.stats("eth", Some(10), None).unwrap();
+        assert_eq!(empty.count, 0);
+        assert_eq!(empty.change_pct, None);
+    }
+}
@@ -0,0 +1,379 @@
+//! Personal TODO list behind the `todo` tool and the morning digest.
+//!
+//! These are the user's own to-dos ("call the dentist", "renew passport"), distinct from
+//! the runtime's TaskManager tasks. A TODO can reference a task by id so "remind me about
+//! this task tomorrow" keeps the link. Times are Unix milliseconds.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use blockcell_core::{Error, Result};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+
+fn db_err(e: rusqlite::Error) -> Error {
+    Error::Storage(format!("todos: {}", e))
+}
+
+/// Location of the TODO database inside an agent workspace.
+pub fn db_path(workspace: &Path) -> PathBuf {
+    workspace.join("todos.db")
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Todo {
+    pub id: String,
+    pub text: String,
+    /// "open" or "done".
+    pub status: String,
+    pub due_at: Option<i64>,
+    /// Hidden from the digest and `due` listing until this time.
+    pub snoozed_until: Option<i64>,
+    /// Linked TaskManager task, if any.
+    pub task_id: Option<String>,
+    /// Session the TODO was created from.
+    pub session_key: Option<String>,
+    pub created_at: i64,
+    pub completed_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NewTodo {
+    pub text: String,
+    pub due_at: Option<i64>,
+    pub task_id: Option<String>,
+    pub session_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TodoFilter {
+    Open,
+    /// Open, due at or before the given time, and not snoozed past now.
+    DueBy(i64),
+    Done,
+    All,
+}
+
+#[derive(Clone)]
+pub struct TodoStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+const COLUMNS: &str =
+    "id, text, status, due_at, snoozed_until, task_id, session_key, created_at, completed_at";
+
+fn row_to_todo(row: &Row<'_>) -> rusqlite::Result<Todo> {
+    Ok(Todo {
+        id: row.get(0)?,
+        text: row.get(1)?,
+        status: row.get(2)?,
+        due_at: row.get(3)?,
+        snoozed_until: row.get(4)?,
+        task_id: row.get(5)?,
+        session_key: row.get(6)?,
+        created_at: row.get(7)?,
+        completed_at: row.get(8)?,
+    })
+}
+
+impl TodoStore {
+    pub fn open(db_path: &Path) -> Result<Self> {
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(db_path).map_err(db_err)?;
+        conn.execute_batch("PRAGMA journal_mode=WAL;").ok();
+        Self::init(conn)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory().map_err(db_err)?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            "
+            CREATE TABLE IF NOT EXISTS todos (
+                id TEXT PRIMARY KEY,
+                text TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'open',
+                due_at INTEGER,
+                snoozed_until INTEGER,
+                task_id TEXT,
+                session_key TEXT,
+                created_at INTEGER NOT NULL,
+                completed_at INTEGER
+            );
+            CREATE INDEX IF NOT EXISTS idx_todos_status_due ON todos(status, due_at);
+            CREATE TABLE IF NOT EXISTS todo_meta (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );
+            ",
+        )
+        .map_err(db_err)?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>> {
+        self.conn
+            .lock()
+            .map_err(|e| Error::Storage(format!("Lock error: {}", e)))
+    }
+
+    pub fn add(&self, new: NewTodo) -> Result<Todo> {
+        let text = new.text.trim();
+        if text.is_empty() {
+            return Err(Error::Validation("TODO text must not be empty".to_string()));
+        }
+        let todo = Todo {
+            id: format!(
+                "todo_{}",
+                uuid::Uuid::new_v4()
+                    .to_string()
+                    .split('-')
+                    .next()
+                    .unwrap_or("x")
+            ),
+            text: text.to_string(),
+            status: "open".to_string(),
+            due_at: new.due_at,
+            snoozed_until: None,
+            task_id: new.task_id,
+            session_key: new.session_key,
+            created_at: Utc::now().timestamp_millis(),
+            completed_at: None,
+        };
+        self.lock()?
+            .execute(
+                &format!(
+                    "INSERT INTO todos ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                    COLUMNS
+                ),
+                params![
+                    todo.id,
+                    todo.text,
+                    todo.status,
+                    todo.due_at,
+                    todo.snoozed_until,
+                    todo.task_id,
+                    todo.session_key,
+                    todo.created_at,
+                    todo.completed_at
+                ],
+            )
+            .map_err(db_err)?;
+        Ok(todo)
+    }
+
+    pub fn get(&self, id: &str) -> Result<Option<Todo>> {
+        self.lock()?
+            .query_row(
+                &format!("SELECT {} FROM todos WHERE id = ?1", COLUMNS),
+                params![id],
+                row_to_todo,
+            )
+            .optional()
+            .map_err(db_err)
+    }
+
+    fn require(&self, id: &str) -> Result<Todo> {
+        self.get(id)?
+            .ok_or_else(|| Error::NotFound(format!("TODO not found: {}", id)))
+    }
+
+    pub fn complete(&self, id: &str) -> Result<Todo> {
+        self.require(id)?;
+        self.lock()?
+            .execute(
+                "UPDATE todos SET status = 'done', completed_at = ?2 WHERE id = ?1",
+                params![id, Utc::now().timestamp_millis()],
+            )
+            .map_err(db_err)?;
+        self.require(id)
+    }
+
+    /// Hide an open TODO until `until`; a due date earlier than that moves along with it.
+    pub fn snooze(&self, id: &str, until: i64) -> Result<Todo> {
+        let todo = self.require(id)?;
+        if todo.status != "open" {
+            return Err(Error::Validation(format!("TODO {} is already done", id)));
+        }
+        let due_at = todo.due_at.map(|due| due.max(until));
+        self.lock()?
+            .execute(
+                "UPDATE todos SET snoozed_until = ?2, due_at = ?3 WHERE id = ?1",
+                params![id, until, due_at],
+            )
+            .map_err(db_err)?;
+        self.require(id)
+    }
+
+    pub fn remove(&self, id: &str) -> Result<bool> {
+        let n = self
+            .lock()?
+            .execute("DELETE FROM todos WHERE id = ?1", params![id])
+            .map_err(db_err)?;
+        Ok(n > 0)
+    }
+
+    /// TODOs matching `filter`; open ones by due date (undated last), done ones newest first.
+    pub fn list(&self, filter: TodoFilter, limit: usize) -> Result<Vec<Todo>> {
+        let now = Utc::now().timestamp_millis();
+        let (clause, order, args): (&str, &str, Vec<i64>) = match filter {
+            TodoFilter::Open => (
+                "status = 'open'",
+                "due_at IS NULL, due_at, created_at",
+                vec![],
+            ),
+            TodoFilter::DueBy(cutoff) => (
+                "status = 'open' AND due_at IS NOT NULL AND due_at <= ?1 \
+                 AND (snoozed_until IS NULL OR snoozed_until <= ?2)",
+                "due_at, created_at",
+                vec![cutoff, now],
+            ),
+            TodoFilter::Done => ("status = 'done'", "completed_at DESC", vec![]),
+            TodoFilter::All => (
+                "1 = 1",
+                "status DESC, due_at IS NULL, due_at, created_at",
+                vec![],
+            ),
+        };
+        let conn = self.lock()?;
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM todos WHERE {} ORDER BY {} LIMIT {}",
+                COLUMNS, clause, order, limit
+            ))
+            .map_err(db_err)?;
+        let rows = stmt
+            .query_map(rusqlite::params_from_iter(args), row_to_todo)
+            .map_err(db_err)?;
+        rows.collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(db_err)
+    }
+
+    /// Open TODOs linked to a TaskManager task.
+    pub fn for_task(&self, task_id: &str) -> Result<Vec<Todo>> {
+        let conn = self.lock()?;
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM todos WHERE task_id = ?1 AND status = 'open' ORDER BY created_at",
+                COLUMNS
+            ))
+            .map_err(db_err)?;
+        let rows = stmt
+            .query_map(params![task_id], row_to_todo)
+            .map_err(db_err)?;
+        rows.collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(db_err)
+    }
+
+    pub fn meta(&self, key: &str) -> Result<Option<String>> {
+        self.lock()?
+            .query_row(
+                "SELECT value FROM todo_meta WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(db_err)
+    }
+
+    pub fn set_meta(&self, key: &str, value: &str) -> Result<()> {
+        self.lock()?
+            .execute(
+                "INSERT INTO todo_meta (key, value) VALUES (?1, ?2)
+                 ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                params![key, value],
+            )
+            .map_err(db_err)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: i64 = 3_600_000;
+
+    fn add(store: &TodoStore, text: &str, due_at: Option<i64>) -> Todo {
+        store
+            .add(NewTodo {
+                text: text.to_string(),
+                due_at,
+                ..Default::default()
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_add_complete_and_list() {
+        let store = TodoStore::open_in_memory().unwrap();
+        let now = Utc::now().timestamp_millis();
+        let later = add(&store, "renew passport", Some(now + 48 * HOUR));
+        let soon = add(&store, "call dentist", Some(now - HOUR));
+        let undated = add(&store, "read book", None);
+        assert!(store.add(NewTodo::default()).is_err());
+
+        let open: Vec<String> = store
+            .list(TodoFilter::Open, 50)
+            .unwrap()
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(
+            open,
+            vec![soon.id.clone(), later.id.clone(), undated.id.clone()]
+        );
+
+        let due = store.list(TodoFilter::DueBy(now + HOUR), 50).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].text, "call dentist");
+
+        let done = store.complete(&soon.id).unwrap();
+        assert_eq!(done.status, "done");
+        assert!(done.completed_at.is_some());
+        assert_eq!(store.list(TodoFilter::Done, 50).unwrap().len(), 1);
+        assert!(store
+            .list(TodoFilter::DueBy(now + HOUR), 50)
+            .unwrap()
+            .is_empty());
+        assert!(store.remove(&undated.id).unwrap());
+        assert_eq!(store.list(TodoFilter::All, 50).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_snooze_hides_from_due_and_links_task() {
+        let store = TodoStore::open_in_memory().unwrap();
+        let now = Utc::now().timestamp_millis();
+        let todo = store
+            .add(NewTodo {
+                text: "follow up on report".to_string(),
+                due_at: Some(now - HOUR),
+                task_id: Some("msg_123".to_string()),
+                session_key: Some("cli:default".to_string()),
+            })
+            .unwrap();
+        let snoozed = store.snooze(&todo.id, now + 24 * HOUR).unwrap();
+        assert_eq!(snoozed.due_at, Some(now + 24 * HOUR));
+        assert!(store.list(TodoFilter::DueBy(now), 50).unwrap().is_empty());
+        assert_eq!(store.for_task("msg_123").unwrap().len(), 1);
+
+        store.complete(&todo.id).unwrap();
+        assert!(store.snooze(&todo.id, now).is_err());
+        assert!(store.complete("todo_missing").is_err());
+
+        assert_eq!(store.meta("last_digest").unwrap(), None);
+        store.set_meta("last_digest", "2026-10-14").unwrap();
+        store.set_meta("last_digest", "2026-10-15").unwrap();
+        assert_eq!(
+            store.meta("last_digest").unwrap().as_deref(),
+            Some("2026-10-15")
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use blockcell_core::permissions::{Role, ToolPermissions};
 use blockcell_core::{Error, Paths, Result};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
@@ -57,6 +58,26 @@ struct AlertRule {
     state: AlertState,
     created_at: i64,
     updated_at: i64,
+    /// Role of whoever created the rule; its source and actions never run with more.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    role: Option<String>,
+}
+
+impl AlertRule {
+    /// Whether this rule may call `tool` when evaluated from `ctx`.
+    fn may_call(&self, ctx: &ToolContext, tool: &str) -> bool {
+        let permissions = ToolPermissions::new(&ctx.config.permissions);
+        if !permissions.is_enabled() {
+            return true;
+        }
+        let role = match self.role.as_deref() {
+            Some(recorded) => ctx
+                .role()
+                .capped(Role::parse(recorded).unwrap_or(Role::Readonly)),
+            None => ctx.role(),
+        };
+        permissions.allows(role, tool)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -157,7 +178,7 @@ impl Tool for AlertRuleTool {
                     },
                     "source": {
                         "type": "object",
-                        "description": "(create/update) Data source tool call spec: {\"tool\": \"finance_api\", \"params\": {\"action\": \"stock_quote\", \"symbol\": \"AAPL\"}}"
+                        "description": "(create/update) Data source tool call spec: {\"tool\": \"finance_api\", \"params\": {\"action\": \"stock_quote\", \"symbol\": \"AAPL\"}}. To evaluate over locally stored stream ticks instead of a remote API: {\"tool\": \"stream_subscribe\", \"params\": {\"action\": \"local_history\", \"series\": \"binance:trade:btcusdt\", \"window_secs\": 3600, \"limit\": 1}} with metric_path 'stats.avg' (or stats.max/min/last/change_pct)"
                     },
                     "metric_path": {
                         "type": "string",
@@ -299,10 +320,11 @@ impl Tool for AlertRuleTool {
             _ => {
                 let p = params.clone();
                 let a = action.to_string();
+                let role = ctx.role();
                 tokio::task::spawn_blocking(move || {
                     let paths = Paths::new();
                     match a.as_str() {
-                        "create" => action_create(&paths, &p),
+                        "create" => action_create(&paths, &p, role),
                         "list" => action_list(&paths),
                         "get" => action_get(&paths, &p),
                         "update" => action_update(&paths, &p),
@@ -318,7 +340,7 @@ impl Tool for AlertRuleTool {
     }
 }
 
-fn action_create(paths: &Paths, params: &Value) -> Result<Value> {
+fn action_create(paths: &Paths, params: &Value, role: Role) -> Result<Value> {
     let mut store = load_store(paths)?;
     let now = Utc::now().timestamp_millis();
     let rule_id = format!(
@@ -384,6 +406,7 @@ fn action_create(paths: &Paths, params: &Value) -> Result<Value> {
         state: AlertState::default(),
         created_at: now,
         updated_at: now,
+        role: Some(role.as_str().to_string()),
     };
 
     let summary = json!({
@@ -571,6 +594,19 @@ async fn action_evaluate(paths: &Paths, ctx: &ToolContext, params: &Value) -> Re
     let last_value = store.rules[rule_idx].state.last_value;
     let cooldown_secs = store.rules[rule_idx].cooldown_secs;
     let last_triggered_at = store.rules[rule_idx].state.last_triggered_at;
+    let rule_snapshot = store.rules[rule_idx].clone();
+
+    if !rule_snapshot.may_call(ctx, &tool_name) {
+        let error = format!("Tool '{}' is not available to the rule's role", tool_name);
+        store.rules[rule_idx].state.last_error = Some(error.clone());
+        store.rules[rule_idx].state.last_check_at = Some(now);
+        save_store(paths, &store)?;
+        return Ok(json!({
+            "rule_id": rule_id,
+            "error": format!("Source tool denied: {}", error),
+            "triggered": false
+        }));
+    }
 
     // Execute the source tool call
     let tool_registry = crate::ToolRegistry::with_defaults();
@@ -694,7 +730,14 @@ async fn action_evaluate(paths: &Paths, ctx: &ToolContext, params: &Value) -> Re
 
             let label = action.label.as_deref().unwrap_or(&action.tool);
 
-            if action.require_confirm {
+            if !rule_snapshot.may_call(ctx, &action.tool) {
+                action_results.push(json!({
+                    "tool": action.tool,
+                    "label": label,
+                    "status": "denied",
+                    "error": format!("Tool '{}' is not available to the rule's role", action.tool)
+                }));
+            } else if action.require_confirm {
                 // For actions requiring confirmation, just report them — don't auto-execute
                 action_results.push(json!({
                     "tool": action.tool,
@@ -6,7 +6,7 @@ use tokio::process::Command;
 use tokio::time::sleep;
 use tracing::{debug, info, warn};
 
-use crate::{Tool, ToolContext, ToolSchema};
+use crate::{Tool, ToolConcurrency, ToolContext, ToolSchema};
 
 /// Tool for controlling any macOS application via AppleScript + System Events.
 ///
@@ -128,6 +128,11 @@ impl Tool for AppControlTool {
         Ok(())
     }
 
+    /// UI automation fights over focus and the keyboard when two calls overlap.
+    fn concurrency(&self) -> ToolConcurrency {
+        ToolConcurrency::exclusive(60)
+    }
+
     async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
         let action = params
             .get("action")
@@ -0,0 +1,715 @@
+//! Rule-based strategy backtests over locally stored price history.
+//!
+//! Candles come from the tick history `stream_subscribe` keeps with `persist: true`
+//! (`streams/history.db`), bucketed by `interval_secs`, or are passed inline. A strategy
+//! is a JSON object of entry/exit conditions over price fields and indicators; the
+//! simulation is long-only, all-in, and fills at the close of the signal candle with
+//! fees and slippage applied on both sides.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use blockcell_core::{Error, Result};
+use blockcell_storage::timeseries::TimeSeriesStore;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{Tool, ToolContext, ToolSchema};
+
+/// Ticks read from history for one run.
+const MAX_TICKS: usize = 500_000;
+/// Equity curve points returned (and charted); longer curves are downsampled.
+const MAX_CURVE_POINTS: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+struct Candle {
+    timestamp: i64,
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum Operand {
+    Number(f64),
+    Indicator(String),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Condition {
+    left: Operand,
+    op: String,
+    right: Operand,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Strategy {
+    /// All must hold to open a position.
+    entry: Vec<Condition>,
+    /// All must hold to close it; empty = only stops close positions.
+    #[serde(default)]
+    exit: Vec<Condition>,
+    #[serde(default)]
+    stop_loss_pct: Option<f64>,
+    #[serde(default)]
+    take_profit_pct: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Costs {
+    initial_capital: f64,
+    fee_pct: f64,
+    slippage_pct: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Trade {
+    entry_time: i64,
+    entry_price: f64,
+    exit_time: i64,
+    exit_price: f64,
+    return_pct: f64,
+    /// `signal`, `stop_loss`, `take_profit` or `end_of_data`.
+    exit_reason: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Summary {
+    candles: usize,
+    initial_capital: f64,
+    final_equity: f64,
+    total_return_pct: f64,
+    buy_and_hold_return_pct: f64,
+    max_drawdown_pct: f64,
+    trades: usize,
+    win_rate_pct: Option<f64>,
+    avg_trade_return_pct: Option<f64>,
+    best_trade_pct: Option<f64>,
+    worst_trade_pct: Option<f64>,
+    fees_paid: f64,
+    /// Share of candles spent in a position.
+    exposure_pct: f64,
+}
+
+struct Backtest {
+    summary: Summary,
+    trades: Vec<Trade>,
+    /// `(timestamp, strategy equity, buy-and-hold equity)` per candle.
+    equity: Vec<(i64, f64, f64)>,
+}
+
+/// Indicator values per candle; `None` while the lookback is not filled yet.
+fn indicator(name: &str, candles: &[Candle]) -> Result<Vec<Option<f64>>> {
+    let closes: Vec<f64> = candles.iter().map(|c| c.close).collect();
+    let field = |f: fn(&Candle) -> f64| -> Vec<Option<f64>> {
+        candles.iter().map(|c| Some(f(c))).collect()
+    };
+    let (kind, period) = match name.split_once(':') {
+        Some((kind, period)) => {
+            let period: usize = period
+                .trim()
+                .parse()
+                .ok()
+                .filter(|p| *p > 0)
+                .ok_or_else(|| Error::Tool(format!("Invalid period in indicator '{}'", name)))?;
+            (kind.trim(), period)
+        }
+        None => (name.trim(), 0),
+    };
+    let n = candles.len();
+    let values = match (kind, period) {
+        ("open", 0) => field(|c| c.open),
+        ("high", 0) => field(|c| c.high),
+        ("low", 0) => field(|c| c.low),
+        ("close", 0) => field(|c| c.close),
+        ("sma", p) if p > 0 => (0..n)
+            .map(|i| (i + 1 >= p).then(|| closes[i + 1 - p..=i].iter().sum::<f64>() / p as f64))
+            .collect(),
+        ("ema", p) if p > 0 => {
+            let k = 2.0 / (p as f64 + 1.0);
+            let mut out = vec![None; n];
+            if n >= p {
+                let mut ema = closes[..p].iter().sum::<f64>() / p as f64;
+                out[p - 1] = Some(ema);
+                for (i, close) in closes.iter().enumerate().skip(p) {
+                    ema = close * k + ema * (1.0 - k);
+                    out[i] = Some(ema);
+                }
+            }
+            out
+        }
+        ("rsi", p) if p > 0 => {
+            // Wilder's smoothing.
+            let mut out = vec![None; n];
+            let (mut gain, mut loss) = (0.0, 0.0);
+            for i in 1..n {
+                let change = closes[i] - closes[i - 1];
+                let (up, down) = (change.max(0.0), (-change).max(0.0));
+                if i <= p {
+                    gain += up / p as f64;
+                    loss += down / p as f64;
+                } else {
+                    gain = (gain * (p as f64 - 1.0) + up) / p as f64;
+                    loss = (loss * (p as f64 - 1.0) + down) / p as f64;
+                }
+                if i >= p {
+                    out[i] = Some(if loss == 0.0 {
+                        100.0
+                    } else {
+                        100.0 - 100.0 / (1.0 + gain / loss)
+                    });
+                }
+            }
+            out
+        }
+        // Extremes of the previous `p` candles, so `close > highest:20` is a breakout.
+        ("highest", p) if p > 0 => (0..n)
+            .map(|i| {
+                (i >= p).then(|| {
+                    candles[i - p..i]
+                        .iter()
+                        .map(|c| c.high)
+                        .fold(f64::MIN, f64::max)
+                })
+            })
+            .collect(),
+        ("lowest", p) if p > 0 => (0..n)
+            .map(|i| {
+                (i >= p).then(|| {
+                    candles[i - p..i]
+                        .iter()
+                        .map(|c| c.low)
+                        .fold(f64::MAX, f64::min)
+                })
+            })
+            .collect(),
+        ("change_pct", p) if p > 0 => (0..n)
+            .map(|i| {
+                (i >= p && closes[i - p] != 0.0)
+                    .then(|| (closes[i] - closes[i - p]) / closes[i - p] * 100.0)
+            })
+            .collect(),
+        _ => {
+            return Err(Error::Tool(format!(
+                "Unknown indicator '{}'. Use open, high, low, close, sma:N, ema:N, rsi:N, highest:N, lowest:N or change_pct:N",
+                name
+            )))
+        }
+    };
+    Ok(values)
+}
+
+struct Signals {
+    series: HashMap<String, Vec<Option<f64>>>,
+}
+
+impl Signals {
+    fn new(strategy: &Strategy, candles: &[Candle]) -> Result<Self> {
+        let mut series = HashMap::new();
+        for condition in strategy.entry.iter().chain(&strategy.exit) {
+            if !matches!(
+                condition.op.as_str(),
+                ">" | "<" | ">=" | "<=" | "crosses_above" | "crosses_below"
+            ) {
+                return Err(Error::Tool(format!(
+                    "Unknown operator '{}'. Use >, <, >=, <=, crosses_above or crosses_below",
+                    condition.op
+                )));
+            }
+            for operand in [&condition.left, &condition.right] {
+                if let Operand::Indicator(name) = operand {
+                    if let Entry::Vacant(slot) = series.entry(name.clone()) {
+                        slot.insert(indicator(name, candles)?);
+                    }
+                }
+            }
+        }
+        Ok(Self { series })
+    }
+
+    fn value(&self, operand: &Operand, i: usize) -> Option<f64> {
+        match operand {
+            Operand::Number(n) => Some(*n),
+            Operand::Indicator(name) => self.series.get(name)?.get(i).copied().flatten(),
+        }
+    }
+
+    fn holds(&self, condition: &Condition, i: usize) -> bool {
+        let (Some(left), Some(right)) = (
+            self.value(&condition.left, i),
+            self.value(&condition.right, i),
+        ) else {
+            return false;
+        };
+        let previous = || {
+            let i = i.checked_sub(1)?;
+            Some((
+                self.value(&condition.left, i)?,
+                self.value(&condition.right, i)?,
+            ))
+        };
+        match condition.op.as_str() {
+            ">" => left > right,
+            "<" => left < right,
+            ">=" => left >= right,
+            "<=" => left <= right,
+            "crosses_above" => previous().is_some_and(|(l, r)| l <= r) && left > right,
+            "crosses_below" => previous().is_some_and(|(l, r)| l >= r) && left < right,
+            _ => false,
+        }
+    }
+
+    fn all(&self, conditions: &[Condition], i: usize) -> bool {
+        !conditions.is_empty() && conditions.iter().all(|c| self.holds(c, i))
+    }
+}
+
+fn run(candles: &[Candle], strategy: &Strategy, costs: Costs) -> Result<Backtest> {
+    if candles.len() < 2 {
+        return Err(Error::Tool("Backtest needs at least 2 candles".into()));
+    }
+    if strategy.entry.is_empty() {
+        return Err(Error::Tool(
+            "Strategy needs at least one entry condition".into(),
+        ));
+    }
+    let signals = Signals::new(strategy, candles)?;
+    let fee = costs.fee_pct / 100.0;
+    let slippage = costs.slippage_pct / 100.0;
+
+    let mut cash = costs.initial_capital;
+    // (entry candle, entry fill price, units held, cash spent including fee)
+    let mut position: Option<(usize, f64, f64, f64)> = None;
+    let mut trades = Vec::new();
+    let mut equity = Vec::with_capacity(candles.len());
+    let mut fees_paid = 0.0;
+    let mut in_market = 0;
+    let first_close = candles[0].close;
+
+    for (i, candle) in candles.iter().enumerate() {
+        if let Some((entry_i, entry_price, units, spent)) = position {
+            let stop = strategy
+                .stop_loss_pct
+                .map(|pct| entry_price * (1.0 - pct / 100.0))
+                .filter(|stop| candle.low <= *stop);
+            let target = strategy
+                .take_profit_pct
+                .map(|pct| entry_price * (1.0 + pct / 100.0))
+                .filter(|target| candle.high >= *target);
+            let exit = if let Some(stop) = stop {
+                Some((stop.min(candle.open), "stop_loss"))
+            } else if let Some(target) = target {
+                Some((target.max(candle.open), "take_profit"))
+            } else if signals.all(&strategy.exit, i) {
+                Some((candle.close, "signal"))
+            } else if i == candles.len() - 1 {
+                Some((candle.close, "end_of_data"))
+            } else {
+                None
+            };
+            if let Some((price, reason)) = exit {
+                let fill = price * (1.0 - slippage);
+                let gross = units * fill;
+                let exit_fee = gross * fee;
+                fees_paid += exit_fee;
+                cash = gross - exit_fee;
+                trades.push(Trade {
+                    entry_time: candles[entry_i].timestamp,
+                    entry_price,
+                    exit_time: candle.timestamp,
+                    exit_price: fill,
+                    return_pct: (cash - spent) / spent * 100.0,
+                    exit_reason: reason,
+                });
+                position = None;
+            }
+        } else if i < candles.len() - 1 && signals.all(&strategy.entry, i) {
+            let fill = candle.close * (1.0 + slippage);
+            let entry_fee = cash * fee;
+            fees_paid += entry_fee;
+            position = Some((i, fill, (cash - entry_fee) / fill, cash));
+            cash = 0.0;
+        }
+
+        let value = match position {
+            Some((_, _, units, _)) => {
+                in_market += 1;
+                units * candle.close
+            }
+            None => cash,
+        };
+        let hold = costs.initial_capital * candle.close / first_close;
+        equity.push((candle.timestamp, value, hold));
+    }
+
+    let mut peak = f64::MIN;
+    let mut max_drawdown: f64 = 0.0;
+    for (_, value, _) in &equity {
+        peak = peak.max(*value);
+        if peak > 0.0 {
+            max_drawdown = max_drawdown.max((peak - value) / peak * 100.0);
+        }
+    }
+    let returns: Vec<f64> = trades.iter().map(|t| t.return_pct).collect();
+    let count = returns.len();
+    let wins = returns.iter().filter(|r| **r > 0.0).count();
+    let last_close = candles[candles.len() - 1].close;
+
+    Ok(Backtest {
+        summary: Summary {
+            candles: candles.len(),
+            initial_capital: costs.initial_capital,
+            final_equity: cash,
+            total_return_pct: (cash - costs.initial_capital) / costs.initial_capital * 100.0,
+            buy_and_hold_return_pct: (last_close - first_close) / first_close * 100.0,
+            max_drawdown_pct: max_drawdown,
+            trades: count,
+            win_rate_pct: (count > 0).then(|| wins as f64 / count as f64 * 100.0),
+            avg_trade_return_pct: (count > 0).then(|| returns.iter().sum::<f64>() / count as f64),
+            best_trade_pct: returns.iter().copied().reduce(f64::max),
+            worst_trade_pct: returns.iter().copied().reduce(f64::min),
+            fees_paid,
+            exposure_pct: in_market as f64 / candles.len() as f64 * 100.0,
+        },
+        trades,
+        equity,
+    })
+}
+
+/// OHLC candles of `interval_ms` from ticks ordered by time; ticks without a value are skipped.
+fn candles_from_ticks(ticks: &[(i64, f64)], interval_ms: i64) -> Vec<Candle> {
+    let mut candles: Vec<Candle> = Vec::new();
+    for &(timestamp, price) in ticks {
+        let bucket = timestamp.div_euclid(interval_ms) * interval_ms;
+        match candles.last_mut() {
+            Some(c) if c.timestamp == bucket => {
+                c.high = c.high.max(price);
+                c.low = c.low.min(price);
+                c.close = price;
+            }
+            _ => candles.push(Candle {
+                timestamp: bucket,
+                open: price,
+                high: price,
+                low: price,
+                close: price,
+            }),
+        }
+    }
+    candles
+}
+
+/// Every `step`-th point plus the last one, at most `MAX_CURVE_POINTS`.
+fn downsample<T: Copy>(points: &[T]) -> Vec<T> {
+    if points.is_empty() {
+        return Vec::new();
+    }
+    let step = points.len().div_ceil(MAX_CURVE_POINTS).max(1);
+    let mut out: Vec<T> = points.iter().step_by(step).copied().collect();
+    if !(points.len() - 1).is_multiple_of(step) {
+        out.extend(points.last().copied());
+    }
+    out
+}
+
+fn load_candles(ctx: &ToolContext, params: &Value) -> Result<(String, Vec<Candle>)> {
+    if let Some(inline) = params.get("candles") {
+        let mut candles: Vec<Candle> = serde_json::from_value(inline.clone()).map_err(|e| {
+            Error::Tool(format!(
+                "Invalid 'candles' (expected [{{timestamp, open, high, low, close}}]): {}",
+                e
+            ))
+        })?;
+        candles.sort_by_key(|c| c.timestamp);
+        return Ok(("inline".to_string(), candles));
+    }
+
+    let series = params
+        .get("series")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.trim().is_empty())
+        .ok_or_else(|| {
+            Error::Tool("Provide 'series' (stored stream history) or 'candles'".into())
+        })?;
+    let path = ctx.workspace.join("streams").join("history.db");
+    if !path.exists() {
+        return Err(Error::Tool(
+            "No local history yet; subscribe with stream_subscribe persist=true first".into(),
+        ));
+    }
+    let store = TimeSeriesStore::open(&path)?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let since = params
+        .get("since_timestamp")
+        .and_then(|v| v.as_i64())
+        .or_else(|| {
+            params
+                .get("window_secs")
+                .and_then(|v| v.as_i64())
+                .map(|secs| now - secs * 1000)
+        });
+    let until = params.get("until_timestamp").and_then(|v| v.as_i64());
+    let interval_secs = params
+        .get("interval_secs")
+        .and_then(|v| v.as_i64())
+        .unwrap_or(3600)
+        .max(1);
+
+    let ticks: Vec<(i64, f64)> = store
+        .range(series, since, until, MAX_TICKS)?
+        .into_iter()
+        .filter_map(|p| Some((p.timestamp, p.value?)))
+        .collect();
+    if ticks.is_empty() {
+        return Err(Error::Tool(format!(
+            "Series '{}' has no numeric points in this window (was it stored with value_path?)",
+            series
+        )));
+    }
+    Ok((
+        series.to_string(),
+        candles_from_ticks(&ticks, interval_secs * 1000),
+    ))
+}
+
+fn chart_params(label: &str, equity: &[(i64, f64, f64)]) -> Value {
+    let x: Vec<String> = equity
+        .iter()
+        .map(|(ts, _, _)| {
+            chrono::DateTime::from_timestamp_millis(*ts)
+                .map(|d| d.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_default()
+        })
+        .collect();
+    json!({
+        "action": "generate",
+        "chart_type": "line",
+        "title": format!("Backtest: {}", label),
+        "x_label": "Time",
+        "y_label": "Equity",
+        "data": {
+            "series": [
+                { "name": "Strategy", "x": x, "values": equity.iter().map(|e| e.1).collect::<Vec<_>>() },
+                { "name": "Buy & hold", "x": x, "values": equity.iter().map(|e| e.2).collect::<Vec<_>>() },
+            ]
+        }
+    })
+}
+
+pub struct BacktestTool;
+
+#[async_trait]
+impl Tool for BacktestTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "backtest",
+            description: "Backtest a rule-based long-only strategy over stored price history. Requires `strategy` and either `series` (a stream_subscribe series stored with persist=true, bucketed into candles of `interval_secs`) or inline `candles`. Optional `since_timestamp`/`window_secs`, `until_timestamp`, `initial_capital`, `fee_pct`, `slippage_pct`, `render_chart`. Returns summary stats, trades, an equity curve and `chart` params for chart_generate.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "series": {
+                        "type": "string",
+                        "description": "Stored series name (see stream_subscribe action=local_history)"
+                    },
+                    "candles": {
+                        "type": "array",
+                        "description": "Inline candles instead of a series: [{timestamp (ms), open, high, low, close}], oldest first",
+                        "items": { "type": "object" }
+                    },
+                    "interval_secs": {
+                        "type": "integer",
+                        "description": "Candle size when building candles from a series. Default: 3600"
+                    },
+                    "since_timestamp": {
+                        "type": "integer",
+                        "description": "Start of the window (ms since epoch)"
+                    },
+                    "window_secs": {
+                        "type": "integer",
+                        "description": "Window ending now, in seconds (alternative to since_timestamp)"
+                    },
+                    "until_timestamp": {
+                        "type": "integer",
+                        "description": "End of the window (ms since epoch)"
+                    },
+                    "strategy": {
+                        "type": "object",
+                        "description": "{entry: [conditions], exit: [conditions], stop_loss_pct, take_profit_pct}. A condition is {left, op, right}; operands are numbers or indicators: open, high, low, close, sma:N, ema:N, rsi:N, highest:N, lowest:N (previous N candles), change_pct:N. op: >, <, >=, <=, crosses_above, crosses_below. All conditions of a list must hold. Example: {\"entry\":[{\"left\":\"sma:10\",\"op\":\"crosses_above\",\"right\":\"sma:30\"}],\"exit\":[{\"left\":\"rsi:14\",\"op\":\">\",\"right\":70}],\"stop_loss_pct\":5}"
+                    },
+                    "initial_capital": {
+                        "type": "number",
+                        "description": "Starting capital. Default: 10000"
+                    },
+                    "fee_pct": {
+                        "type": "number",
+                        "description": "Fee per fill, percent of notional. Default: 0.1"
+                    },
+                    "slippage_pct": {
+                        "type": "number",
+                        "description": "Adverse price slippage per fill, percent. Default: 0.05"
+                    },
+                    "render_chart": {
+                        "type": "boolean",
+                        "description": "Render the equity curve with chart_generate right away. Default: false"
+                    }
+                },
+                "required": ["strategy"]
+            }),
+        }
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        if !params.get("strategy").is_some_and(|s| s.is_object()) {
+            return Err(Error::Tool("'strategy' must be an object".into()));
+        }
+        if params.get("series").is_none() && params.get("candles").is_none() {
+            return Err(Error::Tool(
+                "Provide 'series' (stored stream history) or 'candles'".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        let strategy: Strategy = serde_json::from_value(params["strategy"].clone())
+            .map_err(|e| Error::Tool(format!("Invalid strategy: {}", e)))?;
+        let number = |key: &str, default: f64| {
+            params
+                .get(key)
+                .and_then(|v| v.as_f64())
+                .unwrap_or(default)
+                .max(0.0)
+        };
+        let costs = Costs {
+            initial_capital: number("initial_capital", 10_000.0).max(1.0),
+            fee_pct: number("fee_pct", 0.1),
+            slippage_pct: number("slippage_pct", 0.05),
+        };
+
+        let (label, candles) = load_candles(&ctx, &params)?;
+        let backtest = run(&candles, &strategy, costs)?;
+        let curve = downsample(&backtest.equity);
+        let chart = chart_params(&label, &curve);
+
+        let rendered = if params
+            .get("render_chart")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+        {
+            let tool = crate::chart_generate::ChartGenerateTool;
+            match tool.execute(ctx.clone(), chart.clone()).await {
+                Ok(result) => result,
+                Err(e) => json!({ "error": e.to_string() }),
+            }
+        } else {
+            Value::Null
+        };
+
+        Ok(json!({
+            "source": label,
+            "from": candles.first().map(|c| c.timestamp),
+            "to": candles.last().map(|c| c.timestamp),
+            "summary": backtest.summary,
+            "trades": backtest.trades,
+            "equity_curve": curve
+                .iter()
+                .map(|(ts, equity, hold)| json!({ "timestamp": ts, "equity": equity, "buy_and_hold": hold }))
+                .collect::<Vec<_>>(),
+            "chart": chart,
+            "rendered_chart": rendered,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candles(closes: &[f64]) -> Vec<Candle> {
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, close)| Candle {
+                timestamp: i as i64 * 60_000,
+                open: *close,
+                high: *close,
+                low: *close,
+                close: *close,
+            })
+            .collect()
+    }
+
+    fn parse_strategy(value: Value) -> Strategy {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_crossover_strategy_trades_with_costs() {
+        let closes = [10.0, 9.0, 8.0, 9.0, 11.0, 12.0, 13.0, 12.0, 10.0, 9.0];
+        let strategy = parse_strategy(json!({
+            "entry": [{ "left": "close", "op": "crosses_above", "right": "sma:3" }],
+            "exit": [{ "left": "close", "op": "crosses_below", "right": "sma:3" }],
+        }));
+        let free = Costs {
+            initial_capital: 1000.0,
+            fee_pct: 0.0,
+            slippage_pct: 0.0,
+        };
+
+        let result = run(&candles(&closes), &strategy, free).unwrap();
+        assert_eq!(result.trades.len(), 1);
+        let trade = &result.trades[0];
+        assert_eq!((trade.entry_price, trade.exit_price), (9.0, 12.0));
+        assert_eq!(trade.exit_reason, "signal");
+        assert!((result.summary.final_equity - 1000.0 * 12.0 / 9.0).abs() < 1e-9);
+        assert!((result.summary.buy_and_hold_return_pct + 10.0).abs() < 1e-9);
+
+        let costly = run(
+            &candles(&closes),
+            &strategy,
+            Costs {
+                fee_pct: 0.1,
+                slippage_pct: 0.05,
+                ..free
+            },
+        )
+        .unwrap();
+        assert!(costly.summary.final_equity < result.summary.final_equity);
+        assert!(costly.summary.fees_paid > 0.0);
+    }
+
+    #[test]
+    fn test_stop_loss_candles_and_downsampling() {
+        let ticks = [(0, 100.0), (30_000, 104.0), (59_999, 101.0), (60_000, 90.0)];
+        let built = candles_from_ticks(&ticks, 60_000);
+        assert_eq!(built.len(), 2);
+        assert_eq!(
+            (built[0].open, built[0].high, built[0].close),
+            (100.0, 104.0, 101.0)
+        );
+
+        let strategy = parse_strategy(json!({
+            "entry": [{ "left": "close", "op": ">", "right": 0 }],
+            "stop_loss_pct": 5,
+        }));
+        let mut series = candles(&[100.0, 100.0, 100.0]);
+        series[1].low = 90.0;
+        let costs = Costs {
+            initial_capital: 1000.0,
+            fee_pct: 0.0,
+            slippage_pct: 0.0,
+        };
+        let result = run(&series, &strategy, costs).unwrap();
+        assert_eq!(result.trades[0].exit_reason, "stop_loss");
+        assert!((result.trades[0].return_pct + 5.0).abs() < 1e-9);
+        assert!(run(&series, &parse_strategy(json!({ "entry": [] })), costs).is_err());
+
+        let long: Vec<usize> = (0..1234).collect();
+        let sampled = downsample(&long);
+        assert!(sampled.len() <= MAX_CURVE_POINTS + 1);
+        assert_eq!(sampled.last(), Some(&1233));
+    }
+}
@@ -0,0 +1,712 @@
+//! Birthday and anniversary reminders for the user's contacts.
+//!
+//! Occasions are kept in `workspace/birthdays/occasions.json`. Each one owns yearly cron
+//! jobs (one per `tools.birthdays.leadDays` entry). On the day itself the job runs in agent
+//! mode so the agent drafts a greeting and asks before sending it through the contact's
+//! channel. `scan` finds dates in `contact` memory items and the channel contact registry
+//! resolves where a greeting should go.
+
+use async_trait::async_trait;
+use blockcell_core::{Error, Paths, Result};
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::cron::execute_cron_action_with_paths;
+use crate::{PromptContext, Tool, ToolContext, ToolSchema};
+
+/// Non-leap reference year for lead-day arithmetic; Feb 29 occasions use Feb 28.
+const REFERENCE_YEAR: i32 = 2025;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OccasionKind {
+    Birthday,
+    Anniversary,
+}
+
+impl OccasionKind {
+    fn parse(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "birthday" => Ok(Self::Birthday),
+            "anniversary" => Ok(Self::Anniversary),
+            other => Err(Error::Validation(format!(
+                "Unknown kind '{}'. Use birthday or anniversary",
+                other
+            ))),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Birthday => "生日",
+            Self::Anniversary => "纪念日",
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct OccasionStore {
+    #[serde(default)]
+    occasions: Vec<Occasion>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Occasion {
+    id: String,
+    person: String,
+    kind: OccasionKind,
+    month: u32,
+    day: u32,
+    /// Birth/wedding year when known; used for ages and "n-th anniversary".
+    #[serde(default)]
+    year: Option<i32>,
+    /// Where a greeting goes (channel + chat id), when known.
+    #[serde(default)]
+    channel: Option<String>,
+    #[serde(default)]
+    chat_id: Option<String>,
+    #[serde(default)]
+    note: Option<String>,
+    #[serde(default)]
+    reminder_jobs: Vec<String>,
+    created_at: i64,
+}
+
+fn store_path(workspace: &Path) -> PathBuf {
+    workspace.join("birthdays").join("occasions.json")
+}
+
+fn load_store(workspace: &Path) -> Result<OccasionStore> {
+    let path = store_path(workspace);
+    if !path.exists() {
+        return Ok(OccasionStore::default());
+    }
+    let content = std::fs::read_to_string(&path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+fn save_store(workspace: &Path, store: &OccasionStore) -> Result<()> {
+    let path = store_path(workspace);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(store)?)?;
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Dates
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Month, day and optional year from `YYYY-MM-DD`, `MM-DD` or vCard `--MM-DD`.
+fn parse_occasion_date(value: &str) -> Result<(u32, u32, Option<i32>)> {
+    let v = value.trim().trim_start_matches("--").replace('/', "-");
+    let parts: Vec<&str> = v.split('-').collect();
+    let (year, month, day) = match parts.as_slice() {
+        [y, m, d] => (Some(y.parse::<i32>().ok()), m.parse().ok(), d.parse().ok()),
+        [m, d] => (None, m.parse().ok(), d.parse().ok()),
+        _ => (None, None, None),
+    };
+    let (Some(month), Some(day)) = (month, day) else {
+        return Err(Error::Validation(format!(
+            "Invalid date '{}'. Use YYYY-MM-DD or MM-DD",
+            value
+        )));
+    };
+    // 2024 is a leap year, so Feb 29 validates.
+    if NaiveDate::from_ymd_opt(2024, month, day).is_none() {
+        return Err(Error::Validation(format!(
+            "'{}' is not a calendar date",
+            value
+        )));
+    }
+    let year = match year {
+        Some(Some(y)) => Some(y),
+        Some(None) => {
+            return Err(Error::Validation(format!("Invalid year in '{}'", value)));
+        }
+        None => None,
+    };
+    Ok((month, day, year))
+}
+
+/// Next date (today or later) on which a `month`/`day` occasion falls.
+fn next_occurrence(month: u32, day: u32, today: NaiveDate) -> NaiveDate {
+    let in_year = |y: i32| {
+        NaiveDate::from_ymd_opt(y, month, day)
+            .or_else(|| NaiveDate::from_ymd_opt(y, month, day - 1))
+            .expect("validated month/day")
+    };
+    let this_year = in_year(today.year());
+    if this_year >= today {
+        this_year
+    } else {
+        in_year(today.year() + 1)
+    }
+}
+
+/// Month/day of the reminder `lead` days before a `month`/`day` occasion.
+fn reminder_day(month: u32, day: u32, lead: u32) -> (u32, u32) {
+    let base = NaiveDate::from_ymd_opt(REFERENCE_YEAR, month, day)
+        .or_else(|| NaiveDate::from_ymd_opt(REFERENCE_YEAR, month, day - 1))
+        .expect("validated month/day");
+    let at = base - Duration::days(lead as i64);
+    (at.month(), at.day())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Detection in contact notes
+// ─────────────────────────────────────────────────────────────────────────────
+
+static ISO_DATE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\b(\d{4})[-/.](\d{1,2})[-/.](\d{1,2})\b").unwrap());
+static CJK_DATE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?:(\d{4})年)?(\d{1,2})月(\d{1,2})[日号]").unwrap());
+static MONTH_DAY: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec)[a-z]*\.?\s+(\d{1,2})(?:st|nd|rd|th)?(?:,?\s+(\d{4}))?\b").unwrap()
+});
+static DAY_MONTH: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(\d{1,2})(?:st|nd|rd|th)?\s+(?:of\s+)?(jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec)[a-z]*\.?(?:\s+(\d{4}))?\b").unwrap()
+});
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+fn month_index(name: &str) -> Option<u32> {
+    let lower = name.to_ascii_lowercase();
+    MONTHS
+        .iter()
+        .position(|m| *m == lower)
+        .map(|i| i as u32 + 1)
+}
+
+/// First date mentioned in `text`.
+fn find_date(text: &str) -> Option<(u32, u32, Option<i32>)> {
+    let num = |m: Option<regex::Match>| m.and_then(|m| m.as_str().parse::<i64>().ok());
+    let candidates = [
+        ISO_DATE
+            .captures(text)
+            .map(|c| (num(c.get(2)), num(c.get(3)), num(c.get(1)))),
+        CJK_DATE
+            .captures(text)
+            .map(|c| (num(c.get(2)), num(c.get(3)), num(c.get(1)))),
+        MONTH_DAY.captures(text).map(|c| {
+            (
+                c.get(1)
+                    .and_then(|m| month_index(m.as_str()))
+                    .map(i64::from),
+                num(c.get(2)),
+                num(c.get(3)),
+            )
+        }),
+        DAY_MONTH.captures(text).map(|c| {
+            (
+                c.get(2)
+                    .and_then(|m| month_index(m.as_str()))
+                    .map(i64::from),
+                num(c.get(1)),
+                num(c.get(3)),
+            )
+        }),
+    ];
+    candidates.into_iter().flatten().find_map(|(m, d, y)| {
+        let (m, d) = (m? as u32, d? as u32);
+        NaiveDate::from_ymd_opt(2024, m, d)?;
+        Some((m, d, y.map(|y| y as i32)))
+    })
+}
+
+fn kind_in(segment: &str) -> Option<OccasionKind> {
+    let lower = segment.to_lowercase();
+    if ["birthday", "bday", "born", "生日", "出生"]
+        .iter()
+        .any(|k| lower.contains(k))
+    {
+        Some(OccasionKind::Birthday)
+    } else if ["anniversary", "wedding", "married", "纪念日", "结婚"]
+        .iter()
+        .any(|k| lower.contains(k))
+    {
+        Some(OccasionKind::Anniversary)
+    } else {
+        None
+    }
+}
+
+/// Occasions mentioned in a contact note: each line/sentence with a keyword and a date.
+fn detect_occasions(text: &str) -> Vec<(OccasionKind, u32, u32, Option<i32>)> {
+    let mut found = Vec::new();
+    for segment in text.split(['\n', ';', '；', '。']) {
+        if let (Some(kind), Some((m, d, y))) = (kind_in(segment), find_date(segment)) {
+            if !found
+                .iter()
+                .any(|(k, fm, fd, _)| *k == kind && *fm == m && *fd == d)
+            {
+                found.push((kind, m, d, y));
+            }
+        }
+    }
+    found
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Reminders
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn reminder_message(o: &Occasion, lead: u32, draft: bool) -> String {
+    let when = format!("{}月{}日", o.month, o.day);
+    let mut text = if lead == 0 {
+        format!("今天是 {} 的{}（{}）。", o.person, o.kind.label(), when)
+    } else {
+        format!(
+            "提醒：{} 的{}在 {} 天后（{}）。",
+            o.person,
+            o.kind.label(),
+            lead,
+            when
+        )
+    };
+    if let Some(year) = o.year {
+        let word = match o.kind {
+            OccasionKind::Birthday => "岁",
+            OccasionKind::Anniversary => "周年",
+        };
+        text.push_str(&format!("（始于 {} 年，可据此计算{}数）", year, word));
+    }
+    if let Some(note) = &o.note {
+        text.push_str(&format!("\n备注：{}", note));
+    }
+    if lead == 0 && draft {
+        let target = match (&o.channel, &o.chat_id) {
+            (Some(channel), Some(chat_id)) => {
+                format!(
+                    "通过 message 工具发送到 channel={} chat_id={}",
+                    channel, chat_id
+                )
+            }
+            _ => "用户确认后由用户自行发送（没有已知的联系渠道）".to_string(),
+        };
+        text.push_str(&format!(
+            "\n请为用户起草一条简短、真诚的祝福消息，先展示草稿并征求用户确认；确认后{}。未经确认不要发送。",
+            target
+        ));
+    }
+    text
+}
+
+fn remove_jobs(paths: &Paths, occasion: &Occasion, channel: &str, chat_id: &str, tz: Option<&str>) {
+    for job in &occasion.reminder_jobs {
+        if let Err(e) = execute_cron_action_with_paths(
+            paths,
+            "remove",
+            &json!({"job_id": job}),
+            channel,
+            chat_id,
+            tz,
+            None,
+        ) {
+            warn!(job = %job, error = %e, "Failed to remove occasion reminder");
+        }
+    }
+}
+
+/// (Re)creates the yearly cron jobs for `occasion`; reminders go to the origin chat.
+#[allow(clippy::too_many_arguments)]
+fn schedule_reminders(
+    paths: &Paths,
+    occasion: &mut Occasion,
+    lead_days: &[u32],
+    hour: u32,
+    draft: bool,
+    channel: &str,
+    chat_id: &str,
+    tz: Option<&str>,
+    role: Option<&str>,
+) -> Result<()> {
+    remove_jobs(paths, occasion, channel, chat_id, tz);
+    occasion.reminder_jobs.clear();
+    let mut leads: Vec<u32> = lead_days.iter().copied().filter(|l| *l <= 60).collect();
+    leads.sort_unstable();
+    leads.dedup();
+    for lead in leads {
+        let (m, d) = reminder_day(occasion.month, occasion.day, lead);
+        let draft_here = draft && lead == 0;
+        let message = reminder_message(occasion, lead, draft);
+        let created = execute_cron_action_with_paths(
+            paths,
+            "add",
+            &json!({
+                "name": format!("{}: {}{}", occasion.kind.label(), occasion.person,
+                    if lead == 0 { String::new() } else { format!(" (-{}d)", lead) }),
+                "message": message,
+                "cron_expr": format!("0 0 {} {} {} *", hour.min(23), d, m),
+                "mode": if draft_here { "agent" } else { "reminder" },
+            }),
+            channel,
+            chat_id,
+            tz,
+            role,
+        )?;
+        if let Some(job) = created["job_id"].as_str() {
+            occasion.reminder_jobs.push(job.to_string());
+        }
+    }
+    Ok(())
+}
+
+fn occasion_json(o: &Occasion, today: NaiveDate) -> Value {
+    let next = next_occurrence(o.month, o.day, today);
+    json!({
+        "id": o.id,
+        "person": o.person,
+        "kind": o.kind,
+        "date": match o.year {
+            Some(y) => format!("{:04}-{:02}-{:02}", y, o.month, o.day),
+            None => format!("--{:02}-{:02}", o.month, o.day),
+        },
+        "next": next.format("%Y-%m-%d").to_string(),
+        "days_until": (next - today).num_days(),
+        "turning": o.year.map(|y| next.year() - y),
+        "channel": o.channel,
+        "chat_id": o.chat_id,
+        "note": o.note,
+        "reminders": o.reminder_jobs.len(),
+    })
+}
+
+/// Looks up where to send greetings for `person` in the channel contact registry.
+fn resolve_contact(ctx: &ToolContext, person: &str) -> Option<(String, String)> {
+    let file = ctx.channel_contacts_file.as_ref()?;
+    let base = file.parent()?;
+    let contacts = blockcell_storage::ChannelContacts::new(Paths::with_base(base.to_path_buf()));
+    let lower = person.to_lowercase();
+    let mut matches: Vec<_> = contacts
+        .load()
+        .into_iter()
+        .filter(|c| c.chat_type == "private" && c.name.to_lowercase() == lower)
+        .collect();
+    matches.sort_by(|a, b| b.last_active.cmp(&a.last_active));
+    matches.into_iter().next().map(|c| (c.channel, c.chat_id))
+}
+
+fn str_param<'a>(params: &'a Value, key: &str) -> Option<&'a str> {
+    params[key]
+        .as_str()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+pub struct BirthdaysTool;
+
+#[async_trait]
+impl Tool for BirthdaysTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "birthdays",
+            description: "Birthday and anniversary reminders for contacts. Each occasion gets yearly reminders (lead days from config); on the day the agent drafts a greeting and asks before sending it to the contact's channel. You MUST provide `action`. action='add': requires `person` and `date` (YYYY-MM-DD, or MM-DD when the year is unknown); optional `kind` (birthday|anniversary), `channel`+`chat_id` for greetings (looked up in known contacts when omitted), `note`. action='list'. action='upcoming': optional `days` (default 30). action='remove': requires `id`. action='scan': finds birthdays/anniversaries in saved contact memories; set `apply`=true to add them. action='resync': rebuilds all reminder jobs after a config change.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["add", "list", "upcoming", "remove", "scan", "resync"],
+                        "description": "Action to perform"
+                    },
+                    "person": {"type": "string", "description": "(add) Contact name"},
+                    "date": {"type": "string", "description": "(add) YYYY-MM-DD or MM-DD"},
+                    "kind": {"type": "string", "enum": ["birthday", "anniversary"]},
+                    "channel": {"type": "string", "description": "(add) Channel to greet on, e.g. telegram"},
+                    "chat_id": {"type": "string", "description": "(add) Chat id on that channel"},
+                    "note": {"type": "string", "description": "(add) Gift ideas, preferences, ..."},
+                    "id": {"type": "string", "description": "(remove) Occasion id"},
+                    "days": {"type": "integer", "description": "(upcoming) Window in days. Default: 30"},
+                    "apply": {"type": "boolean", "description": "(scan) Add the detected occasions. Default: false"}
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        match params["action"].as_str().unwrap_or("") {
+            "add" => {
+                if str_param(params, "person").is_none() {
+                    return Err(Error::Validation("'person' is required".into()));
+                }
+                parse_occasion_date(str_param(params, "date").unwrap_or(""))?;
+                OccasionKind::parse(params["kind"].as_str().unwrap_or(""))?;
+                Ok(())
+            }
+            "remove" => {
+                if str_param(params, "id").is_none() {
+                    return Err(Error::Validation("'id' is required for remove".into()));
+                }
+                Ok(())
+            }
+            "list" | "upcoming" | "scan" | "resync" => Ok(()),
+            other => Err(Error::Validation(format!(
+                "Unknown action '{}'. Use add, list, upcoming, remove, scan or resync",
+                other
+            ))),
+        }
+    }
+
+    fn prompt_rule(&self, _ctx: &PromptContext) -> Option<String> {
+        Some(
+            "- When the user mentions someone's birthday or anniversary, store it with `birthdays` `add` (not a hand-made cron job). Never send a greeting drafted for a birthday reminder without the user's explicit confirmation."
+                .to_string(),
+        )
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        let action = params["action"].as_str().unwrap_or("").to_string();
+        let cfg = ctx.config.tools.birthdays.clone();
+        let tz = ctx.config.default_timezone.clone();
+        let role = ctx.role();
+        tokio::task::spawn_blocking(move || {
+            let tz = tz.as_deref();
+            let today = NaiveDate::parse_from_str(&crate::todo::local_date_hour(tz).0, "%Y-%m-%d")
+                .unwrap_or_else(|_| Utc::now().date_naive());
+            let workspace = ctx.workspace.clone();
+            let paths = ctx.paths();
+            let mut store = load_store(&workspace)?;
+            let schedule = |o: &mut Occasion| {
+                schedule_reminders(
+                    &paths,
+                    o,
+                    &cfg.lead_days,
+                    cfg.remind_hour,
+                    cfg.draft_messages,
+                    &ctx.channel,
+                    &ctx.chat_id,
+                    tz,
+                    Some(role.as_str()),
+                )
+            };
+            match action.as_str() {
+                "add" => {
+                    let person = str_param(&params, "person").unwrap_or_default().to_string();
+                    let (month, day, year) =
+                        parse_occasion_date(str_param(&params, "date").unwrap_or_default())?;
+                    let kind = OccasionKind::parse(params["kind"].as_str().unwrap_or(""))?;
+                    if let Some(existing) = store
+                        .occasions
+                        .iter()
+                        .find(|o| o.kind == kind && o.person.eq_ignore_ascii_case(&person))
+                    {
+                        return Ok(
+                            json!({"status": "exists", "occasion": occasion_json(existing, today)}),
+                        );
+                    }
+                    let (channel, chat_id) =
+                        match (str_param(&params, "channel"), str_param(&params, "chat_id")) {
+                            (Some(c), Some(id)) => (Some(c.to_string()), Some(id.to_string())),
+                            _ => resolve_contact(&ctx, &person).unzip(),
+                        };
+                    let mut occasion = Occasion {
+                        id: format!(
+                            "occ_{}",
+                            Uuid::new_v4().to_string().split('-').next().unwrap_or("x")
+                        ),
+                        person,
+                        kind,
+                        month,
+                        day,
+                        year,
+                        channel,
+                        chat_id,
+                        note: str_param(&params, "note").map(String::from),
+                        reminder_jobs: Vec::new(),
+                        created_at: Utc::now().timestamp_millis(),
+                    };
+                    schedule(&mut occasion)?;
+                    let out = occasion_json(&occasion, today);
+                    store.occasions.push(occasion);
+                    save_store(&workspace, &store)?;
+                    Ok(json!({"status": "added", "occasion": out}))
+                }
+                "list" => {
+                    let mut items: Vec<&Occasion> = store.occasions.iter().collect();
+                    items.sort_by_key(|o| (o.month, o.day));
+                    let items: Vec<Value> = items.iter().map(|o| occasion_json(o, today)).collect();
+                    Ok(json!({"occasions": items, "count": items.len()}))
+                }
+                "upcoming" => {
+                    let days = params["days"].as_i64().unwrap_or(30).clamp(1, 366);
+                    let mut items: Vec<(NaiveDate, &Occasion)> = store
+                        .occasions
+                        .iter()
+                        .map(|o| (next_occurrence(o.month, o.day, today), o))
+                        .filter(|(next, _)| (*next - today).num_days() < days)
+                        .collect();
+                    items.sort_by_key(|(next, _)| *next);
+                    let items: Vec<Value> =
+                        items.iter().map(|(_, o)| occasion_json(o, today)).collect();
+                    Ok(json!({"upcoming": items, "days": days}))
+                }
+                "remove" => {
+                    let id = str_param(&params, "id").unwrap_or_default();
+                    let idx = store
+                        .occasions
+                        .iter()
+                        .position(|o| o.id == id)
+                        .ok_or_else(|| Error::NotFound(format!("Occasion '{}' not found", id)))?;
+                    let removed = store.occasions.remove(idx);
+                    remove_jobs(&paths, &removed, &ctx.channel, &ctx.chat_id, tz);
+                    save_store(&workspace, &store)?;
+                    Ok(json!({"status": "removed", "id": removed.id, "person": removed.person}))
+                }
+                "resync" => {
+                    for o in store.occasions.iter_mut() {
+                        schedule(o)?;
+                    }
+                    save_store(&workspace, &store)?;
+                    Ok(json!({"status": "resynced", "count": store.occasions.len()}))
+                }
+                "scan" => {
+                    let memory = ctx.memory_store.as_ref().ok_or_else(|| {
+                        Error::Tool("Memory store is not available for scanning contacts".into())
+                    })?;
+                    let results = memory.query_json(json!({"type": "contact", "top_k": 200}))?;
+                    let apply = params["apply"].as_bool().unwrap_or(false);
+                    let mut found = Vec::new();
+                    for r in results.as_array().into_iter().flatten() {
+                        let item = &r["item"];
+                        let Some(person) = item["title"]
+                            .as_str()
+                            .map(str::trim)
+                            .filter(|s| !s.is_empty())
+                        else {
+                            continue;
+                        };
+                        let text = format!(
+                            "{}\n{}",
+                            item["content"].as_str().unwrap_or(""),
+                            item["summary"].as_str().unwrap_or("")
+                        );
+                        for (kind, month, day, year) in detect_occasions(&text) {
+                            let known = store
+                                .occasions
+                                .iter()
+                                .any(|o| o.kind == kind && o.person.eq_ignore_ascii_case(person));
+                            let mut entry = json!({
+                                "person": person,
+                                "kind": kind,
+                                "month": month,
+                                "day": day,
+                                "year": year,
+                                "memory_id": item["id"],
+                                "already_tracked": known,
+                            });
+                            if apply && !known {
+                                let (channel, chat_id) = resolve_contact(&ctx, person).unzip();
+                                let mut occasion = Occasion {
+                                    id: format!(
+                                        "occ_{}",
+                                        Uuid::new_v4().to_string().split('-').next().unwrap_or("x")
+                                    ),
+                                    person: person.to_string(),
+                                    kind,
+                                    month,
+                                    day,
+                                    year,
+                                    channel,
+                                    chat_id,
+                                    note: None,
+                                    reminder_jobs: Vec::new(),
+                                    created_at: Utc::now().timestamp_millis(),
+                                };
+                                schedule(&mut occasion)?;
+                                entry["added"] = json!(occasion.id);
+                                store.occasions.push(occasion);
+                            }
+                            found.push(entry);
+                        }
+                    }
+                    if apply {
+                        save_store(&workspace, &store)?;
+                    }
+                    Ok(json!({"found": found, "count": found.len(), "applied": apply}))
+                }
+                other => Err(Error::Tool(format!("Unknown action: {}", other))),
+            }
+        })
+        .await
+        .map_err(|e| Error::Tool(format!("Birthdays task failed: {}", e)))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_dates() {
+        assert_eq!(
+            parse_occasion_date("1990-03-05").unwrap(),
+            (3, 5, Some(1990))
+        );
+        assert_eq!(parse_occasion_date("--02-29").unwrap(), (2, 29, None));
+        assert!(parse_occasion_date("02-30").is_err());
+
+        assert_eq!(next_occurrence(10, 20, d("2026-10-15")), d("2026-10-20"));
+        assert_eq!(next_occurrence(1, 2, d("2026-10-15")), d("2027-01-02"));
+        assert_eq!(next_occurrence(2, 29, d("2026-10-15")), d("2027-02-28"));
+
+        assert_eq!(reminder_day(3, 2, 3), (2, 27));
+        assert_eq!(reminder_day(2, 29, 0), (2, 28));
+    }
+
+    #[test]
+    fn test_detect_occasions() {
+        let found = detect_occasions(
+            "Alice works at ACME.\nBirthday: March 5th, 1990\n结婚纪念日是6月18日；likes tulips",
+        );
+        assert_eq!(
+            found,
+            vec![
+                (OccasionKind::Birthday, 3, 5, Some(1990)),
+                (OccasionKind::Anniversary, 6, 18, None),
+            ]
+        );
+        assert_eq!(
+            detect_occasions("born 1988-11-02 in Lyon"),
+            vec![(OccasionKind::Birthday, 11, 2, Some(1988))]
+        );
+        assert!(detect_occasions("Meeting on 2026-03-05").is_empty());
+    }
+
+    #[test]
+    fn test_reminder_message() {
+        let o = Occasion {
+            id: "occ_1".into(),
+            person: "Bob".into(),
+            kind: OccasionKind::Birthday,
+            month: 4,
+            day: 1,
+            year: Some(1990),
+            channel: Some("telegram".into()),
+            chat_id: Some("42".into()),
+            note: None,
+            reminder_jobs: Vec::new(),
+            created_at: 0,
+        };
+        let msg = reminder_message(&o, 0, true);
+        assert!(msg.contains("始于 1990 年"));
+        assert!(msg.contains("channel=telegram chat_id=42"));
+        let early = reminder_message(&o, 3, true);
+        assert!(early.contains("3 天后"));
+        assert!(!early.contains("起草"));
+    }
+}
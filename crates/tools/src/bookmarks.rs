@@ -0,0 +1,702 @@
+//! Read-later bookmarks with page snapshots, LLM summaries and full-text search.
+//!
+//! Bookmarks live in `workspace/bookmarks/bookmarks.json`; the fetched page text (Markdown)
+//! is kept next to it under `pages/<id>.md` so search works offline. Summaries are also
+//! upserted into memory (type `note`, dedup key `bookmark:<id>`) so they surface in recall.
+//! The runtime tick calls [`send_weekly_digest`] for the "saved but not read" reminder.
+
+use async_trait::async_trait;
+use blockcell_core::system_event::{EventPriority, SystemEvent};
+use blockcell_core::types::ChatMessage;
+use blockcell_core::{Config, Error, Result};
+use chrono::{Datelike, NaiveDate, Utc, Weekday};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::egress::HttpClientFactory;
+use crate::todo::local_date_hour;
+use crate::{EventEmitterHandle, PromptContext, Tool, ToolContext, ToolSchema};
+
+/// Page text sent to the summarizer.
+const MAX_SUMMARY_INPUT_CHARS: usize = 12_000;
+const SNIPPET_CHARS: usize = 160;
+const DIGEST_MAX_ITEMS: usize = 10;
+
+static STORE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BookmarkStore {
+    version: u32,
+    /// Local date of the last weekly digest.
+    #[serde(default)]
+    last_digest: Option<String>,
+    #[serde(default)]
+    bookmarks: Vec<Bookmark>,
+}
+
+impl Default for BookmarkStore {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            last_digest: None,
+            bookmarks: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Bookmark {
+    id: String,
+    url: String,
+    title: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    note: Option<String>,
+    #[serde(default)]
+    summary: Option<String>,
+    saved_at: i64,
+    #[serde(default)]
+    read_at: Option<i64>,
+    #[serde(default)]
+    fetch_error: Option<String>,
+    /// Memory item holding the summary, when `saveToMemory` is on.
+    #[serde(default)]
+    memory_id: Option<String>,
+}
+
+fn store_dir(workspace: &Path) -> PathBuf {
+    workspace.join("bookmarks")
+}
+
+fn store_path(workspace: &Path) -> PathBuf {
+    store_dir(workspace).join("bookmarks.json")
+}
+
+fn page_path(workspace: &Path, id: &str) -> PathBuf {
+    store_dir(workspace)
+        .join("pages")
+        .join(format!("{}.md", id))
+}
+
+fn load_store(workspace: &Path) -> Result<BookmarkStore> {
+    let path = store_path(workspace);
+    if !path.exists() {
+        return Ok(BookmarkStore::default());
+    }
+    let content = std::fs::read_to_string(&path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+fn save_store(workspace: &Path, store: &BookmarkStore) -> Result<()> {
+    let path = store_path(workspace);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(store)?)?;
+    Ok(())
+}
+
+fn normalize_tags(value: &Value) -> Vec<String> {
+    let raw: Vec<String> = match value {
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(String::from)
+            .collect(),
+        Value::String(s) => s.split(',').map(String::from).collect(),
+        _ => Vec::new(),
+    };
+    let mut tags: Vec<String> = raw
+        .iter()
+        .map(|t| t.trim().trim_start_matches('#').to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Fetch + summarize
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// First Markdown heading, else the last path segment of the URL.
+fn guess_title(markdown: &str, url: &str) -> String {
+    markdown
+        .lines()
+        .map(str::trim)
+        .find(|l| l.starts_with("# "))
+        .map(|l| l.trim_start_matches('#').trim().to_string())
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| {
+            url.trim_end_matches('/')
+                .rsplit('/')
+                .next()
+                .filter(|s| !s.is_empty() && !s.contains(':'))
+                .unwrap_or(url)
+                .to_string()
+        })
+}
+
+async fn fetch_page(config: &Config, url: &str) -> Result<String> {
+    let egress = HttpClientFactory::for_tool(config, "bookmarks");
+    egress.check(url).await?;
+    let max_chars = config.tools.bookmarks.max_content_chars.max(1_000);
+    let (content, meta) = crate::html_to_md::fetch_as_markdown(&egress, url, max_chars).await?;
+    if meta.status >= 400 {
+        return Err(Error::Tool(format!(
+            "{} returned HTTP {}",
+            url, meta.status
+        )));
+    }
+    Ok(content)
+}
+
+async fn summarize_page(config: &Config, title: &str, markdown: &str) -> Result<String> {
+    let provider = crate::doc_diff::default_provider(config)?;
+    let body: String = markdown.chars().take(MAX_SUMMARY_INPUT_CHARS).collect();
+    let system = "You write read-later summaries. Give a one-sentence gist followed by 2-5 short \
+        bullet points with the key facts, arguments or instructions. No preamble. Answer in the \
+        page's language.";
+    let user = format!("Title: {}\n\n{}", title, body);
+    let response = provider
+        .chat(
+            &[ChatMessage::system(system), ChatMessage::user(&user)],
+            &[],
+        )
+        .await?;
+    Ok(response.content.unwrap_or_default().trim().to_string())
+}
+
+/// Upserts the bookmark into memory and returns the memory item id.
+fn remember(ctx: &ToolContext, bookmark: &Bookmark) -> Option<String> {
+    let store = ctx.memory_store.as_ref()?;
+    let content = format!(
+        "{}\n{}\n\n{}",
+        bookmark.title,
+        bookmark.url,
+        bookmark.summary.as_deref().unwrap_or("")
+    );
+    let mut tags = vec!["bookmark".to_string()];
+    tags.extend(bookmark.tags.iter().cloned());
+    let params = json!({
+        "scope": "long_term",
+        "type": "note",
+        "title": format!("Bookmark: {}", bookmark.title),
+        "content": content,
+        "summary": bookmark.summary,
+        "tags": tags.join(","),
+        "source": "tool",
+        "channel": ctx.channel,
+        "session_key": ctx.session_key,
+        "importance": 0.4,
+        "dedup_key": format!("bookmark:{}", bookmark.id),
+    });
+    match store.upsert_json(params) {
+        Ok(item) => item["id"].as_str().map(String::from),
+        Err(e) => {
+            warn!(bookmark = %bookmark.id, error = %e, "Failed to store bookmark in memory");
+            None
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Search
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn count_matches(haystack: &str, term: &str) -> usize {
+    haystack.matches(term).count()
+}
+
+/// Scores a bookmark against lower-cased `terms`; every term must appear somewhere.
+fn score(bookmark: &Bookmark, content: &str, terms: &[String]) -> Option<usize> {
+    let title = bookmark.title.to_lowercase();
+    let summary = bookmark.summary.as_deref().unwrap_or("").to_lowercase();
+    let note = bookmark.note.as_deref().unwrap_or("").to_lowercase();
+    let url = bookmark.url.to_lowercase();
+    let mut total = 0;
+    for term in terms {
+        let hits = count_matches(&title, term) * 5
+            + bookmark
+                .tags
+                .iter()
+                .filter(|t| t.contains(term.as_str()))
+                .count()
+                * 5
+            + (count_matches(&summary, term) + count_matches(&note, term)) * 2
+            + count_matches(&url, term)
+            + count_matches(content, term);
+        if hits == 0 {
+            return None;
+        }
+        total += hits;
+    }
+    Some(total)
+}
+
+/// Text around the first occurrence of any term (char-boundary safe).
+fn snippet(content: &str, lower: &str, terms: &[String]) -> Option<String> {
+    let pos = terms.iter().filter_map(|t| lower.find(t.as_str())).min()?;
+    // Lower-casing can shift byte offsets for some scripts; clamp onto the original text.
+    let pos = pos.min(content.len());
+    let mut start = pos.saturating_sub(SNIPPET_CHARS / 2);
+    while start > 0 && !content.is_char_boundary(start) {
+        start -= 1;
+    }
+    let text: String = content[start..]
+        .chars()
+        .take(SNIPPET_CHARS)
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some(format!("…{}…", text))
+}
+
+fn bookmark_json(b: &Bookmark) -> Value {
+    json!({
+        "id": b.id,
+        "url": b.url,
+        "title": b.title,
+        "tags": b.tags,
+        "note": b.note,
+        "summary": b.summary,
+        "saved_at": b.saved_at,
+        "read": b.read_at.is_some(),
+        "fetch_error": b.fetch_error,
+    })
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Weekly digest
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn digest_text(bookmarks: &[Bookmark], now_ms: i64) -> Option<String> {
+    let week_ago = now_ms - 7 * 86_400_000;
+    let mut unread: Vec<&Bookmark> = bookmarks.iter().filter(|b| b.read_at.is_none()).collect();
+    if unread.is_empty() {
+        return None;
+    }
+    unread.sort_by_key(|b| std::cmp::Reverse(b.saved_at));
+    let recent = unread.iter().filter(|b| b.saved_at >= week_ago).count();
+    let mut out = format!(
+        "你有 {} 篇收藏还没读（本周新增 {} 篇）：",
+        unread.len(),
+        recent
+    );
+    for b in unread.iter().take(DIGEST_MAX_ITEMS) {
+        out.push_str(&format!("\n• {} — {}", b.title, b.url));
+        if let Some(gist) = b.summary.as_deref().and_then(|s| s.lines().next()) {
+            out.push_str(&format!("\n  {}", gist.trim()));
+        }
+    }
+    if unread.len() > DIGEST_MAX_ITEMS {
+        out.push_str(&format!(
+            "\n…以及另外 {} 篇",
+            unread.len() - DIGEST_MAX_ITEMS
+        ));
+    }
+    Some(out)
+}
+
+/// Emit the weekly "saved but not read" digest once on the configured day and hour.
+pub fn send_weekly_digest(config: &Config, workspace: &Path, emitter: &EventEmitterHandle) {
+    let cfg = &config.tools.bookmarks;
+    if !cfg.weekly_digest || !store_path(workspace).exists() {
+        return;
+    }
+    let (today, hour) = local_date_hour(config.default_timezone.as_deref());
+    let day: Weekday = match cfg.digest_day.trim().parse() {
+        Ok(day) => day,
+        Err(_) => {
+            warn!(day = %cfg.digest_day, "Invalid tools.bookmarks.digestDay");
+            return;
+        }
+    };
+    let is_day = NaiveDate::parse_from_str(&today, "%Y-%m-%d").is_ok_and(|d| d.weekday() == day);
+    if !is_day || hour < cfg.digest_hour {
+        return;
+    }
+    // Ticks run outside the tool lock; a concurrent save is picked up next week at worst.
+    let Ok(mut store) = load_store(workspace) else {
+        return;
+    };
+    if store.last_digest.as_deref() == Some(today.as_str()) {
+        return;
+    }
+    if let Some(text) = digest_text(&store.bookmarks, Utc::now().timestamp_millis()) {
+        let mut event = SystemEvent::new_main_session(
+            "bookmarks.digest",
+            "bookmarks",
+            EventPriority::Low,
+            "稍后阅读",
+            text,
+        );
+        event.dedup_key = Some(format!("bookmarks-digest:{}", today));
+        emitter.emit(event);
+        info!(date = %today, "Weekly bookmark digest queued");
+    }
+    store.last_digest = Some(today);
+    if let Err(e) = save_store(workspace, &store) {
+        warn!(error = %e, "Failed to record bookmark digest date");
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tool
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct BookmarksTool;
+
+#[async_trait]
+impl Tool for BookmarksTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "bookmarks",
+            description: "Read-later bookmarks. Saved pages are fetched, summarized and indexed for full-text search; unread items are listed in a weekly digest. You MUST provide `action`. action='save': requires `url`; optional `tags` (array or comma list), `note`, `title`. action='list': optional `tag`, `unread_only`, `limit`. action='search': requires `query`; optional `limit`. action='open': requires `id`; returns summary and saved text and marks it read. action='mark_read' / 'mark_unread': requires `id`. action='delete': requires `id`. action='refresh': requires `id`; re-fetches and re-summarizes.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["save", "list", "search", "open", "mark_read", "mark_unread", "delete", "refresh"],
+                        "description": "Action to perform"
+                    },
+                    "url": {"type": "string", "description": "(save) Page URL"},
+                    "title": {"type": "string", "description": "(save) Title override"},
+                    "tags": {"type": "array", "items": {"type": "string"}, "description": "(save) Tags"},
+                    "note": {"type": "string", "description": "(save) Why it was saved"},
+                    "tag": {"type": "string", "description": "(list) Only bookmarks with this tag"},
+                    "unread_only": {"type": "boolean", "description": "(list) Only unread bookmarks"},
+                    "query": {"type": "string", "description": "(search) Words to find in titles, tags, summaries and page text"},
+                    "id": {"type": "string", "description": "Bookmark id"},
+                    "limit": {"type": "integer", "description": "Maximum results. Default: 20 (list), 10 (search)"}
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        let has = |key: &str| params[key].as_str().is_some_and(|s| !s.trim().is_empty());
+        match params["action"].as_str().unwrap_or("") {
+            "save" => {
+                let url = params["url"].as_str().unwrap_or("").trim();
+                if !url.starts_with("http://") && !url.starts_with("https://") {
+                    return Err(Error::Validation(
+                        "'url' must start with http:// or https://".into(),
+                    ));
+                }
+                Ok(())
+            }
+            "search" => {
+                if !has("query") {
+                    return Err(Error::Validation("'query' is required for search".into()));
+                }
+                Ok(())
+            }
+            "open" | "mark_read" | "mark_unread" | "delete" | "refresh" => {
+                if !has("id") {
+                    return Err(Error::Validation("'id' is required".into()));
+                }
+                Ok(())
+            }
+            "list" => Ok(()),
+            other => Err(Error::Validation(format!(
+                "Unknown action '{}'. Use save, list, search, open, mark_read, mark_unread, delete or refresh",
+                other
+            ))),
+        }
+    }
+
+    fn prompt_rule(&self, _ctx: &PromptContext) -> Option<String> {
+        Some(
+            "- \"Save this for later\" / \"read later\" → `bookmarks` `save`. For \"that article I saved about X\", use `bookmarks` `search` before searching the web."
+                .to_string(),
+        )
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        let workspace = ctx.workspace.clone();
+        let cfg = ctx.config.tools.bookmarks.clone();
+        match params["action"].as_str().unwrap_or("") {
+            "save" => {
+                let url = params["url"].as_str().unwrap_or("").trim().to_string();
+                {
+                    let _guard = STORE_LOCK.lock().await;
+                    let store = load_store(&workspace)?;
+                    if let Some(existing) = store.bookmarks.iter().find(|b| b.url == url) {
+                        return Ok(
+                            json!({"status": "exists", "bookmark": bookmark_json(existing)}),
+                        );
+                    }
+                }
+                let id = format!(
+                    "bm_{}",
+                    Uuid::new_v4().to_string().split('-').next().unwrap_or("x")
+                );
+                let (content, fetch_error) = match fetch_page(&ctx.config, &url).await {
+                    Ok(content) => (Some(content), None),
+                    Err(e) => {
+                        warn!(url = %url, error = %e, "Bookmark fetch failed");
+                        (None, Some(e.to_string()))
+                    }
+                };
+                let title = params["title"]
+                    .as_str()
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(String::from)
+                    .unwrap_or_else(|| guess_title(content.as_deref().unwrap_or(""), &url));
+                let summary = match &content {
+                    Some(text) if cfg.summarize && !text.trim().is_empty() => {
+                        match summarize_page(&ctx.config, &title, text).await {
+                            Ok(s) if !s.is_empty() => Some(s),
+                            Ok(_) => None,
+                            Err(e) => {
+                                debug!(error = %e, "Bookmark summary skipped");
+                                None
+                            }
+                        }
+                    }
+                    _ => None,
+                };
+                let mut bookmark = Bookmark {
+                    id: id.clone(),
+                    url,
+                    title,
+                    tags: normalize_tags(&params["tags"]),
+                    note: params["note"]
+                        .as_str()
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .map(String::from),
+                    summary,
+                    saved_at: Utc::now().timestamp_millis(),
+                    read_at: None,
+                    fetch_error,
+                    memory_id: None,
+                };
+                if cfg.save_to_memory {
+                    bookmark.memory_id = remember(&ctx, &bookmark);
+                }
+                if let Some(text) = &content {
+                    let path = page_path(&workspace, &id);
+                    if let Some(parent) = path.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    std::fs::write(&path, text)?;
+                }
+                {
+                    let _guard = STORE_LOCK.lock().await;
+                    let mut store = load_store(&workspace)?;
+                    store.bookmarks.push(bookmark.clone());
+                    save_store(&workspace, &store)?;
+                }
+                Ok(json!({"status": "saved", "bookmark": bookmark_json(&bookmark)}))
+            }
+            "list" => {
+                let tag = params["tag"]
+                    .as_str()
+                    .map(|t| t.trim().trim_start_matches('#').to_lowercase());
+                let unread_only = params["unread_only"].as_bool().unwrap_or(false);
+                let limit = params["limit"].as_u64().unwrap_or(20).clamp(1, 200) as usize;
+                let _guard = STORE_LOCK.lock().await;
+                let store = load_store(&workspace)?;
+                let mut items: Vec<&Bookmark> = store
+                    .bookmarks
+                    .iter()
+                    .filter(|b| !unread_only || b.read_at.is_none())
+                    .filter(|b| tag.as_ref().is_none_or(|t| b.tags.contains(t)))
+                    .collect();
+                items.sort_by_key(|b| std::cmp::Reverse(b.saved_at));
+                let total = items.len();
+                let items: Vec<Value> = items.into_iter().take(limit).map(bookmark_json).collect();
+                Ok(json!({"bookmarks": items, "total": total}))
+            }
+            "search" => {
+                let query = params["query"].as_str().unwrap_or("").to_lowercase();
+                let terms: Vec<String> = query.split_whitespace().map(String::from).collect();
+                let limit = params["limit"].as_u64().unwrap_or(10).clamp(1, 50) as usize;
+                let store = {
+                    let _guard = STORE_LOCK.lock().await;
+                    load_store(&workspace)?
+                };
+                let mut hits: Vec<(usize, Value)> = Vec::new();
+                for b in &store.bookmarks {
+                    let content =
+                        std::fs::read_to_string(page_path(&workspace, &b.id)).unwrap_or_default();
+                    let lower = content.to_lowercase();
+                    if let Some(s) = score(b, &lower, &terms) {
+                        let mut item = bookmark_json(b);
+                        item["score"] = json!(s);
+                        item["snippet"] = json!(snippet(&content, &lower, &terms));
+                        hits.push((s, item));
+                    }
+                }
+                hits.sort_by_key(|h| std::cmp::Reverse(h.0));
+                let total = hits.len();
+                let results: Vec<Value> = hits.into_iter().take(limit).map(|(_, v)| v).collect();
+                Ok(json!({"query": query, "results": results, "total": total}))
+            }
+            "open" | "mark_read" | "mark_unread" => {
+                let action = params["action"].as_str().unwrap_or("");
+                let id = params["id"].as_str().unwrap_or("").trim();
+                let _guard = STORE_LOCK.lock().await;
+                let mut store = load_store(&workspace)?;
+                let b = store
+                    .bookmarks
+                    .iter_mut()
+                    .find(|b| b.id == id)
+                    .ok_or_else(|| Error::NotFound(format!("Bookmark '{}' not found", id)))?;
+                b.read_at = match action {
+                    "mark_unread" => None,
+                    _ => b.read_at.or(Some(Utc::now().timestamp_millis())),
+                };
+                let mut out = bookmark_json(b);
+                save_store(&workspace, &store)?;
+                if action == "open" {
+                    let text = std::fs::read_to_string(page_path(&workspace, id)).ok();
+                    out["content"] = json!(text);
+                }
+                Ok(out)
+            }
+            "refresh" => {
+                let id = params["id"].as_str().unwrap_or("").trim().to_string();
+                let existing = {
+                    let _guard = STORE_LOCK.lock().await;
+                    load_store(&workspace)?
+                        .bookmarks
+                        .into_iter()
+                        .find(|b| b.id == id)
+                        .ok_or_else(|| Error::NotFound(format!("Bookmark '{}' not found", id)))?
+                };
+                let content = fetch_page(&ctx.config, &existing.url).await?;
+                let summary = if cfg.summarize {
+                    summarize_page(&ctx.config, &existing.title, &content)
+                        .await
+                        .ok()
+                } else {
+                    None
+                };
+                let path = page_path(&workspace, &id);
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&path, &content)?;
+                let updated = {
+                    let _guard = STORE_LOCK.lock().await;
+                    let mut store = load_store(&workspace)?;
+                    let b = store
+                        .bookmarks
+                        .iter_mut()
+                        .find(|b| b.id == id)
+                        .ok_or_else(|| Error::NotFound(format!("Bookmark '{}' not found", id)))?;
+                    b.fetch_error = None;
+                    if summary.is_some() {
+                        b.summary = summary;
+                    }
+                    if cfg.save_to_memory {
+                        // Same dedup key, so this updates the existing memory item.
+                        b.memory_id = remember(&ctx, b).or(b.memory_id.take());
+                    }
+                    let updated = b.clone();
+                    save_store(&workspace, &store)?;
+                    updated
+                };
+                Ok(json!({"status": "refreshed", "bookmark": bookmark_json(&updated)}))
+            }
+            "delete" => {
+                let id = params["id"].as_str().unwrap_or("").trim();
+                let _guard = STORE_LOCK.lock().await;
+                let mut store = load_store(&workspace)?;
+                let pos = store
+                    .bookmarks
+                    .iter()
+                    .position(|b| b.id == id)
+                    .ok_or_else(|| Error::NotFound(format!("Bookmark '{}' not found", id)))?;
+                let removed = store.bookmarks.remove(pos);
+                save_store(&workspace, &store)?;
+                let _ = std::fs::remove_file(page_path(&workspace, id));
+                if let (Some(memory), Some(memory_id)) = (&ctx.memory_store, &removed.memory_id) {
+                    let _ = memory.soft_delete(memory_id);
+                }
+                Ok(json!({"status": "deleted", "id": id}))
+            }
+            other => Err(Error::Tool(format!("Unknown action: {}", other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bookmark(title: &str, tags: &[&str], saved_at: i64, read: bool) -> Bookmark {
+        Bookmark {
+            id: Uuid::new_v4().to_string(),
+            url: format!(
+                "https://example.com/{}",
+                title.to_lowercase().replace(' ', "-")
+            ),
+            title: title.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            note: None,
+            summary: None,
+            saved_at,
+            read_at: read.then_some(saved_at + 1),
+            fetch_error: None,
+            memory_id: None,
+        }
+    }
+
+    #[test]
+    fn test_title_and_tags() {
+        assert_eq!(
+            guess_title("intro\n# Rust Async Book\nbody", "https://x.io/a"),
+            "Rust Async Book"
+        );
+        assert_eq!(
+            guess_title("no heading", "https://x.io/posts/my-post/"),
+            "my-post"
+        );
+        assert_eq!(
+            normalize_tags(&json!("#Rust, async ,rust")),
+            vec!["async", "rust"]
+        );
+    }
+
+    #[test]
+    fn test_search_scoring() {
+        let terms = vec!["tokio".to_string(), "runtime".to_string()];
+        let b = bookmark("Tokio internals", &["rust"], 0, false);
+        assert!(score(&b, "the runtime drives futures", &terms).is_some());
+        assert!(score(&b, "nothing relevant here", &terms).is_none());
+
+        let lower = "a long page about the tokio runtime".to_string();
+        let snip = snippet("A long page about the Tokio runtime", &lower, &terms).unwrap();
+        assert!(snip.contains("Tokio runtime"));
+    }
+
+    #[test]
+    fn test_digest_text() {
+        let now = 30 * 86_400_000;
+        let items = vec![
+            bookmark("Old unread", &[], now - 20 * 86_400_000, false),
+            bookmark("New unread", &[], now - 86_400_000, false),
+            bookmark("Already read", &[], now - 86_400_000, true),
+        ];
+        let text = digest_text(&items, now).unwrap();
+        assert!(text.contains("2 篇收藏还没读（本周新增 1 篇）"));
+        assert!(text.find("New unread").unwrap() < text.find("Old unread").unwrap());
+        assert!(!text.contains("Already read"));
+        assert!(digest_text(&items[2..], now).is_none());
+    }
+}
@@ -247,6 +247,37 @@ impl CdpClient {
             .ok_or_else(|| "No screenshot data returned".to_string())
     }
 
+    /// Capture a screenshot of an explicit page region (CSS pixels), including content
+    /// beyond the current viewport. Returns base64-encoded PNG data.
+    pub async fn screenshot_clip(
+        &self,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    ) -> Result<String, String> {
+        let result = self
+            .send_command(
+                "Page.captureScreenshot",
+                json!({
+                    "format": "png",
+                    "captureBeyondViewport": true,
+                    "clip": {"x": x, "y": y, "width": width, "height": height, "scale": 1},
+                }),
+            )
+            .await?;
+        result
+            .get("data")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .ok_or_else(|| "No screenshot data returned".to_string())
+    }
+
+    /// Get page layout metrics (viewport and full content size).
+    pub async fn get_layout_metrics(&self) -> Result<Value, String> {
+        self.send_command("Page.getLayoutMetrics", json!({})).await
+    }
+
     /// Get the full accessibility tree via CDP.
     pub async fn get_accessibility_tree(&self) -> Result<Value, String> {
         self.send_command("Accessibility.getFullAXTree", json!({}))
@@ -414,9 +445,13 @@ impl CdpClient {
 
     /// Print page to PDF and return base64 data.
     pub async fn print_to_pdf(&self) -> Result<String, String> {
-        let result = self
-            .send_command("Page.printToPDF", json!({"printBackground": true}))
-            .await?;
+        self.print_to_pdf_with_options(json!({"printBackground": true}))
+            .await
+    }
+
+    /// Print page to PDF with explicit `Page.printToPDF` options and return base64 data.
+    pub async fn print_to_pdf_with_options(&self, options: Value) -> Result<String, String> {
+        let result = self.send_command("Page.printToPDF", options).await?;
         result
             .get("data")
             .and_then(|v| v.as_str())
@@ -354,6 +354,232 @@ fn render_node(
     }
 }
 
+/// Landmark roles used to group the accessible summary.
+const LANDMARK_ROLES: &[&str] = &[
+    "banner",
+    "navigation",
+    "main",
+    "complementary",
+    "contentinfo",
+    "search",
+    "form",
+    "region",
+];
+
+/// Characters of main-content text quoted at the end of the accessible summary.
+const ACCESSIBLE_INTRO_CHARS: usize = 400;
+/// Links listed per landmark before the rest are only counted.
+const ACCESSIBLE_LINKS_PER_GROUP: usize = 15;
+
+#[derive(Default)]
+struct Outline {
+    title: String,
+    landmarks: Vec<String>,
+    headings: Vec<String>,
+    /// (form label, field descriptions); the first group holds controls outside any form.
+    forms: Vec<(String, Vec<String>)>,
+    /// (landmark label, link descriptions).
+    links: Vec<(String, Vec<String>)>,
+    intro: String,
+}
+
+/// Plain-language role names, so the summary reads naturally through TTS.
+fn spoken_role(role: &str) -> &str {
+    match role {
+        "textbox" | "textField" | "TextField" => "text field",
+        "searchbox" => "search field",
+        "combobox" | "select" => "combo box",
+        "listbox" => "list box",
+        "checkbox" | "menuitemcheckbox" => "check box",
+        "radio" | "menuitemradio" => "radio button",
+        "spinbutton" => "number field",
+        "menuitem" => "menu item",
+        "treeitem" => "tree item",
+        "gridcell" => "cell",
+        "columnheader" => "column header",
+        "rowheader" => "row header",
+        "contentinfo" => "footer",
+        "banner" => "header",
+        "complementary" => "sidebar",
+        other => other,
+    }
+}
+
+fn landmark_label(node: &AXNode) -> String {
+    let role = spoken_role(&node.role);
+    if node.name.is_empty() {
+        role.to_string()
+    } else {
+        format!("{} \"{}\"", role, node.name)
+    }
+}
+
+fn control_label(node: &AXNode) -> String {
+    let mut label = spoken_role(&node.role).to_string();
+    if !node.name.is_empty() {
+        label.push_str(&format!(" \"{}\"", node.name));
+    }
+    if let Some(checked) = node.checked {
+        label.push_str(if checked {
+            ", checked"
+        } else {
+            ", not checked"
+        });
+    }
+    if node.properties.get("required").and_then(|v| v.as_bool()) == Some(true) {
+        label.push_str(", required");
+    }
+    if node.disabled {
+        label.push_str(", disabled");
+    }
+    if !node.value.is_empty() && node.value != node.name {
+        label.push_str(&format!(", value \"{}\"", node.value));
+    }
+    if let Some(ref ref_id) = node.ref_id {
+        label.push_str(&format!(" [ref={}]", ref_id));
+    }
+    label
+}
+
+fn collect_outline(
+    outline: &mut Outline,
+    node: &AXNode,
+    landmark: &str,
+    form: usize,
+    in_main: bool,
+) {
+    let role = node.role.as_str();
+    if role == "RootWebArea" && outline.title.is_empty() {
+        outline.title = node.name.clone();
+    }
+
+    let (mut landmark, mut form, mut in_main) = (landmark.to_string(), form, in_main);
+    if LANDMARK_ROLES.contains(&role) && (role != "region" || !node.name.is_empty()) {
+        landmark = landmark_label(node);
+        outline.landmarks.push(landmark.clone());
+        if role == "form" || role == "search" {
+            outline.forms.push((landmark.clone(), Vec::new()));
+            form = outline.forms.len() - 1;
+        }
+        in_main |= role == "main";
+    }
+
+    match role {
+        "heading" => {
+            let level = node.level.unwrap_or(2);
+            let indent = "  ".repeat(level.clamp(1, 6) as usize - 1);
+            let mut line = format!("{}Level {}: {}", indent, level, node.name);
+            if let Some(ref ref_id) = node.ref_id {
+                line.push_str(&format!(" [ref={}]", ref_id));
+            }
+            outline.headings.push(line);
+            return;
+        }
+        "link" => {
+            if node.name.is_empty() {
+                return;
+            }
+            let mut line = node.name.clone();
+            if let Some(ref ref_id) = node.ref_id {
+                line.push_str(&format!(" [ref={}]", ref_id));
+            }
+            match outline.links.iter_mut().find(|(l, _)| *l == landmark) {
+                Some((_, links)) => links.push(line),
+                None => outline.links.push((landmark.clone(), vec![line])),
+            }
+            return;
+        }
+        "StaticText" => {
+            if in_main && outline.intro.len() < ACCESSIBLE_INTRO_CHARS {
+                let text = node.name.trim();
+                if !text.is_empty() {
+                    if !outline.intro.is_empty() {
+                        outline.intro.push(' ');
+                    }
+                    outline.intro.push_str(text);
+                }
+            }
+            return;
+        }
+        _ if node.interactive => {
+            outline.forms[form].1.push(control_label(node));
+            return;
+        }
+        _ => {}
+    }
+
+    for child in &node.children {
+        collect_outline(outline, child, &landmark, form, in_main);
+    }
+}
+
+/// Render a screen-reader style summary: page title, landmarks, a headings outline,
+/// forms with their fields, and links grouped by landmark, in short sentences meant to be
+/// read aloud. Refs match the ones assigned for the regular snapshot.
+pub fn render_accessible(nodes: &[AXNode]) -> String {
+    let mut outline = Outline {
+        forms: vec![("Other controls".to_string(), Vec::new())],
+        ..Default::default()
+    };
+    for node in nodes {
+        collect_outline(&mut outline, node, "page", 0, false);
+    }
+
+    let mut out = String::new();
+    if !outline.title.is_empty() {
+        out.push_str(&format!("Page: {}.\n", outline.title));
+    }
+    if !outline.landmarks.is_empty() {
+        out.push_str(&format!("Landmarks: {}.\n", outline.landmarks.join(", ")));
+    }
+
+    out.push_str(&format!("\nHeadings ({}):\n", outline.headings.len()));
+    for heading in &outline.headings {
+        out.push_str(&format!("- {}\n", heading));
+    }
+
+    let forms: Vec<_> = outline
+        .forms
+        .iter()
+        .filter(|(_, fields)| !fields.is_empty())
+        .collect();
+    out.push_str(&format!("\nForms and controls ({}):\n", forms.len()));
+    for (label, fields) in forms {
+        out.push_str(&format!("- {}, {} fields:\n", label, fields.len()));
+        for field in fields {
+            out.push_str(&format!("  - {}\n", field));
+        }
+    }
+
+    let total: usize = outline.links.iter().map(|(_, l)| l.len()).sum();
+    out.push_str(&format!("\nLinks ({}):\n", total));
+    for (label, links) in &outline.links {
+        out.push_str(&format!("- In {}, {} links:\n", label, links.len()));
+        for link in links.iter().take(ACCESSIBLE_LINKS_PER_GROUP) {
+            out.push_str(&format!("  - {}\n", link));
+        }
+        if links.len() > ACCESSIBLE_LINKS_PER_GROUP {
+            out.push_str(&format!(
+                "  - and {} more\n",
+                links.len() - ACCESSIBLE_LINKS_PER_GROUP
+            ));
+        }
+    }
+
+    if !outline.intro.is_empty() {
+        let intro = if outline.intro.len() > ACCESSIBLE_INTRO_CHARS {
+            format!(
+                "{}...",
+                crate::safe_truncate(&outline.intro, ACCESSIBLE_INTRO_CHARS)
+            )
+        } else {
+            outline.intro.clone()
+        };
+        out.push_str(&format!("\nMain content begins: {}\n", intro));
+    }
+    out
+}
+
 /// Build a JSON representation of the snapshot for --json mode.
 pub fn snapshot_to_json(tree_text: &str, refs: &HashMap<String, Value>) -> Value {
     json!({
@@ -427,6 +653,82 @@ mod tests {
         assert!(text.contains("heading \"Example Domain\" [ref=e1] [level=1]"));
     }
 
+    fn ax(role: &str, name: &str, ref_id: Option<&str>, children: Vec<AXNode>) -> AXNode {
+        AXNode {
+            role: role.to_string(),
+            name: name.to_string(),
+            value: String::new(),
+            description: String::new(),
+            node_id: None,
+            backend_node_id: None,
+            ref_id: ref_id.map(str::to_string),
+            properties: HashMap::new(),
+            children,
+            depth: 0,
+            interactive: INTERACTIVE_ROLES.contains(&role),
+            focused: false,
+            checked: None,
+            disabled: false,
+            expanded: None,
+            level: None,
+        }
+    }
+
+    #[test]
+    fn test_render_accessible() {
+        let mut heading = ax("heading", "Welcome", Some("e3"), vec![]);
+        heading.level = Some(1);
+        let mut remember = ax("checkbox", "Remember me", Some("e6"), vec![]);
+        remember.checked = Some(false);
+        let nodes = vec![ax(
+            "RootWebArea",
+            "Example Shop",
+            None,
+            vec![
+                ax(
+                    "navigation",
+                    "Main",
+                    None,
+                    vec![
+                        ax("link", "Home", Some("e1"), vec![]),
+                        ax("link", "Cart", Some("e2"), vec![]),
+                    ],
+                ),
+                ax(
+                    "main",
+                    "",
+                    None,
+                    vec![
+                        heading,
+                        ax("StaticText", "Today's deals are below.", None, vec![]),
+                        ax(
+                            "form",
+                            "Sign in",
+                            None,
+                            vec![
+                                ax("textbox", "Email", Some("e4"), vec![]),
+                                ax("button", "Sign in", Some("e5"), vec![]),
+                                remember,
+                            ],
+                        ),
+                    ],
+                ),
+            ],
+        )];
+
+        let text = render_accessible(&nodes);
+        assert!(text.contains("Page: Example Shop."));
+        assert!(text.contains("Landmarks: navigation \"Main\", main, form \"Sign in\"."));
+        assert!(text.contains("- Level 1: Welcome [ref=e3]"));
+        assert!(text.contains("- form \"Sign in\", 3 fields:"));
+        assert!(text.contains("  - text field \"Email\" [ref=e4]"));
+        assert!(text.contains("  - check box \"Remember me\", not checked [ref=e6]"));
+        assert!(text.contains("- In navigation \"Main\", 2 links:"));
+        assert!(text.contains("  - Cart [ref=e2]"));
+        assert!(text.contains("Main content begins: Today's deals are below."));
+        assert!(!text.contains("Other controls"));
+    }
+
     #[test]
     fn test_compact_skips_structural() {
         let nodes = vec![AXNode {
@@ -7,8 +7,10 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use super::session::{list_available_browsers, BrowserEngine, SessionManager};
-use super::snapshot::{assign_refs, parse_ax_tree, render_tree, snapshot_to_json};
-use crate::{Tool, ToolContext, ToolSchema};
+use super::snapshot::{
+    assign_refs, parse_ax_tree, render_accessible, render_tree, snapshot_to_json,
+};
+use crate::{Tool, ToolConcurrency, ToolContext, ToolSchema};
 
 /// Global session manager (daemon model — persists across tool calls).
 static SESSION_MANAGER: once_cell::sync::Lazy<Arc<Mutex<Option<SessionManager>>>> =
@@ -41,7 +43,8 @@ impl Tool for BrowseTool {
                         "type": "string",
                         "enum": [
                             "navigate", "snapshot", "click", "fill", "type_text",
-                            "press_key", "scroll", "wait", "screenshot", "pdf",
+                            "fill_form", "press_key", "scroll", "wait", "screenshot", "screenshot_full",
+                            "pdf", "print_pdf",
                             "execute_js", "get_content", "get_url",
                             "cookies_get", "cookies_set", "cookies_clear",
                             "tab_list", "tab_new", "tab_close", "tab_switch",
@@ -52,7 +55,7 @@ impl Tool for BrowseTool {
                             "network_intercept", "network_continue", "network_block",
                             "list_browsers"
                         ],
-                        "description": "Browser action: 'navigate'=open URL (requires url param); 'snapshot'=get accessibility tree of current page (read page structure/links/text); 'get_content'=get full page text as markdown; 'screenshot'=capture page image (requires output_path); 'click'=click element (requires ref or selector); 'fill'=fill input field (requires ref/selector + text); 'type_text'=type into focused element; 'press_key'=press keyboard key; 'scroll'=scroll page; 'wait'=wait for element or time; 'execute_js'=run JavaScript; 'get_url'=get current URL; 'tab_list'=list open tabs; 'tab_new'=open new tab; 'tab_close'=close tab; 'tab_switch'=switch tab; 'back'/'forward'/'reload'=navigation; 'cookies_get'/'cookies_set'/'cookies_clear'=cookie ops; 'session_list'/'session_close'=session management; 'upload_file'=file upload; 'dialog_handle'=handle JS dialogs; 'network_intercept'/'network_continue'/'network_block'=network control; 'pdf'=save page as PDF; 'set_viewport'=set window size; 'set_headers'=set HTTP headers; 'list_browsers'=list available browsers. ALWAYS specify action explicitly."
+                        "description": "Browser action: 'navigate'=open URL (requires url param); 'snapshot'=get accessibility tree of current page (read page structure/links/text); 'get_content'=get full page text as markdown; 'screenshot'=capture page image (requires output_path); 'click'=click element (requires ref or selector); 'fill'=fill input field (requires ref/selector + text); 'fill_form'=fill a whole form in one call (requires fields object mapping label/name/id/placeholder/aria-label to value; handles selects and checkboxes, returns a diff of what was set); 'type_text'=type into focused element; 'press_key'=press keyboard key; 'scroll'=scroll page; 'wait'=wait for element or time; 'execute_js'=run JavaScript; 'get_url'=get current URL; 'tab_list'=list open tabs; 'tab_new'=open new tab; 'tab_close'=close tab; 'tab_switch'=switch tab; 'back'/'forward'/'reload'=navigation; 'cookies_get'/'cookies_set'/'cookies_clear'=cookie ops; 'session_list'/'session_close'=session management; 'upload_file'=file upload; 'dialog_handle'=handle JS dialogs; 'network_intercept'/'network_continue'/'network_block'=network control; 'print_pdf' (alias 'pdf')=save page as PDF via print (optional paper/landscape/scale/page_ranges); 'screenshot_full'=capture the entire scrollable page as one image; 'set_viewport'=set window size; 'set_headers'=set HTTP headers; 'list_browsers'=list available browsers. ALWAYS specify action explicitly."
                     },
                     "url": {
                         "type": "string",
@@ -74,6 +77,14 @@ impl Tool for BrowseTool {
                         "type": "string",
                         "description": "Text to fill/type, or JS expression for execute_js"
                     },
+                    "fields": {
+                        "type": "object",
+                        "description": "For 'fill_form': map of field identifier (label text, name, id, placeholder, aria-label or snapshot ref like 'e5') to value. Use true/false for checkboxes, option text or value for selects."
+                    },
+                    "submit": {
+                        "type": "boolean",
+                        "description": "For 'fill_form': submit the form after filling when all fields are valid (default: false)"
+                    },
                     "key": {
                         "type": "string",
                         "description": "Key to press (e.g. 'Enter', 'Tab', 'Escape', 'ArrowDown')"
@@ -107,6 +118,27 @@ impl Tool for BrowseTool {
                         "type": "boolean",
                         "description": "Full page screenshot (default: false)"
                     },
+                    "paper": {
+                        "type": "string",
+                        "enum": ["a4", "letter", "legal", "a3", "a5"],
+                        "description": "Paper size for print_pdf (default: page CSS size, else a4)"
+                    },
+                    "landscape": {
+                        "type": "boolean",
+                        "description": "Landscape orientation for print_pdf (default: false)"
+                    },
+                    "print_background": {
+                        "type": "boolean",
+                        "description": "Include background graphics in print_pdf (default: true)"
+                    },
+                    "scale": {
+                        "type": "number",
+                        "description": "Rendering scale for print_pdf, 0.1-2.0 (default: 1)"
+                    },
+                    "page_ranges": {
+                        "type": "string",
+                        "description": "Page ranges for print_pdf, e.g. '1-3, 5'"
+                    },
                     "output_path": {
                         "type": "string",
                         "description": "File path for screenshot/PDF output"
@@ -115,6 +147,11 @@ impl Tool for BrowseTool {
                         "type": "boolean",
                         "description": "Compact snapshot (skip empty structural nodes, default: true)"
                     },
+                    "mode": {
+                        "type": "string",
+                        "enum": ["tree", "accessible"],
+                        "description": "Snapshot format for 'snapshot'/'navigate': 'tree'=accessibility tree; 'accessible'=screen-reader style summary (landmarks, headings outline, forms, links grouped by landmark) suited to reading aloud. Default from tools.browser.accessibleMode"
+                    },
                     "cookie_name": { "type": "string" },
                     "cookie_value": { "type": "string" },
                     "cookie_domain": { "type": "string" },
@@ -174,8 +211,9 @@ impl Tool for BrowseTool {
 
     fn prompt_rule(&self, _ctx: &crate::PromptContext) -> Option<String> {
         Some(concat!(
-            "- **`browse` action选择规则**: 打开网页用 `navigate`+url; 读取页面内容用 `get_content`; 查看页面结构/元素用 `snapshot`; **截图用 `screenshot`（无需指定output_path）**; 点击元素用 `click`+ref/selector; 填写表单用 `fill`; 按键用 `press_key`. **绝对禁止**调用 `browse` 时不带 `action` 参数——必须明确指定 action。\n",
-            "- **`browse screenshot` 路径规则**: 截图**始终**自动保存在 workspace/media/ 下，返回结果中的 `path` 字段即为可展示的路径，直接用该路径给用户展示即可。**不要**把 `output_path` 设为桌面或其他绝对路径——那样会导致 WebUI 无法显示截图。如果用户要求把截图存到某个特定位置（如桌面），工具会自动 copy 一份过去，你无需额外操作，直接用返回的 `path` 字段展示图片。"
+            "- **`browse` action选择规则**: 打开网页用 `navigate`+url; 读取页面内容用 `get_content`; 查看页面结构/元素用 `snapshot`; **截图用 `screenshot`（无需指定output_path），整页长截图用 `screenshot_full`**; **保存网页为 PDF 用 `print_pdf`**; 点击元素用 `click`+ref/selector; 填写单个输入框用 `fill`; **填写多个表单字段时优先用 `fill_form`+fields 一次完成**，不要逐个 `fill`; 按键用 `press_key`. **绝对禁止**调用 `browse` 时不带 `action` 参数——必须明确指定 action。\n",
+            "- **`browse screenshot` 路径规则**: 截图**始终**自动保存在 workspace/media/ 下，返回结果中的 `path` 字段即为可展示的路径（PDF 同理，`serve_url` 可直接作为下载链接），直接用该路径给用户展示即可。**不要**把 `output_path` 设为桌面或其他绝对路径——那样会导致 WebUI 无法显示截图。如果用户要求把截图存到某个特定位置（如桌面），工具会自动 copy 一份过去，你无需额外操作，直接用返回的 `path` 字段展示图片。\n",
+            "- **无障碍浏览**: 用户看不见屏幕或要求朗读网页时，`navigate`/`snapshot` 传 `mode: \"accessible\"`，按 地标→标题大纲→表单→链接 的顺序用简短口语句子转述，不要念出 ref；用户选中某项后再用对应 ref 执行 `click`/`fill`。"
         ).to_string())
     }
 
@@ -183,6 +221,11 @@ impl Tool for BrowseTool {
         Ok(())
     }
 
+    /// Actions drive one shared browser profile; interleaving them corrupts page state.
+    fn concurrency(&self) -> ToolConcurrency {
+        ToolConcurrency::exclusive(120)
+    }
+
     async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
         let action = params["action"].as_str().unwrap_or_else(|| {
             if params.get("url").and_then(|v| v.as_str()).is_some() {
@@ -198,6 +241,11 @@ impl Tool for BrowseTool {
             .and_then(|s| s.parse::<BrowserEngine>().ok())
             .unwrap_or(BrowserEngine::Chrome);
 
+        let accessible = match params["mode"].as_str() {
+            Some(mode) => mode == "accessible",
+            None => ctx.config.tools.browser.accessible_mode,
+        };
+
         let workspace = ctx.workspace.clone();
         let mgr_arc = ensure_manager(&workspace).await;
         let mut mgr_guard = mgr_arc.lock().await;
@@ -236,16 +284,18 @@ impl Tool for BrowseTool {
             .map_err(|e| blockcell_core::Error::Tool(format!("session error: {}", e)))?;
 
         match action {
-            "navigate" => action_navigate(session, &params).await,
-            "snapshot" => action_snapshot(session, &params).await,
+            "navigate" => action_navigate(session, &params, accessible).await,
+            "snapshot" => action_snapshot(session, &params, accessible).await,
             "click" => action_click(session, &params).await,
             "fill" => action_fill(session, &params).await,
+            "fill_form" => action_fill_form(session, &params).await,
             "type_text" => action_type_text(session, &params).await,
             "press_key" => action_press_key(session, &params).await,
             "scroll" => action_scroll(session, &params).await,
             "wait" => action_wait(session, &params).await,
             "screenshot" => action_screenshot(session, &params, &workspace).await,
-            "pdf" => action_pdf(session, &params, &workspace).await,
+            "screenshot_full" => action_screenshot_full(session, &params, &workspace).await,
+            "pdf" | "print_pdf" => action_print_pdf(session, &params, &workspace).await,
             "execute_js" => action_execute_js(session, &params).await,
             "get_content" => action_get_content(session).await,
             "get_url" => action_get_url(session).await,
@@ -279,7 +329,11 @@ use super::session::BrowserSession;
 
 // ─── Action implementations ───────────────────────────────────────────
 
-async fn action_navigate(session: &mut BrowserSession, params: &Value) -> Result<Value> {
+async fn action_navigate(
+    session: &mut BrowserSession,
+    params: &Value,
+    accessible: bool,
+) -> Result<Value> {
     let url = params["url"]
         .as_str()
         .ok_or_else(|| blockcell_core::Error::Tool("navigate requires 'url'".into()))?;
@@ -327,7 +381,7 @@ async fn action_navigate(session: &mut BrowserSession, params: &Value) -> Result
         session.current_url = Some(url.to_string());
 
         tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
-        let snap = take_snapshot(session, true).await?;
+        let snap = take_snapshot(session, true, accessible).await?;
 
         tracing::info!(
             session = %session.name,
@@ -354,7 +408,7 @@ async fn action_navigate(session: &mut BrowserSession, params: &Value) -> Result
     tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
 
     // Auto-snapshot after navigation
-    let snap = take_snapshot(session, true).await?;
+    let snap = take_snapshot(session, true, accessible).await?;
 
     tracing::info!(
         session = %session.name,
@@ -371,9 +425,13 @@ async fn action_navigate(session: &mut BrowserSession, params: &Value) -> Result
     }))
 }
 
-async fn action_snapshot(session: &mut BrowserSession, params: &Value) -> Result<Value> {
+async fn action_snapshot(
+    session: &mut BrowserSession,
+    params: &Value,
+    accessible: bool,
+) -> Result<Value> {
     let compact = params["compact"].as_bool().unwrap_or(true);
-    take_snapshot(session, compact).await
+    take_snapshot(session, compact, accessible).await
 }
 
 async fn action_click(session: &mut BrowserSession, params: &Value) -> Result<Value> {
@@ -446,6 +504,232 @@ async fn action_fill(session: &mut BrowserSession, params: &Value) -> Result<Val
     Ok(json!({"status": "filled", "target": target, "text": text}))
 }
 
+/// In-page script for `fill_form`. `__FIELDS__` / `__SUBMIT__` are replaced with JSON literals.
+///
+/// Fields are matched in order of confidence: snapshot ref tag, id, name, exact label,
+/// aria-label, placeholder, then partial label match.
+const FILL_FORM_JS: &str = r#"(function(fields, submit) {
+  const norm = s => String(s == null ? '' : s).replace(/\s+/g, ' ').trim().toLowerCase();
+  const controls = Array.from(document.querySelectorAll('input, select, textarea'))
+    .filter(el => el.type !== 'hidden' && !el.disabled);
+  const labelOf = el => {
+    const parts = [];
+    if (el.labels) for (const l of el.labels) parts.push(l.innerText);
+    const ids = el.getAttribute('aria-labelledby');
+    if (ids) ids.split(/\s+/).forEach(id => { const n = document.getElementById(id); if (n) parts.push(n.innerText); });
+    return norm(parts.join(' '));
+  };
+  const find = key => {
+    const tagged = document.querySelector('[data-bc-fill="' + CSS.escape(key) + '"]');
+    if (tagged) return [tagged, 'ref'];
+    const k = norm(key);
+    const checks = [
+      ['id', el => norm(el.id) === k],
+      ['name', el => norm(el.name) === k],
+      ['label', el => labelOf(el) === k],
+      ['aria-label', el => norm(el.getAttribute('aria-label')) === k],
+      ['placeholder', el => norm(el.placeholder) === k],
+      ['label_partial', el => { const l = labelOf(el); return !!l && (l.includes(k) || k.includes(l)); }],
+    ];
+    for (const [by, test] of checks) {
+      const el = controls.find(test);
+      if (el) return [el, by];
+    }
+    return [null, null];
+  };
+  const current = el => {
+    if (el.type === 'checkbox') return el.checked;
+    if (el.type === 'radio') {
+      const picked = el.name ? document.querySelector('input[type=radio][name="' + CSS.escape(el.name) + '"]:checked') : (el.checked ? el : null);
+      return picked ? picked.value : null;
+    }
+    if (el.tagName === 'SELECT' && el.multiple) return Array.from(el.selectedOptions).map(o => o.value);
+    return el.value;
+  };
+  const truthy = v => v === true || ['true', 'yes', 'on', '1', 'checked'].includes(norm(v));
+  const results = [];
+  const unmatched = [];
+  let form = null;
+  for (const [key, value] of Object.entries(fields)) {
+    let [el, by] = find(key);
+    if (!el) { unmatched.push(key); continue; }
+    const before = current(el);
+    let error = null;
+    try {
+      el.scrollIntoView({block: 'center'});
+      el.focus();
+      if (el.type === 'checkbox') {
+        if (el.checked !== truthy(value)) el.click();
+      } else if (el.type === 'radio') {
+        const group = el.name ? Array.from(document.querySelectorAll('input[type=radio][name="' + CSS.escape(el.name) + '"]')) : [el];
+        const wanted = norm(value);
+        const target = group.find(r => norm(r.value) === wanted || labelOf(r) === wanted) || (truthy(value) ? el : null);
+        if (target) { if (!target.checked) target.click(); el = target; }
+        else error = 'no radio option matches ' + JSON.stringify(value);
+      } else if (el.tagName === 'SELECT') {
+        const wants = (Array.isArray(value) ? value : [value]).map(norm);
+        let hits = 0;
+        for (const o of el.options) {
+          const hit = wants.includes(norm(o.value)) || wants.includes(norm(o.text));
+          if (el.multiple) o.selected = hit;
+          else if (hit && hits === 0) el.value = o.value;
+          if (hit) hits++;
+        }
+        if (hits === 0) error = 'no option matches ' + JSON.stringify(value);
+      } else {
+        const proto = el.tagName === 'TEXTAREA' ? HTMLTextAreaElement.prototype : HTMLInputElement.prototype;
+        const text = Array.isArray(value) ? value.join(', ') : String(value);
+        Object.getOwnPropertyDescriptor(proto, 'value').set.call(el, text);
+      }
+      el.dispatchEvent(new Event('input', {bubbles: true}));
+      el.dispatchEvent(new Event('change', {bubbles: true}));
+      if (el.blur) el.blur();
+    } catch (e) {
+      error = String(e);
+    }
+    if (!form && el.form) form = el.form;
+    results.push({
+      field: key,
+      matched_by: by,
+      tag: el.tagName.toLowerCase(),
+      type: el.type || '',
+      name: el.name || el.id || '',
+      before: before,
+      after: current(el),
+      valid: el.checkValidity ? el.checkValidity() : true,
+      validation_message: el.validationMessage || '',
+      error: error,
+    });
+  }
+  document.querySelectorAll('[data-bc-fill]').forEach(n => n.removeAttribute('data-bc-fill'));
+  let submitted = false;
+  if (submit && form && unmatched.length === 0 && results.every(r => r.valid && !r.error)) {
+    if (form.requestSubmit) form.requestSubmit(); else form.submit();
+    submitted = true;
+  }
+  return {results: results, unmatched: unmatched, submitted: submitted, form_found: !!form};
+})(__FIELDS__, __SUBMIT__)"#;
+
+/// Fill multiple form fields in a single round-trip and return a diff of what changed.
+async fn action_fill_form(session: &mut BrowserSession, params: &Value) -> Result<Value> {
+    let fields = params["fields"]
+        .as_object()
+        .filter(|f| !f.is_empty())
+        .ok_or_else(|| {
+            blockcell_core::Error::Tool(
+                "fill_form requires a non-empty 'fields' object (label/name -> value)".into(),
+            )
+        })?
+        .clone();
+    let submit = params["submit"].as_bool().unwrap_or(false);
+
+    // Keys that are snapshot refs get tagged on the DOM node so the page script can find them.
+    for key in fields.keys() {
+        let ref_id = key.trim_start_matches('@');
+        let backend_node_id = session
+            .refs
+            .get(ref_id)
+            .and_then(|r| r["backendNodeId"].as_i64());
+        if let Some(backend_node_id) = backend_node_id {
+            tag_backend_node(session, backend_node_id, key).await?;
+        }
+    }
+
+    let script = build_fill_form_script(&Value::Object(fields), submit);
+    let result = session.cdp.evaluate_js(&script).await.map_err(cdp_err)?;
+
+    if let Some(err) = result
+        .get("exceptionDetails")
+        .and_then(|e| e.get("text"))
+        .and_then(|t| t.as_str())
+    {
+        return Err(blockcell_core::Error::Tool(format!("fill_form: {}", err)));
+    }
+
+    let raw = result
+        .get("result")
+        .and_then(|r| r.get("value"))
+        .cloned()
+        .unwrap_or(Value::Null);
+
+    if submit {
+        tokio::time::sleep(std::time::Duration::from_millis(1000)).await;
+    }
+
+    Ok(summarize_fill_form(raw))
+}
+
+fn build_fill_form_script(fields: &Value, submit: bool) -> String {
+    FILL_FORM_JS
+        .replace("__FIELDS__", &fields.to_string())
+        .replace("__SUBMIT__", if submit { "true" } else { "false" })
+}
+
+/// Turn the raw page-script result into the tool output: mask password values and add counts.
+fn summarize_fill_form(raw: Value) -> Value {
+    let mut results = raw["results"].as_array().cloned().unwrap_or_default();
+    for entry in results.iter_mut() {
+        if entry["type"].as_str() == Some("password") {
+            entry["before"] = json!("***");
+            entry["after"] = json!("***");
+        }
+    }
+    let unmatched = raw["unmatched"].as_array().cloned().unwrap_or_default();
+    let filled = results.iter().filter(|r| r["error"].is_null()).count();
+    let invalid: Vec<Value> = results
+        .iter()
+        .filter(|r| r["valid"].as_bool() == Some(false) || !r["error"].is_null())
+        .map(|r| r["field"].clone())
+        .collect();
+
+    let status = if unmatched.is_empty() && invalid.is_empty() {
+        "form_filled"
+    } else {
+        "form_partially_filled"
+    };
+
+    json!({
+        "status": status,
+        "filled": filled,
+        "fields": results,
+        "unmatched": unmatched,
+        "invalid": invalid,
+        "submitted": raw["submitted"].as_bool().unwrap_or(false),
+        "form_found": raw["form_found"].as_bool().unwrap_or(false),
+    })
+}
+
+/// Mark a DOM node (by backendNodeId) with `data-bc-fill` so page scripts can locate it.
+async fn tag_backend_node(
+    session: &mut BrowserSession,
+    backend_node_id: i64,
+    tag: &str,
+) -> Result<()> {
+    let resolved = session
+        .cdp
+        .send_command("DOM.resolveNode", json!({"backendNodeId": backend_node_id}))
+        .await
+        .map_err(cdp_err)?;
+    let object_id = resolved
+        .get("object")
+        .and_then(|o| o.get("objectId"))
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| blockcell_core::Error::Tool("Failed to resolve ref node".into()))?;
+    session
+        .cdp
+        .send_command(
+            "Runtime.callFunctionOn",
+            json!({
+                "objectId": object_id,
+                "functionDeclaration": "function(tag) { this.setAttribute('data-bc-fill', tag); }",
+                "arguments": [{"value": tag}],
+            }),
+        )
+        .await
+        .map_err(cdp_err)?;
+    Ok(())
+}
+
 async fn action_type_text(session: &mut BrowserSession, params: &Value) -> Result<Value> {
     let text = params["text"]
         .as_str()
@@ -570,71 +854,120 @@ async fn action_screenshot(
     params: &Value,
     workspace: &std::path::Path,
 ) -> Result<Value> {
-    let full_page = params["full_page"].as_bool().unwrap_or(false);
-    let base64_data = session.cdp.screenshot(full_page).await.map_err(cdp_err)?;
-
-    let media_dir = workspace.join("media");
-    std::fs::create_dir_all(&media_dir).ok();
-    let ts = chrono::Local::now().format("%Y%m%d_%H%M%S");
-    let workspace_path = media_dir.join(format!("screenshot_{}.png", ts));
+    if params["full_page"].as_bool().unwrap_or(false) {
+        return action_screenshot_full(session, params, workspace).await;
+    }
+    let base64_data = session.cdp.screenshot(false).await.map_err(cdp_err)?;
+    let mut result = save_media_output(workspace, params, &base64_data, "screenshot", "png")?;
+    result["status"] = json!("screenshot_saved");
+    Ok(result)
+}
 
-    let user_path = params["output_path"].as_str().map(|p| {
-        if p.starts_with("~/") {
-            dirs::home_dir()
-                .map(|h| h.join(&p[2..]))
-                .unwrap_or_else(|| std::path::PathBuf::from(p))
-        } else {
-            std::path::PathBuf::from(p)
-        }
-    });
+/// Capture the whole scrollable page (not just the viewport) as a single PNG.
+async fn action_screenshot_full(
+    session: &mut BrowserSession,
+    params: &Value,
+    workspace: &std::path::Path,
+) -> Result<Value> {
+    let metrics = session.cdp.get_layout_metrics().await.map_err(cdp_err)?;
+    let (width, height) = content_size_from_metrics(&metrics);
+    let base64_data = session
+        .cdp
+        .screenshot_clip(0.0, 0.0, width, height)
+        .await
+        .map_err(cdp_err)?;
+    let mut result = save_media_output(workspace, params, &base64_data, "screenshot_full", "png")?;
+    result["status"] = json!("screenshot_saved");
+    result["full_page"] = json!(true);
+    result["width"] = json!(width as u64);
+    result["height"] = json!(height as u64);
+    Ok(result)
+}
 
-    // Decode base64 and write
-    use base64::Engine;
-    let bytes = base64::engine::general_purpose::STANDARD
-        .decode(&base64_data)
-        .map_err(|e| blockcell_core::Error::Tool(format!("base64 decode: {}", e)))?;
+async fn action_print_pdf(
+    session: &mut BrowserSession,
+    params: &Value,
+    workspace: &std::path::Path,
+) -> Result<Value> {
+    let options = pdf_print_options(params);
+    let base64_data = session
+        .cdp
+        .print_to_pdf_with_options(options.clone())
+        .await
+        .map_err(cdp_err)?;
+    let mut result = save_media_output(workspace, params, &base64_data, "page", "pdf")?;
+    result["status"] = json!("pdf_saved");
+    result["options"] = options;
+    Ok(result)
+}
 
-    // Always write to workspace first (for webui display)
-    std::fs::write(&workspace_path, &bytes)
-        .map_err(|e| blockcell_core::Error::Tool(format!("write screenshot: {}", e)))?;
+/// Largest renderable capture height; Chrome fails on taller bitmaps.
+const MAX_FULL_PAGE_HEIGHT: f64 = 16384.0;
+
+/// Extract the full content size (CSS pixels) from a `Page.getLayoutMetrics` response.
+fn content_size_from_metrics(metrics: &Value) -> (f64, f64) {
+    let size = metrics
+        .get("cssContentSize")
+        .or_else(|| metrics.get("contentSize"));
+    let width = size
+        .and_then(|s| s.get("width"))
+        .and_then(|v| v.as_f64())
+        .unwrap_or(1280.0)
+        .max(1.0);
+    let height = size
+        .and_then(|s| s.get("height"))
+        .and_then(|v| v.as_f64())
+        .unwrap_or(720.0)
+        .clamp(1.0, MAX_FULL_PAGE_HEIGHT);
+    (width.ceil(), height.ceil())
+}
 
-    // If user specified a path outside workspace, also copy there
-    let extra_path = if let Some(ref up) = user_path {
-        if !up.starts_with(workspace) {
-            if let Some(parent) = up.parent() {
-                std::fs::create_dir_all(parent).ok();
-            }
-            std::fs::copy(&workspace_path, up).ok();
-            Some(up.display().to_string())
-        } else {
-            None
-        }
-    } else {
-        None
+/// Build `Page.printToPDF` options from tool params.
+fn pdf_print_options(params: &Value) -> Value {
+    // Paper sizes in inches (width, height).
+    let (paper_width, paper_height) = match params["paper"]
+        .as_str()
+        .unwrap_or("a4")
+        .to_lowercase()
+        .as_str()
+    {
+        "letter" => (8.5, 11.0),
+        "legal" => (8.5, 14.0),
+        "a3" => (11.69, 16.54),
+        "a5" => (5.83, 8.27),
+        _ => (8.27, 11.69),
     };
 
-    let mut result = json!({
-        "status": "screenshot_saved",
-        "path": workspace_path.display().to_string(),
-        "size_bytes": bytes.len(),
+    let mut options = json!({
+        "printBackground": params["print_background"].as_bool().unwrap_or(true),
+        "landscape": params["landscape"].as_bool().unwrap_or(false),
+        "paperWidth": paper_width,
+        "paperHeight": paper_height,
+        "preferCSSPageSize": params["paper"].is_null(),
     });
-    if let Some(extra) = extra_path {
-        result["also_saved_to"] = json!(extra);
+    if let Some(scale) = params["scale"].as_f64() {
+        options["scale"] = json!(scale.clamp(0.1, 2.0));
     }
-    Ok(result)
+    if let Some(ranges) = params["page_ranges"].as_str() {
+        options["pageRanges"] = json!(ranges);
+    }
+    options
 }
 
-async fn action_pdf(
-    session: &mut BrowserSession,
-    params: &Value,
+/// Decode base64 browser output, save it under workspace/media, optionally copy it to a
+/// user-provided `output_path`, and return the saved path plus a gateway-serveable URL.
+fn save_media_output(
     workspace: &std::path::Path,
+    params: &Value,
+    base64_data: &str,
+    prefix: &str,
+    ext: &str,
 ) -> Result<Value> {
-    let base64_data = session.cdp.print_to_pdf().await.map_err(cdp_err)?;
-
     let media_dir = workspace.join("media");
     std::fs::create_dir_all(&media_dir).ok();
     let ts = chrono::Local::now().format("%Y%m%d_%H%M%S");
-    let workspace_path = media_dir.join(format!("page_{}.pdf", ts));
+    let file_name = format!("{}_{}.{}", prefix, ts, ext);
+    let workspace_path = media_dir.join(&file_name);
 
     let user_path = params["output_path"].as_str().map(|p| {
         if p.starts_with("~/") {
@@ -648,12 +981,12 @@ async fn action_pdf(
 
     use base64::Engine;
     let bytes = base64::engine::general_purpose::STANDARD
-        .decode(&base64_data)
+        .decode(base64_data)
         .map_err(|e| blockcell_core::Error::Tool(format!("base64 decode: {}", e)))?;
 
-    // Always write to workspace first
+    // Always write to workspace first (for webui display)
     std::fs::write(&workspace_path, &bytes)
-        .map_err(|e| blockcell_core::Error::Tool(format!("write pdf: {}", e)))?;
+        .map_err(|e| blockcell_core::Error::Tool(format!("write {}: {}", ext, e)))?;
 
     // If user specified a path outside workspace, also copy there
     let extra_path = if let Some(ref up) = user_path {
@@ -670,9 +1003,11 @@ async fn action_pdf(
         None
     };
 
+    let relative = format!("media/{}", file_name);
     let mut result = json!({
-        "status": "pdf_saved",
         "path": workspace_path.display().to_string(),
+        "workspace_path": relative,
+        "serve_url": format!("/v1/files/serve?path={}", urlencoding::encode(&relative)),
         "size_bytes": bytes.len(),
     });
     if let Some(extra) = extra_path {
@@ -1164,7 +1499,13 @@ async fn action_network_block(session: &mut BrowserSession, params: &Value) -> R
 // ─── Helper functions ─────────────────────────────────────────────────
 
 /// Take an accessibility snapshot, assign refs, return structured result.
-async fn take_snapshot(session: &mut BrowserSession, compact: bool) -> Result<Value> {
+/// `accessible` renders the screen-reader style summary instead of the tree; refs are
+/// assigned the same way, so they work for follow-up clicks either way.
+async fn take_snapshot(
+    session: &mut BrowserSession,
+    compact: bool,
+    accessible: bool,
+) -> Result<Value> {
     let ax_tree = session
         .cdp
         .get_accessibility_tree()
@@ -1181,8 +1522,15 @@ async fn take_snapshot(session: &mut BrowserSession, compact: bool) -> Result<Va
     session.ref_counter = new_counter;
     session.refs = ref_map.clone();
 
-    let tree_text = render_tree(&nodes, compact, Some(15));
-    let result = snapshot_to_json(&tree_text, &ref_map);
+    let tree_text = if accessible {
+        render_accessible(&nodes)
+    } else {
+        render_tree(&nodes, compact, Some(15))
+    };
+    let mut result = snapshot_to_json(&tree_text, &ref_map);
+    if accessible {
+        result["mode"] = json!("accessible");
+    }
 
     Ok(result)
 }
@@ -1443,6 +1791,84 @@ mod tests {
         assert!((y - 40.0).abs() < 0.01);
     }
 
+    #[test]
+    fn test_build_fill_form_script_embeds_fields() {
+        let script = build_fill_form_script(&json!({"Email": "a@b.c", "it's": "x"}), true);
+        assert!(script.contains(r#"{"Email":"a@b.c","it's":"x"}"#));
+        assert!(script.ends_with(", true)"));
+        assert!(!script.contains("__FIELDS__"));
+        assert!(!script.contains("__SUBMIT__"));
+    }
+
+    #[test]
+    fn test_summarize_fill_form_masks_passwords_and_counts() {
+        let raw = json!({
+            "results": [
+                {"field": "Email", "type": "email", "before": "", "after": "a@b.c", "valid": true, "error": null},
+                {"field": "Password", "type": "password", "before": "", "after": "hunter2", "valid": true, "error": null},
+                {"field": "Country", "type": "select-one", "before": "", "after": "", "valid": false, "error": "no option matches \"Atlantis\""}
+            ],
+            "unmatched": ["Nickname"],
+            "submitted": false,
+            "form_found": true
+        });
+        let out = summarize_fill_form(raw);
+        assert_eq!(out["status"], "form_partially_filled");
+        assert_eq!(out["filled"], 2);
+        assert_eq!(out["fields"][1]["after"], "***");
+        assert_eq!(out["invalid"], json!(["Country"]));
+        assert_eq!(out["unmatched"], json!(["Nickname"]));
+    }
+
+    #[test]
+    fn test_pdf_print_options() {
+        let opts = pdf_print_options(&json!({}));
+        assert_eq!(opts["printBackground"], true);
+        assert_eq!(opts["landscape"], false);
+        assert_eq!(opts["preferCSSPageSize"], true);
+        assert!(opts.get("scale").is_none());
+
+        let opts = pdf_print_options(
+            &json!({"paper": "Letter", "landscape": true, "scale": 5.0, "page_ranges": "1-2"}),
+        );
+        assert_eq!(opts["paperWidth"], 8.5);
+        assert_eq!(opts["paperHeight"], 11.0);
+        assert_eq!(opts["landscape"], true);
+        assert_eq!(opts["preferCSSPageSize"], false);
+        assert_eq!(opts["scale"], 2.0);
+        assert_eq!(opts["pageRanges"], "1-2");
+    }
+
+    #[test]
+    fn test_content_size_from_metrics() {
+        let metrics = json!({"cssContentSize": {"width": 1280.4, "height": 5000.2}});
+        assert_eq!(content_size_from_metrics(&metrics), (1281.0, 5001.0));
+
+        let tall = json!({"contentSize": {"width": 800, "height": 100000}});
+        assert_eq!(
+            content_size_from_metrics(&tall),
+            (800.0, MAX_FULL_PAGE_HEIGHT)
+        );
+
+        assert_eq!(content_size_from_metrics(&json!({})), (1280.0, 720.0));
+    }
+
+    #[test]
+    fn test_save_media_output_returns_serve_url() {
+        use base64::Engine;
+        let dir = std::env::temp_dir().join(format!("bc-browse-{}", uuid::Uuid::new_v4()));
+        let data = base64::engine::general_purpose::STANDARD.encode(b"%PDF-1.4");
+        let out = save_media_output(&dir, &json!({}), &data, "page", "pdf").unwrap();
+        let rel = out["workspace_path"].as_str().unwrap();
+        assert!(rel.starts_with("media/page_") && rel.ends_with(".pdf"));
+        assert!(out["serve_url"]
+            .as_str()
+            .unwrap()
+            .starts_with("/v1/files/serve?path=media%2Fpage_"));
+        assert_eq!(out["size_bytes"], 8);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_schema_has_new_actions() {
         let tool = BrowseTool;
@@ -1466,6 +1892,11 @@ mod tests {
         assert!(action_strs.contains(&"network_block"));
         // Multi-browser
         assert!(action_strs.contains(&"list_browsers"));
+        // Form filling
+        assert!(action_strs.contains(&"fill_form"));
+        // Page capture
+        assert!(action_strs.contains(&"print_pdf"));
+        assert!(action_strs.contains(&"screenshot_full"));
     }
 
     #[test]
@@ -0,0 +1,853 @@
+//! Deterministic calculations the model should not do in its head: timezone conversion,
+//! date arithmetic, recurring-date expansion and unit/currency conversion.
+//!
+//! Currency rates come from the ECB reference rates published by Frankfurter (no key
+//! needed) and are cached in `workspace/calc/fx_rates.json`; a stale cache is used, and
+//! flagged, when the API cannot be reached.
+
+use async_trait::async_trait;
+use blockcell_core::{Error, Result};
+use chrono::{
+    DateTime, Datelike, Duration, Months, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday,
+};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::egress::HttpClientFactory;
+use crate::{PromptContext, Tool, ToolContext, ToolSchema};
+
+const FX_URL: &str = "https://api.frankfurter.app/latest";
+const FX_BASE: &str = "EUR";
+/// Reference rates are published once per working day.
+const FX_TTL_SECS: i64 = 12 * 3600;
+const FX_TIMEOUT_SECS: u64 = 15;
+const DEFAULT_RECUR_COUNT: usize = 10;
+const MAX_RECUR_COUNT: usize = 500;
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Units
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dim {
+    Length,
+    Mass,
+    Volume,
+    Area,
+    Speed,
+    Time,
+    Data,
+    Energy,
+    Pressure,
+    Temperature,
+}
+
+impl Dim {
+    fn name(self) -> &'static str {
+        match self {
+            Dim::Length => "length",
+            Dim::Mass => "mass",
+            Dim::Volume => "volume",
+            Dim::Area => "area",
+            Dim::Speed => "speed",
+            Dim::Time => "time",
+            Dim::Data => "data",
+            Dim::Energy => "energy",
+            Dim::Pressure => "pressure",
+            Dim::Temperature => "temperature",
+        }
+    }
+}
+
+/// Aliases, dimension and factor to the dimension's base unit (m, kg, L, m², m/s, s,
+/// byte, J, Pa). Temperature factors are unused; see [`convert_temperature`].
+const UNITS: &[(&[&str], Dim, f64)] = &[
+    (
+        &["m", "meter", "meters", "metre", "metres"],
+        Dim::Length,
+        1.0,
+    ),
+    (
+        &["km", "kilometer", "kilometers", "kilometre", "kilometres"],
+        Dim::Length,
+        1000.0,
+    ),
+    (&["cm", "centimeter", "centimeters"], Dim::Length, 0.01),
+    (&["mm", "millimeter", "millimeters"], Dim::Length, 0.001),
+    (&["um", "µm", "micrometer"], Dim::Length, 1e-6),
+    (&["nm", "nanometer"], Dim::Length, 1e-9),
+    (&["mi", "mile", "miles"], Dim::Length, 1609.344),
+    (&["yd", "yard", "yards"], Dim::Length, 0.9144),
+    (&["ft", "foot", "feet"], Dim::Length, 0.3048),
+    (&["in", "inch", "inches"], Dim::Length, 0.0254),
+    (
+        &["nmi", "nautical_mile", "nautical_miles"],
+        Dim::Length,
+        1852.0,
+    ),
+    (&["kg", "kilogram", "kilograms"], Dim::Mass, 1.0),
+    (&["g", "gram", "grams"], Dim::Mass, 0.001),
+    (&["mg", "milligram", "milligrams"], Dim::Mass, 1e-6),
+    (&["t", "tonne", "tonnes", "metric_ton"], Dim::Mass, 1000.0),
+    (&["lb", "lbs", "pound", "pounds"], Dim::Mass, 0.453_592_37),
+    (&["oz", "ounce", "ounces"], Dim::Mass, 0.028_349_523_125),
+    (&["st", "stone"], Dim::Mass, 6.350_293_18),
+    (
+        &["l", "liter", "liters", "litre", "litres"],
+        Dim::Volume,
+        1.0,
+    ),
+    (&["ml", "milliliter", "milliliters"], Dim::Volume, 0.001),
+    (&["cl"], Dim::Volume, 0.01),
+    (&["dl"], Dim::Volume, 0.1),
+    (&["m3", "m³", "cubic_meter"], Dim::Volume, 1000.0),
+    (&["cm3", "cm³", "cc"], Dim::Volume, 0.001),
+    (
+        &["gal", "gallon", "gallons", "us_gal"],
+        Dim::Volume,
+        3.785_411_784,
+    ),
+    (&["imp_gal", "imperial_gallon"], Dim::Volume, 4.546_09),
+    (&["qt", "quart", "quarts"], Dim::Volume, 0.946_352_946),
+    (&["pt", "pint", "pints"], Dim::Volume, 0.473_176_473),
+    (&["cup", "cups"], Dim::Volume, 0.236_588_236_5),
+    (&["fl_oz", "floz"], Dim::Volume, 0.029_573_529_562_5),
+    (
+        &["tbsp", "tablespoon", "tablespoons"],
+        Dim::Volume,
+        0.014_786_764_781_25,
+    ),
+    (
+        &["tsp", "teaspoon", "teaspoons"],
+        Dim::Volume,
+        0.004_928_921_593_75,
+    ),
+    (&["m2", "m²", "sqm"], Dim::Area, 1.0),
+    (&["km2", "km²"], Dim::Area, 1e6),
+    (&["cm2", "cm²"], Dim::Area, 1e-4),
+    (&["ha", "hectare", "hectares"], Dim::Area, 1e4),
+    (&["acre", "acres"], Dim::Area, 4_046.856_422_4),
+    (&["ft2", "ft²", "sqft"], Dim::Area, 0.092_903_04),
+    (&["mi2", "mi²", "sqmi"], Dim::Area, 2_589_988.110_336),
+    (&["m/s", "mps"], Dim::Speed, 1.0),
+    (&["km/h", "kmh", "kph"], Dim::Speed, 1.0 / 3.6),
+    (&["mph"], Dim::Speed, 0.447_04),
+    (&["kn", "kt", "knot", "knots"], Dim::Speed, 1852.0 / 3600.0),
+    (&["ft/s", "fps"], Dim::Speed, 0.3048),
+    (&["s", "sec", "secs", "second", "seconds"], Dim::Time, 1.0),
+    (&["ms", "millisecond", "milliseconds"], Dim::Time, 0.001),
+    (&["min", "mins", "minute", "minutes"], Dim::Time, 60.0),
+    (&["h", "hr", "hrs", "hour", "hours"], Dim::Time, 3600.0),
+    (&["d", "day", "days"], Dim::Time, 86_400.0),
+    (&["wk", "week", "weeks"], Dim::Time, 604_800.0),
+    (&["b", "byte", "bytes"], Dim::Data, 1.0),
+    (&["bit", "bits"], Dim::Data, 0.125),
+    (&["kb"], Dim::Data, 1e3),
+    (&["mb"], Dim::Data, 1e6),
+    (&["gb"], Dim::Data, 1e9),
+    (&["tb"], Dim::Data, 1e12),
+    (&["kib"], Dim::Data, 1024.0),
+    (&["mib"], Dim::Data, 1_048_576.0),
+    (&["gib"], Dim::Data, 1_073_741_824.0),
+    (&["tib"], Dim::Data, 1_099_511_627_776.0),
+    (&["j", "joule", "joules"], Dim::Energy, 1.0),
+    (&["kj"], Dim::Energy, 1000.0),
+    (&["cal", "calorie", "calories"], Dim::Energy, 4.184),
+    (&["kcal"], Dim::Energy, 4184.0),
+    (&["wh"], Dim::Energy, 3600.0),
+    (&["kwh"], Dim::Energy, 3.6e6),
+    (&["btu"], Dim::Energy, 1_055.055_852_62),
+    (&["pa", "pascal"], Dim::Pressure, 1.0),
+    (&["hpa"], Dim::Pressure, 100.0),
+    (&["kpa"], Dim::Pressure, 1000.0),
+    (&["mbar", "millibar"], Dim::Pressure, 100.0),
+    (&["bar"], Dim::Pressure, 1e5),
+    (&["psi"], Dim::Pressure, 6_894.757_293_168),
+    (&["atm"], Dim::Pressure, 101_325.0),
+    (&["mmhg"], Dim::Pressure, 133.322_387_415),
+    (&["c", "°c", "celsius"], Dim::Temperature, 0.0),
+    (&["f", "°f", "fahrenheit"], Dim::Temperature, 0.0),
+    (&["k", "kelvin"], Dim::Temperature, 0.0),
+];
+
+fn lookup_unit(name: &str) -> Option<(&'static str, Dim, f64)> {
+    let key = name.trim().to_lowercase().replace(' ', "_");
+    UNITS
+        .iter()
+        .find(|(aliases, _, _)| aliases.contains(&key.as_str()))
+        .map(|(aliases, dim, factor)| (aliases[0], *dim, *factor))
+}
+
+fn convert_temperature(value: f64, from: &str, to: &str) -> f64 {
+    let kelvin = match from {
+        "c" => value + 273.15,
+        "f" => (value - 32.0) * 5.0 / 9.0 + 273.15,
+        _ => value,
+    };
+    match to {
+        "c" => kelvin - 273.15,
+        "f" => (kelvin - 273.15) * 9.0 / 5.0 + 32.0,
+        _ => kelvin,
+    }
+}
+
+/// Converts between physical units. `Ok(None)` means neither name is a known unit
+/// (the caller may then try currencies).
+fn convert_units(value: f64, from: &str, to: &str) -> Result<Option<(f64, Dim)>> {
+    let (from_u, to_u) = match (lookup_unit(from), lookup_unit(to)) {
+        (Some(f), Some(t)) => (f, t),
+        (None, None) => return Ok(None),
+        (None, _) => return Err(Error::Validation(format!("Unknown unit '{}'", from))),
+        (_, None) => return Err(Error::Validation(format!("Unknown unit '{}'", to))),
+    };
+    if from_u.1 != to_u.1 {
+        return Err(Error::Validation(format!(
+            "Cannot convert {} ({}) to {} ({})",
+            from,
+            from_u.1.name(),
+            to,
+            to_u.1.name()
+        )));
+    }
+    let result = if from_u.1 == Dim::Temperature {
+        convert_temperature(value, from_u.0, to_u.0)
+    } else {
+        value * from_u.2 / to_u.2
+    };
+    Ok(Some((result, from_u.1)))
+}
+
+/// Up to 10 significant digits without trailing zeros; scientific notation for very
+/// large or small magnitudes.
+fn fmt_num(v: f64) -> String {
+    if v == 0.0 {
+        return "0".to_string();
+    }
+    if !v.is_finite() {
+        return v.to_string();
+    }
+    let magnitude = v.abs().log10().floor() as i32;
+    if !(-6..15).contains(&magnitude) {
+        return format!("{:e}", v);
+    }
+    let decimals = (9 - magnitude).clamp(0, 15) as usize;
+    let s = format!("{:.*}", decimals, v);
+    if s.contains('.') {
+        s.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        s
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Currency rates
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct FxCache {
+    /// Publication date of the reference rates.
+    date: String,
+    fetched_at: i64,
+    /// Units of each currency per one EUR.
+    rates: BTreeMap<String, f64>,
+}
+
+fn fx_cache_path(workspace: &Path) -> PathBuf {
+    workspace.join("calc").join("fx_rates.json")
+}
+
+fn load_fx_cache(workspace: &Path) -> Option<FxCache> {
+    let content = std::fs::read_to_string(fx_cache_path(workspace)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_fx_cache(workspace: &Path, cache: &FxCache) -> Result<()> {
+    let path = fx_cache_path(workspace);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(cache)?)?;
+    Ok(())
+}
+
+async fn fetch_fx(ctx: &ToolContext) -> Result<FxCache> {
+    let factory = HttpClientFactory::for_tool(&ctx.config, "calc");
+    factory.check(FX_URL).await?;
+    let client = factory
+        .builder()
+        .timeout(std::time::Duration::from_secs(FX_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| Error::Tool(format!("Failed to build HTTP client: {}", e)))?;
+    let body: Value = client
+        .get(FX_URL)
+        .query(&[("from", FX_BASE)])
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| Error::Tool(format!("Exchange rate request failed: {}", e)))?
+        .json()
+        .await
+        .map_err(|e| Error::Tool(format!("Invalid exchange rate response: {}", e)))?;
+    let mut rates: BTreeMap<String, f64> = body["rates"]
+        .as_object()
+        .map(|m| {
+            m.iter()
+                .filter_map(|(k, v)| v.as_f64().map(|r| (k.to_ascii_uppercase(), r)))
+                .collect()
+        })
+        .unwrap_or_default();
+    if rates.is_empty() {
+        return Err(Error::Tool("Exchange rate response had no rates".into()));
+    }
+    rates.insert(FX_BASE.to_string(), 1.0);
+    Ok(FxCache {
+        date: body["date"].as_str().unwrap_or_default().to_string(),
+        fetched_at: Utc::now().timestamp(),
+        rates,
+    })
+}
+
+/// Cached rates, refreshed when older than [`FX_TTL_SECS`]. The flag is true when the
+/// refresh failed and an outdated cache is returned.
+async fn fx_rates(ctx: &ToolContext) -> Result<(FxCache, bool)> {
+    let cached = load_fx_cache(&ctx.workspace);
+    let now = Utc::now().timestamp();
+    if let Some(cache) = cached.as_ref().filter(|c| now - c.fetched_at < FX_TTL_SECS) {
+        return Ok((cache.clone(), false));
+    }
+    match fetch_fx(ctx).await {
+        Ok(fresh) => {
+            info!(date = %fresh.date, count = fresh.rates.len(), "💱 Exchange rates refreshed");
+            if let Err(e) = save_fx_cache(&ctx.workspace, &fresh) {
+                warn!(error = %e, "Failed to cache exchange rates");
+            }
+            Ok((fresh, false))
+        }
+        Err(e) => match cached {
+            Some(cache) => {
+                warn!(error = %e, "Using stale exchange rates");
+                Ok((cache, true))
+            }
+            None => Err(e),
+        },
+    }
+}
+
+/// Units of `to` per one unit of `from`.
+fn cross_rate(rates: &BTreeMap<String, f64>, from: &str, to: &str) -> Result<f64> {
+    let get = |code: &str| {
+        rates
+            .get(code)
+            .copied()
+            .filter(|r| *r > 0.0)
+            .ok_or_else(|| Error::Validation(format!("No exchange rate for '{}'", code)))
+    };
+    Ok(get(to)? / get(from)?)
+}
+
+fn is_currency_code(s: &str) -> bool {
+    s.len() == 3 && s.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Dates and times
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn parse_tz(name: &str) -> Result<Tz> {
+    name.trim().parse::<Tz>().map_err(|_| {
+        Error::Validation(format!(
+            "Unknown timezone '{}'. Use an IANA name such as Europe/Berlin",
+            name
+        ))
+    })
+}
+
+/// A point in time as written by the user: a calendar date, or a wall time in `tz`
+/// (RFC 3339 input is converted into `tz`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Moment {
+    Date(NaiveDate),
+    Local(NaiveDateTime),
+}
+
+impl Moment {
+    fn naive(self) -> NaiveDateTime {
+        match self {
+            Moment::Date(d) => d.and_time(chrono::NaiveTime::MIN),
+            Moment::Local(dt) => dt,
+        }
+    }
+
+    fn render(self, tz: Tz) -> String {
+        match self {
+            Moment::Date(d) => format!("{} ({})", d.format("%Y-%m-%d"), d.weekday()),
+            Moment::Local(dt) => match tz.from_local_datetime(&dt).earliest() {
+                Some(local) => local.format("%Y-%m-%d %H:%M:%S %Z (%a)").to_string(),
+                None => format!(
+                    "{} (nonexistent local time)",
+                    dt.format("%Y-%m-%d %H:%M:%S")
+                ),
+            },
+        }
+    }
+}
+
+fn parse_moment(value: Option<&str>, tz: Tz) -> Result<Moment> {
+    let value = match value.map(str::trim).filter(|v| !v.is_empty()) {
+        None | Some("now") => {
+            return Ok(Moment::Local(Utc::now().with_timezone(&tz).naive_local()))
+        }
+        Some("today") => return Ok(Moment::Date(Utc::now().with_timezone(&tz).date_naive())),
+        Some(v) => v,
+    };
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Ok(Moment::Local(dt.with_timezone(&tz).naive_local()));
+    }
+    if let Ok(d) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(Moment::Date(d));
+    }
+    [
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M:%S",
+    ]
+    .iter()
+    .find_map(|f| NaiveDateTime::parse_from_str(value, f).ok())
+    .map(Moment::Local)
+    .ok_or_else(|| {
+        Error::Validation(format!(
+            "Invalid time '{}'. Use YYYY-MM-DD, YYYY-MM-DD HH:MM or RFC 3339",
+            value
+        ))
+    })
+}
+
+fn to_instant(dt: NaiveDateTime, tz: Tz) -> Result<DateTime<Tz>> {
+    tz.from_local_datetime(&dt).earliest().ok_or_else(|| {
+        Error::Validation(format!(
+            "{} does not exist in {} (DST gap)",
+            dt.format("%Y-%m-%d %H:%M"),
+            tz
+        ))
+    })
+}
+
+fn add_months(date: NaiveDate, months: i64) -> Option<NaiveDate> {
+    if months >= 0 {
+        date.checked_add_months(Months::new(months as u32))
+    } else {
+        date.checked_sub_months(Months::new(months.unsigned_abs() as u32))
+    }
+}
+
+fn is_business_day(d: NaiveDate) -> bool {
+    !matches!(d.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+fn add_business_days(mut date: NaiveDate, n: i64) -> NaiveDate {
+    let step = if n >= 0 { 1 } else { -1 };
+    let mut left = n.abs();
+    while left > 0 {
+        date += Duration::days(step);
+        if is_business_day(date) {
+            left -= 1;
+        }
+    }
+    date
+}
+
+/// Weekdays in `[from, to)`, negative when `to` is earlier.
+fn business_days_between(from: NaiveDate, to: NaiveDate) -> i64 {
+    let (start, end, sign) = if from <= to {
+        (from, to, 1)
+    } else {
+        (to, from, -1)
+    };
+    let days = (end - start).num_days();
+    let full_weeks = days / 7;
+    let mut count = full_weeks * 5;
+    let mut d = start + Duration::days(full_weeks * 7);
+    while d < end {
+        if is_business_day(d) {
+            count += 1;
+        }
+        d += Duration::days(1);
+    }
+    sign * count
+}
+
+/// Whole years, months and days from `from` to `to` (`from <= to`).
+fn calendar_span(from: NaiveDate, to: NaiveDate) -> (i64, i64, i64) {
+    let mut months =
+        (to.year() - from.year()) as i64 * 12 + to.month() as i64 - from.month() as i64;
+    if add_months(from, months).is_some_and(|d| d > to) {
+        months -= 1;
+    }
+    let anchor = add_months(from, months).unwrap_or(from);
+    (months / 12, months % 12, (to - anchor).num_days())
+}
+
+fn tz_convert(params: &Value, default_tz: Tz) -> Result<Value> {
+    let from_tz = match params["from_tz"].as_str() {
+        Some(name) => parse_tz(name)?,
+        None => default_tz,
+    };
+    let targets: Vec<String> = match &params["to_tz"] {
+        Value::String(s) => vec![s.clone()],
+        Value::Array(a) => a
+            .iter()
+            .filter_map(|v| v.as_str().map(String::from))
+            .collect(),
+        _ => Vec::new(),
+    };
+    let instant = match parse_moment(params["time"].as_str(), from_tz)? {
+        Moment::Date(d) => to_instant(d.and_time(chrono::NaiveTime::MIN), from_tz)?,
+        Moment::Local(dt) => to_instant(dt, from_tz)?,
+    };
+    let describe = |tz: Tz| {
+        let local = instant.with_timezone(&tz);
+        json!({
+            "timezone": tz.name(),
+            "local": local.format("%Y-%m-%d %H:%M:%S").to_string(),
+            "weekday": local.format("%A").to_string(),
+            "abbreviation": local.format("%Z").to_string(),
+            "utc_offset": local.format("%:z").to_string(),
+        })
+    };
+    let converted = targets
+        .iter()
+        .map(|name| parse_tz(name).map(describe))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(json!({
+        "input": describe(from_tz),
+        "utc": instant.with_timezone(&Utc).to_rfc3339(),
+        "converted": converted,
+    }))
+}
+
+fn date_add(params: &Value, tz: Tz) -> Result<Value> {
+    let start = parse_moment(params["date"].as_str(), tz)?;
+    let int = |key: &str| params[key].as_i64().unwrap_or(0);
+    let months = int("years") * 12 + int("months");
+    let days = int("weeks") * 7 + int("days");
+    let clock_minutes = int("hours") * 60 + int("minutes");
+    if clock_minutes != 0 && matches!(start, Moment::Date(_)) {
+        return Err(Error::Validation(
+            "hours/minutes need a date with a time, e.g. '2026-03-28 22:00'".into(),
+        ));
+    }
+
+    let mut naive = start.naive();
+    let mut clamped = false;
+    if months != 0 {
+        let moved = add_months(naive.date(), months)
+            .ok_or_else(|| Error::Validation("Date out of range".into()))?;
+        clamped = moved.day() != naive.date().day();
+        naive = moved.and_time(naive.time());
+    }
+    // Calendar units move the wall clock (a day is a day across DST changes); hours and
+    // minutes move the instant.
+    naive += Duration::days(days);
+    if let Some(n) = params["business_days"].as_i64() {
+        naive = add_business_days(naive.date(), n).and_time(naive.time());
+    }
+    let result = match start {
+        Moment::Date(_) if clock_minutes == 0 => Moment::Date(naive.date()),
+        _ if clock_minutes != 0 => {
+            let instant = to_instant(naive, tz)? + Duration::minutes(clock_minutes);
+            Moment::Local(instant.naive_local())
+        }
+        _ => Moment::Local(naive),
+    };
+    let mut out = json!({
+        "from": start.render(tz),
+        "result": result.render(tz),
+        "timezone": tz.name(),
+    });
+    if clamped {
+        out["note"] =
+            json!("Day of month did not exist in the target month and was clamped to its last day");
+    }
+    Ok(out)
+}
+
+fn date_diff(params: &Value, tz: Tz) -> Result<Value> {
+    let from = parse_moment(params["from"].as_str(), tz)?;
+    let to = parse_moment(params["to"].as_str(), tz)?;
+    let (a, b) = (from.naive().date(), to.naive().date());
+    let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+    let (years, months, days) = calendar_span(lo, hi);
+    let mut out = json!({
+        "from": from.render(tz),
+        "to": to.render(tz),
+        "days": (b - a).num_days(),
+        "weeks": fmt_num((b - a).num_days() as f64 / 7.0),
+        "calendar": {"years": years, "months": months, "days": days},
+        "business_days": business_days_between(a, b),
+    });
+    if matches!(from, Moment::Local(_)) || matches!(to, Moment::Local(_)) {
+        let seconds = (to_instant(to.naive(), tz)? - to_instant(from.naive(), tz)?).num_seconds();
+        out["hours"] = json!(fmt_num(seconds as f64 / 3600.0));
+        out["minutes"] = json!(fmt_num(seconds as f64 / 60.0));
+    }
+    Ok(out)
+}
+
+fn recur(params: &Value, tz: Tz) -> Result<Value> {
+    let start = parse_moment(params["start"].as_str(), tz)?;
+    let rrule = params["rrule"].as_str().unwrap_or("");
+    let limit = params["count"]
+        .as_u64()
+        .map(|n| n as usize)
+        .unwrap_or(DEFAULT_RECUR_COUNT)
+        .clamp(1, MAX_RECUR_COUNT);
+    let occurrences: Vec<String> =
+        crate::calendar::expand_rrule(start.naive(), rrule, Some(tz.name()), limit)?
+            .into_iter()
+            .map(|dt| match start {
+                Moment::Date(_) => Moment::Date(dt.date()).render(tz),
+                Moment::Local(_) => Moment::Local(dt).render(tz),
+            })
+            .collect();
+    Ok(json!({
+        "rrule": rrule,
+        "timezone": tz.name(),
+        "count": occurrences.len(),
+        "occurrences": occurrences,
+    }))
+}
+
+async fn convert(ctx: &ToolContext, params: &Value) -> Result<Value> {
+    let value = params["value"]
+        .as_f64()
+        .ok_or_else(|| Error::Validation("'value' must be a number".into()))?;
+    let from = params["from"].as_str().unwrap_or("").trim();
+    let to = params["to"].as_str().unwrap_or("").trim();
+    if let Some((result, dim)) = convert_units(value, from, to)? {
+        return Ok(json!({
+            "value": value,
+            "from": from,
+            "to": to,
+            "dimension": dim.name(),
+            "result": result,
+            "text": format!("{} {} = {} {}", fmt_num(value), from, fmt_num(result), to),
+        }));
+    }
+    if !(is_currency_code(from) && is_currency_code(to)) {
+        return Err(Error::Validation(format!(
+            "Unknown units '{}' / '{}'. Currencies use ISO codes such as USD",
+            from, to
+        )));
+    }
+    let (from, to) = (from.to_ascii_uppercase(), to.to_ascii_uppercase());
+    let (cache, stale) = fx_rates(ctx).await?;
+    let rate = cross_rate(&cache.rates, &from, &to)?;
+    let result = value * rate;
+    let mut out = json!({
+        "value": value,
+        "from": from,
+        "to": to,
+        "dimension": "currency",
+        "rate": rate,
+        "result": (result * 100.0).round() / 100.0,
+        "text": format!("{:.2} {} = {:.2} {}", value, from, result, to),
+        "rates_date": cache.date,
+        "source": "ECB reference rates via frankfurter.app",
+    });
+    if stale {
+        out["stale"] = json!(true);
+    }
+    Ok(out)
+}
+
+pub struct CalcTool;
+
+#[async_trait]
+impl Tool for CalcTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "calc",
+            description: "Exact date/time and unit calculations. You MUST provide `action`. action='tz_convert': optional `time` (default now), `from_tz`; requires `to_tz` (string or array of IANA names). action='date_add': optional `date` (default now), any of `years`, `months`, `weeks`, `days`, `business_days`, `hours`, `minutes` (negative to subtract). action='date_diff': requires `from`, `to`. action='recur': requires `start`, `rrule` (RFC 5545, e.g. 'FREQ=MONTHLY;BYDAY=FR'), optional `count`. action='convert': requires `value`, `from`, `to` (units like km/mi/°F/kWh or ISO currency codes). Times are YYYY-MM-DD, YYYY-MM-DD HH:MM or RFC 3339; optional `timezone` overrides the default.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["tz_convert", "date_add", "date_diff", "recur", "convert"],
+                        "description": "Calculation to perform"
+                    },
+                    "time": {"type": "string", "description": "(tz_convert) Time to convert; default now"},
+                    "from_tz": {"type": "string", "description": "(tz_convert) Timezone of `time`; default the configured timezone"},
+                    "to_tz": {
+                        "description": "(tz_convert) Target IANA timezone(s)",
+                        "oneOf": [{"type": "string"}, {"type": "array", "items": {"type": "string"}}]
+                    },
+                    "date": {"type": "string", "description": "(date_add) Start date/time; default now"},
+                    "years": {"type": "integer"},
+                    "months": {"type": "integer"},
+                    "weeks": {"type": "integer"},
+                    "days": {"type": "integer"},
+                    "business_days": {"type": "integer", "description": "(date_add) Monday–Friday days"},
+                    "hours": {"type": "integer"},
+                    "minutes": {"type": "integer"},
+                    "from": {"type": "string", "description": "(date_diff) Start date/time; (convert) source unit or currency"},
+                    "to": {"type": "string", "description": "(date_diff) End date/time; (convert) target unit or currency"},
+                    "start": {"type": "string", "description": "(recur) First occurrence"},
+                    "rrule": {"type": "string", "description": "(recur) Recurrence rule"},
+                    "count": {"type": "integer", "description": "(recur) Occurrences to list, default 10, max 500"},
+                    "value": {"type": "number", "description": "(convert) Amount to convert"},
+                    "timezone": {"type": "string", "description": "IANA timezone for local times; default the configured timezone"}
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        let require = |keys: &[&str]| -> Result<()> {
+            for key in keys {
+                if params[*key].is_null() {
+                    return Err(Error::Validation(format!("'{}' is required", key)));
+                }
+            }
+            Ok(())
+        };
+        match params["action"].as_str().unwrap_or("") {
+            "tz_convert" => require(&["to_tz"]),
+            "date_add" => Ok(()),
+            "date_diff" => require(&["from", "to"]),
+            "recur" => require(&["start", "rrule"]),
+            "convert" => require(&["value", "from", "to"]),
+            other => Err(Error::Validation(format!(
+                "Unknown action '{}'. Use tz_convert, date_add, date_diff, recur or convert",
+                other
+            ))),
+        }
+    }
+
+    fn prompt_rule(&self, _ctx: &PromptContext) -> Option<String> {
+        Some(
+            "- Never do timezone conversions, date arithmetic (\"in 3 weeks\", \"last Friday of the month\"), unit or currency conversions in your head: call `calc` and quote its result."
+                .to_string(),
+        )
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        let tz = match params["timezone"]
+            .as_str()
+            .or(ctx.config.default_timezone.as_deref())
+        {
+            Some(name) => parse_tz(name)?,
+            None => iana_system_tz(),
+        };
+        match params["action"].as_str().unwrap_or("") {
+            "tz_convert" => tz_convert(&params, tz),
+            "date_add" => date_add(&params, tz),
+            "date_diff" => date_diff(&params, tz),
+            "recur" => recur(&params, tz),
+            "convert" => convert(&ctx, &params).await,
+            other => Err(Error::Tool(format!("Unknown action: {}", other))),
+        }
+    }
+}
+
+/// The host timezone when no default is configured, falling back to UTC.
+fn iana_system_tz() -> Tz {
+    std::env::var("TZ")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .or_else(|| {
+            std::fs::read_link("/etc/localtime").ok().and_then(|p| {
+                let s = p.to_string_lossy().into_owned();
+                s.split("zoneinfo/")
+                    .nth(1)
+                    .and_then(|name| name.parse().ok())
+            })
+        })
+        .unwrap_or(Tz::UTC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_conversion() {
+        let (km, dim) = convert_units(26.2, "mi", "km").unwrap().unwrap();
+        assert_eq!(dim, Dim::Length);
+        assert_eq!(fmt_num(km), "42.1648128");
+        let (f, _) = convert_units(100.0, "°C", "fahrenheit").unwrap().unwrap();
+        assert_eq!(fmt_num(f), "212");
+        let (kib, _) = convert_units(1.0, "MiB", "KiB").unwrap().unwrap();
+        assert_eq!(kib, 1024.0);
+        assert!(convert_units(1.0, "kg", "m").is_err());
+        assert!(convert_units(1.0, "USD", "EUR").unwrap().is_none());
+
+        let rates: BTreeMap<String, f64> = [
+            ("EUR".to_string(), 1.0),
+            ("USD".to_string(), 1.25),
+            ("GBP".to_string(), 0.8),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(fmt_num(cross_rate(&rates, "GBP", "USD").unwrap()), "1.5625");
+        assert!(cross_rate(&rates, "USD", "XYZ").is_err());
+    }
+
+    #[test]
+    fn test_date_arithmetic() {
+        let tz: Tz = "Europe/Berlin".parse().unwrap();
+        let out = date_add(&json!({"date": "2026-01-31", "months": 1}), tz).unwrap();
+        assert_eq!(out["result"], "2026-02-28 (Sat)");
+        assert!(out["note"].is_string());
+
+        let out = date_add(&json!({"date": "2026-10-16", "business_days": 3}), tz).unwrap();
+        assert_eq!(out["result"], "2026-10-21 (Wed)");
+
+        // 24 hours across the October DST change end one wall-clock hour earlier.
+        let out = date_add(&json!({"date": "2026-10-24 12:00", "hours": 24}), tz).unwrap();
+        assert_eq!(out["result"], "2026-10-25 11:00:00 CET (Sun)");
+
+        let diff = date_diff(&json!({"from": "2026-01-15", "to": "2027-03-20"}), tz).unwrap();
+        assert_eq!(
+            diff["calendar"],
+            json!({"years": 1, "months": 2, "days": 5})
+        );
+        assert_eq!(
+            business_days_between(
+                NaiveDate::from_ymd_opt(2026, 10, 12).unwrap(),
+                NaiveDate::from_ymd_opt(2026, 10, 26).unwrap()
+            ),
+            10
+        );
+    }
+
+    #[test]
+    fn test_tz_convert_and_recur() {
+        let tz: Tz = "America/New_York".parse().unwrap();
+        let out = tz_convert(
+            &json!({"time": "2026-07-01 09:30", "to_tz": ["Asia/Tokyo", "Europe/London"]}),
+            tz,
+        )
+        .unwrap();
+        assert_eq!(out["converted"][0]["local"], "2026-07-01 22:30:00");
+        assert_eq!(out["converted"][1]["utc_offset"], "+01:00");
+        assert!(tz_convert(&json!({"to_tz": "Mars/Olympus"}), tz).is_err());
+
+        let out = recur(
+            &json!({"start": "2026-01-30", "rrule": "RRULE:FREQ=MONTHLY;COUNT=3"}),
+            tz,
+        )
+        .unwrap();
+        // February has no 30th, so RFC 5545 skips it.
+        assert_eq!(
+            out["occurrences"],
+            json!(["2026-01-30 (Fri)", "2026-03-30 (Mon)", "2026-04-30 (Thu)"])
+        );
+    }
+}
@@ -0,0 +1,1139 @@
+//! Calendar-aware scheduling: free/busy from iCalendar feeds plus blockcell's own
+//! reminders (cron jobs) and due TODOs, conflict detection and free-slot suggestions.
+//!
+//! The `calendar` tool exposes this to the model; the `cron` tool calls
+//! [`check_slot`] before creating a one-time reminder so every skill gets conflict
+//! warnings without reimplementing them.
+
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use blockcell_core::config::CalendarConfig;
+use blockcell_core::secrets::resolve_secret_ref;
+use blockcell_core::{Error, Paths, Result};
+use chrono::{Datelike, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::egress::HttpClientFactory;
+use crate::todo::{format_time, parse_local_time};
+use crate::{PromptContext, Tool, ToolContext, ToolSchema};
+
+const MINUTE_MS: i64 = 60_000;
+const DAY_MS: i64 = 24 * 60 * MINUTE_MS;
+/// Suggested slots start on this grid.
+const SLOT_STEP_MS: i64 = 30 * MINUTE_MS;
+/// Upper bound on expanded occurrences per recurring item.
+const MAX_OCCURRENCES: usize = 2000;
+const FETCH_TIMEOUT_SECS: u64 = 20;
+
+static FEED_CACHE: once_cell::sync::Lazy<Mutex<HashMap<String, (Instant, String)>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BusyKind {
+    Event,
+    Reminder,
+    Todo,
+}
+
+/// One occupied interval.
+#[derive(Debug, Clone, Serialize)]
+pub struct Busy {
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub title: String,
+    pub kind: BusyKind,
+    /// Calendar name, cron job id or TODO id.
+    pub source: String,
+    pub recurring: bool,
+}
+
+impl Busy {
+    fn overlaps(&self, start: i64, end: i64) -> bool {
+        self.start_ms < end && start < self.end_ms
+    }
+
+    /// Calendar events and one-off items block suggestions; recurring reminders
+    /// (water every hour, daily stand-up ping) are only reported.
+    fn blocks_suggestions(&self) -> bool {
+        self.kind == BusyKind::Event || !self.recurring
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// iCalendar parsing
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Clone, Copy)]
+enum Zone {
+    Utc,
+    Named(chrono_tz::Tz),
+    System,
+}
+
+fn zone_for(tz: Option<&str>) -> Zone {
+    match tz.and_then(|t| t.parse::<chrono_tz::Tz>().ok()) {
+        Some(tz) => Zone::Named(tz),
+        None => Zone::System,
+    }
+}
+
+fn to_ms(naive: NaiveDateTime, zone: Zone) -> Option<i64> {
+    match zone {
+        Zone::Utc => Some(naive.and_utc().timestamp_millis()),
+        Zone::Named(tz) => tz
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|d| d.timestamp_millis()),
+        Zone::System => chrono::Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|d| d.timestamp_millis()),
+    }
+}
+
+struct Property {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl Property {
+    fn param(&self, key: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in text.lines() {
+        let raw = raw.trim_end_matches('\r');
+        if let Some(rest) = raw.strip_prefix([' ', '\t']) {
+            if let Some(last) = lines.last_mut() {
+                last.push_str(rest);
+                continue;
+            }
+        }
+        lines.push(raw.to_string());
+    }
+    lines
+}
+
+fn parse_property(line: &str) -> Option<Property> {
+    let colon = line.find(':')?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_ascii_uppercase();
+    let params = parts
+        .filter_map(|p| p.split_once('='))
+        .map(|(k, v)| (k.trim().to_string(), v.trim_matches('"').to_string()))
+        .collect();
+    Some(Property {
+        name,
+        params,
+        value: value.to_string(),
+    })
+}
+
+/// Parse a DATE or DATE-TIME value into (local time, zone, is_all_day).
+fn parse_ics_time(prop: &Property, default: Zone) -> Option<(NaiveDateTime, Zone, bool)> {
+    let value = prop.value.trim();
+    if prop.param("VALUE") == Some("DATE") || (value.len() == 8 && !value.contains('T')) {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        return Some((date.and_hms_opt(0, 0, 0)?, default, true));
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some((naive, Zone::Utc, false));
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    let zone = match prop.param("TZID") {
+        Some(tzid) => tzid
+            .parse::<chrono_tz::Tz>()
+            .map(Zone::Named)
+            .unwrap_or(default),
+        None => default,
+    };
+    Some((naive, zone, false))
+}
+
+/// RFC 5545 DURATION (`PT1H30M`, `P1D`, `P2W`) in milliseconds.
+fn parse_duration(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let (sign, body) = match value.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let body = body.strip_prefix('P')?;
+    let mut total = 0i64;
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in body.chars() {
+        match c {
+            'T' => in_time = true,
+            '0'..='9' => number.push(c),
+            unit => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                total += n * match (unit, in_time) {
+                    ('W', false) => 7 * DAY_MS,
+                    ('D', false) => DAY_MS,
+                    ('H', true) => 60 * MINUTE_MS,
+                    ('M', true) => MINUTE_MS,
+                    ('S', true) => 1000,
+                    _ => return None,
+                };
+            }
+        }
+    }
+    Some(sign * total)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Freq {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+struct Rule {
+    freq: Freq,
+    interval: u32,
+    count: Option<usize>,
+    until_ms: Option<i64>,
+    by_day: Vec<Weekday>,
+}
+
+fn parse_rrule(value: &str, default: Zone) -> Option<Rule> {
+    let mut rule = Rule {
+        freq: Freq::Daily,
+        interval: 1,
+        count: None,
+        until_ms: None,
+        by_day: Vec::new(),
+    };
+    let mut has_freq = false;
+    for part in value.split(';') {
+        let (key, val) = part.split_once('=')?;
+        match key.to_ascii_uppercase().as_str() {
+            "FREQ" => {
+                has_freq = true;
+                rule.freq = match val.to_ascii_uppercase().as_str() {
+                    "DAILY" => Freq::Daily,
+                    "WEEKLY" => Freq::Weekly,
+                    "MONTHLY" => Freq::Monthly,
+                    "YEARLY" => Freq::Yearly,
+                    // Sub-daily rules are not worth expanding for free/busy.
+                    _ => return None,
+                };
+            }
+            "INTERVAL" => rule.interval = val.parse().ok().filter(|n| *n > 0)?,
+            "COUNT" => rule.count = val.parse().ok(),
+            "UNTIL" => {
+                let prop = Property {
+                    name: "UNTIL".to_string(),
+                    params: Vec::new(),
+                    value: val.to_string(),
+                };
+                rule.until_ms =
+                    parse_ics_time(&prop, default).and_then(|(naive, zone, all_day)| {
+                        let naive = if all_day {
+                            naive.date().and_hms_opt(23, 59, 59)?
+                        } else {
+                            naive
+                        };
+                        to_ms(naive, zone)
+                    });
+            }
+            "BYDAY" => {
+                rule.by_day = val
+                    .split(',')
+                    .filter_map(|d| {
+                        // Ignore ordinal prefixes such as `1MO`/`-1FR`.
+                        let d = d.trim_start_matches(|c: char| {
+                            c == '-' || c == '+' || c.is_ascii_digit()
+                        });
+                        Weekday::from_str(match d {
+                            "MO" => "Mon",
+                            "TU" => "Tue",
+                            "WE" => "Wed",
+                            "TH" => "Thu",
+                            "FR" => "Fri",
+                            "SA" => "Sat",
+                            "SU" => "Sun",
+                            _ => "",
+                        })
+                        .ok()
+                    })
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+    has_freq.then_some(rule)
+}
+
+/// Local start times of a recurring event, in order, starting with `start`.
+fn expand_rule(start: NaiveDateTime, rule: &Rule) -> Vec<NaiveDateTime> {
+    let mut out = Vec::new();
+    let interval = rule.interval as i64;
+    if rule.freq == Freq::Weekly && !rule.by_day.is_empty() {
+        let week_start =
+            start.date() - chrono::Duration::days(start.weekday().num_days_from_monday() as i64);
+        let mut days: Vec<u32> = rule
+            .by_day
+            .iter()
+            .map(|d| d.num_days_from_monday())
+            .collect();
+        days.sort_unstable();
+        days.dedup();
+        for week in 0.. {
+            let monday = week_start + chrono::Duration::weeks(week * interval);
+            for &offset in &days {
+                let candidate =
+                    (monday + chrono::Duration::days(offset as i64)).and_time(start.time());
+                if candidate >= start {
+                    out.push(candidate);
+                }
+                if out.len() >= MAX_OCCURRENCES {
+                    return out;
+                }
+            }
+        }
+        return out;
+    }
+    for n in 0..MAX_OCCURRENCES as i64 {
+        let candidate = match rule.freq {
+            Freq::Daily => Some(start + chrono::Duration::days(n * interval)),
+            Freq::Weekly => Some(start + chrono::Duration::weeks(n * interval)),
+            Freq::Monthly | Freq::Yearly => {
+                let months = if rule.freq == Freq::Monthly {
+                    n * interval
+                } else {
+                    n * interval * 12
+                };
+                start
+                    .date()
+                    .checked_add_months(Months::new(months as u32))
+                    // `Jan 31 + 1 month` clamps to Feb 28; RFC 5545 skips it instead.
+                    .filter(|d| d.day() == start.day())
+                    .map(|d| d.and_time(start.time()))
+            }
+        };
+        if let Some(candidate) = candidate {
+            out.push(candidate);
+        }
+    }
+    out
+}
+
+/// Occurrences of an RFC 5545 `RRULE` (with or without the `RRULE:` prefix) starting at
+/// `start`, a wall time in `tz`. Honours COUNT/UNTIL and stops after `limit` occurrences.
+pub(crate) fn expand_rrule(
+    start: NaiveDateTime,
+    rrule: &str,
+    tz: Option<&str>,
+    limit: usize,
+) -> Result<Vec<NaiveDateTime>> {
+    let value = rrule.trim();
+    let value = value
+        .strip_prefix("RRULE:")
+        .or_else(|| value.strip_prefix("rrule:"))
+        .unwrap_or(value);
+    let zone = zone_for(tz);
+    let rule = parse_rrule(value, zone).ok_or_else(|| {
+        Error::Validation(format!(
+            "Unsupported RRULE '{}'. Use FREQ=DAILY|WEEKLY|MONTHLY|YEARLY with optional INTERVAL, COUNT, UNTIL, BYDAY",
+            rrule
+        ))
+    })?;
+    let count = rule.count.unwrap_or(usize::MAX).min(limit);
+    Ok(expand_rule(start, &rule)
+        .into_iter()
+        .take(count)
+        .take_while(|occ| match (rule.until_ms, to_ms(*occ, zone)) {
+            (Some(until), Some(ms)) => ms <= until,
+            _ => true,
+        })
+        .collect())
+}
+
+/// Busy intervals of one ICS document that overlap `[from_ms, to_ms)`.
+pub fn parse_ics(
+    text: &str,
+    calendar: &str,
+    default_tz: Option<&str>,
+    from_ms: i64,
+    to_ms_end: i64,
+) -> Vec<Busy> {
+    let default = zone_for(default_tz);
+    let mut events: Vec<Vec<Property>> = Vec::new();
+    let mut current: Option<Vec<Property>> = None;
+    for line in unfold(text) {
+        let upper = line.trim().to_ascii_uppercase();
+        if upper == "BEGIN:VEVENT" {
+            current = Some(Vec::new());
+        } else if upper == "END:VEVENT" {
+            if let Some(event) = current.take() {
+                events.push(event);
+            }
+        } else if let (Some(event), Some(prop)) = (current.as_mut(), parse_property(&line)) {
+            event.push(prop);
+        }
+    }
+
+    let find = |event: &[Property], name: &str| event.iter().position(|p| p.name == name);
+
+    // Instances moved via RECURRENCE-ID replace the master occurrence at that time.
+    let mut overridden: HashSet<(String, i64)> = HashSet::new();
+    for event in &events {
+        if let (Some(uid), Some(rid)) = (find(event, "UID"), find(event, "RECURRENCE-ID")) {
+            if let Some(ms) = parse_ics_time(&event[rid], default).and_then(|(n, z, _)| to_ms(n, z))
+            {
+                overridden.insert((event[uid].value.clone(), ms));
+            }
+        }
+    }
+
+    let mut busy = Vec::new();
+    for event in &events {
+        let get = |name: &str| find(event, name).map(|i| &event[i]);
+        if get("STATUS").is_some_and(|p| p.value.eq_ignore_ascii_case("CANCELLED"))
+            || get("TRANSP").is_some_and(|p| p.value.eq_ignore_ascii_case("TRANSPARENT"))
+        {
+            continue;
+        }
+        let Some((start, zone, all_day)) = get("DTSTART").and_then(|p| parse_ics_time(p, default))
+        else {
+            continue;
+        };
+        let Some(start_ms) = to_ms(start, zone) else {
+            continue;
+        };
+        let duration_ms = if let Some(end) = get("DTEND").and_then(|p| parse_ics_time(p, default)) {
+            to_ms(end.0, end.1).map(|e| e - start_ms).unwrap_or(0)
+        } else if let Some(d) = get("DURATION").and_then(|p| parse_duration(&p.value)) {
+            d
+        } else if all_day {
+            DAY_MS
+        } else {
+            0
+        };
+        if duration_ms <= 0 {
+            continue;
+        }
+        let title = get("SUMMARY")
+            .map(|p| p.value.replace("\\,", ",").replace("\\n", " "))
+            .unwrap_or_else(|| "(busy)".to_string());
+        let uid = get("UID").map(|p| p.value.clone()).unwrap_or_default();
+        let exdates: HashSet<i64> = event
+            .iter()
+            .filter(|p| p.name == "EXDATE")
+            .flat_map(|p| {
+                p.value
+                    .split(',')
+                    .filter_map(|v| {
+                        let single = Property {
+                            name: "EXDATE".to_string(),
+                            params: p.params.clone(),
+                            value: v.to_string(),
+                        };
+                        parse_ics_time(&single, default).and_then(|(n, z, _)| to_ms(n, z))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        let rule = get("RRULE").and_then(|p| parse_rrule(&p.value, default));
+        // A RECURRENCE-ID instance is a plain event even if it repeats the master's RRULE.
+        let rule = rule.filter(|_| get("RECURRENCE-ID").is_none());
+        let starts: Vec<i64> = match &rule {
+            None => vec![start_ms],
+            Some(rule) => {
+                let mut starts = Vec::new();
+                for (i, occurrence) in expand_rule(start, rule).into_iter().enumerate() {
+                    if rule.count.is_some_and(|c| i >= c) {
+                        break;
+                    }
+                    let Some(ms) = to_ms(occurrence, zone) else {
+                        continue;
+                    };
+                    if ms >= to_ms_end || rule.until_ms.is_some_and(|u| ms > u) {
+                        break;
+                    }
+                    if exdates.contains(&ms) || overridden.contains(&(uid.clone(), ms)) {
+                        continue;
+                    }
+                    starts.push(ms);
+                }
+                starts
+            }
+        };
+        for s in starts {
+            let item = Busy {
+                start_ms: s,
+                end_ms: s + duration_ms,
+                title: title.clone(),
+                kind: BusyKind::Event,
+                source: calendar.to_string(),
+                recurring: rule.is_some(),
+            };
+            if item.overlaps(from_ms, to_ms_end) {
+                busy.push(item);
+            }
+        }
+    }
+    busy
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Sources: calendar feeds, cron reminders, TODOs
+// ─────────────────────────────────────────────────────────────────────────────
+
+async fn load_feed(ctx: &ToolContext, location: &str, cache_minutes: u32) -> Result<String> {
+    let ttl = Duration::from_secs(cache_minutes as u64 * 60);
+    if let Ok(cache) = FEED_CACHE.lock() {
+        if let Some((fetched, body)) = cache.get(location) {
+            if fetched.elapsed() < ttl {
+                return Ok(body.clone());
+            }
+        }
+    }
+    let body = if location.starts_with("http://")
+        || location.starts_with("https://")
+        || location.starts_with("webcal://")
+    {
+        let url = location.replacen("webcal://", "https://", 1);
+        let egress = HttpClientFactory::for_tool(&ctx.config, "calendar");
+        egress.check(&url).await?;
+        let client = egress
+            .builder()
+            .timeout(Duration::from_secs(FETCH_TIMEOUT_SECS))
+            .build()
+            .map_err(|e| Error::Tool(format!("Failed to create HTTP client: {}", e)))?;
+        let response = client
+            .get(&url)
+            .header(
+                "User-Agent",
+                format!("blockcell/{}", env!("CARGO_PKG_VERSION")),
+            )
+            .send()
+            .await
+            .map_err(|e| Error::Tool(format!("Calendar fetch failed: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(Error::Tool(format!(
+                "Calendar fetch returned HTTP {}",
+                response.status().as_u16()
+            )));
+        }
+        response
+            .text()
+            .await
+            .map_err(|e| Error::Tool(format!("Calendar fetch failed: {}", e)))?
+    } else {
+        let path = match location.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().unwrap_or_default().join(rest),
+            None => std::path::PathBuf::from(location),
+        };
+        tokio::fs::read_to_string(&path).await?
+    };
+    if let Ok(mut cache) = FEED_CACHE.lock() {
+        cache.insert(location.to_string(), (Instant::now(), body.clone()));
+    }
+    Ok(body)
+}
+
+/// Occurrences of blockcell's own cron jobs in the window.
+fn reminder_busy(
+    paths: &Paths,
+    tz: Option<&str>,
+    block_ms: i64,
+    from_ms: i64,
+    to_ms_end: i64,
+) -> Vec<Busy> {
+    let Ok(content) = std::fs::read_to_string(paths.cron_jobs_file()) else {
+        return Vec::new();
+    };
+    let Ok(store) = serde_json::from_str::<Value>(&content) else {
+        return Vec::new();
+    };
+    let mut busy = Vec::new();
+    for job in store["jobs"].as_array().into_iter().flatten() {
+        if !job["enabled"].as_bool().unwrap_or(false) {
+            continue;
+        }
+        let schedule = &job["schedule"];
+        let mut push = |start: i64, recurring: bool| {
+            let item = Busy {
+                start_ms: start,
+                end_ms: start + block_ms,
+                title: job["name"].as_str().unwrap_or("reminder").to_string(),
+                kind: BusyKind::Reminder,
+                source: job["id"].as_str().unwrap_or("").to_string(),
+                recurring,
+            };
+            if item.overlaps(from_ms, to_ms_end) {
+                busy.push(item);
+            }
+        };
+        match schedule["kind"].as_str().unwrap_or("") {
+            "at" => {
+                if let Some(at) = schedule["atMs"].as_i64() {
+                    push(at, false);
+                }
+            }
+            "every" => {
+                let Some(every) = schedule["everyMs"].as_i64().filter(|e| *e > 0) else {
+                    continue;
+                };
+                let mut next = job["state"]["nextRunAtMs"]
+                    .as_i64()
+                    .unwrap_or_else(|| Utc::now().timestamp_millis() + every);
+                if next < from_ms {
+                    next += (from_ms - next) / every * every;
+                }
+                for _ in 0..MAX_OCCURRENCES {
+                    if next >= to_ms_end {
+                        break;
+                    }
+                    push(next, true);
+                    next += every;
+                }
+            }
+            "cron" => {
+                let Some(schedule_expr) = schedule["expr"]
+                    .as_str()
+                    .and_then(|e| cron::Schedule::from_str(e).ok())
+                else {
+                    continue;
+                };
+                let job_tz = schedule["tz"].as_str().or(tz);
+                let from = Utc
+                    .timestamp_millis_opt(from_ms - block_ms)
+                    .single()
+                    .unwrap_or_else(Utc::now);
+                let times: Vec<i64> = match job_tz.and_then(|t| t.parse::<chrono_tz::Tz>().ok()) {
+                    Some(tz) => schedule_expr
+                        .after(&from.with_timezone(&tz))
+                        .take(MAX_OCCURRENCES)
+                        .map(|t| t.timestamp_millis())
+                        .take_while(|t| *t < to_ms_end)
+                        .collect(),
+                    None => schedule_expr
+                        .after(&from.with_timezone(&chrono::Local))
+                        .take(MAX_OCCURRENCES)
+                        .map(|t| t.timestamp_millis())
+                        .take_while(|t| *t < to_ms_end)
+                        .collect(),
+                };
+                for t in times {
+                    push(t, true);
+                }
+            }
+            _ => {}
+        }
+    }
+    busy
+}
+
+fn todo_busy(
+    workspace: &std::path::Path,
+    block_ms: i64,
+    from_ms: i64,
+    to_ms_end: i64,
+) -> Vec<Busy> {
+    let db = blockcell_storage::todos::db_path(workspace);
+    if !db.exists() {
+        return Vec::new();
+    }
+    let Ok(store) = blockcell_storage::todos::TodoStore::open(&db) else {
+        return Vec::new();
+    };
+    store
+        .list(blockcell_storage::todos::TodoFilter::Open, 500)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|t| {
+            let due = t.due_at?;
+            let item = Busy {
+                start_ms: due,
+                end_ms: due + block_ms,
+                title: t.text,
+                kind: BusyKind::Todo,
+                source: t.id,
+                recurring: false,
+            };
+            item.overlaps(from_ms, to_ms_end).then_some(item)
+        })
+        .collect()
+}
+
+/// Everything occupying `[from_ms, to_ms)`, sorted by start. Feed failures are reported
+/// in the second value rather than failing the whole lookup.
+pub async fn collect_busy(
+    ctx: &ToolContext,
+    from_ms: i64,
+    to_ms_end: i64,
+) -> (Vec<Busy>, Vec<String>) {
+    let cfg = &ctx.config.tools.calendar;
+    let tz = ctx.config.default_timezone.as_deref();
+    let paths = ctx.paths();
+    let mut busy = Vec::new();
+    let mut errors = Vec::new();
+    for source in &cfg.sources {
+        let location = match resolve_secret_ref(&paths, &source.url) {
+            Ok(location) => location,
+            Err(e) => {
+                errors.push(format!("{}: {}", source.name, e));
+                continue;
+            }
+        };
+        match load_feed(ctx, &location, cfg.cache_minutes).await {
+            Ok(text) => busy.extend(parse_ics(&text, &source.name, tz, from_ms, to_ms_end)),
+            Err(e) => errors.push(format!("{}: {}", source.name, e)),
+        }
+    }
+    let block_ms = cfg.reminder_minutes.max(1) as i64 * MINUTE_MS;
+    let workspace = ctx.workspace.clone();
+    let tz_owned = tz.map(str::to_string);
+    let local = tokio::task::spawn_blocking(move || {
+        let mut items = reminder_busy(&paths, tz_owned.as_deref(), block_ms, from_ms, to_ms_end);
+        items.extend(todo_busy(&workspace, block_ms, from_ms, to_ms_end));
+        items
+    })
+    .await
+    .unwrap_or_default();
+    busy.extend(local);
+    busy.sort_by_key(|b| (b.start_ms, b.end_ms));
+    (busy, errors)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Conflicts and suggestions
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub fn conflicts(busy: &[Busy], start_ms: i64, end_ms: i64) -> Vec<&Busy> {
+    busy.iter()
+        .filter(|b| b.overlaps(start_ms, end_ms))
+        .collect()
+}
+
+fn parse_hhmm(value: &str, fallback: (u32, u32)) -> NaiveTime {
+    let (h, m) = value
+        .split_once(':')
+        .and_then(|(h, m)| Some((h.trim().parse().ok()?, m.trim().parse().ok()?)))
+        .unwrap_or(fallback);
+    NaiveTime::from_hms_opt(h, m, 0)
+        .or_else(|| NaiveTime::from_hms_opt(fallback.0, fallback.1, 0))
+        .unwrap_or(NaiveTime::MIN)
+}
+
+/// Up to `count` free slots of `duration_ms` inside working hours between `from_ms`
+/// and `to_ms`, on a 30-minute grid.
+pub fn suggest_slots(
+    busy: &[Busy],
+    cfg: &CalendarConfig,
+    tz: Option<&str>,
+    duration_ms: i64,
+    from_ms: i64,
+    to_ms_end: i64,
+    count: usize,
+) -> Vec<(i64, i64)> {
+    let zone = zone_for(tz);
+    let work_start = parse_hhmm(&cfg.work_start, (9, 0));
+    let work_end = parse_hhmm(&cfg.work_end, (18, 0));
+    let blocking: Vec<&Busy> = busy.iter().filter(|b| b.blocks_suggestions()).collect();
+    let local_date = |ms: i64| -> Option<NaiveDate> {
+        let utc = Utc.timestamp_millis_opt(ms).single()?;
+        Some(match zone {
+            Zone::Named(tz) => utc.with_timezone(&tz).date_naive(),
+            Zone::Utc => utc.date_naive(),
+            Zone::System => utc.with_timezone(&chrono::Local).date_naive(),
+        })
+    };
+    let (Some(mut day), Some(last_day)) = (local_date(from_ms), local_date(to_ms_end)) else {
+        return Vec::new();
+    };
+    let mut slots = Vec::new();
+    while day <= last_day && slots.len() < count {
+        let weekend = matches!(day.weekday(), Weekday::Sat | Weekday::Sun);
+        if cfg.include_weekends || !weekend {
+            if let (Some(open), Some(close)) = (
+                to_ms(day.and_time(work_start), zone),
+                to_ms(day.and_time(work_end), zone),
+            ) {
+                let close = close.min(to_ms_end);
+                let mut candidate = open.max(from_ms);
+                candidate = (candidate + SLOT_STEP_MS - 1).div_euclid(SLOT_STEP_MS) * SLOT_STEP_MS;
+                while candidate + duration_ms <= close && slots.len() < count {
+                    let end = candidate + duration_ms;
+                    match blocking
+                        .iter()
+                        .filter(|b| b.overlaps(candidate, end))
+                        .map(|b| b.end_ms)
+                        .max()
+                    {
+                        None => {
+                            slots.push((candidate, end));
+                            candidate = end.max(candidate + SLOT_STEP_MS);
+                        }
+                        Some(busy_end) => {
+                            candidate = busy_end.max(candidate + 1);
+                        }
+                    }
+                    candidate =
+                        (candidate + SLOT_STEP_MS - 1).div_euclid(SLOT_STEP_MS) * SLOT_STEP_MS;
+                }
+            }
+        }
+        day = match day.succ_opt() {
+            Some(next) => next,
+            None => break,
+        };
+    }
+    slots
+}
+
+fn busy_json(item: &Busy, tz: Option<&str>) -> Value {
+    json!({
+        "title": item.title,
+        "kind": item.kind,
+        "source": item.source,
+        "recurring": item.recurring,
+        "start": format_time(item.start_ms, tz),
+        "end": format_time(item.end_ms, tz),
+    })
+}
+
+fn slot_json(slot: (i64, i64), tz: Option<&str>) -> Value {
+    json!({
+        "start": format_time(slot.0, tz),
+        "end": format_time(slot.1, tz),
+        "start_ms": slot.0,
+    })
+}
+
+/// Conflicts for a proposed `[start_ms, end_ms)` plus, when there are any, up to three
+/// alternative slots of the same length within the next week.
+pub async fn check_slot(ctx: &ToolContext, start_ms: i64, end_ms: i64) -> Value {
+    let tz = ctx.config.default_timezone.as_deref();
+    let horizon = start_ms.max(Utc::now().timestamp_millis()) + 7 * DAY_MS;
+    let (busy, errors) =
+        collect_busy(ctx, start_ms.min(Utc::now().timestamp_millis()), horizon).await;
+    let hits: Vec<Value> = conflicts(&busy, start_ms, end_ms)
+        .into_iter()
+        .map(|b| busy_json(b, tz))
+        .collect();
+    let alternatives: Vec<Value> = if hits.is_empty() {
+        Vec::new()
+    } else {
+        let from = start_ms.max(Utc::now().timestamp_millis());
+        suggest_slots(
+            &busy,
+            &ctx.config.tools.calendar,
+            tz,
+            end_ms - start_ms,
+            from,
+            horizon,
+            3,
+        )
+        .into_iter()
+        .map(|s| slot_json(s, tz))
+        .collect()
+    };
+    let mut result = json!({
+        "start": format_time(start_ms, tz),
+        "end": format_time(end_ms, tz),
+        "free": hits.is_empty(),
+        "conflicts": hits,
+        "alternatives": alternatives,
+    });
+    if !errors.is_empty() {
+        result["calendar_errors"] = json!(errors);
+    }
+    result
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tool
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn time_arg(params: &Value, key: &str, tz: Option<&str>) -> Result<Option<i64>> {
+    match params.get(key).and_then(|v| v.as_str()).map(str::trim) {
+        Some(s) if !s.is_empty() => parse_local_time(s, tz).map(Some),
+        _ => Ok(None),
+    }
+}
+
+fn duration_arg(params: &Value) -> i64 {
+    params
+        .get("duration_minutes")
+        .and_then(|v| v.as_i64())
+        .filter(|m| *m > 0)
+        .unwrap_or(30)
+        .min(24 * 60)
+        * MINUTE_MS
+}
+
+pub struct CalendarTool;
+
+#[async_trait]
+impl Tool for CalendarTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "calendar",
+            description: "Check availability before scheduling. Combines the user's calendars (tools.calendar.sources, iCalendar) \
+                with blockcell reminders (cron) and due TODOs. Actions: 'free_busy' (busy intervals between from/to), \
+                'check' (conflicts for start + duration_minutes or end, with alternatives), \
+                'suggest' (free slots of duration_minutes inside working hours).",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["free_busy", "check", "suggest"],
+                        "description": "Action to perform"
+                    },
+                    "start": {"type": "string", "description": "(check) Proposed start: 'YYYY-MM-DD HH:MM' in the user's timezone or RFC 3339"},
+                    "end": {"type": "string", "description": "(check) Proposed end; alternative to duration_minutes"},
+                    "duration_minutes": {"type": "integer", "description": "(check/suggest) Length in minutes, default 30"},
+                    "from": {"type": "string", "description": "(free_busy/suggest) Window start, default now"},
+                    "to": {"type": "string", "description": "(free_busy/suggest) Window end, default 7 days after from"},
+                    "count": {"type": "integer", "description": "(suggest) Number of slots, default 3, max 10"}
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        match params.get("action").and_then(|v| v.as_str()).unwrap_or("") {
+            "free_busy" | "suggest" => Ok(()),
+            "check" => {
+                if params
+                    .get("start")
+                    .and_then(|v| v.as_str())
+                    .is_none_or(|s| s.trim().is_empty())
+                {
+                    return Err(Error::Validation("check requires 'start'".to_string()));
+                }
+                Ok(())
+            }
+            "" => Err(Error::Validation("Missing 'action'".to_string())),
+            other => Err(Error::Validation(format!("Unknown action: {}", other))),
+        }
+    }
+
+    fn prompt_rule(&self, _ctx: &PromptContext) -> Option<String> {
+        Some(
+            "- Before proposing a meeting time or creating a one-time reminder at a specific time, call `calendar` `check` (or `suggest` when no time is given). If there are conflicts, tell the user and offer the returned alternatives instead of silently double-booking."
+                .to_string(),
+        )
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        let action = params["action"].as_str().unwrap_or("");
+        let tz = ctx.config.default_timezone.clone();
+        let tz = tz.as_deref();
+        let now = Utc::now().timestamp_millis();
+        let from = time_arg(&params, "from", tz)?.unwrap_or(now);
+        let to = time_arg(&params, "to", tz)?.unwrap_or(from + 7 * DAY_MS);
+        if to <= from {
+            return Err(Error::Validation("'to' must be after 'from'".to_string()));
+        }
+
+        match action {
+            "free_busy" => {
+                let (busy, errors) = collect_busy(&ctx, from, to).await;
+                let mut result = json!({
+                    "from": format_time(from, tz),
+                    "to": format_time(to, tz),
+                    "busy": busy.iter().map(|b| busy_json(b, tz)).collect::<Vec<_>>(),
+                    "calendars": ctx.config.tools.calendar.sources.iter().map(|s| s.name.clone()).collect::<Vec<_>>(),
+                });
+                if !errors.is_empty() {
+                    result["calendar_errors"] = json!(errors);
+                }
+                Ok(result)
+            }
+            "check" => {
+                let start = time_arg(&params, "start", tz)?
+                    .ok_or_else(|| Error::Validation("check requires 'start'".to_string()))?;
+                let end = match time_arg(&params, "end", tz)? {
+                    Some(end) if end > start => end,
+                    Some(_) => {
+                        return Err(Error::Validation("'end' must be after 'start'".to_string()))
+                    }
+                    None => start + duration_arg(&params),
+                };
+                Ok(check_slot(&ctx, start, end).await)
+            }
+            "suggest" => {
+                let count = params
+                    .get("count")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(3)
+                    .clamp(1, 10) as usize;
+                let (busy, errors) = collect_busy(&ctx, from, to).await;
+                let slots: Vec<Value> = suggest_slots(
+                    &busy,
+                    &ctx.config.tools.calendar,
+                    tz,
+                    duration_arg(&params),
+                    from,
+                    to,
+                    count,
+                )
+                .into_iter()
+                .map(|s| slot_json(s, tz))
+                .collect();
+                let mut result = json!({"slots": slots, "count": slots.len()});
+                if !errors.is_empty() {
+                    result["calendar_errors"] = json!(errors);
+                }
+                Ok(result)
+            }
+            _ => Err(Error::Tool(format!("Unknown action: {}", action))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ICS: &str = "BEGIN:VCALENDAR\r\n\
+BEGIN:VEVENT\r\n\
+UID:standup\r\n\
+SUMMARY:Stand-up\r\n\
+DTSTART;TZID=Asia/Shanghai:20260302T100000\r\n\
+DURATION:PT30M\r\n\
+RRULE:FREQ=WEEKLY;BYDAY=MO,WE,FR;COUNT=6\r\n\
+EXDATE;TZID=Asia/Shanghai:20260304T100000\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+UID:lunch\r\n\
+SUMMARY:Lunch with\r\n  Alice\r\n\
+DTSTART:20260302T040000Z\r\n\
+DTEND:20260302T050000Z\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+UID:free\r\n\
+SUMMARY:Focus (free)\r\n\
+TRANSP:TRANSPARENT\r\n\
+DTSTART:20260302T060000Z\r\n\
+DTEND:20260302T070000Z\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+
+    fn ms(s: &str) -> i64 {
+        parse_local_time(s, Some("Asia/Shanghai")).unwrap()
+    }
+
+    #[test]
+    fn test_parse_ics_expands_rules_and_skips_free_events() {
+        let busy = parse_ics(
+            ICS,
+            "work",
+            Some("Asia/Shanghai"),
+            ms("2026-03-01 00:00"),
+            ms("2026-03-31 00:00"),
+        );
+        let standups: Vec<&Busy> = busy.iter().filter(|b| b.title == "Stand-up").collect();
+        // COUNT=6 occurrences minus one EXDATE.
+        assert_eq!(standups.len(), 5);
+        assert_eq!(standups[0].start_ms, ms("2026-03-02 10:00"));
+        assert_eq!(standups[0].end_ms - standups[0].start_ms, 30 * MINUTE_MS);
+        assert!(!standups
+            .iter()
+            .any(|b| b.start_ms == ms("2026-03-04 10:00")));
+        let lunch = busy.iter().find(|b| b.title == "Lunch with Alice").unwrap();
+        assert_eq!(lunch.start_ms, ms("2026-03-02 12:00"));
+        assert!(!busy.iter().any(|b| b.title.starts_with("Focus")));
+        assert_eq!(parse_duration("P1DT2H"), Some(DAY_MS + 120 * MINUTE_MS));
+    }
+
+    #[test]
+    fn test_conflicts_and_suggestions_avoid_busy_time() {
+        let busy = parse_ics(
+            ICS,
+            "work",
+            Some("Asia/Shanghai"),
+            ms("2026-03-02 00:00"),
+            ms("2026-03-03 00:00"),
+        );
+        let hits = conflicts(&busy, ms("2026-03-02 10:15"), ms("2026-03-02 11:00"));
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].title, "Stand-up");
+
+        let cfg = CalendarConfig::default();
+        let slots = suggest_slots(
+            &busy,
+            &cfg,
+            Some("Asia/Shanghai"),
+            60 * MINUTE_MS,
+            ms("2026-03-02 09:30"),
+            ms("2026-03-02 18:00"),
+            3,
+        );
+        assert_eq!(
+            slots.iter().map(|s| s.0).collect::<Vec<_>>(),
+            vec![
+                ms("2026-03-02 10:30"),
+                ms("2026-03-02 13:00"),
+                ms("2026-03-02 14:00")
+            ]
+        );
+        // Weekends are skipped by default (2026-03-07 is a Saturday).
+        assert!(suggest_slots(
+            &[],
+            &cfg,
+            Some("Asia/Shanghai"),
+            30 * MINUTE_MS,
+            ms("2026-03-07 00:00"),
+            ms("2026-03-08 23:00"),
+            3
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_recurring_reminders_do_not_block_suggestions() {
+        let reminder = Busy {
+            start_ms: ms("2026-03-02 09:00"),
+            end_ms: ms("2026-03-02 09:15"),
+            title: "drink water".to_string(),
+            kind: BusyKind::Reminder,
+            source: "job".to_string(),
+            recurring: true,
+        };
+        let slots = suggest_slots(
+            std::slice::from_ref(&reminder),
+            &CalendarConfig::default(),
+            Some("Asia/Shanghai"),
+            30 * MINUTE_MS,
+            ms("2026-03-02 09:00"),
+            ms("2026-03-02 10:00"),
+            1,
+        );
+        assert_eq!(slots[0].0, ms("2026-03-02 09:00"));
+        assert_eq!(
+            conflicts(
+                std::slice::from_ref(&reminder),
+                ms("2026-03-02 09:00"),
+                ms("2026-03-02 09:30")
+            )
+            .len(),
+            1
+        );
+    }
+}
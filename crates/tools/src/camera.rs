@@ -3,7 +3,7 @@ use blockcell_core::{Error, Result};
 use serde_json::{json, Value};
 use tracing::{debug, info, warn};
 
-use crate::{Tool, ToolContext, ToolSchema};
+use crate::{Tool, ToolConcurrency, ToolContext, ToolSchema};
 
 /// Tool for capturing photos using macOS camera.
 ///
@@ -54,6 +54,11 @@ impl Tool for CameraCaptureTool {
         Ok(())
     }
 
+    /// The camera can only be opened by one capture at a time.
+    fn concurrency(&self) -> ToolConcurrency {
+        ToolConcurrency::exclusive(60)
+    }
+
     async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
         let action = params
             .get("action")
@@ -97,11 +97,16 @@ impl Tool for ChartGenerateTool {
             .and_then(|v| v.as_str())
             .unwrap_or("info");
 
-        match action {
+        let result = match action {
             "generate" => action_generate(&ctx, &params).await,
             "info" => action_info().await,
             _ => Err(Error::Tool(format!("Unknown action: {}", action))),
+        }?;
+        if let Some(path) = result.get("output_path").and_then(|v| v.as_str()) {
+            let title = params.get("title").and_then(|v| v.as_str());
+            ctx.register_artifact(path, "", title);
         }
+        Ok(result)
     }
 }
 
@@ -29,13 +29,14 @@ fn resolve_skill_payload_kind(paths: &Paths, skill_name: Option<&str>) -> &'stat
     }
 }
 
-fn execute_cron_action_with_paths(
+pub(crate) fn execute_cron_action_with_paths(
     paths: &Paths,
     action: &str,
     params: &Value,
     origin_channel: &str,
     origin_chat_id: &str,
     default_timezone: Option<&str>,
+    role: Option<&str>,
 ) -> Result<Value> {
     match action {
         "add" => {
@@ -54,6 +55,10 @@ fn execute_cron_action_with_paths(
                 .get("delete_after_run")
                 .and_then(|v| v.as_bool())
                 .unwrap_or(false);
+            let maintenance_only = params
+                .get("maintenance_only")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
 
             let (kind, schedule) = if let Some(delay) =
                 params.get("delay_seconds").and_then(|v| v.as_i64())
@@ -143,6 +148,7 @@ fn execute_cron_action_with_paths(
                 Some("agent") => "agent",
                 Some("script") => "script",
                 Some("reminder") => "reminder",
+                Some("follow_up") => "follow_up",
                 Some(_) | None => {
                     if skill_name.is_some() {
                         "script"
@@ -171,6 +177,13 @@ fn execute_cron_action_with_paths(
             if let Some(sn) = skill_name {
                 payload["skillName"] = json!(sn);
             }
+            if payload_kind == "follow_up" {
+                payload["context"] = params.get("context").cloned().unwrap_or(Value::Null);
+                payload["accountId"] = params.get("account_id").cloned().unwrap_or(Value::Null);
+            }
+            if let Some(role) = role {
+                payload["role"] = json!(role);
+            }
 
             let job = json!({
                 "id": job_id,
@@ -181,7 +194,8 @@ fn execute_cron_action_with_paths(
                 "state": {},
                 "createdAtMs": now_ms,
                 "updatedAtMs": now_ms,
-                "deleteAfterRun": delete_after_run
+                "deleteAfterRun": delete_after_run,
+                "maintenanceOnly": maintenance_only
             });
 
             store.jobs.push(job);
@@ -327,7 +341,7 @@ impl Tool for CronTool {
     fn schema(&self) -> ToolSchema {
         ToolSchema {
             name: "cron",
-            description: "Manage scheduled tasks (cron jobs). You MUST provide `action`. action='add': requires `name` + `message` and exactly one schedule field from `delay_seconds`, `at_ms`, `every_seconds`, or `cron_expr`; optional `delete_after_run`, `mode`, and `skill_name`. action='list': no extra params. action='remove': requires `job_id`.",
+            description: "Manage scheduled tasks (cron jobs). You MUST provide `action`. action='add': requires `name` + `message` and exactly one schedule field from `delay_seconds`, `at_ms`, `every_seconds`, or `cron_expr`; optional `delete_after_run`, `maintenance_only`, `mode`, and `skill_name`. action='list': no extra params. action='remove': requires `job_id`.",
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -372,6 +386,10 @@ impl Tool for CronTool {
                         "type": "boolean",
                         "description": "(add) If true, the job will be deleted after it runs once. Default false (job is disabled instead)."
                     },
+                    "maintenance_only": {
+                        "type": "boolean",
+                        "description": "(add) For heavy background work (bulk processing, long syncs). If true and a maintenance calendar is configured, the job waits for the next maintenance window when due. Default false."
+                    },
                     "mode": {
                         "type": "string",
                         "enum": ["reminder", "script", "agent"],
@@ -474,6 +492,7 @@ impl Tool for CronTool {
         let origin_channel = ctx.channel.clone();
         let origin_chat_id = ctx.chat_id.clone();
         let default_timezone = ctx.config.default_timezone.clone();
+        let role = ctx.role();
         // Derive agent-specific paths from the workspace directory.
         // ctx.workspace = <base>/workspace, so parent() = <base> (e.g. ~/.blockcell/agents/<id>).
         let paths = if let Some(base) = ctx.workspace.parent() {
@@ -481,7 +500,27 @@ impl Tool for CronTool {
         } else {
             Paths::new()
         };
-        tokio::task::spawn_blocking(move || {
+        // One-time reminders are checked against calendars, other reminders and due TODOs
+        // before they are created; conflicts are reported alongside the new job.
+        let slot_check = if action == "add" {
+            let at_ms = params.get("at_ms").and_then(|v| v.as_i64()).or_else(|| {
+                params
+                    .get("delay_seconds")
+                    .and_then(|v| v.as_i64())
+                    .map(|d| Utc::now().timestamp_millis() + d * 1000)
+            });
+            match at_ms {
+                Some(at) if at >= 1_000_000_000_000 => {
+                    let block_ms =
+                        ctx.config.tools.calendar.reminder_minutes.max(1) as i64 * 60_000;
+                    Some(crate::calendar::check_slot(&ctx, at, at + block_ms).await)
+                }
+                _ => None,
+            }
+        } else {
+            None
+        };
+        let mut result = tokio::task::spawn_blocking(move || {
             execute_cron_action_with_paths(
                 &paths,
                 &action,
@@ -489,10 +528,16 @@ impl Tool for CronTool {
                 &origin_channel,
                 &origin_chat_id,
                 default_timezone.as_deref(),
+                Some(role.as_str()),
             )
         })
         .await
-        .map_err(|e| Error::Tool(format!("Cron task failed: {}", e)))?
+        .map_err(|e| Error::Tool(format!("Cron task failed: {}", e)))??;
+        if let Some(check) = slot_check.filter(|c| c["free"] == json!(false)) {
+            result["calendar_conflicts"] = check["conflicts"].clone();
+            result["alternatives"] = check["alternatives"].clone();
+        }
+        Ok(result)
     }
 }
 
@@ -544,6 +589,7 @@ mod tests {
             "telegram",
             "12345",
             None,
+            None,
         );
         assert!(r.is_ok(), "unexpected error: {:?}", r.err());
         let _ = std::fs::remove_dir_all(paths.base);
@@ -584,6 +630,7 @@ mod tests {
             "telegram",
             "12345",
             None,
+            None,
         );
         assert!(r.is_ok(), "unexpected error: {:?}", r.err());
 
@@ -652,6 +699,7 @@ mod tests {
             "telegram",
             "12345",
             Some("Invalid/Timezone"),
+            None,
         );
         assert!(r.is_err(), "Should reject invalid timezone");
         let err = r.unwrap_err().to_string();
@@ -677,6 +725,7 @@ mod tests {
             "telegram",
             "12345",
             Some("Asia/Shanghai"), // default_timezone
+            None,
         );
         assert!(
             r.is_ok(),
@@ -712,6 +761,7 @@ mod tests {
             "telegram",
             "12345",
             None,
+            None,
         );
         assert!(r.is_err(), "Should reject invalid cron expression");
         let err = r.unwrap_err().to_string();
@@ -736,6 +786,7 @@ mod tests {
             "telegram",
             "12345",
             None,
+            None,
         );
         assert!(r.is_err(), "Should reject negative every_seconds");
         let err = r.unwrap_err().to_string();
@@ -760,6 +811,7 @@ mod tests {
             "telegram",
             "12345",
             None,
+            None,
         );
         assert!(r.is_err(), "Should reject zero every_seconds");
         let _ = std::fs::remove_dir_all(paths.base);
@@ -779,6 +831,7 @@ mod tests {
             "telegram",
             "12345",
             Some("Asia/Shanghai"),
+            None,
         );
         assert!(r.is_ok(), "Should accept default_timezone: {:?}", r.err());
         let result = r.unwrap();
@@ -812,6 +865,7 @@ mod tests {
             "telegram",
             "12345",
             None,
+            None,
         );
         assert!(r.is_ok(), "Should accept run_immediately: {:?}", r.err());
 
@@ -0,0 +1,867 @@
+//! Document comparison: text extraction (markdown/plain text, docx, PDF text layer), a
+//! line-based unified diff and an LLM-written change summary.
+//!
+//! Watched directories keep a text snapshot of every matching file; the runtime tick calls
+//! [`spawn_due_scans`], which diffs changed files against their snapshot and posts the
+//! summary as a system event.
+
+use async_trait::async_trait;
+use blockcell_core::system_event::EventPriority;
+use blockcell_core::types::ChatMessage;
+use blockcell_core::{Config, Error, Result};
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{EventEmitterHandle, Tool, ToolContext, ToolSchema};
+
+/// Lines of unchanged context around each hunk.
+const CONTEXT_LINES: usize = 3;
+/// Above this many cells the LCS table is skipped and the changed middle is treated as
+/// one replacement.
+const MAX_LCS_CELLS: usize = 4_000_000;
+/// Diff text beyond this is not sent to the model.
+const MAX_SUMMARY_DIFF_CHARS: usize = 24_000;
+/// Changed files summarized per scan; the rest are reported by name only.
+const MAX_SUMMARIES_PER_SCAN: usize = 5;
+const DEFAULT_EXTENSIONS: &[&str] = &["md", "markdown", "txt", "docx", "pdf"];
+
+static STORE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+static SCAN_RUNNING: AtomicBool = AtomicBool::new(false);
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Text extraction and diff
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Text content of a document, one entry per line with trailing whitespace removed.
+pub fn extract_lines(path: &Path) -> Result<Vec<String>> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    let text = match ext.as_str() {
+        "docx" => crate::office::read_docx(path)?,
+        "pdf" => pdf_extract::extract_text(path)
+            .map_err(|e| Error::Tool(format!("Failed to extract PDF text: {}", e)))?,
+        _ => {
+            let bytes = std::fs::read(path)?;
+            String::from_utf8_lossy(&bytes).into_owned()
+        }
+    };
+    Ok(text
+        .replace('\u{0C}', "\n")
+        .lines()
+        .map(|l| l.trim_end().to_string())
+        .collect())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    /// Index into the old lines.
+    Equal(usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+fn diff_ops(old: &[String], new: &[String]) -> Vec<Op> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (a, b) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+
+    let mut ops: Vec<Op> = (0..prefix).map(Op::Equal).collect();
+    if a.len() * b.len() <= MAX_LCS_CELLS {
+        // lcs[i][j] = LCS length of a[i..] and b[j..].
+        let width = b.len() + 1;
+        let mut lcs = vec![0u32; (a.len() + 1) * width];
+        for i in (0..a.len()).rev() {
+            for j in (0..b.len()).rev() {
+                lcs[i * width + j] = if a[i] == b[j] {
+                    lcs[(i + 1) * width + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() && j < b.len() {
+            if a[i] == b[j] {
+                ops.push(Op::Equal(prefix + i));
+                i += 1;
+                j += 1;
+            } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+                ops.push(Op::Delete(prefix + i));
+                i += 1;
+            } else {
+                ops.push(Op::Insert(prefix + j));
+                j += 1;
+            }
+        }
+        ops.extend((i..a.len()).map(|i| Op::Delete(prefix + i)));
+        ops.extend((j..b.len()).map(|j| Op::Insert(prefix + j)));
+    } else {
+        ops.extend((0..a.len()).map(|i| Op::Delete(prefix + i)));
+        ops.extend((0..b.len()).map(|j| Op::Insert(prefix + j)));
+    }
+    ops.extend((old.len() - suffix..old.len()).map(Op::Equal));
+    ops
+}
+
+/// Result of comparing two texts.
+#[derive(Debug, Clone, Serialize)]
+pub struct TextDiff {
+    pub unified: String,
+    pub added: usize,
+    pub removed: usize,
+}
+
+impl TextDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added == 0 && self.removed == 0
+    }
+}
+
+/// Unified diff (`---`/`+++`/`@@` hunks) of two line lists.
+pub fn unified_diff(old: &[String], new: &[String], old_name: &str, new_name: &str) -> TextDiff {
+    let ops = diff_ops(old, new);
+    let added = ops.iter().filter(|o| matches!(o, Op::Insert(_))).count();
+    let removed = ops.iter().filter(|o| matches!(o, Op::Delete(_))).count();
+    if added == 0 && removed == 0 {
+        return TextDiff {
+            unified: String::new(),
+            added,
+            removed,
+        };
+    }
+
+    // Group changed ops into hunks, merging ones whose context would overlap.
+    let changed: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, o)| !matches!(o, Op::Equal(_)))
+        .map(|(i, _)| i)
+        .collect();
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for idx in changed {
+        let start = idx.saturating_sub(CONTEXT_LINES);
+        let end = (idx + CONTEXT_LINES + 1).min(ops.len());
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    let mut out = format!("--- {}\n+++ {}\n", old_name, new_name);
+    for (start, end) in ranges {
+        let hunk = &ops[start..end];
+        // Position where the hunk starts in each file (0-based), even if it opens with an insert.
+        let old_start = ops[..start]
+            .iter()
+            .filter(|o| !matches!(o, Op::Insert(_)))
+            .count();
+        let new_start = ops[..start]
+            .iter()
+            .filter(|o| !matches!(o, Op::Delete(_)))
+            .count();
+        let old_len = hunk.iter().filter(|o| !matches!(o, Op::Insert(_))).count();
+        let new_len = hunk.iter().filter(|o| !matches!(o, Op::Delete(_))).count();
+        let pos = |start: usize, len: usize| if len == 0 { start } else { start + 1 };
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            pos(old_start, old_len),
+            old_len,
+            pos(new_start, new_len),
+            new_len
+        ));
+        for op in hunk {
+            match *op {
+                Op::Equal(i) => out.push_str(&format!(" {}\n", old[i])),
+                Op::Delete(i) => out.push_str(&format!("-{}\n", old[i])),
+                Op::Insert(j) => out.push_str(&format!("+{}\n", new[j])),
+            }
+        }
+    }
+    TextDiff {
+        unified: out,
+        added,
+        removed,
+    }
+}
+
+/// The agent's default model (highest-priority pool entry, else `agents.defaults.model`).
+pub(crate) fn default_provider(config: &Config) -> Result<Box<dyn blockcell_providers::Provider>> {
+    let defaults = &config.agents.defaults;
+    let (model, provider) = match defaults.model_pool.iter().min_by_key(|e| e.priority) {
+        Some(entry) => (entry.model.clone(), Some(entry.provider.clone())),
+        None => (defaults.model.clone(), defaults.provider.clone()),
+    };
+    blockcell_providers::create_provider(config, &model, provider.as_deref())
+        .map_err(|e| Error::Tool(format!("Summary LLM unavailable: {}", e)))
+}
+
+/// LLM-written summary of `diff` for a reader who cares about substance, not formatting.
+pub async fn summarize_diff(config: &Config, name: &str, diff: &TextDiff) -> Result<String> {
+    let provider = default_provider(config)?;
+    let mut body: String = diff.unified.chars().take(MAX_SUMMARY_DIFF_CHARS).collect();
+    if body.len() < diff.unified.len() {
+        body.push_str("\n[diff truncated]\n");
+    }
+    let system = "You summarize changes between two versions of a document (often a contract, \
+        policy or specification). Write 3-8 short bullet points covering substantive changes: \
+        obligations, rights, dates, deadlines, amounts, parties, definitions, scope. \
+        Call out anything that could disadvantage the reader. Ignore pure formatting or \
+        whitespace changes. Answer in the document's language.";
+    let user = format!(
+        "Document: {}\n{} lines added, {} lines removed.\n\n{}",
+        name, diff.added, diff.removed, body
+    );
+    let response = provider
+        .chat(
+            &[ChatMessage::system(system), ChatMessage::user(&user)],
+            &[],
+        )
+        .await?;
+    Ok(response.content.unwrap_or_default().trim().to_string())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Watched directories
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileState {
+    pub modified_ms: i64,
+    pub len: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Watch {
+    pub id: String,
+    /// Absolute directory path.
+    pub path: String,
+    /// Lower-case extensions without the dot.
+    pub extensions: Vec<String>,
+    pub interval_minutes: u32,
+    #[serde(default)]
+    pub last_scan_ms: i64,
+    /// Relative path → state at the last snapshot.
+    #[serde(default)]
+    pub files: BTreeMap<String, FileState>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct WatchStore {
+    version: u32,
+    #[serde(default)]
+    watches: Vec<Watch>,
+}
+
+impl Default for WatchStore {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            watches: Vec::new(),
+        }
+    }
+}
+
+fn store_dir(workspace: &Path) -> PathBuf {
+    workspace.join("doc_diff")
+}
+
+fn store_path(workspace: &Path) -> PathBuf {
+    store_dir(workspace).join("watches.json")
+}
+
+fn snapshot_path(workspace: &Path, watch_id: &str, rel: &str) -> PathBuf {
+    let digest = hex_digest(rel);
+    store_dir(workspace)
+        .join("snapshots")
+        .join(watch_id)
+        .join(format!("{}.txt", &digest[..16]))
+}
+
+fn hex_digest(value: &str) -> String {
+    Sha256::digest(value.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn load_store(workspace: &Path) -> Result<WatchStore> {
+    let path = store_path(workspace);
+    if !path.exists() {
+        return Ok(WatchStore::default());
+    }
+    let content = std::fs::read_to_string(&path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+fn save_store(workspace: &Path, store: &WatchStore) -> Result<()> {
+    let path = store_path(workspace);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(store)?)?;
+    Ok(())
+}
+
+/// Matching files under `dir` (recursive, hidden entries skipped) with their state.
+fn list_files(dir: &Path, extensions: &[String]) -> BTreeMap<String, FileState> {
+    let mut out = BTreeMap::new();
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(current) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&current) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if meta.is_dir() {
+                stack.push(path);
+                continue;
+            }
+            let ext = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .unwrap_or("")
+                .to_ascii_lowercase();
+            if !extensions.contains(&ext) {
+                continue;
+            }
+            let modified_ms = meta
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_millis() as i64)
+                .unwrap_or(0);
+            if let Ok(rel) = path.strip_prefix(dir) {
+                out.insert(
+                    rel.to_string_lossy().replace('\\', "/"),
+                    FileState {
+                        modified_ms,
+                        len: meta.len(),
+                    },
+                );
+            }
+        }
+    }
+    out
+}
+
+/// One file that changed since the previous snapshot.
+#[derive(Debug, Clone, Serialize)]
+pub struct Change {
+    pub file: String,
+    /// "added", "modified" or "removed".
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<TextDiff>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Compare a watch's directory with its snapshots, refreshing them. The first scan of a
+/// file only records its snapshot (reported as "added" without a diff).
+fn scan_watch(workspace: &Path, watch: &mut Watch) -> Vec<Change> {
+    let dir = PathBuf::from(&watch.path);
+    let current = list_files(&dir, &watch.extensions);
+    let mut changes = Vec::new();
+    for (rel, state) in &current {
+        let previous = watch.files.get(rel);
+        if previous == Some(state) {
+            continue;
+        }
+        let snapshot = snapshot_path(workspace, &watch.id, rel);
+        let new_lines = match extract_lines(&dir.join(rel)) {
+            Ok(lines) => lines,
+            Err(e) => {
+                changes.push(Change {
+                    file: rel.clone(),
+                    kind: "modified".to_string(),
+                    diff: None,
+                    summary: None,
+                    error: Some(e.to_string()),
+                });
+                continue;
+            }
+        };
+        let old_text = std::fs::read_to_string(&snapshot).ok();
+        let diff = old_text.as_ref().map(|text| {
+            let old_lines: Vec<String> = text.lines().map(str::to_string).collect();
+            unified_diff(
+                &old_lines,
+                &new_lines,
+                &format!("a/{}", rel),
+                &format!("b/{}", rel),
+            )
+        });
+        if let Some(parent) = snapshot.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        let _ = std::fs::write(&snapshot, new_lines.join("\n"));
+        match diff {
+            // Touched but textually identical (re-saved, metadata only).
+            Some(d) if d.is_empty() => {}
+            Some(d) => changes.push(Change {
+                file: rel.clone(),
+                kind: "modified".to_string(),
+                diff: Some(d),
+                summary: None,
+                error: None,
+            }),
+            None => {
+                if previous.is_some() || watch.last_scan_ms > 0 {
+                    changes.push(Change {
+                        file: rel.clone(),
+                        kind: "added".to_string(),
+                        diff: None,
+                        summary: None,
+                        error: None,
+                    });
+                }
+            }
+        }
+    }
+    for rel in watch.files.keys() {
+        if !current.contains_key(rel) {
+            let _ = std::fs::remove_file(snapshot_path(workspace, &watch.id, rel));
+            changes.push(Change {
+                file: rel.clone(),
+                kind: "removed".to_string(),
+                diff: None,
+                summary: None,
+                error: None,
+            });
+        }
+    }
+    watch.files = current;
+    watch.last_scan_ms = Utc::now().timestamp_millis();
+    changes
+}
+
+async fn summarize_changes(config: &Config, changes: &mut [Change]) {
+    for change in changes
+        .iter_mut()
+        .filter(|c| c.diff.is_some())
+        .take(MAX_SUMMARIES_PER_SCAN)
+    {
+        let Some(diff) = change.diff.as_ref() else {
+            continue;
+        };
+        match summarize_diff(config, &change.file, diff).await {
+            Ok(summary) => change.summary = Some(summary),
+            Err(e) => change.error = Some(e.to_string()),
+        }
+    }
+}
+
+/// Scan watches (all, or the one with `only` id) and summarize modified files.
+async fn scan(
+    config: &Config,
+    workspace: &Path,
+    only: Option<&str>,
+    due_only: bool,
+) -> Result<Vec<(Watch, Vec<Change>)>> {
+    let guard = STORE_LOCK.lock().await;
+    let mut store = load_store(workspace)?;
+    let now = Utc::now().timestamp_millis();
+    let mut results = Vec::new();
+    for watch in store.watches.iter_mut() {
+        if only.is_some_and(|id| id != watch.id) {
+            continue;
+        }
+        if due_only && now - watch.last_scan_ms < watch.interval_minutes.max(1) as i64 * 60_000 {
+            continue;
+        }
+        let ws = workspace.to_path_buf();
+        let mut owned = watch.clone();
+        let (owned, changes) = tokio::task::spawn_blocking(move || {
+            let changes = scan_watch(&ws, &mut owned);
+            (owned, changes)
+        })
+        .await
+        .map_err(|e| Error::Tool(format!("Scan task failed: {}", e)))?;
+        *watch = owned;
+        results.push((watch.clone(), changes));
+    }
+    save_store(workspace, &store)?;
+    drop(guard);
+    for (_, changes) in results.iter_mut() {
+        summarize_changes(config, changes).await;
+    }
+    Ok(results)
+}
+
+fn change_event_text(watch: &Watch, changes: &[Change]) -> String {
+    let mut out = String::new();
+    for change in changes {
+        let label = match change.kind.as_str() {
+            "added" => "新增",
+            "removed" => "删除",
+            _ => "修改",
+        };
+        out.push_str(&format!("【{}】{}", label, change.file));
+        if let Some(diff) = &change.diff {
+            out.push_str(&format!("（+{} / -{} 行）", diff.added, diff.removed));
+        }
+        out.push('\n');
+        if let Some(summary) = &change.summary {
+            out.push_str(summary);
+            out.push_str("\n\n");
+        }
+    }
+    out.push_str(&format!("目录：{}", watch.path));
+    out
+}
+
+/// Scan due watches in the background and post a system event per watch with changes.
+pub fn spawn_due_scans(config: Config, workspace: PathBuf, emitter: EventEmitterHandle) {
+    if !store_path(&workspace).exists() {
+        return;
+    }
+    if SCAN_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    tokio::spawn(async move {
+        match scan(&config, &workspace, None, true).await {
+            Ok(results) => {
+                for (watch, changes) in results.iter().filter(|(_, c)| !c.is_empty()) {
+                    info!(watch = %watch.id, changes = changes.len(), "Watched documents changed");
+                    emitter.emit_simple(
+                        "doc_diff.changed",
+                        "doc_diff",
+                        EventPriority::Normal,
+                        "文档变更",
+                        &change_event_text(watch, changes),
+                    );
+                }
+            }
+            Err(e) => warn!(error = %e, "Document watch scan failed"),
+        }
+        SCAN_RUNNING.store(false, Ordering::SeqCst);
+    });
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tool
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn resolve_path(path: &str, workspace: &Path) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir().unwrap_or_default().join(rest),
+        None if Path::new(path).is_absolute() => PathBuf::from(path),
+        None => workspace.join(path),
+    }
+}
+
+pub struct DocDiffTool;
+
+#[async_trait]
+impl Tool for DocDiffTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "doc_diff",
+            description: "Compare document versions (markdown/text, docx, PDF text layer). \
+                Actions: 'compare' (old + new paths: unified diff plus an LLM change summary), \
+                'watch' (path to a directory: snapshot its documents and auto-summarize changes as they appear), \
+                'unwatch' (id), 'watches' (list), 'scan' (check watches now, optional id).",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["compare", "watch", "unwatch", "watches", "scan"],
+                        "description": "Action to perform"
+                    },
+                    "old": {"type": "string", "description": "(compare) Previous version, workspace-relative or absolute"},
+                    "new": {"type": "string", "description": "(compare) New version"},
+                    "summarize": {"type": "boolean", "description": "(compare) Add an LLM change summary. Default: true"},
+                    "path": {"type": "string", "description": "(watch) Directory to watch (recursive)"},
+                    "extensions": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "(watch) File extensions to track. Default: md, markdown, txt, docx, pdf"
+                    },
+                    "interval_minutes": {"type": "integer", "description": "(watch) Check interval, default 15"},
+                    "id": {"type": "string", "description": "(unwatch/scan) Watch id"}
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        let action = params.get("action").and_then(|v| v.as_str()).unwrap_or("");
+        let has = |key: &str| {
+            params
+                .get(key)
+                .and_then(|v| v.as_str())
+                .is_some_and(|s| !s.trim().is_empty())
+        };
+        match action {
+            "compare" => {
+                if !has("old") || !has("new") {
+                    return Err(Error::Validation(
+                        "'old' and 'new' are required for compare".into(),
+                    ));
+                }
+            }
+            "watch" => {
+                if !has("path") {
+                    return Err(Error::Validation("'path' is required for watch".into()));
+                }
+            }
+            "unwatch" => {
+                if !has("id") {
+                    return Err(Error::Validation("'id' is required for unwatch".into()));
+                }
+            }
+            "watches" | "scan" => {}
+            _ => return Err(Error::Validation(format!("Unknown action: {}", action))),
+        }
+        Ok(())
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        let action = params["action"].as_str().unwrap_or("");
+        let str_param = |key: &str| params[key].as_str().unwrap_or("").trim().to_string();
+        match action {
+            "compare" => {
+                let old = resolve_path(&str_param("old"), &ctx.workspace);
+                let new = resolve_path(&str_param("new"), &ctx.workspace);
+                for p in [&old, &new] {
+                    if !p.is_file() {
+                        return Err(Error::NotFound(format!("File not found: {}", p.display())));
+                    }
+                }
+                let (old_name, new_name) = (old.display().to_string(), new.display().to_string());
+                let diff = tokio::task::spawn_blocking(move || -> Result<TextDiff> {
+                    let a = extract_lines(&old)?;
+                    let b = extract_lines(&new)?;
+                    Ok(unified_diff(&a, &b, &old_name, &new_name))
+                })
+                .await
+                .map_err(|e| Error::Tool(format!("Diff task failed: {}", e)))??;
+                let mut result = json!({
+                    "identical": diff.is_empty(),
+                    "added_lines": diff.added,
+                    "removed_lines": diff.removed,
+                    "diff": diff.unified,
+                });
+                if !diff.is_empty() && params["summarize"].as_bool().unwrap_or(true) {
+                    let name = Path::new(&str_param("new"))
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    match summarize_diff(&ctx.config, &name, &diff).await {
+                        Ok(summary) => result["summary"] = json!(summary),
+                        Err(e) => result["summary_error"] = json!(e.to_string()),
+                    }
+                }
+                Ok(result)
+            }
+            "watch" => {
+                let dir = resolve_path(&str_param("path"), &ctx.workspace);
+                if !dir.is_dir() {
+                    return Err(Error::NotFound(format!(
+                        "Directory not found: {}",
+                        dir.display()
+                    )));
+                }
+                let extensions: Vec<String> = match params["extensions"].as_array() {
+                    Some(items) if !items.is_empty() => items
+                        .iter()
+                        .filter_map(|v| v.as_str())
+                        .map(|e| e.trim().trim_start_matches('.').to_ascii_lowercase())
+                        .filter(|e| !e.is_empty())
+                        .collect(),
+                    _ => DEFAULT_EXTENSIONS.iter().map(|e| e.to_string()).collect(),
+                };
+                let mut watch = Watch {
+                    id: format!(
+                        "watch_{}",
+                        Uuid::new_v4().to_string().split('-').next().unwrap_or("x")
+                    ),
+                    path: dir.display().to_string(),
+                    extensions,
+                    interval_minutes: params["interval_minutes"]
+                        .as_u64()
+                        .unwrap_or(15)
+                        .clamp(1, 24 * 60) as u32,
+                    last_scan_ms: 0,
+                    files: BTreeMap::new(),
+                    created_at: Utc::now().timestamp_millis(),
+                };
+                let workspace = ctx.workspace.clone();
+                let _guard = STORE_LOCK.lock().await;
+                let watch = tokio::task::spawn_blocking(move || -> Result<Watch> {
+                    // Baseline snapshot: existing documents are not reported as new.
+                    scan_watch(&workspace, &mut watch);
+                    let mut store = load_store(&workspace)?;
+                    store.watches.push(watch.clone());
+                    save_store(&workspace, &store)?;
+                    Ok(watch)
+                })
+                .await
+                .map_err(|e| Error::Tool(format!("Watch task failed: {}", e)))??;
+                Ok(json!({
+                    "status": "watching",
+                    "id": watch.id,
+                    "path": watch.path,
+                    "files": watch.files.len(),
+                    "interval_minutes": watch.interval_minutes,
+                }))
+            }
+            "unwatch" => {
+                let id = str_param("id");
+                let _guard = STORE_LOCK.lock().await;
+                let mut store = load_store(&ctx.workspace)?;
+                let before = store.watches.len();
+                store.watches.retain(|w| w.id != id);
+                if store.watches.len() == before {
+                    return Err(Error::NotFound(format!("Watch not found: {}", id)));
+                }
+                save_store(&ctx.workspace, &store)?;
+                let _ =
+                    std::fs::remove_dir_all(store_dir(&ctx.workspace).join("snapshots").join(&id));
+                Ok(json!({"status": "removed", "id": id}))
+            }
+            "watches" => {
+                let store = load_store(&ctx.workspace)?;
+                let watches: Vec<Value> = store
+                    .watches
+                    .iter()
+                    .map(|w| {
+                        json!({
+                            "id": w.id,
+                            "path": w.path,
+                            "extensions": w.extensions,
+                            "interval_minutes": w.interval_minutes,
+                            "files": w.files.len(),
+                            "last_scan_ms": w.last_scan_ms,
+                        })
+                    })
+                    .collect();
+                Ok(json!({"watches": watches, "count": watches.len()}))
+            }
+            "scan" => {
+                let id = str_param("id");
+                let only = (!id.is_empty()).then_some(id.as_str());
+                let results = scan(&ctx.config, &ctx.workspace, only, false).await?;
+                if only.is_some() && results.is_empty() {
+                    return Err(Error::NotFound(format!("Watch not found: {}", id)));
+                }
+                let reports: Vec<Value> = results
+                    .iter()
+                    .map(|(w, changes)| json!({"id": w.id, "path": w.path, "changes": changes}))
+                    .collect();
+                Ok(json!({"watches": reports}))
+            }
+            _ => Err(Error::Tool(format!("Unknown action: {}", action))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_unified_diff_hunks() {
+        let old = lines("a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm");
+        let new = lines("a\nb\nc\nD\ne\nf\ng\nh\ni\nj\nk\nl\nm\nn");
+        let diff = unified_diff(&old, &new, "a/x.md", "b/x.md");
+        assert_eq!((diff.added, diff.removed), (2, 1));
+        assert_eq!(
+            diff.unified,
+            "--- a/x.md\n+++ b/x.md\n\
+             @@ -1,7 +1,7 @@\n a\n b\n c\n-d\n+D\n e\n f\n g\n\
+             @@ -11,3 +11,4 @@\n k\n l\n m\n+n\n"
+        );
+        assert!(unified_diff(&old, &old, "a", "b").is_empty());
+    }
+
+    #[test]
+    fn test_diff_from_empty_and_large_fallback() {
+        let diff = unified_diff(&[], &lines("x\ny"), "a", "b");
+        assert_eq!(diff.unified, "--- a\n+++ b\n@@ -0,0 +1,2 @@\n+x\n+y\n");
+        let big_old: Vec<String> = (0..3000).map(|i| format!("old {}", i)).collect();
+        let big_new: Vec<String> = (0..3000).map(|i| format!("new {}", i)).collect();
+        let diff = unified_diff(&big_old, &big_new, "a", "b");
+        assert_eq!((diff.added, diff.removed), (3000, 3000));
+    }
+
+    #[test]
+    fn test_scan_watch_detects_changes() {
+        let root = std::env::temp_dir().join(format!("blockcell_doc_diff_{}", Uuid::new_v4()));
+        let docs = root.join("docs");
+        std::fs::create_dir_all(&docs).unwrap();
+        std::fs::write(
+            docs.join("policy.md"),
+            "# Policy\nRefunds within 30 days.\n",
+        )
+        .unwrap();
+        std::fs::write(docs.join("image.png"), "not tracked").unwrap();
+        let mut watch = Watch {
+            id: "watch_t".to_string(),
+            path: docs.display().to_string(),
+            extensions: vec!["md".to_string()],
+            interval_minutes: 15,
+            last_scan_ms: 0,
+            files: BTreeMap::new(),
+            created_at: 0,
+        };
+        assert!(scan_watch(&root, &mut watch).is_empty());
+        assert_eq!(watch.files.len(), 1);
+
+        std::fs::write(
+            docs.join("policy.md"),
+            "# Policy\nRefunds within 14 days.\n",
+        )
+        .unwrap();
+        // Force a state change even on filesystems with coarse mtimes.
+        watch.files.get_mut("policy.md").unwrap().len = 0;
+        std::fs::write(docs.join("terms.md"), "New terms\n").unwrap();
+        let changes = scan_watch(&root, &mut watch);
+        let _ = std::fs::remove_dir_all(&root);
+
+        let modified = changes.iter().find(|c| c.file == "policy.md").unwrap();
+        assert_eq!(modified.kind, "modified");
+        let diff = modified.diff.as_ref().unwrap();
+        assert!(diff.unified.contains("-Refunds within 30 days."));
+        assert!(diff.unified.contains("+Refunds within 14 days."));
+        assert!(changes
+            .iter()
+            .any(|c| c.file == "terms.md" && c.kind == "added"));
+    }
+}
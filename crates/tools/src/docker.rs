@@ -0,0 +1,757 @@
+use async_trait::async_trait;
+use blockcell_core::config::DockerConfig;
+use blockcell_core::{Config, Error, Result};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::{debug, info};
+
+use crate::{Tool, ToolContext, ToolSchema};
+
+const DEFAULT_LOG_TAIL: u64 = 100;
+const LIFECYCLE_ACTIONS: &[&str] = &["start", "stop", "restart"];
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Engine API over the unix socket
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Candidate sockets, in order: config, `DOCKER_HOST`, Docker default, rootless Podman.
+fn socket_candidates(cfg: &DockerConfig) -> Vec<PathBuf> {
+    let strip = |s: &str| PathBuf::from(s.trim().strip_prefix("unix://").unwrap_or(s.trim()));
+    if !cfg.socket.trim().is_empty() {
+        return vec![strip(&cfg.socket)];
+    }
+    let mut out = Vec::new();
+    if let Ok(host) = std::env::var("DOCKER_HOST") {
+        if host.starts_with("unix://") {
+            out.push(strip(&host));
+        }
+    }
+    out.push(PathBuf::from("/var/run/docker.sock"));
+    if let Ok(runtime) = std::env::var("XDG_RUNTIME_DIR") {
+        out.push(PathBuf::from(runtime).join("podman/podman.sock"));
+    }
+    out.push(PathBuf::from("/run/podman/podman.sock"));
+    out
+}
+
+/// Minimal HTTP/1.1 client for the Docker Engine (and Podman compat) API.
+pub(crate) struct DockerClient {
+    socket: PathBuf,
+}
+
+pub(crate) struct ApiResponse {
+    pub status: u16,
+    pub body: Vec<u8>,
+}
+
+impl ApiResponse {
+    pub fn json(&self) -> Result<Value> {
+        if self.body.is_empty() {
+            return Ok(Value::Null);
+        }
+        serde_json::from_slice(&self.body)
+            .map_err(|e| Error::Tool(format!("Invalid engine response: {}", e)))
+    }
+}
+
+impl DockerClient {
+    pub fn from_config(cfg: &DockerConfig) -> Result<Self> {
+        let candidates = socket_candidates(cfg);
+        candidates
+            .iter()
+            .find(|p| p.exists())
+            .map(|p| Self { socket: p.clone() })
+            .ok_or_else(|| {
+                Error::Tool(format!(
+                    "No Docker/Podman socket found (tried {}). Set tools.docker.socket.",
+                    candidates
+                        .iter()
+                        .map(|p| p.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+            })
+    }
+
+    #[cfg(unix)]
+    pub async fn request(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<&Value>,
+        timeout: Duration,
+    ) -> Result<ApiResponse> {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let payload = body.map(|b| b.to_string()).unwrap_or_default();
+        let mut req = format!(
+            "{} {} HTTP/1.1\r\nHost: docker\r\nUser-Agent: blockcell\r\nConnection: close\r\nContent-Length: {}\r\n",
+            method,
+            path,
+            payload.len()
+        );
+        if body.is_some() {
+            req.push_str("Content-Type: application/json\r\n");
+        }
+        req.push_str("\r\n");
+        req.push_str(&payload);
+        debug!(method, path, "docker api");
+
+        let io = async {
+            let mut stream = tokio::net::UnixStream::connect(&self.socket).await?;
+            stream.write_all(req.as_bytes()).await?;
+            let mut raw = Vec::new();
+            stream.read_to_end(&mut raw).await?;
+            Ok::<_, std::io::Error>(raw)
+        };
+        let raw = tokio::time::timeout(timeout, io)
+            .await
+            .map_err(|_| Error::Timeout(format!("Docker API {} {} timed out", method, path)))?
+            .map_err(|e| Error::Tool(format!("Cannot talk to {}: {}", self.socket.display(), e)))?;
+        parse_http_response(&raw)
+    }
+
+    #[cfg(not(unix))]
+    pub async fn request(
+        &self,
+        _method: &str,
+        _path: &str,
+        _body: Option<&Value>,
+        _timeout: Duration,
+    ) -> Result<ApiResponse> {
+        Err(Error::Tool(
+            "The docker tool needs a unix socket; named pipes are not supported".to_string(),
+        ))
+    }
+
+    /// Request and require a 2xx status, mapping engine errors to their `message`.
+    pub async fn call(&self, method: &str, path: &str, timeout: Duration) -> Result<ApiResponse> {
+        let resp = self.request(method, path, None, timeout).await?;
+        match resp.status {
+            200..=299 => Ok(resp),
+            // 304: container already started/stopped.
+            304 => Ok(resp),
+            status => {
+                let message = resp
+                    .json()
+                    .ok()
+                    .and_then(|v| v.get("message").and_then(|m| m.as_str()).map(String::from))
+                    .unwrap_or_else(|| String::from_utf8_lossy(&resp.body).trim().to_string());
+                if status == 404 {
+                    Err(Error::NotFound(message))
+                } else {
+                    Err(Error::Tool(format!(
+                        "Docker API error {}: {}",
+                        status, message
+                    )))
+                }
+            }
+        }
+    }
+}
+
+fn parse_http_response(raw: &[u8]) -> Result<ApiResponse> {
+    let split = raw
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(|| Error::Tool("Malformed response from engine socket".to_string()))?;
+    let head = String::from_utf8_lossy(&raw[..split]);
+    let mut lines = head.lines();
+    let status = lines
+        .next()
+        .and_then(|l| l.split_whitespace().nth(1))
+        .and_then(|c| c.parse::<u16>().ok())
+        .ok_or_else(|| Error::Tool("Malformed status line from engine socket".to_string()))?;
+    let chunked = lines.any(|l| {
+        let l = l.to_ascii_lowercase();
+        l.starts_with("transfer-encoding:") && l.contains("chunked")
+    });
+    let body = &raw[split + 4..];
+    let body = if chunked {
+        decode_chunked(body)
+    } else {
+        body.to_vec()
+    };
+    Ok(ApiResponse { status, body })
+}
+
+fn decode_chunked(mut data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    while let Some(line_end) = data.windows(2).position(|w| w == b"\r\n") {
+        let size_str = String::from_utf8_lossy(&data[..line_end]);
+        let size_hex = size_str.split(';').next().unwrap_or("").trim();
+        let Ok(size) = usize::from_str_radix(size_hex, 16) else {
+            break;
+        };
+        data = &data[line_end + 2..];
+        if size == 0 || data.len() < size {
+            out.extend_from_slice(&data[..size.min(data.len())]);
+            break;
+        }
+        out.extend_from_slice(&data[..size]);
+        data = data.get(size + 2..).unwrap_or(&[]);
+    }
+    out
+}
+
+/// Split the multiplexed log stream (8-byte frame headers) used for non-TTY containers.
+/// TTY containers send raw bytes, which are returned unchanged.
+fn demux_logs(data: &[u8]) -> String {
+    let framed = data.len() >= 8 && data[0] <= 2 && data[1..4] == [0, 0, 0];
+    if !framed {
+        return String::from_utf8_lossy(data).into_owned();
+    }
+    let mut out = Vec::with_capacity(data.len());
+    let mut rest = data;
+    while rest.len() >= 8 {
+        let size = u32::from_be_bytes([rest[4], rest[5], rest[6], rest[7]]) as usize;
+        let end = (8 + size).min(rest.len());
+        out.extend_from_slice(&rest[8..end]);
+        rest = &rest[end..];
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Summaries
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn container_summary(c: &Value) -> Value {
+    let name = c["Names"]
+        .as_array()
+        .and_then(|n| n.first())
+        .and_then(|n| n.as_str())
+        .unwrap_or("")
+        .trim_start_matches('/');
+    let ports: Vec<String> = c["Ports"]
+        .as_array()
+        .map(|ports| {
+            ports
+                .iter()
+                .map(|p| match p["PublicPort"].as_u64() {
+                    Some(public) => format!(
+                        "{}->{}/{}",
+                        public,
+                        p["PrivatePort"].as_u64().unwrap_or(0),
+                        p["Type"].as_str().unwrap_or("tcp")
+                    ),
+                    None => format!(
+                        "{}/{}",
+                        p["PrivatePort"].as_u64().unwrap_or(0),
+                        p["Type"].as_str().unwrap_or("tcp")
+                    ),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    json!({
+        "id": c["Id"].as_str().map(|s| &s[..s.len().min(12)]),
+        "name": name,
+        "image": c["Image"],
+        "state": c["State"],
+        "status": c["Status"],
+        "created": c["Created"],
+        "ports": ports,
+    })
+}
+
+fn image_summary(i: &Value) -> Value {
+    json!({
+        "id": i["Id"].as_str().map(|s| s.trim_start_matches("sha256:")).map(|s| &s[..s.len().min(12)]),
+        "tags": i["RepoTags"],
+        "size_mb": i["Size"].as_f64().map(|b| (b / 1_048_576.0 * 10.0).round() / 10.0),
+        "created": i["Created"],
+    })
+}
+
+/// CPU % and memory the way `docker stats` computes them.
+fn stats_summary(name: &str, s: &Value) -> Value {
+    let cpu_total = s["cpu_stats"]["cpu_usage"]["total_usage"]
+        .as_f64()
+        .unwrap_or(0.0);
+    let pre_total = s["precpu_stats"]["cpu_usage"]["total_usage"]
+        .as_f64()
+        .unwrap_or(0.0);
+    let sys = s["cpu_stats"]["system_cpu_usage"].as_f64().unwrap_or(0.0);
+    let pre_sys = s["precpu_stats"]["system_cpu_usage"]
+        .as_f64()
+        .unwrap_or(0.0);
+    let cpus = s["cpu_stats"]["online_cpus"]
+        .as_f64()
+        .or_else(|| {
+            s["cpu_stats"]["cpu_usage"]["percpu_usage"]
+                .as_array()
+                .map(|a| a.len() as f64)
+        })
+        .unwrap_or(1.0);
+    let cpu_percent = if sys > pre_sys && cpu_total > pre_total {
+        (cpu_total - pre_total) / (sys - pre_sys) * cpus * 100.0
+    } else {
+        0.0
+    };
+    let mem = &s["memory_stats"];
+    let cache = mem["stats"]["inactive_file"]
+        .as_f64()
+        .or_else(|| mem["stats"]["cache"].as_f64())
+        .unwrap_or(0.0);
+    let usage = (mem["usage"].as_f64().unwrap_or(0.0) - cache).max(0.0);
+    let limit = mem["limit"].as_f64().unwrap_or(0.0);
+    let (mut rx, mut tx) = (0.0, 0.0);
+    if let Some(nets) = s["networks"].as_object() {
+        for n in nets.values() {
+            rx += n["rx_bytes"].as_f64().unwrap_or(0.0);
+            tx += n["tx_bytes"].as_f64().unwrap_or(0.0);
+        }
+    }
+    let round1 = |v: f64| (v * 10.0).round() / 10.0;
+    json!({
+        "name": name,
+        "cpu_percent": round1(cpu_percent),
+        "memory_mb": round1(usage / 1_048_576.0),
+        "memory_limit_mb": round1(limit / 1_048_576.0),
+        "memory_percent": if limit > 0.0 { round1(usage / limit * 100.0) } else { 0.0 },
+        "net_rx_mb": round1(rx / 1_048_576.0),
+        "net_tx_mb": round1(tx / 1_048_576.0),
+        "pids": s["pids_stats"]["current"],
+    })
+}
+
+fn container_param(params: &Value) -> Result<String> {
+    let c = params
+        .get("container")
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim())
+        .unwrap_or("");
+    if c.is_empty()
+        || !c
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.'))
+    {
+        return Err(Error::Validation(
+            "'container' must be a container name or id".to_string(),
+        ));
+    }
+    Ok(c.to_string())
+}
+
+const API_TIMEOUT: Duration = Duration::from_secs(30);
+
+async fn running_names(client: &DockerClient) -> Result<Vec<String>> {
+    let list = client
+        .call("GET", "/containers/json", API_TIMEOUT)
+        .await?
+        .json()?;
+    Ok(list
+        .as_array()
+        .map(|a| {
+            a.iter()
+                .map(|c| {
+                    container_summary(c)["name"]
+                        .as_str()
+                        .unwrap_or("")
+                        .to_string()
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+async fn stats_for(client: &DockerClient, name: &str) -> Result<Value> {
+    let path = format!("/containers/{}/stats?stream=false", name);
+    let raw = client.call("GET", &path, API_TIMEOUT).await?.json()?;
+    Ok(stats_summary(name, &raw))
+}
+
+pub struct DockerTool;
+
+#[async_trait]
+impl Tool for DockerTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "docker",
+            description: "Inspect and manage containers on the local Docker or Podman engine (via its socket). \
+                Actions: 'summary' (running/stopped/unhealthy overview — use for status checks), 'list_containers', \
+                'inspect' (container details), 'list_images', 'inspect_image', 'logs' (tail-limited), \
+                'stats' (CPU/memory/network; one container or all running), 'start' / 'stop' / 'restart' (require confirmation).",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["summary", "list_containers", "inspect", "list_images", "inspect_image", "logs", "stats", "start", "stop", "restart"],
+                        "description": "Action to perform"
+                    },
+                    "container": {
+                        "type": "string",
+                        "description": "Container name or id (inspect/logs/stats/start/stop/restart)"
+                    },
+                    "image": {
+                        "type": "string",
+                        "description": "(inspect_image) Image name or id"
+                    },
+                    "all": {
+                        "type": "boolean",
+                        "description": "(list_containers) Include stopped containers. Default true"
+                    },
+                    "tail": {
+                        "type": "integer",
+                        "description": "(logs) Number of lines from the end. Default 100, capped by tools.docker.maxLogLines"
+                    },
+                    "since": {
+                        "type": "string",
+                        "description": "(logs) Only logs newer than this: Unix timestamp or relative like '10m', '2h'"
+                    },
+                    "timestamps": {
+                        "type": "boolean",
+                        "description": "(logs) Prefix lines with timestamps"
+                    },
+                    "timeout_secs": {
+                        "type": "integer",
+                        "description": "(stop/restart) Seconds to wait before killing. Default 10"
+                    }
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    fn prompt_rule(&self, _ctx: &crate::PromptContext) -> Option<String> {
+        Some("- **容器 (docker)**: 检查 Docker/Podman 状态用 `docker` 工具（action='summary' 获取总览），不要通过 exec 运行 docker 命令。排查问题时用 'logs'（限制 tail）和 'stats'。start/stop/restart 需要用户确认，只在用户明确要求时执行。".to_string())
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        let action = params.get("action").and_then(|v| v.as_str()).unwrap_or("");
+        match action {
+            "summary" | "list_containers" | "list_images" | "stats" => {
+                if params.get("container").is_some() {
+                    container_param(params)?;
+                }
+            }
+            "inspect" | "logs" | "start" | "stop" | "restart" => {
+                container_param(params)?;
+            }
+            "inspect_image" => {
+                let image = params.get("image").and_then(|v| v.as_str()).unwrap_or("");
+                let valid = image.chars().all(|c| {
+                    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '/' | ':' | '@')
+                });
+                if image.trim().is_empty() || !valid || image.contains("..") {
+                    return Err(Error::Validation(
+                        "inspect_image requires a valid 'image' name or id".to_string(),
+                    ));
+                }
+            }
+            "" => return Err(Error::Validation("Missing 'action'".to_string())),
+            _ => return Err(Error::Validation(format!("Unknown action: {}", action))),
+        }
+        Ok(())
+    }
+
+    fn confirmation_items(&self, config: &Config, params: &Value) -> Vec<String> {
+        let action = params.get("action").and_then(|v| v.as_str()).unwrap_or("");
+        if config.tools.docker.confirm_lifecycle && LIFECYCLE_ACTIONS.contains(&action) {
+            vec![format!(
+                "docker {} {}",
+                action,
+                params["container"].as_str().unwrap_or("")
+            )]
+        } else {
+            Vec::new()
+        }
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        let cfg = &ctx.config.tools.docker;
+        let client = DockerClient::from_config(cfg)?;
+        let action = params["action"].as_str().unwrap_or("");
+        match action {
+            "summary" => {
+                let list = client
+                    .call("GET", "/containers/json?all=true", API_TIMEOUT)
+                    .await?
+                    .json()?;
+                let containers: Vec<Value> = list
+                    .as_array()
+                    .map(|a| a.iter().map(container_summary).collect())
+                    .unwrap_or_default();
+                let state = |c: &Value| c["state"].as_str().unwrap_or("").to_string();
+                let running = containers.iter().filter(|c| state(c) == "running").count();
+                let unhealthy: Vec<&Value> = containers
+                    .iter()
+                    .filter(|c| c["status"].as_str().unwrap_or("").contains("unhealthy"))
+                    .collect();
+                let not_running: Vec<&Value> = containers
+                    .iter()
+                    .filter(|c| state(c) != "running")
+                    .collect();
+                let restarting = containers
+                    .iter()
+                    .filter(|c| state(c) == "restarting")
+                    .count();
+                let version = client
+                    .call("GET", "/version", API_TIMEOUT)
+                    .await
+                    .and_then(|r| r.json())
+                    .ok();
+                Ok(json!({
+                    "engine": version.as_ref().map(|v| json!({
+                        "version": v["Version"],
+                        "api_version": v["ApiVersion"],
+                        "os": v["Os"],
+                        "components": v["Components"].as_array().map(|c| c.iter().filter_map(|x| x["Name"].as_str()).collect::<Vec<_>>()),
+                    })),
+                    "total": containers.len(),
+                    "running": running,
+                    "restarting": restarting,
+                    "stopped": not_running.len() - restarting,
+                    "unhealthy": unhealthy,
+                    "not_running": not_running,
+                    "healthy": unhealthy.is_empty() && not_running.is_empty(),
+                }))
+            }
+            "list_containers" => {
+                let all = params.get("all").and_then(|v| v.as_bool()).unwrap_or(true);
+                let path = format!("/containers/json?all={}", all);
+                let list = client.call("GET", &path, API_TIMEOUT).await?.json()?;
+                let containers: Vec<Value> = list
+                    .as_array()
+                    .map(|a| a.iter().map(container_summary).collect())
+                    .unwrap_or_default();
+                Ok(json!({ "count": containers.len(), "containers": containers }))
+            }
+            "inspect" => {
+                let name = container_param(&params)?;
+                let c = client
+                    .call("GET", &format!("/containers/{}/json", name), API_TIMEOUT)
+                    .await?
+                    .json()?;
+                Ok(json!({
+                    "id": c["Id"],
+                    "name": c["Name"].as_str().map(|n| n.trim_start_matches('/')),
+                    "image": c["Config"]["Image"],
+                    "state": c["State"],
+                    "restart_count": c["RestartCount"],
+                    "restart_policy": c["HostConfig"]["RestartPolicy"],
+                    "created": c["Created"],
+                    "command": c["Config"]["Cmd"],
+                    "entrypoint": c["Config"]["Entrypoint"],
+                    "env_keys": c["Config"]["Env"].as_array().map(|env| env.iter()
+                        .filter_map(|e| e.as_str().and_then(|s| s.split('=').next()))
+                        .collect::<Vec<_>>()),
+                    "labels": c["Config"]["Labels"],
+                    "mounts": c["Mounts"].as_array().map(|m| m.iter().map(|x| json!({
+                        "source": x["Source"], "destination": x["Destination"], "rw": x["RW"],
+                    })).collect::<Vec<_>>()),
+                    "ports": c["NetworkSettings"]["Ports"],
+                    "networks": c["NetworkSettings"]["Networks"].as_object().map(|n| n.keys().cloned().collect::<Vec<_>>()),
+                }))
+            }
+            "list_images" => {
+                let list = client
+                    .call("GET", "/images/json", API_TIMEOUT)
+                    .await?
+                    .json()?;
+                let images: Vec<Value> = list
+                    .as_array()
+                    .map(|a| a.iter().map(image_summary).collect())
+                    .unwrap_or_default();
+                Ok(json!({ "count": images.len(), "images": images }))
+            }
+            "inspect_image" => {
+                let image = params["image"].as_str().unwrap_or("").trim();
+                // The engine routes `/images/{name:.*}/json`, so registry paths stay unencoded.
+                let path = format!("/images/{}/json", image);
+                let i = client.call("GET", &path, API_TIMEOUT).await?.json()?;
+                Ok(json!({
+                    "id": i["Id"],
+                    "tags": i["RepoTags"],
+                    "digests": i["RepoDigests"],
+                    "created": i["Created"],
+                    "architecture": i["Architecture"],
+                    "os": i["Os"],
+                    "size_mb": i["Size"].as_f64().map(|b| (b / 1_048_576.0 * 10.0).round() / 10.0),
+                    "entrypoint": i["Config"]["Entrypoint"],
+                    "command": i["Config"]["Cmd"],
+                    "exposed_ports": i["Config"]["ExposedPorts"],
+                    "labels": i["Config"]["Labels"],
+                    "layers": i["RootFS"]["Layers"].as_array().map(|l| l.len()),
+                }))
+            }
+            "logs" => {
+                let name = container_param(&params)?;
+                let tail = params
+                    .get("tail")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(DEFAULT_LOG_TAIL)
+                    .clamp(1, cfg.max_log_lines.max(1) as u64);
+                let mut path = format!(
+                    "/containers/{}/logs?stdout=true&stderr=true&tail={}",
+                    name, tail
+                );
+                if params.get("timestamps").and_then(|v| v.as_bool()) == Some(true) {
+                    path.push_str("&timestamps=true");
+                }
+                if let Some(since) = params.get("since").and_then(|v| v.as_str()) {
+                    let since = parse_since(since)
+                        .ok_or_else(|| Error::Validation(format!("Invalid 'since': {}", since)))?;
+                    path.push_str(&format!("&since={}", since));
+                }
+                let resp = client.call("GET", &path, API_TIMEOUT).await?;
+                let text = demux_logs(&resp.body);
+                let lines: Vec<&str> = text.lines().collect();
+                Ok(json!({
+                    "container": name,
+                    "tail": tail,
+                    "line_count": lines.len(),
+                    "logs": text,
+                }))
+            }
+            "stats" => {
+                let names = match params.get("container") {
+                    Some(_) => vec![container_param(&params)?],
+                    None => running_names(&client).await?,
+                };
+                let results =
+                    futures::future::join_all(names.iter().map(|n| stats_for(&client, n))).await;
+                let mut stats = Vec::new();
+                let mut errors = Vec::new();
+                for (name, r) in names.iter().zip(results) {
+                    match r {
+                        Ok(s) => stats.push(s),
+                        Err(e) => errors.push(json!({"name": name, "error": e.to_string()})),
+                    }
+                }
+                Ok(json!({ "count": stats.len(), "stats": stats, "errors": errors }))
+            }
+            "start" | "stop" | "restart" => {
+                let name = container_param(&params)?;
+                let wait = params
+                    .get("timeout_secs")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(10)
+                    .min(300);
+                let path = match action {
+                    "start" => format!("/containers/{}/start", name),
+                    _ => format!("/containers/{}/{}?t={}", name, action, wait),
+                };
+                info!(container = %name, action, "docker lifecycle");
+                let resp = client
+                    .call("POST", &path, Duration::from_secs(wait + 30))
+                    .await?;
+                let state = client
+                    .call("GET", &format!("/containers/{}/json", name), API_TIMEOUT)
+                    .await
+                    .and_then(|r| r.json())
+                    .map(|c| c["State"]["Status"].clone())
+                    .unwrap_or(Value::Null);
+                Ok(json!({
+                    "container": name,
+                    "action": action,
+                    "changed": resp.status != 304,
+                    "state": state,
+                }))
+            }
+            other => Err(Error::Validation(format!("Unknown action: {}", other))),
+        }
+    }
+}
+
+/// Unix timestamp, or `30s` / `10m` / `2h` / `1d` relative to now.
+fn parse_since(s: &str) -> Option<i64> {
+    let s = s.trim();
+    if let Ok(ts) = s.parse::<i64>() {
+        return Some(ts);
+    }
+    let unit = s.chars().last()?;
+    let n: i64 = s[..s.len() - unit.len_utf8()].trim().parse().ok()?;
+    let secs = match unit {
+        's' => n,
+        'm' => n * 60,
+        'h' => n * 3600,
+        'd' => n * 86400,
+        _ => return None,
+    };
+    Some(chrono::Utc::now().timestamp() - secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_http_response_chunked() {
+        let raw = b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nTransfer-Encoding: chunked\r\n\r\n7\r\n[{\"a\":1\r\n2\r\n}]\r\n0\r\n\r\n";
+        let resp = parse_http_response(raw).unwrap();
+        assert_eq!(resp.status, 200);
+        assert_eq!(resp.json().unwrap(), json!([{"a": 1}]));
+
+        let raw =
+            b"HTTP/1.1 404 Not Found\r\nContent-Length: 27\r\n\r\n{\"message\":\"No such thing\"}";
+        let resp = parse_http_response(raw).unwrap();
+        assert_eq!(resp.status, 404);
+        assert_eq!(resp.json().unwrap()["message"], "No such thing");
+    }
+
+    #[test]
+    fn test_demux_logs() {
+        let mut framed = vec![1, 0, 0, 0, 0, 0, 0, 6];
+        framed.extend_from_slice(b"hello\n");
+        framed.extend_from_slice(&[2, 0, 0, 0, 0, 0, 0, 5]);
+        framed.extend_from_slice(b"oops\n");
+        assert_eq!(demux_logs(&framed), "hello\noops\n");
+        assert_eq!(demux_logs(b"plain tty output\n"), "plain tty output\n");
+    }
+
+    #[test]
+    fn test_summaries() {
+        let c = json!({
+            "Id": "3f4e8c2a1b0d9e8f7a6b5c4d",
+            "Names": ["/web"],
+            "Image": "nginx:1.25",
+            "State": "running",
+            "Status": "Up 3 hours (unhealthy)",
+            "Created": 1700000000,
+            "Ports": [{"PrivatePort": 80, "PublicPort": 8080, "Type": "tcp"}, {"PrivatePort": 443, "Type": "tcp"}],
+        });
+        let s = container_summary(&c);
+        assert_eq!(s["id"], "3f4e8c2a1b0d");
+        assert_eq!(s["name"], "web");
+        assert_eq!(s["ports"], json!(["8080->80/tcp", "443/tcp"]));
+
+        let stats = json!({
+            "cpu_stats": {"cpu_usage": {"total_usage": 2_000_000_000u64}, "system_cpu_usage": 20_000_000_000u64, "online_cpus": 4},
+            "precpu_stats": {"cpu_usage": {"total_usage": 1_000_000_000u64}, "system_cpu_usage": 10_000_000_000u64},
+            "memory_stats": {"usage": 104_857_600u64, "limit": 1_048_576_000u64, "stats": {"inactive_file": 52_428_800u64}},
+            "networks": {"eth0": {"rx_bytes": 1_048_576u64, "tx_bytes": 2_097_152u64}},
+            "pids_stats": {"current": 7},
+        });
+        let s = stats_summary("web", &stats);
+        assert_eq!(s["cpu_percent"], 40.0);
+        assert_eq!(s["memory_mb"], 50.0);
+        assert_eq!(s["memory_percent"], 5.0);
+        assert_eq!(s["net_tx_mb"], 2.0);
+    }
+
+    #[test]
+    fn test_confirmation_and_validation() {
+        let tool = DockerTool;
+        let mut config = Config::default();
+        let restart = json!({"action": "restart", "container": "web"});
+        assert_eq!(
+            tool.confirmation_items(&config, &restart),
+            vec!["docker restart web"]
+        );
+        assert!(tool
+            .confirmation_items(&config, &json!({"action": "logs", "container": "web"}))
+            .is_empty());
+        config.tools.docker.confirm_lifecycle = false;
+        assert!(tool.confirmation_items(&config, &restart).is_empty());
+
+        assert!(tool
+            .validate(&json!({"action": "logs", "container": "web/../../x"}))
+            .is_err());
+        assert!(tool.validate(&json!({"action": "stop"})).is_err());
+        assert!(tool.validate(&json!({"action": "summary"})).is_ok());
+    }
+}
@@ -0,0 +1,647 @@
+use async_trait::async_trait;
+use blockcell_core::{Config, Error, Result};
+use blockcell_storage::documents::{db_path, ChunkHit, DocumentStore, NewChunk, NewDocument};
+use blockcell_storage::vector::Embedder;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::{PromptContext, Tool, ToolContext, ToolSchema};
+
+/// Target chunk size in characters; chunks never split a paragraph unless it alone is larger.
+const CHUNK_CHARS: usize = 1500;
+/// Characters carried over from the end of one chunk into the next.
+const CHUNK_OVERLAP: usize = 200;
+/// Upper bound on text taken from one source (about a 300-page book).
+const MAX_SOURCE_CHARS: usize = 1_000_000;
+/// Files ingested from one directory.
+const MAX_DIR_FILES: usize = 500;
+const SUPPORTED_EXTENSIONS: &[&str] = &[
+    "pdf", "md", "markdown", "txt", "html", "htm", "docx", "pptx", "xlsx", "xls",
+];
+
+fn resolve_path(path: &str, workspace: &Path) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir().unwrap_or_default().join(rest),
+        None if Path::new(path).is_absolute() => PathBuf::from(path),
+        None => workspace.join(path),
+    }
+}
+
+fn is_url(source: &str) -> bool {
+    source.starts_with("http://") || source.starts_with("https://")
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+}
+
+fn is_supported(path: &Path) -> bool {
+    extension(path).is_some_and(|e| SUPPORTED_EXTENSIONS.contains(&e.as_str()))
+}
+
+fn hex_digest(value: &str) -> String {
+    Sha256::digest(value.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Text of one source as markdown-ish plain text, plus its kind.
+struct Extracted {
+    kind: String,
+    text: String,
+}
+
+fn extract_file(path: &Path) -> Result<Extracted> {
+    let ext = extension(path).unwrap_or_default();
+    let (kind, text) = match ext.as_str() {
+        "pdf" => (
+            "pdf",
+            pdf_extract::extract_text(path)
+                .map_err(|e| Error::Tool(format!("Failed to extract PDF text: {}", e)))?,
+        ),
+        "html" | "htm" => (
+            "html",
+            crate::html_to_md::html_to_markdown(&std::fs::read_to_string(path)?),
+        ),
+        "md" | "markdown" => ("markdown", std::fs::read_to_string(path)?),
+        "txt" => ("text", std::fs::read_to_string(path)?),
+        _ if crate::office::is_office_file(path) => {
+            (ext.as_str(), crate::office::read_office_file(path)?)
+        }
+        _ => {
+            return Err(Error::Validation(format!(
+                "Unsupported document type: {} (supported: {})",
+                path.display(),
+                SUPPORTED_EXTENSIONS.join(", ")
+            )))
+        }
+    };
+    Ok(Extracted {
+        kind: kind.to_string(),
+        text,
+    })
+}
+
+/// Title of a document: its first markdown heading, else the file name or URL.
+fn document_title(text: &str, source: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .find_map(|line| line.strip_prefix("# ").map(str::trim))
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| {
+            if is_url(source) {
+                source.to_string()
+            } else {
+                Path::new(source)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| source.to_string())
+            }
+        })
+}
+
+fn tail_chars(text: &str, n: usize) -> &str {
+    let count = text.chars().count();
+    if count <= n {
+        return text;
+    }
+    let start = text
+        .char_indices()
+        .nth(count - n)
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+    &text[start..]
+}
+
+/// Split text into `(heading, chunk)` pairs of about `max_chars`. Markdown headings start
+/// a new chunk and label the chunks below them; paragraphs are kept whole where possible
+/// and consecutive chunks of one section overlap by `overlap` characters.
+fn chunk_text(text: &str, max_chars: usize, overlap: usize) -> Vec<(String, String)> {
+    let mut chunks = Vec::new();
+    let mut heading = String::new();
+    let mut current = String::new();
+
+    fn flush(chunks: &mut Vec<(String, String)>, heading: &str, current: &mut String) {
+        let text = current.trim();
+        if !text.is_empty() {
+            chunks.push((heading.to_string(), text.to_string()));
+        }
+        current.clear();
+    }
+
+    // `(is_heading, text)` blocks separated by blank lines or headings.
+    let mut blocks: Vec<(bool, String)> = Vec::new();
+    let mut para = String::new();
+    for line in text.lines() {
+        let trimmed = line.trim();
+        let is_heading = trimmed.starts_with('#')
+            && trimmed.trim_start_matches('#').starts_with(' ')
+            && trimmed.len() > 2;
+        if trimmed.is_empty() || is_heading {
+            if !para.trim().is_empty() {
+                blocks.push((false, std::mem::take(&mut para)));
+            }
+            para.clear();
+            if is_heading {
+                blocks.push((true, trimmed.trim_start_matches('#').trim().to_string()));
+            }
+        } else {
+            para.push_str(line);
+            para.push('\n');
+        }
+    }
+    if !para.trim().is_empty() {
+        blocks.push((false, para));
+    }
+
+    for (is_heading, block) in blocks {
+        if is_heading {
+            flush(&mut chunks, &heading, &mut current);
+            heading = block;
+            continue;
+        }
+        let trimmed = block.trim();
+
+        let para_len = trimmed.chars().count();
+        if para_len > max_chars {
+            // A single oversized paragraph (PDF text often has no blank lines): hard-split
+            // it into overlapping windows; the last window stays open for what follows.
+            flush(&mut chunks, &heading, &mut current);
+            let chars: Vec<char> = trimmed.chars().collect();
+            let step = max_chars.saturating_sub(overlap).max(1);
+            let mut start = 0;
+            loop {
+                let end = (start + max_chars).min(chars.len());
+                current.extend(&chars[start..end]);
+                if end == chars.len() {
+                    current.push_str("\n\n");
+                    break;
+                }
+                flush(&mut chunks, &heading, &mut current);
+                start += step;
+            }
+            continue;
+        }
+
+        let current_len = current.trim().chars().count();
+        if current_len > 0 && current_len + para_len + 2 > max_chars {
+            let carry = tail_chars(current.trim(), overlap).to_string();
+            flush(&mut chunks, &heading, &mut current);
+            if carry.chars().count() + para_len + 2 <= max_chars {
+                current.push_str(&carry);
+                current.push_str("\n\n");
+            }
+        }
+        current.push_str(trimmed);
+        current.push_str("\n\n");
+    }
+    flush(&mut chunks, &heading, &mut current);
+    chunks
+}
+
+/// Files to ingest for a path: the file itself, or supported files below a directory.
+fn collect_files(path: &Path) -> Result<Vec<PathBuf>> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    if !path.is_dir() {
+        return Err(Error::NotFound(format!(
+            "File not found: {}",
+            path.display()
+        )));
+    }
+    let mut files = Vec::new();
+    let mut stack = vec![path.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let mut entries: Vec<PathBuf> = std::fs::read_dir(&dir)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| {
+                !p.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with('.'))
+            })
+            .collect();
+        entries.sort();
+        for entry in entries {
+            if entry.is_dir() {
+                stack.push(entry);
+            } else if is_supported(&entry) {
+                files.push(entry);
+                if files.len() >= MAX_DIR_FILES {
+                    return Ok(files);
+                }
+            }
+        }
+    }
+    Ok(files)
+}
+
+fn embedder(config: &Config) -> Option<Arc<dyn Embedder>> {
+    match blockcell_providers::create_embedder(config) {
+        Ok(embedder) => embedder,
+        Err(e) => {
+            warn!(error = %e, "Embeddings unavailable; documents use keyword search only");
+            None
+        }
+    }
+}
+
+/// Embed every chunk, or none: a partially embedded document would rank unevenly.
+async fn embed_chunks(
+    embedder: Option<Arc<dyn Embedder>>,
+    texts: Vec<String>,
+) -> (Option<String>, Vec<Option<Vec<f32>>>) {
+    let count = texts.len();
+    let Some(embedder) = embedder else {
+        return (None, vec![None; count]);
+    };
+    let result = tokio::task::spawn_blocking(move || {
+        texts
+            .iter()
+            .map(|t| embedder.embed_document(t))
+            .collect::<Result<Vec<_>>>()
+            .map(|vectors| (embedder.model_id().to_string(), vectors))
+    })
+    .await;
+    match result {
+        Ok(Ok((model, vectors))) => (Some(model), vectors.into_iter().map(Some).collect()),
+        Ok(Err(e)) => {
+            warn!(error = %e, "Embedding document chunks failed; storing without embeddings");
+            (None, vec![None; count])
+        }
+        Err(e) => {
+            warn!(error = %e, "Embedding task failed; storing without embeddings");
+            (None, vec![None; count])
+        }
+    }
+}
+
+async fn ingest_one(
+    store: &DocumentStore,
+    embedder: Option<Arc<dyn Embedder>>,
+    source: String,
+    extracted: Extracted,
+    force: bool,
+) -> Result<Value> {
+    let text: String = extracted.text.chars().take(MAX_SOURCE_CHARS).collect();
+    if text.trim().is_empty() {
+        return Err(Error::Tool(format!(
+            "No text could be extracted from {} (scanned PDFs need OCR first)",
+            source
+        )));
+    }
+    let content_hash = hex_digest(&text);
+    let model = embedder.as_ref().map(|e| e.model_id().to_string());
+    if !force {
+        if let Some(existing) = store.find_by_source(&source)? {
+            if existing.content_hash == content_hash && existing.embedding_model == model {
+                return Ok(json!({"status": "unchanged", "document": existing}));
+            }
+        }
+    }
+
+    let chunks = chunk_text(&text, CHUNK_CHARS, CHUNK_OVERLAP);
+    let texts = chunks
+        .iter()
+        .map(|(heading, text)| {
+            if heading.is_empty() {
+                text.clone()
+            } else {
+                format!("{}\n\n{}", heading, text)
+            }
+        })
+        .collect();
+    let (embedding_model, embeddings) = embed_chunks(embedder, texts).await;
+
+    let doc = NewDocument {
+        title: document_title(&text, &source),
+        source,
+        kind: extracted.kind,
+        content_hash,
+        chars: text.chars().count(),
+        embedding_model,
+        chunks: chunks
+            .into_iter()
+            .zip(embeddings)
+            .map(|((heading, text), embedding)| NewChunk {
+                heading,
+                text,
+                embedding,
+            })
+            .collect(),
+    };
+    let info = store.upsert(doc)?;
+    info!(
+        source = %info.source,
+        chunks = info.chunk_count,
+        embedded = info.embedding_model.is_some(),
+        "Document ingested"
+    );
+    Ok(json!({"status": "ingested", "document": info}))
+}
+
+/// Ingest a file, a directory of documents or a URL into `<workspace>/documents.db`.
+/// Sources whose text and embedding model are unchanged are skipped unless `force`.
+pub async fn ingest(config: &Config, workspace: &Path, source: &str, force: bool) -> Result<Value> {
+    let source = source.trim();
+    let store = DocumentStore::open(&db_path(workspace))?;
+    let embedder = embedder(config);
+
+    if is_url(source) {
+        let egress = crate::egress::HttpClientFactory::for_tool(config, "doc_ingest");
+        let (markdown, meta) =
+            crate::html_to_md::fetch_as_markdown(&egress, source, MAX_SOURCE_CHARS).await?;
+        let extracted = Extracted {
+            kind: "web".to_string(),
+            text: markdown,
+        };
+        let source = if meta.final_url.is_empty() {
+            source.to_string()
+        } else {
+            meta.final_url
+        };
+        let result = ingest_one(&store, embedder, source, extracted, force).await?;
+        return Ok(json!({"results": [result]}));
+    }
+
+    let root = resolve_path(source, workspace);
+    let files = {
+        let root = root.clone();
+        tokio::task::spawn_blocking(move || collect_files(&root))
+            .await
+            .map_err(|e| Error::Tool(format!("Directory scan failed: {}", e)))??
+    };
+    if files.is_empty() {
+        return Err(Error::NotFound(format!(
+            "No supported documents under {} (supported: {})",
+            root.display(),
+            SUPPORTED_EXTENSIONS.join(", ")
+        )));
+    }
+
+    let mut results = Vec::new();
+    let mut failed = Vec::new();
+    for file in files {
+        let path = file.clone();
+        let extracted = tokio::task::spawn_blocking(move || extract_file(&path))
+            .await
+            .map_err(|e| Error::Tool(format!("Extraction task failed: {}", e)))?;
+        let source = file
+            .canonicalize()
+            .unwrap_or_else(|_| file.clone())
+            .display()
+            .to_string();
+        let outcome = match extracted {
+            Ok(extracted) => {
+                ingest_one(&store, embedder.clone(), source.clone(), extracted, force).await
+            }
+            Err(e) => Err(e),
+        };
+        match outcome {
+            Ok(result) => results.push(result),
+            Err(e) => failed.push(json!({"source": source, "error": e.to_string()})),
+        }
+    }
+    if results.is_empty() && failed.len() == 1 {
+        let error = failed[0]["error"].as_str().unwrap_or_default();
+        return Err(Error::Tool(error.to_string()));
+    }
+    Ok(json!({"results": results, "failed": failed}))
+}
+
+/// Best matching chunks for `query`, semantic + keyword when embeddings are configured.
+pub async fn search(
+    config: &Config,
+    workspace: &Path,
+    query: &str,
+    top_k: usize,
+    document: Option<&str>,
+) -> Result<Vec<ChunkHit>> {
+    let store = DocumentStore::open(&db_path(workspace))?;
+    let query_embedding = match embedder(config) {
+        Some(embedder) => {
+            let q = query.to_string();
+            match tokio::task::spawn_blocking(move || embedder.embed_query(&q)).await {
+                Ok(Ok(vector)) => Some(vector),
+                Ok(Err(e)) => {
+                    warn!(error = %e, "Query embedding failed; using keyword search");
+                    None
+                }
+                Err(_) => None,
+            }
+        }
+        None => None,
+    };
+    store.search(query, query_embedding.as_deref(), top_k, document)
+}
+
+pub struct DocIngestTool;
+
+#[async_trait]
+impl Tool for DocIngestTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "doc_ingest",
+            description: "Add documents to the local document library that `doc_search` retrieves from. \
+                Actions: 'ingest' (source: a PDF, Markdown, HTML, text or Office file, a directory of them, or an http(s) URL; \
+                the text is chunked and embedded; unchanged sources are skipped unless force=true), \
+                'list' (ingested documents), 'remove' (document id or source).",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["ingest", "list", "remove"],
+                        "description": "Action to perform. Default: ingest"
+                    },
+                    "source": {"type": "string", "description": "(ingest) File or directory path (workspace-relative or absolute) or URL"},
+                    "force": {"type": "boolean", "description": "(ingest) Re-ingest even if the source is unchanged"},
+                    "document": {"type": "string", "description": "(remove) Document id or source"}
+                }
+            }),
+        }
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        let action = params
+            .get("action")
+            .and_then(|v| v.as_str())
+            .unwrap_or("ingest");
+        let has = |key: &str| {
+            params
+                .get(key)
+                .and_then(|v| v.as_str())
+                .is_some_and(|s| !s.trim().is_empty())
+        };
+        match action {
+            "ingest" => {
+                if !has("source") {
+                    return Err(Error::Validation("'source' is required for ingest".into()));
+                }
+            }
+            "remove" => {
+                if !has("document") {
+                    return Err(Error::Validation(
+                        "'document' is required for remove".into(),
+                    ));
+                }
+            }
+            "list" => {}
+            _ => return Err(Error::Validation(format!("Unknown action: {}", action))),
+        }
+        Ok(())
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        let action = params
+            .get("action")
+            .and_then(|v| v.as_str())
+            .unwrap_or("ingest");
+        match action {
+            "ingest" => {
+                let source = params["source"].as_str().unwrap_or("");
+                let force = params
+                    .get("force")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                ingest(&ctx.config, &ctx.workspace, source, force).await
+            }
+            "list" => {
+                let documents = DocumentStore::open(&db_path(&ctx.workspace))?.list()?;
+                Ok(json!({"documents": documents, "count": documents.len()}))
+            }
+            "remove" => {
+                let document = params["document"].as_str().unwrap_or("").trim();
+                if !DocumentStore::open(&db_path(&ctx.workspace))?.remove(document)? {
+                    return Err(Error::NotFound(format!("Document not found: {}", document)));
+                }
+                Ok(json!({"status": "removed", "document": document}))
+            }
+            _ => Err(Error::Tool(format!("Unknown action: {}", action))),
+        }
+    }
+}
+
+pub struct DocSearchTool;
+
+#[async_trait]
+impl Tool for DocSearchTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "doc_search",
+            description: "Search the document library built with `doc_ingest` (manuals, papers, contracts, notes) \
+                and return the most relevant passages with their source and section heading.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "query": {"type": "string", "description": "What to look for, in natural language"},
+                    "top_k": {"type": "integer", "description": "Passages to return (1-20). Default: 5"},
+                    "document": {"type": "string", "description": "Only search this document (id or source)"}
+                },
+                "required": ["query"]
+            }),
+        }
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        if params
+            .get("query")
+            .and_then(|v| v.as_str())
+            .is_none_or(|s| s.trim().is_empty())
+        {
+            return Err(Error::Validation("'query' is required".into()));
+        }
+        Ok(())
+    }
+
+    fn prompt_rule(&self, _ctx: &PromptContext) -> Option<String> {
+        Some(
+            "- Questions about the user's own documents (manuals, papers, contracts the user has ingested) go to `doc_search` first; cite the `source` and `heading` of the passages you use. Add new files or URLs to the library with `doc_ingest`."
+                .to_string(),
+        )
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        let query = params["query"].as_str().unwrap_or("").trim();
+        let top_k = params
+            .get("top_k")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(5)
+            .clamp(1, 20) as usize;
+        let document = params
+            .get("document")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty());
+        let hits = search(&ctx.config, &ctx.workspace, query, top_k, document).await?;
+        if hits.is_empty() {
+            return Ok(json!({
+                "results": [],
+                "hint": "No matching passages. Use doc_ingest list to see which documents are in the library."
+            }));
+        }
+        Ok(json!({"results": hits, "count": hits.len()}))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_text_follows_headings_and_size() {
+        let text = "# Guide\n\nIntro paragraph.\n\n## Setup\n\nInstall it.\n\nConfigure it.\n\n## Usage\n\nRun it.";
+        let chunks = chunk_text(text, 1500, 200);
+        assert_eq!(
+            chunks,
+            vec![
+                ("Guide".to_string(), "Intro paragraph.".to_string()),
+                (
+                    "Setup".to_string(),
+                    "Install it.\n\nConfigure it.".to_string()
+                ),
+                ("Usage".to_string(), "Run it.".to_string()),
+            ]
+        );
+
+        // One long paragraph without breaks is split with overlap.
+        let long = "word ".repeat(1000);
+        let chunks = chunk_text(&long, 1500, 200);
+        assert!(chunks.len() >= 4);
+        assert!(chunks.iter().all(|(_, c)| c.chars().count() <= 1500));
+        let first_tail = tail_chars(&chunks[0].1, 50).to_string();
+        assert!(chunks[1].1.contains(first_tail.trim()));
+    }
+
+    #[test]
+    fn test_extract_and_title() {
+        let dir =
+            std::env::temp_dir().join(format!("blockcell_documents_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let html = dir.join("page.html");
+        std::fs::write(
+            &html,
+            "<h1>Lease terms</h1><p>Deposit is due on signing.</p>",
+        )
+        .unwrap();
+        let extracted = extract_file(&html).unwrap();
+        assert_eq!(extracted.kind, "html");
+        assert!(extracted.text.contains("Deposit is due"));
+        assert_eq!(document_title(&extracted.text, "page.html"), "Lease terms");
+        assert_eq!(document_title("no heading", "/tmp/notes.txt"), "notes.txt");
+
+        std::fs::write(dir.join("image.png"), b"x").unwrap();
+        std::fs::write(dir.join("notes.md"), "hi").unwrap();
+        let files = collect_files(&dir).unwrap();
+        assert_eq!(files.len(), 2);
+        assert!(extract_file(&dir.join("image.png")).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,136 @@
+//! HTTP client factory enforcing `tools.egress`.
+//!
+//! Tools that talk to arbitrary hosts build their `reqwest` client here instead of
+//! calling `Client::builder()` directly. The factory checks the target before the
+//! request is sent and re-checks every redirect hop, so a hallucinated or injected
+//! URL cannot be used to ship data to a host the policy does not allow.
+
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use blockcell_core::egress::EgressPolicy;
+use blockcell_core::{Config, Error, Result};
+use reqwest::{redirect, ClientBuilder, Url};
+
+/// Default redirect limit, matching what the tools used before the factory existed.
+pub const MAX_REDIRECTS: usize = 10;
+
+#[derive(Clone)]
+pub struct HttpClientFactory {
+    tool: String,
+    policy: Arc<EgressPolicy>,
+}
+
+impl HttpClientFactory {
+    pub fn for_tool(config: &Config, tool: &str) -> Self {
+        Self {
+            tool: tool.to_string(),
+            policy: Arc::new(EgressPolicy::new(&config.tools.egress)),
+        }
+    }
+
+    /// Client builder whose redirects follow up to `MAX_REDIRECTS` allowed hops.
+    pub fn builder(&self) -> ClientBuilder {
+        self.builder_with_redirects(MAX_REDIRECTS)
+    }
+
+    /// Client builder with a custom redirect limit (0 disables redirects).
+    pub fn builder_with_redirects(&self, max_redirects: usize) -> ClientBuilder {
+        if !self.policy.is_enabled() {
+            let policy = if max_redirects == 0 {
+                redirect::Policy::none()
+            } else {
+                redirect::Policy::limited(max_redirects)
+            };
+            return reqwest::Client::builder().redirect(policy);
+        }
+        let policy = self.policy.clone();
+        let tool = self.tool.clone();
+        reqwest::Client::builder().redirect(redirect::Policy::custom(move |attempt| {
+            if max_redirects == 0 {
+                return attempt.stop();
+            }
+            if attempt.previous().len() > max_redirects {
+                return attempt.error(format!("too many redirects (> {})", max_redirects));
+            }
+            // Redirect hops are checked synchronously: host rules and IP literals only.
+            match attempt
+                .url()
+                .host_str()
+                .map(|host| policy.check_host(&tool, host, &[]))
+            {
+                Some(Ok(())) => attempt.follow(),
+                Some(Err(e)) => attempt.error(e.to_string()),
+                None => attempt.error("redirect without host"),
+            }
+        }))
+    }
+
+    /// Check `url` against the policy, resolving the host when CIDR rules apply.
+    pub async fn check(&self, url: &str) -> Result<()> {
+        if !self.policy.is_enabled() {
+            return Ok(());
+        }
+        let parsed = Url::parse(url)
+            .map_err(|e| Error::Validation(format!("Invalid URL '{}': {}", url, e)))?;
+        let host = parsed
+            .host_str()
+            .ok_or_else(|| Error::Validation(format!("URL has no host: {}", url)))?;
+        let mut resolved: Vec<IpAddr> = Vec::new();
+        if self.policy.needs_resolution(&self.tool) && host.parse::<IpAddr>().is_err() {
+            let port = parsed.port_or_known_default().unwrap_or(80);
+            match tokio::net::lookup_host((host, port)).await {
+                Ok(addrs) => resolved.extend(addrs.map(|a| a.ip())),
+                // Leave the decision to host rules; the request will fail anyway.
+                Err(e) => tracing::debug!(host, error = %e, "Egress DNS lookup failed"),
+            }
+        }
+        let result = self.policy.check_host(&self.tool, host, &resolved);
+        if let Err(ref e) = result {
+            tracing::warn!(tool = %self.tool, host, error = %e, "Egress blocked");
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blockcell_core::config::ToolEgressRule;
+
+    fn config() -> Config {
+        let mut config = Config::default();
+        config.tools.egress.enabled = true;
+        config.tools.egress.deny = vec!["127.0.0.0/8".to_string()];
+        config.tools.egress.tools.insert(
+            "http_request".to_string(),
+            ToolEgressRule {
+                default_action: Some("deny".to_string()),
+                allow: vec!["example.com".to_string()],
+                ..Default::default()
+            },
+        );
+        config
+    }
+
+    #[tokio::test]
+    async fn test_check_applies_tool_rules() {
+        let config = config();
+        let http = HttpClientFactory::for_tool(&config, "http_request");
+        assert!(http.check("https://www.example.com/x").await.is_ok());
+        assert!(http.check("https://attacker.test/upload").await.is_err());
+        assert!(http.check("not a url").await.is_err());
+
+        let web = HttpClientFactory::for_tool(&config, "web_fetch");
+        assert!(web.check("https://attacker.test/").await.is_ok());
+        assert!(web.check("http://127.0.0.1:8080/").await.is_err());
+        assert!(web.check("http://localhost:8080/").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_disabled_policy_is_transparent() {
+        let http = HttpClientFactory::for_tool(&Config::default(), "http_request");
+        assert!(http.check("http://127.0.0.1/").await.is_ok());
+        assert!(http.builder().build().is_ok());
+    }
+}
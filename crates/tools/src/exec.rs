@@ -7,6 +7,7 @@ use std::time::Duration;
 use tokio::process::Command;
 use tokio::time::timeout;
 
+use crate::sandbox::{self, SandboxSpec, SandboxedCommand};
 use crate::{Tool, ToolContext, ToolSchema};
 
 pub struct ExecTool;
@@ -91,15 +92,30 @@ impl Tool for ExecTool {
             })
             .unwrap_or_else(|| ctx.workspace.clone());
 
-        let timeout_secs = ctx.config.tools.exec.timeout as u64;
+        let exec_config = &ctx.config.tools.exec;
+        let timeout_secs = exec_config.timeout as u64;
         let max_output_chars = 10000;
 
-        let mut cmd = Command::new("sh");
-        cmd.arg("-c")
-            .arg(command)
-            .current_dir(&working_dir)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
+        let privilege = sandbox::exec_privilege(&ctx);
+        let backend = exec_config.sandbox.backend_for(&privilege);
+        let SandboxedCommand {
+            command: mut cmd,
+            container,
+        } = sandbox::build(
+            &SandboxSpec {
+                backend,
+                config: &exec_config.sandbox,
+                privilege,
+                workspace: &ctx.workspace,
+                working_dir: &working_dir,
+                restrict_to_workspace: exec_config.restrict_to_workspace,
+                timeout_secs,
+            },
+            command,
+        )?;
+        cmd.stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
 
         let result = timeout(Duration::from_secs(timeout_secs), cmd.output()).await;
 
@@ -129,14 +145,23 @@ impl Tool for ExecTool {
                     "exit_code": output.status.code(),
                     "stdout": stdout,
                     "stderr": stderr,
-                    "truncated": truncated
+                    "truncated": truncated,
+                    "sandbox": backend.as_str()
                 }))
             }
             Ok(Err(e)) => Err(Error::Tool(format!("Failed to execute command: {}", e))),
-            Err(_) => Err(Error::Timeout(format!(
-                "Command timed out after {} seconds",
-                timeout_secs
-            ))),
+            Err(_) => {
+                if let Some(name) = container {
+                    let _ = Command::new("docker")
+                        .args(["rm", "-f", name.as_str()])
+                        .output()
+                        .await;
+                }
+                Err(Error::Timeout(format!(
+                    "Command timed out after {} seconds",
+                    timeout_secs
+                )))
+            }
         }
     }
 }
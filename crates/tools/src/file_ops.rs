@@ -1,5 +1,6 @@
 use async_trait::async_trait;
-use blockcell_core::{Error, Result};
+use blockcell_core::file_share::{NewShare, ShareStore};
+use blockcell_core::{Error, Paths, Result};
 use serde_json::{json, Value};
 use std::path::{Path, PathBuf};
 
@@ -24,13 +25,13 @@ impl Tool for FileOpsTool {
     fn schema(&self) -> ToolSchema {
         ToolSchema {
             name: "file_ops",
-            description: "Multi-action file utility. You MUST provide `action`. action='delete': requires `path`, optional `recursive` for directories. action='rename'|'move'|'copy': requires `path` and `destination`. action='compress': requires `destination` and either `path` or `paths`, optional `format`. action='decompress': requires `path`, optional `destination`. action='read_pdf': requires `path`. action='file_info': requires `path`.",
+            description: "Multi-action file utility. You MUST provide `action`. action='delete': requires `path`, optional `recursive` for directories. action='rename'|'move'|'copy': requires `path` and `destination`. action='compress': requires `destination` and either `path` or `paths`, optional `format`. action='decompress': requires `path`, optional `destination`. action='read_pdf': requires `path`. action='file_info': requires `path`. action='share': requires `path` (a workspace file), optional `expires_in_secs`, `password`, `max_downloads`; returns a download link to hand to someone else.",
             parameters: json!({
                 "type": "object",
                 "properties": {
                     "action": {
                         "type": "string",
-                        "enum": ["delete", "rename", "move", "copy", "compress", "decompress", "read_pdf", "file_info"],
+                        "enum": ["delete", "rename", "move", "copy", "compress", "decompress", "read_pdf", "file_info", "share"],
                         "description": "Action to perform"
                     },
                     "path": {
@@ -54,6 +55,18 @@ impl Tool for FileOpsTool {
                     "recursive": {
                         "type": "boolean",
                         "description": "(delete) If true, delete directories recursively. Default false."
+                    },
+                    "expires_in_secs": {
+                        "type": "integer",
+                        "description": "(share) Link lifetime in seconds. Default 86400, capped by gateway.shareMaxTtlSecs"
+                    },
+                    "password": {
+                        "type": "string",
+                        "description": "(share) Optional password the recipient must enter"
+                    },
+                    "max_downloads": {
+                        "type": "integer",
+                        "description": "(share) Optional download limit"
                     }
                 },
                 "required": ["action"]
@@ -68,7 +81,7 @@ impl Tool for FileOpsTool {
             .ok_or_else(|| Error::Validation("Missing required parameter: action".to_string()))?;
 
         match action {
-            "delete" | "read_pdf" | "file_info" => {
+            "delete" | "read_pdf" | "file_info" | "share" => {
                 if params.get("path").and_then(|v| v.as_str()).is_none() {
                     return Err(Error::Validation(
                         "Missing required parameter: path".to_string(),
@@ -149,6 +162,7 @@ impl Tool for FileOpsTool {
                     .map_err(|e| Error::Tool(format!("PDF read task failed: {}", e)))?
             }
             "file_info" => action_file_info(&workspace, &params).await,
+            "share" => action_share(&ctx, &params),
             _ => Err(Error::Tool(format!("Unknown action: {}", action))),
         }
     }
@@ -574,6 +588,51 @@ fn action_read_pdf(workspace: &Path, params: &Value) -> Result<Value> {
     }))
 }
 
+fn action_share(ctx: &ToolContext, params: &Value) -> Result<Value> {
+    let path = expand_path(params["path"].as_str().unwrap(), &ctx.workspace);
+    let canonical = path
+        .canonicalize()
+        .map_err(|_| Error::NotFound(format!("Path not found: {}", path.display())))?;
+    let ws_canonical = ctx
+        .workspace
+        .canonicalize()
+        .unwrap_or_else(|_| ctx.workspace.clone());
+    let rel = canonical
+        .strip_prefix(&ws_canonical)
+        .map_err(|_| {
+            Error::PermissionDenied("Only files inside the workspace can be shared".to_string())
+        })?
+        .to_string_lossy()
+        .replace('\\', "/");
+    if !canonical.is_file() {
+        return Err(Error::Validation(format!(
+            "Not a file: {}",
+            canonical.display()
+        )));
+    }
+
+    let paths = match ctx.workspace.parent() {
+        Some(base) => Paths::with_base(base.to_path_buf()),
+        None => Paths::new(),
+    };
+    let mut store = ShareStore::open(&paths)?;
+    let link = store.create(
+        &rel,
+        NewShare {
+            ttl_secs: params.get("expires_in_secs").and_then(|v| v.as_u64()),
+            password: params.get("password").and_then(|v| v.as_str()),
+            max_downloads: params
+                .get("max_downloads")
+                .and_then(|v| v.as_u64())
+                .map(|n| n.min(u32::MAX as u64) as u32),
+            note: None,
+        },
+        ctx.config.gateway.share_max_ttl_secs,
+    )?;
+    store.save()?;
+    Ok(link.to_json(&ctx.config.gateway))
+}
+
 async fn action_file_info(workspace: &Path, params: &Value) -> Result<Value> {
     let path = expand_path(params["path"].as_str().unwrap(), workspace);
 
@@ -638,6 +697,15 @@ mod tests {
         assert!(tool.validate(&json!({"action": "delete"})).is_err());
     }
 
+    #[test]
+    fn test_validate_share() {
+        let tool = FileOpsTool;
+        assert!(tool
+            .validate(&json!({"action": "share", "path": "reports/q3.pdf", "password": "x"}))
+            .is_ok());
+        assert!(tool.validate(&json!({"action": "share"})).is_err());
+    }
+
     #[test]
     fn test_validate_compress() {
         let tool = FileOpsTool;
@@ -0,0 +1,236 @@
+//! Agent-initiated follow-ups ("ask how the deployment went tomorrow 10am").
+//!
+//! A follow-up is a one-shot cron job of kind `follow_up` that remembers the chat it was
+//! scheduled from. When it fires, the scheduler sends the question back into that chat's
+//! session, so the agent writes the follow-up with the conversation history and related
+//! memory in context. Every follow-up needs the user's confirmation before it is created.
+
+use async_trait::async_trait;
+use blockcell_core::{Config, Error, Paths, Result};
+use chrono::Utc;
+use serde_json::{json, Value};
+
+use crate::cron::execute_cron_action_with_paths;
+use crate::todo::{format_time, parse_local_time};
+use crate::{PromptContext, Tool, ToolContext, ToolSchema};
+
+/// Channels without a user to follow up with.
+const NON_INTERACTIVE_CHANNELS: &[&str] = &["cron", "ghost", "subagent", "system", ""];
+
+fn str_param<'a>(params: &'a Value, key: &str) -> Option<&'a str> {
+    params[key]
+        .as_str()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+/// When the follow-up fires, from `at` (local time) or `delay_minutes`.
+fn due_at_ms(params: &Value, tz: Option<&str>) -> Result<i64> {
+    let now = Utc::now().timestamp_millis();
+    let at = match (params["delay_minutes"].as_i64(), str_param(params, "at")) {
+        (Some(minutes), _) => now + minutes * 60_000,
+        (None, Some(at)) => parse_local_time(at, tz)?,
+        (None, None) => {
+            return Err(Error::Validation(
+                "Specify when to follow up: `at` or `delay_minutes`".to_string(),
+            ))
+        }
+    };
+    if at <= now {
+        return Err(Error::Validation(
+            "The follow-up time must be in the future".to_string(),
+        ));
+    }
+    Ok(at)
+}
+
+pub struct FollowUpTool;
+
+#[async_trait]
+impl Tool for FollowUpTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "follow_up",
+            description: "Schedule a one-time follow-up with the user in this chat, e.g. to ask how a deployment, trip or appointment went. When it fires you get the question and context back in this conversation and write the follow-up message. The user is asked to approve each follow-up. Requires `question`, `context` and one of `at` / `delay_minutes`. Scheduled follow-ups show up in `cron` list and are cancelled with `cron` remove.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "question": {
+                        "type": "string",
+                        "description": "What to follow up on, e.g. 'Ask how the production deployment went'"
+                    },
+                    "context": {
+                        "type": "string",
+                        "description": "Short summary of the situation you'll need later (what happened, names, versions, dates)"
+                    },
+                    "at": {
+                        "type": "string",
+                        "description": "When to follow up, local time: 'YYYY-MM-DD HH:MM' or RFC 3339"
+                    },
+                    "delay_minutes": {
+                        "type": "integer",
+                        "description": "Alternative to `at`: minutes from now"
+                    },
+                    "name": {
+                        "type": "string",
+                        "description": "Optional short name for the job list"
+                    }
+                },
+                "required": ["question", "context"]
+            }),
+        }
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        if str_param(params, "question").is_none() {
+            return Err(Error::Validation("'question' is required".to_string()));
+        }
+        if str_param(params, "at").is_none() && params["delay_minutes"].as_i64().is_none() {
+            return Err(Error::Validation(
+                "Specify when to follow up: `at` or `delay_minutes`".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn prompt_rule(&self, _ctx: &PromptContext) -> Option<String> {
+        Some(
+            "- **follow_up**: when the user mentions something with a natural check-in point (a deployment, exam, trip, doctor's visit), you may offer to follow up and schedule it with `follow_up`. Put everything you'll need later into `context`. Use `cron` for reminders the user explicitly asks for."
+                .to_string(),
+        )
+    }
+
+    fn confirmation_items(&self, config: &Config, params: &Value) -> Vec<String> {
+        let tz = config.default_timezone.as_deref();
+        let when = due_at_ms(params, tz)
+            .map(|ms| format_time(ms, tz))
+            .unwrap_or_else(|_| "?".to_string());
+        vec![format!(
+            "follow up at {}: {}",
+            when,
+            str_param(params, "question").unwrap_or_default()
+        )]
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        if NON_INTERACTIVE_CHANNELS.contains(&ctx.channel.as_str()) {
+            return Err(Error::Tool(format!(
+                "Follow-ups need a user chat; '{}' has none",
+                ctx.channel
+            )));
+        }
+        let paths = match ctx.workspace.parent() {
+            Some(base) => Paths::with_base(base.to_path_buf()),
+            None => Paths::new(),
+        };
+        tokio::task::spawn_blocking(move || {
+            schedule(
+                &paths,
+                &params,
+                &ctx.channel,
+                &ctx.chat_id,
+                ctx.account_id.as_deref(),
+                ctx.config.default_timezone.as_deref(),
+                Some(ctx.role().as_str()),
+            )
+        })
+        .await
+        .map_err(|e| Error::Tool(format!("follow_up task failed: {}", e)))?
+    }
+}
+
+/// Create the one-shot job that brings the follow-up back to `channel`/`chat_id`.
+fn schedule(
+    paths: &Paths,
+    params: &Value,
+    channel: &str,
+    chat_id: &str,
+    account_id: Option<&str>,
+    tz: Option<&str>,
+    role: Option<&str>,
+) -> Result<Value> {
+    let at_ms = due_at_ms(params, tz)?;
+    let question = str_param(params, "question").unwrap_or_default();
+    let name = str_param(params, "name")
+        .map(str::to_string)
+        .unwrap_or_else(|| {
+            format!(
+                "Follow-up: {}",
+                question.chars().take(40).collect::<String>()
+            )
+        });
+    let created = execute_cron_action_with_paths(
+        paths,
+        "add",
+        &json!({
+            "name": name,
+            "message": question,
+            "context": str_param(params, "context"),
+            "account_id": account_id,
+            "at_ms": at_ms,
+            "mode": "follow_up",
+            "delete_after_run": true,
+        }),
+        channel,
+        chat_id,
+        None,
+        role,
+    )?;
+    Ok(json!({
+        "status": "scheduled",
+        "job_id": created["job_id"],
+        "at": format_time(at_ms, tz),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_due_at_requires_future_time() {
+        let at = due_at_ms(&json!({"delay_minutes": 30}), None).unwrap();
+        assert!(at > Utc::now().timestamp_millis() + 29 * 60_000);
+        assert!(due_at_ms(&json!({"at": "2020-01-01 10:00"}), Some("UTC")).is_err());
+        assert!(due_at_ms(&json!({"question": "x"}), None).is_err());
+        assert!(FollowUpTool
+            .validate(&json!({"question": "Ask about the exam", "at": "2099-06-01 10:00"}))
+            .is_ok());
+        assert!(FollowUpTool.validate(&json!({"question": "x"})).is_err());
+    }
+
+    #[test]
+    fn test_schedule_creates_follow_up_job() {
+        let paths = Paths::with_base(
+            std::env::temp_dir().join(format!("blockcell-follow-up-{}", uuid::Uuid::new_v4())),
+        );
+        let params = json!({
+            "question": "Ask how the deployment went",
+            "context": "v2 goes to production tonight",
+            "delay_minutes": 600
+        });
+        let result = schedule(
+            &paths,
+            &params,
+            "telegram",
+            "42",
+            Some("work"),
+            None,
+            Some("user"),
+        )
+        .unwrap();
+        assert_eq!(result["status"], "scheduled");
+
+        let store: Value =
+            serde_json::from_str(&std::fs::read_to_string(paths.cron_jobs_file()).unwrap())
+                .unwrap();
+        let job = &store["jobs"][0];
+        assert_eq!(job["payload"]["kind"], "follow_up");
+        assert_eq!(job["payload"]["channel"], "telegram");
+        assert_eq!(job["payload"]["to"], "42");
+        assert_eq!(job["payload"]["context"], "v2 goes to production tonight");
+        assert_eq!(job["payload"]["accountId"], "work");
+        assert_eq!(job["payload"]["role"], "user");
+        assert_eq!(job["deleteAfterRun"], true);
+    }
+}
@@ -0,0 +1,629 @@
+use async_trait::async_trait;
+use blockcell_core::config::GitHubConfig;
+use blockcell_core::secrets::resolve_secret_ref;
+use blockcell_core::{Config, Error, Result};
+use serde_json::{json, Value};
+
+use crate::egress::HttpClientFactory;
+use crate::{Tool, ToolContext, ToolSchema};
+
+const API_TIMEOUT_SECS: u64 = 30;
+const WRITE_ACTIONS: &[&str] = &["review", "comment", "label", "assign"];
+const REVIEW_EVENTS: &[&str] = &["COMMENT", "APPROVE", "REQUEST_CHANGES"];
+
+/// `owner/repo` with conservative characters only (no path traversal into other endpoints).
+fn valid_repo(repo: &str) -> bool {
+    let mut parts = repo.split('/');
+    let (Some(owner), Some(name), None) = (parts.next(), parts.next(), parts.next()) else {
+        return false;
+    };
+    let ok = |s: &str| {
+        !s.is_empty()
+            && s != "."
+            && s != ".."
+            && s.chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+    ok(owner) && ok(name)
+}
+
+/// Whether `tools.github.repos` lets the tool touch `repo`.
+fn repo_allowed(cfg: &GitHubConfig, repo: &str) -> bool {
+    if cfg.repos.is_empty() {
+        return true;
+    }
+    let repo = repo.to_ascii_lowercase();
+    cfg.repos.iter().any(|entry| {
+        let entry = entry.trim().to_ascii_lowercase();
+        match entry.strip_suffix("/*") {
+            Some(owner) => repo.split('/').next() == Some(owner),
+            None => entry == repo,
+        }
+    })
+}
+
+fn str_list(params: &Value, key: &str) -> Vec<String> {
+    match params.get(key) {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect(),
+        Some(Value::String(s)) => s
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn truncate_chars(text: &str, max: usize) -> (String, bool) {
+    if text.chars().count() <= max {
+        return (text.to_string(), false);
+    }
+    (text.chars().take(max).collect(), true)
+}
+
+fn pr_summary(pr: &Value) -> Value {
+    json!({
+        "number": pr["number"],
+        "title": pr["title"],
+        "state": pr["state"],
+        "draft": pr["draft"],
+        "author": pr["user"]["login"],
+        "head": pr["head"]["ref"],
+        "base": pr["base"]["ref"],
+        "labels": pr["labels"].as_array().map(|l| l.iter().filter_map(|x| x["name"].as_str()).collect::<Vec<_>>()),
+        "created_at": pr["created_at"],
+        "updated_at": pr["updated_at"],
+        "url": pr["html_url"],
+    })
+}
+
+fn issue_summary(issue: &Value) -> Value {
+    json!({
+        "number": issue["number"],
+        "title": issue["title"],
+        "state": issue["state"],
+        "author": issue["user"]["login"],
+        "labels": issue["labels"].as_array().map(|l| l.iter().filter_map(|x| x["name"].as_str()).collect::<Vec<_>>()),
+        "assignees": issue["assignees"].as_array().map(|a| a.iter().filter_map(|x| x["login"].as_str()).collect::<Vec<_>>()),
+        "comments": issue["comments"],
+        "is_pull_request": issue.get("pull_request").is_some(),
+        "created_at": issue["created_at"],
+        "url": issue["html_url"],
+    })
+}
+
+struct GitHubClient {
+    client: reqwest::Client,
+    base: String,
+    token: Option<String>,
+}
+
+impl GitHubClient {
+    async fn new(ctx: &ToolContext) -> Result<Self> {
+        let cfg = &ctx.config.tools.github;
+        let token = if cfg.token.trim().is_empty() {
+            std::env::var("GITHUB_TOKEN")
+                .ok()
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+        } else {
+            Some(resolve_secret_ref(&ctx.paths(), &cfg.token)?)
+        };
+        let base = cfg.api_base.trim().trim_end_matches('/').to_string();
+        let egress = HttpClientFactory::for_tool(&ctx.config, "git_api");
+        egress.check(&base).await?;
+        let client = egress
+            .builder()
+            .timeout(std::time::Duration::from_secs(API_TIMEOUT_SECS))
+            .build()
+            .map_err(|e| Error::Tool(format!("Failed to create HTTP client: {}", e)))?;
+        Ok(Self {
+            client,
+            base,
+            token,
+        })
+    }
+
+    async fn send(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<Value>,
+        accept: &str,
+    ) -> Result<String> {
+        let mut request = self
+            .client
+            .request(method, format!("{}{}", self.base, path))
+            .header(
+                "User-Agent",
+                format!("blockcell/{}", env!("CARGO_PKG_VERSION")),
+            )
+            .header("Accept", accept)
+            .header("X-GitHub-Api-Version", "2022-11-28");
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| Error::Tool(format!("GitHub request failed: {}", e)))?;
+        let status = response.status();
+        let text = response
+            .text()
+            .await
+            .map_err(|e| Error::Tool(format!("Failed to read GitHub response: {}", e)))?;
+        if !status.is_success() {
+            let message = serde_json::from_str::<Value>(&text)
+                .ok()
+                .and_then(|v| v["message"].as_str().map(str::to_string))
+                .unwrap_or_else(|| text.chars().take(300).collect());
+            let hint = if status.as_u16() == 401 || (status.as_u16() == 404 && self.token.is_none())
+            {
+                " (set tools.github.token or GITHUB_TOKEN)"
+            } else {
+                ""
+            };
+            return Err(Error::Tool(format!(
+                "GitHub API {} {}: {}{}",
+                status.as_u16(),
+                path,
+                message,
+                hint
+            )));
+        }
+        Ok(text)
+    }
+
+    async fn json(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> Result<Value> {
+        let text = self
+            .send(method, path, body, "application/vnd.github+json")
+            .await?;
+        if text.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+        Ok(serde_json::from_str(&text)?)
+    }
+}
+
+pub struct GitApiTool;
+
+#[async_trait]
+impl Tool for GitApiTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "git_api",
+            description: "GitHub REST API for pull request review and issue triage. \
+                Read: 'list_prs', 'get_pr', 'pr_diff' (unified diff, truncated), 'pr_files', 'list_issues', 'get_issue'. \
+                Write: 'review' (post a PR review with optional inline comments), 'comment' (issue/PR comment), \
+                'label' (add/remove/set labels), 'assign' (add assignees). All actions need `repo` as owner/name.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["list_prs", "get_pr", "pr_diff", "pr_files", "list_issues", "get_issue", "review", "comment", "label", "assign"],
+                        "description": "Action to perform"
+                    },
+                    "repo": {"type": "string", "description": "Repository as owner/name"},
+                    "number": {"type": "integer", "description": "PR or issue number"},
+                    "state": {"type": "string", "enum": ["open", "closed", "all"], "description": "(list_prs/list_issues) Default: open"},
+                    "labels": {
+                        "type": "array", "items": {"type": "string"},
+                        "description": "(list_issues) filter; (label) labels to apply"
+                    },
+                    "mode": {"type": "string", "enum": ["add", "remove", "set"], "description": "(label) Default: add"},
+                    "assignees": {"type": "array", "items": {"type": "string"}, "description": "(assign) GitHub logins"},
+                    "body": {"type": "string", "description": "(review/comment) Markdown text"},
+                    "event": {"type": "string", "enum": ["COMMENT", "APPROVE", "REQUEST_CHANGES"], "description": "(review) Default: COMMENT"},
+                    "comments": {
+                        "type": "array",
+                        "description": "(review) Inline comments",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "path": {"type": "string"},
+                                "line": {"type": "integer", "description": "Line in the new file"},
+                                "side": {"type": "string", "enum": ["LEFT", "RIGHT"]},
+                                "body": {"type": "string"}
+                            },
+                            "required": ["path", "line", "body"]
+                        }
+                    },
+                    "limit": {"type": "integer", "description": "(list_*) Max items, default 20, max 100"}
+                },
+                "required": ["action", "repo"]
+            }),
+        }
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        let action = params.get("action").and_then(|v| v.as_str()).unwrap_or("");
+        let repo = params.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+        if !valid_repo(repo) {
+            return Err(Error::Validation("'repo' must be owner/name".to_string()));
+        }
+        let needs_number = !matches!(action, "list_prs" | "list_issues");
+        match action {
+            "list_prs" | "list_issues" | "get_pr" | "pr_diff" | "pr_files" | "get_issue" => {}
+            "review" => {
+                let event = params
+                    .get("event")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("COMMENT");
+                if !REVIEW_EVENTS.contains(&event) {
+                    return Err(Error::Validation(format!(
+                        "Invalid review event: {}",
+                        event
+                    )));
+                }
+                let has_body = params
+                    .get("body")
+                    .and_then(|v| v.as_str())
+                    .is_some_and(|s| !s.trim().is_empty());
+                let has_comments = params
+                    .get("comments")
+                    .and_then(|v| v.as_array())
+                    .is_some_and(|c| !c.is_empty());
+                if !has_body && !has_comments {
+                    return Err(Error::Validation(
+                        "review requires 'body' or 'comments'".to_string(),
+                    ));
+                }
+            }
+            "comment" => {
+                if params
+                    .get("body")
+                    .and_then(|v| v.as_str())
+                    .is_none_or(|s| s.trim().is_empty())
+                {
+                    return Err(Error::Validation("comment requires 'body'".to_string()));
+                }
+            }
+            "label" => {
+                let mode = params.get("mode").and_then(|v| v.as_str()).unwrap_or("add");
+                if !matches!(mode, "add" | "remove" | "set") {
+                    return Err(Error::Validation(format!("Invalid label mode: {}", mode)));
+                }
+                if mode != "set" && str_list(params, "labels").is_empty() {
+                    return Err(Error::Validation("label requires 'labels'".to_string()));
+                }
+            }
+            "assign" => {
+                if str_list(params, "assignees").is_empty() {
+                    return Err(Error::Validation("assign requires 'assignees'".to_string()));
+                }
+            }
+            "" => return Err(Error::Validation("Missing 'action'".to_string())),
+            _ => return Err(Error::Validation(format!("Unknown action: {}", action))),
+        }
+        if needs_number && params.get("number").and_then(|v| v.as_u64()).is_none() {
+            return Err(Error::Validation(format!("{} requires 'number'", action)));
+        }
+        Ok(())
+    }
+
+    fn confirmation_items(&self, config: &Config, params: &Value) -> Vec<String> {
+        let action = params.get("action").and_then(|v| v.as_str()).unwrap_or("");
+        if !config.tools.github.confirm_writes || !WRITE_ACTIONS.contains(&action) {
+            return Vec::new();
+        }
+        let target = format!(
+            "{}#{}",
+            params["repo"].as_str().unwrap_or(""),
+            params["number"].as_u64().unwrap_or(0)
+        );
+        let detail = match action {
+            "review" => format!(
+                "post {} review on {}",
+                params["event"].as_str().unwrap_or("COMMENT"),
+                target
+            ),
+            "comment" => format!("comment on {}", target),
+            "label" => format!(
+                "{} labels [{}] on {}",
+                params["mode"].as_str().unwrap_or("add"),
+                str_list(params, "labels").join(", "),
+                target
+            ),
+            _ => format!(
+                "assign [{}] to {}",
+                str_list(params, "assignees").join(", "),
+                target
+            ),
+        };
+        vec![format!("git_api: {}", detail)]
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        let cfg = ctx.config.tools.github.clone();
+        let action = params["action"].as_str().unwrap_or("");
+        let repo = params["repo"].as_str().unwrap_or("").trim();
+        if !repo_allowed(&cfg, repo) {
+            return Err(Error::PermissionDenied(format!(
+                "Repository '{}' is not listed in tools.github.repos",
+                repo
+            )));
+        }
+        let number = params.get("number").and_then(|v| v.as_u64()).unwrap_or(0);
+        let limit = params
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(20)
+            .clamp(1, 100);
+        let state = params
+            .get("state")
+            .and_then(|v| v.as_str())
+            .unwrap_or("open");
+        let gh = GitHubClient::new(&ctx).await?;
+        use reqwest::Method;
+
+        match action {
+            "list_prs" => {
+                let path = format!(
+                    "/repos/{}/pulls?state={}&per_page={}&sort=updated&direction=desc",
+                    repo, state, limit
+                );
+                let list = gh.json(Method::GET, &path, None).await?;
+                let prs: Vec<Value> = list
+                    .as_array()
+                    .map(|a| a.iter().map(pr_summary).collect())
+                    .unwrap_or_default();
+                Ok(json!({"repo": repo, "count": prs.len(), "pull_requests": prs}))
+            }
+            "get_pr" => {
+                let pr = gh
+                    .json(
+                        Method::GET,
+                        &format!("/repos/{}/pulls/{}", repo, number),
+                        None,
+                    )
+                    .await?;
+                let mut summary = pr_summary(&pr);
+                summary["body"] = pr["body"].clone();
+                summary["mergeable"] = pr["mergeable"].clone();
+                summary["commits"] = pr["commits"].clone();
+                summary["additions"] = pr["additions"].clone();
+                summary["deletions"] = pr["deletions"].clone();
+                summary["changed_files"] = pr["changed_files"].clone();
+                summary["head_sha"] = pr["head"]["sha"].clone();
+                Ok(summary)
+            }
+            "pr_diff" => {
+                let diff = gh
+                    .send(
+                        Method::GET,
+                        &format!("/repos/{}/pulls/{}", repo, number),
+                        None,
+                        "application/vnd.github.diff",
+                    )
+                    .await?;
+                let (diff_text, truncated) = truncate_chars(&diff, cfg.max_diff_chars);
+                Ok(json!({
+                    "repo": repo,
+                    "number": number,
+                    "diff": diff_text,
+                    "truncated": truncated,
+                    "total_chars": diff.chars().count(),
+                }))
+            }
+            "pr_files" => {
+                let path = format!("/repos/{}/pulls/{}/files?per_page={}", repo, number, limit);
+                let files = gh.json(Method::GET, &path, None).await?;
+                let files: Vec<Value> = files
+                    .as_array()
+                    .map(|a| {
+                        a.iter()
+                            .map(|f| {
+                                json!({
+                                    "filename": f["filename"],
+                                    "status": f["status"],
+                                    "additions": f["additions"],
+                                    "deletions": f["deletions"],
+                                })
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                Ok(json!({"repo": repo, "number": number, "count": files.len(), "files": files}))
+            }
+            "list_issues" => {
+                let mut path = format!(
+                    "/repos/{}/issues?state={}&per_page={}&sort=updated&direction=desc",
+                    repo, state, limit
+                );
+                let labels = str_list(&params, "labels");
+                if !labels.is_empty() {
+                    path.push_str(&format!(
+                        "&labels={}",
+                        urlencoding::encode(&labels.join(","))
+                    ));
+                }
+                let list = gh.json(Method::GET, &path, None).await?;
+                // The issues endpoint also returns PRs; keep only real issues.
+                let issues: Vec<Value> = list
+                    .as_array()
+                    .map(|a| {
+                        a.iter()
+                            .filter(|i| i.get("pull_request").is_none())
+                            .map(issue_summary)
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                Ok(json!({"repo": repo, "count": issues.len(), "issues": issues}))
+            }
+            "get_issue" => {
+                let issue = gh
+                    .json(
+                        Method::GET,
+                        &format!("/repos/{}/issues/{}", repo, number),
+                        None,
+                    )
+                    .await?;
+                let mut summary = issue_summary(&issue);
+                summary["body"] = issue["body"].clone();
+                Ok(summary)
+            }
+            "review" => {
+                let comments: Vec<Value> = params
+                    .get("comments")
+                    .and_then(|v| v.as_array())
+                    .map(|c| {
+                        c.iter()
+                            .map(|c| {
+                                json!({
+                                    "path": c["path"],
+                                    "line": c["line"],
+                                    "side": c["side"].as_str().unwrap_or("RIGHT"),
+                                    "body": c["body"],
+                                })
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let mut body = json!({
+                    "event": params["event"].as_str().unwrap_or("COMMENT"),
+                    "body": params["body"].as_str().unwrap_or(""),
+                });
+                if !comments.is_empty() {
+                    body["comments"] = json!(comments);
+                }
+                let review = gh
+                    .json(
+                        Method::POST,
+                        &format!("/repos/{}/pulls/{}/reviews", repo, number),
+                        Some(body),
+                    )
+                    .await?;
+                Ok(json!({
+                    "status": "posted",
+                    "review_id": review["id"],
+                    "state": review["state"],
+                    "inline_comments": comments.len(),
+                    "url": review["html_url"],
+                }))
+            }
+            "comment" => {
+                let comment = gh
+                    .json(
+                        Method::POST,
+                        &format!("/repos/{}/issues/{}/comments", repo, number),
+                        Some(json!({"body": params["body"]})),
+                    )
+                    .await?;
+                Ok(
+                    json!({"status": "posted", "comment_id": comment["id"], "url": comment["html_url"]}),
+                )
+            }
+            "label" => {
+                let labels = str_list(&params, "labels");
+                let base = format!("/repos/{}/issues/{}/labels", repo, number);
+                let result = match params["mode"].as_str().unwrap_or("add") {
+                    "set" => {
+                        gh.json(Method::PUT, &base, Some(json!({"labels": labels})))
+                            .await?
+                    }
+                    "remove" => {
+                        let mut last = Value::Null;
+                        for label in &labels {
+                            let path = format!("{}/{}", base, urlencoding::encode(label));
+                            last = gh.json(Method::DELETE, &path, None).await?;
+                        }
+                        last
+                    }
+                    _ => {
+                        gh.json(Method::POST, &base, Some(json!({"labels": labels})))
+                            .await?
+                    }
+                };
+                let current: Vec<&str> = result
+                    .as_array()
+                    .map(|l| l.iter().filter_map(|x| x["name"].as_str()).collect())
+                    .unwrap_or_default();
+                Ok(json!({"status": "updated", "labels": current}))
+            }
+            "assign" => {
+                let assignees = str_list(&params, "assignees");
+                let issue = gh
+                    .json(
+                        Method::POST,
+                        &format!("/repos/{}/issues/{}/assignees", repo, number),
+                        Some(json!({"assignees": assignees})),
+                    )
+                    .await?;
+                Ok(json!({"status": "updated", "issue": issue_summary(&issue)}))
+            }
+            _ => Err(Error::Tool(format!("Unknown action: {}", action))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let tool = GitApiTool;
+        assert!(tool
+            .validate(&json!({"action": "list_prs", "repo": "octo/hello"}))
+            .is_ok());
+        assert!(tool
+            .validate(&json!({"action": "get_pr", "repo": "octo/hello"}))
+            .is_err());
+        assert!(tool
+            .validate(&json!({"action": "list_prs", "repo": "../etc/passwd"}))
+            .is_err());
+        assert!(tool
+            .validate(&json!({"action": "review", "repo": "o/r", "number": 1, "event": "MERGE", "body": "x"}))
+            .is_err());
+        assert!(tool
+            .validate(&json!({"action": "review", "repo": "o/r", "number": 1, "comments": [{"path": "a.rs", "line": 3, "body": "nit"}]}))
+            .is_ok());
+        assert!(tool
+            .validate(
+                &json!({"action": "label", "repo": "o/r", "number": 1, "mode": "set", "labels": []})
+            )
+            .is_ok());
+        assert!(tool
+            .validate(&json!({"action": "assign", "repo": "o/r", "number": 1}))
+            .is_err());
+    }
+
+    #[test]
+    fn test_repo_allowlist_and_confirmation() {
+        let mut config = Config::default();
+        assert!(repo_allowed(&config.tools.github, "any/repo"));
+        config.tools.github.repos = vec!["octo/hello".to_string(), "acme/*".to_string()];
+        assert!(repo_allowed(&config.tools.github, "Octo/Hello"));
+        assert!(repo_allowed(&config.tools.github, "acme/widgets"));
+        assert!(!repo_allowed(&config.tools.github, "octo/other"));
+
+        let tool = GitApiTool;
+        let label = json!({"action": "label", "repo": "acme/w", "number": 7, "labels": ["bug"]});
+        assert_eq!(
+            tool.confirmation_items(&config, &label),
+            vec!["git_api: add labels [bug] on acme/w#7".to_string()]
+        );
+        assert!(tool
+            .confirmation_items(
+                &config,
+                &json!({"action": "pr_diff", "repo": "acme/w", "number": 7})
+            )
+            .is_empty());
+        config.tools.github.confirm_writes = false;
+        assert!(tool.confirmation_items(&config, &label).is_empty());
+    }
+}
@@ -0,0 +1,681 @@
+//! Habit tracker: schedules, completions, streaks and weekly adherence.
+//!
+//! Everything lives in `workspace/habits/habits.json`. The runtime tick calls
+//! [`send_due_checkins`], which posts one check-in prompt per habit that is scheduled today
+//! and still open at its check-in time; the morning digest includes [`digest_section`].
+
+use async_trait::async_trait;
+use blockcell_core::system_event::{EventPriority, SystemEvent};
+use blockcell_core::{Config, Error, Result};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{EventEmitterHandle, PromptContext, Tool, ToolContext, ToolSchema};
+
+/// How far back streaks are followed.
+const MAX_STREAK_DAYS: i64 = 3 * 366;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HabitSchedule {
+    Daily,
+    /// Specific weekdays, numbered from Monday = 0.
+    Days {
+        days: Vec<u32>,
+    },
+    /// Any `times` days per ISO week.
+    PerWeek {
+        times: u32,
+    },
+}
+
+impl HabitSchedule {
+    /// `daily`, `weekdays`, `weekends`, `mon,wed,fri`, `3x/week` (or `3/week`, `每周3次`).
+    fn parse(value: &str) -> Result<Self> {
+        let v = value.trim().to_lowercase();
+        match v.as_str() {
+            "" | "daily" | "every day" | "每天" => return Ok(Self::Daily),
+            "weekdays" | "工作日" => {
+                return Ok(Self::Days {
+                    days: vec![0, 1, 2, 3, 4],
+                })
+            }
+            "weekends" | "周末" => return Ok(Self::Days { days: vec![5, 6] }),
+            _ => {}
+        }
+        let per_week = v
+            .trim_start_matches("每周")
+            .trim_end_matches("次")
+            .trim_end_matches("/week")
+            .trim_end_matches(" per week")
+            .trim_end_matches("x")
+            .trim()
+            .parse::<u32>();
+        if v.contains("week") || v.starts_with("每周") {
+            return match per_week {
+                Ok(times) if (1..=7).contains(&times) => Ok(Self::PerWeek { times }),
+                _ => Err(Error::Validation(format!(
+                    "Invalid schedule '{}'. Use e.g. '3x/week'",
+                    value
+                ))),
+            };
+        }
+        let mut days: Vec<u32> = Vec::new();
+        for part in v.split([',', ' ', '/']).filter(|p| !p.is_empty()) {
+            let day: Weekday = part.parse().map_err(|_| {
+                Error::Validation(format!(
+                    "Invalid schedule '{}'. Use daily, weekdays, weekends, 'mon,wed,fri' or '3x/week'",
+                    value
+                ))
+            })?;
+            days.push(day.num_days_from_monday());
+        }
+        days.sort_unstable();
+        days.dedup();
+        if days.is_empty() {
+            return Err(Error::Validation(format!("Invalid schedule '{}'", value)));
+        }
+        Ok(Self::Days { days })
+    }
+
+    fn is_scheduled(&self, date: NaiveDate) -> bool {
+        match self {
+            Self::Daily | Self::PerWeek { .. } => true,
+            Self::Days { days } => days.contains(&date.weekday().num_days_from_monday()),
+        }
+    }
+
+    fn describe(&self) -> String {
+        const NAMES: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+        match self {
+            Self::Daily => "daily".to_string(),
+            Self::Days { days } => days
+                .iter()
+                .filter_map(|d| NAMES.get(*d as usize))
+                .copied()
+                .collect::<Vec<_>>()
+                .join(","),
+            Self::PerWeek { times } => format!("{}x/week", times),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HabitStore {
+    #[serde(default)]
+    habits: Vec<Habit>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Habit {
+    id: String,
+    name: String,
+    schedule: HabitSchedule,
+    /// Local `HH:MM`; falls back to `tools.habits.defaultCheckinTime`.
+    #[serde(default)]
+    checkin_time: Option<String>,
+    #[serde(default = "default_active")]
+    active: bool,
+    created: NaiveDate,
+    /// Dates on which the habit was done.
+    #[serde(default)]
+    done: BTreeSet<NaiveDate>,
+    /// Last date a check-in prompt was sent.
+    #[serde(default)]
+    last_prompted: Option<NaiveDate>,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+fn store_path(workspace: &Path) -> PathBuf {
+    workspace.join("habits").join("habits.json")
+}
+
+fn load_store(workspace: &Path) -> Result<HabitStore> {
+    let path = store_path(workspace);
+    if !path.exists() {
+        return Ok(HabitStore::default());
+    }
+    let content = std::fs::read_to_string(&path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+fn save_store(workspace: &Path, store: &HabitStore) -> Result<()> {
+    let path = store_path(workspace);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(store)?)?;
+    Ok(())
+}
+
+fn local_now(tz: Option<&str>) -> NaiveDateTime {
+    match tz.and_then(|t| t.parse::<chrono_tz::Tz>().ok()) {
+        Some(tz) => Utc::now().with_timezone(&tz).naive_local(),
+        None => chrono::Local::now().naive_local(),
+    }
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|_| Error::Validation(format!("Invalid time '{}'. Use HH:MM", value)))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Streaks and adherence
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+fn week_count(habit: &Habit, monday: NaiveDate) -> u32 {
+    habit.done.range(monday..monday + Duration::days(7)).count() as u32
+}
+
+/// Current streak: consecutive scheduled days (or weeks, for per-week habits) completed.
+/// An open today/this week does not break it.
+fn current_streak(habit: &Habit, today: NaiveDate) -> u32 {
+    match &habit.schedule {
+        HabitSchedule::PerWeek { times } => {
+            let mut monday = week_start(today);
+            if week_count(habit, monday) < *times {
+                monday -= Duration::days(7);
+            }
+            let mut streak = 0;
+            while monday >= week_start(habit.created) && week_count(habit, monday) >= *times {
+                streak += 1;
+                monday -= Duration::days(7);
+            }
+            streak
+        }
+        schedule => {
+            let mut day = if habit.done.contains(&today) {
+                today
+            } else {
+                today - Duration::days(1)
+            };
+            let mut streak = 0;
+            let floor = (today - Duration::days(MAX_STREAK_DAYS)).max(habit.created);
+            while day >= floor {
+                if schedule.is_scheduled(day) {
+                    if !habit.done.contains(&day) {
+                        break;
+                    }
+                    streak += 1;
+                }
+                day -= Duration::days(1);
+            }
+            streak
+        }
+    }
+}
+
+/// Longest run of scheduled days completed (per-week habits: weeks meeting the target).
+fn longest_streak(habit: &Habit, today: NaiveDate) -> u32 {
+    let (mut best, mut run) = (0, 0);
+    match &habit.schedule {
+        HabitSchedule::PerWeek { times } => {
+            let mut monday = week_start(habit.created);
+            while monday <= week_start(today) {
+                if week_count(habit, monday) >= *times {
+                    run += 1;
+                    best = best.max(run);
+                } else if monday < week_start(today) {
+                    run = 0;
+                }
+                monday += Duration::days(7);
+            }
+        }
+        schedule => {
+            let mut day = habit.created.max(today - Duration::days(MAX_STREAK_DAYS));
+            while day <= today {
+                if schedule.is_scheduled(day) {
+                    if habit.done.contains(&day) {
+                        run += 1;
+                        best = best.max(run);
+                    } else if day < today {
+                        run = 0;
+                    }
+                }
+                day += Duration::days(1);
+            }
+        }
+    }
+    best
+}
+
+/// Share of the target met in the 7 days ending `end` (0.0-1.0), or `None` before any
+/// scheduled day.
+fn adherence(habit: &Habit, end: NaiveDate) -> Option<f64> {
+    let start = (end - Duration::days(6)).max(habit.created);
+    if start > end {
+        return None;
+    }
+    let done = habit.done.range(start..=end).count() as f64;
+    match &habit.schedule {
+        HabitSchedule::PerWeek { times } => {
+            let days = (end - start).num_days() as f64 + 1.0;
+            let target = (*times as f64 * days / 7.0).ceil().max(1.0);
+            Some((done / target).min(1.0))
+        }
+        schedule => {
+            let scheduled = (0..=(end - start).num_days())
+                .map(|i| start + Duration::days(i))
+                .filter(|d| schedule.is_scheduled(*d))
+                .count() as f64;
+            (scheduled > 0.0).then(|| (done / scheduled).min(1.0))
+        }
+    }
+}
+
+/// Whether the habit still needs doing today.
+fn open_today(habit: &Habit, today: NaiveDate) -> bool {
+    if !habit.active || habit.done.contains(&today) {
+        return false;
+    }
+    match &habit.schedule {
+        HabitSchedule::PerWeek { times } => week_count(habit, week_start(today)) < *times,
+        schedule => schedule.is_scheduled(today),
+    }
+}
+
+fn habit_json(h: &Habit, today: NaiveDate) -> Value {
+    let unit = match h.schedule {
+        HabitSchedule::PerWeek { .. } => "weeks",
+        _ => "days",
+    };
+    json!({
+        "id": h.id,
+        "name": h.name,
+        "schedule": h.schedule.describe(),
+        "checkin_time": h.checkin_time,
+        "active": h.active,
+        "done_today": h.done.contains(&today),
+        "open_today": open_today(h, today),
+        "streak": current_streak(h, today),
+        "longest_streak": longest_streak(h, today),
+        "streak_unit": unit,
+        "adherence_7d": adherence(h, today).map(|a| (a * 100.0).round()),
+        "total_done": h.done.len(),
+    })
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Check-ins and digest
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Post a check-in prompt for each habit that is still open past its check-in time.
+pub fn send_due_checkins(config: &Config, workspace: &Path, emitter: &EventEmitterHandle) {
+    let cfg = &config.tools.habits;
+    if !cfg.checkin_prompts || !store_path(workspace).exists() {
+        return;
+    }
+    let Ok(mut store) = load_store(workspace) else {
+        return;
+    };
+    let now = local_now(config.default_timezone.as_deref());
+    let today = now.date();
+    let fallback = parse_time(&cfg.default_checkin_time).unwrap_or(NaiveTime::MIN);
+    let mut prompted = false;
+    for habit in store.habits.iter_mut() {
+        if habit.last_prompted == Some(today) || !open_today(habit, today) {
+            continue;
+        }
+        let at = habit
+            .checkin_time
+            .as_deref()
+            .and_then(|t| parse_time(t).ok())
+            .unwrap_or(fallback);
+        if now.time() < at {
+            continue;
+        }
+        let streak = current_streak(habit, today);
+        let mut text = format!("今天完成「{}」了吗？完成后告诉我即可记录。", habit.name);
+        if streak > 0 {
+            text.push_str(&format!("当前连续 {} 次，别断了！", streak));
+        }
+        let mut event = SystemEvent::new_main_session(
+            "habits.checkin",
+            "habits",
+            EventPriority::Normal,
+            "习惯打卡",
+            text,
+        );
+        event.dedup_key = Some(format!("habit-checkin:{}:{}", habit.id, today));
+        emitter.emit(event);
+        habit.last_prompted = Some(today);
+        prompted = true;
+        info!(habit = %habit.name, "Habit check-in prompt queued");
+    }
+    if prompted {
+        if let Err(e) = save_store(workspace, &store) {
+            warn!(error = %e, "Failed to record habit check-in prompts");
+        }
+    }
+}
+
+/// Habits section for the morning digest: today's open habits with their streaks.
+pub fn digest_section(config: &Config, workspace: &Path) -> Option<String> {
+    if !config.tools.habits.in_digest {
+        return None;
+    }
+    let store = load_store(workspace).ok()?;
+    let today = local_now(config.default_timezone.as_deref()).date();
+    let yesterday = today - Duration::days(1);
+    let lines: Vec<String> = store
+        .habits
+        .iter()
+        .filter(|h| open_today(h, today))
+        .map(|h| {
+            let streak = current_streak(h, today);
+            let missed = h.schedule.is_scheduled(yesterday)
+                && !matches!(h.schedule, HabitSchedule::PerWeek { .. })
+                && yesterday >= h.created
+                && !h.done.contains(&yesterday);
+            match (streak, missed) {
+                (0, true) => format!("- {}（昨天没完成，今天重新开始）", h.name),
+                (0, false) => format!("- {}", h.name),
+                (n, _) => format!("- {}（连续 {} 次）", h.name, n),
+            }
+        })
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+    Some(format!("今日习惯：\n{}", lines.join("\n")))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tool
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn find_habit<'a>(store: &'a mut HabitStore, key: &str) -> Result<&'a mut Habit> {
+    let lower = key.trim().to_lowercase();
+    store
+        .habits
+        .iter_mut()
+        .find(|h| h.id == key.trim() || h.name.to_lowercase() == lower)
+        .ok_or_else(|| Error::NotFound(format!("Habit '{}' not found", key)))
+}
+
+fn date_param(params: &Value, today: NaiveDate) -> Result<NaiveDate> {
+    match params["date"]
+        .as_str()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        Some("yesterday") | Some("昨天") => Ok(today - Duration::days(1)),
+        Some(d) => NaiveDate::parse_from_str(d, "%Y-%m-%d")
+            .map_err(|_| Error::Validation(format!("Invalid date '{}'. Use YYYY-MM-DD", d))),
+        None => Ok(today),
+    }
+}
+
+pub struct HabitsTool;
+
+#[async_trait]
+impl Tool for HabitsTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "habits",
+            description: "Habit tracker with streaks, weekly adherence and automatic evening check-in prompts. You MUST provide `action`. action='add': requires `name`; optional `schedule` ('daily' default, 'weekdays', 'weekends', 'mon,wed,fri', '3x/week') and `checkin_time` (HH:MM). action='done' / 'undo': requires `habit` (id or name); optional `date` (YYYY-MM-DD or 'yesterday', default today). action='list': all habits with streaks. action='stats': requires `habit`; optional `weeks` (default 4) of weekly adherence. action='pause' / 'resume' / 'remove': requires `habit`.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["add", "done", "undo", "list", "stats", "pause", "resume", "remove"],
+                        "description": "Action to perform"
+                    },
+                    "name": {"type": "string", "description": "(add) Habit name, e.g. 'Run 5km'"},
+                    "schedule": {"type": "string", "description": "(add) daily | weekdays | weekends | mon,wed,fri | 3x/week"},
+                    "checkin_time": {"type": "string", "description": "(add) Local HH:MM for the check-in prompt"},
+                    "habit": {"type": "string", "description": "Habit id or name"},
+                    "date": {"type": "string", "description": "(done/undo) YYYY-MM-DD or 'yesterday'. Default: today"},
+                    "weeks": {"type": "integer", "description": "(stats) Weeks of history. Default: 4"}
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        let has = |key: &str| params[key].as_str().is_some_and(|s| !s.trim().is_empty());
+        match params["action"].as_str().unwrap_or("") {
+            "add" => {
+                if !has("name") {
+                    return Err(Error::Validation("'name' is required".into()));
+                }
+                HabitSchedule::parse(params["schedule"].as_str().unwrap_or(""))?;
+                if let Some(t) = params["checkin_time"].as_str() {
+                    parse_time(t)?;
+                }
+                Ok(())
+            }
+            "done" | "undo" | "stats" | "pause" | "resume" | "remove" => {
+                if !has("habit") {
+                    return Err(Error::Validation("'habit' is required".into()));
+                }
+                Ok(())
+            }
+            "list" => Ok(()),
+            other => Err(Error::Validation(format!(
+                "Unknown action '{}'. Use add, done, undo, list, stats, pause, resume or remove",
+                other
+            ))),
+        }
+    }
+
+    fn prompt_rule(&self, _ctx: &PromptContext) -> Option<String> {
+        Some(
+            "- When the user says they did a tracked habit (or answers a habit check-in), record it with `habits` `done`; use `list`/`stats` for streak and adherence questions."
+                .to_string(),
+        )
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        let action = params["action"].as_str().unwrap_or("").to_string();
+        let tz = ctx.config.default_timezone.clone();
+        let workspace = ctx.workspace.clone();
+        tokio::task::spawn_blocking(move || {
+            let today = local_now(tz.as_deref()).date();
+            let mut store = load_store(&workspace)?;
+            let habit_key = params["habit"].as_str().unwrap_or("").to_string();
+            match action.as_str() {
+                "add" => {
+                    let name = params["name"].as_str().unwrap_or("").trim().to_string();
+                    if store.habits.iter().any(|h| h.name.eq_ignore_ascii_case(&name)) {
+                        return Err(Error::Validation(format!("Habit '{}' already exists", name)));
+                    }
+                    let habit = Habit {
+                        id: format!(
+                            "habit_{}",
+                            Uuid::new_v4().to_string().split('-').next().unwrap_or("x")
+                        ),
+                        name,
+                        schedule: HabitSchedule::parse(params["schedule"].as_str().unwrap_or(""))?,
+                        checkin_time: params["checkin_time"]
+                            .as_str()
+                            .map(|t| parse_time(t).map(|t| t.format("%H:%M").to_string()))
+                            .transpose()?,
+                        active: true,
+                        created: today,
+                        done: BTreeSet::new(),
+                        last_prompted: None,
+                    };
+                    let out = habit_json(&habit, today);
+                    store.habits.push(habit);
+                    save_store(&workspace, &store)?;
+                    Ok(json!({"status": "added", "habit": out}))
+                }
+                "done" | "undo" => {
+                    let date = date_param(&params, today)?;
+                    if date > today {
+                        return Err(Error::Validation("Cannot record a future date".into()));
+                    }
+                    let habit = find_habit(&mut store, &habit_key)?;
+                    let changed = if action == "done" {
+                        // Back-filled completions may predate the habit's creation.
+                        habit.created = habit.created.min(date);
+                        habit.done.insert(date)
+                    } else {
+                        habit.done.remove(&date)
+                    };
+                    let out = habit_json(habit, today);
+                    save_store(&workspace, &store)?;
+                    Ok(json!({"status": if changed { action.as_str() } else { "unchanged" }, "date": date, "habit": out}))
+                }
+                "list" => {
+                    let habits: Vec<Value> = store.habits.iter().map(|h| habit_json(h, today)).collect();
+                    Ok(json!({"habits": habits, "count": habits.len(), "today": today}))
+                }
+                "stats" => {
+                    let weeks = params["weeks"].as_i64().unwrap_or(4).clamp(1, 52);
+                    let habit = find_habit(&mut store, &habit_key)?;
+                    let history: Vec<Value> = (0..weeks)
+                        .map(|w| {
+                            let end = today - Duration::days(7 * w);
+                            json!({
+                                "week_ending": end,
+                                "done": habit.done.range(end - Duration::days(6)..=end).count(),
+                                "adherence": adherence(habit, end).map(|a| (a * 100.0).round()),
+                            })
+                        })
+                        .collect();
+                    let mut out = habit_json(habit, today);
+                    out["weekly"] = json!(history);
+                    Ok(out)
+                }
+                "pause" | "resume" => {
+                    let habit = find_habit(&mut store, &habit_key)?;
+                    habit.active = action == "resume";
+                    let out = habit_json(habit, today);
+                    save_store(&workspace, &store)?;
+                    Ok(out)
+                }
+                "remove" => {
+                    let id = find_habit(&mut store, &habit_key)?.id.clone();
+                    store.habits.retain(|h| h.id != id);
+                    save_store(&workspace, &store)?;
+                    Ok(json!({"status": "removed", "id": id}))
+                }
+                other => Err(Error::Tool(format!("Unknown action: {}", other))),
+            }
+        })
+        .await
+        .map_err(|e| Error::Tool(format!("Habits task failed: {}", e)))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn habit(schedule: HabitSchedule, created: &str, done: &[&str]) -> Habit {
+        Habit {
+            id: "habit_1".into(),
+            name: "Run".into(),
+            schedule,
+            checkin_time: None,
+            active: true,
+            created: d(created),
+            done: done.iter().map(|s| d(s)).collect(),
+            last_prompted: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_schedule() {
+        assert_eq!(HabitSchedule::parse("daily").unwrap(), HabitSchedule::Daily);
+        assert_eq!(
+            HabitSchedule::parse("Mon, wed,FRI").unwrap(),
+            HabitSchedule::Days {
+                days: vec![0, 2, 4]
+            }
+        );
+        assert_eq!(
+            HabitSchedule::parse("3x/week").unwrap(),
+            HabitSchedule::PerWeek { times: 3 }
+        );
+        assert_eq!(
+            HabitSchedule::parse("每周2次").unwrap(),
+            HabitSchedule::PerWeek { times: 2 }
+        );
+        assert!(HabitSchedule::parse("sometimes").is_err());
+    }
+
+    #[test]
+    fn test_streaks() {
+        // 2026-10-15 is a Thursday.
+        let today = d("2026-10-15");
+        let daily = habit(
+            HabitSchedule::Daily,
+            "2026-10-01",
+            &["2026-10-10", "2026-10-12", "2026-10-13", "2026-10-14"],
+        );
+        // Today still open: the streak counts up to yesterday.
+        assert_eq!(current_streak(&daily, today), 3);
+        assert_eq!(longest_streak(&daily, today), 3);
+
+        // Mon/Wed/Fri: Tuesday is not scheduled, so it does not break the run.
+        let mwf = habit(
+            HabitSchedule::Days {
+                days: vec![0, 2, 4],
+            },
+            "2026-10-01",
+            &["2026-10-09", "2026-10-12", "2026-10-14"],
+        );
+        assert_eq!(current_streak(&mwf, today), 3);
+
+        let weekly = habit(
+            HabitSchedule::PerWeek { times: 2 },
+            "2026-09-28",
+            &[
+                "2026-09-29",
+                "2026-10-01",
+                "2026-10-06",
+                "2026-10-08",
+                "2026-10-13",
+            ],
+        );
+        // This week has one of two so far; the two previous weeks met the target.
+        assert_eq!(current_streak(&weekly, today), 2);
+        assert!(open_today(&weekly, today));
+    }
+
+    #[test]
+    fn test_adherence() {
+        let today = d("2026-10-15");
+        let daily = habit(
+            HabitSchedule::Daily,
+            "2026-01-01",
+            &["2026-10-09", "2026-10-11", "2026-10-13", "2026-10-15"],
+        );
+        assert_eq!(
+            adherence(&daily, today).map(|a| (a * 7.0).round()),
+            Some(4.0)
+        );
+
+        let weekly = habit(
+            HabitSchedule::PerWeek { times: 3 },
+            "2026-01-01",
+            &["2026-10-14"],
+        );
+        assert_eq!(
+            adherence(&weekly, today).map(|a| (a * 3.0).round()),
+            Some(1.0)
+        );
+    }
+}
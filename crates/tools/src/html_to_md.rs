@@ -9,7 +9,7 @@
 //! - `content-signal`: content usage permissions (ai-train, search, ai-input)
 
 use blockcell_core::{Error, Result};
-use reqwest::{Client, Response};
+use reqwest::Response;
 use serde_json::{json, Value};
 
 /// Metadata extracted from a markdown-aware HTTP response.
@@ -54,9 +54,13 @@ impl MarkdownMeta {
 /// 2. If response is `text/markdown` → return as-is (server-side conversion)
 /// 3. If response is `text/html` → convert locally via `htmd`
 /// 4. Otherwise → return raw text
-pub async fn fetch_as_markdown(url: &str, max_chars: usize) -> Result<(String, MarkdownMeta)> {
-    let client = Client::builder()
-        .redirect(reqwest::redirect::Policy::limited(10))
+pub async fn fetch_as_markdown(
+    egress: &crate::egress::HttpClientFactory,
+    url: &str,
+    max_chars: usize,
+) -> Result<(String, MarkdownMeta)> {
+    let client = egress
+        .builder()
         .timeout(std::time::Duration::from_secs(30))
         .build()
         .map_err(|e| Error::Tool(format!("Failed to create HTTP client: {}", e)))?;
@@ -1,8 +1,8 @@
 use async_trait::async_trait;
 use blockcell_core::{Error, Result};
-use reqwest::Client;
 use serde_json::{json, Value};
 
+use crate::egress::{HttpClientFactory, MAX_REDIRECTS};
 use crate::{Tool, ToolContext, ToolSchema};
 
 fn parse_string_map(input: &str) -> Option<serde_json::Map<String, Value>> {
@@ -231,15 +231,11 @@ impl Tool for HttpRequestTool {
             .and_then(|v| v.as_u64())
             .unwrap_or(50000) as usize;
 
-        // Build client
-        let redirect_policy = if follow_redirects {
-            reqwest::redirect::Policy::limited(10)
-        } else {
-            reqwest::redirect::Policy::none()
-        };
-
-        let client = Client::builder()
-            .redirect(redirect_policy)
+        // Build client (egress policy checked up front and on every redirect hop)
+        let egress = HttpClientFactory::for_tool(&ctx.config, "http_request");
+        egress.check(url).await?;
+        let client = egress
+            .builder_with_redirects(if follow_redirects { MAX_REDIRECTS } else { 0 })
             .timeout(std::time::Duration::from_secs(timeout_secs))
             .build()
             .map_err(|e| Error::Tool(format!("Failed to create HTTP client: {}", e)))?;
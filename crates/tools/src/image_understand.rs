@@ -157,20 +157,7 @@ impl Tool for ImageUnderstandTool {
                 call_anthropic(&ctx, &system_prompt, &encoded_images, max_tokens).await?
             }
             "gemini" => call_gemini(&ctx, &system_prompt, &encoded_images, max_tokens).await?,
-            _ => {
-                // Try providers in order: openai → anthropic → gemini
-                if has_provider_key(&ctx, "openai") {
-                    call_openai(&ctx, &system_prompt, &encoded_images, &params, max_tokens).await?
-                } else if has_provider_key(&ctx, "anthropic") {
-                    call_anthropic(&ctx, &system_prompt, &encoded_images, max_tokens).await?
-                } else if has_provider_key(&ctx, "gemini") {
-                    call_gemini(&ctx, &system_prompt, &encoded_images, max_tokens).await?
-                } else {
-                    return Err(Error::Tool(
-                        "No vision API key found. Set OPENAI_API_KEY, ANTHROPIC_API_KEY, or GEMINI_API_KEY.".into()
-                    ));
-                }
-            }
+            _ => call_auto(&ctx, &system_prompt, &encoded_images, &params, max_tokens).await?,
         };
 
         info!(
@@ -193,7 +180,30 @@ impl Tool for ImageUnderstandTool {
     }
 }
 
-fn encode_image(path: &str) -> Result<(String, String)> {
+/// Call the first configured vision provider (openai → anthropic → gemini).
+/// With no images this is a plain text completion.
+pub(crate) async fn call_auto(
+    ctx: &ToolContext,
+    prompt: &str,
+    images: &[(String, String)],
+    params: &Value,
+    max_tokens: u32,
+) -> Result<(String, String, String)> {
+    if has_provider_key(ctx, "openai") {
+        call_openai(ctx, prompt, images, params, max_tokens).await
+    } else if has_provider_key(ctx, "anthropic") {
+        call_anthropic(ctx, prompt, images, max_tokens).await
+    } else if has_provider_key(ctx, "gemini") {
+        call_gemini(ctx, prompt, images, max_tokens).await
+    } else {
+        Err(Error::Tool(
+            "No vision API key found. Set OPENAI_API_KEY, ANTHROPIC_API_KEY, or GEMINI_API_KEY."
+                .into(),
+        ))
+    }
+}
+
+pub(crate) fn encode_image(path: &str) -> Result<(String, String)> {
     use base64::Engine;
     let bytes = std::fs::read(path)
         .map_err(|e| Error::Tool(format!("Failed to read image {}: {}", path, e)))?;
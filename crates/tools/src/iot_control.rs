@@ -0,0 +1,294 @@
+//! Control IoT devices over the shared MQTT connection (`iot.mqtt`).
+//!
+//! Commands are published to a topic, or to `topicPrefix + device[/subtopic]`.
+//! `request` waits for the device's reply on a response topic and `get_state` returns
+//! the first (usually retained) message on a state topic.
+
+use async_trait::async_trait;
+use blockcell_core::config::MqttConfig;
+use blockcell_core::{Error, Result};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use crate::mqtt::{self, qos_from};
+use crate::{PromptContext, Tool, ToolConcurrency, ToolContext, ToolSchema};
+
+const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 10;
+const DEFAULT_STATE_TIMEOUT_SECS: u64 = 5;
+const MAX_TIMEOUT_SECS: u64 = 120;
+
+pub struct IotControlTool;
+
+#[async_trait]
+impl Tool for IotControlTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "iot_control",
+            description: "Control IoT devices over MQTT (broker configured under iot.mqtt). \
+                Actions: 'publish' (send a command to a topic or device), 'request' (publish and wait for the device's reply on response_topic), \
+                'get_state' (read the current/retained message on a topic), 'status' (broker connection and active subscriptions). \
+                For continuous sensor data use stream_subscribe with protocol='mqtt'.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["publish", "request", "get_state", "status"]
+                    },
+                    "topic": {
+                        "type": "string",
+                        "description": "MQTT topic, e.g. 'zigbee2mqtt/living_room_lamp/set'. get_state accepts + and # wildcards"
+                    },
+                    "device": {
+                        "type": "string",
+                        "description": "Device name; the topic becomes iot.mqtt.topicPrefix + device (+ '/' + subtopic). Alternative to topic"
+                    },
+                    "subtopic": {
+                        "type": "string",
+                        "description": "(with device) Topic suffix, e.g. 'set' or 'cmnd/POWER'"
+                    },
+                    "payload": {
+                        "description": "(publish/request) Message body: a string, or a JSON object/array/number that is sent serialized"
+                    },
+                    "qos": {
+                        "type": "integer",
+                        "enum": [0, 1, 2],
+                        "description": "MQTT QoS. Default: iot.mqtt.defaultQos"
+                    },
+                    "retain": {
+                        "type": "boolean",
+                        "description": "(publish) Ask the broker to keep the message for new subscribers. Default: false"
+                    },
+                    "response_topic": {
+                        "type": "string",
+                        "description": "(request) Topic the device replies on"
+                    },
+                    "timeout_secs": {
+                        "type": "integer",
+                        "description": "(request/get_state) Seconds to wait for a message. Default: 10 for request, 5 for get_state"
+                    }
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        let action = params.get("action").and_then(|v| v.as_str()).unwrap_or("");
+        let has = |key: &str| {
+            params
+                .get(key)
+                .and_then(|v| v.as_str())
+                .is_some_and(|s| !s.trim().is_empty())
+        };
+        match action {
+            "status" => return Ok(()),
+            "publish" | "request" | "get_state" => {}
+            _ => return Err(Error::Validation(format!("Unknown action: {}", action))),
+        }
+        if !has("topic") && !has("device") {
+            return Err(Error::Validation(format!(
+                "'topic' or 'device' is required for {}",
+                action
+            )));
+        }
+        if action != "get_state" && params.get("payload").is_none_or(Value::is_null) {
+            return Err(Error::Validation(format!(
+                "'payload' is required for {}",
+                action
+            )));
+        }
+        if action == "request" && !has("response_topic") {
+            return Err(Error::Validation(
+                "'response_topic' is required for request".into(),
+            ));
+        }
+        if let Some(qos) = params.get("qos").and_then(|v| v.as_u64()) {
+            qos_from(qos.min(u8::MAX as u64) as u8)?;
+        }
+        Ok(())
+    }
+
+    fn prompt_rule(&self, _ctx: &PromptContext) -> Option<String> {
+        Some(
+            "- Device commands (lights, plugs, thermostats on MQTT) go through `iot_control`; prefer `request` or `get_state` afterwards to confirm the device actually changed."
+                .to_string(),
+        )
+    }
+
+    /// Overlapping `request` calls on one response topic would take each other's replies,
+    /// and two commands to a device must not interleave.
+    fn concurrency(&self) -> ToolConcurrency {
+        ToolConcurrency::exclusive(MAX_TIMEOUT_SECS)
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        let cfg = &ctx.config.iot.mqtt;
+        let action = params["action"].as_str().unwrap_or("");
+        let hub = mqtt::hub(cfg).await?;
+        if action == "status" {
+            return Ok(hub.status().await);
+        }
+
+        let topic = resolve_topic(cfg, &params);
+        let qos = qos_from(
+            params
+                .get("qos")
+                .and_then(|v| v.as_u64())
+                .map(|q| q.min(u8::MAX as u64) as u8)
+                .unwrap_or(cfg.default_qos),
+        )?;
+        let timeout = |default: u64| {
+            Duration::from_secs(
+                params
+                    .get("timeout_secs")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(default)
+                    .clamp(1, MAX_TIMEOUT_SECS),
+            )
+        };
+
+        match action {
+            "publish" => {
+                let retain = params
+                    .get("retain")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                let payload = payload_bytes(&params["payload"]);
+                let size = payload.len();
+                hub.publish(&topic, payload, qos, retain).await?;
+                Ok(json!({
+                    "published": true,
+                    "topic": topic,
+                    "qos": qos as u8,
+                    "retain": retain,
+                    "bytes": size,
+                    "connected": hub.is_connected(),
+                }))
+            }
+            "request" => {
+                let response_topic = params["response_topic"].as_str().unwrap_or("").trim();
+                // Subscribe first so a fast reply isn't missed.
+                let (listener, mut rx) = hub.subscribe(response_topic, qos).await?;
+                let sent = hub
+                    .publish(&topic, payload_bytes(&params["payload"]), qos, false)
+                    .await;
+                let reply = match sent {
+                    Ok(()) => {
+                        tokio::time::timeout(timeout(DEFAULT_REQUEST_TIMEOUT_SECS), rx.recv())
+                            .await
+                            .ok()
+                            .flatten()
+                    }
+                    Err(e) => {
+                        hub.unsubscribe(listener).await;
+                        return Err(e);
+                    }
+                };
+                hub.unsubscribe(listener).await;
+                match reply {
+                    Some(message) => Ok(json!({
+                        "topic": topic,
+                        "response": message.to_json(),
+                    })),
+                    None => Err(Error::Tool(format!(
+                        "No reply on '{}' within {}s (command was sent to '{}')",
+                        response_topic,
+                        timeout(DEFAULT_REQUEST_TIMEOUT_SECS).as_secs(),
+                        topic
+                    ))),
+                }
+            }
+            "get_state" => {
+                let (listener, mut rx) = hub.subscribe(&topic, qos).await?;
+                let wait = timeout(DEFAULT_STATE_TIMEOUT_SECS);
+                let message = tokio::time::timeout(wait, rx.recv()).await.ok().flatten();
+                hub.unsubscribe(listener).await;
+                match message {
+                    Some(message) => Ok(message.to_json()),
+                    None => Err(Error::Tool(format!(
+                        "No message on '{}' within {}s; the device may not publish a retained state",
+                        topic,
+                        wait.as_secs()
+                    ))),
+                }
+            }
+            _ => Err(Error::Tool(format!("Unknown action: {}", action))),
+        }
+    }
+}
+
+/// `topic`, or `topicPrefix + device[/subtopic]`.
+fn resolve_topic(cfg: &MqttConfig, params: &Value) -> String {
+    let text = |key: &str| {
+        params
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+    };
+    if let Some(topic) = text("topic") {
+        return topic.to_string();
+    }
+    let mut topic = format!("{}{}", cfg.topic_prefix, text("device").unwrap_or_default());
+    if let Some(subtopic) = text("subtopic") {
+        if !topic.ends_with('/') {
+            topic.push('/');
+        }
+        topic.push_str(subtopic.trim_start_matches('/'));
+    }
+    topic
+}
+
+/// Strings are sent as-is, anything else as JSON.
+fn payload_bytes(payload: &Value) -> Vec<u8> {
+    match payload {
+        Value::String(s) => s.as_bytes().to_vec(),
+        other => other.to_string().into_bytes(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let tool = IotControlTool;
+        assert!(tool.validate(&json!({"action": "status"})).is_ok());
+        assert!(tool
+            .validate(&json!({"action": "publish", "device": "lamp", "payload": {"state": "ON"}}))
+            .is_ok());
+        assert!(tool
+            .validate(&json!({"action": "publish", "topic": "home/lamp"}))
+            .is_err());
+        assert!(tool
+            .validate(&json!({"action": "request", "topic": "home/lamp", "payload": "ON"}))
+            .is_err());
+        assert!(tool
+            .validate(&json!({"action": "get_state", "topic": "home/lamp", "qos": 3}))
+            .is_err());
+        assert!(tool.validate(&json!({"action": "blink"})).is_err());
+    }
+
+    #[test]
+    fn test_resolve_topic_and_payload() {
+        let cfg = MqttConfig {
+            topic_prefix: "zigbee2mqtt/".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            resolve_topic(&cfg, &json!({"device": "lamp", "subtopic": "set"})),
+            "zigbee2mqtt/lamp/set"
+        );
+        assert_eq!(
+            resolve_topic(&cfg, &json!({"topic": "cmnd/plug/POWER", "device": "lamp"})),
+            "cmnd/plug/POWER"
+        );
+        assert_eq!(payload_bytes(&json!("ON")), b"ON".to_vec());
+        assert_eq!(
+            payload_bytes(&json!({"state": "ON"})),
+            br#"{"state":"ON"}"#.to_vec()
+        );
+    }
+}
@@ -0,0 +1,1077 @@
+use async_trait::async_trait;
+use blockcell_core::config::KubernetesConfig;
+use blockcell_core::{Config, Error, Result};
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
+use k8s_openapi::api::batch::v1::Job;
+use k8s_openapi::api::core::v1::{Event, Namespace, Node, Pod, Service};
+use k8s_openapi::NamespaceResourceScope;
+use kube::api::{Api, ListParams, LogParams, Patch, PatchParams};
+use kube::config::{KubeConfigOptions, Kubeconfig};
+use kube::Client;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fmt::Debug;
+use tracing::info;
+
+use crate::{Tool, ToolContext, ToolSchema};
+
+const DEFAULT_LIST_LIMIT: u32 = 100;
+const DEFAULT_LOG_TAIL: u32 = 200;
+/// Cap on bytes pulled for one `logs` call, independent of the line tail.
+const MAX_LOG_BYTES: i64 = 1024 * 1024;
+const MUTATING_ACTIONS: &[&str] = &["scale", "rollout_restart"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Pod,
+    Deployment,
+    StatefulSet,
+    DaemonSet,
+    Job,
+    Service,
+    Node,
+    Namespace,
+    Event,
+}
+
+impl Kind {
+    fn parse(s: &str) -> Option<Self> {
+        Some(match s.trim().to_lowercase().as_str() {
+            "pod" | "pods" | "po" => Self::Pod,
+            "deployment" | "deployments" | "deploy" => Self::Deployment,
+            "statefulset" | "statefulsets" | "sts" => Self::StatefulSet,
+            "daemonset" | "daemonsets" | "ds" => Self::DaemonSet,
+            "job" | "jobs" => Self::Job,
+            "service" | "services" | "svc" => Self::Service,
+            "node" | "nodes" | "no" => Self::Node,
+            "namespace" | "namespaces" | "ns" => Self::Namespace,
+            "event" | "events" | "ev" => Self::Event,
+            _ => return None,
+        })
+    }
+
+    /// The `kind` string used in `involvedObject.kind`.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Pod => "Pod",
+            Self::Deployment => "Deployment",
+            Self::StatefulSet => "StatefulSet",
+            Self::DaemonSet => "DaemonSet",
+            Self::Job => "Job",
+            Self::Service => "Service",
+            Self::Node => "Node",
+            Self::Namespace => "Namespace",
+            Self::Event => "Event",
+        }
+    }
+
+    fn cluster_scoped(self) -> bool {
+        matches!(self, Self::Node | Self::Namespace)
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Summaries (operate on the serialized objects so they're kind-agnostic)
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// kubectl-style age: `45s`, `12m`, `5h`, `3d4h`.
+fn age(created: &Value, now: DateTime<Utc>) -> String {
+    let Some(ts) = created
+        .as_str()
+        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+    else {
+        return String::new();
+    };
+    let secs = (now - ts.with_timezone(&Utc)).num_seconds().max(0);
+    match secs {
+        s if s < 120 => format!("{}s", s),
+        s if s < 3600 * 2 => format!("{}m", s / 60),
+        s if s < 86400 * 2 => format!("{}h", s / 3600),
+        s => {
+            let hours = (s % 86400) / 3600;
+            if hours > 0 && s < 86400 * 8 {
+                format!("{}d{}h", s / 86400, hours)
+            } else {
+                format!("{}d", s / 86400)
+            }
+        }
+    }
+}
+
+fn images(pod_spec: &Value) -> Vec<String> {
+    pod_spec["containers"]
+        .as_array()
+        .map(|cs| {
+            cs.iter()
+                .filter_map(|c| c["image"].as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn pod_summary(p: &Value, now: DateTime<Utc>) -> Value {
+    let statuses = p["status"]["containerStatuses"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let total = p["spec"]["containers"]
+        .as_array()
+        .map(|c| c.len())
+        .unwrap_or(statuses.len());
+    let ready = statuses
+        .iter()
+        .filter(|s| s["ready"].as_bool() == Some(true))
+        .count();
+    let restarts: u64 = statuses
+        .iter()
+        .map(|s| s["restartCount"].as_u64().unwrap_or(0))
+        .sum();
+    let waiting = statuses.iter().find_map(|s| {
+        s["state"]["waiting"]["reason"]
+            .as_str()
+            .or_else(|| s["state"]["terminated"]["reason"].as_str())
+    });
+    let status = if !p["metadata"]["deletionTimestamp"].is_null() {
+        "Terminating"
+    } else {
+        waiting.unwrap_or_else(|| p["status"]["phase"].as_str().unwrap_or("Unknown"))
+    };
+    json!({
+        "name": p["metadata"]["name"],
+        "namespace": p["metadata"]["namespace"],
+        "ready": format!("{}/{}", ready, total),
+        "status": status,
+        "restarts": restarts,
+        "node": p["spec"]["nodeName"],
+        "ip": p["status"]["podIP"],
+        "age": age(&p["metadata"]["creationTimestamp"], now),
+    })
+}
+
+fn workload_summary(kind: Kind, w: &Value, now: DateTime<Utc>) -> Value {
+    let spec = &w["spec"];
+    let status = &w["status"];
+    let n = |v: &Value| v.as_u64().unwrap_or(0);
+    let mut out = json!({
+        "name": w["metadata"]["name"],
+        "namespace": w["metadata"]["namespace"],
+        "age": age(&w["metadata"]["creationTimestamp"], now),
+        "images": images(&spec["template"]["spec"]),
+    });
+    match kind {
+        Kind::Deployment => {
+            out["ready"] = json!(format!(
+                "{}/{}",
+                n(&status["readyReplicas"]),
+                n(&spec["replicas"])
+            ));
+            out["up_to_date"] = json!(n(&status["updatedReplicas"]));
+            out["available"] = json!(n(&status["availableReplicas"]));
+        }
+        Kind::StatefulSet => {
+            out["ready"] = json!(format!(
+                "{}/{}",
+                n(&status["readyReplicas"]),
+                n(&spec["replicas"])
+            ));
+        }
+        Kind::DaemonSet => {
+            out["desired"] = json!(n(&status["desiredNumberScheduled"]));
+            out["ready"] = json!(n(&status["numberReady"]));
+            out["available"] = json!(n(&status["numberAvailable"]));
+        }
+        Kind::Job => {
+            let completions = spec["completions"].as_u64().unwrap_or(1);
+            out["completions"] = json!(format!("{}/{}", n(&status["succeeded"]), completions));
+            out["active"] = json!(n(&status["active"]));
+            out["failed"] = json!(n(&status["failed"]));
+        }
+        _ => {}
+    }
+    out
+}
+
+fn node_summary(node: &Value, now: DateTime<Utc>) -> Value {
+    let ready = node["status"]["conditions"]
+        .as_array()
+        .and_then(|cs| cs.iter().find(|c| c["type"] == "Ready"))
+        .map(|c| c["status"] == "True")
+        .unwrap_or(false);
+    let mut status = if ready { "Ready" } else { "NotReady" }.to_string();
+    if node["spec"]["unschedulable"].as_bool() == Some(true) {
+        status.push_str(",SchedulingDisabled");
+    }
+    let pressure: Vec<&str> = node["status"]["conditions"]
+        .as_array()
+        .map(|cs| {
+            cs.iter()
+                .filter(|c| c["type"] != "Ready" && c["status"] == "True")
+                .filter_map(|c| c["type"].as_str())
+                .collect()
+        })
+        .unwrap_or_default();
+    let roles: Vec<&str> = node["metadata"]["labels"]
+        .as_object()
+        .map(|labels| {
+            labels
+                .keys()
+                .filter_map(|k| k.strip_prefix("node-role.kubernetes.io/"))
+                .collect()
+        })
+        .unwrap_or_default();
+    json!({
+        "name": node["metadata"]["name"],
+        "status": status,
+        "conditions": pressure,
+        "roles": roles,
+        "version": node["status"]["nodeInfo"]["kubeletVersion"],
+        "cpu": node["status"]["capacity"]["cpu"],
+        "memory": node["status"]["capacity"]["memory"],
+        "age": age(&node["metadata"]["creationTimestamp"], now),
+    })
+}
+
+fn service_summary(svc: &Value, now: DateTime<Utc>) -> Value {
+    let ports: Vec<String> = svc["spec"]["ports"]
+        .as_array()
+        .map(|ps| {
+            ps.iter()
+                .map(|p| {
+                    let proto = p["protocol"].as_str().unwrap_or("TCP");
+                    match p["nodePort"].as_u64() {
+                        Some(np) => format!("{}:{}/{}", p["port"], np, proto),
+                        None => format!("{}/{}", p["port"], proto),
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    json!({
+        "name": svc["metadata"]["name"],
+        "namespace": svc["metadata"]["namespace"],
+        "type": svc["spec"]["type"],
+        "cluster_ip": svc["spec"]["clusterIP"],
+        "external": svc["status"]["loadBalancer"]["ingress"],
+        "ports": ports,
+        "age": age(&svc["metadata"]["creationTimestamp"], now),
+    })
+}
+
+fn event_summary(ev: &Value, now: DateTime<Utc>) -> Value {
+    let last = [
+        &ev["lastTimestamp"],
+        &ev["eventTime"],
+        &ev["metadata"]["creationTimestamp"],
+    ]
+    .into_iter()
+    .find(|v| v.is_string())
+    .cloned()
+    .unwrap_or(Value::Null);
+    json!({
+        "type": ev["type"],
+        "reason": ev["reason"],
+        "object": format!(
+            "{}/{}",
+            ev["involvedObject"]["kind"].as_str().unwrap_or(""),
+            ev["involvedObject"]["name"].as_str().unwrap_or("")
+        ),
+        "message": ev["message"],
+        "count": ev["count"],
+        "last_seen": age(&last, now),
+        "timestamp": last,
+    })
+}
+
+fn summarize(kind: Kind, v: &Value, now: DateTime<Utc>) -> Value {
+    match kind {
+        Kind::Pod => pod_summary(v, now),
+        Kind::Deployment | Kind::StatefulSet | Kind::DaemonSet | Kind::Job => {
+            workload_summary(kind, v, now)
+        }
+        Kind::Node => node_summary(v, now),
+        Kind::Service => service_summary(v, now),
+        Kind::Event => event_summary(v, now),
+        Kind::Namespace => json!({
+            "name": v["metadata"]["name"],
+            "status": v["status"]["phase"],
+            "age": age(&v["metadata"]["creationTimestamp"], now),
+        }),
+    }
+}
+
+/// Drop bulky bookkeeping fields before returning a full object.
+fn trim_object(mut v: Value) -> Value {
+    if let Some(meta) = v.get_mut("metadata").and_then(|m| m.as_object_mut()) {
+        meta.remove("managedFields");
+        if let Some(ann) = meta.get_mut("annotations").and_then(|a| a.as_object_mut()) {
+            ann.remove("kubectl.kubernetes.io/last-applied-configuration");
+        }
+    }
+    v
+}
+
+/// Sort events newest first by their effective timestamp.
+fn sort_events(events: &mut [Value]) {
+    events.sort_by(|a, b| {
+        b["timestamp"]
+            .as_str()
+            .unwrap_or("")
+            .cmp(a["timestamp"].as_str().unwrap_or(""))
+    });
+}
+
+/// Deployment rollout state in the terms `kubectl rollout status` uses.
+fn rollout_state(d: &Value) -> (bool, String) {
+    let n = |v: &Value| v.as_u64().unwrap_or(0);
+    let desired = d["spec"]["replicas"].as_u64().unwrap_or(1);
+    let status = &d["status"];
+    if n(&d["metadata"]["generation"]) > n(&status["observedGeneration"]) {
+        return (
+            false,
+            "Waiting for the rollout spec update to be observed".into(),
+        );
+    }
+    let updated = n(&status["updatedReplicas"]);
+    let available = n(&status["availableReplicas"]);
+    let total = n(&status["replicas"]);
+    if updated < desired {
+        (
+            false,
+            format!("{} of {} new replicas updated", updated, desired),
+        )
+    } else if total > updated {
+        (
+            false,
+            format!("{} old replicas pending termination", total - updated),
+        )
+    } else if available < updated {
+        (
+            false,
+            format!("{} of {} updated replicas available", available, updated),
+        )
+    } else {
+        (true, "Rollout complete".into())
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// API access
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn kube_err(e: kube::Error) -> Error {
+    match &e {
+        kube::Error::Api(resp) if resp.code == 404 => Error::NotFound(resp.message.clone()),
+        kube::Error::Api(resp) if resp.code == 403 => {
+            Error::PermissionDenied(format!("Kubernetes RBAC: {}", resp.message))
+        }
+        _ => Error::Tool(format!("Kubernetes API error: {}", e)),
+    }
+}
+
+async fn client(cfg: &KubernetesConfig, context: Option<&str>) -> Result<Client> {
+    let context = context.map(String::from).or_else(|| cfg.context.clone());
+    let config = if cfg.kubeconfig.trim().is_empty() && context.is_none() {
+        kube::Config::infer()
+            .await
+            .map_err(|e| Error::Config(format!("No usable kubeconfig: {}", e)))?
+    } else {
+        let kubeconfig = if cfg.kubeconfig.trim().is_empty() {
+            Kubeconfig::read()
+        } else {
+            Kubeconfig::read_from(cfg.kubeconfig.trim())
+        }
+        .map_err(|e| Error::Config(format!("Failed to read kubeconfig: {}", e)))?;
+        let options = KubeConfigOptions {
+            context,
+            ..Default::default()
+        };
+        kube::Config::from_custom_kubeconfig(kubeconfig, &options)
+            .await
+            .map_err(|e| Error::Config(format!("Invalid kubeconfig context: {}", e)))?
+    };
+    Client::try_from(config).map_err(kube_err)
+}
+
+fn namespaced<K>(client: Client, namespace: Option<&str>) -> Api<K>
+where
+    K: kube::Resource<Scope = NamespaceResourceScope>,
+    <K as kube::Resource>::DynamicType: Default,
+{
+    match namespace {
+        Some(ns) => Api::namespaced(client, ns),
+        None => Api::all(client),
+    }
+}
+
+async fn list_values<K>(api: Api<K>, lp: &ListParams) -> Result<(Vec<Value>, Option<String>)>
+where
+    K: Clone + DeserializeOwned + Debug + Serialize,
+{
+    let list = api.list(lp).await.map_err(kube_err)?;
+    let token = list.metadata.continue_.clone().filter(|t| !t.is_empty());
+    let items = list
+        .items
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok((items, token))
+}
+
+async fn get_value<K>(api: Api<K>, name: &str) -> Result<Value>
+where
+    K: Clone + DeserializeOwned + Debug + Serialize,
+{
+    let obj = api.get(name).await.map_err(kube_err)?;
+    Ok(serde_json::to_value(obj)?)
+}
+
+/// List any supported kind; `namespace = None` means all namespaces.
+async fn list_kind(
+    client: Client,
+    kind: Kind,
+    namespace: Option<&str>,
+    lp: &ListParams,
+) -> Result<(Vec<Value>, Option<String>)> {
+    match kind {
+        Kind::Pod => list_values(namespaced::<Pod>(client, namespace), lp).await,
+        Kind::Deployment => list_values(namespaced::<Deployment>(client, namespace), lp).await,
+        Kind::StatefulSet => list_values(namespaced::<StatefulSet>(client, namespace), lp).await,
+        Kind::DaemonSet => list_values(namespaced::<DaemonSet>(client, namespace), lp).await,
+        Kind::Job => list_values(namespaced::<Job>(client, namespace), lp).await,
+        Kind::Service => list_values(namespaced::<Service>(client, namespace), lp).await,
+        Kind::Event => list_values(namespaced::<Event>(client, namespace), lp).await,
+        Kind::Node => list_values(Api::<Node>::all(client), lp).await,
+        Kind::Namespace => list_values(Api::<Namespace>::all(client), lp).await,
+    }
+}
+
+async fn get_kind(client: Client, kind: Kind, namespace: &str, name: &str) -> Result<Value> {
+    let ns = Some(namespace);
+    match kind {
+        Kind::Pod => get_value(namespaced::<Pod>(client, ns), name).await,
+        Kind::Deployment => get_value(namespaced::<Deployment>(client, ns), name).await,
+        Kind::StatefulSet => get_value(namespaced::<StatefulSet>(client, ns), name).await,
+        Kind::DaemonSet => get_value(namespaced::<DaemonSet>(client, ns), name).await,
+        Kind::Job => get_value(namespaced::<Job>(client, ns), name).await,
+        Kind::Service => get_value(namespaced::<Service>(client, ns), name).await,
+        Kind::Event => get_value(namespaced::<Event>(client, ns), name).await,
+        Kind::Node => get_value(Api::<Node>::all(client), name).await,
+        Kind::Namespace => get_value(Api::<Namespace>::all(client), name).await,
+    }
+}
+
+async fn events_for(
+    client: Client,
+    namespace: Option<&str>,
+    kind: Option<Kind>,
+    name: Option<&str>,
+    limit: u32,
+) -> Result<Vec<Value>> {
+    let mut fields = Vec::new();
+    if let Some(n) = name {
+        fields.push(format!("involvedObject.name={}", n));
+    }
+    if let Some(k) = kind {
+        fields.push(format!("involvedObject.kind={}", k.name()));
+    }
+    let mut lp = ListParams::default();
+    if !fields.is_empty() {
+        lp = lp.fields(&fields.join(","));
+    }
+    let (items, _) = list_values(namespaced::<Event>(client, namespace), &lp).await?;
+    let now = Utc::now();
+    let mut events: Vec<Value> = items.iter().map(|e| event_summary(e, now)).collect();
+    sort_events(&mut events);
+    events.truncate(limit as usize);
+    Ok(events)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tool
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn str_param<'a>(params: &'a Value, key: &str) -> Option<&'a str> {
+    params
+        .get(key)
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+}
+
+/// DNS-1123 subdomain names, which covers every kind this tool touches.
+fn valid_name(s: &str) -> bool {
+    !s.is_empty()
+        && s.len() <= 253
+        && s.chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '.'))
+}
+
+/// Resolve the namespace for a call, enforcing `allowedNamespaces`.
+/// Returns `None` for all-namespace queries.
+fn resolve_namespace<'a>(cfg: &'a KubernetesConfig, params: &'a Value) -> Result<Option<&'a str>> {
+    let all = params.get("all_namespaces").and_then(|v| v.as_bool()) == Some(true);
+    if all {
+        if !cfg.allowed_namespaces.is_empty() {
+            return Err(Error::PermissionDenied(
+                "all_namespaces is disabled because tools.kubernetes.allowedNamespaces is set"
+                    .to_string(),
+            ));
+        }
+        return Ok(None);
+    }
+    let ns = str_param(params, "namespace").unwrap_or(cfg.default_namespace.as_str());
+    if !valid_name(ns) {
+        return Err(Error::Validation(format!("Invalid namespace '{}'", ns)));
+    }
+    if !cfg.allowed_namespaces.is_empty() && !cfg.allowed_namespaces.iter().any(|a| a == ns) {
+        return Err(Error::PermissionDenied(format!(
+            "Namespace '{}' is not in tools.kubernetes.allowedNamespaces {:?}",
+            ns, cfg.allowed_namespaces
+        )));
+    }
+    Ok(Some(ns))
+}
+
+fn kind_param(params: &Value) -> Result<Kind> {
+    let raw = str_param(params, "kind").unwrap_or("");
+    Kind::parse(raw).ok_or_else(|| {
+        Error::Validation(format!(
+            "Unsupported kind '{}'. Use pods, deployments, statefulsets, daemonsets, jobs, services, nodes, namespaces or events",
+            raw
+        ))
+    })
+}
+
+pub struct KubectlTool;
+
+#[async_trait]
+impl Tool for KubectlTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "kubectl",
+            description: "Read-only Kubernetes observability through the configured kubeconfig. \
+                Actions: 'list_contexts', 'list' (pods/deployments/statefulsets/daemonsets/jobs/services/nodes/namespaces/events with label/field selectors), \
+                'describe' (one object plus its recent events), 'events' (newest first), 'logs' (pod logs with tail limit), \
+                'rollout_status' (deployment). Mutations 'scale' and 'rollout_restart' only when tools.kubernetes.allowMutations is enabled, and always need confirmation.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["list_contexts", "list", "describe", "events", "logs", "rollout_status", "scale", "rollout_restart"],
+                        "description": "Action to perform"
+                    },
+                    "context": {
+                        "type": "string",
+                        "description": "kubeconfig context. Default: tools.kubernetes.context or the current context"
+                    },
+                    "kind": {
+                        "type": "string",
+                        "description": "(list/describe/events/scale/rollout_restart) Resource kind, e.g. pods, deploy, sts, ds, svc, nodes"
+                    },
+                    "name": {
+                        "type": "string",
+                        "description": "Object name (describe/rollout_status/scale/rollout_restart; events: filter by object)"
+                    },
+                    "namespace": {
+                        "type": "string",
+                        "description": "Namespace. Default: tools.kubernetes.defaultNamespace"
+                    },
+                    "all_namespaces": {
+                        "type": "boolean",
+                        "description": "(list/events) Query every namespace"
+                    },
+                    "label_selector": {
+                        "type": "string",
+                        "description": "(list) e.g. 'app=web,tier!=cache'"
+                    },
+                    "field_selector": {
+                        "type": "string",
+                        "description": "(list) e.g. 'status.phase!=Running'"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "(list/events) Max items. Default 100"
+                    },
+                    "continue": {
+                        "type": "string",
+                        "description": "(list) Continue token from the previous page"
+                    },
+                    "pod": {
+                        "type": "string",
+                        "description": "(logs) Pod name"
+                    },
+                    "container": {
+                        "type": "string",
+                        "description": "(logs) Container name, required for multi-container pods"
+                    },
+                    "tail": {
+                        "type": "integer",
+                        "description": "(logs) Lines from the end. Default 200, capped by tools.kubernetes.maxLogLines"
+                    },
+                    "since_seconds": {
+                        "type": "integer",
+                        "description": "(logs) Only logs newer than this many seconds"
+                    },
+                    "previous": {
+                        "type": "boolean",
+                        "description": "(logs) Logs of the previous (crashed) container instance"
+                    },
+                    "replicas": {
+                        "type": "integer",
+                        "description": "(scale) Desired replica count"
+                    }
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    fn prompt_rule(&self, _ctx: &crate::PromptContext) -> Option<String> {
+        Some("- **Kubernetes (kubectl)**: 排查集群问题时先用 action='list'（kind=pods，可加 field_selector='status.phase!=Running'）和 'events' 找异常，再对具体对象 'describe'，崩溃的容器用 'logs' 加 `previous: true`。默认只读；scale / rollout_restart 需要配置允许并经用户确认，只在用户明确要求时使用。".to_string())
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        let action = params.get("action").and_then(|v| v.as_str()).unwrap_or("");
+        let need_name = |what: &str| -> Result<()> {
+            match str_param(params, what) {
+                Some(n) if valid_name(n) => Ok(()),
+                Some(n) => Err(Error::Validation(format!("Invalid {} '{}'", what, n))),
+                None => Err(Error::Validation(format!("{} requires '{}'", action, what))),
+            }
+        };
+        match action {
+            "list_contexts" => {}
+            "list" => {
+                kind_param(params)?;
+            }
+            "describe" => {
+                kind_param(params)?;
+                need_name("name")?;
+            }
+            "events" => {
+                if str_param(params, "kind").is_some() {
+                    kind_param(params)?;
+                }
+                if str_param(params, "name").is_some() {
+                    need_name("name")?;
+                }
+            }
+            "logs" => need_name("pod")?,
+            "rollout_status" => need_name("name")?,
+            "scale" | "rollout_restart" => {
+                let kind = kind_param(params)?;
+                let ok = match action {
+                    "scale" => matches!(kind, Kind::Deployment | Kind::StatefulSet),
+                    _ => matches!(kind, Kind::Deployment | Kind::StatefulSet | Kind::DaemonSet),
+                };
+                if !ok {
+                    return Err(Error::Validation(format!(
+                        "{} does not support kind '{}'",
+                        action,
+                        kind.name()
+                    )));
+                }
+                need_name("name")?;
+                if action == "scale" && params.get("replicas").and_then(|v| v.as_u64()).is_none() {
+                    return Err(Error::Validation(
+                        "scale requires 'replicas' (integer ≥ 0)".to_string(),
+                    ));
+                }
+            }
+            "" => return Err(Error::Validation("Missing 'action'".to_string())),
+            _ => return Err(Error::Validation(format!("Unknown action: {}", action))),
+        }
+        Ok(())
+    }
+
+    fn confirmation_items(&self, config: &Config, params: &Value) -> Vec<String> {
+        let action = params.get("action").and_then(|v| v.as_str()).unwrap_or("");
+        if !config.tools.kubernetes.allow_mutations || !MUTATING_ACTIONS.contains(&action) {
+            return Vec::new();
+        }
+        let ns = str_param(params, "namespace")
+            .unwrap_or(config.tools.kubernetes.default_namespace.as_str());
+        let target = format!(
+            "{}/{} -n {}",
+            str_param(params, "kind").unwrap_or(""),
+            str_param(params, "name").unwrap_or(""),
+            ns
+        );
+        match action {
+            "scale" => vec![format!(
+                "kubectl scale {} --replicas={}",
+                target,
+                params["replicas"].as_u64().unwrap_or(0)
+            )],
+            _ => vec![format!("kubectl rollout restart {}", target)],
+        }
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        let cfg = &ctx.config.tools.kubernetes;
+        let action = params["action"].as_str().unwrap_or("");
+
+        if action == "list_contexts" {
+            let kubeconfig = if cfg.kubeconfig.trim().is_empty() {
+                Kubeconfig::read()
+            } else {
+                Kubeconfig::read_from(cfg.kubeconfig.trim())
+            }
+            .map_err(|e| Error::Config(format!("Failed to read kubeconfig: {}", e)))?;
+            let contexts: Vec<Value> = kubeconfig
+                .contexts
+                .iter()
+                .map(|c| {
+                    let inner = c.context.as_ref();
+                    json!({
+                        "name": c.name,
+                        "cluster": inner.map(|i| i.cluster.clone()),
+                        "namespace": inner.and_then(|i| i.namespace.clone()),
+                    })
+                })
+                .collect();
+            return Ok(json!({
+                "current_context": kubeconfig.current_context,
+                "configured_context": cfg.context,
+                "contexts": contexts,
+            }));
+        }
+
+        if MUTATING_ACTIONS.contains(&action) && !cfg.allow_mutations {
+            return Err(Error::PermissionDenied(
+                "kubectl is read-only. Set tools.kubernetes.allowMutations: true to enable scale/rollout_restart"
+                    .to_string(),
+            ));
+        }
+
+        let client = client(cfg, str_param(&params, "context")).await?;
+        let now = Utc::now();
+        match action {
+            "list" => {
+                let kind = kind_param(&params)?;
+                let namespace = if kind.cluster_scoped() {
+                    None
+                } else {
+                    resolve_namespace(cfg, &params)?
+                };
+                let limit = params
+                    .get("limit")
+                    .and_then(|v| v.as_u64())
+                    .map(|n| n.clamp(1, 1000) as u32)
+                    .unwrap_or(DEFAULT_LIST_LIMIT);
+                let mut lp = ListParams::default().limit(limit);
+                if let Some(sel) = str_param(&params, "label_selector") {
+                    lp = lp.labels(sel);
+                }
+                if let Some(sel) = str_param(&params, "field_selector") {
+                    lp = lp.fields(sel);
+                }
+                if let Some(token) = str_param(&params, "continue") {
+                    lp = lp.continue_token(token);
+                }
+                let (items, token) = list_kind(client, kind, namespace, &lp).await?;
+                let mut rows: Vec<Value> = items.iter().map(|v| summarize(kind, v, now)).collect();
+                if kind == Kind::Event {
+                    sort_events(&mut rows);
+                }
+                Ok(json!({
+                    "kind": kind.name(),
+                    "namespace": namespace,
+                    "count": rows.len(),
+                    "items": rows,
+                    "continue": token,
+                }))
+            }
+            "describe" => {
+                let kind = kind_param(&params)?;
+                let name = str_param(&params, "name").unwrap_or("");
+                let namespace = if kind.cluster_scoped() {
+                    None
+                } else {
+                    resolve_namespace(cfg, &params)?
+                };
+                let obj = get_kind(
+                    client.clone(),
+                    kind,
+                    namespace.unwrap_or(cfg.default_namespace.as_str()),
+                    name,
+                )
+                .await?;
+                let events = events_for(client, namespace, Some(kind), Some(name), 20)
+                    .await
+                    .unwrap_or_default();
+                Ok(json!({
+                    "kind": kind.name(),
+                    "summary": summarize(kind, &obj, now),
+                    "object": trim_object(obj),
+                    "events": events,
+                }))
+            }
+            "events" => {
+                let namespace = resolve_namespace(cfg, &params)?;
+                let kind = str_param(&params, "kind").and_then(Kind::parse);
+                let limit = params
+                    .get("limit")
+                    .and_then(|v| v.as_u64())
+                    .map(|n| n.clamp(1, 1000) as u32)
+                    .unwrap_or(DEFAULT_LIST_LIMIT);
+                let events =
+                    events_for(client, namespace, kind, str_param(&params, "name"), limit).await?;
+                let warnings = events.iter().filter(|e| e["type"] == "Warning").count();
+                Ok(json!({
+                    "namespace": namespace,
+                    "count": events.len(),
+                    "warnings": warnings,
+                    "events": events,
+                }))
+            }
+            "logs" => {
+                let namespace = resolve_namespace(cfg, &params)?
+                    .unwrap_or(cfg.default_namespace.as_str())
+                    .to_string();
+                let pod = str_param(&params, "pod").unwrap_or("");
+                let tail = params
+                    .get("tail")
+                    .and_then(|v| v.as_u64())
+                    .map(|n| n as u32)
+                    .unwrap_or(DEFAULT_LOG_TAIL)
+                    .clamp(1, cfg.max_log_lines.max(1));
+                let lp = LogParams {
+                    container: str_param(&params, "container").map(String::from),
+                    tail_lines: Some(tail as i64),
+                    since_seconds: params.get("since_seconds").and_then(|v| v.as_i64()),
+                    previous: params.get("previous").and_then(|v| v.as_bool()) == Some(true),
+                    limit_bytes: Some(MAX_LOG_BYTES),
+                    ..Default::default()
+                };
+                let api: Api<Pod> = Api::namespaced(client, &namespace);
+                let logs = api.logs(pod, &lp).await.map_err(kube_err)?;
+                Ok(json!({
+                    "pod": pod,
+                    "namespace": namespace,
+                    "container": lp.container,
+                    "tail": tail,
+                    "line_count": logs.lines().count(),
+                    "logs": logs,
+                }))
+            }
+            "rollout_status" => {
+                let namespace = resolve_namespace(cfg, &params)?
+                    .unwrap_or(cfg.default_namespace.as_str())
+                    .to_string();
+                let name = str_param(&params, "name").unwrap_or("");
+                let d = get_kind(client, Kind::Deployment, &namespace, name).await?;
+                let (complete, message) = rollout_state(&d);
+                Ok(json!({
+                    "name": name,
+                    "namespace": namespace,
+                    "complete": complete,
+                    "message": message,
+                    "summary": workload_summary(Kind::Deployment, &d, now),
+                }))
+            }
+            "scale" | "rollout_restart" => {
+                let kind = kind_param(&params)?;
+                let namespace = resolve_namespace(cfg, &params)?
+                    .unwrap_or(cfg.default_namespace.as_str())
+                    .to_string();
+                let name = str_param(&params, "name").unwrap_or("");
+                let pp = PatchParams::default();
+                let result = if action == "scale" {
+                    let replicas = params["replicas"].as_u64().unwrap_or(0);
+                    let patch = Patch::Merge(json!({ "spec": { "replicas": replicas } }));
+                    let scale = match kind {
+                        Kind::StatefulSet => {
+                            Api::<StatefulSet>::namespaced(client, &namespace)
+                                .patch_scale(name, &pp, &patch)
+                                .await
+                        }
+                        _ => {
+                            Api::<Deployment>::namespaced(client, &namespace)
+                                .patch_scale(name, &pp, &patch)
+                                .await
+                        }
+                    }
+                    .map_err(kube_err)?;
+                    json!({ "replicas": scale.spec.and_then(|s| s.replicas) })
+                } else {
+                    let patch = Patch::Merge(json!({
+                        "spec": { "template": { "metadata": { "annotations": {
+                            "kubectl.kubernetes.io/restartedAt": now.to_rfc3339()
+                        }}}}
+                    }));
+                    match kind {
+                        Kind::StatefulSet => Api::<StatefulSet>::namespaced(client, &namespace)
+                            .patch(name, &pp, &patch)
+                            .await
+                            .map(|_| ()),
+                        Kind::DaemonSet => Api::<DaemonSet>::namespaced(client, &namespace)
+                            .patch(name, &pp, &patch)
+                            .await
+                            .map(|_| ()),
+                        _ => Api::<Deployment>::namespaced(client, &namespace)
+                            .patch(name, &pp, &patch)
+                            .await
+                            .map(|_| ()),
+                    }
+                    .map_err(kube_err)?;
+                    json!({ "restarted_at": now.to_rfc3339() })
+                };
+                info!(action, kind = kind.name(), name = %name, namespace = %namespace, "kubectl mutation");
+                let mut out = json!({
+                    "action": action,
+                    "kind": kind.name(),
+                    "name": name,
+                    "namespace": namespace,
+                });
+                if let (Some(o), Some(r)) = (out.as_object_mut(), result.as_object()) {
+                    o.extend(r.clone());
+                }
+                Ok(out)
+            }
+            other => Err(Error::Validation(format!("Unknown action: {}", other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 6, 10, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_age_and_kind_parsing() {
+        assert_eq!(age(&json!("2024-06-10T11:59:15Z"), now()), "45s");
+        assert_eq!(age(&json!("2024-06-10T11:48:00Z"), now()), "12m");
+        assert_eq!(age(&json!("2024-06-10T07:00:00Z"), now()), "5h");
+        assert_eq!(age(&json!("2024-06-07T08:00:00Z"), now()), "3d4h");
+        assert_eq!(age(&json!("2024-04-01T12:00:00Z"), now()), "70d");
+        assert_eq!(age(&Value::Null, now()), "");
+
+        assert_eq!(Kind::parse("deploy"), Some(Kind::Deployment));
+        assert_eq!(Kind::parse("Pods"), Some(Kind::Pod));
+        assert_eq!(Kind::parse("secrets"), None);
+        assert!(Kind::parse("nodes").unwrap().cluster_scoped());
+    }
+
+    #[test]
+    fn test_pod_and_node_summary() {
+        let pod = json!({
+            "metadata": {"name": "web-1", "namespace": "prod", "creationTimestamp": "2024-06-10T07:00:00Z"},
+            "spec": {"nodeName": "n1", "containers": [{"name": "app"}, {"name": "sidecar"}]},
+            "status": {"phase": "Running", "podIP": "10.1.2.3", "containerStatuses": [
+                {"ready": true, "restartCount": 0, "state": {"running": {}}},
+                {"ready": false, "restartCount": 7, "state": {"waiting": {"reason": "CrashLoopBackOff"}}}
+            ]}
+        });
+        let s = pod_summary(&pod, now());
+        assert_eq!(s["ready"], "1/2");
+        assert_eq!(s["restarts"], 7);
+        assert_eq!(s["status"], "CrashLoopBackOff");
+        assert_eq!(s["age"], "5h");
+
+        let node = json!({
+            "metadata": {"name": "n1", "labels": {"node-role.kubernetes.io/control-plane": ""}},
+            "spec": {"unschedulable": true},
+            "status": {
+                "conditions": [{"type": "Ready", "status": "True"}, {"type": "DiskPressure", "status": "True"}],
+                "nodeInfo": {"kubeletVersion": "v1.30.1"},
+                "capacity": {"cpu": "4", "memory": "16Gi"}
+            }
+        });
+        let s = node_summary(&node, now());
+        assert_eq!(s["status"], "Ready,SchedulingDisabled");
+        assert_eq!(s["conditions"], json!(["DiskPressure"]));
+        assert_eq!(s["roles"], json!(["control-plane"]));
+    }
+
+    #[test]
+    fn test_workloads_services_and_rollout() {
+        let d = json!({
+            "metadata": {"name": "api", "namespace": "prod", "generation": 4},
+            "spec": {"replicas": 3, "template": {"spec": {"containers": [{"image": "api:2.1"}]}}},
+            "status": {"observedGeneration": 4, "replicas": 4, "updatedReplicas": 3, "readyReplicas": 3, "availableReplicas": 3}
+        });
+        let s = workload_summary(Kind::Deployment, &d, now());
+        assert_eq!(s["ready"], "3/3");
+        assert_eq!(s["images"], json!(["api:2.1"]));
+        assert_eq!(
+            rollout_state(&d),
+            (false, "1 old replicas pending termination".to_string())
+        );
+        let mut done = d.clone();
+        done["status"]["replicas"] = json!(3);
+        assert!(rollout_state(&done).0);
+
+        let svc = json!({
+            "metadata": {"name": "web"},
+            "spec": {"type": "NodePort", "clusterIP": "10.0.0.9", "ports": [{"port": 80, "nodePort": 30080, "protocol": "TCP"}]}
+        });
+        assert_eq!(
+            service_summary(&svc, now())["ports"],
+            json!(["80:30080/TCP"])
+        );
+    }
+
+    #[test]
+    fn test_events_sorted_and_trim() {
+        let mut events: Vec<Value> = [
+            json!({"type": "Normal", "reason": "Pulled", "involvedObject": {"kind": "Pod", "name": "a"}, "lastTimestamp": "2024-06-10T10:00:00Z"}),
+            json!({"type": "Warning", "reason": "BackOff", "involvedObject": {"kind": "Pod", "name": "a"}, "eventTime": "2024-06-10T11:30:00Z"}),
+        ]
+        .iter()
+        .map(|e| event_summary(e, now()))
+        .collect();
+        sort_events(&mut events);
+        assert_eq!(events[0]["reason"], "BackOff");
+        assert_eq!(events[0]["object"], "Pod/a");
+        assert_eq!(events[0]["last_seen"], "30m");
+
+        let trimmed = trim_object(json!({"metadata": {"name": "x", "managedFields": [1],
+            "annotations": {"kubectl.kubernetes.io/last-applied-configuration": "{}", "keep": "1"}}}));
+        assert!(trimmed["metadata"].get("managedFields").is_none());
+        assert_eq!(trimmed["metadata"]["annotations"], json!({"keep": "1"}));
+    }
+
+    #[test]
+    fn test_namespace_policy_and_confirmation() {
+        let mut cfg = KubernetesConfig {
+            allowed_namespaces: vec!["prod".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            resolve_namespace(&cfg, &json!({"namespace": "prod"})).unwrap(),
+            Some("prod")
+        );
+        assert!(resolve_namespace(&cfg, &json!({"namespace": "kube-system"})).is_err());
+        assert!(resolve_namespace(&cfg, &json!({"all_namespaces": true})).is_err());
+        cfg.allowed_namespaces.clear();
+        assert_eq!(
+            resolve_namespace(&cfg, &json!({"all_namespaces": true})).unwrap(),
+            None
+        );
+        assert!(resolve_namespace(&cfg, &json!({"namespace": "Bad_NS"})).is_err());
+
+        let tool = KubectlTool;
+        let mut config = Config::default();
+        let scale = json!({"action": "scale", "kind": "deploy", "name": "api", "namespace": "prod", "replicas": 5});
+        assert!(tool.validate(&scale).is_ok());
+        // Read-only by default: execute rejects, so nothing to confirm.
+        assert!(tool.confirmation_items(&config, &scale).is_empty());
+        config.tools.kubernetes.allow_mutations = true;
+        assert_eq!(
+            tool.confirmation_items(&config, &scale),
+            vec!["kubectl scale deploy/api -n prod --replicas=5"]
+        );
+        assert!(tool
+            .validate(&json!({"action": "scale", "kind": "pods", "name": "x", "replicas": 1}))
+            .is_err());
+    }
+}
@@ -1,55 +1,94 @@
+#![recursion_limit = "256"]
+
 pub mod agent_status;
 pub mod alert_rule;
 pub mod app_control;
 pub mod audio_transcribe;
+pub mod backtest;
+pub mod birthdays;
+pub mod bookmarks;
 pub mod browser;
+pub mod calc;
+pub mod calendar;
 pub mod camera;
 pub mod chart_generate;
 pub mod community_hub;
 pub mod cron;
 pub mod data_process;
+pub mod db_query;
+pub mod doc_diff;
+pub mod docker;
+pub mod documents;
+pub mod egress;
 pub mod email;
 pub mod encrypt;
 pub mod exec;
 pub mod exec_local;
 pub mod exec_skill_script;
 pub mod file_ops;
+pub mod follow_up;
 pub mod fs;
+pub mod git_api;
+pub mod habits;
 pub mod html_to_md;
 pub mod http_request;
 pub mod image_understand;
+pub mod iot_control;
 pub mod knowledge_graph;
+#[cfg(feature = "kubernetes")]
+pub mod kubectl;
 pub mod mcp;
+pub mod media_control;
 pub mod memory;
 pub mod memory_maintenance;
 pub mod message;
+pub mod mqtt;
 #[cfg(feature = "napcat")]
 pub mod napcat;
 pub mod network_monitor;
+pub mod nutrition;
+pub mod object_storage;
 pub mod ocr;
 pub mod office;
 pub mod office_write;
+pub mod parcel_track;
+pub mod projects;
+pub mod python;
+pub mod python_session;
+pub mod receipts;
 pub mod registry;
 pub mod registry_builder;
+pub mod rss;
+pub mod sandbox;
 pub mod session_recall;
 pub mod skills;
 pub mod spawn;
+pub mod spreadsheet;
+pub mod ssh;
 pub mod stream_subscribe;
+pub mod subscriptions;
 pub mod system_info;
 pub mod tasks;
 pub mod termux_api;
+pub mod todo;
 pub mod toggle_manage;
+pub mod translate;
+pub mod travel;
 pub mod tts;
 pub mod video_process;
+pub mod weather;
 pub mod web;
 
 use async_trait::async_trait;
+use blockcell_core::permissions::{Role, ToolPermissions};
 use blockcell_core::system_event::{EventPriority, SystemEvent};
 use blockcell_core::types::PermissionSet;
-use blockcell_core::{Config, OutboundMessage, Result};
+use blockcell_core::{Config, OutboundMessage, Paths, Result};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::{mpsc, Mutex};
 
 pub use registry::ToolRegistry;
@@ -71,6 +110,15 @@ pub fn safe_truncate(s: &str, max_chars: usize) -> &str {
     &s[..end]
 }
 
+/// Paths of the agent that owns `workspace`; the workspace sits in its base directory.
+/// For background jobs that only have the workspace (see [`ToolContext::paths`]).
+pub(crate) fn paths_for_workspace(workspace: &Path) -> Paths {
+    match workspace.parent() {
+        Some(base) => Paths::with_base(base.to_path_buf()),
+        None => Paths::new(),
+    }
+}
+
 /// Sender handle for outbound messages (used by message tool).
 pub type OutboundSender = mpsc::Sender<OutboundMessage>;
 
@@ -169,6 +217,12 @@ pub trait MemoryStoreOps: Send + Sync {
     fn upsert_json(&self, params_json: Value) -> Result<Value>;
     /// Query memory items. Returns results as JSON array.
     fn query_json(&self, params_json: Value) -> Result<Value>;
+    /// Query memory items by embedding similarity alone (same params as `query_json`).
+    fn semantic_query_json(&self, _params_json: Value) -> Result<Value> {
+        Err(blockcell_core::Error::Tool(
+            "Semantic memory search is not available".to_string(),
+        ))
+    }
     /// Soft-delete a memory item by ID. Returns success boolean.
     fn soft_delete(&self, id: &str) -> Result<bool>;
     /// Batch soft-delete by filter. Returns count of deleted items.
@@ -187,6 +241,10 @@ pub trait MemoryStoreOps: Send + Sync {
     fn get_session_summary(&self, session_key: &str) -> Result<Option<String>>;
     /// Run maintenance (TTL cleanup, recycle bin purge).
     fn maintenance(&self, recycle_days: i64) -> Result<(usize, usize)>;
+    /// Underlying store, for jobs that need direct access (privacy purge, retention).
+    fn store(&self) -> Option<blockcell_storage::MemoryStore> {
+        None
+    }
 }
 
 /// Trait abstracting session response cache operations needed by tools.
@@ -229,6 +287,170 @@ pub struct ToolContext {
     pub channel_contacts_file: Option<PathBuf>,
     /// Session response cache handle for session_recall tool.
     pub response_cache: Option<ResponseCacheHandle>,
+    /// Collects files this call produced; the runtime announces them as `artifact` events.
+    pub artifacts: Option<ArtifactCollector>,
+}
+
+/// Shared sink for artifacts registered during one tool call.
+pub type ArtifactCollector = Arc<std::sync::Mutex<Vec<ToolArtifact>>>;
+
+/// A file produced by a tool that clients can offer for download.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolArtifact {
+    pub name: String,
+    /// Absolute path on disk.
+    pub path: String,
+    /// Coarse category: document, image, audio, video, archive, data or file.
+    pub kind: String,
+    pub mime: String,
+    pub size: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl ToolArtifact {
+    /// Gateway URL that streams the file inline (images, audio, video).
+    pub fn serve_url(&self, agent_id: &str) -> String {
+        format!(
+            "/v1/files/serve?path={}&agent={}",
+            urlencoding::encode(&self.path),
+            urlencoding::encode(agent_id)
+        )
+    }
+
+    /// Gateway URL that downloads the file as an attachment.
+    pub fn download_url(&self, agent_id: &str) -> String {
+        format!(
+            "/v1/files/download?path={}&agent={}",
+            urlencoding::encode(&self.path),
+            urlencoding::encode(agent_id)
+        )
+    }
+}
+
+impl ToolContext {
+    /// Role of the caller under `permissions`. The runtime passes the role a scheduled
+    /// message runs with as a `role:` permission, and that caps the channel's role.
+    pub fn role(&self) -> Role {
+        let role = ToolPermissions::new(&self.config.permissions).role_for(
+            &self.channel,
+            self.sender_id.as_deref().unwrap_or_default(),
+        );
+        match self
+            .permissions
+            .permissions
+            .iter()
+            .find_map(|p| p.strip_prefix("role:").and_then(Role::parse))
+        {
+            Some(granted) => role.capped(granted),
+            None => role,
+        }
+    }
+
+    /// Paths of the agent this call runs for.
+    pub fn paths(&self) -> Paths {
+        paths_for_workspace(&self.workspace)
+    }
+
+    /// Register a generated file as an artifact of this call. Relative paths resolve
+    /// against the workspace; an empty `kind` is inferred from the MIME type.
+    /// Returns `None` when the file does not exist.
+    pub fn register_artifact(
+        &self,
+        path: &str,
+        kind: &str,
+        description: Option<&str>,
+    ) -> Option<ToolArtifact> {
+        let raw = Path::new(path);
+        let full = if raw.is_absolute() {
+            raw.to_path_buf()
+        } else {
+            self.workspace.join(raw)
+        };
+        let meta = std::fs::metadata(&full).ok().filter(|m| m.is_file())?;
+        let mime = artifact_mime(&full);
+        let artifact = ToolArtifact {
+            name: full
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            path: full.display().to_string(),
+            kind: if kind.trim().is_empty() {
+                artifact_kind(mime).to_string()
+            } else {
+                kind.trim().to_string()
+            },
+            mime: mime.to_string(),
+            size: meta.len(),
+            description: description
+                .map(str::trim)
+                .filter(|d| !d.is_empty())
+                .map(String::from),
+        };
+        if let Some(ref collector) = self.artifacts {
+            if let Ok(mut list) = collector.lock() {
+                if !list.iter().any(|a| a.path == artifact.path) {
+                    list.push(artifact.clone());
+                }
+            }
+        }
+        Some(artifact)
+    }
+}
+
+/// Best-effort MIME type from the file extension.
+pub fn artifact_mime(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    match ext.as_str() {
+        "pdf" => "application/pdf",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        "md" => "text/markdown",
+        "txt" | "log" => "text/plain",
+        "html" | "htm" => "text/html",
+        "csv" => "text/csv",
+        "json" => "application/json",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "ogg" | "opus" => "audio/ogg",
+        "m4a" => "audio/mp4",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "mov" => "video/quicktime",
+        "zip" => "application/zip",
+        "gz" | "tgz" => "application/gzip",
+        "tar" => "application/x-tar",
+        _ => "application/octet-stream",
+    }
+}
+
+fn artifact_kind(mime: &str) -> &'static str {
+    match mime.split('/').next().unwrap_or("") {
+        "image" => "image",
+        "audio" => "audio",
+        "video" => "video",
+        _ if mime.contains("officedocument") || mime == "application/pdf" => "document",
+        "text" if mime != "text/csv" => "document",
+        _ if matches!(mime, "text/csv" | "application/json") => "data",
+        _ if matches!(
+            mime,
+            "application/zip" | "application/gzip" | "application/x-tar"
+        ) =>
+        {
+            "archive"
+        }
+        _ => "file",
+    }
 }
 
 pub struct ToolSchema {
@@ -237,6 +459,26 @@ pub struct ToolSchema {
     pub parameters: Value,
 }
 
+/// How many calls of one tool may run at the same time, across all sessions.
+///
+/// Calls beyond the limit queue in arrival order and fail with a "busy" error if no slot
+/// frees up within `queue_timeout`, instead of racing on a shared device or profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolConcurrency {
+    Unlimited,
+    Limited { max: usize, queue_timeout: Duration },
+}
+
+impl ToolConcurrency {
+    /// One call at a time, waiting up to `queue_timeout_secs` for the previous one.
+    pub const fn exclusive(queue_timeout_secs: u64) -> Self {
+        Self::Limited {
+            max: 1,
+            queue_timeout: Duration::from_secs(queue_timeout_secs),
+        }
+    }
+}
+
 /// Context passed to `Tool::prompt_rule()` so each tool can emit channel-aware / intent-aware rules.
 pub struct PromptContext<'a> {
     pub channel: &'a str,
@@ -282,5 +524,101 @@ pub trait Tool: Send + Sync {
     fn prompt_rule(&self, _ctx: &PromptContext) -> Option<String> {
         None
     }
+    /// Describe the parts of this call that need explicit user confirmation before it runs
+    /// (one line per item). The runtime routes non-empty results through the confirm flow.
+    fn confirmation_items(&self, _config: &Config, _params: &Value) -> Vec<String> {
+        Vec::new()
+    }
+    /// Concurrency limit enforced by the registry. Override for tools that drive a device
+    /// or shared state (camera, browser profile, serial port) which must not be used twice at once.
+    fn concurrency(&self) -> ToolConcurrency {
+        ToolConcurrency::Unlimited
+    }
     async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_context(workspace: PathBuf, artifacts: Option<ArtifactCollector>) -> ToolContext {
+        ToolContext {
+            workspace,
+            builtin_skills_dir: None,
+            active_skill_dir: None,
+            session_key: "cli:test".to_string(),
+            channel: "cli".to_string(),
+            account_id: None,
+            sender_id: None,
+            chat_id: "chat-1".to_string(),
+            config: Config::default(),
+            permissions: PermissionSet::new(),
+            task_manager: None,
+            memory_store: None,
+            outbound_tx: None,
+            spawn_handle: None,
+            capability_registry: None,
+            core_evolution: None,
+            event_emitter: None,
+            channel_contacts_file: None,
+            response_cache: None,
+            artifacts,
+        }
+    }
+
+    #[test]
+    fn test_register_artifact_collects_once() {
+        let ws = std::env::temp_dir().join(format!("blockcell-artifacts-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(ws.join("reports")).unwrap();
+        std::fs::write(ws.join("reports/q3.docx"), b"PK..").unwrap();
+
+        let collector = ArtifactCollector::default();
+        let ctx = test_context(ws.clone(), Some(collector.clone()));
+        let artifact = ctx
+            .register_artifact("reports/q3.docx", "", Some(" Q3 report "))
+            .unwrap();
+        assert_eq!(artifact.name, "q3.docx");
+        assert_eq!(artifact.kind, "document");
+        assert_eq!(artifact.size, 4);
+        assert_eq!(artifact.description.as_deref(), Some("Q3 report"));
+        assert!(artifact.mime.contains("wordprocessingml"));
+
+        let abs = ws.join("reports/q3.docx").display().to_string();
+        assert!(ctx.register_artifact(&abs, "document", None).is_some());
+        assert!(ctx.register_artifact("missing.pdf", "", None).is_none());
+        assert!(ctx.register_artifact("reports", "", None).is_none());
+        assert_eq!(collector.lock().unwrap().len(), 1);
+
+        assert!(artifact
+            .download_url("ops")
+            .starts_with("/v1/files/download?path=%2F"));
+        assert!(artifact.serve_url("ops").ends_with("&agent=ops"));
+        let _ = std::fs::remove_dir_all(&ws);
+    }
+
+    #[test]
+    fn test_role_is_capped_by_granted_role() {
+        let mut ctx = test_context(std::env::temp_dir(), None);
+        assert_eq!(ctx.role(), Role::Admin);
+
+        ctx.permissions = PermissionSet::new().with_permission("role:user");
+        assert_eq!(ctx.role(), Role::User);
+
+        ctx.config.permissions.enabled = true;
+        ctx.channel = "telegram".to_string();
+        ctx.sender_id = Some("42".to_string());
+        ctx.permissions = PermissionSet::new().with_permission("role:admin");
+        assert_eq!(ctx.role(), Role::User);
+    }
+
+    #[test]
+    fn test_artifact_kind_from_mime() {
+        assert_eq!(artifact_kind(artifact_mime(Path::new("a.PNG"))), "image");
+        assert_eq!(artifact_kind(artifact_mime(Path::new("a.mp3"))), "audio");
+        assert_eq!(artifact_kind(artifact_mime(Path::new("a.pdf"))), "document");
+        assert_eq!(artifact_kind(artifact_mime(Path::new("a.md"))), "document");
+        assert_eq!(artifact_kind(artifact_mime(Path::new("a.csv"))), "data");
+        assert_eq!(artifact_kind(artifact_mime(Path::new("a.tgz"))), "archive");
+        assert_eq!(artifact_kind(artifact_mime(Path::new("a.bin"))), "file");
+    }
+}
@@ -0,0 +1,846 @@
+//! Play/pause/skip and volume control for local media players and Spotify Connect.
+//!
+//! Local backends: `playerctl` (MPRIS) and `pactl`/`amixer` on Linux, AppleScript on macOS,
+//! virtual media keys via PowerShell on Windows. Spotify Connect goes through the Web API
+//! with a long-lived refresh token from `tools.mediaControl`.
+
+use async_trait::async_trait;
+use blockcell_core::config::MediaControlConfig;
+use blockcell_core::secrets::resolve_secret_ref;
+use blockcell_core::{Config, Error, Result};
+use once_cell::sync::Lazy;
+use serde_json::{json, Value};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+use tracing::{debug, info};
+
+use crate::egress::HttpClientFactory;
+use crate::{PromptContext, Tool, ToolContext, ToolSchema};
+
+const COMMAND_TIMEOUT_SECS: u64 = 10;
+const REQUEST_TIMEOUT_SECS: u64 = 15;
+const SPOTIFY_API: &str = "https://api.spotify.com/v1";
+const SPOTIFY_TOKEN_URL: &str = "https://accounts.spotify.com/api/token";
+/// Windows moves the master volume by 2% per volume key press.
+const WINDOWS_VOLUME_STEP: i32 = 2;
+
+/// Cached Spotify access token and its expiry.
+static SPOTIFY_TOKEN: Lazy<tokio::sync::Mutex<Option<(String, Instant)>>> =
+    Lazy::new(|| tokio::sync::Mutex::new(None));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Transport {
+    Play,
+    Pause,
+    Toggle,
+    Next,
+    Previous,
+    Stop,
+}
+
+impl Transport {
+    fn from_action(action: &str) -> Option<Self> {
+        Some(match action {
+            "play" => Self::Play,
+            "pause" => Self::Pause,
+            "toggle" => Self::Toggle,
+            "next" => Self::Next,
+            "previous" => Self::Previous,
+            "stop" => Self::Stop,
+            _ => return None,
+        })
+    }
+
+    fn playerctl(self) -> &'static str {
+        match self {
+            Self::Play => "play",
+            Self::Pause => "pause",
+            Self::Toggle => "play-pause",
+            Self::Next => "next",
+            Self::Previous => "previous",
+            Self::Stop => "stop",
+        }
+    }
+
+    fn applescript(self) -> &'static str {
+        match self {
+            Self::Play => "play",
+            Self::Pause | Self::Stop => "pause",
+            Self::Toggle => "playpause",
+            Self::Next => "next track",
+            Self::Previous => "previous track",
+        }
+    }
+
+    /// Virtual-key code of the matching Windows media key.
+    fn windows_key(self) -> u8 {
+        match self {
+            // Windows has no separate play/pause keys; both toggle.
+            Self::Play | Self::Pause | Self::Toggle => 179,
+            Self::Next => 176,
+            Self::Previous => 177,
+            Self::Stop => 178,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VolumeChange {
+    Set(u8),
+    Delta(i32),
+}
+
+impl VolumeChange {
+    /// `50` / `"50"` / `"50%"` set the level; `"+10"` / `"-10"` change it.
+    fn parse(value: &Value) -> Result<Self> {
+        let invalid = || {
+            Error::Validation(format!(
+                "Invalid volume '{}'. Use 0-100, or '+10' / '-10' for relative changes",
+                value
+            ))
+        };
+        if let Some(n) = value.as_i64() {
+            return (0..=100)
+                .contains(&n)
+                .then_some(Self::Set(n as u8))
+                .ok_or_else(invalid);
+        }
+        let s = value
+            .as_str()
+            .ok_or_else(invalid)?
+            .trim()
+            .trim_end_matches('%');
+        let n: i32 = s.trim_start_matches('+').parse().map_err(|_| invalid())?;
+        if s.starts_with('+') || s.starts_with('-') {
+            return (-100..=100)
+                .contains(&n)
+                .then_some(Self::Delta(n))
+                .ok_or_else(invalid);
+        }
+        (0..=100)
+            .contains(&n)
+            .then_some(Self::Set(n as u8))
+            .ok_or_else(invalid)
+    }
+
+    fn apply(self, current: u8) -> u8 {
+        match self {
+            Self::Set(v) => v,
+            Self::Delta(d) => (current as i32 + d).clamp(0, 100) as u8,
+        }
+    }
+}
+
+async fn run(program: &str, args: &[&str]) -> Result<String> {
+    debug!(program, ?args, "Running media command");
+    let output = tokio::time::timeout(
+        Duration::from_secs(COMMAND_TIMEOUT_SECS),
+        Command::new(program).args(args).output(),
+    )
+    .await
+    .map_err(|_| Error::Tool(format!("{} timed out", program)))?
+    .map_err(|e| Error::Tool(format!("Failed to run {}: {}", program, e)))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        Err(Error::Tool(format!("{} failed: {}", program, stderr)))
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Local players
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn playerctl_args<'a>(player: &'a str, rest: &[&'a str]) -> Vec<&'a str> {
+    let mut args = Vec::new();
+    if !player.is_empty() {
+        args.extend(["-p", player]);
+    }
+    args.extend_from_slice(rest);
+    args
+}
+
+const PLAYERCTL_FORMAT: &str =
+    "{{playerName}}\t{{status}}\t{{artist}}\t{{title}}\t{{album}}\t{{volume}}";
+
+/// Parse one line of `playerctl metadata --format PLAYERCTL_FORMAT`.
+fn parse_playerctl_status(line: &str) -> Value {
+    let mut f = line.split('\t');
+    let mut next = || f.next().map(str::trim).filter(|s| !s.is_empty());
+    let player = next();
+    let status = next().map(str::to_lowercase);
+    let artist = next();
+    let title = next();
+    let album = next();
+    let volume = next()
+        .and_then(|v| v.parse::<f64>().ok())
+        .map(|v| (v * 100.0).round() as u8);
+    json!({
+        "player": player,
+        "state": status,
+        "artist": artist,
+        "title": title,
+        "album": album,
+        "player_volume": volume,
+    })
+}
+
+fn escape_applescript(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// The macOS player app to drive: the configured one, else Spotify or Music if running.
+async fn mac_player(preferred: &str) -> Result<String> {
+    if !preferred.is_empty() {
+        return Ok(preferred.to_string());
+    }
+    for app in ["Spotify", "Music"] {
+        let script = format!("application \"{}\" is running", app);
+        if run("osascript", &["-e", &script])
+            .await
+            .is_ok_and(|out| out == "true")
+        {
+            return Ok(app.to_string());
+        }
+    }
+    Err(Error::Tool(
+        "No running media player (Spotify or Music) found".to_string(),
+    ))
+}
+
+async fn windows_keys(key: u8, presses: usize) -> Result<()> {
+    if presses == 0 {
+        return Ok(());
+    }
+    let script = format!(
+        "$w = New-Object -ComObject WScript.Shell; 1..{} | ForEach-Object {{ $w.SendKeys([char]{}) }}",
+        presses,
+        key
+    );
+    run("powershell", &["-NoProfile", "-Command", &script])
+        .await
+        .map(|_| ())
+}
+
+async fn linux_system_volume() -> Result<u8> {
+    let out = run("pactl", &["get-sink-volume", "@DEFAULT_SINK@"]).await?;
+    out.split_whitespace()
+        .find_map(|w| w.strip_suffix('%').and_then(|n| n.parse().ok()))
+        .ok_or_else(|| Error::Tool(format!("Unexpected pactl output: {}", out)))
+}
+
+async fn local_transport(cfg: &MediaControlConfig, t: Transport) -> Result<Value> {
+    if cfg!(target_os = "linux") {
+        run("playerctl", &playerctl_args(&cfg.player, &[t.playerctl()])).await?;
+        Ok(json!({"backend": "mpris"}))
+    } else if cfg!(target_os = "macos") {
+        let app = mac_player(&cfg.player).await?;
+        let script = format!(
+            "tell application \"{}\" to {}",
+            escape_applescript(&app),
+            t.applescript()
+        );
+        run("osascript", &["-e", &script]).await?;
+        Ok(json!({"backend": "applescript", "player": app}))
+    } else if cfg!(target_os = "windows") {
+        windows_keys(t.windows_key(), 1).await?;
+        Ok(json!({"backend": "media_keys"}))
+    } else {
+        Err(Error::Tool(
+            "Local media control is not supported on this platform".to_string(),
+        ))
+    }
+}
+
+async fn local_volume(change: VolumeChange) -> Result<Value> {
+    if cfg!(target_os = "linux") {
+        let level = change.apply(linux_system_volume().await.unwrap_or(50));
+        let arg = format!("{}%", level);
+        if run("pactl", &["set-sink-volume", "@DEFAULT_SINK@", &arg])
+            .await
+            .is_err()
+        {
+            run("amixer", &["-q", "sset", "Master", &arg]).await?;
+        }
+        Ok(json!({"backend": "system", "volume": level}))
+    } else if cfg!(target_os = "macos") {
+        let current = run(
+            "osascript",
+            &["-e", "output volume of (get volume settings)"],
+        )
+        .await
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(50);
+        let level = change.apply(current);
+        let script = format!("set volume output volume {}", level);
+        run("osascript", &["-e", &script]).await?;
+        Ok(json!({"backend": "system", "volume": level}))
+    } else if cfg!(target_os = "windows") {
+        // Media keys are relative only: an absolute level is reached from zero.
+        match change {
+            VolumeChange::Set(v) => {
+                windows_keys(174, (100 / WINDOWS_VOLUME_STEP) as usize).await?;
+                windows_keys(175, (v as i32 / WINDOWS_VOLUME_STEP) as usize).await?;
+            }
+            VolumeChange::Delta(d) => {
+                let key = if d < 0 { 174 } else { 175 };
+                windows_keys(key, (d.abs() / WINDOWS_VOLUME_STEP) as usize).await?;
+            }
+        }
+        Ok(json!({"backend": "media_keys"}))
+    } else {
+        Err(Error::Tool(
+            "Local volume control is not supported on this platform".to_string(),
+        ))
+    }
+}
+
+async fn local_mute(muted: Option<bool>) -> Result<Value> {
+    if cfg!(target_os = "linux") {
+        let arg = match muted {
+            Some(true) => "1",
+            Some(false) => "0",
+            None => "toggle",
+        };
+        run("pactl", &["set-sink-mute", "@DEFAULT_SINK@", arg]).await?;
+    } else if cfg!(target_os = "macos") {
+        let value = match muted {
+            Some(m) => m.to_string(),
+            None => "not (output muted of (get volume settings))".to_string(),
+        };
+        run(
+            "osascript",
+            &["-e", &format!("set volume output muted {}", value)],
+        )
+        .await?;
+    } else if cfg!(target_os = "windows") {
+        // The mute key only toggles.
+        windows_keys(173, 1).await?;
+    } else {
+        return Err(Error::Tool(
+            "Local mute is not supported on this platform".to_string(),
+        ));
+    }
+    Ok(json!({"backend": "system", "muted": muted.map_or(json!("toggled"), |m| json!(m))}))
+}
+
+async fn local_status(cfg: &MediaControlConfig) -> Result<Value> {
+    if cfg!(target_os = "linux") {
+        let line = run(
+            "playerctl",
+            &playerctl_args(&cfg.player, &["metadata", "--format", PLAYERCTL_FORMAT]),
+        )
+        .await?;
+        let mut status = parse_playerctl_status(&line);
+        status["system_volume"] = json!(linux_system_volume().await.ok());
+        Ok(status)
+    } else if cfg!(target_os = "macos") {
+        let app = mac_player(&cfg.player).await?;
+        let script = format!(
+            "tell application \"{}\"\n\
+             set s to (player state as text)\n\
+             try\n\
+             set s to s & tab & (artist of current track) & tab & (name of current track) & tab & (album of current track)\n\
+             end try\n\
+             return s\n\
+             end tell",
+            escape_applescript(&app)
+        );
+        let out = run("osascript", &["-e", &script]).await?;
+        let mut status = parse_playerctl_status(&format!("{}\t{}", app, out));
+        status["system_volume"] = json!(run(
+            "osascript",
+            &["-e", "output volume of (get volume settings)"]
+        )
+        .await
+        .ok()
+        .and_then(|v| v.parse::<u8>().ok()));
+        Ok(status)
+    } else {
+        Err(Error::Tool(
+            "Playback status is not available for local players on this platform".to_string(),
+        ))
+    }
+}
+
+async fn local_players() -> Result<Value> {
+    if cfg!(target_os = "linux") {
+        let out = run("playerctl", &["-l"]).await.unwrap_or_default();
+        let players: Vec<&str> = out
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .collect();
+        Ok(json!(players))
+    } else if cfg!(target_os = "macos") {
+        let mut players = Vec::new();
+        for app in ["Spotify", "Music"] {
+            let script = format!("application \"{}\" is running", app);
+            if run("osascript", &["-e", &script])
+                .await
+                .is_ok_and(|out| out == "true")
+            {
+                players.push(app);
+            }
+        }
+        Ok(json!(players))
+    } else {
+        Ok(json!([]))
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Spotify Connect
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn credential(value: &str, env_key: &str, workspace: &Path) -> Option<String> {
+    if value.trim().is_empty() {
+        return std::env::var(env_key)
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+    }
+    resolve_secret_ref(&crate::paths_for_workspace(workspace), value).ok()
+}
+
+fn spotify_configured(cfg: &MediaControlConfig, workspace: &Path) -> bool {
+    credential(
+        &cfg.spotify_refresh_token,
+        "SPOTIFY_REFRESH_TOKEN",
+        workspace,
+    )
+    .is_some()
+}
+
+struct Spotify {
+    client: reqwest::Client,
+    token: String,
+    device: String,
+}
+
+impl Spotify {
+    async fn connect(config: &Config, workspace: &Path) -> Result<Self> {
+        let cfg = &config.tools.media_control;
+        let factory = HttpClientFactory::for_tool(config, "media_control");
+        factory.check(SPOTIFY_API).await?;
+        let client = factory
+            .builder()
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()
+            .map_err(|e| Error::Tool(format!("Failed to build HTTP client: {}", e)))?;
+
+        let mut cached = SPOTIFY_TOKEN.lock().await;
+        let token = match cached.as_ref() {
+            Some((token, expires)) if *expires > Instant::now() => token.clone(),
+            _ => {
+                let missing = |what: &str, env: &str| {
+                    Error::Tool(format!(
+                        "Spotify Connect needs tools.mediaControl.{} (or {})",
+                        what, env
+                    ))
+                };
+                let client_id = credential(&cfg.spotify_client_id, "SPOTIFY_CLIENT_ID", workspace)
+                    .ok_or_else(|| missing("spotifyClientId", "SPOTIFY_CLIENT_ID"))?;
+                let client_secret = credential(
+                    &cfg.spotify_client_secret,
+                    "SPOTIFY_CLIENT_SECRET",
+                    workspace,
+                )
+                .ok_or_else(|| missing("spotifyClientSecret", "SPOTIFY_CLIENT_SECRET"))?;
+                let refresh = credential(
+                    &cfg.spotify_refresh_token,
+                    "SPOTIFY_REFRESH_TOKEN",
+                    workspace,
+                )
+                .ok_or_else(|| missing("spotifyRefreshToken", "SPOTIFY_REFRESH_TOKEN"))?;
+                factory.check(SPOTIFY_TOKEN_URL).await?;
+                let resp = client
+                    .post(SPOTIFY_TOKEN_URL)
+                    .basic_auth(client_id, Some(client_secret))
+                    .form(&[
+                        ("grant_type", "refresh_token"),
+                        ("refresh_token", refresh.as_str()),
+                    ])
+                    .send()
+                    .await
+                    .map_err(|e| Error::Tool(format!("Spotify token refresh failed: {}", e)))?;
+                let status = resp.status();
+                let body: Value = resp.json().await.unwrap_or_default();
+                let token = body["access_token"]
+                    .as_str()
+                    .filter(|_| status.is_success())
+                    .ok_or_else(|| {
+                        Error::Tool(format!(
+                            "Spotify token refresh failed ({}): {}",
+                            status,
+                            body["error_description"]
+                                .as_str()
+                                .unwrap_or("no access token")
+                        ))
+                    })?
+                    .to_string();
+                // Refresh a minute early.
+                let ttl = body["expires_in"]
+                    .as_u64()
+                    .unwrap_or(3600)
+                    .saturating_sub(60);
+                *cached = Some((token.clone(), Instant::now() + Duration::from_secs(ttl)));
+                token
+            }
+        };
+        drop(cached);
+
+        let mut spotify = Self {
+            client,
+            token,
+            device: String::new(),
+        };
+        if !cfg.spotify_device.trim().is_empty() {
+            spotify.device = spotify.resolve_device(cfg.spotify_device.trim()).await?;
+        }
+        Ok(spotify)
+    }
+
+    async fn request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<Option<Value>> {
+        let mut query = query.to_vec();
+        if !self.device.is_empty() && method != reqwest::Method::GET {
+            query.push(("device_id", self.device.clone()));
+        }
+        let resp = self
+            .client
+            .request(method, format!("{}{}", SPOTIFY_API, path))
+            .bearer_auth(&self.token)
+            .query(&query)
+            .header(reqwest::header::CONTENT_LENGTH, "0")
+            .send()
+            .await
+            .map_err(|e| Error::Tool(format!("Spotify request failed: {}", e)))?;
+        let status = resp.status();
+        if status == reqwest::StatusCode::NO_CONTENT {
+            return Ok(None);
+        }
+        let body: Value = resp.json().await.unwrap_or_default();
+        if !status.is_success() {
+            let reason = body["error"]["reason"]
+                .as_str()
+                .or(body["error"]["message"].as_str())
+                .unwrap_or("unknown error");
+            let hint = if reason == "NO_ACTIVE_DEVICE" {
+                " — open Spotify on a device or set tools.mediaControl.spotifyDevice"
+            } else {
+                ""
+            };
+            return Err(Error::Tool(format!(
+                "Spotify API error ({}): {}{}",
+                status, reason, hint
+            )));
+        }
+        Ok(Some(body))
+    }
+
+    async fn resolve_device(&self, wanted: &str) -> Result<String> {
+        let body = self
+            .request(reqwest::Method::GET, "/me/player/devices", &[])
+            .await?
+            .unwrap_or_default();
+        body["devices"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|d| {
+                d["id"].as_str() == Some(wanted)
+                    || d["name"]
+                        .as_str()
+                        .is_some_and(|n| n.eq_ignore_ascii_case(wanted))
+            })
+            .and_then(|d| d["id"].as_str().map(str::to_string))
+            .ok_or_else(|| Error::NotFound(format!("Spotify device '{}' not found", wanted)))
+    }
+
+    async fn playback(&self) -> Result<Option<Value>> {
+        self.request(reqwest::Method::GET, "/me/player", &[]).await
+    }
+
+    async fn transport(&self, t: Transport) -> Result<Value> {
+        let t = match t {
+            Transport::Toggle => {
+                let playing = self
+                    .playback()
+                    .await?
+                    .is_some_and(|p| p["is_playing"].as_bool() == Some(true));
+                if playing {
+                    Transport::Pause
+                } else {
+                    Transport::Play
+                }
+            }
+            other => other,
+        };
+        let (method, path) = match t {
+            Transport::Play => (reqwest::Method::PUT, "/me/player/play"),
+            Transport::Pause | Transport::Stop | Transport::Toggle => {
+                (reqwest::Method::PUT, "/me/player/pause")
+            }
+            Transport::Next => (reqwest::Method::POST, "/me/player/next"),
+            Transport::Previous => (reqwest::Method::POST, "/me/player/previous"),
+        };
+        self.request(method, path, &[]).await?;
+        Ok(json!({"backend": "spotify", "command": t.playerctl()}))
+    }
+
+    async fn volume(&self, change: VolumeChange) -> Result<Value> {
+        let current = match change {
+            VolumeChange::Set(_) => 0,
+            VolumeChange::Delta(_) => self
+                .playback()
+                .await?
+                .and_then(|p| p["device"]["volume_percent"].as_u64())
+                .unwrap_or(50) as u8,
+        };
+        let level = change.apply(current);
+        self.request(
+            reqwest::Method::PUT,
+            "/me/player/volume",
+            &[("volume_percent", level.to_string())],
+        )
+        .await?;
+        Ok(json!({"backend": "spotify", "volume": level}))
+    }
+
+    async fn status(&self) -> Result<Value> {
+        let Some(p) = self.playback().await? else {
+            return Ok(json!({"backend": "spotify", "state": "idle"}));
+        };
+        let item = &p["item"];
+        let artists: Vec<&str> = item["artists"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|a| a["name"].as_str())
+            .collect();
+        Ok(json!({
+            "backend": "spotify",
+            "player": p["device"]["name"],
+            "state": if p["is_playing"].as_bool() == Some(true) { "playing" } else { "paused" },
+            "artist": artists.join(", "),
+            "title": item["name"],
+            "album": item["album"]["name"],
+            "player_volume": p["device"]["volume_percent"],
+        }))
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tool
+// ─────────────────────────────────────────────────────────────────────────────
+
+async fn run_local(cfg: &MediaControlConfig, action: &str, params: &Value) -> Result<Value> {
+    if let Some(t) = Transport::from_action(action) {
+        return local_transport(cfg, t).await;
+    }
+    match action {
+        "volume" => local_volume(VolumeChange::parse(&params["level"])?).await,
+        "mute" => local_mute(params["muted"].as_bool()).await,
+        "status" => local_status(cfg).await,
+        other => Err(Error::Tool(format!("Unknown action: {}", other))),
+    }
+}
+
+async fn run_spotify(
+    config: &Config,
+    workspace: &Path,
+    action: &str,
+    params: &Value,
+) -> Result<Value> {
+    let spotify = Spotify::connect(config, workspace).await?;
+    if let Some(t) = Transport::from_action(action) {
+        return spotify.transport(t).await;
+    }
+    match action {
+        "volume" => spotify.volume(VolumeChange::parse(&params["level"])?).await,
+        // Spotify has no mute; unmuting needs an explicit level.
+        "mute" if params["muted"].as_bool() != Some(false) => {
+            spotify.volume(VolumeChange::Set(0)).await
+        }
+        "mute" => Err(Error::Validation(
+            "Spotify has no unmute; use action='volume' with a level".to_string(),
+        )),
+        "status" => spotify.status().await,
+        other => Err(Error::Tool(format!("Unknown action: {}", other))),
+    }
+}
+
+pub struct MediaControlTool;
+
+#[async_trait]
+impl Tool for MediaControlTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "media_control",
+            description: "Control music/media playback: local players (MPRIS on Linux, Music/Spotify apps on macOS, media keys on Windows) or Spotify Connect. You MUST provide `action`. action='play'|'pause'|'toggle'|'next'|'previous'|'stop': no extra params. action='volume': requires `level` (0-100, or '+10'/'-10'). action='mute': optional `muted` (true/false; omit to toggle). action='status': what is playing. action='players': list controllable players. Optional `target`: 'local' or 'spotify' (default from config; auto falls back to Spotify when no local player responds).",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["play", "pause", "toggle", "next", "previous", "stop", "volume", "mute", "status", "players"],
+                        "description": "Action to perform"
+                    },
+                    "level": {
+                        "type": ["integer", "string"],
+                        "description": "(volume) 0-100, or a relative change like '+10' / '-20'"
+                    },
+                    "muted": {
+                        "type": "boolean",
+                        "description": "(mute) true to mute, false to unmute; omit to toggle"
+                    },
+                    "target": {
+                        "type": "string",
+                        "enum": ["local", "spotify"],
+                        "description": "Backend override"
+                    }
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        let action = params["action"].as_str().unwrap_or("");
+        match action {
+            "play" | "pause" | "toggle" | "next" | "previous" | "stop" | "mute" | "status"
+            | "players" => {}
+            "volume" => {
+                VolumeChange::parse(&params["level"])?;
+            }
+            other => {
+                return Err(Error::Validation(format!(
+                    "Unknown action '{}'. Use play, pause, toggle, next, previous, stop, volume, mute, status or players",
+                    other
+                )))
+            }
+        }
+        match params["target"].as_str() {
+            None | Some("local") | Some("spotify") => Ok(()),
+            Some(other) => Err(Error::Validation(format!(
+                "Invalid target '{}'. Use 'local' or 'spotify'",
+                other
+            ))),
+        }
+    }
+
+    fn prompt_rule(&self, _ctx: &PromptContext) -> Option<String> {
+        Some(
+            "- Music requests like \"pause the music, I'm on a call\", \"next song\" or \"turn it down\" go straight to `media_control`; no confirmation needed."
+                .to_string(),
+        )
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        let action = params["action"].as_str().unwrap_or("");
+        let cfg = &ctx.config.tools.media_control;
+        let backend = params["target"]
+            .as_str()
+            .unwrap_or(cfg.backend.as_str())
+            .to_string();
+
+        if action == "players" {
+            let mut out = json!({"local": local_players().await?});
+            if spotify_configured(cfg, &ctx.workspace) {
+                let spotify = Spotify::connect(&ctx.config, &ctx.workspace).await?;
+                let devices = spotify
+                    .request(reqwest::Method::GET, "/me/player/devices", &[])
+                    .await?
+                    .unwrap_or_default();
+                out["spotify"] = devices["devices"].clone();
+            }
+            return Ok(out);
+        }
+
+        let mut result = match backend.as_str() {
+            "spotify" => run_spotify(&ctx.config, &ctx.workspace, action, &params).await?,
+            "local" => run_local(cfg, action, &params).await?,
+            _ => match run_local(cfg, action, &params).await {
+                Ok(v) => v,
+                Err(e) if spotify_configured(cfg, &ctx.workspace) => {
+                    info!(error = %e, "Local media control failed, using Spotify Connect");
+                    let mut v = run_spotify(&ctx.config, &ctx.workspace, action, &params).await?;
+                    v["local_error"] = json!(e.to_string());
+                    v
+                }
+                Err(e) => return Err(e),
+            },
+        };
+        result["action"] = json!(action);
+        result["status"] = json!("ok");
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_volume_parse() {
+        assert_eq!(
+            VolumeChange::parse(&json!(40)).unwrap(),
+            VolumeChange::Set(40)
+        );
+        assert_eq!(
+            VolumeChange::parse(&json!("65%")).unwrap(),
+            VolumeChange::Set(65)
+        );
+        assert_eq!(
+            VolumeChange::parse(&json!("+10")).unwrap(),
+            VolumeChange::Delta(10)
+        );
+        assert_eq!(
+            VolumeChange::parse(&json!("-20")).unwrap(),
+            VolumeChange::Delta(-20)
+        );
+        assert!(VolumeChange::parse(&json!(150)).is_err());
+        assert!(VolumeChange::parse(&json!("loud")).is_err());
+        assert_eq!(VolumeChange::Delta(-30).apply(20), 0);
+        assert_eq!(VolumeChange::Delta(15).apply(90), 100);
+    }
+
+    #[test]
+    fn test_parse_playerctl_status() {
+        let status = parse_playerctl_status(
+            "spotify\tPlaying\tDaft Punk\tOne More Time\tDiscovery\t0.750000",
+        );
+        assert_eq!(status["player"], "spotify");
+        assert_eq!(status["state"], "playing");
+        assert_eq!(status["title"], "One More Time");
+        assert_eq!(status["player_volume"], 75);
+
+        let idle = parse_playerctl_status("vlc\tStopped\t\t\t\t");
+        assert_eq!(idle["state"], "stopped");
+        assert!(idle["title"].is_null());
+    }
+
+    #[test]
+    fn test_transport_mapping() {
+        assert_eq!(Transport::from_action("toggle"), Some(Transport::Toggle));
+        assert_eq!(Transport::from_action("volume"), None);
+        assert_eq!(Transport::Toggle.playerctl(), "play-pause");
+        assert_eq!(Transport::Next.applescript(), "next track");
+        assert_eq!(
+            Transport::Pause.windows_key(),
+            Transport::Play.windows_key()
+        );
+        assert_eq!(
+            playerctl_args("vlc", &["pause"]),
+            vec!["-p", "vlc", "pause"]
+        );
+    }
+}
@@ -39,7 +39,7 @@ impl Tool for MemoryQueryTool {
     fn schema(&self) -> ToolSchema {
         ToolSchema {
             name: "memory_query",
-            description: "Search and retrieve memory items using full-text search with structured filters. Use this to recall facts, preferences, past decisions, project context, or any previously stored information. Supports filtering by scope (long_term/short_term), type, tags, and time range. Results are ranked by relevance, importance, and recency.",
+            description: "Search and retrieve memory items using full-text search with structured filters. Use this to recall facts, preferences, past decisions, project context, or any previously stored information. Supports filtering by scope (long_term/short_term/team), type, tags, and time range. Results are ranked by relevance, importance, and recency. Set mode='semantic' to rank purely by meaning (embedding similarity) when memory.vector is enabled.",
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -47,9 +47,14 @@ impl Tool for MemoryQueryTool {
                         "type": "string",
                         "description": "Full-text search query. Leave empty to browse by filters."
                     },
+                    "mode": {
+                        "type": "string",
+                        "enum": ["hybrid", "semantic"],
+                        "description": "hybrid (default): full-text plus vector ranking. semantic: embedding similarity only; requires a query and memory.vector."
+                    },
                     "scope": {
                         "type": "string",
-                        "enum": ["long_term", "short_term"],
+                        "enum": ["long_term", "short_term", "team"],
                         "description": "Filter by memory scope. Omit to search all scopes."
                     },
                     "type": {
@@ -87,8 +92,14 @@ impl Tool for MemoryQueryTool {
         Some("- Search `memory_query` before asking the user for information you might already know.".to_string())
     }
 
-    fn validate(&self, _params: &Value) -> Result<()> {
-        Ok(())
+    fn validate(&self, params: &Value) -> Result<()> {
+        match params.get("mode").and_then(|v| v.as_str()) {
+            None | Some("hybrid") | Some("semantic") => Ok(()),
+            Some(other) => Err(Error::Validation(format!(
+                "mode must be 'hybrid' or 'semantic', got '{}'",
+                other
+            ))),
+        }
     }
 
     async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
@@ -113,7 +124,10 @@ impl Tool for MemoryQueryTool {
             "include_deleted": params.get("include_deleted").and_then(|v| v.as_bool()).unwrap_or(false),
         });
 
-        let results = store.query_json(query_params)?;
+        let results = match params.get("mode").and_then(|v| v.as_str()) {
+            Some("semantic") => store.semantic_query_json(query_params)?,
+            _ => store.query_json(query_params)?,
+        };
 
         debug!("memory_query executed");
         Ok(results)
@@ -125,7 +139,7 @@ impl Tool for MemoryUpsertTool {
     fn schema(&self) -> ToolSchema {
         ToolSchema {
             name: "memory_upsert",
-            description: "Save or update a memory item. Supports structured metadata (type, scope, tags, importance) and dedup_key for automatic merge/update of existing items. Use scope='long_term' for persistent facts/preferences, scope='short_term' for session notes and temporary context.",
+            description: "Save or update a memory item. Supports structured metadata (type, scope, tags, importance) and dedup_key for automatic merge/update of existing items. Use scope='long_term' for persistent facts/preferences, scope='short_term' for session notes and temporary context, scope='team' for organizational facts shared with the whole team (when team memory is enabled).",
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -135,8 +149,8 @@ impl Tool for MemoryUpsertTool {
                     },
                     "scope": {
                         "type": "string",
-                        "enum": ["long_term", "short_term"],
-                        "description": "Memory scope. 'long_term' for persistent facts/preferences, 'short_term' for session notes. Default: 'short_term'."
+                        "enum": ["long_term", "short_term", "team"],
+                        "description": "Memory scope. 'long_term' for persistent facts/preferences, 'short_term' for session notes, 'team' for facts every team member's agent should know. Default: 'short_term'."
                     },
                     "type": {
                         "type": "string",
@@ -284,7 +298,7 @@ impl Tool for MemoryForgetTool {
                     },
                     "scope": {
                         "type": "string",
-                        "enum": ["long_term", "short_term"],
+                        "enum": ["long_term", "short_term", "team"],
                         "description": "Filter for batch_delete: only delete items in this scope."
                     },
                     "type": {
@@ -483,6 +497,7 @@ mod tests {
             event_emitter: None,
             channel_contacts_file: None,
             response_cache: None,
+            artifacts: None,
         }
     }
 
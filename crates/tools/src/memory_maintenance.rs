@@ -599,6 +599,7 @@ mod tests {
             event_emitter: None,
             channel_contacts_file: None,
             response_cache: None,
+            artifacts: None,
         }
     }
 
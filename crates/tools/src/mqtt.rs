@@ -0,0 +1,487 @@
+//! Shared MQTT connection (`iot.mqtt`) used by `iot_control` and `stream_subscribe`.
+//!
+//! One client per process talks to the configured broker. Callers register topic
+//! listeners and get their messages on a channel; the event loop reconnects with
+//! backoff and re-sends every active subscription after each `CONNACK`, since the
+//! session is clean and the broker forgets them on disconnect.
+
+use blockcell_core::config::{MqttConfig, MqttTlsConfig};
+use blockcell_core::{Error, Result};
+use once_cell::sync::Lazy;
+use rumqttc::{
+    AsyncClient, Event, EventLoop, Key, MqttOptions, Packet, QoS, TlsConfiguration, Transport,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, watch, Mutex};
+use tracing::{debug, info, warn};
+
+/// The process-wide connection, created on first use.
+static MQTT_HUB: Lazy<Mutex<Option<Arc<MqttHub>>>> = Lazy::new(|| Mutex::new(None));
+
+/// Capacity of the request channel between the client and its event loop.
+const REQUEST_CHANNEL_CAPACITY: usize = 64;
+
+/// A message received on a subscribed topic.
+#[derive(Debug, Clone)]
+pub struct MqttMessage {
+    pub topic: String,
+    pub payload: Vec<u8>,
+    pub retain: bool,
+    pub timestamp: i64,
+}
+
+impl MqttMessage {
+    /// Payload as JSON when it parses, as a string otherwise.
+    pub fn payload_value(&self) -> Value {
+        let text = String::from_utf8_lossy(&self.payload);
+        serde_json::from_str(&text).unwrap_or_else(|_| Value::String(text.into_owned()))
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "topic": self.topic,
+            "payload": self.payload_value(),
+            "retain": self.retain,
+            "timestamp": self.timestamp,
+        })
+    }
+}
+
+struct Listener {
+    filter: String,
+    qos: QoS,
+    tx: mpsc::UnboundedSender<MqttMessage>,
+}
+
+pub struct MqttHub {
+    client: AsyncClient,
+    /// The config this connection was opened with; a changed config opens a new one.
+    config: MqttConfig,
+    listeners: Mutex<HashMap<u64, Listener>>,
+    next_listener_id: AtomicU64,
+    connected: watch::Sender<bool>,
+    last_error: Mutex<Option<String>>,
+    closed: AtomicBool,
+}
+
+/// The shared connection for `config`, opening it if needed.
+pub async fn hub(config: &MqttConfig) -> Result<Arc<MqttHub>> {
+    if !config.enabled || config.host.trim().is_empty() {
+        return Err(Error::Tool(
+            "MQTT is not configured; set iot.mqtt.enabled and iot.mqtt.host".into(),
+        ));
+    }
+
+    let mut slot = MQTT_HUB.lock().await;
+    if let Some(existing) = slot.as_ref() {
+        if same_connection(&existing.config, config) {
+            return Ok(existing.clone());
+        }
+    }
+
+    let (client, eventloop) = AsyncClient::new(mqtt_options(config)?, REQUEST_CHANNEL_CAPACITY);
+    let (connected, _) = watch::channel(false);
+    let hub = Arc::new(MqttHub {
+        client,
+        config: config.clone(),
+        listeners: Mutex::new(HashMap::new()),
+        next_listener_id: AtomicU64::new(1),
+        connected,
+        last_error: Mutex::new(None),
+        closed: AtomicBool::new(false),
+    });
+
+    // Closing the old connection closes its listener channels, so long-lived
+    // subscribers notice and subscribe again here.
+    if let Some(old) = slot.take() {
+        info!("MQTT config changed, reconnecting");
+        old.close().await;
+    }
+
+    info!(host = %config.host, port = config.effective_port(), tls = config.tls.enabled, "Connecting to MQTT broker");
+    tokio::spawn(run_event_loop(hub.clone(), eventloop));
+    *slot = Some(hub.clone());
+    Ok(hub)
+}
+
+/// Everything that affects the connection; `defaultQos` and `topicPrefix` don't.
+fn same_connection(a: &MqttConfig, b: &MqttConfig) -> bool {
+    let key = |c: &MqttConfig| {
+        json!([
+            c.host.trim(),
+            c.effective_port(),
+            c.client_id,
+            c.username,
+            c.password,
+            c.keep_alive_secs,
+            c.tls.enabled,
+            c.tls.ca_file,
+            c.tls.client_cert_file,
+            c.tls.client_key_file,
+        ])
+    };
+    key(a) == key(b)
+}
+
+fn mqtt_options(config: &MqttConfig) -> Result<MqttOptions> {
+    let client_id = config
+        .client_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| {
+            format!(
+                "blockcell-{}",
+                uuid::Uuid::new_v4()
+                    .to_string()
+                    .split('-')
+                    .next()
+                    .unwrap_or("x")
+            )
+        });
+    let mut options = MqttOptions::new(client_id, config.host.trim(), config.effective_port());
+    options.set_keep_alive(Duration::from_secs(config.keep_alive_secs.max(5)));
+    options.set_clean_session(true);
+    if let Some(username) = config.username.as_deref().filter(|u| !u.is_empty()) {
+        options.set_credentials(username, config.password.clone().unwrap_or_default());
+    }
+    if config.tls.enabled {
+        options.set_transport(Transport::tls_with_config(tls_configuration(&config.tls)?));
+    }
+    Ok(options)
+}
+
+/// PKCS#1 keys (`BEGIN RSA PRIVATE KEY`) load as RSA; anything else is read as PKCS#8.
+fn client_key(pem: Vec<u8>) -> Key {
+    if String::from_utf8_lossy(&pem).contains("BEGIN RSA PRIVATE KEY") {
+        Key::RSA(pem)
+    } else {
+        Key::ECC(pem)
+    }
+}
+
+fn tls_configuration(tls: &MqttTlsConfig) -> Result<TlsConfiguration> {
+    let read = |path: &str| {
+        std::fs::read(path)
+            .map_err(|e| Error::Tool(format!("Failed to read MQTT TLS file '{}': {}", path, e)))
+    };
+    let client_auth = match (&tls.client_cert_file, &tls.client_key_file) {
+        (Some(cert), Some(key)) => Some((read(cert)?, client_key(read(key)?))),
+        _ => None,
+    };
+
+    if let Some(ca_file) = tls.ca_file.as_deref().filter(|f| !f.is_empty()) {
+        return Ok(TlsConfiguration::Simple {
+            ca: read(ca_file)?,
+            alpn: None,
+            client_auth,
+        });
+    }
+    if client_auth.is_some() {
+        return Err(Error::Tool(
+            "iot.mqtt.tls.clientCertFile requires iot.mqtt.tls.caFile".into(),
+        ));
+    }
+
+    use tokio_rustls::rustls::{Certificate, ClientConfig, RootCertStore};
+    let mut root_store = RootCertStore::empty();
+    let native_certs = rustls_native_certs::load_native_certs()
+        .map_err(|e| Error::Tool(format!("Failed to load native certs: {}", e)))?;
+    for cert in native_certs {
+        let _ = root_store.add(&Certificate(cert.as_ref().to_vec()));
+    }
+    let config = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(root_store)
+        .with_no_client_auth();
+    Ok(TlsConfiguration::Rustls(Arc::new(config)))
+}
+
+async fn run_event_loop(hub: Arc<MqttHub>, mut eventloop: EventLoop) {
+    let mut failures: u32 = 0;
+    while !hub.closed.load(Ordering::SeqCst) {
+        match eventloop.poll().await {
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                failures = 0;
+                *hub.last_error.lock().await = None;
+                hub.connected.send_replace(true);
+                info!(host = %hub.config.host, "MQTT connected");
+                hub.resubscribe().await;
+            }
+            Ok(Event::Incoming(Packet::Publish(publish))) => {
+                hub.dispatch(MqttMessage {
+                    topic: publish.topic,
+                    payload: publish.payload.to_vec(),
+                    retain: publish.retain,
+                    timestamp: chrono::Utc::now().timestamp_millis(),
+                })
+                .await;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                if hub.closed.load(Ordering::SeqCst) {
+                    break;
+                }
+                hub.connected.send_replace(false);
+                *hub.last_error.lock().await = Some(e.to_string());
+                failures = failures.saturating_add(1);
+                let delay = reconnect_delay(failures);
+                warn!(error = %e, attempt = failures, delay_secs = delay.as_secs(), "MQTT connection lost, reconnecting");
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+    hub.connected.send_replace(false);
+    debug!(host = %hub.config.host, "MQTT event loop stopped");
+}
+
+/// Exponential backoff: 1s, 2s, 4s, ... capped at 60s.
+fn reconnect_delay(failures: u32) -> Duration {
+    Duration::from_secs(2u64.pow(failures.saturating_sub(1).min(6)).min(60))
+}
+
+impl MqttHub {
+    pub fn config(&self) -> &MqttConfig {
+        &self.config
+    }
+
+    pub fn is_connected(&self) -> bool {
+        *self.connected.borrow()
+    }
+
+    /// Watch connection state changes.
+    pub fn connection(&self) -> watch::Receiver<bool> {
+        self.connected.subscribe()
+    }
+
+    /// Receive messages on `filter` (`+` and `#` wildcards allowed) until
+    /// [`MqttHub::unsubscribe`] is called with the returned id. The channel closes
+    /// when the connection is replaced after a config change.
+    pub async fn subscribe(
+        &self,
+        filter: &str,
+        qos: QoS,
+    ) -> Result<(u64, mpsc::UnboundedReceiver<MqttMessage>)> {
+        validate_topic_filter(filter)?;
+        let (tx, rx) = mpsc::unbounded_channel();
+        let id = self.next_listener_id.fetch_add(1, Ordering::SeqCst);
+        self.listeners.lock().await.insert(
+            id,
+            Listener {
+                filter: filter.to_string(),
+                qos,
+                tx,
+            },
+        );
+        // Queued while disconnected; the CONNACK handler re-sends it anyway.
+        self.client
+            .subscribe(filter, qos)
+            .await
+            .map_err(|e| Error::Tool(format!("MQTT subscribe failed: {}", e)))?;
+        Ok((id, rx))
+    }
+
+    pub async fn unsubscribe(&self, listener_id: u64) {
+        let mut listeners = self.listeners.lock().await;
+        let Some(removed) = listeners.remove(&listener_id) else {
+            return;
+        };
+        let still_used = listeners.values().any(|l| l.filter == removed.filter);
+        drop(listeners);
+        if !still_used {
+            if let Err(e) = self.client.unsubscribe(&removed.filter).await {
+                debug!(error = %e, topic = %removed.filter, "MQTT unsubscribe failed");
+            }
+        }
+    }
+
+    pub async fn publish(
+        &self,
+        topic: &str,
+        payload: Vec<u8>,
+        qos: QoS,
+        retain: bool,
+    ) -> Result<()> {
+        if topic.is_empty() || topic.contains(['+', '#']) {
+            return Err(Error::Validation(format!(
+                "Invalid MQTT topic '{}': wildcards are not allowed when publishing",
+                topic
+            )));
+        }
+        self.client
+            .publish(topic, qos, retain, payload)
+            .await
+            .map_err(|e| Error::Tool(format!("MQTT publish failed: {}", e)))
+    }
+
+    /// Connection state and active topic filters.
+    pub async fn status(&self) -> Value {
+        let mut topics: Vec<String> = self
+            .listeners
+            .lock()
+            .await
+            .values()
+            .map(|l| l.filter.clone())
+            .collect();
+        topics.sort();
+        topics.dedup();
+        json!({
+            "connected": self.is_connected(),
+            "host": self.config.host,
+            "port": self.config.effective_port(),
+            "tls": self.config.tls.enabled,
+            "subscriptions": topics,
+            "last_error": *self.last_error.lock().await,
+        })
+    }
+
+    async fn resubscribe(&self) {
+        let mut filters: HashMap<String, QoS> = HashMap::new();
+        for listener in self.listeners.lock().await.values() {
+            let qos = filters
+                .entry(listener.filter.clone())
+                .or_insert(listener.qos);
+            if listener.qos as u8 > *qos as u8 {
+                *qos = listener.qos;
+            }
+        }
+        if filters.is_empty() {
+            return;
+        }
+        // Called from the event loop task, which is the one draining the request
+        // channel; awaiting `subscribe` here could deadlock once it fills up.
+        let client = self.client.clone();
+        tokio::spawn(async move {
+            for (filter, qos) in filters {
+                if let Err(e) = client.subscribe(&filter, qos).await {
+                    warn!(error = %e, topic = %filter, "MQTT resubscribe failed");
+                }
+            }
+        });
+    }
+
+    async fn dispatch(&self, message: MqttMessage) {
+        let mut listeners = self.listeners.lock().await;
+        listeners.retain(|_, listener| {
+            if !topic_matches(&listener.filter, &message.topic) {
+                return true;
+            }
+            listener.tx.send(message.clone()).is_ok()
+        });
+    }
+
+    async fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        self.listeners.lock().await.clear();
+        let _ = self.client.disconnect().await;
+    }
+}
+
+/// QoS level from a tool parameter.
+pub fn qos_from(level: u8) -> Result<QoS> {
+    match level {
+        0 => Ok(QoS::AtMostOnce),
+        1 => Ok(QoS::AtLeastOnce),
+        2 => Ok(QoS::ExactlyOnce),
+        _ => Err(Error::Validation(format!(
+            "MQTT qos must be 0, 1 or 2, got {}",
+            level
+        ))),
+    }
+}
+
+fn validate_topic_filter(filter: &str) -> Result<()> {
+    let levels: Vec<&str> = filter.split('/').collect();
+    let valid = !filter.is_empty()
+        && levels.iter().enumerate().all(|(i, level)| match *level {
+            "#" => i == levels.len() - 1,
+            "+" => true,
+            level => !level.contains(['+', '#']),
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::Validation(format!(
+            "Invalid MQTT topic filter '{}'",
+            filter
+        )))
+    }
+}
+
+/// MQTT topic filter matching: `+` is one level, a trailing `#` is any number of
+/// levels (including none). Wildcards at the first level don't match `$SYS`-style topics.
+pub fn topic_matches(filter: &str, topic: &str) -> bool {
+    if topic.starts_with('$') && (filter.starts_with('+') || filter.starts_with('#')) {
+        return false;
+    }
+    let mut topic_levels = topic.split('/');
+    for level in filter.split('/') {
+        match level {
+            "#" => return true,
+            "+" => {
+                if topic_levels.next().is_none() {
+                    return false;
+                }
+            }
+            level => {
+                if topic_levels.next() != Some(level) {
+                    return false;
+                }
+            }
+        }
+    }
+    topic_levels.next().is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_topic_matches_wildcards() {
+        assert!(topic_matches(
+            "home/+/temperature",
+            "home/kitchen/temperature"
+        ));
+        assert!(!topic_matches(
+            "home/+/temperature",
+            "home/kitchen/fridge/temperature"
+        ));
+        assert!(topic_matches("home/#", "home/kitchen/light"));
+        assert!(topic_matches("home/#", "home"));
+        assert!(topic_matches("home/kitchen", "home/kitchen"));
+        assert!(!topic_matches("home/kitchen", "home/kitchen/light"));
+        assert!(!topic_matches("#", "$SYS/broker/uptime"));
+        assert!(topic_matches("$SYS/#", "$SYS/broker/uptime"));
+
+        assert!(validate_topic_filter("home/+/state").is_ok());
+        assert!(validate_topic_filter("home/#/state").is_err());
+        assert!(validate_topic_filter("home/kit+chen").is_err());
+        assert!(validate_topic_filter("").is_err());
+    }
+
+    #[test]
+    fn test_connection_key_ignores_tool_defaults() {
+        let base = MqttConfig {
+            enabled: true,
+            host: "broker.local".to_string(),
+            ..Default::default()
+        };
+        let mut other = base.clone();
+        other.default_qos = 0;
+        other.topic_prefix = "home/".to_string();
+        assert!(same_connection(&base, &other));
+        other.username = Some("iot".to_string());
+        assert!(!same_connection(&base, &other));
+
+        assert_eq!(reconnect_delay(1), Duration::from_secs(1));
+        assert_eq!(reconnect_delay(3), Duration::from_secs(4));
+        assert_eq!(reconnect_delay(20), Duration::from_secs(60));
+        assert!(qos_from(3).is_err());
+    }
+}
@@ -778,7 +778,7 @@ mod tests {
     fn test_build_napcat_permissions() {
         let perms = build_napcat_permissions("napcat_set_group_kick");
         assert!(perms.has("channel:napcat"));
-        assert!(perms.has("napcat:napcat_set_group_kick"));
         assert!(perms.has("napcat:high_risk"));
+        assert!(!perms.has("napcat:read_only"));
     }
 }
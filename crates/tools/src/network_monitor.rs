@@ -1,8 +1,13 @@
 use async_trait::async_trait;
-use blockcell_core::{Error, Result};
+use blockcell_core::system_event::{EventPriority, SystemEvent};
+use blockcell_core::{Error, Paths, Result};
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::net::Ipv4Addr;
 use tracing::debug;
 
+use crate::egress::HttpClientFactory;
 use crate::{Tool, ToolContext, ToolSchema};
 
 /// Network monitoring and diagnostics tool.
@@ -16,13 +21,16 @@ use crate::{Tool, ToolContext, ToolSchema};
 /// - **whois**: Domain WHOIS lookup
 /// - **http_check**: HTTP endpoint health check with timing
 /// - **bandwidth**: Simple bandwidth estimation via download test
+/// - **lan_scan**: LAN discovery (TCP sweep + ARP cache, mDNS, SSDP) with new-device alerts
+/// - **lan_devices**: Device inventory accumulated across scans
+/// - **lan_trust**: Mark an inventoried device as known (and label it)
 pub struct NetworkMonitorTool;
 
 #[async_trait]
 impl Tool for NetworkMonitorTool {
     fn schema(&self) -> ToolSchema {
         let mut props = serde_json::Map::new();
-        props.insert("action".into(), json!({"type": "string", "description": "Action: ping|traceroute|port_scan|ssl_check|dns_lookup|whois|http_check|bandwidth"}));
+        props.insert("action".into(), json!({"type": "string", "description": "Action: ping|traceroute|port_scan|ssl_check|dns_lookup|whois|http_check|bandwidth|lan_scan|lan_devices|lan_trust"}));
         props.insert(
             "host".into(),
             json!({"type": "string", "description": "Target hostname or IP address"}),
@@ -47,11 +55,20 @@ impl Tool for NetworkMonitorTool {
             "max_hops".into(),
             json!({"type": "integer", "description": "(traceroute) Maximum hops. Default: 30"}),
         );
+        props.insert("subnet".into(), json!({"type": "string", "description": "(lan_scan) IPv4 CIDR to scan, e.g. '192.168.1.0/24'. Default: tools.lan.subnets or the local /24"}));
+        props.insert("mac".into(), json!({"type": "string", "description": "(lan_trust) Device MAC address (or pass `host` with its IP)"}));
+        props.insert(
+            "label".into(),
+            json!({"type": "string", "description": "(lan_trust) Friendly name for the device"}),
+        );
+        props.insert("trusted".into(), json!({"type": "boolean", "description": "(lan_trust) Mark as known (true, default) or unknown again (false)"}));
+        props.insert("untrusted_only".into(), json!({"type": "boolean", "description": "(lan_devices) Only devices not yet marked as known"}));
+        props.insert("since_hours".into(), json!({"type": "integer", "description": "(lan_devices) Only devices first seen within this many hours"}));
         props.insert("concurrent".into(), json!({"type": "integer", "description": "(port_scan) Max concurrent connections. Default: 50"}));
 
         ToolSchema {
             name: "network_monitor",
-            description: "Network diagnostics. You MUST provide `action`. action='ping'|'traceroute'|'dns_lookup'|'whois'|'http_check'|'ssl_check': requires `host`, plus action-specific optional fields like `count`, `timeout`, `record_type`, or `url`. action='port_scan': requires `host`, optional `ports`, `port_range`, and `concurrent`. action='bandwidth': optional `url`. Use action-specific fields only with the matching action.",
+            description: "Network diagnostics. You MUST provide `action`. action='ping'|'traceroute'|'dns_lookup'|'whois'|'http_check'|'ssl_check': requires `host`, plus action-specific optional fields like `count`, `timeout`, `record_type`, or `url`. action='port_scan': requires `host`, optional `ports`, `port_range`, and `concurrent`. action='bandwidth': optional `url`. action='lan_scan': optional `subnet`; inventories LAN devices and alerts on unknown newcomers. action='lan_devices': optional `untrusted_only`, `since_hours`. action='lan_trust': requires `mac` or `host`, optional `label`, `trusted`. Use action-specific fields only with the matching action.",
             parameters: json!({
                 "type": "object",
                 "properties": Value::Object(props),
@@ -71,6 +88,9 @@ impl Tool for NetworkMonitorTool {
             "whois",
             "http_check",
             "bandwidth",
+            "lan_scan",
+            "lan_devices",
+            "lan_trust",
         ];
         if !valid.contains(&action) {
             return Err(Error::Tool(format!(
@@ -82,7 +102,7 @@ impl Tool for NetworkMonitorTool {
         Ok(())
     }
 
-    async fn execute(&self, _ctx: ToolContext, params: Value) -> Result<Value> {
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
         let action = params["action"].as_str().unwrap_or("");
         debug!(action = action, "network_monitor execute");
 
@@ -93,8 +113,11 @@ impl Tool for NetworkMonitorTool {
             "ssl_check" => action_ssl_check(&params).await,
             "dns_lookup" => action_dns_lookup(&params).await,
             "whois" => action_whois(&params).await,
-            "http_check" => action_http_check(&params).await,
-            "bandwidth" => action_bandwidth(&params).await,
+            "http_check" => action_http_check(&ctx, &params).await,
+            "bandwidth" => action_bandwidth(&ctx, &params).await,
+            "lan_scan" => action_lan_scan(&ctx, &params).await,
+            "lan_devices" => action_lan_devices(&ctx, &params).await,
+            "lan_trust" => action_lan_trust(&ctx, &params).await,
             _ => Err(Error::Tool(format!("Unknown action: {}", action))),
         }
     }
@@ -619,7 +642,7 @@ async fn action_whois(params: &Value) -> Result<Value> {
 
 // ─── HTTP Check ─────────────────────────────────────────────────────────────
 
-async fn action_http_check(params: &Value) -> Result<Value> {
+async fn action_http_check(ctx: &ToolContext, params: &Value) -> Result<Value> {
     let url = params
         .get("url")
         .and_then(|v| v.as_str())
@@ -634,11 +657,13 @@ async fn action_http_check(params: &Value) -> Result<Value> {
 
     let timeout = params.get("timeout").and_then(|v| v.as_u64()).unwrap_or(10);
 
+    let egress = HttpClientFactory::for_tool(&ctx.config, "network_monitor");
+    egress.check(url).await?;
     let start = std::time::Instant::now();
 
-    let client = reqwest::Client::builder()
+    let client = egress
+        .builder_with_redirects(5)
         .timeout(std::time::Duration::from_secs(timeout))
-        .redirect(reqwest::redirect::Policy::limited(5))
         .build()
         .map_err(|e| Error::Tool(format!("HTTP client error: {}", e)))?;
 
@@ -686,14 +711,17 @@ async fn action_http_check(params: &Value) -> Result<Value> {
 
 // ─── Bandwidth ──────────────────────────────────────────────────────────────
 
-async fn action_bandwidth(params: &Value) -> Result<Value> {
+async fn action_bandwidth(ctx: &ToolContext, params: &Value) -> Result<Value> {
     let url = params
         .get("url")
         .and_then(|v| v.as_str())
         .unwrap_or("https://speed.cloudflare.com/__down?bytes=10000000");
     let timeout = params.get("timeout").and_then(|v| v.as_u64()).unwrap_or(30);
 
-    let client = reqwest::Client::builder()
+    let egress = HttpClientFactory::for_tool(&ctx.config, "network_monitor");
+    egress.check(url).await?;
+    let client = egress
+        .builder()
         .timeout(std::time::Duration::from_secs(timeout))
         .build()
         .map_err(|e| Error::Tool(format!("HTTP client error: {}", e)))?;
@@ -728,6 +756,730 @@ async fn action_bandwidth(params: &Value) -> Result<Value> {
     }))
 }
 
+// ─── LAN discovery ──────────────────────────────────────────────────────────
+
+const MDNS_ADDR: &str = "224.0.0.251:5353";
+const SSDP_ADDR: &str = "239.255.255.250:1900";
+/// Service types queried over mDNS; responders include their A/SRV records.
+const MDNS_SERVICES: &[&str] = &[
+    "_services._dns-sd._udp.local",
+    "_http._tcp.local",
+    "_googlecast._tcp.local",
+    "_airplay._tcp.local",
+    "_hap._tcp.local",
+    "_ipp._tcp.local",
+    "_printer._tcp.local",
+    "_smb._tcp.local",
+    "_ssh._tcp.local",
+    "_workstation._tcp.local",
+];
+/// Ports touched during the sweep. A refusal still proves the host is up and
+/// fills the kernel ARP cache, which is where MACs come from.
+const SWEEP_PORTS: &[u16] = &[80, 443, 22, 445];
+
+/// Small OUI table for devices commonly found on home networks.
+const OUI_VENDORS: &[(&str, &str)] = &[
+    ("B8:27:EB", "Raspberry Pi"),
+    ("DC:A6:32", "Raspberry Pi"),
+    ("E4:5F:01", "Raspberry Pi"),
+    ("D8:3A:DD", "Raspberry Pi"),
+    ("28:CD:C1", "Raspberry Pi"),
+    ("2C:CF:67", "Raspberry Pi"),
+    ("18:FE:34", "Espressif"),
+    ("24:0A:C4", "Espressif"),
+    ("24:6F:28", "Espressif"),
+    ("30:AE:A4", "Espressif"),
+    ("3C:71:BF", "Espressif"),
+    ("5C:CF:7F", "Espressif"),
+    ("60:01:94", "Espressif"),
+    ("84:CC:A8", "Espressif"),
+    ("84:F3:EB", "Espressif"),
+    ("A4:CF:12", "Espressif"),
+    ("BC:DD:C2", "Espressif"),
+    ("EC:FA:BC", "Espressif"),
+    ("00:17:88", "Philips Hue"),
+    ("EC:B5:FA", "Philips Hue"),
+    ("00:0E:58", "Sonos"),
+    ("5C:AA:FD", "Sonos"),
+    ("94:9F:3E", "Sonos"),
+    ("B8:E9:37", "Sonos"),
+    ("48:A6:B8", "Sonos"),
+    ("54:60:09", "Google"),
+    ("3C:5A:B4", "Google"),
+    ("F4:F5:D8", "Google"),
+    ("F4:F5:E8", "Google"),
+    ("74:C2:46", "Amazon"),
+    ("68:37:E9", "Amazon"),
+    ("FC:65:DE", "Amazon"),
+    ("44:65:0D", "Amazon"),
+    ("F0:27:2D", "Amazon"),
+    ("40:B4:CD", "Amazon"),
+    ("00:1C:B3", "Apple"),
+    ("28:CF:E9", "Apple"),
+    ("3C:07:54", "Apple"),
+    ("40:6C:8F", "Apple"),
+    ("A4:5E:60", "Apple"),
+    ("AC:BC:32", "Apple"),
+    ("F0:18:98", "Apple"),
+    ("24:A4:3C", "Ubiquiti"),
+    ("04:18:D6", "Ubiquiti"),
+    ("78:8A:20", "Ubiquiti"),
+    ("FC:EC:DA", "Ubiquiti"),
+    ("74:83:C2", "Ubiquiti"),
+    ("F0:9F:C2", "Ubiquiti"),
+    ("50:C7:BF", "TP-Link"),
+    ("14:CC:20", "TP-Link"),
+    ("EC:08:6B", "TP-Link"),
+    ("F4:F2:6D", "TP-Link"),
+    ("98:DA:C4", "TP-Link"),
+    ("28:6C:07", "Xiaomi"),
+    ("64:09:80", "Xiaomi"),
+    ("78:11:DC", "Xiaomi"),
+    ("B0:A7:37", "Roku"),
+    ("D8:31:34", "Roku"),
+    ("00:11:32", "Synology"),
+    ("00:50:56", "VMware"),
+    ("00:0C:29", "VMware"),
+    ("52:54:00", "QEMU/KVM"),
+];
+
+/// Device inventory persisted across scans — saved to workspace/network/lan_devices.json
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LanInventory {
+    #[serde(default)]
+    devices: Vec<LanDevice>,
+    #[serde(default)]
+    scans: u64,
+    #[serde(default)]
+    last_scan_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LanDevice {
+    /// Normalized MAC (`aa:bb:cc:dd:ee:ff`), or `ip:<addr>` when no MAC was learned.
+    key: String,
+    mac: Option<String>,
+    ip: String,
+    vendor: Option<String>,
+    hostname: Option<String>,
+    #[serde(default)]
+    services: Vec<String>,
+    #[serde(default)]
+    ssdp_server: Option<String>,
+    #[serde(default)]
+    trusted: bool,
+    #[serde(default)]
+    label: Option<String>,
+    first_seen: i64,
+    last_seen: i64,
+    #[serde(default)]
+    seen_count: u64,
+}
+
+/// What one scan learned about an address.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Discovered {
+    mac: Option<String>,
+    hostname: Option<String>,
+    services: Vec<String>,
+    ssdp_server: Option<String>,
+}
+
+fn inventory_path(paths: &Paths) -> std::path::PathBuf {
+    paths.workspace().join("network").join("lan_devices.json")
+}
+
+fn load_inventory(paths: &Paths) -> Result<LanInventory> {
+    let path = inventory_path(paths);
+    if !path.exists() {
+        return Ok(LanInventory::default());
+    }
+    let content = std::fs::read_to_string(&path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+fn save_inventory(paths: &Paths, inv: &LanInventory) -> Result<()> {
+    let path = inventory_path(paths);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(inv)?)?;
+    Ok(())
+}
+
+/// Parse `a.b.c.d/len` into `(network, mask)`.
+fn parse_cidr(cidr: &str) -> Result<(u32, u32)> {
+    let (addr, len) = cidr.trim().split_once('/').unwrap_or((cidr.trim(), "32"));
+    let addr: Ipv4Addr = addr
+        .parse()
+        .map_err(|_| Error::Validation(format!("Invalid subnet address '{}'", cidr)))?;
+    let len: u32 = len
+        .parse()
+        .ok()
+        .filter(|l| *l <= 32)
+        .ok_or_else(|| Error::Validation(format!("Invalid prefix length in '{}'", cidr)))?;
+    let mask = if len == 0 { 0 } else { u32::MAX << (32 - len) };
+    Ok((u32::from(addr) & mask, mask))
+}
+
+/// Usable host addresses of a subnet (network and broadcast excluded below /31).
+fn subnet_hosts(cidr: &str, max_hosts: u32) -> Result<Vec<Ipv4Addr>> {
+    let (network, mask) = parse_cidr(cidr)?;
+    let size = (!mask) as u64 + 1;
+    let (first, last) = if size <= 2 {
+        (network as u64, network as u64 + size - 1)
+    } else {
+        (network as u64 + 1, network as u64 + size - 2)
+    };
+    let usable = last - first + 1;
+    if usable > max_hosts as u64 {
+        return Err(Error::Validation(format!(
+            "Subnet {} has {} hosts, above tools.lan.maxHosts ({})",
+            cidr, usable, max_hosts
+        )));
+    }
+    Ok((first..=last).map(|n| Ipv4Addr::from(n as u32)).collect())
+}
+
+fn in_subnets(ip: Ipv4Addr, subnets: &[(u32, u32)]) -> bool {
+    subnets
+        .iter()
+        .any(|(net, mask)| u32::from(ip) & mask == *net)
+}
+
+/// Primary interface address, found by "connecting" a UDP socket (no packet is sent).
+fn primary_ipv4() -> Option<Ipv4Addr> {
+    let sock = std::net::UdpSocket::bind("0.0.0.0:0").ok()?;
+    sock.connect("8.8.8.8:53").ok()?;
+    match sock.local_addr().ok()?.ip() {
+        std::net::IpAddr::V4(v4) if !v4.is_loopback() && !v4.is_unspecified() => Some(v4),
+        _ => None,
+    }
+}
+
+fn normalize_mac(raw: &str) -> Option<String> {
+    let parts: Vec<&str> = raw.split([':', '-']).collect();
+    if parts.len() != 6 {
+        return None;
+    }
+    let mut bytes = [0u8; 6];
+    for (b, p) in bytes.iter_mut().zip(&parts) {
+        if p.is_empty() || p.len() > 2 {
+            return None;
+        }
+        *b = u8::from_str_radix(p, 16).ok()?;
+    }
+    if bytes == [0; 6] || bytes == [0xff; 6] {
+        return None;
+    }
+    Some(
+        bytes
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<_>>()
+            .join(":"),
+    )
+}
+
+/// Parse `/proc/net/arp`, BSD/macOS `arp -an` or Windows `arp -a` output.
+fn parse_arp_table(text: &str) -> Vec<(Ipv4Addr, String)> {
+    let mut out = Vec::new();
+    for line in text.lines() {
+        let mut ip = None;
+        let mut mac = None;
+        for token in line.split_whitespace() {
+            let token = token.trim_matches(|c| c == '(' || c == ')');
+            if ip.is_none() {
+                if let Ok(addr) = token.parse::<Ipv4Addr>() {
+                    ip = Some(addr);
+                    continue;
+                }
+            }
+            if mac.is_none() {
+                mac = normalize_mac(token);
+            }
+        }
+        if let (Some(ip), Some(mac)) = (ip, mac) {
+            out.push((ip, mac));
+        }
+    }
+    out
+}
+
+async fn read_arp_table() -> Vec<(Ipv4Addr, String)> {
+    if let Ok(text) = tokio::fs::read_to_string("/proc/net/arp").await {
+        return parse_arp_table(&text);
+    }
+    let args: &[&str] = if cfg!(windows) { &["-a"] } else { &["-an"] };
+    match tokio::process::Command::new("arp")
+        .args(args)
+        .kill_on_drop(true)
+        .output()
+        .await
+    {
+        Ok(out) => parse_arp_table(&String::from_utf8_lossy(&out.stdout)),
+        Err(e) => {
+            debug!(error = %e, "arp table unavailable");
+            Vec::new()
+        }
+    }
+}
+
+fn mac_vendor(mac: &str) -> Option<&'static str> {
+    let first = u8::from_str_radix(mac.get(0..2)?, 16).ok()?;
+    if first & 0x02 != 0 {
+        return Some("Randomized (locally administered)");
+    }
+    let prefix = mac.get(0..8)?.to_uppercase();
+    OUI_VENDORS
+        .iter()
+        .find(|(oui, _)| *oui == prefix)
+        .map(|(_, v)| *v)
+}
+
+/// TCP-touch every host; returns the ones that answered (accepted or refused).
+async fn sweep(hosts: Vec<Ipv4Addr>, timeout_ms: u64) -> Vec<Ipv4Addr> {
+    let mut alive = Vec::new();
+    for chunk in hosts.chunks(128) {
+        let mut handles = Vec::with_capacity(chunk.len());
+        for &ip in chunk {
+            handles.push(tokio::spawn(async move {
+                for &port in SWEEP_PORTS {
+                    let attempt = tokio::time::timeout(
+                        std::time::Duration::from_millis(timeout_ms),
+                        tokio::net::TcpStream::connect((ip, port)),
+                    )
+                    .await;
+                    match attempt {
+                        Ok(Ok(_)) => return Some(ip),
+                        Ok(Err(e)) if e.kind() == std::io::ErrorKind::ConnectionRefused => {
+                            return Some(ip)
+                        }
+                        _ => {}
+                    }
+                }
+                None
+            }));
+        }
+        for h in handles {
+            if let Ok(Some(ip)) = h.await {
+                alive.push(ip);
+            }
+        }
+    }
+    alive
+}
+
+fn mdns_query() -> Vec<u8> {
+    let mut pkt = vec![0, 0, 0, 0];
+    pkt.extend_from_slice(&(MDNS_SERVICES.len() as u16).to_be_bytes());
+    pkt.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+    for name in MDNS_SERVICES {
+        for label in name.split('.') {
+            pkt.push(label.len() as u8);
+            pkt.extend_from_slice(label.as_bytes());
+        }
+        pkt.push(0);
+        // QTYPE=PTR, QCLASS=IN
+        pkt.extend_from_slice(&[0, 12, 0, 1]);
+    }
+    pkt
+}
+
+/// Read a (possibly compressed) DNS name; returns the name and the offset after it.
+fn read_dns_name(buf: &[u8], mut pos: usize) -> Option<(String, usize)> {
+    let mut labels = Vec::new();
+    let mut end = None;
+    for _ in 0..64 {
+        let len = *buf.get(pos)? as usize;
+        if len == 0 {
+            return Some((labels.join("."), end.unwrap_or(pos + 1)));
+        }
+        if len & 0xC0 == 0xC0 {
+            let ptr = ((len & 0x3F) << 8) | *buf.get(pos + 1)? as usize;
+            end.get_or_insert(pos + 2);
+            pos = ptr;
+            continue;
+        }
+        let label = buf.get(pos + 1..pos + 1 + len)?;
+        labels.push(String::from_utf8_lossy(label).into_owned());
+        pos += 1 + len;
+    }
+    None
+}
+
+/// Pull hostname and service instances for `source` out of an mDNS response.
+fn parse_mdns_response(buf: &[u8], source: Ipv4Addr) -> Discovered {
+    let mut found = Discovered::default();
+    let Some(header) = buf.get(..12) else {
+        return found;
+    };
+    let count = |i: usize| u16::from_be_bytes([header[i], header[i + 1]]) as usize;
+    let (qd, records) = (count(4), count(6) + count(8) + count(10));
+    let mut pos = 12;
+    for _ in 0..qd {
+        let Some((_, next)) = read_dns_name(buf, pos) else {
+            return found;
+        };
+        pos = next + 4;
+    }
+    let mut srv_target = None;
+    for _ in 0..records {
+        let Some((name, next)) = read_dns_name(buf, pos) else {
+            break;
+        };
+        let Some(fixed) = buf.get(next..next + 10) else {
+            break;
+        };
+        let rtype = u16::from_be_bytes([fixed[0], fixed[1]]);
+        let rdlen = u16::from_be_bytes([fixed[8], fixed[9]]) as usize;
+        let rdata = next + 10;
+        let Some(data) = buf.get(rdata..rdata + rdlen) else {
+            break;
+        };
+        match rtype {
+            1 if rdlen == 4 && Ipv4Addr::new(data[0], data[1], data[2], data[3]) == source => {
+                found.hostname.get_or_insert(name);
+            }
+            12 => {
+                if let Some((target, _)) = read_dns_name(buf, rdata) {
+                    if name != "_services._dns-sd._udp.local" {
+                        found.services.push(target);
+                    }
+                }
+            }
+            33 if rdlen > 6 => {
+                if let Some((target, _)) = read_dns_name(buf, rdata + 6) {
+                    srv_target.get_or_insert(target);
+                }
+            }
+            _ => {}
+        }
+        pos = rdata + rdlen;
+    }
+    if found.hostname.is_none() {
+        found.hostname = srv_target;
+    }
+    found
+}
+
+fn parse_ssdp_response(text: &str) -> Option<String> {
+    if !text.starts_with("HTTP/1.1 200") && !text.starts_with("NOTIFY") {
+        return None;
+    }
+    text.lines().find_map(|line| {
+        let (k, v) = line.split_once(':')?;
+        k.trim()
+            .eq_ignore_ascii_case("server")
+            .then(|| v.trim().to_string())
+    })
+}
+
+/// Multicast a probe and collect replies until `listen` elapses.
+async fn multicast_collect(
+    target: &str,
+    probe: &[u8],
+    listen: std::time::Duration,
+) -> Vec<(Ipv4Addr, Vec<u8>)> {
+    let mut replies = Vec::new();
+    let Ok(sock) = tokio::net::UdpSocket::bind("0.0.0.0:0").await else {
+        return replies;
+    };
+    let _ = sock.set_multicast_ttl_v4(2);
+    if let Err(e) = sock.send_to(probe, target).await {
+        debug!(target, error = %e, "multicast probe failed");
+        return replies;
+    }
+    let deadline = tokio::time::Instant::now() + listen;
+    let mut buf = vec![0u8; 9000];
+    while let Ok(Ok((n, from))) = tokio::time::timeout_at(deadline, sock.recv_from(&mut buf)).await
+    {
+        if let std::net::IpAddr::V4(ip) = from.ip() {
+            replies.push((ip, buf[..n].to_vec()));
+        }
+    }
+    replies
+}
+
+async fn discover_mdns(listen: std::time::Duration) -> HashMap<Ipv4Addr, Discovered> {
+    let mut out: HashMap<Ipv4Addr, Discovered> = HashMap::new();
+    for (ip, pkt) in multicast_collect(MDNS_ADDR, &mdns_query(), listen).await {
+        let found = parse_mdns_response(&pkt, ip);
+        let entry = out.entry(ip).or_default();
+        if entry.hostname.is_none() {
+            entry.hostname = found.hostname;
+        }
+        for s in found.services {
+            if !entry.services.contains(&s) {
+                entry.services.push(s);
+            }
+        }
+    }
+    out
+}
+
+async fn discover_ssdp(listen: std::time::Duration) -> HashMap<Ipv4Addr, String> {
+    let probe = "M-SEARCH * HTTP/1.1\r\nHOST: 239.255.255.250:1900\r\nMAN: \"ssdp:discover\"\r\nMX: 1\r\nST: ssdp:all\r\n\r\n";
+    let mut out = HashMap::new();
+    for (ip, pkt) in multicast_collect(SSDP_ADDR, probe.as_bytes(), listen).await {
+        if let Some(server) = parse_ssdp_response(&String::from_utf8_lossy(&pkt)) {
+            out.entry(ip).or_insert(server);
+        }
+    }
+    out
+}
+
+/// Merge one scan into the inventory; returns the keys of devices seen for the first time.
+fn merge_scan(
+    inv: &mut LanInventory,
+    found: &BTreeMap<Ipv4Addr, Discovered>,
+    now: i64,
+) -> Vec<String> {
+    let mut new_keys = Vec::new();
+    for (ip, d) in found {
+        let key = d.mac.clone().unwrap_or_else(|| format!("ip:{}", ip));
+        match inv.devices.iter_mut().find(|dev| dev.key == key) {
+            Some(dev) => {
+                dev.ip = ip.to_string();
+                dev.last_seen = now;
+                dev.seen_count += 1;
+                if d.hostname.is_some() {
+                    dev.hostname = d.hostname.clone();
+                }
+                if d.ssdp_server.is_some() {
+                    dev.ssdp_server = d.ssdp_server.clone();
+                }
+                for s in &d.services {
+                    if !dev.services.contains(s) {
+                        dev.services.push(s.clone());
+                    }
+                }
+            }
+            None => {
+                new_keys.push(key.clone());
+                inv.devices.push(LanDevice {
+                    vendor: d.mac.as_deref().and_then(mac_vendor).map(String::from),
+                    key,
+                    mac: d.mac.clone(),
+                    ip: ip.to_string(),
+                    hostname: d.hostname.clone(),
+                    services: d.services.clone(),
+                    ssdp_server: d.ssdp_server.clone(),
+                    trusted: false,
+                    label: None,
+                    first_seen: now,
+                    last_seen: now,
+                    seen_count: 1,
+                });
+            }
+        }
+    }
+    inv.scans += 1;
+    inv.last_scan_at = Some(now);
+    new_keys
+}
+
+fn device_json(d: &LanDevice) -> Value {
+    json!({
+        "ip": d.ip,
+        "mac": d.mac,
+        "vendor": d.vendor,
+        "hostname": d.hostname,
+        "label": d.label,
+        "services": d.services,
+        "ssdp_server": d.ssdp_server,
+        "trusted": d.trusted,
+        "first_seen": chrono::DateTime::from_timestamp(d.first_seen, 0).map(|t| t.to_rfc3339()),
+        "last_seen": chrono::DateTime::from_timestamp(d.last_seen, 0).map(|t| t.to_rfc3339()),
+        "seen_count": d.seen_count,
+    })
+}
+
+fn workspace_paths(ctx: &ToolContext) -> Paths {
+    match ctx.workspace.parent() {
+        Some(base) => Paths::with_base(base.to_path_buf()),
+        None => Paths::new(),
+    }
+}
+
+async fn action_lan_scan(ctx: &ToolContext, params: &Value) -> Result<Value> {
+    let cfg = &ctx.config.tools.lan;
+    let subnets: Vec<String> = match params.get("subnet").and_then(|v| v.as_str()) {
+        Some(s) => vec![s.to_string()],
+        None if !cfg.subnets.is_empty() => cfg.subnets.clone(),
+        None => {
+            let ip = primary_ipv4().ok_or_else(|| {
+                Error::Tool(
+                    "Could not detect the local network; set tools.lan.subnets or pass `subnet`"
+                        .into(),
+                )
+            })?;
+            let o = ip.octets();
+            vec![format!("{}.{}.{}.0/24", o[0], o[1], o[2])]
+        }
+    };
+
+    let mut hosts = Vec::new();
+    let mut masks = Vec::new();
+    for cidr in &subnets {
+        hosts.extend(subnet_hosts(cidr, cfg.max_hosts)?);
+        masks.push(parse_cidr(cidr)?);
+    }
+    if hosts.len() as u64 > cfg.max_hosts as u64 {
+        return Err(Error::Validation(format!(
+            "{} hosts across subnets, above tools.lan.maxHosts ({})",
+            hosts.len(),
+            cfg.max_hosts
+        )));
+    }
+
+    let started = std::time::Instant::now();
+    let listen = std::time::Duration::from_secs(cfg.discovery_secs.clamp(1, 15));
+    let (alive, mdns, ssdp) = tokio::join!(
+        sweep(hosts, cfg.probe_timeout_ms.clamp(100, 5000)),
+        discover_mdns(listen),
+        discover_ssdp(listen),
+    );
+    let arp = read_arp_table().await;
+
+    let mut found: BTreeMap<Ipv4Addr, Discovered> = BTreeMap::new();
+    for ip in alive {
+        found.entry(ip).or_default();
+    }
+    for (ip, d) in mdns.into_iter().filter(|(ip, _)| in_subnets(*ip, &masks)) {
+        let entry = found.entry(ip).or_default();
+        entry.hostname = d.hostname;
+        entry.services = d.services;
+    }
+    for (ip, server) in ssdp.into_iter().filter(|(ip, _)| in_subnets(*ip, &masks)) {
+        found.entry(ip).or_default().ssdp_server = Some(server);
+    }
+    for (ip, mac) in arp.into_iter().filter(|(ip, _)| in_subnets(*ip, &masks)) {
+        found.entry(ip).or_default().mac = Some(mac);
+    }
+
+    let paths = workspace_paths(ctx);
+    let mut inv = load_inventory(&paths)?;
+    let baseline = inv.scans == 0;
+    let now = chrono::Utc::now().timestamp();
+    let new_keys = merge_scan(&mut inv, &found, now);
+    save_inventory(&paths, &inv)?;
+
+    let new_devices: Vec<&LanDevice> = inv
+        .devices
+        .iter()
+        .filter(|d| new_keys.contains(&d.key))
+        .collect();
+    let unknown: Vec<&&LanDevice> = new_devices.iter().filter(|d| !d.trusted).collect();
+    let mut alerted = false;
+    if !baseline && !unknown.is_empty() && cfg.alert_on_new_device {
+        if let Some(emitter) = &ctx.event_emitter {
+            let names: Vec<String> = unknown
+                .iter()
+                .map(|d| {
+                    format!(
+                        "{} ({}{})",
+                        d.ip,
+                        d.mac.as_deref().unwrap_or("no MAC"),
+                        d.vendor
+                            .as_deref()
+                            .map(|v| format!(", {}", v))
+                            .unwrap_or_default()
+                    )
+                })
+                .collect();
+            let mut event = SystemEvent::new_main_session(
+                "network.new_device",
+                "network_monitor",
+                EventPriority::High,
+                format!("{} unknown device(s) joined the LAN", unknown.len()),
+                names.join("; "),
+            );
+            event.dedup_key = Some(format!(
+                "lan:new:{}",
+                unknown
+                    .iter()
+                    .map(|d| d.key.as_str())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ));
+            event.details = json!({
+                "devices": unknown.iter().map(|d| device_json(d)).collect::<Vec<_>>(),
+            });
+            emitter.emit(event);
+            alerted = true;
+        }
+    }
+
+    let seen: Vec<Value> = inv
+        .devices
+        .iter()
+        .filter(|d| d.last_seen == now)
+        .map(device_json)
+        .collect();
+    Ok(json!({
+        "subnets": subnets,
+        "device_count": seen.len(),
+        "devices": seen,
+        "new_devices": new_devices.iter().map(|d| device_json(d)).collect::<Vec<_>>(),
+        "baseline": baseline,
+        "alert_raised": alerted,
+        "inventory_size": inv.devices.len(),
+        "duration_ms": started.elapsed().as_millis() as u64,
+    }))
+}
+
+async fn action_lan_devices(ctx: &ToolContext, params: &Value) -> Result<Value> {
+    let inv = load_inventory(&workspace_paths(ctx))?;
+    let untrusted_only = params.get("untrusted_only").and_then(|v| v.as_bool()) == Some(true);
+    let since = params
+        .get("since_hours")
+        .and_then(|v| v.as_u64())
+        .map(|h| chrono::Utc::now().timestamp() - (h as i64) * 3600);
+    let mut devices: Vec<&LanDevice> = inv
+        .devices
+        .iter()
+        .filter(|d| !untrusted_only || !d.trusted)
+        .filter(|d| since.is_none_or(|t| d.first_seen >= t))
+        .collect();
+    devices.sort_by_key(|d| std::cmp::Reverse(d.last_seen));
+    Ok(json!({
+        "count": devices.len(),
+        "devices": devices.into_iter().map(device_json).collect::<Vec<_>>(),
+        "scans": inv.scans,
+        "last_scan_at": inv.last_scan_at.and_then(|t| chrono::DateTime::from_timestamp(t, 0)).map(|t| t.to_rfc3339()),
+    }))
+}
+
+async fn action_lan_trust(ctx: &ToolContext, params: &Value) -> Result<Value> {
+    let target = params
+        .get("mac")
+        .or_else(|| params.get("host"))
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| Error::Tool("mac (or host IP) is required for lan_trust".into()))?;
+    let key = normalize_mac(target);
+    let paths = workspace_paths(ctx);
+    let mut inv = load_inventory(&paths)?;
+    let dev = inv
+        .devices
+        .iter_mut()
+        .find(|d| match &key {
+            Some(mac) => d.mac.as_deref() == Some(mac.as_str()),
+            None => d.ip == target,
+        })
+        .ok_or_else(|| Error::NotFound(format!("No device '{}' in the LAN inventory", target)))?;
+    dev.trusted = params
+        .get("trusted")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+    if let Some(label) = params.get("label").and_then(|v| v.as_str()) {
+        dev.label = Some(label.to_string()).filter(|l| !l.trim().is_empty());
+    }
+    let out = device_json(dev);
+    save_inventory(&paths, &inv)?;
+    Ok(out)
+}
+
 // ─── Helpers ────────────────────────────────────────────────────────────────
 
 fn extract_between<'a>(text: &'a str, start: &str, end: &str) -> Option<&'a str> {
@@ -827,4 +1579,147 @@ mod tests {
             assert!(tool.validate(&json!({"action": action})).is_ok());
         }
     }
+
+    #[test]
+    fn test_subnet_hosts_and_membership() {
+        let hosts = subnet_hosts("192.168.1.77/30", 1024).unwrap();
+        assert_eq!(
+            hosts,
+            vec![
+                Ipv4Addr::new(192, 168, 1, 77),
+                Ipv4Addr::new(192, 168, 1, 78)
+            ]
+        );
+        assert_eq!(subnet_hosts("10.0.0.0/24", 1024).unwrap().len(), 254);
+        assert_eq!(subnet_hosts("10.0.0.5/32", 1).unwrap().len(), 1);
+        assert!(subnet_hosts("10.0.0.0/16", 1024).is_err());
+        assert!(subnet_hosts("10.0.0.0/33", 1024).is_err());
+        assert!(subnet_hosts("nope/24", 1024).is_err());
+
+        let nets = vec![parse_cidr("192.168.1.0/24").unwrap()];
+        assert!(in_subnets(Ipv4Addr::new(192, 168, 1, 9), &nets));
+        assert!(!in_subnets(Ipv4Addr::new(192, 168, 2, 9), &nets));
+    }
+
+    #[test]
+    fn test_parse_arp_table_formats() {
+        let linux =
+            "IP address       HW type     Flags       HW address            Mask     Device\n\
+            192.168.1.1      0x1         0x2         b8:27:eb:12:34:56     *        eth0\n\
+            192.168.1.50     0x1         0x0         00:00:00:00:00:00     *        eth0\n";
+        assert_eq!(
+            parse_arp_table(linux),
+            vec![(
+                Ipv4Addr::new(192, 168, 1, 1),
+                "b8:27:eb:12:34:56".to_string()
+            )]
+        );
+        let bsd = "? (192.168.1.20) at 0:17:88:a:b:c on en0 ifscope [ethernet]\n\
+            ? (192.168.1.255) at ff:ff:ff:ff:ff:ff on en0 ifscope [ethernet]";
+        assert_eq!(
+            parse_arp_table(bsd),
+            vec![(
+                Ipv4Addr::new(192, 168, 1, 20),
+                "00:17:88:0a:0b:0c".to_string()
+            )]
+        );
+        let windows = "  192.168.1.30          5c-aa-fd-01-02-03     dynamic";
+        assert_eq!(parse_arp_table(windows)[0].1, "5c:aa:fd:01:02:03");
+    }
+
+    #[test]
+    fn test_mac_vendor() {
+        assert_eq!(mac_vendor("b8:27:eb:12:34:56"), Some("Raspberry Pi"));
+        assert_eq!(mac_vendor("00:17:88:0a:0b:0c"), Some("Philips Hue"));
+        assert_eq!(
+            mac_vendor("da:a1:19:00:00:01"),
+            Some("Randomized (locally administered)")
+        );
+        assert_eq!(mac_vendor("00:00:01:00:00:01"), None);
+    }
+
+    #[test]
+    fn test_parse_mdns_response() {
+        // Response: 1 PTR answer + 1 A record in additional, with name compression.
+        let mut pkt = vec![0, 0, 0x84, 0, 0, 0, 0, 1, 0, 0, 0, 1];
+        let svc_at = pkt.len();
+        for l in ["_googlecast", "_tcp", "local"] {
+            pkt.push(l.len() as u8);
+            pkt.extend_from_slice(l.as_bytes());
+        }
+        pkt.push(0);
+        pkt.extend_from_slice(&[0, 12, 0, 1, 0, 0, 0, 120]);
+        let rdata = [&[11u8][..], b"Living Room", &[0xC0, svc_at as u8]].concat();
+        pkt.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        pkt.extend_from_slice(&rdata);
+        for l in ["chromecast", "local"] {
+            pkt.push(l.len() as u8);
+            pkt.extend_from_slice(l.as_bytes());
+        }
+        pkt.push(0);
+        pkt.extend_from_slice(&[0, 1, 0x80, 1, 0, 0, 0, 120, 0, 4, 192, 168, 1, 42]);
+
+        let d = parse_mdns_response(&pkt, Ipv4Addr::new(192, 168, 1, 42));
+        assert_eq!(d.hostname.as_deref(), Some("chromecast.local"));
+        assert_eq!(d.services, vec!["Living Room._googlecast._tcp.local"]);
+        let other = parse_mdns_response(&pkt, Ipv4Addr::new(192, 168, 1, 43));
+        assert!(other.hostname.is_none());
+        assert!(parse_mdns_response(&pkt[..20], Ipv4Addr::LOCALHOST)
+            .services
+            .is_empty());
+    }
+
+    #[test]
+    fn test_parse_ssdp_response() {
+        let reply = "HTTP/1.1 200 OK\r\nCACHE-CONTROL: max-age=1800\r\nSERVER: Linux/3.14 UPnP/1.0 Sonos/70.3\r\nST: upnp:rootdevice\r\n\r\n";
+        assert_eq!(
+            parse_ssdp_response(reply).as_deref(),
+            Some("Linux/3.14 UPnP/1.0 Sonos/70.3")
+        );
+        assert!(parse_ssdp_response("M-SEARCH * HTTP/1.1\r\n").is_none());
+    }
+
+    #[test]
+    fn test_merge_scan_tracks_new_devices() {
+        let mut inv = LanInventory::default();
+        let mut found = BTreeMap::new();
+        found.insert(
+            Ipv4Addr::new(192, 168, 1, 10),
+            Discovered {
+                mac: Some("b8:27:eb:00:00:01".into()),
+                ..Default::default()
+            },
+        );
+        found.insert(Ipv4Addr::new(192, 168, 1, 11), Discovered::default());
+        let first = merge_scan(&mut inv, &found, 1_000);
+        assert_eq!(first, vec!["b8:27:eb:00:00:01", "ip:192.168.1.11"]);
+        assert_eq!(inv.devices[0].vendor.as_deref(), Some("Raspberry Pi"));
+
+        // Same MAC on a new IP is the same device; a new MAC is new.
+        let mut again = BTreeMap::new();
+        again.insert(
+            Ipv4Addr::new(192, 168, 1, 99),
+            Discovered {
+                mac: Some("b8:27:eb:00:00:01".into()),
+                hostname: Some("pi.local".into()),
+                ..Default::default()
+            },
+        );
+        again.insert(
+            Ipv4Addr::new(192, 168, 1, 12),
+            Discovered {
+                mac: Some("5c:aa:fd:00:00:02".into()),
+                ..Default::default()
+            },
+        );
+        let second = merge_scan(&mut inv, &again, 2_000);
+        assert_eq!(second, vec!["5c:aa:fd:00:00:02"]);
+        let pi = &inv.devices[0];
+        assert_eq!(
+            (pi.ip.as_str(), pi.seen_count, pi.first_seen),
+            ("192.168.1.99", 2, 1_000)
+        );
+        assert_eq!(pi.hostname.as_deref(), Some("pi.local"));
+        assert_eq!(inv.scans, 2);
+    }
 }
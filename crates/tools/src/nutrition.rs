@@ -0,0 +1,1020 @@
+//! Food lookup, recipe macros and a local meal log.
+//!
+//! Products come from Open Food Facts (barcodes, branded names) and USDA FoodData Central
+//! (generic foods, when a key is configured). Every food that was looked up is cached in
+//! `workspace/health/foods.json`, so repeated meals resolve offline; logged meals live in
+//! `workspace/health/meals.json` — the local health store that summaries read from.
+
+use async_trait::async_trait;
+use blockcell_core::secrets::resolve_secret_ref;
+use blockcell_core::{Config, Error, Result};
+use chrono::{Duration, NaiveDate, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::egress::HttpClientFactory;
+use crate::todo::local_date_hour;
+use crate::{PromptContext, Tool, ToolContext, ToolSchema};
+
+const REQUEST_TIMEOUT_SECS: u64 = 15;
+/// Portion assumed when neither grams nor a serving size are known.
+const DEFAULT_PORTION_G: f64 = 100.0;
+const MEAL_TYPES: &[&str] = &["breakfast", "lunch", "dinner", "snack"];
+
+static STORE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Nutrients
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Nutrients {
+    pub kcal: f64,
+    pub protein_g: f64,
+    pub carbs_g: f64,
+    pub fat_g: f64,
+    #[serde(default)]
+    pub fiber_g: f64,
+    #[serde(default)]
+    pub sugar_g: f64,
+    #[serde(default)]
+    pub sodium_mg: f64,
+}
+
+impl Nutrients {
+    fn scaled(&self, factor: f64) -> Self {
+        Self {
+            kcal: self.kcal * factor,
+            protein_g: self.protein_g * factor,
+            carbs_g: self.carbs_g * factor,
+            fat_g: self.fat_g * factor,
+            fiber_g: self.fiber_g * factor,
+            sugar_g: self.sugar_g * factor,
+            sodium_mg: self.sodium_mg * factor,
+        }
+    }
+
+    fn add(&mut self, other: &Self) {
+        self.kcal += other.kcal;
+        self.protein_g += other.protein_g;
+        self.carbs_g += other.carbs_g;
+        self.fat_g += other.fat_g;
+        self.fiber_g += other.fiber_g;
+        self.sugar_g += other.sugar_g;
+        self.sodium_mg += other.sodium_mg;
+    }
+
+    /// One decimal is plenty for food labels and keeps the JSON readable.
+    fn rounded(&self) -> Self {
+        let r = |v: f64| (v * 10.0).round() / 10.0;
+        Self {
+            kcal: self.kcal.round(),
+            protein_g: r(self.protein_g),
+            carbs_g: r(self.carbs_g),
+            fat_g: r(self.fat_g),
+            fiber_g: r(self.fiber_g),
+            sugar_g: r(self.sugar_g),
+            sodium_mg: self.sodium_mg.round(),
+        }
+    }
+
+    /// Reads explicit values from tool params; `None` when no energy value is given.
+    fn from_params(v: &Value) -> Option<Self> {
+        let kcal = v["kcal"].as_f64()?;
+        let f = |key: &str| v[key].as_f64().unwrap_or(0.0);
+        Some(Self {
+            kcal,
+            protein_g: f("protein_g"),
+            carbs_g: f("carbs_g"),
+            fat_g: f("fat_g"),
+            fiber_g: f("fiber_g"),
+            sugar_g: f("sugar_g"),
+            sodium_mg: f("sodium_mg"),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Food {
+    pub name: String,
+    /// `off`, `usda` or `custom`.
+    pub source: String,
+    /// Barcode or FDC id.
+    pub code: Option<String>,
+    pub per_100g: Nutrients,
+    pub serving_g: Option<f64>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Ingredient parsing
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+struct Portion {
+    name: String,
+    grams: Option<f64>,
+    count: Option<f64>,
+}
+
+/// Grams per unit; volumes are treated as water density.
+fn unit_grams(unit: &str) -> Option<f64> {
+    Some(match unit.trim_end_matches('.') {
+        "g" | "gr" | "gram" | "grams" => 1.0,
+        "kg" | "kilo" | "kilos" => 1000.0,
+        "mg" => 0.001,
+        "oz" | "ounce" | "ounces" => 28.3495,
+        "lb" | "lbs" | "pound" | "pounds" => 453.592,
+        "ml" => 1.0,
+        "l" | "liter" | "litre" | "liters" | "litres" => 1000.0,
+        "tsp" | "teaspoon" | "teaspoons" => 5.0,
+        "tbsp" | "tablespoon" | "tablespoons" => 15.0,
+        "cup" | "cups" => 240.0,
+        _ => return None,
+    })
+}
+
+/// `200g chicken breast`, `1.5 kg flour`, `2 eggs`, `oats` → portion.
+fn parse_ingredient(text: &str) -> Portion {
+    let text = text.trim();
+    let num_end = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+        .unwrap_or(text.len());
+    let amount = text[..num_end].replace(',', ".").parse::<f64>().ok();
+    let Some(amount) = amount else {
+        return Portion {
+            name: text.to_string(),
+            grams: None,
+            count: None,
+        };
+    };
+    let rest = text[num_end..].trim_start();
+    let (unit, after) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    match unit_grams(&unit.to_ascii_lowercase()) {
+        Some(factor) => Portion {
+            name: after.trim().trim_start_matches("of ").trim().to_string(),
+            grams: Some(amount * factor),
+            count: None,
+        },
+        None => Portion {
+            name: rest.trim().to_string(),
+            grams: None,
+            count: Some(amount),
+        },
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Backends
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn opt_f64(v: &Value) -> Option<f64> {
+    v.as_f64()
+        .or_else(|| v.as_str().and_then(|s| s.trim().parse().ok()))
+}
+
+fn parse_off_product(product: &Value, code: Option<&str>) -> Option<Food> {
+    let n = &product["nutriments"];
+    let kcal = opt_f64(&n["energy-kcal_100g"])
+        .or_else(|| opt_f64(&n["energy_100g"]).map(|kj| kj / 4.184))?;
+    let g = |key: &str| opt_f64(&n[format!("{}_100g", key)]).unwrap_or(0.0);
+    let sodium_g = opt_f64(&n["sodium_100g"])
+        .or_else(|| opt_f64(&n["salt_100g"]).map(|s| s / 2.5))
+        .unwrap_or(0.0);
+    let name = product["product_name"]
+        .as_str()
+        .filter(|s| !s.trim().is_empty())?;
+    let name = match product["brands"].as_str().and_then(|b| b.split(',').next()) {
+        Some(brand) if !brand.trim().is_empty() => format!("{} ({})", name.trim(), brand.trim()),
+        _ => name.trim().to_string(),
+    };
+    Some(Food {
+        name,
+        source: "off".to_string(),
+        code: code
+            .map(String::from)
+            .or_else(|| product["code"].as_str().map(String::from)),
+        per_100g: Nutrients {
+            kcal,
+            protein_g: g("proteins"),
+            carbs_g: g("carbohydrates"),
+            fat_g: g("fat"),
+            fiber_g: g("fiber"),
+            sugar_g: g("sugars"),
+            sodium_mg: sodium_g * 1000.0,
+        },
+        serving_g: opt_f64(&product["serving_quantity"]).filter(|g| *g > 0.0),
+    })
+}
+
+fn parse_usda_food(food: &Value) -> Option<Food> {
+    let nutrients = food["foodNutrients"].as_array()?;
+    let value = |numbers: &[&str]| {
+        numbers.iter().find_map(|num| {
+            nutrients
+                .iter()
+                .find(|n| n["nutrientNumber"].as_str() == Some(*num))
+                .and_then(|n| n["value"].as_f64())
+        })
+    };
+    // 208 is label energy; Foundation foods often only carry the Atwater factors (957/958).
+    let kcal = value(&["208", "957", "958"])?;
+    let g = |num: &str| value(&[num]).unwrap_or(0.0);
+    Some(Food {
+        name: food["description"].as_str()?.trim().to_string(),
+        source: "usda".to_string(),
+        code: food["fdcId"].as_i64().map(|id| id.to_string()),
+        per_100g: Nutrients {
+            kcal,
+            protein_g: g("203"),
+            carbs_g: g("205"),
+            fat_g: g("204"),
+            fiber_g: g("291"),
+            sugar_g: g("269"),
+            sodium_mg: g("307"),
+        },
+        serving_g: None,
+    })
+}
+
+fn usda_key(config: &Config, workspace: &Path) -> Option<String> {
+    let configured = config.tools.nutrition.usda_api_key.trim();
+    if configured.is_empty() {
+        return std::env::var("USDA_FDC_API_KEY")
+            .ok()
+            .map(|k| k.trim().to_string())
+            .filter(|k| !k.is_empty());
+    }
+    resolve_secret_ref(&crate::paths_for_workspace(workspace), configured).ok()
+}
+
+async fn get_json(config: &Config, url: &str, query: &[(&str, String)]) -> Result<Value> {
+    let factory = HttpClientFactory::for_tool(config, "nutrition");
+    factory.check(url).await?;
+    let client = factory
+        .builder()
+        .timeout(std::time::Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .user_agent(concat!("blockcell/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| Error::Tool(format!("Failed to build HTTP client: {}", e)))?;
+    client
+        .get(url)
+        .query(query)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        // Without the URL: the USDA key travels in the query string.
+        .map_err(|e| Error::Tool(format!("Food lookup failed: {}", e.without_url())))?
+        .json()
+        .await
+        .map_err(|e| Error::Tool(format!("Invalid food lookup response: {}", e)))
+}
+
+const OFF_FIELDS: &str = "code,product_name,brands,nutriments,serving_quantity";
+
+async fn off_barcode(config: &Config, barcode: &str) -> Result<Option<Food>> {
+    let base = config
+        .tools
+        .nutrition
+        .open_food_facts_base
+        .trim_end_matches('/');
+    let body = get_json(
+        config,
+        &format!(
+            "{}/api/v2/product/{}.json",
+            base,
+            urlencoding::encode(barcode)
+        ),
+        &[("fields", OFF_FIELDS.to_string())],
+    )
+    .await?;
+    Ok(off_barcode_product(&body, barcode))
+}
+
+/// The product in an OFF barcode response; unknown barcodes come back with `status: 0`.
+fn off_barcode_product(body: &Value, barcode: &str) -> Option<Food> {
+    if body["status"].as_i64() != Some(1) {
+        return None;
+    }
+    parse_off_product(&body["product"], Some(barcode))
+}
+
+async fn off_search(config: &Config, query: &str, limit: usize) -> Result<Vec<Food>> {
+    let base = config
+        .tools
+        .nutrition
+        .open_food_facts_base
+        .trim_end_matches('/');
+    let body = get_json(
+        config,
+        &format!("{}/cgi/search.pl", base),
+        &[
+            ("search_terms", query.to_string()),
+            ("search_simple", "1".to_string()),
+            ("json", "1".to_string()),
+            ("page_size", limit.to_string()),
+            ("fields", OFF_FIELDS.to_string()),
+        ],
+    )
+    .await?;
+    Ok(body["products"]
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|p| parse_off_product(p, None))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+async fn usda_search(config: &Config, key: &str, query: &str, limit: usize) -> Result<Vec<Food>> {
+    let base = config.tools.nutrition.usda_base.trim_end_matches('/');
+    let body = get_json(
+        config,
+        &format!("{}/foods/search", base),
+        &[
+            ("api_key", key.to_string()),
+            ("query", query.to_string()),
+            ("pageSize", limit.to_string()),
+            (
+                "dataType",
+                "Foundation,SR Legacy,Survey (FNDDS)".to_string(),
+            ),
+        ],
+    )
+    .await?;
+    usda_foods(&body)
+}
+
+/// Foods in a USDA search response. Key problems are reported as
+/// `{"error": {"code": ..., "message": ...}}` rather than an empty result.
+fn usda_foods(body: &Value) -> Result<Vec<Food>> {
+    if let Some(err) = body.get("error").filter(|e| !e.is_null()) {
+        return Err(Error::Tool(format!(
+            "USDA lookup failed: {}",
+            err["message"]
+                .as_str()
+                .or_else(|| err["code"].as_str())
+                .unwrap_or("unknown error")
+        )));
+    }
+    Ok(body["foods"]
+        .as_array()
+        .map(|items| items.iter().filter_map(parse_usda_food).collect())
+        .unwrap_or_default())
+}
+
+/// Search candidates for a food name: USDA generic foods first (when keyed), then OFF.
+async fn search_foods(
+    config: &Config,
+    workspace: &Path,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<Food>> {
+    let mut found = Vec::new();
+    let mut last_err = None;
+    if let Some(key) = usda_key(config, workspace) {
+        match usda_search(config, &key, query, limit).await {
+            Ok(foods) => found.extend(foods),
+            Err(e) => last_err = Some(e),
+        }
+    }
+    if found.len() < limit {
+        match off_search(config, query, limit - found.len()).await {
+            Ok(foods) => found.extend(foods),
+            Err(e) => last_err = Some(e),
+        }
+    }
+    match (found.is_empty(), last_err) {
+        (true, Some(e)) => Err(e),
+        _ => Ok(found),
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Stores
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn health_dir(workspace: &Path) -> PathBuf {
+    workspace.join("health")
+}
+
+fn load_json<T: Default + for<'de> Deserialize<'de>>(path: &Path) -> Result<T> {
+    if !path.exists() {
+        return Ok(T::default());
+    }
+    Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+fn save_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(value)?)?;
+    Ok(())
+}
+
+/// Every food resolved so far, keyed by `barcode:<code>` or `name:<lowercase query>`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct FoodCache {
+    #[serde(default)]
+    foods: BTreeMap<String, Food>,
+}
+
+fn food_cache_path(workspace: &Path) -> PathBuf {
+    health_dir(workspace).join("foods.json")
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MealStore {
+    version: u32,
+    #[serde(default)]
+    pub meals: Vec<Meal>,
+}
+
+impl Default for MealStore {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            meals: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Meal {
+    pub id: String,
+    /// Local date, `YYYY-MM-DD`.
+    pub date: String,
+    /// breakfast, lunch, dinner or snack.
+    pub meal: String,
+    #[serde(default)]
+    pub note: Option<String>,
+    pub items: Vec<MealItem>,
+    pub total: Nutrients,
+    pub logged_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MealItem {
+    pub name: String,
+    pub grams: Option<f64>,
+    pub source: String,
+    pub nutrients: Nutrients,
+    /// Set when the portion size was guessed rather than given.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub assumed_portion: bool,
+}
+
+fn meals_path(workspace: &Path) -> PathBuf {
+    health_dir(workspace).join("meals.json")
+}
+
+pub fn load_meals(workspace: &Path) -> Result<MealStore> {
+    load_json(&meals_path(workspace))
+}
+
+/// Per-day nutrient totals for `from..=to` (days without meals are omitted).
+pub fn daily_totals(
+    store: &MealStore,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> BTreeMap<String, Nutrients> {
+    let mut days: BTreeMap<String, Nutrients> = BTreeMap::new();
+    for meal in &store.meals {
+        let in_range =
+            NaiveDate::parse_from_str(&meal.date, "%Y-%m-%d").is_ok_and(|d| d >= from && d <= to);
+        if in_range {
+            days.entry(meal.date.clone()).or_default().add(&meal.total);
+        }
+    }
+    days
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Resolution
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Finds a food by barcode or name, preferring the local cache so known foods work offline.
+async fn resolve_food(
+    config: &Config,
+    workspace: &Path,
+    barcode: Option<&str>,
+    name: &str,
+) -> Result<Food> {
+    let key = match barcode {
+        Some(code) => format!("barcode:{}", code),
+        None => format!("name:{}", name.trim().to_lowercase()),
+    };
+    {
+        let _guard = STORE_LOCK.lock().await;
+        let cache: FoodCache = load_json(&food_cache_path(workspace))?;
+        if let Some(food) = cache.foods.get(&key) {
+            return Ok(food.clone());
+        }
+    }
+    let food = match barcode {
+        Some(code) => off_barcode(config, code)
+            .await?
+            .ok_or_else(|| Error::NotFound(format!("No product with barcode {}", code)))?,
+        None => search_foods(config, workspace, name, 1)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                Error::NotFound(format!(
+                    "No nutrition data for '{}'. Give kcal/protein_g/carbs_g/fat_g explicitly",
+                    name
+                ))
+            })?,
+    };
+    debug!(key = %key, food = %food.name, "Caching food lookup");
+    let _guard = STORE_LOCK.lock().await;
+    let path = food_cache_path(workspace);
+    let mut cache: FoodCache = load_json(&path)?;
+    cache.foods.insert(key, food.clone());
+    save_json(&path, &cache)?;
+    Ok(food)
+}
+
+/// Resolves one recipe/meal item: a string like `200g rice` or an object with `name`,
+/// `barcode`, `grams`, `quantity` and optionally explicit nutrients for the portion.
+async fn resolve_item(config: &Config, workspace: &Path, item: &Value) -> Result<MealItem> {
+    let (portion, barcode, explicit) = match item {
+        Value::String(s) => (parse_ingredient(s), None, None),
+        Value::Object(_) => {
+            let name = item["name"].as_str().unwrap_or("").trim();
+            let mut portion = parse_ingredient(name);
+            if let Some(g) = item["grams"].as_f64() {
+                portion.grams = Some(g);
+            }
+            if let Some(q) = item["quantity"].as_f64() {
+                portion.count = Some(q);
+            }
+            let barcode = item["barcode"]
+                .as_str()
+                .map(|b| b.trim().to_string())
+                .filter(|b| !b.is_empty());
+            (portion, barcode, Nutrients::from_params(item))
+        }
+        _ => {
+            return Err(Error::Validation(
+                "Each item must be a string like '200g rice' or an object".into(),
+            ))
+        }
+    };
+    if portion.name.is_empty() && barcode.is_none() {
+        return Err(Error::Validation(
+            "Item is missing a name or barcode".into(),
+        ));
+    }
+    if let Some(nutrients) = explicit {
+        return Ok(MealItem {
+            name: portion.name,
+            grams: portion.grams,
+            source: "custom".to_string(),
+            nutrients,
+            assumed_portion: false,
+        });
+    }
+    let food = resolve_food(config, workspace, barcode.as_deref(), &portion.name).await?;
+    let (grams, assumed) = match (portion.grams, portion.count) {
+        (Some(g), _) => (g, false),
+        (None, Some(n)) => (
+            n * food.serving_g.unwrap_or(DEFAULT_PORTION_G),
+            food.serving_g.is_none(),
+        ),
+        (None, None) => (food.serving_g.unwrap_or(DEFAULT_PORTION_G), true),
+    };
+    Ok(MealItem {
+        name: if portion.name.is_empty() {
+            food.name.clone()
+        } else {
+            portion.name
+        },
+        grams: Some((grams * 10.0).round() / 10.0),
+        source: food.source.clone(),
+        nutrients: food.per_100g.scaled(grams / 100.0).rounded(),
+        assumed_portion: assumed,
+    })
+}
+
+async fn resolve_items(
+    config: &Config,
+    workspace: &Path,
+    items: &[Value],
+) -> Result<(Vec<MealItem>, Nutrients)> {
+    let mut resolved = Vec::with_capacity(items.len());
+    let mut total = Nutrients::default();
+    for item in items {
+        let r = resolve_item(config, workspace, item).await?;
+        total.add(&r.nutrients);
+        resolved.push(r);
+    }
+    Ok((resolved, total.rounded()))
+}
+
+fn items_param(params: &Value) -> Result<&Vec<Value>> {
+    params["items"]
+        .as_array()
+        .filter(|a| !a.is_empty())
+        .ok_or_else(|| Error::Validation("'items' must be a non-empty array".into()))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tool
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct NutritionTool;
+
+#[async_trait]
+impl Tool for NutritionTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "nutrition",
+            description: "Food and nutrition: look up products (barcode or name via Open Food Facts / USDA), compute recipe macros, and keep a local meal log with daily/weekly summaries. Items are strings like '200g chicken breast', '2 eggs', or objects {name, barcode, grams, quantity, kcal, protein_g, carbs_g, fat_g} (explicit nutrients skip the lookup). You MUST provide `action`. action='lookup': `barcode` or `query`. action='recipe': `items`, optional `servings`. action='log_meal': `items`; optional `meal` (breakfast|lunch|dinner|snack), `date` (YYYY-MM-DD, default today), `note`. action='meals': optional `date`. action='delete_meal': `id`. action='summary': optional `days` (default 7) ending at `date`.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["lookup", "recipe", "log_meal", "meals", "delete_meal", "summary"],
+                        "description": "Action to perform"
+                    },
+                    "barcode": {"type": "string", "description": "(lookup) EAN/UPC barcode"},
+                    "query": {"type": "string", "description": "(lookup) Food name"},
+                    "items": {
+                        "type": "array",
+                        "description": "(recipe/log_meal) Ingredients or foods eaten",
+                        "items": {}
+                    },
+                    "servings": {"type": "number", "description": "(recipe) Number of servings. Default: 1"},
+                    "meal": {"type": "string", "enum": ["breakfast", "lunch", "dinner", "snack"]},
+                    "date": {"type": "string", "description": "Local date YYYY-MM-DD. Default: today"},
+                    "note": {"type": "string"},
+                    "id": {"type": "string", "description": "(delete_meal) Meal id"},
+                    "days": {"type": "integer", "description": "(summary) Days to cover. Default: 7"}
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        let has = |key: &str| params[key].as_str().is_some_and(|s| !s.trim().is_empty());
+        match params["action"].as_str().unwrap_or("") {
+            "lookup" => {
+                if !has("barcode") && !has("query") {
+                    return Err(Error::Validation(
+                        "lookup needs 'barcode' or 'query'".into(),
+                    ));
+                }
+            }
+            "recipe" | "log_meal" => {
+                items_param(params)?;
+                if let Some(meal) = params["meal"].as_str() {
+                    if !MEAL_TYPES.contains(&meal) {
+                        return Err(Error::Validation(format!(
+                            "Unknown meal '{}'. Use breakfast, lunch, dinner or snack",
+                            meal
+                        )));
+                    }
+                }
+            }
+            "delete_meal" => {
+                if !has("id") {
+                    return Err(Error::Validation("'id' is required for delete_meal".into()));
+                }
+            }
+            "meals" | "summary" => {}
+            other => {
+                return Err(Error::Validation(format!(
+                "Unknown action '{}'. Use lookup, recipe, log_meal, meals, delete_meal or summary",
+                other
+            )))
+            }
+        }
+        if let Some(date) = params["date"].as_str() {
+            NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").map_err(|_| {
+                Error::Validation(format!("Invalid date '{}'. Use YYYY-MM-DD", date))
+            })?;
+        }
+        Ok(())
+    }
+
+    fn prompt_rule(&self, _ctx: &PromptContext) -> Option<String> {
+        Some(
+            "- When the user reports what they ate (\"log my lunch\"), call `nutrition` `log_meal` with the foods and amounts; use `summary` for nutrition overviews instead of estimating from memory."
+                .to_string(),
+        )
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        let workspace = ctx.workspace.clone();
+        let tz = ctx.config.default_timezone.clone();
+        let date = match params["date"].as_str().map(str::trim) {
+            Some(d) if !d.is_empty() => d.to_string(),
+            _ => local_date_hour(tz.as_deref()).0,
+        };
+        match params["action"].as_str().unwrap_or("") {
+            "lookup" => {
+                let foods = match params["barcode"]
+                    .as_str()
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                {
+                    Some(code) => {
+                        vec![resolve_food(&ctx.config, &workspace, Some(code), "").await?]
+                    }
+                    None => {
+                        let query = params["query"].as_str().unwrap_or("").trim();
+                        search_foods(&ctx.config, &workspace, query, 5).await?
+                    }
+                };
+                Ok(json!({"foods": foods, "note": "Nutrients are per 100 g"}))
+            }
+            "recipe" => {
+                let (items, total) =
+                    resolve_items(&ctx.config, &workspace, items_param(&params)?).await?;
+                let servings = params["servings"]
+                    .as_f64()
+                    .filter(|s| *s > 0.0)
+                    .unwrap_or(1.0);
+                Ok(json!({
+                    "items": items,
+                    "total": total,
+                    "servings": servings,
+                    "per_serving": total.scaled(1.0 / servings).rounded(),
+                }))
+            }
+            "log_meal" => {
+                let (items, total) =
+                    resolve_items(&ctx.config, &workspace, items_param(&params)?).await?;
+                let meal_type = match params["meal"].as_str() {
+                    Some(m) => m.to_string(),
+                    None => match local_date_hour(tz.as_deref()).1 {
+                        5..=10 => "breakfast",
+                        11..=14 => "lunch",
+                        17..=21 => "dinner",
+                        _ => "snack",
+                    }
+                    .to_string(),
+                };
+                let meal = Meal {
+                    id: format!(
+                        "meal_{}",
+                        Uuid::new_v4().to_string().split('-').next().unwrap_or("x")
+                    ),
+                    date: date.clone(),
+                    meal: meal_type,
+                    note: params["note"]
+                        .as_str()
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .map(String::from),
+                    items,
+                    total,
+                    logged_at: Utc::now().timestamp_millis(),
+                };
+                let _guard = STORE_LOCK.lock().await;
+                let mut store = load_meals(&workspace)?;
+                store.meals.push(meal.clone());
+                save_json(&meals_path(&workspace), &store)?;
+                let day = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                    .map_err(|e| Error::Validation(e.to_string()))?;
+                let day_total = daily_totals(&store, day, day)
+                    .remove(&date)
+                    .unwrap_or_default()
+                    .rounded();
+                Ok(json!({"status": "logged", "meal": meal, "day_total": day_total}))
+            }
+            "meals" => {
+                let _guard = STORE_LOCK.lock().await;
+                let store = load_meals(&workspace)?;
+                let meals: Vec<&Meal> = store.meals.iter().filter(|m| m.date == date).collect();
+                let mut total = Nutrients::default();
+                meals.iter().for_each(|m| total.add(&m.total));
+                Ok(json!({"date": date, "meals": meals, "total": total.rounded()}))
+            }
+            "delete_meal" => {
+                let id = params["id"].as_str().unwrap_or("").trim();
+                let _guard = STORE_LOCK.lock().await;
+                let mut store = load_meals(&workspace)?;
+                let before = store.meals.len();
+                store.meals.retain(|m| m.id != id);
+                if store.meals.len() == before {
+                    return Err(Error::NotFound(format!("Meal '{}' not found", id)));
+                }
+                save_json(&meals_path(&workspace), &store)?;
+                Ok(json!({"status": "deleted", "id": id}))
+            }
+            "summary" => {
+                let days = params["days"].as_i64().unwrap_or(7).clamp(1, 366);
+                let to = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                    .map_err(|e| Error::Validation(e.to_string()))?;
+                let from = to - Duration::days(days - 1);
+                let store = {
+                    let _guard = STORE_LOCK.lock().await;
+                    load_meals(&workspace)?
+                };
+                let totals = daily_totals(&store, from, to);
+                let mut sum = Nutrients::default();
+                totals.values().for_each(|n| sum.add(n));
+                let logged_days = totals.len();
+                let average = if logged_days > 0 {
+                    sum.scaled(1.0 / logged_days as f64).rounded()
+                } else {
+                    Nutrients::default()
+                };
+                let target = ctx.config.tools.nutrition.daily_kcal_target;
+                let days_json: Vec<Value> = totals
+                    .iter()
+                    .map(|(day, n)| json!({"date": day, "total": n.rounded()}))
+                    .collect();
+                Ok(json!({
+                    "from": from.format("%Y-%m-%d").to_string(),
+                    "to": to.format("%Y-%m-%d").to_string(),
+                    "logged_days": logged_days,
+                    "days": days_json,
+                    "total": sum.rounded(),
+                    "daily_average": average,
+                    "daily_kcal_target": (target > 0).then_some(target),
+                }))
+            }
+            other => Err(Error::Tool(format!("Unknown action: {}", other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ingredient() {
+        let p = parse_ingredient("200g chicken breast");
+        assert_eq!(
+            (p.name.as_str(), p.grams, p.count),
+            ("chicken breast", Some(200.0), None)
+        );
+        let p = parse_ingredient("1,5 kg of flour");
+        assert_eq!((p.name.as_str(), p.grams), ("flour", Some(1500.0)));
+        let p = parse_ingredient("2 eggs");
+        assert_eq!(
+            (p.name.as_str(), p.grams, p.count),
+            ("eggs", None, Some(2.0))
+        );
+        let p = parse_ingredient("oats");
+        assert_eq!((p.name.as_str(), p.grams, p.count), ("oats", None, None));
+    }
+
+    #[test]
+    fn test_parse_backends() {
+        let product = json!({
+            "product_name": "Greek Yogurt", "brands": "Fage, Total",
+            "serving_quantity": "170",
+            "nutriments": {"energy_100g": 402, "proteins_100g": 9, "carbohydrates_100g": 3.9,
+                           "fat_100g": 5, "salt_100g": 0.1}
+        });
+        let food = parse_off_product(&product, Some("5201054017371")).unwrap();
+        assert_eq!(food.name, "Greek Yogurt (Fage)");
+        assert!((food.per_100g.kcal - 96.1).abs() < 0.1);
+        assert!((food.per_100g.sodium_mg - 40.0).abs() < 0.01);
+        assert_eq!(food.serving_g, Some(170.0));
+
+        let usda = json!({
+            "fdcId": 171077, "description": "Chicken, breast, raw",
+            "foodNutrients": [
+                {"nutrientNumber": "957", "value": 120.0},
+                {"nutrientNumber": "203", "value": 22.5},
+                {"nutrientNumber": "204", "value": 2.6}
+            ]
+        });
+        let food = parse_usda_food(&usda).unwrap();
+        assert_eq!(food.per_100g.kcal, 120.0);
+        assert_eq!(food.code.as_deref(), Some("171077"));
+    }
+
+    #[test]
+    fn test_daily_totals() {
+        let meal = |date: &str, kcal: f64| Meal {
+            id: Uuid::new_v4().to_string(),
+            date: date.to_string(),
+            meal: "lunch".to_string(),
+            note: None,
+            items: Vec::new(),
+            total: Nutrients {
+                kcal,
+                ..Default::default()
+            },
+            logged_at: 0,
+        };
+        let store = MealStore {
+            version: 1,
+            meals: vec![
+                meal("2026-10-13", 500.0),
+                meal("2026-10-14", 600.0),
+                meal("2026-10-14", 300.0),
+                meal("2026-10-20", 999.0),
+            ],
+        };
+        let d = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        let totals = daily_totals(&store, d("2026-10-10"), d("2026-10-16"));
+        assert_eq!(totals.len(), 2);
+        assert_eq!(totals["2026-10-14"].kcal, 900.0);
+    }
+
+    #[test]
+    fn test_unit_conversion() {
+        let grams = |text: &str| parse_ingredient(text).grams.unwrap();
+        assert!((grams("8 oz steak") - 226.796).abs() < 1e-9);
+        assert!((grams("1 lb ground beef") - 453.592).abs() < 1e-9);
+        assert_eq!(grams("2 tbsp olive oil"), 30.0);
+        assert_eq!(grams("1 cup of milk"), 240.0);
+        assert_eq!(grams("500 mg salt"), 0.5);
+        assert_eq!(grams("0.5 l water"), 500.0);
+        // Unknown units are counted, not weighed.
+        let p = parse_ingredient("3 slices bread");
+        assert_eq!((p.grams, p.count), (None, Some(3.0)));
+        assert_eq!(unit_grams("handful"), None);
+    }
+
+    fn test_workspace() -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("blockcell-nutrition-{}", Uuid::new_v4()))
+            .join("workspace")
+    }
+
+    #[tokio::test]
+    async fn test_cached_foods_resolve_offline() {
+        let workspace = test_workspace();
+        // Every request is blocked, so only cached foods can resolve.
+        let mut config = Config::default();
+        config.tools.egress.enabled = true;
+        config.tools.egress.default_action = "deny".into();
+        let egg = Food {
+            name: "Egg".into(),
+            source: "off".into(),
+            code: Some("123".into()),
+            per_100g: Nutrients {
+                kcal: 143.0,
+                protein_g: 12.6,
+                ..Default::default()
+            },
+            serving_g: Some(50.0),
+        };
+        let mut cache = FoodCache::default();
+        cache.foods.insert("name:eggs".into(), egg.clone());
+        cache.foods.insert("barcode:123".into(), egg);
+        save_json(&food_cache_path(&workspace), &cache).unwrap();
+
+        // A count uses the serving size.
+        let item = resolve_item(&config, &workspace, &json!("2 eggs"))
+            .await
+            .unwrap();
+        assert_eq!(item.grams, Some(100.0));
+        assert_eq!(item.nutrients.kcal, 143.0);
+        assert!(!item.assumed_portion);
+        let item = resolve_item(&config, &workspace, &json!({"barcode": "123", "grams": 25}))
+            .await
+            .unwrap();
+        assert_eq!(item.name, "Egg");
+        assert_eq!(item.nutrients.protein_g, 3.2);
+
+        // Foods not in the cache need a lookup, which fails here.
+        assert!(resolve_item(&config, &workspace, &json!("100g tofu"))
+            .await
+            .is_err());
+        assert!(resolve_food(&config, &workspace, Some("999"), "")
+            .await
+            .is_err());
+
+        let _ = std::fs::remove_dir_all(workspace.parent().unwrap());
+    }
+
+    #[test]
+    fn test_error_responses() {
+        let not_found = json!({"status": 0, "status_verbose": "product not found"});
+        assert!(off_barcode_product(&not_found, "4006381333931").is_none());
+        // A product without energy data cannot be used.
+        let no_energy =
+            json!({"status": 1, "product": {"product_name": "Water", "nutriments": {}}});
+        assert!(off_barcode_product(&no_energy, "1").is_none());
+        let found = json!({"status": 1, "product": {"product_name": "Oats",
+            "nutriments": {"energy-kcal_100g": 372}}});
+        let food = off_barcode_product(&found, "2").unwrap();
+        assert_eq!(food.code.as_deref(), Some("2"));
+
+        let err = usda_foods(&json!({"error": {"code": "API_KEY_INVALID",
+            "message": "An invalid api_key was supplied."}}))
+        .unwrap_err();
+        assert!(err.to_string().contains("invalid api_key"));
+        assert!(usda_foods(&json!({"foods": []})).unwrap().is_empty());
+    }
+}
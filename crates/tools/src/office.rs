@@ -1,47 +1,36 @@
 use blockcell_core::{Error, Result};
-use calamine::{open_workbook_auto, Data, Reader};
 use std::io::{BufReader, Read};
 use std::path::Path;
 
+use crate::spreadsheet::{Evaluator, Workbook};
+
 /// Read an Excel file (.xlsx, .xls) and return its content as text.
+///
+/// Formula cells are reported as computed values: cached results are used when the
+/// file was saved by Excel, otherwise the formula is evaluated locally.
 pub fn read_excel(path: &Path) -> Result<String> {
-    let mut workbook = open_workbook_auto(path)
-        .map_err(|e| Error::Tool(format!("Failed to open Excel file: {}", e)))?;
+    let workbook = Workbook::open(path)?;
+    let evaluator = Evaluator::new(&workbook);
 
     let mut output = String::new();
-    let sheet_names = workbook.sheet_names().to_vec();
 
-    for (idx, name) in sheet_names.iter().enumerate() {
+    for (idx, sheet) in workbook.sheets.iter().enumerate() {
         if idx > 0 {
             output.push_str("\n\n");
         }
-        output.push_str(&format!("## Sheet: {}\n\n", name));
-
-        if let Ok(range) = workbook.worksheet_range(name) {
-            let mut rows_output = Vec::new();
-            for row in range.rows() {
-                let cells: Vec<String> = row
-                    .iter()
-                    .map(|cell| match cell {
-                        Data::Empty => String::new(),
-                        Data::String(s) => s.clone(),
-                        Data::Float(f) => {
-                            if *f == (*f as i64) as f64 {
-                                format!("{}", *f as i64)
-                            } else {
-                                format!("{}", f)
-                            }
-                        }
-                        Data::Int(i) => format!("{}", i),
-                        Data::Bool(b) => format!("{}", b),
-                        Data::DateTime(dt) => format!("{}", dt),
-                        Data::DateTimeIso(s) => s.clone(),
-                        Data::DurationIso(s) => s.clone(),
-                        Data::Error(e) => format!("#ERR:{:?}", e),
-                    })
-                    .collect();
-                rows_output.push(cells.join("\t"));
-            }
+        output.push_str(&format!("## Sheet: {}\n\n", sheet.name));
+
+        if let Some((r0, c0, r1, c1)) = sheet.bounds() {
+            let rows_output: Vec<String> = evaluator
+                .grid(idx, (r0, c0), (r1, c1))
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|cell| cell.display())
+                        .collect::<Vec<_>>()
+                        .join("\t")
+                })
+                .collect();
 
             // Format as markdown table if we have data
             if !rows_output.is_empty() {
@@ -179,11 +168,9 @@ fn extract_text_from_xml(xml: &str, target_tag: &str) -> Result<String> {
                     current_paragraph.clear();
                 }
             }
-            Ok(Event::Text(ref e)) => {
-                if inside_target {
-                    if let Ok(text) = e.unescape() {
-                        current_paragraph.push_str(&text);
-                    }
+            Ok(Event::Text(ref e)) if inside_target => {
+                if let Ok(text) = e.unescape() {
+                    current_paragraph.push_str(&text);
                 }
             }
             Ok(Event::Eof) => break,
@@ -1,14 +1,27 @@
 use async_trait::async_trait;
 use blockcell_core::{Error, Result};
 use serde_json::{json, Value};
-use tracing::info;
+use tracing::{info, warn};
 
+use crate::spreadsheet::{self, Aggregate, CellValue, Evaluator, PivotSpec, Workbook};
 use crate::{Tool, ToolContext, ToolSchema};
 
+const ACTIONS: &[&str] = &[
+    "create_pptx",
+    "create_docx",
+    "create_xlsx",
+    "read_xlsx",
+    "evaluate",
+    "pivot",
+    "format_xlsx",
+    "info",
+];
+
 /// Tool for generating Office documents (PPTX, DOCX, XLSX).
 ///
 /// Uses Python libraries (python-pptx, python-docx, openpyxl) to create
-/// properly formatted Office files from structured data.
+/// properly formatted Office files from structured data. Spreadsheet reads,
+/// formula evaluation and pivots run natively via [`crate::spreadsheet`].
 pub struct OfficeWriteTool;
 
 #[async_trait]
@@ -16,14 +29,71 @@ impl Tool for OfficeWriteTool {
     fn schema(&self) -> ToolSchema {
         ToolSchema {
             name: "office_write",
-            description: "Generate Office documents. You MUST provide `action`. action='info': no extra params. action='create_pptx': requires `slides`, optional `title`, `output_path`, and `style`. action='create_docx': requires `sections`, optional `title`, `output_path`, and `style`. action='create_xlsx': requires `sheets`, optional `title`, `output_path`, and `style`.",
+            description: "Generate Office documents and analyze spreadsheets. You MUST provide `action`. action='info': no extra params. action='create_pptx': requires `slides`, optional `title`, `output_path`, and `style`. action='create_docx': requires `sections`, optional `title`, `output_path`, and `style`. action='create_xlsx': requires `sheets`, optional `title`, `output_path`, and `style`; cell strings starting with '=' are written as formulas and their computed values are returned. action='read_xlsx': requires `path`, optional `sheet`, `range`, `max_rows`; returns computed cell values (formulas evaluated). action='evaluate': requires `path` and `formula` (e.g. '=SUM(B2:B13)') or `cells` (e.g. ['B14']), optional `sheet`. action='pivot': requires `path`, `rows` and `values`, optional `columns`, `sheet`, `range`, `totals`, and `output_path`/`output_sheet` to write the pivot into a workbook. action='format_xlsx': requires `path` and `conditional_formats`, optional `sheet`, `output_path`.",
             parameters: json!({
                 "type": "object",
                 "properties": {
                     "action": {
                         "type": "string",
-                        "enum": ["create_pptx", "create_docx", "create_xlsx", "info"],
-                        "description": "Action: create_pptx/create_docx/create_xlsx to generate files, 'info' to check backends"
+                        "enum": ["create_pptx", "create_docx", "create_xlsx", "read_xlsx", "evaluate", "pivot", "format_xlsx", "info"],
+                        "description": "Action: create_pptx/create_docx/create_xlsx to generate files, read_xlsx/evaluate/pivot to analyze a spreadsheet, format_xlsx to add conditional formatting, 'info' to check backends"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "For read_xlsx/evaluate/pivot/format_xlsx: existing .xlsx/.xls file"
+                    },
+                    "sheet": {
+                        "type": "string",
+                        "description": "Sheet name. Default: first sheet"
+                    },
+                    "range": {
+                        "type": "string",
+                        "description": "Cell range such as 'A1:F200'. Default: used range of the sheet"
+                    },
+                    "max_rows": {
+                        "type": "integer",
+                        "description": "For read_xlsx: max rows returned per sheet. Default 100, max 1000"
+                    },
+                    "formula": {
+                        "type": "string",
+                        "description": "For evaluate: formula to compute against the sheet, e.g. '=SUMIF(A2:A50,\"East\",C2:C50)'"
+                    },
+                    "cells": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "For evaluate: cell addresses whose computed values to return, e.g. ['B14', 'C14']"
+                    },
+                    "rows": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "For pivot: header names (or column letters) to group rows by"
+                    },
+                    "columns": {
+                        "type": "string",
+                        "description": "For pivot: header whose distinct values become output columns"
+                    },
+                    "values": {
+                        "type": "array",
+                        "items": {
+                            "oneOf": [
+                                { "type": "string" },
+                                { "type": "object", "description": "{field, agg}: agg = sum|avg|count|min|max" }
+                            ]
+                        },
+                        "description": "For pivot: value fields to aggregate. A string means sum of that field"
+                    },
+                    "totals": {
+                        "type": "boolean",
+                        "description": "For pivot: append a Grand Total row. Default true"
+                    },
+                    "output_sheet": {
+                        "type": "string",
+                        "description": "For pivot with output_path: sheet name to write the pivot into. Default 'Pivot'"
+                    },
+                    "conditional_formats": {
+                        "type": "array",
+                        "items": { "type": "object" },
+                        "description": "For format_xlsx (also allowed per sheet in create_xlsx): rules. Each: {range, type: cell_is|color_scale|data_bar|formula, operator ('>', '<', '>=', '<=', '=', '!=', 'between'), value | values, formula, fill, font_color, start_color, mid_color, end_color, color}"
                     },
                     "output_path": {
                         "type": "string",
@@ -46,7 +116,7 @@ impl Tool for OfficeWriteTool {
                     "sheets": {
                         "type": "array",
                         "items": { "type": "object" },
-                        "description": "For XLSX: array of sheets. Each: {name, headers, rows, column_widths, bold_header}"
+                        "description": "For XLSX: array of sheets. Each: {name, headers, rows, column_widths, bold_header, conditional_formats}. Cell strings starting with '=' become formulas"
                     },
                     "style": {
                         "oneOf": [
@@ -67,12 +137,25 @@ impl Tool for OfficeWriteTool {
         }
     }
 
+    #[allow(clippy::collapsible_match)]
     fn validate(&self, params: &Value) -> Result<()> {
         let action = params.get("action").and_then(|v| v.as_str()).unwrap_or("");
-        if !["create_pptx", "create_docx", "create_xlsx", "info"].contains(&action) {
-            return Err(Error::Tool(
-                "action must be 'create_pptx', 'create_docx', 'create_xlsx', or 'info'".into(),
-            ));
+        if !ACTIONS.contains(&action) {
+            return Err(Error::Tool(format!(
+                "action must be one of: {}",
+                ACTIONS.join(", ")
+            )));
+        }
+        let has_str = |key: &str| {
+            params
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(|s| !s.trim().is_empty())
+                .unwrap_or(false)
+        };
+        if matches!(action, "read_xlsx" | "evaluate" | "pivot" | "format_xlsx") && !has_str("path")
+        {
+            return Err(Error::Tool(format!("'path' is required for {}", action)));
         }
         match action {
             "create_pptx" => {
@@ -110,7 +193,58 @@ impl Tool for OfficeWriteTool {
                         "'sheets' array is required for create_xlsx".into(),
                     ));
                 }
+                for sheet in params["sheets"].as_array().into_iter().flatten() {
+                    if let Some(rules) = sheet.get("conditional_formats") {
+                        validate_conditional_formats(rules)?;
+                    }
+                }
+            }
+            "evaluate" => {
+                let has_cells = params
+                    .get("cells")
+                    .and_then(|v| v.as_array())
+                    .map(|a| !a.is_empty())
+                    .unwrap_or(false);
+                if !has_str("formula") && !has_cells {
+                    return Err(Error::Tool(
+                        "'formula' or 'cells' is required for evaluate".into(),
+                    ));
+                }
             }
+            "pivot" => {
+                let non_empty = |key: &str| {
+                    params
+                        .get(key)
+                        .and_then(|v| v.as_array())
+                        .map(|a| !a.is_empty())
+                        .unwrap_or(false)
+                };
+                if !non_empty("rows") || !non_empty("values") {
+                    return Err(Error::Tool(
+                        "'rows' and 'values' arrays are required for pivot".into(),
+                    ));
+                }
+                for v in params["values"].as_array().into_iter().flatten() {
+                    if let Some(agg) = v.get("agg").and_then(|a| a.as_str()) {
+                        if Aggregate::parse(agg).is_none() {
+                            return Err(Error::Tool(format!(
+                                "Unknown agg '{}'. Use sum, avg, count, min or max",
+                                agg
+                            )));
+                        }
+                    }
+                }
+            }
+            "format_xlsx" => match params.get("conditional_formats") {
+                Some(rules) if rules.as_array().map(|a| !a.is_empty()).unwrap_or(false) => {
+                    validate_conditional_formats(rules)?;
+                }
+                _ => {
+                    return Err(Error::Tool(
+                        "'conditional_formats' array is required for format_xlsx".into(),
+                    ));
+                }
+            },
             _ => {}
         }
         Ok(())
@@ -122,13 +256,22 @@ impl Tool for OfficeWriteTool {
             .and_then(|v| v.as_str())
             .unwrap_or("info");
 
-        match action {
+        let result = match action {
             "create_pptx" => action_create_pptx(&ctx, &params).await,
             "create_docx" => action_create_docx(&ctx, &params).await,
             "create_xlsx" => action_create_xlsx(&ctx, &params).await,
+            "read_xlsx" => action_read_xlsx(&ctx, &params).await,
+            "evaluate" => action_evaluate(&ctx, &params).await,
+            "pivot" => action_pivot(&ctx, &params).await,
+            "format_xlsx" => action_format_xlsx(&ctx, &params).await,
             "info" => action_info().await,
             _ => Err(Error::Tool(format!("Unknown action: {}", action))),
+        }?;
+        if let Some(path) = result.get("output_path").and_then(|v| v.as_str()) {
+            let title = params.get("title").and_then(|v| v.as_str());
+            ctx.register_artifact(path, "document", title);
         }
+        Ok(result)
     }
 }
 
@@ -474,7 +617,7 @@ import json
 from openpyxl import Workbook
 from openpyxl.styles import Font, Alignment, PatternFill, Border, Side
 from openpyxl.utils import get_column_letter
-
+{cf_helper}
 sheets_data = json.loads('''{sheets_json}''')
 style = json.loads('''{style_json}''')
 output_path = {output_path}
@@ -548,15 +691,531 @@ for sheet_data in sheets_data:
     if headers and rows:
         ws.auto_filter.ref = f'A1:{{get_column_letter(len(headers))}}{{len(rows) + 1}}'
 
+    apply_conditional_formats(ws, sheet_data.get('conditional_formats', []))
+
 wb.save(output_path)
 print('OK')
 "#,
         sheets_json = sheets_json.replace('\\', "\\\\").replace('\'', "\\'"),
         style_json = style_json.replace('\\', "\\\\").replace('\'', "\\'"),
         output_path = quote_python_str(&output_path),
+        cf_helper = PY_CONDITIONAL_FORMATS,
+    );
+
+    let mut result = run_python_script(&script, &output_path, "xlsx", sheets.len(), ctx).await?;
+
+    // openpyxl stores formulas without cached results — compute them so the caller
+    // sees the numbers instead of formula text.
+    let has_formulas = sheets.iter().any(|sheet| {
+        sheet["rows"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|row| row.as_array())
+            .flatten()
+            .any(|v| v.as_str().map(|s| s.starts_with('=')).unwrap_or(false))
+    });
+    if has_formulas {
+        let path = std::path::PathBuf::from(&output_path);
+        match tokio::task::spawn_blocking(move || formula_results(&path, 50)).await {
+            Ok(Ok(values)) => result["formula_results"] = json!(values),
+            Ok(Err(e)) => warn!(error = %e, "Failed to evaluate formulas in generated xlsx"),
+            Err(e) => warn!(error = %e, "Formula evaluation task failed"),
+        }
+    }
+    Ok(result)
+}
+
+/// Computed values of formula cells across all sheets (row-major, capped at `limit`).
+fn formula_results(path: &std::path::Path, limit: usize) -> Result<Vec<Value>> {
+    let workbook = Workbook::open(path)?;
+    let evaluator = Evaluator::new(&workbook);
+    let mut out = Vec::new();
+    for (idx, sheet) in workbook.sheets.iter().enumerate() {
+        for ((row, col), formula) in sheet.formula_cells() {
+            if out.len() >= limit {
+                return Ok(out);
+            }
+            out.push(json!({
+                "sheet": sheet.name,
+                "cell": spreadsheet::cell_name(row, col),
+                "formula": format!("={}", formula),
+                "value": evaluator.cell(idx, row, col).to_json(),
+            }));
+        }
+    }
+    Ok(out)
+}
+
+/// Read computed cell values from an xlsx/xls workbook.
+async fn action_read_xlsx(ctx: &ToolContext, params: &Value) -> Result<Value> {
+    let path = std::path::PathBuf::from(expand_path(params["path"].as_str().unwrap_or(""), ctx));
+    let sheet = params
+        .get("sheet")
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    let range = params
+        .get("range")
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    let max_rows = params
+        .get("max_rows")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(100)
+        .clamp(1, 1000) as usize;
+
+    run_blocking(move || {
+        let workbook = Workbook::open(&path)?;
+        let evaluator = Evaluator::new(&workbook);
+        let indexes: Vec<usize> = match sheet.as_deref() {
+            Some(name) => vec![workbook.sheet_index(Some(name))?],
+            None => (0..workbook.sheets.len()).collect(),
+        };
+
+        let mut sheets = Vec::new();
+        for idx in indexes {
+            let Some((from, to)) = sheet_rect(&workbook, idx, range.as_deref())? else {
+                sheets.push(json!({
+                    "name": workbook.sheets[idx].name,
+                    "rows": [],
+                    "total_rows": 0,
+                }));
+                continue;
+            };
+            let total_rows = (to.0 - from.0 + 1) as usize;
+            let last = (from.0 + max_rows as u32 - 1).min(to.0);
+            let rows: Vec<Value> = evaluator
+                .grid(idx, from, (last, to.1))
+                .iter()
+                .map(|row| Value::Array(row.iter().map(CellValue::to_json).collect()))
+                .collect();
+            sheets.push(json!({
+                "name": workbook.sheets[idx].name,
+                "range": format!(
+                    "{}:{}",
+                    spreadsheet::cell_name(from.0, from.1),
+                    spreadsheet::cell_name(to.0, to.1)
+                ),
+                "rows": rows,
+                "total_rows": total_rows,
+                "truncated": total_rows > max_rows,
+                "formula_cells": workbook.sheets[idx].formula_count(),
+            }));
+        }
+
+        Ok(json!({
+            "path": path.display().to_string(),
+            "sheets": sheets,
+            "formulas_evaluated": evaluator.evaluated_count(),
+        }))
+    })
+    .await
+}
+
+/// Evaluate a formula or look up computed cell values.
+async fn action_evaluate(ctx: &ToolContext, params: &Value) -> Result<Value> {
+    let path = std::path::PathBuf::from(expand_path(params["path"].as_str().unwrap_or(""), ctx));
+    let sheet = params
+        .get("sheet")
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    let formula = params
+        .get("formula")
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    let cells: Vec<String> = params
+        .get("cells")
+        .and_then(|v| v.as_array())
+        .map(|a| {
+            a.iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    run_blocking(move || {
+        let workbook = Workbook::open(&path)?;
+        let evaluator = Evaluator::new(&workbook);
+        let idx = workbook.sheet_index(sheet.as_deref())?;
+
+        let mut out = json!({ "sheet": workbook.sheets[idx].name });
+        if let Some(f) = formula.as_deref() {
+            out["formula"] = json!(f);
+            out["value"] = evaluator.eval_formula(idx, f).to_json();
+        }
+        if !cells.is_empty() {
+            let mut results = Vec::new();
+            for cell in &cells {
+                let (row, col) = spreadsheet::parse_cell_ref(cell)
+                    .ok_or_else(|| Error::Tool(format!("Invalid cell address '{}'", cell)))?;
+                results.push(json!({
+                    "cell": cell.to_ascii_uppercase(),
+                    "value": evaluator.cell(idx, row, col).to_json(),
+                }));
+            }
+            out["cells"] = json!(results);
+        }
+        Ok(out)
+    })
+    .await
+}
+
+/// Pivot-table style aggregation over a sheet; optionally written into a workbook.
+async fn action_pivot(ctx: &ToolContext, params: &Value) -> Result<Value> {
+    let path = std::path::PathBuf::from(expand_path(params["path"].as_str().unwrap_or(""), ctx));
+    let sheet = params
+        .get("sheet")
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    let range = params
+        .get("range")
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    let p = params.clone();
+
+    let (table, source_sheet) = run_blocking(move || {
+        let workbook = Workbook::open(&path)?;
+        let evaluator = Evaluator::new(&workbook);
+        let idx = workbook.sheet_index(sheet.as_deref())?;
+        let (from, to) = sheet_rect(&workbook, idx, range.as_deref())?
+            .ok_or_else(|| Error::Tool("Sheet is empty".into()))?;
+        let grid = evaluator.grid(idx, from, to);
+        let headers: Vec<String> = grid[0].iter().map(CellValue::display).collect();
+        let spec = pivot_spec(&p, &headers)?;
+        let table = spreadsheet::pivot(&headers, &grid[1..], &spec);
+        Ok((table, workbook.sheets[idx].name.clone()))
+    })
+    .await?;
+
+    let rows_json: Vec<Value> = table
+        .rows
+        .iter()
+        .map(|row| Value::Array(row.iter().map(CellValue::to_json).collect()))
+        .collect();
+    let mut result = json!({
+        "source_sheet": source_sheet,
+        "headers": table.headers,
+        "row_count": rows_json.len(),
+        "rows": rows_json.iter().take(500).collect::<Vec<_>>(),
+        "truncated": rows_json.len() > 500,
+    });
+
+    if let Some(out) = params
+        .get("output_path")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+    {
+        let output_path = expand_path(out, ctx);
+        let sheet_name = params
+            .get("output_sheet")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Pivot");
+        let ops = json!([{
+            "kind": "write_sheet",
+            "name": sheet_name,
+            "headers": table.headers,
+            "rows": rows_json,
+            "bold_last_row": params.get("totals").and_then(|v| v.as_bool()).unwrap_or(true),
+        }]);
+        // Write into the output workbook if it already exists, otherwise create it.
+        let written = edit_xlsx(ctx, &output_path, &output_path, &ops, 1).await?;
+        result["output_path"] = written["output_path"].clone();
+        result["output_sheet"] = json!(sheet_name);
+    }
+    Ok(result)
+}
+
+/// Apply conditional formatting rules to an existing workbook.
+async fn action_format_xlsx(ctx: &ToolContext, params: &Value) -> Result<Value> {
+    let source = expand_path(params["path"].as_str().unwrap_or(""), ctx);
+    if !std::path::Path::new(&source).exists() {
+        return Err(Error::Tool(format!("File not found: {}", source)));
+    }
+    let output_path = match params.get("output_path").and_then(|v| v.as_str()) {
+        Some(p) if !p.is_empty() => expand_path(p, ctx),
+        _ => source.clone(),
+    };
+    let rules = params
+        .get("conditional_formats")
+        .cloned()
+        .unwrap_or_else(|| json!([]));
+    let rule_count = rules.as_array().map(|a| a.len()).unwrap_or(0);
+    let ops = json!([{
+        "kind": "conditional_formats",
+        "sheet": params.get("sheet").and_then(|v| v.as_str()),
+        "rules": rules,
+    }]);
+    edit_xlsx(ctx, &source, &output_path, &ops, rule_count).await
+}
+
+/// Run blocking spreadsheet work off the async runtime.
+async fn run_blocking<T, F>(f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| Error::Tool(format!("Spreadsheet task failed: {}", e)))?
+}
+
+/// Rectangle to operate on: explicit `range`, or the sheet's used range.
+#[allow(clippy::type_complexity)]
+fn sheet_rect(
+    workbook: &Workbook,
+    idx: usize,
+    range: Option<&str>,
+) -> Result<Option<((u32, u32), (u32, u32))>> {
+    match range.map(str::trim).filter(|r| !r.is_empty()) {
+        Some(r) => spreadsheet::parse_range_ref(r)
+            .map(Some)
+            .ok_or_else(|| Error::Tool(format!("Invalid range '{}'", r))),
+        None => Ok(workbook.sheets[idx]
+            .bounds()
+            .map(|(r0, c0, r1, c1)| ((r0, c0), (r1, c1)))),
+    }
+}
+
+/// Resolve a pivot field by header name (case-insensitive) or column letter.
+fn resolve_field(headers: &[String], field: &str) -> Result<usize> {
+    let field = field.trim();
+    if let Some(i) = headers
+        .iter()
+        .position(|h| h.trim().eq_ignore_ascii_case(field))
+    {
+        return Ok(i);
+    }
+    if field.len() <= 3 && field.chars().all(|c| c.is_ascii_uppercase()) {
+        if let Some(i) = spreadsheet::column_index(field).filter(|i| (*i as usize) < headers.len())
+        {
+            return Ok(i as usize);
+        }
+    }
+    Err(Error::Tool(format!(
+        "Field '{}' not found. Headers: {}",
+        field,
+        headers.join(", ")
+    )))
+}
+
+fn pivot_spec(params: &Value, headers: &[String]) -> Result<PivotSpec> {
+    let rows = params["rows"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str())
+        .map(|f| resolve_field(headers, f))
+        .collect::<Result<Vec<_>>>()?;
+    let columns = match params.get("columns").and_then(|v| v.as_str()) {
+        Some(f) if !f.trim().is_empty() => Some(resolve_field(headers, f)?),
+        _ => None,
+    };
+    let mut values = Vec::new();
+    for v in params["values"].as_array().into_iter().flatten() {
+        let (field, agg) = match v {
+            Value::String(f) => (f.as_str(), "sum"),
+            _ => (
+                v.get("field").and_then(|f| f.as_str()).unwrap_or(""),
+                v.get("agg").and_then(|a| a.as_str()).unwrap_or("sum"),
+            ),
+        };
+        let agg =
+            Aggregate::parse(agg).ok_or_else(|| Error::Tool(format!("Unknown agg '{}'", agg)))?;
+        values.push((resolve_field(headers, field)?, agg));
+    }
+    Ok(PivotSpec {
+        rows,
+        columns,
+        values,
+        totals: params
+            .get("totals")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true),
+    })
+}
+
+fn validate_conditional_formats(rules: &Value) -> Result<()> {
+    let rules = rules
+        .as_array()
+        .ok_or_else(|| Error::Tool("'conditional_formats' must be an array".into()))?;
+    for rule in rules {
+        let range = rule.get("range").and_then(|v| v.as_str()).unwrap_or("");
+        if range.split_whitespace().next().is_none()
+            || !range
+                .split_whitespace()
+                .all(|r| spreadsheet::parse_range_ref(r).is_some())
+        {
+            return Err(Error::Tool(format!(
+                "conditional format needs a valid 'range' like 'C2:C100', got '{}'",
+                range
+            )));
+        }
+        match rule
+            .get("type")
+            .and_then(|v| v.as_str())
+            .unwrap_or("cell_is")
+        {
+            "cell_is" => {
+                let op = rule.get("operator").and_then(|v| v.as_str()).unwrap_or(">");
+                if !CF_OPERATORS.contains(&op) {
+                    return Err(Error::Tool(format!(
+                        "Unknown conditional format operator '{}'. Use one of: {}",
+                        op,
+                        CF_OPERATORS.join(", ")
+                    )));
+                }
+                if rule.get("value").is_none() && rule.get("values").is_none() {
+                    return Err(Error::Tool(
+                        "cell_is conditional format requires 'value' or 'values'".into(),
+                    ));
+                }
+            }
+            "formula" => {
+                if rule.get("formula").and_then(|v| v.as_str()).is_none() {
+                    return Err(Error::Tool(
+                        "formula conditional format requires 'formula'".into(),
+                    ));
+                }
+            }
+            "color_scale" | "data_bar" => {}
+            other => {
+                return Err(Error::Tool(format!(
+                    "Unknown conditional format type '{}'. Use cell_is, color_scale, data_bar or formula",
+                    other
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+const CF_OPERATORS: &[&str] = &[
+    ">",
+    ">=",
+    "<",
+    "<=",
+    "=",
+    "==",
+    "!=",
+    "<>",
+    "between",
+    "not_between",
+];
+
+/// Python helper shared by create_xlsx and edit scripts (openpyxl conditional formatting).
+const PY_CONDITIONAL_FORMATS: &str = r#"
+from openpyxl.formatting.rule import CellIsRule, ColorScaleRule, DataBarRule, FormulaRule
+
+_CF_OPERATORS = {
+    '>': 'greaterThan', '>=': 'greaterThanOrEqual', '<': 'lessThan', '<=': 'lessThanOrEqual',
+    '=': 'equal', '==': 'equal', '!=': 'notEqual', '<>': 'notEqual',
+    'between': 'between', 'not_between': 'notBetween',
+}
+
+def _cf_operand(v):
+    if isinstance(v, bool):
+        return 'TRUE' if v else 'FALSE'
+    if isinstance(v, (int, float)):
+        return str(v)
+    v = str(v)
+    if v.startswith('='):
+        return v[1:]
+    try:
+        float(v)
+        return v
+    except ValueError:
+        return '"' + v.replace('"', '""') + '"'
+
+def apply_conditional_formats(ws, rules):
+    for rule in rules or []:
+        rng = rule.get('range')
+        if not rng:
+            continue
+        kind = rule.get('type', 'cell_is')
+        fill_color = rule.get('fill', 'FFC7CE')
+        fill = PatternFill(start_color=fill_color, end_color=fill_color, fill_type='solid')
+        font = Font(color=rule.get('font_color', '9C0006'), bold=bool(rule.get('bold', False)))
+        if kind == 'color_scale':
+            if rule.get('mid_color'):
+                cf = ColorScaleRule(start_type='min', start_color=rule.get('start_color', 'F8696B'),
+                                    mid_type='percentile', mid_value=50, mid_color=rule['mid_color'],
+                                    end_type='max', end_color=rule.get('end_color', '63BE7B'))
+            else:
+                cf = ColorScaleRule(start_type='min', start_color=rule.get('start_color', 'F8696B'),
+                                    end_type='max', end_color=rule.get('end_color', '63BE7B'))
+        elif kind == 'data_bar':
+            cf = DataBarRule(start_type='min', end_type='max', color=rule.get('color', '638EC6'))
+        elif kind == 'formula':
+            cf = FormulaRule(formula=[rule['formula'].lstrip('=')], fill=fill, font=font)
+        else:
+            values = rule.get('values')
+            if values is None:
+                values = [rule.get('value', 0)]
+            op = _CF_OPERATORS.get(rule.get('operator', '>'), 'greaterThan')
+            cf = CellIsRule(operator=op, formula=[_cf_operand(v) for v in values], fill=fill, font=font)
+        ws.conditional_formatting.add(rng, cf)
+"#;
+
+/// Apply `ops` (write_sheet / conditional_formats) to a workbook via openpyxl.
+/// A missing `source` starts from an empty workbook.
+async fn edit_xlsx(
+    ctx: &ToolContext,
+    source: &str,
+    output_path: &str,
+    ops: &Value,
+    item_count: usize,
+) -> Result<Value> {
+    let ops_json = serde_json::to_string(ops)
+        .map_err(|e| Error::Tool(format!("Failed to serialize operations: {}", e)))?;
+
+    let script = format!(
+        r#"
+import json, os
+from openpyxl import Workbook, load_workbook
+from openpyxl.styles import Font, PatternFill
+{cf_helper}
+ops = json.loads('''{ops_json}''')
+source_path = {source}
+output_path = {output_path}
+
+existing = os.path.exists(source_path)
+wb = load_workbook(source_path) if existing else Workbook()
+
+for op in ops:
+    if op['kind'] == 'write_sheet':
+        name = op['name']
+        if name in wb.sheetnames:
+            del wb[name]
+        ws = wb.create_sheet(title=name)
+        if not existing and 'Sheet' in wb.sheetnames and name != 'Sheet':
+            del wb['Sheet']
+        header_font = Font(bold=True, color='FFFFFF')
+        header_fill = PatternFill(start_color='4472C4', end_color='4472C4', fill_type='solid')
+        for col_idx, header in enumerate(op['headers'], 1):
+            cell = ws.cell(row=1, column=col_idx, value=header)
+            cell.font = header_font
+            cell.fill = header_fill
+        for row_idx, row in enumerate(op['rows'], 2):
+            for col_idx, value in enumerate(row, 1):
+                ws.cell(row=row_idx, column=col_idx, value=value)
+        if op.get('bold_last_row') and op['rows']:
+            for cell in ws[len(op['rows']) + 1]:
+                cell.font = Font(bold=True)
+        ws.freeze_panes = 'A2'
+    elif op['kind'] == 'conditional_formats':
+        ws = wb[op['sheet']] if op.get('sheet') else wb.worksheets[0]
+        apply_conditional_formats(ws, op['rules'])
+
+wb.save(output_path)
+print('OK')
+"#,
+        cf_helper = PY_CONDITIONAL_FORMATS,
+        ops_json = ops_json.replace('\\', "\\\\").replace('\'', "\\'"),
+        source = quote_python_str(source),
+        output_path = quote_python_str(output_path),
     );
 
-    run_python_script(&script, &output_path, "xlsx", sheets.len(), ctx).await
+    run_python_script(&script, output_path, "xlsx", item_count, ctx).await
 }
 
 /// Resolve output path from params or generate default.
@@ -752,6 +1411,91 @@ mod tests {
             .is_err());
     }
 
+    #[test]
+    fn test_validate_spreadsheet_actions() {
+        let tool = OfficeWriteTool;
+        assert!(tool
+            .validate(&json!({"action": "read_xlsx", "path": "a.xlsx"}))
+            .is_ok());
+        assert!(tool.validate(&json!({"action": "read_xlsx"})).is_err());
+        assert!(tool
+            .validate(&json!({"action": "evaluate", "path": "a.xlsx", "formula": "=SUM(A1:A3)"}))
+            .is_ok());
+        assert!(tool
+            .validate(&json!({"action": "evaluate", "path": "a.xlsx"}))
+            .is_err());
+        assert!(tool
+            .validate(&json!({
+                "action": "pivot",
+                "path": "a.xlsx",
+                "rows": ["Region"],
+                "values": ["Sales", {"field": "Sales", "agg": "avg"}]
+            }))
+            .is_ok());
+        assert!(tool
+            .validate(&json!({
+                "action": "pivot",
+                "path": "a.xlsx",
+                "rows": ["Region"],
+                "values": [{"field": "Sales", "agg": "stddev"}]
+            }))
+            .is_err());
+        assert!(tool
+            .validate(&json!({"action": "format_xlsx", "path": "a.xlsx"}))
+            .is_err());
+    }
+
+    #[test]
+    fn test_validate_conditional_formats() {
+        assert!(validate_conditional_formats(&json!([
+            {"range": "C2:C100", "operator": ">", "value": 1000},
+            {"range": "D2:D100", "type": "color_scale"},
+            {"range": "A2:F100", "type": "formula", "formula": "$C2<0"}
+        ]))
+        .is_ok());
+        assert!(validate_conditional_formats(&json!([{"operator": ">", "value": 1}])).is_err());
+        assert!(validate_conditional_formats(
+            &json!([{"range": "C2:C9", "operator": "~", "value": 1}])
+        )
+        .is_err());
+        assert!(validate_conditional_formats(&json!([{"range": "C2:C9"}])).is_err());
+        assert!(
+            validate_conditional_formats(&json!([{"range": "C2:C9", "type": "sparkle"}])).is_err()
+        );
+
+        let tool = OfficeWriteTool;
+        assert!(tool
+            .validate(&json!({
+                "action": "create_xlsx",
+                "sheets": [{"name": "Data", "rows": [[1]], "conditional_formats": [{"range": "bad"}]}]
+            }))
+            .is_err());
+    }
+
+    #[test]
+    fn test_pivot_spec_resolves_fields() {
+        let headers = vec![
+            "Region".to_string(),
+            "Quarter".to_string(),
+            "Sales".to_string(),
+        ];
+        let spec = pivot_spec(
+            &json!({
+                "rows": ["region"],
+                "columns": "B",
+                "values": ["Sales", {"field": "C", "agg": "max"}],
+                "totals": false
+            }),
+            &headers,
+        )
+        .unwrap();
+        assert_eq!(spec.rows, vec![0]);
+        assert_eq!(spec.columns, Some(1));
+        assert_eq!(spec.values, vec![(2, Aggregate::Sum), (2, Aggregate::Max)]);
+        assert!(!spec.totals);
+        assert!(resolve_field(&headers, "Profit").is_err());
+    }
+
     #[test]
     fn test_validate_info() {
         let tool = OfficeWriteTool;
@@ -0,0 +1,902 @@
+//! Parcel tracking through 17track or AfterShip.
+//!
+//! Tracking numbers are registered with the provider (which resolves the carrier) and kept
+//! in `workspace/parcels/parcels.json`. The runtime tick calls [`spawn_due_checks`], which
+//! polls undelivered parcels every `tools.parcel.pollMinutes` and posts a system event when
+//! the status or the latest checkpoint changes.
+
+use async_trait::async_trait;
+use blockcell_core::config::ParcelConfig;
+use blockcell_core::secrets::resolve_secret_ref;
+use blockcell_core::system_event::EventPriority;
+use blockcell_core::{Config, Error, Result};
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::egress::HttpClientFactory;
+use crate::{EventEmitterHandle, PromptContext, Tool, ToolContext, ToolSchema};
+
+const REQUEST_TIMEOUT_SECS: u64 = 20;
+const SEVENTEENTRACK_BASE: &str = "https://api.17track.net/track/v2.2";
+const AFTERSHIP_BASE: &str = "https://api.aftership.com/tracking/2024-04";
+/// 17track's "already registered" error code; re-registering is harmless.
+const SEVENTEENTRACK_ALREADY_REGISTERED: i64 = -18019901;
+
+static STORE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+static CHECK_RUNNING: AtomicBool = AtomicBool::new(false);
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Carrier detection
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Best local guess of the carrier (AfterShip slug) from the number's shape. Providers
+/// detect carriers themselves; this only fills the gap before their first answer.
+pub fn detect_carrier(number: &str) -> Option<&'static str> {
+    let n = normalize_number(number);
+    let digits = n.chars().all(|c| c.is_ascii_digit());
+    let upu = |suffix: &str| {
+        n.len() == 13
+            && n[..2].chars().all(|c| c.is_ascii_alphabetic())
+            && n[2..11].chars().all(|c| c.is_ascii_digit())
+            && n.ends_with(suffix)
+    };
+    if n.starts_with("1Z") && n.len() == 18 {
+        Some("ups")
+    } else if n.starts_with("TBA") && n.len() >= 12 {
+        Some("amazon")
+    } else if n.starts_with("SF") && n.len() >= 14 && n[2..].chars().all(|c| c.is_ascii_digit()) {
+        Some("sf-express")
+    } else if n.starts_with("JD") && n.len() >= 14 {
+        Some("jd")
+    } else if upu("GB") {
+        Some("royal-mail")
+    } else if upu("CN") {
+        Some("china-post")
+    } else if upu("US") {
+        Some("usps")
+    } else if upu("DE") {
+        Some("deutsch-post")
+    } else if digits && (20..=22).contains(&n.len()) && n.starts_with('9') {
+        Some("usps")
+    } else if digits && (n.len() == 12 || n.len() == 20) && n.starts_with("00340") {
+        Some("dhl-germany")
+    } else if digits && matches!(n.len(), 12 | 15) {
+        Some("fedex")
+    } else if digits && n.len() == 10 {
+        Some("dhl")
+    } else {
+        None
+    }
+}
+
+fn normalize_number(number: &str) -> String {
+    number
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_uppercase()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Providers
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub time: Option<String>,
+    pub location: Option<String>,
+    pub message: String,
+}
+
+/// Status as reported by the provider, normalized to snake_case tags.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParcelStatus {
+    /// pending, info_received, in_transit, out_for_delivery, available_for_pickup,
+    /// delivered, exception, expired or unknown.
+    pub status: String,
+    pub carrier: Option<String>,
+    pub latest: Option<Checkpoint>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eta: Option<String>,
+}
+
+/// Maps 17track/AfterShip status tags (both CamelCase) to our snake_case set.
+fn normalize_status(tag: &str) -> &'static str {
+    match tag {
+        "NotFound" | "Pending" => "pending",
+        "InfoReceived" => "info_received",
+        "InTransit" => "in_transit",
+        "OutForDelivery" => "out_for_delivery",
+        "AvailableForPickup" => "available_for_pickup",
+        "Delivered" => "delivered",
+        "DeliveryFailure" | "AttemptFail" | "Exception" => "exception",
+        "Expired" => "expired",
+        _ => "unknown",
+    }
+}
+
+fn opt_str(v: &Value) -> Option<String> {
+    v.as_str()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+}
+
+fn parse_17track(info: &Value) -> ParcelStatus {
+    let track = &info["track_info"];
+    let latest = &track["latest_event"];
+    let provider = track["tracking"]["providers"]
+        .as_array()
+        .and_then(|p| p.first())
+        .map(|p| &p["provider"]);
+    ParcelStatus {
+        status: normalize_status(track["latest_status"]["status"].as_str().unwrap_or(""))
+            .to_string(),
+        carrier: provider.and_then(|p| opt_str(&p["name"])),
+        latest: opt_str(&latest["description"]).map(|message| Checkpoint {
+            time: opt_str(&latest["time_iso"]),
+            location: opt_str(&latest["location"]),
+            message,
+        }),
+        eta: opt_str(&track["time_metrics"]["estimated_delivery_date"]["from"]),
+    }
+}
+
+fn parse_aftership(tracking: &Value) -> ParcelStatus {
+    let latest = tracking["checkpoints"].as_array().and_then(|c| c.last());
+    ParcelStatus {
+        status: normalize_status(tracking["tag"].as_str().unwrap_or("")).to_string(),
+        carrier: opt_str(&tracking["slug"]),
+        latest: latest.and_then(|c| {
+            opt_str(&c["message"]).map(|message| Checkpoint {
+                time: opt_str(&c["checkpoint_time"]),
+                location: opt_str(&c["location"]).or_else(|| opt_str(&c["city"])),
+                message,
+            })
+        }),
+        eta: opt_str(&tracking["courier_estimated_delivery_date"]["estimated_delivery_date"])
+            .or_else(|| opt_str(&tracking["expected_delivery"])),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Provider {
+    SeventeenTrack,
+    AfterShip,
+}
+
+impl Provider {
+    fn from_config(cfg: &ParcelConfig) -> Result<Self> {
+        match cfg.provider.trim().to_ascii_lowercase().as_str() {
+            "" | "17track" => Ok(Provider::SeventeenTrack),
+            "aftership" => Ok(Provider::AfterShip),
+            other => Err(Error::Config(format!(
+                "Unknown tools.parcel.provider '{}'. Use 17track or aftership",
+                other
+            ))),
+        }
+    }
+
+    fn base(self, cfg: &ParcelConfig) -> String {
+        let custom = cfg.api_base.trim().trim_end_matches('/');
+        if !custom.is_empty() {
+            return custom.to_string();
+        }
+        match self {
+            Provider::SeventeenTrack => SEVENTEENTRACK_BASE.to_string(),
+            Provider::AfterShip => AFTERSHIP_BASE.to_string(),
+        }
+    }
+
+    fn env_key(self) -> &'static str {
+        match self {
+            Provider::SeventeenTrack => "SEVENTEENTRACK_API_KEY",
+            Provider::AfterShip => "AFTERSHIP_API_KEY",
+        }
+    }
+
+    fn auth_header(self) -> &'static str {
+        match self {
+            Provider::SeventeenTrack => "17token",
+            Provider::AfterShip => "as-api-key",
+        }
+    }
+}
+
+fn api_key(cfg: &ParcelConfig, provider: Provider, workspace: &Path) -> Result<String> {
+    if cfg.api_key.trim().is_empty() {
+        return std::env::var(provider.env_key())
+            .ok()
+            .map(|k| k.trim().to_string())
+            .filter(|k| !k.is_empty())
+            .ok_or_else(|| {
+                Error::Tool(format!(
+                    "Parcel tracking needs tools.parcel.apiKey (or {})",
+                    provider.env_key()
+                ))
+            });
+    }
+    resolve_secret_ref(&crate::paths_for_workspace(workspace), &cfg.api_key)
+}
+
+struct Api {
+    provider: Provider,
+    base: String,
+    key: String,
+    client: reqwest::Client,
+}
+
+impl Api {
+    async fn new(config: &Config, workspace: &Path) -> Result<Self> {
+        let cfg = &config.tools.parcel;
+        let provider = Provider::from_config(cfg)?;
+        let base = provider.base(cfg);
+        let factory = HttpClientFactory::for_tool(config, "parcel_track");
+        factory.check(&base).await?;
+        let client = factory
+            .builder()
+            .timeout(std::time::Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()
+            .map_err(|e| Error::Tool(format!("Failed to build HTTP client: {}", e)))?;
+        Ok(Self {
+            provider,
+            base,
+            key: api_key(cfg, provider, workspace)?,
+            client,
+        })
+    }
+
+    async fn send(&self, req: reqwest::RequestBuilder) -> Result<Value> {
+        let resp = req
+            .header(self.provider.auth_header(), &self.key)
+            .send()
+            .await
+            .map_err(|e| Error::Tool(format!("Tracking request failed: {}", e)))?;
+        let status = resp.status();
+        let body: Value = resp
+            .json()
+            .await
+            .map_err(|e| Error::Tool(format!("Invalid tracking response: {}", e)))?;
+        // AfterShip reports "already exists" as 4003 with the existing tracking attached.
+        if !status.is_success() && body["meta"]["code"].as_i64() != Some(4003) {
+            let message = opt_str(&body["meta"]["message"]).unwrap_or_else(|| status.to_string());
+            return Err(Error::Tool(format!("Tracking API error: {}", message)));
+        }
+        Ok(body)
+    }
+
+    /// Registers a number with the provider; returns the provider-side id when it has one.
+    async fn register(&self, number: &str, carrier: Option<&str>) -> Result<Option<String>> {
+        match self.provider {
+            Provider::SeventeenTrack => {
+                let mut item = json!({"number": number});
+                if let Some(code) = carrier.and_then(|c| c.parse::<i64>().ok()) {
+                    item["carrier"] = json!(code);
+                }
+                let body = self
+                    .send(
+                        self.client
+                            .post(format!("{}/register", self.base))
+                            .json(&json!([item])),
+                    )
+                    .await?;
+                if let Some(rejected) = body["data"]["rejected"].as_array().and_then(|r| r.first())
+                {
+                    if rejected["error"]["code"].as_i64() != Some(SEVENTEENTRACK_ALREADY_REGISTERED)
+                    {
+                        let message = opt_str(&rejected["error"]["message"])
+                            .unwrap_or_else(|| "rejected".to_string());
+                        return Err(Error::Tool(format!("17track: {}", message)));
+                    }
+                }
+                Ok(None)
+            }
+            Provider::AfterShip => {
+                let mut tracking = json!({"tracking_number": number});
+                if let Some(slug) = carrier {
+                    tracking["slug"] = json!(slug);
+                }
+                let body = self
+                    .send(
+                        self.client
+                            .post(format!("{}/trackings", self.base))
+                            .json(&tracking),
+                    )
+                    .await?;
+                let data = &body["data"];
+                let tracking = if data["tracking"].is_object() {
+                    &data["tracking"]
+                } else {
+                    data
+                };
+                Ok(opt_str(&tracking["id"]))
+            }
+        }
+    }
+
+    async fn status(&self, number: &str, provider_id: Option<&str>) -> Result<ParcelStatus> {
+        match self.provider {
+            Provider::SeventeenTrack => {
+                let body = self
+                    .send(
+                        self.client
+                            .post(format!("{}/gettrackinfo", self.base))
+                            .json(&json!([{"number": number}])),
+                    )
+                    .await?;
+                body["data"]["accepted"]
+                    .as_array()
+                    .and_then(|a| a.first())
+                    .map(parse_17track)
+                    .ok_or_else(|| {
+                        Error::NotFound(format!("17track has no data for {} yet", number))
+                    })
+            }
+            Provider::AfterShip => {
+                let id = provider_id.ok_or_else(|| {
+                    Error::Tool(format!("{} is not registered with AfterShip", number))
+                })?;
+                let body = self
+                    .send(self.client.get(format!(
+                        "{}/trackings/{}",
+                        self.base,
+                        urlencoding::encode(id)
+                    )))
+                    .await?;
+                let data = &body["data"];
+                let tracking = if data["tracking"].is_object() {
+                    &data["tracking"]
+                } else {
+                    data
+                };
+                Ok(parse_aftership(tracking))
+            }
+        }
+    }
+
+    async fn unregister(&self, number: &str, provider_id: Option<&str>) -> Result<()> {
+        match self.provider {
+            Provider::SeventeenTrack => {
+                self.send(
+                    self.client
+                        .post(format!("{}/stoptrack", self.base))
+                        .json(&json!([{"number": number}])),
+                )
+                .await?;
+            }
+            Provider::AfterShip => {
+                if let Some(id) = provider_id {
+                    self.send(self.client.delete(format!(
+                        "{}/trackings/{}",
+                        self.base,
+                        urlencoding::encode(id)
+                    )))
+                    .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Store
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ParcelStore {
+    version: u32,
+    parcels: Vec<Parcel>,
+}
+
+impl Default for ParcelStore {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            parcels: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Parcel {
+    id: String,
+    number: String,
+    #[serde(default)]
+    label: Option<String>,
+    /// User-supplied or locally detected carrier.
+    #[serde(default)]
+    carrier_hint: Option<String>,
+    #[serde(default)]
+    provider_id: Option<String>,
+    active: bool,
+    created_at: i64,
+    #[serde(default)]
+    last_checked: Option<i64>,
+    #[serde(default)]
+    last_status: Option<ParcelStatus>,
+    #[serde(default)]
+    last_error: Option<String>,
+}
+
+impl Parcel {
+    fn title(&self) -> String {
+        match &self.label {
+            Some(label) => format!("{}（{}）", label, self.number),
+            None => self.number.clone(),
+        }
+    }
+}
+
+fn store_path(workspace: &Path) -> PathBuf {
+    workspace.join("parcels").join("parcels.json")
+}
+
+fn load_store(workspace: &Path) -> Result<ParcelStore> {
+    let path = store_path(workspace);
+    if !path.exists() {
+        return Ok(ParcelStore::default());
+    }
+    let content = std::fs::read_to_string(&path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+fn save_store(workspace: &Path, store: &ParcelStore) -> Result<()> {
+    let path = store_path(workspace);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(store)?)?;
+    Ok(())
+}
+
+fn find_parcel<'a>(store: &'a mut ParcelStore, key: &str) -> Option<&'a mut Parcel> {
+    let number = normalize_number(key);
+    store
+        .parcels
+        .iter_mut()
+        .find(|p| p.id == key || p.number == number)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Polling
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn status_label(status: &str) -> &str {
+    match status {
+        "pending" => "待揽收",
+        "info_received" => "已录入",
+        "in_transit" => "运输中",
+        "out_for_delivery" => "派送中",
+        "available_for_pickup" => "待取件",
+        "delivered" => "已签收",
+        "exception" => "异常",
+        "expired" => "已过期",
+        other => other,
+    }
+}
+
+/// Describes what changed between two polls, or `None` when nothing worth telling did.
+fn describe_change(
+    old: Option<&ParcelStatus>,
+    new: &ParcelStatus,
+) -> Option<(String, EventPriority)> {
+    let status_changed = old.is_none_or(|o| o.status != new.status);
+    let checkpoint_changed =
+        new.latest.is_some() && old.and_then(|o| o.latest.as_ref()) != new.latest.as_ref();
+    if !status_changed && !checkpoint_changed {
+        return None;
+    }
+    let mut text = match old {
+        Some(o) if status_changed => format!(
+            "状态：{} → {}",
+            status_label(&o.status),
+            status_label(&new.status)
+        ),
+        _ => format!("状态：{}", status_label(&new.status)),
+    };
+    if let Some(cp) = &new.latest {
+        text.push_str("\n最新：");
+        if let Some(time) = &cp.time {
+            text.push_str(&format!("{} ", time));
+        }
+        if let Some(location) = &cp.location {
+            text.push_str(&format!("[{}] ", location));
+        }
+        text.push_str(&cp.message);
+    }
+    if let Some(eta) = &new.eta {
+        text.push_str(&format!("\n预计送达：{}", eta));
+    }
+    let priority = match new.status.as_str() {
+        "delivered" | "out_for_delivery" | "available_for_pickup" | "exception"
+            if status_changed =>
+        {
+            EventPriority::High
+        }
+        _ => EventPriority::Normal,
+    };
+    Some((text, priority))
+}
+
+struct CheckOutcome {
+    parcel: Parcel,
+    change: Option<(String, EventPriority)>,
+}
+
+async fn check_parcels(
+    config: &Config,
+    workspace: &Path,
+    force: bool,
+) -> Result<Vec<CheckOutcome>> {
+    let now = Utc::now().timestamp_millis();
+    let interval = config.tools.parcel.poll_minutes.max(5) as i64 * 60_000;
+    let due: Vec<Parcel> = {
+        let _guard = STORE_LOCK.lock().await;
+        load_store(workspace)?
+            .parcels
+            .into_iter()
+            .filter(|p| p.active)
+            .filter(|p| force || p.last_checked.is_none_or(|t| now - t >= interval))
+            .collect()
+    };
+    if due.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let api = Api::new(config, workspace).await?;
+    let mut outcomes = Vec::new();
+    for mut parcel in due {
+        parcel.last_checked = Some(Utc::now().timestamp_millis());
+        let change = match api
+            .status(&parcel.number, parcel.provider_id.as_deref())
+            .await
+        {
+            Ok(status) => {
+                parcel.last_error = None;
+                let change = describe_change(parcel.last_status.as_ref(), &status);
+                if matches!(status.status.as_str(), "delivered" | "expired") {
+                    parcel.active = false;
+                }
+                parcel.last_status = Some(status);
+                change
+            }
+            Err(e) => {
+                warn!(parcel = %parcel.id, error = %e, "Parcel status check failed");
+                parcel.last_error = Some(e.to_string());
+                None
+            }
+        };
+        outcomes.push(CheckOutcome { parcel, change });
+    }
+
+    let _guard = STORE_LOCK.lock().await;
+    let mut store = load_store(workspace)?;
+    for outcome in &outcomes {
+        // The parcel may have been removed while the lookup was running.
+        if let Some(p) = store.parcels.iter_mut().find(|p| p.id == outcome.parcel.id) {
+            *p = outcome.parcel.clone();
+        }
+    }
+    save_store(workspace, &store)?;
+    Ok(outcomes)
+}
+
+/// Poll due parcels in the background and post a system event per status change.
+pub fn spawn_due_checks(config: Config, workspace: PathBuf, emitter: EventEmitterHandle) {
+    if !store_path(&workspace).exists() {
+        return;
+    }
+    if CHECK_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    tokio::spawn(async move {
+        match check_parcels(&config, &workspace, false).await {
+            Ok(outcomes) => {
+                for o in &outcomes {
+                    if let Some((text, priority)) = &o.change {
+                        info!(parcel = %o.parcel.id, "Parcel status changed");
+                        emitter.emit_simple(
+                            "parcel.changed",
+                            "parcel_track",
+                            *priority,
+                            &format!("包裹动态：{}", o.parcel.title()),
+                            text,
+                        );
+                    }
+                }
+            }
+            Err(e) => warn!(error = %e, "Parcel polling failed"),
+        }
+        CHECK_RUNNING.store(false, Ordering::SeqCst);
+    });
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tool
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn parcel_json(p: &Parcel) -> Value {
+    json!({
+        "id": p.id,
+        "number": p.number,
+        "label": p.label,
+        "carrier": p
+            .last_status
+            .as_ref()
+            .and_then(|s| s.carrier.clone())
+            .or_else(|| p.carrier_hint.clone()),
+        "active": p.active,
+        "last_checked": p.last_checked,
+        "last_status": p.last_status,
+        "last_error": p.last_error,
+    })
+}
+
+pub struct ParcelTrackTool;
+
+#[async_trait]
+impl Tool for ParcelTrackTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "parcel_track",
+            description: "Track packages across carriers (17track/AfterShip); the carrier is detected automatically. Registered parcels are polled and status changes are announced. You MUST provide `action`. action='add': requires `number`; optional `carrier` (slug like 'ups', or a 17track carrier code) and `label`. action='status': requires `id` (parcel id or tracking number); refreshes now. action='remove': requires `id`. action='list': all parcels. action='check': polls every active parcel now. action='detect': requires `number`; guesses the carrier offline.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["add", "status", "remove", "list", "check", "detect"],
+                        "description": "Action to perform"
+                    },
+                    "number": {"type": "string", "description": "Tracking number"},
+                    "carrier": {"type": "string", "description": "(add) Carrier slug or 17track code; omit to auto-detect"},
+                    "label": {"type": "string", "description": "(add) Short description, e.g. 'New headphones'"},
+                    "id": {"type": "string", "description": "Parcel id or tracking number"}
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        let has = |key: &str| params[key].as_str().is_some_and(|s| !s.trim().is_empty());
+        match params["action"].as_str().unwrap_or("") {
+            "add" | "detect" => {
+                if !has("number") {
+                    return Err(Error::Validation("'number' is required".into()));
+                }
+                Ok(())
+            }
+            "status" | "remove" => {
+                if !has("id") {
+                    return Err(Error::Validation("'id' is required".into()));
+                }
+                Ok(())
+            }
+            "list" | "check" => Ok(()),
+            other => Err(Error::Validation(format!(
+                "Unknown action '{}'. Use add, status, remove, list, check or detect",
+                other
+            ))),
+        }
+    }
+
+    fn prompt_rule(&self, _ctx: &PromptContext) -> Option<String> {
+        Some(
+            "- For package tracking numbers, use `parcel_track` `add` (carrier is auto-detected); updates are announced automatically, so no custom skill or cron job is needed."
+                .to_string(),
+        )
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        let workspace = ctx.workspace.clone();
+        match params["action"].as_str().unwrap_or("") {
+            "detect" => {
+                let number = normalize_number(params["number"].as_str().unwrap_or(""));
+                Ok(json!({"number": number, "carrier": detect_carrier(&number)}))
+            }
+            "add" => {
+                let number = normalize_number(params["number"].as_str().unwrap_or(""));
+                if number.len() < 6 {
+                    return Err(Error::Validation(format!(
+                        "'{}' does not look like a tracking number",
+                        number
+                    )));
+                }
+                {
+                    let _guard = STORE_LOCK.lock().await;
+                    let mut store = load_store(&workspace)?;
+                    if let Some(existing) = find_parcel(&mut store, &number) {
+                        return Ok(json!({"status": "exists", "parcel": parcel_json(existing)}));
+                    }
+                }
+                let explicit = params["carrier"]
+                    .as_str()
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(String::from);
+                let api = Api::new(&ctx.config, &workspace).await?;
+                let provider_id = api.register(&number, explicit.as_deref()).await?;
+                // A fresh registration often has no events yet; that is not an error.
+                let status = api.status(&number, provider_id.as_deref()).await.ok();
+                let now = Utc::now().timestamp_millis();
+                let parcel = Parcel {
+                    id: format!(
+                        "parcel_{}",
+                        Uuid::new_v4().to_string().split('-').next().unwrap_or("x")
+                    ),
+                    carrier_hint: explicit.or_else(|| detect_carrier(&number).map(String::from)),
+                    number,
+                    label: params["label"]
+                        .as_str()
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .map(String::from),
+                    provider_id,
+                    active: status.as_ref().is_none_or(|s| s.status != "delivered"),
+                    created_at: now,
+                    last_checked: Some(now),
+                    last_status: status,
+                    last_error: None,
+                };
+                let _guard = STORE_LOCK.lock().await;
+                let mut store = load_store(&workspace)?;
+                store.parcels.push(parcel.clone());
+                save_store(&workspace, &store)?;
+                Ok(json!({"status": "tracking", "parcel": parcel_json(&parcel)}))
+            }
+            "status" => {
+                let key = params["id"].as_str().unwrap_or("").trim();
+                let parcel = {
+                    let _guard = STORE_LOCK.lock().await;
+                    let mut store = load_store(&workspace)?;
+                    find_parcel(&mut store, key)
+                        .cloned()
+                        .ok_or_else(|| Error::NotFound(format!("Parcel '{}' not found", key)))?
+                };
+                let api = Api::new(&ctx.config, &workspace).await?;
+                let status = api
+                    .status(&parcel.number, parcel.provider_id.as_deref())
+                    .await?;
+                let _guard = STORE_LOCK.lock().await;
+                let mut store = load_store(&workspace)?;
+                let stored = find_parcel(&mut store, &parcel.id)
+                    .ok_or_else(|| Error::NotFound(format!("Parcel '{}' not found", key)))?;
+                stored.last_checked = Some(Utc::now().timestamp_millis());
+                stored.last_error = None;
+                if status.status == "delivered" {
+                    stored.active = false;
+                }
+                stored.last_status = Some(status);
+                let out = parcel_json(stored);
+                save_store(&workspace, &store)?;
+                Ok(out)
+            }
+            "remove" => {
+                let key = params["id"].as_str().unwrap_or("").trim();
+                let parcel = {
+                    let _guard = STORE_LOCK.lock().await;
+                    let mut store = load_store(&workspace)?;
+                    let parcel = find_parcel(&mut store, key)
+                        .cloned()
+                        .ok_or_else(|| Error::NotFound(format!("Parcel '{}' not found", key)))?;
+                    store.parcels.retain(|p| p.id != parcel.id);
+                    save_store(&workspace, &store)?;
+                    parcel
+                };
+                // Stopping provider-side tracking is best effort; the local entry is gone already.
+                if let Ok(api) = Api::new(&ctx.config, &workspace).await {
+                    if let Err(e) = api
+                        .unregister(&parcel.number, parcel.provider_id.as_deref())
+                        .await
+                    {
+                        warn!(parcel = %parcel.id, error = %e, "Failed to stop provider tracking");
+                    }
+                }
+                Ok(json!({"status": "removed", "id": parcel.id, "number": parcel.number}))
+            }
+            "list" => {
+                let _guard = STORE_LOCK.lock().await;
+                let store = load_store(&workspace)?;
+                let parcels: Vec<Value> = store.parcels.iter().map(parcel_json).collect();
+                Ok(json!({"parcels": parcels, "count": parcels.len()}))
+            }
+            "check" => {
+                let outcomes = check_parcels(&ctx.config, &workspace, true).await?;
+                let results: Vec<Value> = outcomes
+                    .iter()
+                    .map(|o| {
+                        json!({
+                            "parcel": parcel_json(&o.parcel),
+                            "changed": o.change.as_ref().map(|(text, _)| text),
+                        })
+                    })
+                    .collect();
+                Ok(json!({"checked": results.len(), "results": results}))
+            }
+            other => Err(Error::Tool(format!("Unknown action: {}", other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_carrier() {
+        assert_eq!(detect_carrier("1Z999AA10123456784"), Some("ups"));
+        assert_eq!(detect_carrier("rr 123 456 785 gb"), Some("royal-mail"));
+        assert_eq!(detect_carrier("9400111899223197428490"), Some("usps"));
+        assert_eq!(detect_carrier("SF1234567890123"), Some("sf-express"));
+        assert_eq!(detect_carrier("1234567890"), Some("dhl"));
+        assert_eq!(detect_carrier("hello"), None);
+    }
+
+    #[test]
+    fn test_parse_providers() {
+        let info = json!({
+            "number": "RR123456785GB",
+            "track_info": {
+                "latest_status": {"status": "InTransit"},
+                "latest_event": {"time_iso": "2026-10-14T09:12:00+01:00", "description": "Arrived at sorting centre", "location": "Leeds"},
+                "tracking": {"providers": [{"provider": {"name": "Royal Mail"}}]}
+            }
+        });
+        let s = parse_17track(&info);
+        assert_eq!(s.status, "in_transit");
+        assert_eq!(s.carrier.as_deref(), Some("Royal Mail"));
+        assert_eq!(s.latest.unwrap().location.as_deref(), Some("Leeds"));
+
+        let tracking = json!({
+            "slug": "ups", "tag": "AttemptFail",
+            "checkpoints": [
+                {"message": "Picked up", "checkpoint_time": "2026-10-12T10:00:00"},
+                {"message": "Delivery attempted", "city": "Austin", "checkpoint_time": "2026-10-14T16:00:00"}
+            ]
+        });
+        let s = parse_aftership(&tracking);
+        assert_eq!(s.status, "exception");
+        assert_eq!(s.latest.unwrap().message, "Delivery attempted");
+    }
+
+    #[test]
+    fn test_describe_change() {
+        let old = ParcelStatus {
+            status: "in_transit".into(),
+            carrier: None,
+            latest: Some(Checkpoint {
+                time: None,
+                location: None,
+                message: "Departed hub".into(),
+            }),
+            eta: None,
+        };
+        assert!(describe_change(Some(&old), &old).is_none());
+
+        let mut new = old.clone();
+        new.latest = Some(Checkpoint {
+            time: None,
+            location: None,
+            message: "Arrived at depot".into(),
+        });
+        assert_eq!(
+            describe_change(Some(&old), &new).unwrap().1,
+            EventPriority::Normal
+        );
+
+        new.status = "delivered".into();
+        let (text, priority) = describe_change(Some(&old), &new).unwrap();
+        assert!(text.contains("已签收"));
+        assert_eq!(priority, EventPriority::High);
+    }
+}
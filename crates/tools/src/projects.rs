@@ -0,0 +1,660 @@
+use async_trait::async_trait;
+use blockcell_core::{Error, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::{PromptContext, Tool, ToolContext, ToolSchema};
+
+/// Persistent project store — saved to workspace/projects/projects.json
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectStore {
+    version: u32,
+    #[serde(default)]
+    pub projects: Vec<Project>,
+    /// Session key → project id for chats bound to a project.
+    #[serde(default)]
+    pub bindings: BTreeMap<String, String>,
+}
+
+impl Default for ProjectStore {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            projects: Vec::new(),
+            bindings: BTreeMap::new(),
+        }
+    }
+}
+
+/// A long-running piece of work with its own context pack.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Project {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// "active", "paused" or "done".
+    #[serde(default = "default_status")]
+    pub status: String,
+    /// Files the project revolves around (workspace-relative or absolute).
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// Other sessions that belong to the project (besides bound ones).
+    #[serde(default)]
+    pub sessions: Vec<String>,
+    /// Tag used for the project's memories in memory_upsert/memory_query.
+    #[serde(default)]
+    pub memory_namespace: String,
+    #[serde(default)]
+    pub todos: Vec<ProjectTodo>,
+    #[serde(default)]
+    pub milestones: Vec<Milestone>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectTodo {
+    pub id: String,
+    pub text: String,
+    #[serde(default)]
+    pub done: bool,
+    #[serde(default)]
+    pub done_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Milestone {
+    pub id: String,
+    pub title: String,
+    /// Target date as YYYY-MM-DD.
+    #[serde(default)]
+    pub due: Option<String>,
+    #[serde(default)]
+    pub done: bool,
+    #[serde(default)]
+    pub done_at: Option<i64>,
+}
+
+fn default_status() -> String {
+    "active".to_string()
+}
+
+fn short_id(prefix: &str) -> String {
+    format!(
+        "{}_{}",
+        prefix,
+        Uuid::new_v4().to_string().split('-').next().unwrap_or("x")
+    )
+}
+
+fn store_path(workspace: &Path) -> PathBuf {
+    workspace.join("projects").join("projects.json")
+}
+
+pub fn load_store(workspace: &Path) -> Result<ProjectStore> {
+    let path = store_path(workspace);
+    if !path.exists() {
+        return Ok(ProjectStore::default());
+    }
+    let content = std::fs::read_to_string(&path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+fn save_store(workspace: &Path, store: &ProjectStore) -> Result<()> {
+    let path = store_path(workspace);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(store)?)?;
+    Ok(())
+}
+
+impl ProjectStore {
+    /// Find by id or (case-insensitive) name.
+    fn find_index(&self, key: &str) -> Option<usize> {
+        let key = key.trim();
+        self.projects.iter().position(|p| p.id == key).or_else(|| {
+            self.projects
+                .iter()
+                .position(|p| p.name.eq_ignore_ascii_case(key))
+        })
+    }
+
+    fn get_mut(&mut self, key: &str) -> Result<&mut Project> {
+        let idx = self
+            .find_index(key)
+            .ok_or_else(|| Error::NotFound(format!("Project not found: {}", key)))?;
+        Ok(&mut self.projects[idx])
+    }
+
+    /// Project bound to `session_key`, if any.
+    pub fn bound_project(&self, session_key: &str) -> Option<&Project> {
+        let id = self.bindings.get(session_key)?;
+        self.projects.iter().find(|p| &p.id == id)
+    }
+}
+
+impl Project {
+    /// Context pack injected into the system prompt of chats bound to this project.
+    pub fn brief(&self) -> String {
+        let mut out = format!("## Active Project: {} (`{}`)\n", self.name, self.id);
+        if !self.description.is_empty() {
+            out.push_str(&self.description);
+            out.push('\n');
+        }
+        out.push_str(&format!("Status: {}\n", self.status));
+        if !self.memory_namespace.is_empty() {
+            out.push_str(&format!(
+                "Memory namespace: tag `{}` — pass it as `tags` to memory_upsert/memory_query for project memories.\n",
+                self.memory_namespace
+            ));
+        }
+        if !self.files.is_empty() {
+            out.push_str(&format!("Linked files: {}\n", self.files.join(", ")));
+        }
+        let open: Vec<&ProjectTodo> = self.todos.iter().filter(|t| !t.done).collect();
+        if !open.is_empty() {
+            out.push_str("Open TODOs:\n");
+            for todo in open.iter().take(15) {
+                out.push_str(&format!("- [ ] {} (`{}`)\n", todo.text, todo.id));
+            }
+            if open.len() > 15 {
+                out.push_str(&format!("- … {} more\n", open.len() - 15));
+            }
+        }
+        let upcoming: Vec<&Milestone> = self.milestones.iter().filter(|m| !m.done).collect();
+        if !upcoming.is_empty() {
+            out.push_str("Milestones:\n");
+            for m in upcoming {
+                match &m.due {
+                    Some(due) => {
+                        out.push_str(&format!("- {} — due {} (`{}`)\n", m.title, due, m.id))
+                    }
+                    None => out.push_str(&format!("- {} (`{}`)\n", m.title, m.id)),
+                }
+            }
+        }
+        out.push_str("Keep this project up to date with the `project` tool (TODOs, milestones, linked files).\n");
+        out
+    }
+}
+
+/// Brief for the project bound to `session_key`, or `None` when the chat is unbound.
+pub fn active_project_brief(workspace: &Path, session_key: &str) -> Option<String> {
+    let store = load_store(workspace).ok()?;
+    store.bound_project(session_key).map(Project::brief)
+}
+
+fn str_param<'a>(params: &'a Value, key: &str) -> Option<&'a str> {
+    params
+        .get(key)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+fn str_list(params: &Value, key: &str) -> Vec<String> {
+    match params.get(key) {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect(),
+        Some(Value::String(s)) if !s.trim().is_empty() => vec![s.trim().to_string()],
+        _ => Vec::new(),
+    }
+}
+
+fn slug(name: &str) -> String {
+    let slug: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    let slug = slug.trim_matches('_').to_string();
+    if slug.is_empty() {
+        "project".to_string()
+    } else {
+        format!("project_{}", slug)
+    }
+}
+
+/// Project id from params, falling back to the project bound to the current chat.
+fn target_key(store: &ProjectStore, params: &Value, session_key: &str) -> Result<String> {
+    if let Some(key) = str_param(params, "project") {
+        return Ok(key.to_string());
+    }
+    store.bindings.get(session_key).cloned().ok_or_else(|| {
+        Error::Validation("'project' is required (this chat is not bound to a project)".to_string())
+    })
+}
+
+pub struct ProjectTool;
+
+#[async_trait]
+impl Tool for ProjectTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "project",
+            description: "Manage named long-term projects (multi-week work). A project has a description, \
+                linked files/sessions, a memory namespace tag, TODOs and milestones. Binding the current chat \
+                to a project injects its brief into every later turn. \
+                Actions: 'create', 'list', 'get', 'update', 'link', 'unlink', 'add_todo', 'complete_todo', \
+                'remove_todo', 'add_milestone', 'complete_milestone', 'bind', 'unbind'. \
+                When 'project' is omitted, the project bound to the current chat is used.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["create", "list", "get", "update", "link", "unlink", "add_todo", "complete_todo", "remove_todo", "add_milestone", "complete_milestone", "bind", "unbind"],
+                        "description": "Action to perform"
+                    },
+                    "project": {
+                        "type": "string",
+                        "description": "Project id or name (defaults to the project bound to this chat)"
+                    },
+                    "name": {
+                        "type": "string",
+                        "description": "(create/update) Project name"
+                    },
+                    "description": {
+                        "type": "string",
+                        "description": "(create/update) Goal, scope and constraints of the project"
+                    },
+                    "status": {
+                        "type": "string",
+                        "enum": ["active", "paused", "done"],
+                        "description": "(update) Project status"
+                    },
+                    "memory_namespace": {
+                        "type": "string",
+                        "description": "(create/update) Memory tag for this project. Default: derived from the name"
+                    },
+                    "files": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "(create/link/unlink) File paths"
+                    },
+                    "sessions": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "(link/unlink) Session keys, e.g. 'telegram:12345'"
+                    },
+                    "text": {
+                        "type": "string",
+                        "description": "(add_todo) TODO text; (add_milestone) milestone title"
+                    },
+                    "due": {
+                        "type": "string",
+                        "description": "(add_milestone) Target date YYYY-MM-DD"
+                    },
+                    "item_id": {
+                        "type": "string",
+                        "description": "(complete_todo/remove_todo/complete_milestone) TODO or milestone id"
+                    },
+                    "bind": {
+                        "type": "boolean",
+                        "description": "(create) Also bind the current chat to the new project. Default: true"
+                    }
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        let action = params.get("action").and_then(|v| v.as_str()).unwrap_or("");
+        match action {
+            "create" => {
+                if str_param(params, "name").is_none() {
+                    return Err(Error::Validation("'name' is required for create".into()));
+                }
+            }
+            "add_todo" | "add_milestone" => {
+                if str_param(params, "text").is_none() {
+                    return Err(Error::Validation(format!(
+                        "'text' is required for {}",
+                        action
+                    )));
+                }
+            }
+            "complete_todo" | "remove_todo" | "complete_milestone" => {
+                if str_param(params, "item_id").is_none() {
+                    return Err(Error::Validation(format!(
+                        "'item_id' is required for {}",
+                        action
+                    )));
+                }
+            }
+            "list" | "get" | "update" | "link" | "unlink" | "bind" | "unbind" => {}
+            _ => return Err(Error::Validation(format!("Unknown action: {}", action))),
+        }
+        Ok(())
+    }
+
+    fn prompt_rule(&self, _ctx: &PromptContext) -> Option<String> {
+        Some(
+            "- For multi-week work the user calls a project, use the `project` tool: create it, bind the chat, and record TODOs and milestones there instead of only in memory."
+                .to_string(),
+        )
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        let workspace = ctx.workspace.clone();
+        let session_key = ctx.session_key.clone();
+        tokio::task::spawn_blocking(move || run_action(&workspace, &session_key, &params))
+            .await
+            .map_err(|e| Error::Tool(format!("Project task failed: {}", e)))?
+    }
+}
+
+fn run_action(workspace: &Path, session_key: &str, params: &Value) -> Result<Value> {
+    let action = params["action"].as_str().unwrap_or("");
+    let mut store = load_store(workspace)?;
+    let now = Utc::now().timestamp_millis();
+
+    if action == "list" {
+        let bound = store.bindings.get(session_key).cloned();
+        let projects: Vec<Value> = store
+            .projects
+            .iter()
+            .map(|p| {
+                json!({
+                    "id": p.id,
+                    "name": p.name,
+                    "status": p.status,
+                    "open_todos": p.todos.iter().filter(|t| !t.done).count(),
+                    "open_milestones": p.milestones.iter().filter(|m| !m.done).count(),
+                    "bound_to_this_chat": bound.as_deref() == Some(p.id.as_str()),
+                })
+            })
+            .collect();
+        return Ok(json!({"projects": projects, "count": projects.len()}));
+    }
+
+    if action == "create" {
+        let name = str_param(params, "name").unwrap_or_default().to_string();
+        if store.find_index(&name).is_some() {
+            return Err(Error::Validation(format!(
+                "A project named '{}' already exists",
+                name
+            )));
+        }
+        let project = Project {
+            id: short_id("proj"),
+            memory_namespace: str_param(params, "memory_namespace")
+                .map(str::to_string)
+                .unwrap_or_else(|| slug(&name)),
+            name,
+            description: str_param(params, "description")
+                .unwrap_or_default()
+                .to_string(),
+            status: default_status(),
+            files: str_list(params, "files"),
+            sessions: Vec::new(),
+            todos: Vec::new(),
+            milestones: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        };
+        let bind = params.get("bind").and_then(|v| v.as_bool()).unwrap_or(true);
+        if bind {
+            store
+                .bindings
+                .insert(session_key.to_string(), project.id.clone());
+        }
+        let out = json!({"status": "created", "project": project, "bound": bind});
+        store.projects.push(project);
+        save_store(workspace, &store)?;
+        return Ok(out);
+    }
+
+    if action == "unbind" {
+        let removed = store.bindings.remove(session_key).is_some();
+        save_store(workspace, &store)?;
+        return Ok(json!({"status": if removed { "unbound" } else { "not_bound" }}));
+    }
+
+    let key = target_key(&store, params, session_key)?;
+
+    let project = store.get_mut(&key)?;
+    let mut result = match action {
+        "get" => return Ok(json!({"project": project.clone(), "brief": project.brief()})),
+        "update" => {
+            if let Some(name) = str_param(params, "name") {
+                project.name = name.to_string();
+            }
+            if let Some(description) = params.get("description").and_then(|v| v.as_str()) {
+                project.description = description.trim().to_string();
+            }
+            if let Some(status) = str_param(params, "status") {
+                if !["active", "paused", "done"].contains(&status) {
+                    return Err(Error::Validation(format!("Invalid status: {}", status)));
+                }
+                project.status = status.to_string();
+            }
+            if let Some(ns) = str_param(params, "memory_namespace") {
+                project.memory_namespace = ns.to_string();
+            }
+            json!({"status": "updated"})
+        }
+        "link" | "unlink" => {
+            let files = str_list(params, "files");
+            let sessions = str_list(params, "sessions");
+            if files.is_empty() && sessions.is_empty() {
+                return Err(Error::Validation(
+                    "'files' or 'sessions' is required".to_string(),
+                ));
+            }
+            for (list, items) in [
+                (&mut project.files, files),
+                (&mut project.sessions, sessions),
+            ] {
+                for item in items {
+                    if action == "link" {
+                        if !list.contains(&item) {
+                            list.push(item);
+                        }
+                    } else {
+                        list.retain(|existing| existing != &item);
+                    }
+                }
+            }
+            json!({"status": if action == "link" { "linked" } else { "unlinked" }, "files": project.files, "sessions": project.sessions})
+        }
+        "add_todo" => {
+            let todo = ProjectTodo {
+                id: short_id("todo"),
+                text: str_param(params, "text").unwrap_or_default().to_string(),
+                done: false,
+                done_at: None,
+            };
+            let out = json!({"status": "added", "todo": todo});
+            project.todos.push(todo);
+            out
+        }
+        "complete_todo" | "remove_todo" => {
+            let item_id = str_param(params, "item_id").unwrap_or_default();
+            let idx = project
+                .todos
+                .iter()
+                .position(|t| t.id == item_id)
+                .ok_or_else(|| Error::NotFound(format!("TODO not found: {}", item_id)))?;
+            if action == "remove_todo" {
+                project.todos.remove(idx);
+                json!({"status": "removed"})
+            } else {
+                project.todos[idx].done = true;
+                project.todos[idx].done_at = Some(now);
+                json!({"status": "completed", "todo": project.todos[idx]})
+            }
+        }
+        "add_milestone" => {
+            let due = str_param(params, "due").map(str::to_string);
+            if let Some(ref d) = due {
+                if chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").is_err() {
+                    return Err(Error::Validation(format!(
+                        "Invalid due date '{}', expected YYYY-MM-DD",
+                        d
+                    )));
+                }
+            }
+            let milestone = Milestone {
+                id: short_id("ms"),
+                title: str_param(params, "text").unwrap_or_default().to_string(),
+                due,
+                done: false,
+                done_at: None,
+            };
+            let out = json!({"status": "added", "milestone": milestone});
+            project.milestones.push(milestone);
+            out
+        }
+        "complete_milestone" => {
+            let item_id = str_param(params, "item_id").unwrap_or_default();
+            let milestone = project
+                .milestones
+                .iter_mut()
+                .find(|m| m.id == item_id)
+                .ok_or_else(|| Error::NotFound(format!("Milestone not found: {}", item_id)))?;
+            milestone.done = true;
+            milestone.done_at = Some(now);
+            json!({"status": "completed", "milestone": milestone.clone()})
+        }
+        "bind" => {
+            let id = project.id.clone();
+            store.bindings.insert(session_key.to_string(), id.clone());
+            save_store(workspace, &store)?;
+            return Ok(json!({"status": "bound", "project": id, "session_key": session_key}));
+        }
+        _ => return Err(Error::Tool(format!("Unknown action: {}", action))),
+    };
+    project.updated_at = now;
+    let id = project.id.clone();
+    save_store(workspace, &store)?;
+    result["project"] = json!(id);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_workspace() -> PathBuf {
+        std::env::temp_dir().join(format!("blockcell_projects_{}", Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_schema() {
+        let tool = ProjectTool;
+        assert_eq!(tool.schema().name, "project");
+        assert!(tool.validate(&json!({"action": "create"})).is_err());
+        assert!(tool
+            .validate(&json!({"action": "create", "name": "Thesis"}))
+            .is_ok());
+        assert!(tool.validate(&json!({"action": "complete_todo"})).is_err());
+        assert!(tool.validate(&json!({"action": "explode"})).is_err());
+    }
+
+    #[test]
+    fn test_project_lifecycle_and_binding() {
+        let ws = temp_workspace();
+        let chat = "telegram:42";
+        let created = run_action(
+            &ws,
+            chat,
+            &json!({"action": "create", "name": "Kitchen Remodel", "description": "Redo the kitchen by June"}),
+        )
+        .unwrap();
+        let id = created["project"]["id"].as_str().unwrap().to_string();
+        assert_eq!(
+            created["project"]["memory_namespace"],
+            "project_kitchen_remodel"
+        );
+
+        // Bound chat can omit `project`.
+        let todo = run_action(
+            &ws,
+            chat,
+            &json!({"action": "add_todo", "text": "Get quotes"}),
+        )
+        .unwrap();
+        let todo_id = todo["todo"]["id"].as_str().unwrap().to_string();
+        run_action(
+            &ws,
+            chat,
+            &json!({"action": "add_milestone", "text": "Cabinets installed", "due": "2026-05-01"}),
+        )
+        .unwrap();
+        run_action(
+            &ws,
+            chat,
+            &json!({"action": "link", "files": ["plans/layout.pdf"]}),
+        )
+        .unwrap();
+
+        let brief = active_project_brief(&ws, chat).unwrap();
+        assert!(brief.contains("Kitchen Remodel"));
+        assert!(brief.contains("- [ ] Get quotes"));
+        assert!(brief.contains("due 2026-05-01"));
+        assert!(brief.contains("plans/layout.pdf"));
+        assert!(active_project_brief(&ws, "cli:other").is_none());
+
+        run_action(
+            &ws,
+            chat,
+            &json!({"action": "complete_todo", "item_id": todo_id}),
+        )
+        .unwrap();
+        assert!(!active_project_brief(&ws, chat)
+            .unwrap()
+            .contains("Get quotes"));
+
+        // Another chat needs an explicit project, then can bind by name.
+        assert!(run_action(&ws, "cli:other", &json!({"action": "get"})).is_err());
+        let bound = run_action(
+            &ws,
+            "cli:other",
+            &json!({"action": "bind", "project": "kitchen remodel"}),
+        )
+        .unwrap();
+        assert_eq!(bound["project"], id.as_str());
+        run_action(&ws, chat, &json!({"action": "unbind"})).unwrap();
+        assert!(active_project_brief(&ws, chat).is_none());
+        assert!(active_project_brief(&ws, "cli:other").is_some());
+
+        let _ = std::fs::remove_dir_all(&ws);
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        let ws = temp_workspace();
+        run_action(
+            &ws,
+            "cli:1",
+            &json!({"action": "create", "name": "A", "bind": false}),
+        )
+        .unwrap();
+        assert!(run_action(&ws, "cli:1", &json!({"action": "create", "name": "a"})).is_err());
+        assert!(run_action(
+            &ws,
+            "cli:1",
+            &json!({"action": "add_milestone", "project": "A", "text": "x", "due": "May 1"})
+        )
+        .is_err());
+        assert!(run_action(
+            &ws,
+            "cli:1",
+            &json!({"action": "update", "project": "A", "status": "someday"})
+        )
+        .is_err());
+        let _ = std::fs::remove_dir_all(&ws);
+    }
+}
@@ -0,0 +1,771 @@
+//! Code interpreter: runs Python snippets in a dedicated virtualenv or a throwaway
+//! container.
+//!
+//! The venv interpreter is wrapped in the `tools.exec.sandbox` backend chosen for the
+//! caller's privilege (see [`crate::sandbox`]), always without network. A `docker`
+//! choice there switches to the container backend; with `none` the venv runs directly
+//! on the host.
+//!
+//! Every call gets its own run directory under `workspace/tmp/python/<run_id>/`; the
+//! snippet runs with the workspace as its working directory, and anything written to
+//! `OUTPUT_DIR` (including matplotlib figures left open at exit) is returned as an
+//! artifact. Wall-clock, CPU and memory limits come from `tools.python`, and `install`
+//! only accepts packages on the configured allowlist. Persistent named sessions live in
+//! [`crate::python_session`].
+
+use async_trait::async_trait;
+use blockcell_core::config::{ExecSandboxConfig, PythonConfig, SandboxBackend};
+use blockcell_core::{Error, Result};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::sandbox::{self, SandboxSpec, SandboxedCommand};
+use crate::{python_session, PromptContext, Tool, ToolContext, ToolSchema};
+
+/// Hard ceiling for a single call, whatever the config or the model asks for.
+const MAX_TIMEOUT_SECS: u64 = 600;
+const INSTALL_TIMEOUT_SECS: u64 = 300;
+/// Output files registered as artifacts per run.
+const MAX_ARTIFACTS: usize = 20;
+
+/// Runs before the user's code: applies resource limits and saves open figures on exit.
+/// Reads its settings from `BLOCKCELL_*` environment variables so the same prelude works
+/// for the venv and docker backends.
+pub(crate) const PRELUDE: &str = r#"import atexit, os, sys
+OUTPUT_DIR = os.environ.get("BLOCKCELL_OUTPUT_DIR", ".")
+os.makedirs(OUTPUT_DIR, exist_ok=True)
+try:
+    import resource
+    _mem = int(os.environ.get("BLOCKCELL_MEMORY_BYTES", "0"))
+    if _mem > 0:
+        resource.setrlimit(resource.RLIMIT_AS, (_mem, _mem))
+    _cpu = int(os.environ.get("BLOCKCELL_CPU_SECS", "0"))
+    if _cpu > 0:
+        resource.setrlimit(resource.RLIMIT_CPU, (_cpu, _cpu))
+except Exception:
+    pass
+
+def _blockcell_save_figures():
+    plt = sys.modules.get("matplotlib.pyplot")
+    if plt is None:
+        return
+    for i, num in enumerate(plt.get_fignums(), 1):
+        try:
+            plt.figure(num).savefig(os.path.join(OUTPUT_DIR, "figure_%d.png" % i), dpi=110, bbox_inches="tight")
+        except Exception as e:
+            print("[figure %d not saved: %s]" % (i, e), file=sys.stderr)
+
+atexit.register(_blockcell_save_figures)
+"#;
+
+/// Executes `cell.py` from the run directory so tracebacks point at the user's lines.
+const RUNNER: &str = r#"
+_cell = os.path.join(os.path.dirname(os.path.abspath(__file__)), "cell.py")
+with open(_cell, encoding="utf-8") as _f:
+    _src = _f.read()
+exec(compile(_src, "cell.py", "exec"), {"__name__": "__main__", "OUTPUT_DIR": OUTPUT_DIR})
+"#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Backend {
+    Venv,
+    Docker,
+}
+
+impl Backend {
+    pub(crate) fn from_config(cfg: &PythonConfig) -> Result<Self> {
+        match cfg.backend.trim().to_ascii_lowercase().as_str() {
+            "" | "venv" => Ok(Backend::Venv),
+            "docker" => Ok(Backend::Docker),
+            other => Err(Error::Tool(format!(
+                "Unknown tools.python.backend '{}'. Use 'venv' or 'docker'",
+                other
+            ))),
+        }
+    }
+}
+
+/// Backend a call from `ctx` runs with, and the sandbox wrapping the venv interpreter.
+pub(crate) fn isolation(ctx: &ToolContext) -> Result<(Backend, SandboxBackend)> {
+    match Backend::from_config(&ctx.config.tools.python)? {
+        Backend::Docker => Ok((Backend::Docker, SandboxBackend::Docker)),
+        Backend::Venv => {
+            let privilege = sandbox::exec_privilege(ctx);
+            match ctx.config.tools.exec.sandbox.backend_for(&privilege) {
+                SandboxBackend::Docker => Ok((Backend::Docker, SandboxBackend::Docker)),
+                other => Ok((Backend::Venv, other)),
+            }
+        }
+    }
+}
+
+/// Directory holding the interpreter state (venv or docker `--target` packages).
+pub(crate) fn python_home(workspace: &Path) -> PathBuf {
+    workspace.join(".python")
+}
+
+fn venv_python(workspace: &Path) -> PathBuf {
+    let venv = python_home(workspace).join("venv");
+    if cfg!(windows) {
+        venv.join("Scripts").join("python.exe")
+    } else {
+        venv.join("bin").join("python")
+    }
+}
+
+fn container_runtime() -> Result<&'static str> {
+    ["docker", "podman"]
+        .into_iter()
+        .find(|bin| which::which(bin).is_ok())
+        .ok_or_else(|| Error::Tool("Neither docker nor podman was found on PATH".into()))
+}
+
+/// Creates the venv on first use. It sees system site-packages so interpreters that
+/// already ship numpy/pandas work without an install step.
+pub(crate) async fn ensure_venv(cfg: &PythonConfig, workspace: &Path) -> Result<PathBuf> {
+    let python = venv_python(workspace);
+    if python.exists() {
+        return Ok(python);
+    }
+    let interpreter = which::which(&cfg.interpreter).map_err(|_| {
+        Error::Tool(format!(
+            "Python interpreter '{}' not found. Install Python 3 or set tools.python.interpreter",
+            cfg.interpreter
+        ))
+    })?;
+    let venv = python_home(workspace).join("venv");
+    std::fs::create_dir_all(python_home(workspace))?;
+    info!(venv = %venv.display(), "🐍 Creating python venv");
+    let output = tokio::process::Command::new(interpreter)
+        .args(["-m", "venv", "--system-site-packages"])
+        .arg(&venv)
+        .output()
+        .await
+        .map_err(|e| Error::Tool(format!("Failed to create venv: {}", e)))?;
+    if !output.status.success() {
+        return Err(Error::Tool(format!(
+            "Failed to create venv: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(python)
+}
+
+fn effective_timeout(cfg: &PythonConfig, requested: Option<u64>) -> u64 {
+    let base = cfg.timeout_secs.clamp(1, MAX_TIMEOUT_SECS);
+    requested.map(|t| t.clamp(1, base)).unwrap_or(base)
+}
+
+/// Keeps the head and the tail of long output; the tail usually holds the traceback.
+pub(crate) fn truncate_output(s: &str, max_chars: usize) -> String {
+    let count = s.chars().count();
+    if count <= max_chars {
+        return s.to_string();
+    }
+    let head_len = max_chars / 3;
+    let tail_len = max_chars - head_len;
+    let head: String = s.chars().take(head_len).collect();
+    let tail: String = s.chars().skip(count - tail_len).collect();
+    format!(
+        "{}\n... [{} characters omitted] ...\n{}",
+        head,
+        count - head_len - tail_len,
+        tail
+    )
+}
+
+/// Lower-cased, `_`→`-` normalized distribution name of a requirement such as
+/// `pandas>=2.0`. Rejects URLs, paths, pip options and anything else pip would treat as
+/// something other than an index lookup.
+pub(crate) fn package_name(spec: &str) -> Result<String> {
+    let spec = spec.trim();
+    let invalid = || Error::Validation(format!("Invalid package spec '{}'", spec));
+    if spec.is_empty() || spec.starts_with('-') || spec.contains(['/', '\\', ':', '@', ' ', ';']) {
+        return Err(invalid());
+    }
+    let end = spec
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.'))
+        .unwrap_or(spec.len());
+    let (name, rest) = spec.split_at(end);
+    if name.is_empty() {
+        return Err(invalid());
+    }
+    let rest_ok = rest.is_empty()
+        || (rest.starts_with(['<', '>', '=', '!', '~', '['])
+            && rest
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "<>=!~.,*[]-_".contains(c)));
+    if !rest_ok {
+        return Err(invalid());
+    }
+    Ok(name.to_ascii_lowercase().replace('_', "-"))
+}
+
+fn check_allowed(cfg: &PythonConfig, specs: &[String]) -> Result<()> {
+    let allowed: Vec<String> = cfg
+        .allowed_packages
+        .iter()
+        .map(|p| p.trim().to_ascii_lowercase().replace('_', "-"))
+        .collect();
+    let denied: Vec<String> = specs
+        .iter()
+        .map(|s| package_name(s))
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .filter(|name| !allowed.contains(name))
+        .collect();
+    if denied.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::Tool(format!(
+            "Packages not in tools.python.allowedPackages: {}",
+            denied.join(", ")
+        )))
+    }
+}
+
+/// Output of one interpreter process.
+pub(crate) struct ProcessOutput {
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub stdout: String,
+    pub stderr: String,
+    pub duration_ms: u128,
+}
+
+/// Runs `cmd` to completion or until `timeout`, killing it on expiry. For containers,
+/// `container` names the container so it can be stopped as well.
+pub(crate) async fn run_with_timeout(
+    mut cmd: tokio::process::Command,
+    timeout: Duration,
+    container: Option<&str>,
+) -> Result<ProcessOutput> {
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let started = Instant::now();
+    let child = cmd
+        .spawn()
+        .map_err(|e| Error::Tool(format!("Failed to start python: {}", e)))?;
+    match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(output) => {
+            let output =
+                output.map_err(|e| Error::Tool(format!("Python execution failed: {}", e)))?;
+            Ok(ProcessOutput {
+                exit_code: output.status.code(),
+                timed_out: false,
+                stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+                duration_ms: started.elapsed().as_millis(),
+            })
+        }
+        Err(_) => {
+            if let (Some(name), Ok(runtime)) = (container, container_runtime()) {
+                let _ = tokio::process::Command::new(runtime)
+                    .args(["kill", name])
+                    .output()
+                    .await;
+            }
+            Ok(ProcessOutput {
+                exit_code: None,
+                timed_out: true,
+                stdout: String::new(),
+                stderr: format!("Execution timed out after {}s", timeout.as_secs()),
+                duration_ms: started.elapsed().as_millis(),
+            })
+        }
+    }
+}
+
+/// Environment shared by both backends. `output_dir` is the path as seen by the
+/// interpreter (host path for venv, `/workspace/...` inside the container).
+fn sandbox_env(cfg: &PythonConfig, timeout_secs: u64, output_dir: &str) -> Vec<(String, String)> {
+    vec![
+        ("BLOCKCELL_OUTPUT_DIR".into(), output_dir.to_string()),
+        (
+            "BLOCKCELL_MEMORY_BYTES".into(),
+            (cfg.memory_mb * 1024 * 1024).to_string(),
+        ),
+        ("BLOCKCELL_CPU_SECS".into(), timeout_secs.to_string()),
+        ("MPLBACKEND".into(), "Agg".into()),
+        ("PYTHONIOENCODING".into(), "utf-8".into()),
+        ("PYTHONDONTWRITEBYTECODE".into(), "1".into()),
+        ("PYTHONUNBUFFERED".into(), "1".into()),
+        ("OPENBLAS_NUM_THREADS".into(), "1".into()),
+        ("OMP_NUM_THREADS".into(), "1".into()),
+    ]
+}
+
+/// Builds the interpreter command for `script` (a file inside the workspace).
+pub(crate) async fn interpreter_command(
+    ctx: &ToolContext,
+    script: &Path,
+    output_dir: &Path,
+    timeout_secs: u64,
+    container_name: &str,
+) -> Result<tokio::process::Command> {
+    let cfg = &ctx.config.tools.python;
+    let workspace = ctx.workspace.as_path();
+    match isolation(ctx)? {
+        (Backend::Venv, backend) => {
+            let python = ensure_venv(cfg, workspace).await?;
+            let sandbox_config = ExecSandboxConfig {
+                network: false,
+                memory_mb: cfg.memory_mb,
+                ..ctx.config.tools.exec.sandbox.clone()
+            };
+            let SandboxedCommand {
+                command: mut cmd, ..
+            } = sandbox::wrap(
+                &SandboxSpec {
+                    backend,
+                    config: &sandbox_config,
+                    privilege: sandbox::exec_privilege(ctx),
+                    workspace,
+                    working_dir: workspace,
+                    restrict_to_workspace: false,
+                    timeout_secs,
+                },
+                &[python.display().to_string(), script.display().to_string()],
+            )?;
+            cmd.env_clear();
+            let bin_dir = python.parent().map(|p| p.display().to_string());
+            let path = match bin_dir {
+                Some(bin) => format!("{}:/usr/local/bin:/usr/bin:/bin", bin),
+                None => "/usr/local/bin:/usr/bin:/bin".to_string(),
+            };
+            cmd.env("PATH", path)
+                .env("HOME", output_dir.parent().unwrap_or(workspace))
+                .envs(sandbox_env(
+                    cfg,
+                    timeout_secs,
+                    &output_dir.display().to_string(),
+                ));
+            Ok(cmd)
+        }
+        (Backend::Docker, _) => {
+            let runtime = container_runtime()?;
+            let to_container = |p: &Path| -> Result<String> {
+                let rel = p.strip_prefix(workspace).map_err(|_| {
+                    Error::Tool(format!("{} is outside the workspace", p.display()))
+                })?;
+                Ok(format!(
+                    "/workspace/{}",
+                    rel.to_string_lossy().replace('\\', "/")
+                ))
+            };
+            let mut cmd = tokio::process::Command::new(runtime);
+            cmd.args([
+                "run",
+                "--rm",
+                "-i",
+                "--network",
+                "none",
+                "--name",
+                container_name,
+            ])
+            .arg(format!("--memory={}m", cfg.memory_mb))
+            .args(["--cpus", "1", "--pids-limit", "256"])
+            .arg("-v")
+            .arg(format!("{}:/workspace", workspace.display()))
+            .args(["-w", "/workspace"])
+            .args(["-e", "PYTHONPATH=/workspace/.python/packages"]);
+            for (k, v) in sandbox_env(cfg, timeout_secs, &to_container(output_dir)?) {
+                cmd.arg("-e").arg(format!("{}={}", k, v));
+            }
+            cmd.arg(&cfg.image).arg("python").arg(to_container(script)?);
+            Ok(cmd)
+        }
+    }
+}
+
+async fn install_packages(ctx: &ToolContext, specs: &[String]) -> Result<Value> {
+    let cfg = &ctx.config.tools.python;
+    let workspace = ctx.workspace.as_path();
+    check_allowed(cfg, specs)?;
+    // Installation is the one step that needs network access, so it is not sandboxed;
+    // only allowlisted packages get this far.
+    let mut cmd = match isolation(ctx)?.0 {
+        Backend::Venv => {
+            let python = ensure_venv(cfg, workspace).await?;
+            let mut cmd = tokio::process::Command::new(python);
+            cmd.args([
+                "-m",
+                "pip",
+                "install",
+                "--disable-pip-version-check",
+                "--no-input",
+            ]);
+            cmd
+        }
+        Backend::Docker => {
+            // Packages land in the workspace so the network-less run containers can
+            // import them.
+            std::fs::create_dir_all(python_home(workspace).join("packages"))?;
+            let mut cmd = tokio::process::Command::new(container_runtime()?);
+            cmd.args(["run", "--rm", "-v"])
+                .arg(format!("{}:/workspace", workspace.display()))
+                .arg(&cfg.image)
+                .args([
+                    "pip",
+                    "install",
+                    "--disable-pip-version-check",
+                    "--no-input",
+                    "--target",
+                    "/workspace/.python/packages",
+                ]);
+            cmd
+        }
+    };
+    cmd.args(specs);
+    info!(packages = ?specs, "🐍 Installing python packages");
+    let out = run_with_timeout(cmd, Duration::from_secs(INSTALL_TIMEOUT_SECS), None).await?;
+    let success = out.exit_code == Some(0);
+    if !success {
+        warn!(packages = ?specs, "Python package installation failed");
+    }
+    Ok(json!({
+        "success": success,
+        "packages": specs,
+        "timed_out": out.timed_out,
+        "output": truncate_output(
+            if success { &out.stdout } else { &out.stderr },
+            cfg.max_output_chars.min(4000)
+        ),
+    }))
+}
+
+/// Files written to `output_dir`, as workspace-relative paths, registered as artifacts.
+pub(crate) fn collect_outputs(ctx: &ToolContext, output_dir: &Path) -> Vec<String> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(output_dir)
+        .map(|rd| {
+            rd.filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.is_file())
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+        .into_iter()
+        .take(MAX_ARTIFACTS)
+        .map(|p| {
+            ctx.register_artifact(&p.display().to_string(), "", None);
+            p.strip_prefix(&ctx.workspace)
+                .unwrap_or(&p)
+                .display()
+                .to_string()
+        })
+        .collect()
+}
+
+async fn run_code(ctx: &ToolContext, code: &str, requested_timeout: Option<u64>) -> Result<Value> {
+    let cfg = &ctx.config.tools.python;
+    let timeout_secs = effective_timeout(cfg, requested_timeout);
+    let run_id = format!(
+        "py_{}",
+        Uuid::new_v4().to_string().split('-').next().unwrap_or("x")
+    );
+    let run_dir = ctx.workspace.join("tmp").join("python").join(&run_id);
+    let output_dir = run_dir.join("output");
+    std::fs::create_dir_all(&output_dir)?;
+    std::fs::write(run_dir.join("cell.py"), code)?;
+    let script = run_dir.join("main.py");
+    std::fs::write(&script, format!("{}{}", PRELUDE, RUNNER))?;
+
+    let container_name = format!("blockcell-{}", run_id.replace('_', "-"));
+    let cmd = interpreter_command(ctx, &script, &output_dir, timeout_secs, &container_name).await?;
+    info!(run_id = %run_id, timeout_secs, "🐍 Running python");
+    let out = run_with_timeout(
+        cmd,
+        Duration::from_secs(timeout_secs),
+        Some(&container_name),
+    )
+    .await?;
+    let files = collect_outputs(ctx, &output_dir);
+
+    Ok(json!({
+        "success": out.exit_code == Some(0),
+        "exit_code": out.exit_code,
+        "timed_out": out.timed_out,
+        "stdout": truncate_output(&out.stdout, cfg.max_output_chars),
+        "stderr": truncate_output(&out.stderr, cfg.max_output_chars),
+        "files": files,
+        "run_dir": run_dir.strip_prefix(&ctx.workspace).unwrap_or(&run_dir).display().to_string(),
+        "duration_ms": out.duration_ms as u64,
+    }))
+}
+
+async fn info(ctx: &ToolContext) -> Result<Value> {
+    let cfg = &ctx.config.tools.python;
+    let (backend, sandbox) = isolation(ctx)?;
+    let version = match backend {
+        Backend::Venv => {
+            let python = venv_python(&ctx.workspace);
+            let bin = if python.exists() {
+                Some(python)
+            } else {
+                which::which(&cfg.interpreter).ok()
+            };
+            match bin {
+                Some(bin) => tokio::process::Command::new(bin)
+                    .arg("--version")
+                    .output()
+                    .await
+                    .ok()
+                    .map(|o| {
+                        let text = if o.stdout.is_empty() {
+                            o.stderr
+                        } else {
+                            o.stdout
+                        };
+                        String::from_utf8_lossy(&text).trim().to_string()
+                    }),
+                None => None,
+            }
+        }
+        Backend::Docker => Some(cfg.image.clone()),
+    };
+    Ok(json!({
+        "backend": match backend { Backend::Venv => "venv", Backend::Docker => "docker" },
+        "sandbox": sandbox.as_str(),
+        "available": version.is_some(),
+        "version": version,
+        "venv_ready": venv_python(&ctx.workspace).exists(),
+        "timeout_secs": effective_timeout(cfg, None),
+        "memory_mb": cfg.memory_mb,
+        "allowed_packages": cfg.allowed_packages,
+    }))
+}
+
+pub struct PythonTool;
+
+#[async_trait]
+impl Tool for PythonTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "python",
+            description: "Run Python code with the workspace as working directory, in a dedicated venv or a network-less container. The venv is isolated by the tools.exec.sandbox backend for the caller's privilege (network off); when that backend is 'none' it runs directly on the host. You MUST provide `action`. action='run': requires `code`, optional `timeout_secs` and `session`; files written to OUTPUT_DIR and open matplotlib figures are returned as artifacts. With `session`, code runs in a persistent named interpreter of this chat so variables survive across calls. action='reset': requires `session`. action='sessions': lists this chat's sessions. action='install': requires `packages` (only allowlisted names). action='info': no extra params.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["run", "reset", "sessions", "install", "info"],
+                        "description": "Action to perform"
+                    },
+                    "code": {
+                        "type": "string",
+                        "description": "(run) Python source. Print results to stdout; save files to OUTPUT_DIR to return them"
+                    },
+                    "timeout_secs": {
+                        "type": "integer",
+                        "description": "(run) Wall-clock limit, capped by tools.python.timeoutSecs"
+                    },
+                    "session": {
+                        "type": "string",
+                        "description": "(run/reset) Persistent session name, e.g. 'sales'. Omit for a one-off run"
+                    },
+                    "packages": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "(install) Requirement specs, e.g. ['pandas', 'scipy>=1.11']"
+                    }
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        match params["action"].as_str().unwrap_or("") {
+            "run" => {
+                if params["code"].as_str().is_none_or(|c| c.trim().is_empty()) {
+                    return Err(Error::Validation("'code' is required for run".into()));
+                }
+                if let Some(name) = params["session"].as_str() {
+                    python_session::validate_session_name(name)?;
+                }
+            }
+            "reset" => {
+                let name = params["session"]
+                    .as_str()
+                    .ok_or_else(|| Error::Validation("'session' is required for reset".into()))?;
+                python_session::validate_session_name(name)?;
+            }
+            "install" => {
+                let packages = params["packages"].as_array().filter(|a| !a.is_empty());
+                let packages = packages.ok_or_else(|| {
+                    Error::Validation("'packages' is required for install".into())
+                })?;
+                for p in packages {
+                    package_name(p.as_str().unwrap_or(""))?;
+                }
+            }
+            "sessions" | "info" => {}
+            other => {
+                return Err(Error::Validation(format!(
+                    "Unknown action '{}'. Use run, reset, sessions, install or info",
+                    other
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    fn prompt_rule(&self, _ctx: &PromptContext) -> Option<String> {
+        Some(
+            "- For calculations, data analysis and plots over workspace files, prefer `python` `run` over `exec`. Save generated files to OUTPUT_DIR; print the values you need to read back. For multi-step analyses pass the same `session` name so loaded data stays in memory instead of re-reading it every call."
+                .to_string(),
+        )
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        match params["action"].as_str().unwrap_or("") {
+            "run" => {
+                let code = params["code"].as_str().unwrap_or("");
+                let timeout = params["timeout_secs"].as_u64();
+                match params["session"].as_str() {
+                    Some(name) => {
+                        let timeout = effective_timeout(&ctx.config.tools.python, timeout);
+                        python_session::run_cell(&ctx, name, code, timeout).await
+                    }
+                    None => run_code(&ctx, code, timeout).await,
+                }
+            }
+            "reset" => {
+                let name = params["session"].as_str().unwrap_or("");
+                let existed = python_session::reset(&ctx.session_key, name).await;
+                Ok(json!({"session": name, "status": if existed { "reset" } else { "not_found" }}))
+            }
+            "sessions" => Ok(json!({"sessions": python_session::list(&ctx.session_key).await})),
+            "install" => {
+                let specs: Vec<String> = params["packages"]
+                    .as_array()
+                    .map(|a| {
+                        a.iter()
+                            .filter_map(|v| v.as_str())
+                            .map(|s| s.trim().to_string())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                install_packages(&ctx, &specs).await
+            }
+            "info" => info(&ctx).await,
+            other => Err(Error::Tool(format!("Unknown action: {}", other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let tool = PythonTool;
+        assert!(tool
+            .validate(&json!({"action": "run", "code": "print(1)"}))
+            .is_ok());
+        assert!(tool.validate(&json!({"action": "run"})).is_err());
+        assert!(tool
+            .validate(&json!({"action": "install", "packages": ["pandas>=2"]}))
+            .is_ok());
+        assert!(tool
+            .validate(&json!({"action": "install", "packages": []}))
+            .is_err());
+        assert!(tool
+            .validate(&json!({"action": "run", "code": "x = 1", "session": "eda"}))
+            .is_ok());
+        assert!(tool
+            .validate(&json!({"action": "run", "code": "x = 1", "session": "a/b"}))
+            .is_err());
+        assert!(tool.validate(&json!({"action": "reset"})).is_err());
+        assert!(tool.validate(&json!({"action": "shell"})).is_err());
+    }
+
+    #[test]
+    fn test_package_allowlist() {
+        assert_eq!(package_name("Scikit_Learn>=1.3").unwrap(), "scikit-learn");
+        assert_eq!(package_name("pandas[excel]").unwrap(), "pandas");
+        for bad in [
+            "-r requirements.txt",
+            "git+https://example.com/x.git",
+            "./local",
+            "pkg @ file:///tmp/x",
+            "numpy; os_name",
+        ] {
+            assert!(package_name(bad).is_err(), "{}", bad);
+        }
+        let cfg = PythonConfig::default();
+        assert!(check_allowed(&cfg, &["numpy".into(), "scikit_learn".into()]).is_ok());
+        assert!(check_allowed(&cfg, &["requests".into()]).is_err());
+    }
+
+    #[test]
+    fn test_venv_runs_under_exec_sandbox_per_privilege() {
+        let mut config = blockcell_core::Config::default();
+        config.tools.exec.sandbox.full = SandboxBackend::Namespaces;
+        config.tools.exec.sandbox.limited = SandboxBackend::Docker;
+        let mut ctx = ToolContext {
+            workspace: std::env::temp_dir(),
+            builtin_skills_dir: None,
+            active_skill_dir: None,
+            session_key: "cli:test".to_string(),
+            channel: "cli".to_string(),
+            account_id: None,
+            sender_id: None,
+            chat_id: "chat-1".to_string(),
+            config,
+            permissions: blockcell_core::types::PermissionSet::new(),
+            task_manager: None,
+            memory_store: None,
+            outbound_tx: None,
+            spawn_handle: None,
+            capability_registry: None,
+            core_evolution: None,
+            event_emitter: None,
+            channel_contacts_file: None,
+            response_cache: None,
+            artifacts: None,
+        };
+        assert_eq!(
+            isolation(&ctx).unwrap(),
+            (Backend::Venv, SandboxBackend::Namespaces)
+        );
+        // Unattended runs are capped at `limited`, whose docker choice moves the run
+        // into a container.
+        ctx.channel = "cron".to_string();
+        assert_eq!(
+            isolation(&ctx).unwrap(),
+            (Backend::Docker, SandboxBackend::Docker)
+        );
+        ctx.config.tools.exec.sandbox = Default::default();
+        assert_eq!(
+            isolation(&ctx).unwrap(),
+            (Backend::Venv, SandboxBackend::None)
+        );
+    }
+
+    #[test]
+    fn test_timeout_and_truncation() {
+        let cfg = PythonConfig {
+            timeout_secs: 30,
+            ..Default::default()
+        };
+        assert_eq!(effective_timeout(&cfg, None), 30);
+        assert_eq!(effective_timeout(&cfg, Some(5)), 5);
+        assert_eq!(effective_timeout(&cfg, Some(999)), 30);
+
+        let long = format!("{}Traceback: boom", "x".repeat(500));
+        let cut = truncate_output(&long, 60);
+        assert!(cut.ends_with("Traceback: boom"));
+        assert!(cut.contains("characters omitted"));
+        assert_eq!(truncate_output("short", 60), "short");
+    }
+}
@@ -0,0 +1,432 @@
+//! Persistent compute sessions for the `python` tool.
+//!
+//! A session is a long-lived interpreter ("kernel") that executes cells in one shared
+//! namespace, so variables and loaded datasets survive between calls. Sessions are keyed
+//! by the calling chat/task plus a name, capped by `tools.python.maxSessions`, and stopped
+//! after `sessionIdleSecs` without use or on an explicit reset.
+
+use blockcell_core::config::PythonConfig;
+use blockcell_core::{Error, Result};
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::python::{
+    collect_outputs, interpreter_command, isolation, truncate_output, Backend, PRELUDE,
+};
+use crate::ToolContext;
+
+/// Prefix of the line a kernel writes after each cell; anything else on its stdout is
+/// output that bypassed the cell's redirection.
+const RESULT_MARK: &str = "\u{1e}BLOCKCELL_RESULT\u{1e}";
+const MAX_NAME_LEN: usize = 40;
+
+/// Reads one JSON request per stdin line and answers with one marked JSON line.
+const KERNEL: &str = r#"
+import contextlib, io, json, traceback, types
+_MARK = "\x1eBLOCKCELL_RESULT\x1e"
+_ns = {"__name__": "__main__"}
+_proto = sys.stdout
+for _line in sys.stdin:
+    try:
+        _req = json.loads(_line)
+    except ValueError:
+        continue
+    OUTPUT_DIR = _req["output_dir"]
+    os.makedirs(OUTPUT_DIR, exist_ok=True)
+    _ns["OUTPUT_DIR"] = OUTPUT_DIR
+    _out, _err, _ok = io.StringIO(), io.StringIO(), True
+    with contextlib.redirect_stdout(_out), contextlib.redirect_stderr(_err):
+        try:
+            exec(compile(_req["code"], "cell_%d.py" % _req["id"], "exec"), _ns)
+        except BaseException:
+            _ok = False
+            traceback.print_exc()
+        _blockcell_save_figures()
+        _plt = sys.modules.get("matplotlib.pyplot")
+        if _plt is not None:
+            _plt.close("all")
+    _vars = sorted(k for k, v in _ns.items()
+                   if not k.startswith("_") and k != "OUTPUT_DIR" and not isinstance(v, types.ModuleType))
+    _proto.write(_MARK + json.dumps({"ok": _ok, "stdout": _out.getvalue(), "stderr": _err.getvalue(),
+                                     "variables": _vars[:100]}) + "\n")
+    _proto.flush()
+"#;
+
+#[derive(Debug, Deserialize)]
+struct CellResult {
+    ok: bool,
+    #[serde(default)]
+    stdout: String,
+    #[serde(default)]
+    stderr: String,
+    #[serde(default)]
+    variables: Vec<String>,
+}
+
+struct Kernel {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+    container: Option<String>,
+}
+
+impl Kernel {
+    async fn shutdown(&mut self) {
+        let _ = self.child.start_kill();
+        if let Some(name) = &self.container {
+            for runtime in ["docker", "podman"] {
+                if which::which(runtime).is_ok() {
+                    let _ = tokio::process::Command::new(runtime)
+                        .args(["kill", name])
+                        .output()
+                        .await;
+                    break;
+                }
+            }
+        }
+    }
+}
+
+struct SessionStats {
+    created_at: i64,
+    last_used: Instant,
+    cells: u64,
+    busy: bool,
+}
+
+#[derive(Clone)]
+struct SessionEntry {
+    scope: String,
+    name: String,
+    /// Workspace-relative directory holding the kernel script and per-cell outputs.
+    dir: String,
+    kernel: Arc<Mutex<Kernel>>,
+    stats: Arc<std::sync::Mutex<SessionStats>>,
+}
+
+impl SessionEntry {
+    fn idle_for(&self) -> Option<Duration> {
+        let stats = self.stats.lock().ok()?;
+        (!stats.busy).then(|| stats.last_used.elapsed())
+    }
+}
+
+static SESSIONS: Lazy<Mutex<HashMap<String, SessionEntry>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub(crate) fn validate_session_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || name.len() > MAX_NAME_LEN
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(Error::Validation(format!(
+            "Invalid session name '{}'. Use up to {} letters, digits, '-' or '_'",
+            name, MAX_NAME_LEN
+        )));
+    }
+    Ok(())
+}
+
+fn session_key(scope: &str, name: &str) -> String {
+    format!("{}\u{0}{}", scope, name)
+}
+
+/// Workspace-relative directory holding a session's kernel script and cell outputs.
+fn session_dir(scope: &str, name: &str) -> String {
+    let sanitize = |s: &str| -> String {
+        s.chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect()
+    };
+    format!("tmp/python/sessions/{}-{}", sanitize(scope), sanitize(name))
+}
+
+async fn start_kernel(ctx: &ToolContext, scope: &str, name: &str) -> Result<SessionEntry> {
+    let dir = session_dir(scope, name);
+    let abs_dir = ctx.workspace.join(&dir);
+    std::fs::create_dir_all(&abs_dir)?;
+    let script = abs_dir.join("kernel.py");
+    std::fs::write(&script, format!("{}{}", PRELUDE, KERNEL))?;
+
+    let container = format!(
+        "blockcell-pysess-{}",
+        Uuid::new_v4().to_string().split('-').next().unwrap_or("x")
+    );
+    // CPU time accumulates over the kernel's lifetime, so sessions rely on the per-cell
+    // wall-clock limit and the memory cap instead of RLIMIT_CPU.
+    let mut cmd = interpreter_command(ctx, &script, &abs_dir, 0, &container).await?;
+    let container = (isolation(ctx)?.0 == Backend::Docker).then_some(container);
+    cmd.stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true);
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| Error::Tool(format!("Failed to start python session: {}", e)))?;
+    let stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| Error::Tool("Python session has no stdin".into()))?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| Error::Tool("Python session has no stdout".into()))?;
+    info!(scope = %scope, session = %name, "🐍 Started python session");
+
+    Ok(SessionEntry {
+        scope: scope.to_string(),
+        name: name.to_string(),
+        dir,
+        kernel: Arc::new(Mutex::new(Kernel {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout).lines(),
+            container,
+        })),
+        stats: Arc::new(std::sync::Mutex::new(SessionStats {
+            created_at: Utc::now().timestamp_millis(),
+            last_used: Instant::now(),
+            cells: 0,
+            busy: false,
+        })),
+    })
+}
+
+async fn stop(entry: SessionEntry) {
+    let mut kernel = entry.kernel.lock().await;
+    kernel.shutdown().await;
+    info!(scope = %entry.scope, session = %entry.name, "🐍 Stopped python session");
+}
+
+/// Stops sessions idle for longer than `sessionIdleSecs`.
+async fn reap_idle(cfg: &PythonConfig) {
+    let limit = Duration::from_secs(cfg.session_idle_secs.max(60));
+    let expired: Vec<SessionEntry> = {
+        let mut sessions = SESSIONS.lock().await;
+        let keys: Vec<String> = sessions
+            .iter()
+            .filter(|(_, e)| e.idle_for().is_some_and(|idle| idle > limit))
+            .map(|(k, _)| k.clone())
+            .collect();
+        keys.iter().filter_map(|k| sessions.remove(k)).collect()
+    };
+    for entry in expired {
+        stop(entry).await;
+    }
+}
+
+/// Returns the named session, starting it (and evicting the least recently used idle
+/// session when at capacity) if needed. The flag is true for a fresh session.
+async fn get_or_start(ctx: &ToolContext, scope: &str, name: &str) -> Result<(SessionEntry, bool)> {
+    let cfg = &ctx.config.tools.python;
+    let key = session_key(scope, name);
+    let mut sessions = SESSIONS.lock().await;
+    if let Some(entry) = sessions.get(&key) {
+        return Ok((entry.clone(), false));
+    }
+    let max = cfg.max_sessions.max(1);
+    let mut evicted = Vec::new();
+    while sessions.len() >= max {
+        let lru = sessions
+            .iter()
+            .filter_map(|(k, e)| e.idle_for().map(|idle| (k.clone(), idle)))
+            .max_by_key(|(_, idle)| *idle)
+            .map(|(k, _)| k);
+        match lru.and_then(|k| sessions.remove(&k)) {
+            Some(entry) => evicted.push(entry),
+            None => {
+                return Err(Error::Tool(format!(
+                    "All {} python sessions are busy; try again later",
+                    max
+                )))
+            }
+        }
+    }
+    let entry = start_kernel(ctx, scope, name).await?;
+    sessions.insert(key, entry.clone());
+    drop(sessions);
+    for old in evicted {
+        warn!(scope = %old.scope, session = %old.name, "Evicting python session (maxSessions reached)");
+        stop(old).await;
+    }
+    Ok((entry, true))
+}
+
+async fn remove(scope: &str, name: &str) -> Option<SessionEntry> {
+    SESSIONS.lock().await.remove(&session_key(scope, name))
+}
+
+/// Runs `code` in the named session of `ctx.session_key`.
+pub(crate) async fn run_cell(
+    ctx: &ToolContext,
+    name: &str,
+    code: &str,
+    timeout_secs: u64,
+) -> Result<Value> {
+    validate_session_name(name)?;
+    let cfg = &ctx.config.tools.python;
+    reap_idle(cfg).await;
+    let scope = ctx.session_key.as_str();
+    let (entry, started) = get_or_start(ctx, scope, name).await?;
+
+    let mut kernel = entry.kernel.lock().await;
+    let cell = {
+        let mut stats = entry
+            .stats
+            .lock()
+            .map_err(|e| Error::Tool(format!("Lock error: {}", e)))?;
+        stats.busy = true;
+        stats.cells += 1;
+        stats.cells
+    };
+    let output_dir = format!("{}/cell_{}", entry.dir, cell);
+    let request = json!({"id": cell, "code": code, "output_dir": output_dir});
+    let started_at = Instant::now();
+
+    let exchange = async {
+        kernel
+            .stdin
+            .write_all(format!("{}\n", request).as_bytes())
+            .await?;
+        kernel.stdin.flush().await?;
+        let mut stray = String::new();
+        while let Some(line) = kernel.stdout.next_line().await? {
+            match line.strip_prefix(RESULT_MARK) {
+                Some(payload) => {
+                    return Ok::<_, std::io::Error>(Some((payload.to_string(), stray)))
+                }
+                None => {
+                    stray.push_str(&line);
+                    stray.push('\n');
+                }
+            }
+        }
+        Ok(None)
+    };
+    let outcome = tokio::time::timeout(Duration::from_secs(timeout_secs), exchange).await;
+    let timed_out = outcome.is_err();
+
+    if let Ok(mut stats) = entry.stats.lock() {
+        stats.busy = false;
+        stats.last_used = Instant::now();
+    }
+    let duration_ms = started_at.elapsed().as_millis() as u64;
+
+    let (payload, stray) = match outcome {
+        Ok(Ok(Some(found))) => found,
+        _ => {
+            // Timed out, or the kernel died (most often the memory cap): the namespace is
+            // gone either way, so drop the session and say so.
+            kernel.shutdown().await;
+            drop(kernel);
+            remove(scope, name).await;
+            return Ok(json!({
+                "success": false,
+                "session": name,
+                "cell": cell,
+                "timed_out": timed_out,
+                "session_reset": true,
+                "stderr": if timed_out {
+                    format!("Execution timed out after {}s; session '{}' was stopped and its variables are lost", timeout_secs, name)
+                } else {
+                    format!("Session '{}' exited unexpectedly (memory limit is {} MB); its variables are lost", name, cfg.memory_mb)
+                },
+                "duration_ms": duration_ms,
+            }));
+        }
+    };
+    drop(kernel);
+
+    let result: CellResult = serde_json::from_str(&payload)
+        .map_err(|e| Error::Tool(format!("Malformed python session reply: {}", e)))?;
+    let files = collect_outputs(ctx, &ctx.workspace.join(&output_dir));
+    Ok(json!({
+        "success": result.ok,
+        "session": name,
+        "cell": cell,
+        "new_session": started,
+        "timed_out": false,
+        "stdout": truncate_output(&format!("{}{}", stray, result.stdout), cfg.max_output_chars),
+        "stderr": truncate_output(&result.stderr, cfg.max_output_chars),
+        "variables": result.variables,
+        "files": files,
+        "duration_ms": duration_ms,
+    }))
+}
+
+/// Stops the named session of `scope`. Returns false when it did not exist.
+pub(crate) async fn reset(scope: &str, name: &str) -> bool {
+    match remove(scope, name).await {
+        Some(entry) => {
+            stop(entry).await;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Sessions belonging to `scope`.
+pub(crate) async fn list(scope: &str) -> Vec<Value> {
+    let sessions = SESSIONS.lock().await;
+    let mut out: Vec<Value> = sessions
+        .values()
+        .filter(|e| e.scope == scope)
+        .filter_map(|e| {
+            let stats = e.stats.lock().ok()?;
+            Some(json!({
+                "session": e.name,
+                "cells": stats.cells,
+                "busy": stats.busy,
+                "created_at": stats.created_at,
+                "idle_secs": stats.last_used.elapsed().as_secs(),
+                "dir": e.dir,
+            }))
+        })
+        .collect();
+    out.sort_by(|a, b| a["session"].as_str().cmp(&b["session"].as_str()));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_names() {
+        assert!(validate_session_name("sales-2026_q1").is_ok());
+        assert!(validate_session_name("").is_err());
+        assert!(validate_session_name("../etc").is_err());
+        assert!(validate_session_name(&"a".repeat(MAX_NAME_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_sessions_are_scoped_per_chat() {
+        assert_ne!(
+            session_key("telegram:1", "main"),
+            session_key("telegram:2", "main")
+        );
+        assert_eq!(
+            session_dir("telegram:1", "main"),
+            "tmp/python/sessions/telegram_1-main"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reset_unknown_session() {
+        assert!(!reset("cli:direct", "missing").await);
+        assert!(list("cli:direct").await.is_empty());
+    }
+}
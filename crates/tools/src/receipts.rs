@@ -0,0 +1,459 @@
+use async_trait::async_trait;
+use blockcell_core::{Error, Result};
+use blockcell_storage::receipts::{db_path, Receipt, ReceiptRecord, ReceiptStore};
+use chrono::NaiveDate;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use crate::image_understand::{call_auto, encode_image};
+use crate::{PromptContext, Tool, ToolContext, ToolSchema};
+
+/// PDF text beyond this is not sent for extraction.
+const MAX_PDF_CHARS: usize = 20_000;
+
+const EXTRACT_PROMPT: &str = "You are extracting an accounting record from a receipt or invoice. \
+Reply with ONLY a JSON object, no prose, with these keys: \
+\"vendor\" (merchant or issuer name), \"date\" (issue date as YYYY-MM-DD), \
+\"currency\" (ISO 4217 code such as USD, EUR, CNY), \"subtotal\" (number or null), \
+\"tax\" (total tax/VAT as a number or null), \"total\" (amount paid, number), \
+\"category\" (one of: meals, travel, lodging, office, software, utilities, groceries, other), \
+\"invoice_number\" (string or null), \
+\"line_items\" (array of {\"description\", \"quantity\", \"unit_price\", \"amount\"}). \
+Use plain numbers without currency symbols or thousands separators.";
+
+fn resolve_path(path: &str, workspace: &Path) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir().unwrap_or_default().join(rest),
+        None if Path::new(path).is_absolute() => PathBuf::from(path),
+        None => workspace.join(path),
+    }
+}
+
+/// Accept the common date spellings models and receipts use and return `YYYY-MM-DD`.
+fn normalize_date(value: &str) -> Option<String> {
+    let value = value.trim();
+    [
+        "%Y-%m-%d",
+        "%Y/%m/%d",
+        "%Y.%m.%d",
+        "%d.%m.%Y",
+        "%d/%m/%Y",
+        "%Y年%m月%d日",
+    ]
+    .iter()
+    .find_map(|f| NaiveDate::parse_from_str(value, f).ok())
+    .or_else(|| {
+        chrono::DateTime::parse_from_rfc3339(value)
+            .ok()
+            .map(|d| d.date_naive())
+    })
+    .map(|d| d.format("%Y-%m-%d").to_string())
+}
+
+/// Parse the model's reply (possibly wrapped in a code fence) into a normalized record.
+fn parse_record(reply: &str) -> Result<ReceiptRecord> {
+    let (start, end) = match (reply.find('{'), reply.rfind('}')) {
+        (Some(s), Some(e)) if e > s => (s, e),
+        _ => {
+            return Err(Error::Tool(
+                "Extraction did not return a JSON object".to_string(),
+            ))
+        }
+    };
+    let raw: Value = serde_json::from_str(&reply[start..=end])
+        .map_err(|e| Error::Tool(format!("Extraction returned invalid JSON: {}", e)))?;
+    record_from_value(&raw)
+}
+
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => {
+            // "8,10" is a decimal comma; "1,234.50" uses it as a thousands separator.
+            let s = if s.contains(',') && !s.contains('.') {
+                s.replace(',', ".")
+            } else {
+                s.replace(',', "")
+            };
+            s.chars()
+                .filter(|c| c.is_ascii_digit() || *c == '.' || *c == '-')
+                .collect::<String>()
+                .parse()
+                .ok()
+        }
+        _ => None,
+    }
+}
+
+/// Build a record from loosely typed JSON (model output or tool params).
+fn record_from_value(raw: &Value) -> Result<ReceiptRecord> {
+    let text = |key: &str| {
+        raw.get(key)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+    let date = text("date").unwrap_or_default();
+    let record = ReceiptRecord {
+        vendor: text("vendor").unwrap_or_default(),
+        date: normalize_date(&date).unwrap_or(date),
+        currency: text("currency").unwrap_or_default().to_ascii_uppercase(),
+        subtotal: raw.get("subtotal").and_then(number),
+        tax: raw.get("tax").and_then(number),
+        total: raw
+            .get("total")
+            .and_then(number)
+            .ok_or_else(|| Error::Validation("Receipt has no total".to_string()))?,
+        category: text("category").map(|c| c.to_lowercase()),
+        invoice_number: text("invoice_number"),
+        line_items: raw
+            .get("line_items")
+            .and_then(|v| v.as_array())
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| {
+                        Some(blockcell_storage::receipts::LineItem {
+                            description: item.get("description")?.as_str()?.trim().to_string(),
+                            quantity: item.get("quantity").and_then(number),
+                            unit_price: item.get("unit_price").and_then(number),
+                            amount: item.get("amount").and_then(number),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+    Ok(record)
+}
+
+/// Monthly CSV, one row per receipt; line items are summarized in one column.
+fn export_csv(receipts: &[Receipt], path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut writer = csv::Writer::from_path(path)
+        .map_err(|e| Error::Tool(format!("Failed to create CSV: {}", e)))?;
+    let amount = |v: Option<f64>| v.map(|n| format!("{:.2}", n)).unwrap_or_default();
+    let mut rows: Vec<Vec<String>> = vec![[
+        "date",
+        "vendor",
+        "category",
+        "invoice_number",
+        "currency",
+        "subtotal",
+        "tax",
+        "total",
+        "items",
+        "source",
+        "id",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()];
+    for r in receipts {
+        let rec = &r.record;
+        rows.push(vec![
+            rec.date.clone(),
+            rec.vendor.clone(),
+            rec.category.clone().unwrap_or_default(),
+            rec.invoice_number.clone().unwrap_or_default(),
+            rec.currency.clone(),
+            amount(rec.subtotal),
+            amount(rec.tax),
+            format!("{:.2}", rec.total),
+            rec.line_items
+                .iter()
+                .map(|i| i.description.clone())
+                .collect::<Vec<_>>()
+                .join("; "),
+            r.source_path.clone().unwrap_or_default(),
+            r.id.clone(),
+        ]);
+    }
+    for row in rows {
+        writer
+            .write_record(&row)
+            .map_err(|e| Error::Tool(format!("Failed to write CSV: {}", e)))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn totals_by_currency(receipts: &[Receipt]) -> BTreeMap<String, Value> {
+    let mut totals: BTreeMap<String, (f64, f64)> = BTreeMap::new();
+    for r in receipts {
+        let entry = totals.entry(r.record.currency.clone()).or_default();
+        entry.0 += r.record.total;
+        entry.1 += r.record.tax.unwrap_or(0.0);
+    }
+    totals
+        .into_iter()
+        .map(|(currency, (total, tax))| {
+            let round = |v: f64| (v * 100.0).round() / 100.0;
+            (currency, json!({"total": round(total), "tax": round(tax)}))
+        })
+        .collect()
+}
+
+fn month_param(params: &Value) -> Result<Option<String>> {
+    match params.get("month").and_then(|v| v.as_str()).map(str::trim) {
+        Some(m) if !m.is_empty() => {
+            if NaiveDate::parse_from_str(&format!("{}-01", m), "%Y-%m-%d").is_err() {
+                return Err(Error::Validation(format!(
+                    "Invalid month '{}'. Use YYYY-MM",
+                    m
+                )));
+            }
+            Ok(Some(m.to_string()))
+        }
+        _ => Ok(None),
+    }
+}
+
+pub struct ReceiptsTool;
+
+#[async_trait]
+impl Tool for ReceiptsTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "receipts",
+            description: "Receipt and invoice ledger for expense accounting. \
+                Actions: 'extract' (path to a receipt photo or PDF invoice: runs vision/text extraction into \
+                vendor, date, currency, subtotal, tax, total, line items and saves it unless save=false), \
+                'add' (save a record you already have: vendor, date, total, ...), 'list' (optional month YYYY-MM), \
+                'remove' (id), 'export' (month YYYY-MM: writes a CSV for accounting).",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["extract", "add", "list", "remove", "export"],
+                        "description": "Action to perform"
+                    },
+                    "path": {"type": "string", "description": "(extract) Receipt image (jpg/png/webp) or PDF invoice, workspace-relative or absolute"},
+                    "save": {"type": "boolean", "description": "(extract) Store the record in the ledger. Default: true"},
+                    "id": {"type": "string", "description": "(remove) Receipt id"},
+                    "month": {"type": "string", "description": "(list/export) Month as YYYY-MM"},
+                    "vendor": {"type": "string", "description": "(add) Merchant or issuer"},
+                    "date": {"type": "string", "description": "(add) Issue date YYYY-MM-DD"},
+                    "currency": {"type": "string", "description": "(add) ISO 4217 code"},
+                    "subtotal": {"type": "number", "description": "(add) Amount before tax"},
+                    "tax": {"type": "number", "description": "(add) Tax/VAT amount"},
+                    "total": {"type": "number", "description": "(add) Amount paid"},
+                    "category": {"type": "string", "description": "(add/extract) Expense category; overrides the extracted one"},
+                    "invoice_number": {"type": "string", "description": "(add) Invoice or receipt number"},
+                    "line_items": {
+                        "type": "array",
+                        "items": {"type": "object"},
+                        "description": "(add) Items as {description, quantity, unit_price, amount}"
+                    }
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        let action = params.get("action").and_then(|v| v.as_str()).unwrap_or("");
+        let has = |key: &str| {
+            params
+                .get(key)
+                .and_then(|v| v.as_str())
+                .is_some_and(|s| !s.trim().is_empty())
+        };
+        match action {
+            "extract" => {
+                if !has("path") {
+                    return Err(Error::Validation("'path' is required for extract".into()));
+                }
+            }
+            "add" => {
+                if !has("vendor") || !has("date") || params.get("total").is_none() {
+                    return Err(Error::Validation(
+                        "'vendor', 'date' and 'total' are required for add".into(),
+                    ));
+                }
+            }
+            "remove" => {
+                if !has("id") {
+                    return Err(Error::Validation("'id' is required for remove".into()));
+                }
+            }
+            "export" => {
+                if !has("month") {
+                    return Err(Error::Validation("'month' is required for export".into()));
+                }
+            }
+            "list" => {}
+            _ => return Err(Error::Validation(format!("Unknown action: {}", action))),
+        }
+        Ok(())
+    }
+
+    fn prompt_rule(&self, _ctx: &PromptContext) -> Option<String> {
+        Some(
+            "- When the user sends a receipt or invoice (photo or PDF) to record an expense, use `receipts` `extract` instead of image_understand/ocr; monthly expense reports come from `receipts` `export`."
+                .to_string(),
+        )
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        let action = params["action"].as_str().unwrap_or("").to_string();
+        let store = ReceiptStore::open(&db_path(&ctx.workspace))?;
+        match action.as_str() {
+            "extract" => {
+                let raw_path = params["path"].as_str().unwrap_or("").trim();
+                let path = resolve_path(raw_path, &ctx.workspace);
+                if !path.is_file() {
+                    return Err(Error::NotFound(format!("File not found: {}", raw_path)));
+                }
+                let is_pdf = path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| e.eq_ignore_ascii_case("pdf"));
+                let reply = if is_pdf {
+                    let pdf = path.clone();
+                    let text = tokio::task::spawn_blocking(move || pdf_extract::extract_text(&pdf))
+                        .await
+                        .map_err(|e| Error::Tool(format!("PDF task failed: {}", e)))?
+                        .map_err(|e| Error::Tool(format!("Failed to extract PDF text: {}", e)))?;
+                    if text.trim().is_empty() {
+                        return Err(Error::Tool(
+                            "PDF has no text layer; convert the page to an image and extract that instead"
+                                .to_string(),
+                        ));
+                    }
+                    let text: String = text.chars().take(MAX_PDF_CHARS).collect();
+                    let prompt = format!("{}\n\nDocument text:\n{}", EXTRACT_PROMPT, text);
+                    match call_auto(&ctx, &prompt, &[], &params, 2048).await {
+                        Ok((reply, _, _)) => reply,
+                        // No model available: hand the text back so the agent can `add` it.
+                        Err(e) => {
+                            return Ok(json!({
+                                "status": "needs_review",
+                                "reason": e.to_string(),
+                                "text": text,
+                                "hint": "Read the text and call receipts add with vendor, date, total, tax and line_items."
+                            }))
+                        }
+                    }
+                } else {
+                    let image = encode_image(&path.display().to_string())?;
+                    call_auto(&ctx, EXTRACT_PROMPT, &[image], &params, 2048)
+                        .await?
+                        .0
+                };
+                let mut record = parse_record(&reply)?;
+                if let Some(category) = params.get("category").and_then(|v| v.as_str()) {
+                    record.category = Some(category.trim().to_lowercase());
+                }
+                info!(vendor = %record.vendor, total = record.total, pdf = is_pdf, "Receipt extracted");
+                if !params.get("save").and_then(|v| v.as_bool()).unwrap_or(true) {
+                    return Ok(json!({"status": "extracted", "record": record}));
+                }
+                let receipt = store.insert(record, Some(path.display().to_string()))?;
+                Ok(json!({"status": "saved", "receipt": receipt}))
+            }
+            "add" => {
+                let record = record_from_value(&params)?;
+                let receipt = store.insert(record, None)?;
+                Ok(json!({"status": "saved", "receipt": receipt}))
+            }
+            "list" => {
+                let month = month_param(&params)?;
+                let receipts = store.list(month.as_deref(), 500)?;
+                Ok(json!({
+                    "receipts": receipts,
+                    "count": receipts.len(),
+                    "totals": totals_by_currency(&receipts),
+                }))
+            }
+            "remove" => {
+                let id = params["id"].as_str().unwrap_or("").trim();
+                if !store.remove(id)? {
+                    return Err(Error::NotFound(format!("Receipt not found: {}", id)));
+                }
+                Ok(json!({"status": "removed", "id": id}))
+            }
+            "export" => {
+                let month = month_param(&params)?
+                    .ok_or_else(|| Error::Validation("'month' is required for export".into()))?;
+                let receipts = store.list(Some(&month), 10_000)?;
+                let path = ctx
+                    .workspace
+                    .join("receipts")
+                    .join(format!("receipts-{}.csv", month));
+                export_csv(&receipts, &path)?;
+                ctx.register_artifact(
+                    &path.display().to_string(),
+                    "data",
+                    Some(&format!("Receipts {}", month)),
+                );
+                Ok(json!({
+                    "status": "exported",
+                    "path": path.display().to_string(),
+                    "count": receipts.len(),
+                    "totals": totals_by_currency(&receipts),
+                }))
+            }
+            _ => Err(Error::Tool(format!("Unknown action: {}", action))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_record_normalizes_model_reply() {
+        let reply = "```json\n{\"vendor\": \"Blue Bottle\", \"date\": \"14.03.2026\", \"currency\": \"eur\", \
+            \"subtotal\": \"8,10\", \"tax\": 0.9, \"total\": \"€9.00\", \"category\": \"Meals\", \
+            \"line_items\": [{\"description\": \"Latte\", \"quantity\": 2, \"amount\": 9}]}\n```";
+        let record = parse_record(reply).unwrap();
+        assert_eq!(record.vendor, "Blue Bottle");
+        assert_eq!(record.date, "2026-03-14");
+        assert_eq!(record.currency, "EUR");
+        assert_eq!(record.total, 9.0);
+        assert_eq!(record.subtotal, Some(8.1));
+        assert_eq!(record.category.as_deref(), Some("meals"));
+        assert_eq!(record.line_items.len(), 1);
+        assert!(parse_record("I could not read this receipt").is_err());
+    }
+
+    #[test]
+    fn test_export_csv() {
+        let store = ReceiptStore::open_in_memory().unwrap();
+        let record = record_from_value(&json!({
+            "vendor": "Hotel, Berlin", "date": "2026-03-02", "currency": "EUR", "total": 120, "tax": 7.85
+        }))
+        .unwrap();
+        store.insert(record, Some("inv.pdf".to_string())).unwrap();
+        let receipts = store.list(Some("2026-03"), 10).unwrap();
+        let path =
+            std::env::temp_dir().join(format!("blockcell_receipts_{}.csv", uuid::Uuid::new_v4()));
+        export_csv(&receipts, &path).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert!(csv.starts_with("date,vendor,category"));
+        assert!(csv.contains("2026-03-02,\"Hotel, Berlin\",,,EUR,,7.85,120.00,,inv.pdf,"));
+        assert_eq!(totals_by_currency(&receipts)["EUR"]["tax"], json!(7.85));
+    }
+
+    #[test]
+    fn test_validate() {
+        let tool = ReceiptsTool;
+        assert!(tool.validate(&json!({"action": "extract"})).is_err());
+        assert!(tool
+            .validate(
+                &json!({"action": "add", "vendor": "Cafe", "date": "2026-03-01", "total": 3.5})
+            )
+            .is_ok());
+        assert!(tool.validate(&json!({"action": "export"})).is_err());
+        assert!(month_param(&json!({"month": "2026-13"})).is_err());
+    }
+}
@@ -1,47 +1,78 @@
+use async_trait::async_trait;
+use blockcell_core::Config;
 use blockcell_core::{Error, Result};
 use serde_json::{json, Value};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, OnceLock};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tracing::{debug, warn};
 
 use crate::agent_status::AgentStatusTool;
 use crate::alert_rule::AlertRuleTool;
 use crate::app_control::AppControlTool;
 use crate::audio_transcribe::AudioTranscribeTool;
+use crate::backtest::BacktestTool;
+use crate::birthdays::BirthdaysTool;
+use crate::bookmarks::BookmarksTool;
 use crate::browser::BrowseTool;
+use crate::calc::CalcTool;
+use crate::calendar::CalendarTool;
 use crate::camera::CameraCaptureTool;
 use crate::chart_generate::ChartGenerateTool;
 use crate::community_hub::CommunityHubTool;
 use crate::cron::CronTool;
 use crate::data_process::DataProcessTool;
+use crate::db_query::DbQueryTool;
+use crate::doc_diff::DocDiffTool;
+use crate::docker::DockerTool;
+use crate::documents::{DocIngestTool, DocSearchTool};
 use crate::email::EmailTool;
 use crate::encrypt::EncryptTool;
 use crate::exec::ExecTool;
 use crate::exec_local::ExecLocalTool;
 use crate::exec_skill_script::ExecSkillScriptTool;
 use crate::file_ops::FileOpsTool;
+use crate::follow_up::FollowUpTool;
 use crate::fs::{EditFileTool, ListDirTool, ReadFileTool, WriteFileTool};
+use crate::git_api::GitApiTool;
+use crate::habits::HabitsTool;
 use crate::http_request::HttpRequestTool;
 use crate::image_understand::ImageUnderstandTool;
+use crate::iot_control::IotControlTool;
 use crate::knowledge_graph::KnowledgeGraphTool;
+use crate::media_control::MediaControlTool;
 use crate::memory::{MemoryForgetTool, MemoryQueryTool, MemoryUpsertTool};
 use crate::memory_maintenance::MemoryMaintenanceTool;
 use crate::message::MessageTool;
 use crate::network_monitor::NetworkMonitorTool;
+use crate::nutrition::NutritionTool;
+use crate::object_storage::ObjectStorageTool;
 use crate::ocr::OcrTool;
 use crate::office_write::OfficeWriteTool;
+use crate::parcel_track::ParcelTrackTool;
+use crate::projects::ProjectTool;
+use crate::python::PythonTool;
+use crate::receipts::ReceiptsTool;
+use crate::rss::RssTool;
 use crate::session_recall::SessionRecallTool;
 use crate::skills::ListSkillsTool;
 use crate::spawn::SpawnTool;
+use crate::ssh::SshTool;
 use crate::stream_subscribe::StreamSubscribeTool;
+use crate::subscriptions::SubscriptionsTool;
 use crate::system_info::{CapabilityEvolveTool, SystemInfoTool};
 use crate::tasks::ListTasksTool;
 use crate::termux_api::TermuxApiTool;
+use crate::todo::TodoTool;
 use crate::toggle_manage::ToggleManageTool;
+use crate::translate::TranslateTool;
+use crate::travel::TravelTool;
 use crate::tts::TtsTool;
 use crate::video_process::VideoProcessTool;
+use crate::weather::WeatherTool;
 use crate::web::{WebFetchTool, WebSearchTool};
-use crate::{Tool, ToolContext};
+use crate::{Tool, ToolConcurrency, ToolContext, ToolSchema};
+use blockcell_core::types::PermissionSet;
 
 pub const GLOBAL_CORE_TOOL_NAMES: &[&str] = &[
     "memory_query",
@@ -60,130 +91,314 @@ pub fn global_core_tool_names() -> &'static [&'static str] {
     GLOBAL_CORE_TOOL_NAMES
 }
 
+/// A built-in tool that is only constructed when it is first described or called.
+struct LazyTool {
+    factory: fn() -> Arc<dyn Tool>,
+    inner: OnceLock<Arc<dyn Tool>>,
+}
+
+impl LazyTool {
+    fn tool(&self) -> &Arc<dyn Tool> {
+        self.inner.get_or_init(|| (self.factory)())
+    }
+}
+
+#[async_trait]
+impl Tool for LazyTool {
+    fn schema(&self) -> ToolSchema {
+        self.tool().schema()
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        self.tool().validate(params)
+    }
+
+    fn required_permissions(&self, params: &Value) -> PermissionSet {
+        self.tool().required_permissions(params)
+    }
+
+    fn prompt_rule(&self, ctx: &crate::PromptContext) -> Option<String> {
+        self.tool().prompt_rule(ctx)
+    }
+
+    fn confirmation_items(&self, config: &Config, params: &Value) -> Vec<String> {
+        self.tool().confirmation_items(config, params)
+    }
+
+    fn concurrency(&self) -> ToolConcurrency {
+        self.tool().concurrency()
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        self.tool().execute(ctx, params).await
+    }
+}
+
+/// Running-call slots per tool name. Process-wide, so agents with separate registries
+/// still share one camera or browser profile.
+fn tool_slots(name: &str, max: usize) -> Arc<Semaphore> {
+    static SLOTS: OnceLock<Mutex<HashMap<String, Arc<Semaphore>>>> = OnceLock::new();
+    let mut slots = SLOTS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    slots
+        .entry(name.to_string())
+        .or_insert_with(|| Arc::new(Semaphore::new(max.max(1))))
+        .clone()
+}
+
+/// Wait for a free slot under the tool's concurrency limit; `None` when it has none.
+async fn acquire_slot(
+    name: &str,
+    concurrency: ToolConcurrency,
+) -> Result<Option<OwnedSemaphorePermit>> {
+    let ToolConcurrency::Limited { max, queue_timeout } = concurrency else {
+        return Ok(None);
+    };
+    let slots = tool_slots(name, max);
+    if slots.available_permits() == 0 {
+        debug!(tool = name, max, "Tool busy, queuing call");
+    }
+    match tokio::time::timeout(queue_timeout, slots.acquire_owned()).await {
+        Ok(Ok(permit)) => Ok(Some(permit)),
+        _ => {
+            warn!(tool = name, max, "Tool still busy after queue timeout");
+            Err(Error::Tool(format!(
+                "Tool '{}' is busy: it allows {} call(s) at a time and none finished within {}s. Try again later.",
+                name,
+                max,
+                queue_timeout.as_secs()
+            )))
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct ToolRegistry {
     tools: HashMap<String, Arc<dyn Tool>>,
+    /// Defer construction of built-in tools until first use (`lite` mode).
+    lazy: bool,
+    lazy_tools: Vec<Arc<LazyTool>>,
 }
 
 impl ToolRegistry {
     pub fn new() -> Self {
         Self {
             tools: HashMap::new(),
+            lazy: false,
+            lazy_tools: Vec::new(),
         }
     }
 
     pub fn with_defaults() -> Self {
+        Self::with_default_tools(false)
+    }
+
+    /// Built-in tools for low-memory mode (`lite`): each tool is created on first use.
+    pub fn lite() -> Self {
+        Self::with_default_tools(true)
+    }
+
+    fn with_default_tools(lazy: bool) -> Self {
         let mut registry = Self::new();
+        registry.lazy = lazy;
 
         // File system tools
-        registry.register(Arc::new(ReadFileTool));
-        registry.register(Arc::new(WriteFileTool));
-        registry.register(Arc::new(EditFileTool));
-        registry.register(Arc::new(ListDirTool));
+        registry.add(|| Arc::new(ReadFileTool));
+        registry.add(|| Arc::new(WriteFileTool));
+        registry.add(|| Arc::new(EditFileTool));
+        registry.add(|| Arc::new(ListDirTool));
 
         // Exec tool
-        registry.register(Arc::new(ExecTool));
-        registry.register(Arc::new(ExecLocalTool));
-        registry.register(Arc::new(ExecSkillScriptTool));
+        registry.add(|| Arc::new(ExecTool));
+        registry.add(|| Arc::new(ExecLocalTool));
+        registry.add(|| Arc::new(ExecSkillScriptTool));
 
         // Web tools
-        registry.register(Arc::new(WebSearchTool));
-        registry.register(Arc::new(WebFetchTool));
+        registry.add(|| Arc::new(WebSearchTool));
+        registry.add(|| Arc::new(WebFetchTool));
 
         // Communication tools
-        registry.register(Arc::new(MessageTool));
-        registry.register(Arc::new(SpawnTool));
+        registry.add(|| Arc::new(MessageTool));
+        registry.add(|| Arc::new(SpawnTool));
 
         // Task management
-        registry.register(Arc::new(ListTasksTool));
+        registry.add(|| Arc::new(ListTasksTool));
 
         // Browser tools
-        registry.register(Arc::new(BrowseTool));
+        registry.add(|| Arc::new(BrowseTool));
 
         // Scheduler tools
-        registry.register(Arc::new(CronTool));
+        registry.add(|| Arc::new(CronTool));
 
         // Memory tools
-        registry.register(Arc::new(MemoryQueryTool));
-        registry.register(Arc::new(MemoryUpsertTool));
-        registry.register(Arc::new(MemoryForgetTool));
+        registry.add(|| Arc::new(MemoryQueryTool));
+        registry.add(|| Arc::new(MemoryUpsertTool));
+        registry.add(|| Arc::new(MemoryForgetTool));
 
         // Skill evolution tools
-        registry.register(Arc::new(ListSkillsTool));
+        registry.add(|| Arc::new(ListSkillsTool));
 
         // System info & capability evolution tools
-        registry.register(Arc::new(SystemInfoTool));
-        registry.register(Arc::new(AgentStatusTool));
-        registry.register(Arc::new(CapabilityEvolveTool));
+        registry.add(|| Arc::new(SystemInfoTool));
+        registry.add(|| Arc::new(AgentStatusTool));
+        registry.add(|| Arc::new(CapabilityEvolveTool));
 
         // Camera tools
-        registry.register(Arc::new(CameraCaptureTool));
+        registry.add(|| Arc::new(CameraCaptureTool));
 
         // General app control (any macOS app)
-        registry.register(Arc::new(AppControlTool));
+        registry.add(|| Arc::new(AppControlTool));
+
+        // Local player / Spotify Connect playback and volume control
+        registry.add(|| Arc::new(MediaControlTool));
 
         // File operations (delete, rename, move, copy, compress, decompress, PDF)
-        registry.register(Arc::new(FileOpsTool));
+        registry.add(|| Arc::new(FileOpsTool));
 
         // Structured data processing (CSV, stats, query, transform)
-        registry.register(Arc::new(DataProcessTool));
+        registry.add(|| Arc::new(DataProcessTool));
+
+        // Database queries (PostgreSQL / MySQL / SQLite connection profiles)
+        registry.add(|| Arc::new(DbQueryTool));
 
         // Generic HTTP/REST API requests
-        registry.register(Arc::new(HttpRequestTool));
+        registry.add(|| Arc::new(HttpRequestTool));
 
         // Email (SMTP/IMAP)
-        registry.register(Arc::new(EmailTool));
+        registry.add(|| Arc::new(EmailTool));
 
         // Audio transcription (Whisper CLI / API)
-        registry.register(Arc::new(AudioTranscribeTool));
+        registry.add(|| Arc::new(AudioTranscribeTool));
 
         // Chart generation (matplotlib / plotly)
-        registry.register(Arc::new(ChartGenerateTool));
+        registry.add(|| Arc::new(ChartGenerateTool));
 
         // Office document generation (PPTX / DOCX / XLSX)
-        registry.register(Arc::new(OfficeWriteTool));
+        registry.add(|| Arc::new(OfficeWriteTool));
+
+        // S3-compatible object storage (upload, download, list, presigned URLs)
+        registry.add(|| Arc::new(ObjectStorageTool));
+
+        // Remote hosts over SSH/SFTP (allowlisted commands, confirm-gated uploads)
+        registry.add(|| Arc::new(SshTool));
+
+        // Docker / Podman engine (containers, images, logs, stats, lifecycle)
+        registry.add(|| Arc::new(DockerTool));
+
+        // GitHub pull request review and issue triage
+        registry.add(|| Arc::new(GitApiTool));
+
+        // Kubernetes observability (read-only unless allowMutations)
+        #[cfg(feature = "kubernetes")]
+        registry.add(|| Arc::new(crate::kubectl::KubectlTool));
+
+        // Translation with glossaries (text, Markdown, DOCX)
+        registry.add(|| Arc::new(TranslateTool));
 
         // Text-to-speech
-        registry.register(Arc::new(TtsTool));
+        registry.add(|| Arc::new(TtsTool));
 
         // OCR (image text recognition)
-        registry.register(Arc::new(OcrTool));
+        registry.add(|| Arc::new(OcrTool));
 
         // Multimodal image understanding
-        registry.register(Arc::new(ImageUnderstandTool));
+        registry.add(|| Arc::new(ImageUnderstandTool));
 
         // Video processing (ffmpeg)
-        registry.register(Arc::new(VideoProcessTool));
+        registry.add(|| Arc::new(VideoProcessTool));
 
         // Encryption and security utilities
-        registry.register(Arc::new(EncryptTool));
+        registry.add(|| Arc::new(EncryptTool));
 
         // Network monitoring and diagnostics
-        registry.register(Arc::new(NetworkMonitorTool));
+        registry.add(|| Arc::new(NetworkMonitorTool));
 
         // Knowledge graph (SQLite-backed)
-        registry.register(Arc::new(KnowledgeGraphTool));
+        registry.add(|| Arc::new(KnowledgeGraphTool));
+
+        // Real-time data streams (WebSocket/SSE/MQTT)
+        registry.add(|| Arc::new(StreamSubscribeTool));
 
-        // Real-time data streams (WebSocket/SSE)
-        registry.register(Arc::new(StreamSubscribeTool));
+        // IoT device commands over MQTT
+        registry.add(|| Arc::new(IotControlTool));
 
         // Conditional alert rules
-        registry.register(Arc::new(AlertRuleTool));
+        registry.add(|| Arc::new(AlertRuleTool));
+
+        // Strategy backtests over stored price history
+        registry.add(|| Arc::new(BacktestTool));
+
+        // RSS/Atom feed subscriptions and digests
+        registry.add(|| Arc::new(RssTool));
+
+        // Long-term projects with context packs
+        registry.add(|| Arc::new(ProjectTool));
+
+        // Personal TODO list (SQLite, morning digest)
+        registry.add(|| Arc::new(TodoTool));
+
+        // Calendar free/busy, conflict checks and slot suggestions
+        registry.add(|| Arc::new(CalendarTool));
+
+        // Recurring expenses and renewal reminders
+        registry.add(|| Arc::new(SubscriptionsTool));
+
+        // Receipt/invoice extraction into a local ledger with CSV export
+        registry.add(|| Arc::new(ReceiptsTool));
+
+        // Document version diff, change summaries and watched folders
+        registry.add(|| Arc::new(DocDiffTool));
+
+        // Document library: chunked, embedded PDFs/Markdown/HTML/Office files and URLs
+        registry.add(|| Arc::new(DocIngestTool));
+        registry.add(|| Arc::new(DocSearchTool));
+
+        // Sandboxed Python code interpreter
+        registry.add(|| Arc::new(PythonTool));
+
+        // Deterministic date/time, unit and currency calculations
+        registry.add(|| Arc::new(CalcTool));
+
+        // Weather (Open-Meteo / OpenWeather) with caching
+        registry.add(|| Arc::new(WeatherTool));
+
+        // Flight/train status and trip watchers
+        registry.add(|| Arc::new(TravelTool));
+
+        // Parcel tracking (17track / AfterShip) with status polling
+        registry.add(|| Arc::new(ParcelTrackTool));
+
+        // Food lookup, recipe macros and meal log
+        registry.add(|| Arc::new(NutritionTool));
+
+        // Read-later bookmarks with summaries and full-text search
+        registry.add(|| Arc::new(BookmarksTool));
+
+        // Birthday/anniversary reminders with greeting drafts
+        registry.add(|| Arc::new(BirthdaysTool));
+
+        // Habit tracker with streaks and evening check-ins
+        registry.add(|| Arc::new(HabitsTool));
+
+        // Agent-scheduled follow-ups that return to the originating chat
+        registry.add(|| Arc::new(FollowUpTool));
 
         // Community Hub (social interactions, skill discovery)
-        registry.register(Arc::new(CommunityHubTool));
+        registry.add(|| Arc::new(CommunityHubTool));
 
         // Memory maintenance (Ghost Agent memory gardening)
-        registry.register(Arc::new(MemoryMaintenanceTool));
+        registry.add(|| Arc::new(MemoryMaintenanceTool));
 
         // Toggle management (enable/disable skills and capabilities)
-        registry.register(Arc::new(ToggleManageTool));
+        registry.add(|| Arc::new(ToggleManageTool));
 
         // Termux API (Android device control via Termux)
-        registry.register(Arc::new(TermuxApiTool));
+        registry.add(|| Arc::new(TermuxApiTool));
 
         // Session response cache recall
-        registry.register(Arc::new(SessionRecallTool));
+        registry.add(|| Arc::new(SessionRecallTool));
 
         // NapCatQQ tools (conditional)
         #[cfg(feature = "napcat")]
@@ -197,6 +412,32 @@ impl ToolRegistry {
         registry
     }
 
+    /// Register a built-in tool; in lazy mode only its name is kept until first use.
+    fn add(&mut self, factory: fn() -> Arc<dyn Tool>) {
+        if !self.lazy {
+            self.register(factory());
+            return;
+        }
+        // The name is needed up front; the probe instance is dropped right away.
+        let name = factory().schema().name.to_string();
+        let tool = Arc::new(LazyTool {
+            factory,
+            inner: OnceLock::new(),
+        });
+        self.lazy_tools.push(Arc::clone(&tool));
+        self.tools.insert(name, tool);
+    }
+
+    /// Number of tools constructed so far (all of them unless in lazy mode).
+    pub fn loaded_tool_count(&self) -> usize {
+        let pending = self
+            .lazy_tools
+            .iter()
+            .filter(|tool| tool.inner.get().is_none())
+            .count();
+        self.tools.len() - pending
+    }
+
     pub fn register(&mut self, tool: Arc<dyn Tool>) {
         let schema = tool.schema();
         debug!(name = schema.name, "Registering tool");
@@ -339,6 +580,7 @@ impl ToolRegistry {
             )));
         }
 
+        let _slot = acquire_slot(name, tool.concurrency()).await?;
         debug!(tool = name, "Executing tool");
         tool.execute(ctx, params).await
     }
@@ -440,6 +682,51 @@ mod tests {
         assert!(filtered.is_empty());
     }
 
+    #[test]
+    fn test_lite_registry_builds_tools_on_first_use() {
+        let reg = ToolRegistry::lite();
+        assert_eq!(
+            reg.tool_names().len(),
+            ToolRegistry::with_defaults().tool_names().len()
+        );
+        // Feature-gated extras (napcat) register eagerly; built-ins wait.
+        let loaded = reg.loaded_tool_count();
+        assert!(loaded + 40 <= reg.tool_names().len());
+
+        let filtered = reg.get_filtered_schemas(&["read_file", "exec"]);
+        assert_eq!(filtered.len(), 2);
+        assert_eq!(reg.loaded_tool_count(), loaded + 2);
+        assert_eq!(reg.get("exec").unwrap().schema().name, "exec");
+    }
+
+    #[tokio::test]
+    async fn test_limited_tool_queues_then_times_out() {
+        assert!(
+            acquire_slot("slot_test_unlimited", ToolConcurrency::Unlimited)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let limit = ToolConcurrency::Limited {
+            max: 1,
+            queue_timeout: std::time::Duration::from_millis(50),
+        };
+        let first = acquire_slot("slot_test_camera", limit).await.unwrap();
+        assert!(first.is_some());
+        let err = acquire_slot("slot_test_camera", limit).await.unwrap_err();
+        assert!(err.to_string().contains("busy"));
+
+        // A queued call gets the slot as soon as the running one finishes.
+        let waiting = tokio::spawn(acquire_slot(
+            "slot_test_camera",
+            ToolConcurrency::exclusive(5),
+        ));
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        drop(first);
+        assert!(waiting.await.unwrap().unwrap().is_some());
+    }
+
     #[test]
     fn test_registry_register_custom() {
         let mut reg = ToolRegistry::new();
@@ -5,11 +5,20 @@ use blockcell_core::{Config, Result};
 use crate::mcp::manager::McpManager;
 use crate::ToolRegistry;
 
+/// Built-in tools, created lazily in `lite` mode.
+fn default_registry(config: &Config) -> ToolRegistry {
+    if config.lite {
+        ToolRegistry::lite()
+    } else {
+        ToolRegistry::with_defaults()
+    }
+}
+
 pub async fn build_tool_registry_for_agent_config(
     config: &Config,
     mcp_manager: Option<&Arc<McpManager>>,
 ) -> Result<ToolRegistry> {
-    let mut registry = ToolRegistry::with_defaults();
+    let mut registry = default_registry(config);
     if let Some(manager) = mcp_manager {
         manager
             .extend_registry_for_rules(
@@ -23,9 +32,10 @@ pub async fn build_tool_registry_for_agent_config(
 }
 
 pub async fn build_tool_registry_with_all_mcp(
+    config: &Config,
     mcp_manager: Option<&Arc<McpManager>>,
 ) -> Result<ToolRegistry> {
-    let mut registry = ToolRegistry::with_defaults();
+    let mut registry = default_registry(config);
     if let Some(manager) = mcp_manager {
         manager.extend_registry_all(&mut registry).await?;
     }
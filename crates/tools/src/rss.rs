@@ -0,0 +1,1336 @@
+use async_trait::async_trait;
+use blockcell_core::{Config, Error, Paths, Result};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use futures::stream::{self, StreamExt};
+use once_cell::sync::Lazy;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::egress::HttpClientFactory;
+use crate::{Tool, ToolContext, ToolSchema};
+
+/// Items older than this are dropped from the store.
+const ITEM_RETENTION_DAYS: i64 = 30;
+/// Hard cap on stored items across all feeds.
+const MAX_STORED_ITEMS: usize = 5000;
+/// Max characters kept per item summary.
+const MAX_SUMMARY_CHARS: usize = 400;
+const DEFAULT_REFRESH_MINS: u64 = 60;
+const MIN_REFRESH_MINS: u64 = 10;
+const DEFAULT_DIGEST_CRON: &str = "0 0 8 * * *";
+
+/// Serializes load-modify-save cycles on the feed store (tool calls + background refresh).
+static STORE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+/// Prevents overlapping background refreshes from the runtime tick.
+static REFRESH_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Persistent feed store — saved to workspace/rss/feeds.json
+#[derive(Debug, Serialize, Deserialize)]
+struct RssStore {
+    version: u32,
+    feeds: Vec<Feed>,
+    #[serde(default)]
+    items: Vec<FeedItem>,
+    /// Last time `digest` was marked as read (Unix ms).
+    #[serde(default)]
+    last_digest_at: Option<i64>,
+}
+
+impl Default for RssStore {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            feeds: Vec::new(),
+            items: Vec::new(),
+            last_digest_at: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Feed {
+    id: String,
+    url: String,
+    title: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    enabled: bool,
+    /// How often the background refresh fetches this feed.
+    refresh_interval_mins: u64,
+    created_at: i64,
+    #[serde(default)]
+    last_fetched_at: Option<i64>,
+    #[serde(default)]
+    last_error: Option<String>,
+    /// HTTP validators for conditional GET.
+    #[serde(default)]
+    etag: Option<String>,
+    #[serde(default)]
+    last_modified: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FeedItem {
+    /// Dedupe key: `<feed_id>#<guid|link|title>`.
+    id: String,
+    feed_id: String,
+    title: String,
+    link: String,
+    summary: String,
+    #[serde(default)]
+    published_at: Option<i64>,
+    first_seen_at: i64,
+}
+
+impl FeedItem {
+    /// Timestamp used for "since" queries: publish date when known, otherwise first sighting.
+    fn effective_ts(&self) -> i64 {
+        self.published_at.unwrap_or(self.first_seen_at)
+    }
+}
+
+fn store_path(workspace: &Path) -> PathBuf {
+    workspace.join("rss").join("feeds.json")
+}
+
+fn load_store(workspace: &Path) -> Result<RssStore> {
+    let path = store_path(workspace);
+    if !path.exists() {
+        return Ok(RssStore::default());
+    }
+    let content = std::fs::read_to_string(&path)?;
+    let store: RssStore = serde_json::from_str(&content)?;
+    Ok(store)
+}
+
+fn save_store(workspace: &Path, store: &RssStore) -> Result<()> {
+    let dir = workspace.join("rss");
+    std::fs::create_dir_all(&dir)?;
+    let content = serde_json::to_string_pretty(store)?;
+    std::fs::write(dir.join("feeds.json"), content)?;
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Feed parsing (RSS 2.0 / RSS 1.0 (RDF) / Atom)
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default)]
+struct ParsedFeed {
+    title: String,
+    items: Vec<ParsedItem>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct ParsedItem {
+    guid: String,
+    title: String,
+    link: String,
+    summary: String,
+    published_at: Option<i64>,
+}
+
+impl ParsedItem {
+    fn dedupe_key(&self) -> &str {
+        [&self.guid, &self.link, &self.title]
+            .into_iter()
+            .map(|s| s.trim())
+            .find(|s| !s.is_empty())
+            .unwrap_or("")
+    }
+}
+
+fn parse_feed(xml: &str) -> Result<ParsedFeed> {
+    use quick_xml::events::{BytesStart, Event};
+    use quick_xml::reader::Reader;
+
+    fn local_name(e: &BytesStart) -> String {
+        String::from_utf8_lossy(e.local_name().as_ref()).to_string()
+    }
+
+    /// Atom `<link href="..." rel="alternate"/>`: take the first alternate (or rel-less) link.
+    fn apply_atom_link(item: &mut ParsedItem, e: &BytesStart) {
+        let mut href = None;
+        let mut rel = None;
+        for attr in e.attributes().flatten() {
+            let value = attr
+                .unescape_value()
+                .map(|v| v.to_string())
+                .unwrap_or_default();
+            match attr.key.local_name().as_ref() {
+                b"href" => href = Some(value),
+                b"rel" => rel = Some(value),
+                _ => {}
+            }
+        }
+        if let Some(href) = href {
+            if item.link.is_empty() && matches!(rel.as_deref(), None | Some("alternate")) {
+                item.link = href;
+            }
+        }
+    }
+
+    fn apply_text(
+        feed: &mut ParsedFeed,
+        item: Option<&mut ParsedItem>,
+        field: Option<&str>,
+        text: &str,
+    ) {
+        let Some(field) = field else {
+            return;
+        };
+        match item {
+            None => {
+                if field == "title" && feed.title.is_empty() {
+                    feed.title = text.trim().to_string();
+                }
+            }
+            Some(item) => match field {
+                "title" => item.title.push_str(text),
+                "link" if item.link.is_empty() => item.link = text.trim().to_string(),
+                "guid" | "id" if item.guid.is_empty() => item.guid = text.trim().to_string(),
+                "pubDate" | "published" | "updated" | "date" if item.published_at.is_none() => {
+                    item.published_at = parse_feed_date(text);
+                }
+                "description" | "summary" | "content" | "encoded" if item.summary.is_empty() => {
+                    item.summary = text.to_string();
+                }
+                _ => {}
+            },
+        }
+    }
+
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut feed = ParsedFeed::default();
+    let mut current: Option<ParsedItem> = None;
+    let mut field: Option<String> = None;
+    let mut saw_root = false;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                let name = local_name(&e);
+                match name.as_str() {
+                    "rss" | "feed" | "RDF" => saw_root = true,
+                    "item" | "entry" => current = Some(ParsedItem::default()),
+                    "link" => {
+                        if let Some(item) = current.as_mut() {
+                            apply_atom_link(item, &e);
+                        }
+                    }
+                    _ => {}
+                }
+                field = Some(name);
+            }
+            Ok(Event::Empty(e)) if local_name(&e) == "link" => {
+                if let Some(item) = current.as_mut() {
+                    apply_atom_link(item, &e);
+                }
+            }
+            Ok(Event::Text(t)) => {
+                let text = t
+                    .unescape()
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|_| String::from_utf8_lossy(t.as_ref()).to_string());
+                apply_text(&mut feed, current.as_mut(), field.as_deref(), &text);
+            }
+            Ok(Event::CData(c)) => {
+                let text = String::from_utf8_lossy(&c.into_inner()).to_string();
+                apply_text(&mut feed, current.as_mut(), field.as_deref(), &text);
+            }
+            Ok(Event::End(e)) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                if name == "item" || name == "entry" {
+                    if let Some(item) = current.take() {
+                        if !item.dedupe_key().is_empty() {
+                            feed.items.push(item);
+                        }
+                    }
+                }
+                field = None;
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(Error::Tool(format!("Feed parse error: {}", e))),
+            _ => {}
+        }
+    }
+
+    if !saw_root {
+        return Err(Error::Tool(
+            "Response is not an RSS or Atom feed".to_string(),
+        ));
+    }
+    Ok(feed)
+}
+
+/// Parse RFC 2822 (RSS), RFC 3339 (Atom) or plain `YYYY-MM-DD` dates into Unix ms.
+fn parse_feed_date(s: &str) -> Option<i64> {
+    let s = s.trim();
+    DateTime::parse_from_rfc2822(s)
+        .or_else(|_| DateTime::parse_from_rfc3339(s))
+        .map(|d| d.timestamp_millis())
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .map(|dt| dt.and_utc().timestamp_millis())
+        })
+}
+
+fn clean_summary(raw: &str) -> String {
+    let text = if raw.contains('<') {
+        crate::web::extract_text_from_html(raw)
+    } else {
+        raw.split_whitespace().collect::<Vec<_>>().join(" ")
+    };
+    let truncated = crate::safe_truncate(&text, MAX_SUMMARY_CHARS);
+    if truncated.len() < text.len() {
+        format!("{}…", truncated)
+    } else {
+        text
+    }
+}
+
+/// Parse a `since` expression into a Unix ms cutoff.
+/// Accepts `today`, `yesterday` (local midnight in `tz`), relative `30m` / `12h` / `7d`,
+/// or an RFC 3339 / `YYYY-MM-DD` timestamp.
+fn parse_since(s: &str, now: DateTime<Utc>, tz: Option<Tz>) -> Option<i64> {
+    let s = s.trim();
+    let tz = tz.unwrap_or(Tz::UTC);
+    let local_midnight = |days_back: i64| {
+        let date = now.with_timezone(&tz).date_naive() - chrono::Duration::days(days_back);
+        date.and_hms_opt(0, 0, 0)
+            .and_then(|dt| tz.from_local_datetime(&dt).earliest())
+            .map(|dt| dt.timestamp_millis())
+    };
+    match s.to_ascii_lowercase().as_str() {
+        "today" | "今天" => return local_midnight(0),
+        "yesterday" | "昨天" => return local_midnight(1),
+        _ => {}
+    }
+    if let Some(unit) = s.chars().last() {
+        if let Ok(n) = s[..s.len() - unit.len_utf8()].trim().parse::<i64>() {
+            let secs = match unit {
+                'm' => Some(n * 60),
+                'h' => Some(n * 3600),
+                'd' => Some(n * 86400),
+                _ => None,
+            };
+            if let Some(secs) = secs {
+                return Some(now.timestamp_millis() - secs * 1000);
+            }
+        }
+    }
+    parse_feed_date(s)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Fetching & merging
+// ─────────────────────────────────────────────────────────────────────────────
+
+enum FetchOutcome {
+    NotModified,
+    Fetched {
+        feed: ParsedFeed,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    },
+}
+
+fn http_client(egress: &HttpClientFactory) -> Result<Client> {
+    egress
+        .builder()
+        .timeout(std::time::Duration::from_secs(20))
+        .user_agent(concat!("blockcell-rss/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| Error::Tool(format!("HTTP client error: {}", e)))
+}
+
+/// Every fetch is checked against `tools.egress`, so a policy change also stops
+/// refreshes of feeds subscribed before it.
+async fn fetch_feed(
+    egress: &HttpClientFactory,
+    client: &Client,
+    url: &str,
+    etag: Option<&str>,
+    last_modified: Option<&str>,
+) -> Result<FetchOutcome> {
+    egress.check(url).await?;
+    let mut req = client.get(url).header(
+        "Accept",
+        "application/rss+xml, application/atom+xml, application/xml;q=0.9, text/xml;q=0.8, */*;q=0.5",
+    );
+    if let Some(etag) = etag {
+        req = req.header("If-None-Match", etag);
+    }
+    if let Some(lm) = last_modified {
+        req = req.header("If-Modified-Since", lm);
+    }
+
+    let response = req
+        .send()
+        .await
+        .map_err(|e| Error::Tool(format!("Feed request failed: {}", e)))?;
+
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(FetchOutcome::NotModified);
+    }
+    if !response.status().is_success() {
+        return Err(Error::Tool(format!(
+            "Feed returned HTTP {}",
+            response.status()
+        )));
+    }
+
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string())
+    };
+    let etag = header("etag");
+    let last_modified = header("last-modified");
+
+    let body = response
+        .text()
+        .await
+        .map_err(|e| Error::Tool(format!("Failed to read feed body: {}", e)))?;
+    let feed = parse_feed(&body)?;
+
+    Ok(FetchOutcome::Fetched {
+        feed,
+        etag,
+        last_modified,
+    })
+}
+
+/// Merge freshly parsed items into the store, skipping anything already seen.
+/// Returns the number of new items.
+fn merge_items(store: &mut RssStore, feed_id: &str, parsed: &ParsedFeed, now_ms: i64) -> usize {
+    let mut seen: HashSet<String> = store
+        .items
+        .iter()
+        .filter(|i| i.feed_id == feed_id)
+        .map(|i| i.id.clone())
+        .collect();
+
+    let mut added = 0;
+    for item in &parsed.items {
+        let id = format!("{}#{}", feed_id, item.dedupe_key());
+        if !seen.insert(id.clone()) {
+            continue;
+        }
+        let title = item.title.split_whitespace().collect::<Vec<_>>().join(" ");
+        store.items.push(FeedItem {
+            id,
+            feed_id: feed_id.to_string(),
+            title: if title.is_empty() {
+                item.link.clone()
+            } else {
+                title
+            },
+            link: item.link.clone(),
+            summary: clean_summary(&item.summary),
+            published_at: item.published_at,
+            first_seen_at: now_ms,
+        });
+        added += 1;
+    }
+    added
+}
+
+fn prune_items(store: &mut RssStore, now_ms: i64) {
+    let cutoff = now_ms - ITEM_RETENTION_DAYS * 86_400_000;
+    let feed_ids: HashSet<&str> = store.feeds.iter().map(|f| f.id.as_str()).collect();
+    store.items.retain(|i| {
+        feed_ids.contains(i.feed_id.as_str()) && i.first_seen_at.max(i.effective_ts()) >= cutoff
+    });
+    if store.items.len() > MAX_STORED_ITEMS {
+        store
+            .items
+            .sort_by_key(|i| std::cmp::Reverse(i.first_seen_at));
+        store.items.truncate(MAX_STORED_ITEMS);
+    }
+}
+
+/// Which feeds a refresh pass should fetch.
+#[derive(Clone, Copy)]
+enum RefreshScope<'a> {
+    /// Only feeds whose refresh interval has elapsed (background tick).
+    Due,
+    /// Every enabled feed, regardless of interval.
+    All,
+    /// A single feed (id or id prefix).
+    One(&'a str),
+}
+
+fn is_due(feed: &Feed, now_ms: i64) -> bool {
+    let interval_ms = feed.refresh_interval_mins.max(MIN_REFRESH_MINS) as i64 * 60_000;
+    feed.last_fetched_at
+        .map(|t| now_ms - t >= interval_ms)
+        .unwrap_or(true)
+}
+
+/// Fetch feeds in `scope` and merge new items into the store.
+/// Network I/O happens outside the store lock; results are merged in a second pass.
+async fn refresh_feeds(
+    egress: &HttpClientFactory,
+    workspace: &Path,
+    scope: RefreshScope<'_>,
+) -> Result<Vec<Value>> {
+    let now_ms = Utc::now().timestamp_millis();
+    let targets: Vec<Feed> = {
+        let _guard = STORE_LOCK.lock().await;
+        let store = load_store(workspace)?;
+        store
+            .feeds
+            .into_iter()
+            .filter(|f| match scope {
+                RefreshScope::Due => f.enabled && is_due(f, now_ms),
+                RefreshScope::All => f.enabled,
+                RefreshScope::One(id) => f.id.starts_with(id),
+            })
+            .collect()
+    };
+
+    if targets.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let client = http_client(egress)?;
+    let outcomes: Vec<(Feed, Result<FetchOutcome>)> = stream::iter(targets)
+        .map(|feed| {
+            let client = client.clone();
+            async move {
+                let outcome = fetch_feed(
+                    egress,
+                    &client,
+                    &feed.url,
+                    feed.etag.as_deref(),
+                    feed.last_modified.as_deref(),
+                )
+                .await;
+                (feed, outcome)
+            }
+        })
+        .buffer_unordered(4)
+        .collect()
+        .await;
+
+    let _guard = STORE_LOCK.lock().await;
+    let mut store = load_store(workspace)?;
+    let now_ms = Utc::now().timestamp_millis();
+    let mut reports = Vec::new();
+
+    for (target, outcome) in outcomes {
+        let mut new_items = 0;
+        let mut error = None;
+        match outcome {
+            Ok(FetchOutcome::NotModified) => {}
+            Ok(FetchOutcome::Fetched {
+                feed: parsed,
+                etag,
+                last_modified,
+            }) => {
+                new_items = merge_items(&mut store, &target.id, &parsed, now_ms);
+                if let Some(feed) = store.feeds.iter_mut().find(|f| f.id == target.id) {
+                    if feed.title.is_empty() && !parsed.title.is_empty() {
+                        feed.title = parsed.title.clone();
+                    }
+                    feed.etag = etag;
+                    feed.last_modified = last_modified;
+                }
+            }
+            Err(e) => error = Some(e.to_string()),
+        }
+
+        // Feed may have been unsubscribed while we were fetching.
+        let Some(feed) = store.feeds.iter_mut().find(|f| f.id == target.id) else {
+            continue;
+        };
+        feed.last_fetched_at = Some(now_ms);
+        feed.last_error = error.clone();
+        if let Some(ref e) = error {
+            warn!(feed = %feed.url, error = %e, "RSS feed refresh failed");
+        }
+        reports.push(json!({
+            "feed_id": feed.id,
+            "title": feed.title,
+            "new_items": new_items,
+            "error": error,
+        }));
+    }
+
+    prune_items(&mut store, now_ms);
+    save_store(workspace, &store)?;
+    Ok(reports)
+}
+
+/// Background refresh hook for the agent runtime tick.
+/// Fetches only feeds whose interval elapsed; no-op when nothing is subscribed
+/// or a previous pass is still running.
+pub fn spawn_due_refresh(config: &Config, workspace: PathBuf) {
+    if !store_path(&workspace).exists() {
+        return;
+    }
+    if REFRESH_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let egress = HttpClientFactory::for_tool(config, "rss");
+    tokio::spawn(async move {
+        match refresh_feeds(&egress, &workspace, RefreshScope::Due).await {
+            Ok(reports) if !reports.is_empty() => {
+                let new_items: u64 = reports.iter().filter_map(|r| r["new_items"].as_u64()).sum();
+                info!(
+                    feeds = reports.len(),
+                    new_items, "RSS background refresh completed"
+                );
+            }
+            Ok(_) => {}
+            Err(e) => warn!(error = %e, "RSS background refresh failed"),
+        }
+        REFRESH_RUNNING.store(false, Ordering::SeqCst);
+    });
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Queries
+// ─────────────────────────────────────────────────────────────────────────────
+
+struct ItemFilter<'a> {
+    since_ms: i64,
+    feed_id: Option<&'a str>,
+    tag: Option<&'a str>,
+    keyword: Option<String>,
+    limit: usize,
+}
+
+fn select_items<'a>(store: &'a RssStore, filter: &ItemFilter<'_>) -> Vec<&'a FeedItem> {
+    let allowed_feeds: HashSet<&str> = store
+        .feeds
+        .iter()
+        .filter(|f| {
+            filter
+                .feed_id
+                .map(|id| f.id.starts_with(id))
+                .unwrap_or(true)
+        })
+        .filter(|f| {
+            filter
+                .tag
+                .map(|t| f.tags.iter().any(|ft| ft.eq_ignore_ascii_case(t)))
+                .unwrap_or(true)
+        })
+        .map(|f| f.id.as_str())
+        .collect();
+
+    let mut items: Vec<&FeedItem> = store
+        .items
+        .iter()
+        .filter(|i| allowed_feeds.contains(i.feed_id.as_str()))
+        .filter(|i| i.effective_ts() >= filter.since_ms)
+        .filter(|i| match &filter.keyword {
+            Some(k) => {
+                i.title.to_lowercase().contains(k.as_str())
+                    || i.summary.to_lowercase().contains(k.as_str())
+            }
+            None => true,
+        })
+        .collect();
+    items.sort_by_key(|i| std::cmp::Reverse(i.effective_ts()));
+    items.truncate(filter.limit);
+    items
+}
+
+fn item_json(item: &FeedItem, store: &RssStore) -> Value {
+    let feed_title = store
+        .feeds
+        .iter()
+        .find(|f| f.id == item.feed_id)
+        .map(|f| f.title.as_str())
+        .unwrap_or("");
+    json!({
+        "title": item.title,
+        "link": item.link,
+        "summary": item.summary,
+        "published": item.published_at.and_then(DateTime::from_timestamp_millis).map(|d| d.to_rfc3339()),
+        "feed_id": item.feed_id,
+        "feed": feed_title,
+    })
+}
+
+/// Group items by feed for briefing-style output.
+fn group_by_feed(items: &[&FeedItem], store: &RssStore) -> Vec<Value> {
+    store
+        .feeds
+        .iter()
+        .filter_map(|feed| {
+            let entries: Vec<Value> = items
+                .iter()
+                .filter(|i| i.feed_id == feed.id)
+                .map(|i| item_json(i, store))
+                .collect();
+            if entries.is_empty() {
+                None
+            } else {
+                Some(json!({
+                    "feed_id": feed.id,
+                    "feed": feed.title,
+                    "count": entries.len(),
+                    "items": entries,
+                }))
+            }
+        })
+        .collect()
+}
+
+fn feed_json(feed: &Feed, store: &RssStore) -> Value {
+    json!({
+        "id": feed.id,
+        "url": feed.url,
+        "title": feed.title,
+        "tags": feed.tags,
+        "enabled": feed.enabled,
+        "refresh_interval_mins": feed.refresh_interval_mins,
+        "last_fetched_at": feed.last_fetched_at.and_then(DateTime::from_timestamp_millis).map(|d| d.to_rfc3339()),
+        "last_error": feed.last_error,
+        "stored_items": store.items.iter().filter(|i| i.feed_id == feed.id).count(),
+    })
+}
+
+fn str_list(params: &Value, key: &str) -> Vec<String> {
+    params
+        .get(key)
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str())
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tool
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct RssTool;
+
+#[async_trait]
+impl Tool for RssTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "rss",
+            description: "Subscribe to RSS/Atom news feeds and query what's new. \
+                Subscribed feeds are persisted and refreshed in the background (deduplicated by guid/link). \
+                Actions: 'subscribe' (add a feed by url), 'unsubscribe' (remove by feed_id or url), 'list' (subscribed feeds), \
+                'refresh' (fetch now), 'query' (items since a time, e.g. since='yesterday'), \
+                'digest' (new items since the last digest, grouped by feed — use for morning briefings), \
+                'schedule_digest' (create a recurring cron job that delivers a briefing to the current chat).",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["subscribe", "unsubscribe", "list", "refresh", "query", "digest", "schedule_digest"],
+                        "description": "Action to perform"
+                    },
+                    "url": {
+                        "type": "string",
+                        "description": "(subscribe/unsubscribe) Feed URL (http/https)"
+                    },
+                    "feed_id": {
+                        "type": "string",
+                        "description": "(unsubscribe/refresh/query/digest) Feed ID or ID prefix"
+                    },
+                    "name": {
+                        "type": "string",
+                        "description": "(subscribe) Display name; defaults to the feed's own title. (schedule_digest) Job name"
+                    },
+                    "tags": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "(subscribe) Tags for grouping, e.g. ['tech', 'ai']"
+                    },
+                    "tag": {
+                        "type": "string",
+                        "description": "(query/digest) Only include feeds with this tag"
+                    },
+                    "refresh_interval_mins": {
+                        "type": "integer",
+                        "description": "(subscribe) Background refresh interval in minutes. Default 60, minimum 10"
+                    },
+                    "since": {
+                        "type": "string",
+                        "description": "(query) Cutoff: 'today', 'yesterday', relative '12h' / '7d', or an ISO date. Default '24h'"
+                    },
+                    "keyword": {
+                        "type": "string",
+                        "description": "(query/digest) Case-insensitive filter on title/summary"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "(query/digest) Max items to return. Default 30, max 200"
+                    },
+                    "refresh": {
+                        "type": "boolean",
+                        "description": "(query/digest) Fetch due feeds before answering. Default true"
+                    },
+                    "mark_read": {
+                        "type": "boolean",
+                        "description": "(digest) Advance the digest cursor so the next digest only shows newer items. Default true"
+                    },
+                    "cron_expr": {
+                        "type": "string",
+                        "description": "(schedule_digest) 6-field cron expression. Default '0 0 8 * * *' (every day 08:00)"
+                    },
+                    "tz": {
+                        "type": "string",
+                        "description": "(schedule_digest) Optional IANA timezone override, e.g. 'Asia/Shanghai'"
+                    }
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    fn prompt_rule(&self, _ctx: &crate::PromptContext) -> Option<String> {
+        Some("- **RSS 订阅 (rss)**: 用户要订阅新闻源/博客时用 `rss` action='subscribe'（只需 url）。问『我订阅的源昨天以来有什么新内容』用 action='query' + `since='yesterday'`；生成早报/简报用 action='digest'（按源分组，自动只返回上次简报之后的新条目）。用户要求『每天早上推送订阅简报』时用 action='schedule_digest'，不要自己拼 cron 任务。汇总时每条给出标题+链接+一句话摘要，不要编造条目。".to_string())
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        let action = params
+            .get("action")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| Error::Validation("Missing required parameter: action".to_string()))?;
+
+        match action {
+            "subscribe" => {
+                let url = params.get("url").and_then(|v| v.as_str()).unwrap_or("");
+                if !(url.starts_with("http://") || url.starts_with("https://")) {
+                    return Err(Error::Validation(
+                        "subscribe requires an http(s) 'url'".to_string(),
+                    ));
+                }
+            }
+            "unsubscribe" => {
+                if params.get("feed_id").and_then(|v| v.as_str()).is_none()
+                    && params.get("url").and_then(|v| v.as_str()).is_none()
+                {
+                    return Err(Error::Validation(
+                        "unsubscribe requires 'feed_id' or 'url'".to_string(),
+                    ));
+                }
+            }
+            "schedule_digest" => {
+                if let Some(expr) = params.get("cron_expr").and_then(|v| v.as_str()) {
+                    if expr.parse::<cron::Schedule>().is_err() {
+                        return Err(Error::Validation(format!(
+                            "Invalid cron expression '{}'. Use 6-field format: 'sec min hour day month weekday'",
+                            expr
+                        )));
+                    }
+                }
+            }
+            "list" | "refresh" | "query" | "digest" => {}
+            _ => {
+                return Err(Error::Validation(format!("Unknown action: {}", action)));
+            }
+        }
+        Ok(())
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        let action = params["action"].as_str().unwrap_or("");
+        let workspace = ctx.workspace.clone();
+        let tz: Option<Tz> = ctx
+            .config
+            .default_timezone
+            .as_deref()
+            .and_then(|s| s.parse().ok());
+
+        let egress = HttpClientFactory::for_tool(&ctx.config, "rss");
+
+        match action {
+            "subscribe" => action_subscribe(&egress, &workspace, &params).await,
+            "unsubscribe" => action_unsubscribe(&workspace, &params).await,
+            "list" => {
+                let _guard = STORE_LOCK.lock().await;
+                let store = load_store(&workspace)?;
+                let feeds: Vec<Value> = store.feeds.iter().map(|f| feed_json(f, &store)).collect();
+                Ok(json!({ "feeds": feeds, "count": feeds.len() }))
+            }
+            "refresh" => {
+                let scope = match params.get("feed_id").and_then(|v| v.as_str()) {
+                    Some(id) => RefreshScope::One(id),
+                    None => RefreshScope::All,
+                };
+                let reports = refresh_feeds(&egress, &workspace, scope).await?;
+                let new_items: u64 = reports.iter().filter_map(|r| r["new_items"].as_u64()).sum();
+                Ok(json!({ "refreshed": reports, "new_items": new_items }))
+            }
+            "query" | "digest" => {
+                action_query(&egress, &workspace, &params, action == "digest", tz).await
+            }
+            "schedule_digest" => action_schedule_digest(&ctx, &params).await,
+            _ => Err(Error::Tool(format!("Unknown action: {}", action))),
+        }
+    }
+}
+
+async fn action_subscribe(
+    egress: &HttpClientFactory,
+    workspace: &Path,
+    params: &Value,
+) -> Result<Value> {
+    let url = params["url"].as_str().unwrap_or("").trim().to_string();
+
+    {
+        let _guard = STORE_LOCK.lock().await;
+        let store = load_store(workspace)?;
+        if let Some(existing) = store.feeds.iter().find(|f| f.url == url) {
+            return Ok(json!({
+                "status": "already_subscribed",
+                "feed": feed_json(existing, &store),
+            }));
+        }
+    }
+
+    // Fetch once up front so bad URLs fail fast instead of becoming dead subscriptions.
+    let client = http_client(egress)?;
+    let (parsed, etag, last_modified) = match fetch_feed(egress, &client, &url, None, None).await? {
+        FetchOutcome::Fetched {
+            feed,
+            etag,
+            last_modified,
+        } => (feed, etag, last_modified),
+        FetchOutcome::NotModified => (ParsedFeed::default(), None, None),
+    };
+
+    let now_ms = Utc::now().timestamp_millis();
+    let name = params
+        .get("name")
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    let feed = Feed {
+        id: Uuid::new_v4().to_string()[..8].to_string(),
+        url,
+        title: name.unwrap_or_else(|| parsed.title.clone()),
+        tags: str_list(params, "tags"),
+        enabled: true,
+        refresh_interval_mins: params
+            .get("refresh_interval_mins")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_REFRESH_MINS)
+            .max(MIN_REFRESH_MINS),
+        created_at: now_ms,
+        last_fetched_at: Some(now_ms),
+        last_error: None,
+        etag,
+        last_modified,
+    };
+
+    let _guard = STORE_LOCK.lock().await;
+    let mut store = load_store(workspace)?;
+    let feed_id = feed.id.clone();
+    store.feeds.push(feed);
+    let added = merge_items(&mut store, &feed_id, &parsed, now_ms);
+    prune_items(&mut store, now_ms);
+    save_store(workspace, &store)?;
+    debug!(feed_id = %feed_id, items = added, "RSS feed subscribed");
+
+    let feed = store
+        .feeds
+        .iter()
+        .find(|f| f.id == feed_id)
+        .expect("feed just inserted");
+    Ok(json!({
+        "status": "subscribed",
+        "feed": feed_json(feed, &store),
+        "items_fetched": added,
+    }))
+}
+
+async fn action_unsubscribe(workspace: &Path, params: &Value) -> Result<Value> {
+    let feed_id = params.get("feed_id").and_then(|v| v.as_str());
+    let url = params.get("url").and_then(|v| v.as_str()).map(str::trim);
+
+    let _guard = STORE_LOCK.lock().await;
+    let mut store = load_store(workspace)?;
+    let removed: Vec<Feed> = store
+        .feeds
+        .iter()
+        .filter(|f| {
+            feed_id.map(|id| f.id.starts_with(id)).unwrap_or(false) || Some(f.url.as_str()) == url
+        })
+        .cloned()
+        .collect();
+    if removed.is_empty() {
+        return Err(Error::Tool("No matching feed subscription".to_string()));
+    }
+    let removed_ids: HashSet<&str> = removed.iter().map(|f| f.id.as_str()).collect();
+    store.feeds.retain(|f| !removed_ids.contains(f.id.as_str()));
+    store
+        .items
+        .retain(|i| !removed_ids.contains(i.feed_id.as_str()));
+    save_store(workspace, &store)?;
+
+    Ok(json!({
+        "status": "unsubscribed",
+        "removed": removed.iter().map(|f| json!({"id": f.id, "title": f.title, "url": f.url})).collect::<Vec<_>>(),
+    }))
+}
+
+async fn action_query(
+    egress: &HttpClientFactory,
+    workspace: &Path,
+    params: &Value,
+    digest: bool,
+    tz: Option<Tz>,
+) -> Result<Value> {
+    let mut refresh_errors = Vec::new();
+    if params
+        .get("refresh")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true)
+    {
+        match refresh_feeds(egress, workspace, RefreshScope::Due).await {
+            Ok(reports) => {
+                refresh_errors = reports
+                    .into_iter()
+                    .filter(|r| !r["error"].is_null())
+                    .collect();
+            }
+            Err(e) => warn!(error = %e, "RSS refresh before query failed"),
+        }
+    }
+
+    let now = Utc::now();
+    let _guard = STORE_LOCK.lock().await;
+    let mut store = load_store(workspace)?;
+    if store.feeds.is_empty() {
+        return Err(Error::Tool(
+            "No RSS feeds subscribed. Use action='subscribe' with a feed url first.".to_string(),
+        ));
+    }
+
+    let default_since = now.timestamp_millis() - 86_400_000;
+    let since_ms = if digest {
+        store.last_digest_at.unwrap_or(default_since)
+    } else {
+        match params.get("since").and_then(|v| v.as_str()) {
+            Some(s) => parse_since(s, now, tz).ok_or_else(|| {
+                Error::Validation(format!(
+                    "Invalid 'since' value '{}'. Use today, yesterday, 12h, 7d or an ISO date.",
+                    s
+                ))
+            })?,
+            None => default_since,
+        }
+    };
+
+    let filter = ItemFilter {
+        since_ms,
+        feed_id: params.get("feed_id").and_then(|v| v.as_str()),
+        tag: params.get("tag").and_then(|v| v.as_str()),
+        keyword: params
+            .get("keyword")
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty()),
+        limit: params
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(30)
+            .clamp(1, 200) as usize,
+    };
+
+    let since_str = DateTime::from_timestamp_millis(since_ms).map(|d| d.to_rfc3339());
+    let items = select_items(&store, &filter);
+    let mut out = if digest {
+        json!({
+            "since": since_str,
+            "count": items.len(),
+            "feeds": group_by_feed(&items, &store),
+        })
+    } else {
+        json!({
+            "since": since_str,
+            "count": items.len(),
+            "items": items.iter().map(|i| item_json(i, &store)).collect::<Vec<_>>(),
+        })
+    };
+    if !refresh_errors.is_empty() {
+        out["refresh_errors"] = json!(refresh_errors);
+    }
+
+    if digest
+        && params
+            .get("mark_read")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true)
+    {
+        store.last_digest_at = Some(now.timestamp_millis());
+        save_store(workspace, &store)?;
+    }
+    Ok(out)
+}
+
+async fn action_schedule_digest(ctx: &ToolContext, params: &Value) -> Result<Value> {
+    let name = params
+        .get("name")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or("RSS 早报");
+    let cron_expr = params
+        .get("cron_expr")
+        .and_then(|v| v.as_str())
+        .unwrap_or(DEFAULT_DIGEST_CRON);
+
+    let mut cron_params = json!({
+        "action": "add",
+        "name": name,
+        "mode": "agent",
+        "cron_expr": cron_expr,
+        "message": "Call the `rss` tool with action='digest' and turn the result into a concise morning briefing: group by feed, one line per item with title, link and a one-sentence summary. If there are no new items, reply briefly that there is nothing new.",
+    });
+    if let Some(tz) = params.get("tz").and_then(|v| v.as_str()) {
+        cron_params["tz"] = json!(tz);
+    }
+
+    let paths = if let Some(base) = ctx.workspace.parent() {
+        Paths::with_base(base.to_path_buf())
+    } else {
+        Paths::new()
+    };
+    let origin_channel = ctx.channel.clone();
+    let origin_chat_id = ctx.chat_id.clone();
+    let default_timezone = ctx.config.default_timezone.clone();
+    let role = ctx.role();
+    tokio::task::spawn_blocking(move || {
+        crate::cron::execute_cron_action_with_paths(
+            &paths,
+            "add",
+            &cron_params,
+            &origin_channel,
+            &origin_chat_id,
+            default_timezone.as_deref(),
+            Some(role.as_str()),
+        )
+    })
+    .await
+    .map_err(|e| Error::Tool(format!("Cron task failed: {}", e)))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RSS_SAMPLE: &str = r#"<?xml version="1.0"?>
+<rss version="2.0"><channel>
+  <title>Example News</title>
+  <link>https://example.com</link>
+  <item>
+    <title>First &amp; foremost</title>
+    <link>https://example.com/1</link>
+    <guid>urn:1</guid>
+    <pubDate>Mon, 02 Jan 2006 15:04:05 GMT</pubDate>
+    <description><![CDATA[<p>Hello <b>world</b></p>]]></description>
+  </item>
+  <item>
+    <title>Second</title>
+    <link>https://example.com/2</link>
+  </item>
+</channel></rss>"#;
+
+    const ATOM_SAMPLE: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Atom Blog</title>
+  <entry>
+    <title>Post</title>
+    <link rel="self" href="https://blog.example/self"/>
+    <link href="https://blog.example/post"/>
+    <id>tag:blog.example,2024:1</id>
+    <updated>2024-03-01T10:00:00Z</updated>
+    <summary>Short summary</summary>
+  </entry>
+</feed>"#;
+
+    #[test]
+    fn test_rss_schema() {
+        let tool = RssTool;
+        assert_eq!(tool.schema().name, "rss");
+    }
+
+    #[test]
+    fn test_rss_validate() {
+        let tool = RssTool;
+        assert!(tool
+            .validate(&json!({"action": "subscribe", "url": "https://example.com/feed"}))
+            .is_ok());
+        assert!(tool
+            .validate(&json!({"action": "subscribe", "url": "ftp://x"}))
+            .is_err());
+        assert!(tool.validate(&json!({"action": "unsubscribe"})).is_err());
+        assert!(tool
+            .validate(&json!({"action": "schedule_digest", "cron_expr": "bad"}))
+            .is_err());
+        assert!(tool.validate(&json!({"action": "query"})).is_ok());
+        assert!(tool.validate(&json!({"action": "nope"})).is_err());
+    }
+
+    #[test]
+    fn test_parse_rss() {
+        let feed = parse_feed(RSS_SAMPLE).unwrap();
+        assert_eq!(feed.title, "Example News");
+        assert_eq!(feed.items.len(), 2);
+        assert_eq!(feed.items[0].title, "First & foremost");
+        assert_eq!(feed.items[0].guid, "urn:1");
+        assert!(feed.items[0].published_at.is_some());
+        assert_eq!(clean_summary(&feed.items[0].summary), "Hello world");
+        assert_eq!(feed.items[1].dedupe_key(), "https://example.com/2");
+    }
+
+    #[test]
+    fn test_parse_atom() {
+        let feed = parse_feed(ATOM_SAMPLE).unwrap();
+        assert_eq!(feed.title, "Atom Blog");
+        assert_eq!(feed.items.len(), 1);
+        assert_eq!(feed.items[0].link, "https://blog.example/post");
+        assert_eq!(
+            feed.items[0].published_at,
+            parse_feed_date("2024-03-01T10:00:00Z")
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_non_feed() {
+        assert!(parse_feed("<html><body>nope</body></html>").is_err());
+    }
+
+    #[test]
+    fn test_merge_dedupes() {
+        let mut store = RssStore::default();
+        let parsed = parse_feed(RSS_SAMPLE).unwrap();
+        assert_eq!(merge_items(&mut store, "f1", &parsed, 1_000), 2);
+        assert_eq!(merge_items(&mut store, "f1", &parsed, 2_000), 0);
+        assert_eq!(merge_items(&mut store, "f2", &parsed, 2_000), 2);
+        assert_eq!(store.items.len(), 4);
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let now = DateTime::parse_from_rfc3339("2024-05-10T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let yesterday = parse_since("yesterday", now, None).unwrap();
+        assert_eq!(yesterday, parse_feed_date("2024-05-09T00:00:00Z").unwrap());
+        let shanghai: Tz = "Asia/Shanghai".parse().unwrap();
+        assert_eq!(
+            parse_since("today", now, Some(shanghai)).unwrap(),
+            parse_feed_date("2024-05-09T16:00:00Z").unwrap()
+        );
+        assert_eq!(
+            parse_since("12h", now, None).unwrap(),
+            now.timestamp_millis() - 12 * 3_600_000
+        );
+        assert!(parse_since("2024-05-01", now, None).is_some());
+        assert!(parse_since("whenever", now, None).is_none());
+    }
+
+    #[test]
+    fn test_select_items_filters() {
+        let mut store = RssStore::default();
+        store.feeds.push(Feed {
+            id: "f1".to_string(),
+            url: "https://example.com/rss".to_string(),
+            title: "Example".to_string(),
+            tags: vec!["tech".to_string()],
+            enabled: true,
+            refresh_interval_mins: 60,
+            created_at: 0,
+            last_fetched_at: None,
+            last_error: None,
+            etag: None,
+            last_modified: None,
+        });
+        let parsed = parse_feed(RSS_SAMPLE).unwrap();
+        let now_ms = Utc::now().timestamp_millis();
+        merge_items(&mut store, "f1", &parsed, now_ms);
+
+        // Item 1 has an old pubDate; item 2 falls back to first_seen_at.
+        let recent = select_items(
+            &store,
+            &ItemFilter {
+                since_ms: now_ms - 1000,
+                feed_id: None,
+                tag: Some("TECH"),
+                keyword: None,
+                limit: 10,
+            },
+        );
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].title, "Second");
+
+        let by_keyword = select_items(
+            &store,
+            &ItemFilter {
+                since_ms: 0,
+                feed_id: Some("f"),
+                tag: None,
+                keyword: Some("world".to_string()),
+                limit: 10,
+            },
+        );
+        assert_eq!(by_keyword.len(), 1);
+
+        let other_tag = select_items(
+            &store,
+            &ItemFilter {
+                since_ms: 0,
+                feed_id: None,
+                tag: Some("sports"),
+                keyword: None,
+                limit: 10,
+            },
+        );
+        assert!(other_tag.is_empty());
+    }
+
+    #[test]
+    fn test_is_due() {
+        let mut feed = Feed {
+            id: "f".to_string(),
+            url: String::new(),
+            title: String::new(),
+            tags: vec![],
+            enabled: true,
+            refresh_interval_mins: 1,
+            created_at: 0,
+            last_fetched_at: None,
+            last_error: None,
+            etag: None,
+            last_modified: None,
+        };
+        assert!(is_due(&feed, 0));
+        feed.last_fetched_at = Some(0);
+        // Interval is clamped to the minimum.
+        assert!(!is_due(&feed, 5 * 60_000));
+        assert!(is_due(&feed, MIN_REFRESH_MINS as i64 * 60_000));
+    }
+}
@@ -0,0 +1,346 @@
+//! Sandboxed launch of `exec` commands and `python` venv runs (`tools.exec.sandbox`).
+//!
+//! Backends differ only in what wraps the program. Sandboxed programs only see the
+//! workspace (read-only for `ReadOnly` callers) and run under the configured CPU, memory
+//! and process limits. The wall-clock limit stays with the caller.
+
+use std::path::{Path, PathBuf};
+
+use blockcell_core::config::{ExecSandboxConfig, SandboxBackend};
+use blockcell_core::{Error, PrivilegeLevel, Result};
+use tokio::process::Command;
+
+use crate::ToolContext;
+
+/// Channels that run without a human watching; they never get `Full`.
+const UNATTENDED_CHANNELS: &[&str] = &["cron", "heartbeat", "ghost", "subagent"];
+
+/// Host directories the `namespaces` backend exposes read-only.
+const SYSTEM_DIRS: &[&str] = &["/bin", "/sbin", "/lib", "/lib64", "/etc"];
+
+/// Privilege an `exec` or `python` call from this context runs with.
+pub fn exec_privilege(ctx: &ToolContext) -> PrivilegeLevel {
+    let level = ctx.role().privilege_level();
+    if UNATTENDED_CHANNELS.contains(&ctx.channel.as_str()) {
+        level.min(PrivilegeLevel::Limited)
+    } else {
+        level
+    }
+}
+
+pub struct SandboxSpec<'a> {
+    pub backend: SandboxBackend,
+    pub config: &'a ExecSandboxConfig,
+    pub privilege: PrivilegeLevel,
+    pub workspace: &'a Path,
+    pub working_dir: &'a Path,
+    /// Refuse working directories outside the workspace even without a sandbox.
+    pub restrict_to_workspace: bool,
+    pub timeout_secs: u64,
+}
+
+pub struct SandboxedCommand {
+    pub command: Command,
+    /// Docker container to remove if the command times out (killing the client
+    /// does not stop it).
+    pub container: Option<String>,
+}
+
+/// Build the command that runs the shell `command` as `spec` asks.
+pub fn build(spec: &SandboxSpec<'_>, command: &str) -> Result<SandboxedCommand> {
+    wrap(
+        spec,
+        &["sh".to_string(), "-c".to_string(), command.to_string()],
+    )
+}
+
+/// Build the command that runs `program` (program path and arguments) as `spec` asks.
+pub fn wrap(spec: &SandboxSpec<'_>, program: &[String]) -> Result<SandboxedCommand> {
+    if spec.backend == SandboxBackend::None {
+        if spec.restrict_to_workspace {
+            confine(spec.workspace, spec.working_dir)?;
+        }
+        let mut cmd = Command::new(&program[0]);
+        cmd.args(&program[1..]).current_dir(spec.working_dir);
+        return Ok(SandboxedCommand {
+            command: cmd,
+            container: None,
+        });
+    }
+
+    let (workspace, working_dir) = confine(spec.workspace, spec.working_dir)?;
+    let container = (spec.backend == SandboxBackend::Docker)
+        .then(|| format!("blockcell-exec-{}", uuid::Uuid::new_v4().simple()));
+    let argv = sandbox_argv(
+        spec,
+        &workspace,
+        &working_dir,
+        workspace_owner(&workspace).as_deref(),
+        container.as_deref(),
+        program,
+    );
+    // Resolved here so callers may clear the child's environment.
+    let launcher = which::which(&argv[0]).map_err(|_| {
+        Error::Tool(format!(
+            "Sandbox backend '{}' needs `{}` on PATH",
+            spec.backend.as_str(),
+            argv[0]
+        ))
+    })?;
+    let mut cmd = Command::new(launcher);
+    cmd.args(&argv[1..]).current_dir(&working_dir);
+    Ok(SandboxedCommand {
+        command: cmd,
+        container,
+    })
+}
+
+/// Canonical workspace and working directory; fails if the latter is outside the former.
+fn confine(workspace: &Path, working_dir: &Path) -> Result<(PathBuf, PathBuf)> {
+    let workspace = workspace
+        .canonicalize()
+        .map_err(|e| Error::Tool(format!("Workspace {}: {}", workspace.display(), e)))?;
+    let dir = working_dir.canonicalize().map_err(|e| {
+        Error::Tool(format!(
+            "Working directory {}: {}",
+            working_dir.display(),
+            e
+        ))
+    })?;
+    if !dir.starts_with(&workspace) {
+        return Err(Error::PermissionDenied(format!(
+            "Working directory {} is outside the workspace",
+            working_dir.display()
+        )));
+    }
+    Ok((workspace, dir))
+}
+
+/// `uid:gid` owning the workspace, so files a container writes stay editable.
+#[cfg(unix)]
+fn workspace_owner(workspace: &Path) -> Option<String> {
+    use std::os::unix::fs::MetadataExt;
+    let meta = std::fs::metadata(workspace).ok()?;
+    Some(format!("{}:{}", meta.uid(), meta.gid()))
+}
+
+#[cfg(not(unix))]
+fn workspace_owner(_workspace: &Path) -> Option<String> {
+    None
+}
+
+fn sandbox_argv(
+    spec: &SandboxSpec<'_>,
+    workspace: &Path,
+    working_dir: &Path,
+    owner: Option<&str>,
+    container: Option<&str>,
+    program: &[String],
+) -> Vec<String> {
+    let cfg = spec.config;
+    let read_only = spec.privilege == PrivilegeLevel::ReadOnly;
+    let ws = workspace.display().to_string();
+    let wd = working_dir.display().to_string();
+    let mut argv: Vec<String> = Vec::new();
+    let mut push = |args: &[&str]| argv.extend(args.iter().map(|a| a.to_string()));
+
+    match spec.backend {
+        SandboxBackend::None => {}
+        SandboxBackend::Docker => {
+            let memory = format!("{}m", cfg.memory_mb);
+            push(&["docker", "run", "--rm", "--init"]);
+            if let Some(name) = container {
+                push(&["--name", name]);
+            }
+            if !cfg.network {
+                push(&["--network", "none"]);
+            }
+            push(&[
+                "--cpus",
+                &cfg.cpus.to_string(),
+                "--memory",
+                &memory,
+                "--memory-swap",
+                &memory,
+                "--pids-limit",
+                &cfg.max_processes.to_string(),
+                "--cap-drop",
+                "ALL",
+                "--security-opt",
+                "no-new-privileges",
+                "--read-only",
+                "--tmpfs",
+                "/tmp",
+                "-e",
+                "HOME=/tmp",
+            ]);
+            if let Some(owner) = owner {
+                push(&["--user", owner]);
+            }
+            let mount = if read_only {
+                format!("{}:{}:ro", ws, ws)
+            } else {
+                format!("{}:{}", ws, ws)
+            };
+            push(&["-v", &mount, "-w", &wd, &cfg.image]);
+        }
+        SandboxBackend::Namespaces => {
+            push(&[
+                "bwrap",
+                "--die-with-parent",
+                "--new-session",
+                "--unshare-all",
+            ]);
+            if cfg.network {
+                push(&["--share-net"]);
+            }
+            push(&["--ro-bind", "/usr", "/usr"]);
+            for dir in SYSTEM_DIRS {
+                push(&["--ro-bind-try", *dir, *dir]);
+            }
+            push(&["--proc", "/proc", "--dev", "/dev", "--tmpfs", "/tmp"]);
+            push(&[if read_only { "--ro-bind" } else { "--bind" }, &ws, &ws]);
+            // bwrap has no resource limits of its own; a shell sets rlimits and then
+            // execs the program, which is passed as arguments rather than spliced in.
+            let mut prelude = String::new();
+            if spec.timeout_secs > 0 {
+                prelude.push_str(&format!("ulimit -t {} 2>/dev/null; ", spec.timeout_secs));
+            }
+            prelude.push_str(&format!(
+                "ulimit -v {} 2>/dev/null; ulimit -u {} 2>/dev/null; exec \"$@\"",
+                cfg.memory_mb * 1024,
+                cfg.max_processes
+            ));
+            push(&["--chdir", &wd, "sh", "-c", &prelude, "sh"]);
+        }
+        SandboxBackend::Firejail => {
+            push(&[
+                "firejail",
+                "--quiet",
+                "--noprofile",
+                "--caps.drop=all",
+                "--nonewprivs",
+                "--seccomp",
+                "--private-tmp",
+                "--private-dev",
+            ]);
+            if !cfg.network {
+                push(&["--net=none"]);
+            }
+            push(&[&format!("--whitelist={}", ws)]);
+            if read_only {
+                push(&[&format!("--read-only={}", ws)]);
+            }
+            push(&[
+                &format!("--rlimit-as={}", cfg.memory_mb * 1024 * 1024),
+                &format!("--rlimit-nproc={}", cfg.max_processes),
+            ]);
+            if spec.timeout_secs > 0 {
+                push(&[&format!("--rlimit-cpu={}", spec.timeout_secs)]);
+            }
+        }
+    }
+    argv.extend(program.iter().cloned());
+    argv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec<'a>(
+        backend: SandboxBackend,
+        config: &'a ExecSandboxConfig,
+        privilege: PrivilegeLevel,
+        dir: &'a Path,
+    ) -> SandboxSpec<'a> {
+        SandboxSpec {
+            backend,
+            config,
+            privilege,
+            workspace: dir,
+            working_dir: dir,
+            restrict_to_workspace: false,
+            timeout_secs: 30,
+        }
+    }
+
+    fn shell(command: &str) -> Vec<String> {
+        vec!["sh".to_string(), "-c".to_string(), command.to_string()]
+    }
+
+    #[test]
+    fn test_backends_mount_only_the_workspace_with_limits() {
+        let config = ExecSandboxConfig::default();
+        let ws = Path::new("/srv/ws");
+        let wd = Path::new("/srv/ws/src");
+
+        let docker = spec(
+            SandboxBackend::Docker,
+            &config,
+            PrivilegeLevel::ReadOnly,
+            ws,
+        );
+        let argv = sandbox_argv(&docker, ws, wd, Some("1000:1000"), Some("c1"), &shell("ls"));
+        let joined = argv.join(" ");
+        assert!(joined.starts_with("docker run --rm --init --name c1 --network none"));
+        assert!(joined.contains("--cpus 1 --memory 512m"));
+        assert!(joined.contains("--user 1000:1000"));
+        assert!(joined.contains("-v /srv/ws:/srv/ws:ro -w /srv/ws/src debian:bookworm-slim"));
+        assert_eq!(argv.last().unwrap(), "ls");
+
+        let bwrap = spec(
+            SandboxBackend::Namespaces,
+            &config,
+            PrivilegeLevel::Limited,
+            ws,
+        );
+        let argv = sandbox_argv(&bwrap, ws, wd, None, None, &shell("echo $HOME"));
+        let joined = argv.join(" ");
+        assert!(!joined.contains("--share-net"));
+        assert!(joined.contains("--bind /srv/ws /srv/ws --chdir /srv/ws/src"));
+        assert!(joined.contains("ulimit -v 524288"));
+        // The command is passed as an argument, never spliced into the script.
+        assert_eq!(argv.last().unwrap(), "echo $HOME");
+
+        let firejail = spec(
+            SandboxBackend::Firejail,
+            &config,
+            PrivilegeLevel::ReadOnly,
+            ws,
+        );
+        let joined = sandbox_argv(&firejail, ws, wd, None, None, &shell("ls")).join(" ");
+        assert!(joined.contains("--net=none --whitelist=/srv/ws --read-only=/srv/ws"));
+        assert!(joined.contains("--rlimit-cpu=30"));
+    }
+
+    #[test]
+    fn test_sandboxed_working_dir_must_be_in_workspace() {
+        let base = std::env::temp_dir().join(format!("blockcell-sandbox-{}", uuid::Uuid::new_v4()));
+        let ws = base.join("workspace");
+        std::fs::create_dir_all(ws.join("sub")).unwrap();
+        let config = ExecSandboxConfig::default();
+
+        let sub = ws.join("sub");
+        let mut inside = spec(SandboxBackend::None, &config, PrivilegeLevel::Full, &ws);
+        inside.working_dir = &sub;
+        inside.restrict_to_workspace = true;
+        assert!(build(&inside, "true").is_ok());
+
+        let mut outside = spec(SandboxBackend::None, &config, PrivilegeLevel::Full, &ws);
+        outside.working_dir = &base;
+        assert!(build(&outside, "true").is_ok());
+        outside.restrict_to_workspace = true;
+        assert!(matches!(
+            build(&outside, "true"),
+            Err(Error::PermissionDenied(_))
+        ));
+        outside.backend = SandboxBackend::Docker;
+        outside.restrict_to_workspace = false;
+        assert!(matches!(
+            build(&outside, "true"),
+            Err(Error::PermissionDenied(_))
+        ));
+        let _ = std::fs::remove_dir_all(base);
+    }
+}
@@ -0,0 +1,1782 @@
+//! Spreadsheet evaluation helpers: load xlsx/xls workbooks, evaluate formulas
+//! (so cells written by openpyxl without cached results still yield values),
+//! and build pivot-style aggregations over sheet data.
+
+use blockcell_core::{Error, Result};
+use calamine::{open_workbook_auto, Data, Reader};
+use serde_json::{json, Value};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Computed cell value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellValue {
+    Empty,
+    Number(f64),
+    Text(String),
+    Bool(bool),
+    /// Excel date serial plus its display form.
+    Date(f64, String),
+    /// Excel error literal, e.g. `#DIV/0!`.
+    Error(String),
+}
+
+impl CellValue {
+    fn error(code: &str) -> Self {
+        CellValue::Error(code.to_string())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        matches!(self, CellValue::Empty)
+    }
+
+    pub fn display(&self) -> String {
+        match self {
+            CellValue::Empty => String::new(),
+            CellValue::Number(n) => format_number(*n),
+            CellValue::Text(s) => s.clone(),
+            CellValue::Bool(b) => if *b { "TRUE" } else { "FALSE" }.to_string(),
+            CellValue::Date(_, s) => s.clone(),
+            CellValue::Error(e) => e.clone(),
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        match self {
+            CellValue::Empty => Value::Null,
+            CellValue::Number(n) => {
+                if n.fract() == 0.0 && n.abs() < 9.0e15 {
+                    json!(*n as i64)
+                } else {
+                    json!(round_to(*n, 10))
+                }
+            }
+            CellValue::Text(s) => json!(s),
+            CellValue::Bool(b) => json!(b),
+            CellValue::Date(_, s) => json!(s),
+            CellValue::Error(e) => json!(e),
+        }
+    }
+
+    /// Numeric view used by aggregates over ranges (text and blanks are skipped).
+    fn as_range_number(&self) -> Option<f64> {
+        match self {
+            CellValue::Number(n) | CellValue::Date(n, _) => Some(*n),
+            _ => None,
+        }
+    }
+}
+
+fn format_number(n: f64) -> String {
+    if n.fract() == 0.0 && n.abs() < 9.0e15 {
+        format!("{}", n as i64)
+    } else {
+        format!("{}", round_to(n, 10))
+    }
+}
+
+fn round_to(n: f64, digits: i32) -> f64 {
+    let factor = 10f64.powi(digits);
+    (n * factor).round() / factor
+}
+
+fn from_data(cell: &Data) -> CellValue {
+    match cell {
+        Data::Empty => CellValue::Empty,
+        Data::String(s) if s.is_empty() => CellValue::Empty,
+        Data::String(s) => CellValue::Text(s.clone()),
+        Data::Float(f) => CellValue::Number(*f),
+        Data::Int(i) => CellValue::Number(*i as f64),
+        Data::Bool(b) => CellValue::Bool(*b),
+        Data::DateTime(dt) => CellValue::Date(dt.as_f64(), format!("{}", dt)),
+        Data::DateTimeIso(s) | Data::DurationIso(s) => CellValue::Text(s.clone()),
+        Data::Error(e) => CellValue::Error(format!("{}", e)),
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Workbook
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct Sheet {
+    pub name: String,
+    /// Cached values keyed by 0-based (row, col).
+    values: HashMap<(u32, u32), CellValue>,
+    /// Formula text (without leading '=') keyed by 0-based (row, col).
+    formulas: HashMap<(u32, u32), String>,
+}
+
+impl Sheet {
+    /// Bounding box of used cells: (min_row, min_col, max_row, max_col).
+    pub fn bounds(&self) -> Option<(u32, u32, u32, u32)> {
+        let mut keys = self
+            .values
+            .iter()
+            .filter(|(_, v)| !v.is_empty())
+            .map(|(k, _)| k)
+            .chain(self.formulas.keys())
+            .peekable();
+        keys.peek()?;
+        let (mut r0, mut c0, mut r1, mut c1) = (u32::MAX, u32::MAX, 0, 0);
+        for &(r, c) in keys {
+            r0 = r0.min(r);
+            c0 = c0.min(c);
+            r1 = r1.max(r);
+            c1 = c1.max(c);
+        }
+        Some((r0, c0, r1, c1))
+    }
+
+    pub fn formula_count(&self) -> usize {
+        self.formulas.len()
+    }
+
+    /// Formula cells in row-major order.
+    pub fn formula_cells(&self) -> Vec<((u32, u32), &str)> {
+        let mut cells: Vec<((u32, u32), &str)> = self
+            .formulas
+            .iter()
+            .map(|(k, f)| (*k, f.as_str()))
+            .collect();
+        cells.sort_by_key(|(k, _)| *k);
+        cells
+    }
+}
+
+pub struct Workbook {
+    pub sheets: Vec<Sheet>,
+}
+
+impl Workbook {
+    pub fn open(path: &Path) -> Result<Self> {
+        let mut workbook = open_workbook_auto(path)
+            .map_err(|e| Error::Tool(format!("Failed to open Excel file: {}", e)))?;
+        let names = workbook.sheet_names().to_vec();
+        let mut sheets = Vec::with_capacity(names.len());
+        for name in names {
+            let mut values = HashMap::new();
+            if let Ok(range) = workbook.worksheet_range(&name) {
+                let (r0, c0) = range.start().unwrap_or((0, 0));
+                for (r, c, cell) in range.cells() {
+                    let v = from_data(cell);
+                    if !v.is_empty() {
+                        values.insert((r0 + r as u32, c0 + c as u32), v);
+                    }
+                }
+            }
+            let mut formulas = HashMap::new();
+            if let Ok(range) = workbook.worksheet_formula(&name) {
+                let (r0, c0) = range.start().unwrap_or((0, 0));
+                for (r, c, f) in range.cells() {
+                    let f = f.trim();
+                    let f = f.strip_prefix('=').unwrap_or(f);
+                    if !f.is_empty() {
+                        formulas.insert((r0 + r as u32, c0 + c as u32), f.to_string());
+                    }
+                }
+            }
+            sheets.push(Sheet {
+                name,
+                values,
+                formulas,
+            });
+        }
+        Ok(Self { sheets })
+    }
+
+    /// Resolve a sheet by name (case-insensitive); `None` selects the first sheet.
+    pub fn sheet_index(&self, name: Option<&str>) -> Result<usize> {
+        match name.map(str::trim).filter(|n| !n.is_empty()) {
+            None if !self.sheets.is_empty() => Ok(0),
+            None => Err(Error::Tool("Workbook has no sheets".to_string())),
+            Some(n) => self
+                .sheets
+                .iter()
+                .position(|s| s.name.eq_ignore_ascii_case(n))
+                .ok_or_else(|| {
+                    let names: Vec<&str> = self.sheets.iter().map(|s| s.name.as_str()).collect();
+                    Error::Tool(format!(
+                        "Sheet '{}' not found. Available: {}",
+                        n,
+                        names.join(", ")
+                    ))
+                }),
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// References
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub fn column_index(letters: &str) -> Option<u32> {
+    if letters.is_empty() || letters.len() > 3 {
+        return None;
+    }
+    let mut idx: u32 = 0;
+    for ch in letters.chars() {
+        if !ch.is_ascii_alphabetic() {
+            return None;
+        }
+        idx = idx * 26 + (ch.to_ascii_uppercase() as u32 - 'A' as u32 + 1);
+    }
+    Some(idx - 1)
+}
+
+pub fn column_name(mut col: u32) -> String {
+    let mut name = Vec::new();
+    loop {
+        name.push((b'A' + (col % 26) as u8) as char);
+        if col < 26 {
+            break;
+        }
+        col = col / 26 - 1;
+    }
+    name.iter().rev().collect()
+}
+
+/// `"B14"` / `"$B$14"` → 0-based (row, col).
+pub fn parse_cell_ref(s: &str) -> Option<(u32, u32)> {
+    let s = s.trim().replace('$', "");
+    let split = s.find(|c: char| c.is_ascii_digit())?;
+    let (letters, digits) = s.split_at(split);
+    let col = column_index(letters)?;
+    let row: u32 = digits.parse().ok()?;
+    if row == 0 {
+        return None;
+    }
+    Some((row - 1, col))
+}
+
+pub fn cell_name(row: u32, col: u32) -> String {
+    format!("{}{}", column_name(col), row + 1)
+}
+
+/// `"A1:D20"` → normalized ((r0, c0), (r1, c1)); a single cell is a 1×1 range.
+pub fn parse_range_ref(s: &str) -> Option<((u32, u32), (u32, u32))> {
+    let (a, b) = match s.split_once(':') {
+        Some((a, b)) => (parse_cell_ref(a)?, parse_cell_ref(b)?),
+        None => {
+            let c = parse_cell_ref(s)?;
+            (c, c)
+        }
+    };
+    Some(((a.0.min(b.0), a.1.min(b.1)), (a.0.max(b.0), a.1.max(b.1))))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Formula parser
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(f64),
+    Str(String),
+    Bool(bool),
+    Cell(u32, u32),
+    Column(u32),
+    Ident(String),
+    Sheet(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+    Comma,
+    Colon,
+}
+
+fn tokenize(src: &str) -> std::result::Result<Vec<Token>, String> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit()))
+        {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                let mut j = i + 1;
+                if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+                    j += 1;
+                }
+                if j < chars.len() && chars[j].is_ascii_digit() {
+                    i = j;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            let text: String = chars[start..i].iter().collect();
+            let n = text
+                .parse::<f64>()
+                .map_err(|_| format!("bad number '{}'", text))?;
+            tokens.push(Token::Num(n));
+            continue;
+        }
+        match c {
+            '"' => {
+                let mut s = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => return Err("unterminated string".to_string()),
+                        Some('"') if chars.get(i + 1) == Some(&'"') => {
+                            s.push('"');
+                            i += 2;
+                        }
+                        Some('"') => {
+                            i += 1;
+                            break;
+                        }
+                        Some(ch) => {
+                            s.push(*ch);
+                            i += 1;
+                        }
+                    }
+                }
+                tokens.push(Token::Str(s));
+            }
+            '\'' => {
+                let mut s = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => return Err("unterminated sheet name".to_string()),
+                        Some('\'') if chars.get(i + 1) == Some(&'\'') => {
+                            s.push('\'');
+                            i += 2;
+                        }
+                        Some('\'') => {
+                            i += 1;
+                            break;
+                        }
+                        Some(ch) => {
+                            s.push(*ch);
+                            i += 1;
+                        }
+                    }
+                }
+                if chars.get(i) != Some(&'!') {
+                    return Err("expected '!' after sheet name".to_string());
+                }
+                i += 1;
+                tokens.push(Token::Sheet(s));
+            }
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            ',' | ';' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            ':' => {
+                tokens.push(Token::Colon);
+                i += 1;
+            }
+            '+' | '-' | '*' | '/' | '^' | '&' | '%' | '=' => {
+                let op = match c {
+                    '+' => "+",
+                    '-' => "-",
+                    '*' => "*",
+                    '/' => "/",
+                    '^' => "^",
+                    '&' => "&",
+                    '%' => "%",
+                    _ => "=",
+                };
+                tokens.push(Token::Op(op));
+                i += 1;
+            }
+            '<' | '>' => {
+                let next = chars.get(i + 1).copied();
+                let op = match (c, next) {
+                    ('<', Some('=')) => "<=",
+                    ('<', Some('>')) => "<>",
+                    ('>', Some('=')) => ">=",
+                    ('<', _) => "<",
+                    _ => ">",
+                };
+                i += op.len();
+                tokens.push(Token::Op(op));
+            }
+            _ if c.is_alphabetic() || c == '_' || c == '$' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '.' | '$'))
+                {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                if chars.get(i) == Some(&'!') {
+                    i += 1;
+                    tokens.push(Token::Sheet(word));
+                } else if chars.get(i) == Some(&'(') {
+                    tokens.push(Token::Ident(word.to_ascii_uppercase()));
+                } else if let Some((r, c)) = parse_cell_ref(&word) {
+                    tokens.push(Token::Cell(r, c));
+                } else if let Some(col) = column_index(&word.replace('$', ""))
+                    .filter(|_| chars.get(i) == Some(&':') || tokens.last() == Some(&Token::Colon))
+                {
+                    tokens.push(Token::Column(col));
+                } else {
+                    match word.to_ascii_uppercase().as_str() {
+                        "TRUE" => tokens.push(Token::Bool(true)),
+                        "FALSE" => tokens.push(Token::Bool(false)),
+                        _ => tokens.push(Token::Ident(word.to_ascii_uppercase())),
+                    }
+                }
+            }
+            _ => return Err(format!("unexpected character '{}'", c)),
+        }
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Num(f64),
+    Str(String),
+    Bool(bool),
+    Ref {
+        sheet: Option<String>,
+        row: u32,
+        col: u32,
+    },
+    /// Inclusive range; whole-column ranges use `u32::MAX` as the last row.
+    Range {
+        sheet: Option<String>,
+        r0: u32,
+        c0: u32,
+        r1: u32,
+        c1: u32,
+    },
+    /// An identifier that is not a function call; defined names are not supported.
+    Name,
+    Neg(Box<Expr>),
+    Percent(Box<Expr>),
+    Binary(&'static str, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let t = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        t
+    }
+
+    fn peek_op(&self, ops: &[&str]) -> Option<&'static str> {
+        match self.peek() {
+            Some(Token::Op(op)) if ops.contains(op) => Some(op),
+            _ => None,
+        }
+    }
+
+    fn expr(&mut self) -> std::result::Result<Expr, String> {
+        let mut left = self.concat()?;
+        while let Some(op) = self.peek_op(&["=", "<>", "<", ">", "<=", ">="]) {
+            self.pos += 1;
+            let right = self.concat()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn concat(&mut self) -> std::result::Result<Expr, String> {
+        let mut left = self.additive()?;
+        while self.peek_op(&["&"]).is_some() {
+            self.pos += 1;
+            let right = self.additive()?;
+            left = Expr::Binary("&", Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn additive(&mut self) -> std::result::Result<Expr, String> {
+        let mut left = self.multiplicative()?;
+        while let Some(op) = self.peek_op(&["+", "-"]) {
+            self.pos += 1;
+            let right = self.multiplicative()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn multiplicative(&mut self) -> std::result::Result<Expr, String> {
+        let mut left = self.power()?;
+        while let Some(op) = self.peek_op(&["*", "/"]) {
+            self.pos += 1;
+            let right = self.power()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn power(&mut self) -> std::result::Result<Expr, String> {
+        let mut left = self.unary()?;
+        while self.peek_op(&["^"]).is_some() {
+            self.pos += 1;
+            let right = self.unary()?;
+            left = Expr::Binary("^", Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    // Excel binds unary minus tighter than '^': -2^2 = 4.
+    fn unary(&mut self) -> std::result::Result<Expr, String> {
+        match self.peek_op(&["-", "+"]) {
+            Some("-") => {
+                self.pos += 1;
+                Ok(Expr::Neg(Box::new(self.unary()?)))
+            }
+            Some(_) => {
+                self.pos += 1;
+                self.unary()
+            }
+            None => self.postfix(),
+        }
+    }
+
+    fn postfix(&mut self) -> std::result::Result<Expr, String> {
+        let mut e = self.primary()?;
+        while self.peek_op(&["%"]).is_some() {
+            self.pos += 1;
+            e = Expr::Percent(Box::new(e));
+        }
+        Ok(e)
+    }
+
+    fn primary(&mut self) -> std::result::Result<Expr, String> {
+        match self.next() {
+            Some(Token::Num(n)) => Ok(Expr::Num(n)),
+            Some(Token::Str(s)) => Ok(Expr::Str(s)),
+            Some(Token::Bool(b)) => Ok(Expr::Bool(b)),
+            Some(Token::LParen) => {
+                let e = self.expr()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(e),
+                    _ => Err("expected ')'".to_string()),
+                }
+            }
+            Some(Token::Sheet(name)) => self.reference(Some(name)),
+            Some(Token::Cell(..)) | Some(Token::Column(_)) => {
+                self.pos -= 1;
+                self.reference(None)
+            }
+            Some(Token::Ident(name)) => {
+                if self.peek() != Some(&Token::LParen) {
+                    return Ok(Expr::Name);
+                }
+                self.pos += 1;
+                let mut args = Vec::new();
+                if self.peek() == Some(&Token::RParen) {
+                    self.pos += 1;
+                    return Ok(Expr::Call(name, args));
+                }
+                loop {
+                    // Empty argument, e.g. IF(A1,,1)
+                    if matches!(self.peek(), Some(Token::Comma) | Some(Token::RParen)) {
+                        args.push(Expr::Str(String::new()));
+                    } else {
+                        args.push(self.expr()?);
+                    }
+                    match self.next() {
+                        Some(Token::Comma) => continue,
+                        Some(Token::RParen) => break,
+                        _ => return Err(format!("expected ',' or ')' in {}()", name)),
+                    }
+                }
+                Ok(Expr::Call(name, args))
+            }
+            Some(t) => Err(format!("unexpected token {:?}", t)),
+            None => Err("unexpected end of formula".to_string()),
+        }
+    }
+
+    fn reference(&mut self, sheet: Option<String>) -> std::result::Result<Expr, String> {
+        let start = match self.next() {
+            Some(Token::Cell(r, c)) => (Some(r), c),
+            Some(Token::Column(c)) => (None, c),
+            _ => return Err("expected cell reference".to_string()),
+        };
+        if self.peek() != Some(&Token::Colon) {
+            return match start {
+                (Some(row), col) => Ok(Expr::Ref { sheet, row, col }),
+                (None, _) => Err("column reference needs a range, e.g. A:A".to_string()),
+            };
+        }
+        self.pos += 1;
+        let end = match self.next() {
+            Some(Token::Cell(r, c)) => (Some(r), c),
+            Some(Token::Column(c)) => (None, c),
+            _ => return Err("expected range end".to_string()),
+        };
+        let (r0, r1) = match (start.0, end.0) {
+            (Some(a), Some(b)) => (a.min(b), a.max(b)),
+            _ => (0, u32::MAX),
+        };
+        Ok(Expr::Range {
+            sheet,
+            r0,
+            c0: start.1.min(end.1),
+            r1,
+            c1: start.1.max(end.1),
+        })
+    }
+}
+
+fn parse_formula(src: &str) -> std::result::Result<Expr, String> {
+    let src = src.trim();
+    let src = src.strip_prefix('=').unwrap_or(src);
+    let mut parser = Parser {
+        tokens: tokenize(src)?,
+        pos: 0,
+    };
+    let expr = parser.expr()?;
+    if parser.pos < parser.tokens.len() {
+        return Err(format!(
+            "unexpected trailing token {:?}",
+            parser.tokens[parser.pos]
+        ));
+    }
+    Ok(expr)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Evaluator
+// ─────────────────────────────────────────────────────────────────────────────
+
+enum Val {
+    Scalar(CellValue),
+    /// Row-major cells of a rectangular range.
+    Range(Vec<Vec<CellValue>>),
+}
+
+impl Val {
+    fn scalar(self) -> CellValue {
+        match self {
+            Val::Scalar(v) => v,
+            Val::Range(rows) => {
+                if rows.len() == 1 && rows[0].len() == 1 {
+                    rows[0][0].clone()
+                } else {
+                    CellValue::error("#VALUE!")
+                }
+            }
+        }
+    }
+
+    fn flatten(self) -> Vec<CellValue> {
+        match self {
+            Val::Scalar(v) => vec![v],
+            Val::Range(rows) => rows.into_iter().flatten().collect(),
+        }
+    }
+}
+
+/// Early-return the error if the value is an Excel error.
+macro_rules! propagate {
+    ($v:expr) => {
+        match $v {
+            CellValue::Error(e) => return CellValue::Error(e),
+            other => other,
+        }
+    };
+}
+
+fn to_number(v: &CellValue) -> std::result::Result<f64, CellValue> {
+    match v {
+        CellValue::Number(n) | CellValue::Date(n, _) => Ok(*n),
+        CellValue::Bool(b) => Ok(if *b { 1.0 } else { 0.0 }),
+        CellValue::Empty => Ok(0.0),
+        CellValue::Text(s) => {
+            let t = s.trim().replace(',', "");
+            if let Some(p) = t.strip_suffix('%') {
+                p.trim()
+                    .parse::<f64>()
+                    .map(|n| n / 100.0)
+                    .map_err(|_| CellValue::error("#VALUE!"))
+            } else {
+                t.parse::<f64>().map_err(|_| CellValue::error("#VALUE!"))
+            }
+        }
+        CellValue::Error(e) => Err(CellValue::Error(e.clone())),
+    }
+}
+
+fn to_bool(v: &CellValue) -> std::result::Result<bool, CellValue> {
+    match v {
+        CellValue::Bool(b) => Ok(*b),
+        CellValue::Text(s) if s.eq_ignore_ascii_case("true") => Ok(true),
+        CellValue::Text(s) if s.eq_ignore_ascii_case("false") => Ok(false),
+        other => to_number(other).map(|n| n != 0.0),
+    }
+}
+
+fn compare(a: &CellValue, b: &CellValue) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+    let is_text = |v: &CellValue| matches!(v, CellValue::Text(_));
+    match (is_text(a), is_text(b)) {
+        (true, true) => a.display().to_lowercase().cmp(&b.display().to_lowercase()),
+        // Blank compares as "" against text and as 0 against numbers.
+        (true, false) if b.is_empty() => a.display().to_lowercase().cmp(&String::new()),
+        (false, true) if a.is_empty() => String::new().cmp(&b.display().to_lowercase()),
+        // Excel orders numbers before text.
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => {
+            let x = to_number(a).unwrap_or(0.0);
+            let y = to_number(b).unwrap_or(0.0);
+            x.partial_cmp(&y).unwrap_or(Ordering::Equal)
+        }
+    }
+}
+
+/// Simple `*` / `?` wildcard match (case-insensitive), as used by SUMIF/COUNTIF.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.to_lowercase().chars().collect();
+    let t: Vec<char> = text.to_lowercase().chars().collect();
+    let (mut pi, mut ti) = (0, 0);
+    let (mut star, mut mark) = (None, 0);
+    while ti < t.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == t[ti]) {
+            pi += 1;
+            ti += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some(pi);
+            mark = ti;
+            pi += 1;
+        } else if let Some(s) = star {
+            pi = s + 1;
+            mark += 1;
+            ti = mark;
+        } else {
+            return false;
+        }
+    }
+    while pi < p.len() && p[pi] == '*' {
+        pi += 1;
+    }
+    pi == p.len()
+}
+
+/// Evaluate a SUMIF-style criterion (">100", "<>x", "app*", 5) against a cell.
+fn criteria_match(criteria: &CellValue, value: &CellValue) -> bool {
+    let (op, operand) = match criteria {
+        CellValue::Text(s) => {
+            let s = s.trim_start();
+            let (op, rest) = ["<=", ">=", "<>", "<", ">", "="]
+                .iter()
+                .find_map(|op| s.strip_prefix(op).map(|r| (*op, r)))
+                .unwrap_or(("=", s));
+            let operand = match rest.trim().parse::<f64>() {
+                Ok(n) => CellValue::Number(n),
+                Err(_) if rest.is_empty() => CellValue::Empty,
+                Err(_) => CellValue::Text(rest.to_string()),
+            };
+            (op, operand)
+        }
+        other => ("=", other.clone()),
+    };
+    if let (CellValue::Text(pat), "=" | "<>") = (&operand, op) {
+        if pat.contains(['*', '?']) {
+            let m = matches!(value, CellValue::Text(t) if wildcard_match(pat, t));
+            return if op == "=" { m } else { !m };
+        }
+    }
+    // Numeric criteria never match text cells (and vice versa) except for "<>".
+    let comparable = match (&operand, value) {
+        (CellValue::Number(_), CellValue::Text(_)) => false,
+        (CellValue::Text(_), CellValue::Number(_) | CellValue::Date(..)) => false,
+        (CellValue::Empty, v) => v.is_empty() || op == "<>",
+        _ => true,
+    };
+    if !comparable {
+        return op == "<>";
+    }
+    let ord = compare(value, &operand);
+    match op {
+        "=" => ord.is_eq(),
+        "<>" => !ord.is_eq(),
+        "<" => ord.is_lt(),
+        "<=" => ord.is_le(),
+        ">" => ord.is_gt(),
+        _ => ord.is_ge(),
+    }
+}
+
+pub struct Evaluator<'a> {
+    wb: &'a Workbook,
+    cache: RefCell<HashMap<(usize, u32, u32), CellValue>>,
+    visiting: RefCell<HashSet<(usize, u32, u32)>>,
+    evaluated: RefCell<usize>,
+}
+
+impl<'a> Evaluator<'a> {
+    pub fn new(wb: &'a Workbook) -> Self {
+        Self {
+            wb,
+            cache: RefCell::new(HashMap::new()),
+            visiting: RefCell::new(HashSet::new()),
+            evaluated: RefCell::new(0),
+        }
+    }
+
+    /// Number of formula cells computed locally (i.e. without a cached result).
+    pub fn evaluated_count(&self) -> usize {
+        *self.evaluated.borrow()
+    }
+
+    /// Computed value of a cell. Cached results saved by Excel are trusted;
+    /// formula cells without a cached result are evaluated.
+    pub fn cell(&self, sheet: usize, row: u32, col: u32) -> CellValue {
+        let Some(s) = self.wb.sheets.get(sheet) else {
+            return CellValue::error("#REF!");
+        };
+        let cached = s.values.get(&(row, col));
+        let Some(formula) = s.formulas.get(&(row, col)) else {
+            return cached.cloned().unwrap_or(CellValue::Empty);
+        };
+        if let Some(v) = cached {
+            return v.clone();
+        }
+        let key = (sheet, row, col);
+        if let Some(v) = self.cache.borrow().get(&key) {
+            return v.clone();
+        }
+        if !self.visiting.borrow_mut().insert(key) {
+            return CellValue::error("#CIRC!");
+        }
+        let value = self.eval_formula(sheet, formula);
+        self.visiting.borrow_mut().remove(&key);
+        *self.evaluated.borrow_mut() += 1;
+        self.cache.borrow_mut().insert(key, value.clone());
+        value
+    }
+
+    /// Evaluate an arbitrary formula (with or without leading '=') in the context of a sheet.
+    pub fn eval_formula(&self, sheet: usize, formula: &str) -> CellValue {
+        match parse_formula(formula) {
+            Ok(expr) => self.eval(sheet, &expr).scalar(),
+            Err(_) => CellValue::error("#NAME?"),
+        }
+    }
+
+    /// Computed rows within a rectangle (inclusive).
+    pub fn grid(&self, sheet: usize, from: (u32, u32), to: (u32, u32)) -> Vec<Vec<CellValue>> {
+        (from.0..=to.0)
+            .map(|r| (from.1..=to.1).map(|c| self.cell(sheet, r, c)).collect())
+            .collect()
+    }
+
+    fn resolve_sheet(&self, current: usize, name: &Option<String>) -> Option<usize> {
+        match name {
+            None => Some(current),
+            Some(n) => self
+                .wb
+                .sheets
+                .iter()
+                .position(|s| s.name.eq_ignore_ascii_case(n)),
+        }
+    }
+
+    fn eval(&self, sheet: usize, expr: &Expr) -> Val {
+        match expr {
+            Expr::Num(n) => Val::Scalar(CellValue::Number(*n)),
+            Expr::Str(s) => Val::Scalar(CellValue::Text(s.clone())),
+            Expr::Bool(b) => Val::Scalar(CellValue::Bool(*b)),
+            Expr::Name => Val::Scalar(CellValue::error("#NAME?")),
+            Expr::Ref {
+                sheet: name,
+                row,
+                col,
+            } => match self.resolve_sheet(sheet, name) {
+                Some(s) => Val::Scalar(self.cell(s, *row, *col)),
+                None => Val::Scalar(CellValue::error("#REF!")),
+            },
+            Expr::Range {
+                sheet: name,
+                r0,
+                c0,
+                r1,
+                c1,
+            } => {
+                let Some(s) = self.resolve_sheet(sheet, name) else {
+                    return Val::Scalar(CellValue::error("#REF!"));
+                };
+                let last_row = if *r1 == u32::MAX {
+                    self.wb.sheets[s].bounds().map(|b| b.2).unwrap_or(0)
+                } else {
+                    *r1
+                };
+                Val::Range(self.grid(s, (*r0, *c0), (last_row, *c1)))
+            }
+            Expr::Neg(e) => {
+                let v = self.eval(sheet, e).scalar();
+                Val::Scalar(match to_number(&v) {
+                    Ok(n) => CellValue::Number(-n),
+                    Err(e) => e,
+                })
+            }
+            Expr::Percent(e) => {
+                let v = self.eval(sheet, e).scalar();
+                Val::Scalar(match to_number(&v) {
+                    Ok(n) => CellValue::Number(n / 100.0),
+                    Err(e) => e,
+                })
+            }
+            Expr::Binary(op, l, r) => {
+                let a = self.eval(sheet, l).scalar();
+                let b = self.eval(sheet, r).scalar();
+                Val::Scalar(binary(op, a, b))
+            }
+            Expr::Call(name, args) => Val::Scalar(self.call(sheet, name, args)),
+        }
+    }
+
+    fn scalar_arg(&self, sheet: usize, args: &[Expr], i: usize) -> Option<CellValue> {
+        args.get(i).map(|e| self.eval(sheet, e).scalar())
+    }
+
+    fn number_arg(
+        &self,
+        sheet: usize,
+        args: &[Expr],
+        i: usize,
+        default: Option<f64>,
+    ) -> std::result::Result<f64, CellValue> {
+        match self.scalar_arg(sheet, args, i) {
+            Some(v) => to_number(&v),
+            None => default.ok_or_else(|| CellValue::error("#VALUE!")),
+        }
+    }
+
+    /// Numbers from all arguments: ranges contribute numeric cells only,
+    /// direct scalar arguments are coerced.
+    fn numbers(&self, sheet: usize, args: &[Expr]) -> std::result::Result<Vec<f64>, CellValue> {
+        let mut out = Vec::new();
+        for arg in args {
+            match self.eval(sheet, arg) {
+                Val::Range(rows) => {
+                    for v in rows.iter().flatten() {
+                        if let CellValue::Error(e) = v {
+                            return Err(CellValue::Error(e.clone()));
+                        }
+                        if let Some(n) = v.as_range_number() {
+                            out.push(n);
+                        }
+                    }
+                }
+                Val::Scalar(v) => out.push(to_number(&v)?),
+            }
+        }
+        Ok(out)
+    }
+
+    fn call(&self, sheet: usize, name: &str, args: &[Expr]) -> CellValue {
+        macro_rules! num {
+            ($i:expr) => {
+                match self.number_arg(sheet, args, $i, None) {
+                    Ok(n) => n,
+                    Err(e) => return e,
+                }
+            };
+            ($i:expr, $default:expr) => {
+                match self.number_arg(sheet, args, $i, Some($default)) {
+                    Ok(n) => n,
+                    Err(e) => return e,
+                }
+            };
+        }
+        macro_rules! nums {
+            () => {
+                match self.numbers(sheet, args) {
+                    Ok(v) => v,
+                    Err(e) => return e,
+                }
+            };
+        }
+        let text =
+            |i: usize| -> CellValue { self.scalar_arg(sheet, args, i).unwrap_or(CellValue::Empty) };
+
+        match name {
+            "SUM" => CellValue::Number(nums!().iter().sum()),
+            "PRODUCT" => CellValue::Number(nums!().iter().product()),
+            "AVERAGE" => {
+                let v = nums!();
+                if v.is_empty() {
+                    CellValue::error("#DIV/0!")
+                } else {
+                    CellValue::Number(v.iter().sum::<f64>() / v.len() as f64)
+                }
+            }
+            "MIN" => CellValue::Number(nums!().into_iter().reduce(f64::min).unwrap_or(0.0)),
+            "MAX" => CellValue::Number(nums!().into_iter().reduce(f64::max).unwrap_or(0.0)),
+            "MEDIAN" => {
+                let mut v = nums!();
+                if v.is_empty() {
+                    return CellValue::error("#NUM!");
+                }
+                v.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+                let mid = v.len() / 2;
+                CellValue::Number(if v.len() % 2 == 0 {
+                    (v[mid - 1] + v[mid]) / 2.0
+                } else {
+                    v[mid]
+                })
+            }
+            "COUNT" => CellValue::Number(
+                args.iter()
+                    .map(|a| {
+                        self.eval(sheet, a)
+                            .flatten()
+                            .iter()
+                            .filter(|v| v.as_range_number().is_some())
+                            .count()
+                    })
+                    .sum::<usize>() as f64,
+            ),
+            "COUNTA" => CellValue::Number(
+                args.iter()
+                    .map(|a| {
+                        self.eval(sheet, a)
+                            .flatten()
+                            .iter()
+                            .filter(|v| !v.is_empty())
+                            .count()
+                    })
+                    .sum::<usize>() as f64,
+            ),
+            "COUNTBLANK" => CellValue::Number(
+                args.iter()
+                    .map(|a| {
+                        self.eval(sheet, a)
+                            .flatten()
+                            .iter()
+                            .filter(|v| v.is_empty())
+                            .count()
+                    })
+                    .sum::<usize>() as f64,
+            ),
+            "IF" => {
+                let cond = propagate!(text(0));
+                match to_bool(&cond) {
+                    Ok(true) => self
+                        .scalar_arg(sheet, args, 1)
+                        .unwrap_or(CellValue::Bool(true)),
+                    Ok(false) => self
+                        .scalar_arg(sheet, args, 2)
+                        .unwrap_or(CellValue::Bool(false)),
+                    Err(e) => e,
+                }
+            }
+            "IFERROR" => match text(0) {
+                CellValue::Error(_) => text(1),
+                v => v,
+            },
+            "AND" | "OR" => {
+                let mut values = Vec::new();
+                for a in args {
+                    for v in self.eval(sheet, a).flatten() {
+                        if v.is_empty() {
+                            continue;
+                        }
+                        match to_bool(&v) {
+                            Ok(b) => values.push(b),
+                            Err(e) => return e,
+                        }
+                    }
+                }
+                if name == "AND" {
+                    CellValue::Bool(values.iter().all(|b| *b))
+                } else {
+                    CellValue::Bool(values.iter().any(|b| *b))
+                }
+            }
+            "NOT" => match to_bool(&propagate!(text(0))) {
+                Ok(b) => CellValue::Bool(!b),
+                Err(e) => e,
+            },
+            "ROUND" | "ROUNDUP" | "ROUNDDOWN" => {
+                let n = num!(0);
+                let digits = num!(1, 0.0) as i32;
+                let factor = 10f64.powi(digits);
+                // Pre-round away binary noise so ROUND(2.345, 2) gives 2.35 like Excel.
+                let scaled = round_to(n * factor, 9);
+                let r = match name {
+                    "ROUND" => scaled.round(),
+                    "ROUNDUP" => scaled.abs().ceil() * scaled.signum(),
+                    _ => scaled.trunc(),
+                };
+                CellValue::Number(r / factor)
+            }
+            "INT" => CellValue::Number(num!(0).floor()),
+            "ABS" => CellValue::Number(num!(0).abs()),
+            "SQRT" => {
+                let n = num!(0);
+                if n < 0.0 {
+                    CellValue::error("#NUM!")
+                } else {
+                    CellValue::Number(n.sqrt())
+                }
+            }
+            "POWER" => CellValue::Number(num!(0).powf(num!(1))),
+            "MOD" => {
+                let (n, d) = (num!(0), num!(1));
+                if d == 0.0 {
+                    CellValue::error("#DIV/0!")
+                } else {
+                    // Excel's MOD takes the sign of the divisor.
+                    CellValue::Number(n - d * (n / d).floor())
+                }
+            }
+            "SUMIF" | "COUNTIF" | "AVERAGEIF" => {
+                let range = match args.first() {
+                    Some(e) => self.eval(sheet, e).flatten(),
+                    None => return CellValue::error("#VALUE!"),
+                };
+                let criteria = propagate!(text(1));
+                let targets = match args.get(2) {
+                    Some(e) if name != "COUNTIF" => self.eval(sheet, e).flatten(),
+                    _ => range.clone(),
+                };
+                let matched: Vec<&CellValue> = range
+                    .iter()
+                    .zip(targets.iter())
+                    .filter(|(v, _)| criteria_match(&criteria, v))
+                    .map(|(_, t)| t)
+                    .collect();
+                match name {
+                    "COUNTIF" => CellValue::Number(matched.len() as f64),
+                    _ => {
+                        let nums: Vec<f64> =
+                            matched.iter().filter_map(|v| v.as_range_number()).collect();
+                        if name == "SUMIF" {
+                            CellValue::Number(nums.iter().sum())
+                        } else if nums.is_empty() {
+                            CellValue::error("#DIV/0!")
+                        } else {
+                            CellValue::Number(nums.iter().sum::<f64>() / nums.len() as f64)
+                        }
+                    }
+                }
+            }
+            "SUMPRODUCT" => {
+                let arrays: Vec<Vec<CellValue>> =
+                    args.iter().map(|a| self.eval(sheet, a).flatten()).collect();
+                let Some(len) = arrays.first().map(|a| a.len()) else {
+                    return CellValue::error("#VALUE!");
+                };
+                if arrays.iter().any(|a| a.len() != len) {
+                    return CellValue::error("#VALUE!");
+                }
+                let total = (0..len)
+                    .map(|i| {
+                        arrays
+                            .iter()
+                            .map(|a| a[i].as_range_number().unwrap_or(0.0))
+                            .product::<f64>()
+                    })
+                    .sum();
+                CellValue::Number(total)
+            }
+            "CONCATENATE" | "CONCAT" => {
+                let mut s = String::new();
+                for a in args {
+                    for v in self.eval(sheet, a).flatten() {
+                        s.push_str(&propagate!(v).display());
+                    }
+                }
+                CellValue::Text(s)
+            }
+            "LEN" => CellValue::Number(propagate!(text(0)).display().chars().count() as f64),
+            "UPPER" => CellValue::Text(propagate!(text(0)).display().to_uppercase()),
+            "LOWER" => CellValue::Text(propagate!(text(0)).display().to_lowercase()),
+            "TRIM" => CellValue::Text(
+                propagate!(text(0))
+                    .display()
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+            "LEFT" | "RIGHT" => {
+                let s = propagate!(text(0)).display();
+                let n = num!(1, 1.0).max(0.0) as usize;
+                let chars: Vec<char> = s.chars().collect();
+                let n = n.min(chars.len());
+                let slice = if name == "LEFT" {
+                    &chars[..n]
+                } else {
+                    &chars[chars.len() - n..]
+                };
+                CellValue::Text(slice.iter().collect())
+            }
+            "MID" => {
+                let s = propagate!(text(0)).display();
+                let start = (num!(1).max(1.0) as usize) - 1;
+                let len = num!(2).max(0.0) as usize;
+                CellValue::Text(s.chars().skip(start).take(len).collect())
+            }
+            "NPV" => {
+                let rate = num!(0);
+                let values = match self.numbers(sheet, &args[1.min(args.len())..]) {
+                    Ok(v) => v,
+                    Err(e) => return e,
+                };
+                CellValue::Number(
+                    values
+                        .iter()
+                        .enumerate()
+                        .map(|(i, v)| v / (1.0 + rate).powi(i as i32 + 1))
+                        .sum(),
+                )
+            }
+            "PMT" | "FV" | "PV" => {
+                let rate = num!(0);
+                let nper = num!(1);
+                let third = num!(2);
+                let fourth = num!(3, 0.0);
+                let when = num!(4, 0.0);
+                CellValue::Number(match name {
+                    "PMT" => pmt(rate, nper, third, fourth, when),
+                    "FV" => fv(rate, nper, third, fourth, when),
+                    _ => pv(rate, nper, third, fourth, when),
+                })
+            }
+            "IRR" => {
+                let values = match args.first() {
+                    Some(e) => self
+                        .eval(sheet, e)
+                        .flatten()
+                        .iter()
+                        .filter_map(|v| v.as_range_number())
+                        .collect::<Vec<f64>>(),
+                    None => return CellValue::error("#VALUE!"),
+                };
+                let guess = num!(1, 0.1);
+                match irr(&values, guess) {
+                    Some(r) => CellValue::Number(r),
+                    None => CellValue::error("#NUM!"),
+                }
+            }
+            _ => CellValue::error("#NAME?"),
+        }
+    }
+}
+
+fn binary(op: &str, a: CellValue, b: CellValue) -> CellValue {
+    let a = propagate!(a);
+    let b = propagate!(b);
+    match op {
+        "&" => CellValue::Text(format!("{}{}", a.display(), b.display())),
+        "=" | "<>" | "<" | ">" | "<=" | ">=" => {
+            let ord = compare(&a, &b);
+            CellValue::Bool(match op {
+                "=" => ord.is_eq(),
+                "<>" => !ord.is_eq(),
+                "<" => ord.is_lt(),
+                ">" => ord.is_gt(),
+                "<=" => ord.is_le(),
+                _ => ord.is_ge(),
+            })
+        }
+        _ => {
+            let (x, y) = match (to_number(&a), to_number(&b)) {
+                (Ok(x), Ok(y)) => (x, y),
+                (Err(e), _) | (_, Err(e)) => return e,
+            };
+            match op {
+                "+" => CellValue::Number(x + y),
+                "-" => CellValue::Number(x - y),
+                "*" => CellValue::Number(x * y),
+                "/" if y == 0.0 => CellValue::error("#DIV/0!"),
+                "/" => CellValue::Number(x / y),
+                _ => {
+                    let r = x.powf(y);
+                    if r.is_finite() {
+                        CellValue::Number(r)
+                    } else {
+                        CellValue::error("#NUM!")
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn pmt(rate: f64, nper: f64, pv: f64, fv: f64, when: f64) -> f64 {
+    if rate == 0.0 {
+        return -(pv + fv) / nper;
+    }
+    let growth = (1.0 + rate).powf(nper);
+    -(rate * (pv * growth + fv)) / ((1.0 + rate * when) * (growth - 1.0))
+}
+
+fn fv(rate: f64, nper: f64, pmt: f64, pv: f64, when: f64) -> f64 {
+    if rate == 0.0 {
+        return -(pv + pmt * nper);
+    }
+    let growth = (1.0 + rate).powf(nper);
+    -(pv * growth + pmt * (1.0 + rate * when) * (growth - 1.0) / rate)
+}
+
+fn pv(rate: f64, nper: f64, pmt: f64, fv: f64, when: f64) -> f64 {
+    if rate == 0.0 {
+        return -(fv + pmt * nper);
+    }
+    let growth = (1.0 + rate).powf(nper);
+    -(fv + pmt * (1.0 + rate * when) * (growth - 1.0) / rate) / growth
+}
+
+/// Newton-Raphson IRR, matching Excel's convergence tolerance.
+fn irr(values: &[f64], guess: f64) -> Option<f64> {
+    if !values.iter().any(|v| *v > 0.0) || !values.iter().any(|v| *v < 0.0) {
+        return None;
+    }
+    let mut rate = guess;
+    for _ in 0..100 {
+        let (mut npv, mut d_npv) = (0.0, 0.0);
+        for (i, v) in values.iter().enumerate() {
+            let t = i as f64;
+            let denom = (1.0 + rate).powf(t);
+            npv += v / denom;
+            d_npv -= t * v / (denom * (1.0 + rate));
+        }
+        if d_npv == 0.0 {
+            return None;
+        }
+        let next = rate - npv / d_npv;
+        if (next - rate).abs() < 1e-10 {
+            return Some(next);
+        }
+        rate = next;
+    }
+    None
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Pivot aggregation
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+    Sum,
+    Avg,
+    Count,
+    Min,
+    Max,
+}
+
+impl Aggregate {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "sum" | "" => Some(Self::Sum),
+            "avg" | "average" | "mean" => Some(Self::Avg),
+            "count" => Some(Self::Count),
+            "min" => Some(Self::Min),
+            "max" => Some(Self::Max),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Sum => "sum",
+            Self::Avg => "avg",
+            Self::Count => "count",
+            Self::Min => "min",
+            Self::Max => "max",
+        }
+    }
+
+    fn apply(&self, values: &[&CellValue]) -> CellValue {
+        if *self == Self::Count {
+            return CellValue::Number(values.iter().filter(|v| !v.is_empty()).count() as f64);
+        }
+        let nums: Vec<f64> = values
+            .iter()
+            .filter_map(|v| {
+                v.as_range_number()
+                    .or_else(|| to_number(v).ok().filter(|_| !v.is_empty()))
+            })
+            .collect();
+        if nums.is_empty() {
+            return CellValue::Empty;
+        }
+        CellValue::Number(match self {
+            Self::Sum => nums.iter().sum(),
+            Self::Avg => nums.iter().sum::<f64>() / nums.len() as f64,
+            Self::Min => nums.iter().cloned().fold(f64::INFINITY, f64::min),
+            _ => nums.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+        })
+    }
+}
+
+pub struct PivotSpec {
+    /// Row-group column indexes.
+    pub rows: Vec<usize>,
+    /// Optional column whose distinct values become output columns.
+    pub columns: Option<usize>,
+    /// (value column, aggregate)
+    pub values: Vec<(usize, Aggregate)>,
+    pub totals: bool,
+}
+
+pub struct PivotTable {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<CellValue>>,
+}
+
+fn cell_at(row: &[CellValue], i: usize) -> &CellValue {
+    static EMPTY: CellValue = CellValue::Empty;
+    row.get(i).unwrap_or(&EMPTY)
+}
+
+/// Group `data` (rows without header) like an Excel pivot table. Group keys keep
+/// first-appearance order; a grand-total row is appended when `spec.totals` is set.
+pub fn pivot(headers: &[String], data: &[Vec<CellValue>], spec: &PivotSpec) -> PivotTable {
+    let header = |i: usize| {
+        headers
+            .get(i)
+            .cloned()
+            .unwrap_or_else(|| column_name(i as u32))
+    };
+
+    let mut pivot_keys: Vec<String> = Vec::new();
+    if let Some(pc) = spec.columns {
+        for row in data {
+            let k = cell_at(row, pc).display();
+            if !pivot_keys.contains(&k) {
+                pivot_keys.push(k);
+            }
+        }
+    }
+
+    let mut group_order: Vec<Vec<String>> = Vec::new();
+    let mut groups: HashMap<Vec<String>, Vec<&Vec<CellValue>>> = HashMap::new();
+    for row in data {
+        if row.iter().all(|v| v.is_empty()) {
+            continue;
+        }
+        let key: Vec<String> = spec
+            .rows
+            .iter()
+            .map(|&i| cell_at(row, i).display())
+            .collect();
+        groups
+            .entry(key.clone())
+            .or_insert_with(|| {
+                group_order.push(key);
+                Vec::new()
+            })
+            .push(row);
+    }
+
+    let value_label = |(col, agg): &(usize, Aggregate)| format!("{}({})", agg.name(), header(*col));
+    let mut out_headers: Vec<String> = spec.rows.iter().map(|&i| header(i)).collect();
+    if spec.columns.is_some() {
+        for pk in &pivot_keys {
+            for v in &spec.values {
+                if spec.values.len() == 1 {
+                    out_headers.push(pk.clone());
+                } else {
+                    out_headers.push(format!("{} {}", pk, value_label(v)));
+                }
+            }
+        }
+    } else {
+        out_headers.extend(spec.values.iter().map(&value_label));
+    }
+
+    let aggregate_cells = |rows: &[&Vec<CellValue>]| -> Vec<CellValue> {
+        let mut cells = Vec::new();
+        match spec.columns {
+            Some(pc) => {
+                for pk in &pivot_keys {
+                    let subset: Vec<&Vec<CellValue>> = rows
+                        .iter()
+                        .filter(|r| cell_at(r, pc).display() == *pk)
+                        .copied()
+                        .collect();
+                    for (col, agg) in &spec.values {
+                        let vals: Vec<&CellValue> =
+                            subset.iter().map(|r| cell_at(r, *col)).collect();
+                        cells.push(agg.apply(&vals));
+                    }
+                }
+            }
+            None => {
+                for (col, agg) in &spec.values {
+                    let vals: Vec<&CellValue> = rows.iter().map(|r| cell_at(r, *col)).collect();
+                    cells.push(agg.apply(&vals));
+                }
+            }
+        }
+        cells
+    };
+
+    let mut out_rows = Vec::new();
+    for key in &group_order {
+        let mut row: Vec<CellValue> = key
+            .iter()
+            .map(|k| {
+                if k.is_empty() {
+                    CellValue::Text("(blank)".to_string())
+                } else {
+                    CellValue::Text(k.clone())
+                }
+            })
+            .collect();
+        row.extend(aggregate_cells(&groups[key]));
+        out_rows.push(row);
+    }
+
+    if spec.totals && !data.is_empty() {
+        let all: Vec<&Vec<CellValue>> = data.iter().collect();
+        let mut row: Vec<CellValue> = spec
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(i, _)| {
+                if i == 0 {
+                    CellValue::Text("Grand Total".to_string())
+                } else {
+                    CellValue::Empty
+                }
+            })
+            .collect();
+        row.extend(aggregate_cells(&all));
+        out_rows.push(row);
+    }
+
+    PivotTable {
+        headers: out_headers,
+        rows: out_rows,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sheet(cells: &[(&str, CellValue)], formulas: &[(&str, &str)]) -> Workbook {
+        let values = cells
+            .iter()
+            .map(|(r, v)| (parse_cell_ref(r).unwrap(), v.clone()))
+            .collect();
+        let formulas = formulas
+            .iter()
+            .map(|(r, f)| (parse_cell_ref(r).unwrap(), f.to_string()))
+            .collect();
+        Workbook {
+            sheets: vec![Sheet {
+                name: "Data".to_string(),
+                values,
+                formulas,
+            }],
+        }
+    }
+
+    fn n(v: f64) -> CellValue {
+        CellValue::Number(v)
+    }
+
+    fn t(s: &str) -> CellValue {
+        CellValue::Text(s.to_string())
+    }
+
+    #[test]
+    fn test_cell_refs() {
+        assert_eq!(parse_cell_ref("A1"), Some((0, 0)));
+        assert_eq!(parse_cell_ref("$AB$10"), Some((9, 27)));
+        assert_eq!(parse_cell_ref("A0"), None);
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(27), "AB");
+        assert_eq!(cell_name(9, 27), "AB10");
+        assert_eq!(parse_range_ref("C5:A1"), Some(((0, 0), (4, 2))));
+    }
+
+    #[test]
+    fn test_arithmetic_and_precedence() {
+        let wb = sheet(&[], &[]);
+        let ev = Evaluator::new(&wb);
+        assert_eq!(ev.eval_formula(0, "=1+2*3"), n(7.0));
+        assert_eq!(ev.eval_formula(0, "=(1+2)*3"), n(9.0));
+        assert_eq!(ev.eval_formula(0, "=-2^2"), n(4.0));
+        assert_eq!(ev.eval_formula(0, "=2^3^2"), n(64.0));
+        assert_eq!(ev.eval_formula(0, "=50%*10"), n(5.0));
+        assert_eq!(ev.eval_formula(0, "=1/0"), CellValue::error("#DIV/0!"));
+        assert_eq!(ev.eval_formula(0, "=\"a\"&\"b\"&1"), t("ab1"));
+        assert_eq!(ev.eval_formula(0, "=3>=2"), CellValue::Bool(true));
+        assert_eq!(ev.eval_formula(0, "=NOPE(1)"), CellValue::error("#NAME?"));
+    }
+
+    #[test]
+    fn test_references_and_dependent_formulas() {
+        let wb = sheet(
+            &[
+                ("A1", t("Revenue")),
+                ("A2", n(100.0)),
+                ("A3", n(250.0)),
+                ("A4", t("n/a")),
+            ],
+            &[("A5", "SUM(A2:A4)"), ("A6", "A5*2"), ("B1", "B1+1")],
+        );
+        let ev = Evaluator::new(&wb);
+        assert_eq!(ev.cell(0, 5, 0), n(700.0));
+        assert_eq!(ev.cell(0, 4, 0), n(350.0));
+        assert_eq!(ev.eval_formula(0, "AVERAGE(A:A)"), n(350.0));
+        assert_eq!(ev.eval_formula(0, "COUNT(A1:A6)"), n(4.0));
+        assert_eq!(ev.eval_formula(0, "COUNTA(A1:A4)"), n(4.0));
+        assert_eq!(ev.eval_formula(0, "'Data'!A2+Data!A3"), n(350.0));
+        assert_eq!(ev.cell(0, 0, 1), CellValue::error("#CIRC!"));
+        assert!(ev.evaluated_count() >= 2);
+    }
+
+    #[test]
+    fn test_cached_values_are_trusted() {
+        let wb = sheet(&[("A1", n(1.0)), ("A2", n(42.0))], &[("A2", "A1*2")]);
+        let ev = Evaluator::new(&wb);
+        assert_eq!(ev.cell(0, 1, 0), n(42.0));
+        assert_eq!(ev.evaluated_count(), 0);
+    }
+
+    #[test]
+    fn test_logical_and_text_functions() {
+        let wb = sheet(&[("A1", n(5.0)), ("A2", t(" hello  world "))], &[]);
+        let ev = Evaluator::new(&wb);
+        assert_eq!(ev.eval_formula(0, "IF(A1>3,\"big\",\"small\")"), t("big"));
+        assert_eq!(ev.eval_formula(0, "IFERROR(1/0,0)"), n(0.0));
+        assert_eq!(ev.eval_formula(0, "AND(A1>1,A1<10)"), CellValue::Bool(true));
+        assert_eq!(ev.eval_formula(0, "TRIM(A2)"), t("hello world"));
+        assert_eq!(ev.eval_formula(0, "UPPER(LEFT(TRIM(A2),5))"), t("HELLO"));
+        assert_eq!(ev.eval_formula(0, "ROUND(2.345,2)"), n(2.35));
+        assert_eq!(ev.eval_formula(0, "ROUNDDOWN(-2.7,0)"), n(-2.0));
+        assert_eq!(ev.eval_formula(0, "MOD(-3,2)"), n(1.0));
+    }
+
+    #[test]
+    fn test_conditional_aggregates() {
+        let wb = sheet(
+            &[
+                ("A1", t("apple")),
+                ("A2", t("pear")),
+                ("A3", t("apricot")),
+                ("B1", n(10.0)),
+                ("B2", n(20.0)),
+                ("B3", n(30.0)),
+            ],
+            &[],
+        );
+        let ev = Evaluator::new(&wb);
+        assert_eq!(ev.eval_formula(0, "SUMIF(A1:A3,\"ap*\",B1:B3)"), n(40.0));
+        assert_eq!(ev.eval_formula(0, "COUNTIF(B1:B3,\">15\")"), n(2.0));
+        assert_eq!(
+            ev.eval_formula(0, "AVERAGEIF(A1:A3,\"<>pear\",B1:B3)"),
+            n(20.0)
+        );
+        assert_eq!(ev.eval_formula(0, "SUMPRODUCT(B1:B3,B1:B3)"), n(1400.0));
+    }
+
+    #[test]
+    fn test_financial_functions() {
+        let wb = sheet(
+            &[
+                ("A1", n(-1000.0)),
+                ("A2", n(300.0)),
+                ("A3", n(400.0)),
+                ("A4", n(500.0)),
+            ],
+            &[],
+        );
+        let ev = Evaluator::new(&wb);
+        let CellValue::Number(p) = ev.eval_formula(0, "PMT(0.05/12,360,200000)") else {
+            panic!("PMT should be numeric");
+        };
+        assert!((p - -1073.64).abs() < 0.01);
+        let CellValue::Number(npv) = ev.eval_formula(0, "NPV(0.1,A2:A4)") else {
+            panic!("NPV should be numeric");
+        };
+        assert!((npv - 978.96).abs() < 0.01);
+        let CellValue::Number(r) = ev.eval_formula(0, "IRR(A1:A4)") else {
+            panic!("IRR should be numeric");
+        };
+        assert!((r - 0.0889633947).abs() < 1e-8);
+        let CellValue::Number(f) = ev.eval_formula(0, "FV(0.1,2,0,-100)") else {
+            panic!("FV should be numeric");
+        };
+        assert!((f - 121.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_pivot_with_columns_and_totals() {
+        let headers = vec![
+            "Region".to_string(),
+            "Quarter".to_string(),
+            "Sales".to_string(),
+        ];
+        let data = vec![
+            vec![t("East"), t("Q1"), n(10.0)],
+            vec![t("West"), t("Q1"), n(5.0)],
+            vec![t("East"), t("Q2"), n(7.0)],
+            vec![t("East"), t("Q1"), n(3.0)],
+        ];
+        let table = pivot(
+            &headers,
+            &data,
+            &PivotSpec {
+                rows: vec![0],
+                columns: Some(1),
+                values: vec![(2, Aggregate::Sum)],
+                totals: true,
+            },
+        );
+        assert_eq!(table.headers, vec!["Region", "Q1", "Q2"]);
+        assert_eq!(table.rows[0], vec![t("East"), n(13.0), n(7.0)]);
+        assert_eq!(table.rows[1], vec![t("West"), n(5.0), CellValue::Empty]);
+        assert_eq!(table.rows[2], vec![t("Grand Total"), n(18.0), n(7.0)]);
+
+        let table = pivot(
+            &headers,
+            &data,
+            &PivotSpec {
+                rows: vec![0],
+                columns: None,
+                values: vec![(2, Aggregate::Avg), (2, Aggregate::Count)],
+                totals: false,
+            },
+        );
+        assert_eq!(table.headers, vec!["Region", "avg(Sales)", "count(Sales)"]);
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[1], vec![t("West"), n(5.0), n(1.0)]);
+    }
+
+    #[test]
+    fn test_cell_value_json() {
+        assert_eq!(n(3.0).to_json(), json!(3));
+        assert_eq!(n(0.1 + 0.2).to_json(), json!(0.3));
+        assert_eq!(CellValue::Empty.to_json(), Value::Null);
+        assert_eq!(n(1.5).display(), "1.5");
+    }
+}
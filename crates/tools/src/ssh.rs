@@ -0,0 +1,630 @@
+use async_trait::async_trait;
+use blockcell_core::config::SshHostConfig;
+use blockcell_core::paths::Paths;
+use blockcell_core::secrets::resolve_secret_ref;
+use blockcell_core::{Config, Error, Result};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+use tracing::info;
+
+use crate::{Tool, ToolContext, ToolSchema};
+
+/// Captured stdout/stderr are cut at this many characters each.
+const MAX_OUTPUT_CHARS: usize = 64 * 1024;
+/// Characters that chain or redirect shell commands. Commands containing them only pass
+/// a bare `*` allowlist entry, so `uptime; rm -rf /` cannot ride on an `uptime*` pattern.
+const SHELL_META: &[&str] = &[";", "&", "|", "`", "$(", ">", "<", "\n", "\r"];
+
+fn expand_path(path: &str, workspace: &Path) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/") {
+        dirs::home_dir()
+            .map(|h| h.join(rest))
+            .unwrap_or_else(|| PathBuf::from(path))
+    } else if path.starts_with('/') {
+        PathBuf::from(path)
+    } else {
+        workspace.join(path)
+    }
+}
+
+/// Whole-string match where `*` matches any run of characters.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == text;
+    }
+    let mut rest = text;
+    for (i, part) in parts.iter().enumerate() {
+        if i == 0 {
+            match rest.strip_prefix(part) {
+                Some(r) => rest = r,
+                None => return false,
+            }
+        } else if i == parts.len() - 1 {
+            return rest.ends_with(part);
+        } else if let Some(pos) = rest.find(part) {
+            rest = &rest[pos + part.len()..];
+        } else {
+            return false;
+        }
+    }
+    true
+}
+
+fn command_allowed(host: &SshHostConfig, command: &str) -> bool {
+    let command = command.trim();
+    let has_meta = SHELL_META.iter().any(|m| command.contains(m));
+    host.allow_commands.iter().any(|p| {
+        let p = p.trim();
+        p == "*" || (!has_meta && glob_match(p, command))
+    })
+}
+
+fn remote_path_allowed(host: &SshHostConfig, remote: &str) -> bool {
+    if remote.split('/').any(|seg| seg == "..") {
+        return false;
+    }
+    host.remote_dirs.is_empty()
+        || host.remote_dirs.iter().any(|d| {
+            let d = d.trim_end_matches('/');
+            remote == d || remote.starts_with(&format!("{}/", d))
+        })
+}
+
+fn host_name<'a>(config: &'a Config, params: &'a Value) -> Option<&'a str> {
+    params
+        .get("host")
+        .and_then(|v| v.as_str())
+        .or(config.tools.ssh.default_host.as_deref())
+        .or_else(|| {
+            let hosts = &config.tools.ssh.hosts;
+            hosts
+                .keys()
+                .next()
+                .filter(|_| hosts.len() == 1)
+                .map(|s| s.as_str())
+        })
+}
+
+fn lookup_host<'a>(config: &'a Config, params: &'a Value) -> Result<(&'a str, &'a SshHostConfig)> {
+    let name = host_name(config, params).ok_or_else(|| {
+        Error::Validation("No 'host' given and no tools.ssh.defaultHost configured".to_string())
+    })?;
+    let host = config.tools.ssh.hosts.get(name).ok_or_else(|| {
+        let mut known: Vec<&String> = config.tools.ssh.hosts.keys().collect();
+        known.sort();
+        Error::Config(format!(
+            "Unknown ssh host profile '{}'. Configured: {:?}",
+            name, known
+        ))
+    })?;
+    Ok((name, host))
+}
+
+/// A private key written from the secrets store for the duration of one call.
+struct TempKey(PathBuf);
+
+impl Drop for TempKey {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+fn write_temp_key(paths: &Paths, pem: &str) -> Result<TempKey> {
+    let dir = paths.base.join("ssh");
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!(".key-{}", uuid::Uuid::new_v4()));
+    let mut body = pem.trim().to_string();
+    body.push('\n');
+    #[cfg(unix)]
+    {
+        use std::io::Write;
+        use std::os::unix::fs::OpenOptionsExt;
+        let mut f = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(&path)?;
+        f.write_all(body.as_bytes())?;
+    }
+    #[cfg(not(unix))]
+    std::fs::write(&path, body)?;
+    Ok(TempKey(path))
+}
+
+/// Common `-o` options plus identity; `port_flag` is `-p` for ssh and `-P` for sftp.
+fn connection_args(
+    host: &SshHostConfig,
+    identity: Option<&Path>,
+    port_flag: &str,
+    workspace: &Path,
+) -> Vec<String> {
+    let mut args = vec![
+        "-o".to_string(),
+        "BatchMode=yes".to_string(),
+        "-o".to_string(),
+        "ConnectTimeout=10".to_string(),
+        "-o".to_string(),
+        format!(
+            "StrictHostKeyChecking={}",
+            if host.strict_host_key_checking {
+                "yes"
+            } else {
+                "accept-new"
+            }
+        ),
+        port_flag.to_string(),
+        host.port.to_string(),
+    ];
+    if !host.known_hosts.is_empty() {
+        args.push("-o".to_string());
+        args.push(format!(
+            "UserKnownHostsFile={}",
+            expand_path(&host.known_hosts, workspace).display()
+        ));
+    }
+    if let Some(key) = identity {
+        args.push("-o".to_string());
+        args.push("IdentitiesOnly=yes".to_string());
+        args.push("-i".to_string());
+        args.push(key.display().to_string());
+    }
+    args
+}
+
+fn destination(host: &SshHostConfig) -> String {
+    if host.user.is_empty() {
+        host.host.clone()
+    } else {
+        format!("{}@{}", host.user, host.host)
+    }
+}
+
+fn truncate_output(bytes: &[u8]) -> (String, bool) {
+    let text = String::from_utf8_lossy(bytes);
+    let cut = crate::safe_truncate(&text, MAX_OUTPUT_CHARS);
+    (cut.to_string(), cut.len() < text.len())
+}
+
+/// Quote a path for an sftp batch line.
+fn sftp_quote(path: &str) -> String {
+    format!("\"{}\"", path.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+struct Prepared {
+    name: String,
+    host: SshHostConfig,
+    identity: Option<PathBuf>,
+    _temp_key: Option<TempKey>,
+}
+
+fn prepare(ctx: &ToolContext, params: &Value) -> Result<Prepared> {
+    let (name, host) = lookup_host(&ctx.config, params)?;
+    let paths = match ctx.workspace.parent() {
+        Some(base) => Paths::with_base(base.to_path_buf()),
+        None => Paths::new(),
+    };
+    let mut temp_key = None;
+    let identity = if host.identity.trim().is_empty() {
+        None
+    } else {
+        let resolved = resolve_secret_ref(&paths, &host.identity)?;
+        if resolved.contains("-----BEGIN") {
+            let key = write_temp_key(&paths, &resolved)?;
+            let path = key.0.clone();
+            temp_key = Some(key);
+            Some(path)
+        } else {
+            Some(expand_path(resolved.trim(), &ctx.workspace))
+        }
+    };
+    Ok(Prepared {
+        name: name.to_string(),
+        host: host.clone(),
+        identity,
+        _temp_key: temp_key,
+    })
+}
+
+async fn run_process(
+    program: &str,
+    args: &[String],
+    stdin: Option<String>,
+    timeout: Duration,
+) -> Result<(std::process::Output, u64)> {
+    let bin = which::which(program)
+        .map_err(|_| Error::Tool(format!("`{}` not found in PATH", program)))?;
+    let mut cmd = tokio::process::Command::new(bin);
+    cmd.args(args)
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let started = Instant::now();
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| Error::Tool(format!("Failed to start {}: {}", program, e)))?;
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        pipe.write_all(input.as_bytes()).await?;
+        drop(pipe);
+    }
+    let output = tokio::time::timeout(timeout, child.wait_with_output())
+        .await
+        .map_err(|_| {
+            Error::Timeout(format!(
+                "{} timed out after {}s",
+                program,
+                timeout.as_secs()
+            ))
+        })?
+        .map_err(|e| Error::Tool(format!("{} failed: {}", program, e)))?;
+    Ok((output, started.elapsed().as_millis() as u64))
+}
+
+async fn action_exec(ctx: &ToolContext, params: &Value) -> Result<Value> {
+    let p = prepare(ctx, params)?;
+    let command = params["command"].as_str().unwrap_or("").trim();
+    if !command_allowed(&p.host, command) {
+        return Err(Error::PermissionDenied(format!(
+            "Command not in the allowlist for host '{}'. Allowed patterns: {:?}",
+            p.name, p.host.allow_commands
+        )));
+    }
+    let timeout_secs = params
+        .get("timeout_secs")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(p.host.timeout_secs as u64)
+        .clamp(1, 3600);
+    let mut args = connection_args(&p.host, p.identity.as_deref(), "-p", &ctx.workspace);
+    args.push(destination(&p.host));
+    args.push("--".to_string());
+    args.push(command.to_string());
+
+    info!(host = %p.name, command = %command, "ssh exec");
+    let (output, duration_ms) =
+        run_process("ssh", &args, None, Duration::from_secs(timeout_secs)).await?;
+    let exit_code = output.status.code();
+    // ssh itself exits 255 on connection/auth failures.
+    if exit_code == Some(255) {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::Tool(format!(
+            "ssh connection to '{}' failed: {}",
+            p.name,
+            stderr.trim()
+        )));
+    }
+    let (stdout, stdout_truncated) = truncate_output(&output.stdout);
+    let (stderr, stderr_truncated) = truncate_output(&output.stderr);
+    Ok(json!({
+        "host": p.name,
+        "command": command,
+        "exit_code": exit_code,
+        "success": output.status.success(),
+        "stdout": stdout,
+        "stderr": stderr,
+        "truncated": stdout_truncated || stderr_truncated,
+        "duration_ms": duration_ms,
+    }))
+}
+
+async fn action_transfer(ctx: &ToolContext, params: &Value, upload: bool) -> Result<Value> {
+    let p = prepare(ctx, params)?;
+    let remote = params["remote_path"].as_str().unwrap_or("").trim();
+    if !remote_path_allowed(&p.host, remote) {
+        return Err(Error::PermissionDenied(format!(
+            "Remote path '{}' is outside the allowed directories for host '{}': {:?}",
+            remote, p.name, p.host.remote_dirs
+        )));
+    }
+    if upload && !p.host.allow_upload {
+        return Err(Error::PermissionDenied(format!(
+            "Uploads are disabled for host '{}' (set allowUpload: true)",
+            p.name
+        )));
+    }
+    let local = match params.get("local_path").and_then(|v| v.as_str()) {
+        Some(l) if !l.trim().is_empty() => expand_path(l.trim(), &ctx.workspace),
+        _ if !upload => {
+            let file = remote
+                .rsplit('/')
+                .next()
+                .filter(|s| !s.is_empty())
+                .unwrap_or("download");
+            ctx.workspace.join("ssh").join(&p.name).join(file)
+        }
+        _ => return Err(Error::Validation("put requires 'local_path'".to_string())),
+    };
+    if upload && !local.is_file() {
+        return Err(Error::Tool(format!("{} is not a file", local.display())));
+    }
+    if !upload {
+        if let Some(parent) = local.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+    }
+
+    let local_str = local.display().to_string();
+    let batch = if upload {
+        format!("put {} {}\n", sftp_quote(&local_str), sftp_quote(remote))
+    } else {
+        format!("get {} {}\n", sftp_quote(remote), sftp_quote(&local_str))
+    };
+    let mut args = connection_args(&p.host, p.identity.as_deref(), "-P", &ctx.workspace);
+    args.push("-b".to_string());
+    args.push("-".to_string());
+    args.push(destination(&p.host));
+
+    info!(host = %p.name, remote = %remote, upload, "sftp transfer");
+    let (output, duration_ms) = run_process(
+        "sftp",
+        &args,
+        Some(batch),
+        Duration::from_secs(p.host.timeout_secs.max(1) as u64 * 5),
+    )
+    .await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::Tool(format!(
+            "sftp {} failed: {}",
+            if upload { "put" } else { "get" },
+            stderr.trim()
+        )));
+    }
+    let size = tokio::fs::metadata(&local).await.map(|m| m.len()).ok();
+    Ok(json!({
+        "host": p.name,
+        "direction": if upload { "put" } else { "get" },
+        "remote_path": remote,
+        "local_path": local_str,
+        "size": size,
+        "duration_ms": duration_ms,
+    }))
+}
+
+pub struct SshTool;
+
+#[async_trait]
+impl Tool for SshTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "ssh",
+            description: "Run commands on and copy files from configured remote hosts (tools.ssh.hosts). \
+                Each host has a strict command allowlist; some commands and every upload need user confirmation. \
+                Actions: 'list_hosts', 'exec' (run an allowed command, returns exit_code/stdout/stderr), \
+                'get' (SFTP download into the workspace), 'put' (SFTP upload from the workspace, only where allowed).",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["list_hosts", "exec", "get", "put"],
+                        "description": "Action to perform"
+                    },
+                    "host": {
+                        "type": "string",
+                        "description": "Host profile name. Default: tools.ssh.defaultHost"
+                    },
+                    "command": {
+                        "type": "string",
+                        "description": "(exec) Command to run; must match the host's allowCommands"
+                    },
+                    "timeout_secs": {
+                        "type": "integer",
+                        "description": "(exec) Timeout in seconds. Default: the host's timeoutSecs"
+                    },
+                    "remote_path": {
+                        "type": "string",
+                        "description": "(get/put) Path on the remote host"
+                    },
+                    "local_path": {
+                        "type": "string",
+                        "description": "(get) Destination, default workspace/ssh/<host>/<name>. (put) Local file to upload"
+                    }
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    fn prompt_rule(&self, _ctx: &crate::PromptContext) -> Option<String> {
+        Some("- **远程主机 (ssh)**: 排查告警的服务器时用 `ssh`，先 action='list_hosts' 查看可用主机和允许的命令模式，只执行白名单内的只读诊断命令（如 uptime、df -h、systemctl status）。命令被拒绝时告诉用户需要在 allowCommands 中添加，不要尝试绕过。拉取日志/配置文件用 action='get'。".to_string())
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        let action = params.get("action").and_then(|v| v.as_str()).unwrap_or("");
+        let has = |key: &str| {
+            params
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(|s| !s.trim().is_empty())
+                .unwrap_or(false)
+        };
+        match action {
+            "list_hosts" => {}
+            "exec" => {
+                if !has("command") {
+                    return Err(Error::Validation("exec requires 'command'".to_string()));
+                }
+            }
+            "get" => {
+                if !has("remote_path") {
+                    return Err(Error::Validation("get requires 'remote_path'".to_string()));
+                }
+            }
+            "put" => {
+                if !has("remote_path") || !has("local_path") {
+                    return Err(Error::Validation(
+                        "put requires 'local_path' and 'remote_path'".to_string(),
+                    ));
+                }
+            }
+            "" => return Err(Error::Validation("Missing 'action'".to_string())),
+            _ => return Err(Error::Validation(format!("Unknown action: {}", action))),
+        }
+        Ok(())
+    }
+
+    fn confirmation_items(&self, config: &Config, params: &Value) -> Vec<String> {
+        let Ok((name, host)) = lookup_host(config, params) else {
+            return Vec::new();
+        };
+        match params.get("action").and_then(|v| v.as_str()) {
+            Some("exec") => {
+                let command = params["command"].as_str().unwrap_or("").trim();
+                let confirm = host.confirm_all
+                    || host
+                        .confirm_commands
+                        .iter()
+                        .any(|p| p.trim() == "*" || glob_match(p.trim(), command));
+                // Unlisted commands are rejected in execute; don't prompt for them.
+                if confirm && command_allowed(host, command) {
+                    vec![format!("ssh {} ({}): {}", name, host.host, command)]
+                } else {
+                    Vec::new()
+                }
+            }
+            Some("put") if host.allow_upload => vec![format!(
+                "ssh put {} → {}:{}",
+                params["local_path"].as_str().unwrap_or(""),
+                name,
+                params["remote_path"].as_str().unwrap_or("")
+            )],
+            _ => Vec::new(),
+        }
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        match params["action"].as_str().unwrap_or("") {
+            "list_hosts" => {
+                let ssh = &ctx.config.tools.ssh;
+                let mut names: Vec<&String> = ssh.hosts.keys().collect();
+                names.sort();
+                let hosts: Vec<Value> = names
+                    .into_iter()
+                    .map(|name| {
+                        let h = &ssh.hosts[name];
+                        json!({
+                            "name": name,
+                            "host": h.host,
+                            "port": h.port,
+                            "user": h.user,
+                            "description": h.description,
+                            "allow_commands": h.allow_commands,
+                            "confirm_commands": h.confirm_commands,
+                            "confirm_all": h.confirm_all,
+                            "allow_upload": h.allow_upload,
+                            "remote_dirs": h.remote_dirs,
+                            "default": ssh.default_host.as_deref() == Some(name.as_str()),
+                        })
+                    })
+                    .collect();
+                Ok(json!({ "hosts": hosts }))
+            }
+            "exec" => action_exec(&ctx, &params).await,
+            "get" => action_transfer(&ctx, &params, false).await,
+            "put" => action_transfer(&ctx, &params, true).await,
+            other => Err(Error::Validation(format!("Unknown action: {}", other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blockcell_core::config::SshConfig;
+    use std::collections::HashMap;
+
+    fn host(allow: &[&str]) -> SshHostConfig {
+        serde_json::from_value(json!({
+            "host": "10.0.0.5",
+            "user": "ops",
+            "allowCommands": allow,
+            "confirmCommands": ["systemctl restart *"],
+            "allowUpload": true,
+            "remoteDirs": ["/var/log", "/etc/nginx/"],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_glob_and_allowlist() {
+        assert!(glob_match("systemctl status *", "systemctl status nginx"));
+        assert!(glob_match("*.log", "app.log"));
+        assert!(glob_match("uptime", "uptime"));
+        assert!(!glob_match("uptime", "uptime -p"));
+        assert!(glob_match("a*b*c", "aXXbYYc"));
+        assert!(!glob_match("a*b*c", "aXXcYYb"));
+
+        let h = host(&[
+            "uptime",
+            "df -h",
+            "systemctl status *",
+            "systemctl restart *",
+        ]);
+        assert!(command_allowed(&h, "uptime"));
+        assert!(command_allowed(&h, "systemctl status nginx"));
+        assert!(!command_allowed(&h, "rm -rf /"));
+        assert!(!command_allowed(&h, "systemctl status nginx; rm -rf /"));
+        assert!(!command_allowed(&h, "systemctl status $(reboot)"));
+        assert!(command_allowed(&host(&["*"]), "ps aux | grep nginx"));
+        assert!(!command_allowed(&host(&[]), "uptime"));
+    }
+
+    #[test]
+    fn test_remote_dirs() {
+        let h = host(&[]);
+        assert!(remote_path_allowed(&h, "/var/log/syslog"));
+        assert!(remote_path_allowed(&h, "/etc/nginx/nginx.conf"));
+        assert!(!remote_path_allowed(&h, "/var/logs/x"));
+        assert!(!remote_path_allowed(&h, "/var/log/../../etc/shadow"));
+        assert!(!remote_path_allowed(&h, "/etc/passwd"));
+    }
+
+    #[test]
+    fn test_confirmation_items() {
+        let mut config = Config::default();
+        config.tools.ssh = SshConfig {
+            hosts: HashMap::from([("web1".to_string(), host(&["uptime", "systemctl restart *"]))]),
+            default_host: None,
+        };
+        let tool = SshTool;
+        let items = |params: Value| tool.confirmation_items(&config, &params);
+
+        assert!(items(json!({"action": "exec", "command": "uptime"})).is_empty());
+        assert_eq!(
+            items(json!({"action": "exec", "command": "systemctl restart nginx"})),
+            vec!["ssh web1 (10.0.0.5): systemctl restart nginx"]
+        );
+        assert!(items(json!({"action": "get", "remote_path": "/var/log/syslog"})).is_empty());
+        assert_eq!(
+            items(
+                json!({"action": "put", "local_path": "a.conf", "remote_path": "/etc/nginx/a.conf"})
+            )
+            .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_connection_args() {
+        let mut h = host(&[]);
+        h.port = 2222;
+        h.strict_host_key_checking = false;
+        let args = connection_args(&h, Some(Path::new("/tmp/key")), "-p", Path::new("/ws"));
+        assert!(args.contains(&"StrictHostKeyChecking=accept-new".to_string()));
+        assert!(args.windows(2).any(|w| w[0] == "-p" && w[1] == "2222"));
+        assert!(args.windows(2).any(|w| w[0] == "-i" && w[1] == "/tmp/key"));
+        assert_eq!(destination(&h), "ops@10.0.0.5");
+        assert_eq!(
+            sftp_quote("/var/log/my \"app\".log"),
+            "\"/var/log/my \\\"app\\\".log\""
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use async_trait::async_trait;
+use blockcell_core::config::MqttConfig;
 use blockcell_core::{Error, Result};
+use blockcell_storage::timeseries::TimeSeriesStore;
 use futures::{SinkExt, StreamExt};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
@@ -11,6 +13,8 @@ use tokio::sync::Mutex;
 use tokio_tungstenite::tungstenite::Message as WsMessage;
 use tracing::{debug, error, info, warn};
 
+use crate::egress::HttpClientFactory;
+use crate::mqtt::{self, qos_from};
 use crate::{Tool, ToolContext, ToolSchema};
 
 /// Type alias for WebSocket write half
@@ -29,6 +33,18 @@ static STREAM_MANAGER: Lazy<Arc<Mutex<StreamManager>>> =
 /// Whether we have already restored persisted subscriptions on this process run.
 static RESTORED: Lazy<Arc<Mutex<bool>>> = Lazy::new(|| Arc::new(Mutex::new(false)));
 
+/// Default number of days of local history kept per series.
+const DEFAULT_RETENTION_DAYS: u32 = 30;
+
+/// Where a persisting subscription writes its ticks in the local history store.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct HistorySink {
+    series: String,
+    /// JSON path of the numeric value stored with each tick (e.g. 'p' or 'data.price').
+    value_path: Option<String>,
+    retention_days: u32,
+}
+
 /// Serializable subscription rule — persisted to disk for auto-restore.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct SubscriptionRule {
@@ -44,13 +60,21 @@ struct SubscriptionRule {
     auto_restore: bool,
     /// Max reconnect attempts before giving up (0 = unlimited).
     max_reconnect: u32,
+    /// Local history persistence, if enabled.
+    #[serde(default)]
+    history: Option<HistorySink>,
+    /// MQTT topic filter, for `protocol: "mqtt"`.
+    #[serde(default)]
+    topic: Option<String>,
+    #[serde(default)]
+    qos: Option<u8>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct StreamSubscription {
     id: String,
     url: String,
-    protocol: String, // "websocket" | "sse"
+    protocol: String, // "websocket" | "sse" | "mqtt"
     status: String,   // "connecting" | "connected" | "disconnected" | "error"
     /// Number of messages received so far.
     message_count: u64,
@@ -74,6 +98,9 @@ struct StreamSubscription {
     reconnect_count: u32,
     /// Max reconnect attempts (0 = unlimited).
     max_reconnect: u32,
+    history: Option<HistorySink>,
+    topic: Option<String>,
+    qos: Option<u8>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -88,6 +115,12 @@ struct StreamManager {
     cancel_handles: HashMap<String, tokio::sync::watch::Sender<bool>>,
     /// Workspace path for persistence (set on first tool execution).
     workspace: Option<PathBuf>,
+    /// Local tick history, opened on first use.
+    history_store: Option<TimeSeriesStore>,
+    /// `iot.mqtt` from the latest tool call, used by MQTT subscriptions.
+    mqtt_config: Option<MqttConfig>,
+    /// `tools.egress` from the latest tool call, applied to WebSocket and SSE streams.
+    egress: Option<HttpClientFactory>,
 }
 
 impl StreamManager {
@@ -96,6 +129,9 @@ impl StreamManager {
             subscriptions: HashMap::new(),
             cancel_handles: HashMap::new(),
             workspace: None,
+            history_store: None,
+            mqtt_config: None,
+            egress: None,
         }
     }
 
@@ -105,6 +141,34 @@ impl StreamManager {
             .map(|ws| ws.join("streams").join("subscriptions.json"))
     }
 
+    fn history_store(&mut self) -> Option<TimeSeriesStore> {
+        if self.history_store.is_none() {
+            let path = self.workspace.as_ref()?.join("streams").join("history.db");
+            match TimeSeriesStore::open(&path) {
+                Ok(store) => self.history_store = Some(store),
+                Err(e) => {
+                    warn!(error = %e, path = %path.display(), "Failed to open stream history");
+                    return None;
+                }
+            }
+        }
+        self.history_store.clone()
+    }
+
+    /// Drop history older than the sink's retention window.
+    fn prune_history(&mut self, sink: &HistorySink) {
+        let Some(store) = self.history_store() else {
+            return;
+        };
+        let cutoff =
+            chrono::Utc::now().timestamp_millis() - i64::from(sink.retention_days) * 86_400_000;
+        match store.prune_before(&sink.series, cutoff) {
+            Ok(0) => {}
+            Ok(removed) => debug!(series = %sink.series, removed, "Pruned stream history"),
+            Err(e) => warn!(error = %e, series = %sink.series, "Failed to prune stream history"),
+        }
+    }
+
     /// Save all auto_restore subscriptions to disk.
     fn save_rules(&self) {
         let path = match self.persistence_path() {
@@ -126,6 +190,9 @@ impl StreamManager {
                 created_at: s.created_at,
                 auto_restore: true,
                 max_reconnect: s.max_reconnect,
+                history: s.history.clone(),
+                topic: s.topic.clone(),
+                qos: s.qos,
             })
             .collect();
         if let Some(parent) = path.parent() {
@@ -166,16 +233,17 @@ impl Tool for StreamSubscribeTool {
     fn schema(&self) -> ToolSchema {
         ToolSchema {
             name: "stream_subscribe",
-            description: "Subscribe to real-time data streams via WebSocket or SSE (Server-Sent Events). \
-                Manage persistent connections for live market data, blockchain events, news feeds, etc. \
+            description: "Subscribe to real-time data streams via WebSocket, SSE (Server-Sent Events) or MQTT topics (broker from iot.mqtt). \
+                Manage persistent connections for live market data, blockchain events, news feeds, IoT sensors, etc. \
                 Actions: 'subscribe' (create new stream), 'unsubscribe' (close stream), 'read' (get buffered messages), \
-                'send' (send message to WebSocket), 'list' (list active streams), 'status' (get stream status).",
+                'send' (send message to WebSocket, or publish to an MQTT stream's topic), 'list' (list active streams), 'status' (get stream status), \
+                'local_history' (query ticks stored locally by subscriptions created with persist=true; works offline).",
             parameters: json!({
                 "type": "object",
                 "properties": {
                     "action": {
                         "type": "string",
-                        "enum": ["subscribe", "unsubscribe", "read", "send", "list", "status", "restore"],
+                        "enum": ["subscribe", "unsubscribe", "read", "send", "list", "status", "restore", "local_history"],
                         "description": "Action to perform. 'restore' re-connects all persisted subscriptions."
                     },
                     "url": {
@@ -184,8 +252,17 @@ impl Tool for StreamSubscribeTool {
                     },
                     "protocol": {
                         "type": "string",
-                        "enum": ["websocket", "sse", "auto"],
-                        "description": "(subscribe) Protocol type. 'auto' detects from URL scheme. Default: auto"
+                        "enum": ["websocket", "sse", "mqtt", "auto"],
+                        "description": "(subscribe) Protocol type. 'auto' detects from URL scheme, or picks mqtt when 'topic' is given. Default: auto"
+                    },
+                    "topic": {
+                        "type": "string",
+                        "description": "(subscribe) MQTT topic filter, + and # wildcards allowed, e.g. 'home/+/temperature'. Messages are buffered as {topic, payload, retain, timestamp}, so filter/value_path look like 'payload.temperature'. (send) Topic to publish to; defaults to the stream's topic"
+                    },
+                    "qos": {
+                        "type": "integer",
+                        "enum": [0, 1, 2],
+                        "description": "(subscribe/send, MQTT) QoS level. Default: iot.mqtt.defaultQos"
                     },
                     "stream_id": {
                         "type": "string",
@@ -197,7 +274,7 @@ impl Tool for StreamSubscribeTool {
                     },
                     "message": {
                         "type": "string",
-                        "description": "(send) Message to send to the WebSocket stream"
+                        "description": "(send) Message to send to the WebSocket stream or publish on the MQTT topic"
                     },
                     "headers": {
                         "type": "object",
@@ -213,11 +290,11 @@ impl Tool for StreamSubscribeTool {
                     },
                     "limit": {
                         "type": "integer",
-                        "description": "(read) Max messages to return. Default: 20"
+                        "description": "(read/local_history) Max messages to return. Default: 20 for read, 100 for local_history"
                     },
                     "since_timestamp": {
                         "type": "integer",
-                        "description": "(read) Only return messages after this Unix timestamp (ms)"
+                        "description": "(read/local_history) Only return messages after this Unix timestamp (ms)"
                     },
                     "auto_restore": {
                         "type": "boolean",
@@ -234,6 +311,30 @@ impl Tool for StreamSubscribeTool {
                     "symbol": {
                         "type": "string",
                         "description": "(subscribe with preset) Trading pair symbol, e.g. 'btcusdt'. Alternative to embedding in preset string."
+                    },
+                    "persist": {
+                        "type": "boolean",
+                        "description": "(subscribe) Also store every tick in the local history database for later local_history queries. Default: false"
+                    },
+                    "series": {
+                        "type": "string",
+                        "description": "(subscribe/local_history) History series name. Default: the preset (e.g. 'binance:trade:btcusdt') or the stream_id. local_history without series or stream_id lists stored series."
+                    },
+                    "value_path": {
+                        "type": "string",
+                        "description": "(subscribe) JSON path of the numeric value kept with each stored tick, used for stats, e.g. 'p' (Binance trade price) or 'data.0.last'"
+                    },
+                    "retention_days": {
+                        "type": "integer",
+                        "description": "(subscribe) Days of local history to keep. Default: 30"
+                    },
+                    "window_secs": {
+                        "type": "integer",
+                        "description": "(local_history) Only ticks from the last N seconds. Alternative to since_timestamp"
+                    },
+                    "until_timestamp": {
+                        "type": "integer",
+                        "description": "(local_history) Only ticks at or before this Unix timestamp (ms)"
                     }
                 },
                 "required": ["action"]
@@ -255,13 +356,19 @@ impl Tool for StreamSubscribeTool {
                     .and_then(|v| v.as_str())
                     .unwrap_or("")
                     .is_empty();
-                if !has_url && !has_preset {
+                let has_topic = !params
+                    .get("topic")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .trim()
+                    .is_empty();
+                if !has_url && !has_preset && !has_topic {
                     return Err(Error::Validation(
-                        "'url' or 'preset' is required for subscribe".into(),
+                        "'url', 'preset' or 'topic' is required for subscribe".into(),
                     ));
                 }
             }
-            "restore" => {}
+            "restore" | "local_history" => {}
             "unsubscribe" | "read" | "send" | "status" => {
                 if params
                     .get("stream_id")
@@ -294,6 +401,8 @@ impl Tool for StreamSubscribeTool {
             if mgr.workspace.is_none() {
                 mgr.workspace = Some(ctx.workspace.clone());
             }
+            mgr.mqtt_config = Some(ctx.config.iot.mqtt.clone());
+            mgr.egress = Some(HttpClientFactory::for_tool(&ctx.config, "stream_subscribe"));
         }
 
         // Auto-restore persisted subscriptions on first call in this process
@@ -315,6 +424,7 @@ impl Tool for StreamSubscribeTool {
             "list" => action_list().await,
             "status" => action_status(&params).await,
             "restore" => action_restore().await,
+            "local_history" => action_local_history(&params).await,
             _ => Err(Error::Tool(format!("Unknown action: {}", action))),
         }
     }
@@ -470,11 +580,52 @@ fn resolve_preset(
     }
 }
 
+/// Egress policy for stream connections (the default policy before the first tool call).
+async fn stream_egress() -> HttpClientFactory {
+    STREAM_MANAGER
+        .lock()
+        .await
+        .egress
+        .clone()
+        .unwrap_or_else(|| {
+            HttpClientFactory::for_tool(&blockcell_core::Config::default(), "stream_subscribe")
+        })
+}
+
 async fn action_subscribe(params: &Value) -> Result<Value> {
+    let topic = params
+        .get("topic")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string);
+    let mqtt_config = STREAM_MANAGER
+        .lock()
+        .await
+        .mqtt_config
+        .clone()
+        .unwrap_or_default();
+    let qos = match params.get("qos").and_then(|v| v.as_u64()) {
+        Some(level) => level.min(u8::MAX as u64) as u8,
+        None => mqtt_config.default_qos,
+    };
+
     // Resolve preset if provided
-    let (url, init_message, filter) = if let Some(preset) =
-        params.get("preset").and_then(|v| v.as_str())
-    {
+    let (url, init_message, filter) = if let Some(ref topic) = topic {
+        (
+            format!(
+                "mqtt://{}:{}/{}",
+                mqtt_config.host.trim(),
+                mqtt_config.effective_port(),
+                topic
+            ),
+            None,
+            params
+                .get("filter")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+        )
+    } else if let Some(preset) = params.get("preset").and_then(|v| v.as_str()) {
         if !preset.is_empty() {
             let symbol_override = params.get("symbol").and_then(|v| v.as_str());
             let (preset_url, preset_init, preset_filter) = resolve_preset(preset, symbol_override)?;
@@ -542,7 +693,9 @@ async fn action_subscribe(params: &Value) -> Result<Value> {
     }
 
     // Detect protocol
-    let detected_protocol = if protocol == "auto" {
+    let detected_protocol = if topic.is_some() {
+        "mqtt"
+    } else if protocol == "auto" {
         if url.starts_with("wss://") || url.starts_with("ws://") {
             "websocket"
         } else {
@@ -570,6 +723,7 @@ async fn action_subscribe(params: &Value) -> Result<Value> {
         .get("max_reconnect")
         .and_then(|v| v.as_u64())
         .unwrap_or(0) as u32;
+    let history = history_sink(params, &stream_id);
 
     let sub = StreamSubscription {
         id: stream_id.clone(),
@@ -588,8 +742,19 @@ async fn action_subscribe(params: &Value) -> Result<Value> {
         auto_restore,
         reconnect_count: 0,
         max_reconnect,
+        history: history.clone(),
+        topic: topic.clone(),
+        qos: topic.as_ref().map(|_| qos),
     };
 
+    if detected_protocol == "mqtt" {
+        qos_from(qos)?;
+        // Fail fast on a missing broker config instead of leaving a dead stream behind.
+        mqtt::hub(&mqtt_config).await?;
+    } else {
+        stream_egress().await.check(&url).await?;
+    }
+
     // Create cancel channel
     let (cancel_tx, cancel_rx) = tokio::sync::watch::channel(false);
 
@@ -598,6 +763,9 @@ async fn action_subscribe(params: &Value) -> Result<Value> {
         mgr.subscriptions.insert(stream_id.clone(), sub);
         mgr.cancel_handles.insert(stream_id.clone(), cancel_tx);
         mgr.save_rules();
+        if let Some(ref sink) = history {
+            mgr.prune_history(sink);
+        }
     }
 
     // Spawn background task
@@ -617,6 +785,15 @@ async fn action_subscribe(params: &Value) -> Result<Value> {
         "sse" => {
             tokio::spawn(run_sse_stream(sid, url_clone, headers_clone, cancel_rx));
         }
+        "mqtt" => {
+            tokio::spawn(run_mqtt_stream(
+                sid,
+                mqtt_config,
+                topic.clone().unwrap_or_default(),
+                qos,
+                cancel_rx,
+            ));
+        }
         _ => {
             return Err(Error::Tool(format!(
                 "Unknown protocol: {}",
@@ -629,11 +806,13 @@ async fn action_subscribe(params: &Value) -> Result<Value> {
         "stream_id": stream_id,
         "url": url,
         "protocol": detected_protocol,
+        "topic": topic,
         "status": "connecting",
         "buffer_size": buffer_size,
         "filter": filter,
         "auto_restore": auto_restore,
         "max_reconnect": max_reconnect,
+        "history_series": history.as_ref().map(|h| h.series.clone()),
         "note": "Stream is connecting in the background. Use action='read' with this stream_id to get messages, or action='status' to check connection state."
     }))
 }
@@ -802,6 +981,90 @@ async fn run_websocket_stream(
     } // 'reconnect loop
 }
 
+/// Feed an MQTT subscription into the stream buffer. Reconnects and resubscription
+/// are handled by the shared connection; this only mirrors its state, and subscribes
+/// again when the connection is replaced after an `iot.mqtt` change.
+async fn run_mqtt_stream(
+    stream_id: String,
+    config: MqttConfig,
+    topic: String,
+    qos: u8,
+    mut cancel_rx: tokio::sync::watch::Receiver<bool>,
+) {
+    let qos = match qos_from(qos) {
+        Ok(qos) => qos,
+        Err(e) => {
+            set_stream_error(&stream_id, &e.to_string()).await;
+            return;
+        }
+    };
+
+    loop {
+        let config = STREAM_MANAGER
+            .lock()
+            .await
+            .mqtt_config
+            .clone()
+            .unwrap_or_else(|| config.clone());
+        let hub = match mqtt::hub(&config).await {
+            Ok(hub) => hub,
+            Err(e) => {
+                set_stream_error(&stream_id, &e.to_string()).await;
+                return;
+            }
+        };
+        let (listener, mut rx) = match hub.subscribe(&topic, qos).await {
+            Ok(subscribed) => subscribed,
+            Err(e) => {
+                set_stream_error(&stream_id, &format!("MQTT subscribe failed: {}", e)).await;
+                return;
+            }
+        };
+        info!(stream_id = %stream_id, topic = %topic, "MQTT stream subscribed");
+
+        let mut connection = hub.connection();
+        let state = |up: bool| if up { "connected" } else { "connecting" };
+        set_stream_status(&stream_id, state(hub.is_connected())).await;
+        let mut was_cancelled = false;
+        loop {
+            tokio::select! {
+                message = rx.recv() => match message {
+                    Some(message) => {
+                        buffer_message(&stream_id, &message.to_json().to_string()).await;
+                    }
+                    None => break,
+                },
+                changed = connection.changed() => {
+                    if changed.is_err() {
+                        break;
+                    }
+                    let up = *connection.borrow();
+                    set_stream_status(&stream_id, state(up)).await;
+                }
+                _ = cancel_rx.changed() => {
+                    if *cancel_rx.borrow() {
+                        info!(stream_id = %stream_id, "MQTT stream cancelled");
+                        set_stream_status(&stream_id, "disconnected").await;
+                        was_cancelled = true;
+                        break;
+                    }
+                }
+            }
+        }
+        hub.unsubscribe(listener).await;
+
+        let removed = !STREAM_MANAGER
+            .lock()
+            .await
+            .subscriptions
+            .contains_key(&stream_id);
+        if was_cancelled || removed {
+            return;
+        }
+        set_stream_status(&stream_id, "connecting").await;
+    }
+}
+
 /// Global WebSocket write handles for the send action.
 static WS_WRITERS: Lazy<Arc<Mutex<HashMap<String, WsWriteHandle>>>> =
     Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
@@ -817,7 +1080,7 @@ async fn run_sse_stream(
     'reconnect: loop {
         info!(stream_id = %stream_id, url = %url, attempt = reconnect_attempt, "SSE stream connecting");
 
-        let client = reqwest::Client::new();
+        let client = stream_egress().await.builder().build().unwrap_or_default();
         let mut req = client
             .get(&url)
             .header("Accept", "text/event-stream")
@@ -967,6 +1230,7 @@ async fn run_sse_stream(
 async fn buffer_message(stream_id: &str, data: &str) {
     let now = chrono::Utc::now().timestamp_millis();
     let mut mgr = STREAM_MANAGER.lock().await;
+    let mut persist = None;
     if let Some(sub) = mgr.subscriptions.get_mut(stream_id) {
         // Apply filter if set
         let should_buffer = if let Some(ref filter_path) = sub.filter {
@@ -1002,8 +1266,71 @@ async fn buffer_message(stream_id: &str, data: &str) {
             while sub.buffer.len() > sub.buffer_size {
                 sub.buffer.remove(0);
             }
+            persist = sub.history.clone();
         }
     }
+
+    let Some(sink) = persist else {
+        return;
+    };
+    let Some(store) = mgr.history_store() else {
+        return;
+    };
+    drop(mgr);
+    let value = sink
+        .value_path
+        .as_deref()
+        .and_then(|path| tick_value(data, path));
+    if let Err(e) = store.append(&sink.series, now, value, data) {
+        warn!(error = %e, series = %sink.series, "Failed to store stream tick");
+    }
+}
+
+/// Numeric value at `path` in a JSON tick; numeric strings count (exchanges send prices as strings).
+fn tick_value(data: &str, path: &str) -> Option<f64> {
+    let parsed: Value = serde_json::from_str(data).ok()?;
+    let mut current = &parsed;
+    for part in path.split('.') {
+        current = match part.parse::<usize>() {
+            Ok(index) if current.is_array() => current.get(index)?,
+            _ => current.get(part)?,
+        };
+    }
+    match current {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// History settings from subscribe params, when `persist` is set.
+fn history_sink(params: &Value, stream_id: &str) -> Option<HistorySink> {
+    if !params
+        .get("persist")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+    {
+        return None;
+    }
+    let non_empty = |key: &str| {
+        params
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+    Some(HistorySink {
+        series: non_empty("series")
+            .or_else(|| non_empty("preset"))
+            .unwrap_or_else(|| stream_id.to_string()),
+        value_path: non_empty("value_path"),
+        retention_days: params
+            .get("retention_days")
+            .and_then(|v| v.as_u64())
+            .map(|d| d.clamp(1, 3650) as u32)
+            .unwrap_or(DEFAULT_RETENTION_DAYS),
+    })
 }
 
 /// Check if a stream should attempt reconnection.
@@ -1135,9 +1462,16 @@ async fn action_send(params: &Value) -> Result<Value> {
             .subscriptions
             .get(stream_id)
             .ok_or_else(|| Error::Tool(format!("Stream '{}' not found", stream_id)))?;
+        if sub.protocol == "mqtt" {
+            let stream_topic = sub.topic.clone().unwrap_or_default();
+            let qos = sub.qos;
+            let config = mgr.mqtt_config.clone().unwrap_or_default();
+            drop(mgr);
+            return mqtt_publish(params, &config, &stream_topic, qos, message).await;
+        }
         if sub.protocol != "websocket" {
             return Err(Error::Tool(
-                "Can only send messages to WebSocket streams".into(),
+                "Can only send messages to WebSocket or MQTT streams".into(),
             ));
         }
         if sub.status != "connected" {
@@ -1171,6 +1505,37 @@ async fn action_send(params: &Value) -> Result<Value> {
     }
 }
 
+/// `send` on an MQTT stream: publish to `topic`, or to the stream's own topic.
+async fn mqtt_publish(
+    params: &Value,
+    config: &MqttConfig,
+    stream_topic: &str,
+    stream_qos: Option<u8>,
+    message: &str,
+) -> Result<Value> {
+    let topic = params
+        .get("topic")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .unwrap_or(stream_topic);
+    let qos = params
+        .get("qos")
+        .and_then(|v| v.as_u64())
+        .map(|q| q.min(u8::MAX as u64) as u8)
+        .or(stream_qos)
+        .unwrap_or(config.default_qos);
+    let hub = mqtt::hub(config).await?;
+    hub.publish(topic, message.as_bytes().to_vec(), qos_from(qos)?, false)
+        .await?;
+    Ok(json!({
+        "stream_id": params["stream_id"],
+        "sent": true,
+        "topic": topic,
+        "message_length": message.len()
+    }))
+}
+
 async fn action_list() -> Result<Value> {
     let mgr = STREAM_MANAGER.lock().await;
     let streams: Vec<Value> = mgr
@@ -1181,6 +1546,7 @@ async fn action_list() -> Result<Value> {
                 "stream_id": sub.id,
                 "url": sub.url,
                 "protocol": sub.protocol,
+                "topic": sub.topic,
                 "status": sub.status,
                 "message_count": sub.message_count,
                 "buffered": sub.buffer.len(),
@@ -1211,6 +1577,8 @@ async fn action_status(params: &Value) -> Result<Value> {
         "stream_id": sub.id,
         "url": sub.url,
         "protocol": sub.protocol,
+        "topic": sub.topic,
+        "qos": sub.qos,
         "status": sub.status,
         "message_count": sub.message_count,
         "buffered": sub.buffer.len(),
@@ -1222,6 +1590,79 @@ async fn action_status(params: &Value) -> Result<Value> {
         "auto_restore": sub.auto_restore,
         "reconnect_count": sub.reconnect_count,
         "max_reconnect": sub.max_reconnect,
+        "history": sub.history,
+    }))
+}
+
+/// Query the local history store. Needs no network, so it also works offline.
+async fn action_local_history(params: &Value) -> Result<Value> {
+    let mut mgr = STREAM_MANAGER.lock().await;
+    let series = match params.get("series").and_then(|v| v.as_str()) {
+        Some(series) if !series.is_empty() => Some(series.to_string()),
+        _ => match params.get("stream_id").and_then(|v| v.as_str()) {
+            Some(stream_id) if !stream_id.is_empty() => {
+                let sub = mgr
+                    .subscriptions
+                    .get(stream_id)
+                    .ok_or_else(|| Error::Tool(format!("Stream '{}' not found", stream_id)))?;
+                let sink = sub.history.as_ref().ok_or_else(|| {
+                    Error::Tool(format!(
+                        "Stream '{}' does not store history; subscribe with persist=true",
+                        stream_id
+                    ))
+                })?;
+                Some(sink.series.clone())
+            }
+            _ => None,
+        },
+    };
+    let store = mgr
+        .history_store()
+        .ok_or_else(|| Error::Tool("Local stream history is not available".into()))?;
+    drop(mgr);
+
+    let Some(series) = series else {
+        let series = store.list_series()?;
+        return Ok(json!({ "count": series.len(), "series": series }));
+    };
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let since = params
+        .get("since_timestamp")
+        .and_then(|v| v.as_i64())
+        .or_else(|| {
+            params
+                .get("window_secs")
+                .and_then(|v| v.as_i64())
+                .map(|secs| now - secs * 1000)
+        });
+    let until = params.get("until_timestamp").and_then(|v| v.as_i64());
+    let limit = params
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(100)
+        .min(10_000) as usize;
+
+    let stats = store.stats(&series, since, until)?;
+    let points: Vec<Value> = store
+        .range(&series, since, until, limit)?
+        .into_iter()
+        .map(|p| {
+            json!({
+                "timestamp": p.timestamp,
+                "value": p.value,
+                "data": serde_json::from_str::<Value>(&p.data).unwrap_or(Value::String(p.data)),
+            })
+        })
+        .collect();
+
+    Ok(json!({
+        "series": series,
+        "since_timestamp": since,
+        "until_timestamp": until,
+        "stats": stats,
+        "returned": points.len(),
+        "points": points,
     }))
 }
 
@@ -1264,7 +1705,16 @@ async fn restore_all_subscriptions() -> Result<Value> {
             auto_restore: true,
             reconnect_count: 0,
             max_reconnect: rule.max_reconnect,
+            history: rule.history.clone(),
+            topic: rule.topic.clone(),
+            qos: rule.qos,
         };
+        if rule.protocol != "mqtt" {
+            if let Err(e) = stream_egress().await.check(&rule.url).await {
+                warn!(stream_id = %rule.id, error = %e, "Not restoring stream blocked by egress policy");
+                continue;
+            }
+        }
 
         let (cancel_tx, cancel_rx) = tokio::sync::watch::channel(false);
 
@@ -1272,6 +1722,9 @@ async fn restore_all_subscriptions() -> Result<Value> {
             let mut mgr = STREAM_MANAGER.lock().await;
             mgr.subscriptions.insert(rule.id.clone(), sub);
             mgr.cancel_handles.insert(rule.id.clone(), cancel_tx);
+            if let Some(ref sink) = rule.history {
+                mgr.prune_history(sink);
+            }
         }
 
         let sid = rule.id.clone();
@@ -1293,6 +1746,19 @@ async fn restore_all_subscriptions() -> Result<Value> {
             "sse" => {
                 tokio::spawn(run_sse_stream(sid, url, headers, cancel_rx));
             }
+            "mqtt" => {
+                let config = {
+                    let mgr = STREAM_MANAGER.lock().await;
+                    mgr.mqtt_config.clone().unwrap_or_default()
+                };
+                tokio::spawn(run_mqtt_stream(
+                    sid,
+                    config.clone(),
+                    rule.topic.clone().unwrap_or_default(),
+                    rule.qos.unwrap_or(config.default_qos),
+                    cancel_rx,
+                ));
+            }
             _ => {
                 warn!(protocol = %protocol, "Unknown protocol in persisted rule, skipping");
                 continue;
@@ -1355,6 +1821,29 @@ mod tests {
         assert!(tool.validate(&params).is_err());
     }
 
+    #[test]
+    fn test_validate_subscribe_mqtt_topic() {
+        let tool = StreamSubscribeTool;
+        let params = json!({"action": "subscribe", "topic": "home/+/temperature"});
+        assert!(tool.validate(&params).is_ok());
+
+        // Persisted rules from before MQTT support still load.
+        let rule: SubscriptionRule = serde_json::from_str(
+            r#"{"id":"stream_old","url":"wss://x","protocol":"websocket","buffer_size":10,"filter":null,"headers":{},"init_message":null,"created_at":0,"auto_restore":true,"max_reconnect":0}"#,
+        )
+        .unwrap();
+        assert!(rule.topic.is_none());
+
+        let message = mqtt::MqttMessage {
+            topic: "home/kitchen/temperature".to_string(),
+            payload: br#"{"temperature": 21.5}"#.to_vec(),
+            retain: false,
+            timestamp: 0,
+        };
+        let data = message.to_json().to_string();
+        assert_eq!(tick_value(&data, "payload.temperature"), Some(21.5));
+    }
+
     #[test]
     fn test_validate_read() {
         let tool = StreamSubscribeTool;
@@ -1413,6 +1902,9 @@ mod tests {
             created_at: 1000,
             auto_restore: true,
             max_reconnect: 5,
+            history: None,
+            topic: None,
+            qos: None,
         };
         let json = serde_json::to_string(&rule).unwrap();
         let parsed: SubscriptionRule = serde_json::from_str(&json).unwrap();
@@ -1422,6 +1914,34 @@ mod tests {
         assert_eq!(parsed.filter, Some("data.price".to_string()));
     }
 
+    #[test]
+    fn test_history_sink_and_tick_value() {
+        assert!(history_sink(&json!({"action": "subscribe"}), "stream_1").is_none());
+        let sink = history_sink(
+            &json!({"persist": true, "preset": "binance:trade:btcusdt", "value_path": "p"}),
+            "stream_1",
+        )
+        .unwrap();
+        assert_eq!(sink.series, "binance:trade:btcusdt");
+        assert_eq!(sink.retention_days, DEFAULT_RETENTION_DAYS);
+        let named = history_sink(&json!({"persist": true, "series": "btc"}), "stream_1").unwrap();
+        assert_eq!(named.series, "btc");
+
+        assert_eq!(tick_value(r#"{"p": "68523.50"}"#, "p"), Some(68523.5));
+        assert_eq!(
+            tick_value(r#"{"data": [{"last": 3.5}]}"#, "data.0.last"),
+            Some(3.5)
+        );
+        assert_eq!(tick_value(r#"{"p": "n/a"}"#, "p"), None);
+        assert_eq!(tick_value("pong", "p"), None);
+
+        // Rules saved before history existed still load.
+        let old = r#"{"id":"s","url":"wss://x","protocol":"websocket","buffer_size":1,"filter":null,
+            "headers":{},"init_message":null,"created_at":0,"auto_restore":true,"max_reconnect":0}"#;
+        let rule: SubscriptionRule = serde_json::from_str(old).unwrap();
+        assert!(rule.history.is_none());
+    }
+
     #[test]
     fn test_persistence_path() {
         let mut mgr = StreamManager::new();
@@ -0,0 +1,573 @@
+use async_trait::async_trait;
+use blockcell_core::{Error, Result};
+use chrono::{Months, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::cron::execute_cron_action_with_paths;
+use crate::todo::parse_local_time;
+use crate::{PromptContext, Tool, ToolContext, ToolSchema};
+
+/// Cancellation reminders fire at this local hour on the chosen day.
+const REMINDER_HOUR: &str = "10:00";
+
+/// Persistent subscription store — saved to workspace/subscriptions/subscriptions.json
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubscriptionStore {
+    version: u32,
+    #[serde(default)]
+    pub subscriptions: Vec<Subscription>,
+}
+
+impl Default for SubscriptionStore {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            subscriptions: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Period {
+    Weekly,
+    Monthly,
+    Quarterly,
+    Yearly,
+}
+
+impl Period {
+    fn parse(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "weekly" | "week" => Ok(Self::Weekly),
+            "monthly" | "month" => Ok(Self::Monthly),
+            "quarterly" | "quarter" => Ok(Self::Quarterly),
+            "yearly" | "year" | "annual" | "annually" => Ok(Self::Yearly),
+            other => Err(Error::Validation(format!(
+                "Invalid period '{}'. Use weekly, monthly, quarterly or yearly",
+                other
+            ))),
+        }
+    }
+
+    /// The `n`-th renewal after `anchor`. Month-based periods are computed from the
+    /// anchor each time so a renewal on the 31st does not drift after a short month.
+    fn nth(self, anchor: NaiveDate, n: u32) -> Option<NaiveDate> {
+        match self {
+            Self::Weekly => anchor.checked_add_days(chrono::Days::new(7 * n as u64)),
+            Self::Monthly => anchor.checked_add_months(Months::new(n)),
+            Self::Quarterly => anchor.checked_add_months(Months::new(3 * n)),
+            Self::Yearly => anchor.checked_add_months(Months::new(12 * n)),
+        }
+    }
+
+    /// Multiplier to a monthly amount.
+    fn per_month(self) -> f64 {
+        match self {
+            Self::Weekly => 52.0 / 12.0,
+            Self::Monthly => 1.0,
+            Self::Quarterly => 1.0 / 3.0,
+            Self::Yearly => 1.0 / 12.0,
+        }
+    }
+}
+
+/// A recurring charge.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Subscription {
+    pub id: String,
+    pub name: String,
+    pub amount: f64,
+    /// ISO 4217 code, e.g. "USD", "CNY".
+    pub currency: String,
+    pub period: Period,
+    /// A known renewal date (YYYY-MM-DD); later renewals are derived from it.
+    pub anchor: NaiveDate,
+    #[serde(default)]
+    pub notes: String,
+    /// Cron job id of the pending cancellation reminder, if one was created.
+    #[serde(default)]
+    pub cancel_reminder_job: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl Subscription {
+    /// First renewal on or after `today`.
+    pub fn next_renewal(&self, today: NaiveDate) -> NaiveDate {
+        if self.anchor >= today {
+            return self.anchor;
+        }
+        (1..=5000)
+            .filter_map(|n| self.period.nth(self.anchor, n))
+            .find(|d| *d >= today)
+            .unwrap_or(today)
+    }
+}
+
+fn store_path(workspace: &Path) -> PathBuf {
+    workspace.join("subscriptions").join("subscriptions.json")
+}
+
+pub fn load_store(workspace: &Path) -> Result<SubscriptionStore> {
+    let path = store_path(workspace);
+    if !path.exists() {
+        return Ok(SubscriptionStore::default());
+    }
+    let content = std::fs::read_to_string(&path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+fn save_store(workspace: &Path, store: &SubscriptionStore) -> Result<()> {
+    let path = store_path(workspace);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(store)?)?;
+    Ok(())
+}
+
+impl SubscriptionStore {
+    /// Find by id or (case-insensitive) name.
+    fn find_index(&self, key: &str) -> Result<usize> {
+        let key = key.trim();
+        self.subscriptions
+            .iter()
+            .position(|s| s.id == key)
+            .or_else(|| {
+                self.subscriptions
+                    .iter()
+                    .position(|s| s.name.eq_ignore_ascii_case(key))
+            })
+            .ok_or_else(|| Error::NotFound(format!("Subscription not found: {}", key)))
+    }
+
+    /// Subscriptions renewing within `days` of `today`, soonest first.
+    pub fn upcoming(&self, today: NaiveDate, days: u32) -> Vec<(NaiveDate, &Subscription)> {
+        let Some(until) = today.checked_add_days(chrono::Days::new(days as u64)) else {
+            return Vec::new();
+        };
+        let mut due: Vec<(NaiveDate, &Subscription)> = self
+            .subscriptions
+            .iter()
+            .map(|s| (s.next_renewal(today), s))
+            .filter(|(d, _)| *d <= until)
+            .collect();
+        due.sort_by_key(|(d, _)| *d);
+        due
+    }
+
+    /// Monthly and yearly totals per currency.
+    fn totals(&self) -> BTreeMap<String, (f64, f64)> {
+        let mut totals: BTreeMap<String, (f64, f64)> = BTreeMap::new();
+        for s in &self.subscriptions {
+            let monthly = s.amount * s.period.per_month();
+            let entry = totals.entry(s.currency.clone()).or_default();
+            entry.0 += monthly;
+            entry.1 += monthly * 12.0;
+        }
+        totals
+    }
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+pub fn local_today(tz: Option<&str>) -> NaiveDate {
+    match tz.and_then(|t| t.parse::<chrono_tz::Tz>().ok()) {
+        Some(tz) => Utc::now().with_timezone(&tz).date_naive(),
+        None => chrono::Local::now().date_naive(),
+    }
+}
+
+fn subscription_json(s: &Subscription, today: NaiveDate) -> Value {
+    let next = s.next_renewal(today);
+    let mut value = json!(s);
+    value["next_renewal"] = json!(next.format("%Y-%m-%d").to_string());
+    value["days_until_renewal"] = json!((next - today).num_days());
+    value
+}
+
+/// Digest text for subscriptions renewing in the next 7 days, or `None` when there are none.
+pub fn renewals_digest(workspace: &Path, tz: Option<&str>) -> Option<String> {
+    let store = load_store(workspace).ok()?;
+    let today = local_today(tz);
+    let due = store.upcoming(today, 7);
+    if due.is_empty() {
+        return None;
+    }
+    let lines: Vec<String> = due
+        .iter()
+        .map(|(date, s)| {
+            format!(
+                "- {}：{} {}（{}）",
+                s.name,
+                round2(s.amount),
+                s.currency,
+                date.format("%m-%d")
+            )
+        })
+        .collect();
+    Some(format!(
+        "本周有 {} 项订阅续费：\n{}",
+        due.len(),
+        lines.join("\n")
+    ))
+}
+
+fn str_param<'a>(params: &'a Value, key: &str) -> Option<&'a str> {
+    params
+        .get(key)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| Error::Validation(format!("Invalid date '{}'. Use YYYY-MM-DD", value)))
+}
+
+pub struct SubscriptionsTool;
+
+#[async_trait]
+impl Tool for SubscriptionsTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "subscriptions",
+            description: "Track the user's recurring charges (streaming, SaaS, memberships). \
+                Renewals in the coming week appear in the morning digest. \
+                Actions: 'add' (name, amount, currency, period, next_renewal), 'update' (id + fields), \
+                'remove' (id), 'list' (with monthly/yearly totals per currency), \
+                'upcoming' (renewals within days, default 7), \
+                'cancel_reminder' (id, days_before default 3: creates a one-time cron reminder before the next renewal).",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["add", "update", "remove", "list", "upcoming", "cancel_reminder"],
+                        "description": "Action to perform"
+                    },
+                    "id": {
+                        "type": "string",
+                        "description": "(update/remove/cancel_reminder) Subscription id or name"
+                    },
+                    "name": {"type": "string", "description": "(add/update) Service name, e.g. 'Netflix'"},
+                    "amount": {"type": "number", "description": "(add/update) Charge per period"},
+                    "currency": {"type": "string", "description": "(add/update) ISO 4217 code, e.g. USD, CNY"},
+                    "period": {
+                        "type": "string",
+                        "enum": ["weekly", "monthly", "quarterly", "yearly"],
+                        "description": "(add/update) Billing period"
+                    },
+                    "next_renewal": {"type": "string", "description": "(add/update) Next charge date, YYYY-MM-DD"},
+                    "notes": {"type": "string", "description": "(add/update) Free-form notes, e.g. how to cancel"},
+                    "days": {"type": "integer", "description": "(upcoming) Look-ahead in days, default 7"},
+                    "days_before": {"type": "integer", "description": "(cancel_reminder) Remind this many days before the renewal, default 3"}
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        let action = params.get("action").and_then(|v| v.as_str()).unwrap_or("");
+        match action {
+            "add" => {
+                for key in ["name", "currency", "period", "next_renewal"] {
+                    if str_param(params, key).is_none() {
+                        return Err(Error::Validation(format!("'{}' is required for add", key)));
+                    }
+                }
+                if params.get("amount").and_then(|v| v.as_f64()).is_none() {
+                    return Err(Error::Validation("'amount' is required for add".into()));
+                }
+            }
+            "update" | "remove" | "cancel_reminder" => {
+                if str_param(params, "id").is_none() {
+                    return Err(Error::Validation(format!(
+                        "'id' is required for {}",
+                        action
+                    )));
+                }
+            }
+            "list" | "upcoming" => {}
+            _ => return Err(Error::Validation(format!("Unknown action: {}", action))),
+        }
+        Ok(())
+    }
+
+    fn prompt_rule(&self, _ctx: &PromptContext) -> Option<String> {
+        Some(
+            "- Recurring charges (\"I pay 15 USD a month for Netflix\", \"what subscriptions renew this week\", \"remind me to cancel before it renews\") go through the `subscriptions` tool; use `cancel_reminder` rather than creating the cron job yourself."
+                .to_string(),
+        )
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        let action = params["action"].as_str().unwrap_or("").to_string();
+        let tz = ctx.config.default_timezone.clone();
+        let workspace = ctx.workspace.clone();
+        let paths = ctx.paths();
+        let channel = ctx.channel.clone();
+        let chat_id = ctx.chat_id.clone();
+        let role = ctx.role();
+        tokio::task::spawn_blocking(move || {
+            let tz = tz.as_deref();
+            let today = local_today(tz);
+            let mut store = load_store(&workspace)?;
+            let now = Utc::now().timestamp_millis();
+            match action.as_str() {
+                "add" => {
+                    let amount = params["amount"].as_f64().unwrap_or(0.0);
+                    if amount < 0.0 {
+                        return Err(Error::Validation("'amount' must not be negative".into()));
+                    }
+                    let sub = Subscription {
+                        id: format!(
+                            "sub_{}",
+                            Uuid::new_v4().to_string().split('-').next().unwrap_or("x")
+                        ),
+                        name: str_param(&params, "name").unwrap_or_default().to_string(),
+                        amount,
+                        currency: str_param(&params, "currency")
+                            .unwrap_or_default()
+                            .to_ascii_uppercase(),
+                        period: Period::parse(str_param(&params, "period").unwrap_or_default())?,
+                        anchor: parse_date(str_param(&params, "next_renewal").unwrap_or_default())?,
+                        notes: str_param(&params, "notes").unwrap_or_default().to_string(),
+                        cancel_reminder_job: None,
+                        created_at: now,
+                        updated_at: now,
+                    };
+                    let out =
+                        json!({"status": "added", "subscription": subscription_json(&sub, today)});
+                    store.subscriptions.push(sub);
+                    save_store(&workspace, &store)?;
+                    Ok(out)
+                }
+                "update" => {
+                    let idx = store.find_index(str_param(&params, "id").unwrap_or_default())?;
+                    let sub = &mut store.subscriptions[idx];
+                    if let Some(name) = str_param(&params, "name") {
+                        sub.name = name.to_string();
+                    }
+                    if let Some(amount) = params.get("amount").and_then(|v| v.as_f64()) {
+                        sub.amount = amount;
+                    }
+                    if let Some(currency) = str_param(&params, "currency") {
+                        sub.currency = currency.to_ascii_uppercase();
+                    }
+                    if let Some(period) = str_param(&params, "period") {
+                        sub.period = Period::parse(period)?;
+                    }
+                    if let Some(date) = str_param(&params, "next_renewal") {
+                        sub.anchor = parse_date(date)?;
+                    }
+                    if let Some(notes) = params.get("notes").and_then(|v| v.as_str()) {
+                        sub.notes = notes.trim().to_string();
+                    }
+                    sub.updated_at = now;
+                    let out =
+                        json!({"status": "updated", "subscription": subscription_json(sub, today)});
+                    save_store(&workspace, &store)?;
+                    Ok(out)
+                }
+                "remove" => {
+                    let idx = store.find_index(str_param(&params, "id").unwrap_or_default())?;
+                    let sub = store.subscriptions.remove(idx);
+                    if let Some(job) = &sub.cancel_reminder_job {
+                        let _ = execute_cron_action_with_paths(
+                            &paths,
+                            "remove",
+                            &json!({"job_id": job}),
+                            &channel,
+                            &chat_id,
+                            tz,
+                            None,
+                        );
+                    }
+                    save_store(&workspace, &store)?;
+                    Ok(json!({"status": "removed", "id": sub.id, "name": sub.name}))
+                }
+                "list" => {
+                    let subs: Vec<Value> = store
+                        .subscriptions
+                        .iter()
+                        .map(|s| subscription_json(s, today))
+                        .collect();
+                    let totals: BTreeMap<String, Value> = store
+                        .totals()
+                        .into_iter()
+                        .map(|(currency, (monthly, yearly))| {
+                            (
+                                currency,
+                                json!({"monthly": round2(monthly), "yearly": round2(yearly)}),
+                            )
+                        })
+                        .collect();
+                    Ok(json!({"subscriptions": subs, "count": subs.len(), "totals": totals}))
+                }
+                "upcoming" => {
+                    let days = params
+                        .get("days")
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(7)
+                        .min(366) as u32;
+                    let due: Vec<Value> = store
+                        .upcoming(today, days)
+                        .into_iter()
+                        .map(|(_, s)| subscription_json(s, today))
+                        .collect();
+                    Ok(json!({"renewals": due, "count": due.len(), "days": days}))
+                }
+                "cancel_reminder" => {
+                    let idx = store.find_index(str_param(&params, "id").unwrap_or_default())?;
+                    let days_before = params
+                        .get("days_before")
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(3)
+                        .min(60);
+                    let sub = &store.subscriptions[idx];
+                    let renewal = sub.next_renewal(today);
+                    let mut remind_on = renewal
+                        .checked_sub_days(chrono::Days::new(days_before))
+                        .unwrap_or(today);
+                    if remind_on < today {
+                        remind_on = today;
+                    }
+                    let mut at_ms = parse_local_time(
+                        &format!("{} {}", remind_on.format("%Y-%m-%d"), REMINDER_HOUR),
+                        tz,
+                    )?;
+                    // Already past today's reminder hour: fire shortly instead.
+                    at_ms = at_ms.max(now + 60_000);
+                    if let Some(old) = &sub.cancel_reminder_job {
+                        let _ = execute_cron_action_with_paths(
+                            &paths,
+                            "remove",
+                            &json!({"job_id": old}),
+                            &channel,
+                            &chat_id,
+                            tz,
+                            None,
+                        );
+                    }
+                    let created = execute_cron_action_with_paths(
+                        &paths,
+                        "add",
+                        &json!({
+                            "name": format!("Cancel {}", sub.name),
+                            "message": format!(
+                                "提醒：{} 将于 {} 续费（{} {}），如不再使用请记得取消。",
+                                sub.name,
+                                renewal.format("%Y-%m-%d"),
+                                round2(sub.amount),
+                                sub.currency
+                            ),
+                            "at_ms": at_ms,
+                            "mode": "reminder",
+                            "delete_after_run": true,
+                        }),
+                        &channel,
+                        &chat_id,
+                        tz,
+                        Some(role.as_str()),
+                    )?;
+                    let job_id = created["job_id"].as_str().map(str::to_string);
+                    let sub = &mut store.subscriptions[idx];
+                    sub.cancel_reminder_job = job_id.clone();
+                    sub.updated_at = now;
+                    save_store(&workspace, &store)?;
+                    Ok(json!({
+                        "status": "reminder_created",
+                        "job_id": job_id,
+                        "remind_on": remind_on.format("%Y-%m-%d").to_string(),
+                        "renewal": renewal.format("%Y-%m-%d").to_string(),
+                    }))
+                }
+                _ => Err(Error::Tool(format!("Unknown action: {}", action))),
+            }
+        })
+        .await
+        .map_err(|e| Error::Tool(format!("Subscriptions task failed: {}", e)))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sub(period: Period, anchor: &str, amount: f64) -> Subscription {
+        Subscription {
+            id: "sub_1".to_string(),
+            name: "Netflix".to_string(),
+            amount,
+            currency: "USD".to_string(),
+            period,
+            anchor: parse_date(anchor).unwrap(),
+            notes: String::new(),
+            cancel_reminder_job: None,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_next_renewal_does_not_drift() {
+        let s = sub(Period::Monthly, "2026-01-31", 10.0);
+        assert_eq!(
+            s.next_renewal(parse_date("2026-02-10").unwrap()),
+            parse_date("2026-02-28").unwrap()
+        );
+        assert_eq!(
+            s.next_renewal(parse_date("2026-03-01").unwrap()),
+            parse_date("2026-03-31").unwrap()
+        );
+        let weekly = sub(Period::Weekly, "2026-03-02", 1.0);
+        assert_eq!(
+            weekly.next_renewal(parse_date("2026-03-10").unwrap()),
+            parse_date("2026-03-16").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_upcoming_and_totals() {
+        let store = SubscriptionStore {
+            version: 1,
+            subscriptions: vec![
+                sub(Period::Monthly, "2026-03-05", 15.0),
+                sub(Period::Yearly, "2026-12-01", 120.0),
+            ],
+        };
+        let due = store.upcoming(parse_date("2026-04-01").unwrap(), 7);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].0, parse_date("2026-04-05").unwrap());
+        let (monthly, yearly) = store.totals()["USD"];
+        assert_eq!(round2(monthly), 25.0);
+        assert_eq!(round2(yearly), 300.0);
+    }
+
+    #[test]
+    fn test_validate() {
+        let tool = SubscriptionsTool;
+        assert!(tool
+            .validate(
+                &json!({"action": "add", "name": "Netflix", "currency": "USD", "period": "monthly"})
+            )
+            .is_err());
+        assert!(tool
+            .validate(&json!({"action": "add", "name": "Netflix", "amount": 15.49, "currency": "USD", "period": "monthly", "next_renewal": "2026-03-05"}))
+            .is_ok());
+        assert!(tool
+            .validate(&json!({"action": "cancel_reminder"}))
+            .is_err());
+        assert!(Period::parse("fortnightly").is_err());
+    }
+}
@@ -0,0 +1,406 @@
+use async_trait::async_trait;
+use blockcell_core::{Error, Result};
+use blockcell_storage::todos::{db_path, NewTodo, Todo, TodoFilter, TodoStore};
+use chrono::{NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde_json::{json, Value};
+
+use crate::{PromptContext, Tool, ToolContext, ToolSchema};
+
+/// A date without a time means this local hour.
+const DEFAULT_DUE_HOUR: u32 = 9;
+
+/// Parse `due`/`until` values: RFC 3339, `YYYY-MM-DD HH:MM` or `YYYY-MM-DD`, the latter two
+/// in `tz` (IANA name) or the system timezone.
+pub fn parse_local_time(value: &str, tz: Option<&str>) -> Result<i64> {
+    let value = value.trim();
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(dt.timestamp_millis());
+    }
+    let naive = ["%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S"]
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(value, f).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(DEFAULT_DUE_HOUR, 0, 0))
+        })
+        .ok_or_else(|| {
+            Error::Validation(format!(
+                "Invalid time '{}'. Use YYYY-MM-DD, YYYY-MM-DD HH:MM or RFC 3339",
+                value
+            ))
+        })?;
+    let local = match tz.and_then(|t| t.parse::<chrono_tz::Tz>().ok()) {
+        Some(tz) => tz
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|d| d.timestamp_millis()),
+        None => chrono::Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|d| d.timestamp_millis()),
+    };
+    local.ok_or_else(|| Error::Validation(format!("Time '{}' does not exist locally", value)))
+}
+
+/// Resolve a time from `<key>` (absolute) or `<key>_in_minutes` (relative).
+fn time_param(params: &Value, key: &str, tz: Option<&str>) -> Result<Option<i64>> {
+    if let Some(minutes) = params
+        .get(format!("{}_in_minutes", key))
+        .and_then(|v| v.as_i64())
+    {
+        return Ok(Some(Utc::now().timestamp_millis() + minutes * 60_000));
+    }
+    match params.get(key).and_then(|v| v.as_str()).map(str::trim) {
+        Some(s) if !s.is_empty() => parse_local_time(s, tz).map(Some),
+        _ => Ok(None),
+    }
+}
+
+pub(crate) fn format_time(ms: i64, tz: Option<&str>) -> String {
+    let Some(utc) = Utc.timestamp_millis_opt(ms).single() else {
+        return ms.to_string();
+    };
+    match tz.and_then(|t| t.parse::<chrono_tz::Tz>().ok()) {
+        Some(tz) => utc.with_timezone(&tz).format("%Y-%m-%d %H:%M").to_string(),
+        None => utc
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string(),
+    }
+}
+
+fn todo_json(todo: &Todo, tz: Option<&str>) -> Value {
+    let mut value = json!(todo);
+    if let Some(due) = todo.due_at {
+        value["due"] = json!(format_time(due, tz));
+        value["overdue"] = json!(todo.status == "open" && due < Utc::now().timestamp_millis());
+    }
+    if let Some(until) = todo.snoozed_until {
+        value["snoozed_until_local"] = json!(format_time(until, tz));
+    }
+    value
+}
+
+/// Digest text for TODOs due by `cutoff` (overdue first), or `None` when there is nothing.
+pub fn digest(store: &TodoStore, cutoff: i64, tz: Option<&str>) -> Result<Option<String>> {
+    let due = store.list(TodoFilter::DueBy(cutoff), 30)?;
+    if due.is_empty() {
+        return Ok(None);
+    }
+    let now = Utc::now().timestamp_millis();
+    let lines: Vec<String> = due
+        .iter()
+        .map(|t| {
+            let when = t.due_at.map(|d| format_time(d, tz)).unwrap_or_default();
+            let marker = if t.due_at.is_some_and(|d| d < now) {
+                "⚠️ 已逾期"
+            } else {
+                "今天"
+            };
+            format!("- {}（{} {}）", t.text, marker, when)
+        })
+        .collect();
+    Ok(Some(format!(
+        "今天有 {} 项待办：\n{}",
+        due.len(),
+        lines.join("\n")
+    )))
+}
+
+pub struct TodoTool;
+
+#[async_trait]
+impl Tool for TodoTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "todo",
+            description: "The user's personal TODO list (persisted in SQLite, shown in the morning digest). \
+                Distinct from internal background tasks (list_tasks), but a TODO can link one via `task_id`. \
+                Actions: 'add' (text, optional due / due_in_minutes, task_id), 'complete' (id), \
+                'snooze' (id + until / until_in_minutes), 'list' (filter: open/due/done/all), 'remove' (id).",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["add", "complete", "snooze", "list", "remove"],
+                        "description": "Action to perform"
+                    },
+                    "id": {
+                        "type": "string",
+                        "description": "(complete/snooze/remove) TODO id"
+                    },
+                    "text": {
+                        "type": "string",
+                        "description": "(add) What to do. Optional when task_id is given (the task label is used)"
+                    },
+                    "due": {
+                        "type": "string",
+                        "description": "(add) Due time in the user's timezone: 'YYYY-MM-DD HH:MM' or 'YYYY-MM-DD' (09:00)"
+                    },
+                    "due_in_minutes": {
+                        "type": "integer",
+                        "description": "(add) Due this many minutes from now; alternative to due"
+                    },
+                    "until": {
+                        "type": "string",
+                        "description": "(snooze) Hide until this time: 'YYYY-MM-DD HH:MM' or 'YYYY-MM-DD'"
+                    },
+                    "until_in_minutes": {
+                        "type": "integer",
+                        "description": "(snooze) Hide for this many minutes; alternative to until"
+                    },
+                    "task_id": {
+                        "type": "string",
+                        "description": "(add) Link to a background task id from list_tasks, e.g. for 'remind me about this task tomorrow'"
+                    },
+                    "filter": {
+                        "type": "string",
+                        "enum": ["open", "due", "done", "all"],
+                        "description": "(list) open (default), due (due by end of today), done or all"
+                    }
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        let action = params.get("action").and_then(|v| v.as_str()).unwrap_or("");
+        let has = |key: &str| {
+            params
+                .get(key)
+                .and_then(|v| v.as_str())
+                .is_some_and(|s| !s.trim().is_empty())
+        };
+        match action {
+            "add" => {
+                if !has("text") && !has("task_id") {
+                    return Err(Error::Validation(
+                        "'text' or 'task_id' is required for add".into(),
+                    ));
+                }
+            }
+            "complete" | "remove" => {
+                if !has("id") {
+                    return Err(Error::Validation(format!(
+                        "'id' is required for {}",
+                        action
+                    )));
+                }
+            }
+            "snooze" => {
+                if !has("id") {
+                    return Err(Error::Validation("'id' is required for snooze".into()));
+                }
+                if !has("until") && params.get("until_in_minutes").is_none() {
+                    return Err(Error::Validation(
+                        "'until' or 'until_in_minutes' is required for snooze".into(),
+                    ));
+                }
+            }
+            "list" => {}
+            _ => return Err(Error::Validation(format!("Unknown action: {}", action))),
+        }
+        Ok(())
+    }
+
+    fn prompt_rule(&self, _ctx: &PromptContext) -> Option<String> {
+        Some(
+            "- Personal to-dos (\"add to my todo list\", \"what do I have to do today\", \"remind me about this task tomorrow\") go through the `todo` tool, not memory or cron. Link a background task with `task_id`."
+                .to_string(),
+        )
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        let action = params["action"].as_str().unwrap_or("").to_string();
+        let tz = ctx.config.default_timezone.clone();
+
+        // Pull the task label for linked TODOs before moving to the blocking pool.
+        let task_id = params
+            .get("task_id")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string);
+        let mut task_label = None;
+        if action == "add" {
+            if let (Some(id), Some(tm)) = (&task_id, &ctx.task_manager) {
+                match tm.get_task_json(id).await {
+                    Some(task) => {
+                        task_label = task
+                            .get("label")
+                            .and_then(|v| v.as_str())
+                            .map(str::to_string)
+                    }
+                    None => {
+                        return Err(Error::NotFound(format!("Task not found: {}", id)));
+                    }
+                }
+            }
+        }
+
+        let workspace = ctx.workspace.clone();
+        let session_key = ctx.session_key.clone();
+        tokio::task::spawn_blocking(move || {
+            let store = TodoStore::open(&db_path(&workspace))?;
+            let tz = tz.as_deref();
+            let id = params
+                .get("id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .trim()
+                .to_string();
+            match action.as_str() {
+                "add" => {
+                    let text = params
+                        .get("text")
+                        .and_then(|v| v.as_str())
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .map(str::to_string)
+                        .or(task_label)
+                        .or_else(|| {
+                            task_id
+                                .as_ref()
+                                .map(|id| format!("Follow up on task {}", id))
+                        })
+                        .unwrap_or_default();
+                    let todo = store.add(NewTodo {
+                        text,
+                        due_at: time_param(&params, "due", tz)?,
+                        task_id,
+                        session_key: Some(session_key),
+                    })?;
+                    Ok(json!({"status": "added", "todo": todo_json(&todo, tz)}))
+                }
+                "complete" => {
+                    let todo = store.complete(&id)?;
+                    Ok(json!({"status": "completed", "todo": todo_json(&todo, tz)}))
+                }
+                "snooze" => {
+                    let until = time_param(&params, "until", tz)?.ok_or_else(|| {
+                        Error::Validation("'until' or 'until_in_minutes' is required".into())
+                    })?;
+                    let todo = store.snooze(&id, until)?;
+                    Ok(json!({"status": "snoozed", "todo": todo_json(&todo, tz)}))
+                }
+                "remove" => {
+                    if !store.remove(&id)? {
+                        return Err(Error::NotFound(format!("TODO not found: {}", id)));
+                    }
+                    Ok(json!({"status": "removed", "id": id}))
+                }
+                "list" => {
+                    let filter = match params.get("filter").and_then(|v| v.as_str()) {
+                        Some("due") => TodoFilter::DueBy(end_of_today(tz)),
+                        Some("done") => TodoFilter::Done,
+                        Some("all") => TodoFilter::All,
+                        _ => TodoFilter::Open,
+                    };
+                    let todos: Vec<Value> = store
+                        .list(filter, 100)?
+                        .iter()
+                        .map(|t| todo_json(t, tz))
+                        .collect();
+                    Ok(json!({"todos": todos, "count": todos.len()}))
+                }
+                _ => Err(Error::Tool(format!("Unknown action: {}", action))),
+            }
+        })
+        .await
+        .map_err(|e| Error::Tool(format!("Todo task failed: {}", e)))?
+    }
+}
+
+/// Today's date (`YYYY-MM-DD`) and the current hour in `tz` (or the system timezone).
+pub fn local_date_hour(tz: Option<&str>) -> (String, u32) {
+    use chrono::Timelike;
+    match tz.and_then(|t| t.parse::<chrono_tz::Tz>().ok()) {
+        Some(tz) => {
+            let now = Utc::now().with_timezone(&tz);
+            (now.format("%Y-%m-%d").to_string(), now.hour())
+        }
+        None => {
+            let now = chrono::Local::now();
+            (now.format("%Y-%m-%d").to_string(), now.hour())
+        }
+    }
+}
+
+/// Last millisecond of today in `tz` (or the system timezone).
+pub fn end_of_today(tz: Option<&str>) -> i64 {
+    let today = match tz.and_then(|t| t.parse::<chrono_tz::Tz>().ok()) {
+        Some(tz) => Utc::now().with_timezone(&tz).date_naive(),
+        None => chrono::Local::now().date_naive(),
+    };
+    let end = today
+        .and_hms_milli_opt(23, 59, 59, 999)
+        .expect("valid end of day")
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+    parse_local_time(&end, tz).unwrap_or_else(|_| Utc::now().timestamp_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_and_validate() {
+        let tool = TodoTool;
+        assert_eq!(tool.schema().name, "todo");
+        assert!(tool.validate(&json!({"action": "add"})).is_err());
+        assert!(tool
+            .validate(&json!({"action": "add", "task_id": "msg_1"}))
+            .is_ok());
+        assert!(tool
+            .validate(&json!({"action": "snooze", "id": "todo_1"}))
+            .is_err());
+        assert!(tool
+            .validate(&json!({"action": "snooze", "id": "todo_1", "until_in_minutes": 60}))
+            .is_ok());
+        assert!(tool.validate(&json!({"action": "clear"})).is_err());
+    }
+
+    #[test]
+    fn test_parse_local_time() {
+        let shanghai = parse_local_time("2026-03-01 08:30", Some("Asia/Shanghai")).unwrap();
+        let utc = parse_local_time("2026-03-01T00:30:00Z", None).unwrap();
+        assert_eq!(shanghai, utc);
+        let date_only = parse_local_time("2026-03-01", Some("UTC")).unwrap();
+        assert_eq!(
+            date_only,
+            parse_local_time("2026-03-01T09:00:00Z", None).unwrap()
+        );
+        assert!(parse_local_time("next tuesday", None).is_err());
+        assert!(end_of_today(Some("UTC")) > Utc::now().timestamp_millis());
+    }
+
+    #[test]
+    fn test_digest_lists_due_items() {
+        let store = TodoStore::open_in_memory().unwrap();
+        let now = Utc::now().timestamp_millis();
+        assert!(digest(&store, now, Some("UTC")).unwrap().is_none());
+        store
+            .add(NewTodo {
+                text: "pay rent".to_string(),
+                due_at: Some(now - 3_600_000),
+                ..Default::default()
+            })
+            .unwrap();
+        store
+            .add(NewTodo {
+                text: "someday".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        let text = digest(&store, now + 3_600_000, Some("UTC"))
+            .unwrap()
+            .unwrap();
+        assert!(text.contains("pay rent"));
+        assert!(text.contains("已逾期"));
+        assert!(!text.contains("someday"));
+    }
+}
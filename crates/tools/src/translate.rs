@@ -0,0 +1,1519 @@
+use async_trait::async_trait;
+use blockcell_core::config::TranslateConfig;
+use blockcell_core::types::ChatMessage;
+use blockcell_core::{Config, Error, Result};
+use regex::Regex;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+
+use crate::egress::HttpClientFactory;
+use crate::{Tool, ToolContext, ToolSchema};
+
+/// Max characters per LLM batch request.
+const LLM_BATCH_CHARS: usize = 3000;
+/// Max segments per LLM batch request.
+const LLM_BATCH_ITEMS: usize = 40;
+/// DeepL accepts up to 50 texts per request; Google up to 128.
+const DEEPL_BATCH_ITEMS: usize = 50;
+const GOOGLE_BATCH_ITEMS: usize = 100;
+
+fn expand_path(path: &str, workspace: &Path) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/") {
+        dirs::home_dir()
+            .map(|h| h.join(rest))
+            .unwrap_or_else(|| PathBuf::from(path))
+    } else if path.starts_with('/') {
+        PathBuf::from(path)
+    } else {
+        workspace.join(path)
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Language detection
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Cheap script-based language guess. Good enough to pick a translation direction;
+/// the LLM backend does its own detection for anything finer-grained.
+fn detect_language(text: &str) -> &'static str {
+    let (mut han, mut kana, mut hangul, mut cyrillic, mut arabic, mut thai) = (0, 0, 0, 0, 0, 0);
+    let (mut ascii_alpha, mut other_alpha) = (0, 0);
+    for c in text.chars() {
+        match c as u32 {
+            0x4E00..=0x9FFF | 0x3400..=0x4DBF => han += 1,
+            0x3040..=0x30FF => kana += 1,
+            0xAC00..=0xD7AF | 0x1100..=0x11FF => hangul += 1,
+            0x0400..=0x04FF => cyrillic += 1,
+            0x0600..=0x06FF => arabic += 1,
+            0x0E00..=0x0E7F => thai += 1,
+            _ if c.is_ascii_alphabetic() => ascii_alpha += 1,
+            _ if c.is_alphabetic() => other_alpha += 1,
+            _ => {}
+        }
+    }
+    let cjk = han + kana + hangul;
+    if kana > 0 && kana * 10 >= cjk {
+        "ja"
+    } else if hangul > 0 && hangul >= han {
+        "ko"
+    } else if han > 0 && han * 4 >= ascii_alpha {
+        "zh"
+    } else if cyrillic > 0 && cyrillic >= ascii_alpha {
+        "ru"
+    } else if arabic > 0 && arabic >= ascii_alpha {
+        "ar"
+    } else if thai > 0 && thai >= ascii_alpha {
+        "th"
+    } else if ascii_alpha > 0 && other_alpha * 20 <= ascii_alpha {
+        "en"
+    } else {
+        "auto"
+    }
+}
+
+/// Pick a target when the caller did not: translate into `default_target`,
+/// or into English when the text is already in the default target language.
+fn resolve_target(explicit: Option<&str>, detected: &str, default_target: &str) -> String {
+    if let Some(t) = explicit.map(str::trim).filter(|t| !t.is_empty()) {
+        return t.to_string();
+    }
+    let default_target = if default_target.trim().is_empty() {
+        "zh"
+    } else {
+        default_target.trim()
+    };
+    if base_lang(detected) == base_lang(default_target) {
+        if base_lang(default_target) == "en" {
+            "zh".to_string()
+        } else {
+            "en".to_string()
+        }
+    } else {
+        default_target.to_string()
+    }
+}
+
+fn base_lang(code: &str) -> String {
+    code.split(['-', '_'])
+        .next()
+        .unwrap_or("")
+        .to_ascii_lowercase()
+}
+
+fn language_name(code: &str) -> String {
+    let name = match code.to_ascii_lowercase().as_str() {
+        "zh" | "zh-cn" | "zh-hans" => "Simplified Chinese",
+        "zh-tw" | "zh-hk" | "zh-hant" => "Traditional Chinese",
+        "en" | "en-us" => "English",
+        "en-gb" => "British English",
+        "ja" => "Japanese",
+        "ko" => "Korean",
+        "fr" => "French",
+        "de" => "German",
+        "es" => "Spanish",
+        "pt" | "pt-br" => "Portuguese",
+        "it" => "Italian",
+        "ru" => "Russian",
+        "ar" => "Arabic",
+        "th" => "Thai",
+        "vi" => "Vietnamese",
+        _ => return code.to_string(),
+    };
+    name.to_string()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Glossaries (workspace/glossaries/<name>.json)
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Glossary {
+    name: String,
+    #[serde(default)]
+    source_lang: Option<String>,
+    #[serde(default)]
+    target_lang: Option<String>,
+    /// Optional domain hint passed to the LLM backend, e.g. "legal", "medical".
+    #[serde(default)]
+    domain: Option<String>,
+    /// source term → required translation
+    #[serde(default)]
+    terms: BTreeMap<String, String>,
+    #[serde(default)]
+    updated_at: i64,
+}
+
+impl Glossary {
+    /// Terms that occur in `text`, longest first so overlapping terms resolve predictably.
+    fn matching_terms(&self, text: &str) -> Vec<(&str, &str)> {
+        let lower = text.to_lowercase();
+        let mut hits: Vec<(&str, &str)> = self
+            .terms
+            .iter()
+            .filter(|(src, _)| !src.trim().is_empty() && lower.contains(&src.to_lowercase()))
+            .map(|(s, t)| (s.as_str(), t.as_str()))
+            .collect();
+        hits.sort_by_key(|(s, _)| std::cmp::Reverse(s.chars().count()));
+        hits
+    }
+}
+
+fn glossary_dir(workspace: &Path) -> PathBuf {
+    workspace.join("glossaries")
+}
+
+fn validate_glossary_name(name: &str) -> Result<&str> {
+    let name = name.trim();
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(Error::Validation(format!(
+            "Invalid glossary name '{}': use letters, digits, '-' or '_'",
+            name
+        )));
+    }
+    Ok(name)
+}
+
+fn load_glossary(workspace: &Path, name: &str) -> Result<Glossary> {
+    let name = validate_glossary_name(name)?;
+    let path = glossary_dir(workspace).join(format!("{}.json", name));
+    if !path.exists() {
+        return Err(Error::Tool(format!("Glossary '{}' not found", name)));
+    }
+    let content = std::fs::read_to_string(&path)?;
+    let mut glossary: Glossary = serde_json::from_str(&content)?;
+    if glossary.name.is_empty() {
+        glossary.name = name.to_string();
+    }
+    Ok(glossary)
+}
+
+fn save_glossary(workspace: &Path, glossary: &Glossary) -> Result<PathBuf> {
+    let dir = glossary_dir(workspace);
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{}.json", glossary.name));
+    std::fs::write(&path, serde_json::to_string_pretty(glossary)?)?;
+    Ok(path)
+}
+
+fn list_glossaries(workspace: &Path) -> Result<Vec<Value>> {
+    let dir = glossary_dir(workspace);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut out = Vec::new();
+    for entry in std::fs::read_dir(&dir)?.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        match load_glossary(workspace, stem) {
+            Ok(g) => out.push(json!({
+                "name": g.name,
+                "source_lang": g.source_lang,
+                "target_lang": g.target_lang,
+                "domain": g.domain,
+                "term_count": g.terms.len(),
+            })),
+            Err(e) => warn!(path = %path.display(), error = %e, "Skipping unreadable glossary"),
+        }
+    }
+    out.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+    Ok(out)
+}
+
+/// Replace glossary terms with opaque placeholders so MT backends cannot alter them.
+fn protect_terms(text: &str, terms: &[(&str, &str)]) -> (String, Vec<String>) {
+    let mut out = text.to_string();
+    let mut targets = Vec::new();
+    for (src, tgt) in terms {
+        let Ok(re) = Regex::new(&format!("(?i){}", regex::escape(src))) else {
+            continue;
+        };
+        if re.is_match(&out) {
+            let token = format!("__T{}__", targets.len());
+            out = re.replace_all(&out, token.as_str()).to_string();
+            targets.push(tgt.to_string());
+        }
+    }
+    (out, targets)
+}
+
+fn restore_terms(text: &str, targets: &[String]) -> String {
+    let mut out = text.to_string();
+    for (i, tgt) in targets.iter().enumerate() {
+        out = out.replace(&format!("__T{}__", i), tgt);
+    }
+    out
+}
+
+/// Glossary terms present in the source whose required translation is missing from the output.
+fn glossary_violations(glossary: &Glossary, source: &str, translated: &str) -> Vec<Value> {
+    let lower = translated.to_lowercase();
+    glossary
+        .matching_terms(source)
+        .into_iter()
+        .filter(|(_, tgt)| !lower.contains(&tgt.to_lowercase()))
+        .map(|(src, tgt)| json!({ "term": src, "expected": tgt }))
+        .collect()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Backends
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Backend {
+    Llm,
+    DeepL,
+    Google,
+}
+
+impl Backend {
+    fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "llm" | "" => Some(Self::Llm),
+            "deepl" => Some(Self::DeepL),
+            "google" => Some(Self::Google),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Llm => "llm",
+            Self::DeepL => "deepl",
+            Self::Google => "google",
+        }
+    }
+}
+
+fn resolve_key(value: &str, env: &str) -> Option<String> {
+    let v = value.trim();
+    if !v.is_empty() {
+        return Some(v.to_string());
+    }
+    std::env::var(env)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+struct Translator<'a> {
+    backend: Backend,
+    config: &'a Config,
+    settings: &'a TranslateConfig,
+    egress: HttpClientFactory,
+    client: Client,
+}
+
+impl<'a> Translator<'a> {
+    fn new(config: &'a Config, backend: Backend) -> Result<Self> {
+        let egress = HttpClientFactory::for_tool(config, "translate");
+        let client = egress
+            .builder()
+            .timeout(std::time::Duration::from_secs(60))
+            .build()
+            .map_err(|e| Error::Tool(format!("HTTP client error: {}", e)))?;
+        Ok(Self {
+            backend,
+            config,
+            settings: &config.tools.translate,
+            egress,
+            client,
+        })
+    }
+
+    /// Translate a list of segments, preserving order and length.
+    async fn translate_many(
+        &self,
+        texts: &[String],
+        source: Option<&str>,
+        target: &str,
+        glossary: Option<&Glossary>,
+    ) -> Result<Vec<String>> {
+        match self.backend {
+            Backend::Llm => {
+                self.llm_translate_many(texts, source, target, glossary)
+                    .await
+            }
+            Backend::DeepL | Backend::Google => {
+                let batch_size = if self.backend == Backend::DeepL {
+                    DEEPL_BATCH_ITEMS
+                } else {
+                    GOOGLE_BATCH_ITEMS
+                };
+                let mut out = Vec::with_capacity(texts.len());
+                for chunk in texts.chunks(batch_size) {
+                    let protected: Vec<(String, Vec<String>)> = chunk
+                        .iter()
+                        .map(|t| match glossary {
+                            Some(g) => protect_terms(t, &g.matching_terms(t)),
+                            None => (t.clone(), Vec::new()),
+                        })
+                        .collect();
+                    let inputs: Vec<String> = protected.iter().map(|(t, _)| t.clone()).collect();
+                    let translated = if self.backend == Backend::DeepL {
+                        self.deepl_translate(&inputs, source, target).await?
+                    } else {
+                        self.google_translate(&inputs, source, target).await?
+                    };
+                    if translated.len() != inputs.len() {
+                        return Err(Error::Tool(format!(
+                            "{} returned {} translations for {} inputs",
+                            self.backend.name(),
+                            translated.len(),
+                            inputs.len()
+                        )));
+                    }
+                    out.extend(
+                        translated
+                            .iter()
+                            .zip(protected.iter())
+                            .map(|(t, (_, targets))| restore_terms(t, targets)),
+                    );
+                }
+                Ok(out)
+            }
+        }
+    }
+
+    fn llm_system_prompt(
+        &self,
+        source: Option<&str>,
+        target: &str,
+        glossary: Option<&Glossary>,
+        sample: &str,
+    ) -> String {
+        let from = source
+            .map(language_name)
+            .unwrap_or_else(|| "the source language".to_string());
+        let mut prompt = format!(
+            "You are a professional translator. Translate from {} into {}. \
+             Preserve meaning, tone and formatting exactly (Markdown, line breaks, URLs, code, placeholders). \
+             Output only the translation, without notes or explanations.",
+            from,
+            language_name(target)
+        );
+        if let Some(domain) = glossary.and_then(|g| g.domain.as_deref()) {
+            prompt.push_str(&format!(" The text belongs to the {} domain.", domain));
+        }
+        if let Some(g) = glossary {
+            let terms = g.matching_terms(sample);
+            if !terms.is_empty() {
+                prompt.push_str(
+                    "\n\nMandatory terminology (always translate these terms exactly as given):\n",
+                );
+                for (src, tgt) in terms {
+                    prompt.push_str(&format!("- {} → {}\n", src, tgt));
+                }
+            }
+        }
+        prompt
+    }
+
+    async fn llm_chat(&self, system: &str, user: &str) -> Result<String> {
+        let (model, provider) = resolve_llm_model(self.config, self.settings);
+        let provider =
+            blockcell_providers::create_provider(self.config, &model, provider.as_deref())
+                .map_err(|e| Error::Tool(format!("Translation LLM unavailable: {}", e)))?;
+        let response = provider
+            .chat(&[ChatMessage::system(system), ChatMessage::user(user)], &[])
+            .await?;
+        Ok(response.content.unwrap_or_default().trim().to_string())
+    }
+
+    async fn llm_translate_many(
+        &self,
+        texts: &[String],
+        source: Option<&str>,
+        target: &str,
+        glossary: Option<&Glossary>,
+    ) -> Result<Vec<String>> {
+        if texts.len() == 1 {
+            let system = self.llm_system_prompt(source, target, glossary, &texts[0]);
+            return Ok(vec![self.llm_chat(&system, &texts[0]).await?]);
+        }
+
+        let mut out = Vec::with_capacity(texts.len());
+        for batch in llm_batches(texts) {
+            let joined = batch.join("\n");
+            let system = self.llm_system_prompt(source, target, glossary, &joined);
+            let user = format!(
+                "Translate each string in this JSON array. Reply with ONLY a JSON array of the translated strings, same length and order.\n{}",
+                serde_json::to_string(&batch)?
+            );
+            let reply = self.llm_chat(&system, &user).await?;
+            match parse_json_string_array(&reply) {
+                Some(items) if items.len() == batch.len() => out.extend(items),
+                _ => {
+                    // Model broke the array contract — fall back to one call per segment.
+                    debug!(
+                        expected = batch.len(),
+                        "LLM batch translation returned malformed array, retrying per segment"
+                    );
+                    for text in batch {
+                        let system = self.llm_system_prompt(source, target, glossary, text);
+                        out.push(self.llm_chat(&system, text).await?);
+                    }
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    async fn deepl_translate(
+        &self,
+        texts: &[String],
+        source: Option<&str>,
+        target: &str,
+    ) -> Result<Vec<String>> {
+        let key = resolve_key(&self.settings.deepl_api_key, "DEEPL_API_KEY").ok_or_else(|| {
+            Error::Tool(
+                "DeepL API key not configured. Set tools.translate.deeplApiKey or env DEEPL_API_KEY."
+                    .to_string(),
+            )
+        })?;
+        // Free-tier keys end with ":fx" and use a separate host.
+        let endpoint = if key.ends_with(":fx") {
+            "https://api-free.deepl.com/v2/translate"
+        } else {
+            "https://api.deepl.com/v2/translate"
+        };
+        let mut body = json!({
+            "text": texts,
+            "target_lang": deepl_lang(target, true),
+            "preserve_formatting": true,
+        });
+        if let Some(src) = source {
+            body["source_lang"] = json!(deepl_lang(src, false));
+        }
+        self.egress.check(endpoint).await?;
+
+        let response = self
+            .client
+            .post(endpoint)
+            .header("Authorization", format!("DeepL-Auth-Key {}", key))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| Error::Tool(format!("DeepL request failed: {}", e)))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(Error::Tool(format!("DeepL API error {}: {}", status, text)));
+        }
+        let data: Value = response
+            .json()
+            .await
+            .map_err(|e| Error::Tool(format!("Failed to parse DeepL response: {}", e)))?;
+        Ok(data["translations"]
+            .as_array()
+            .map(|arr| {
+                arr.iter()
+                    .map(|t| t["text"].as_str().unwrap_or("").to_string())
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn google_translate(
+        &self,
+        texts: &[String],
+        source: Option<&str>,
+        target: &str,
+    ) -> Result<Vec<String>> {
+        let key = resolve_key(&self.settings.google_api_key, "GOOGLE_TRANSLATE_API_KEY")
+            .ok_or_else(|| {
+                Error::Tool(
+                    "Google Translate API key not configured. Set tools.translate.googleApiKey or env GOOGLE_TRANSLATE_API_KEY."
+                        .to_string(),
+                )
+            })?;
+        let mut body = json!({
+            "q": texts,
+            "target": google_lang(target),
+            "format": "text",
+        });
+        if let Some(src) = source {
+            body["source"] = json!(google_lang(src));
+        }
+        let endpoint = "https://translation.googleapis.com/language/translate/v2";
+        self.egress.check(endpoint).await?;
+
+        let response = self
+            .client
+            .post(endpoint)
+            .query(&[("key", key.as_str())])
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| Error::Tool(format!("Google Translate request failed: {}", e)))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(Error::Tool(format!(
+                "Google Translate API error {}: {}",
+                status, text
+            )));
+        }
+        let data: Value = response.json().await.map_err(|e| {
+            Error::Tool(format!("Failed to parse Google Translate response: {}", e))
+        })?;
+        Ok(data["data"]["translations"]
+            .as_array()
+            .map(|arr| {
+                arr.iter()
+                    .map(|t| t["translatedText"].as_str().unwrap_or("").to_string())
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
+
+/// `tools.translate.model` overrides the agent model; otherwise use the highest-priority
+/// model_pool entry, then `agents.defaults.model`.
+fn resolve_llm_model(config: &Config, settings: &TranslateConfig) -> (String, Option<String>) {
+    let explicit_provider = settings
+        .provider
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(str::to_string);
+    if !settings.model.trim().is_empty() {
+        return (settings.model.trim().to_string(), explicit_provider);
+    }
+    let defaults = &config.agents.defaults;
+    if let Some(entry) = defaults.model_pool.iter().min_by_key(|e| e.priority) {
+        return (entry.model.clone(), Some(entry.provider.clone()));
+    }
+    (
+        defaults.model.clone(),
+        explicit_provider.or_else(|| defaults.provider.clone()),
+    )
+}
+
+fn deepl_lang(code: &str, is_target: bool) -> String {
+    let lower = code.to_ascii_lowercase();
+    match lower.as_str() {
+        "en" if is_target => "EN-US".to_string(),
+        "pt" if is_target => "PT-BR".to_string(),
+        "zh-cn" | "zh-hans" => "ZH".to_string(),
+        _ if !is_target => base_lang(code).to_ascii_uppercase(),
+        _ => code.to_ascii_uppercase(),
+    }
+}
+
+fn google_lang(code: &str) -> String {
+    match code.to_ascii_lowercase().as_str() {
+        "zh" | "zh-hans" => "zh-CN".to_string(),
+        "zh-hant" => "zh-TW".to_string(),
+        _ => code.to_string(),
+    }
+}
+
+/// Group segments into LLM-sized batches.
+fn llm_batches(texts: &[String]) -> Vec<&[String]> {
+    let mut batches = Vec::new();
+    let mut start = 0;
+    let mut chars = 0;
+    for (i, t) in texts.iter().enumerate() {
+        let len = t.chars().count();
+        if i > start && (chars + len > LLM_BATCH_CHARS || i - start >= LLM_BATCH_ITEMS) {
+            batches.push(&texts[start..i]);
+            start = i;
+            chars = 0;
+        }
+        chars += len;
+    }
+    if start < texts.len() {
+        batches.push(&texts[start..]);
+    }
+    batches
+}
+
+/// Extract a JSON string array from an LLM reply (tolerates code fences and chatter).
+fn parse_json_string_array(reply: &str) -> Option<Vec<String>> {
+    let start = reply.find('[')?;
+    let end = reply.rfind(']')?;
+    if end <= start {
+        return None;
+    }
+    let items: Vec<Value> = serde_json::from_str(&reply[start..=end]).ok()?;
+    items
+        .into_iter()
+        .map(|v| v.as_str().map(|s| s.to_string()))
+        .collect()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// File translation (Markdown / plain text / DOCX)
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// A Markdown/text document split into translatable blocks and verbatim blocks
+/// (fenced code, front matter, blank lines). Joining `lines` with '\n' round-trips the source.
+struct TextBlocks {
+    blocks: Vec<(bool, String)>,
+}
+
+fn split_markdown(src: &str) -> TextBlocks {
+    let mut blocks: Vec<(bool, String)> = Vec::new();
+    let mut para: Vec<&str> = Vec::new();
+    let mut fence: Option<(&str, Vec<&str>)> = None;
+    let lines: Vec<&str> = src.split('\n').collect();
+    let mut i = 0;
+
+    let flush = |para: &mut Vec<&str>, blocks: &mut Vec<(bool, String)>| {
+        if !para.is_empty() {
+            blocks.push((true, para.join("\n")));
+            para.clear();
+        }
+    };
+
+    // YAML front matter stays verbatim.
+    if lines.first().map(|l| l.trim_end()) == Some("---") {
+        if let Some(end) = lines.iter().skip(1).position(|l| l.trim_end() == "---") {
+            blocks.push((false, lines[..end + 2].join("\n")));
+            i = end + 2;
+        }
+    }
+
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim_start();
+        if let Some((marker, body)) = fence.as_mut() {
+            body.push(line);
+            if trimmed.starts_with(*marker) {
+                blocks.push((false, body.join("\n")));
+                fence = None;
+            }
+        } else if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            flush(&mut para, &mut blocks);
+            let marker = if trimmed.starts_with("```") {
+                "```"
+            } else {
+                "~~~"
+            };
+            fence = Some((marker, vec![line]));
+        } else if line.trim().is_empty() {
+            flush(&mut para, &mut blocks);
+            blocks.push((false, line.to_string()));
+        } else {
+            para.push(line);
+        }
+        i += 1;
+    }
+    if let Some((_, body)) = fence {
+        blocks.push((false, body.join("\n")));
+    }
+    flush(&mut para, &mut blocks);
+    TextBlocks { blocks }
+}
+
+impl TextBlocks {
+    fn translatable(&self) -> Vec<String> {
+        self.blocks
+            .iter()
+            .filter(|(t, _)| *t)
+            .map(|(_, s)| s.clone())
+            .collect()
+    }
+
+    fn rebuild(&self, translated: &[String]) -> String {
+        let mut iter = translated.iter();
+        self.blocks
+            .iter()
+            .map(|(t, s)| {
+                if *t {
+                    iter.next().cloned().unwrap_or_else(|| s.clone())
+                } else {
+                    s.clone()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn xml_unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+static PARAGRAPH_RE: once_cell::sync::Lazy<Regex> =
+    once_cell::sync::Lazy::new(|| Regex::new(r"(?s)<w:p[ >].*?</w:p>").unwrap());
+static RUN_TEXT_RE: once_cell::sync::Lazy<Regex> =
+    once_cell::sync::Lazy::new(|| Regex::new(r"(?s)<w:t(?:\s[^>]*)?>(.*?)</w:t>").unwrap());
+
+/// Paragraph texts of a WordprocessingML part, in document order.
+fn docx_paragraphs(xml: &str) -> Vec<String> {
+    PARAGRAPH_RE
+        .find_iter(xml)
+        .map(|p| {
+            RUN_TEXT_RE
+                .captures_iter(p.as_str())
+                .map(|c| xml_unescape(&c[1]))
+                .collect::<String>()
+        })
+        .filter(|t| !t.trim().is_empty())
+        .collect()
+}
+
+/// Write translated paragraph text back: the first run carries the whole translation,
+/// remaining runs are emptied (run-level formatting inside a paragraph is flattened).
+fn docx_apply(xml: &str, translated: &[String]) -> String {
+    let mut iter = translated.iter();
+    PARAGRAPH_RE
+        .replace_all(xml, |caps: &regex::Captures| {
+            let para = &caps[0];
+            let text: String = RUN_TEXT_RE
+                .captures_iter(para)
+                .map(|c| xml_unescape(&c[1]))
+                .collect();
+            if text.trim().is_empty() {
+                return para.to_string();
+            }
+            let Some(replacement) = iter.next() else {
+                return para.to_string();
+            };
+            let mut first = true;
+            RUN_TEXT_RE
+                .replace_all(para, |_: &regex::Captures| {
+                    if first {
+                        first = false;
+                        format!(
+                            "<w:t xml:space=\"preserve\">{}</w:t>",
+                            xml_escape(replacement)
+                        )
+                    } else {
+                        "<w:t></w:t>".to_string()
+                    }
+                })
+                .to_string()
+        })
+        .to_string()
+}
+
+fn is_docx_text_part(name: &str) -> bool {
+    name == "word/document.xml"
+        || (name.starts_with("word/header") && name.ends_with(".xml"))
+        || (name.starts_with("word/footer") && name.ends_with(".xml"))
+        || name == "word/footnotes.xml"
+        || name == "word/endnotes.xml"
+}
+
+fn read_docx_parts(path: &Path) -> Result<Vec<(String, Vec<u8>)>> {
+    let file = std::fs::File::open(path)?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| Error::Tool(format!("Failed to open DOCX: {}", e)))?;
+    let mut parts = Vec::with_capacity(archive.len());
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| Error::Tool(format!("DOCX read error: {}", e)))?;
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data)?;
+        parts.push((entry.name().to_string(), data));
+    }
+    Ok(parts)
+}
+
+fn write_docx_parts(path: &Path, parts: &[(String, Vec<u8>)]) -> Result<()> {
+    let file = std::fs::File::create(path)?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    for (name, data) in parts {
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| Error::Tool(format!("Zip error: {}", e)))?;
+        zip.write_all(data)?;
+    }
+    zip.finish()
+        .map_err(|e| Error::Tool(format!("Zip finish error: {}", e)))?;
+    Ok(())
+}
+
+/// `report.md` → `report.en.md` next to the source.
+fn default_output_path(src: &Path, target: &str) -> PathBuf {
+    let stem = src
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "translated".to_string());
+    let name = match src.extension() {
+        Some(ext) => format!("{}.{}.{}", stem, target, ext.to_string_lossy()),
+        None => format!("{}.{}", stem, target),
+    };
+    src.with_file_name(name)
+}
+
+struct FileJob<'a> {
+    translator: &'a Translator<'a>,
+    source: Option<&'a str>,
+    target: &'a str,
+    glossary: Option<&'a Glossary>,
+}
+
+impl FileJob<'_> {
+    async fn translate_file(&self, src: &Path, output: Option<PathBuf>) -> Result<Value> {
+        if !src.is_file() {
+            return Err(Error::Tool(format!("File not found: {}", src.display())));
+        }
+        let ext = src
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        let out_path = output.unwrap_or_else(|| default_output_path(src, self.target));
+        if let Some(parent) = out_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let (segments, violations) = match ext.as_str() {
+            "md" | "markdown" | "txt" => {
+                let content = std::fs::read_to_string(src)?;
+                let blocks = split_markdown(&content);
+                let inputs = blocks.translatable();
+                let translated = self
+                    .translator
+                    .translate_many(&inputs, self.source, self.target, self.glossary)
+                    .await?;
+                let violations = self.collect_violations(&inputs, &translated);
+                std::fs::write(&out_path, blocks.rebuild(&translated))?;
+                (inputs.len(), violations)
+            }
+            "docx" => {
+                let mut parts = read_docx_parts(src)?;
+                let mut total = 0;
+                let mut violations = Vec::new();
+                for (name, data) in parts.iter_mut() {
+                    if !is_docx_text_part(name) {
+                        continue;
+                    }
+                    let xml = String::from_utf8_lossy(data).to_string();
+                    let inputs = docx_paragraphs(&xml);
+                    if inputs.is_empty() {
+                        continue;
+                    }
+                    let translated = self
+                        .translator
+                        .translate_many(&inputs, self.source, self.target, self.glossary)
+                        .await?;
+                    violations.extend(self.collect_violations(&inputs, &translated));
+                    *data = docx_apply(&xml, &translated).into_bytes();
+                    total += inputs.len();
+                }
+                write_docx_parts(&out_path, &parts)?;
+                (total, violations)
+            }
+            other => {
+                return Err(Error::Tool(format!(
+                    "Unsupported file type '.{}'. Supported: .md, .markdown, .txt, .docx",
+                    other
+                )));
+            }
+        };
+
+        info!(
+            src = %src.display(),
+            out = %out_path.display(),
+            segments,
+            "File translated"
+        );
+        Ok(json!({
+            "source_file": src.display().to_string(),
+            "output_file": out_path.display().to_string(),
+            "segments": segments,
+            "glossary_violations": violations,
+        }))
+    }
+
+    fn collect_violations(&self, inputs: &[String], translated: &[String]) -> Vec<Value> {
+        let Some(g) = self.glossary else {
+            return Vec::new();
+        };
+        inputs
+            .iter()
+            .zip(translated)
+            .flat_map(|(s, t)| glossary_violations(g, s, t))
+            .collect()
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tool
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct TranslateTool;
+
+#[async_trait]
+impl Tool for TranslateTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "translate",
+            description: "Translate text or documents with consistent terminology. \
+                Backends: 'llm' (default, uses the configured agent model), 'deepl', 'google'. \
+                If `target_lang` is omitted it is chosen automatically from the detected source language. \
+                Glossaries stored in the workspace force fixed translations for domain terms. \
+                Actions: 'translate' (text/texts), 'translate_file' (batch translate .md/.txt/.docx via path/paths; writes `<name>.<lang>.<ext>`), \
+                'detect' (guess language), 'glossary_list', 'glossary_get', 'glossary_set', 'glossary_delete'.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["translate", "translate_file", "detect", "glossary_list", "glossary_get", "glossary_set", "glossary_delete"],
+                        "description": "Action to perform. Default: translate"
+                    },
+                    "text": {
+                        "type": "string",
+                        "description": "(translate/detect) Text to translate"
+                    },
+                    "texts": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "(translate) Multiple segments translated in one batch"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "(translate_file) Source file (.md, .markdown, .txt, .docx)"
+                    },
+                    "paths": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "(translate_file) Several source files"
+                    },
+                    "output_path": {
+                        "type": "string",
+                        "description": "(translate_file) Output file, only with a single `path`. Default: `<name>.<target_lang>.<ext>` next to the source"
+                    },
+                    "target_lang": {
+                        "type": "string",
+                        "description": "Target language code, e.g. 'en', 'zh', 'ja', 'de'. Omit to auto-select"
+                    },
+                    "source_lang": {
+                        "type": "string",
+                        "description": "Source language code. Omit to auto-detect"
+                    },
+                    "backend": {
+                        "type": "string",
+                        "enum": ["llm", "deepl", "google"],
+                        "description": "Translation backend. Default from tools.translate.backend (llm)"
+                    },
+                    "glossary": {
+                        "type": "string",
+                        "description": "(translate/translate_file) Glossary name to enforce. (glossary_get/set/delete) Glossary name"
+                    },
+                    "terms": {
+                        "type": "object",
+                        "description": "(glossary_set) Map of source term → required translation, e.g. {\"smart contract\": \"智能合约\"}"
+                    },
+                    "remove_terms": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "(glossary_set) Source terms to remove"
+                    },
+                    "replace": {
+                        "type": "boolean",
+                        "description": "(glossary_set) Replace all terms instead of merging. Default false"
+                    },
+                    "domain": {
+                        "type": "string",
+                        "description": "(glossary_set) Domain hint for the LLM, e.g. 'legal', 'medical', 'blockchain'"
+                    }
+                }
+            }),
+        }
+    }
+
+    fn prompt_rule(&self, _ctx: &crate::PromptContext) -> Option<String> {
+        Some("- **翻译 (translate)**: 需要翻译文本或整份文档时用 `translate`，不要自己逐段翻译长文档。翻译 .md/.txt/.docx 文件用 action='translate_file'（支持 `paths` 批量）。用户提到固定术语/术语表时，先用 action='glossary_set' 保存到术语表，再在翻译时传 `glossary`。未指定目标语言时可省略 `target_lang` 自动选择。结果里 `glossary_violations` 非空时要告知用户哪些术语未按术语表翻译。".to_string())
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        let action = params
+            .get("action")
+            .and_then(|v| v.as_str())
+            .unwrap_or("translate");
+        if let Some(b) = params.get("backend").and_then(|v| v.as_str()) {
+            if Backend::parse(b).is_none() {
+                return Err(Error::Validation(format!(
+                    "Unknown backend '{}'. Use llm, deepl or google.",
+                    b
+                )));
+            }
+        }
+        let has_str = |key: &str| {
+            params
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(|s| !s.trim().is_empty())
+                .unwrap_or(false)
+        };
+        match action {
+            "translate" => {
+                let has_texts = params
+                    .get("texts")
+                    .and_then(|v| v.as_array())
+                    .map(|a| !a.is_empty())
+                    .unwrap_or(false);
+                if !has_str("text") && !has_texts {
+                    return Err(Error::Validation(
+                        "translate requires 'text' or 'texts'".to_string(),
+                    ));
+                }
+            }
+            "detect" => {
+                if !has_str("text") {
+                    return Err(Error::Validation("detect requires 'text'".to_string()));
+                }
+            }
+            "translate_file" => {
+                let paths = params.get("paths").and_then(|v| v.as_array());
+                if !has_str("path") && paths.map(|a| a.is_empty()).unwrap_or(true) {
+                    return Err(Error::Validation(
+                        "translate_file requires 'path' or 'paths'".to_string(),
+                    ));
+                }
+                if has_str("output_path") && paths.map(|a| !a.is_empty()).unwrap_or(false) {
+                    return Err(Error::Validation(
+                        "'output_path' can only be used with a single 'path'".to_string(),
+                    ));
+                }
+            }
+            "glossary_get" | "glossary_delete" => {
+                validate_glossary_name(params["glossary"].as_str().unwrap_or(""))?;
+            }
+            "glossary_set" => {
+                validate_glossary_name(params["glossary"].as_str().unwrap_or(""))?;
+                if let Some(terms) = params.get("terms") {
+                    let ok = terms
+                        .as_object()
+                        .map(|m| m.values().all(|v| v.is_string()))
+                        .unwrap_or(false);
+                    if !ok {
+                        return Err(Error::Validation(
+                            "'terms' must be an object of string → string".to_string(),
+                        ));
+                    }
+                }
+            }
+            "glossary_list" => {}
+            _ => return Err(Error::Validation(format!("Unknown action: {}", action))),
+        }
+        Ok(())
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        let action = params
+            .get("action")
+            .and_then(|v| v.as_str())
+            .unwrap_or("translate");
+        let workspace = ctx.workspace.clone();
+
+        match action {
+            "detect" => {
+                let text = params["text"].as_str().unwrap_or("");
+                Ok(json!({ "language": detect_language(text) }))
+            }
+            "glossary_list" => {
+                let glossaries = list_glossaries(&workspace)?;
+                Ok(json!({ "count": glossaries.len(), "glossaries": glossaries }))
+            }
+            "glossary_get" => {
+                let g = load_glossary(&workspace, params["glossary"].as_str().unwrap_or(""))?;
+                Ok(serde_json::to_value(g)?)
+            }
+            "glossary_delete" => {
+                let name = validate_glossary_name(params["glossary"].as_str().unwrap_or(""))?;
+                let path = glossary_dir(&workspace).join(format!("{}.json", name));
+                let existed = path.exists();
+                if existed {
+                    std::fs::remove_file(&path)?;
+                }
+                Ok(json!({ "deleted": existed, "glossary": name }))
+            }
+            "glossary_set" => action_glossary_set(&workspace, &params),
+            "translate" | "translate_file" => {
+                let settings = &ctx.config.tools.translate;
+                let backend_name = params
+                    .get("backend")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or(&settings.backend);
+                let backend = Backend::parse(backend_name).ok_or_else(|| {
+                    Error::Config(format!(
+                        "Unknown translate backend '{}' in tools.translate.backend",
+                        backend_name
+                    ))
+                })?;
+                let translator = Translator::new(&ctx.config, backend)?;
+
+                let glossary = match params.get("glossary").and_then(|v| v.as_str()) {
+                    Some(name) if !name.trim().is_empty() => Some(load_glossary(&workspace, name)?),
+                    _ => None,
+                };
+                let source = params
+                    .get("source_lang")
+                    .and_then(|v| v.as_str())
+                    .or_else(|| glossary.as_ref().and_then(|g| g.source_lang.as_deref()));
+                let explicit_target = params
+                    .get("target_lang")
+                    .and_then(|v| v.as_str())
+                    .or_else(|| glossary.as_ref().and_then(|g| g.target_lang.as_deref()));
+
+                if action == "translate" {
+                    translate_texts(
+                        &translator,
+                        &params,
+                        source,
+                        explicit_target,
+                        glossary.as_ref(),
+                    )
+                    .await
+                } else {
+                    translate_files(
+                        &translator,
+                        &workspace,
+                        &params,
+                        source,
+                        explicit_target,
+                        glossary.as_ref(),
+                    )
+                    .await
+                }
+            }
+            _ => Err(Error::Tool(format!("Unknown action: {}", action))),
+        }
+    }
+}
+
+async fn translate_texts(
+    translator: &Translator<'_>,
+    params: &Value,
+    source: Option<&str>,
+    explicit_target: Option<&str>,
+    glossary: Option<&Glossary>,
+) -> Result<Value> {
+    let single = params.get("text").and_then(|v| v.as_str());
+    let texts: Vec<String> = match single {
+        Some(t) => vec![t.to_string()],
+        None => params["texts"]
+            .as_array()
+            .map(|a| {
+                a.iter()
+                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+
+    let detected = detect_language(&texts.join("\n"));
+    let target = resolve_target(
+        explicit_target,
+        source.unwrap_or(detected),
+        &translator.settings.default_target,
+    );
+    let translated = translator
+        .translate_many(&texts, source, &target, glossary)
+        .await?;
+
+    let violations: Vec<Value> = match glossary {
+        Some(g) => texts
+            .iter()
+            .zip(&translated)
+            .flat_map(|(s, t)| glossary_violations(g, s, t))
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let mut out = json!({
+        "backend": translator.backend.name(),
+        "source_lang": source.unwrap_or(detected),
+        "target_lang": target,
+        "glossary": glossary.map(|g| g.name.clone()),
+        "glossary_violations": violations,
+    });
+    if single.is_some() {
+        out["translation"] = json!(translated.into_iter().next().unwrap_or_default());
+    } else {
+        out["translations"] = json!(translated);
+    }
+    Ok(out)
+}
+
+async fn translate_files(
+    translator: &Translator<'_>,
+    workspace: &Path,
+    params: &Value,
+    source: Option<&str>,
+    explicit_target: Option<&str>,
+    glossary: Option<&Glossary>,
+) -> Result<Value> {
+    let mut files: Vec<PathBuf> = Vec::new();
+    if let Some(p) = params.get("path").and_then(|v| v.as_str()) {
+        files.push(expand_path(p, workspace));
+    }
+    if let Some(arr) = params.get("paths").and_then(|v| v.as_array()) {
+        files.extend(
+            arr.iter()
+                .filter_map(|v| v.as_str())
+                .map(|p| expand_path(p, workspace)),
+        );
+    }
+    let output = params
+        .get("output_path")
+        .and_then(|v| v.as_str())
+        .map(|p| expand_path(p, workspace));
+
+    // Without an explicit target, detect from the first readable text file.
+    let target = match explicit_target {
+        Some(t) => t.to_string(),
+        None => {
+            let sample = files
+                .iter()
+                .find_map(|f| std::fs::read_to_string(f).ok())
+                .unwrap_or_default();
+            let detected = source.unwrap_or_else(|| detect_language(&sample));
+            resolve_target(None, detected, &translator.settings.default_target)
+        }
+    };
+
+    let job = FileJob {
+        translator,
+        source,
+        target: &target,
+        glossary,
+    };
+    let mut results = Vec::new();
+    let mut failed = 0;
+    for file in &files {
+        match job.translate_file(file, output.clone()).await {
+            Ok(r) => results.push(r),
+            Err(e) => {
+                failed += 1;
+                results.push(json!({
+                    "source_file": file.display().to_string(),
+                    "error": e.to_string(),
+                }));
+            }
+        }
+    }
+
+    if failed == files.len() {
+        return Err(Error::Tool(format!(
+            "All file translations failed: {}",
+            Value::Array(results)
+        )));
+    }
+    Ok(json!({
+        "backend": translator.backend.name(),
+        "target_lang": target,
+        "glossary": glossary.map(|g| g.name.clone()),
+        "translated": files.len() - failed,
+        "failed": failed,
+        "files": results,
+    }))
+}
+
+fn action_glossary_set(workspace: &Path, params: &Value) -> Result<Value> {
+    let name = validate_glossary_name(params["glossary"].as_str().unwrap_or(""))?;
+    let mut glossary = load_glossary(workspace, name).unwrap_or_else(|_| Glossary {
+        name: name.to_string(),
+        ..Default::default()
+    });
+    if params
+        .get("replace")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+    {
+        glossary.terms.clear();
+    }
+    if let Some(terms) = params.get("terms").and_then(|v| v.as_object()) {
+        for (src, tgt) in terms {
+            if let Some(tgt) = tgt.as_str() {
+                let src = src.trim();
+                if !src.is_empty() {
+                    glossary
+                        .terms
+                        .insert(src.to_string(), tgt.trim().to_string());
+                }
+            }
+        }
+    }
+    if let Some(remove) = params.get("remove_terms").and_then(|v| v.as_array()) {
+        for term in remove.iter().filter_map(|v| v.as_str()) {
+            glossary.terms.remove(term.trim());
+        }
+    }
+    for (key, slot) in [
+        ("source_lang", &mut glossary.source_lang),
+        ("target_lang", &mut glossary.target_lang),
+        ("domain", &mut glossary.domain),
+    ] {
+        if let Some(v) = params.get(key).and_then(|v| v.as_str()) {
+            *slot = Some(v.trim().to_string()).filter(|s| !s.is_empty());
+        }
+    }
+    glossary.updated_at = chrono::Utc::now().timestamp_millis();
+    let path = save_glossary(workspace, &glossary)?;
+
+    Ok(json!({
+        "status": "saved",
+        "glossary": glossary.name,
+        "term_count": glossary.terms.len(),
+        "path": path.display().to_string(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glossary() -> Glossary {
+        Glossary {
+            name: "chain".to_string(),
+            terms: BTreeMap::from([
+                ("smart contract".to_string(), "智能合约".to_string()),
+                ("contract".to_string(), "合约".to_string()),
+            ]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_translate_schema() {
+        let tool = TranslateTool;
+        assert_eq!(tool.schema().name, "translate");
+    }
+
+    #[test]
+    fn test_translate_validate() {
+        let tool = TranslateTool;
+        assert!(tool.validate(&json!({"text": "hello"})).is_ok());
+        assert!(tool.validate(&json!({"action": "translate"})).is_err());
+        assert!(tool
+            .validate(&json!({"text": "hi", "backend": "babelfish"}))
+            .is_err());
+        assert!(tool
+            .validate(&json!({"action": "translate_file", "paths": ["a.md", "b.md"], "output_path": "x.md"}))
+            .is_err());
+        assert!(tool
+            .validate(&json!({"action": "glossary_set", "glossary": "../etc"}))
+            .is_err());
+        assert!(tool
+            .validate(
+                &json!({"action": "glossary_set", "glossary": "法律", "terms": {"tort": "侵权"}})
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(detect_language("Hello, world"), "en");
+        assert_eq!(detect_language("你好，世界"), "zh");
+        assert_eq!(detect_language("こんにちは世界"), "ja");
+        assert_eq!(detect_language("안녕하세요"), "ko");
+        assert_eq!(detect_language("Привет мир"), "ru");
+    }
+
+    #[test]
+    fn test_resolve_target() {
+        assert_eq!(resolve_target(None, "en", "zh"), "zh");
+        assert_eq!(resolve_target(None, "zh", "zh"), "en");
+        assert_eq!(resolve_target(None, "en", "en-US"), "zh");
+        assert_eq!(resolve_target(Some("de"), "en", "zh"), "de");
+    }
+
+    #[test]
+    fn test_glossary_protect_restore() {
+        let g = glossary();
+        let text = "Deploy the Smart Contract, then audit the contract.";
+        let terms = g.matching_terms(text);
+        assert_eq!(terms[0].0, "smart contract");
+        let (protected, targets) = protect_terms(text, &terms);
+        assert!(!protected.to_lowercase().contains("contract"));
+        let restored = restore_terms(&protected, &targets);
+        assert!(restored.contains("智能合约"));
+        assert!(restored.contains("合约."));
+    }
+
+    #[test]
+    fn test_glossary_violations() {
+        let g = glossary();
+        let v = glossary_violations(&g, "the smart contract", "这个智能合约");
+        assert!(v.is_empty());
+        let v = glossary_violations(&g, "the smart contract", "这个聪明的契约");
+        assert_eq!(v.len(), 2);
+    }
+
+    #[test]
+    fn test_split_markdown_roundtrip() {
+        let src = "---\ntitle: x\n---\n# Title\n\nSome text\nmore text\n\n```rust\nlet a = 1;\n\nlet b = 2;\n```\n\nEnd\n";
+        let blocks = split_markdown(src);
+        let inputs = blocks.translatable();
+        assert_eq!(inputs, vec!["# Title", "Some text\nmore text", "End"]);
+        assert_eq!(blocks.rebuild(&inputs), src);
+        let upper: Vec<String> = inputs.iter().map(|s| s.to_uppercase()).collect();
+        let rebuilt = blocks.rebuild(&upper);
+        assert!(rebuilt.contains("SOME TEXT\nMORE TEXT"));
+        assert!(rebuilt.contains("let a = 1;\n\nlet b = 2;"));
+        assert!(rebuilt.starts_with("---\ntitle: x\n---\n"));
+    }
+
+    #[test]
+    fn test_docx_paragraph_roundtrip() {
+        let xml = r#"<w:body><w:p><w:pPr/><w:r><w:t>Hello </w:t></w:r><w:r><w:t xml:space="preserve">A &amp; B</w:t></w:r></w:p><w:p><w:r><w:t></w:t></w:r></w:p><w:p w:rsidR="1"><w:r><w:t>Bye</w:t></w:r></w:p></w:body>"#;
+        let paras = docx_paragraphs(xml);
+        assert_eq!(paras, vec!["Hello A & B", "Bye"]);
+        let out = docx_apply(xml, &["你好 <A>".to_string(), "再见".to_string()]);
+        assert!(out.contains("<w:t xml:space=\"preserve\">你好 &lt;A&gt;</w:t>"));
+        assert!(out.contains("<w:t></w:t>"));
+        assert!(out.contains("再见"));
+        assert_eq!(docx_paragraphs(&out), vec!["你好 <A>", "再见"]);
+    }
+
+    #[test]
+    fn test_llm_batches_and_parse() {
+        let texts: Vec<String> = (0..100).map(|i| format!("segment {}", i)).collect();
+        let batches = llm_batches(&texts);
+        assert!(batches.iter().all(|b| b.len() <= LLM_BATCH_ITEMS));
+        assert_eq!(batches.iter().map(|b| b.len()).sum::<usize>(), 100);
+
+        let reply = "```json\n[\"a\", \"b\"]\n```";
+        assert_eq!(
+            parse_json_string_array(reply),
+            Some(vec!["a".to_string(), "b".to_string()])
+        );
+        assert_eq!(parse_json_string_array("no array"), None);
+    }
+
+    #[test]
+    fn test_default_output_path_and_lang_codes() {
+        assert_eq!(
+            default_output_path(Path::new("/tmp/report.md"), "en"),
+            PathBuf::from("/tmp/report.en.md")
+        );
+        assert_eq!(deepl_lang("en", true), "EN-US");
+        assert_eq!(deepl_lang("en-GB", false), "EN");
+        assert_eq!(google_lang("zh"), "zh-CN");
+    }
+}
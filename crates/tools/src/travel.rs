@@ -0,0 +1,867 @@
+//! Flight and train status lookups plus persistent trip watchers.
+//!
+//! Flights come from aviationstack, trains from a HAFAS REST endpoint (db-rest API).
+//! Both are normalized into [`TravelStatus`]. Watchers live in
+//! `workspace/travel/watches.json`; the runtime tick calls [`spawn_due_checks`], which
+//! re-queries active trips every `tools.travel.pollMinutes` and posts a system event when
+//! the status, a delay, a gate/platform or a terminal changes.
+
+use async_trait::async_trait;
+use blockcell_core::config::TravelConfig;
+use blockcell_core::secrets::resolve_secret_ref;
+use blockcell_core::system_event::EventPriority;
+use blockcell_core::{Config, Error, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::egress::HttpClientFactory;
+use crate::{EventEmitterHandle, PromptContext, Tool, ToolContext, ToolSchema};
+
+const REQUEST_TIMEOUT_SECS: u64 = 20;
+/// Statuses after which a trip needs no more watching.
+const FINAL_STATUSES: &[&str] = &["landed", "arrived", "cancelled", "diverted"];
+
+static STORE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+static CHECK_RUNNING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TripKind {
+    Flight,
+    Train,
+}
+
+impl TripKind {
+    fn parse(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "flight" => Ok(TripKind::Flight),
+            "train" => Ok(TripKind::Train),
+            other => Err(Error::Validation(format!(
+                "Unknown trip kind '{}'. Use flight or train",
+                other
+            ))),
+        }
+    }
+}
+
+/// One end of a trip.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Endpoint {
+    pub station: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    pub scheduled: Option<String>,
+    pub estimated: Option<String>,
+    pub actual: Option<String>,
+    pub delay_minutes: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub terminal: Option<String>,
+    /// Gate for flights, platform for trains.
+    pub gate: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TravelStatus {
+    pub kind: TripKind,
+    pub number: String,
+    pub date: String,
+    /// scheduled, active, landed/arrived, cancelled, diverted, incident or unknown.
+    pub status: String,
+    pub carrier: Option<String>,
+    pub departure: Endpoint,
+    pub arrival: Endpoint,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Providers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn aviationstack_key(cfg: &TravelConfig, workspace: &Path) -> Result<String> {
+    if cfg.aviationstack_api_key.trim().is_empty() {
+        return std::env::var("AVIATIONSTACK_API_KEY")
+            .ok()
+            .map(|k| k.trim().to_string())
+            .filter(|k| !k.is_empty())
+            .ok_or_else(|| {
+                Error::Tool(
+                    "Flight status needs tools.travel.aviationstackApiKey (or AVIATIONSTACK_API_KEY)"
+                        .into(),
+                )
+            });
+    }
+    resolve_secret_ref(
+        &crate::paths_for_workspace(workspace),
+        &cfg.aviationstack_api_key,
+    )
+}
+
+async fn get_json(config: &Config, url: &str, query: &[(&str, String)]) -> Result<Value> {
+    let factory = HttpClientFactory::for_tool(config, "travel");
+    factory.check(url).await?;
+    let client = factory
+        .builder()
+        .timeout(std::time::Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| Error::Tool(format!("Failed to build HTTP client: {}", e)))?;
+    client
+        .get(url)
+        .query(query)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        // Without the URL: the aviationstack key travels in the query string.
+        .map_err(|e| Error::Tool(format!("Travel status request failed: {}", e.without_url())))?
+        .json()
+        .await
+        .map_err(|e| Error::Tool(format!("Invalid travel status response: {}", e)))
+}
+
+fn opt_str(v: &Value) -> Option<String> {
+    match v {
+        Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// `LH 400`/`lh400` → `LH400`.
+fn normalize_flight(number: &str) -> String {
+    number
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect::<String>()
+        .to_ascii_uppercase()
+}
+
+/// `ICE123`/`ice 123` → `ICE 123`, the form HAFAS line names use.
+fn normalize_train(number: &str) -> String {
+    let compact: String = number
+        .split_whitespace()
+        .collect::<String>()
+        .to_ascii_uppercase();
+    match compact.find(|c: char| c.is_ascii_digit()) {
+        Some(i) if i > 0 => format!("{} {}", &compact[..i], &compact[i..]),
+        _ => compact,
+    }
+}
+
+fn parse_aviationstack(item: &Value, number: &str, date: &str) -> TravelStatus {
+    let endpoint = |e: &Value| Endpoint {
+        station: opt_str(&e["airport"]).unwrap_or_default(),
+        code: opt_str(&e["iata"]),
+        scheduled: opt_str(&e["scheduled"]),
+        estimated: opt_str(&e["estimated"]),
+        actual: opt_str(&e["actual"]),
+        delay_minutes: e["delay"].as_i64(),
+        terminal: opt_str(&e["terminal"]),
+        gate: opt_str(&e["gate"]),
+    };
+    TravelStatus {
+        kind: TripKind::Flight,
+        number: number.to_string(),
+        date: opt_str(&item["flight_date"]).unwrap_or_else(|| date.to_string()),
+        status: opt_str(&item["flight_status"]).unwrap_or_else(|| "unknown".to_string()),
+        carrier: opt_str(&item["airline"]["name"]),
+        departure: endpoint(&item["departure"]),
+        arrival: endpoint(&item["arrival"]),
+    }
+}
+
+async fn flight_status(
+    config: &Config,
+    workspace: &Path,
+    number: &str,
+    date: &str,
+) -> Result<TravelStatus> {
+    let cfg = &config.tools.travel;
+    let key = aviationstack_key(cfg, workspace)?;
+    let number = normalize_flight(number);
+    let url = format!("{}/flights", cfg.aviationstack_base.trim_end_matches('/'));
+    let body = get_json(
+        config,
+        &url,
+        &[
+            ("access_key", key),
+            ("flight_iata", number.clone()),
+            ("flight_date", date.to_string()),
+        ],
+    )
+    .await?;
+    if let Some(message) = body["error"]["message"].as_str() {
+        return Err(Error::Tool(format!("aviationstack: {}", message)));
+    }
+    body["data"]
+        .as_array()
+        .and_then(|items| {
+            items
+                .iter()
+                .find(|i| i["flight_date"].as_str() == Some(date))
+                .or_else(|| items.first())
+        })
+        .map(|item| parse_aviationstack(item, &number, date))
+        .ok_or_else(|| Error::NotFound(format!("No flight {} found on {}", number, date)))
+}
+
+fn parse_hafas_trip(trip: &Value, number: &str, date: &str, now: DateTime<Utc>) -> TravelStatus {
+    let endpoint = |prefix: &str, place: &Value| {
+        let planned = opt_str(&trip[format!("planned{}", capitalize(prefix))]);
+        let current = opt_str(&trip[prefix]);
+        let passed = current
+            .as_deref()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .is_some_and(|t| t <= now);
+        Endpoint {
+            station: opt_str(&place["name"]).unwrap_or_default(),
+            code: opt_str(&place["id"]),
+            scheduled: planned,
+            estimated: current.clone().filter(|_| !passed),
+            actual: current.filter(|_| passed),
+            delay_minutes: trip[format!("{}Delay", prefix)].as_i64().map(|s| s / 60),
+            terminal: None,
+            gate: opt_str(&trip[format!("{}Platform", prefix)])
+                .or_else(|| opt_str(&trip[format!("planned{}Platform", capitalize(prefix))])),
+        }
+    };
+    let departure = endpoint("departure", &trip["origin"]);
+    let arrival = endpoint("arrival", &trip["destination"]);
+    let status = if trip["cancelled"].as_bool().unwrap_or(false) {
+        "cancelled"
+    } else if arrival.actual.is_some() {
+        "arrived"
+    } else if departure.actual.is_some() {
+        "active"
+    } else {
+        "scheduled"
+    };
+    TravelStatus {
+        kind: TripKind::Train,
+        number: opt_str(&trip["line"]["name"]).unwrap_or_else(|| number.to_string()),
+        date: date.to_string(),
+        status: status.to_string(),
+        carrier: opt_str(&trip["line"]["operator"]["name"]),
+        departure,
+        arrival,
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+async fn train_status(config: &Config, number: &str, date: &str) -> Result<TravelStatus> {
+    let base = config
+        .tools
+        .travel
+        .train_api_base
+        .trim()
+        .trim_end_matches('/');
+    if base.is_empty() {
+        return Err(Error::Tool(
+            "Train status is disabled (tools.travel.trainApiBase is empty)".into(),
+        ));
+    }
+    let number = normalize_train(number);
+    let found = get_json(
+        config,
+        &format!("{}/trips", base),
+        &[
+            ("query", number.clone()),
+            ("onlyCurrentlyRunning", "false".to_string()),
+            ("fromWhen", format!("{}T00:00", date)),
+            ("untilWhen", format!("{}T23:59", date)),
+        ],
+    )
+    .await?;
+    let id = found["trips"]
+        .as_array()
+        .and_then(|trips| {
+            trips
+                .iter()
+                .find(|t| {
+                    t["line"]["name"]
+                        .as_str()
+                        .is_some_and(|n| n.eq_ignore_ascii_case(&number))
+                })
+                .or_else(|| trips.first())
+        })
+        .and_then(|t| t["id"].as_str())
+        .ok_or_else(|| Error::NotFound(format!("No train {} found on {}", number, date)))?
+        .to_string();
+    let body = get_json(
+        config,
+        &format!("{}/trips/{}", base, urlencoding::encode(&id)),
+        &[("stopovers", "false".to_string())],
+    )
+    .await?;
+    let trip = if body["trip"].is_object() {
+        &body["trip"]
+    } else {
+        &body
+    };
+    Ok(parse_hafas_trip(trip, &number, date, Utc::now()))
+}
+
+pub async fn lookup(
+    config: &Config,
+    workspace: &Path,
+    kind: TripKind,
+    number: &str,
+    date: &str,
+) -> Result<TravelStatus> {
+    match kind {
+        TripKind::Flight => flight_status(config, workspace, number, date).await,
+        TripKind::Train => train_status(config, number, date).await,
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Change detection
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn status_label(status: &str) -> &str {
+    match status {
+        "scheduled" => "计划中",
+        "active" => "运行中",
+        "landed" => "已降落",
+        "arrived" => "已到达",
+        "cancelled" => "已取消",
+        "diverted" => "已备降",
+        "incident" => "发生事故",
+        other => other,
+    }
+}
+
+/// Human-readable changes from `old` to `new` and the priority they deserve.
+fn detect_changes(
+    old: &TravelStatus,
+    new: &TravelStatus,
+    threshold: i64,
+) -> (Vec<String>, EventPriority) {
+    let mut changes = Vec::new();
+    let mut priority = EventPriority::Normal;
+    if old.status != new.status {
+        changes.push(format!(
+            "状态：{} → {}",
+            status_label(&old.status),
+            status_label(&new.status)
+        ));
+        if matches!(new.status.as_str(), "cancelled" | "diverted" | "incident") {
+            priority = EventPriority::Critical;
+        }
+    }
+    let gate_word = match new.kind {
+        TripKind::Flight => "登机口",
+        TripKind::Train => "站台",
+    };
+    for (label, o, n) in [
+        ("出发", &old.departure, &new.departure),
+        ("到达", &old.arrival, &new.arrival),
+    ] {
+        let (was, now) = (o.delay_minutes.unwrap_or(0), n.delay_minutes.unwrap_or(0));
+        if (now - was).abs() >= threshold.max(1) {
+            changes.push(format!("{}延误：{} 分钟（之前 {} 分钟）", label, now, was));
+            if priority != EventPriority::Critical {
+                priority = EventPriority::High;
+            }
+        }
+        if o.gate != n.gate && n.gate.is_some() {
+            changes.push(format!(
+                "{}{}：{} → {}",
+                label,
+                gate_word,
+                o.gate.as_deref().unwrap_or("-"),
+                n.gate.as_deref().unwrap_or("-")
+            ));
+            if priority != EventPriority::Critical {
+                priority = EventPriority::High;
+            }
+        }
+        if o.terminal != n.terminal && n.terminal.is_some() {
+            changes.push(format!(
+                "{}航站楼：{} → {}",
+                label,
+                o.terminal.as_deref().unwrap_or("-"),
+                n.terminal.as_deref().unwrap_or("-")
+            ));
+        }
+    }
+    (changes, priority)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Watchers
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize)]
+struct WatchStore {
+    version: u32,
+    watches: Vec<TripWatch>,
+}
+
+impl Default for WatchStore {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            watches: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TripWatch {
+    id: String,
+    kind: TripKind,
+    number: String,
+    date: String,
+    #[serde(default)]
+    label: Option<String>,
+    active: bool,
+    created_at: i64,
+    #[serde(default)]
+    last_checked: Option<i64>,
+    #[serde(default)]
+    last_status: Option<TravelStatus>,
+    #[serde(default)]
+    last_error: Option<String>,
+}
+
+impl TripWatch {
+    fn title(&self) -> String {
+        match &self.label {
+            Some(label) => format!("{}（{} {}）", label, self.number, self.date),
+            None => format!("{}（{}）", self.number, self.date),
+        }
+    }
+}
+
+fn store_path(workspace: &Path) -> PathBuf {
+    workspace.join("travel").join("watches.json")
+}
+
+fn load_store(workspace: &Path) -> Result<WatchStore> {
+    let path = store_path(workspace);
+    if !path.exists() {
+        return Ok(WatchStore::default());
+    }
+    let content = std::fs::read_to_string(&path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+fn save_store(workspace: &Path, store: &WatchStore) -> Result<()> {
+    let path = store_path(workspace);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(store)?)?;
+    Ok(())
+}
+
+/// Result of checking one watch.
+struct CheckOutcome {
+    watch: TripWatch,
+    changes: Vec<String>,
+    priority: EventPriority,
+}
+
+/// Re-queries active watches (only those due unless `force`) and records their status.
+async fn check_watches(
+    config: &Config,
+    workspace: &Path,
+    force: bool,
+) -> Result<Vec<CheckOutcome>> {
+    let cfg = &config.tools.travel;
+    let now = Utc::now();
+    let today = now.date_naive();
+    let due: Vec<TripWatch> = {
+        let _guard = STORE_LOCK.lock().await;
+        let mut store = load_store(workspace)?;
+        let mut expired = false;
+        for w in store.watches.iter_mut().filter(|w| w.active) {
+            // Trips more than a day in the past are done, whatever the API says.
+            let stale = NaiveDate::parse_from_str(&w.date, "%Y-%m-%d")
+                .is_ok_and(|d| d < today - chrono::Duration::days(1));
+            if stale {
+                w.active = false;
+                expired = true;
+            }
+        }
+        if expired {
+            save_store(workspace, &store)?;
+        }
+        let interval = cfg.poll_minutes.max(1) as i64 * 60_000;
+        store
+            .watches
+            .into_iter()
+            .filter(|w| w.active)
+            .filter(|w| {
+                force
+                    || w.last_checked
+                        .is_none_or(|t| now.timestamp_millis() - t >= interval)
+            })
+            .collect()
+    };
+
+    let mut outcomes = Vec::new();
+    for mut watch in due {
+        let result = lookup(config, workspace, watch.kind, &watch.number, &watch.date).await;
+        watch.last_checked = Some(Utc::now().timestamp_millis());
+        let (changes, priority) = match result {
+            Ok(status) => {
+                watch.last_error = None;
+                let found = match &watch.last_status {
+                    Some(old) => detect_changes(old, &status, cfg.delay_threshold_minutes),
+                    None => (Vec::new(), EventPriority::Normal),
+                };
+                if FINAL_STATUSES.contains(&status.status.as_str()) {
+                    watch.active = false;
+                }
+                watch.last_status = Some(status);
+                found
+            }
+            Err(e) => {
+                warn!(watch = %watch.id, error = %e, "Trip status check failed");
+                watch.last_error = Some(e.to_string());
+                (Vec::new(), EventPriority::Normal)
+            }
+        };
+        outcomes.push(CheckOutcome {
+            watch,
+            changes,
+            priority,
+        });
+    }
+
+    if !outcomes.is_empty() {
+        let _guard = STORE_LOCK.lock().await;
+        let mut store = load_store(workspace)?;
+        for outcome in &outcomes {
+            // The watch may have been removed while the lookup was running.
+            if let Some(w) = store.watches.iter_mut().find(|w| w.id == outcome.watch.id) {
+                *w = outcome.watch.clone();
+            }
+        }
+        save_store(workspace, &store)?;
+    }
+    Ok(outcomes)
+}
+
+fn change_event_text(watch: &TripWatch, changes: &[String]) -> String {
+    let mut out = changes.join("\n");
+    if let Some(status) = &watch.last_status {
+        if let Some(t) = status
+            .departure
+            .estimated
+            .as_ref()
+            .or(status.departure.scheduled.as_ref())
+        {
+            out.push_str(&format!("\n出发：{} {}", status.departure.station, t));
+        }
+        if let Some(t) = status
+            .arrival
+            .estimated
+            .as_ref()
+            .or(status.arrival.scheduled.as_ref())
+        {
+            out.push_str(&format!("\n到达：{} {}", status.arrival.station, t));
+        }
+    }
+    out
+}
+
+/// Check due trip watchers in the background and post a system event per changed trip.
+pub fn spawn_due_checks(config: Config, workspace: PathBuf, emitter: EventEmitterHandle) {
+    if !store_path(&workspace).exists() {
+        return;
+    }
+    if CHECK_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    tokio::spawn(async move {
+        match check_watches(&config, &workspace, false).await {
+            Ok(outcomes) => {
+                for o in outcomes.iter().filter(|o| !o.changes.is_empty()) {
+                    info!(watch = %o.watch.id, changes = o.changes.len(), "Trip status changed");
+                    emitter.emit_simple(
+                        "travel.changed",
+                        "travel",
+                        o.priority,
+                        &format!("行程变动：{}", o.watch.title()),
+                        &change_event_text(&o.watch, &o.changes),
+                    );
+                }
+            }
+            Err(e) => warn!(error = %e, "Trip watcher check failed"),
+        }
+        CHECK_RUNNING.store(false, Ordering::SeqCst);
+    });
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tool
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn date_param(params: &Value, tz: Option<&str>) -> Result<String> {
+    match params["date"]
+        .as_str()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        Some(d) => NaiveDate::parse_from_str(d, "%Y-%m-%d")
+            .map(|d| d.format("%Y-%m-%d").to_string())
+            .map_err(|_| Error::Validation(format!("Invalid date '{}'. Use YYYY-MM-DD", d))),
+        None => {
+            let now = Utc::now();
+            let today = match tz.and_then(|t| t.parse::<chrono_tz::Tz>().ok()) {
+                Some(zone) => now.with_timezone(&zone).date_naive(),
+                None => now.with_timezone(&chrono::Local).date_naive(),
+            };
+            Ok(today.format("%Y-%m-%d").to_string())
+        }
+    }
+}
+
+fn watch_json(w: &TripWatch) -> Value {
+    json!({
+        "id": w.id,
+        "kind": w.kind,
+        "number": w.number,
+        "date": w.date,
+        "label": w.label,
+        "active": w.active,
+        "last_checked": w.last_checked,
+        "last_status": w.last_status,
+        "last_error": w.last_error,
+    })
+}
+
+pub struct TravelTool;
+
+#[async_trait]
+impl Tool for TravelTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "travel",
+            description: "Flight and train status with trip watchers that alert on delays, gate/platform changes and cancellations. You MUST provide `action`. action='status': requires `kind` ('flight'|'train') and `number` (e.g. 'LH400', 'ICE 123'); optional `date` (YYYY-MM-DD, default today). action='watch': same params plus optional `label`; changes are announced automatically. action='unwatch': requires `id`. action='watches': lists watchers. action='check': re-checks active watchers now.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["status", "watch", "unwatch", "watches", "check"],
+                        "description": "Action to perform"
+                    },
+                    "kind": {"type": "string", "enum": ["flight", "train"]},
+                    "number": {"type": "string", "description": "Flight (IATA, e.g. 'BA117') or train number (e.g. 'ICE 123')"},
+                    "date": {"type": "string", "description": "Travel date YYYY-MM-DD. Default: today"},
+                    "label": {"type": "string", "description": "(watch) Name shown in alerts, e.g. 'Trip to Lisbon'"},
+                    "id": {"type": "string", "description": "(unwatch) Watcher id"}
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        match params["action"].as_str().unwrap_or("") {
+            "status" | "watch" => {
+                TripKind::parse(params["kind"].as_str().unwrap_or(""))?;
+                if params["number"]
+                    .as_str()
+                    .is_none_or(|s| s.trim().is_empty())
+                {
+                    return Err(Error::Validation("'number' is required".into()));
+                }
+                Ok(())
+            }
+            "unwatch" => {
+                if params["id"].as_str().is_none_or(|s| s.trim().is_empty()) {
+                    return Err(Error::Validation("'id' is required for unwatch".into()));
+                }
+                Ok(())
+            }
+            "watches" | "check" => Ok(()),
+            other => Err(Error::Validation(format!(
+                "Unknown action '{}'. Use status, watch, unwatch, watches or check",
+                other
+            ))),
+        }
+    }
+
+    fn prompt_rule(&self, _ctx: &PromptContext) -> Option<String> {
+        Some(
+            "- When the user mentions an upcoming flight or train with its number, offer `travel` `watch` so delays and gate/platform changes are announced automatically."
+                .to_string(),
+        )
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        let tz = ctx.config.default_timezone.clone();
+        let workspace = ctx.workspace.clone();
+        match params["action"].as_str().unwrap_or("") {
+            "status" => {
+                let kind = TripKind::parse(params["kind"].as_str().unwrap_or(""))?;
+                let date = date_param(&params, tz.as_deref())?;
+                let number = params["number"].as_str().unwrap_or("").trim();
+                let status = lookup(&ctx.config, &workspace, kind, number, &date).await?;
+                Ok(json!(status))
+            }
+            "watch" => {
+                let kind = TripKind::parse(params["kind"].as_str().unwrap_or(""))?;
+                let date = date_param(&params, tz.as_deref())?;
+                let raw = params["number"].as_str().unwrap_or("").trim();
+                let number = match kind {
+                    TripKind::Flight => normalize_flight(raw),
+                    TripKind::Train => normalize_train(raw),
+                };
+                // The first lookup validates the trip and becomes the baseline for alerts.
+                let status = lookup(&ctx.config, &workspace, kind, &number, &date).await?;
+                let watch = TripWatch {
+                    id: format!(
+                        "trip_{}",
+                        Uuid::new_v4().to_string().split('-').next().unwrap_or("x")
+                    ),
+                    kind,
+                    number,
+                    date,
+                    label: params["label"]
+                        .as_str()
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .map(String::from),
+                    active: !FINAL_STATUSES.contains(&status.status.as_str()),
+                    created_at: Utc::now().timestamp_millis(),
+                    last_checked: Some(Utc::now().timestamp_millis()),
+                    last_status: Some(status),
+                    last_error: None,
+                };
+                let _guard = STORE_LOCK.lock().await;
+                let mut store = load_store(&workspace)?;
+                store.watches.push(watch.clone());
+                save_store(&workspace, &store)?;
+                Ok(json!({"status": "watching", "watch": watch_json(&watch)}))
+            }
+            "unwatch" => {
+                let id = params["id"].as_str().unwrap_or("").trim();
+                let _guard = STORE_LOCK.lock().await;
+                let mut store = load_store(&workspace)?;
+                let before = store.watches.len();
+                store.watches.retain(|w| w.id != id);
+                if store.watches.len() == before {
+                    return Err(Error::NotFound(format!("Watcher '{}' not found", id)));
+                }
+                save_store(&workspace, &store)?;
+                Ok(json!({"status": "removed", "id": id}))
+            }
+            "watches" => {
+                let _guard = STORE_LOCK.lock().await;
+                let store = load_store(&workspace)?;
+                let watches: Vec<Value> = store.watches.iter().map(watch_json).collect();
+                Ok(json!({"watches": watches, "count": watches.len()}))
+            }
+            "check" => {
+                let outcomes = check_watches(&ctx.config, &workspace, true).await?;
+                let results: Vec<Value> = outcomes
+                    .iter()
+                    .map(|o| {
+                        json!({
+                            "watch": watch_json(&o.watch),
+                            "changes": o.changes,
+                        })
+                    })
+                    .collect();
+                Ok(json!({"checked": results.len(), "results": results}))
+            }
+            other => Err(Error::Tool(format!("Unknown action: {}", other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_numbers() {
+        assert_eq!(normalize_flight("lh 400"), "LH400");
+        assert_eq!(normalize_train("ice123"), "ICE 123");
+        assert_eq!(normalize_train("RE 5"), "RE 5");
+    }
+
+    #[test]
+    fn test_parse_providers() {
+        let item = json!({
+            "flight_date": "2026-10-15", "flight_status": "scheduled",
+            "airline": {"name": "Lufthansa"},
+            "departure": {"airport": "Frankfurt", "iata": "FRA", "terminal": "1", "gate": "B20",
+                          "delay": 25, "scheduled": "2026-10-15T10:25:00+00:00"},
+            "arrival": {"airport": "John F Kennedy", "iata": "JFK", "gate": null}
+        });
+        let f = parse_aviationstack(&item, "LH400", "2026-10-15");
+        assert_eq!(f.departure.gate.as_deref(), Some("B20"));
+        assert_eq!(f.departure.delay_minutes, Some(25));
+        assert_eq!(f.arrival.gate, None);
+
+        let trip = json!({
+            "line": {"name": "ICE 123", "operator": {"name": "DB Fernverkehr AG"}},
+            "origin": {"id": "8000105", "name": "Frankfurt(Main)Hbf"},
+            "destination": {"id": "8000261", "name": "München Hbf"},
+            "departure": "2026-10-15T08:04:00+02:00", "plannedDeparture": "2026-10-15T08:00:00+02:00",
+            "departureDelay": 240, "departurePlatform": "7",
+            "arrival": "2026-10-15T12:10:00+02:00", "plannedArrival": "2026-10-15T12:00:00+02:00",
+            "arrivalDelay": 600, "plannedArrivalPlatform": "12"
+        });
+        let now = DateTime::parse_from_rfc3339("2026-10-15T10:00:00+02:00")
+            .unwrap()
+            .with_timezone(&Utc);
+        let t = parse_hafas_trip(&trip, "ICE 123", "2026-10-15", now);
+        assert_eq!(t.status, "active");
+        assert_eq!(t.departure.delay_minutes, Some(4));
+        assert!(t.departure.actual.is_some());
+        assert_eq!(
+            t.arrival.estimated.as_deref(),
+            Some("2026-10-15T12:10:00+02:00")
+        );
+        assert_eq!(t.arrival.gate.as_deref(), Some("12"));
+    }
+
+    #[test]
+    fn test_detect_changes() {
+        let base = TravelStatus {
+            kind: TripKind::Flight,
+            number: "LH400".into(),
+            date: "2026-10-15".into(),
+            status: "scheduled".into(),
+            carrier: None,
+            departure: Endpoint {
+                gate: Some("B20".into()),
+                delay_minutes: Some(5),
+                ..Default::default()
+            },
+            arrival: Endpoint::default(),
+        };
+        let mut next = base.clone();
+        next.departure.delay_minutes = Some(12);
+        assert!(detect_changes(&base, &next, 15).0.is_empty());
+
+        next.departure.delay_minutes = Some(45);
+        next.departure.gate = Some("B44".into());
+        let (changes, priority) = detect_changes(&base, &next, 15);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(priority, EventPriority::High);
+
+        next.status = "cancelled".into();
+        assert_eq!(detect_changes(&base, &next, 15).1, EventPriority::Critical);
+    }
+}
@@ -89,12 +89,16 @@ impl Tool for TtsTool {
             .and_then(|v| v.as_str())
             .unwrap_or("info");
 
-        match action {
+        let result = match action {
             "speak" => action_speak(&ctx, &params).await,
             "list_voices" => action_list_voices(&params).await,
             "info" => action_info().await,
             _ => Err(Error::Tool(format!("Unknown action: {}", action))),
+        }?;
+        if let Some(path) = result.get("output_path").and_then(|v| v.as_str()) {
+            ctx.register_artifact(path, "audio", None);
         }
+        Ok(result)
     }
 }
 
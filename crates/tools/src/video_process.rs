@@ -867,6 +867,7 @@ mod tests {
             event_emitter: None,
             channel_contacts_file: None,
             response_cache: None,
+            artifacts: None,
         };
         assert_eq!(
             resolve_path(&ctx, "/absolute/path.mp4"),
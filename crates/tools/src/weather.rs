@@ -0,0 +1,1072 @@
+//! Weather lookups: place resolution, current conditions and hourly/daily forecasts from
+//! Open-Meteo (keyless) or OpenWeather, normalized into one shape.
+//!
+//! Forecasts are cached per location in `workspace/weather/cache.json` for
+//! `tools.weather.cacheMinutes`, and resolved place names are kept there as well, so
+//! repeated questions and the morning digest ([`digest_line`]) share one request.
+
+use async_trait::async_trait;
+use blockcell_core::config::WeatherConfig;
+use blockcell_core::secrets::resolve_secret_ref;
+use blockcell_core::{Config, Error, Result};
+use chrono::{DateTime, Timelike, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::egress::HttpClientFactory;
+use crate::{PromptContext, Tool, ToolContext, ToolSchema};
+
+const OPEN_METEO_FORECAST: &str = "https://api.open-meteo.com/v1/forecast";
+const OPEN_METEO_GEOCODE: &str = "https://geocoding-api.open-meteo.com/v1/search";
+const OPENWEATHER_BASE: &str = "https://api.openweathermap.org";
+const REQUEST_TIMEOUT_SECS: u64 = 15;
+const FORECAST_DAYS: usize = 7;
+const MAX_HOURS: usize = 48;
+/// Forecasts older than this are dropped from the cache file.
+const CACHE_RETENTION_SECS: i64 = 24 * 3600;
+
+static CACHE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Location {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl Location {
+    fn label(&self) -> String {
+        [
+            Some(self.name.as_str()),
+            self.region.as_deref(),
+            self.country.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Current {
+    /// Local time at the location, `YYYY-MM-DDTHH:MM`.
+    pub time: String,
+    pub condition: String,
+    pub temperature: Option<f64>,
+    pub feels_like: Option<f64>,
+    pub humidity: Option<f64>,
+    pub wind_speed: Option<f64>,
+    pub precipitation: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Hour {
+    pub time: String,
+    pub condition: String,
+    pub temperature: Option<f64>,
+    pub precipitation_probability: Option<f64>,
+    pub precipitation: Option<f64>,
+    pub wind_speed: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Day {
+    pub date: String,
+    pub condition: String,
+    pub temp_min: Option<f64>,
+    pub temp_max: Option<f64>,
+    pub precipitation: Option<f64>,
+    pub precipitation_probability: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sunrise: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sunset: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uv_index_max: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Forecast {
+    pub provider: String,
+    pub units: String,
+    pub fetched_at: i64,
+    pub current: Option<Current>,
+    /// Hourly (Open-Meteo) or 3-hourly (OpenWeather) steps from the current hour on.
+    pub hourly: Vec<Hour>,
+    pub daily: Vec<Day>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Cache {
+    #[serde(default)]
+    places: BTreeMap<String, Location>,
+    #[serde(default)]
+    forecasts: BTreeMap<String, Forecast>,
+}
+
+fn cache_path(workspace: &Path) -> PathBuf {
+    workspace.join("weather").join("cache.json")
+}
+
+fn load_cache(workspace: &Path) -> Cache {
+    std::fs::read_to_string(cache_path(workspace))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_cache(workspace: &Path, cache: &mut Cache) -> Result<()> {
+    let cutoff = Utc::now().timestamp() - CACHE_RETENTION_SECS;
+    cache.forecasts.retain(|_, f| f.fetched_at >= cutoff);
+    let path = cache_path(workspace);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(cache)?)?;
+    Ok(())
+}
+
+fn imperial(cfg: &WeatherConfig) -> bool {
+    cfg.units.trim().eq_ignore_ascii_case("imperial")
+}
+
+fn units_label(cfg: &WeatherConfig) -> &'static str {
+    if imperial(cfg) {
+        "°F, mph, inch"
+    } else {
+        "°C, km/h, mm"
+    }
+}
+
+fn forecast_key(loc: &Location, cfg: &WeatherConfig) -> String {
+    format!(
+        "{:.3},{:.3}:{}",
+        loc.latitude,
+        loc.longitude,
+        if imperial(cfg) { "imperial" } else { "metric" }
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Provider {
+    OpenMeteo,
+    OpenWeather,
+}
+
+impl Provider {
+    fn name(self) -> &'static str {
+        match self {
+            Provider::OpenMeteo => "open-meteo",
+            Provider::OpenWeather => "openweather",
+        }
+    }
+}
+
+/// Providers to try, preferred first. OpenWeather is only usable with a key.
+fn provider_order(cfg: &WeatherConfig, has_key: bool) -> Vec<Provider> {
+    let prefer_ow = cfg.provider.trim().eq_ignore_ascii_case("openweather");
+    match (prefer_ow, has_key) {
+        (true, true) => vec![Provider::OpenWeather, Provider::OpenMeteo],
+        (false, true) => vec![Provider::OpenMeteo, Provider::OpenWeather],
+        (_, false) => vec![Provider::OpenMeteo],
+    }
+}
+
+fn openweather_key(cfg: &WeatherConfig, workspace: &Path) -> Option<String> {
+    if cfg.openweather_api_key.trim().is_empty() {
+        return std::env::var("OPENWEATHER_API_KEY")
+            .ok()
+            .map(|k| k.trim().to_string())
+            .filter(|k| !k.is_empty());
+    }
+    match resolve_secret_ref(
+        &crate::paths_for_workspace(workspace),
+        &cfg.openweather_api_key,
+    ) {
+        Ok(key) => Some(key),
+        Err(e) => {
+            warn!(error = %e, "OpenWeather API key could not be resolved");
+            None
+        }
+    }
+}
+
+async fn get_json(config: &Config, url: &str, query: &[(&str, String)]) -> Result<Value> {
+    let factory = HttpClientFactory::for_tool(config, "weather");
+    factory.check(url).await?;
+    let client = factory
+        .builder()
+        .timeout(std::time::Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| Error::Tool(format!("Failed to build HTTP client: {}", e)))?;
+    client
+        .get(url)
+        .query(query)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        // Without the URL: OpenWeather keys travel in the query string.
+        .map_err(|e| Error::Tool(format!("Weather request failed: {}", e.without_url())))?
+        .json()
+        .await
+        .map_err(|e| Error::Tool(format!("Invalid weather response: {}", e)))
+}
+
+/// Text for a WMO weather interpretation code (used by Open-Meteo).
+fn wmo_condition(code: i64) -> &'static str {
+    match code {
+        0 => "clear sky",
+        1 => "mainly clear",
+        2 => "partly cloudy",
+        3 => "overcast",
+        45 | 48 => "fog",
+        51 | 53 | 55 => "drizzle",
+        56 | 57 => "freezing drizzle",
+        61 => "light rain",
+        63 => "rain",
+        65 => "heavy rain",
+        66 | 67 => "freezing rain",
+        71 => "light snow",
+        73 => "snow",
+        75 => "heavy snow",
+        77 => "snow grains",
+        80..=82 => "rain showers",
+        85 | 86 => "snow showers",
+        95 => "thunderstorm",
+        96 | 99 => "thunderstorm with hail",
+        _ => "unknown",
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Location resolution
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// `"52.52,13.40"` style coordinates.
+fn parse_coordinates(query: &str) -> Option<Location> {
+    let (lat, lon) = query.split_once(',')?;
+    let latitude: f64 = lat.trim().parse().ok()?;
+    let longitude: f64 = lon.trim().parse().ok()?;
+    ((-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude)).then(|| {
+        Location {
+            name: format!("{:.4},{:.4}", latitude, longitude),
+            region: None,
+            country: None,
+            latitude,
+            longitude,
+        }
+    })
+}
+
+async fn geocode_open_meteo(config: &Config, query: &str) -> Result<Vec<Location>> {
+    let body = get_json(
+        config,
+        OPEN_METEO_GEOCODE,
+        &[
+            ("name", query.to_string()),
+            ("count", "5".to_string()),
+            ("format", "json".to_string()),
+        ],
+    )
+    .await?;
+    Ok(body["results"]
+        .as_array()
+        .map(|results| {
+            results
+                .iter()
+                .filter_map(|r| {
+                    Some(Location {
+                        name: r["name"].as_str()?.to_string(),
+                        region: r["admin1"].as_str().map(String::from),
+                        country: r["country"].as_str().map(String::from),
+                        latitude: r["latitude"].as_f64()?,
+                        longitude: r["longitude"].as_f64()?,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+async fn geocode_openweather(config: &Config, key: &str, query: &str) -> Result<Vec<Location>> {
+    let body = get_json(
+        config,
+        &format!("{}/geo/1.0/direct", OPENWEATHER_BASE),
+        &[
+            ("q", query.to_string()),
+            ("limit", "5".to_string()),
+            ("appid", key.to_string()),
+        ],
+    )
+    .await?;
+    Ok(body
+        .as_array()
+        .map(|results| {
+            results
+                .iter()
+                .filter_map(|r| {
+                    Some(Location {
+                        name: r["name"].as_str()?.to_string(),
+                        region: r["state"].as_str().map(String::from),
+                        country: r["country"].as_str().map(String::from),
+                        latitude: r["lat"].as_f64()?,
+                        longitude: r["lon"].as_f64()?,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Candidate places for `query`, best match first.
+pub async fn geocode(config: &Config, workspace: &Path, query: &str) -> Result<Vec<Location>> {
+    let query = query.trim();
+    if let Some(loc) = parse_coordinates(query) {
+        return Ok(vec![loc]);
+    }
+    let cfg = &config.tools.weather;
+    let key = openweather_key(cfg, workspace);
+    let mut errors = Vec::new();
+    // Open-Meteo's geocoder is keyless, so it is always tried first.
+    match geocode_open_meteo(config, query).await {
+        Ok(found) if !found.is_empty() => return Ok(found),
+        Ok(_) => {}
+        Err(e) => errors.push(e.to_string()),
+    }
+    if let Some(key) = key {
+        match geocode_openweather(config, &key, query).await {
+            Ok(found) if !found.is_empty() => return Ok(found),
+            Ok(_) => {}
+            Err(e) => errors.push(e.to_string()),
+        }
+    }
+    if errors.is_empty() {
+        Err(Error::NotFound(format!("No place found for '{}'", query)))
+    } else {
+        Err(Error::Tool(errors.join("; ")))
+    }
+}
+
+/// Best match for `query`, remembered in the cache file.
+pub async fn resolve_location(config: &Config, workspace: &Path, query: &str) -> Result<Location> {
+    let key = query.trim().to_lowercase();
+    if key.is_empty() {
+        return Err(Error::Validation(
+            "No location given and tools.weather.defaultLocation is not set".into(),
+        ));
+    }
+    {
+        let _guard = CACHE_LOCK.lock().await;
+        if let Some(loc) = load_cache(workspace).places.get(&key) {
+            return Ok(loc.clone());
+        }
+    }
+    let loc = geocode(config, workspace, query)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| Error::NotFound(format!("No place found for '{}'", query)))?;
+    let _guard = CACHE_LOCK.lock().await;
+    let mut cache = load_cache(workspace);
+    cache.places.insert(key, loc.clone());
+    save_cache(workspace, &mut cache)?;
+    Ok(loc)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Providers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn column(section: &Value, key: &str, i: usize) -> Option<f64> {
+    section[key].get(i).and_then(|v| v.as_f64())
+}
+
+fn text_column(section: &Value, key: &str, i: usize) -> Option<String> {
+    section[key]
+        .get(i)
+        .and_then(|v| v.as_str())
+        .map(String::from)
+}
+
+fn parse_open_meteo(body: &Value) -> Forecast {
+    let cur = &body["current"];
+    let current = cur["time"].as_str().map(|time| Current {
+        time: time.to_string(),
+        condition: wmo_condition(cur["weather_code"].as_i64().unwrap_or(-1)).to_string(),
+        temperature: cur["temperature_2m"].as_f64(),
+        feels_like: cur["apparent_temperature"].as_f64(),
+        humidity: cur["relative_humidity_2m"].as_f64(),
+        wind_speed: cur["wind_speed_10m"].as_f64(),
+        precipitation: cur["precipitation"].as_f64(),
+    });
+    // Hour slots are `YYYY-MM-DDTHH:00`; keep the one containing "now" and later ones.
+    let this_hour = current
+        .as_ref()
+        .map(|c| c.time.chars().take(13).collect::<String>())
+        .unwrap_or_default();
+
+    let h = &body["hourly"];
+    let hourly = h["time"]
+        .as_array()
+        .map(|times| {
+            times
+                .iter()
+                .enumerate()
+                .filter_map(|(i, t)| {
+                    let time = t.as_str()?;
+                    (&time[..time.len().min(13)] >= this_hour.as_str()).then(|| Hour {
+                        time: time.to_string(),
+                        condition: wmo_condition(
+                            h["weather_code"]
+                                .get(i)
+                                .and_then(|v| v.as_i64())
+                                .unwrap_or(-1),
+                        )
+                        .to_string(),
+                        temperature: column(h, "temperature_2m", i),
+                        precipitation_probability: column(h, "precipitation_probability", i),
+                        precipitation: column(h, "precipitation", i),
+                        wind_speed: column(h, "wind_speed_10m", i),
+                    })
+                })
+                .take(MAX_HOURS)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let d = &body["daily"];
+    let daily = d["time"]
+        .as_array()
+        .map(|dates| {
+            dates
+                .iter()
+                .enumerate()
+                .filter_map(|(i, date)| {
+                    Some(Day {
+                        date: date.as_str()?.to_string(),
+                        condition: wmo_condition(
+                            d["weather_code"]
+                                .get(i)
+                                .and_then(|v| v.as_i64())
+                                .unwrap_or(-1),
+                        )
+                        .to_string(),
+                        temp_min: column(d, "temperature_2m_min", i),
+                        temp_max: column(d, "temperature_2m_max", i),
+                        precipitation: column(d, "precipitation_sum", i),
+                        precipitation_probability: column(d, "precipitation_probability_max", i),
+                        sunrise: text_column(d, "sunrise", i),
+                        sunset: text_column(d, "sunset", i),
+                        uv_index_max: column(d, "uv_index_max", i),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Forecast {
+        provider: Provider::OpenMeteo.name().to_string(),
+        current,
+        hourly,
+        daily,
+        ..Default::default()
+    }
+}
+
+async fn fetch_open_meteo(config: &Config, loc: &Location) -> Result<Forecast> {
+    let cfg = &config.tools.weather;
+    let mut query = vec![
+        ("latitude", loc.latitude.to_string()),
+        ("longitude", loc.longitude.to_string()),
+        (
+            "current",
+            "temperature_2m,apparent_temperature,relative_humidity_2m,precipitation,weather_code,wind_speed_10m".to_string(),
+        ),
+        (
+            "hourly",
+            "temperature_2m,precipitation_probability,precipitation,weather_code,wind_speed_10m".to_string(),
+        ),
+        (
+            "daily",
+            "weather_code,temperature_2m_max,temperature_2m_min,precipitation_sum,precipitation_probability_max,sunrise,sunset,uv_index_max".to_string(),
+        ),
+        ("timezone", "auto".to_string()),
+        ("forecast_days", FORECAST_DAYS.to_string()),
+    ];
+    if imperial(cfg) {
+        query.push(("temperature_unit", "fahrenheit".to_string()));
+        query.push(("wind_speed_unit", "mph".to_string()));
+        query.push(("precipitation_unit", "inch".to_string()));
+    }
+    open_meteo_forecast(&get_json(config, OPEN_METEO_FORECAST, &query).await?)
+}
+
+/// Forecast from an Open-Meteo response. Error bodies (`{"error": true, "reason": ...}`)
+/// carry neither `current` nor `daily` and are reported with their reason.
+fn open_meteo_forecast(body: &Value) -> Result<Forecast> {
+    if body["current"].is_null() && body["daily"].is_null() {
+        return Err(Error::Tool(format!(
+            "Open-Meteo returned no data: {}",
+            body["reason"].as_str().unwrap_or("unknown reason")
+        )));
+    }
+    Ok(parse_open_meteo(body))
+}
+
+/// Local `YYYY-MM-DDTHH:MM` for a unix timestamp and a UTC offset in seconds.
+fn local_time(ts: i64, offset_secs: i64) -> String {
+    DateTime::from_timestamp(ts + offset_secs, 0)
+        .map(|dt| dt.naive_utc().format("%Y-%m-%dT%H:%M").to_string())
+        .unwrap_or_default()
+}
+
+/// Normalizes OpenWeather's free `weather` + 3-hourly `forecast` responses. Metric wind
+/// speeds are converted from m/s to km/h to match Open-Meteo.
+fn parse_openweather(current: &Value, forecast: &Value, imperial: bool) -> Forecast {
+    let wind = |v: Option<f64>| v.map(|s| if imperial { s } else { s * 3.6 });
+    let condition = |item: &Value| {
+        item["weather"][0]["description"]
+            .as_str()
+            .unwrap_or("unknown")
+            .to_string()
+    };
+    let rain = |item: &Value, key: &str| {
+        let r =
+            item["rain"][key].as_f64().unwrap_or(0.0) + item["snow"][key].as_f64().unwrap_or(0.0);
+        if imperial {
+            r / 25.4
+        } else {
+            r
+        }
+    };
+    let offset = forecast["city"]["timezone"]
+        .as_i64()
+        .or_else(|| current["timezone"].as_i64())
+        .unwrap_or(0);
+
+    let now = current["dt"].as_i64().map(|dt| Current {
+        time: local_time(dt, offset),
+        condition: condition(current),
+        temperature: current["main"]["temp"].as_f64(),
+        feels_like: current["main"]["feels_like"].as_f64(),
+        humidity: current["main"]["humidity"].as_f64(),
+        wind_speed: wind(current["wind"]["speed"].as_f64()),
+        precipitation: Some(rain(current, "1h")),
+    });
+
+    let items: Vec<&Value> = forecast["list"]
+        .as_array()
+        .map(|l| l.iter().collect())
+        .unwrap_or_default();
+    let hourly: Vec<Hour> = items
+        .iter()
+        .filter_map(|item| {
+            Some(Hour {
+                time: local_time(item["dt"].as_i64()?, offset),
+                condition: condition(item),
+                temperature: item["main"]["temp"].as_f64(),
+                precipitation_probability: item["pop"].as_f64().map(|p| (p * 100.0).round()),
+                precipitation: Some(rain(item, "3h")),
+                wind_speed: wind(item["wind"]["speed"].as_f64()),
+            })
+        })
+        .collect();
+
+    let mut days: BTreeMap<String, Vec<(&Value, u32)>> = BTreeMap::new();
+    for item in &items {
+        if let Some(ts) = item["dt"].as_i64() {
+            if let Some(dt) = DateTime::from_timestamp(ts + offset, 0) {
+                days.entry(dt.format("%Y-%m-%d").to_string())
+                    .or_default()
+                    .push((item, dt.hour()));
+            }
+        }
+    }
+    let daily = days
+        .into_iter()
+        .map(|(date, entries)| {
+            let fold = |key: &str, pick: fn(f64, f64) -> f64| {
+                entries
+                    .iter()
+                    .filter_map(|(i, _)| i["main"][key].as_f64())
+                    .reduce(pick)
+            };
+            // The condition closest to midday describes the day best.
+            let midday = entries
+                .iter()
+                .min_by_key(|(_, hour)| (*hour as i32 - 12).abs())
+                .map(|(i, _)| condition(i))
+                .unwrap_or_default();
+            Day {
+                date,
+                condition: midday,
+                temp_min: fold("temp_min", f64::min),
+                temp_max: fold("temp_max", f64::max),
+                precipitation: Some(entries.iter().map(|(i, _)| rain(i, "3h")).sum()),
+                precipitation_probability: entries
+                    .iter()
+                    .filter_map(|(i, _)| i["pop"].as_f64())
+                    .reduce(f64::max)
+                    .map(|p| (p * 100.0).round()),
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    Forecast {
+        provider: Provider::OpenWeather.name().to_string(),
+        current: now,
+        hourly: hourly.into_iter().take(MAX_HOURS).collect(),
+        daily,
+        ..Default::default()
+    }
+}
+
+async fn fetch_openweather(config: &Config, key: &str, loc: &Location) -> Result<Forecast> {
+    let imperial = imperial(&config.tools.weather);
+    let query = vec![
+        ("lat", loc.latitude.to_string()),
+        ("lon", loc.longitude.to_string()),
+        (
+            "units",
+            if imperial { "imperial" } else { "metric" }.to_string(),
+        ),
+        ("appid", key.to_string()),
+    ];
+    let current = get_json(
+        config,
+        &format!("{}/data/2.5/weather", OPENWEATHER_BASE),
+        &query,
+    )
+    .await?;
+    let forecast = get_json(
+        config,
+        &format!("{}/data/2.5/forecast", OPENWEATHER_BASE),
+        &query,
+    )
+    .await?;
+    Ok(parse_openweather(&current, &forecast, imperial))
+}
+
+/// Forecast for `loc`, from the cache when fresh. The flag is true when every provider
+/// failed and an outdated cached forecast is returned instead.
+pub async fn forecast(
+    config: &Config,
+    workspace: &Path,
+    loc: &Location,
+) -> Result<(Forecast, bool)> {
+    let cfg = &config.tools.weather;
+    let key = forecast_key(loc, cfg);
+    let now = Utc::now().timestamp();
+    let cached = {
+        let _guard = CACHE_LOCK.lock().await;
+        load_cache(workspace).forecasts.get(&key).cloned()
+    };
+    if let Some(f) = cached
+        .as_ref()
+        .filter(|f| now - f.fetched_at < cfg.cache_minutes as i64 * 60)
+    {
+        return Ok((f.clone(), false));
+    }
+
+    let api_key = openweather_key(cfg, workspace);
+    let mut errors = Vec::new();
+    for provider in provider_order(cfg, api_key.is_some()) {
+        let result = match (provider, api_key.as_deref()) {
+            (Provider::OpenWeather, Some(k)) => fetch_openweather(config, k, loc).await,
+            _ => fetch_open_meteo(config, loc).await,
+        };
+        match result {
+            Ok(mut fresh) => {
+                fresh.fetched_at = now;
+                fresh.units = units_label(cfg).to_string();
+                info!(provider = provider.name(), location = %loc.name, "🌦 Weather fetched");
+                let _guard = CACHE_LOCK.lock().await;
+                let mut cache = load_cache(workspace);
+                cache.forecasts.insert(key, fresh.clone());
+                if let Err(e) = save_cache(workspace, &mut cache) {
+                    warn!(error = %e, "Failed to cache weather");
+                }
+                return Ok((fresh, false));
+            }
+            Err(e) => {
+                warn!(provider = provider.name(), error = %e, "Weather provider failed");
+                errors.push(format!("{}: {}", provider.name(), e));
+            }
+        }
+    }
+    match cached {
+        Some(f) => Ok((f, true)),
+        None => Err(Error::Tool(errors.join("; "))),
+    }
+}
+
+fn temp_unit(cfg: &WeatherConfig) -> &'static str {
+    if imperial(cfg) {
+        "°F"
+    } else {
+        "°C"
+    }
+}
+
+/// One-line weather summary for the morning digest, or `None` when no default location
+/// is configured or no data could be fetched.
+pub async fn digest_line(config: &Config, workspace: &Path) -> Option<String> {
+    let cfg = &config.tools.weather;
+    let place = cfg.default_location.trim();
+    if place.is_empty() {
+        return None;
+    }
+    let loc = resolve_location(config, workspace, place)
+        .await
+        .map_err(|e| warn!(error = %e, "Weather digest: location lookup failed"))
+        .ok()?;
+    let (forecast, _) = forecast(config, workspace, &loc)
+        .await
+        .map_err(|e| warn!(error = %e, "Weather digest: forecast failed"))
+        .ok()?;
+    let today = forecast.daily.first()?;
+    let unit = temp_unit(cfg);
+    let mut line = format!("今日天气（{}）：{}", loc.name, today.condition);
+    if let (Some(lo), Some(hi)) = (today.temp_min, today.temp_max) {
+        line.push_str(&format!("，{:.0}–{:.0}{}", lo, hi, unit));
+    }
+    if let Some(p) = today.precipitation_probability {
+        line.push_str(&format!("，降水概率 {:.0}%", p));
+    }
+    Some(line)
+}
+
+pub struct WeatherTool;
+
+#[async_trait]
+impl Tool for WeatherTool {
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "weather",
+            description: "Current weather and forecasts (Open-Meteo, optional OpenWeather), cached per location. You MUST provide `action`. action='current': optional `location`. action='forecast': optional `location`, `days` (1-7, default 3), `hours` (hourly steps to include, 0-48, default 0). action='geocode': requires `location`; returns candidate places. `location` is a place name ('Paris, FR') or 'lat,lon'; default tools.weather.defaultLocation.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["current", "forecast", "geocode"],
+                        "description": "Action to perform"
+                    },
+                    "location": {
+                        "type": "string",
+                        "description": "Place name or 'lat,lon'"
+                    },
+                    "days": {
+                        "type": "integer",
+                        "description": "(forecast) Days to return, 1-7. Default: 3"
+                    },
+                    "hours": {
+                        "type": "integer",
+                        "description": "(forecast) Hourly steps to include, 0-48. Default: 0"
+                    }
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    fn validate(&self, params: &Value) -> Result<()> {
+        match params["action"].as_str().unwrap_or("") {
+            "current" | "forecast" => Ok(()),
+            "geocode" => {
+                if params["location"]
+                    .as_str()
+                    .is_none_or(|s| s.trim().is_empty())
+                {
+                    return Err(Error::Validation(
+                        "'location' is required for geocode".into(),
+                    ));
+                }
+                Ok(())
+            }
+            other => Err(Error::Validation(format!(
+                "Unknown action '{}'. Use current, forecast or geocode",
+                other
+            ))),
+        }
+    }
+
+    fn prompt_rule(&self, _ctx: &PromptContext) -> Option<String> {
+        Some(
+            "- For weather questions call `weather` instead of fetching weather websites; if the user gives no place and no default is configured, ask for the city. Name concrete dates rather than only \"today\"/\"tomorrow\"."
+                .to_string(),
+        )
+    }
+
+    async fn execute(&self, ctx: ToolContext, params: Value) -> Result<Value> {
+        let cfg = &ctx.config.tools.weather;
+        let query = params["location"]
+            .as_str()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .unwrap_or(cfg.default_location.trim())
+            .to_string();
+        match params["action"].as_str().unwrap_or("") {
+            "geocode" => {
+                let places = geocode(&ctx.config, &ctx.workspace, &query).await?;
+                Ok(json!({"query": query, "places": places}))
+            }
+            action @ ("current" | "forecast") => {
+                let loc = resolve_location(&ctx.config, &ctx.workspace, &query).await?;
+                let (forecast, stale) = forecast(&ctx.config, &ctx.workspace, &loc).await?;
+                let mut out = json!({
+                    "location": loc.label(),
+                    "latitude": loc.latitude,
+                    "longitude": loc.longitude,
+                    "provider": forecast.provider,
+                    "units": forecast.units,
+                    "fetched_at": DateTime::from_timestamp(forecast.fetched_at, 0)
+                        .map(|d| d.to_rfc3339())
+                        .unwrap_or_default(),
+                    "current": forecast.current,
+                });
+                if action == "forecast" {
+                    let days = params["days"]
+                        .as_u64()
+                        .unwrap_or(3)
+                        .clamp(1, FORECAST_DAYS as u64);
+                    let hours = params["hours"].as_u64().unwrap_or(0).min(MAX_HOURS as u64);
+                    out["daily"] = json!(forecast
+                        .daily
+                        .iter()
+                        .take(days as usize)
+                        .collect::<Vec<_>>());
+                    if hours > 0 {
+                        out["hourly"] = json!(forecast
+                            .hourly
+                            .iter()
+                            .take(hours as usize)
+                            .collect::<Vec<_>>());
+                    }
+                } else if let Some(today) = forecast.daily.first() {
+                    out["today"] = json!(today);
+                }
+                if stale {
+                    out["stale"] = json!(true);
+                }
+                Ok(out)
+            }
+            other => Err(Error::Tool(format!("Unknown action: {}", other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_open_meteo() {
+        let body = json!({
+            "current": {"time": "2026-10-15T13:15", "temperature_2m": 12.4, "weather_code": 61,
+                        "apparent_temperature": 10.9, "relative_humidity_2m": 81, "wind_speed_10m": 14.2},
+            "hourly": {
+                "time": ["2026-10-15T12:00", "2026-10-15T13:00", "2026-10-15T14:00"],
+                "temperature_2m": [12.0, 12.4, 13.1],
+                "precipitation_probability": [40, 55, 60],
+                "weather_code": [3, 61, 63]
+            },
+            "daily": {
+                "time": ["2026-10-15", "2026-10-16"],
+                "weather_code": [61, 0],
+                "temperature_2m_min": [8.1, 6.0],
+                "temperature_2m_max": [14.3, 15.2],
+                "precipitation_probability_max": [70, 5],
+                "sunrise": ["2026-10-15T07:31", "2026-10-16T07:33"]
+            }
+        });
+        let f = parse_open_meteo(&body);
+        let current = f.current.unwrap();
+        assert_eq!(current.condition, "light rain");
+        assert_eq!(current.temperature, Some(12.4));
+        // The 12:00 slot is already over.
+        assert_eq!(f.hourly.len(), 2);
+        assert_eq!(f.hourly[0].time, "2026-10-15T13:00");
+        assert_eq!(f.hourly[1].condition, "rain");
+        assert_eq!(f.daily[1].condition, "clear sky");
+        assert_eq!(f.daily[0].precipitation_probability, Some(70.0));
+        assert_eq!(f.daily[0].sunrise.as_deref(), Some("2026-10-15T07:31"));
+    }
+
+    #[test]
+    fn test_parse_openweather_aggregates_days() {
+        let current = json!({"dt": 1_792_000_000, "timezone": 7200,
+            "main": {"temp": 11.0, "feels_like": 9.5, "humidity": 70},
+            "wind": {"speed": 5.0}, "weather": [{"description": "broken clouds"}]});
+        let item = |dt: i64, lo: f64, hi: f64, pop: f64, rain: f64, desc: &str| {
+            json!({"dt": dt, "main": {"temp": (lo + hi) / 2.0, "temp_min": lo, "temp_max": hi},
+                   "pop": pop, "rain": {"3h": rain}, "wind": {"speed": 2.0},
+                   "weather": [{"description": desc}]})
+        };
+        // 2026-10-15 10:00Z = 12:00 local at +02:00.
+        let base = 1_792_058_400;
+        let forecast = json!({"city": {"timezone": 7200}, "list": [
+            item(base - 3 * 3600, 7.0, 9.0, 0.1, 0.0, "fog"),
+            item(base, 10.0, 13.0, 0.6, 1.5, "light rain"),
+            item(base + 3 * 3600, 11.0, 14.0, 0.3, 0.5, "overcast clouds"),
+        ]});
+        let f = parse_openweather(&current, &forecast, false);
+        assert_eq!(f.current.as_ref().unwrap().wind_speed, Some(18.0));
+        assert_eq!(f.daily.len(), 1);
+        let day = &f.daily[0];
+        assert_eq!(day.date, "2026-10-15");
+        assert_eq!(day.condition, "light rain");
+        assert_eq!((day.temp_min, day.temp_max), (Some(7.0), Some(14.0)));
+        assert_eq!(day.precipitation, Some(2.0));
+        assert_eq!(day.precipitation_probability, Some(60.0));
+    }
+
+    #[test]
+    fn test_coordinates_and_provider_order() {
+        let loc = parse_coordinates("52.52, 13.405").unwrap();
+        assert_eq!((loc.latitude, loc.longitude), (52.52, 13.405));
+        assert!(parse_coordinates("Berlin").is_none());
+        assert!(parse_coordinates("95,10").is_none());
+
+        let mut cfg = WeatherConfig::default();
+        assert_eq!(
+            provider_order(&cfg, true),
+            vec![Provider::OpenMeteo, Provider::OpenWeather]
+        );
+        cfg.provider = "openweather".into();
+        assert_eq!(
+            provider_order(&cfg, true),
+            vec![Provider::OpenWeather, Provider::OpenMeteo]
+        );
+        assert_eq!(provider_order(&cfg, false), vec![Provider::OpenMeteo]);
+    }
+
+    fn test_workspace() -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("blockcell-weather-{}", uuid::Uuid::new_v4()))
+            .join("workspace")
+    }
+
+    /// Config whose egress policy blocks every request, so providers always fail.
+    fn offline_config() -> Config {
+        let mut config = Config::default();
+        config.tools.egress.enabled = true;
+        config.tools.egress.default_action = "deny".into();
+        config
+    }
+
+    #[tokio::test]
+    async fn test_forecast_cache_expiry() {
+        let workspace = test_workspace();
+        let mut config = offline_config();
+        let loc = parse_coordinates("52.52,13.40").unwrap();
+        let key = forecast_key(&loc, &config.tools.weather);
+        let now = Utc::now().timestamp();
+        let mut cache = Cache::default();
+        cache.forecasts.insert(
+            key.clone(),
+            Forecast {
+                provider: "open-meteo".into(),
+                fetched_at: now - 10 * 60,
+                ..Default::default()
+            },
+        );
+        cache.forecasts.insert(
+            "0.000,0.000:metric".into(),
+            Forecast {
+                fetched_at: now - CACHE_RETENTION_SECS - 60,
+                ..Default::default()
+            },
+        );
+        save_cache(&workspace, &mut cache).unwrap();
+        // Forecasts past the retention window are dropped when the cache is written.
+        assert_eq!(
+            load_cache(&workspace).forecasts.keys().collect::<Vec<_>>(),
+            vec![&key]
+        );
+
+        // Within cacheMinutes the cached forecast is served without a request.
+        config.tools.weather.cache_minutes = 30;
+        let (cached, stale) = forecast(&config, &workspace, &loc).await.unwrap();
+        assert_eq!(cached.fetched_at, now - 10 * 60);
+        assert!(!stale);
+
+        // Expired: the providers are asked again and, all failing, the old forecast is
+        // returned marked stale.
+        config.tools.weather.cache_minutes = 5;
+        let (old, stale) = forecast(&config, &workspace, &loc).await.unwrap();
+        assert_eq!(old.provider, "open-meteo");
+        assert!(stale);
+
+        // Nothing cached: the provider errors come back.
+        let elsewhere = parse_coordinates("48.85,2.35").unwrap();
+        let err = forecast(&config, &workspace, &elsewhere)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("open-meteo"), "{}", err);
+
+        let _ = std::fs::remove_dir_all(workspace.parent().unwrap());
+    }
+
+    #[test]
+    fn test_units() {
+        let mut cfg = WeatherConfig::default();
+        let loc = parse_coordinates("52.52,13.40").unwrap();
+        assert_eq!(units_label(&cfg), "°C, km/h, mm");
+        assert_eq!(temp_unit(&cfg), "°C");
+        let metric_key = forecast_key(&loc, &cfg);
+        assert_eq!(metric_key, "52.520,13.400:metric");
+
+        cfg.units = " Imperial ".into();
+        assert_eq!(units_label(&cfg), "°F, mph, inch");
+        assert_eq!(temp_unit(&cfg), "°F");
+        // Units are part of the cache key, so switching them never serves stale units.
+        assert_eq!(forecast_key(&loc, &cfg), "52.520,13.400:imperial");
+
+        // OpenWeather reports metric wind in m/s and rain in mm; both are normalized.
+        let current = json!({"dt": 1_792_000_000, "main": {"temp": 10.0},
+            "wind": {"speed": 10.0}, "rain": {"1h": 25.4},
+            "weather": [{"description": "rain"}]});
+        let forecast = json!({"list": []});
+        let metric = parse_openweather(&current, &forecast, false)
+            .current
+            .unwrap();
+        assert_eq!(metric.wind_speed, Some(36.0));
+        assert_eq!(metric.precipitation, Some(25.4));
+        let imperial = parse_openweather(&current, &forecast, true)
+            .current
+            .unwrap();
+        assert_eq!(imperial.wind_speed, Some(10.0));
+        assert_eq!(imperial.precipitation, Some(1.0));
+    }
+
+    #[test]
+    fn test_open_meteo_error_response() {
+        let err = open_meteo_forecast(&json!({
+            "error": true,
+            "reason": "Latitude must be in range of -90 to 90°."
+        }))
+        .unwrap_err();
+        assert!(err.to_string().contains("Latitude must be in range"));
+        assert!(open_meteo_forecast(&json!({}))
+            .unwrap_err()
+            .to_string()
+            .contains("unknown reason"));
+        let f = open_meteo_forecast(&json!({"daily": {"time": ["2026-10-15"]}})).unwrap();
+        assert_eq!(f.daily.len(), 1);
+        assert!(f.current.is_none());
+    }
+}
@@ -1,8 +1,9 @@
 use async_trait::async_trait;
+use blockcell_core::config::WebSearchConfig;
 use blockcell_core::{Error, Result};
-use reqwest::Client;
 use serde_json::{json, Value};
 
+use crate::egress::HttpClientFactory;
 use crate::{Tool, ToolContext, ToolSchema};
 
 // ============ web_search ============
@@ -14,7 +15,7 @@ impl Tool for WebSearchTool {
     fn schema(&self) -> ToolSchema {
         ToolSchema {
             name: "web_search",
-            description: "Search the web. REQUIRED: always provide a non-empty string parameter `query`; do not call this tool with `{}`. Optional parameters: `count` (number of results) and `freshness` (time filter). Supports two backends: Brave Search API (`api_key`) and Baidu AI Search API (`baidu_api_key`). For Chinese queries, Baidu is preferred. Configure keys in `tools.web.search` or env var `BAIDU_API_KEY`.",
+            description: "Search the web. REQUIRED: always provide a non-empty string parameter `query`; do not call this tool with `{}`. Optional parameters: `count` (number of results), `freshness` (time filter) and `engine` (force a backend). Supports four backends: SearXNG (self-hosted, `searxng_url`), Brave Search API (`api_key`), Bing Web Search API (`bing_api_key`) and Baidu AI Search API (`baidu_api_key`). Engines are tried in order with automatic fallback; for Chinese queries, Baidu is preferred. Every result has the same shape: `title`, `url`, `snippet`, `published`. Configure engines in `tools.web.search` or env vars `SEARXNG_URL` / `BING_API_KEY` / `BAIDU_API_KEY`.",
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -28,8 +29,13 @@ impl Tool for WebSearchTool {
                     },
                     "freshness": {
                         "type": "string",
-                        "description": "Recency filter. Brave: day/week/month/year. Baidu: week/month/semiyear/year.",
+                        "description": "Recency filter: day/week/month/semiyear/year. Mapped to the closest value each engine supports.",
                         "enum": ["day", "week", "month", "semiyear", "year"]
+                    },
+                    "engine": {
+                        "type": "string",
+                        "description": "Engine to try first (default auto). Other configured engines are still used as fallback.",
+                        "enum": ["auto", "searxng", "brave", "bing", "baidu"]
                     }
                 },
                 "required": ["query"]
@@ -38,7 +44,7 @@ impl Tool for WebSearchTool {
     }
 
     fn prompt_rule(&self, _ctx: &crate::PromptContext) -> Option<String> {
-        Some("- **Web search**: Use `web_search` for discovery. Supports Brave Search API and Baidu AI Search API. Chinese queries prefer Baidu; non-Chinese prefer Brave. For 'latest/最近/24小时/今天' news queries, set `freshness=day`. **If `web_search` returns a config/API-key error, you MUST tell the user to configure the API key** (tools.web.search.apiKey for Brave, tools.web.search.baiduApiKey or env BAIDU_API_KEY for Baidu) — do NOT answer from memory as if search succeeded. **If results are irrelevant**: retry with rephrased query (shorter, different keywords) before concluding no results exist — never give up after just one failed search.".to_string())
+        Some("- **Web search**: Use `web_search` for discovery. Supports SearXNG, Brave Search API, Bing Web Search API and Baidu AI Search API with automatic fallback; leave `engine` unset unless the user asks for a specific engine. Chinese queries prefer Baidu; non-Chinese prefer Brave. For 'latest/最近/24小时/今天' news queries, set `freshness=day`. **If `web_search` returns a config/API-key error, you MUST tell the user to configure a search engine** (tools.web.search.searxngUrl for SearXNG, tools.web.search.apiKey for Brave, tools.web.search.bingApiKey for Bing, tools.web.search.baiduApiKey or env BAIDU_API_KEY for Baidu) — do NOT answer from memory as if search succeeded. **If results are irrelevant**: retry with rephrased query (shorter, different keywords) before concluding no results exist — never give up after just one failed search.".to_string())
     }
 
     fn validate(&self, params: &Value) -> Result<()> {
@@ -47,6 +53,14 @@ impl Tool for WebSearchTool {
                 "Missing required parameter: query".to_string(),
             ));
         }
+        if let Some(engine) = params.get("engine").and_then(|v| v.as_str()) {
+            if engine != "auto" && SearchEngine::parse(engine).is_none() {
+                return Err(Error::Validation(format!(
+                    "Unknown search engine '{}'. Use auto, searxng, brave, bing or baidu.",
+                    engine
+                )));
+            }
+        }
         Ok(())
     }
 
@@ -63,19 +77,13 @@ impl Tool for WebSearchTool {
             .and_then(|v| v.as_str())
             .map(|s| s.to_string());
 
-        // Resolve Baidu API key: config > env
-        let baidu_key = {
-            let k = &ctx.config.tools.web.search.baidu_api_key;
-            if !k.is_empty() {
-                Some(k.clone())
-            } else {
-                std::env::var("BAIDU_API_KEY")
-                    .ok()
-                    .filter(|v| !v.is_empty())
-            }
-        };
+        let requested = params
+            .get("engine")
+            .and_then(|v| v.as_str())
+            .and_then(SearchEngine::parse);
 
-        let brave_key = &ctx.config.tools.web.search.api_key;
+        let search_cfg = &ctx.config.tools.web.search;
+        let creds = EngineCredentials::from_config(search_cfg);
 
         // Detect if query is primarily Chinese
         let is_chinese = query.chars().any(|c| {
@@ -83,91 +91,334 @@ impl Tool for WebSearchTool {
             (0x4E00..=0x9FFF).contains(&cp)
         });
 
-        // Strategy: Chinese → Baidu first, then Brave; non-Chinese → Brave first, then Baidu
-        if is_chinese {
-            // 1. Try Baidu API
-            if let Some(ref key) = baidu_key {
-                match baidu_search(key, query, count, freshness.as_deref()).await {
-                    Ok(results) if !results.is_empty() => {
-                        return Ok(
-                            json!({ "query": query, "results": results, "source": "baidu" }),
-                        );
-                    }
-                    Ok(_) => tracing::warn!("Baidu API returned empty results, trying Brave"),
-                    Err(e) => tracing::warn!(error = %e, "Baidu API failed, trying Brave"),
-                }
+        if let Some(engine) = requested {
+            if !creds.is_configured(engine) {
+                tracing::warn!(
+                    engine = engine.name(),
+                    "Requested search engine is not configured, using fallback chain"
+                );
             }
-            // 2. Fallback to Brave
-            if !brave_key.is_empty() {
-                match brave_search(brave_key, query, count, freshness.as_deref()).await {
-                    Ok(results) => {
-                        return Ok(json!({ "query": query, "results": results, "source": "brave" }))
+        }
+
+        let order: Vec<SearchEngine> = engine_order(&search_cfg.engines, requested, is_chinese)
+            .into_iter()
+            .filter(|e| creds.is_configured(*e))
+            .collect();
+
+        if order.is_empty() {
+            return Err(Error::Tool(
+                "No search API configured. Set tools.web.search.searxng_url (SearXNG), tools.web.search.api_key (Brave), tools.web.search.bing_api_key (Bing) or tools.web.search.baidu_api_key / env BAIDU_API_KEY (Baidu).".to_string()
+            ));
+        }
+
+        let egress = HttpClientFactory::for_tool(&ctx.config, "web_search");
+        // Try engines in order; the first non-empty result set wins.
+        let mut attempts: Vec<Value> = Vec::new();
+        let mut empty_from: Option<SearchEngine> = None;
+        for engine in order {
+            match run_engine(engine, &creds, &egress, query, count, freshness.as_deref()).await {
+                Ok(results) if !results.is_empty() => {
+                    let mut out = json!({
+                        "query": query,
+                        "results": results,
+                        "source": engine.name(),
+                    });
+                    if !attempts.is_empty() {
+                        out["fallback_from"] = json!(attempts);
                     }
-                    Err(e) => tracing::warn!(error = %e, "Brave search also failed"),
+                    return Ok(out);
                 }
-            }
-        } else {
-            // 1. Try Brave
-            if !brave_key.is_empty() {
-                match brave_search(brave_key, query, count, freshness.as_deref()).await {
-                    Ok(results) if !results.is_empty() => {
-                        return Ok(
-                            json!({ "query": query, "results": results, "source": "brave" }),
-                        );
-                    }
-                    Ok(_) => tracing::warn!("Brave returned empty results, trying Baidu"),
-                    Err(e) => tracing::warn!(error = %e, "Brave search failed, trying Baidu"),
+                Ok(_) => {
+                    tracing::warn!(
+                        engine = engine.name(),
+                        "Search engine returned empty results"
+                    );
+                    empty_from.get_or_insert(engine);
+                    attempts.push(json!({ "engine": engine.name(), "error": "empty results" }));
                 }
-            }
-            // 2. Fallback to Baidu
-            if let Some(ref key) = baidu_key {
-                match baidu_search(key, query, count, freshness.as_deref()).await {
-                    Ok(results) if !results.is_empty() => {
-                        return Ok(
-                            json!({ "query": query, "results": results, "source": "baidu" }),
-                        );
-                    }
-                    Ok(_) => tracing::warn!("Baidu API returned empty results"),
-                    Err(e) => tracing::warn!(error = %e, "Baidu API also failed"),
+                Err(e) => {
+                    tracing::warn!(engine = engine.name(), error = %e, "Search engine failed");
+                    attempts.push(json!({ "engine": engine.name(), "error": e.to_string() }));
                 }
             }
         }
 
-        // If we get here, all attempts failed or no keys configured
-        if brave_key.is_empty() && baidu_key.is_none() {
-            return Err(Error::Tool(
-                "No search API configured. Set tools.web.search.api_key (Brave) or tools.web.search.baidu_api_key / env BAIDU_API_KEY (Baidu).".to_string()
-            ));
+        // At least one engine answered, just with nothing: report an empty result set.
+        if let Some(engine) = empty_from {
+            return Ok(json!({
+                "query": query,
+                "results": [],
+                "source": engine.name(),
+                "fallback_from": attempts,
+            }));
         }
 
         Err(Error::Tool(format!(
-            "All search backends failed for query '{}'. Check API keys and network connectivity.",
-            query
+            "All search backends failed for query '{}'. Check API keys and network connectivity. Attempts: {}",
+            query,
+            Value::Array(attempts)
         )))
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SearchEngine {
+    Searxng,
+    Brave,
+    Bing,
+    Baidu,
+}
+
+impl SearchEngine {
+    fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "searxng" | "searx" => Some(Self::Searxng),
+            "brave" => Some(Self::Brave),
+            "bing" => Some(Self::Bing),
+            "baidu" => Some(Self::Baidu),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Searxng => "searxng",
+            Self::Brave => "brave",
+            Self::Bing => "bing",
+            Self::Baidu => "baidu",
+        }
+    }
+}
+
+/// Per-engine credentials resolved from config, with env var fallbacks.
+struct EngineCredentials {
+    searxng_url: Option<String>,
+    brave_key: Option<String>,
+    bing_key: Option<String>,
+    baidu_key: Option<String>,
+}
+
+impl EngineCredentials {
+    fn from_config(cfg: &WebSearchConfig) -> Self {
+        fn resolve(value: &str, env: Option<&str>) -> Option<String> {
+            let v = value.trim();
+            if !v.is_empty() {
+                return Some(v.to_string());
+            }
+            env.and_then(|name| std::env::var(name).ok())
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        }
+
+        Self {
+            searxng_url: resolve(&cfg.searxng_url, Some("SEARXNG_URL")),
+            brave_key: resolve(&cfg.api_key, None),
+            bing_key: resolve(&cfg.bing_api_key, Some("BING_API_KEY")),
+            baidu_key: resolve(&cfg.baidu_api_key, Some("BAIDU_API_KEY")),
+        }
+    }
+
+    fn is_configured(&self, engine: SearchEngine) -> bool {
+        match engine {
+            SearchEngine::Searxng => self.searxng_url.is_some(),
+            SearchEngine::Brave => self.brave_key.is_some(),
+            SearchEngine::Bing => self.bing_key.is_some(),
+            SearchEngine::Baidu => self.baidu_key.is_some(),
+        }
+    }
+}
+
+/// Build the engine fallback chain.
+///
+/// `configured` is `tools.web.search.engines`; when empty the default order is used
+/// (Chinese → Baidu first, otherwise Brave first). A `requested` engine always goes first.
+fn engine_order(
+    configured: &[String],
+    requested: Option<SearchEngine>,
+    is_chinese: bool,
+) -> Vec<SearchEngine> {
+    let mut base: Vec<SearchEngine> = configured
+        .iter()
+        .filter_map(|s| SearchEngine::parse(s))
+        .collect();
+    if base.is_empty() {
+        base = if is_chinese {
+            vec![
+                SearchEngine::Baidu,
+                SearchEngine::Searxng,
+                SearchEngine::Brave,
+                SearchEngine::Bing,
+            ]
+        } else {
+            vec![
+                SearchEngine::Brave,
+                SearchEngine::Searxng,
+                SearchEngine::Bing,
+                SearchEngine::Baidu,
+            ]
+        };
+    }
+
+    let mut order = Vec::with_capacity(base.len() + 1);
+    for engine in requested.into_iter().chain(base) {
+        if !order.contains(&engine) {
+            order.push(engine);
+        }
+    }
+    order
+}
+
+async fn run_engine(
+    engine: SearchEngine,
+    creds: &EngineCredentials,
+    egress: &HttpClientFactory,
+    query: &str,
+    count: usize,
+    freshness: Option<&str>,
+) -> Result<Vec<Value>> {
+    let missing = || Error::Tool(format!("{} search is not configured", engine.name()));
+    match engine {
+        SearchEngine::Searxng => {
+            let url = creds.searxng_url.as_deref().ok_or_else(missing)?;
+            searxng_search(egress, url, query, count, freshness).await
+        }
+        SearchEngine::Brave => {
+            let key = creds.brave_key.as_deref().ok_or_else(missing)?;
+            brave_search(egress, key, query, count, freshness).await
+        }
+        SearchEngine::Bing => {
+            let key = creds.bing_key.as_deref().ok_or_else(missing)?;
+            bing_search(egress, key, query, count, freshness).await
+        }
+        SearchEngine::Baidu => {
+            let key = creds.baidu_key.as_deref().ok_or_else(missing)?;
+            baidu_search(egress, key, query, count, freshness).await
+        }
+    }
+}
+
+/// Unified result shape shared by every engine.
+fn search_result(title: &str, url: &str, snippet: &str, published: &str) -> Value {
+    json!({
+        "title": title.trim(),
+        "url": url.trim(),
+        "snippet": snippet.trim(),
+        "published": published.trim(),
+    })
+}
+
+fn has_title_and_url(r: &Value) -> bool {
+    let url = r["url"].as_str().unwrap_or("");
+    let title = r["title"].as_str().unwrap_or("");
+    !url.is_empty() && !title.is_empty()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// SearXNG (self-hosted, JSON output must be enabled in settings.yml)
+// ─────────────────────────────────────────────────────────────────────────────
+
+async fn searxng_search(
+    egress: &HttpClientFactory,
+    base_url: &str,
+    query: &str,
+    count: usize,
+    freshness: Option<&str>,
+) -> Result<Vec<Value>> {
+    let endpoint = format!("{}/search", base_url.trim_end_matches('/'));
+    egress.check(&endpoint).await?;
+    let client = egress
+        .builder()
+        .timeout(std::time::Duration::from_secs(15))
+        .build()
+        .map_err(|e| Error::Tool(format!("HTTP client error: {}", e)))?;
+
+    let mut req = client
+        .get(&endpoint)
+        .header("Accept", "application/json")
+        .query(&[("q", query), ("format", "json")]);
+
+    if let Some(range) = freshness.and_then(searxng_time_range) {
+        req = req.query(&[("time_range", range)]);
+    }
+
+    let response = req
+        .send()
+        .await
+        .map_err(|e| Error::Tool(format!("SearXNG request failed: {}", e)))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        return Err(Error::Tool(format!(
+            "SearXNG error {} (is `json` listed in search.formats?): {}",
+            status, text
+        )));
+    }
+
+    let data: Value = response
+        .json()
+        .await
+        .map_err(|e| Error::Tool(format!("Failed to parse SearXNG response: {}", e)))?;
+
+    Ok(parse_searxng_results(&data, count))
+}
+
+fn searxng_time_range(freshness: &str) -> Option<&'static str> {
+    match freshness {
+        "day" => Some("day"),
+        "week" => Some("week"),
+        "month" => Some("month"),
+        "semiyear" | "year" => Some("year"),
+        _ => None,
+    }
+}
+
+fn parse_searxng_results(data: &Value, count: usize) -> Vec<Value> {
+    data["results"]
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .map(|r| {
+                    search_result(
+                        r["title"].as_str().unwrap_or(""),
+                        r["url"].as_str().unwrap_or(""),
+                        r["content"].as_str().unwrap_or(""),
+                        r["publishedDate"].as_str().unwrap_or(""),
+                    )
+                })
+                .filter(has_title_and_url)
+                .take(count)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Brave Search API
 // ─────────────────────────────────────────────────────────────────────────────
 
 async fn brave_search(
+    egress: &HttpClientFactory,
     api_key: &str,
     query: &str,
     count: usize,
     freshness: Option<&str>,
 ) -> Result<Vec<Value>> {
-    let client = Client::builder()
+    const ENDPOINT: &str = "https://api.search.brave.com/res/v1/web/search";
+    egress.check(ENDPOINT).await?;
+    let client = egress
+        .builder()
         .timeout(std::time::Duration::from_secs(15))
         .build()
         .map_err(|e| Error::Tool(format!("HTTP client error: {}", e)))?;
 
     let mut req = client
-        .get("https://api.search.brave.com/res/v1/web/search")
+        .get(ENDPOINT)
         .header("X-Subscription-Token", api_key)
         .query(&[("q", query), ("count", &count.to_string())]);
 
-    if let Some(f) = freshness {
+    if let Some(f) = freshness.and_then(brave_freshness) {
         req = req.query(&[("freshness", f)]);
     }
 
@@ -187,20 +438,123 @@ async fn brave_search(
         .await
         .map_err(|e| Error::Tool(format!("Failed to parse Brave response: {}", e)))?;
 
-    let results: Vec<Value> = data["web"]["results"]
+    Ok(parse_brave_results(&data, count))
+}
+
+fn brave_freshness(freshness: &str) -> Option<&'static str> {
+    match freshness {
+        "day" => Some("pd"),
+        "week" => Some("pw"),
+        "month" => Some("pm"),
+        "semiyear" | "year" => Some("py"),
+        _ => None,
+    }
+}
+
+fn parse_brave_results(data: &Value, count: usize) -> Vec<Value> {
+    data["web"]["results"]
         .as_array()
-        .unwrap_or(&vec![])
-        .iter()
-        .map(|r| {
-            json!({
-                "title": r["title"],
-                "url": r["url"],
-                "snippet": r["description"]
-            })
+        .map(|items| {
+            items
+                .iter()
+                .map(|r| {
+                    search_result(
+                        r["title"].as_str().unwrap_or(""),
+                        r["url"].as_str().unwrap_or(""),
+                        r["description"].as_str().unwrap_or(""),
+                        r["page_age"]
+                            .as_str()
+                            .or_else(|| r["age"].as_str())
+                            .unwrap_or(""),
+                    )
+                })
+                .filter(has_title_and_url)
+                .take(count)
+                .collect()
         })
-        .collect();
+        .unwrap_or_default()
+}
 
-    Ok(results)
+// ─────────────────────────────────────────────────────────────────────────────
+// Bing Web Search API v7
+// ─────────────────────────────────────────────────────────────────────────────
+
+async fn bing_search(
+    egress: &HttpClientFactory,
+    api_key: &str,
+    query: &str,
+    count: usize,
+    freshness: Option<&str>,
+) -> Result<Vec<Value>> {
+    const ENDPOINT: &str = "https://api.bing.microsoft.com/v7.0/search";
+    egress.check(ENDPOINT).await?;
+    let client = egress
+        .builder()
+        .timeout(std::time::Duration::from_secs(15))
+        .build()
+        .map_err(|e| Error::Tool(format!("HTTP client error: {}", e)))?;
+
+    let mut req = client
+        .get(ENDPOINT)
+        .header("Ocp-Apim-Subscription-Key", api_key)
+        .query(&[
+            ("q", query),
+            ("count", &count.to_string()),
+            ("responseFilter", "Webpages"),
+        ]);
+
+    if let Some(f) = freshness.and_then(bing_freshness) {
+        req = req.query(&[("freshness", f)]);
+    }
+
+    let response = req
+        .send()
+        .await
+        .map_err(|e| Error::Tool(format!("Bing search request failed: {}", e)))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        return Err(Error::Tool(format!("Bing API error {}: {}", status, text)));
+    }
+
+    let data: Value = response
+        .json()
+        .await
+        .map_err(|e| Error::Tool(format!("Failed to parse Bing response: {}", e)))?;
+
+    Ok(parse_bing_results(&data, count))
+}
+
+/// Bing only supports Day/Week/Month; wider windows are left unfiltered.
+fn bing_freshness(freshness: &str) -> Option<&'static str> {
+    match freshness {
+        "day" => Some("Day"),
+        "week" => Some("Week"),
+        "month" => Some("Month"),
+        _ => None,
+    }
+}
+
+fn parse_bing_results(data: &Value, count: usize) -> Vec<Value> {
+    data["webPages"]["value"]
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .map(|r| {
+                    search_result(
+                        r["name"].as_str().unwrap_or(""),
+                        r["url"].as_str().unwrap_or(""),
+                        r["snippet"].as_str().unwrap_or(""),
+                        r["datePublished"].as_str().unwrap_or(""),
+                    )
+                })
+                .filter(has_title_and_url)
+                .take(count)
+                .collect()
+        })
+        .unwrap_or_default()
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -208,12 +562,16 @@ async fn brave_search(
 // ─────────────────────────────────────────────────────────────────────────────
 
 async fn baidu_search(
+    egress: &HttpClientFactory,
     api_key: &str,
     query: &str,
     count: usize,
     freshness: Option<&str>,
 ) -> Result<Vec<Value>> {
-    let client = Client::builder()
+    const ENDPOINT: &str = "https://qianfan.baidubce.com/v2/ai_search/web_search";
+    egress.check(ENDPOINT).await?;
+    let client = egress
+        .builder()
         .timeout(std::time::Duration::from_secs(20))
         .build()
         .map_err(|e| Error::Tool(format!("HTTP client error: {}", e)))?;
@@ -241,7 +599,7 @@ async fn baidu_search(
     }
 
     let response = client
-        .post("https://qianfan.baidubce.com/v2/ai_search/web_search")
+        .post(ENDPOINT)
         .header("Authorization", format!("Bearer {}", api_key))
         .header("Content-Type", "application/json")
         .json(&body)
@@ -273,21 +631,21 @@ async fn baidu_search(
 
     let results: Vec<Value> = references
         .iter()
-        .take(count)
         .map(|r| {
-            json!({
-                "title": r["title"].as_str().unwrap_or(""),
-                "url": r["url"].as_str().unwrap_or(""),
-                "snippet": r["content"].as_str().or_else(|| r["abstract"].as_str()).unwrap_or(""),
-                "site_name": r["site_name"].as_str().unwrap_or(""),
-                "publish_time": r["publish_time"].as_str().unwrap_or("")
-            })
-        })
-        .filter(|r| {
-            let url = r["url"].as_str().unwrap_or("");
-            let title = r["title"].as_str().unwrap_or("");
-            !url.is_empty() && !title.is_empty()
+            let mut item = search_result(
+                r["title"].as_str().unwrap_or(""),
+                r["url"].as_str().unwrap_or(""),
+                r["content"]
+                    .as_str()
+                    .or_else(|| r["abstract"].as_str())
+                    .unwrap_or(""),
+                r["publish_time"].as_str().unwrap_or(""),
+            );
+            item["site_name"] = json!(r["site_name"].as_str().unwrap_or(""));
+            item
         })
+        .filter(has_title_and_url)
+        .take(count)
         .collect();
 
     tracing::debug!(count = results.len(), query, "Baidu API results");
@@ -357,10 +715,13 @@ impl Tool for WebFetchTool {
             .and_then(|v| v.as_u64())
             .unwrap_or(50000) as usize;
 
+        let egress = HttpClientFactory::for_tool(&ctx.config, "web_fetch");
+        egress.check(url).await?;
+
         match extract_mode {
-            "raw" => fetch_raw(url, max_chars).await,
-            "text" => fetch_text(url, max_chars).await,
-            _ => fetch_markdown(url, max_chars, Some(&ctx.workspace)).await,
+            "raw" => fetch_raw(&egress, url, max_chars).await,
+            "text" => fetch_text(&egress, url, max_chars).await,
+            _ => fetch_markdown(&egress, url, max_chars, Some(&ctx.workspace)).await,
         }
     }
 }
@@ -380,11 +741,12 @@ fn html_looks_like_challenge(body: &str) -> bool {
 /// Fetch with markdown content negotiation (default mode).
 /// Falls back to CDP browser fetch if the response looks like a JS challenge page.
 async fn fetch_markdown(
+    egress: &HttpClientFactory,
     url: &str,
     max_chars: usize,
     workspace: Option<&std::path::Path>,
 ) -> Result<Value> {
-    let (content, meta) = crate::html_to_md::fetch_as_markdown(url, max_chars).await?;
+    let (content, meta) = crate::html_to_md::fetch_as_markdown(egress, url, max_chars).await?;
 
     // If the result looks like a JS challenge page, try CDP.
     let is_challenge =
@@ -518,9 +880,9 @@ async fn fetch_via_cdp(url: &str, max_chars: usize, workspace: &std::path::Path)
 }
 
 /// Fetch and extract plain text (strip all formatting).
-async fn fetch_text(url: &str, max_chars: usize) -> Result<Value> {
-    let client = Client::builder()
-        .redirect(reqwest::redirect::Policy::limited(10))
+async fn fetch_text(egress: &HttpClientFactory, url: &str, max_chars: usize) -> Result<Value> {
+    let client = egress
+        .builder()
         .timeout(std::time::Duration::from_secs(30))
         .build()
         .map_err(|e| Error::Tool(format!("Failed to create HTTP client: {}", e)))?;
@@ -577,9 +939,9 @@ async fn fetch_text(url: &str, max_chars: usize) -> Result<Value> {
 }
 
 /// Fetch raw response body without conversion.
-async fn fetch_raw(url: &str, max_chars: usize) -> Result<Value> {
-    let client = Client::builder()
-        .redirect(reqwest::redirect::Policy::limited(10))
+async fn fetch_raw(egress: &HttpClientFactory, url: &str, max_chars: usize) -> Result<Value> {
+    let client = egress
+        .builder()
         .timeout(std::time::Duration::from_secs(30))
         .build()
         .map_err(|e| Error::Tool(format!("Failed to create HTTP client: {}", e)))?;
@@ -630,7 +992,7 @@ async fn fetch_raw(url: &str, max_chars: usize) -> Result<Value> {
     }))
 }
 
-fn extract_text_from_html(html: &str) -> String {
+pub(crate) fn extract_text_from_html(html: &str) -> String {
     use scraper::{Html, Selector};
 
     let document = Html::parse_document(html);
@@ -685,6 +1047,116 @@ mod tests {
         assert!(tool.validate(&json!({})).is_err());
     }
 
+    #[test]
+    fn test_web_search_validate_engine() {
+        let tool = WebSearchTool;
+        assert!(tool
+            .validate(&json!({"query": "rust", "engine": "searxng"}))
+            .is_ok());
+        assert!(tool
+            .validate(&json!({"query": "rust", "engine": "auto"}))
+            .is_ok());
+        assert!(tool
+            .validate(&json!({"query": "rust", "engine": "yahoo"}))
+            .is_err());
+    }
+
+    #[test]
+    fn test_engine_order_defaults() {
+        assert_eq!(
+            engine_order(&[], None, false),
+            vec![
+                SearchEngine::Brave,
+                SearchEngine::Searxng,
+                SearchEngine::Bing,
+                SearchEngine::Baidu
+            ]
+        );
+        assert_eq!(engine_order(&[], None, true)[0], SearchEngine::Baidu);
+    }
+
+    #[test]
+    fn test_engine_order_configured_and_requested() {
+        let configured = vec![
+            "searxng".to_string(),
+            "bogus".to_string(),
+            "bing".to_string(),
+        ];
+        assert_eq!(
+            engine_order(&configured, None, true),
+            vec![SearchEngine::Searxng, SearchEngine::Bing]
+        );
+        assert_eq!(
+            engine_order(&configured, Some(SearchEngine::Bing), false),
+            vec![SearchEngine::Bing, SearchEngine::Searxng]
+        );
+        assert_eq!(
+            engine_order(&configured, Some(SearchEngine::Brave), false),
+            vec![
+                SearchEngine::Brave,
+                SearchEngine::Searxng,
+                SearchEngine::Bing
+            ]
+        );
+    }
+
+    #[test]
+    fn test_engine_credentials_configured() {
+        let cfg = WebSearchConfig {
+            searxng_url: " http://127.0.0.1:8888 ".to_string(),
+            ..Default::default()
+        };
+        let creds = EngineCredentials::from_config(&cfg);
+        assert!(creds.is_configured(SearchEngine::Searxng));
+        assert_eq!(creds.searxng_url.as_deref(), Some("http://127.0.0.1:8888"));
+        assert!(!creds.is_configured(SearchEngine::Brave));
+    }
+
+    #[test]
+    fn test_parse_results_unified_schema() {
+        let searxng = json!({"results": [
+            {"title": "Rust", "url": "https://rust-lang.org", "content": "A language", "publishedDate": "2024-01-01T00:00:00"},
+            {"title": "", "url": "https://empty.example"}
+        ]});
+        let brave = json!({"web": {"results": [
+            {"title": "Rust", "url": "https://rust-lang.org", "description": "A language", "page_age": "2024-01-01"}
+        ]}});
+        let bing = json!({"webPages": {"value": [
+            {"name": "Rust", "url": "https://rust-lang.org", "snippet": "A language", "datePublished": "2024-01-01"},
+            {"name": "Docs", "url": "https://doc.rust-lang.org", "snippet": "Docs"}
+        ]}});
+
+        let s = parse_searxng_results(&searxng, 5);
+        assert_eq!(s.len(), 1);
+        assert_eq!(s[0]["snippet"], "A language");
+        assert_eq!(s[0]["published"], "2024-01-01T00:00:00");
+
+        let b = parse_brave_results(&brave, 5);
+        assert_eq!(b[0]["published"], "2024-01-01");
+
+        let g = parse_bing_results(&bing, 1);
+        assert_eq!(g.len(), 1);
+        assert_eq!(g[0]["title"], "Rust");
+
+        let all = parse_bing_results(&bing, 5);
+        assert_eq!(all[1]["published"], "");
+        for r in s.iter().chain(b.iter()).chain(all.iter()) {
+            for key in ["title", "url", "snippet", "published"] {
+                assert!(r.get(key).is_some(), "missing {}", key);
+            }
+        }
+        assert!(parse_bing_results(&json!({}), 5).is_empty());
+    }
+
+    #[test]
+    fn test_freshness_mapping() {
+        assert_eq!(brave_freshness("day"), Some("pd"));
+        assert_eq!(brave_freshness("semiyear"), Some("py"));
+        assert_eq!(searxng_time_range("semiyear"), Some("year"));
+        assert_eq!(bing_freshness("month"), Some("Month"));
+        assert_eq!(bing_freshness("year"), None);
+    }
+
     #[test]
     fn test_web_fetch_schema() {
         let tool = WebFetchTool;
@@ -1,5 +1,4 @@
 use blockcell_core::{Error, Result};
-use chrono::Timelike;
 use std::path::{Path, PathBuf};
 use tracing::{debug, info, warn};
 
@@ -240,84 +239,3 @@ impl AtomicSwitcher {
         Ok(())
     }
 }
-
-/// 维护窗口检查器
-pub struct MaintenanceWindow {
-    window: String, // 格式: "HH:MM-HH:MM"
-}
-
-impl MaintenanceWindow {
-    pub fn new(window: String) -> Self {
-        Self { window }
-    }
-
-    /// 检查当前时间是否在维护窗口内
-    pub fn is_in_window(&self) -> bool {
-        if self.window.is_empty() {
-            return true; // 没有配置维护窗口，任何时间都可以
-        }
-
-        let parts: Vec<&str> = self.window.split('-').collect();
-        if parts.len() != 2 {
-            warn!(window = %self.window, "Invalid maintenance window format");
-            return false;
-        }
-
-        let start = match self.parse_time(parts[0]) {
-            Some(t) => t,
-            None => return false,
-        };
-
-        let end = match self.parse_time(parts[1]) {
-            Some(t) => t,
-            None => return false,
-        };
-
-        let now = chrono::Local::now();
-        let current = (now.hour(), now.minute());
-
-        // 处理跨午夜的情况
-        if start <= end {
-            current >= start && current < end
-        } else {
-            current >= start || current < end
-        }
-    }
-
-    fn parse_time(&self, time_str: &str) -> Option<(u32, u32)> {
-        let parts: Vec<&str> = time_str.split(':').collect();
-        if parts.len() != 2 {
-            return None;
-        }
-
-        let hour = parts[0].trim().parse::<u32>().ok()?;
-        let minute = parts[1].trim().parse::<u32>().ok()?;
-
-        if hour >= 24 || minute >= 60 {
-            return None;
-        }
-
-        Some((hour, minute))
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_maintenance_window() {
-        let window = MaintenanceWindow::new("03:00-05:00".to_string());
-        // 这个测试依赖于当前时间，所以只是验证不会 panic
-        let _ = window.is_in_window();
-    }
-
-    #[test]
-    fn test_parse_time() {
-        let window = MaintenanceWindow::new("03:00-05:00".to_string());
-        assert_eq!(window.parse_time("03:00"), Some((3, 0)));
-        assert_eq!(window.parse_time("23:59"), Some((23, 59)));
-        assert_eq!(window.parse_time("24:00"), None);
-        assert_eq!(window.parse_time("invalid"), None);
-    }
-}
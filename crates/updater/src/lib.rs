@@ -3,7 +3,8 @@ pub mod manager;
 pub mod manifest;
 pub mod verification;
 
-pub use atomic::{AtomicSwitcher, MaintenanceWindow};
+pub use atomic::AtomicSwitcher;
+pub use blockcell_core::maintenance::MaintenanceWindow;
 pub use manager::UpdateManager;
 pub use manifest::Manifest;
 pub use verification::{HealthChecker, Sha256Verifier, SignatureVerifier};
@@ -1,6 +1,7 @@
-use crate::atomic::{AtomicSwitcher, MaintenanceWindow};
+use crate::atomic::AtomicSwitcher;
 use crate::manifest::Manifest;
 use crate::verification::{HealthChecker, Sha256Verifier, SignatureVerifier};
+use blockcell_core::maintenance::MaintenanceWindow;
 use blockcell_core::{Config, Error, Paths, Result};
 use reqwest::Client;
 use std::path::PathBuf;
@@ -159,9 +160,15 @@ impl UpdateManager {
     pub async fn apply(&self, staging_path: &std::path::Path, version: &str) -> Result<()> {
         info!(version = %version, "Applying update");
 
-        // 1. 检查维护窗口
-        let window = MaintenanceWindow::new(self.config.auto_upgrade.maintenance_window.clone());
-        if !window.is_in_window() {
+        // 1. 检查维护窗口（全局维护日历优先）
+        let maintenance = &self.config.maintenance;
+        let in_window = if maintenance.has_windows() {
+            maintenance.is_active()
+        } else {
+            MaintenanceWindow::new(self.config.auto_upgrade.maintenance_window.clone())
+                .is_in_window()
+        };
+        if !in_window {
             return Err(Error::Other(
                 "Not in maintenance window, update postponed".to_string(),
             ));
@@ -25,6 +25,7 @@ import { ThemeProvider } from './components/theme-provider';
 import { useSidebarStore, useChatStore, useConnectionStore, useReminderAlertsStore, useAgentStore } from './lib/store';
 import { wsManager, type WsEvent } from './lib/ws';
 import { WsEventBatcher } from './lib/ws-batcher';
+import { clearAuth, hasAuth } from './lib/api';
 import { cn } from './lib/utils';
 import { registerShortcuts, handleGlobalKeyDown } from './lib/keyboard';
 
@@ -48,7 +49,7 @@ export default function App() {
   const dismissReminderAlert = useReminderAlertsStore((s) => s.dismissAlert);
   const selectedAgentId = useAgentStore((s) => s.selectedAgentId);
   const visibleReminderAlerts = reminderAlerts.filter((alert) => alert.agentId === selectedAgentId);
-  const [authenticated, setAuthenticated] = useState(() => !!localStorage.getItem('blockcell_token'));
+  const [authenticated, setAuthenticated] = useState(() => hasAuth());
   const [confirmDialog, setConfirmDialog] = useState<ConfirmDialog | null>(null);
   const [showWizard, setShowWizard] = useState(() => {
     return authenticated && !localStorage.getItem('blockcell_wizard_done');
@@ -70,12 +71,12 @@ export default function App() {
   setConnectedRef.current = setConnected;
 
   useEffect(() => {
-    if (localStorage.getItem('blockcell_token')) {
+    if (hasAuth()) {
       wsManager.connect();
     }
     const wsEventBatcher = new WsEventBatcher<WsEvent>((event) => {
       if (event.type === 'confirm_request' && event.request_id) {
-        setConfirmDialog({ requestId: event.request_id, tool: event.tool || '', paths: event.paths || [] });
+        setConfirmDialog({ requestId: event.request_id, tool: event.tool_name || event.tool || '', paths: event.paths || [] });
       } else {
         handleWsEventRef.current(event);
       }
@@ -88,7 +89,7 @@ export default function App() {
 
       // Only force re-login when backend explicitly rejects the token.
       if (state.reason === 'auth_failed') {
-        localStorage.removeItem('blockcell_token');
+        clearAuth();
         wsManager.disconnect();
         setAuthenticated(false);
       }
@@ -224,27 +225,38 @@ export default function App() {
             }}
           />
         )}
-        {/* Path access confirmation dialog */}
+        {/* Path access / plan approval confirmation dialog */}
         {confirmDialog && (
           <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/60 backdrop-blur-sm">
             <div className="bg-card border border-border rounded-xl shadow-2xl max-w-md w-full mx-4 p-6 space-y-4">
               <div className="flex items-start gap-3">
-                <span className="text-2xl">⚠️</span>
+                <span className="text-2xl">{confirmDialog.tool === 'plan' ? '📋' : '⚠️'}</span>
                 <div>
-                  <h2 className="font-semibold text-foreground">安全确认 / Security Confirmation</h2>
-                  <p className="text-sm text-muted-foreground mt-1">
-                    工具 <code className="font-mono text-[hsl(var(--brand-green))]">{confirmDialog.tool}</code> 请求访问工作区以外的路径：
-                  </p>
+                  {confirmDialog.tool === 'plan' ? (
+                    <>
+                      <h2 className="font-semibold text-foreground">执行计划 / Plan Approval</h2>
+                      <p className="text-sm text-muted-foreground mt-1">助手计划按以下步骤完成任务：</p>
+                    </>
+                  ) : (
+                    <>
+                      <h2 className="font-semibold text-foreground">安全确认 / Security Confirmation</h2>
+                      <p className="text-sm text-muted-foreground mt-1">
+                        工具 <code className="font-mono text-[hsl(var(--brand-green))]">{confirmDialog.tool}</code> 请求访问工作区以外的路径：
+                      </p>
+                    </>
+                  )}
                 </div>
               </div>
               <ul className="space-y-1 max-h-40 overflow-y-auto">
                 {confirmDialog.paths.map((p) => (
                   <li key={p} className="text-xs font-mono bg-muted/50 rounded px-3 py-1.5 break-all">
-                    📁 {p}
+                    {confirmDialog.tool === 'plan' ? p : `📁 ${p}`}
                   </li>
                 ))}
               </ul>
-              <p className="text-sm text-muted-foreground">是否允许访问？/ Allow access?</p>
+              <p className="text-sm text-muted-foreground">
+                {confirmDialog.tool === 'plan' ? '是否按此计划执行？/ Run this plan?' : '是否允许访问？/ Allow access?'}
+              </p>
               <div className="flex gap-3 justify-end">
                 <button
                   onClick={() => handleConfirm(false)}
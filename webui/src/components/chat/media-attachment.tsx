@@ -1,5 +1,5 @@
 import { useState } from 'react';
-import { Image, Volume2, Download, Maximize2, X, FileAudio } from 'lucide-react';
+import { Image, Volume2, Download, Maximize2, X, FileAudio, FileText } from 'lucide-react';
 import { cn } from '@/lib/utils';
 import { mediaFileUrl, downloadFileUrl } from '@/lib/api';
 import { useAgentStore } from '@/lib/store';
@@ -68,7 +68,7 @@ export function MediaAttachment({ path }: { path: string }) {
   if (type === 'video') {
     return <VideoAttachment url={url} dlUrl={dlUrl} filename={filename} />;
   }
-  return null;
+  return <FileAttachment dlUrl={dlUrl} filename={filename} />;
 }
 
 function ImageAttachment({ url, dlUrl, filename }: { url: string; dlUrl: string; filename: string }) {
@@ -168,6 +168,23 @@ function VideoAttachment({ url, dlUrl, filename }: { url: string; dlUrl: string;
   );
 }
 
+/** Download card for generated files that cannot be previewed inline (documents, archives, …) */
+function FileAttachment({ dlUrl, filename }: { dlUrl: string; filename: string }) {
+  const ext = getExt(filename);
+  return (
+    <a
+      href={dlUrl}
+      download
+      className="flex items-center gap-2 rounded-lg border border-border bg-card/50 px-3 py-2 text-xs max-w-sm hover:bg-muted/50 transition-colors"
+    >
+      <FileText size={14} className="text-[hsl(var(--brand-green))] shrink-0" />
+      <span className="truncate font-medium">{filename}</span>
+      {ext && <span className="text-[10px] uppercase text-muted-foreground">{ext}</span>}
+      <Download size={14} className="ml-auto text-muted-foreground shrink-0" />
+    </a>
+  );
+}
+
 /** Renders a list of media attachments */
 export function MediaList({ paths }: { paths: string[] }) {
   if (!paths.length) return null;
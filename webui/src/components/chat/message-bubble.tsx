@@ -1,7 +1,7 @@
 import { useState, useMemo, memo } from 'react';
-import { User, Bot, ChevronDown, ChevronRight, Clock, Check, AlertCircle } from 'lucide-react';
+import { User, Bot, ChevronDown, ChevronRight, Clock, Check, AlertCircle, Circle, MinusCircle, ListChecks } from 'lucide-react';
 import { cn } from '@/lib/utils';
-import type { UiMessage, ToolCallInfo } from '@/lib/store';
+import type { UiMessage, ToolCallInfo, PlanInfo } from '@/lib/store';
 import { MarkdownContent } from './markdown-content';
 import { MediaList, extractMediaPaths, isMediaPath } from './media-attachment';
 
@@ -42,6 +42,9 @@ export const MessageBubble = memo(function MessageBubble({ message }: { message:
           </CollapsibleSection>
         )}
 
+        {/* Plan */}
+        {message.plan && <PlanCard plan={message.plan} />}
+
         {/* Tool calls */}
         {message.toolCalls && message.toolCalls.length > 0 && (
           <div className="w-full space-y-1">
@@ -86,6 +89,38 @@ export const MessageBubble = memo(function MessageBubble({ message }: { message:
   );
 });
 
+function PlanCard({ plan }: { plan: PlanInfo }) {
+  const statusIcon = {
+    pending: <Circle size={12} className="text-muted-foreground" />,
+    running: <Clock size={12} className="text-yellow-500 animate-spin" />,
+    done: <Check size={12} className="text-[hsl(var(--success))]" />,
+    failed: <AlertCircle size={12} className="text-red-500" />,
+    skipped: <MinusCircle size={12} className="text-muted-foreground" />,
+  };
+  const done = plan.steps.filter((s) => s.status === 'done').length;
+
+  return (
+    <div className="w-full border border-border rounded-lg bg-card/50 px-3 py-2 text-xs space-y-1.5">
+      <div className="flex items-center gap-2 font-medium">
+        <ListChecks size={14} className="text-[hsl(var(--brand-green))]" />
+        <span>Plan{plan.revision > 1 ? ` · v${plan.revision}` : ''}</span>
+        <span className="ml-auto text-muted-foreground">{done}/{plan.steps.length}</span>
+      </div>
+      <ol className="space-y-1">
+        {plan.steps.map((step) => (
+          <li key={step.index} className="flex items-start gap-2">
+            <span className="mt-0.5 shrink-0">{statusIcon[step.status]}</span>
+            <span className={cn(step.status === 'skipped' && 'line-through text-muted-foreground')}>
+              {step.index}. {step.title}
+              {step.note && <span className="text-muted-foreground"> — {step.note}</span>}
+            </span>
+          </li>
+        ))}
+      </ol>
+    </div>
+  );
+}
+
 function ToolCallCard({ toolCall }: { toolCall: ToolCallInfo }) {
   const [isOpen, setIsOpen] = useState(false);
 
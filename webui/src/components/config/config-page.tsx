@@ -2,10 +2,13 @@ import { useEffect, useState } from 'react';
 import {
   Save, RefreshCw, FlaskConical, Loader2, Globe, Sun, Moon, Monitor,
   ExternalLink, LogOut, ChevronRight, Languages, FileCode, Info, X,
-  ToggleLeft, ToggleRight,
+  ToggleLeft, ToggleRight, KeyRound, Trash2, Plus,
 } from 'lucide-react';
 import { cn } from '@/lib/utils';
-import { getConfig, getConfigRaw, updateConfig, updateConfigRaw, testProvider, getHealth, logout, reloadConfig } from '@/lib/api';
+import {
+  getConfig, getConfigRaw, updateConfig, updateConfigRaw, testProvider, getHealth, logout, reloadConfig,
+  getPasskeys, registerPasskey, deletePasskey, type PasskeyInfo,
+} from '@/lib/api';
 import { useThemeStore } from '@/lib/store';
 import { useI18nStore, useT, type Locale } from '@/lib/i18n';
 
@@ -208,6 +211,106 @@ function ConfigEditor({ onBack, t }: { onBack: () => void; t: (k: string, p?: Re
   );
 }
 
+// ── Passkeys (gateway.webauthn) ──
+function PasskeysSection({ t }: { t: (key: string) => string }) {
+  const [enabled, setEnabled] = useState(false);
+  const [passkeys, setPasskeys] = useState<PasskeyInfo[]>([]);
+  const [name, setName] = useState('');
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState('');
+
+  async function load() {
+    try {
+      const data = await getPasskeys();
+      setEnabled(data.enabled);
+      setPasskeys(data.passkeys);
+    } catch {
+      setEnabled(false);
+    }
+  }
+
+  useEffect(() => {
+    load();
+  }, []);
+
+  async function handleAdd() {
+    setBusy(true);
+    setError('');
+    try {
+      await registerPasskey(name.trim() || t('settings.passkeyDefaultName'));
+      setName('');
+      await load();
+    } catch (e: any) {
+      setError(e?.name === 'NotAllowedError' ? t('login.passkeyFailed') : e.message);
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function handleDelete(id: string) {
+    setError('');
+    try {
+      await deletePasskey(id);
+      await load();
+    } catch (e: any) {
+      setError(e.message);
+    }
+  }
+
+  if (!enabled) return null;
+
+  return (
+    <section>
+      <h2 className="text-sm font-semibold text-muted-foreground uppercase tracking-wider mb-4 flex items-center gap-2">
+        <KeyRound size={14} />
+        {t('settings.passkeys')}
+      </h2>
+      <div className="bg-card border border-border rounded-xl divide-y divide-border">
+        {passkeys.length === 0 && (
+          <div className="px-5 py-4 text-xs text-muted-foreground">{t('settings.passkeysEmpty')}</div>
+        )}
+        {passkeys.map((passkey) => (
+          <div key={passkey.id} className="flex items-center justify-between px-5 py-4">
+            <div>
+              <div className="text-sm font-medium">{passkey.name}</div>
+              <div className="text-xs text-muted-foreground mt-0.5">
+                {t('settings.passkeyAdded')} {passkey.created_at ? new Date(passkey.created_at).toLocaleDateString() : '-'}
+                {' · '}
+                {t('settings.passkeyLastUsed')} {passkey.last_used_at ? new Date(passkey.last_used_at).toLocaleString() : '-'}
+              </div>
+            </div>
+            <button
+              onClick={() => handleDelete(passkey.id)}
+              className="p-1.5 rounded-lg text-muted-foreground hover:text-destructive hover:bg-destructive/10 transition-colors"
+              title={t('settings.passkeyRemove')}
+            >
+              <Trash2 size={14} />
+            </button>
+          </div>
+        ))}
+        <div className="flex items-center gap-2 px-5 py-4">
+          <input
+            value={name}
+            onChange={(e) => setName(e.target.value)}
+            placeholder={t('settings.passkeyName')}
+            maxLength={64}
+            className="flex-1 px-3 py-1.5 text-sm bg-background border border-border rounded-lg outline-none focus:ring-2 focus:ring-ring"
+          />
+          <button
+            onClick={handleAdd}
+            disabled={busy}
+            className="flex items-center gap-1.5 px-3 py-1.5 text-sm rounded-lg bg-primary text-primary-foreground hover:bg-primary/90 disabled:opacity-50"
+          >
+            {busy ? <Loader2 size={14} className="animate-spin" /> : <Plus size={14} />}
+            {t('settings.passkeyAdd')}
+          </button>
+        </div>
+        {error && <div className="px-5 py-3 text-xs text-red-500">{error}</div>}
+      </div>
+    </section>
+  );
+}
+
 // ── Main Settings page ──
 export function ConfigPage() {
   const [showEditor, setShowEditor] = useState(false);
@@ -439,6 +542,9 @@ export function ConfigPage() {
             </div>
           </section>
 
+          {/* ── Passkeys ── */}
+          <PasskeysSection t={t} />
+
           {/* ── About ── */}
           <section>
             <h2 className="text-sm font-semibold text-muted-foreground uppercase tracking-wider mb-4 flex items-center gap-2">
@@ -1,9 +1,9 @@
-import { useState } from 'react';
-import { Loader2, AlertCircle, Eye, EyeOff } from 'lucide-react';
+import { useEffect, useState } from 'react';
+import { Loader2, AlertCircle, Eye, EyeOff, KeyRound } from 'lucide-react';
 import { BlockcellLogo } from './blockcell-logo';
 import { cn } from '@/lib/utils';
 import { useI18nStore, useT, type Locale } from '@/lib/i18n';
-import { API_BASE } from '@/lib/api';
+import { getAuthMethods, login, markSession, passkeyLogin, type AuthMethods } from '@/lib/api';
 
 interface LoginPageProps {
   onLogin: () => void;
@@ -16,6 +16,13 @@ export function LoginPage({ onLogin }: LoginPageProps) {
   const [error, setError] = useState('');
   const [loading, setLoading] = useState(false);
   const [showPassword, setShowPassword] = useState(false);
+  const [methods, setMethods] = useState<AuthMethods | null>(null);
+  // Set after the password step when the gateway also requires a passkey.
+  const [ticket, setTicket] = useState<string | null>(null);
+
+  useEffect(() => {
+    getAuthMethods().then(setMethods).catch(() => setMethods(null));
+  }, []);
 
   const languages: { value: Locale; label: string }[] = [
     { value: 'zh', label: '中文' },
@@ -30,17 +37,17 @@ export function LoginPage({ onLogin }: LoginPageProps) {
     setError('');
 
     try {
-      const res = await fetch(`${API_BASE}/v1/auth/login`, {
-        method: 'POST',
-        headers: { 'Content-Type': 'application/json' },
-        body: JSON.stringify({ password }),
-      });
-
-      const data = await res.json();
+      const data = await login(password);
 
-      if (res.ok && data.token) {
+      if (data.token) {
         localStorage.setItem('blockcell_token', data.token);
         onLogin();
+      } else if (data.status === 'ok') {
+        markSession();
+        onLogin();
+      } else if (data.status === 'passkey_required' && data.ticket) {
+        setTicket(data.ticket);
+        await handlePasskey(data.ticket);
       } else {
         setError(data.error || t('login.invalidPassword'));
       }
@@ -51,6 +58,22 @@ export function LoginPage({ onLogin }: LoginPageProps) {
     }
   }
 
+  async function handlePasskey(passkeyTicket = ticket ?? undefined) {
+    setLoading(true);
+    setError('');
+    try {
+      await passkeyLogin(passkeyTicket);
+      onLogin();
+    } catch (err) {
+      setError(err instanceof Error && err.name !== 'NotAllowedError' ? err.message : t('login.passkeyFailed'));
+    } finally {
+      setLoading(false);
+    }
+  }
+
+  const passwordStep = !ticket && (methods?.password ?? true);
+  const passkeyStep = !!ticket || (!!methods?.passkey && methods.mode !== 'password_and_passkey');
+
   return (
     <div className="relative flex items-center justify-center min-h-screen bg-background">
       <div className="absolute top-4 right-4">
@@ -82,42 +105,65 @@ export function LoginPage({ onLogin }: LoginPageProps) {
           <p className="text-sm text-muted-foreground mt-1">{t('login.subtitle')}</p>
         </div>
 
-        <form onSubmit={handleSubmit} className="space-y-4">
-          <div className="relative">
-            <input
-              type={showPassword ? 'text' : 'password'}
-              value={password}
-              onChange={(e) => { setPassword(e.target.value); setError(''); }}
-              placeholder={t('login.password')}
-              autoFocus
-              className="w-full px-4 py-3 pr-10 text-sm bg-card border border-border rounded-xl outline-none focus:ring-2 focus:ring-ring placeholder:text-muted-foreground"
-            />
+        {passwordStep && (
+          <form onSubmit={handleSubmit} className="space-y-4">
+            <div className="relative">
+              <input
+                type={showPassword ? 'text' : 'password'}
+                value={password}
+                onChange={(e) => { setPassword(e.target.value); setError(''); }}
+                placeholder={t('login.password')}
+                autoFocus
+                className="w-full px-4 py-3 pr-10 text-sm bg-card border border-border rounded-xl outline-none focus:ring-2 focus:ring-ring placeholder:text-muted-foreground"
+              />
+              <button
+                type="button"
+                onClick={() => setShowPassword(!showPassword)}
+                className="absolute right-3 top-1/2 -translate-y-1/2 text-muted-foreground hover:text-foreground transition-colors"
+                tabIndex={-1}
+              >
+                {showPassword ? <EyeOff size={16} /> : <Eye size={16} />}
+              </button>
+            </div>
+
+            {error && (
+              <div className="flex items-center gap-2 text-sm text-red-500">
+                <AlertCircle size={14} />
+                <span>{error}</span>
+              </div>
+            )}
+
+            <button
+              type="submit"
+              disabled={loading || !password.trim()}
+              className="w-full py-3 text-sm font-medium rounded-xl bg-primary text-primary-foreground hover:bg-primary/90 disabled:opacity-50 flex items-center justify-center gap-2"
+            >
+              {loading ? <Loader2 size={16} className="animate-spin" /> : null}
+              {loading ? t('login.signingIn') : t('login.signIn')}
+            </button>
+          </form>
+        )}
+
+        {passkeyStep && (
+          <div className={cn('space-y-3', passwordStep && 'mt-4')}>
+            {ticket && <p className="text-sm text-muted-foreground text-center">{t('login.passkeyStep')}</p>}
+            {!passwordStep && error && (
+              <div className="flex items-center gap-2 text-sm text-red-500">
+                <AlertCircle size={14} />
+                <span>{error}</span>
+              </div>
+            )}
             <button
               type="button"
-              onClick={() => setShowPassword(!showPassword)}
-              className="absolute right-3 top-1/2 -translate-y-1/2 text-muted-foreground hover:text-foreground transition-colors"
-              tabIndex={-1}
+              onClick={() => handlePasskey()}
+              disabled={loading}
+              className="w-full py-3 text-sm font-medium rounded-xl border border-border bg-card hover:bg-accent disabled:opacity-50 flex items-center justify-center gap-2"
             >
-              {showPassword ? <EyeOff size={16} /> : <Eye size={16} />}
+              {loading ? <Loader2 size={16} className="animate-spin" /> : <KeyRound size={16} />}
+              {t('login.passkey')}
             </button>
           </div>
-
-          {error && (
-            <div className="flex items-center gap-2 text-sm text-red-500">
-              <AlertCircle size={14} />
-              <span>{error}</span>
-            </div>
-          )}
-
-          <button
-            type="submit"
-            disabled={loading || !password.trim()}
-            className="w-full py-3 text-sm font-medium rounded-xl bg-primary text-primary-foreground hover:bg-primary/90 disabled:opacity-50 flex items-center justify-center gap-2"
-          >
-            {loading ? <Loader2 size={16} className="animate-spin" /> : null}
-            {loading ? t('login.signingIn') : t('login.signIn')}
-          </button>
-        </form>
+        )}
 
         <p className="text-xs text-muted-foreground text-center mt-6">
           {t('login.hint')}
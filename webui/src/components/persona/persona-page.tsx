@@ -243,7 +243,7 @@ export function PersonaPage() {
 
   // AI streaming: accumulate tokens in buffer ref, apply to editor only on done
   useEffect(() => {
-    const offToken = wsManager.on('token', (ev: any) => {
+    const offToken = wsManager.on('message_delta', (ev: any) => {
       if (ev.chat_id !== aiChatIdRef.current) return;
       aiBufferRef.current += ev.delta || '';
     });
@@ -38,7 +38,40 @@ async function fetchWithTimeout(input: RequestInfo | URL, init: RequestInit = {}
   }
 }
 
-async function request<T>(path: string, options?: RequestInit): Promise<T> {
+// With gateway.webauthn enabled, logins use HttpOnly cookies; this flag only tells the
+// WebUI that a cookie session exists.
+const SESSION_FLAG = 'blockcell_session';
+
+export function hasAuth() {
+  return !!(localStorage.getItem('blockcell_token') || localStorage.getItem(SESSION_FLAG));
+}
+
+export function markSession() {
+  localStorage.setItem(SESSION_FLAG, '1');
+}
+
+export function clearAuth() {
+  localStorage.removeItem('blockcell_token');
+  localStorage.removeItem(SESSION_FLAG);
+}
+
+let refreshing: Promise<boolean> | null = null;
+
+/** Renew an expired session cookie with the refresh cookie. Concurrent callers share one request. */
+export function refreshSession(): Promise<boolean> {
+  if (!localStorage.getItem(SESSION_FLAG)) return Promise.resolve(false);
+  if (!refreshing) {
+    refreshing = fetchWithTimeout(`${API_BASE}/v1/auth/refresh`, { method: 'POST', credentials: 'include' })
+      .then((res) => res.ok)
+      .catch(() => false)
+      .finally(() => {
+        refreshing = null;
+      });
+  }
+  return refreshing;
+}
+
+async function request<T>(path: string, options?: RequestInit, retried = false): Promise<T> {
   const url = `${API_BASE}/v1${path}`;
   const token = localStorage.getItem('blockcell_token');
   const headers: Record<string, string> = {
@@ -48,7 +81,10 @@ async function request<T>(path: string, options?: RequestInit): Promise<T> {
   if (token) {
     headers['Authorization'] = `Bearer ${token}`;
   }
-  const res = await fetchWithTimeout(url, { ...options, headers });
+  const res = await fetchWithTimeout(url, { ...options, headers, credentials: 'include' });
+  if (res.status === 401 && !retried && (await refreshSession())) {
+    return request<T>(path, options, true);
+  }
   if (!res.ok) {
     const text = await res.text();
     throw new Error(`API ${res.status}: ${text}`);
@@ -64,18 +100,151 @@ function ensureStatusOk<T extends { status?: string; message?: string }>(result:
 }
 
 // Auth
-export async function login(password: string): Promise<{ token?: string; error?: string }> {
+export interface LoginResult {
+  token?: string;
+  status?: 'ok' | 'passkey_required';
+  ticket?: string;
+  error?: string;
+}
+
+export async function login(password: string): Promise<LoginResult> {
   const url = `${API_BASE}/v1/auth/login`;
   const res = await fetchWithTimeout(url, {
     method: 'POST',
     headers: { 'Content-Type': 'application/json' },
+    credentials: 'include',
     body: JSON.stringify({ password }),
   });
   return res.json();
 }
 
-export function logout() {
-  localStorage.removeItem('blockcell_token');
+export interface AuthMethods {
+  password: boolean;
+  passkey: boolean;
+  mode: 'optional' | 'password_and_passkey' | 'passkey_only';
+  sessions: boolean;
+}
+
+export async function getAuthMethods(): Promise<AuthMethods> {
+  const res = await fetchWithTimeout(`${API_BASE}/v1/auth/methods`);
+  return res.json();
+}
+
+function b64urlToBuffer(value: string): ArrayBuffer {
+  const base64 = value.replace(/-/g, '+').replace(/_/g, '/').padEnd(Math.ceil(value.length / 4) * 4, '=');
+  return Uint8Array.from(atob(base64), (c) => c.charCodeAt(0)).buffer;
+}
+
+function bufferToB64url(buffer: ArrayBuffer | null): string | null {
+  if (!buffer) return null;
+  let binary = '';
+  for (const byte of new Uint8Array(buffer)) binary += String.fromCharCode(byte);
+  return btoa(binary).replace(/\+/g, '-').replace(/\//g, '_').replace(/=+$/, '');
+}
+
+function decodeCredentialList(list?: { id: string }[]) {
+  return list?.map((c) => ({ ...c, id: b64urlToBuffer(c.id) }));
+}
+
+async function postAuth(path: string, body: unknown) {
+  const res = await fetchWithTimeout(`${API_BASE}/v1/auth${path}`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    credentials: 'include',
+    body: JSON.stringify(body),
+  });
+  const data = await res.json();
+  if (!res.ok) throw new Error(data.error || `API ${res.status}`);
+  return data;
+}
+
+/** Sign in with a passkey. `ticket` comes from the password step in password_and_passkey mode. */
+export async function passkeyLogin(ticket?: string): Promise<void> {
+  const { challenge_id, options } = await postAuth('/passkey/login/start', { ticket });
+  const publicKey = options.publicKey;
+  const credential = (await navigator.credentials.get({
+    publicKey: {
+      ...publicKey,
+      challenge: b64urlToBuffer(publicKey.challenge),
+      allowCredentials: decodeCredentialList(publicKey.allowCredentials),
+    },
+  })) as PublicKeyCredential | null;
+  if (!credential) throw new Error('Passkey sign-in was cancelled');
+  const response = credential.response as AuthenticatorAssertionResponse;
+  await postAuth('/passkey/login/finish', {
+    challenge_id,
+    ticket,
+    credential: {
+      id: credential.id,
+      rawId: bufferToB64url(credential.rawId),
+      type: credential.type,
+      response: {
+        authenticatorData: bufferToB64url(response.authenticatorData),
+        clientDataJSON: bufferToB64url(response.clientDataJSON),
+        signature: bufferToB64url(response.signature),
+        userHandle: bufferToB64url(response.userHandle),
+      },
+      extensions: credential.getClientExtensionResults(),
+    },
+  });
+  markSession();
+}
+
+export interface PasskeyInfo {
+  id: string;
+  name: string;
+  created_at: string | null;
+  last_used_at: string | null;
+}
+
+export function getPasskeys() {
+  return request<{ enabled: boolean; mode: AuthMethods['mode']; passkeys: PasskeyInfo[] }>('/auth/passkeys');
+}
+
+/** Register a passkey on this device for the signed-in WebUI. */
+export async function registerPasskey(name: string): Promise<PasskeyInfo> {
+  const { challenge_id, options } = await request<{ challenge_id: string; options: any }>('/auth/passkey/register/start', {
+    method: 'POST',
+    body: JSON.stringify({ name }),
+  });
+  const publicKey = options.publicKey;
+  const credential = (await navigator.credentials.create({
+    publicKey: {
+      ...publicKey,
+      challenge: b64urlToBuffer(publicKey.challenge),
+      user: { ...publicKey.user, id: b64urlToBuffer(publicKey.user.id) },
+      excludeCredentials: decodeCredentialList(publicKey.excludeCredentials),
+    },
+  })) as PublicKeyCredential | null;
+  if (!credential) throw new Error('Passkey registration was cancelled');
+  const response = credential.response as AuthenticatorAttestationResponse;
+  return request<PasskeyInfo>('/auth/passkey/register/finish', {
+    method: 'POST',
+    body: JSON.stringify({
+      challenge_id,
+      credential: {
+        id: credential.id,
+        rawId: bufferToB64url(credential.rawId),
+        type: credential.type,
+        response: {
+          attestationObject: bufferToB64url(response.attestationObject),
+          clientDataJSON: bufferToB64url(response.clientDataJSON),
+        },
+        extensions: credential.getClientExtensionResults(),
+      },
+    }),
+  });
+}
+
+export function deletePasskey(id: string) {
+  return request<{ status: string; id: string }>(`/auth/passkeys/${encodeURIComponent(id)}`, { method: 'DELETE' });
+}
+
+export async function logout() {
+  if (localStorage.getItem(SESSION_FLAG)) {
+    await fetchWithTimeout(`${API_BASE}/v1/auth/logout`, { method: 'POST', credentials: 'include' }).catch(() => undefined);
+  }
+  clearAuth();
   window.location.reload();
 }
 
@@ -70,6 +70,9 @@ const translations: Record<Locale, Record<string, string>> = {
     'login.hint': 'Set gateway.webuiPass in config.json5 for a stable password, or check the gateway console for the temporary one-time password',
     'login.invalidPassword': 'Invalid password',
     'login.cannotConnect': 'Cannot connect to gateway',
+    'login.passkey': 'Sign in with a passkey',
+    'login.passkeyStep': 'Password accepted. Confirm with your passkey to finish signing in.',
+    'login.passkeyFailed': 'Passkey sign-in was cancelled or failed',
 
     // Chat
     'chat.emptyTitle': 'BLOCKCELL',
@@ -210,6 +213,14 @@ const translations: Record<Locale, Record<string, string>> = {
     'settings.visitWebsite': 'Visit',
     'settings.security': 'Security',
     'settings.logoutDesc': 'Sign out and clear your session',
+    'settings.passkeys': 'Passkeys',
+    'settings.passkeysEmpty': 'No passkeys yet. Add one to sign in with Face ID, Touch ID, Windows Hello or a security key.',
+    'settings.passkeyName': 'Passkey name, e.g. MacBook',
+    'settings.passkeyDefaultName': 'Passkey',
+    'settings.passkeyAdd': 'Add passkey',
+    'settings.passkeyAdded': 'Added',
+    'settings.passkeyLastUsed': 'last used',
+    'settings.passkeyRemove': 'Remove passkey',
     'settings.logoutBtn': 'Sign Out',
 
     // Cron
@@ -690,6 +701,9 @@ const translations: Record<Locale, Record<string, string>> = {
     'login.hint': '在 config.json5 中设置 gateway.webuiPass 可固定密码，否则每次启动会生成临时密码显示在控制台',
     'login.invalidPassword': '密码错误',
     'login.cannotConnect': '无法连接到 Gateway',
+    'login.passkey': '使用通行密钥登录',
+    'login.passkeyStep': '密码正确，请使用通行密钥确认以完成登录。',
+    'login.passkeyFailed': '通行密钥登录已取消或失败',
 
     // Chat
     'chat.emptyTitle': 'BLOCKCELL',
@@ -827,6 +841,14 @@ const translations: Record<Locale, Record<string, string>> = {
     'settings.visitWebsite': '访问',
     'settings.security': '安全',
     'settings.logoutDesc': '退出登录并清除会话',
+    'settings.passkeys': '通行密钥',
+    'settings.passkeysEmpty': '尚未添加通行密钥。添加后可使用面容 ID、触控 ID、Windows Hello 或安全密钥登录。',
+    'settings.passkeyName': '通行密钥名称，例如 MacBook',
+    'settings.passkeyDefaultName': '通行密钥',
+    'settings.passkeyAdd': '添加通行密钥',
+    'settings.passkeyAdded': '添加于',
+    'settings.passkeyLastUsed': '最近使用',
+    'settings.passkeyRemove': '删除通行密钥',
     'settings.logoutBtn': '安全退出',
 
     // Cron
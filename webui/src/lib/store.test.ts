@@ -86,7 +86,7 @@ test('message_done does not duplicate a completed streaming assistant reply', ()
   const store = useChatStore.getState();
   const content = '✅ 已设置30秒后的睡觉提醒！';
 
-  store.handleWsEvent({ type: 'token', chat_id: 'chat-1', delta: content });
+  store.handleWsEvent({ type: 'message_delta', chat_id: 'chat-1', delta: content });
   store.handleWsEvent({ type: 'message_done', chat_id: 'chat-1' });
   store.handleWsEvent({ type: 'message_done', chat_id: 'chat-1', content });
 
@@ -100,7 +100,7 @@ test('stream_reset drops the current streaming assistant message', () => {
   resetStore();
   const store = useChatStore.getState();
 
-  store.handleWsEvent({ type: 'token', chat_id: 'chat-1', delta: 'partial reply' });
+  store.handleWsEvent({ type: 'message_delta', chat_id: 'chat-1', delta: 'partial reply' });
   store.handleWsEvent({ type: 'stream_reset', chat_id: 'chat-1' });
 
   const { messages } = useChatStore.getState();
@@ -113,7 +113,7 @@ test('message_done still appends a new assistant reply when content changes', ()
   const preamble = '我来为您设置一个30秒后的睡觉提醒。';
   const finalReply = '✅ 已设置30秒后的睡觉提醒！';
 
-  store.handleWsEvent({ type: 'token', chat_id: 'chat-1', delta: preamble });
+  store.handleWsEvent({ type: 'message_delta', chat_id: 'chat-1', delta: preamble });
   store.handleWsEvent({ type: 'message_done', chat_id: 'chat-1' });
   store.handleWsEvent({ type: 'message_done', chat_id: 'chat-1', content: finalReply });
 
@@ -129,7 +129,7 @@ test('message_done does not keep pseudo tool-call text as a visible assistant me
   const toolTrace = '我来帮您查看上一级目录的内容。<tool_call><function=list_dir><parameter=path>/Users/apple/.blockcell</parameter></function></tool_call>';
   const finalReply = '上一级目录包含 3 个文件夹和 2 个文件。';
 
-  store.handleWsEvent({ type: 'token', chat_id: 'chat-1', delta: toolTrace });
+  store.handleWsEvent({ type: 'message_delta', chat_id: 'chat-1', delta: toolTrace });
   store.handleWsEvent({ type: 'message_done', chat_id: 'chat-1' });
   store.handleWsEvent({ type: 'message_done', chat_id: 'chat-1', content: finalReply });
 
@@ -1,6 +1,6 @@
 import { create } from 'zustand';
 import type { SessionInfo, ChatMsg } from './api';
-import type { WsEvent, ConnectionState, DisconnectReason } from './ws';
+import type { WsEvent, ConnectionState, DisconnectReason, PlanStep } from './ws';
 import { notifyTaskCompleted, notifyAlertTriggered, notifySystemEvent } from './notifications';
 
 function normalizeSessionId(id: string) {
@@ -135,6 +135,13 @@ export interface UiMessage {
   streaming?: boolean;
   media?: string[];
   highlight?: boolean;
+  plan?: PlanInfo;
+}
+
+export interface PlanInfo {
+  id: string;
+  revision: number;
+  steps: PlanStep[];
 }
 
 export interface ToolCallInfo {
@@ -260,7 +267,7 @@ export const useChatStore = create<ChatState>((set, get) => ({
 
     // Filter chat-specific events by both agent_id and chat_id to prevent
     // cross-agent and cross-session leaking.
-    const chatEventTypes: string[] = ['message_done', 'token', 'tool_call_start', 'tool_call_result', 'thinking'];
+    const chatEventTypes: string[] = ['message_done', 'message_delta', 'tool_call_start', 'tool_call_result', 'artifact', 'plan_proposed', 'plan_step', 'thinking'];
     if (chatEventTypes.includes(event.type) && event.chat_id) {
       if (event.agent_id && event.agent_id !== selectedAgentId) {
         return;
@@ -393,7 +400,7 @@ export const useChatStore = create<ChatState>((set, get) => ({
         break;
       }
 
-      case 'token': {
+      case 'message_delta': {
         // 直接使用 set() 并在回调中获取最新状态，确保流式追加正确
         set((s) => {
           const lastMsg = s.messages[s.messages.length - 1];
@@ -500,6 +507,39 @@ export const useChatStore = create<ChatState>((set, get) => ({
         break;
       }
 
+      case 'plan_proposed':
+      case 'plan_step': {
+        if (!event.plan_id || !event.steps) break;
+        const plan: PlanInfo = { id: event.plan_id, revision: event.revision || 1, steps: event.steps };
+        const lastMsg = state.messages[state.messages.length - 1];
+        if (lastMsg?.role === 'assistant' && lastMsg.streaming) {
+          state.updateLastAssistantMessage((m) => ({ ...m, plan }));
+        } else {
+          // Plans are proposed before any tool call, so hold them in a fresh assistant message
+          state.addMessage({
+            id: nextMsgId(),
+            role: 'assistant',
+            content: '',
+            plan,
+            timestamp: Date.now(),
+            streaming: true,
+          });
+        }
+        break;
+      }
+
+      case 'artifact': {
+        // Attach the file to the answer in progress so it renders as a card right away.
+        const artifactPath = event.path;
+        if (artifactPath) {
+          state.updateLastAssistantMessage((m) => ({
+            ...m,
+            media: [...new Set([...(m.media || []), artifactPath])],
+          }));
+        }
+        break;
+      }
+
       case 'task_update': {
         // Send browser notification for task completion
         if (event.status === 'Completed' || event.status === 'Failed') {
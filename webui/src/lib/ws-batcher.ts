@@ -7,7 +7,7 @@ export interface BatchableWsEvent {
 }
 
 function isBufferedTextEvent(event: BatchableWsEvent): boolean {
-  return event.type === 'token' || event.type === 'thinking';
+  return event.type === 'message_delta' || event.type === 'thinking';
 }
 
 function canMergeBufferedEvent(current: BatchableWsEvent, next: BatchableWsEvent): boolean {
@@ -33,7 +33,7 @@ export class WsEventBatcher<T extends BatchableWsEvent> {
     }
 
     if (this.pendingEvent && canMergeBufferedEvent(this.pendingEvent, event)) {
-      if (this.pendingEvent.type === 'token') {
+      if (this.pendingEvent.type === 'message_delta') {
         this.pendingEvent = {
           ...this.pendingEvent,
           delta: `${this.pendingEvent.delta || ''}${event.delta || ''}`,
@@ -1,8 +1,14 @@
+import { clearAuth, hasAuth, refreshSession } from './api';
+
 export type WsEventType =
-  | 'token'
+  | 'message_delta'
   | 'stream_reset'
   | 'tool_call_start'
   | 'tool_call_result'
+  | 'artifact'
+  | 'plan_proposed'
+  | 'plan_step'
+  | 'agent_stuck'
   | 'message_done'
   | 'session_bound'
   | 'task_update'
@@ -16,6 +22,14 @@ export type WsEventType =
   | 'system_event_notification'
   | 'system_event_summary';
 
+export interface PlanStep {
+  index: number;
+  title: string;
+  detail?: string;
+  status: 'pending' | 'running' | 'done' | 'failed' | 'skipped';
+  note?: string;
+}
+
 export interface WsEvent {
   type: WsEventType;
   agent_id?: string;
@@ -28,6 +42,7 @@ export interface WsEvent {
   delta?: string;
   content?: string;
   tool?: string;
+  tool_name?: string;
   call_id?: string;
   params?: any;
   result?: any;
@@ -43,6 +58,23 @@ export interface WsEvent {
   new_skills?: string[];
   media?: string[];
   name?: string;
+  // artifact fields
+  path?: string;
+  kind?: string;
+  mime?: string;
+  size?: number;
+  description?: string;
+  serve_url?: string;
+  download_url?: string;
+  // plan fields
+  plan_id?: string;
+  revision?: number;
+  steps?: PlanStep[];
+  step?: PlanStep;
+  finished?: boolean;
+  // agent_stuck fields
+  repeats?: number;
+  outcome?: 'replan' | 'clarify';
   // system event fields
   event_id?: string;
   priority?: string;
@@ -100,8 +132,11 @@ class WebSocketManager {
   private _wasConnected = false;
   private _generation = 0;
   private _healthProbed = false;
+  /** A 4401 already triggered a session refresh; a second one means the login is gone. */
+  private _sessionRefreshed = false;
   private _emitTimer: ReturnType<typeof setTimeout> | null = null;
   private _emitScheduled = false;
+  private subscription: { chat_ids: string[]; event_types: string[] } | null = null;
 
   constructor() {
     this.url = resolveWsUrl();
@@ -127,6 +162,8 @@ class WebSocketManager {
         this._reconnectAttempt = 0;
         this._reason = 'none';
         this._wasConnected = true;
+        this._sessionRefreshed = false;
+        if (this.subscription) this.send({ type: 'subscribe', ...this.subscription });
         this.emitInternal('_connected');
         this.emitConnectionState();
       };
@@ -146,7 +183,22 @@ class WebSocketManager {
         // code 1006 (abnormal close) is ambiguous — it fires on server restarts,
         // network blips, and CORS issues, NOT just auth failures. Treating it as
         // auth_failed causes logged-in users to be kicked out on page refresh.
-        if (event.code === 4401) {
+        if (event.code === 4401 && localStorage.getItem('blockcell_session') && !this._sessionRefreshed) {
+          // The session cookie expired: renew it once, then reconnect.
+          this._sessionRefreshed = true;
+          this.shouldReconnect = false;
+          const gen = this._generation;
+          void refreshSession().then((ok) => {
+            if (gen !== this._generation) return;
+            if (ok) {
+              this.forceReconnect();
+            } else {
+              this._reason = 'auth_failed';
+              this.emitConnectionState();
+            }
+          });
+          this._reason = 'connecting';
+        } else if (event.code === 4401) {
           this._reason = 'auth_failed';
           this.shouldReconnect = false;
           if (this.reconnectTimer) {
@@ -163,8 +215,7 @@ class WebSocketManager {
         // this is often caused by the gateway restarting and invalidating the token.
         // Probe /v1/health to distinguish auth failure from server down.
         if (this._reason === 'server_down' && !this._healthProbed) {
-          const token = localStorage.getItem('blockcell_token');
-          if (token && !this._wasConnected) {
+          if (hasAuth() && !this._wasConnected) {
             this._healthProbed = true;
             const gen = this._generation;
             void this.probeHealthAndSetReason(gen);
@@ -227,7 +278,7 @@ class WebSocketManager {
   /** Re-login: clear token and signal auth_failed so App shows login */
   relogin() {
     this.disconnect();
-    localStorage.removeItem('blockcell_token');
+    clearAuth();
     window.location.reload();
   }
 
@@ -287,6 +338,12 @@ class WebSocketManager {
     this.send({ type: 'cancel', chat_id: chatId, agent_id: agentId });
   }
 
+  /** Only receive events for these chats / event types (empty = all). Re-sent on reconnect. */
+  subscribe(chatIds: string[] = [], eventTypes: string[] = []) {
+    this.subscription = chatIds.length || eventTypes.length ? { chat_ids: chatIds, event_types: eventTypes } : null;
+    this.send({ type: 'subscribe', chat_ids: chatIds, event_types: eventTypes });
+  }
+
   sendConfirmResponse(requestId: string, approved: boolean) {
     this.send({ type: 'confirm_response', request_id: requestId, approved });
   }
@@ -314,7 +371,7 @@ class WebSocketManager {
 
   /** Emit internal events (like _connected/_disconnected) without triggering '*' wildcard */
   private emitInternal(event: string) {
-    this.listeners.get(event)?.forEach((fn) => fn({ type: 'token' } as WsEvent));
+    this.listeners.get(event)?.forEach((fn) => fn({ type: 'message_delta' } as WsEvent));
   }
 
   private emitConnectionState() {